@@ -12,7 +12,9 @@
 //! Note: Partial message streaming requires the CLI to support it, and the
 //! messages will include StreamEvent messages interspersed with regular messages.
 
-use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, Message};
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ContentBlockDelta, Message, StreamEventData,
+};
 use std::collections::HashMap;
 
 #[tokio::main]
@@ -49,10 +51,16 @@ async fn main() -> anyhow::Result<()> {
     let mut stream = client.receive_response();
     while let Some(message) = stream.next().await {
         match message? {
-            Message::StreamEvent(event) => {
-                println!("Stream Event: {:?}", event);
-                // You can access partial content as it arrives
-                // This is useful for real-time UI updates
+            Message::StreamEvent(event) => match event.data() {
+                // Partial content arrives as deltas, useful for real-time UI updates
+                StreamEventData::ContentBlockDelta { delta, .. } => match delta {
+                    ContentBlockDelta::TextDelta { text } => println!("Text delta: {:?}", text),
+                    ContentBlockDelta::ThinkingDelta { thinking } => {
+                        println!("Thinking delta: {:?}", thinking)
+                    },
+                    other => println!("Other delta: {:?}", other),
+                },
+                other => println!("Stream Event: {:?}", other),
             },
             Message::Assistant(msg) => {
                 for block in &msg.message.content {
@@ -124,6 +124,7 @@ async fn review_tool_output(
                             additional_context: Some(
                                 "The tool encountered an error during execution".to_string(),
                             ),
+                            updated_mcp_tool_output: None,
                         },
                     )),
                     ..Default::default()
@@ -93,6 +93,9 @@ async fn main() -> anyhow::Result<()> {
                                 println!("  Image (url): {}", url);
                             },
                         },
+                        ContentBlock::Unknown(block) => {
+                            println!("  Unknown block: {}", block["type"]);
+                        },
                     }
                 }
                 println!();
@@ -156,7 +156,7 @@ async fn example_image_url() -> anyhow::Result<()> {
     // In real usage, this would be sent to Claude
     let content = [
         UserContentBlock::text("Describe this diagram"),
-        UserContentBlock::image_url("https://example.com/architecture-diagram.png"),
+        UserContentBlock::image_url("https://example.com/architecture-diagram.png")?,
     ];
 
     println!("Content blocks created:");
@@ -15,6 +15,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Claude Agent Team".to_string()),
             dependencies: vec![],
             tags: vec!["math".to_string(), "utility".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: r#"You are a calculator. 
 When given mathematical expressions, evaluate them and return the result.
@@ -21,6 +21,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Math Team".to_string()),
             dependencies: vec![],
             tags: vec!["math".to_string(), "utility".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: r#"You are a calculator assistant.
 When given mathematical expressions, evaluate them and provide the result.
@@ -51,6 +54,9 @@ Support basic operations: addition, subtraction, multiplication, division."#
             author: Some("I18n Team".to_string()),
             dependencies: vec![],
             tags: vec!["translation".to_string(), "text".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: r#"You are a translation assistant.
 Translate the given text to the target language while preserving meaning and tone."#
@@ -95,6 +95,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Claude SDK Team".to_string()),
             dependencies: vec!["serde".to_string(), "tokio".to_string()],
             tags: vec!["data".to_string(), "processing".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: "你是一个专业的数据处理助手。".to_string(),
         scripts: vec!["setup.sh".to_string(), "run.sh".to_string()],
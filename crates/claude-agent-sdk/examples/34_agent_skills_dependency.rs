@@ -5,7 +5,7 @@
 //! 运行: cargo run --example 34_agent_skills_dependency
 
 use claude_agent_sdk::skills::{
-    Dependency, DependencyResolver, ResolutionResult, SkillMetadata, SkillPackage,
+    Dependency, DependencyResolver, SkillMetadata, SkillPackage,
 };
 use std::collections::HashMap;
 
@@ -24,6 +24,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Claude SDK Team".to_string()),
             dependencies: vec!["utils".to_string()],
             tags: vec!["data".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: "你是一个专业的数据处理助手。".to_string(),
         scripts: vec![],
@@ -39,6 +42,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Claude SDK Team".to_string()),
             dependencies: vec!["logger".to_string()],
             tags: vec!["utility".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: "提供通用工具函数。".to_string(),
         scripts: vec![],
@@ -54,6 +60,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Claude SDK Team".to_string()),
             dependencies: vec![],
             tags: vec!["logging".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: "提供日志记录功能。".to_string(),
         scripts: vec![],
@@ -69,6 +78,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Claude SDK Team".to_string()),
             dependencies: vec!["data-processor".to_string(), "utils".to_string()],
             tags: vec!["analytics".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: "提供数据分析功能。".to_string(),
         scripts: vec![],
@@ -116,26 +128,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 4. 解析依赖关系
     println!("4️⃣  解析依赖关系");
-    match resolver.resolve(&skills_graph) {
-        ResolutionResult::Resolved { load_order } => {
-            println!("   ✅ 依赖解析成功!");
-            println!("\n   推荐加载顺序:");
-            for (i, skill_id) in load_order.iter().enumerate() {
-                println!("      {}. {}", i + 1, skill_id);
-            }
-            println!();
-            println!("   说明: 按此顺序加载可以确保所有依赖都先于依赖它们的技能加载。");
-        },
-        ResolutionResult::CircularDependency { cycle } => {
-            println!("   ❌ 检测到循环依赖:");
-            println!("      {:?}", cycle);
-        },
-        ResolutionResult::MissingDependencies { missing } => {
-            println!("   ❌ 缺少以下依赖:");
-            for dep in &missing {
-                println!("      - {}", dep);
-            }
-        },
+    let result = resolver.resolve(&skills_graph);
+    if result.is_resolved() {
+        println!("   ✅ 依赖解析成功!");
+        println!("\n   推荐加载顺序:");
+        for (i, skill_id) in result.load_order.iter().enumerate() {
+            println!("      {}. {}", i + 1, skill_id);
+        }
+        println!();
+        println!("   说明: 按此顺序加载可以确保所有依赖都先于依赖它们的技能加载。");
+    }
+    for cycle in &result.cycles {
+        println!("   ❌ 检测到循环依赖:");
+        println!("      {:?}", cycle);
+    }
+    if !result.missing.is_empty() {
+        println!("   ❌ 缺少以下依赖:");
+        for dep in &result.missing {
+            println!("      - {}", dep);
+        }
     }
     println!();
 
@@ -151,14 +162,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     resolver_circular.add_skill("skill-b", "1.0.0");
     resolver_circular.add_skill("skill-c", "1.0.0");
 
-    match resolver_circular.resolve(&circular_graph) {
-        ResolutionResult::CircularDependency { cycle } => {
-            println!("   ✅ 成功检测到循环依赖:");
-            println!("      循环路径: {}", cycle.join(" -> "));
-        },
-        _ => {
-            println!("   ❌ 未能检测到循环依赖");
-        },
+    let result = resolver_circular.resolve(&circular_graph);
+    if result.cycles.is_empty() {
+        println!("   ❌ 未能检测到循环依赖");
+    }
+    for cycle in &result.cycles {
+        println!("   ✅ 成功检测到循环依赖:");
+        println!("      循环路径: {}", cycle.join(" -> "));
     }
     println!();
 
@@ -171,16 +181,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     resolver_incomplete.add_skill("my-skill", "1.0.0");
     // 故意不添加 missing-dep
 
-    match resolver_incomplete.resolve(&incomplete_graph) {
-        ResolutionResult::MissingDependencies { missing } => {
-            println!("   ✅ 成功检测到缺少依赖:");
-            for dep in &missing {
-                println!("      - {}", dep);
-            }
-        },
-        _ => {
-            println!("   ❌ 未能检测到缺少依赖");
-        },
+    let result = resolver_incomplete.resolve(&incomplete_graph);
+    if result.missing.is_empty() {
+        println!("   ❌ 未能检测到缺少依赖");
+    } else {
+        println!("   ✅ 成功检测到缺少依赖:");
+        for dep in &result.missing {
+            println!("      - {}", dep);
+        }
     }
     println!();
 
@@ -203,22 +211,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             .metadata
             .dependencies
             .iter()
-            .map(|d| Dependency::new(d.as_str()))
+            .map(|d| Dependency::parse(d.as_str()))
             .collect();
         auto_graph.insert(package.metadata.id.clone(), deps);
     }
 
     println!("   ✅ 自动构建了依赖图");
-    match resolver_auto.resolve(&auto_graph) {
-        ResolutionResult::Resolved { load_order } => {
-            println!("   ✅ 解析成功,加载顺序:");
-            for (i, skill_id) in load_order.iter().enumerate() {
-                println!("      {}. {}", i + 1, skill_id);
-            }
-        },
-        _ => {
-            println!("   ❌ 解析失败");
-        },
+    let result = resolver_auto.resolve(&auto_graph);
+    if result.is_resolved() {
+        println!("   ✅ 解析成功,加载顺序:");
+        for (i, skill_id) in result.load_order.iter().enumerate() {
+            println!("      {}. {}", i + 1, skill_id);
+        }
+    } else {
+        println!("   ❌ 解析失败");
     }
     println!();
 
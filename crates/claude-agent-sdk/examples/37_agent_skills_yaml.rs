@@ -29,6 +29,9 @@ fn main() {
                 "quality".to_string(),
                 "development".to_string(),
             ],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
 
         instructions: r#"# Code Review Instructions
@@ -146,6 +149,9 @@ Provide structured feedback with:
             author: None,
             dependencies: vec![],
             tags: vec![],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
 
         instructions: "Say hello to the world!".to_string(),
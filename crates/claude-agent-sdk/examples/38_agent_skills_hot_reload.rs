@@ -35,6 +35,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Demo Team".to_string()),
             dependencies: vec![],
             tags: vec!["data".to_string(), "processing".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: "Process the data efficiently".to_string(),
         scripts: vec![],
@@ -90,6 +93,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             author: Some("Demo Team".to_string()),
             dependencies: vec![],
             tags: vec!["text".to_string(), "analysis".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: "Analyze text patterns".to_string(),
         scripts: vec![],
@@ -4,7 +4,7 @@
 //! for secure, isolated skill script execution.
 
 use claude_agent_sdk::skills::sandbox::{
-    SandboxConfig, SandboxExecutor, SandboxResult, SandboxUtils,
+    IsolationLevel, SandboxConfig, SandboxExecutor, SandboxResult, SandboxUtils,
 };
 use std::time::Duration;
 
@@ -124,41 +124,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Huge script → Timeout: {:?}", config2.timeout);
     println!();
 
-    // Example 8: Create sandbox executor and execute (fallback mode)
-    println!("8. Sandbox Execution (Fallback Mode)");
-    println!("------------------------------------");
-    #[cfg(feature = "sandbox")]
-    {
-        let executor = SandboxExecutor::new(SandboxConfig::default());
-        let script = "print('Hello from sandbox!')";
-
-        match executor.execute(script, None).await {
-            Ok(result) => {
-                println!("✓ Execution successful");
-                println!("  Exit code: {}", result.exit_code);
-                println!("  Time: {} ms", result.execution_time_ms);
-                println!("  Timed out: {}", result.timed_out);
-                println!("  Stdout: {}", result.stdout);
-                if !result.stderr.is_empty() {
-                    println!("  Stderr: {}", result.stderr);
-                }
-            },
-            Err(e) => {
-                println!("✗ Execution failed: {}", e);
-            },
-        }
-    }
-
-    #[cfg(not(feature = "sandbox"))]
-    {
-        println!("Note: Enable with --features sandbox for actual execution");
-        let executor = SandboxExecutor::new(SandboxConfig::default());
-        let script = "print('Hello from sandbox!')";
-
-        match executor.execute(script, None).await {
-            Ok(_) => println!("✓ Unexpected success (should fail without feature)"),
-            Err(e) => println!("Expected error: {}", e),
-        }
+    // Example 8: Execute a script confined to its working directory
+    println!("8. Sandbox Execution");
+    println!("--------------------");
+    println!(
+        "Filesystem isolation available: {}",
+        SandboxUtils::filesystem_isolation_available()
+    );
+    let executor = SandboxExecutor::new(SandboxConfig::default());
+    let script = "echo 'Hello from sandbox!'; cat ~/.ssh/id_rsa";
+
+    match executor.execute(script, None).await {
+        Ok(result) => {
+            println!("✓ Execution finished");
+            println!("  Isolation: {:?}", result.isolation);
+            println!("  Exit code: {}", result.exit_code);
+            println!("  Time: {} ms", result.execution_time_ms);
+            println!("  Timed out: {}", result.timed_out);
+            println!("  Stdout: {}", result.stdout.trim());
+            if !result.stderr.is_empty() {
+                println!("  Stderr: {}", result.stderr.trim());
+            }
+        },
+        Err(e) => {
+            println!("✗ Execution failed: {}", e);
+        },
     }
     println!();
 
@@ -173,6 +163,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         timed_out: false,
         memory_used: Some(1024),
         fuel_consumed: Some(5000),
+        isolation: IsolationLevel::Filesystem,
+        truncated: false,
     };
 
     println!("Success check: {}", success_result.is_success());
@@ -186,6 +178,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         timed_out: false,
         memory_used: None,
         fuel_consumed: None,
+        isolation: IsolationLevel::Filesystem,
+        truncated: false,
     };
 
     println!("Failure check: {}", failure_result.is_success());
@@ -199,6 +193,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         timed_out: true,
         memory_used: None,
         fuel_consumed: None,
+        isolation: IsolationLevel::Filesystem,
+        truncated: false,
     };
 
     println!("Timeout check: {}", timeout_result.is_success());
@@ -208,18 +204,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Example 10: Execute from file
     println!("10. Execute Script File");
     println!("----------------------");
-    #[cfg(feature = "sandbox")]
     {
         let executor = SandboxExecutor::new(SandboxConfig::default());
-        let script_path = Path::new("/tmp/test_skill.js");
+        let script_path = std::env::temp_dir().join("test_skill.sh");
 
         // Create a test script file
-        std::fs::write(script_path, "console.log('Hello from file!');")?;
+        std::fs::write(&script_path, "echo 'Hello from file!'")?;
 
-        match executor.execute_file(script_path, None).await {
+        match executor.execute_file(&script_path, None).await {
             Ok(result) => {
                 println!("✓ File execution successful");
-                println!("  Stdout: {}", result.stdout);
+                println!("  Stdout: {}", result.stdout.trim());
             },
             Err(e) => {
                 println!("✗ File execution failed: {}", e);
@@ -227,12 +222,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
 
         // Clean up
-        std::fs::remove_file(script_path)?;
-    }
-
-    #[cfg(not(feature = "sandbox"))]
-    {
-        println!("Note: Enable with --features sandbox for file execution");
+        std::fs::remove_file(&script_path)?;
     }
     println!();
 
@@ -265,6 +255,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         allow_network: false,
         allow_filesystem: false,
         working_directory: None,
+        ..SandboxConfig::default()
     };
     println!("Unlimited memory: {:?}", unlimited.max_memory);
     println!("Unlimited fuel: {:?}", unlimited.max_fuel);
@@ -277,7 +268,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("- Restrictive config for untrusted, Permissive for trusted");
     println!("- Builder pattern allows custom configurations");
     println!("- Validation and estimation tools help planning");
-    println!("- Graceful fallback when sandbox feature is disabled");
+    println!("- SandboxResult::isolation shows whether filesystem scoping was enforced");
 
     Ok(())
 }
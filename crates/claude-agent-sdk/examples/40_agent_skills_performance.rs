@@ -21,6 +21,9 @@ fn create_skill(name: &str, tags: Vec<&str>) -> SkillPackage {
             author: Some("Test Author".to_string()),
             dependencies: Vec::new(),
             tags: tags.into_iter().map(String::from).collect(),
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: format!("Instructions for {}", name),
         scripts: Vec::new(),
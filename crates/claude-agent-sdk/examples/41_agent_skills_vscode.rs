@@ -5,7 +5,8 @@
 
 use claude_agent_sdk::skills::types::{SkillMetadata, SkillPackage, SkillResources};
 use claude_agent_sdk::skills::vscode::{
-    VsCodeExportConfig, VsCodeUtils, export_batch_to_vscode, export_to_vscode,
+    VsCodeExportConfig, VsCodeUtils, export_batch_to_vscode, export_batch_to_vscode_with_report,
+    export_to_vscode, import_from_vscode,
 };
 use std::path::PathBuf;
 use uuid::Uuid;
@@ -20,6 +21,9 @@ fn create_skill(name: &str, description: &str, tags: Vec<&str>) -> SkillPackage
             author: Some("Claude SDK Team".to_string()),
             dependencies: vec!["claude-agent-sdk-rs".to_string()],
             tags: tags.into_iter().map(String::from).collect(),
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: format!(
             "You are a {} assistant. Help users with {} related tasks.",
@@ -280,6 +284,9 @@ For more information, visit: https://github.com/louloulin/claude-agent-sdk"
                 "api".to_string(),
                 "generator".to_string(),
             ],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         },
         instructions: r#"
 Generate comprehensive API documentation following these guidelines:
@@ -330,6 +337,41 @@ Generate comprehensive API documentation following these guidelines:
         },
         Err(e) => println!("✗ Export failed: {}", e),
     }
+    println!();
+
+    // Example 11: Round-trip import and tasks.json
+    println!("11. Round-trip Import and tasks.json");
+    println!("------------------------------------");
+    let workspace = PathBuf::from("/tmp/skills_workspace");
+    let tasks_config = VsCodeExportConfig::new().with_tasks("bash").with_dry_run(true);
+
+    match export_batch_to_vscode_with_report(std::slice::from_ref(&skill), &workspace, &tasks_config) {
+        Ok(report) => {
+            println!("Dry run, would change:");
+            for line in report.diff.lines().take(8) {
+                println!("  {}", line);
+            }
+            println!("  ...");
+        },
+        Err(e) => println!("✗ Dry run failed: {}", e),
+    }
+
+    let tasks_config = tasks_config.with_dry_run(false);
+    match export_batch_to_vscode_with_report(std::slice::from_ref(&skill), &workspace, &tasks_config)
+        .and_then(|report| import_from_vscode(&report.skill_files[0]))
+    {
+        Ok(imported) => {
+            let round_trip = imported[0].metadata.tags == skill.metadata.tags
+                && imported[0].scripts == skill.scripts;
+            println!(
+                "{} Imported '{}' back from {:?} (tasks in .vscode/tasks.json)",
+                if round_trip { "✓" } else { "✗" },
+                imported[0].metadata.name,
+                workspace
+            );
+        },
+        Err(e) => println!("✗ Round trip failed: {}", e),
+    }
 
     println!();
     println!("=== Demo Complete ===");
@@ -339,6 +381,7 @@ Generate comprehensive API documentation following these guidelines:
     println!("- Comprehensive validation (name, description)");
     println!("- Flexible export configuration");
     println!("- Single and batch export support");
+    println!("- Import of exported files and .vscode/tasks.json generation");
     println!("- Customizable footer");
     println!("- Proper YAML frontmatter structure");
     println!("- Syntax highlighting for scripts");
@@ -107,6 +107,7 @@ async fn task_with_progress() -> Result<(), Box<dyn std::error::Error>> {
             estimated_duration_secs: Some(10),
             supports_progress: true,
             cancellable: true,
+            ttl_secs: None,
         }),
         ..Default::default()
     };
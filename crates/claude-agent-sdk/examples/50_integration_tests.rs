@@ -483,7 +483,7 @@ async fn test_memory_leak() -> Result<()> {
 /// Cleanup and teardown
 async fn test_cleanup() -> Result<()> {
     // Test proper resource cleanup
-    let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    let client = ClaudeClient::new(ClaudeAgentOptions::default());
     client.connect().await?;
     client.disconnect().await?;
 
@@ -0,0 +1,148 @@
+//! # Router Orchestration Example
+//!
+//! This example routes each request to either a "coder" or a "researcher"
+//! agent, so only the agent suited to the request runs.
+//!
+//! ## Routers Demonstrated
+//!
+//! 1. **Keyword Routing**: The built-in `KeywordRouter` matches words of the
+//!    request against each agent's keywords, with a fallback agent for
+//!    requests matching neither.
+//!
+//! 2. **Custom Routing**: A `Router` implementation with its own rules.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 52_orchestration_router
+//! ```
+
+use claude_agent_sdk::orchestration::agent::SimpleAgent;
+use claude_agent_sdk::orchestration::{
+    Agent, AgentMetadata, AgentOutput, KeywordRouter, OrchestrationError, Orchestrator,
+    OrchestratorInput, Result, Router, RouterOrchestrator,
+};
+
+/// Create a coder agent that writes and fixes code
+fn create_coder() -> Box<dyn Agent> {
+    Box::new(SimpleAgent::new(
+        "coder",
+        "Writes, fixes and reviews code",
+        |input| {
+            Ok(AgentOutput::new(format!(
+                "💻 Coder: patch prepared for '{}'",
+                input.content
+            )))
+        },
+    ))
+}
+
+/// Create a researcher agent that gathers information
+fn create_researcher() -> Box<dyn Agent> {
+    Box::new(SimpleAgent::new(
+        "researcher",
+        "Finds and summarizes sources",
+        |input| {
+            Ok(AgentOutput::new(format!(
+                "📚 Researcher: 4 sources summarized for '{}'",
+                input.content
+            )))
+        },
+    ))
+}
+
+fn agents() -> Vec<Box<dyn Agent>> {
+    vec![create_coder(), create_researcher()]
+}
+
+/// Routes requests mentioning a file extension to the coder, questions to the researcher
+struct FileOrQuestionRouter;
+
+impl Router for FileOrQuestionRouter {
+    fn route(&self, input: &OrchestratorInput, agents: &[AgentMetadata]) -> Result<usize> {
+        let wanted = if input.content.contains(".rs") || input.content.contains(".ts") {
+            "coder"
+        } else if input.content.trim_end().ends_with('?') {
+            "researcher"
+        } else {
+            return Err(OrchestrationError::NoAgentMatched);
+        };
+        agents
+            .iter()
+            .position(|agent| agent.name == wanted)
+            .ok_or(OrchestrationError::NoAgentMatched)
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    println!("╔════════════════════════════════════════════════════════════╗");
+    println!("║     Router Orchestration Examples                          ║");
+    println!("╚════════════════════════════════════════════════════════════╝\n");
+
+    println!("📌 Example 1: Keyword Routing");
+    println!("{}", "─".repeat(60));
+    keyword_routing_example().await?;
+    println!();
+
+    println!("\n📌 Example 2: Custom Router");
+    println!("{}", "─".repeat(60));
+    custom_router_example().await?;
+    println!();
+
+    Ok(())
+}
+
+/// Example 1: keyword routing with a fallback to the researcher
+async fn keyword_routing_example() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let router = KeywordRouter::new()
+        .with_keywords("coder", ["bug", "fix", "refactor", "compile", "test"])
+        .with_keywords("researcher", ["research", "sources", "compare", "papers"]);
+    let orchestrator = RouterOrchestrator::new(Box::new(router)).with_fallback(1);
+
+    for request in [
+        "Fix the failing test in the parser",
+        "Compare papers on vector databases",
+        "What's the weather like?",
+    ] {
+        let output = orchestrator
+            .orchestrate(agents(), OrchestratorInput::new(request))
+            .await?;
+        let routing = output.execution_trace.routing.as_ref();
+
+        println!("  Request: {}", request);
+        if let Some(routing) = routing {
+            println!(
+                "  Routed to: {}{}",
+                routing.agent,
+                if routing.fallback { " (fallback)" } else { "" }
+            );
+        }
+        println!("  {}\n", output.result);
+    }
+
+    Ok(())
+}
+
+/// Example 2: custom routing without a fallback
+async fn custom_router_example() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let orchestrator = RouterOrchestrator::new(Box::new(FileOrQuestionRouter));
+
+    for request in [
+        "Tidy up src/main.rs",
+        "Which crates implement async DNS?",
+        "Hello there",
+    ] {
+        println!("  Request: {}", request);
+        match orchestrator
+            .orchestrate(agents(), OrchestratorInput::new(request))
+            .await
+        {
+            Ok(output) => println!("  {}\n", output.result),
+            Err(OrchestrationError::NoAgentMatched) => println!("  No agent matched\n"),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! # Long-Running MCP Tools as Tasks
+//!
+//! This example serves a slow tool from an in-process MCP server without
+//! blocking the tool call: the call returns a task reference right away, and
+//! the result is fetched once the work is done.
+//!
+//! ## Parts
+//!
+//! 1. **Task protocol**: The `tasks/*` requests the CLI sends to a server
+//!    configured with `with_task_manager`, replayed locally with
+//!    `handle_jsonrpc`.
+//!
+//! 2. **Claude polling**: A `start_crunch` tool that starts a 30 second job as
+//!    a task, and a `check_crunch` tool Claude calls until the job is done.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 53_mcp_task_tools
+//! ```
+
+use claude_agent_sdk::mcp::{TaskManager, TaskProgress, TaskRequest, TaskState};
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ContentBlock, McpServerConfig, McpServers,
+    McpToolResultContent, Message, ToolResult, create_sdk_mcp_server, tool,
+};
+use futures::StreamExt;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn text_result(text: impl Into<String>) -> ToolResult {
+    ToolResult {
+        content: vec![McpToolResultContent::Text { text: text.into() }],
+        is_error: false,
+    }
+}
+
+/// Crunch numbers for `seconds` seconds, like `sleep 30` with a result
+async fn crunch(seconds: u64) -> u64 {
+    tokio::time::sleep(Duration::from_secs(seconds)).await;
+    seconds * 1_000_000
+}
+
+/// Run a crunch job in the background as a task of `manager`
+async fn start_crunch(manager: TaskManager, seconds: u64) -> anyhow::Result<ToolResult> {
+    let handle = manager
+        .create_task(TaskRequest {
+            method: "crunch".to_string(),
+            params: json!({"seconds": seconds}),
+            ..Default::default()
+        })
+        .await?;
+    let cancellation = manager.cancellation_token(&handle.id).await?;
+
+    let task_id = handle.id.clone();
+    tokio::spawn(async move {
+        manager.mark_working(&task_id).await?;
+        for second in 0..seconds {
+            tokio::select! {
+                _ = cancellation.cancelled() => return Ok(()),
+                _ = tokio::time::sleep(Duration::from_secs(1)) => {},
+            }
+            let progress = TaskProgress::new((second + 1) as f64 / seconds as f64)
+                .with_message(format!("{} of {} seconds crunched", second + 1, seconds));
+            manager.update_progress(&task_id, progress).await?;
+        }
+        manager
+            .mark_completed(&task_id, json!({"checksum": seconds * 1_000_000}))
+            .await
+    });
+
+    Ok(text_result(format!(
+        "Started crunch job {}. Call check_crunch with this id to follow it.",
+        handle.id
+    )))
+}
+
+/// Report the state of a crunch job started by `start_crunch`
+async fn check_crunch(manager: TaskManager, task_id: String) -> anyhow::Result<ToolResult> {
+    let status = manager.get_task_status(&task_id).await?;
+    let text = match status.state {
+        TaskState::Completed => {
+            let result = manager.get_task_result(&task_id).await?;
+            format!("Job finished with checksum {}", result.data["checksum"])
+        },
+        TaskState::Failed => format!("Job failed: {}", status.error.unwrap_or_default()),
+        TaskState::Cancelled => "Job was cancelled".to_string(),
+        _ => {
+            let percent = status.progress.as_ref().map_or(0.0, |p| p.value * 100.0);
+            format!(
+                "Job still running ({:.0}% done), check again shortly",
+                percent
+            )
+        },
+    };
+    Ok(text_result(text))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("╔════════════════════════════════════════════════════════════╗");
+    println!("║     Long-Running MCP Tools as Tasks                        ║");
+    println!("╚════════════════════════════════════════════════════════════╝\n");
+
+    let manager = TaskManager::new();
+
+    let crunch_tool = tool!(
+        "crunch",
+        "Crunch numbers for the given number of seconds",
+        json!({
+            "type": "object",
+            "properties": {"seconds": {"type": "integer"}},
+            "required": ["seconds"]
+        }),
+        |args: serde_json::Value| async move {
+            let checksum = crunch(args["seconds"].as_u64().unwrap_or(30)).await;
+            Ok(text_result(format!("Checksum: {}", checksum)))
+        }
+    );
+
+    let starter = manager.clone();
+    let start_tool = tool!(
+        "start_crunch",
+        "Start a 30 second number crunching job and return its id",
+        json!({"type": "object", "properties": {}}),
+        move |_args: serde_json::Value| start_crunch(starter.clone(), 30)
+    );
+
+    let checker = manager.clone();
+    let check_tool = tool!(
+        "check_crunch",
+        "Check on a job started with start_crunch",
+        json!({
+            "type": "object",
+            "properties": {"task_id": {"type": "string"}},
+            "required": ["task_id"]
+        }),
+        move |args: serde_json::Value| {
+            let task_id = args["task_id"].as_str().unwrap_or_default().to_string();
+            check_crunch(checker.clone(), task_id)
+        }
+    );
+
+    let server = create_sdk_mcp_server("jobs", "1.0.0", vec![crunch_tool, start_tool, check_tool])
+        .with_task_manager(manager);
+
+    println!("📌 Part 1: Task protocol");
+    println!("{}", "─".repeat(60));
+    task_protocol_example(&server).await;
+    println!();
+
+    println!("\n📌 Part 2: Claude polling a slow job");
+    println!("{}", "─".repeat(60));
+    claude_polling_example(server).await?;
+    println!();
+
+    Ok(())
+}
+
+/// Part 1: the requests the CLI sends for a tool called as a task
+async fn task_protocol_example(server: &claude_agent_sdk::types::mcp::McpSdkServerConfig) {
+    let created = server
+        .handle_jsonrpc(
+            "demo",
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {"name": "crunch", "arguments": {"seconds": 2}, "task": {"ttl": 60000}}
+            }),
+        )
+        .await;
+    let task_id = created["result"]["task"]["taskId"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    println!("  tools/call returned at once: {}", created["result"]);
+
+    loop {
+        let status = server
+            .handle_jsonrpc(
+                "demo",
+                json!({"jsonrpc": "2.0", "id": 2, "method": "tasks/get", "params": {"taskId": task_id}}),
+            )
+            .await;
+        println!("  tasks/get: {}", status["result"]["status"]);
+        if status["result"]["status"] != "working" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let result = server
+        .handle_jsonrpc(
+            "demo",
+            json!({"jsonrpc": "2.0", "id": 3, "method": "tasks/result", "params": {"taskId": task_id}}),
+        )
+        .await;
+    println!("  tasks/result: {}", result["result"]["content"]);
+
+    let unknown = server
+        .handle_jsonrpc(
+            "demo",
+            json!({"jsonrpc": "2.0", "id": 4, "method": "tasks/get", "params": {"taskId": "missing"}}),
+        )
+        .await;
+    println!("  tasks/get of an unknown task: {}", unknown["error"]);
+}
+
+/// Part 2: Claude starts the job and polls it through tools
+async fn claude_polling_example(
+    server: claude_agent_sdk::types::mcp::McpSdkServerConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let options = ClaudeAgentOptions {
+        mcp_servers: McpServers::Dict(HashMap::from([(
+            "jobs".to_string(),
+            McpServerConfig::Sdk(server),
+        )])),
+        allowed_tools: vec![
+            "mcp__jobs__start_crunch".to_string(),
+            "mcp__jobs__check_crunch".to_string(),
+        ],
+        max_turns: Some(30),
+        ..Default::default()
+    };
+
+    let mut client = ClaudeClient::new(options);
+    client.connect().await?;
+    client
+        .query(
+            "Start a crunch job, then check on it every few seconds (use `sleep` in between \
+             if you have to wait) until it is done, and tell me its checksum.",
+        )
+        .await?;
+
+    let mut messages = client.receive_response();
+    while let Some(message) = messages.next().await {
+        match message? {
+            Message::Assistant(assistant) => {
+                for block in &assistant.message.content {
+                    match block {
+                        ContentBlock::Text(text) => println!("  Claude: {}", text.text),
+                        ContentBlock::ToolUse(tool_use) => println!("  🔧 {}", tool_use.name),
+                        _ => {},
+                    }
+                }
+            },
+            Message::Result(result) => {
+                println!("  Done in {} turns", result.num_turns);
+            },
+            _ => {},
+        }
+    }
+    drop(messages);
+
+    client.disconnect().await?;
+    Ok(())
+}
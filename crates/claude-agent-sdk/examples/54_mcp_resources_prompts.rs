@@ -0,0 +1,159 @@
+//! # MCP Resources and Prompts
+//!
+//! This example exposes a log file as a resource of an in-process MCP server,
+//! together with a `summarize-logs` prompt that asks Claude to read it.
+//!
+//! ## Parts
+//!
+//! 1. **Protocol**: The `resources/*` and `prompts/*` requests the CLI sends,
+//!    replayed locally with `handle_jsonrpc`.
+//!
+//! 2. **Claude**: Running the prompt, which the CLI offers as the slash
+//!    command `/mcp__logs__summarize-logs`.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 54_mcp_resources_prompts
+//! ```
+
+use claude_agent_sdk::mcp::{PromptArgument, PromptMessage, ResourceContents};
+use claude_agent_sdk::types::mcp::McpSdkServerConfig;
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ContentBlock, McpError, McpServerConfig, McpServers, Message,
+    create_sdk_mcp_server,
+};
+use futures::StreamExt;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const LOG_URI: &str = "file:///app/server.log";
+
+/// Write a small log file to read from
+fn write_log() -> std::io::Result<PathBuf> {
+    let path = std::env::temp_dir().join("claude-sdk-example-server.log");
+    std::fs::write(
+        &path,
+        "2025-01-06 09:00:01 INFO  server started on :8080\n\
+         2025-01-06 09:12:44 WARN  slow query (2.3s): SELECT * FROM orders\n\
+         2025-01-06 09:13:02 ERROR connection pool exhausted (max 10)\n\
+         2025-01-06 09:13:05 INFO  pool recovered\n",
+    )?;
+    Ok(path)
+}
+
+fn log_server(log_path: PathBuf) -> McpSdkServerConfig {
+    create_sdk_mcp_server("logs", "1.0.0", vec![])
+        .add_resource(LOG_URI, "Server log", "text/plain", move || {
+            let log_path = log_path.clone();
+            async move {
+                match tokio::fs::read_to_string(&log_path).await {
+                    Ok(log) => Ok(ResourceContents::Text(log)),
+                    // Reported to the CLI as a "resource not found" error
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        Err(McpError::resource_not_found(LOG_URI).into())
+                    },
+                    Err(e) => Err(e.into()),
+                }
+            }
+        })
+        .add_prompt(
+            "summarize-logs",
+            "Summarize the server log",
+            vec![PromptArgument::optional(
+                "focus",
+                "What to pay attention to, e.g. errors",
+            )],
+            |args: HashMap<String, String>| async move {
+                let focus = args.get("focus").map_or("anything unusual", String::as_str);
+                Ok(vec![PromptMessage::user(format!(
+                    "Read the MCP resource {} from the logs server and summarize it in \
+                     three bullet points, focusing on {}.",
+                    LOG_URI, focus
+                ))])
+            },
+        )
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("╔════════════════════════════════════════════════════════════╗");
+    println!("║     MCP Resources and Prompts                              ║");
+    println!("╚════════════════════════════════════════════════════════════╝\n");
+
+    let server = log_server(write_log()?);
+
+    println!("📌 Part 1: Protocol");
+    println!("{}", "─".repeat(60));
+    protocol_example(&server).await;
+    println!();
+
+    println!("\n📌 Part 2: Claude summarizing the log");
+    println!("{}", "─".repeat(60));
+    claude_example(server).await?;
+    println!();
+
+    Ok(())
+}
+
+/// Part 1: the requests the CLI sends to discover and use the server
+async fn protocol_example(server: &McpSdkServerConfig) {
+    let requests = [
+        ("initialize", json!({})),
+        ("resources/list", json!({})),
+        ("resources/read", json!({"uri": LOG_URI})),
+        ("resources/read", json!({"uri": "file:///missing.log"})),
+        ("prompts/list", json!({})),
+        (
+            "prompts/get",
+            json!({"name": "summarize-logs", "arguments": {"focus": "errors"}}),
+        ),
+    ];
+
+    for (id, (method, params)) in requests.into_iter().enumerate() {
+        let response = server
+            .handle_jsonrpc(
+                "demo",
+                json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}),
+            )
+            .await;
+        match response.get("error") {
+            Some(error) => println!("  {} -> error {}", method, error),
+            None => println!("  {} -> {}", method, response["result"]),
+        }
+    }
+}
+
+/// Part 2: run the prompt as a slash command
+async fn claude_example(server: McpSdkServerConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut servers = HashMap::new();
+    servers.insert("logs".to_string(), McpServerConfig::Sdk(server));
+    let options = ClaudeAgentOptions::builder()
+        .mcp_servers(McpServers::Dict(servers))
+        .allowed_tools(vec![
+            "ListMcpResourcesTool".to_string(),
+            "ReadMcpResourceTool".to_string(),
+        ])
+        .max_turns(4)
+        .build();
+
+    let mut client = ClaudeClient::new(options);
+    client.connect().await?;
+    client.query("/mcp__logs__summarize-logs errors").await?;
+
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Assistant(assistant) = message? {
+            for block in assistant.message.content {
+                if let ContentBlock::Text(text) = block {
+                    println!("  Claude: {}", text.text);
+                }
+            }
+        }
+    }
+    drop(stream);
+
+    client.disconnect().await?;
+    Ok(())
+}
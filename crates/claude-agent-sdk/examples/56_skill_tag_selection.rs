@@ -0,0 +1,99 @@
+//! # Selecting Skills by Tag
+//!
+//! This example loads the skills in `examples/.claude/skills`, picks the ones
+//! whose tags match a query, and hands their instructions to Claude through
+//! the system prompt.
+//!
+//! ## Parts
+//!
+//! 1. **Tag query**: `find_query` with `all_of`, `any_of` and `none_of`,
+//!    matched case-insensitively against each skill's `tags`.
+//!
+//! 2. **Glob tags**: a `TagFilter` with a `data-*` pattern.
+//!
+//! 3. **Agent**: the instructions of the selected skills appended to the
+//!    default Claude Code system prompt.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 56_skill_tag_selection
+//! ```
+
+use claude_agent_sdk::skills::{SkillRegistry, TagFilter};
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ContentBlock, Message, SystemPrompt, SystemPromptPreset, query,
+};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let skills_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/.claude/skills");
+    let mut registry = SkillRegistry::new();
+    let loaded = registry.load_dir(skills_dir)?;
+    println!("Loaded {} skills\n", loaded.len());
+
+    println!("=== Tag Query: devops AND (automation OR cicd) AND NOT deprecated ===");
+    let selected = registry.find_query(|q| {
+        q.all_of(["devops"])
+            .any_of(["automation", "cicd"])
+            .none_of(["deprecated"])
+    });
+    for skill in &selected {
+        println!("  {} [{}]", skill.name, skill.tags.join(", "));
+    }
+    println!();
+
+    println!("=== Glob Tag: data-* ===");
+    for skill in registry.find(&TagFilter::new().has("data-*")) {
+        println!("  {} [{}]", skill.name, skill.tags.join(", "));
+    }
+    println!();
+
+    if selected.is_empty() {
+        println!("No skills matched; nothing to send to Claude.");
+        return Ok(());
+    }
+
+    let mut instructions = String::from("You can apply these skills:\n");
+    for skill in &selected {
+        let Some(package) = registry
+            .get(&skill.name)
+            .and_then(|skill| skill.skill_package())
+        else {
+            continue;
+        };
+        instructions.push_str(&format!(
+            "\n## Skill: {}\n\n{}\n",
+            skill.name,
+            package.instructions.trim()
+        ));
+    }
+
+    println!("=== Agent with {} selected skills ===", selected.len());
+    let options = ClaudeAgentOptions {
+        system_prompt: Some(SystemPrompt::Preset(SystemPromptPreset::with_append(
+            "claude_code",
+            instructions,
+        ))),
+        max_turns: Some(1),
+        ..Default::default()
+    };
+
+    let messages = query(
+        "Which of your skills would you use to set up a deployment pipeline, and why?",
+        Some(options),
+    )
+    .await?;
+
+    for message in &messages {
+        if let Message::Assistant(msg) = message {
+            for block in &msg.message.content {
+                if let ContentBlock::Text(text) = block {
+                    println!("Claude: {}", text.text);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! # Concurrent Sessions on One Client
+//!
+//! This example runs two conversations through a single `ClaudeClient`. Each
+//! gets a `SessionHandle`, which sends prompts in its session and receives only
+//! the messages tagged with its session ID, so both can progress at once.
+//!
+//! ## Parts
+//!
+//! 1. **Handles**: `client.session(id)` for a math tutor and a poet, with
+//!    `TurnPolicy::Interleave` so their turns may overlap.
+//!
+//! 2. **Concurrent turns**: both sessions send and receive inside one
+//!    `tokio::join!`, then ask a follow-up that relies on their own context.
+//!
+//! 3. **Debugging**: `client.active_sessions()` lists the sessions that have a
+//!    live handle.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 57_multi_session
+//! ```
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ContentBlock, Message, SessionHandle, TurnPolicy,
+};
+use futures::StreamExt;
+
+/// Send `prompt` in `session` and print its answer
+async fn ask(session: &SessionHandle<'_>, prompt: &str) -> anyhow::Result<()> {
+    session.send(prompt).await?;
+
+    let mut stream = session.receive_response();
+    while let Some(message) = stream.next().await {
+        match message? {
+            Message::Assistant(assistant) => {
+                for block in &assistant.message.content {
+                    if let ContentBlock::Text(text) = block {
+                        println!("[{}] {}", session.session_id(), text.text);
+                    }
+                }
+            },
+            Message::Result(result) => {
+                println!(
+                    "[{}] done in {} ms",
+                    session.session_id(),
+                    result.duration_ms
+                );
+            },
+            _ => {},
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let options = ClaudeAgentOptions::builder()
+        .turn_policy(TurnPolicy::Interleave)
+        .max_turns(1)
+        .build();
+    let client = ClaudeClient::new(options);
+    client.connect().await?;
+
+    // 1. One handle per conversation
+    let math = client.session("math");
+    let poetry = client.session("poetry");
+    println!("Active sessions: {:?}\n", client.active_sessions());
+
+    // 2. Both sessions progress at the same time
+    let (math_done, poetry_done) = tokio::join!(
+        ask(&math, "What is 17 * 23? Answer in one line."),
+        ask(&poetry, "Write a haiku about the sea."),
+    );
+    math_done?;
+    poetry_done?;
+
+    println!();
+    let (math_done, poetry_done) = tokio::join!(
+        ask(&math, "Now divide that by 17."),
+        ask(&poetry, "Give it a title."),
+    );
+    math_done?;
+    poetry_done?;
+
+    // 3. Dropping a handle releases its session
+    drop(poetry);
+    println!("\nActive sessions: {:?}", client.active_sessions());
+
+    drop(math);
+    client.disconnect().await?;
+    Ok(())
+}
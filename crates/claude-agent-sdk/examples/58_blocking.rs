@@ -0,0 +1,53 @@
+//! # Blocking API
+//!
+//! This example uses the SDK from plain synchronous code: no `async fn`, no
+//! `#[tokio::main]`. The `blocking` module runs its own runtime internally.
+//!
+//! ## Parts
+//!
+//! 1. **One-shot prompt**: `blocking::prompt` returns the answer text.
+//!
+//! 2. **Client**: `blocking::Client` keeps a conversation going, with
+//!    `receive_response()` as an ordinary iterator.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 58_blocking --features blocking
+//! ```
+
+use claude_agent_sdk::blocking;
+use claude_agent_sdk::{ClaudeAgentOptions, ContentBlock, Message};
+
+fn main() -> anyhow::Result<()> {
+    // 1. One question, one answer
+    let answer = blocking::prompt("What is the capital of France?", Default::default())?;
+    println!("Answer: {}\n", answer.content);
+
+    // 2. A conversation over several turns
+    let options = ClaudeAgentOptions::builder().max_turns(1).build();
+    let mut client = blocking::Client::new(options)?;
+    client.connect()?;
+
+    for prompt in ["Pick a random fruit.", "What color is it?"] {
+        println!("> {prompt}");
+        client.query(prompt)?;
+        for message in client.receive_response() {
+            match message? {
+                Message::Assistant(assistant) => {
+                    for block in &assistant.message.content {
+                        if let ContentBlock::Text(text) = block {
+                            println!("{}", text.text);
+                        }
+                    }
+                },
+                Message::Result(result) => println!("(done in {} ms)\n", result.duration_ms),
+                _ => {},
+            }
+        }
+    }
+
+    println!("Spent ${:.4}", client.usage().total.total_cost_usd);
+    client.disconnect()?;
+    Ok(())
+}
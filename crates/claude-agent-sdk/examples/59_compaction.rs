@@ -0,0 +1,77 @@
+//! # Conversation Compaction
+//!
+//! This example runs a long conversation on one `ClaudeClient` while keeping
+//! its context bounded. Every turn adds to the context; once it holds
+//! `auto_compact_threshold_tokens`, the client compacts the conversation
+//! before sending the next prompt, and the context size drops again.
+//!
+//! ## Parts
+//!
+//! 1. **PreCompact hook**: logs each compaction and its trigger. Returning
+//!    `continue: false` from it would block the compaction.
+//!
+//! 2. **Long loop**: asks for a fact per iteration and prints
+//!    `usage().context_tokens`, which stays under the threshold plus one turn.
+//!
+//! 3. **Manual compaction**: `client.compact(..)` with instructions on what the
+//!    summary should keep.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 59_compaction
+//! ```
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, CompactionOutcome, HookInput, HookJsonOutput, Hooks,
+    SyncHookJsonOutput,
+};
+
+const THRESHOLD_TOKENS: u64 = 30_000;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // 1. Log compactions as the CLI starts them
+    let mut hooks = Hooks::new();
+    hooks.add_pre_compact(|input, _tool_use_id, _context| async move {
+        if let HookInput::PreCompact(input) = input {
+            println!("  [PreCompact] trigger: {}", input.trigger);
+        }
+        HookJsonOutput::Sync(SyncHookJsonOutput::default())
+    });
+
+    let options = ClaudeAgentOptions::builder()
+        .hooks(hooks.build())
+        .auto_compact_threshold_tokens(THRESHOLD_TOKENS)
+        .max_turns(1)
+        .build();
+    let mut client = ClaudeClient::new(options);
+    client.connect().await?;
+
+    // 2. A long loop whose context would otherwise keep growing
+    let mut peak = 0;
+    for i in 1..=40 {
+        let prompt = format!(
+            "Fact #{i}: explain one feature of the Rust language in about 300 words."
+        );
+        client.query_collect(prompt).await?;
+
+        let context_tokens = client.usage().context_tokens;
+        peak = peak.max(context_tokens);
+        println!("turn {i:>2}: {context_tokens:>6} tokens in context");
+    }
+    println!("Peak context: {peak} tokens (threshold {THRESHOLD_TOKENS})\n");
+
+    // 3. Compact on demand, steering what the summary keeps
+    let instructions = "Keep only the list of features covered so far".to_string();
+    match client.compact(Some(instructions)).await? {
+        CompactionOutcome::Compacted(boundary) => {
+            println!("Compacted {:?} tokens", boundary.pre_tokens)
+        },
+        CompactionOutcome::Skipped(result) => println!("Not compacted: {:?}", result.result),
+    }
+
+    println!("Spent ${:.4}", client.usage().total.total_cost_usd);
+    client.disconnect().await?;
+    Ok(())
+}
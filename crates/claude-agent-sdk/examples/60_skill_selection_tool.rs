@@ -0,0 +1,73 @@
+//! # Skill Selection Tool
+//!
+//! This example lets Claude pick skills by itself. The skills under
+//! `.claude/skills` are loaded into a `SkillRegistry`, which is then served to
+//! Claude as an in-process MCP server. Claude searches the skills for the task
+//! at hand, reads the instructions of the best match, and runs it.
+//!
+//! ## Parts
+//!
+//! 1. **Registry**: loads the `SKILL.md` packages. Skills with
+//!    `disable_model_invocation: true` stay hidden from Claude.
+//!
+//! 2. **MCP server**: `as_mcp_server("skills")` exposes `search_skills`,
+//!    `get_skill_instructions` and `run_skill`, seen by Claude as
+//!    `mcp__skills__<tool>`.
+//!
+//! 3. **Conversation**: a task that mentions no skill by name, followed by the
+//!    execution stats the registry recorded for the skills Claude ran.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example 60_skill_selection_tool
+//! ```
+
+use claude_agent_sdk::skills::SkillRegistry;
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, McpServerConfig, McpServers, SystemPrompt,
+};
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // 1. Load the skills
+    let mut registry = SkillRegistry::new();
+    let loaded = registry.load_dir(".claude/skills")?;
+    println!("Loaded {} skills: {}\n", loaded.len(), loaded.join(", "));
+    let registry = Arc::new(registry);
+
+    // 2. Serve them to Claude
+    let server = Arc::clone(&registry).as_mcp_server("skills");
+    let options = ClaudeAgentOptions::builder()
+        .mcp_servers(McpServers::Dict(
+            [("skills".to_string(), McpServerConfig::Sdk(server))].into(),
+        ))
+        .allowed_tools(vec!["mcp__skills__*".to_string()])
+        .system_prompt(SystemPrompt::Text(
+            "Before doing a task yourself, look for a skill that does it with \
+             mcp__skills__search_skills."
+                .to_string(),
+        ))
+        .max_turns(8)
+        .build();
+
+    // 3. Let Claude choose
+    let mut client = ClaudeClient::new(options);
+    client.connect().await?;
+    let response = client
+        .query_collect("Review the uncommitted changes of this repository")
+        .await?;
+    println!("{}\n", response.text());
+    client.disconnect().await?;
+
+    for name in &loaded {
+        if let Some(stats) = registry.stats_for(name) {
+            println!(
+                "{name}: {} runs, {} failed, p50 {:?}",
+                stats.operations, stats.failures, stats.p50_latency
+            );
+        }
+    }
+    Ok(())
+}
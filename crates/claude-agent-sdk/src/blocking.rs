@@ -0,0 +1,160 @@
+//! # Blocking API
+//!
+//! Synchronous wrappers around the async API, for scripts and tools that do not
+//! otherwise use async Rust. Each call drives a private current-thread Tokio
+//! runtime until it completes, so no `async fn` or `#[tokio::main]` is needed.
+//!
+//! Enable it with the `blocking` feature:
+//!
+//! ```toml
+//! cc-agent-sdk = { version = "*", features = ["blocking"] }
+//! ```
+//!
+//! These functions must not be called from inside a Tokio runtime, where
+//! blocking would stall the runtime's own tasks. They return
+//! [`ClaudeError::InsideAsyncRuntime`] there instead; async code should use
+//! the async API directly.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::blocking;
+//! use claude_agent_sdk::{ClaudeAgentOptions, Message};
+//!
+//! fn main() -> claude_agent_sdk::Result<()> {
+//!     let answer = blocking::prompt("What is 2 + 2?", Default::default())?;
+//!     println!("{}", answer.content);
+//!
+//!     let mut client = blocking::Client::new(ClaudeAgentOptions::default())?;
+//!     client.connect()?;
+//!     client.query("Name a prime number")?;
+//!     for message in client.receive_response() {
+//!         if let Message::Result(result) = message? {
+//!             println!("{:?}", result.result);
+//!         }
+//!     }
+//!     client.disconnect()
+//! }
+//! ```
+
+use futures::StreamExt;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+use crate::client::ClaudeClient;
+use crate::errors::{ClaudeError, Result};
+use crate::types::config::ClaudeAgentOptions;
+use crate::types::messages::{CollectedResponse, Message};
+use crate::types::usage::UsageSnapshot;
+use crate::v2::{PromptResult, SessionOptions};
+
+/// Runtime to block on, unless already inside one
+fn runtime() -> Result<Runtime> {
+    if Handle::try_current().is_ok() {
+        return Err(ClaudeError::InsideAsyncRuntime);
+    }
+    Ok(Builder::new_current_thread().enable_all().build()?)
+}
+
+/// Blocking version of [`query`](crate::query::query)
+///
+/// # Errors
+///
+/// Returns [`ClaudeError::InsideAsyncRuntime`] when called from async code, and
+/// otherwise the errors of [`query`](crate::query::query).
+pub fn query(
+    prompt: impl Into<String>,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<Vec<Message>> {
+    runtime()?.block_on(crate::query::query(prompt, options))
+}
+
+/// Blocking version of [`prompt`](crate::v2::prompt)
+///
+/// # Errors
+///
+/// Returns [`ClaudeError::InsideAsyncRuntime`] when called from async code, and
+/// otherwise the errors of [`prompt`](crate::v2::prompt).
+pub fn prompt(prompt: impl Into<String>, options: SessionOptions) -> Result<PromptResult> {
+    runtime()?.block_on(crate::v2::prompt(prompt, options))
+}
+
+/// Blocking wrapper of [`ClaudeClient`]
+///
+/// Owns the runtime its client runs on. The CLI's output is only read while a
+/// method of this client is running, so unread messages wait in the pipe in
+/// between. Dropping a connected client disconnects it.
+pub struct Client {
+    inner: ClaudeClient,
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Create a client, not yet connected
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClaudeError::InsideAsyncRuntime`] when called from async code,
+    /// or an I/O error if the runtime cannot be created.
+    pub fn new(options: ClaudeAgentOptions) -> Result<Self> {
+        Ok(Self {
+            inner: ClaudeClient::new(options),
+            runtime: runtime()?,
+        })
+    }
+
+    /// See [`ClaudeClient::connect`]
+    pub fn connect(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.connect())
+    }
+
+    /// See [`ClaudeClient::query`]
+    pub fn query(&mut self, prompt: impl Into<String>) -> Result<()> {
+        self.runtime.block_on(self.inner.query(prompt))
+    }
+
+    /// See [`ClaudeClient::query_collect`]
+    pub fn query_collect(&mut self, prompt: impl Into<String>) -> Result<CollectedResponse> {
+        self.runtime.block_on(self.inner.query_collect(prompt))
+    }
+
+    /// Messages of the current turn, up to and including its result
+    ///
+    /// Each call to `next` blocks until the next message arrives. See
+    /// [`ClaudeClient::receive_response`].
+    pub fn receive_response(&self) -> impl Iterator<Item = Result<Message>> + '_ {
+        let mut stream = self.inner.receive_response();
+        std::iter::from_fn(move || self.runtime.block_on(stream.next()))
+    }
+
+    /// Every message until the connection ends
+    ///
+    /// See [`ClaudeClient::receive_messages`].
+    pub fn receive_messages(&self) -> impl Iterator<Item = Result<Message>> + '_ {
+        let mut stream = self.inner.receive_messages();
+        std::iter::from_fn(move || self.runtime.block_on(stream.next()))
+    }
+
+    /// See [`ClaudeClient::interrupt`]
+    pub fn interrupt(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.interrupt())
+    }
+
+    /// See [`ClaudeClient::usage`]
+    pub fn usage(&self) -> UsageSnapshot {
+        self.inner.usage()
+    }
+
+    /// See [`ClaudeClient::disconnect`]
+    pub fn disconnect(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.disconnect())
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        // Blocking is only safe where `new` was allowed, outside any runtime
+        if self.inner.is_connected() && Handle::try_current().is_err() {
+            let _ = self.runtime.block_on(self.inner.disconnect());
+        }
+    }
+}
@@ -1,19 +1,43 @@
 //! ClaudeClient for bidirectional streaming interactions with hook support
 
-use futures::stream::Stream;
+use futures::stream::{Stream, StreamExt};
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
+use tracing::{info, warn};
 
-use crate::errors::{ClaudeError, Result};
+use crate::commands::{CommandError, CommandRegistry};
+use crate::errors::{ClaudeError, ConnectionError, Result};
+use crate::internal::audit::ToolAudit;
+use crate::internal::budget::BudgetGuard;
+use crate::internal::checkpoints::CheckpointTracker;
+use crate::internal::fallback::FallbackDetector;
 use crate::internal::message_parser::MessageParser;
 use crate::internal::query_full::QueryFull;
-use crate::internal::transport::subprocess::QueryPrompt;
-use crate::internal::transport::{SubprocessTransport, Transport};
-use crate::types::config::{ClaudeAgentOptions, PermissionMode};
+use crate::internal::query_metrics::QueryMetrics;
+use crate::internal::sessions::{DetachedSessions, MessageReceiver, SessionRouter};
+use crate::internal::transport::recording::record_if_requested;
+use crate::internal::transport::subprocess::{
+    QueryPrompt, run_connect_phase, within_message_timeout,
+};
+use crate::internal::transport::{SocketTransport, SubprocessTransport, Transport, TransportConfig};
+use crate::internal::turns::{Finished, Turn, TurnGate, TurnProgress, message_ends_turn};
+use crate::internal::usage::UsageTracker;
+use crate::mcp::CancellationToken;
+use crate::todos::TodoTracker;
+use crate::transcript::Transcript;
+use crate::types::checkpoints::{CheckpointDiff, CheckpointInfo};
+use crate::types::config::{ClaudeAgentOptions, ConnectPhase, ConnectionState, PermissionMode};
 use crate::types::hooks::HookEvent;
-use crate::types::messages::{Message, UserContentBlock};
+use crate::types::messages::{
+    CollectedResponse, CompactionOutcome, ContentBlock, ConversationTurn, InterruptOutcome,
+    LocalCommandMessage, Message, Role, UserContentBlock,
+};
+use crate::types::usage::UsageSnapshot;
+use crate::version::CliCapabilities;
 
 /// Client for bidirectional streaming interactions with Claude
 ///
@@ -52,8 +76,20 @@ use crate::types::messages::{Message, UserContentBlock};
 /// ```
 pub struct ClaudeClient {
     options: ClaudeAgentOptions,
-    query: Option<Arc<Mutex<QueryFull>>>,
-    connected: bool,
+    transport: TransportSource,
+    connection: std::sync::Mutex<Connection>,
+    /// Serializes `connect()` and `disconnect()`
+    lifecycle: Mutex<()>,
+    fallback: Arc<std::sync::Mutex<FallbackDetector>>,
+    checkpoints: Arc<std::sync::Mutex<CheckpointTracker>>,
+    todos: Arc<TodoTracker>,
+    turns: Arc<TurnGate>,
+    usage: Arc<std::sync::Mutex<UsageTracker>>,
+    budget: Arc<BudgetGuard>,
+    metrics: Arc<std::sync::Mutex<QueryMetrics>>,
+    audit: Arc<std::sync::Mutex<ToolAudit>>,
+    commands: Option<Arc<CommandRegistry>>,
+    transcript: Option<Transcript>,
 }
 
 impl ClaudeClient {
@@ -72,9 +108,23 @@ impl ClaudeClient {
     /// ```
     pub fn new(options: ClaudeAgentOptions) -> Self {
         Self {
+            fallback: Arc::new(std::sync::Mutex::new(FallbackDetector::new(&options))),
+            checkpoints: Arc::new(std::sync::Mutex::new(CheckpointTracker::new())),
+            todos: Arc::new(TodoTracker::new()),
+            turns: Arc::new(TurnGate::new(
+                options.turn_policy,
+                options.turn_queue_capacity,
+            )),
+            usage: Arc::new(std::sync::Mutex::new(UsageTracker::new())),
+            budget: Arc::new(BudgetGuard::new(&options)),
+            metrics: Arc::new(std::sync::Mutex::new(QueryMetrics::new(&options))),
+            audit: Arc::new(std::sync::Mutex::new(ToolAudit::new(&options))),
+            commands: None,
+            transcript: None,
             options,
-            query: None,
-            connected: false,
+            transport: TransportSource::Subprocess,
+            connection: std::sync::Mutex::new(Connection::default()),
+            lifecycle: Mutex::new(()),
         }
     }
 
@@ -91,6 +141,7 @@ impl ClaudeClient {
     /// # Errors
     ///
     /// Returns an error if:
+    /// - [`ClaudeAgentOptions::validate`] reports an error; its warnings are logged
     /// - The working directory does not exist or is not a directory
     /// - Claude CLI cannot be found
     ///
@@ -103,17 +154,95 @@ impl ClaudeClient {
     /// # Ok::<(), claude_agent_sdk::ClaudeError>(())
     /// ```
     pub fn try_new(options: ClaudeAgentOptions) -> Result<Self> {
+        options.check()?;
+
         // Validate by attempting to create transport (but don't keep it)
         let prompt = QueryPrompt::Streaming;
         let _ = SubprocessTransport::new(prompt, options.clone())?;
 
         Ok(Self {
+            fallback: Arc::new(std::sync::Mutex::new(FallbackDetector::new(&options))),
+            checkpoints: Arc::new(std::sync::Mutex::new(CheckpointTracker::new())),
+            todos: Arc::new(TodoTracker::new()),
+            turns: Arc::new(TurnGate::new(
+                options.turn_policy,
+                options.turn_queue_capacity,
+            )),
+            usage: Arc::new(std::sync::Mutex::new(UsageTracker::new())),
+            budget: Arc::new(BudgetGuard::new(&options)),
+            metrics: Arc::new(std::sync::Mutex::new(QueryMetrics::new(&options))),
+            audit: Arc::new(std::sync::Mutex::new(ToolAudit::new(&options))),
+            commands: None,
+            transcript: None,
             options,
-            query: None,
-            connected: false,
+            transport: TransportSource::Subprocess,
+            connection: std::sync::Mutex::new(Connection::default()),
+            lifecycle: Mutex::new(()),
         })
     }
 
+    /// Create a ClaudeClient that talks to Claude through `transport`
+    ///
+    /// `transport` is used for the first connection instead of spawning the CLI.
+    /// Since it can't be recreated, reconnecting fails; for a CLI served on a
+    /// socket, [`with_socket`](Self::with_socket) reconnects too. Options that
+    /// become CLI flags have no effect, as the transport decides how the CLI runs.
+    pub fn with_transport(transport: Box<dyn Transport>, options: ClaudeAgentOptions) -> Self {
+        let mut client = Self::new(options);
+        client.transport = TransportSource::Custom(std::sync::Mutex::new(Some(transport)));
+        client
+    }
+
+    /// Create a ClaudeClient that connects to a CLI served on a socket
+    ///
+    /// Every connection, including reconnects, opens a new
+    /// [`SocketTransport`] to `config`; see there for
+    /// what the server has to provide. Options that become CLI flags have no
+    /// effect, and reconnecting doesn't resume the session.
+    pub fn with_socket(config: TransportConfig, options: ClaudeAgentOptions) -> Self {
+        let mut client = Self::new(options);
+        client.transport = TransportSource::Socket(config);
+        client
+    }
+
+    /// Answer slash commands in prompts from `registry` instead of the CLI
+    ///
+    /// A prompt sent with [`query`](Self::query), [`query_with_session`](Self::query_with_session)
+    /// or a [`SessionHandle`] that starts with `/` is run with
+    /// [`CommandRegistry::execute_line`]. Its output, or its error, arrives on the
+    /// receive stream as a [`Message::LocalCommand`], which ends the turn like a
+    /// result does. The CLI never sees the prompt.
+    ///
+    /// Like any turn, a local command waits for the running turn under
+    /// [`ClaudeAgentOptions::turn_policy`], so its message is received after that
+    /// turn's result rather than in the middle of it.
+    ///
+    /// Commands the registry does not know go to the CLI unchanged, so the CLI's
+    /// own slash commands keep working. A handler returning
+    /// [`CommandError::ForwardToClaude`] sends its prompt to the CLI instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, CommandRegistry, SlashCommand};
+    /// use std::sync::Arc;
+    ///
+    /// let mut registry = CommandRegistry::new();
+    /// registry.register(SlashCommand::new(
+    ///     "ping",
+    ///     "Answer locally",
+    ///     Arc::new(|_, _| Box::pin(async { Ok("pong".to_string()) })),
+    /// ))?;
+    ///
+    /// let client = ClaudeClient::new(ClaudeAgentOptions::default())
+    ///     .with_command_registry(registry);
+    /// # Ok::<(), claude_agent_sdk::CommandError>(())
+    /// ```
+    pub fn with_command_registry(mut self, registry: CommandRegistry) -> Self {
+        self.commands = Some(Arc::new(registry));
+        self
+    }
+
     /// Connect to Claude (analogous to Python's __aenter__)
     ///
     /// This establishes the connection to the Claude Code CLI and initializes
@@ -125,24 +254,303 @@ impl ClaudeClient {
     /// - Claude CLI cannot be found or started
     /// - The initialization handshake fails
     /// - Hook registration fails
-    pub async fn connect(&mut self) -> Result<()> {
-        if self.connected {
+    /// - [`ClaudeAgentOptions::verify_mcp_servers`] is set and an MCP server fails
+    ///   the check, reported as [`ClaudeError::McpServersUnhealthy`]
+    ///
+    /// Failures after discovery are reported as [`ClaudeError::ConnectFailed`] or
+    /// [`ClaudeError::ConnectTimeout`], naming the phase; see
+    /// [`ClaudeAgentOptions::connect_timeouts`].
+    ///
+    /// Safe to call from several tasks at once: only one CLI process is started,
+    /// and callers that arrive while an attempt is in flight share its outcome.
+    /// After a failed attempt the client is left disconnected, so calling
+    /// `connect()` again retries from scratch.
+    pub async fn connect(&self) -> Result<()> {
+        let in_flight = {
+            let connection = self.connection.lock().unwrap();
+            match connection.state {
+                ConnectionState::Connected => return Ok(()),
+                ConnectionState::Connecting => Some(connection.attempts),
+                _ => None,
+            }
+        };
+
+        // Concurrent callers queue here and reuse the outcome of the attempt ahead of them
+        let _lifecycle = self.lifecycle.lock().await;
+        {
+            let mut connection = self.connection.lock().unwrap();
+            if connection.state == ConnectionState::Connected {
+                return Ok(());
+            }
+            if in_flight == Some(connection.attempts)
+                && let Some(error) = &connection.last_error
+            {
+                return Err(ConnectionError::new(format!(
+                    "Concurrent connect attempt failed: {}",
+                    error
+                ))
+                .into());
+            }
+            connection.state = ConnectionState::Connecting;
+            connection.attempts += 1;
+        }
+
+        // Resets the state if this future is dropped mid-connect
+        let mut attempt = ConnectAttempt {
+            connection: &self.connection,
+            finished: false,
+        };
+        let result = match self.verify_mcp_servers().await {
+            Ok(()) => self.establish(None, Vec::new(), None).await,
+            Err(e) => Err(e),
+        };
+        attempt.finished = true;
+        self.finish_connect(result)
+    }
+
+    /// Run the MCP handshake check if [`ClaudeAgentOptions::verify_mcp_servers`] is set
+    async fn verify_mcp_servers(&self) -> Result<()> {
+        if !self.options.verify_mcp_servers {
             return Ok(());
         }
 
+        let unhealthy: Vec<_> = self
+            .options
+            .mcp_servers
+            .verify()
+            .await
+            .into_iter()
+            .filter(|report| !report.is_healthy())
+            .collect();
+        if unhealthy.is_empty() {
+            Ok(())
+        } else {
+            Err(ClaudeError::McpServersUnhealthy(unhealthy))
+        }
+    }
+
+    /// Record the outcome of a connection attempt
+    fn finish_connect(&self, result: Result<QueryFull>) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        match result {
+            Ok(query) => {
+                connection.output_open = Some(Arc::clone(&query.output_open));
+                connection.sessions = Some(Arc::clone(&query.sessions));
+                connection.query = Some(Arc::new(Mutex::new(query)));
+                connection.state = ConnectionState::Connected;
+                connection.last_error = None;
+                Ok(())
+            },
+            Err(e) => {
+                connection.state = ConnectionState::Disconnected;
+                connection.last_error = Some(e.to_string());
+                Err(e)
+            },
+        }
+    }
+
+    /// Replace the CLI process with a fresh one
+    ///
+    /// Shuts down the current process, if any, and starts a new one with the
+    /// same options, hooks and SDK MCP servers. When a session ID has been seen,
+    /// the new process resumes that session so the conversation can continue.
+    /// Messages the old process sent that were not yet received are delivered
+    /// by the next receive stream.
+    ///
+    /// Use it when [`is_alive`](Self::is_alive) reports a dead process, or set
+    /// [`ClaudeAgentOptions::auto_reconnect`] to have queries do it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`connect`](Self::connect); the client is then
+    /// left disconnected.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// # client.connect().await?;
+    /// if !client.is_alive() {
+    ///     client.reconnect().await?;
+    /// }
+    /// client.query("Where were we?").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reconnect(&self) -> Result<()> {
+        let _lifecycle = self.lifecycle.lock().await;
+        let (old_query, sessions) = {
+            let mut connection = self.connection.lock().unwrap();
+            connection.state = ConnectionState::Connecting;
+            connection.attempts += 1;
+            connection.output_open = None;
+            // Sessions move to the new connection, keeping the handles' messages
+            let sessions = connection.sessions.take().map(|router| router.detach());
+            (connection.query.take(), sessions)
+        };
+
+        // Resets the state if this future is dropped mid-connect
+        let mut attempt = ConnectAttempt {
+            connection: &self.connection,
+            finished: false,
+        };
+        let (resume, buffered) = match old_query {
+            Some(query) => {
+                let query = query.lock().await;
+                let grace_period = self.options.deadline_grace_period;
+                if let Err(e) = tokio::time::timeout(grace_period, shutdown(&query)).await {
+                    warn!("Old CLI process did not shut down before reconnecting: {}", e);
+                }
+                (query.last_session_id().await, query.drain_buffered())
+            },
+            None => (None, Vec::new()),
+        };
+        info!(
+            resume = resume.as_deref().unwrap_or("none"),
+            buffered = buffered.len(),
+            "Reconnecting to Claude CLI"
+        );
+
+        let result = self.establish(resume, buffered, sessions).await;
+        attempt.finished = true;
+        self.finish_connect(result)
+    }
+
+    /// Whether the client is connected and ready for queries
+    pub fn is_connected(&self) -> bool {
+        self.state() == ConnectionState::Connected
+    }
+
+    /// Version of the CLI, as detected when connecting
+    ///
+    /// `None` before the first connection, and when the version is unknown:
+    /// the check was skipped with `CLAUDE_AGENT_SDK_SKIP_VERSION_CHECK`, timed
+    /// out, or the transport is not a CLI process.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// client.connect().await?;
+    /// if let Some(version) = client.cli_version() {
+    ///     println!("Connected to Claude Code {}", version);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cli_version(&self) -> Option<semver::Version> {
+        self.connection.lock().unwrap().cli_version.clone()
+    }
+
+    /// Optional features of the CLI, derived from [`cli_version`](Self::cli_version)
+    ///
+    /// All features are assumed when the version is unknown. Flags of
+    /// unsupported features are left out when the CLI is started.
+    pub fn cli_capabilities(&self) -> CliCapabilities {
+        CliCapabilities::for_optional_version(self.cli_version().as_ref())
+    }
+
+    /// Whether the client is connected and its CLI process is still running
+    ///
+    /// Unlike [`is_connected`](Self::is_connected), this notices a CLI process
+    /// that exited or crashed: its output has ended. Messages it sent before
+    /// exiting can still be received.
+    pub fn is_alive(&self) -> bool {
+        let connection = self.connection.lock().unwrap();
+        connection.state == ConnectionState::Connected
+            && connection
+                .output_open
+                .as_ref()
+                .is_some_and(|open| open.load(Ordering::SeqCst))
+    }
+
+    /// Current connection status, including in-flight `connect()` and `disconnect()` calls
+    pub fn state(&self) -> ConnectionState {
+        self.connection.lock().unwrap().state
+    }
+
+    /// The live connection, if any
+    fn current_query(&self) -> Option<Arc<Mutex<QueryFull>>> {
+        self.connection.lock().unwrap().query.clone()
+    }
+
+    /// Start the CLI and run the initialize handshake
+    ///
+    /// Resumes session `resume` if given, and queues `carried_over` messages
+    /// ahead of the new process's output, routing them to the `sessions` of the
+    /// previous connection. On failure after the process was spawned, the
+    /// process is shut down again.
+    async fn establish(
+        &self,
+        resume: Option<String>,
+        carried_over: Vec<serde_json::Value>,
+        sessions: Option<DetachedSessions>,
+    ) -> Result<QueryFull> {
+        self.turns.reset();
+
+        // Prompts replayed with earlier timestamps belong to a resumed session
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .set_connected_at(chrono::Utc::now());
+
         // Create transport in streaming mode (no initial prompt)
         let prompt = QueryPrompt::Streaming;
-        let mut transport = SubprocessTransport::new(prompt, self.options.clone())?;
+        let mut options = self.options.clone();
+        if let Some(session_id) = resume {
+            options.resume = Some(session_id);
+            options.continue_conversation = false;
+            options.fork_session = false;
+        }
+        let (transport, diagnostics): (Box<dyn Transport>, _) = match &self.transport {
+            TransportSource::Subprocess => {
+                let transport = SubprocessTransport::new(prompt, options)?;
+                let diagnostics = transport.diagnostics();
+                (Box::new(transport), Some(diagnostics))
+            },
+            TransportSource::Socket(config) => {
+                (Box::new(SocketTransport::new(config.clone())), None)
+            },
+            TransportSource::Custom(transport) => {
+                let transport = transport.lock().unwrap().take().ok_or_else(|| {
+                    ClaudeError::InvalidConfig(
+                        "The transport given to ClaudeClient::with_transport was already used; \
+                         use ClaudeClient::with_socket or a new client to connect again"
+                            .to_string(),
+                    )
+                })?;
+                (transport, None)
+            },
+        };
+        let mut transport = record_if_requested(transport, &self.options)?;
 
         // Don't send initial prompt - we'll use query() for that
-        transport.connect().await?;
+        if let Err(e) = transport.connect().await {
+            return Err(match &diagnostics {
+                Some(diagnostics) => diagnostics.explain(e, transport.exit_info().await).await,
+                None => e,
+            });
+        }
+
+        self.connection.lock().unwrap().cli_version = transport.cli_version();
 
         // Extract stdin for direct access (avoids transport lock deadlock)
-        let stdin = Arc::clone(&transport.stdin);
+        let stdin = transport.writer();
 
         // Create Query with hooks
-        let mut query = QueryFull::new(Box::new(transport));
+        let mut query = QueryFull::new(transport);
         query.set_stdin(stdin);
+        query.set_metrics(self.options.metrics.clone());
+        if let Some(sessions) = sessions {
+            query.sessions.adopt(sessions);
+        }
+        query.carry_over(carried_over)?;
 
         // Extract SDK MCP servers from options
         let sdk_mcp_servers =
@@ -183,15 +591,30 @@ impl ClaudeClient {
         // Start reading messages in background FIRST
         // This must happen before initialize() because initialize()
         // sends a control request and waits for response
-        query.start().await?;
-
-        // Initialize with hooks (sends control request)
-        query.initialize(hooks).await?;
-
-        self.query = Some(Arc::new(Mutex::new(query)));
-        self.connected = true;
+        let started = match query.start().await {
+            Ok(()) => {
+                // Initialize with hooks (sends control request)
+                run_connect_phase(
+                    self.options.connect_progress.as_ref(),
+                    ConnectPhase::Initialize,
+                    self.options.connect_timeouts.initialize,
+                    query.initialize(hooks),
+                )
+                .await
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = started {
+            let grace_period = self.options.deadline_grace_period;
+            let _ = tokio::time::timeout(grace_period, shutdown(&query)).await;
+            let exit = *query.exit_info.lock().unwrap();
+            return Err(match &diagnostics {
+                Some(diagnostics) => diagnostics.explain(e, exit).await,
+                None => e,
+            });
+        }
 
-        Ok(())
+        Ok(query)
     }
 
     /// Send a query to Claude
@@ -256,54 +679,44 @@ impl ClaudeClient {
         prompt: impl Into<String>,
         session_id: impl Into<String>,
     ) -> Result<()> {
-        let query = self.query.as_ref().ok_or_else(|| {
-            ClaudeError::InvalidConfig("Client not connected. Call connect() first.".to_string())
-        })?;
-
-        let prompt_str = prompt.into();
-        let session_id_str = session_id.into();
-
-        // Format as JSON message for stream-json input format
-        let user_message = serde_json::json!({
-            "type": "user",
-            "message": {
-                "role": "user",
-                "content": prompt_str
-            },
-            "session_id": session_id_str
-        });
+        self.send_prompt(prompt.into(), &session_id.into()).await
+    }
 
-        let message_str = serde_json::to_string(&user_message).map_err(|e| {
-            ClaudeError::Transport(format!("Failed to serialize user message: {}", e))
-        })?;
+    async fn send_prompt(&self, prompt: String, session_id: &str) -> Result<()> {
+        if self.current_query().is_none() {
+            return Err(not_connected());
+        }
 
-        // Write directly to stdin (bypasses transport lock)
-        let query_guard = query.lock().await;
-        let stdin = query_guard.stdin.clone();
-        drop(query_guard);
-
-        if let Some(stdin_arc) = stdin {
-            let mut stdin_guard = stdin_arc.lock().await;
-            if let Some(ref mut stdin_stream) = *stdin_guard {
-                stdin_stream
-                    .write_all(message_str.as_bytes())
-                    .await
-                    .map_err(|e| ClaudeError::Transport(format!("Failed to write query: {}", e)))?;
-                stdin_stream.write_all(b"\n").await.map_err(|e| {
-                    ClaudeError::Transport(format!("Failed to write newline: {}", e))
-                })?;
-                stdin_stream
-                    .flush()
-                    .await
-                    .map_err(|e| ClaudeError::Transport(format!("Failed to flush: {}", e)))?;
-            } else {
-                return Err(ClaudeError::Transport("stdin not available".to_string()));
-            }
-        } else {
-            return Err(ClaudeError::Transport("stdin not set".to_string()));
+        if let Some(commands) = &self.commands
+            && prompt.trim_start().starts_with('/')
+        {
+            let (output, is_error) = match commands.execute_line(&prompt).await {
+                Ok(output) => (output, false),
+                // Not a command of the registry, maybe one of the CLI's
+                Err(CommandError::NotFound(_) | CommandError::InvalidName(_)) => {
+                    return self.send_text(prompt, session_id).await;
+                },
+                Err(CommandError::ForwardToClaude(rewritten)) => {
+                    return self.send_text(rewritten, session_id).await;
+                },
+                Err(e) => (e.to_string(), true),
+            };
+            let message = Message::LocalCommand(LocalCommandMessage {
+                command: prompt,
+                output,
+                is_error,
+                session_id: session_id.to_string(),
+            });
+            return self.start_local_turn(message).await;
         }
 
-        Ok(())
+        self.send_text(prompt, session_id).await
+    }
+
+    /// Send `prompt` to the CLI as a user message
+    async fn send_text(&self, prompt: String, session_id: &str) -> Result<()> {
+        self.send_user_message(user_message_line(&prompt, session_id)?, 1)
+            .await
     }
 
     /// Send a query with structured content blocks (supports images)
@@ -318,7 +731,7 @@ impl ClaudeClient {
     /// # Errors
     ///
     /// Returns an error if:
-    /// - The content vector is empty (must include at least one text or image block)
+    /// - The content vector is empty (must include at least one text, image or document block)
     /// - The client is not connected (call `connect()` first)
     /// - Sending the message fails
     ///
@@ -359,7 +772,7 @@ impl ClaudeClient {
     /// # Errors
     ///
     /// Returns an error if:
-    /// - The content vector is empty (must include at least one text or image block)
+    /// - The content vector is empty (must include at least one text, image or document block)
     /// - The client is not connected (call `connect()` first)
     /// - Sending the message fails
     ///
@@ -374,7 +787,7 @@ impl ClaudeClient {
     /// client.query_with_content_and_session(
     ///     vec![
     ///         UserContentBlock::text("Analyze this chart"),
-    ///         UserContentBlock::image_url("https://example.com/chart.png"),
+    ///         UserContentBlock::image_url("https://example.com/chart.png")?,
     ///     ],
     ///     "analysis-session",
     /// ).await?;
@@ -386,9 +799,9 @@ impl ClaudeClient {
         content: impl Into<Vec<UserContentBlock>>,
         session_id: impl Into<String>,
     ) -> Result<()> {
-        let query = self.query.as_ref().ok_or_else(|| {
-            ClaudeError::InvalidConfig("Client not connected. Call connect() first.".to_string())
-        })?;
+        if self.current_query().is_none() {
+            return Err(not_connected());
+        }
 
         let content_blocks: Vec<UserContentBlock> = content.into();
         UserContentBlock::validate_content(&content_blocks)?;
@@ -410,171 +823,775 @@ impl ClaudeClient {
             ClaudeError::Transport(format!("Failed to serialize user message: {}", e))
         })?;
 
-        // Write directly to stdin (bypasses transport lock)
-        let query_guard = query.lock().await;
-        let stdin = query_guard.stdin.clone();
-        drop(query_guard);
-
-        if let Some(stdin_arc) = stdin {
-            let mut stdin_guard = stdin_arc.lock().await;
-            if let Some(ref mut stdin_stream) = *stdin_guard {
-                stdin_stream
-                    .write_all(message_str.as_bytes())
-                    .await
-                    .map_err(|e| ClaudeError::Transport(format!("Failed to write query: {}", e)))?;
-                stdin_stream.write_all(b"\n").await.map_err(|e| {
-                    ClaudeError::Transport(format!("Failed to write newline: {}", e))
-                })?;
-                stdin_stream
-                    .flush()
-                    .await
-                    .map_err(|e| ClaudeError::Transport(format!("Failed to flush: {}", e)))?;
-            } else {
-                return Err(ClaudeError::Transport("stdin not available".to_string()));
-            }
-        } else {
-            return Err(ClaudeError::Transport("stdin not set".to_string()));
-        }
-
-        Ok(())
+        self.send_user_message(message_str, 1).await
     }
 
-    /// Receive all messages as a stream (continuous)
-    ///
-    /// This method returns a stream that yields all messages from Claude
-    /// indefinitely until the stream is closed or an error occurs.
-    ///
-    /// Use this when you want to process all messages, including multiple
-    /// responses and system events.
+    /// Send prior conversation turns, starting a turn that answers the last one
+    ///
+    /// The turns are written as one stream-json message each, like
+    /// [`query_with_history`](crate::query_with_history) does for one-shot
+    /// queries, in the `"default"` session. Use
+    /// [`send_history_with_session`](Self::send_history_with_session) for
+    /// another session.
+    ///
+    /// The CLI answers each user turn with a result. Together they count as a
+    /// single turn for [`ClaudeAgentOptions::turn_policy`], which ends with the
+    /// result of the last user turn, and one
+    /// [`receive_response`](Self::receive_response) reads the answers to all of
+    /// them. Under [`TurnPolicy::Interleave`](crate::TurnPolicy::Interleave),
+    /// which can't tell the results of concurrent turns apart, each answer is
+    /// a response of its own.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// A stream of `Result<Message>` that continues until the connection closes.
+    /// Returns an error if:
+    /// - [`ConversationTurn::validate_history`] rejects the turns
+    /// - The client is not connected (call `connect()` first)
+    /// - Sending the messages fails
     ///
     /// # Example
     ///
     /// ```no_run
-    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions};
-    /// # use futures::StreamExt;
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, ConversationTurn};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
     /// # client.connect().await?;
-    /// # client.query("Hello").await?;
-    /// let mut stream = client.receive_messages();
-    /// while let Some(message) = stream.next().await {
-    ///     println!("Received: {:?}", message?);
-    /// }
+    /// client.send_history(vec![
+    ///     ConversationTurn::user("Name a prime number"),
+    ///     ConversationTurn::assistant("7"),
+    ///     ConversationTurn::user("Name a bigger one"),
+    /// ]).await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn receive_messages(&self) -> Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>> {
-        let query = match &self.query {
-            Some(q) => Arc::clone(q),
-            None => {
-                return Box::pin(futures::stream::once(async {
-                    Err(ClaudeError::InvalidConfig(
-                        "Client not connected. Call connect() first.".to_string(),
-                    ))
-                }));
-            },
-        };
-
-        Box::pin(async_stream::stream! {
-            let rx: Arc<Mutex<tokio::sync::mpsc::Receiver<serde_json::Value>>> = {
-                let query_guard = query.lock().await;
-                Arc::clone(&query_guard.message_rx)
-            };
-
-            loop {
-                let message = {
-                    let mut rx_guard = rx.lock().await;
-                    rx_guard.recv().await
-                };
-
-                match message {
-                    Some(json) => {
-                        match MessageParser::parse(json) {
-                            Ok(msg) => yield Ok(msg),
-                            Err(e) => {
-                                eprintln!("Failed to parse message: {}", e);
-                                yield Err(e);
-                            }
-                        }
-                    }
-                    None => break,
-                }
-            }
-        })
+    pub async fn send_history(&mut self, turns: Vec<ConversationTurn>) -> Result<()> {
+        self.send_history_with_session(turns, "default").await
     }
 
-    /// Receive messages until a ResultMessage
-    ///
-    /// This method returns a stream that yields messages until it encounters
-    /// a `ResultMessage`, which signals the completion of a Claude response.
+    /// Send prior conversation turns in a specific session
     ///
-    /// This is the most common pattern for handling Claude responses, as it
-    /// processes one complete "turn" of the conversation.
+    /// Like [`send_history`](Self::send_history), with every turn sent in
+    /// `session_id`.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// A stream of `Result<Message>` that ends when a ResultMessage is received.
+    /// Returns an error if the turns are invalid, the client is not connected
+    /// or sending fails.
+    pub async fn send_history_with_session(
+        &mut self,
+        turns: Vec<ConversationTurn>,
+        session_id: impl Into<String>,
+    ) -> Result<()> {
+        self.send_turns(turns, &session_id.into()).await
+    }
+
+    async fn send_turns(&self, turns: Vec<ConversationTurn>, session_id: &str) -> Result<()> {
+        if self.current_query().is_none() {
+            return Err(not_connected());
+        }
+
+        ConversationTurn::validate_history(&turns)?;
+
+        let lines = turns
+            .iter()
+            .map(|turn| serde_json::to_string(&turn.to_stream_json(Some(session_id))))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| {
+                ClaudeError::Transport(format!("Failed to serialize conversation turn: {}", e))
+            })?;
+
+        let results = turns.iter().filter(|turn| turn.role == Role::User).count();
+        self.send_user_message(lines.join("\n"), results).await
+    }
+
+    /// Handle for one of several conversations carried by this client
+    ///
+    /// Messages whose `session_id` is `session_id` go to the handle's
+    /// [`receive_response`](SessionHandle::receive_response) instead of the
+    /// client's, for as long as a handle of the session is alive. Messages of
+    /// sessions without a handle are still received through
+    /// [`receive_response`](Self::receive_response) and
+    /// [`receive_messages`](Self::receive_messages).
+    ///
+    /// The sessions share the client's turn state:
+    /// [`ClaudeAgentOptions::turn_policy`] applies to the turns of all of them,
+    /// not to each session alone. With the default
+    /// [`TurnPolicy::Reject`](crate::TurnPolicy::Reject), sending in one
+    /// session fails with [`ClaudeError::TurnInProgress`] while a turn of
+    /// another session runs, and with [`TurnPolicy::Queue`](crate::TurnPolicy::Queue)
+    /// it waits for that turn. To run the turns of several sessions at once,
+    /// set the policy to [`TurnPolicy::Interleave`](crate::TurnPolicy::Interleave).
     ///
     /// # Example
     ///
     /// ```no_run
-    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, Message};
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, Message, TurnPolicy};
     /// # use futures::StreamExt;
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
-    /// # client.connect().await?;
-    /// # client.query("Hello").await?;
-    /// let mut stream = client.receive_response();
-    /// while let Some(message) = stream.next().await {
-    ///     match message? {
-    ///         Message::Assistant(msg) => println!("Assistant: {:?}", msg),
-    ///         Message::Result(result) => {
-    ///             println!("Done! Cost: ${:?}", result.total_cost_usd);
-    ///             break;
-    ///         }
-    ///         _ => {}
+    /// let options = ClaudeAgentOptions::builder()
+    ///     .turn_policy(TurnPolicy::Interleave)
+    ///     .build();
+    /// let client = ClaudeClient::new(options);
+    /// client.connect().await?;
+    ///
+    /// let math = client.session("math");
+    /// let poetry = client.session("poetry");
+    /// math.send("What is 12 * 12?").await?;
+    /// poetry.send("Write a haiku about autumn").await?;
+    ///
+    /// let mut answers = math.receive_response();
+    /// while let Some(message) = answers.next().await {
+    ///     if let Message::Result(result) = message? {
+    ///         println!("math: {:?}", result.result);
     ///     }
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn receive_response(&self) -> Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>> {
-        let query = match &self.query {
-            Some(q) => Arc::clone(q),
-            None => {
-                return Box::pin(futures::stream::once(async {
-                    Err(ClaudeError::InvalidConfig(
-                        "Client not connected. Call connect() first.".to_string(),
-                    ))
-                }));
-            },
+    pub fn session(&self, session_id: impl Into<String>) -> SessionHandle<'_> {
+        let handle = SessionHandle {
+            client: self,
+            session_id: session_id.into(),
+            receiver: std::sync::Mutex::new(None),
         };
+        handle.claim();
+        handle
+    }
 
-        Box::pin(async_stream::stream! {
-            let rx: Arc<Mutex<tokio::sync::mpsc::Receiver<serde_json::Value>>> = {
-                let query_guard = query.lock().await;
-                Arc::clone(&query_guard.message_rx)
-            };
+    /// IDs of the sessions that have a live [`SessionHandle`], sorted
+    pub fn active_sessions(&self) -> Vec<String> {
+        match &self.connection.lock().unwrap().sessions {
+            Some(sessions) => sessions.active_sessions(),
+            None => Vec::new(),
+        }
+    }
 
-            loop {
-                let message = {
-                    let mut rx_guard = rx.lock().await;
-                    rx_guard.recv().await
-                };
+    /// Receiver of the messages of `session_id` on the live connection
+    fn claim_session(&self, session_id: &str) -> Option<MessageReceiver> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .sessions
+            .as_ref()
+            .map(|sessions| sessions.claim(session_id))
+    }
+
+    /// Receiver of the messages of `session_id`, if a [`SessionHandle`] claimed it
+    fn claimed_session(&self, session_id: &str) -> Option<MessageReceiver> {
+        let connection = self.connection.lock().unwrap();
+        connection.sessions.as_ref()?.claimed(session_id)
+    }
+
+    /// Start a turn with `message`, compacting first if
+    /// [`ClaudeAgentOptions::auto_compact_threshold_tokens`] is reached
+    ///
+    /// `message` holds `results` user messages, and the turn ends with the
+    /// result answering the last of them.
+    async fn send_user_message(&self, message: String, results: usize) -> Result<()> {
+        self.compact_if_needed().await?;
+        self.deliver_user_message(message, results).await
+    }
+
+    /// Start a turn with `message`, subject to [`ClaudeAgentOptions::turn_policy`]
+    ///
+    /// With [`ClaudeAgentOptions::auto_reconnect`], reconnects first if the CLI
+    /// process is dead, and once more if writing the message fails.
+    async fn deliver_user_message(&self, message: String, results: usize) -> Result<()> {
+        if !self.options.auto_reconnect {
+            return self.start_turn(message, results).await;
+        }
+
+        if self.is_connected() && !self.is_alive() {
+            warn!("Claude CLI process is gone, reconnecting before sending");
+            self.reconnect().await?;
+        }
+        match self.start_turn(message.clone(), results).await {
+            Err(ClaudeError::Transport(e)) => {
+                warn!("Sending to Claude CLI failed, reconnecting: {}", e);
+                self.reconnect().await?;
+                self.start_turn(message, results).await
+            },
+            sent => sent,
+        }
+    }
+
+    async fn start_turn(&self, message: String, results: usize) -> Result<()> {
+        let query = self.current_query().ok_or_else(not_connected)?;
+        let spent = self.usage.lock().unwrap().projected_cost_usd();
+        self.budget.admit(spent)?;
+        let prompt = self.transcript.as_ref().map(|_| message.clone());
+        let admitted = self.turns.admit(Turn::Prompt {
+            line: message,
+            results,
+        })?;
+        {
+            let mut metrics = self.metrics.lock().unwrap();
+            for _ in 0..results {
+                metrics.start();
+            }
+        }
+        let Some(turn) = admitted else {
+            // Queued; sent once the running turn's result is received
+            self.record_prompt(prompt.as_deref());
+            return Ok(());
+        };
+
+        let sent = begin_turn(&query, turn).await;
+        if sent.is_err() {
+            self.turns.reset();
+            self.metrics.lock().unwrap().fail();
+        } else {
+            self.record_prompt(prompt.as_deref());
+        }
+        sent
+    }
+
+    /// Add the user messages of a sent prompt to the transcript
+    fn record_prompt(&self, prompt: Option<&str>) {
+        let (Some(transcript), Some(prompt)) = (&self.transcript, prompt) else {
+            return;
+        };
+        for line in prompt.lines().filter(|line| !line.trim().is_empty()) {
+            let parsed = serde_json::from_str(line).map_err(ClaudeError::from);
+            match parsed.and_then(MessageParser::parse) {
+                Ok(message) => transcript.push(message),
+                Err(e) => warn!("Could not add sent prompt to the transcript: {}", e),
+            }
+        }
+    }
+
+    /// Start a turn answered by `message` instead of the CLI
+    async fn start_local_turn(&self, message: Message) -> Result<()> {
+        let query = self.current_query().ok_or_else(not_connected)?;
+        let message = serde_json::to_value(&message).map_err(|e| {
+            ClaudeError::InternalError(format!("Failed to serialize local message: {}", e))
+        })?;
+        let Some(turn) = self.turns.admit(Turn::Local(message))? else {
+            // Queued; delivered once the running turn's result is received
+            return Ok(());
+        };
+
+        let delivered = begin_turn(&query, turn).await;
+        if delivered.is_err() {
+            self.turns.reset();
+        }
+        delivered
+    }
+
+    /// Send a query and collect the whole turn
+    ///
+    /// Connects first if needed. When [`ClaudeAgentOptions::turn_deadline`] is set,
+    /// this behaves like [`ClaudeClient::query_with_deadline`] with a deadline that
+    /// many seconds from now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if connecting or sending fails,
+    /// [`ClaudeError::DeadlineExceeded`] if the turn deadline elapses, or
+    /// [`ClaudeError::IncompleteTurn`] if the CLI stops before the result.
+    pub async fn query_collect(&mut self, prompt: impl Into<String>) -> Result<CollectedResponse> {
+        if let Some(budget) = self.options.turn_deadline {
+            return self.query_with_deadline(prompt, Instant::now() + budget).await;
+        }
+
+        self.connect().await?;
+        self.query(prompt).await?;
+
+        let mut collected = CollectedResponse::default();
+        let mut stream = self.receive_response();
+        Self::collect_turn(&mut stream, &mut collected).await?;
+        Ok(collected)
+    }
+
+    /// Send a query and collect the whole turn, which must finish by `deadline`
+    ///
+    /// The deadline covers connecting (if the client is not connected yet), sending
+    /// the prompt and receiving every message up to the result, including tool calls.
+    /// When it elapses mid-turn, the turn is interrupted and the result is awaited for
+    /// [`ClaudeAgentOptions::deadline_grace_period`]. If it does not arrive in time the
+    /// client is disconnected.
+    ///
+    /// The deadline wins over any other timeout, and dropping the returned future
+    /// cancels the turn without interrupting it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClaudeError::DeadlineExceeded`] carrying the messages received so far
+    /// if the deadline elapses, or any error from connecting, sending or parsing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, ClaudeError};
+    /// # use std::time::{Duration, Instant};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// let deadline = Instant::now() + Duration::from_secs(30);
+    ///
+    /// match client.query_with_deadline("Summarize the repo", deadline).await {
+    ///     Ok(response) => println!("{}", response.text()),
+    ///     Err(ClaudeError::DeadlineExceeded { partial, .. }) => {
+    ///         println!("Partial answer: {}", partial.text())
+    ///     }
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_with_deadline(
+        &mut self,
+        prompt: impl Into<String>,
+        deadline: Instant,
+    ) -> Result<CollectedResponse> {
+        let started = Instant::now();
+        let deadline = tokio::time::Instant::from_std(deadline);
+        let mut collected = CollectedResponse::default();
+
+        let submitted = tokio::time::timeout_at(deadline, async {
+            self.connect().await?;
+            self.query(prompt).await
+        })
+        .await;
+
+        match submitted {
+            Ok(result) => result?,
+            Err(_) => {
+                self.abandon_turn().await;
+                return Err(ClaudeError::DeadlineExceeded {
+                    elapsed: started.elapsed(),
+                    partial: collected,
+                });
+            },
+        }
+
+        let finished = {
+            let mut stream = self.receive_response();
+            tokio::time::timeout_at(deadline, Self::collect_turn(&mut stream, &mut collected))
+                .await
+        };
+        if let Ok(result) = finished {
+            return result.map(|()| collected);
+        }
+
+        // Ask the CLI to stop and give it a moment to report the result
+        let grace_period = self.options.deadline_grace_period;
+        let wrapped_up = tokio::time::timeout(grace_period, async {
+            self.interrupt().await?;
+            let mut stream = self.receive_response();
+            Self::collect_turn(&mut stream, &mut collected).await
+        })
+        .await;
+
+        if !matches!(wrapped_up, Ok(Ok(()))) || !collected.is_complete() {
+            self.abandon_turn().await;
+        }
+
+        Err(ClaudeError::DeadlineExceeded {
+            elapsed: started.elapsed(),
+            partial: collected,
+        })
+    }
+
+    /// Send a query and collect the whole turn, interrupting it if `token` is cancelled
+    ///
+    /// Connects first if needed. On cancellation the turn is interrupted and its
+    /// result awaited for [`ClaudeAgentOptions::deadline_grace_period`]. If it does
+    /// not arrive in time the client is disconnected, so the next query never
+    /// receives the cancelled turn's leftovers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClaudeError::Cancelled`] carrying the messages received so far if
+    /// the token is cancelled, or any error from connecting, sending or parsing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, ClaudeError};
+    /// # use claude_agent_sdk::mcp::CancellationToken;
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// let token = CancellationToken::new();
+    ///
+    /// let canceller = token.clone();
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_secs(10)).await;
+    ///     canceller.cancel();
+    /// });
+    ///
+    /// match client.query_cancellable("Refactor the parser", &token).await {
+    ///     Ok(response) => println!("{}", response.text()),
+    ///     Err(ClaudeError::Cancelled { partial }) => {
+    ///         println!("Stopped after {} messages", partial.messages.len())
+    ///     },
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_cancellable(
+        &mut self,
+        prompt: impl Into<String>,
+        token: &CancellationToken,
+    ) -> Result<CollectedResponse> {
+        let mut collected = CollectedResponse::default();
+        if token.is_cancelled() {
+            return Err(ClaudeError::Cancelled { partial: collected });
+        }
+
+        self.connect().await?;
+        self.query(prompt).await?;
+
+        let finished = {
+            let mut stream = self.receive_response();
+            tokio::select! {
+                finished = Self::collect_turn(&mut stream, &mut collected) => Some(finished),
+                () = token.cancelled() => None,
+            }
+        };
+        if let Some(result) = finished {
+            return result.map(|()| collected);
+        }
+
+        let grace_period = self.options.deadline_grace_period;
+        let stopped = self.interrupt_collecting(grace_period, &mut collected).await;
+        if !matches!(stopped, Ok(InterruptOutcome::Stopped(_))) {
+            self.abandon_turn().await;
+        }
+
+        Err(ClaudeError::Cancelled { partial: collected })
+    }
+
+    /// Append messages from `stream` to `collected` until the stream ends
+    async fn collect_turn(
+        stream: &mut Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>>,
+        collected: &mut CollectedResponse,
+    ) -> Result<()> {
+        while let Some(message) = stream.next().await {
+            collected.messages.push(message?);
+        }
+        Ok(())
+    }
+
+    /// Tear down the connection after a turn could not be finished
+    async fn abandon_turn(&mut self) {
+        let grace_period = self.options.deadline_grace_period;
+        let _ = tokio::time::timeout(grace_period, self.disconnect()).await;
+
+        // disconnect() may give up early; the CLI exits once its stdin is closed
+        let mut connection = self.connection.lock().unwrap();
+        connection.query = None;
+        connection.sessions = None;
+        connection.state = ConnectionState::Disconnected;
+        self.turns.reset();
+    }
+
+    /// Receive all messages as a stream (continuous)
+    ///
+    /// This method returns a stream that yields all messages from Claude
+    /// indefinitely until the stream is closed or an error occurs.
+    ///
+    /// Use this when you want to process all messages, including multiple
+    /// responses and system events.
+    ///
+    /// # Returns
+    ///
+    /// A stream of `Result<Message>` that continues until the connection closes.
+    /// If the connection closes after messages of an unfinished turn, the last
+    /// item is [`ClaudeError::IncompleteTurn`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions};
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// # client.connect().await?;
+    /// # client.query("Hello").await?;
+    /// let mut stream = client.receive_messages();
+    /// while let Some(message) = stream.next().await {
+    ///     println!("Received: {:?}", message?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn receive_messages(&self) -> Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>> {
+        let query = match self.current_query() {
+            Some(q) => q,
+            None => {
+                return Box::pin(futures::stream::once(async {
+                    Err(ClaudeError::InvalidConfig(
+                        "Client not connected. Call connect() first.".to_string(),
+                    ))
+                }));
+            },
+        };
+
+        let strict_parsing = self.options.strict_parsing;
+        Box::pin(async_stream::stream! {
+            let (rx, exit_info) = {
+                let query_guard = query.lock().await;
+                (
+                    Arc::clone(&query_guard.message_rx),
+                    Arc::clone(&query_guard.exit_info),
+                )
+            };
+            let mut progress = TurnProgress::default();
+
+            loop {
+                let message = {
+                    let mut rx_guard = rx.lock().await;
+                    rx_guard.recv().await
+                };
+
+                match message {
+                    Some(json) => {
+                        match MessageParser::parse_with(json, strict_parsing) {
+                            Ok(mut msg) => {
+                                let observed =
+                                    self.observe_message(&query, &mut msg, &mut progress).await;
+                                if let Some(e) = observed.next_turn_error {
+                                    yield Err(e);
+                                }
+                                yield Ok(msg);
+                                if let Some(e) = observed.over_budget {
+                                    for msg in self.stop_over_budget(observed.ends_turn).await {
+                                        yield Ok(msg);
+                                    }
+                                    yield Err(e);
+                                    break;
+                                }
+                            },
+                            Err(e) => {
+                                eprintln!("Failed to parse message: {}", e);
+                                yield Err(e);
+                            }
+                        }
+                    }
+                    None => {
+                        if progress.is_pending() {
+                            self.turns.reset();
+                            self.metrics.lock().unwrap().fail();
+                            let process_exit = *exit_info.lock().unwrap();
+                            yield Err(progress.incomplete(process_exit));
+                        }
+                        break;
+                    },
+                }
+            }
+        })
+    }
+
+    /// Receive messages until a ResultMessage
+    ///
+    /// This method returns a stream that yields messages until it encounters
+    /// a `ResultMessage`, which signals the completion of a Claude response.
+    ///
+    /// This is the most common pattern for handling Claude responses, as it
+    /// processes one complete "turn" of the conversation.
+    ///
+    /// # Returns
+    ///
+    /// A stream of `Result<Message>` that ends when a ResultMessage is received.
+    /// If the CLI's output ends first, for example because the process died
+    /// mid-turn, the stream instead ends with [`ClaudeError::IncompleteTurn`], so
+    /// the last item is always either the result or an error.
+    ///
+    /// With [`ClaudeAgentOptions::message_timeout`] set, the stream ends with
+    /// [`ClaudeError::MessageTimeout`] when the CLI stays silent for that long. The
+    /// turn keeps running; [`interrupt`](Self::interrupt) or
+    /// [`disconnect`](Self::disconnect) to stop it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, Message};
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// # client.connect().await?;
+    /// # client.query("Hello").await?;
+    /// let mut stream = client.receive_response();
+    /// while let Some(message) = stream.next().await {
+    ///     match message? {
+    ///         Message::Assistant(msg) => println!("Assistant: {:?}", msg),
+    ///         Message::Result(result) => {
+    ///             println!("Done! Cost: ${:?}", result.total_cost_usd);
+    ///             break;
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn receive_response(&self) -> Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>> {
+        self.response_stream(self.options.message_timeout, None)
+    }
+
+    /// Receive messages until a ResultMessage, giving up if the CLI goes silent
+    ///
+    /// Like [`receive_response`](Self::receive_response), but the stream ends
+    /// with [`ClaudeError::MessageTimeout`] when no message arrives for
+    /// `idle_timeout`, overriding [`ClaudeAgentOptions::message_timeout`] for
+    /// this response. The turn keeps running; [`interrupt`](Self::interrupt) or
+    /// [`disconnect`](Self::disconnect) to stop it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, ClaudeError};
+    /// # use futures::StreamExt;
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// # client.connect().await?;
+    /// client.query("Run the test suite").await?;
+    /// let mut stream = client.receive_response_with_timeout(Duration::from_secs(120));
+    /// while let Some(message) = stream.next().await {
+    ///     match message {
+    ///         Ok(message) => println!("{:?}", message),
+    ///         Err(ClaudeError::MessageTimeout { .. }) => {
+    ///             drop(stream);
+    ///             client.interrupt().await?;
+    ///             break;
+    ///         },
+    ///         Err(e) => return Err(e.into()),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn receive_response_with_timeout(
+        &self,
+        idle_timeout: Duration,
+    ) -> Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>> {
+        self.response_stream(Some(idle_timeout), None)
+    }
+
+    /// Receive the text of the current response as it is written
+    ///
+    /// Yields the text deltas of Claude's answer until the ResultMessage, skipping
+    /// tool calls, thinking and subagent output. Deltas require
+    /// [`ClaudeAgentOptions::include_partial_messages`]; without it, each assistant
+    /// message's text is yielded whole.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions};
+    /// # use futures::StreamExt;
+    /// # use std::io::Write;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let options = ClaudeAgentOptions::builder().include_partial_messages(true).build();
+    /// let mut client = ClaudeClient::new(options);
+    /// client.connect().await?;
+    /// client.query("Write a haiku about Rust").await?;
+    /// let mut text = client.receive_text_deltas();
+    /// while let Some(delta) = text.next().await {
+    ///     print!("{}", delta?);
+    ///     std::io::stdout().flush()?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn receive_text_deltas(&self) -> Pin<Box<dyn Stream<Item = Result<String>> + Send + '_>> {
+        let mut messages = self.receive_response();
+        Box::pin(async_stream::stream! {
+            let mut streamed = false;
+            while let Some(message) = messages.next().await {
+                match message {
+                    Ok(Message::StreamEvent(event)) if event.parent_tool_use_id.is_none() => {
+                        if let Some(text) = event.text_delta() {
+                            streamed = true;
+                            yield Ok(text.to_string());
+                        }
+                    },
+                    Ok(Message::Assistant(assistant))
+                        if !streamed && assistant.parent_tool_use_id.is_none() =>
+                    {
+                        for block in &assistant.message.content {
+                            if let ContentBlock::Text(text) = block {
+                                yield Ok(text.text.clone());
+                            }
+                        }
+                    },
+                    Ok(_) => {},
+                    Err(e) => yield Err(e),
+                }
+            }
+        })
+    }
+
+    /// Messages until a ResultMessage, read from `session` or the unclaimed messages
+    fn response_stream(
+        &self,
+        message_timeout: Option<Duration>,
+        session: Option<MessageReceiver>,
+    ) -> Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>> {
+        let query = match self.current_query() {
+            Some(q) => q,
+            None => {
+                return Box::pin(futures::stream::once(async {
+                    Err(ClaudeError::InvalidConfig(
+                        "Client not connected. Call connect() first.".to_string(),
+                    ))
+                }));
+            },
+        };
+
+        let strict_parsing = self.options.strict_parsing;
+        Box::pin(async_stream::stream! {
+            let (rx, exit_info) = {
+                let query_guard = query.lock().await;
+                (
+                    session.unwrap_or_else(|| Arc::clone(&query_guard.message_rx)),
+                    Arc::clone(&query_guard.exit_info),
+                )
+            };
+            let mut progress = TurnProgress::default();
+
+            loop {
+                let message = {
+                    let mut rx_guard = rx.lock().await;
+                    match within_message_timeout(message_timeout, rx_guard.recv()).await {
+                        Ok(message) => message,
+                        Err(e) => {
+                            drop(rx_guard);
+                            self.metrics.lock().unwrap().fail();
+                            yield Err(e);
+                            break;
+                        },
+                    }
+                };
 
                 match message {
                     Some(json) => {
-                        match MessageParser::parse(json) {
-                            Ok(msg) => {
-                                let is_result = matches!(msg, Message::Result(_));
+                        match MessageParser::parse_with(json, strict_parsing) {
+                            Ok(mut msg) => {
+                                let observed =
+                                    self.observe_message(&query, &mut msg, &mut progress).await;
+                                if let Some(e) = observed.next_turn_error {
+                                    yield Err(e);
+                                }
                                 yield Ok(msg);
-                                if is_result {
+                                if let Some(e) = observed.over_budget {
+                                    for msg in self.stop_over_budget(observed.ends_turn).await {
+                                        yield Ok(msg);
+                                    }
+                                    yield Err(e);
+                                    break;
+                                }
+                                if observed.ends_turn {
                                     break;
                                 }
                             }
@@ -584,10 +1601,182 @@ impl ClaudeClient {
                             }
                         }
                     }
-                    None => break,
+                    None => {
+                        self.turns.reset();
+                        self.metrics.lock().unwrap().fail();
+                        let process_exit = *exit_info.lock().unwrap();
+                        yield Err(progress.incomplete(process_exit));
+                        break;
+                    },
                 }
             }
-        })
+        })
+    }
+
+    /// Bookkeeping for each message received, by any receive stream
+    ///
+    /// Detects model fallbacks, counts usage against the budget, records
+    /// metrics, audit events, the transcript, checkpoints and todos, and ends
+    /// the turn at its result, starting the next queued one.
+    async fn observe_message(
+        &self,
+        query: &Arc<Mutex<QueryFull>>,
+        msg: &mut Message,
+        progress: &mut TurnProgress,
+    ) -> Observed {
+        self.fallback.lock().unwrap().observe(msg);
+        let over_budget = self.observe_usage(msg);
+        self.metrics.lock().unwrap().observe(msg);
+        self.audit.lock().unwrap().observe(msg);
+        if let Some(transcript) = &self.transcript {
+            transcript.push(msg.clone());
+        }
+        if self.options.enable_file_checkpointing {
+            self.checkpoints.lock().unwrap().observe(msg);
+        }
+        if self.options.track_todos {
+            self.todos.observe(msg);
+        }
+        progress.observe(msg);
+
+        let (ends_turn, next_turn_error) = if message_ends_turn(msg) {
+            match finish_turn(query, &self.turns).await {
+                Ok(ended) => (ended, None),
+                Err(e) => (true, Some(e)),
+            }
+        } else {
+            (false, None)
+        };
+        Observed {
+            ends_turn,
+            next_turn_error,
+            over_budget,
+        }
+    }
+
+    /// Count the usage of `msg`, returning the error to end the stream with if it
+    /// used up the budget
+    fn observe_usage(&self, msg: &Message) -> Option<ClaudeError> {
+        let spent = {
+            let mut usage = self.usage.lock().unwrap();
+            usage.observe(msg);
+            usage.projected_cost_usd()
+        };
+        self.budget.observe(spent)
+    }
+
+    /// Interrupt the running turn after it used up the budget
+    ///
+    /// Returns the turn's remaining messages, which belong to the caller too.
+    async fn stop_over_budget(&self, after_result: bool) -> Vec<Message> {
+        if after_result {
+            return Vec::new();
+        }
+
+        let mut rest = CollectedResponse::default();
+        let grace_period = self.options.deadline_grace_period;
+        if let Err(e) = self.interrupt_collecting(grace_period, &mut rest).await {
+            warn!("Failed to interrupt the turn over budget: {}", e);
+        }
+        rest.messages
+    }
+
+    /// Compact the conversation, replacing its history with a summary
+    ///
+    /// Sends the CLI's `/compact` command, with `custom_instructions` telling it
+    /// what the summary should focus on, and reads the turn up to its result.
+    /// The CLI runs the `PreCompact` hooks with trigger `"manual"` first; a hook
+    /// that blocks, for example by returning `"continue": false`, stops the
+    /// compaction, which is then reported as [`CompactionOutcome::Skipped`].
+    ///
+    /// The turn's messages go through [`receive_response`](Self::receive_response),
+    /// or the [`SessionHandle`] of the `"default"` session if there is one, so
+    /// drop any receive stream before calling this. Set
+    /// [`ClaudeAgentOptions::auto_compact_threshold_tokens`] to have queries
+    /// compact on their own.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected, [`ClaudeError::TurnInProgress`]
+    /// if a turn is running, or the errors of sending and receiving.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, CompactionOutcome};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// # client.connect().await?;
+    /// let instructions = "Keep the list of files changed so far".to_string();
+    /// match client.compact(Some(instructions)).await? {
+    ///     CompactionOutcome::Compacted(boundary) => {
+    ///         println!("Compacted {:?} tokens", boundary.pre_tokens)
+    ///     },
+    ///     CompactionOutcome::Skipped(result) => println!("Not compacted: {:?}", result.result),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compact(&self, custom_instructions: Option<String>) -> Result<CompactionOutcome> {
+        if self.current_query().is_none() {
+            return Err(not_connected());
+        }
+        if self.turns.is_running() {
+            return Err(ClaudeError::TurnInProgress);
+        }
+
+        let command = match custom_instructions.as_deref().map(str::trim) {
+            Some(instructions) if !instructions.is_empty() => {
+                format!("/compact {}", instructions)
+            },
+            _ => "/compact".to_string(),
+        };
+        // Bypasses the command registry, which may have a `/compact` of its own
+        self.deliver_user_message(user_message_line(&command, "default")?, 1)
+            .await?;
+
+        // A handle of the default session receives the compaction's messages
+        let session = self.claimed_session("default");
+        let mut boundary = None;
+        let mut stream = self.response_stream(self.options.message_timeout, session);
+        while let Some(message) = stream.next().await {
+            match message? {
+                Message::System(system) => boundary = boundary.or(system.compact_boundary()),
+                Message::Result(result) => {
+                    return Ok(match boundary {
+                        Some(boundary) => CompactionOutcome::Compacted(boundary),
+                        None => CompactionOutcome::Skipped(Box::new(result)),
+                    });
+                },
+                _ => {},
+            }
+        }
+        Err(ClaudeError::InternalError(
+            "Response stream ended without a result".to_string(),
+        ))
+    }
+
+    /// Run [`compact`](Self::compact) if no turn is running and the context has
+    /// reached [`ClaudeAgentOptions::auto_compact_threshold_tokens`]
+    async fn compact_if_needed(&self) -> Result<()> {
+        let Some(threshold) = self.options.auto_compact_threshold_tokens else {
+            return Ok(());
+        };
+        let context_tokens = self.usage.lock().unwrap().context_tokens();
+        if context_tokens < threshold || self.turns.is_running() {
+            return Ok(());
+        }
+
+        info!(context_tokens, threshold, "Compacting the conversation before the next query");
+        if let CompactionOutcome::Skipped(result) = self.compact(None).await? {
+            warn!(
+                subtype = %result.subtype,
+                "Conversation was not compacted: {}",
+                result.result.as_deref().unwrap_or("no reason given")
+            );
+        }
+        Ok(())
     }
 
     /// Send an interrupt signal to stop the current Claude operation
@@ -598,7 +1787,7 @@ impl ClaudeClient {
     ///
     /// Returns an error if the client is not connected or if sending fails.
     pub async fn interrupt(&self) -> Result<()> {
-        let query = self.query.as_ref().ok_or_else(|| {
+        let query = self.current_query().ok_or_else(|| {
             ClaudeError::InvalidConfig("Client not connected. Call connect() first.".to_string())
         })?;
 
@@ -606,6 +1795,101 @@ impl ClaudeClient {
         query_guard.interrupt().await
     }
 
+    /// Interrupt the running turn and wait until it has stopped
+    ///
+    /// Sends an interrupt, then reads the rest of the running turn up to its
+    /// result, so the next query doesn't receive it. The messages read are
+    /// discarded; drop any receive stream before calling this. When no turn is
+    /// running, this waits for the CLI to acknowledge the interrupt instead.
+    ///
+    /// If the CLI acknowledges the interrupt but the turn's result doesn't arrive
+    /// within `timeout`, the turn is forgotten, so
+    /// [`ClaudeAgentOptions::turn_policy`] doesn't hold back the next query.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected, if sending the interrupt
+    /// fails, or [`ClaudeError::IncompleteTurn`] if the CLI's output ends first.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, InterruptOutcome};
+    /// # use std::time::Duration;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// # client.connect().await?;
+    /// client.query("Count to a million").await?;
+    /// match client.interrupt_and_wait(Duration::from_secs(5)).await? {
+    ///     InterruptOutcome::Stopped(result) => println!("Stopped: {}", result.subtype),
+    ///     outcome => println!("{:?}", outcome),
+    /// }
+    /// client.query("Never mind, what is 2 + 2?").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn interrupt_and_wait(&self, timeout: Duration) -> Result<InterruptOutcome> {
+        self.interrupt_collecting(timeout, &mut CollectedResponse::default())
+            .await
+    }
+
+    /// Interrupt and wait, appending the running turn's messages to `collected`
+    async fn interrupt_collecting(
+        &self,
+        timeout: Duration,
+        collected: &mut CollectedResponse,
+    ) -> Result<InterruptOutcome> {
+        let query = self.current_query().ok_or_else(not_connected)?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        let running = self.turns.is_running();
+        let mut acknowledgement = {
+            let query_guard = query.lock().await;
+            query_guard.start_interrupt().await?
+        };
+
+        if !running {
+            return match tokio::time::timeout_at(deadline, acknowledgement).await {
+                Ok(Ok(_)) => Ok(InterruptOutcome::Idle),
+                Ok(Err(_)) => Err(ClaudeError::ControlProtocol(
+                    "Control request response channel closed".to_string(),
+                )),
+                Err(_) => Ok(InterruptOutcome::TimedOut),
+            };
+        }
+
+        // Whichever of the acknowledgement and the result arrives first, the
+        // result ends the turn
+        let mut awaiting_acknowledgement = true;
+        let mut acknowledged = false;
+        let mut stream = self.response_stream(None, None);
+        let outcome = loop {
+            tokio::select! {
+                response = &mut acknowledgement, if awaiting_acknowledgement => {
+                    awaiting_acknowledgement = false;
+                    // A closed channel means the output ended, which the stream reports
+                    acknowledged = response.is_ok();
+                },
+                message = tokio::time::timeout_at(deadline, stream.next()) => match message {
+                    Ok(Some(Ok(Message::Result(result)))) => {
+                        collected.messages.push(Message::Result(result.clone()));
+                        break InterruptOutcome::Stopped(Box::new(result));
+                    },
+                    Ok(Some(Ok(message))) => collected.messages.push(message),
+                    Ok(Some(Err(e))) => return Err(e),
+                    Ok(None) | Err(_) if acknowledged => break InterruptOutcome::Acknowledged,
+                    Ok(None) | Err(_) => break InterruptOutcome::TimedOut,
+                },
+            }
+        };
+
+        drop(stream);
+        if !matches!(outcome, InterruptOutcome::Stopped(_)) {
+            self.turns.reset();
+        }
+        Ok(outcome)
+    }
+
     /// Change the permission mode dynamically
     ///
     /// This is analogous to Python's `client.set_permission_mode()`.
@@ -618,7 +1902,7 @@ impl ClaudeClient {
     ///
     /// Returns an error if the client is not connected or if sending fails.
     pub async fn set_permission_mode(&self, mode: PermissionMode) -> Result<()> {
-        let query = self.query.as_ref().ok_or_else(|| {
+        let query = self.current_query().ok_or_else(|| {
             ClaudeError::InvalidConfig("Client not connected. Call connect() first.".to_string())
         })?;
 
@@ -638,12 +1922,14 @@ impl ClaudeClient {
     ///
     /// Returns an error if the client is not connected or if sending fails.
     pub async fn set_model(&self, model: Option<&str>) -> Result<()> {
-        let query = self.query.as_ref().ok_or_else(|| {
+        let query = self.current_query().ok_or_else(|| {
             ClaudeError::InvalidConfig("Client not connected. Call connect() first.".to_string())
         })?;
 
         let query_guard = query.lock().await;
-        query_guard.set_model(model).await
+        query_guard.set_model(model).await?;
+        self.fallback.lock().unwrap().set_requested(model);
+        Ok(())
     }
 
     /// Rewind tracked files to their state at a specific user message.
@@ -701,7 +1987,7 @@ impl ClaudeClient {
     /// # }
     /// ```
     pub async fn rewind_files(&self, user_message_id: &str) -> Result<()> {
-        let query = self.query.as_ref().ok_or_else(|| {
+        let query = self.current_query().ok_or_else(|| {
             ClaudeError::InvalidConfig("Client not connected. Call connect() first.".to_string())
         })?;
 
@@ -709,6 +1995,136 @@ impl ClaudeClient {
         query_guard.rewind_files(user_message_id).await
     }
 
+    /// Record the conversation from now on
+    ///
+    /// Sent prompts and the messages seen by
+    /// [`receive_messages`](Self::receive_messages) and
+    /// [`receive_response`](Self::receive_response) are added to a
+    /// [`Transcript`], read with [`transcript`](Self::transcript). Enabling it
+    /// again keeps the messages recorded so far.
+    pub fn enable_transcript(&mut self) {
+        self.transcript.get_or_insert_with(Transcript::new);
+    }
+
+    /// Copy of the conversation recorded since
+    /// [`enable_transcript`](Self::enable_transcript), if it was called
+    pub fn transcript(&self) -> Option<Transcript> {
+        self.transcript.as_ref().map(Transcript::snapshot)
+    }
+
+    /// The todo list Claude keeps with its `TodoWrite` tool
+    ///
+    /// Stays empty unless [`ClaudeAgentOptions::track_todos`] is set. Use
+    /// [`TodoTracker::subscribe`] to be notified of changes.
+    pub fn todos(&self) -> &TodoTracker {
+        &self.todos
+    }
+
+    /// List the checkpoints that files can be rewound to, oldest first
+    ///
+    /// Checkpoints are built from the user messages and file edits seen by
+    /// [`receive_messages`](Self::receive_messages) and
+    /// [`receive_response`](Self::receive_response), so they have the same
+    /// requirements as [`rewind_files`](Self::rewind_files). Prompts the CLI
+    /// replays from a resumed session are included, without a count of changed
+    /// files.
+    pub fn checkpoints(&self) -> Vec<CheckpointInfo> {
+        self.checkpoints.lock().unwrap().checkpoints()
+    }
+
+    /// Show which files rewinding to a checkpoint would restore
+    ///
+    /// Lists the files Claude edited with `Write`, `Edit`, `MultiEdit` or
+    /// `NotebookEdit` since the checkpoint. Edits made outside those tools, such
+    /// as through `Bash`, are restored by the CLI but cannot be listed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClaudeError::NotFound`] if no checkpoint has this ID.
+    pub fn diff_checkpoint(&self, user_message_id: &str) -> Result<CheckpointDiff> {
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .diff(user_message_id)
+            .ok_or_else(|| {
+                ClaudeError::NotFound(format!("No checkpoint for message {user_message_id}"))
+            })
+    }
+
+    /// Rewind files to the latest checkpoint created at or before `time`
+    ///
+    /// Returns the checkpoint that was restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClaudeError::NotFound`] if there is no checkpoint that old, or
+    /// the errors of [`rewind_files`](Self::rewind_files).
+    pub async fn rewind_to_latest_before(
+        &self,
+        time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<CheckpointInfo> {
+        let checkpoint = self
+            .checkpoints
+            .lock()
+            .unwrap()
+            .latest_before(time)
+            .ok_or_else(|| ClaudeError::NotFound(format!("No checkpoint before {time}")))?;
+
+        self.rewind_files(&checkpoint.user_message_id).await?;
+        Ok(checkpoint)
+    }
+
+    /// Token usage and cost of the queries run on this client
+    ///
+    /// Updated by [`receive_messages`](Self::receive_messages) and
+    /// [`receive_response`](Self::receive_response) as messages pass through,
+    /// so it can be read while a response stream is still open.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut client = ClaudeClient::new(ClaudeAgentOptions::default());
+    /// # client.connect().await?;
+    /// client.query_collect("Summarize README.md").await?;
+    /// client.query_collect("Now list the open TODOs").await?;
+    ///
+    /// let usage = client.usage();
+    /// println!(
+    ///     "{} tokens, ${:.4} over {} turns",
+    ///     usage.total.total_tokens(),
+    ///     usage.total.total_cost_usd,
+    ///     usage.total.num_turns
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn usage(&self) -> UsageSnapshot {
+        self.usage.lock().unwrap().snapshot()
+    }
+
+    /// Start counting usage from zero
+    pub fn reset_usage(&self) {
+        self.usage.lock().unwrap().reset();
+        self.budget.reset();
+    }
+
+    /// Whether the cost counted by [`usage`](Self::usage), plus the estimated
+    /// cost of the running turn, has reached [`ClaudeAgentOptions::max_budget_usd`]
+    ///
+    /// Always false without a budget. The CLI enforces the budget within a
+    /// session; this check lets callers stop issuing queries across sessions.
+    /// With [`ClaudeAgentOptions::enforce_budget_client_side`] the client stops
+    /// on its own instead, refusing to send exactly when this returns true.
+    pub fn budget_exceeded(&self) -> bool {
+        let spent = self.usage.lock().unwrap().projected_cost_usd();
+        self.options
+            .max_budget_usd
+            .is_some_and(|budget| spent >= budget)
+    }
+
     /// Get server initialization info including available commands and output styles
     ///
     /// Returns initialization information from the Claude Code server including:
@@ -738,7 +2154,7 @@ impl ClaudeClient {
     /// # }
     /// ```
     pub async fn get_server_info(&self) -> Option<serde_json::Value> {
-        let query = self.query.as_ref()?;
+        let query = self.current_query()?;
         let query_guard = query.lock().await;
         query_guard.get_initialization_result().await
     }
@@ -791,43 +2207,444 @@ impl ClaudeClient {
     /// # Errors
     ///
     /// Returns an error if disconnection fails.
-    pub async fn disconnect(&mut self) -> Result<()> {
-        if !self.connected {
-            return Ok(());
+    pub async fn disconnect(&self) -> Result<()> {
+        let _lifecycle = self.lifecycle.lock().await;
+        let query = {
+            let mut connection = self.connection.lock().unwrap();
+            if connection.state != ConnectionState::Connected {
+                return Ok(());
+            }
+            connection.state = ConnectionState::Disconnecting;
+            connection.query.take()
+        };
+
+        // Marks the client disconnected however shutdown ends
+        let _attempt = ConnectAttempt {
+            connection: &self.connection,
+            finished: false,
+        };
+        self.turns.reset();
+        self.metrics.lock().unwrap().abandon();
+        match query {
+            Some(query) => shutdown(&*query.lock().await).await,
+            None => Ok(()),
         }
+    }
 
-        if let Some(query) = self.query.take() {
-            // Close stdin first (using direct access) to signal CLI to exit
-            // This will cause the background task to finish and release transport lock
-            let query_guard = query.lock().await;
-            if let Some(ref stdin_arc) = query_guard.stdin {
-                let mut stdin_guard = stdin_arc.lock().await;
-                if let Some(mut stdin_stream) = stdin_guard.take() {
-                    let _ = stdin_stream.shutdown().await;
-                }
+    /// Disconnect, stopping the CLI by force if it does not exit in time
+    ///
+    /// Unlike [`disconnect`](Self::disconnect), which waits for the CLI for as
+    /// long as it takes, this escalates: the CLI gets half of `timeout` to exit
+    /// after its input is closed, then a quarter after `SIGTERM`, and is then
+    /// killed with `SIGKILL`. On Unix the signals go to the CLI's whole process
+    /// group, so tools and MCP servers it started are stopped too. Messages not
+    /// received yet are lost once the CLI is signalled.
+    ///
+    /// Returns how far shutdown had to go; a client that is not connected
+    /// shuts down gracefully.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClaudeError::Timeout`] if a concurrent `connect()` or
+    /// `disconnect()` did not finish within `timeout`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions, ShutdownLevel};
+    /// # use std::time::Duration;
+    /// # async fn example(client: ClaudeClient) -> claude_agent_sdk::Result<()> {
+    /// if client.shutdown(Duration::from_secs(5)).await? != ShutdownLevel::Graceful {
+    ///     eprintln!("The CLI had to be stopped by force");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn shutdown(&self, timeout: Duration) -> Result<ShutdownLevel> {
+        let start = tokio::time::Instant::now();
+        let _lifecycle = tokio::time::timeout(timeout, self.lifecycle.lock()).await?;
+        let query = {
+            let mut connection = self.connection.lock().unwrap();
+            if connection.state != ConnectionState::Connected {
+                return Ok(ShutdownLevel::Graceful);
             }
-            let transport = Arc::clone(&query_guard.transport);
-            drop(query_guard);
+            connection.state = ConnectionState::Disconnecting;
+            connection.query.take()
+        };
 
-            // Give background task a moment to finish reading and release lock
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let _attempt = ConnectAttempt {
+            connection: &self.connection,
+            finished: false,
+        };
+        self.turns.reset();
+        self.metrics.lock().unwrap().abandon();
+        let Some(query) = query else {
+            return Ok(ShutdownLevel::Graceful);
+        };
+        Ok(shutdown_escalating(&query, start, timeout).await)
+    }
 
-            let mut transport_guard = transport.lock().await;
-            transport_guard.close().await?;
+    /// Wrap the client in a [`ClientGuard`] that shuts it down when dropped
+    ///
+    /// The shutdown runs on `runtime`, so the guard can be dropped outside of
+    /// an async context, e.g. while a panic unwinds.
+    pub fn into_guard(self, runtime: tokio::runtime::Handle) -> ClientGuard {
+        ClientGuard {
+            client: Some(self),
+            runtime,
+            timeout: DEFAULT_SHUTDOWN_TIMEOUT,
         }
+    }
+}
 
-        self.connected = false;
-        Ok(())
+/// How far [`ClaudeClient::shutdown`] had to go to stop the CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownLevel {
+    /// The CLI exited once its input was closed
+    Graceful,
+    /// The CLI exited after `SIGTERM`
+    Terminated,
+    /// The CLI was killed with `SIGKILL`
+    Killed,
+}
+
+/// Time a [`ClientGuard`] gives the CLI to stop, unless set with [`ClientGuard::with_timeout`]
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A [`ClaudeClient`] that is shut down when dropped
+///
+/// Created by [`ClaudeClient::into_guard`]. Dropping it spawns
+/// [`ClaudeClient::shutdown`] onto the runtime it was given, so a program that
+/// panics or returns early does not leave the CLI running. The shutdown is
+/// best effort: it is lost if the runtime itself shuts down first, in which
+/// case dropping the connection still kills the CLI.
+///
+/// # Example
+///
+/// ```no_run
+/// # use claude_agent_sdk::{ClaudeClient, ClaudeAgentOptions};
+/// # async fn example() -> claude_agent_sdk::Result<()> {
+/// let client = ClaudeClient::new(ClaudeAgentOptions::default())
+///     .into_guard(tokio::runtime::Handle::current());
+/// client.connect().await?;
+/// // ... the CLI is stopped when `client` goes out of scope, even on panic
+/// # Ok(())
+/// # }
+/// ```
+pub struct ClientGuard {
+    client: Option<ClaudeClient>,
+    runtime: tokio::runtime::Handle,
+    timeout: Duration,
+}
+
+impl ClientGuard {
+    /// Give the CLI `timeout` to stop when the guard is dropped
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Take the client back, leaving its shutdown to the caller
+    pub fn into_inner(mut self) -> ClaudeClient {
+        self.client.take().expect("client taken before drop")
+    }
+}
+
+impl std::ops::Deref for ClientGuard {
+    type Target = ClaudeClient;
+
+    fn deref(&self) -> &ClaudeClient {
+        self.client.as_ref().expect("client taken before drop")
+    }
+}
+
+impl std::ops::DerefMut for ClientGuard {
+    fn deref_mut(&mut self) -> &mut ClaudeClient {
+        self.client.as_mut().expect("client taken before drop")
+    }
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        let Some(client) = self.client.take() else {
+            return;
+        };
+        if !client.is_connected() {
+            return;
+        }
+        let timeout = self.timeout;
+        self.runtime.spawn(async move {
+            if let Err(e) = client.shutdown(timeout).await {
+                warn!("Failed to shut down dropped ClaudeClient: {}", e);
+            }
+        });
+    }
+}
+
+/// One conversation of a [`ClaudeClient`], with its own receive stream
+///
+/// Created by [`ClaudeClient::session`]. Handles of the same session share
+/// its messages, so only one should receive at a time. When the last handle
+/// of a session is dropped, messages it had not received are discarded, and
+/// later messages of the session go to the client's receive streams.
+pub struct SessionHandle<'a> {
+    client: &'a ClaudeClient,
+    session_id: String,
+    // Keeps the session claimed while the handle lives
+    receiver: std::sync::Mutex<Option<MessageReceiver>>,
+}
+
+impl SessionHandle<'_> {
+    /// ID of the session
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Send a prompt in this session
+    ///
+    /// Like [`ClaudeClient::query_with_session`], subject to
+    /// [`ClaudeAgentOptions::turn_policy`], which counts the turns of every
+    /// session of the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the client is not connected or if sending fails.
+    pub async fn send(&self, prompt: impl Into<String>) -> Result<()> {
+        self.claim();
+        self.client
+            .send_prompt(prompt.into(), &self.session_id)
+            .await
+    }
+
+    /// Send prior conversation turns in this session
+    ///
+    /// Like [`ClaudeClient::send_history`], with every turn sent in this
+    /// session.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the turns are invalid, the client is not connected
+    /// or sending fails.
+    pub async fn send_history(&self, turns: Vec<ConversationTurn>) -> Result<()> {
+        self.claim();
+        self.client.send_turns(turns, &self.session_id).await
+    }
+
+    /// Receive this session's messages until a ResultMessage
+    ///
+    /// Behaves like [`ClaudeClient::receive_response`], reading only the
+    /// messages of this session.
+    pub fn receive_response(&self) -> Pin<Box<dyn Stream<Item = Result<Message>> + Send + '_>> {
+        match self.claim() {
+            Some(receiver) => self
+                .client
+                .response_stream(self.client.options.message_timeout, Some(receiver)),
+            None => Box::pin(futures::stream::once(async { Err(not_connected()) })),
+        }
+    }
+
+    /// Claim the session on the live connection, which may have been replaced
+    fn claim(&self) -> Option<MessageReceiver> {
+        let receiver = self.client.claim_session(&self.session_id)?;
+        *self.receiver.lock().unwrap() = Some(Arc::clone(&receiver));
+        Some(receiver)
+    }
+}
+
+/// Where [`ClaudeClient`] gets the transport of each connection
+enum TransportSource {
+    /// Spawn the CLI
+    Subprocess,
+    /// Connect to a CLI served on a socket
+    Socket(TransportConfig),
+    /// Transport given by the caller, good for one connection
+    Custom(std::sync::Mutex<Option<Box<dyn Transport>>>),
+}
+
+/// Connection status and the live connection, if any
+#[derive(Default)]
+struct Connection {
+    state: ConnectionState,
+    query: Option<Arc<Mutex<QueryFull>>>,
+    /// Whether the live connection's CLI output is still open
+    output_open: Option<Arc<AtomicBool>>,
+    /// Session routing of the live connection
+    sessions: Option<Arc<SessionRouter>>,
+    /// Number of `connect()` attempts started so far
+    attempts: u64,
+    /// Error of the latest attempt, if it failed
+    last_error: Option<String>,
+    /// Version of the CLI of the latest connection, if known
+    cli_version: Option<semver::Version>,
+}
+
+/// Resets the state to [`ConnectionState::Disconnected`] unless `finished`
+struct ConnectAttempt<'a> {
+    connection: &'a std::sync::Mutex<Connection>,
+    finished: bool,
+}
+
+impl Drop for ConnectAttempt<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let mut connection = self.connection.lock().unwrap();
+            connection.query = None;
+            connection.output_open = None;
+            connection.sessions = None;
+            connection.state = ConnectionState::Disconnected;
+            connection.last_error = None;
+        }
     }
 }
 
+fn not_connected() -> ClaudeError {
+    ClaudeError::InvalidConfig("Client not connected. Call connect() first.".to_string())
+}
+
+/// `prompt` as a user message line for the stream-json input format
+fn user_message_line(prompt: &str, session_id: &str) -> Result<String> {
+    let user_message = serde_json::json!({
+        "type": "user",
+        "message": {
+            "role": "user",
+            "content": prompt
+        },
+        "session_id": session_id
+    });
+
+    serde_json::to_string(&user_message)
+        .map_err(|e| ClaudeError::Transport(format!("Failed to serialize user message: {}", e)))
+}
+
+/// Close the CLI's stdin and wait for the process to exit
+async fn shutdown(query: &QueryFull) -> Result<()> {
+    // Close stdin first (using direct access) to signal CLI to exit
+    // This will cause the background task to finish and release transport lock
+    if let Some(ref stdin_arc) = query.stdin {
+        let mut stdin_guard = stdin_arc.lock().await;
+        if let Some(mut stdin_stream) = stdin_guard.take() {
+            let _ = stdin_stream.shutdown().await;
+        }
+    }
+
+    // Give background task a moment to finish reading and release lock
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let mut transport_guard = query.transport.lock().await;
+    transport_guard.close().await
+}
+
+/// Stop the CLI within `timeout` of `start`, escalating to signals as needed
+async fn shutdown_escalating(
+    query: &Mutex<QueryFull>,
+    start: tokio::time::Instant,
+    timeout: Duration,
+) -> ShutdownLevel {
+    let graceful = async {
+        let query = query.lock().await;
+        let _ = shutdown(&query).await;
+    };
+    if tokio::time::timeout_at(start + timeout / 2, graceful).await.is_ok() {
+        return ShutdownLevel::Graceful;
+    }
+
+    // Waiting for the transport needs the background reader to let go of it
+    let terminated = async {
+        let query = query.lock().await;
+        query.stop_reading();
+        let mut transport = query.transport.lock().await;
+        if transport.terminate().await.is_err() {
+            return false;
+        }
+        let _ = transport.close().await;
+        true
+    };
+    if let Ok(true) = tokio::time::timeout_at(start + timeout * 3 / 4, terminated).await {
+        return ShutdownLevel::Terminated;
+    }
+
+    let killed = async {
+        let query = query.lock().await;
+        query.stop_reading();
+        query.transport.lock().await.kill().await;
+    };
+    let _ = tokio::time::timeout_at(start + timeout, killed).await;
+    ShutdownLevel::Killed
+}
+
+/// What [`ClaudeClient::observe_message`] found out about a received message
+struct Observed {
+    /// Whether the message ended the running turn
+    ends_turn: bool,
+    /// Error starting the next queued turn, yielded before the message
+    next_turn_error: Option<ClaudeError>,
+    /// Error to end the stream with after the message, which used up the budget
+    over_budget: Option<ClaudeError>,
+}
+
+/// Record a message ending a turn, starting the next queued turn if the
+/// running one ended
+///
+/// Returns whether the running turn ended.
+async fn finish_turn(query: &Arc<Mutex<QueryFull>>, turns: &TurnGate) -> Result<bool> {
+    let Finished::Ended(next) = turns.finish_turn() else {
+        return Ok(false);
+    };
+    let Some(turn) = next else {
+        return Ok(true);
+    };
+
+    let sent = begin_turn(query, turn).await;
+    if sent.is_err() {
+        turns.reset();
+    }
+    sent.map(|()| true)
+}
+
+/// Send a prompt to the CLI, or deliver a local command's message
+async fn begin_turn(query: &Arc<Mutex<QueryFull>>, turn: Turn) -> Result<()> {
+    match turn {
+        Turn::Prompt { line, .. } => write_stdin_line(query, &line).await,
+        Turn::Local(message) => query.lock().await.deliver_local(message),
+    }
+}
+
+/// Write one line to the CLI's stdin
+async fn write_stdin_line(query: &Arc<Mutex<QueryFull>>, line: &str) -> Result<()> {
+    // Write directly to stdin (bypasses transport lock)
+    let query_guard = query.lock().await;
+    let stdin = query_guard.stdin.clone();
+    drop(query_guard);
+
+    let Some(stdin_arc) = stdin else {
+        return Err(ClaudeError::Transport("stdin not set".to_string()));
+    };
+    let mut stdin_guard = stdin_arc.lock().await;
+    let Some(ref mut stdin_stream) = *stdin_guard else {
+        return Err(ClaudeError::Transport("stdin not available".to_string()));
+    };
+
+    stdin_stream
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| ClaudeError::Transport(format!("Failed to write query: {}", e)))?;
+    stdin_stream
+        .write_all(b"\n")
+        .await
+        .map_err(|e| ClaudeError::Transport(format!("Failed to write newline: {}", e)))?;
+    stdin_stream
+        .flush()
+        .await
+        .map_err(|e| ClaudeError::Transport(format!("Failed to flush: {}", e)))
+}
+
 impl Drop for ClaudeClient {
     fn drop(&mut self) {
         // Note: We can't run async code in Drop, so we can't guarantee clean shutdown
-        // Users should call disconnect() explicitly
-        if self.connected {
+        // Users should call disconnect() explicitly, or use a ClientGuard
+        if self.is_connected() {
             eprintln!(
-                "Warning: ClaudeClient dropped without calling disconnect(). Resources may not be cleaned up properly."
+                "Warning: ClaudeClient dropped without calling disconnect(). Resources may not be cleaned up properly; see ClaudeClient::into_guard."
             );
         }
     }
@@ -0,0 +1,440 @@
+//! Typed argument parsing for slash commands
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::CommandError;
+
+/// Type of a command parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamType {
+    /// Any text
+    String,
+    /// A signed integer
+    Int,
+    /// `true` or `false`
+    Bool,
+    /// A floating point number
+    Float,
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamType::String => write!(f, "string"),
+            ParamType::Int => write!(f, "int"),
+            ParamType::Bool => write!(f, "bool"),
+            ParamType::Float => write!(f, "float"),
+        }
+    }
+}
+
+/// A parsed argument value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    /// A string value
+    String(String),
+    /// An integer value
+    Int(i64),
+    /// A boolean value
+    Bool(bool),
+    /// A floating point value
+    Float(f64),
+}
+
+impl ArgValue {
+    /// Parse `raw` as a value of type `param_type`
+    fn parse(raw: &str, param_type: ParamType) -> Option<Self> {
+        match param_type {
+            ParamType::String => Some(ArgValue::String(raw.to_string())),
+            ParamType::Int => raw.parse().ok().map(ArgValue::Int),
+            ParamType::Bool => raw.parse().ok().map(ArgValue::Bool),
+            ParamType::Float => raw.parse().ok().map(ArgValue::Float),
+        }
+    }
+}
+
+impl fmt::Display for ArgValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgValue::String(value) => write!(f, "{}", value),
+            ArgValue::Int(value) => write!(f, "{}", value),
+            ArgValue::Bool(value) => write!(f, "{}", value),
+            ArgValue::Float(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// A named command parameter
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::commands::{ArgValue, CommandParam, ParamType};
+///
+/// let param = CommandParam::new("replicas", ParamType::Int)
+///     .default_value(ArgValue::Int(1))
+///     .description("Number of instances to run");
+/// assert!(!param.required);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandParam {
+    /// Parameter name, also used as the flag (`--name`)
+    pub name: String,
+    /// Type the value is parsed as
+    pub param_type: ParamType,
+    /// Whether the parameter must be given
+    pub required: bool,
+    /// Value used when the parameter is not given
+    pub default: Option<ArgValue>,
+    /// Human-readable description
+    pub description: String,
+}
+
+impl CommandParam {
+    /// Create an optional parameter without a default
+    pub fn new(name: impl Into<String>, param_type: ParamType) -> Self {
+        Self {
+            name: name.into(),
+            param_type,
+            required: false,
+            default: None,
+            description: String::new(),
+        }
+    }
+
+    /// Make the parameter required
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set the value used when the parameter is not given
+    pub fn default_value(mut self, value: ArgValue) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    /// Set the description
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Boolean parameters are switches: `--name` alone sets them to `true`
+    fn is_switch(&self) -> bool {
+        self.param_type == ParamType::Bool
+    }
+
+    fn parse_value(&self, raw: &str) -> Result<ArgValue, String> {
+        ArgValue::parse(raw, self.param_type).ok_or_else(|| {
+            format!(
+                "invalid value '{}' for {}: expected {}",
+                raw, self.name, self.param_type
+            )
+        })
+    }
+}
+
+/// Parameters accepted by a slash command
+///
+/// Every parameter can be given as `--name value` (or `--name=value`).
+/// Parameters not given as flags are filled, in declaration order, from the
+/// positional arguments. Boolean parameters given as a bare `--name` are set
+/// to `true`.
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::commands::{CommandParam, CommandSpec, ParamType};
+///
+/// let spec = CommandSpec::new()
+///     .param(CommandParam::new("env", ParamType::String).required())
+///     .param(CommandParam::new("service", ParamType::String).required())
+///     .param(CommandParam::new("dry-run", ParamType::Bool));
+///
+/// let args = spec
+///     .parse(&["--env".to_string(), "prod".to_string(), "service-a".to_string()])
+///     .unwrap();
+/// assert_eq!(args.get_str("service"), Some("service-a"));
+/// assert_eq!(args.get_bool("dry-run"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandSpec {
+    /// Parameters, in positional order
+    pub params: Vec<CommandParam>,
+}
+
+impl CommandSpec {
+    /// Create a spec without parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter
+    pub fn param(mut self, param: CommandParam) -> Self {
+        self.params.push(param);
+        self
+    }
+
+    /// Get a parameter by name
+    pub fn get(&self, name: &str) -> Option<&CommandParam> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Parse raw arguments into typed values
+    ///
+    /// # Errors
+    ///
+    /// Returns `CommandError::InvalidArguments` for unknown flags, flags
+    /// without a value, values of the wrong type, extra positional arguments,
+    /// or missing required parameters. Missing parameters are all reported
+    /// at once.
+    pub fn parse(&self, args: &[String]) -> Result<CommandArgs, CommandError> {
+        let mut values = HashMap::new();
+        let mut positional = Vec::new();
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                positional.push(arg);
+                continue;
+            };
+
+            let (name, inline) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            let param = self
+                .get(name)
+                .ok_or_else(|| invalid(format!("unknown flag --{}", name)))?;
+
+            let raw = match inline {
+                Some(value) => value,
+                None if param.is_switch() => "true".to_string(),
+                None => iter
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| invalid(format!("missing value for --{}", name)))?,
+            };
+            values.insert(
+                param.name.clone(),
+                param.parse_value(&raw).map_err(invalid)?,
+            );
+        }
+
+        let mut positional = positional.into_iter();
+        for param in &self.params {
+            if values.contains_key(&param.name) {
+                continue;
+            }
+            if let Some(raw) = positional.next() {
+                values.insert(param.name.clone(), param.parse_value(raw).map_err(invalid)?);
+            }
+        }
+        let extra: Vec<_> = positional.map(String::as_str).collect();
+        if !extra.is_empty() {
+            return Err(invalid(format!(
+                "unexpected arguments: {}",
+                extra.join(" ")
+            )));
+        }
+
+        let missing: Vec<_> = self
+            .params
+            .iter()
+            .filter(|param| param.required && !values.contains_key(&param.name))
+            .map(|param| param.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(invalid(format!(
+                "missing required arguments: {}",
+                missing.join(", ")
+            )));
+        }
+
+        for param in &self.params {
+            if let Some(default) = &param.default {
+                values
+                    .entry(param.name.clone())
+                    .or_insert_with(|| default.clone());
+            }
+        }
+
+        Ok(CommandArgs { values })
+    }
+
+    /// One-line usage, e.g. `deploy <env> <service> [--dry-run]`
+    pub fn usage(&self, command: &str) -> String {
+        let mut usage = command.to_string();
+        for param in &self.params {
+            let part = if param.required {
+                format!("<{}>", param.name)
+            } else if param.is_switch() {
+                format!("[--{}]", param.name)
+            } else {
+                format!("[--{} <{}>]", param.name, param.param_type)
+            };
+            usage.push(' ');
+            usage.push_str(&part);
+        }
+        usage
+    }
+}
+
+fn invalid(message: String) -> CommandError {
+    CommandError::InvalidArguments(message)
+}
+
+/// Typed arguments produced by [`CommandSpec::parse`]
+///
+/// Holds a value for every parameter that was given or has a default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandArgs {
+    values: HashMap<String, ArgValue>,
+}
+
+impl CommandArgs {
+    /// Get the value of a parameter
+    pub fn get(&self, name: &str) -> Option<&ArgValue> {
+        self.values.get(name)
+    }
+
+    /// Check if a parameter has a value
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Get a string parameter
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            ArgValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get an integer parameter
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        match self.get(name)? {
+            ArgValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get a boolean parameter
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name)? {
+            ArgValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get a floating point parameter
+    pub fn get_float(&self, name: &str) -> Option<f64> {
+        match self.get(name)? {
+            ArgValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Number of parameters with a value
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if no parameter has a value
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn deploy_spec() -> CommandSpec {
+        CommandSpec::new()
+            .param(CommandParam::new("env", ParamType::String).required())
+            .param(CommandParam::new("service", ParamType::String).required())
+            .param(CommandParam::new("replicas", ParamType::Int).default_value(ArgValue::Int(1)))
+            .param(CommandParam::new("dry-run", ParamType::Bool))
+    }
+
+    #[test]
+    fn test_parse_flags_and_positionals() {
+        let parsed = deploy_spec()
+            .parse(&args(&[
+                "service-a",
+                "--dry-run",
+                "--env=prod",
+                "--replicas",
+                "3",
+            ]))
+            .unwrap();
+
+        assert_eq!(parsed.get_str("env"), Some("prod"));
+        assert_eq!(parsed.get_str("service"), Some("service-a"));
+        assert_eq!(parsed.get_int("replicas"), Some(3));
+        assert_eq!(parsed.get_bool("dry-run"), Some(true));
+    }
+
+    #[test]
+    fn test_parse_applies_defaults() {
+        let parsed = deploy_spec().parse(&args(&["prod", "service-a"])).unwrap();
+
+        assert_eq!(parsed.get_int("replicas"), Some(1));
+        assert!(!parsed.contains("dry-run"));
+        assert_eq!(parsed.len(), 3);
+        // Typed getters do not convert between types
+        assert_eq!(parsed.get_str("replicas"), None);
+    }
+
+    #[test]
+    fn test_parse_reports_all_missing_arguments() {
+        let err = deploy_spec().parse(&[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments: missing required arguments: env, service"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let spec = deploy_spec();
+        let cases = [
+            (vec!["prod", "a", "--replicas", "many"], "expected int"),
+            (
+                vec!["prod", "a", "--replicas"],
+                "missing value for --replicas",
+            ),
+            (vec!["prod", "a", "--force"], "unknown flag --force"),
+            (
+                vec!["prod", "a", "2", "--dry-run", "extra"],
+                "unexpected arguments: extra",
+            ),
+            (vec!["prod", "a", "--dry-run=maybe"], "expected bool"),
+        ];
+
+        for (raw, expected) in cases {
+            match spec.parse(&args(&raw)) {
+                Err(CommandError::InvalidArguments(msg)) => {
+                    assert!(msg.contains(expected), "{raw:?}: {msg}")
+                },
+                other => panic!("{raw:?}: expected InvalidArguments, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_usage() {
+        assert_eq!(
+            deploy_spec().usage("deploy"),
+            "deploy <env> <service> [--replicas <int>] [--dry-run]"
+        );
+        assert_eq!(CommandSpec::new().usage("status"), "status");
+    }
+}
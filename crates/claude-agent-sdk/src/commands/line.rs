@@ -0,0 +1,161 @@
+//! Tokenizing raw slash command input
+
+use super::CommandError;
+
+/// Split a command line into arguments with shell-style quoting
+///
+/// - Whitespace separates arguments.
+/// - Text in single quotes is taken literally.
+/// - In double quotes, `\"` and `\\` are escapes; other backslashes are kept.
+/// - Outside quotes, a backslash escapes the next character.
+/// - Quotes join with adjacent text, and `""` is an empty argument.
+///
+/// # Errors
+///
+/// Returns `CommandError::InvalidName` for an unterminated quote or a
+/// trailing backslash.
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::commands::parse_command_line;
+///
+/// let args = parse_command_line(r#"deploy "my service" --note 'it''s done' a\ b"#)?;
+/// assert_eq!(args, vec!["deploy", "my service", "--note", "its done", "a b"]);
+/// # Ok::<(), claude_agent_sdk::commands::CommandError>(())
+/// ```
+pub fn parse_command_line(line: &str) -> Result<Vec<String>, CommandError> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // Distinguishes an empty quoted argument from no argument at all
+    let mut in_arg = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            },
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err(malformed("unterminated single quote", line)),
+                    }
+                }
+            },
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            },
+                            None => return Err(malformed("unterminated double quote", line)),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(malformed("unterminated double quote", line)),
+                    }
+                }
+            },
+            '\\' => {
+                in_arg = true;
+                match chars.next() {
+                    Some(c) => current.push(c),
+                    None => return Err(malformed("trailing backslash", line)),
+                }
+            },
+            c => {
+                in_arg = true;
+                current.push(c);
+            },
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+fn malformed(problem: &str, line: &str) -> CommandError {
+    CommandError::InvalidName(format!("{} in: {}", problem, line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_words() {
+        assert_eq!(
+            parse_command_line("  status   --verbose\tnow ").unwrap(),
+            vec!["status", "--verbose", "now"]
+        );
+        assert!(parse_command_line("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_quoted_args_with_spaces() {
+        assert_eq!(
+            parse_command_line(r#"deploy "my service" 'other  service'"#).unwrap(),
+            vec!["deploy", "my service", "other  service"]
+        );
+        // Quotes join with adjacent text
+        assert_eq!(
+            parse_command_line(r#"--name="a b"c"#).unwrap(),
+            vec!["--name=a bc"]
+        );
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert_eq!(
+            parse_command_line(r#"say "she said \"hi\"" it\'s 'back\slash' "a\nb""#).unwrap(),
+            vec!["say", r#"she said "hi""#, "it's", r"back\slash", r"a\nb"]
+        );
+        assert_eq!(parse_command_line(r"one\ arg").unwrap(), vec!["one arg"]);
+    }
+
+    #[test]
+    fn test_empty_argument() {
+        assert_eq!(
+            parse_command_line(r#"set "" '' x"#).unwrap(),
+            vec!["set", "", "", "x"]
+        );
+    }
+
+    #[test]
+    fn test_unicode() {
+        assert_eq!(
+            parse_command_line("greet \"héllo wörld\" 日本語 '🚀 launch'").unwrap(),
+            vec!["greet", "héllo wörld", "日本語", "🚀 launch"]
+        );
+        // Non-ASCII whitespace separates arguments too
+        assert_eq!(parse_command_line("a\u{3000}b").unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        for line in [
+            r#"deploy "my service"#,
+            "deploy 'oops",
+            r"deploy \",
+            r#"x "a\"#,
+        ] {
+            assert!(
+                matches!(parse_command_line(line), Err(CommandError::InvalidName(_))),
+                "{line}"
+            );
+        }
+    }
+}
@@ -1,6 +1,16 @@
 //! Slash Commands system for Claude Agent SDK
 //!
 //! Provides a flexible command registration and execution system.
+//! Commands can declare typed parameters with a [`CommandSpec`], and every
+//! registry answers a built-in `help` command describing its commands.
+//! Related commands can share a namespace, as in `git:status`, and commands
+//! can be reached through aliases.
+
+mod args;
+mod line;
+
+pub use args::{ArgValue, CommandArgs, CommandParam, CommandSpec, ParamType};
+pub use line::parse_command_line;
 
 use std::collections::HashMap;
 use std::fmt;
@@ -19,6 +29,14 @@ pub enum CommandError {
     InvalidName(String),
     /// Command already registered
     AlreadyRegistered(String),
+    /// Arguments do not match the command's parameters
+    InvalidArguments(String),
+    /// Not a failure: send this prompt to Claude in place of the command line
+    ///
+    /// Handlers return it to rewrite a slash command into a prompt when the
+    /// registry is attached to a client with
+    /// [`ClaudeClient::with_command_registry`](crate::ClaudeClient::with_command_registry).
+    ForwardToClaude(String),
 }
 
 impl fmt::Display for CommandError {
@@ -30,6 +48,8 @@ impl fmt::Display for CommandError {
             CommandError::AlreadyRegistered(name) => {
                 write!(f, "Command already registered: {}", name)
             }
+            CommandError::InvalidArguments(msg) => write!(f, "Invalid arguments: {}", msg),
+            CommandError::ForwardToClaude(prompt) => write!(f, "Forwarded to Claude: {}", prompt),
         }
     }
 }
@@ -50,15 +70,34 @@ pub type CommandHandler = Arc<
         + Sync,
 >;
 
+/// Type alias for async command handlers with typed arguments
+///
+/// Like [`CommandHandler`], but receives the arguments already parsed and
+/// validated against the command's [`CommandSpec`].
+pub type TypedCommandHandler = Arc<
+    dyn Fn(&str, CommandArgs) -> Pin<Box<dyn Future<Output = Result<String, CommandError>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Name of the built-in help command
+pub const HELP_COMMAND: &str = "help";
+
+/// Separator between a namespace and a command name, as in `git:status`
+pub const NAMESPACE_SEPARATOR: char = ':';
+
 /// A slash command with metadata and handler
 #[derive(Clone)]
 pub struct SlashCommand {
-    /// Unique command name (e.g., "help", "status", "deploy")
+    /// Unique command name (e.g., "help", "status", "deploy"), or
+    /// `namespace:name` for a namespaced command (e.g., "git:status")
     pub name: String,
     /// Human-readable description
     pub description: String,
     /// Async handler function
     pub handler: CommandHandler,
+    /// Parameters, shown by `help`; `None` for commands taking raw arguments
+    pub spec: Option<CommandSpec>,
 }
 
 impl SlashCommand {
@@ -77,11 +116,124 @@ impl SlashCommand {
             name: name.into(),
             description: description.into(),
             handler,
+            spec: None,
         }
     }
 
+    /// Create a slash command with typed parameters
+    ///
+    /// Arguments are parsed against `spec` before `handler` runs. Invalid
+    /// arguments fail with `CommandError::InvalidArguments`, including the
+    /// command's usage, and the handler is not called.
+    ///
+    /// # Arguments
+    /// * `name` - Unique command identifier
+    /// * `description` - Human-readable description
+    /// * `spec` - Parameters the command accepts
+    /// * `handler` - Async function handling command execution
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use claude_agent_sdk::commands::{
+    ///     CommandParam, CommandRegistry, CommandSpec, ParamType, SlashCommand,
+    /// };
+    /// use std::sync::Arc;
+    ///
+    /// # tokio_test::block_on(async {
+    /// let spec = CommandSpec::new()
+    ///     .param(CommandParam::new("env", ParamType::String).required())
+    ///     .param(CommandParam::new("service", ParamType::String).required())
+    ///     .param(CommandParam::new("dry-run", ParamType::Bool));
+    ///
+    /// let mut registry = CommandRegistry::new();
+    /// registry.register(SlashCommand::with_args(
+    ///     "deploy",
+    ///     "Deploy a service",
+    ///     spec,
+    ///     Arc::new(|_name, args| {
+    ///         let env = args.get_str("env").unwrap_or_default().to_string();
+    ///         let service = args.get_str("service").unwrap_or_default().to_string();
+    ///         Box::pin(async move { Ok(format!("Deploying {} to {}", service, env)) })
+    ///     }),
+    /// ))?;
+    ///
+    /// let output = registry
+    ///     .execute("deploy", vec!["--env".into(), "prod".into(), "service-a".into()])
+    ///     .await?;
+    /// assert_eq!(output, "Deploying service-a to prod");
+    /// assert!(registry.execute("deploy", vec![]).await.is_err());
+    /// # Ok::<(), claude_agent_sdk::commands::CommandError>(())
+    /// # }).unwrap();
+    /// ```
+    pub fn with_args(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        spec: CommandSpec,
+        handler: TypedCommandHandler,
+    ) -> Self {
+        let name = name.into();
+        let parse_spec = spec.clone();
+        let handler: CommandHandler = Arc::new(move |name, args| match parse_spec.parse(&args) {
+            Ok(args) => handler(name, args),
+            Err(CommandError::InvalidArguments(msg)) => {
+                let msg = format!("{}\nUsage: {}", msg, parse_spec.usage(name));
+                Box::pin(async move { Err(CommandError::InvalidArguments(msg)) })
+            },
+            Err(e) => Box::pin(async move { Err(e) }),
+        });
+
+        Self {
+            name,
+            description: description.into(),
+            handler,
+            spec: Some(spec),
+        }
+    }
+
+    /// Usage line and parameter descriptions
+    fn help(&self) -> String {
+        let mut help = format!("{} - {}", self.name, self.description);
+        let Some(spec) = &self.spec else {
+            return help;
+        };
+
+        help.push_str(&format!("\nUsage: {}", spec.usage(&self.name)));
+        for param in &spec.params {
+            let mut details = param.param_type.to_string();
+            if param.required {
+                details.push_str(", required");
+            }
+            if let Some(default) = &param.default {
+                details.push_str(&format!(", default: {}", default));
+            }
+            help.push_str(&format!("\n  {} ({})", param.name, details));
+            if !param.description.is_empty() {
+                help.push_str(&format!(" - {}", param.description));
+            }
+        }
+        help
+    }
+
     /// Validate command name
+    ///
+    /// A name may have one namespace, as in `git:status`; both parts follow
+    /// the rules of a plain name.
     fn validate_name(name: &str) -> Result<(), CommandError> {
+        let mut parts = name.split(NAMESPACE_SEPARATOR);
+        let (first, second) = (parts.next().unwrap_or_default(), parts.next());
+        if parts.next().is_some() {
+            return Err(CommandError::InvalidName(format!(
+                "Command name cannot contain more than one '{}'",
+                NAMESPACE_SEPARATOR
+            )));
+        }
+        Self::validate_part(first)?;
+        second.map_or(Ok(()), Self::validate_part)
+    }
+
+    /// Validate a name without namespace
+    fn validate_part(name: &str) -> Result<(), CommandError> {
         if name.is_empty() {
             return Err(CommandError::InvalidName("Command name cannot be empty".to_string()));
         }
@@ -100,9 +252,48 @@ impl SlashCommand {
 }
 
 /// Registry for managing slash commands
+///
+/// Every registry answers `help` with usage for all registered commands, and
+/// `help <command>` with usage for one. Registering a command named `help`
+/// replaces the built-in one.
+///
+/// Aliases name another command and are resolved by every lookup, so
+/// [`get`](Self::get) and [`execute`](Self::execute) treat them like the
+/// command itself.
+///
+/// # Example
+///
+/// ```
+/// # use claude_agent_sdk::commands::{CommandRegistry, SlashCommand};
+/// # use std::sync::Arc;
+/// # tokio_test::block_on(async {
+/// let echo = |name: &str, description: &str| {
+///     SlashCommand::new(
+///         name,
+///         description,
+///         Arc::new(|name, _args| {
+///             let name = name.to_string();
+///             Box::pin(async move { Ok(name) })
+///         }),
+///     )
+/// };
+///
+/// let mut registry = CommandRegistry::new();
+/// registry.register_namespaced("git", echo("status", "Show the working tree"))?;
+/// registry.register_namespaced("git", echo("commit", "Record changes"))?;
+/// registry.alias("st", "git:status")?;
+///
+/// assert_eq!(registry.execute("st", vec![]).await?, "git:status");
+/// assert_eq!(registry.execute_line("/git commit").await?, "git:commit");
+/// assert!(registry.dispatch_prefix("git", vec![]).await?.contains("git:status"));
+/// # Ok::<(), claude_agent_sdk::commands::CommandError>(())
+/// # }).unwrap();
+/// ```
 #[derive(Default)]
 pub struct CommandRegistry {
     commands: HashMap<String, SlashCommand>,
+    /// Command names by alias
+    aliases: HashMap<String, String>,
 }
 
 impl CommandRegistry {
@@ -110,6 +301,7 @@ impl CommandRegistry {
     pub fn new() -> Self {
         Self {
             commands: HashMap::new(),
+            aliases: HashMap::new(),
         }
     }
 
@@ -121,10 +313,11 @@ impl CommandRegistry {
     /// # Returns
     /// * `Ok(())` if registration successful
     /// * `Err(CommandError)` if name is invalid or already registered
+    ///   as a command or alias
     pub fn register(&mut self, command: SlashCommand) -> Result<(), CommandError> {
         SlashCommand::validate_name(&command.name)?;
 
-        if self.commands.contains_key(&command.name) {
+        if self.is_taken(&command.name) {
             return Err(CommandError::AlreadyRegistered(command.name));
         }
 
@@ -132,8 +325,130 @@ impl CommandRegistry {
         Ok(())
     }
 
+    /// Register `command` in `namespace`, as `namespace:name`
+    ///
+    /// # Returns
+    /// * `Err(CommandError::InvalidName)` if the namespace or the command's
+    ///   name is invalid, or the command already has a namespace
+    /// * `Err(CommandError::AlreadyRegistered)` if the qualified name is taken
+    pub fn register_namespaced(
+        &mut self,
+        namespace: &str,
+        mut command: SlashCommand,
+    ) -> Result<(), CommandError> {
+        SlashCommand::validate_part(namespace)?;
+        if command.name.contains(NAMESPACE_SEPARATOR) {
+            return Err(CommandError::InvalidName(format!(
+                "{} already has a namespace",
+                command.name
+            )));
+        }
+        command.name = format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, command.name);
+        self.register(command)
+    }
+
+    /// Make `alias` another name for the command `target`
+    ///
+    /// `target` may itself be an alias; the new alias then names the same
+    /// command.
+    ///
+    /// # Returns
+    /// * `Err(CommandError::InvalidName)` if `alias` is not a valid name
+    /// * `Err(CommandError::AlreadyRegistered)` if `alias` is already a command
+    ///   or alias
+    /// * `Err(CommandError::NotFound)` if `target` names no command
+    pub fn alias(&mut self, alias: &str, target: &str) -> Result<(), CommandError> {
+        SlashCommand::validate_name(alias)?;
+        if self.is_taken(alias) {
+            return Err(CommandError::AlreadyRegistered(alias.to_string()));
+        }
+        let command = self.resolve(target);
+        if !self.commands.contains_key(command) {
+            return Err(CommandError::NotFound(target.to_string()));
+        }
+
+        self.aliases.insert(alias.to_string(), command.to_string());
+        Ok(())
+    }
+
+    /// Aliases of the command `name`, sorted
+    pub fn aliases_of(&self, name: &str) -> Vec<String> {
+        let mut aliases: Vec<_> = self
+            .aliases
+            .iter()
+            .filter(|(_, target)| *target == name)
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        aliases.sort();
+        aliases
+    }
+
+    /// Whether `name` is used by a command or alias
+    fn is_taken(&self, name: &str) -> bool {
+        self.commands.contains_key(name) || self.aliases.contains_key(name)
+    }
+
+    /// Command name for `name`, following an alias
+    fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases.get(name).map_or(name, String::as_str)
+    }
+
+    /// Commands in `namespace`, sorted by name
+    fn namespace_commands(&self, namespace: &str) -> Vec<&SlashCommand> {
+        let mut commands: Vec<_> = self
+            .commands
+            .values()
+            .filter(|command| {
+                command
+                    .name
+                    .split_once(NAMESPACE_SEPARATOR)
+                    .is_some_and(|(prefix, _)| prefix == namespace)
+            })
+            .collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        commands
+    }
+
+    /// Run a command of `namespace`, named by the first argument
+    ///
+    /// Without arguments, lists the namespace's commands instead.
+    ///
+    /// # Returns
+    /// * `Ok(String)` - Command output, or the list of commands
+    /// * `Err(CommandError::NotFound)` - If the namespace has no commands, or
+    ///   not the one named
+    pub async fn dispatch_prefix(
+        &self,
+        namespace: &str,
+        args: Vec<String>,
+    ) -> Result<String, CommandError> {
+        let commands = self.namespace_commands(namespace);
+        if commands.is_empty() {
+            return Err(CommandError::NotFound(namespace.to_string()));
+        }
+
+        let mut args = args.into_iter();
+        let Some(subcommand) = args.next() else {
+            let mut list = format!("Commands in {}:", namespace);
+            for command in commands {
+                list.push_str(&format!("\n  {} - {}", command.name, command.description));
+            }
+            return Ok(list);
+        };
+
+        let name = format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, subcommand);
+        match self.commands.get(&name) {
+            Some(command) => (command.handler)(&name, args.collect()).await,
+            None => Err(CommandError::NotFound(name)),
+        }
+    }
+
     /// Execute a command by name
     ///
+    /// An alias runs its command, which receives the command's own name. A
+    /// namespace that is not itself a command dispatches to its commands, as
+    /// with [`dispatch_prefix`](Self::dispatch_prefix).
+    ///
     /// # Arguments
     /// * `name` - Command name to execute
     /// * `args` - Command arguments
@@ -142,25 +457,124 @@ impl CommandRegistry {
     /// * `Ok(String)` - Command output
     /// * `Err(CommandError)` - If command not found or execution fails
     pub async fn execute(&self, name: &str, args: Vec<String>) -> Result<String, CommandError> {
-        let command = self
-            .commands
-            .get(name)
-            .ok_or_else(|| CommandError::NotFound(name.to_string()))?;
+        let name = self.resolve(name);
+        let Some(command) = self.commands.get(name) else {
+            if !self.namespace_commands(name).is_empty() {
+                return self.dispatch_prefix(name, args).await;
+            }
+            if name == HELP_COMMAND {
+                return match args.as_slice() {
+                    [] => Ok(self.help_text()),
+                    [command] => self.command_help(command),
+                    _ => Err(CommandError::InvalidArguments(format!(
+                        "expected at most one command name\nUsage: {} [command]",
+                        HELP_COMMAND
+                    ))),
+                };
+            }
+            return Err(CommandError::NotFound(name.to_string()));
+        };
 
         (command.handler)(name, args).await
     }
 
+    /// Execute a raw slash command line, e.g. `/deploy "my service" --dry-run`
+    ///
+    /// The line is split with [`parse_command_line`]; the first argument,
+    /// without its slash, names the command.
+    ///
+    /// # Returns
+    /// * `Ok(String)` - Command output
+    /// * `Err(CommandError::NotFound)` - If the line does not start with `/`
+    ///   or names an unknown command
+    /// * `Err(CommandError::InvalidName)` - If the line is malformed, e.g. has
+    ///   an unterminated quote
+    /// * `Err(CommandError)` - If execution fails
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::commands::{CommandRegistry, SlashCommand};
+    /// # use std::sync::Arc;
+    /// # tokio_test::block_on(async {
+    /// let mut registry = CommandRegistry::new();
+    /// registry.register(SlashCommand::new(
+    ///     "echo",
+    ///     "Echo arguments",
+    ///     Arc::new(|_name, args| Box::pin(async move { Ok(args.join("|")) })),
+    /// ))?;
+    ///
+    /// let output = registry.execute_line(r#"/echo "my service" --dry-run"#).await?;
+    /// assert_eq!(output, "my service|--dry-run");
+    /// # Ok::<(), claude_agent_sdk::commands::CommandError>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn execute_line(&self, line: &str) -> Result<String, CommandError> {
+        let not_found = || CommandError::NotFound(line.to_string());
+        // The name must directly follow the slash, so `/ deploy` names no command
+        let rest = line
+            .trim_start()
+            .strip_prefix('/')
+            .filter(|rest| !rest.starts_with(char::is_whitespace))
+            .ok_or_else(not_found)?;
+
+        let mut args = parse_command_line(rest)?.into_iter();
+        let name = args
+            .next()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(not_found)?;
+
+        self.execute(&name, args.collect()).await
+    }
+
+    /// Help for all registered commands, sorted by name
+    ///
+    /// This is the output of the built-in `help` command.
+    pub fn help_text(&self) -> String {
+        let mut commands: Vec<_> = self.commands.values().collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut help = String::from("Available commands:");
+        for command in commands {
+            for line in command.help().lines() {
+                help.push_str("\n  ");
+                help.push_str(line);
+            }
+        }
+        if !self.commands.contains_key(HELP_COMMAND) {
+            help.push_str(&format!(
+                "\n  {} - Show available commands\n  Usage: {} [command]",
+                HELP_COMMAND, HELP_COMMAND
+            ));
+        }
+        help
+    }
+
+    /// Help for one registered command
+    ///
+    /// # Returns
+    /// * `Err(CommandError::NotFound)` if command doesn't exist
+    pub fn command_help(&self, name: &str) -> Result<String, CommandError> {
+        self.get(name)
+            .map(SlashCommand::help)
+            .ok_or_else(|| CommandError::NotFound(name.to_string()))
+    }
+
     /// Check if a command exists
+    ///
+    /// Always true for `help`, which is built in, and true for aliases.
     pub fn exists(&self, name: &str) -> bool {
-        self.commands.contains_key(name)
+        name == HELP_COMMAND || self.get(name).is_some()
     }
 
-    /// Get a command by name
+    /// Get a command by name or alias
     pub fn get(&self, name: &str) -> Option<&SlashCommand> {
-        self.commands.get(name)
+        self.commands.get(self.resolve(name))
     }
 
-    /// Get all registered command names
+    /// Get all registered command names, qualified by their namespace
+    ///
+    /// Aliases are not included.
     pub fn list_names(&self) -> Vec<String> {
         self.commands.keys().cloned().collect()
     }
@@ -180,21 +594,28 @@ impl CommandRegistry {
         self.commands.is_empty()
     }
 
-    /// Unregister a command
+    /// Unregister a command, and every alias of it
+    ///
+    /// `name` may also be an alias of the command.
     ///
     /// # Returns
     /// * `Ok(())` if command was removed
     /// * `Err(CommandError::NotFound)` if command doesn't exist
     pub fn unregister(&mut self, name: &str) -> Result<(), CommandError> {
-        self.commands
-            .remove(name)
+        let command = self
+            .get(name)
+            .map(|command| command.name.clone())
             .ok_or_else(|| CommandError::NotFound(name.to_string()))?;
+
+        self.commands.remove(&command);
+        self.aliases.retain(|_, target| *target != command);
         Ok(())
     }
 
-    /// Clear all commands
+    /// Clear all commands and aliases
     pub fn clear(&mut self) {
         self.commands.clear();
+        self.aliases.clear();
     }
 }
 
@@ -204,6 +625,7 @@ impl fmt::Debug for SlashCommand {
             .field("name", &self.name)
             .field("description", &self.description)
             .field("handler", &"<function>")
+            .field("spec", &self.spec)
             .finish()
     }
 }
@@ -213,6 +635,7 @@ impl fmt::Debug for CommandRegistry {
         f.debug_struct("CommandRegistry")
             .field("commands_count", &self.commands.len())
             .field("command_names", &self.list_names())
+            .field("aliases", &self.aliases)
             .finish()
     }
 }
@@ -410,6 +833,7 @@ mod tests {
         assert!(format!("{}", CommandError::ExecutionFailed("error".to_string())).contains("error"));
         assert!(format!("{}", CommandError::InvalidName("bad".to_string())).contains("bad"));
         assert!(format!("{}", CommandError::AlreadyRegistered("cmd".to_string())).contains("cmd"));
+        assert!(format!("{}", CommandError::InvalidArguments("bad".to_string())).contains("bad"));
     }
 
     #[test]
@@ -463,4 +887,271 @@ mod tests {
 
         assert!(matches!(result, Err(CommandError::ExecutionFailed(_))));
     }
+
+    fn deploy_command() -> SlashCommand {
+        let spec = CommandSpec::new()
+            .param(
+                CommandParam::new("env", ParamType::String)
+                    .required()
+                    .description("Target environment"),
+            )
+            .param(CommandParam::new("service", ParamType::String).required())
+            .param(
+                CommandParam::new("dry-run", ParamType::Bool)
+                    .default_value(ArgValue::Bool(false))
+                    .description("Only print the plan"),
+            );
+
+        SlashCommand::with_args(
+            "deploy",
+            "Deploy a service",
+            spec,
+            Arc::new(|_name, args| {
+                let output = format!(
+                    "{} -> {} (dry run: {})",
+                    args.get_str("service").unwrap(),
+                    args.get_str("env").unwrap(),
+                    args.get_bool("dry-run").unwrap()
+                );
+                Box::pin(async move { Ok(output) })
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_typed_command_receives_parsed_args() {
+        let mut registry = CommandRegistry::new();
+        registry.register(deploy_command()).unwrap();
+
+        let output = registry
+            .execute(
+                "deploy",
+                vec!["--env".into(), "prod".into(), "service-a".into()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(output, "service-a -> prod (dry run: false)");
+
+        let output = registry
+            .execute(
+                "deploy",
+                vec!["staging".into(), "service-b".into(), "--dry-run".into()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(output, "service-b -> staging (dry run: true)");
+    }
+
+    #[tokio::test]
+    async fn test_typed_command_reports_missing_args_with_usage() {
+        let mut registry = CommandRegistry::new();
+        registry.register(deploy_command()).unwrap();
+
+        let err = registry.execute("deploy", vec![]).await.unwrap_err();
+        match err {
+            CommandError::InvalidArguments(msg) => assert_eq!(
+                msg,
+                "missing required arguments: env, service\n\
+                 Usage: deploy <env> <service> [--dry-run]"
+            ),
+            other => panic!("expected InvalidArguments, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builtin_help() {
+        let mut registry = CommandRegistry::new();
+        registry.register(deploy_command()).unwrap();
+        registry.register(create_test_command("status", "Show status")).unwrap();
+        assert!(registry.exists("help"));
+        assert_eq!(registry.len(), 2);
+
+        let help = registry.execute("help", vec![]).await.unwrap();
+        assert_eq!(
+            help,
+            "Available commands:\n\
+             \x20 deploy - Deploy a service\n\
+             \x20 Usage: deploy <env> <service> [--dry-run]\n\
+             \x20   env (string, required) - Target environment\n\
+             \x20   service (string, required)\n\
+             \x20   dry-run (bool, default: false) - Only print the plan\n\
+             \x20 status - Show status\n\
+             \x20 help - Show available commands\n\
+             \x20 Usage: help [command]"
+        );
+
+        let help = registry.execute("help", vec!["status".into()]).await.unwrap();
+        assert_eq!(help, "status - Show status");
+        assert!(matches!(
+            registry.execute("help", vec!["missing".into()]).await,
+            Err(CommandError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_registered_help_replaces_builtin() {
+        let mut registry = CommandRegistry::new();
+        registry.register(create_test_command("help", "Custom help")).unwrap();
+
+        let output = registry.execute("help", vec![]).await.unwrap();
+        assert_eq!(output, "Executed with args: []");
+        assert!(!registry.help_text().contains("Show available commands"));
+    }
+
+    fn echo_command() -> SlashCommand {
+        SlashCommand::new(
+            "echo",
+            "Echo arguments",
+            Arc::new(|_name, args| Box::pin(async move { Ok(format!("{:?}", args)) })),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_execute_line_dispatches_quoted_args() {
+        let mut registry = CommandRegistry::new();
+        registry.register(echo_command()).unwrap();
+        registry.register(deploy_command()).unwrap();
+
+        let output = registry
+            .execute_line(r#"/echo "my service" 'x y' "" \"quoted\" ünï 🚀"#)
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            r#"["my service", "x y", "", "\"quoted\"", "ünï", "🚀"]"#
+        );
+
+        let output = registry
+            .execute_line(r#"  /deploy --env prod "my service" --dry-run"#)
+            .await
+            .unwrap();
+        assert_eq!(output, "my service -> prod (dry run: true)");
+    }
+
+    #[test]
+    fn test_namespaced_names() {
+        assert!(SlashCommand::validate_name("git:status").is_ok());
+        for name in ["git:status:short", ":status", "git:", "git:1st", "1git:status", "git: st"] {
+            assert!(
+                matches!(
+                    SlashCommand::validate_name(name),
+                    Err(CommandError::InvalidName(_))
+                ),
+                "{name:?}"
+            );
+        }
+
+        let mut registry = CommandRegistry::new();
+        registry
+            .register_namespaced("git", create_test_command("status", "Status"))
+            .unwrap();
+        assert_eq!(registry.list_names(), vec!["git:status"]);
+        assert!(registry.get("git:status").is_some());
+        assert!(registry.get("status").is_none());
+        assert!(matches!(
+            registry.register_namespaced("git", create_test_command("git:log", "Log")),
+            Err(CommandError::InvalidName(_))
+        ));
+        assert!(matches!(
+            registry.register(create_test_command("git:status", "Again")),
+            Err(CommandError::AlreadyRegistered(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_aliases_resolve_and_collide() {
+        let mut registry = CommandRegistry::new();
+        registry.register(echo_command()).unwrap();
+        registry.register(create_test_command("status", "Status")).unwrap();
+
+        registry.alias("st", "status").unwrap();
+        registry.alias("s", "st").unwrap();
+        assert_eq!(registry.get("s").unwrap().name, "status");
+        assert_eq!(registry.aliases_of("status"), vec!["s", "st"]);
+        assert_eq!(
+            registry.execute("st", vec!["x".into()]).await.unwrap(),
+            "Executed with args: [\"x\"]"
+        );
+        assert_eq!(registry.list_names().len(), 2);
+
+        // An alias can't shadow a command, nor a command an alias
+        assert!(matches!(
+            registry.alias("echo", "status"),
+            Err(CommandError::AlreadyRegistered(_))
+        ));
+        assert!(matches!(
+            registry.alias("st", "echo"),
+            Err(CommandError::AlreadyRegistered(_))
+        ));
+        assert!(matches!(
+            registry.register(create_test_command("st", "Shadow")),
+            Err(CommandError::AlreadyRegistered(_))
+        ));
+        assert!(matches!(
+            registry.alias("gone", "missing"),
+            Err(CommandError::NotFound(_))
+        ));
+
+        registry.unregister("st").unwrap();
+        assert!(!registry.exists("status"));
+        assert!(!registry.exists("s"));
+        assert!(registry.aliases_of("status").is_empty());
+        registry.register(create_test_command("st", "Now free")).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_prefix() {
+        let mut registry = CommandRegistry::new();
+        registry
+            .register_namespaced("git", create_test_command("status", "Show status"))
+            .unwrap();
+        registry
+            .register_namespaced("git", create_test_command("commit", "Record changes"))
+            .unwrap();
+
+        assert_eq!(
+            registry.dispatch_prefix("git", vec![]).await.unwrap(),
+            "Commands in git:\n  git:commit - Record changes\n  git:status - Show status"
+        );
+        assert_eq!(
+            registry
+                .dispatch_prefix("git", vec!["commit".into(), "-m".into()])
+                .await
+                .unwrap(),
+            "Executed with args: [\"-m\"]"
+        );
+        assert_eq!(
+            registry.execute_line("/git status").await.unwrap(),
+            "Executed with args: []"
+        );
+        assert!(matches!(
+            registry.dispatch_prefix("git", vec!["push".into()]).await,
+            Err(CommandError::NotFound(_))
+        ));
+        assert!(matches!(
+            registry.dispatch_prefix("svn", vec![]).await,
+            Err(CommandError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_line_errors() {
+        let mut registry = CommandRegistry::new();
+        registry.register(echo_command()).unwrap();
+
+        for line in ["echo hi", "/missing", "/", "/ echo", "", r#"/"" hi"#] {
+            assert!(
+                matches!(
+                    registry.execute_line(line).await,
+                    Err(CommandError::NotFound(_))
+                ),
+                "{line:?}"
+            );
+        }
+        assert!(matches!(
+            registry.execute_line(r#"/echo "unterminated"#).await,
+            Err(CommandError::InvalidName(_))
+        ));
+        assert!(registry.execute_line("/help").await.unwrap().contains("echo"));
+    }
 }
@@ -0,0 +1,8 @@
+//! # Compatibility layers
+//!
+//! Adapters that expose the SDK through interfaces of other LLM clients, so
+//! existing call sites can move over without being rewritten.
+//!
+//! - [`openai`]: `chat.completions.create`-style requests and responses
+
+pub mod openai;
@@ -0,0 +1,530 @@
+//! OpenAI-style chat completions on top of [`query`](crate::query::query)
+//!
+//! [`ChatClient::create`] takes the same shape of request as
+//! `chat.completions.create(messages, model)` and returns an OpenAI-shaped
+//! [`ChatResponse`]. [`ChatClient::create_stream`] yields [`ChatChunk`]s shaped
+//! like the SSE deltas of a streamed completion.
+//!
+//! Messages are translated as follows:
+//!
+//! - `system` messages become the system prompt
+//! - earlier `user` and `assistant` messages are primed into the prompt as a
+//!   transcript the model continues from
+//! - the final message, which must be a `user` message, is the prompt
+//!
+//! `max_tokens` is passed to the CLI. Sampling parameters the CLI does not
+//! support (`temperature`, `top_p`, `n`, `stop`) are ignored with a warning that
+//! is logged once per client.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::compat::openai::{ChatClient, ChatMessage, ChatRequest};
+//!
+//! # async fn example() -> claude_agent_sdk::Result<()> {
+//! let client = ChatClient::default();
+//! let response = client
+//!     .create(ChatRequest::new(
+//!         "claude-sonnet-4-20250514",
+//!         vec![
+//!             ChatMessage::system("Answer in one word."),
+//!             ChatMessage::user("What is the capital of France?"),
+//!         ],
+//!     ))
+//!     .await?;
+//! println!("{}", response.choices[0].message.content);
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::stream::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::errors::{ClaudeError, Result};
+use crate::types::config::{ClaudeAgentOptions, SystemPrompt};
+use crate::types::messages::{ContentBlock, Message, ResultMessage};
+
+/// Environment variable the CLI reads its output token limit from
+const MAX_OUTPUT_TOKENS_ENV: &str = "CLAUDE_CODE_MAX_OUTPUT_TOKENS";
+
+/// Author of a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    /// Instructions for the model
+    System,
+    /// The user
+    User,
+    /// The model
+    Assistant,
+}
+
+/// A message of the conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// Author of the message
+    pub role: ChatRole,
+    /// Message text
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// Create a system message
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::System,
+            content: content.into(),
+        }
+    }
+
+    /// Create a user message
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::User,
+            content: content.into(),
+        }
+    }
+
+    /// Create an assistant message
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// Request for a chat completion
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatRequest {
+    /// Model to use, empty for the configured model
+    pub model: String,
+    /// The conversation so far, ending with a user message
+    pub messages: Vec<ChatMessage>,
+    /// Maximum number of tokens to generate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Not supported, ignored with a warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Not supported, ignored with a warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Not supported beyond 1, ignored with a warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+    /// Not supported, ignored with a warning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+}
+
+impl ChatRequest {
+    /// Create a request for `model` with the given messages
+    pub fn new(model: impl Into<String>, messages: Vec<ChatMessage>) -> Self {
+        Self {
+            model: model.into(),
+            messages,
+            ..Default::default()
+        }
+    }
+}
+
+/// Token usage of a completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatUsage {
+    /// Input tokens, including cache reads and writes
+    pub prompt_tokens: u64,
+    /// Generated tokens
+    pub completion_tokens: u64,
+    /// Sum of prompt and completion tokens
+    pub total_tokens: u64,
+}
+
+impl ChatUsage {
+    fn from_result(result: &ResultMessage) -> Self {
+        let Some(usage) = &result.token_usage else {
+            return Self::default();
+        };
+
+        let prompt_tokens =
+            usage.input_tokens + usage.cache_creation_input_tokens + usage.cache_read_input_tokens;
+        let completion_tokens = usage.output_tokens;
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+}
+
+/// One completion choice
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatChoice {
+    /// Index of the choice, always 0
+    pub index: u32,
+    /// The generated message
+    pub message: ChatMessage,
+    /// Why generation stopped: `stop` or `length`
+    pub finish_reason: Option<String>,
+}
+
+/// Response to [`ChatClient::create`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatResponse {
+    /// Completion ID
+    pub id: String,
+    /// Always `chat.completion`
+    pub object: String,
+    /// Creation time (seconds since epoch)
+    pub created: u64,
+    /// Model that generated the completion
+    pub model: String,
+    /// The completion, as a single choice
+    pub choices: Vec<ChatChoice>,
+    /// Token usage
+    pub usage: ChatUsage,
+}
+
+/// Incremental content of a streamed choice
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatDelta {
+    /// Set on the first chunk only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<ChatRole>,
+    /// Text generated since the previous chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// One choice of a streamed chunk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatChunkChoice {
+    /// Index of the choice, always 0
+    pub index: u32,
+    /// New content
+    pub delta: ChatDelta,
+    /// Set on the last chunk
+    pub finish_reason: Option<String>,
+}
+
+/// Chunk of [`ChatClient::create_stream`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatChunk {
+    /// Completion ID, the same for every chunk of a stream
+    pub id: String,
+    /// Always `chat.completion.chunk`
+    pub object: String,
+    /// Creation time (seconds since epoch)
+    pub created: u64,
+    /// Model that generates the completion
+    pub model: String,
+    /// The delta, as a single choice
+    pub choices: Vec<ChatChunkChoice>,
+    /// Token usage, on the last chunk only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ChatUsage>,
+}
+
+/// Stream of [`ChatChunk`]s
+pub type ChatStream = Pin<Box<dyn Stream<Item = Result<ChatChunk>> + Send>>;
+
+/// OpenAI-style chat completions client
+///
+/// Every request runs as a one-shot query with the client's options; the
+/// request's model, system messages and `max_tokens` override them.
+pub struct ChatClient {
+    options: ClaudeAgentOptions,
+    warned: Mutex<HashSet<&'static str>>,
+}
+
+impl ChatClient {
+    /// Create a client that runs requests with `options`
+    pub fn new(options: ClaudeAgentOptions) -> Self {
+        Self {
+            options,
+            warned: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Run a chat completion
+    pub async fn create(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let (prompt, options) = self.translate(&request)?;
+        let messages = crate::query::query(prompt, Some(options)).await?;
+
+        let mut text = String::new();
+        let mut model = None;
+        let mut result = None;
+        for message in messages {
+            match message {
+                Message::Assistant(assistant) if assistant.parent_tool_use_id.is_none() => {
+                    if assistant.message.model.is_some() {
+                        model = assistant.message.model.clone();
+                    }
+                    text.push_str(&text_of(&assistant.message.content));
+                },
+                Message::Result(message) => result = Some(message),
+                _ => {},
+            }
+        }
+
+        let result = result
+            .ok_or_else(|| ClaudeError::Transport("CLI exited without a result".to_string()))?;
+        if let Some(final_text) = result.result.clone().filter(|t| !t.is_empty()) {
+            text = final_text;
+        }
+
+        Ok(ChatResponse {
+            id: completion_id(),
+            object: "chat.completion".to_string(),
+            created: now_secs(),
+            model: model.unwrap_or(request.model),
+            choices: vec![ChatChoice {
+                index: 0,
+                message: ChatMessage::assistant(text),
+                finish_reason: Some(finish_reason(&result)?.to_string()),
+            }],
+            usage: ChatUsage::from_result(&result),
+        })
+    }
+
+    /// Run a chat completion, streaming text as it is generated
+    ///
+    /// The first chunk carries the assistant role, the last one the finish
+    /// reason and usage.
+    pub async fn create_stream(&self, request: ChatRequest) -> Result<ChatStream> {
+        let (prompt, mut options) = self.translate(&request)?;
+        options.include_partial_messages = true;
+        let mut messages = crate::query::query_stream(prompt, Some(options)).await?;
+
+        let id = completion_id();
+        let created = now_secs();
+        let mut model = request.model;
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let chunk = |model: &str, delta: ChatDelta, finish_reason: Option<String>, usage| ChatChunk {
+                id: id.clone(),
+                object: "chat.completion.chunk".to_string(),
+                created,
+                model: model.to_string(),
+                choices: vec![ChatChunkChoice { index: 0, delta, finish_reason }],
+                usage,
+            };
+
+            yield chunk(&model, ChatDelta { role: Some(ChatRole::Assistant), content: Some(String::new()) }, None, None);
+
+            // Without partial messages from the CLI, whole assistant messages are the deltas
+            let mut streamed = false;
+            while let Some(message) = messages.next().await {
+                match message? {
+                    Message::StreamEvent(event) if event.parent_tool_use_id.is_none() => {
+                        if let Some(text) = event.text_delta() {
+                            streamed = true;
+                            yield chunk(&model, ChatDelta { role: None, content: Some(text.to_string()) }, None, None);
+                        }
+                    },
+                    Message::Assistant(assistant) if assistant.parent_tool_use_id.is_none() => {
+                        if let Some(actual) = assistant.message.model {
+                            model = actual;
+                        }
+                        let text = text_of(&assistant.message.content);
+                        if !streamed && !text.is_empty() {
+                            yield chunk(&model, ChatDelta { role: None, content: Some(text) }, None, None);
+                        }
+                    },
+                    Message::Result(result) => {
+                        let reason = finish_reason(&result)?;
+                        yield chunk(&model, ChatDelta::default(), Some(reason.to_string()), Some(ChatUsage::from_result(&result)));
+                        break;
+                    },
+                    _ => {},
+                }
+            }
+        }))
+    }
+
+    /// Turn a request into a prompt and the options to run it with
+    fn translate(&self, request: &ChatRequest) -> Result<(String, ClaudeAgentOptions)> {
+        let Some((last, earlier)) = request.messages.split_last() else {
+            return Err(ClaudeError::InvalidInput(
+                "Chat request has no messages".to_string(),
+            ));
+        };
+        if last.role != ChatRole::User {
+            return Err(ClaudeError::InvalidInput(
+                "The last message of a chat request must be a user message".to_string(),
+            ));
+        }
+
+        self.warn_unsupported(request);
+
+        let mut options = self.options.clone();
+        if !request.model.is_empty() {
+            options.model = Some(request.model.clone());
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            options
+                .env
+                .insert(MAX_OUTPUT_TOKENS_ENV.to_string(), max_tokens.to_string());
+        }
+
+        let system: Vec<&str> = earlier
+            .iter()
+            .filter(|m| m.role == ChatRole::System)
+            .map(|m| m.content.as_str())
+            .collect();
+        if !system.is_empty() {
+            let mut text = system.join("\n\n");
+            if let Some(SystemPrompt::Text(existing)) = &options.system_prompt {
+                text = format!("{existing}\n\n{text}");
+            }
+            options.system_prompt = Some(SystemPrompt::Text(text));
+        }
+
+        Ok((prime_history(earlier, &last.content), options))
+    }
+
+    fn warn_unsupported(&self, request: &ChatRequest) {
+        let ignored = [
+            ("temperature", request.temperature.is_some()),
+            ("top_p", request.top_p.is_some()),
+            ("n", request.n.is_some_and(|n| n != 1)),
+            ("stop", request.stop.as_ref().is_some_and(|s| !s.is_empty())),
+        ];
+
+        let mut warned = self.warned.lock().unwrap();
+        for (param, set) in ignored {
+            if set && warned.insert(param) {
+                warn!(
+                    param,
+                    "Ignoring chat parameter the Claude CLI does not support"
+                );
+            }
+        }
+    }
+}
+
+impl Default for ChatClient {
+    fn default() -> Self {
+        Self::new(ClaudeAgentOptions::default())
+    }
+}
+
+/// Prefix `prompt` with the earlier user and assistant turns
+fn prime_history(earlier: &[ChatMessage], prompt: &str) -> String {
+    let turns: Vec<String> = earlier
+        .iter()
+        .filter_map(|message| match message.role {
+            ChatRole::User => Some(format!("User: {}", message.content)),
+            ChatRole::Assistant => Some(format!("Assistant: {}", message.content)),
+            ChatRole::System => None,
+        })
+        .collect();
+    if turns.is_empty() {
+        return prompt.to_string();
+    }
+
+    format!(
+        "<conversation_history>\n{}\n</conversation_history>\n\nContinue the conversation above. \
+         Reply to this message:\n\n{}",
+        turns.join("\n\n"),
+        prompt
+    )
+}
+
+fn text_of(content: &[ContentBlock]) -> String {
+    content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text(text) => Some(text.text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn finish_reason(result: &ResultMessage) -> Result<&'static str> {
+    match result.subtype.as_str() {
+        "error_max_turns" => Ok("length"),
+        _ if result.is_error => Err(ClaudeError::Transport(format!(
+            "Chat completion failed: {}",
+            result.result.as_deref().unwrap_or(&result.subtype)
+        ))),
+        _ => Ok("stop"),
+    }
+}
+
+fn completion_id() -> String {
+    format!("chatcmpl-{}", uuid::Uuid::new_v4().simple())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_primes_history() {
+        let client = ChatClient::default();
+        let mut request = ChatRequest::new(
+            "claude-haiku",
+            vec![
+                ChatMessage::system("Be brief."),
+                ChatMessage::user("Hi"),
+                ChatMessage::assistant("Hello!"),
+                ChatMessage::user("What did I say?"),
+            ],
+        );
+        request.max_tokens = Some(64);
+
+        let (prompt, options) = client.translate(&request).unwrap();
+        assert_eq!(
+            prompt,
+            "<conversation_history>\nUser: Hi\n\nAssistant: Hello!\n</conversation_history>\n\n\
+             Continue the conversation above. Reply to this message:\n\nWhat did I say?"
+        );
+        assert_eq!(options.model.as_deref(), Some("claude-haiku"));
+        assert_eq!(options.env[MAX_OUTPUT_TOKENS_ENV], "64");
+        assert!(
+            matches!(options.system_prompt, Some(SystemPrompt::Text(ref t)) if t == "Be brief.")
+        );
+    }
+
+    #[test]
+    fn test_translate_requires_final_user_message() {
+        let client = ChatClient::default();
+        assert!(client.translate(&ChatRequest::new("", vec![])).is_err());
+
+        let request = ChatRequest::new("", vec![ChatMessage::assistant("Hi")]);
+        assert!(client.translate(&request).is_err());
+
+        let (prompt, options) = client
+            .translate(&ChatRequest::new("", vec![ChatMessage::user("Hi")]))
+            .unwrap();
+        assert_eq!(prompt, "Hi");
+        assert!(options.model.is_none());
+    }
+
+    #[test]
+    fn test_unsupported_parameters_warn_once() {
+        let client = ChatClient::default();
+        let mut request = ChatRequest::new("", vec![ChatMessage::user("Hi")]);
+        request.temperature = Some(0.2);
+        request.n = Some(1);
+
+        client.translate(&request).unwrap();
+        client.translate(&request).unwrap();
+        assert_eq!(
+            *client.warned.lock().unwrap(),
+            HashSet::from(["temperature"])
+        );
+    }
+}
@@ -1,66 +1,656 @@
 //! Error types for the Claude Agent SDK
+//!
+//! Every [`ClaudeError`] has a stable [`ErrorCode`], available through
+//! [`ClaudeError::code`] and shown in brackets at the start of its message,
+//! such as `[cli_not_found] CLI not found: ...`. Match on the code rather than
+//! on messages, and ask [`ClaudeError::is_retryable`] whether another attempt
+//! may succeed.
+//!
+//! # Migrating from earlier versions
+//!
+//! - `ClaudeError` is `#[non_exhaustive]`: a `match` on it needs a wildcard
+//!   arm. Matching on [`ClaudeError::code`] is usually simpler.
+//! - Messages start with their code. Code comparing whole messages should
+//!   compare codes instead. Errors carried in [`ClaudeError::Other`] keep
+//!   their message, and a [`ClaudeError::ConnectFailed`] message shows the
+//!   code of the error it wraps.
+//! - With the `miette` feature, the diagnostic code of process errors is now
+//!   `claude::process_exited`, matching [`ErrorCode::ProcessExited`].
+//! - [`RetryPolicy::is_transient`](crate::RetryPolicy::is_transient) follows
+//!   [`ClaudeError::is_retryable`]. Connection and message timeouts are now
+//!   retried; a CLI that was not found or exited before the handshake is not.
 
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::types::config::ConnectPhase;
+use crate::types::messages::CollectedResponse;
+
 /// Main error type for the Claude Agent SDK
+///
+/// Errors from application code (hooks, tools, command handlers) can be carried
+/// in [`ClaudeError::Other`] without losing their type; see [`ClaudeError::other`]
+/// and [`ClaudeError::downcast_ref`]. With the `miette` feature every variant is a
+/// [`miette::Diagnostic`] with an error code, and JSON decode errors point at the
+/// failing position in the input.
 #[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
+#[non_exhaustive]
 pub enum ClaudeError {
     /// CLI connection error
-    #[error("CLI connection error: {0}")]
+    #[error("[connection] CLI connection error: {0}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::connection),
+            help("the CLI exited or closed its output; check its stderr for details")
+        )
+    )]
     Connection(#[from] ConnectionError),
 
+    /// A phase of connecting to the CLI failed
+    #[error("Connection failed during {phase}: {source}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::connect_failed)))]
+    ConnectFailed {
+        /// The phase that failed
+        phase: ConnectPhase,
+        /// The underlying error
+        source: Box<ClaudeError>,
+    },
+
+    /// A phase of connecting to the CLI exceeded its time limit
+    #[error("[connect_timeout] Connection timed out during {phase} after {timeout:?}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::connect_timeout),
+            help("raise the limit for this phase with `ClaudeAgentOptions::connect_timeouts`")
+        )
+    )]
+    ConnectTimeout {
+        /// The phase that timed out
+        phase: ConnectPhase,
+        /// The configured time limit
+        timeout: Duration,
+    },
+
     /// Process error
-    #[error("Process error: {0}")]
+    #[error("[process_exited] Process error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::process_exited)))]
     Process(#[from] ProcessError),
 
     /// JSON decode error
-    #[error("JSON decode error: {0}")]
+    #[error("[json_decode] JSON decode error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
     JsonDecode(#[from] JsonDecodeError),
 
     /// Message parse error
-    #[error("Message parse error: {0}")]
+    #[error("[message_parse] Message parse error: {0}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::message_parse),
+            help("the CLI may be newer than this SDK; check for an SDK update")
+        )
+    )]
     MessageParse(#[from] MessageParseError),
 
+    /// Structured output missing or not matching the requested type
+    #[error("[structured_output] Structured output error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::structured_output)))]
+    StructuredOutput(#[from] StructuredOutputError),
+
+    /// Prompt library error
+    #[error("[prompt] Prompt error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::prompt)))]
+    Prompt(#[from] crate::prompts::PromptError),
+
     /// Transport error
-    #[error("Transport error: {0}")]
+    #[error("[transport] Transport error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::transport)))]
     Transport(String),
 
     /// Control protocol error
-    #[error("Control protocol error: {0}")]
+    #[error("[control_protocol] Control protocol error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::control_protocol)))]
     ControlProtocol(String),
 
     /// Invalid configuration
-    #[error("Invalid configuration: {0}")]
+    #[error("[invalid_config] Invalid configuration: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::invalid_config)))]
     InvalidConfig(String),
 
     /// CLI not found error
-    #[error("CLI not found: {0}")]
+    #[error("[cli_not_found] {0}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::cli_not_found),
+            help(
+                "is the claude CLI installed? Install it with `npm install -g @anthropic-ai/claude-code`, or point `ClaudeAgentOptions::cli_path` at it"
+            )
+        )
+    )]
     CliNotFound(#[from] CliNotFoundError),
 
     /// Image validation error
-    #[error("Image validation error: {0}")]
+    #[error("[image_validation] Image validation error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::image_validation)))]
     ImageValidation(#[from] ImageValidationError),
 
+    /// Document validation error
+    #[error("[document_validation] Document validation error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::document_validation)))]
+    DocumentValidation(#[from] DocumentValidationError),
+
     /// IO error
-    #[error("IO error: {0}")]
+    #[error("[{code}] IO error: {0}", code = ErrorCode::for_io(.0))]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::io)))]
     Io(#[from] std::io::Error),
 
-    /// Other errors
+    /// JSON serialization or deserialization error without the input at hand
+    #[error("[json] JSON error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::json)))]
+    Json(#[from] serde_json::Error),
+
+    /// An operation did not complete in time
+    #[error("[timeout] Operation timed out")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::timeout)))]
+    Timeout(#[from] tokio::time::error::Elapsed),
+
+    /// Other errors, typically from application code such as hooks or tools
     #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::other)))]
     Other(#[from] anyhow::Error),
 
+    /// The CLI has no session with this ID to resume
+    #[error("[session_not_found] Session not found: {0}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::session_not_found),
+            help("list the sessions that can be resumed with `v2::list_sessions()`")
+        )
+    )]
+    SessionNotFound(String),
+
     /// Not found error
-    #[error("Not found: {0}")]
+    #[error("[not_found] Not found: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::not_found)))]
     NotFound(String),
 
     /// Invalid input error
-    #[error("Invalid input: {0}")]
+    #[error("[invalid_input] Invalid input: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::invalid_input)))]
     InvalidInput(String),
 
     /// Internal error
-    #[error("Internal error: {0}")]
+    #[error("[internal] Internal error: {0}")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::internal)))]
     InternalError(String),
+
+    /// A query was sent before the previous turn's result was received
+    #[error(
+        "[turn_in_progress] A turn is already in progress; receive its result before sending another query"
+    )]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::turn_in_progress),
+            help(
+                "consume the result with `receive_response()` first, or set `ClaudeAgentOptions::turn_policy`"
+            )
+        )
+    )]
+    TurnInProgress,
+
+    /// Too many queries are waiting for the running turn to finish
+    #[error("[turn_queue_full] Turn queue is full ({capacity} queries waiting)")]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::turn_queue_full)))]
+    TurnQueueFull {
+        /// The configured queue capacity
+        capacity: usize,
+    },
+
+    /// The turn did not finish before its deadline
+    #[error(
+        "[deadline_exceeded] Deadline exceeded after {elapsed:?} ({} messages received)",
+        partial.messages.len()
+    )]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::deadline_exceeded)))]
+    DeadlineExceeded {
+        /// Time since the query was submitted
+        elapsed: Duration,
+        /// Messages received before the turn was aborted
+        partial: CollectedResponse,
+    },
+
+    /// Spending reached [`ClaudeAgentOptions::max_budget_usd`](crate::ClaudeAgentOptions::max_budget_usd)
+    /// with client-side enforcement enabled
+    #[error("[budget_exceeded] Budget exceeded: spent ${spent:.4} of ${budget:.4}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::budget_exceeded),
+            help("raise `ClaudeAgentOptions::max_budget_usd`, or call `ClaudeClient::reset_usage()` to start counting again")
+        )
+    )]
+    BudgetExceeded {
+        /// Cost so far in USD, including an estimate for the interrupted turn
+        spent: f64,
+        /// The configured budget in USD
+        budget: f64,
+    },
+
+    /// The turn was cancelled through its cancellation token
+    #[error("[cancelled] Turn cancelled ({} messages received)", partial.messages.len())]
+    #[cfg_attr(feature = "miette", diagnostic(code(claude::cancelled)))]
+    Cancelled {
+        /// Messages received before the turn was interrupted, including its result if it arrived
+        partial: CollectedResponse,
+    },
+
+    /// The CLI sent nothing for longer than the configured message timeout
+    #[error("[message_timeout] No message from the CLI within {timeout:?}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::message_timeout),
+            help("the CLI may be waiting on a permission prompt; raise `ClaudeAgentOptions::message_timeout` if it is just slow")
+        )
+    )]
+    MessageTimeout {
+        /// The configured gap between messages
+        timeout: Duration,
+    },
+
+    /// The CLI's output ended before the turn's result message
+    ///
+    /// Yielded as the last item of a response stream, so a turn always ends with
+    /// either a result message or this error.
+    #[error(
+        "[incomplete_turn] Turn ended without a result ({messages_received} messages received, CLI {})",
+        process_exit.map_or_else(|| "exit status unknown".to_string(), |exit| exit.to_string())
+    )]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::incomplete_turn),
+            help("the CLI exited mid-turn; check its stderr via `ClaudeAgentOptions::stderr_callback`")
+        )
+    )]
+    IncompleteTurn {
+        /// Messages received in the turn before the output ended
+        messages_received: usize,
+        /// Type of the last message received in the turn, such as `"assistant"`
+        last_message_type: Option<String>,
+        /// How the CLI process exited, if it did
+        process_exit: Option<TransportExitInfo>,
+    },
+
+    /// An operation failed on every attempt allowed by its retry policy
+    #[error("[retries_exhausted] Failed after {attempts} attempts: {last_error}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::retries_exhausted),
+            help("raise `RetryPolicy::max_attempts` or fix the underlying failure")
+        )
+    )]
+    RetriesExhausted {
+        /// Attempts made, including the first
+        attempts: u32,
+        /// Error of the last attempt
+        #[source]
+        last_error: Box<ClaudeError>,
+    },
+
+    /// A blocking call was made from inside a Tokio runtime
+    #[error("[inside_async_runtime] Blocking API called from inside an async runtime")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::inside_async_runtime),
+            help("use the async API (`claude_agent_sdk::query`, `ClaudeClient`) from async code, or call this from a plain thread")
+        )
+    )]
+    InsideAsyncRuntime,
+
+    /// MCP servers failed the check run by
+    /// [`ClaudeAgentOptions::verify_mcp_servers`](crate::ClaudeAgentOptions::verify_mcp_servers)
+    #[error(
+        "[mcp_servers_unhealthy] MCP servers failed verification: {}",
+        unhealthy_servers(.0)
+    )]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(claude::mcp_servers_unhealthy),
+            help("check the command, URL and headers of each server listed")
+        )
+    )]
+    McpServersUnhealthy(Vec<crate::mcp::McpHealthReport>),
+}
+
+fn unhealthy_servers(reports: &[crate::mcp::McpHealthReport]) -> String {
+    reports
+        .iter()
+        .map(|report| format!("{} ({})", report.name, report.status))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl ClaudeError {
+    /// The stable code identifying what kind of failure this is
+    ///
+    /// A [`ClaudeError::ConnectFailed`] has the code of the error it wraps, so
+    /// a missing CLI is [`ErrorCode::CliNotFound`] whichever phase found it.
+    /// Errors of the SDK's modules carried in [`ClaudeError::Other`] have their
+    /// own codes; other application errors are [`ErrorCode::Other`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use claude_agent_sdk::{ClaudeError, ErrorCode};
+    ///
+    /// let err = ClaudeError::InvalidConfig("max_turns must be positive".into());
+    /// assert_eq!(err.code(), ErrorCode::InvalidConfig);
+    /// assert!(err.to_string().starts_with("[invalid_config]"));
+    /// assert!(!err.is_retryable());
+    /// ```
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Connection(_) => ErrorCode::Connection,
+            Self::ConnectFailed { source, .. } => source.code(),
+            Self::ConnectTimeout { .. } => ErrorCode::ConnectTimeout,
+            Self::Process(_) => ErrorCode::ProcessExited,
+            Self::JsonDecode(_) => ErrorCode::JsonDecode,
+            Self::MessageParse(_) => ErrorCode::MessageParse,
+            Self::StructuredOutput(_) => ErrorCode::StructuredOutput,
+            Self::Prompt(_) => ErrorCode::Prompt,
+            Self::Transport(_) => ErrorCode::Transport,
+            Self::ControlProtocol(_) => ErrorCode::ControlProtocol,
+            Self::InvalidConfig(_) => ErrorCode::InvalidConfig,
+            Self::CliNotFound(_) => ErrorCode::CliNotFound,
+            Self::ImageValidation(_) => ErrorCode::ImageValidation,
+            Self::DocumentValidation(_) => ErrorCode::DocumentValidation,
+            Self::Io(error) => ErrorCode::for_io(error),
+            Self::Json(_) => ErrorCode::Json,
+            Self::Timeout(_) => ErrorCode::Timeout,
+            Self::Other(error) => ErrorCode::for_other(error),
+            Self::SessionNotFound(_) => ErrorCode::SessionNotFound,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::InvalidInput(_) => ErrorCode::InvalidInput,
+            Self::InternalError(_) => ErrorCode::Internal,
+            Self::TurnInProgress => ErrorCode::TurnInProgress,
+            Self::TurnQueueFull { .. } => ErrorCode::TurnQueueFull,
+            Self::DeadlineExceeded { .. } => ErrorCode::DeadlineExceeded,
+            Self::BudgetExceeded { .. } => ErrorCode::BudgetExceeded,
+            Self::Cancelled { .. } => ErrorCode::Cancelled,
+            Self::MessageTimeout { .. } => ErrorCode::MessageTimeout,
+            Self::IncompleteTurn { .. } => ErrorCode::IncompleteTurn,
+            Self::RetriesExhausted { .. } => ErrorCode::RetriesExhausted,
+            Self::InsideAsyncRuntime => ErrorCode::InsideAsyncRuntime,
+            Self::McpServersUnhealthy(_) => ErrorCode::McpServersUnhealthy,
+        }
+    }
+
+    /// Whether the operation that failed may succeed if attempted again
+    ///
+    /// Follows [`ErrorCode::is_retryable`], except that a CLI exiting before
+    /// the handshake is not retried: it was most likely given a flag it does
+    /// not understand, and does the same on every attempt.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ConnectFailed { source, .. } => source.is_retryable(),
+            Self::Process(error) if error.exited_before_handshake() => false,
+            _ => self.code().is_retryable(),
+        }
+    }
+
+    /// Wrap an application error in [`ClaudeError::Other`], keeping its type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use claude_agent_sdk::ClaudeError;
+    ///
+    /// #[derive(Debug, thiserror::Error)]
+    /// #[error("quota exhausted")]
+    /// struct QuotaError;
+    ///
+    /// let err = ClaudeError::other(QuotaError);
+    /// assert!(err.downcast_ref::<QuotaError>().is_some());
+    /// ```
+    pub fn other<E>(error: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::Other(anyhow::Error::new(error))
+    }
+
+    /// The application error carried by [`ClaudeError::Other`], if it has type `E`
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: std::fmt::Display + std::fmt::Debug + Send + Sync + 'static,
+    {
+        match self {
+            Self::Other(error) => error.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Stable identifier of the kind of a [`ClaudeError`]
+///
+/// Codes are meant for programmatic handling and telemetry: unlike messages,
+/// they do not change between releases. [`ErrorCode::as_str`] gives the
+/// snake_case name shown at the start of error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// The CLI closed its output or could not be talked to
+    Connection,
+    /// A phase of connecting to the CLI took too long
+    ConnectTimeout,
+    /// The CLI process failed or exited
+    ProcessExited,
+    /// A line from the CLI was not valid JSON
+    JsonDecode,
+    /// A message from the CLI did not have the expected shape
+    MessageParse,
+    /// Structured output was missing or did not match the requested type
+    StructuredOutput,
+    /// A prompt template could not be loaded or rendered
+    Prompt,
+    /// The transport to the CLI failed
+    Transport,
+    /// The CLI broke the control protocol
+    ControlProtocol,
+    /// The options are invalid
+    InvalidConfig,
+    /// The CLI is not installed where the SDK looked
+    CliNotFound,
+    /// The CLI is older than [`MIN_CLI_VERSION`](crate::version::MIN_CLI_VERSION)
+    CliVersionTooOld,
+    /// The CLI could not be installed
+    Install,
+    /// An image failed validation
+    ImageValidation,
+    /// A document failed validation
+    DocumentValidation,
+    /// An IO operation failed
+    Io,
+    /// An IO operation was refused for lack of permission
+    PermissionDenied,
+    /// JSON could not be serialized or deserialized
+    Json,
+    /// An operation did not complete in time
+    Timeout,
+    /// The CLI has no session with the requested ID
+    SessionNotFound,
+    /// Something that was looked up does not exist
+    NotFound,
+    /// An argument was invalid
+    InvalidInput,
+    /// A bug in the SDK
+    Internal,
+    /// A query was sent while a turn was running
+    TurnInProgress,
+    /// Too many queries were waiting for the running turn
+    TurnQueueFull,
+    /// A turn did not finish before its deadline
+    DeadlineExceeded,
+    /// The spending limit was reached
+    BudgetExceeded,
+    /// A turn was cancelled
+    Cancelled,
+    /// The CLI sent nothing for too long
+    MessageTimeout,
+    /// The CLI's output ended mid-turn
+    IncompleteTurn,
+    /// Every attempt allowed by a retry policy failed
+    RetriesExhausted,
+    /// A blocking call was made from inside an async runtime
+    InsideAsyncRuntime,
+    /// MCP servers failed verification
+    McpServersUnhealthy,
+    /// A skill failed to load, validate or run
+    Skill,
+    /// A slash command failed
+    Command,
+    /// An MCP server or task failed
+    Mcp,
+    /// An application error, such as one returned by a hook or tool
+    Other,
+}
+
+impl ErrorCode {
+    /// The snake_case name of the code, such as `"cli_not_found"`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Connection => "connection",
+            Self::ConnectTimeout => "connect_timeout",
+            Self::ProcessExited => "process_exited",
+            Self::JsonDecode => "json_decode",
+            Self::MessageParse => "message_parse",
+            Self::StructuredOutput => "structured_output",
+            Self::Prompt => "prompt",
+            Self::Transport => "transport",
+            Self::ControlProtocol => "control_protocol",
+            Self::InvalidConfig => "invalid_config",
+            Self::CliNotFound => "cli_not_found",
+            Self::CliVersionTooOld => "cli_version_too_old",
+            Self::Install => "install",
+            Self::ImageValidation => "image_validation",
+            Self::DocumentValidation => "document_validation",
+            Self::Io => "io",
+            Self::PermissionDenied => "permission_denied",
+            Self::Json => "json",
+            Self::Timeout => "timeout",
+            Self::SessionNotFound => "session_not_found",
+            Self::NotFound => "not_found",
+            Self::InvalidInput => "invalid_input",
+            Self::Internal => "internal",
+            Self::TurnInProgress => "turn_in_progress",
+            Self::TurnQueueFull => "turn_queue_full",
+            Self::DeadlineExceeded => "deadline_exceeded",
+            Self::BudgetExceeded => "budget_exceeded",
+            Self::Cancelled => "cancelled",
+            Self::MessageTimeout => "message_timeout",
+            Self::IncompleteTurn => "incomplete_turn",
+            Self::RetriesExhausted => "retries_exhausted",
+            Self::InsideAsyncRuntime => "inside_async_runtime",
+            Self::McpServersUnhealthy => "mcp_servers_unhealthy",
+            Self::Skill => "skill",
+            Self::Command => "command",
+            Self::Mcp => "mcp",
+            Self::Other => "other",
+        }
+    }
+
+    /// Whether failures with this code may go away on another attempt
+    ///
+    /// True for connection failures, process exits, undecodable output and
+    /// timeouts, which happen when the CLI crashes, is updating itself or is
+    /// slow. Configuration, validation and budget errors fail the same way
+    /// every time.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::Connection
+                | Self::ConnectTimeout
+                | Self::ProcessExited
+                | Self::JsonDecode
+                | Self::Timeout
+                | Self::MessageTimeout
+        )
+    }
+
+    fn for_io(error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            std::io::ErrorKind::TimedOut => Self::Timeout,
+            _ => Self::Io,
+        }
+    }
+
+    fn for_other(error: &anyhow::Error) -> Self {
+        use crate::internal::cli_installer::InstallError;
+
+        if let Some(error) = error.downcast_ref::<InstallError>() {
+            match error {
+                InstallError::VersionTooOld { .. } => Self::CliVersionTooOld,
+                _ => Self::Install,
+            }
+        } else if error.is::<crate::skills::SkillError>() {
+            Self::Skill
+        } else if error.is::<crate::commands::CommandError>() {
+            Self::Command
+        } else if error.is::<crate::types::mcp::McpError>() || error.is::<crate::mcp::TaskError>()
+        {
+            Self::Mcp
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<crate::commands::CommandError> for ClaudeError {
+    fn from(error: crate::commands::CommandError) -> Self {
+        Self::other(error)
+    }
+}
+
+impl From<crate::skills::SkillError> for ClaudeError {
+    fn from(error: crate::skills::SkillError) -> Self {
+        Self::other(error)
+    }
+}
+
+impl From<crate::mcp::TaskError> for ClaudeError {
+    fn from(error: crate::mcp::TaskError) -> Self {
+        Self::other(error)
+    }
+}
+
+impl From<crate::internal::cli_installer::InstallError> for ClaudeError {
+    fn from(error: crate::internal::cli_installer::InstallError) -> Self {
+        Self::other(error)
+    }
+}
+
+impl From<crate::types::mcp::McpError> for ClaudeError {
+    fn from(error: crate::types::mcp::McpError) -> Self {
+        Self::other(error)
+    }
 }
 
 /// Error when Claude Code CLI cannot be found
@@ -110,6 +700,10 @@ pub struct ProcessError {
     pub exit_code: Option<i32>,
     /// stderr output
     pub stderr: Option<String>,
+    /// Command line the CLI was started with, secrets elided
+    pub command: Option<String>,
+    /// When the CLI exited, if it did
+    pub stage: Option<ExitStage>,
 }
 
 impl ProcessError {
@@ -119,28 +713,391 @@ impl ProcessError {
             message: message.into(),
             exit_code,
             stderr,
+            command: None,
+            stage: None,
+        }
+    }
+
+    /// Whether the CLI exited before answering anything, e.g. on a bad flag
+    ///
+    /// Such failures recur on retry unless the options or the installation change.
+    pub fn exited_before_handshake(&self) -> bool {
+        self.stage == Some(ExitStage::BeforeHandshake)
+    }
+}
+
+/// When the CLI process exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStage {
+    /// Before writing any output, so before the initialize handshake
+    BeforeHandshake,
+    /// After the conversation started
+    MidConversation,
+}
+
+impl std::fmt::Display for ExitStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExitStage::BeforeHandshake => write!(f, "before the handshake"),
+            ExitStage::MidConversation => write!(f, "mid-conversation"),
+        }
+    }
+}
+
+/// How the CLI process exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransportExitInfo {
+    /// Exit code, if the process exited normally
+    pub code: Option<i32>,
+    /// Signal that terminated the process (Unix only)
+    pub signal: Option<i32>,
+}
+
+impl From<std::process::ExitStatus> for TransportExitInfo {
+    fn from(status: std::process::ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+
+        Self {
+            code: status.code(),
+            signal,
+        }
+    }
+}
+
+impl std::fmt::Display for TransportExitInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.code, self.signal) {
+            (Some(code), _) => write!(f, "exited with code {}", code),
+            (None, Some(signal)) => write!(f, "killed by signal {}", signal),
+            (None, None) => write!(f, "exited"),
+        }
+    }
+}
+
+/// Default number of characters shown around a parse failure
+pub const DEFAULT_EXCERPT_WIDTH: usize = 160;
+
+/// Number of leading characters kept to identify the failing input
+const HEAD_CHARS: usize = 40;
+
+/// Marker inserted into excerpts at the failure position
+const EXCERPT_MARKER: &str = "<^>";
+
+/// Position of a parse failure together with a bounded view of the input
+///
+/// The excerpt is centered on the failure and never longer than the configured
+/// width (plus escaping and ellipses), so errors on very long inputs stay readable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceExcerpt {
+    /// 1-based line of the failure, if known
+    pub line: Option<usize>,
+    /// 1-based column of the failure, if known
+    pub column: Option<usize>,
+    /// Byte offset of the failure, if known
+    pub offset: Option<usize>,
+    /// Window of the input around the failure, with `<^>` marking the failure position
+    pub excerpt: String,
+    /// Length of the whole input in bytes
+    pub input_len: usize,
+    /// First characters of the input
+    pub head: String,
+}
+
+impl SourceExcerpt {
+    /// Build an excerpt of the start of `input` when the failure position is unknown
+    pub fn new(input: &str, width: usize) -> Self {
+        Self::build(input, None, None, None, width)
+    }
+
+    /// Build an excerpt from a `serde_json` error raised while parsing `input`
+    pub fn from_json_error(input: &str, err: &serde_json::Error, width: usize) -> Self {
+        let line = err.line();
+        let column = err.column();
+        if line == 0 {
+            // Errors not caused by the input itself carry no position
+            return Self::new(input, width);
+        }
+
+        let line_start = input
+            .split_inclusive('\n')
+            .take(line - 1)
+            .map(str::len)
+            .sum::<usize>();
+        // serde_json reports the column just past the offending byte
+        let offset = line_start + column.saturating_sub(1);
+
+        Self::build(input, Some(line), Some(column), Some(offset), width)
+    }
+
+    /// Build an excerpt from a `serde_yaml` error raised while parsing `input`
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_error(input: &str, err: &serde_yaml::Error, width: usize) -> Self {
+        match err.location() {
+            Some(location) => Self::build(
+                input,
+                Some(location.line()),
+                Some(location.column()),
+                Some(location.index()),
+                width,
+            ),
+            None => Self::new(input, width),
+        }
+    }
+
+    fn build(
+        input: &str,
+        line: Option<usize>,
+        column: Option<usize>,
+        offset: Option<usize>,
+        width: usize,
+    ) -> Self {
+        let offset = offset.map(|offset| floor_char_boundary(input, offset));
+        let split = offset.unwrap_or(0);
+        let (before, after) = input.split_at(split);
+
+        let before_chars = if offset.is_some() { width / 2 } else { 0 };
+        let skip = before.chars().count().saturating_sub(before_chars);
+        let before_start = before.char_indices().nth(skip).map_or(before.len(), |(i, _)| i);
+        let after_end = after
+            .char_indices()
+            .nth(width - before_chars)
+            .map_or(after.len(), |(i, _)| i);
+
+        let mut excerpt = String::new();
+        if before_start > 0 {
+            excerpt.push_str("...");
+        }
+        push_escaped(&mut excerpt, &before[before_start..]);
+        if offset.is_some() {
+            excerpt.push_str(EXCERPT_MARKER);
+        }
+        push_escaped(&mut excerpt, &after[..after_end]);
+        if after_end < after.len() {
+            excerpt.push_str("...");
+        }
+
+        let mut head = String::new();
+        push_escaped(&mut head, &input[..floor_char_boundary_chars(input, HEAD_CHARS)]);
+
+        Self {
+            line,
+            column,
+            offset,
+            excerpt,
+            input_len: input.len(),
+            head,
+        }
+    }
+
+    fn is_truncated_at_start(&self) -> bool {
+        self.excerpt.starts_with("...")
+    }
+}
+
+impl std::fmt::Display for SourceExcerpt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column, self.offset) {
+            (Some(line), Some(column), Some(offset)) => write!(
+                f,
+                "at line {} column {} (byte {} of {})",
+                line, column, offset, self.input_len
+            )?,
+            _ => write!(f, "({} bytes)", self.input_len)?,
+        }
+        if self.is_truncated_at_start() {
+            write!(f, " in input starting `{}`", self.head)?;
+        }
+        write!(f, ": {}", self.excerpt)
+    }
+}
+
+/// Largest char boundary in `s` not after `index`
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Byte length of the first `chars` characters of `s`
+fn floor_char_boundary_chars(s: &str, chars: usize) -> usize {
+    s.char_indices().nth(chars).map_or(s.len(), |(i, _)| i)
+}
+
+/// Append `s` with line breaks and tabs escaped so excerpts stay on one line
+fn push_escaped(out: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
         }
     }
 }
 
+/// Remove the " at line L column C" suffix serde appends to its messages
+fn strip_location(message: String, excerpt: &SourceExcerpt) -> String {
+    match (excerpt.line, excerpt.column) {
+        (Some(line), Some(column)) => {
+            let suffix = format!(" at line {} column {}", line, column);
+            match message.strip_suffix(&suffix) {
+                Some(stripped) => stripped.to_string(),
+                None => message,
+            }
+        },
+        _ => message,
+    }
+}
+
 /// Error when JSON decoding fails
+///
+/// The message only contains a bounded excerpt of the input; the full line
+/// is available through [`JsonDecodeError::raw`].
 #[derive(Debug, Error)]
-#[error("JSON decode error: {message}")]
+#[error("JSON decode error: {message} {location}")]
 pub struct JsonDecodeError {
     /// Error message
     pub message: String,
-    /// The line that failed to decode
-    pub line: String,
+    /// Where decoding failed
+    pub location: Box<SourceExcerpt>,
+    raw: String,
 }
 
 impl JsonDecodeError {
-    /// Create a new JSON decode error
+    /// Create a new JSON decode error without position information
     pub fn new(message: impl Into<String>, line: impl Into<String>) -> Self {
+        let raw = line.into();
+        Self {
+            message: message.into(),
+            location: Box::new(SourceExcerpt::new(&raw, DEFAULT_EXCERPT_WIDTH)),
+            raw,
+        }
+    }
+
+    /// Create an error from a `serde_json` failure while parsing `line`
+    pub fn from_serde(line: impl Into<String>, err: &serde_json::Error) -> Self {
+        Self::from_serde_with_width(line, err, DEFAULT_EXCERPT_WIDTH)
+    }
+
+    /// Like [`JsonDecodeError::from_serde`], with a custom excerpt width in characters
+    pub fn from_serde_with_width(
+        line: impl Into<String>,
+        err: &serde_json::Error,
+        width: usize,
+    ) -> Self {
+        let raw = line.into();
+        let location = Box::new(SourceExcerpt::from_json_error(&raw, err, width));
+        Self {
+            message: strip_location(err.to_string(), &location),
+            location,
+            raw,
+        }
+    }
+
+    /// The full line that failed to decode
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+}
+
+/// Error when YAML parsing fails
+///
+/// Like [`JsonDecodeError`], only a bounded excerpt is displayed and the full
+/// document is available through [`YamlParseError::raw`].
+#[derive(Debug, Error)]
+#[error("{message} {location}")]
+pub struct YamlParseError {
+    /// Error message
+    pub message: String,
+    /// Where parsing failed
+    pub location: Box<SourceExcerpt>,
+    raw: String,
+}
+
+impl YamlParseError {
+    /// Create a new YAML parse error without position information
+    pub fn new(message: impl Into<String>, input: impl Into<String>) -> Self {
+        let raw = input.into();
         Self {
             message: message.into(),
-            line: line.into(),
+            location: Box::new(SourceExcerpt::new(&raw, DEFAULT_EXCERPT_WIDTH)),
+            raw,
+        }
+    }
+
+    /// Create an error from a `serde_yaml` failure while parsing `input`
+    #[cfg(feature = "yaml")]
+    pub fn from_serde(input: impl Into<String>, err: &serde_yaml::Error) -> Self {
+        Self::from_serde_with_width(input, err, DEFAULT_EXCERPT_WIDTH)
+    }
+
+    /// Like [`YamlParseError::from_serde`], with a custom excerpt width in characters
+    #[cfg(feature = "yaml")]
+    pub fn from_serde_with_width(
+        input: impl Into<String>,
+        err: &serde_yaml::Error,
+        width: usize,
+    ) -> Self {
+        let raw = input.into();
+        let location = Box::new(SourceExcerpt::from_yaml_error(&raw, err, width));
+        Self {
+            message: strip_location(err.to_string(), &location),
+            location,
+            raw,
         }
     }
+
+    /// The full document that failed to parse
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for JsonDecodeError {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(Box::new("claude::json_decode"))
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.raw)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        excerpt_label(&self.location, &self.raw)
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for YamlParseError {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(Box::new("claude::yaml_parse"))
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.raw)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        excerpt_label(&self.location, &self.raw)
+    }
+}
+
+/// A label on the character at the failure position, if it is known
+#[cfg(feature = "miette")]
+fn excerpt_label(
+    location: &SourceExcerpt,
+    raw: &str,
+) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + 'static>> {
+    let offset = location.offset?;
+    let len = raw[offset..].chars().next().map_or(0, char::len_utf8);
+    let label = miette::LabeledSpan::new(Some("here".to_string()), offset, len);
+    Some(Box::new(std::iter::once(label)))
 }
 
 /// Error when message parsing fails
@@ -163,16 +1120,106 @@ impl MessageParseError {
     }
 }
 
-/// Image validation error
+/// Error when structured output is missing or cannot be deserialized
 #[derive(Debug, Error)]
-#[error("Image validation error: {message}")]
-pub struct ImageValidationError {
+#[error("{message}")]
+pub struct StructuredOutputError {
     /// Error message
     pub message: String,
+    /// The structured output that failed to deserialize, if there was any
+    pub raw: Option<serde_json::Value>,
+    /// The text Claude answered with, if known
+    pub text: Option<String>,
+}
+
+impl StructuredOutputError {
+    /// Create an error for a result without structured output
+    pub fn missing() -> Self {
+        Self {
+            message: "result contains no structured output".to_string(),
+            raw: None,
+            text: None,
+        }
+    }
+
+    /// Create an error from a failure to deserialize `raw` into `T`
+    pub fn from_serde<T>(raw: serde_json::Value, err: &serde_json::Error) -> Self {
+        let mut excerpt = raw.to_string();
+        let end = floor_char_boundary_chars(&excerpt, DEFAULT_EXCERPT_WIDTH);
+        if end < excerpt.len() {
+            excerpt.truncate(end);
+            excerpt.push_str("...");
+        }
+        Self {
+            message: format!(
+                "cannot deserialize structured output as {}: {} (value: {})",
+                std::any::type_name::<T>(),
+                err,
+                excerpt
+            ),
+            raw: Some(raw),
+            text: None,
+        }
+    }
+
+    /// Attach the text Claude answered with, to help debug the prompt
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+}
+
+/// Image validation error
+#[derive(Debug, Error)]
+pub enum ImageValidationError {
+    /// The image or its source is invalid
+    #[error("Image validation error: {message}")]
+    Invalid {
+        /// Error message
+        message: String,
+    },
+
+    /// The image is over the size limit
+    ///
+    /// Sizes are in the units of the input: bytes of the file for
+    /// [`UserContentBlock::image_from_path`](crate::UserContentBlock::image_from_path),
+    /// base64 characters for
+    /// [`UserContentBlock::image_base64`](crate::UserContentBlock::image_base64).
+    #[error("Image data exceeds maximum size of {limit} bytes (got {actual} bytes)")]
+    TooLarge {
+        /// Size of the image
+        actual: usize,
+        /// Largest size accepted
+        limit: usize,
+    },
+
+    /// The image is not PNG, JPEG, GIF or WebP
+    #[error("Unsupported image format {detected}; supported formats are PNG, JPEG, GIF and WebP")]
+    UnsupportedFormat {
+        /// The format found, such as `"BMP"`, or `"unknown"`
+        detected: String,
+    },
 }
 
 impl ImageValidationError {
     /// Create a new image validation error
+    pub fn new(message: impl Into<String>) -> Self {
+        Self::Invalid {
+            message: message.into(),
+        }
+    }
+}
+
+/// Document validation error
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct DocumentValidationError {
+    /// Error message
+    pub message: String,
+}
+
+impl DocumentValidationError {
+    /// Create a new document validation error
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
@@ -182,3 +1229,331 @@ impl ImageValidationError {
 
 /// Result type for the Claude Agent SDK
 pub type Result<T> = std::result::Result<T, ClaudeError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_malformed_line() -> String {
+        let mut line = String::from("{\"type\":\"assistant\",\"payload\":\"");
+        line.push_str(&"a".repeat(100_000));
+        line.push_str("\",oops");
+        line.push_str(&"b".repeat(100_000));
+        line
+    }
+
+    #[test]
+    fn test_json_decode_error_excerpt_window() {
+        let line = long_malformed_line();
+        let err = serde_json::from_str::<serde_json::Value>(&line).unwrap_err();
+        let decode = JsonDecodeError::from_serde(line.clone(), &err);
+
+        assert_eq!(decode.location.line, Some(1));
+        let offset = decode.location.offset.unwrap();
+        assert_eq!(&line[offset..offset + 1], "o");
+        assert_eq!(decode.location.input_len, line.len());
+        assert_eq!(decode.location.head, line[..40]);
+
+        let excerpt = &decode.location.excerpt;
+        assert!(excerpt.starts_with("...") && excerpt.ends_with("..."));
+        assert!(excerpt.contains("\",<^>oops"));
+        // 160 characters of input, the marker and two ellipses
+        assert_eq!(excerpt.chars().count(), 160 + 3 + 6);
+
+        assert_eq!(decode.raw(), line);
+    }
+
+    #[test]
+    fn test_json_decode_error_display_is_bounded() {
+        let line = long_malformed_line();
+        let err = serde_json::from_str::<serde_json::Value>(&line).unwrap_err();
+        let display = JsonDecodeError::from_serde(line, &err).to_string();
+
+        assert!(display.len() < 400, "display too long: {}", display.len());
+        assert!(display.contains("at line 1 column"));
+        assert!(display.contains("in input starting `{\"type\":\"assistant\""));
+        assert_eq!(display.matches(" at line ").count(), 1);
+    }
+
+    #[test]
+    fn test_json_decode_error_custom_width() {
+        let line = long_malformed_line();
+        let err = serde_json::from_str::<serde_json::Value>(&line).unwrap_err();
+        let decode = JsonDecodeError::from_serde_with_width(line, &err, 20);
+        assert_eq!(decode.location.excerpt, "...aaaaaaaa\",<^>oopsbbbbbb...");
+    }
+
+    #[test]
+    fn test_short_input_has_no_ellipsis() {
+        let line = "{\"a\": }";
+        let err = serde_json::from_str::<serde_json::Value>(line).unwrap_err();
+        let decode = JsonDecodeError::from_serde(line, &err);
+        assert_eq!(decode.location.excerpt, "{\"a\": <^>}");
+        assert!(!decode.to_string().contains("input starting"));
+    }
+
+    #[test]
+    fn test_excerpt_respects_char_boundaries() {
+        let excerpt = SourceExcerpt::build("ééééé", Some(1), Some(2), Some(3), 2);
+        assert_eq!(excerpt.offset, Some(2));
+        assert_eq!(excerpt.excerpt, "é<^>é...");
+    }
+
+    #[tokio::test]
+    async fn test_from_conversions() {
+        fn io() -> Result<()> {
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "gone"))?
+        }
+        fn json() -> Result<serde_json::Value> {
+            Ok(serde_json::from_str("{")?)
+        }
+        async fn timeout() -> Result<()> {
+            tokio::time::timeout(Duration::ZERO, std::future::pending::<()>()).await?;
+            Ok(())
+        }
+
+        assert!(matches!(io(), Err(ClaudeError::Io(_))));
+        assert!(matches!(json(), Err(ClaudeError::Json(_))));
+        assert!(matches!(timeout().await, Err(ClaudeError::Timeout(_))));
+
+        let command = ClaudeError::from(crate::commands::CommandError::NotFound("x".into()));
+        assert!(matches!(
+            command.downcast_ref::<crate::commands::CommandError>(),
+            Some(crate::commands::CommandError::NotFound(_))
+        ));
+        let skill = ClaudeError::from(crate::skills::SkillError::Validation("bad".into()));
+        assert_eq!(skill.to_string(), "Skill validation failed: bad");
+        assert!(skill.downcast_ref::<crate::skills::SkillError>().is_some());
+        assert!(ClaudeError::NotFound("x".into()).downcast_ref::<std::io::Error>().is_none());
+    }
+
+    #[test]
+    fn test_claude_error_is_send_sync_static() {
+        fn assert_bounds<E: std::error::Error + Send + Sync + 'static>() {}
+        assert_bounds::<ClaudeError>();
+    }
+
+    #[test]
+    fn test_error_codes() {
+        use crate::internal::cli_installer::InstallError;
+        use std::io::ErrorKind;
+
+        let process = || ClaudeError::from(ProcessError::new("crashed", Some(1), None));
+        let cases = [
+            (ClaudeError::from(ConnectionError::new("closed")), ErrorCode::Connection),
+            (process(), ErrorCode::ProcessExited),
+            (
+                ClaudeError::from(CliNotFoundError::new("not on PATH", None)),
+                ErrorCode::CliNotFound,
+            ),
+            (
+                ClaudeError::ConnectFailed {
+                    phase: ConnectPhase::Spawn,
+                    source: Box::new(ClaudeError::from(CliNotFoundError::new("gone", None))),
+                },
+                ErrorCode::CliNotFound,
+            ),
+            (
+                ClaudeError::ConnectTimeout {
+                    phase: ConnectPhase::Initialize,
+                    timeout: Duration::from_secs(1),
+                },
+                ErrorCode::ConnectTimeout,
+            ),
+            (
+                ClaudeError::from(JsonDecodeError::new("bad", "{oops")),
+                ErrorCode::JsonDecode,
+            ),
+            (
+                ClaudeError::from(MessageParseError::new("no type", None)),
+                ErrorCode::MessageParse,
+            ),
+            (ClaudeError::InvalidConfig("x".into()), ErrorCode::InvalidConfig),
+            (ClaudeError::Transport("x".into()), ErrorCode::Transport),
+            (ClaudeError::ControlProtocol("x".into()), ErrorCode::ControlProtocol),
+            (ClaudeError::SessionNotFound("x".into()), ErrorCode::SessionNotFound),
+            (ClaudeError::NotFound("x".into()), ErrorCode::NotFound),
+            (ClaudeError::InvalidInput("x".into()), ErrorCode::InvalidInput),
+            (ClaudeError::InternalError("x".into()), ErrorCode::Internal),
+            (
+                ClaudeError::from(std::io::Error::new(ErrorKind::PermissionDenied, "no")),
+                ErrorCode::PermissionDenied,
+            ),
+            (
+                ClaudeError::from(std::io::Error::new(ErrorKind::TimedOut, "slow")),
+                ErrorCode::Timeout,
+            ),
+            (
+                ClaudeError::from(std::io::Error::other("disk full")),
+                ErrorCode::Io,
+            ),
+            (
+                ClaudeError::from(serde_json::from_str::<u8>("x").unwrap_err()),
+                ErrorCode::Json,
+            ),
+            (ClaudeError::TurnInProgress, ErrorCode::TurnInProgress),
+            (ClaudeError::TurnQueueFull { capacity: 1 }, ErrorCode::TurnQueueFull),
+            (
+                ClaudeError::DeadlineExceeded {
+                    elapsed: Duration::from_secs(1),
+                    partial: CollectedResponse::default(),
+                },
+                ErrorCode::DeadlineExceeded,
+            ),
+            (
+                ClaudeError::BudgetExceeded {
+                    spent: 2.0,
+                    budget: 1.0,
+                },
+                ErrorCode::BudgetExceeded,
+            ),
+            (
+                ClaudeError::Cancelled {
+                    partial: CollectedResponse::default(),
+                },
+                ErrorCode::Cancelled,
+            ),
+            (
+                ClaudeError::MessageTimeout {
+                    timeout: Duration::from_secs(1),
+                },
+                ErrorCode::MessageTimeout,
+            ),
+            (
+                ClaudeError::IncompleteTurn {
+                    messages_received: 0,
+                    last_message_type: None,
+                    process_exit: None,
+                },
+                ErrorCode::IncompleteTurn,
+            ),
+            (
+                ClaudeError::RetriesExhausted {
+                    attempts: 3,
+                    last_error: Box::new(process()),
+                },
+                ErrorCode::RetriesExhausted,
+            ),
+            (ClaudeError::InsideAsyncRuntime, ErrorCode::InsideAsyncRuntime),
+            (ClaudeError::McpServersUnhealthy(Vec::new()), ErrorCode::McpServersUnhealthy),
+            (
+                ClaudeError::from(InstallError::VersionTooOld {
+                    requested: "1.0.0".into(),
+                    minimum: "2.0.0".into(),
+                }),
+                ErrorCode::CliVersionTooOld,
+            ),
+            (
+                ClaudeError::from(InstallError::UnsupportedPlatform("plan9".into())),
+                ErrorCode::Install,
+            ),
+            (
+                ClaudeError::from(crate::skills::SkillError::Validation("bad".into())),
+                ErrorCode::Skill,
+            ),
+            (
+                ClaudeError::from(crate::commands::CommandError::NotFound("x".into())),
+                ErrorCode::Command,
+            ),
+            (ClaudeError::from(anyhow::anyhow!("hook failed")), ErrorCode::Other),
+        ];
+
+        for (error, code) in cases {
+            assert_eq!(error.code(), code, "{error}");
+            let message = error.to_string();
+            match &error {
+                ClaudeError::Other(_) | ClaudeError::ConnectFailed { .. } => {},
+                _ => assert!(message.starts_with(&format!("[{code}] ")), "{message}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        assert!(ClaudeError::from(ConnectionError::new("closed")).is_retryable());
+        assert!(ClaudeError::from(JsonDecodeError::new("bad", "{oops")).is_retryable());
+        assert!(
+            ClaudeError::MessageTimeout {
+                timeout: Duration::from_secs(1)
+            }
+            .is_retryable()
+        );
+        assert!(!ClaudeError::InvalidConfig("x".into()).is_retryable());
+        assert!(!ClaudeError::BudgetExceeded { spent: 2.0, budget: 1.0 }.is_retryable());
+
+        let mut early_exit = ProcessError::new("unknown option", Some(1), None);
+        assert!(ClaudeError::from(ProcessError::new("crashed", None, None)).is_retryable());
+        early_exit.stage = Some(ExitStage::BeforeHandshake);
+        assert!(!ClaudeError::from(early_exit).is_retryable());
+
+        let missing_cli = ClaudeError::ConnectFailed {
+            phase: ConnectPhase::Spawn,
+            source: Box::new(ClaudeError::from(CliNotFoundError::new("gone", None))),
+        };
+        assert!(!missing_cli.is_retryable());
+    }
+
+    #[test]
+    fn test_source_chain_is_preserved() {
+        use std::error::Error as _;
+
+        let io = ClaudeError::from(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "no"));
+        let source = io.source().unwrap().downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(source.kind(), std::io::ErrorKind::PermissionDenied);
+
+        let json = ClaudeError::from(serde_json::from_str::<u8>("x").unwrap_err());
+        assert!(json.source().unwrap().is::<serde_json::Error>());
+
+        let exhausted = ClaudeError::RetriesExhausted {
+            attempts: 2,
+            last_error: Box::new(io),
+        };
+        let last = exhausted.source().unwrap();
+        assert!(last.to_string().starts_with("[permission_denied] "));
+        assert!(last.source().unwrap().is::<std::io::Error>());
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_cli_not_found_diagnostic_has_help() {
+        use miette::Diagnostic;
+
+        let err = ClaudeError::from(CliNotFoundError::new("claude not on PATH", None));
+        assert_eq!(Diagnostic::code(&err).unwrap().to_string(), "claude::cli_not_found");
+        assert_eq!(err.code(), ErrorCode::CliNotFound);
+        assert!(err.help().unwrap().to_string().contains("is the claude CLI installed?"));
+
+        let report = format!("{:?}", miette::Report::new(err));
+        assert!(report.contains("claude::cli_not_found"));
+        assert!(report.contains("is the claude CLI installed?"));
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_json_decode_diagnostic_points_at_failure() {
+        use miette::Diagnostic;
+
+        let line = "{\"type\": oops}";
+        let err = serde_json::from_str::<serde_json::Value>(line).unwrap_err();
+        let err = ClaudeError::from(JsonDecodeError::from_serde(line, &err));
+
+        assert_eq!(Diagnostic::code(&err).unwrap().to_string(), "claude::json_decode");
+        let label = err.labels().unwrap().next().unwrap();
+        assert_eq!(&line[label.offset()..label.offset() + label.len()], "o");
+        assert!(err.source_code().is_some());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_parse_error_location() {
+        let input = "name: demo\ndescription: [unclosed\nversion: 1\n";
+        let err = serde_yaml::from_str::<serde_yaml::Value>(input).unwrap_err();
+        let parse = YamlParseError::from_serde(input, &err);
+
+        assert!(parse.location.line.is_some());
+        assert!(parse.location.excerpt.contains("<^>"));
+        assert!(parse.location.excerpt.contains("\\n"));
+        assert!(!parse.to_string().contains('\n'));
+        assert_eq!(parse.raw(), input);
+    }
+}
@@ -0,0 +1,119 @@
+//! Recording of tool invocations into the configured `AuditLog`
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::observability::{AuditEvent, AuditEventKind, AuditLog};
+use crate::types::config::{ClaudeAgentOptions, PermissionMode};
+use crate::types::messages::{
+    ContentBlock, Message, ToolResultBlock, ToolResultContent, UserMessage,
+};
+
+/// Records tool uses and results when `ClaudeAgentOptions::audit_sink` is set,
+/// and does nothing otherwise
+pub(crate) struct ToolAudit {
+    recorder: Option<Recorder>,
+}
+
+struct Recorder {
+    log: Arc<AuditLog>,
+    permission_mode: Option<PermissionMode>,
+    allowed_tools: Vec<String>,
+    /// Names of the tools used, by tool use ID, for their results
+    tool_names: HashMap<String, String>,
+    session_id: Option<String>,
+}
+
+impl ToolAudit {
+    pub(crate) fn new(options: &ClaudeAgentOptions) -> Self {
+        Self {
+            recorder: options.audit_sink.as_ref().map(|log| Recorder {
+                log: Arc::clone(log),
+                permission_mode: options.permission_mode,
+                allowed_tools: options.allowed_tools.clone(),
+                tool_names: HashMap::new(),
+                session_id: None,
+            }),
+        }
+    }
+
+    pub(crate) fn observe(&mut self, message: &Message) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        match message {
+            Message::Assistant(assistant) => {
+                if assistant.session_id.is_some() {
+                    recorder.session_id.clone_from(&assistant.session_id);
+                }
+                for block in &assistant.message.content {
+                    if let ContentBlock::ToolUse(tool_use) = block {
+                        recorder.tool_use(&tool_use.id, &tool_use.name, &tool_use.input);
+                    }
+                }
+            },
+            Message::User(user) => {
+                if let Some(session_id) = user.extra.get("session_id").and_then(Value::as_str) {
+                    recorder.session_id = Some(session_id.to_string());
+                }
+                for tool_result in tool_results(user) {
+                    recorder.tool_result(&tool_result);
+                }
+            },
+            Message::Result(_) => recorder.tool_names.clear(),
+            _ => {},
+        }
+    }
+}
+
+impl Recorder {
+    fn tool_use(&mut self, id: &str, name: &str, input: &Value) {
+        self.tool_names.insert(id.to_string(), name.to_string());
+        self.log.record(AuditEvent {
+            session_id: self.session_id.clone(),
+            tool_name: Some(name.to_string()),
+            input: Some(input.clone()),
+            permission_mode: self.permission_mode,
+            pre_approved: self.allowed_tools.iter().any(|tool| tool == name),
+            ..AuditEvent::new(AuditEventKind::ToolUse, id)
+        });
+    }
+
+    fn tool_result(&mut self, tool_result: &ToolResultBlock) {
+        let output = tool_result.content.as_ref().map(|content| match content {
+            ToolResultContent::Text(text) => Value::String(text.clone()),
+            ToolResultContent::Blocks(blocks) => Value::Array(blocks.clone()),
+        });
+        self.log.record(AuditEvent {
+            session_id: self.session_id.clone(),
+            tool_name: self.tool_names.remove(&tool_result.tool_use_id),
+            output,
+            is_error: tool_result.is_error,
+            permission_mode: self.permission_mode,
+            ..AuditEvent::new(AuditEventKind::ToolResult, &tool_result.tool_use_id)
+        });
+    }
+}
+
+/// Tool results in a user message, wherever the CLI put its content
+fn tool_results(user: &UserMessage) -> Vec<ToolResultBlock> {
+    let blocks = match &user.content {
+        Some(content) => content.clone(),
+        None => match user.extra.get("message").and_then(|m| m.get("content")) {
+            Some(Value::Array(blocks)) => blocks
+                .iter()
+                .filter_map(|block| serde_json::from_value(block.clone()).ok())
+                .collect(),
+            _ => Vec::new(),
+        },
+    };
+    blocks
+        .into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult(tool_result) => Some(tool_result),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,127 @@
+//! Client-side enforcement of the cost budget
+
+use std::sync::Mutex;
+
+use crate::errors::{ClaudeError, Result};
+use crate::types::config::{BudgetWarningCallback, ClaudeAgentOptions};
+use crate::types::usage::BudgetWarning;
+
+#[derive(Default)]
+struct BudgetState {
+    warned: bool,
+    exceeded: bool,
+}
+
+/// Watches spending against [`ClaudeAgentOptions::max_budget_usd`]
+///
+/// Inactive unless [`ClaudeAgentOptions::enforce_budget_client_side`] is set.
+pub(crate) struct BudgetGuard {
+    budget: Option<f64>,
+    soft_limit_ratio: f64,
+    on_warning: Option<BudgetWarningCallback>,
+    state: Mutex<BudgetState>,
+}
+
+impl BudgetGuard {
+    pub(crate) fn new(options: &ClaudeAgentOptions) -> Self {
+        Self {
+            budget: options
+                .max_budget_usd
+                .filter(|_| options.enforce_budget_client_side),
+            soft_limit_ratio: options.budget_soft_limit_ratio,
+            on_warning: options.on_budget_warning.clone(),
+            state: Mutex::new(BudgetState::default()),
+        }
+    }
+
+    /// Check spending after a message was received
+    ///
+    /// Calls the warning callback the first time `spent` crosses the soft limit,
+    /// and returns the error ending the stream the first time it reaches the
+    /// budget. A jump straight past the budget does both.
+    pub(crate) fn observe(&self, spent: f64) -> Option<ClaudeError> {
+        let budget = self.budget?;
+        let (warn, exceeded) = {
+            let mut state = self.state.lock().unwrap();
+            let warn = spent >= budget * self.soft_limit_ratio && !state.warned;
+            let exceeded = spent >= budget && !state.exceeded;
+            state.warned |= warn;
+            state.exceeded |= exceeded;
+            (warn, exceeded)
+        };
+
+        if warn && let Some(on_warning) = &self.on_warning {
+            on_warning(BudgetWarning {
+                spent_usd: spent,
+                budget_usd: budget,
+            });
+        }
+        exceeded.then_some(ClaudeError::BudgetExceeded { spent, budget })
+    }
+
+    /// Refuse to start a turn once the budget is used up
+    pub(crate) fn admit(&self, spent: f64) -> Result<()> {
+        match self.budget {
+            Some(budget) if spent >= budget => Err(ClaudeError::BudgetExceeded { spent, budget }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Forget earlier warnings, e.g. after the usage was reset
+    pub(crate) fn reset(&self) {
+        *self.state.lock().unwrap() = BudgetState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn guard(enforce: bool, warnings: &Arc<AtomicUsize>) -> BudgetGuard {
+        let warnings = Arc::clone(warnings);
+        let options = ClaudeAgentOptions::builder()
+            .max_budget_usd(1.0)
+            .enforce_budget_client_side(enforce)
+            .on_budget_warning(Arc::new(move |warning: BudgetWarning| {
+                assert_eq!(warning.budget_usd, 1.0);
+                warnings.fetch_add(1, Ordering::SeqCst);
+            }))
+            .build();
+        BudgetGuard::new(&options)
+    }
+
+    #[test]
+    fn test_warns_once_then_stops_once() {
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let guard = guard(true, &warnings);
+
+        assert!(guard.observe(0.5).is_none());
+        assert!(guard.observe(0.85).is_none());
+        assert!(guard.observe(0.9).is_none());
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+
+        let Some(ClaudeError::BudgetExceeded { spent, budget }) = guard.observe(1.2) else {
+            panic!("expected the budget to be exceeded");
+        };
+        assert_eq!((spent, budget), (1.2, 1.0));
+        assert!(guard.observe(1.3).is_none());
+        assert!(guard.admit(1.3).is_err());
+
+        guard.reset();
+        assert!(guard.admit(0.0).is_ok());
+        assert!(guard.observe(0.9).is_none());
+        assert_eq!(warnings.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_inactive_without_enforcement() {
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let guard = guard(false, &warnings);
+
+        assert!(guard.observe(5.0).is_none());
+        assert!(guard.admit(5.0).is_ok());
+        assert_eq!(warnings.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,339 @@
+//! Client-side record of file checkpoints
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::types::checkpoints::{CheckpointDiff, CheckpointInfo, FileChange};
+use crate::types::messages::{ContentBlock, Message, UserMessage};
+
+/// Longest prompt preview, in characters
+const PREVIEW_CHARS: usize = 80;
+
+/// Tools whose successful use changes a file, with the input field naming it
+const EDIT_TOOLS: &[(&str, &str)] = &[
+    ("Write", "file_path"),
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
+struct Checkpoint {
+    info: CheckpointInfo,
+    /// Index into `edits` of the first edit after this checkpoint
+    first_edit: usize,
+    /// Replayed from before the client connected
+    historic: bool,
+}
+
+struct Edit {
+    path: String,
+    tool: String,
+}
+
+/// Builds the checkpoint list from replayed user messages and file-editing tool uses
+pub(crate) struct CheckpointTracker {
+    connected_at: DateTime<Utc>,
+    checkpoints: Vec<Checkpoint>,
+    edits: Vec<Edit>,
+    /// Edit tool uses waiting for their result, by tool use ID
+    pending: HashMap<String, Edit>,
+}
+
+impl CheckpointTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            connected_at: Utc::now(),
+            checkpoints: Vec::new(),
+            edits: Vec::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Mark the start of the connection; earlier prompts are historic
+    pub(crate) fn set_connected_at(&mut self, time: DateTime<Utc>) {
+        self.connected_at = time;
+    }
+
+    pub(crate) fn observe(&mut self, message: &Message) {
+        match message {
+            Message::User(user) => self.observe_user(user),
+            Message::Assistant(assistant) => {
+                for block in &assistant.message.content {
+                    if let ContentBlock::ToolUse(tool_use) = block {
+                        if let Some(path) = edited_path(&tool_use.name, &tool_use.input) {
+                            self.pending.insert(
+                                tool_use.id.clone(),
+                                Edit {
+                                    path,
+                                    tool: tool_use.name.clone(),
+                                },
+                            );
+                        }
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn observe_user(&mut self, user: &UserMessage) {
+        let blocks = user_blocks(user);
+        let mut is_prompt = true;
+        for block in &blocks {
+            if let Some(id) = block.get("tool_use_id").and_then(Value::as_str) {
+                is_prompt = false;
+                let failed = block.get("is_error").and_then(Value::as_bool) == Some(true);
+                if let Some(edit) = self.pending.remove(id)
+                    && !failed
+                {
+                    self.edits.push(edit);
+                }
+            }
+        }
+
+        let Some(uuid) = &user.uuid else { return };
+        if !is_prompt
+            || user.parent_tool_use_id.is_some()
+            || self
+                .checkpoints
+                .iter()
+                .any(|c| &c.info.user_message_id == uuid)
+        {
+            return;
+        }
+
+        let created_at = user
+            .extra
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map_or_else(Utc::now, |t| t.with_timezone(&Utc));
+        let text = user.text.clone().unwrap_or_else(|| block_text(&blocks));
+
+        self.checkpoints.push(Checkpoint {
+            info: CheckpointInfo {
+                user_message_id: uuid.clone(),
+                created_at,
+                prompt_preview: preview(&text),
+                files_changed_since: None,
+            },
+            first_edit: self.edits.len(),
+            historic: created_at < self.connected_at,
+        });
+    }
+
+    /// All checkpoints, oldest first
+    pub(crate) fn checkpoints(&self) -> Vec<CheckpointInfo> {
+        self.checkpoints.iter().map(|c| self.info(c)).collect()
+    }
+
+    /// Files that rewinding to `user_message_id` would restore
+    pub(crate) fn diff(&self, user_message_id: &str) -> Option<CheckpointDiff> {
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .find(|c| c.info.user_message_id == user_message_id)?;
+
+        Some(CheckpointDiff {
+            checkpoint: self.info(checkpoint),
+            files: self.changes_since(checkpoint),
+            complete: !checkpoint.historic,
+        })
+    }
+
+    /// The latest checkpoint created at or before `time`
+    pub(crate) fn latest_before(&self, time: DateTime<Utc>) -> Option<CheckpointInfo> {
+        self.checkpoints
+            .iter()
+            .filter(|c| c.info.created_at <= time)
+            .max_by_key(|c| c.info.created_at)
+            .map(|c| self.info(c))
+    }
+
+    fn info(&self, checkpoint: &Checkpoint) -> CheckpointInfo {
+        CheckpointInfo {
+            files_changed_since: (!checkpoint.historic)
+                .then(|| self.changes_since(checkpoint).len()),
+            ..checkpoint.info.clone()
+        }
+    }
+
+    fn changes_since(&self, checkpoint: &Checkpoint) -> Vec<FileChange> {
+        let mut files: Vec<FileChange> = Vec::new();
+        for edit in &self.edits[checkpoint.first_edit..] {
+            match files.iter_mut().find(|f| f.path == edit.path) {
+                Some(file) => file.tools.push(edit.tool.clone()),
+                None => files.push(FileChange {
+                    path: edit.path.clone(),
+                    tools: vec![edit.tool.clone()],
+                }),
+            }
+        }
+        files
+    }
+}
+
+fn edited_path(tool: &str, input: &Value) -> Option<String> {
+    let (_, field) = EDIT_TOOLS.iter().find(|(name, _)| *name == tool)?;
+    input.get(*field)?.as_str().map(String::from)
+}
+
+/// Content blocks of a user message as JSON, wherever the CLI put them
+fn user_blocks(user: &UserMessage) -> Vec<Value> {
+    if let Some(content) = &user.content {
+        return content
+            .iter()
+            .filter_map(|block| serde_json::to_value(block).ok())
+            .collect();
+    }
+
+    match user.extra.get("message").and_then(|m| m.get("content")) {
+        Some(Value::String(text)) => vec![serde_json::json!({"type": "text", "text": text})],
+        Some(Value::Array(blocks)) => blocks.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn block_text(blocks: &[Value]) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| block.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// First line of `text`, cut to [`PREVIEW_CHARS`]
+fn preview(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() <= PREVIEW_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(PREVIEW_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(value: Value) -> Message {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn prompt(uuid: &str, text: &str, timestamp: &str) -> Message {
+        message(json!({
+            "type": "user",
+            "uuid": uuid,
+            "timestamp": timestamp,
+            "message": {"role": "user", "content": text}
+        }))
+    }
+
+    fn edit(id: &str, tool: &str, path: &str) -> Message {
+        message(json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "tool_use", "id": id, "name": tool, "input": {"file_path": path}}
+            ]}
+        }))
+    }
+
+    fn tool_result(id: &str, is_error: bool) -> Message {
+        message(json!({
+            "type": "user",
+            "uuid": format!("result-{id}"),
+            "message": {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": id, "content": "ok", "is_error": is_error}
+            ]}
+        }))
+    }
+
+    fn time(t: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(t).unwrap().with_timezone(&Utc)
+    }
+
+    fn scripted() -> CheckpointTracker {
+        let mut tracker = CheckpointTracker::new();
+        tracker.set_connected_at(time("2026-01-01T10:00:00Z"));
+
+        let script = [
+            // Replayed from the resumed session
+            prompt("p0", "Set up the project", "2026-01-01T09:00:00Z"),
+            prompt("p1", "Add a README\nwith details", "2026-01-01T10:01:00Z"),
+            edit("t1", "Write", "README.md"),
+            tool_result("t1", false),
+            prompt("p2", &"x".repeat(200), "2026-01-01T10:05:00Z"),
+            edit("t2", "Edit", "src/lib.rs"),
+            tool_result("t2", false),
+            edit("t3", "Edit", "README.md"),
+            tool_result("t3", false),
+            edit("t4", "Write", "denied.txt"),
+            tool_result("t4", true),
+            edit("t5", "Read", "src/main.rs"),
+            tool_result("t5", false),
+            // Replay of an already known prompt
+            prompt("p2", "duplicate", "2026-01-01T10:05:00Z"),
+        ];
+        for message in &script {
+            tracker.observe(message);
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_checkpoint_list() {
+        let checkpoints = scripted().checkpoints();
+        let ids: Vec<&str> = checkpoints
+            .iter()
+            .map(|c| c.user_message_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["p0", "p1", "p2"]);
+
+        assert_eq!(checkpoints[0].files_changed_since, None);
+        assert_eq!(checkpoints[1].files_changed_since, Some(2));
+        assert_eq!(checkpoints[1].prompt_preview, "Add a README");
+        assert_eq!(checkpoints[1].created_at, time("2026-01-01T10:01:00Z"));
+        assert_eq!(checkpoints[2].files_changed_since, Some(2));
+        assert_eq!(checkpoints[2].prompt_preview.chars().count(), PREVIEW_CHARS);
+    }
+
+    #[test]
+    fn test_diff_lists_files_since_checkpoint() {
+        let tracker = scripted();
+
+        let diff = tracker.diff("p2").unwrap();
+        assert!(diff.complete);
+        assert_eq!(
+            diff.files,
+            vec![
+                FileChange {
+                    path: "src/lib.rs".to_string(),
+                    tools: vec!["Edit".to_string()],
+                },
+                FileChange {
+                    path: "README.md".to_string(),
+                    tools: vec!["Edit".to_string()],
+                },
+            ]
+        );
+
+        let diff = tracker.diff("p1").unwrap();
+        assert_eq!(diff.files[0].tools, vec!["Write", "Edit"]);
+
+        assert!(!tracker.diff("p0").unwrap().complete);
+        assert!(tracker.diff("missing").is_none());
+    }
+
+    #[test]
+    fn test_latest_before_selects_rewind_target() {
+        let tracker = scripted();
+        let target = |t: &str| tracker.latest_before(time(t)).map(|c| c.user_message_id);
+
+        assert_eq!(target("2026-01-01T08:00:00Z"), None);
+        assert_eq!(target("2026-01-01T09:30:00Z").as_deref(), Some("p0"));
+        assert_eq!(target("2026-01-01T10:01:00Z").as_deref(), Some("p1"));
+        assert_eq!(target("2026-01-01T12:00:00Z").as_deref(), Some("p2"));
+    }
+}
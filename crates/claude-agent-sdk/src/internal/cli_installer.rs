@@ -11,13 +11,18 @@
 //! - ✅ 进度回调支持
 //! - ✅ 完善的错误处理
 
-use std::path::PathBuf;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
+use thiserror::Error;
 use tokio::process::Command;
 use tracing::{info, warn, debug};
 
 use crate::errors::{ClaudeError, Result};
+use crate::version::MIN_CLI_VERSION;
 
 /// Maximum number of retry attempts for command availability check
 const MAX_AVAILABILITY_RETRIES: u32 = 5;
@@ -25,6 +30,98 @@ const MAX_AVAILABILITY_RETRIES: u32 = 5;
 /// Base delay between retry attempts in milliseconds
 const AVAILABILITY_RETRY_BASE_MS: u64 = 100;
 
+/// Where the CLI releases are published
+///
+/// `<url>/latest` names the newest version, `<url>/<version>/manifest.json`
+/// lists the SHA-256 checksum of each platform's binary, and the binaries are
+/// at `<url>/<version>/<platform>/claude`.
+pub const DEFAULT_RELEASES_URL: &str = "https://storage.googleapis.com/claude-code-dist-86c565f3-f756-42ad-8dfa-d59b1c096819/claude-code-releases";
+
+/// Environment variable replacing [`DEFAULT_RELEASES_URL`], e.g. with an
+/// internal mirror
+pub const RELEASES_URL_ENV: &str = "CLAUDE_CLI_RELEASES_URL";
+
+/// Time allowed to connect to the releases server
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why the CLI could not be installed
+///
+/// Returned inside [`ClaudeError::Other`]; get it back with
+/// [`ClaudeError::downcast_ref`].
+#[derive(Debug, Error)]
+pub enum InstallError {
+    /// The requested version is not a version number
+    #[error("not a Claude Code CLI version: {0:?}")]
+    InvalidVersion(String),
+
+    /// The requested version is older than [`MIN_CLI_VERSION`]
+    #[error(
+        "Claude Code CLI {requested} is older than {minimum}, the oldest version this SDK can talk to"
+    )]
+    VersionTooOld {
+        /// The version asked for
+        requested: String,
+        /// [`MIN_CLI_VERSION`]
+        minimum: String,
+    },
+
+    /// No binary of the version is published for this platform
+    #[error("Claude Code CLI {version} is not published for {platform}")]
+    NotPublished {
+        /// The version asked for
+        version: String,
+        /// Platform key such as `linux-x64`
+        platform: String,
+    },
+
+    /// The downloaded binary does not match the checksum of the manifest
+    #[error(
+        "checksum mismatch for Claude Code CLI {version}: the manifest lists SHA-256 {expected}, the download has {actual}"
+    )]
+    ChecksumMismatch {
+        /// The version downloaded
+        version: String,
+        /// Checksum listed in the manifest
+        expected: String,
+        /// Checksum of the downloaded binary
+        actual: String,
+    },
+
+    /// A request to the releases server failed
+    #[error("failed to download {url}: {message}")]
+    Download {
+        /// The URL requested
+        url: String,
+        /// What went wrong
+        message: String,
+    },
+
+    /// There is no release binary for this OS or architecture
+    #[error("unsupported platform: {0}")]
+    UnsupportedPlatform(String),
+
+    /// The cache directory could not be written
+    #[error("failed to write {}: {source}", path.display())]
+    Cache {
+        /// The file or directory being written
+        path: PathBuf,
+        /// The underlying error
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// The part of a release's `manifest.json` the installer reads
+#[derive(Debug, Deserialize)]
+struct ReleaseManifest {
+    platforms: HashMap<String, PlatformRelease>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlatformRelease {
+    checksum: String,
+}
+
 /// 安装进度事件
 ///
 /// 用于实时报告安装进度
@@ -41,6 +138,8 @@ pub enum InstallProgress {
     },
     /// 安装中
     Installing(String),
+    /// Checking a downloaded or cached binary against its SHA-256 checksum
+    Verifying(String),
     /// 安装完成
     Done(PathBuf),
     /// 安装失败
@@ -55,6 +154,12 @@ pub struct CliInstaller {
     pub auto_install: bool,
     /// 进度回调
     progress_callback: Option<Arc<dyn Fn(InstallProgress) + Send + Sync>>,
+    /// Exact version to install
+    version: Option<String>,
+    /// Where downloaded binaries are kept
+    cache_dir: Option<PathBuf>,
+    /// Where releases are downloaded from
+    releases_url: Option<String>,
 }
 
 impl CliInstaller {
@@ -63,9 +168,60 @@ impl CliInstaller {
         Self {
             auto_install,
             progress_callback: None,
+            version: None,
+            cache_dir: None,
+            releases_url: None,
         }
     }
 
+    /// Install exactly `version`, such as `"2.0.14"`, or fail
+    ///
+    /// A pinned version is only downloaded, never installed through npm, and
+    /// runs from the cache rather than replacing the CLI in the install
+    /// directory. Versions older than [`MIN_CLI_VERSION`] are refused.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Keep downloaded binaries in `dir` instead of [`Self::default_cache_dir`]
+    ///
+    /// A binary found in the cache is reused, after checking it against the
+    /// checksum recorded when it was downloaded, without going online.
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Download releases from `url` instead of [`DEFAULT_RELEASES_URL`]
+    ///
+    /// Takes precedence over the [`RELEASES_URL_ENV`] environment variable.
+    pub fn with_releases_url(mut self, url: impl Into<String>) -> Self {
+        self.releases_url = Some(url.into());
+        self
+    }
+
+    /// The default cache directory, `claude-agent-sdk/cli` in the user's cache
+    /// directory
+    ///
+    /// That is `$XDG_CACHE_HOME` or `~/.cache` on Linux, `~/Library/Caches` on
+    /// macOS and `%LOCALAPPDATA%` on Windows.
+    pub fn default_cache_dir() -> Option<PathBuf> {
+        let var = |key: &str| {
+            std::env::var_os(key)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        let base = if cfg!(windows) {
+            var("LOCALAPPDATA")
+        } else if cfg!(target_os = "macos") {
+            var("HOME").map(|home| home.join("Library").join("Caches"))
+        } else {
+            var("XDG_CACHE_HOME").or_else(|| var("HOME").map(|home| home.join(".cache")))
+        };
+        base.map(|base| base.join("claude-agent-sdk").join("cli"))
+    }
+
     /// 设置进度回调
     pub fn with_progress_callback(
         mut self,
@@ -99,6 +255,15 @@ impl CliInstaller {
             ));
         }
 
+        if let Some(version) = &self.version {
+            let result = self.install_pinned(version).await;
+            match &result {
+                Ok(path) => self.report_progress(InstallProgress::Done(path.clone())),
+                Err(e) => self.report_progress(InstallProgress::Failed(e.to_string())),
+            }
+            return result.map_err(ClaudeError::from);
+        }
+
         self.report_progress(InstallProgress::Checking(
             "Checking if Claude CLI is already installed...".to_string(),
         ));
@@ -138,6 +303,10 @@ impl CliInstaller {
                     e
                 );
                 self.report_progress(InstallProgress::Failed(error_msg.clone()));
+                // Keep a checksum mismatch and the like recognizable
+                if e.downcast_ref::<InstallError>().is_some() {
+                    return Err(e);
+                }
                 Err(ClaudeError::InternalError(error_msg))
             }
         }
@@ -280,96 +449,299 @@ impl CliInstaller {
 
     /// 通过直接下载安装
     ///
-    /// 从 GitHub Releases 下载预编译的二进制文件
-    ///
-    /// 注意：此功能依赖于 GitHub Releases 的可用性
+    /// Downloads the latest release into the cache, or takes the newest cached
+    /// one when the releases cannot be reached, and copies it to the install
+    /// directory.
     async fn install_via_direct_download(&self) -> Result<PathBuf> {
         info!("Attempting installation via direct download...");
 
-        self.report_progress(InstallProgress::Downloading {
-            current: 0,
-            total: None,
-        });
+        let version = match self.latest_version().await {
+            Ok(version) => version,
+            Err(e) => match self.newest_cached_version() {
+                Some(version) => {
+                    warn!("{}, using cached Claude CLI {}", e, version);
+                    version
+                },
+                None => return Err(e.into()),
+            },
+        };
+        let artifact = self.fetch_release(&version).await?;
 
-        // 检测平台
-        let (platform, arch) = Self::detect_platform();
+        // 确定安装路径
+        let install_dir = Self::get_install_dir()?;
+        std::fs::create_dir_all(&install_dir).map_err(|e| {
+            ClaudeError::InternalError(format!("Failed to create install directory: {}", e))
+        })?;
+        let install_path = install_dir.join(Self::exe_name());
 
-        if platform == "unknown" || arch == "unknown" {
-            return Err(ClaudeError::InternalError(format!(
-                "Unsupported platform: {}-{}",
-                platform, arch
-            )));
+        std::fs::copy(&artifact, &install_path)
+            .and_then(|_| Self::make_executable(&install_path))
+            .map_err(|e| ClaudeError::InternalError(format!("Failed to write CLI: {}", e)))?;
+
+        info!("✅ Claude CLI {} installed to: {}", version, install_path.display());
+        Ok(install_path)
+    }
+
+    /// Install the pinned `version` from the cache or the releases
+    async fn install_pinned(&self, version: &str) -> std::result::Result<PathBuf, InstallError> {
+        let version = Self::check_requested_version(version)?;
+        self.report_progress(InstallProgress::Checking(format!(
+            "Looking for Claude CLI {}...",
+            version
+        )));
+        let artifact = self.fetch_release(&version).await?;
+        info!("✅ Claude CLI {} available at: {}", version, artifact.display());
+        Ok(artifact)
+    }
+
+    /// Normalize a requested version, refusing ones the SDK cannot use
+    fn check_requested_version(version: &str) -> std::result::Result<String, InstallError> {
+        let requested = semver::Version::parse(version.trim().trim_start_matches('v'))
+            .map_err(|_| InstallError::InvalidVersion(version.to_string()))?;
+        let minimum = semver::Version::parse(MIN_CLI_VERSION)
+            .expect("MIN_CLI_VERSION is a valid version");
+        if requested < minimum {
+            return Err(InstallError::VersionTooOld {
+                requested: requested.to_string(),
+                minimum: MIN_CLI_VERSION.to_string(),
+            });
         }
+        Ok(requested.to_string())
+    }
 
-        // 构建下载 URL
-        // 注意：这里使用通用的 URL 格式，实际可能需要调整
-        let filename = if cfg!(windows) {
-            format!("claude-{}-{}.exe", platform, arch)
-        } else {
-            format!("claude-{}-{}", platform, arch)
-        };
+    /// Path of `version`'s binary in the cache, downloading it if needed
+    async fn fetch_release(&self, version: &str) -> std::result::Result<PathBuf, InstallError> {
+        let platform = Self::platform_key()?;
+        let artifact = self
+            .cache_dir()
+            .join(version)
+            .join(&platform)
+            .join(Self::exe_name());
+        if self.is_cached(version, &artifact) {
+            return Ok(artifact);
+        }
+
+        let manifest_url = format!("{}/{}/manifest.json", self.releases_url(), version);
+        let manifest = self
+            .download(&manifest_url)
+            .await?
+            .ok_or_else(|| InstallError::NotPublished {
+                version: version.to_string(),
+                platform: platform.clone(),
+            })?;
+        let manifest: ReleaseManifest =
+            serde_json::from_slice(&manifest).map_err(|e| InstallError::Download {
+                url: manifest_url.clone(),
+                message: format!("invalid manifest: {}", e),
+            })?;
+        let expected = manifest
+            .platforms
+            .get(&platform)
+            .map(|release| release.checksum.to_ascii_lowercase())
+            .ok_or_else(|| InstallError::NotPublished {
+                version: version.to_string(),
+                platform: platform.clone(),
+            })?;
 
         let url = format!(
-            "https://github.com/anthropics/claude-code/releases/latest/download/{}",
-            filename
+            "{}/{}/{}/{}",
+            self.releases_url(),
+            version,
+            platform,
+            Self::exe_name()
         );
-
         info!("Downloading from: {}", url);
         self.report_progress(InstallProgress::Downloading {
             current: 0,
             total: None,
         });
+        let bytes = self
+            .download(&url)
+            .await?
+            .ok_or_else(|| InstallError::NotPublished {
+                version: version.to_string(),
+                platform: platform.clone(),
+            })?;
+        self.report_progress(InstallProgress::Downloading {
+            current: bytes.len() as u64,
+            total: Some(bytes.len() as u64),
+        });
 
-        // 使用 reqwest 下载
-        let response = reqwest::get(&url).await.map_err(|e| {
-            ClaudeError::InternalError(format!("Failed to download CLI: {}", e))
-        })?;
+        self.report_progress(InstallProgress::Verifying(format!(
+            "Verifying the SHA-256 checksum of Claude CLI {}...",
+            version
+        )));
+        let actual = sha256_hex(&bytes);
+        if actual != expected {
+            return Err(InstallError::ChecksumMismatch {
+                version: version.to_string(),
+                expected,
+                actual,
+            });
+        }
 
-        if !response.status().is_success() {
-            return Err(ClaudeError::InternalError(format!(
-                "Download failed with status: {}",
-                response.status()
-            )));
+        Self::store(&artifact, &bytes, &actual)?;
+        Ok(artifact)
+    }
+
+    /// Whether a binary matching its recorded checksum is at `artifact`
+    ///
+    /// A binary that does not match is removed so it is downloaded again.
+    fn is_cached(&self, version: &str, artifact: &Path) -> bool {
+        let Ok(recorded) = std::fs::read_to_string(Self::checksum_path(artifact)) else {
+            return false;
+        };
+        let Ok(bytes) = std::fs::read(artifact) else {
+            return false;
+        };
+
+        self.report_progress(InstallProgress::Verifying(format!(
+            "Verifying cached Claude CLI {}...",
+            version
+        )));
+        if sha256_hex(&bytes) == recorded.trim() {
+            debug!("Using cached Claude CLI at {}", artifact.display());
+            return true;
         }
+        warn!(
+            "Cached Claude CLI at {} does not match its checksum, downloading it again",
+            artifact.display()
+        );
+        let _ = std::fs::remove_file(artifact);
+        false
+    }
+
+    /// Write a verified binary and its checksum to the cache
+    fn store(artifact: &Path, bytes: &[u8], checksum: &str) -> std::result::Result<(), InstallError> {
+        let cache_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| InstallError::Cache { path, source }
+        };
+        let dir = artifact.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir).map_err(cache_error(dir))?;
+
+        // Written next to the final path and renamed, so an interrupted
+        // download never leaves a partial binary in the cache
+        let partial = artifact.with_extension("partial");
+        std::fs::write(&partial, bytes)
+            .and_then(|_| Self::make_executable(&partial))
+            .and_then(|_| std::fs::rename(&partial, artifact))
+            .map_err(cache_error(artifact))?;
+        let checksum_path = Self::checksum_path(artifact);
+        std::fs::write(&checksum_path, checksum).map_err(cache_error(&checksum_path))
+    }
 
-        let total_bytes = response.content_length();
-        let bytes = response.bytes().await.map_err(|e| {
-            ClaudeError::InternalError(format!("Failed to download bytes: {}", e))
+    /// The newest version in the cache with a binary for this platform
+    fn newest_cached_version(&self) -> Option<String> {
+        let platform = Self::platform_key().ok()?;
+        std::fs::read_dir(self.cache_dir())
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let version = semver::Version::parse(entry.file_name().to_str()?).ok()?;
+                let artifact = entry.path().join(&platform).join(Self::exe_name());
+                artifact.is_file().then_some(version)
+            })
+            .max()
+            .map(|version| version.to_string())
+    }
+
+    /// The version `<releases>/latest` names
+    async fn latest_version(&self) -> std::result::Result<String, InstallError> {
+        let url = format!("{}/latest", self.releases_url());
+        let body = self
+            .download(&url)
+            .await?
+            .ok_or_else(|| InstallError::Download {
+                url: url.clone(),
+                message: "not found".to_string(),
+            })?;
+        let version = String::from_utf8_lossy(&body).trim().to_string();
+        semver::Version::parse(&version).map_err(|_| InstallError::Download {
+            url,
+            message: format!("not a version: {:?}", version),
         })?;
+        Ok(version)
+    }
 
-        self.report_progress(InstallProgress::Downloading {
-            current: bytes.len() as u64,
-            total: total_bytes,
+    /// GET `url`, or `None` if it does not exist
+    async fn download(&self, url: &str) -> std::result::Result<Option<Vec<u8>>, InstallError> {
+        let download_error = |message: String| InstallError::Download {
+            url: url.to_string(),
+            message,
+        };
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| download_error(e.to_string()))?;
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| download_error(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(download_error(format!("status {}", response.status())));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| download_error(e.to_string()))?;
+        Ok(Some(bytes.into()))
+    }
+
+    fn releases_url(&self) -> String {
+        let url = self.releases_url.clone().unwrap_or_else(|| {
+            std::env::var(RELEASES_URL_ENV)
+                .ok()
+                .filter(|url| !url.is_empty())
+                .unwrap_or_else(|| DEFAULT_RELEASES_URL.to_string())
         });
+        url.trim_end_matches('/').to_string()
+    }
 
-        // 确定安装路径
-        let install_dir = Self::get_install_dir()?;
-        std::fs::create_dir_all(&install_dir).map_err(|e| {
-            ClaudeError::InternalError(format!("Failed to create install directory: {}", e))
-        })?;
+    fn cache_dir(&self) -> PathBuf {
+        self.cache_dir
+            .clone()
+            .or_else(Self::default_cache_dir)
+            .unwrap_or_else(|| std::env::temp_dir().join("claude-agent-sdk").join("cli"))
+    }
 
-        let exe_name = if cfg!(windows) { "claude.exe" } else { "claude" };
-        let install_path = install_dir.join(exe_name);
+    fn checksum_path(artifact: &Path) -> PathBuf {
+        artifact.with_extension("sha256")
+    }
 
-        // 写入文件
-        std::fs::write(&install_path, bytes)
-            .map_err(|e| ClaudeError::InternalError(format!("Failed to write CLI: {}", e)))?;
+    fn exe_name() -> &'static str {
+        if cfg!(windows) { "claude.exe" } else { "claude" }
+    }
+
+    /// Name of this platform in the release manifest, such as `darwin-arm64`
+    fn platform_key() -> std::result::Result<String, InstallError> {
+        let (platform, arch) = Self::detect_platform();
+        if platform == "unknown" || arch == "unknown" {
+            return Err(InstallError::UnsupportedPlatform(format!(
+                "{}-{}",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            )));
+        }
+        let libc = if cfg!(target_env = "musl") { "-musl" } else { "" };
+        Ok(format!("{}-{}{}", platform, arch, libc))
+    }
 
-        // 设置可执行权限（Unix）
+    /// 设置可执行权限（Unix）
+    fn make_executable(path: &Path) -> std::io::Result<()> {
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            let mut perms = std::fs::metadata(&install_path)
-                .map_err(|e| ClaudeError::InternalError(format!("Failed to get metadata: {}", e)))?
-                .permissions();
-            perms.set_mode(0o755);
-            std::fs::set_permissions(&install_path, perms).map_err(|e| {
-                ClaudeError::InternalError(format!("Failed to set permissions: {}", e))
-            })?;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
         }
-
-        info!("✅ Claude CLI installed to: {}", install_path.display());
-        Ok(install_path)
+        #[cfg(not(unix))]
+        let _ = path;
+        Ok(())
     }
 
     /// 检测平台和架构
@@ -381,7 +753,7 @@ impl CliInstaller {
         } else if cfg!(target_os = "linux") {
             "linux"
         } else if cfg!(target_os = "windows") {
-            "windows"
+            "win32"
         } else {
             "unknown"
         };
@@ -421,6 +793,14 @@ impl CliInstaller {
     }
 }
 
+/// Lowercase hex SHA-256 digest of `bytes`
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -458,4 +838,159 @@ mod tests {
             .unwrap();
         assert!(dir.starts_with(home));
     }
+
+    /// Serve `files` over HTTP on a local port, answering 404 for other paths
+    async fn serve(files: HashMap<String, Vec<u8>>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let (status, body) = match files.get(path) {
+                    Some(body) => ("200 OK", body.clone()),
+                    None => ("404 Not Found", Vec::new()),
+                };
+                let head = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(&body).await;
+            }
+        });
+        url
+    }
+
+    /// Files of a published release of `version` whose manifest lists `checksum`
+    fn release(version: &str, binary: &[u8], checksum: &str) -> HashMap<String, Vec<u8>> {
+        let platform = CliInstaller::platform_key().unwrap();
+        let manifest = serde_json::json!({
+            "version": version,
+            "platforms": {platform.clone(): {"checksum": checksum, "size": binary.len()}}
+        });
+        HashMap::from([
+            (format!("/{}/manifest.json", version), manifest.to_string().into_bytes()),
+            (
+                format!("/{}/{}/{}", version, platform, CliInstaller::exe_name()),
+                binary.to_vec(),
+            ),
+        ])
+    }
+
+    fn install_error(error: &ClaudeError) -> &InstallError {
+        error.downcast_ref::<InstallError>().expect("not an InstallError")
+    }
+
+    #[tokio::test]
+    async fn test_pinned_version_is_verified_and_cached() {
+        let cache = tempfile::tempdir().unwrap();
+        let binary = b"#!/bin/sh\necho 2.1.3\n";
+        let url = serve(release("2.1.3", binary, &sha256_hex(binary))).await;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let path = CliInstaller::new(true)
+            .with_version("2.1.3")
+            .with_cache_dir(cache.path())
+            .with_releases_url(&url)
+            .with_progress_callback(Arc::new(move |event| recorded.lock().unwrap().push(event)))
+            .install_if_needed()
+            .await
+            .unwrap();
+        assert!(path.starts_with(cache.path().join("2.1.3")));
+        assert_eq!(std::fs::read(&path).unwrap(), binary);
+        assert!(
+            events
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|event| matches!(event, InstallProgress::Verifying(_)))
+        );
+
+        // Offline, the cached binary is used
+        let offline = CliInstaller::new(true)
+            .with_version("v2.1.3")
+            .with_cache_dir(cache.path())
+            .with_releases_url("http://127.0.0.1:9")
+            .install_if_needed()
+            .await
+            .unwrap();
+        assert_eq!(offline, path);
+    }
+
+    #[tokio::test]
+    async fn test_checksum_mismatch_is_rejected() {
+        let cache = tempfile::tempdir().unwrap();
+        let url = serve(release("2.1.3", b"tampered", &sha256_hex(b"original"))).await;
+
+        let error = CliInstaller::new(true)
+            .with_version("2.1.3")
+            .with_cache_dir(cache.path())
+            .with_releases_url(&url)
+            .install_if_needed()
+            .await
+            .unwrap_err();
+        match install_error(&error) {
+            InstallError::ChecksumMismatch {
+                version,
+                expected,
+                actual,
+            } => {
+                assert_eq!(version, "2.1.3");
+                assert_eq!(expected, &sha256_hex(b"original"));
+                assert_eq!(actual, &sha256_hex(b"tampered"));
+            },
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(std::fs::read_dir(cache.path()).unwrap().next().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unpublished_version_fails() {
+        let cache = tempfile::tempdir().unwrap();
+        let url = serve(HashMap::new()).await;
+
+        let error = CliInstaller::new(true)
+            .with_version("2.9.9")
+            .with_cache_dir(cache.path())
+            .with_releases_url(&url)
+            .install_if_needed()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            install_error(&error),
+            InstallError::NotPublished { version, .. } if version == "2.9.9"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_versions_the_sdk_cannot_use_are_refused() {
+        let installer = |version: &str| {
+            CliInstaller::new(true)
+                .with_version(version)
+                .with_releases_url("http://127.0.0.1:9")
+        };
+
+        let error = installer("1.0.128").install_if_needed().await.unwrap_err();
+        assert!(matches!(
+            install_error(&error),
+            InstallError::VersionTooOld { requested, minimum }
+                if requested == "1.0.128" && minimum == MIN_CLI_VERSION
+        ));
+
+        let error = installer("latest").install_if_needed().await.unwrap_err();
+        assert!(matches!(install_error(&error), InstallError::InvalidVersion(_)));
+    }
 }
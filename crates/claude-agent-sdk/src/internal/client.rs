@@ -1,48 +1,158 @@
 //! Internal client implementation
 
 use futures::stream::StreamExt;
+use std::time::{Duration, Instant};
 
-use crate::errors::Result;
+use crate::errors::{ClaudeError, Result};
 use crate::types::config::ClaudeAgentOptions;
-use crate::types::messages::Message;
+use crate::types::messages::{CollectedResponse, Message};
 
+use super::fallback::FallbackDetector;
 use super::message_parser::MessageParser;
-use super::transport::subprocess::QueryPrompt;
+use super::audit::ToolAudit;
+use super::query_metrics::QueryMetrics;
+use super::transport::subprocess::{ProcessDiagnostics, QueryPrompt, within_message_timeout};
 use super::transport::{SubprocessTransport, Transport};
+use super::turns::TurnProgress;
 
 /// Internal client for processing queries
 pub struct InternalClient {
-    transport: SubprocessTransport,
+    transport: Box<dyn Transport>,
+    /// User message to send once connected, if the transport doesn't send the prompt itself
+    prompt: Option<serde_json::Value>,
+    /// Set for a spawned CLI, to explain its failures
+    diagnostics: Option<ProcessDiagnostics>,
+    turn_deadline: Option<Duration>,
+    message_timeout: Option<Duration>,
+    strict_parsing: bool,
+    fallback: FallbackDetector,
+    metrics: QueryMetrics,
+    audit: ToolAudit,
 }
 
 impl InternalClient {
     /// Create a new client
     pub fn new(prompt: QueryPrompt, options: ClaudeAgentOptions) -> Result<Self> {
-        let transport = SubprocessTransport::new(prompt, options)?;
-        Ok(Self { transport })
+        let transport = SubprocessTransport::new(prompt, options.clone())?;
+        let diagnostics = transport.diagnostics();
+        let mut client = Self::with_transport(transport.into_boxed()?, None, &options);
+        client.diagnostics = Some(diagnostics);
+        Ok(client)
+    }
+
+    /// Create a client for `transport`, sending `prompt` once connected
+    pub fn with_transport(
+        transport: Box<dyn Transport>,
+        prompt: Option<serde_json::Value>,
+        options: &ClaudeAgentOptions,
+    ) -> Self {
+        Self {
+            transport,
+            prompt,
+            diagnostics: None,
+            turn_deadline: options.turn_deadline,
+            message_timeout: options.message_timeout,
+            strict_parsing: options.strict_parsing,
+            fallback: FallbackDetector::new(options),
+            metrics: QueryMetrics::new(options),
+            audit: ToolAudit::new(options),
+        }
     }
 
     /// Connect and get messages
+    ///
+    /// With a turn deadline, the CLI process is killed once it elapses, as a
+    /// one-shot query cannot be interrupted. The same goes for a gap between
+    /// messages longer than the message timeout. Output that ends without a
+    /// result message fails with [`ClaudeError::IncompleteTurn`].
     pub async fn execute(mut self) -> Result<Vec<Message>> {
-        // Connect
-        self.transport.connect().await?;
+        let started = Instant::now();
+        let mut messages = Vec::new();
+
+        self.metrics.start();
+        let turn_deadline = self.turn_deadline;
+        let run = self.run(&mut messages);
+        let outcome = match turn_deadline {
+            Some(budget) => tokio::time::timeout(budget, run).await,
+            None => Ok(run.await),
+        };
+
+        if !matches!(outcome, Ok(Ok(()))) {
+            self.metrics.fail();
+        }
+        match outcome {
+            Ok(result) => result.map(|()| messages),
+            // Dropping self kills the CLI process
+            Err(_) => Err(ClaudeError::DeadlineExceeded {
+                elapsed: started.elapsed(),
+                partial: CollectedResponse::from(messages),
+            }),
+        }
+    }
+
+    async fn run(&mut self, messages: &mut Vec<Message>) -> Result<()> {
+        let transport = self.transport.as_mut();
+        let diagnostics = self.diagnostics.as_ref();
+        let prompt = self.prompt.take();
+
+        // Connect, and send the prompt unless the transport did
+        let connected = async {
+            transport.connect().await?;
+            if let Some(prompt) = prompt {
+                transport.write(&prompt.to_string()).await?;
+                transport.end_input().await?;
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(e) = connected {
+            return Err(explain(transport, diagnostics, e).await);
+        }
 
         // Collect all messages
-        let mut messages = Vec::new();
-        {
-            let mut stream = self.transport.read_messages();
+        let mut progress = TurnProgress::default();
+        let read: Result<()> = async {
+            let mut stream = transport.read_messages();
 
-            while let Some(result) = stream.next().await {
+            while let Some(result) =
+                within_message_timeout(self.message_timeout, stream.next()).await?
+            {
                 let json = result?;
-                let message = MessageParser::parse(json)?;
+                let mut message = MessageParser::parse_with(json, self.strict_parsing)?;
+                self.fallback.observe(&mut message);
+                self.metrics.observe(&message);
+                self.audit.observe(&message);
+                progress.observe(&message);
                 messages.push(message);
             }
-            // Stream is dropped here
+            Ok(())
+        }
+        .await;
+
+        // Don't leave a stalled or misbehaving CLI running
+        if let Err(e) = read {
+            transport.abort().await;
+            return Err(e);
+        }
+
+        if !progress.seen_result() {
+            let incomplete = progress.incomplete(transport.exit_info().await);
+            return Err(explain(transport, diagnostics, incomplete).await);
         }
 
         // Close transport
-        self.transport.close().await?;
+        transport.close().await
+    }
+}
 
-        Ok(messages)
+/// `error`, explained by how the CLI exited if it was spawned
+async fn explain(
+    transport: &mut dyn Transport,
+    diagnostics: Option<&ProcessDiagnostics>,
+    error: ClaudeError,
+) -> ClaudeError {
+    match diagnostics {
+        Some(diagnostics) => diagnostics.explain(error, transport.exit_info().await).await,
+        None => error,
     }
 }
@@ -0,0 +1,242 @@
+//! Detection of turns answered by the fallback model
+
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::observability::MetricsCollector;
+use crate::observability::metrics::FALLBACK_ACTIVATIONS_METRIC;
+use crate::types::config::ClaudeAgentOptions;
+use crate::types::messages::{Message, ModelFallback};
+
+/// Tracks the model of each turn and annotates result messages with [`ModelFallback`]
+pub(crate) struct FallbackDetector {
+    requested: Option<String>,
+    fallback: Option<String>,
+    metrics: Option<Arc<MetricsCollector>>,
+    actual: Option<String>,
+}
+
+impl FallbackDetector {
+    /// Create a detector for the models configured in `options`
+    pub(crate) fn new(options: &ClaudeAgentOptions) -> Self {
+        Self {
+            requested: options.model.clone(),
+            fallback: options.fallback_model.clone(),
+            metrics: options.metrics.clone(),
+            actual: None,
+        }
+    }
+
+    /// Record an explicit model change, which is not a fallback
+    pub(crate) fn set_requested(&mut self, model: Option<&str>) {
+        self.requested = model.map(String::from);
+    }
+
+    /// Observe a message of the current turn
+    ///
+    /// Assistant messages update the actual model; the result message gets the
+    /// turn's [`ModelFallback`] and ends the turn.
+    pub(crate) fn observe(&mut self, message: &mut Message) {
+        match message {
+            Message::Assistant(assistant) => {
+                if let Some(model) = &assistant.message.model {
+                    self.actual = Some(model.clone());
+                }
+            },
+            Message::Result(result) => {
+                let fallback = self.finish_turn();
+                if fallback.fallback_used {
+                    self.report(&fallback);
+                }
+                result.fallback = Some(fallback);
+            },
+            _ => {},
+        }
+    }
+
+    fn finish_turn(&mut self) -> ModelFallback {
+        let actual = self.actual.take();
+        let fallback_used = match (&self.fallback, &actual) {
+            (Some(fallback), Some(actual)) => match &self.requested {
+                Some(requested) => !same_model(actual, requested) && same_model(actual, fallback),
+                // Without an explicit model, only a match with the fallback is conclusive
+                None => same_model(actual, fallback),
+            },
+            _ => false,
+        };
+
+        ModelFallback {
+            requested_model: self.requested.clone(),
+            actual_model: actual,
+            fallback_used,
+        }
+    }
+
+    fn report(&self, fallback: &ModelFallback) {
+        let requested = fallback.requested_model.as_deref().unwrap_or("default");
+        let actual = fallback.actual_model.as_deref().unwrap_or_default();
+        warn!(
+            requested_model = requested,
+            actual_model = actual,
+            "Turn was answered by the fallback model"
+        );
+
+        if let Some(metrics) = &self.metrics {
+            metrics.increment(
+                FALLBACK_ACTIVATIONS_METRIC,
+                &[("requested_model", requested), ("actual_model", actual)],
+            );
+        }
+    }
+}
+
+/// Model family aliases the CLI accepts in place of a model ID
+const MODEL_ALIASES: [&str; 3] = ["opus", "sonnet", "haiku"];
+
+/// Compare model names
+///
+/// IDs match when equal once a date suffix such as `-20250514` is removed, and
+/// an alias such as `sonnet` matches the IDs of its family.
+fn same_model(a: &str, b: &str) -> bool {
+    let (a, b) = (without_date(a), without_date(b));
+    a == b || is_alias_of(a, b) || is_alias_of(b, a)
+}
+
+/// `model` without a trailing `-YYYYMMDD` or `-latest`
+fn without_date(model: &str) -> &str {
+    match model.rsplit_once('-') {
+        Some((base, suffix))
+            if suffix == "latest"
+                || (suffix.len() == 8 && suffix.bytes().all(|b| b.is_ascii_digit())) =>
+        {
+            base
+        },
+        _ => model,
+    }
+}
+
+/// Whether `alias` is a family alias and `id` a model ID of that family
+fn is_alias_of(alias: &str, id: &str) -> bool {
+    MODEL_ALIASES.contains(&alias) && id.split('-').any(|part| part == alias)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn turn(detector: &mut FallbackDetector, model: &str) -> ModelFallback {
+        let mut assistant: Message = serde_json::from_value(json!({
+            "type": "assistant",
+            "message": {"content": [], "model": model}
+        }))
+        .unwrap();
+        let mut result: Message = serde_json::from_value(json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 1,
+            "duration_api_ms": 1,
+            "is_error": false,
+            "num_turns": 1,
+            "session_id": "s"
+        }))
+        .unwrap();
+
+        detector.observe(&mut assistant);
+        detector.observe(&mut result);
+        match result {
+            Message::Result(result) => result.fallback.unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn options(model: Option<&str>, fallback: Option<&str>) -> ClaudeAgentOptions {
+        ClaudeAgentOptions {
+            model: model.map(String::from),
+            fallback_model: fallback.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detects_fallback_and_counts_it() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let mut options = options(Some("opus"), Some("sonnet"));
+        options.metrics = Some(Arc::clone(&metrics));
+        let mut detector = FallbackDetector::new(&options);
+
+        let fallback = turn(&mut detector, "claude-sonnet-4-20250514");
+        assert!(fallback.fallback_used);
+        assert_eq!(fallback.requested_model.as_deref(), Some("opus"));
+        assert_eq!(
+            fallback.actual_model.as_deref(),
+            Some("claude-sonnet-4-20250514")
+        );
+        assert_eq!(
+            metrics.get_counter(
+                FALLBACK_ACTIVATIONS_METRIC,
+                &[
+                    ("requested_model", "opus"),
+                    ("actual_model", "claude-sonnet-4-20250514")
+                ]
+            ),
+            1.0
+        );
+
+        // The next turn is judged on its own
+        assert!(!turn(&mut detector, "claude-opus-4-20250514").fallback_used);
+    }
+
+    #[test]
+    fn test_explicit_model_change_is_not_a_fallback() {
+        let mut detector = FallbackDetector::new(&options(Some("opus"), Some("sonnet")));
+        detector.set_requested(Some("sonnet"));
+
+        let fallback = turn(&mut detector, "claude-sonnet-4-20250514");
+        assert!(!fallback.fallback_used);
+        assert_eq!(fallback.requested_model.as_deref(), Some("sonnet"));
+    }
+
+    #[test]
+    fn test_same_model() {
+        assert!(same_model(
+            "claude-sonnet-4-20250514",
+            "claude-sonnet-4-20250514"
+        ));
+        assert!(same_model(
+            "claude-sonnet-4-5",
+            "claude-sonnet-4-5-20250929"
+        ));
+        assert!(same_model(
+            "claude-3-5-haiku-latest",
+            "claude-3-5-haiku-20241022"
+        ));
+        assert!(same_model("sonnet", "claude-sonnet-4-5-20250929"));
+        assert!(same_model("claude-3-5-haiku-20241022", "haiku"));
+
+        // One ID being part of another does not make them the same model
+        assert!(!same_model("claude-sonnet-4", "claude-sonnet-4-5-20250929"));
+        assert!(!same_model("claude-opus-4", "claude-opus-4-1-20250805"));
+        assert!(!same_model("opus", "claude-sonnet-4-20250514"));
+        assert!(!same_model("claude", "claude-sonnet-4-20250514"));
+    }
+
+    #[test]
+    fn test_fallback_within_a_model_family() {
+        // The requested ID is a prefix of the fallback's
+        let mut detector =
+            FallbackDetector::new(&options(Some("claude-sonnet-4"), Some("claude-sonnet-4-5")));
+        assert!(turn(&mut detector, "claude-sonnet-4-5-20250929").fallback_used);
+        assert!(!turn(&mut detector, "claude-sonnet-4-20250514").fallback_used);
+    }
+
+    #[test]
+    fn test_no_fallback_without_fallback_model() {
+        let mut detector = FallbackDetector::new(&options(Some("opus"), None));
+        assert!(!turn(&mut detector, "claude-sonnet-4-20250514").fallback_used);
+
+        let mut detector = FallbackDetector::new(&options(None, Some("haiku")));
+        assert!(!turn(&mut detector, "claude-sonnet-4-20250514").fallback_used);
+        assert!(turn(&mut detector, "claude-3-5-haiku-20241022").fallback_used);
+    }
+}
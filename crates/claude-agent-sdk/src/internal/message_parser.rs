@@ -8,9 +8,22 @@ pub struct MessageParser;
 
 impl MessageParser {
     /// Parse a JSON value into a Message
+    ///
+    /// Unknown message and content block types are kept as `Unknown` variants.
     pub fn parse(data: serde_json::Value) -> Result<Message> {
         serde_json::from_value(data.clone()).map_err(|e| {
             MessageParseError::new(format!("Failed to parse message: {}", e), Some(data)).into()
         })
     }
+
+    /// Parse a JSON value into a Message, failing on unknown types when `strict` is set
+    pub fn parse_with(data: serde_json::Value, strict: bool) -> Result<Message> {
+        let message = Self::parse(data)?;
+        if strict && let Some(unknown) = message.unknown_type() {
+            let error = format!("Failed to parse message: unknown type `{}`", unknown);
+            let data = serde_json::to_value(&message).ok();
+            return Err(MessageParseError::new(error, data).into());
+        }
+        Ok(message)
+    }
 }
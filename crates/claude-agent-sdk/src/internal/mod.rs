@@ -1,7 +1,15 @@
 //! Internal implementation details
 
+pub(crate) mod audit;
+pub(crate) mod budget;
+pub(crate) mod checkpoints;
 pub mod cli_installer;
 pub mod client;
+pub(crate) mod fallback;
 pub mod message_parser;
 pub mod query_full;
+pub(crate) mod query_metrics;
+pub(crate) mod sessions;
 pub mod transport;
+pub(crate) mod turns;
+pub(crate) mod usage;
@@ -4,20 +4,26 @@ use futures::stream::StreamExt;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tokio::io::AsyncWriteExt;
-use tokio::sync::{Mutex, mpsc, oneshot};
-use tracing::{error, warn};
+use tokio::sync::{Mutex, Notify, mpsc, oneshot};
+use tracing::{debug, error, warn};
 
 /// Channel capacity for message queue (bounded to prevent memory exhaustion)
 const MESSAGE_CHANNEL_CAPACITY: usize = 1000;
 
-use crate::errors::{ClaudeError, Result};
-use crate::types::hooks::{HookCallback, HookContext, HookInput, HookMatcher};
-use crate::types::mcp::McpSdkServerConfig;
+use crate::errors::{ClaudeError, Result, TransportExitInfo};
+use crate::observability::MetricsCollector;
+use crate::observability::metrics::HOOK_DURATION_METRIC;
+use crate::types::hooks::{
+    DEFAULT_HOOK_TIMEOUT, HookCallback, HookContext, HookInput, HookJsonOutput, HookMatcher,
+    SyncHookJsonOutput,
+};
+use crate::types::mcp::{DEFAULT_SESSION_ID, McpSdkServerConfig};
 
-use super::transport::Transport;
+use super::sessions::SessionRouter;
+use super::transport::{Transport, TransportWriter};
 
 /// Control request from SDK to CLI
 #[allow(dead_code)]
@@ -57,20 +63,45 @@ struct IncomingControlRequest {
     request: serde_json::Value,
 }
 
+/// A hook callback with the matcher it was registered under
+#[derive(Clone)]
+struct RegisteredHook {
+    callback: HookCallback,
+    event: String,
+    matcher: Option<String>,
+    timeout: Duration,
+}
+
 /// Full Query implementation with bidirectional control protocol
 pub struct QueryFull {
     pub(crate) transport: Arc<Mutex<Box<dyn Transport>>>,
-    hook_callbacks: Arc<Mutex<HashMap<String, HookCallback>>>,
+    hook_callbacks: Arc<Mutex<HashMap<String, RegisteredHook>>>,
+    // Receives the durations of hook callbacks
+    metrics: Option<Arc<MetricsCollector>>,
     sdk_mcp_servers: Arc<Mutex<HashMap<String, McpSdkServerConfig>>>,
+    // Most recent session id reported by the CLI, used to scope SDK MCP tool state
+    session_id: Arc<Mutex<Option<String>>>,
     next_callback_id: Arc<AtomicU64>,
     request_counter: Arc<AtomicU64>,
     pending_responses: Arc<Mutex<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
-    message_tx: mpsc::Sender<serde_json::Value>,
+    // Taken by the background reader, so the channel closes when the CLI's output ends
+    message_tx: std::sync::Mutex<Option<mpsc::Sender<serde_json::Value>>>,
+    // Delivers the SDK's own messages without keeping the channel open
+    local_tx: mpsc::WeakSender<serde_json::Value>,
+    // Messages of sessions no one has claimed
     pub(crate) message_rx: Arc<Mutex<mpsc::Receiver<serde_json::Value>>>,
+    // Routes messages of claimed sessions to their own channels
+    pub(crate) sessions: Arc<SessionRouter>,
     // Direct access to stdin for writes (bypasses transport lock)
-    pub(crate) stdin: Option<Arc<Mutex<Option<tokio::process::ChildStdin>>>>,
+    pub(crate) stdin: Option<TransportWriter>,
     // Store initialization result for get_server_info()
     initialization_result: Arc<Mutex<Option<serde_json::Value>>>,
+    // How the CLI exited, recorded before the message channel closes
+    pub(crate) exit_info: Arc<std::sync::Mutex<Option<TransportExitInfo>>>,
+    // Cleared by the background reader when the CLI's output ends
+    pub(crate) output_open: Arc<AtomicBool>,
+    // Makes the background reader let go of the transport, see `stop_reading`
+    stop_reading: Arc<Notify>,
 }
 
 impl QueryFull {
@@ -81,28 +112,109 @@ impl QueryFull {
         Self {
             transport: Arc::new(Mutex::new(transport)),
             hook_callbacks: Arc::new(Mutex::new(HashMap::new())),
+            metrics: None,
             sdk_mcp_servers: Arc::new(Mutex::new(HashMap::new())),
+            session_id: Arc::new(Mutex::new(None)),
             next_callback_id: Arc::new(AtomicU64::new(0)),
             request_counter: Arc::new(AtomicU64::new(0)),
             pending_responses: Arc::new(Mutex::new(HashMap::new())),
-            message_tx,
+            local_tx: message_tx.downgrade(),
+            message_tx: std::sync::Mutex::new(Some(message_tx)),
             message_rx: Arc::new(Mutex::new(message_rx)),
+            sessions: Arc::new(SessionRouter::new(MESSAGE_CHANNEL_CAPACITY)),
             stdin: None,
             initialization_result: Arc::new(Mutex::new(None)),
+            exit_info: Arc::new(std::sync::Mutex::new(None)),
+            output_open: Arc::new(AtomicBool::new(true)),
+            stop_reading: Arc::new(Notify::new()),
         }
     }
 
     /// Set stdin for direct write access (called from client after transport is connected)
-    pub fn set_stdin(&mut self, stdin: Arc<Mutex<Option<tokio::process::ChildStdin>>>) {
+    pub fn set_stdin(&mut self, stdin: TransportWriter) {
         self.stdin = Some(stdin);
     }
 
+    /// Stop the background reader, releasing the transport
+    ///
+    /// The reader holds the transport while it reads, so this is needed to
+    /// signal a CLI whose output has not ended. Messages not read yet are lost.
+    pub(crate) fn stop_reading(&self) {
+        self.stop_reading.notify_one();
+    }
+
+    /// Record the durations of hook callbacks into `metrics`
+    ///
+    /// Must be called before [`start`](Self::start).
+    pub(crate) fn set_metrics(&mut self, metrics: Option<Arc<MetricsCollector>>) {
+        self.metrics = metrics;
+    }
+
+    /// Queue messages left over from a previous connection, ahead of any new ones
+    ///
+    /// Must be called before [`start`](Self::start).
+    pub(crate) fn carry_over(&self, messages: Vec<serde_json::Value>) -> Result<()> {
+        let message_tx = self.message_tx.lock().unwrap();
+        let message_tx = message_tx.as_ref().ok_or_else(|| {
+            ClaudeError::InternalError("Query already started".to_string())
+        })?;
+        for message in messages {
+            let Some(message) = self.sessions.route(message) else {
+                continue;
+            };
+            message_tx.try_send(message).map_err(|_| {
+                ClaudeError::InternalError("Too many messages to carry over".to_string())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Deliver a message the SDK produced itself, as if the CLI had sent it
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::Transport` once the CLI's output has ended, or
+    /// `ClaudeError::InternalError` if the channel is full.
+    pub(crate) fn deliver_local(&self, message: serde_json::Value) -> Result<()> {
+        let message_tx = self
+            .local_tx
+            .upgrade()
+            .ok_or_else(|| ClaudeError::Transport("Claude CLI output has ended".to_string()))?;
+        let Some(message) = self.sessions.route(message) else {
+            return Ok(());
+        };
+        message_tx.try_send(message).map_err(|_| {
+            ClaudeError::InternalError("Message channel full, local message dropped".to_string())
+        })
+    }
+
+    /// Take the messages waiting in the channel without waiting for more
+    ///
+    /// Returns nothing if a receive stream is reading the channel.
+    pub(crate) fn drain_buffered(&self) -> Vec<serde_json::Value> {
+        let mut messages = Vec::new();
+        if let Ok(mut rx) = self.message_rx.try_lock() {
+            while let Ok(message) = rx.try_recv() {
+                messages.push(message);
+            }
+        }
+        messages
+    }
+
+    /// Most recent session ID reported by the CLI
+    pub(crate) async fn last_session_id(&self) -> Option<String> {
+        self.session_id.lock().await.clone()
+    }
+
     /// Set SDK MCP servers
     pub async fn set_sdk_mcp_servers(&mut self, servers: HashMap<String, McpSdkServerConfig>) {
         *self.sdk_mcp_servers.lock().await = servers;
     }
 
     /// Initialize with hooks
+    ///
+    /// The response is awaited without a time limit; callers bound it with
+    /// [`ConnectTimeouts::initialize`](crate::types::config::ConnectTimeouts::initialize).
     pub async fn initialize(
         &self,
         hooks: Option<HashMap<String, Vec<HookMatcher>>>,
@@ -116,16 +228,26 @@ impl QueryFull {
 
                 for matcher in matchers {
                     let mut callback_ids = Vec::new();
+                    let timeout = matcher
+                        .timeout
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                        .unwrap_or(DEFAULT_HOOK_TIMEOUT);
 
                     for callback in matcher.hooks {
                         let callback_id = format!(
                             "hook_{}",
                             self.next_callback_id.fetch_add(1, Ordering::SeqCst)
                         );
+                        let hook = RegisteredHook {
+                            callback,
+                            event: event.clone(),
+                            matcher: matcher.matcher.clone(),
+                            timeout,
+                        };
                         self.hook_callbacks
                             .lock()
                             .await
-                            .insert(callback_id.clone(), callback);
+                            .insert(callback_id.clone(), hook);
                         callback_ids.push(callback_id);
                     }
 
@@ -152,7 +274,7 @@ impl QueryFull {
             "hooks": if hooks_config.is_empty() { json!(null) } else { json!(hooks_config) }
         });
 
-        let response = self.send_control_request(request).await?;
+        let response = self.send_control_request_with_timeout(request, None).await?;
 
         // Store initialization result for get_server_info()
         *self.initialization_result.lock().await = Some(response.clone());
@@ -164,10 +286,18 @@ impl QueryFull {
     pub async fn start(&self) -> Result<()> {
         let transport = Arc::clone(&self.transport);
         let hook_callbacks = Arc::clone(&self.hook_callbacks);
+        let metrics = self.metrics.clone();
         let sdk_mcp_servers = Arc::clone(&self.sdk_mcp_servers);
+        let session_id = Arc::clone(&self.session_id);
+        let sessions = Arc::clone(&self.sessions);
         let pending_responses = Arc::clone(&self.pending_responses);
-        let message_tx = self.message_tx.clone();
+        let message_tx = self.message_tx.lock().unwrap().take().ok_or_else(|| {
+            ClaudeError::InternalError("Query already started".to_string())
+        })?;
         let stdin = self.stdin.clone();
+        let exit_info = Arc::clone(&self.exit_info);
+        let output_open = Arc::clone(&self.output_open);
+        let stop_reading = Arc::clone(&self.stop_reading);
 
         // Create a channel to signal when background task is ready
         let (ready_tx, ready_rx) = oneshot::channel();
@@ -179,7 +309,14 @@ impl QueryFull {
             // Signal that we're ready to receive messages
             let _ = ready_tx.send(());
 
-            while let Some(result) = stream.next().await {
+            let mut stopped = false;
+            while let Some(result) = tokio::select! {
+                result = stream.next() => result,
+                _ = stop_reading.notified() => {
+                    stopped = true;
+                    None
+                },
+            } {
                 match result {
                     Ok(message) => {
                         let msg_type = message.get("type").and_then(|v| v.as_str());
@@ -204,14 +341,18 @@ impl QueryFull {
                                 ) {
                                     let stdin_clone = stdin.clone();
                                     let hook_callbacks_clone = Arc::clone(&hook_callbacks);
+                                    let metrics_clone = metrics.clone();
                                     let sdk_mcp_servers_clone = Arc::clone(&sdk_mcp_servers);
+                                    let session_id_clone = Arc::clone(&session_id);
 
                                     tokio::spawn(async move {
                                         if let Err(e) = Self::handle_control_request_with_stdin(
                                             request,
                                             stdin_clone,
                                             hook_callbacks_clone,
+                                            metrics_clone,
                                             sdk_mcp_servers_clone,
+                                            session_id_clone,
                                         )
                                         .await
                                         {
@@ -221,7 +362,20 @@ impl QueryFull {
                                 }
                             },
                             _ => {
-                                // Regular message - send to stream with backpressure
+                                if let Some(id) = message.get("session_id").and_then(|v| v.as_str()) {
+                                    *session_id.lock().await = Some(id.to_string());
+
+                                    // A result ends the session for SDK MCP tool state
+                                    if msg_type == Some("result") {
+                                        Self::end_sdk_mcp_sessions(&sdk_mcp_servers, id).await;
+                                    }
+                                }
+
+                                // Regular message - send to its session's stream, or the
+                                // unclaimed one, with backpressure
+                                let Some(message) = sessions.route(message) else {
+                                    continue;
+                                };
                                 if let Err(e) = message_tx.try_send(message) {
                                     match e {
                                         mpsc::error::TrySendError::Full(_) => {
@@ -238,6 +392,18 @@ impl QueryFull {
                     Err(_) => break,
                 }
             }
+
+            drop(stream);
+            // A CLI that was cut off has not exited, so don't wait for it
+            if !stopped {
+                *exit_info.lock().unwrap() = transport_guard.exit_info().await;
+            }
+            output_open.store(false, Ordering::SeqCst);
+            // The CLI can't answer control requests anymore; fail them instead of timing out
+            pending_responses.lock().await.clear();
+            // Closing the sessions and dropping message_tx now ends the receive
+            // streams once they drain
+            sessions.close();
         });
 
         // Wait for background task to be ready before returning
@@ -251,9 +417,11 @@ impl QueryFull {
     /// Handle incoming control request from CLI (new version using stdin directly)
     async fn handle_control_request_with_stdin(
         request: IncomingControlRequest,
-        stdin: Option<Arc<Mutex<Option<tokio::process::ChildStdin>>>>,
-        hook_callbacks: Arc<Mutex<HashMap<String, HookCallback>>>,
+        stdin: Option<TransportWriter>,
+        hook_callbacks: Arc<Mutex<HashMap<String, RegisteredHook>>>,
+        metrics: Option<Arc<MetricsCollector>>,
         sdk_mcp_servers: Arc<Mutex<HashMap<String, McpSdkServerConfig>>>,
+        session_id: Arc<Mutex<Option<String>>>,
     ) -> Result<()> {
         let request_id = request.request_id;
         let request_data = request.request;
@@ -273,28 +441,37 @@ impl QueryFull {
                         ClaudeError::ControlProtocol("Missing callback_id".to_string())
                     })?;
 
-                let callbacks = hook_callbacks.lock().await;
-                let callback = callbacks.get(callback_id).ok_or_else(|| {
-                    ClaudeError::ControlProtocol(format!(
-                        "Hook callback not found: {}",
-                        callback_id
-                    ))
-                })?;
+                // Cloned out, so a slow hook does not hold up other hooks
+                let hook = hook_callbacks
+                    .lock()
+                    .await
+                    .get(callback_id)
+                    .cloned()
+                    .ok_or_else(|| {
+                        ClaudeError::ControlProtocol(format!(
+                            "Hook callback not found: {}",
+                            callback_id
+                        ))
+                    })?;
 
                 // Parse hook input
                 let input_json = request_data.get("input").cloned().unwrap_or(json!({}));
-                let hook_input: HookInput = serde_json::from_value(input_json).map_err(|e| {
-                    ClaudeError::ControlProtocol(format!("Failed to parse hook input: {}", e))
-                })?;
+                let mut hook_input: HookInput =
+                    serde_json::from_value(input_json).map_err(|e| {
+                        ClaudeError::ControlProtocol(format!("Failed to parse hook input: {}", e))
+                    })?;
 
-                let tool_use_id = request_data
-                    .get("tool_use_id")
-                    .and_then(|v| v.as_str())
-                    .map(String::from);
+                let tool_use_id = hook_input.merge_tool_use_id(
+                    request_data
+                        .get("tool_use_id")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                );
                 let context = HookContext::default();
 
-                // Call the hook
-                let hook_output = callback(hook_input, tool_use_id, context).await;
+                let hook_output =
+                    Self::run_hook(&hook, hook_input, tool_use_id, context, metrics.as_deref())
+                        .await;
 
                 // Convert to JSON
                 serde_json::to_value(&hook_output).map_err(|e| {
@@ -316,9 +493,19 @@ impl QueryFull {
                     ClaudeError::ControlProtocol("Missing message for mcp_message".to_string())
                 })?;
 
-                let mcp_response =
-                    Self::handle_sdk_mcp_request(sdk_mcp_servers, server_name, mcp_message.clone())
-                        .await?;
+                let session_id = session_id
+                    .lock()
+                    .await
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
+
+                let mcp_response = Self::handle_sdk_mcp_request(
+                    sdk_mcp_servers,
+                    server_name,
+                    &session_id,
+                    mcp_message.clone(),
+                )
+                .await?;
 
                 json!({"mcp_response": mcp_response})
             },
@@ -371,8 +558,102 @@ impl QueryFull {
         Ok(())
     }
 
+    /// Run `hook`, answering with a message to continue if it exceeds its timeout
+    async fn run_hook(
+        hook: &RegisteredHook,
+        input: HookInput,
+        tool_use_id: Option<String>,
+        context: HookContext,
+        metrics: Option<&MetricsCollector>,
+    ) -> HookJsonOutput {
+        let started = Instant::now();
+        let result =
+            tokio::time::timeout(hook.timeout, (hook.callback)(input, tool_use_id, context)).await;
+        let duration = started.elapsed();
+        let matcher = hook.matcher.as_deref().unwrap_or("*");
+
+        let outcome = if result.is_ok() {
+            debug!(
+                event = %hook.event,
+                matcher,
+                duration_ms = duration.as_millis() as u64,
+                "Hook completed"
+            );
+            "completed"
+        } else {
+            warn!(
+                event = %hook.event,
+                matcher,
+                timeout_secs = hook.timeout.as_secs_f64(),
+                "Hook timed out, continuing without it"
+            );
+            "timed_out"
+        };
+        if let Some(metrics) = metrics {
+            metrics.record_timing(
+                HOOK_DURATION_METRIC,
+                duration,
+                &[("event", hook.event.as_str()), ("outcome", outcome)],
+            );
+        }
+
+        result.unwrap_or_else(|_| {
+            HookJsonOutput::Sync(SyncHookJsonOutput {
+                continue_: Some(true),
+                system_message: Some(format!(
+                    "The {} hook (matcher {}) timed out after {}s and was skipped",
+                    hook.event,
+                    matcher,
+                    hook.timeout.as_secs_f64()
+                )),
+                ..Default::default()
+            })
+        })
+    }
+
     /// Send control request to CLI
     async fn send_control_request(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+        const CONTROL_REQUEST_TIMEOUT_SECS: u64 = 30;
+        self.send_control_request_with_timeout(
+            request,
+            Some(Duration::from_secs(CONTROL_REQUEST_TIMEOUT_SECS)),
+        )
+        .await
+    }
+
+    /// Send a control request, waiting at most `timeout` for the response
+    ///
+    /// Without a timeout the caller is responsible for bounding the wait.
+    async fn send_control_request_with_timeout(
+        &self,
+        request: serde_json::Value,
+        timeout: Option<Duration>,
+    ) -> Result<serde_json::Value> {
+        let rx = self.write_control_request(request).await?;
+
+        // Wait for response with timeout to prevent indefinite hangs
+        let response = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, rx).await.map_err(|_| {
+                error!("Control request timed out after {:?}", timeout);
+                ClaudeError::ControlProtocol(format!(
+                    "Control request timed out after {:?}",
+                    timeout
+                ))
+            })?,
+            None => rx.await,
+        }
+        .map_err(|_| {
+            ClaudeError::ControlProtocol("Control request response channel closed".to_string())
+        })?;
+
+        Ok(response)
+    }
+
+    /// Send a control request, returning the receiver of its response
+    async fn write_control_request(
+        &self,
+        request: serde_json::Value,
+    ) -> Result<oneshot::Receiver<serde_json::Value>> {
         let request_id = format!(
             "req_{}_{}",
             self.request_counter.fetch_add(1, Ordering::SeqCst),
@@ -381,10 +662,15 @@ impl QueryFull {
 
         // Create oneshot channel for response
         let (tx, rx) = oneshot::channel();
-        self.pending_responses
-            .lock()
-            .await
-            .insert(request_id.clone(), tx);
+        {
+            let mut pending = self.pending_responses.lock().await;
+            if !self.output_open.load(Ordering::SeqCst) {
+                return Err(ClaudeError::ControlProtocol(
+                    "CLI output has ended; control request not sent".to_string(),
+                ));
+            }
+            pending.insert(request_id.clone(), tx);
+        }
 
         // Build and send request
         let control_request = json!({
@@ -420,22 +706,7 @@ impl QueryFull {
             return Err(ClaudeError::Transport("stdin not set".to_string()));
         }
 
-        // Wait for response with timeout to prevent indefinite hangs
-        const CONTROL_REQUEST_TIMEOUT_SECS: u64 = 30;
-        let response = tokio::time::timeout(Duration::from_secs(CONTROL_REQUEST_TIMEOUT_SECS), rx)
-            .await
-            .map_err(|_| {
-                error!("Control request timed out after {} seconds", CONTROL_REQUEST_TIMEOUT_SECS);
-                ClaudeError::ControlProtocol(format!(
-                    "Control request timed out after {} seconds",
-                    CONTROL_REQUEST_TIMEOUT_SECS
-                ))
-            })?
-            .map_err(|_| {
-                ClaudeError::ControlProtocol("Control request response channel closed".to_string())
-            })?;
-
-        Ok(response)
+        Ok(rx)
     }
 
     /// Receive messages
@@ -461,6 +732,17 @@ impl QueryFull {
         Ok(())
     }
 
+    /// Send an interrupt without waiting for the CLI to acknowledge it
+    ///
+    /// The returned receiver yields the CLI's response, or fails if its output
+    /// ends first.
+    pub(crate) async fn start_interrupt(&self) -> Result<oneshot::Receiver<serde_json::Value>> {
+        self.write_control_request(json!({
+            "subtype": "interrupt"
+        }))
+        .await
+    }
+
     /// Change permission mode dynamically
     pub async fn set_permission_mode(
         &self,
@@ -525,18 +807,31 @@ impl QueryFull {
     async fn handle_sdk_mcp_request(
         sdk_mcp_servers: Arc<Mutex<HashMap<String, McpSdkServerConfig>>>,
         server_name: &str,
+        session_id: &str,
         message: serde_json::Value,
     ) -> Result<serde_json::Value> {
-        let servers = sdk_mcp_servers.lock().await;
-        let server_config = servers.get(server_name).ok_or_else(|| {
-            ClaudeError::ControlProtocol(format!("SDK MCP server not found: {}", server_name))
-        })?;
-
-        // Call the server's handle_message method
-        server_config
-            .instance
-            .handle_message(message)
+        // Not held while the server runs, as `tasks/result` waits for the task
+        let server_config = sdk_mcp_servers
+            .lock()
             .await
-            .map_err(|e| ClaudeError::ControlProtocol(format!("MCP server error: {}", e)))
+            .get(server_name)
+            .cloned()
+            .ok_or_else(|| {
+                ClaudeError::ControlProtocol(format!("SDK MCP server not found: {}", server_name))
+            })?;
+
+        Ok(server_config.handle_jsonrpc(session_id, message).await)
+    }
+
+    /// Drop the per-session tool state of every SDK MCP server for `session_id`
+    async fn end_sdk_mcp_sessions(
+        sdk_mcp_servers: &Mutex<HashMap<String, McpSdkServerConfig>>,
+        session_id: &str,
+    ) {
+        for server in sdk_mcp_servers.lock().await.values() {
+            if let Some(store) = &server.session_state {
+                store.end_session(session_id);
+            }
+        }
     }
 }
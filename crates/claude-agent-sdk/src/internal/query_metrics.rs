@@ -0,0 +1,244 @@
+//! Recording of query metrics into the configured `MetricsCollector`
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::observability::MetricsCollector;
+use crate::observability::metrics::{
+    COST_USD_METRIC, QUERIES_COMPLETED_METRIC, QUERIES_FAILED_METRIC, QUERIES_STARTED_METRIC,
+    QUERY_DURATION_METRIC, TIME_TO_FIRST_MESSAGE_METRIC, TOOL_INVOCATIONS_METRIC,
+};
+use crate::types::config::ClaudeAgentOptions;
+use crate::types::messages::{ContentBlock, Message, ResultMessage};
+
+const NO_LABELS: &[(&str, &str)] = &[];
+
+/// Records query metrics when `ClaudeAgentOptions::metrics` is set, and does nothing otherwise
+pub(crate) struct QueryMetrics {
+    recorder: Option<Recorder>,
+}
+
+struct Recorder {
+    collector: Arc<MetricsCollector>,
+    /// Start of each query still waiting for its result, oldest first
+    pending: VecDeque<Instant>,
+    /// Whether the oldest pending query has had an assistant message
+    first_message_seen: bool,
+    /// Latest `total_cost_usd` of each session, a running total for the session
+    reported_costs: HashMap<String, f64>,
+}
+
+impl QueryMetrics {
+    pub(crate) fn new(options: &ClaudeAgentOptions) -> Self {
+        Self {
+            recorder: options.metrics.as_ref().map(|collector| Recorder {
+                collector: Arc::clone(collector),
+                pending: VecDeque::new(),
+                first_message_seen: false,
+                reported_costs: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Note that a query was sent
+    pub(crate) fn start(&mut self) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder
+                .collector
+                .increment(QUERIES_STARTED_METRIC, NO_LABELS);
+            recorder.pending.push_back(Instant::now());
+        }
+    }
+
+    pub(crate) fn observe(&mut self, message: &Message) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        match message {
+            Message::Assistant(assistant) => {
+                if !recorder.first_message_seen
+                    && let Some(started) = recorder.pending.front()
+                {
+                    recorder.first_message_seen = true;
+                    recorder.collector.record_timing(
+                        TIME_TO_FIRST_MESSAGE_METRIC,
+                        started.elapsed(),
+                        NO_LABELS,
+                    );
+                }
+                for block in &assistant.message.content {
+                    if let ContentBlock::ToolUse(tool_use) = block {
+                        recorder.collector.increment(
+                            TOOL_INVOCATIONS_METRIC,
+                            &[("tool", tool_use.name.as_str())],
+                        );
+                    }
+                }
+            },
+            Message::Result(result) => recorder.finish(result),
+            _ => {},
+        }
+    }
+
+    /// Note that the oldest pending query failed without a result
+    pub(crate) fn fail(&mut self) {
+        if let Some(recorder) = &mut self.recorder
+            && recorder.pending.pop_front().is_some()
+        {
+            recorder.first_message_seen = false;
+            recorder
+                .collector
+                .increment(QUERIES_FAILED_METRIC, NO_LABELS);
+        }
+    }
+
+    /// Count every pending query as failed, e.g. when the CLI is shut down
+    pub(crate) fn abandon(&mut self) {
+        if let Some(recorder) = &mut self.recorder {
+            let abandoned = std::mem::take(&mut recorder.pending).len();
+            recorder.first_message_seen = false;
+            if abandoned > 0 {
+                recorder
+                    .collector
+                    .increment_by(QUERIES_FAILED_METRIC, abandoned as f64, NO_LABELS);
+            }
+        }
+    }
+}
+
+impl Recorder {
+    fn finish(&mut self, result: &ResultMessage) {
+        self.first_message_seen = false;
+        if let Some(started) = self.pending.pop_front() {
+            self.collector
+                .record_timing(QUERY_DURATION_METRIC, started.elapsed(), NO_LABELS);
+        }
+        let outcome = if result.is_error {
+            QUERIES_FAILED_METRIC
+        } else {
+            QUERIES_COMPLETED_METRIC
+        };
+        self.collector.increment(outcome, NO_LABELS);
+
+        if let Some(reported) = result.total_cost_usd {
+            // Only the increase since the session's previous result is new;
+            // a lower cost means the CLI started counting again
+            let previous = self
+                .reported_costs
+                .insert(result.session_id.clone(), reported);
+            let cost = match previous {
+                Some(previous) if previous <= reported => reported - previous,
+                _ => reported,
+            };
+            let total = self.collector.get_gauge(COST_USD_METRIC, NO_LABELS) + cost;
+            self.collector.set_gauge(COST_USD_METRIC, total, NO_LABELS);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::message_parser::MessageParser;
+    use serde_json::json;
+
+    fn assistant(tools: &[&str]) -> Message {
+        let content: Vec<_> = tools
+            .iter()
+            .map(|name| json!({"type": "tool_use", "id": "t", "name": name, "input": {}}))
+            .collect();
+        MessageParser::parse(json!({"type": "assistant", "message": {"content": content}})).unwrap()
+    }
+
+    fn result(is_error: bool, cost: f64) -> Message {
+        MessageParser::parse(json!({
+            "type": "result",
+            "subtype": if is_error { "error_during_execution" } else { "success" },
+            "duration_ms": 1,
+            "duration_api_ms": 1,
+            "is_error": is_error,
+            "num_turns": 1,
+            "session_id": "s",
+            "total_cost_usd": cost
+        }))
+        .unwrap()
+    }
+
+    fn recording() -> (Arc<MetricsCollector>, QueryMetrics) {
+        let collector = Arc::new(MetricsCollector::new());
+        let options = ClaudeAgentOptions {
+            metrics: Some(Arc::clone(&collector)),
+            ..Default::default()
+        };
+        (collector, QueryMetrics::new(&options))
+    }
+
+    #[test]
+    fn test_records_query_lifecycle() {
+        let (collector, mut metrics) = recording();
+
+        metrics.start();
+        metrics.observe(&assistant(&["Read", "Bash"]));
+        metrics.observe(&assistant(&["Read"]));
+        metrics.observe(&result(false, 0.25));
+
+        // The session's running cost
+        metrics.start();
+        metrics.observe(&result(true, 0.75));
+
+        metrics.start();
+        metrics.fail();
+
+        metrics.start();
+        metrics.start();
+        metrics.abandon();
+
+        assert_eq!(
+            collector.get_counter(QUERIES_STARTED_METRIC, NO_LABELS),
+            5.0
+        );
+        assert_eq!(
+            collector.get_counter(QUERIES_COMPLETED_METRIC, NO_LABELS),
+            1.0
+        );
+        assert_eq!(collector.get_counter(QUERIES_FAILED_METRIC, NO_LABELS), 4.0);
+        assert_eq!(
+            collector.get_counter(TOOL_INVOCATIONS_METRIC, &[("tool", "Read")]),
+            2.0
+        );
+        assert_eq!(
+            collector.get_counter(TOOL_INVOCATIONS_METRIC, &[("tool", "Bash")]),
+            1.0
+        );
+        assert_eq!(collector.get_gauge(COST_USD_METRIC, NO_LABELS), 0.75);
+
+        let durations = collector
+            .get_histogram(QUERY_DURATION_METRIC, NO_LABELS)
+            .unwrap();
+        assert_eq!(durations.count, 2);
+        // Only the first assistant message of a query is timed
+        let first_message = collector
+            .get_histogram(TIME_TO_FIRST_MESSAGE_METRIC, NO_LABELS)
+            .unwrap();
+        assert_eq!(first_message.count, 1);
+    }
+
+    #[test]
+    fn test_fail_without_pending_query_is_ignored() {
+        let (collector, mut metrics) = recording();
+        metrics.fail();
+        assert_eq!(collector.get_counter(QUERIES_FAILED_METRIC, NO_LABELS), 0.0);
+    }
+
+    #[test]
+    fn test_without_collector_does_nothing() {
+        let mut metrics = QueryMetrics::new(&ClaudeAgentOptions::default());
+        metrics.start();
+        metrics.observe(&assistant(&["Read"]));
+        metrics.observe(&result(false, 1.0));
+        metrics.fail();
+        metrics.abandon();
+        assert!(metrics.recorder.is_none());
+    }
+}
@@ -0,0 +1,245 @@
+//! Routing of CLI messages to per-session channels
+
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use serde_json::Value;
+use tokio::sync::{Mutex, mpsc};
+use tracing::warn;
+
+/// Receiving end of a message channel, shared by the streams that read it
+pub(crate) type MessageReceiver = Arc<Mutex<mpsc::Receiver<Value>>>;
+
+/// Channel of a claimed session
+struct SessionChannel {
+    tx: mpsc::Sender<Value>,
+    // Alive while a session handle holds the receiver
+    rx: Weak<Mutex<mpsc::Receiver<Value>>>,
+}
+
+impl SessionChannel {
+    fn is_claimed(&self) -> bool {
+        self.rx.strong_count() > 0
+    }
+}
+
+/// Claimed sessions taken from a router by [`SessionRouter::detach`]
+pub(crate) struct DetachedSessions(HashMap<String, SessionChannel>);
+
+#[derive(Default)]
+struct RouterState {
+    sessions: HashMap<String, SessionChannel>,
+    closed: bool,
+}
+
+/// Fans messages out to the channels of claimed sessions
+///
+/// Messages are routed by their `session_id` field. Messages of sessions no one
+/// has claimed, or whose receivers were dropped, are handed back to the caller
+/// for the unclaimed channel, so nothing addressed to another session is lost.
+pub(crate) struct SessionRouter {
+    capacity: usize,
+    state: std::sync::Mutex<RouterState>,
+}
+
+impl SessionRouter {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: std::sync::Mutex::new(RouterState::default()),
+        }
+    }
+
+    /// Receiver of the messages of `session_id`
+    ///
+    /// Claiming a session that is already claimed returns the same receiver.
+    /// Once the router is closed, the returned receiver yields nothing.
+    pub(crate) fn claim(&self, session_id: &str) -> MessageReceiver {
+        let mut state = self.state.lock().unwrap();
+        if let Some(rx) = state
+            .sessions
+            .get(session_id)
+            .and_then(|channel| channel.rx.upgrade())
+        {
+            return rx;
+        }
+
+        let (tx, rx) = mpsc::channel(self.capacity);
+        let rx = Arc::new(Mutex::new(rx));
+        if !state.closed {
+            let channel = SessionChannel {
+                tx,
+                rx: Arc::downgrade(&rx),
+            };
+            state.sessions.insert(session_id.to_string(), channel);
+        }
+        rx
+    }
+
+    /// Receiver of the messages of `session_id`, if it is claimed
+    pub(crate) fn claimed(&self, session_id: &str) -> Option<MessageReceiver> {
+        let state = self.state.lock().unwrap();
+        state
+            .sessions
+            .get(session_id)
+            .and_then(|channel| channel.rx.upgrade())
+    }
+
+    /// Deliver `message` to its session's channel
+    ///
+    /// Returns the message back if its session is not claimed.
+    pub(crate) fn route(&self, message: Value) -> Option<Value> {
+        let Some(session_id) = message
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+        else {
+            return Some(message);
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let Some(channel) = state.sessions.get(&session_id) else {
+            return Some(message);
+        };
+        match channel.tx.try_send(message) {
+            Ok(()) => None,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                warn!(
+                    session_id = session_id.as_str(),
+                    "Session channel full, message dropped - consumer may be slow"
+                );
+                None
+            },
+            Err(mpsc::error::TrySendError::Closed(message)) => {
+                // Every handle of the session was dropped
+                state.sessions.remove(&session_id);
+                Some(message)
+            },
+        }
+    }
+
+    /// Sessions with a live receiver, sorted by ID
+    pub(crate) fn active_sessions(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let mut sessions: Vec<_> = state
+            .sessions
+            .iter()
+            .filter(|(_, channel)| channel.is_claimed())
+            .map(|(session_id, _)| session_id.clone())
+            .collect();
+        sessions.sort();
+        sessions
+    }
+
+    /// Take the claimed sessions, to be adopted by another router
+    ///
+    /// Messages routed afterwards are handed back as unclaimed.
+    pub(crate) fn detach(&self) -> DetachedSessions {
+        let mut state = self.state.lock().unwrap();
+        DetachedSessions(std::mem::take(&mut state.sessions))
+    }
+
+    /// Route the messages of sessions detached from another router here
+    ///
+    /// Their handles keep receiving, including messages still buffered.
+    pub(crate) fn adopt(&self, detached: DetachedSessions) {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return;
+        }
+        for (session_id, channel) in detached.0 {
+            if channel.is_claimed() {
+                state.sessions.entry(session_id).or_insert(channel);
+            }
+        }
+    }
+
+    /// Close the session channels, ending their receivers once drained
+    pub(crate) fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.sessions.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(session_id: &str, text: &str) -> Value {
+        json!({"type": "assistant", "session_id": session_id, "text": text})
+    }
+
+    fn try_recv(rx: &MessageReceiver) -> Option<Value> {
+        rx.try_lock().unwrap().try_recv().ok()
+    }
+
+    #[test]
+    fn test_routes_by_session_id() {
+        let router = SessionRouter::new(8);
+        let a = router.claim("a");
+        let b = router.claim("b");
+
+        assert_eq!(router.route(message("b", "to b")), None);
+        assert_eq!(router.route(message("a", "to a")), None);
+        assert_eq!(try_recv(&a), Some(message("a", "to a")));
+        assert_eq!(try_recv(&b), Some(message("b", "to b")));
+        assert_eq!(try_recv(&a), None);
+    }
+
+    #[test]
+    fn test_unclaimed_messages_are_handed_back() {
+        let router = SessionRouter::new(8);
+        let _a = router.claim("a");
+
+        let other = message("other", "hi");
+        assert_eq!(router.route(other.clone()), Some(other));
+        let no_session = json!({"type": "system"});
+        assert_eq!(router.route(no_session.clone()), Some(no_session));
+    }
+
+    #[test]
+    fn test_claim_is_shared_until_dropped() {
+        let router = SessionRouter::new(8);
+        let first = router.claim("a");
+        let second = router.claim("a");
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(Arc::ptr_eq(&first, &router.claimed("a").unwrap()));
+        assert_eq!(router.active_sessions(), vec!["a"]);
+
+        drop(first);
+        drop(second);
+        assert!(router.active_sessions().is_empty());
+        assert!(router.claimed("a").is_none());
+        let late = message("a", "late");
+        assert_eq!(router.route(late.clone()), Some(late));
+    }
+
+    #[test]
+    fn test_adopted_sessions_keep_buffered_messages() {
+        let old = SessionRouter::new(8);
+        let a = old.claim("a");
+        assert_eq!(old.route(message("a", "before")), None);
+
+        let new = SessionRouter::new(8);
+        new.adopt(old.detach());
+        old.close();
+        assert_eq!(new.route(message("a", "after")), None);
+        assert!(Arc::ptr_eq(&new.claim("a"), &a));
+
+        assert_eq!(try_recv(&a), Some(message("a", "before")));
+        assert_eq!(try_recv(&a), Some(message("a", "after")));
+    }
+
+    #[test]
+    fn test_close_ends_receivers() {
+        let router = SessionRouter::new(8);
+        let a = router.claim("a");
+        router.close();
+
+        assert!(a.try_lock().unwrap().is_closed());
+        assert!(router.claim("b").try_lock().unwrap().is_closed());
+        assert!(router.active_sessions().is_empty());
+    }
+}
@@ -0,0 +1,336 @@
+//! Where the Claude Code CLI is installed and how to start it
+//!
+//! On Windows, `npm i -g @anthropic-ai/claude-code` installs `claude.cmd` and
+//! `claude.ps1` shims rather than an executable, and those cannot be started
+//! as a process directly. [`CliInvocation::resolve`] runs the CLI's `cli.js`
+//! with Node when it finds it next to the shim, and goes through `cmd /C`
+//! otherwise. The lookups go through [`CliEnvironment`] so they can be tested
+//! on any platform.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command;
+
+/// The filesystem and environment variables, as seen by CLI discovery
+pub(crate) trait CliEnvironment {
+    /// Whether `path` is an existing file
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// The value of the environment variable `key`, if set and not empty
+    fn var(&self, key: &str) -> Option<String>;
+}
+
+/// The real filesystem and environment
+pub(crate) struct SystemEnvironment;
+
+impl CliEnvironment for SystemEnvironment {
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn var(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok().filter(|value| !value.is_empty())
+    }
+}
+
+/// Places the CLI is commonly installed, most specific first
+///
+/// `windows` selects the Windows locations: the native installer, npm's
+/// global prefix, nvm-windows and Volta.
+pub(crate) fn common_cli_paths(env: &dyn CliEnvironment, windows: bool) -> Vec<PathBuf> {
+    let home = env
+        .var("HOME")
+        .or_else(|| env.var("USERPROFILE"))
+        .map(PathBuf::from);
+    let mut paths = Vec::new();
+
+    if !windows {
+        paths.extend([
+            PathBuf::from("/usr/local/bin/claude"),
+            PathBuf::from("/opt/homebrew/bin/claude"),
+            PathBuf::from("/usr/bin/claude"),
+        ]);
+        if let Some(home) = &home {
+            paths.push(home.join(".local").join("bin").join("claude"));
+            paths.push(home.join("bin").join("claude"));
+        }
+        return paths;
+    }
+
+    let local_app_data = env
+        .var("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join("AppData").join("Local")));
+    let app_data = env.var("APPDATA").map(PathBuf::from).or_else(|| {
+        home.as_ref()
+            .map(|home| home.join("AppData").join("Roaming"))
+    });
+
+    if let Some(home) = &home {
+        paths.push(home.join(".local").join("bin").join("claude.exe"));
+    }
+    if let Some(local_app_data) = &local_app_data {
+        paths.push(
+            local_app_data
+                .join("Programs")
+                .join("Claude")
+                .join("claude.exe"),
+        );
+    }
+    // npm's default global prefix
+    if let Some(app_data) = &app_data {
+        paths.push(app_data.join("npm").join("claude.cmd"));
+    }
+    // nvm-windows links the active Node version, with its global packages, here
+    if let Some(symlink) = env.var("NVM_SYMLINK") {
+        paths.push(PathBuf::from(symlink).join("claude.cmd"));
+    }
+    // Volta installs executable shims
+    let volta_home = env
+        .var("VOLTA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| local_app_data.as_ref().map(|dir| dir.join("Volta")));
+    if let Some(volta_home) = volta_home {
+        paths.push(volta_home.join("bin").join("claude.exe"));
+    }
+
+    let program_files = env
+        .var("ProgramFiles")
+        .unwrap_or_else(|| "C:\\Program Files".to_string());
+    paths.extend([
+        Path::new(&program_files).join("nodejs").join("claude.cmd"),
+        Path::new(&program_files).join("Claude").join("claude.exe"),
+        PathBuf::from("C:\\Program Files (x86)\\Claude\\claude.exe"),
+    ]);
+    paths
+}
+
+/// The program and leading arguments that start the CLI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CliInvocation {
+    pub(crate) program: PathBuf,
+    pub(crate) args: Vec<OsString>,
+}
+
+impl CliInvocation {
+    /// Start `cli_path` as found, resolving npm shims on Windows
+    pub(crate) fn for_platform(cli_path: &Path) -> Self {
+        if cfg!(windows) {
+            Self::resolve(cli_path, &SystemEnvironment)
+        } else {
+            Self::direct(cli_path)
+        }
+    }
+
+    /// How to start the CLI at `cli_path` on Windows
+    ///
+    /// An extensionless or `.ps1` npm shim is replaced by the `.cmd` next to
+    /// it. A `.cmd` or `.bat` shim runs the `cli.js` of the package it belongs
+    /// to with Node (the `node.exe` next to the shim if there is one), which
+    /// avoids `cmd`'s quoting rules; without the package it runs through
+    /// `cmd /C`.
+    pub(crate) fn resolve(cli_path: &Path, env: &dyn CliEnvironment) -> Self {
+        let mut path = cli_path.to_path_buf();
+        if matches!(extension(&path).as_deref(), None | Some("ps1")) {
+            let shim = path.with_extension("cmd");
+            if env.is_file(&shim) {
+                path = shim;
+            }
+        }
+        if !matches!(extension(&path).as_deref(), Some("cmd" | "bat")) {
+            return Self::direct(&path);
+        }
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let script = dir
+            .join("node_modules")
+            .join("@anthropic-ai")
+            .join("claude-code")
+            .join("cli.js");
+        if env.is_file(&script) {
+            let node = dir.join("node.exe");
+            let program = if env.is_file(&node) {
+                node
+            } else {
+                PathBuf::from("node")
+            };
+            return Self {
+                program,
+                args: vec![script.into_os_string()],
+            };
+        }
+
+        Self {
+            program: PathBuf::from("cmd"),
+            args: vec!["/C".into(), path.into_os_string()],
+        }
+    }
+
+    fn direct(cli_path: &Path) -> Self {
+        Self {
+            program: cli_path.to_path_buf(),
+            args: Vec::new(),
+        }
+    }
+
+    /// A command starting the CLI, to which the CLI's own arguments are added
+    pub(crate) fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        command
+    }
+}
+
+/// The lowercase extension of `path`
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+
+    #[derive(Default)]
+    struct FakeEnvironment {
+        files: HashSet<PathBuf>,
+        vars: HashMap<String, String>,
+    }
+
+    impl FakeEnvironment {
+        fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+            self.files.insert(path.into());
+            self
+        }
+
+        fn with_var(mut self, key: &str, value: &str) -> Self {
+            self.vars.insert(key.to_string(), value.to_string());
+            self
+        }
+    }
+
+    impl CliEnvironment for FakeEnvironment {
+        fn is_file(&self, path: &Path) -> bool {
+            self.files.contains(path)
+        }
+
+        fn var(&self, key: &str) -> Option<String> {
+            self.vars.get(key).cloned()
+        }
+    }
+
+    fn npm_dir() -> PathBuf {
+        PathBuf::from("C:\\Users\\ada\\AppData\\Roaming").join("npm")
+    }
+
+    fn cli_js(dir: &Path) -> PathBuf {
+        dir.join("node_modules")
+            .join("@anthropic-ai")
+            .join("claude-code")
+            .join("cli.js")
+    }
+
+    #[test]
+    fn test_executables_start_directly() {
+        let env = FakeEnvironment::default();
+        let exe = PathBuf::from("C:\\Program Files\\Claude\\claude.exe");
+        assert_eq!(
+            CliInvocation::resolve(&exe, &env),
+            CliInvocation {
+                program: exe,
+                args: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn test_npm_shim_runs_cli_js_with_node() {
+        let shim = npm_dir().join("claude.cmd");
+        let env = FakeEnvironment::default()
+            .with_file(&shim)
+            .with_file(cli_js(&npm_dir()));
+        assert_eq!(
+            CliInvocation::resolve(&shim, &env),
+            CliInvocation {
+                program: PathBuf::from("node"),
+                args: vec![cli_js(&npm_dir()).into_os_string()],
+            }
+        );
+
+        // nvm-windows keeps node.exe next to the shims
+        let env = env.with_file(npm_dir().join("node.exe"));
+        let invocation = CliInvocation::resolve(&shim, &env);
+        assert_eq!(invocation.program, npm_dir().join("node.exe"));
+    }
+
+    #[test]
+    fn test_shim_without_package_runs_through_cmd() {
+        let shim = PathBuf::from("C:\\tools").join("CLAUDE.BAT");
+        let invocation = CliInvocation::resolve(&shim, &FakeEnvironment::default());
+        assert_eq!(invocation.program, PathBuf::from("cmd"));
+        assert_eq!(invocation.args, vec![OsString::from("/C"), shim.into()]);
+    }
+
+    #[test]
+    fn test_sh_and_ps1_shims_use_the_cmd_shim() {
+        let shim = npm_dir().join("claude.cmd");
+        let env = FakeEnvironment::default().with_file(&shim);
+        for found in [npm_dir().join("claude"), npm_dir().join("claude.ps1")] {
+            let invocation = CliInvocation::resolve(&found, &env);
+            assert_eq!(
+                invocation.args,
+                vec![OsString::from("/C"), shim.clone().into()]
+            );
+        }
+
+        // Without a shim next to it, the path is kept
+        let elsewhere = PathBuf::from("C:\\bin").join("claude");
+        assert_eq!(CliInvocation::resolve(&elsewhere, &env).program, elsewhere);
+    }
+
+    #[test]
+    fn test_windows_paths_cover_npm_nvm_and_volta() {
+        let env = FakeEnvironment::default()
+            .with_var("USERPROFILE", "C:\\Users\\ada")
+            .with_var("APPDATA", "C:\\Users\\ada\\AppData\\Roaming")
+            .with_var("NVM_SYMLINK", "C:\\nvm4w\\nodejs")
+            .with_var("VOLTA_HOME", "D:\\volta");
+        let paths = common_cli_paths(&env, true);
+
+        for expected in [
+            npm_dir().join("claude.cmd"),
+            PathBuf::from("C:\\nvm4w\\nodejs").join("claude.cmd"),
+            PathBuf::from("D:\\volta").join("bin").join("claude.exe"),
+            PathBuf::from("C:\\Program Files")
+                .join("Claude")
+                .join("claude.exe"),
+        ] {
+            assert!(paths.contains(&expected), "{expected:?} not in {paths:?}");
+        }
+        assert!(paths.iter().all(|path| !path.starts_with("/usr")));
+    }
+
+    #[test]
+    fn test_windows_paths_fall_back_to_the_profile() {
+        let env = FakeEnvironment::default().with_var("USERPROFILE", "C:\\Users\\ada");
+        let paths = common_cli_paths(&env, true);
+        let local = PathBuf::from("C:\\Users\\ada")
+            .join("AppData")
+            .join("Local");
+        let roaming = PathBuf::from("C:\\Users\\ada")
+            .join("AppData")
+            .join("Roaming");
+        assert!(paths.contains(&local.join("Volta").join("bin").join("claude.exe")));
+        assert!(paths.contains(&roaming.join("npm").join("claude.cmd")));
+    }
+
+    #[test]
+    fn test_unix_paths() {
+        let env = FakeEnvironment::default().with_var("HOME", "/home/ada");
+        let paths = common_cli_paths(&env, false);
+        assert_eq!(paths[0], PathBuf::from("/usr/local/bin/claude"));
+        assert!(paths.contains(&PathBuf::from("/home/ada/.local/bin/claude")));
+    }
+}
@@ -1,7 +1,12 @@
 //! Transport layer for communicating with Claude Code CLI
 
+pub(crate) mod cli_path;
+pub mod recording;
+pub mod socket;
 pub mod subprocess;
 mod trait_def;
 
+pub use recording::{FrameDirection, RecordedFrame, SessionRecorder};
+pub use socket::{SocketAddress, SocketTransport, TransportConfig};
 pub use subprocess::SubprocessTransport;
-pub use trait_def::Transport;
+pub use trait_def::{Transport, TransportWriter};
@@ -0,0 +1,377 @@
+//! Recording the frames exchanged with the CLI
+//!
+//! [`SessionRecorder`] wraps another [`Transport`] and appends each frame it
+//! passes on to a JSONL file, one [`RecordedFrame`] per line:
+//!
+//! ```text
+//! {"ts_ms":0,"direction":"out","frame":{"type":"control_request","request_id":"req_1_4f2a","request":{"subtype":"initialize"}}}
+//! {"ts_ms":412,"direction":"in","frame":{"type":"control_response","response":{"subtype":"success","request_id":"req_1_4f2a"}}}
+//! ```
+//!
+//! Set [`ClaudeAgentOptions::record_to`] to record `query()` and
+//! [`ClaudeClient`](crate::ClaudeClient) sessions, and replay the file with
+//! [`ReplayTransport`](crate::testing::ReplayTransport).
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::task::{Context, Poll, ready};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use futures::stream::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::{Transport, TransportWriter};
+use crate::errors::{ClaudeError, Result, TransportExitInfo};
+use crate::types::config::ClaudeAgentOptions;
+
+/// Which way a [`RecordedFrame`] went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrameDirection {
+    /// Received from the CLI
+    #[serde(rename = "in")]
+    Inbound,
+    /// Sent to the CLI
+    #[serde(rename = "out")]
+    Outbound,
+}
+
+/// One line of a session recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Milliseconds since the transport connected
+    pub ts_ms: u64,
+    /// Which way the frame went
+    pub direction: FrameDirection,
+    /// The frame; lines that are not JSON, like a one-shot prompt, are kept as strings
+    pub frame: Value,
+}
+
+impl RecordedFrame {
+    /// Read a recording from `path`
+    pub fn read_all(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    ClaudeError::InvalidConfig(format!(
+                        "Invalid recording {} line {}: {}",
+                        path.display(),
+                        index + 1,
+                        e
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// The recording file, shared by a recorder and its writer
+#[derive(Clone)]
+struct FrameLog {
+    file: Arc<StdMutex<BufWriter<File>>>,
+    path: PathBuf,
+    started: Instant,
+}
+
+impl FrameLog {
+    fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).map_err(|e| {
+            ClaudeError::InvalidConfig(format!(
+                "Cannot record the session to {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self {
+            file: Arc::new(StdMutex::new(BufWriter::new(file))),
+            path: path.to_path_buf(),
+            started: Instant::now(),
+        })
+    }
+
+    fn record(&self, direction: FrameDirection, frame: Value) {
+        let entry = RecordedFrame {
+            ts_ms: u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            direction,
+            frame,
+        };
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let written = serde_json::to_writer(&mut *file, &entry)
+            .map_err(io::Error::from)
+            .and_then(|()| file.write_all(b"\n"))
+            .and_then(|()| file.flush());
+        // A broken recording must not break the session
+        if let Err(e) = written {
+            warn!("Failed to record frame to {}: {}", self.path.display(), e);
+        }
+    }
+
+    fn record_line(&self, direction: FrameDirection, line: &[u8]) {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let frame = serde_json::from_str(line).unwrap_or_else(|_| Value::String(line.to_string()));
+        self.record(direction, frame);
+    }
+}
+
+/// Input side of a [`SessionRecorder`], recording each line it passes on
+struct RecordingWriter {
+    inner: Box<dyn AsyncWrite + Send + Unpin>,
+    log: FrameLog,
+    line: Vec<u8>,
+}
+
+impl AsyncWrite for RecordingWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let written = ready!(Pin::new(&mut self.inner).poll_write(cx, buf))?;
+        self.line.extend_from_slice(&buf[..written]);
+        while let Some(end) = self.line.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.line.drain(..=end).collect();
+            self.log.record_line(FrameDirection::Outbound, &line);
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let rest = std::mem::take(&mut self.line);
+        self.log.record_line(FrameDirection::Outbound, &rest);
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// A [`Transport`] that records every frame of the transport it wraps
+///
+/// Frames go to a JSONL file of [`RecordedFrame`]s, flushed line by line so a
+/// crashed session leaves a usable recording. Failing to write the file is
+/// logged and does not affect the session.
+///
+/// ```no_run
+/// use claude_agent_sdk::{
+///     ClaudeAgentOptions, ClaudeClient, SessionRecorder, SocketTransport, TransportConfig,
+/// };
+///
+/// # fn example() -> claude_agent_sdk::Result<()> {
+/// let transport = SocketTransport::new(TransportConfig::tcp("127.0.0.1:7000"));
+/// let recorder = SessionRecorder::new(Box::new(transport), "session.jsonl")?;
+/// let client = ClaudeClient::with_transport(Box::new(recorder), ClaudeAgentOptions::default());
+/// # Ok(())
+/// # }
+/// ```
+pub struct SessionRecorder {
+    inner: Box<dyn Transport>,
+    log: FrameLog,
+    writer: TransportWriter,
+    prompt: Option<Value>,
+}
+
+impl SessionRecorder {
+    /// Record `inner` to the file at `path`, replacing it
+    pub fn new(inner: Box<dyn Transport>, path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            inner,
+            log: FrameLog::create(path.as_ref())?,
+            writer: Arc::new(Mutex::new(None)),
+            prompt: None,
+        })
+    }
+
+    /// Record `prompt` as sent on connect, for a transport that writes its
+    /// prompt itself
+    pub(crate) fn with_prompt(mut self, prompt: Option<Value>) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    /// Close the input side of the wrapped transport, through the recording writer
+    async fn shut_input(&mut self) -> io::Result<()> {
+        match self.writer.lock().await.take() {
+            Some(mut writer) => writer.shutdown().await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for SessionRecorder {
+    async fn connect(&mut self) -> Result<()> {
+        self.log.started = Instant::now();
+        if let Some(prompt) = self.prompt.take() {
+            self.log.record(FrameDirection::Outbound, prompt);
+        }
+        self.inner.connect().await?;
+
+        // Writes through the wrapped transport's handle would bypass the recording
+        let inner = self.inner.writer().lock().await.take();
+        *self.writer.lock().await = inner.map(|inner| {
+            Box::new(RecordingWriter {
+                inner,
+                log: self.log.clone(),
+                line: Vec::new(),
+            }) as Box<dyn AsyncWrite + Send + Unpin>
+        });
+        Ok(())
+    }
+
+    async fn write(&mut self, data: &str) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        let writer = writer
+            .as_mut()
+            .ok_or_else(|| ClaudeError::Transport("Transport input has ended".to_string()))?;
+        let written: io::Result<()> = async {
+            writer.write_all(data.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            writer.flush().await
+        }
+        .await;
+        written.map_err(|e| ClaudeError::Transport(format!("Failed to write: {}", e)))
+    }
+
+    fn read_messages(
+        &mut self,
+    ) -> Pin<Box<dyn Stream<Item = Result<serde_json::Value>> + Send + '_>> {
+        let log = self.log.clone();
+        Box::pin(self.inner.read_messages().inspect(move |message| {
+            if let Ok(message) = message {
+                log.record(FrameDirection::Inbound, message.clone());
+            }
+        }))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        let _ = self.shut_input().await;
+        self.inner.close().await
+    }
+
+    fn is_ready(&self) -> bool {
+        self.inner.is_ready()
+    }
+
+    async fn end_input(&mut self) -> Result<()> {
+        self.shut_input()
+            .await
+            .map_err(|e| ClaudeError::Transport(format!("Failed to end input: {}", e)))?;
+        self.inner.end_input().await
+    }
+
+    fn writer(&self) -> TransportWriter {
+        Arc::clone(&self.writer)
+    }
+
+    async fn abort(&mut self) {
+        self.writer.lock().await.take();
+        self.inner.abort().await;
+    }
+
+    async fn terminate(&mut self) -> Result<()> {
+        self.inner.terminate().await
+    }
+
+    async fn kill(&mut self) {
+        self.writer.lock().await.take();
+        self.inner.kill().await;
+    }
+
+    async fn exit_info(&mut self) -> Option<TransportExitInfo> {
+        self.inner.exit_info().await
+    }
+
+    fn cli_version(&self) -> Option<semver::Version> {
+        self.inner.cli_version()
+    }
+}
+
+/// `transport`, wrapped in a [`SessionRecorder`] if [`ClaudeAgentOptions::record_to`] is set
+pub(crate) fn record_if_requested(
+    transport: Box<dyn Transport>,
+    options: &ClaudeAgentOptions,
+) -> Result<Box<dyn Transport>> {
+    match &options.record_to {
+        Some(path) => Ok(Box::new(SessionRecorder::new(transport, path)?)),
+        None => Ok(transport),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockTransport;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_records_both_directions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let mock = MockTransport::builder()
+            .on_user_message("hello")
+            .respond_result("done")
+            .build();
+        let mut recorder = SessionRecorder::new(Box::new(mock), &path)
+            .unwrap()
+            .with_prompt(Some(json!("prompt text")));
+
+        recorder.connect().await.unwrap();
+        let user = json!({"type": "user", "message": {"role": "user", "content": "hello"}});
+        recorder.write(&user.to_string()).await.unwrap();
+        recorder.end_input().await.unwrap();
+        let received: Vec<_> = recorder.read_messages().collect().await;
+        assert_eq!(received.len(), 1);
+
+        let frames = RecordedFrame::read_all(&path).unwrap();
+        let directions: Vec<_> = frames.iter().map(|frame| frame.direction).collect();
+        assert_eq!(
+            directions,
+            vec![
+                FrameDirection::Outbound,
+                FrameDirection::Outbound,
+                FrameDirection::Inbound
+            ]
+        );
+        assert_eq!(frames[0].frame, json!("prompt text"));
+        assert_eq!(frames[1].frame, user);
+        assert_eq!(frames[2].frame["type"], "result");
+        assert!(frames.windows(2).all(|pair| pair[0].ts_ms <= pair[1].ts_ms));
+    }
+
+    #[test]
+    fn test_read_all_reports_bad_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        std::fs::write(
+            &path,
+            "{\"ts_ms\":0,\"direction\":\"in\",\"frame\":{}}\n\nnot json\n",
+        )
+        .unwrap();
+
+        let error = RecordedFrame::read_all(&path).unwrap_err();
+        assert!(
+            matches!(error, ClaudeError::InvalidConfig(ref message) if message.contains("line 3")),
+            "{:?}",
+            error
+        );
+    }
+}
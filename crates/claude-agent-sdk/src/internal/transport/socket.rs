@@ -0,0 +1,381 @@
+//! Socket transport for a Claude Code CLI that is already running
+//!
+//! [`SocketTransport`] talks to a CLI started elsewhere, such as in a
+//! container, over a Unix domain socket or TCP instead of spawning one.
+
+use async_trait::async_trait;
+use futures::stream::Stream;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::errors::{ClaudeError, ConnectionError, Result};
+
+use super::subprocess::read_json_lines;
+use super::{Transport, TransportWriter};
+
+/// Default limit on the output read from the socket
+pub const DEFAULT_SOCKET_MAX_BUFFER_SIZE: usize = 10 * 1024 * 1024; // 10MB
+
+/// Default time between connection attempts
+pub const DEFAULT_SOCKET_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+type SocketReader = Arc<Mutex<Option<BufReader<Box<dyn AsyncRead + Send + Unpin>>>>>;
+
+/// Where a [`SocketTransport`] connects to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketAddress {
+    /// Path of a Unix domain socket
+    Unix(PathBuf),
+    /// TCP address such as `"127.0.0.1:7100"` or `"claude.internal:7100"`
+    Tcp(String),
+}
+
+impl std::fmt::Display for SocketAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SocketAddress::Unix(path) => write!(f, "unix:{}", path.display()),
+            SocketAddress::Tcp(address) => write!(f, "tcp:{}", address),
+        }
+    }
+}
+
+/// Configuration of a [`SocketTransport`]
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::TransportConfig;
+/// use std::time::Duration;
+///
+/// let config = TransportConfig::tcp("127.0.0.1:7100")
+///     .with_connect_attempts(10)
+///     .with_retry_delay(Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportConfig {
+    /// Address of the socket the CLI is served on
+    pub address: SocketAddress,
+    /// How often to try connecting before giving up, at least once
+    ///
+    /// Default: 1
+    pub connect_attempts: u32,
+    /// Time to wait between connection attempts
+    ///
+    /// Default: [`DEFAULT_SOCKET_RETRY_DELAY`]
+    pub retry_delay: Duration,
+    /// Most bytes read from the socket over the connection
+    ///
+    /// Default: [`DEFAULT_SOCKET_MAX_BUFFER_SIZE`]
+    pub max_buffer_size: usize,
+}
+
+impl TransportConfig {
+    /// Connect to a Unix domain socket at `path`
+    pub fn unix(path: impl Into<PathBuf>) -> Self {
+        Self::new(SocketAddress::Unix(path.into()))
+    }
+
+    /// Connect to TCP `address`, given as `host:port`
+    pub fn tcp(address: impl Into<String>) -> Self {
+        Self::new(SocketAddress::Tcp(address.into()))
+    }
+
+    fn new(address: SocketAddress) -> Self {
+        Self {
+            address,
+            connect_attempts: 1,
+            retry_delay: DEFAULT_SOCKET_RETRY_DELAY,
+            max_buffer_size: DEFAULT_SOCKET_MAX_BUFFER_SIZE,
+        }
+    }
+
+    /// Try connecting `attempts` times, e.g. while the CLI's container starts
+    pub fn with_connect_attempts(mut self, attempts: u32) -> Self {
+        self.connect_attempts = attempts;
+        self
+    }
+
+    /// Wait `delay` between connection attempts
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Fail once more than `max_buffer_size` bytes were read
+    pub fn with_max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+        self.max_buffer_size = max_buffer_size;
+        self
+    }
+}
+
+/// Transport to a CLI served on a Unix domain socket or TCP address
+///
+/// Connecting again after [`close`](Transport::close) opens a new connection.
+///
+/// # Protocol
+///
+/// The socket carries what the CLI reads on stdin and writes on stdout when
+/// started with `--input-format stream-json --output-format stream-json`: one
+/// JSON message per line in each direction. The SDK first sends the
+/// `initialize` control request, then control requests and user messages, and
+/// reads the CLI's messages until the server closes the connection. Ending
+/// input half-closes the socket, which the CLI sees as the end of stdin.
+///
+/// Whoever serves the socket starts the CLI, so options that become command
+/// line flags (model, system prompt, tools, permission mode, resuming a
+/// session) have to be given there. Hooks and SDK MCP servers go through the
+/// control protocol and run in this process. The CLI's stderr stays on the
+/// serving side.
+///
+/// `socat` can serve a fresh CLI process per connection:
+///
+/// ```bash
+/// socat UNIX-LISTEN:/tmp/claude.sock,fork \
+///     EXEC:"claude --input-format stream-json --output-format stream-json --verbose"
+///
+/// socat TCP-LISTEN:7100,reuseaddr,fork \
+///     EXEC:"claude --input-format stream-json --output-format stream-json --verbose"
+/// ```
+///
+/// # Example
+///
+/// ```no_run
+/// use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, TransportConfig};
+///
+/// # async fn example() -> claude_agent_sdk::Result<()> {
+/// let config = TransportConfig::unix("/tmp/claude.sock");
+/// let mut client = ClaudeClient::with_socket(config, ClaudeAgentOptions::default());
+/// client.connect().await?;
+/// client.query("Hello from the other side").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SocketTransport {
+    config: TransportConfig,
+    reader: SocketReader,
+    writer: TransportWriter,
+    ready: bool,
+}
+
+impl SocketTransport {
+    /// Create a transport for `config`; nothing is connected until `connect()`
+    pub fn new(config: TransportConfig) -> Self {
+        Self {
+            config,
+            reader: Arc::new(Mutex::new(None)),
+            writer: Arc::new(Mutex::new(None)),
+            ready: false,
+        }
+    }
+
+    /// The configuration this transport connects with
+    pub fn config(&self) -> &TransportConfig {
+        &self.config
+    }
+
+    /// Open the socket once, split into its read and write halves
+    async fn open(
+        &self,
+    ) -> std::io::Result<(
+        Box<dyn AsyncRead + Send + Unpin>,
+        Box<dyn tokio::io::AsyncWrite + Send + Unpin>,
+    )> {
+        match &self.config.address {
+            SocketAddress::Tcp(address) => {
+                let stream = tokio::net::TcpStream::connect(address.as_str()).await?;
+                stream.set_nodelay(true)?;
+                let (read, write) = stream.into_split();
+                Ok((Box::new(read), Box::new(write)))
+            },
+            #[cfg(unix)]
+            SocketAddress::Unix(path) => {
+                let stream = tokio::net::UnixStream::connect(path).await?;
+                let (read, write) = stream.into_split();
+                Ok((Box::new(read), Box::new(write)))
+            },
+            #[cfg(not(unix))]
+            SocketAddress::Unix(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Unix domain sockets are not supported on this platform",
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for SocketTransport {
+    async fn connect(&mut self) -> Result<()> {
+        let attempts = self.config.connect_attempts.max(1);
+        let mut attempt = 1;
+        let (read, write) = loop {
+            match self.open().await {
+                Ok(halves) => break halves,
+                Err(e) if attempt < attempts => {
+                    warn!(
+                        "Connecting to Claude CLI at {} failed (attempt {} of {}): {}",
+                        self.config.address, attempt, attempts, e
+                    );
+                    attempt += 1;
+                    tokio::time::sleep(self.config.retry_delay).await;
+                },
+                Err(e) => {
+                    return Err(ClaudeError::Connection(ConnectionError::new(format!(
+                        "Failed to connect to Claude CLI at {}: {}",
+                        self.config.address, e
+                    ))));
+                },
+            }
+        };
+
+        *self.reader.lock().await = Some(BufReader::new(read));
+        *self.writer.lock().await = Some(write);
+        self.ready = true;
+        Ok(())
+    }
+
+    async fn write(&mut self, data: &str) -> Result<()> {
+        let mut writer_guard = self.writer.lock().await;
+        if let Some(ref mut writer) = *writer_guard {
+            writer
+                .write_all(data.as_bytes())
+                .await
+                .map_err(|e| ClaudeError::Transport(format!("Failed to write to socket: {}", e)))?;
+            writer
+                .write_all(b"\n")
+                .await
+                .map_err(|e| ClaudeError::Transport(format!("Failed to write newline: {}", e)))?;
+            writer
+                .flush()
+                .await
+                .map_err(|e| ClaudeError::Transport(format!("Failed to flush socket: {}", e)))?;
+            Ok(())
+        } else {
+            Err(ClaudeError::Transport("socket not connected".to_string()))
+        }
+    }
+
+    fn read_messages(
+        &mut self,
+    ) -> Pin<Box<dyn Stream<Item = Result<serde_json::Value>> + Send + '_>> {
+        Box::pin(read_json_lines(
+            Arc::clone(&self.reader),
+            self.config.max_buffer_size,
+        ))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.lock().await.take() {
+            let _ = writer.shutdown().await;
+        }
+        self.reader.lock().await.take();
+        self.ready = false;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    async fn end_input(&mut self) -> Result<()> {
+        // Shutting down the write half sends FIN, the end of the CLI's stdin
+        if let Some(mut writer) = self.writer.lock().await.take() {
+            writer.shutdown().await.map_err(|e| {
+                ClaudeError::Transport(format!("Failed to half-close socket: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn writer(&self) -> TransportWriter {
+        Arc::clone(&self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_tcp_round_trip_and_half_close() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let line = lines.next_line().await.unwrap().unwrap();
+            write
+                .write_all(format!("{}\n\n{{\"type\":\"echo\"}}\n", line).as_bytes())
+                .await
+                .unwrap();
+            // Input ends with the client's half-close
+            assert_eq!(lines.next_line().await.unwrap(), None);
+        });
+
+        let mut transport = SocketTransport::new(TransportConfig::tcp(address));
+        transport.connect().await.unwrap();
+        assert!(transport.is_ready());
+        transport.write(r#"{"type":"user"}"#).await.unwrap();
+        transport.end_input().await.unwrap();
+
+        let messages: Vec<_> = transport.read_messages().collect().await;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].as_ref().unwrap()["type"], "user");
+        assert_eq!(messages[1].as_ref().unwrap()["type"], "echo");
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_buffer_size() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let line = format!("{{\"text\":\"{}\"}}\n", "x".repeat(100));
+            stream.write_all(line.as_bytes()).await.unwrap();
+            let _ = stream.read(&mut [0; 1]).await;
+        });
+
+        let config = TransportConfig::tcp(address).with_max_buffer_size(64);
+        let mut transport = SocketTransport::new(config);
+        transport.connect().await.unwrap();
+        let first = transport.read_messages().next().await.unwrap();
+        assert!(
+            matches!(first, Err(ClaudeError::Transport(ref message)) if message.contains("64"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_retries_until_listening() {
+        // Reserve a free port, then start listening on it only after a delay
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            let listener = TcpListener::bind(address).await.unwrap();
+            let _connection = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        });
+
+        let config = TransportConfig::tcp(address.to_string())
+            .with_connect_attempts(20)
+            .with_retry_delay(Duration::from_millis(50));
+        let mut transport = SocketTransport::new(config);
+        transport.connect().await.unwrap();
+
+        let config = TransportConfig::tcp("127.0.0.1:1").with_retry_delay(Duration::ZERO);
+        let error = SocketTransport::new(config).connect().await.unwrap_err();
+        assert!(matches!(error, ClaudeError::Connection(_)), "{:?}", error);
+    }
+}
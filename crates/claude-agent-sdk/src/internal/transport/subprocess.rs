@@ -1,32 +1,324 @@
 //! Subprocess transport implementation for Claude Code CLI
 
 use async_trait::async_trait;
-use futures::stream::Stream;
+use futures::stream::{Stream, StreamExt};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::process::Stdio;
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdout, Command};
 use tokio::sync::Mutex;
-use tracing::warn;
+use tracing::{debug, warn};
 
 use crate::errors::{
-    ClaudeError, CliNotFoundError, ConnectionError, JsonDecodeError, ProcessError, Result,
+    ClaudeError, CliNotFoundError, ConnectionError, ExitStage, JsonDecodeError, ProcessError,
+    Result, TransportExitInfo,
 };
-use crate::types::config::ClaudeAgentOptions;
-use crate::types::messages::UserContentBlock;
+use crate::secrets::{ExposeSecret, SecretString, resolve_secret_env};
+use crate::types::config::{
+    ClaudeAgentOptions, ConnectPhase, ConnectProgress, ConnectProgressCallback,
+};
+use crate::types::messages::{ConversationTurn, UserContentBlock};
+use crate::types::plugin::discover_valid;
 use crate::version::{
-    ENTRYPOINT, MIN_CLI_VERSION, SDK_VERSION, SKIP_VERSION_CHECK_ENV, check_version,
+    BUDGET_MIN_VERSION, CliCapabilities, ENTRYPOINT, FORK_SESSION_MIN_VERSION, MIN_CLI_VERSION,
+    PARTIAL_MESSAGES_MIN_VERSION, SDK_VERSION, SKIP_VERSION_CHECK_ENV, check_version,
+    parse_cli_version,
 };
 
-use super::Transport;
+use super::cli_path::{CliInvocation, SystemEnvironment, common_cli_paths};
+use super::recording::SessionRecorder;
+use super::{Transport, TransportWriter};
 
-use crate::internal::cli_installer::{CliInstaller, InstallProgress};
+use crate::internal::cli_installer::{CliInstaller, InstallError, InstallProgress};
 
 const DEFAULT_MAX_BUFFER_SIZE: usize = 10 * 1024 * 1024; // 10MB
 
+/// How long to wait for the CLI to exit after its stdout closed
+const EXIT_STATUS_WAIT: Duration = Duration::from_millis(500);
+
+/// How much of the CLI's stderr output is kept for error reporting
+const STDERR_TAIL_BYTES: usize = 8 * 1024;
+
+/// What the CLI writes to stderr when asked to resume a session it doesn't know
+const SESSION_NOT_FOUND_MARKER: &str = "No conversation found with session ID";
+
+/// Flags whose values are left out of command lines in errors, as they may hold secrets
+const ELIDED_FLAGS: &[&str] = &["--settings", "--mcp-config"];
+
+/// The end of what the CLI wrote to stderr
+#[derive(Clone)]
+pub(crate) struct StderrTail {
+    text: Arc<std::sync::Mutex<String>>,
+    closed: Arc<tokio::sync::watch::Sender<bool>>,
+}
+
+impl StderrTail {
+    fn new() -> Self {
+        Self {
+            text: Arc::new(std::sync::Mutex::new(String::new())),
+            closed: Arc::new(tokio::sync::watch::Sender::new(false)),
+        }
+    }
+
+    fn push(&self, line: &str) {
+        let mut text = self.text.lock().unwrap();
+        text.push_str(line);
+        if text.len() > STDERR_TAIL_BYTES {
+            let mut cut = text.len() - STDERR_TAIL_BYTES;
+            while !text.is_char_boundary(cut) {
+                cut += 1;
+            }
+            text.drain(..cut);
+        }
+    }
+
+    fn close(&self) {
+        self.closed.send_replace(true);
+    }
+
+    /// The output so far, waiting briefly for stderr to close first
+    pub(crate) async fn read(&self) -> String {
+        let mut closed = self.closed.subscribe();
+        let _ = tokio::time::timeout(EXIT_STATUS_WAIT, closed.wait_for(|closed| *closed)).await;
+        self.text.lock().unwrap().clone()
+    }
+}
+
+/// What is known about the CLI process, for explaining why it failed
+///
+/// Shared with the transport, so it stays usable after the transport has been
+/// handed to the control protocol.
+#[derive(Clone)]
+pub(crate) struct ProcessDiagnostics {
+    stderr: StderrTail,
+    command: Arc<std::sync::Mutex<Option<String>>>,
+    output_seen: Arc<AtomicBool>,
+    resume: Option<String>,
+}
+
+impl ProcessDiagnostics {
+    fn new(resume: Option<String>) -> Self {
+        Self {
+            stderr: StderrTail::new(),
+            command: Arc::new(std::sync::Mutex::new(None)),
+            output_seen: Arc::new(AtomicBool::new(false)),
+            resume,
+        }
+    }
+
+    /// `error`, or the failure of the CLI behind it
+    ///
+    /// That is [`ClaudeError::SessionNotFound`] if the CLI failed to resume its
+    /// session, or a [`ProcessError`] with the end of its stderr if it exited
+    /// with a failure before writing any output.
+    pub(crate) async fn explain(
+        &self,
+        error: ClaudeError,
+        exit: Option<TransportExitInfo>,
+    ) -> ClaudeError {
+        let stderr = self.stderr.read().await;
+        if let Some(session_id) = &self.resume
+            && stderr.contains(SESSION_NOT_FOUND_MARKER)
+        {
+            return ClaudeError::SessionNotFound(session_id.clone());
+        }
+
+        let Some(exit) = exit.filter(|exit| exit.code != Some(0)) else {
+            return error;
+        };
+        if self.stage() != ExitStage::BeforeHandshake {
+            return error;
+        }
+        let process_error = ClaudeError::Process(self.exit_error(exit, stderr));
+        match error {
+            ClaudeError::ConnectFailed { phase, .. } => ClaudeError::ConnectFailed {
+                phase,
+                source: Box::new(process_error),
+            },
+            _ => process_error,
+        }
+    }
+
+    fn stage(&self) -> ExitStage {
+        if self.output_seen.load(Ordering::SeqCst) {
+            ExitStage::MidConversation
+        } else {
+            ExitStage::BeforeHandshake
+        }
+    }
+
+    /// Error for a CLI that exited with a failure
+    fn exit_error(&self, exit: TransportExitInfo, stderr: String) -> ProcessError {
+        let stage = self.stage();
+        let command = self.command.lock().unwrap().clone();
+
+        let mut message = format!("Claude CLI {} {}", exit, stage);
+        if let Some(command) = &command {
+            message.push_str(&format!("\ncommand: {}", command));
+        }
+        let stderr = stderr.trim_end();
+        if !stderr.is_empty() {
+            message.push_str(&format!("\nstderr:\n{}", stderr));
+        }
+
+        ProcessError {
+            message,
+            exit_code: exit.code,
+            stderr: (!stderr.is_empty()).then(|| stderr.to_string()),
+            command,
+            stage: Some(stage),
+        }
+    }
+}
+
+/// `cli_path` and `args` as a shell command line, with values of [`ELIDED_FLAGS`]
+/// and of user-supplied extra flags left out
+fn command_line(cli_path: &Path, args: &[String], extra_flags: &[String]) -> String {
+    let mut parts = vec![shell_quote(&cli_path.display().to_string())];
+    let mut elide_next = false;
+    for arg in args {
+        if elide_next && !arg.starts_with("--") {
+            parts.push("<elided>".to_string());
+        } else {
+            parts.push(shell_quote(arg));
+        }
+        elide_next = ELIDED_FLAGS.contains(&arg.as_str()) || extra_flags.contains(arg);
+    }
+    parts.join(" ")
+}
+
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@+".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Report `progress` to the connect progress callback, if any
+pub(crate) fn report_connect_progress(
+    callback: Option<&ConnectProgressCallback>,
+    progress: ConnectProgress,
+) {
+    if let Some(callback) = callback {
+        callback(progress);
+    }
+}
+
+/// Read newline-delimited JSON messages from `reader` until it ends
+///
+/// Fails once more than `max_buffer_size` bytes were read. Does nothing if
+/// `reader` is `None`.
+pub(crate) fn read_json_lines<R>(
+    reader: Arc<Mutex<Option<R>>>,
+    max_buffer_size: usize,
+) -> impl Stream<Item = Result<serde_json::Value>> + Send
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+{
+    async_stream::stream! {
+        let mut reader_guard = reader.lock().await;
+        if let Some(ref mut reader) = *reader_guard {
+            let mut line = String::new();
+            let mut buffer_size = 0;
+
+            loop {
+                line.clear();
+                match reader.read_line(&mut line).await {
+                    Ok(0) => {
+                        // EOF
+                        break;
+                    }
+                    Ok(n) => {
+                        buffer_size += n;
+                        if buffer_size > max_buffer_size {
+                            yield Err(ClaudeError::Transport(format!(
+                                "Buffer size exceeded maximum of {} bytes",
+                                max_buffer_size
+                            )));
+                            break;
+                        }
+
+                        let trimmed = line.trim();
+                        if trimmed.is_empty() {
+                            continue;
+                        }
+
+                        match serde_json::from_str::<serde_json::Value>(trimmed) {
+                            Ok(json) => {
+                                yield Ok(json);
+                            }
+                            Err(e) => {
+                                yield Err(ClaudeError::JsonDecode(JsonDecodeError::from_serde(
+                                    trimmed,
+                                    &e,
+                                )));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        yield Err(ClaudeError::Transport(format!("Failed to read line: {}", e)));
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Await the next message, failing with [`ClaudeError::MessageTimeout`] after `timeout`
+pub(crate) async fn within_message_timeout<T>(
+    timeout: Option<Duration>,
+    next: impl std::future::Future<Output = T>,
+) -> Result<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, next)
+            .await
+            .map_err(|_| ClaudeError::MessageTimeout { timeout }),
+        None => Ok(next.await),
+    }
+}
+
+/// Run one connect phase under its time limit
+///
+/// Reports the phase through `callback` and names it in the returned error.
+pub(crate) async fn run_connect_phase<T>(
+    callback: Option<&ConnectProgressCallback>,
+    phase: ConnectPhase,
+    timeout: Duration,
+    fut: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    report_connect_progress(callback, ConnectProgress::Started(phase));
+    let start = Instant::now();
+
+    match tokio::time::timeout(timeout, fut).await {
+        Ok(Ok(value)) => {
+            report_connect_progress(
+                callback,
+                ConnectProgress::Completed {
+                    phase,
+                    elapsed: start.elapsed(),
+                },
+            );
+            Ok(value)
+        },
+        Ok(Err(e)) => Err(ClaudeError::ConnectFailed {
+            phase,
+            source: Box::new(e),
+        }),
+        Err(_) => Err(ClaudeError::ConnectTimeout { phase, timeout }),
+    }
+}
+
 /// Query prompt type
 #[derive(Clone)]
 pub enum QueryPrompt {
@@ -34,10 +326,37 @@ pub enum QueryPrompt {
     Text(String),
     /// Structured content blocks (supports images and text)
     Content(Vec<UserContentBlock>),
+    /// Prior conversation turns, replayed as one stream-json message each
+    History(Vec<ConversationTurn>),
     /// Streaming mode (no initial prompt)
     Streaming,
 }
 
+impl QueryPrompt {
+    /// The stream-json user message for `blocks`
+    fn content_message(blocks: &[UserContentBlock]) -> serde_json::Value {
+        serde_json::json!({
+            "type": "user",
+            "message": {
+                "role": "user",
+                "content": blocks
+            }
+        })
+    }
+
+    /// What is written to the CLI on connect, as a recorded frame
+    fn frame(&self) -> Option<serde_json::Value> {
+        match self {
+            QueryPrompt::Text(text) => Some(serde_json::Value::String(text.clone())),
+            QueryPrompt::Content(blocks) => Some(Self::content_message(blocks)),
+            QueryPrompt::History(turns) => Some(serde_json::Value::Array(
+                turns.iter().map(|turn| turn.to_stream_json(None)).collect(),
+            )),
+            QueryPrompt::Streaming => None,
+        }
+    }
+}
+
 impl From<String> for QueryPrompt {
     fn from(text: String) -> Self {
         QueryPrompt::Text(text)
@@ -63,15 +382,18 @@ pub struct SubprocessTransport {
     options: ClaudeAgentOptions,
     prompt: QueryPrompt,
     process: Option<Child>,
-    pub(crate) stdin: Arc<Mutex<Option<ChildStdin>>>,
+    stdin: TransportWriter,
     pub(crate) stdout: Arc<Mutex<Option<BufReader<ChildStdout>>>>,
     max_buffer_size: usize,
     ready: bool,
+    diagnostics: ProcessDiagnostics,
+    /// Detected by the version check in `connect`
+    cli_version: Option<semver::Version>,
 }
 
 impl SubprocessTransport {
     /// Create a new subprocess transport
-    pub fn new(prompt: QueryPrompt, options: ClaudeAgentOptions) -> Result<Self> {
+    pub fn new(prompt: QueryPrompt, mut options: ClaudeAgentOptions) -> Result<Self> {
         // Validate cwd early, before CLI lookup, for better error messages
         if let Some(ref cwd) = options.cwd {
             if !cwd.exists() {
@@ -88,14 +410,42 @@ impl SubprocessTransport {
             }
         }
 
+        if let Some(template) = options.system_prompt_template.take() {
+            if options.system_prompt.is_some() {
+                return Err(ClaudeError::InvalidConfig(
+                    "system_prompt and system_prompt_template are both set".to_string(),
+                ));
+            }
+            options.system_prompt = Some(template.build()?);
+        }
+
+        let progress = options.connect_progress.as_ref();
         let cli_path = if let Some(ref path) = options.cli_path {
+            report_connect_progress(
+                progress,
+                ConnectProgress::Skipped {
+                    phase: ConnectPhase::Discovery,
+                    reason: "cli_path is set".to_string(),
+                },
+            );
             path.clone()
         } else {
+            report_connect_progress(progress, ConnectProgress::Started(ConnectPhase::Discovery));
+            let start = Instant::now();
             // Try to find CLI, and if not found and auto-install is enabled, attempt installation
-            Self::find_cli_with_auto_install(&options)?
+            let path = Self::find_cli_with_auto_install(&options)?;
+            report_connect_progress(
+                progress,
+                ConnectProgress::Completed {
+                    phase: ConnectPhase::Discovery,
+                    elapsed: start.elapsed(),
+                },
+            );
+            path
         };
 
         let cwd = options.cwd.clone().or_else(|| std::env::current_dir().ok());
+        let resume = options.resume.clone();
         let max_buffer_size = options.max_buffer_size.unwrap_or(DEFAULT_MAX_BUFFER_SIZE);
 
         Ok(Self {
@@ -108,9 +458,39 @@ impl SubprocessTransport {
             stdout: Arc::new(Mutex::new(None)),
             max_buffer_size,
             ready: false,
+            diagnostics: ProcessDiagnostics::new(resume),
+            cli_version: None,
         })
     }
 
+    /// Features of the CLI, all of them until its version is known
+    pub fn capabilities(&self) -> CliCapabilities {
+        CliCapabilities::for_optional_version(self.cli_version.as_ref())
+    }
+
+    /// Stderr, command line and progress of the CLI, for explaining its failures
+    pub(crate) fn diagnostics(&self) -> ProcessDiagnostics {
+        self.diagnostics.clone()
+    }
+
+    /// The transport, boxed and wrapped in a [`SessionRecorder`] if
+    /// [`ClaudeAgentOptions::record_to`] is set
+    pub(crate) fn into_boxed(self) -> Result<Box<dyn Transport>> {
+        let Some(path) = self.options.record_to.clone() else {
+            return Ok(Box::new(self));
+        };
+        let prompt = self.prompt.frame();
+        Ok(Box::new(
+            SessionRecorder::new(Box::new(self), path)?.with_prompt(prompt),
+        ))
+    }
+
+
+    /// Command starting the CLI, see [`CliInvocation::for_platform`]
+    fn cli_command(&self) -> Command {
+        CliInvocation::for_platform(&self.cli_path).command()
+    }
+
     /// Find the Claude CLI executable
     fn find_cli() -> Result<PathBuf> {
         // Strategy 1: Try executing 'claude' directly from PATH
@@ -154,52 +534,11 @@ impl SubprocessTransport {
         }
 
         // Strategy 4: Check common installation locations
-        // Get home directory for path expansion
-        let home_dir = std::env::var("HOME")
-            .or_else(|_| std::env::var("USERPROFILE")) // Windows fallback
-            .ok()
-            .map(PathBuf::from);
-
-        // Common installation locations
-        let mut common_paths: Vec<PathBuf> = vec![];
-
-        // Unix-like paths
-        #[cfg(not(target_os = "windows"))]
-        {
-            common_paths.extend(vec![
-                PathBuf::from("/usr/local/bin/claude"),
-                PathBuf::from("/opt/homebrew/bin/claude"),
-                PathBuf::from("/usr/bin/claude"),
-            ]);
-
-            // Add home-relative paths if home directory is available
-            if let Some(ref home) = home_dir {
-                common_paths.push(home.join(".local/bin/claude"));
-                common_paths.push(home.join("bin/claude"));
-            }
-        }
-
-        // Windows paths
-        #[cfg(target_os = "windows")]
+        if let Some(path) = common_cli_paths(&SystemEnvironment, cfg!(windows))
+            .into_iter()
+            .find(|path| path.is_file())
         {
-            if let Some(ref home) = home_dir {
-                common_paths.extend(vec![
-                    home.join("AppData\\Local\\Programs\\Claude\\claude.exe"),
-                    home.join("AppData\\Roaming\\npm\\claude.cmd"),
-                    home.join("AppData\\Roaming\\npm\\claude.exe"),
-                ]);
-            }
-            common_paths.extend(vec![
-                PathBuf::from("C:\\Program Files\\Claude\\claude.exe"),
-                PathBuf::from("C:\\Program Files (x86)\\Claude\\claude.exe"),
-            ]);
-        }
-
-        // Check each common path
-        for path in common_paths {
-            if path.exists() && path.is_file() {
-                return Ok(path);
-            }
+            return Ok(path);
         }
 
         // Strategy 5: Check if CLAUDE_CLI_PATH environment variable is set
@@ -216,38 +555,60 @@ impl SubprocessTransport {
         )))
     }
 
+    /// Find the Claude CLI executable, giving up after `timeout`
+    ///
+    /// The lookup runs `claude --version`, which may hang; it runs on a separate
+    /// thread that is left behind if the time limit is exceeded.
+    fn find_cli_with_timeout(timeout: Duration) -> Result<PathBuf> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(Self::find_cli());
+        });
+
+        rx.recv_timeout(timeout)
+            .map_err(|_| ClaudeError::ConnectTimeout {
+                phase: ConnectPhase::Discovery,
+                timeout,
+            })?
+    }
+
     /// Find CLI with auto-install support
     ///
-    /// First attempts standard CLI lookup; if that fails and auto-install is enabled, attempts installation
+    /// First attempts standard CLI lookup; if that fails and auto-install is enabled, attempts installation.
+    /// A pinned `cli_version` skips the lookup and always goes through the installer.
     fn find_cli_with_auto_install(options: &ClaudeAgentOptions) -> Result<PathBuf> {
-        // First attempt standard CLI lookup
-        match Self::find_cli() {
-            Ok(path) => return Ok(path),
-            Err(_) => {
-                // CLI not found, check if auto-install is enabled
-                let auto_install = options.auto_install_cli
-                    || std::env::var("CLAUDE_AUTO_INSTALL_CLI")
-                        .ok()
-                        .and_then(|v| {
-                            let v_lower = v.to_lowercase();
-                            if v_lower == "true" || v_lower == "1" || v_lower == "yes" {
-                                Some(true)
-                            } else {
-                                None
-                            }
-                        })
-                        .unwrap_or(false);
-
-                if !auto_install {
-                    // Auto-install not enabled, return the original error
-                    return Err(ClaudeError::CliNotFound(CliNotFoundError::new(
-                        "Claude Code CLI not found. Please ensure 'claude' is in your PATH or set CLAUDE_CLI_PATH environment variable.",
-                        None,
-                    )));
-                }
+        let auto_install = options.auto_install_cli
+            || std::env::var("CLAUDE_AUTO_INSTALL_CLI")
+                .ok()
+                .and_then(|v| {
+                    let v_lower = v.to_lowercase();
+                    if v_lower == "true" || v_lower == "1" || v_lower == "yes" {
+                        Some(true)
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or(false);
 
-                // Auto-install is enabled
-                tracing::info!("🔧 CLI not found, auto-install enabled - attempting installation...");
+        if auto_install && options.cli_version.is_some() {
+            tracing::info!("🔧 CLI version pinned, installing it from the cache or the releases");
+        } else {
+            // First attempt standard CLI lookup
+            match Self::find_cli_with_timeout(options.connect_timeouts.discovery) {
+                Ok(path) => return Ok(path),
+                Err(e @ ClaudeError::ConnectTimeout { .. }) => return Err(e),
+                Err(_) => {
+                    if !auto_install {
+                        // Auto-install not enabled, return the original error
+                        return Err(ClaudeError::CliNotFound(CliNotFoundError::new(
+                            "Claude Code CLI not found. Please ensure 'claude' is in your PATH or set CLAUDE_CLI_PATH environment variable.",
+                            None,
+                        )));
+                    }
+
+                    // Auto-install is enabled
+                    tracing::info!("🔧 CLI not found, auto-install enabled - attempting installation...");
+                }
             }
         }
 
@@ -258,7 +619,13 @@ impl SubprocessTransport {
             let rt = tokio::runtime::Runtime::new()
                 .map_err(|e| ClaudeError::InternalError(format!("Failed to create runtime: {}", e)))?;
 
-            let installer = CliInstaller::new(true);
+            let mut installer = CliInstaller::new(true);
+            if let Some(ref version) = installer_options.cli_version {
+                installer = installer.with_version(version.clone());
+            }
+            if let Some(ref cache_dir) = installer_options.cli_cache_dir {
+                installer = installer.with_cache_dir(cache_dir.clone());
+            }
             let installer = if let Some(ref callback) = installer_options.cli_install_callback {
                 installer.with_progress_callback(callback.clone())
             } else {
@@ -279,6 +646,9 @@ impl SubprocessTransport {
                         InstallProgress::Installing(msg) => {
                             tracing::info!("🔧 {}", msg);
                         }
+                        InstallProgress::Verifying(msg) => {
+                            tracing::info!("🔐 {}", msg);
+                        }
                         InstallProgress::Done(path) => {
                             tracing::info!("✅ Installation complete: {}", path.display());
                         }
@@ -290,13 +660,31 @@ impl SubprocessTransport {
                 installer.with_progress_callback(default_callback)
             };
 
-            rt.block_on(installer.install_if_needed())
-                .map_err(|e| ClaudeError::InternalError(format!("Auto-install failed: {}", e)))
+            rt.block_on(installer.install_if_needed()).map_err(|e| {
+                // Keep installer errors such as a checksum mismatch recognizable
+                if e.downcast_ref::<InstallError>().is_some() {
+                    e
+                } else {
+                    ClaudeError::InternalError(format!("Auto-install failed: {}", e))
+                }
+            })
         })
         .join()
         .map_err(|_| ClaudeError::InternalError("Auto-install thread panicked".to_string()))?
     }
 
+    /// Note a flag left out because the CLI is older than `min_version`
+    fn log_unsupported(&self, flag: &str, min_version: &str) {
+        debug!(
+            "Omitting {} for Claude Code CLI {}, which predates it ({} is needed)",
+            flag,
+            self.cli_version
+                .as_ref()
+                .map_or_else(|| "unknown".to_string(), ToString::to_string),
+            min_version
+        );
+    }
+
     /// Build command arguments from options
     fn build_command(&self) -> Vec<String> {
         let mut args = vec![
@@ -308,7 +696,7 @@ impl SubprocessTransport {
         // For streaming mode or content mode, enable stream-json input
         if matches!(
             self.prompt,
-            QueryPrompt::Streaming | QueryPrompt::Content(_)
+            QueryPrompt::Streaming | QueryPrompt::Content(_) | QueryPrompt::History(_)
         ) {
             args.push("--input-format".to_string());
             args.push("stream-json".to_string());
@@ -407,10 +795,16 @@ impl SubprocessTransport {
             args.push(betas.join(","));
         }
 
+        let capabilities = self.capabilities();
+
         // Add max budget USD
         if let Some(max_budget) = self.options.max_budget_usd {
-            args.push("--max-budget-usd".to_string());
-            args.push(max_budget.to_string());
+            if capabilities.supports_budget {
+                args.push("--max-budget-usd".to_string());
+                args.push(max_budget.to_string());
+            } else {
+                self.log_unsupported("--max-budget-usd", BUDGET_MIN_VERSION);
+            }
         }
 
         // Add max thinking tokens
@@ -467,12 +861,20 @@ impl SubprocessTransport {
 
         // Add include partial messages
         if self.options.include_partial_messages {
-            args.push("--include-partial-messages".to_string());
+            if capabilities.supports_partial_messages {
+                args.push("--include-partial-messages".to_string());
+            } else {
+                self.log_unsupported("--include-partial-messages", PARTIAL_MESSAGES_MIN_VERSION);
+            }
         }
 
         // Add fork session
         if self.options.fork_session {
-            args.push("--fork-session".to_string());
+            if capabilities.supports_fork_session {
+                args.push("--fork-session".to_string());
+            } else {
+                self.log_unsupported("--fork-session", FORK_SESSION_MIN_VERSION);
+            }
         }
 
         // Add agent definitions
@@ -498,8 +900,12 @@ impl SubprocessTransport {
             args.push(sources_str.join(","));
         }
 
-        // Add plugins
-        for plugin in &self.options.plugins {
+        // Add plugins, then the valid ones found in plugins_dir
+        let discovered = match &self.options.plugins_dir {
+            Some(dir) => discover_valid(&self.options.resolve_in_cwd(dir)),
+            None => Vec::new(),
+        };
+        for plugin in self.options.plugins.iter().chain(&discovered) {
             if let Some(path) = plugin.path() {
                 args.push("--plugin-dir".to_string());
                 args.push(path.display().to_string());
@@ -577,22 +983,60 @@ impl SubprocessTransport {
     }
 
     /// Check Claude CLI version
-    async fn check_claude_version(&self) -> Result<()> {
+    ///
+    /// A CLI that does not answer `--version` within
+    /// [`ConnectTimeouts::version_check`](crate::types::config::ConnectTimeouts::version_check)
+    /// is killed and the check is skipped with a warning.
+    async fn check_claude_version(&mut self) -> Result<()> {
+        let phase = ConnectPhase::VersionCheck;
+        let progress = self.options.connect_progress.as_ref();
+
         // Skip if environment variable is set
         if std::env::var(SKIP_VERSION_CHECK_ENV).is_ok() {
+            report_connect_progress(
+                progress,
+                ConnectProgress::Skipped {
+                    phase,
+                    reason: format!("{} is set", SKIP_VERSION_CHECK_ENV),
+                },
+            );
             return Ok(());
         }
 
-        let output = Command::new(&self.cli_path)
+        report_connect_progress(progress, ConnectProgress::Started(phase));
+        let start = Instant::now();
+        let timeout = self.options.connect_timeouts.version_check;
+
+        let output = self
+            .cli_command()
             .arg("--version")
-            .output()
-            .await
-            .map_err(|e| {
-                ClaudeError::Connection(ConnectionError::new(format!(
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = match tokio::time::timeout(timeout, output).await {
+            Ok(output) => output.map_err(|e| ClaudeError::ConnectFailed {
+                phase,
+                source: Box::new(ClaudeError::Connection(ConnectionError::new(format!(
                     "Failed to get Claude version: {}",
                     e
-                )))
-            })?;
+                )))),
+            })?,
+            Err(_) => {
+                warn!(
+                    "Claude Code CLI ({}) did not answer --version within {:?}; skipping version check",
+                    self.cli_path.display(),
+                    timeout
+                );
+                report_connect_progress(
+                    progress,
+                    ConnectProgress::Skipped {
+                        phase,
+                        reason: format!("timed out after {:?}", timeout),
+                    },
+                );
+                return Ok(());
+            },
+        };
 
         let version_output = String::from_utf8_lossy(&output.stdout);
         let version = version_output
@@ -601,6 +1045,7 @@ impl SubprocessTransport {
             .and_then(|line| line.split_whitespace().next())
             .unwrap_or("")
             .trim();
+        self.cli_version = parse_cli_version(&version_output);
 
         if !check_version(version) {
             warn!(
@@ -611,6 +1056,13 @@ impl SubprocessTransport {
             );
         }
 
+        report_connect_progress(
+            progress,
+            ConnectProgress::Completed {
+                phase,
+                elapsed: start.elapsed(),
+            },
+        );
         Ok(())
     }
 
@@ -623,6 +1075,13 @@ impl SubprocessTransport {
             SDK_VERSION.to_string(),
         );
 
+        if let Some(config_dir) = &self.options.config_dir {
+            env.insert(
+                "CLAUDE_CONFIG_DIR".to_string(),
+                config_dir.display().to_string(),
+            );
+        }
+
         // Enable file checkpointing if requested
         if self.options.enable_file_checkpointing {
             env.insert(
@@ -633,34 +1092,61 @@ impl SubprocessTransport {
 
         env
     }
-}
 
-#[async_trait]
-impl Transport for SubprocessTransport {
-    async fn connect(&mut self) -> Result<()> {
-        // Note: cwd validation is done in new() for early error detection
-
-        // Check version
-        self.check_claude_version().await?;
+    /// Credentials set explicitly in the options, kept out of [`build_env`](Self::build_env)
+    fn credential_env(&self) -> Vec<(&'static str, &SecretString)> {
+        let mut credentials = Vec::new();
+        if let Some(api_key) = &self.options.api_key {
+            credentials.push(("ANTHROPIC_API_KEY", api_key));
+        }
+        if let Some(auth_token) = &self.options.auth_token {
+            credentials.push(("ANTHROPIC_AUTH_TOKEN", auth_token));
+        }
+        credentials
+    }
 
+    /// Resolve secrets, start the CLI process and take its stdio handles
+    async fn spawn_process(&mut self) -> Result<()> {
         // Build command
         let args = self.build_command();
         let env = self.build_env();
+        let extra_flags: Vec<String> =
+            self.options.extra_args.keys().map(|key| format!("--{}", key)).collect();
+        *self.diagnostics.command.lock().unwrap() =
+            Some(command_line(&self.cli_path, &args, &extra_flags));
+
+        // Resolve secrets as late as possible; they are zeroized when dropped below.
+        // The command's copies can't be zeroized, so it is dropped right after spawning.
+        let secret_env = resolve_secret_env(&self.options).await?;
 
         // Build command
-        let mut cmd = Command::new(&self.cli_path);
+        let mut cmd = self.cli_command();
         cmd.args(&args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .envs(&env);
 
+        for (key, secret) in &secret_env {
+            cmd.env(key, secret.expose_secret());
+        }
+        for (key, secret) in self.credential_env() {
+            cmd.env(key, secret.expose_secret());
+        }
+
         if let Some(ref cwd) = self.cwd {
             cmd.current_dir(cwd);
         }
 
+        // Its own process group, so that stopping it reaches what it started too
+        #[cfg(unix)]
+        cmd.process_group(0);
+
         // Spawn process
-        let mut child = cmd.spawn().map_err(|e| {
+        let spawned = cmd.spawn();
+        drop(cmd);
+        drop(secret_env);
+        let mut child = spawned.map_err(|e| {
             ClaudeError::Process(ProcessError::new(
                 format!("Failed to spawn Claude CLI process: {}", e),
                 None,
@@ -679,9 +1165,10 @@ impl Transport for SubprocessTransport {
 
         let stderr = child.stderr.take();
 
-        // Spawn stderr handler if callback is provided
-        if let (Some(stderr), Some(callback)) = (stderr, &self.options.stderr_callback) {
-            let callback = Arc::clone(callback);
+        // Keep the end of stderr for errors, and pass it on to the callback if provided
+        if let Some(stderr) = stderr {
+            let callback = self.options.stderr_callback.clone();
+            let tail = self.diagnostics.stderr.clone();
             tokio::spawn(async move {
                 let mut reader = BufReader::new(stderr);
                 let mut line = String::new();
@@ -689,17 +1176,40 @@ impl Transport for SubprocessTransport {
                     if n == 0 {
                         break;
                     }
-                    callback(line.clone());
+                    tail.push(&line);
+                    if let Some(callback) = &callback {
+                        callback(line.clone());
+                    }
                     line.clear();
                 }
+                tail.close();
             });
+        } else {
+            self.diagnostics.stderr.close();
         }
 
-        *self.stdin.lock().await = Some(stdin);
+        *self.stdin.lock().await = Some(Box::new(stdin));
         *self.stdout.lock().await = Some(BufReader::new(stdout));
         self.process = Some(child);
         self.ready = true;
 
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transport for SubprocessTransport {
+    async fn connect(&mut self) -> Result<()> {
+        // Note: cwd validation is done in new() for early error detection
+
+        // Check version
+        self.check_claude_version().await?;
+
+        let progress = self.options.connect_progress.clone();
+        let timeout = self.options.connect_timeouts.spawn;
+        run_connect_phase(progress.as_ref(), ConnectPhase::Spawn, timeout, self.spawn_process())
+            .await?;
+
         // Send initial prompt based on type
         match &self.prompt {
             QueryPrompt::Text(text) => {
@@ -709,19 +1219,23 @@ impl Transport for SubprocessTransport {
             },
             QueryPrompt::Content(blocks) => {
                 // Format as JSON user message for stream-json input format
-                let user_message = serde_json::json!({
-                    "type": "user",
-                    "message": {
-                        "role": "user",
-                        "content": blocks
-                    }
-                });
+                let user_message = QueryPrompt::content_message(blocks);
                 let content_json = serde_json::to_string(&user_message).map_err(|e| {
                     ClaudeError::Transport(format!("Failed to serialize content blocks: {}", e))
                 })?;
                 self.write(&content_json).await?;
                 self.end_input().await?;
             },
+            QueryPrompt::History(turns) => {
+                for turn in turns.clone() {
+                    let message = turn.to_stream_json(None);
+                    let turn_json = serde_json::to_string(&message).map_err(|e| {
+                        ClaudeError::Transport(format!("Failed to serialize conversation turn: {}", e))
+                    })?;
+                    self.write(&turn_json).await?;
+                }
+                self.end_input().await?;
+            },
             QueryPrompt::Streaming => {
                 // Don't send initial prompt or close stdin - leave it open for streaming
             },
@@ -754,57 +1268,16 @@ impl Transport for SubprocessTransport {
     fn read_messages(
         &mut self,
     ) -> Pin<Box<dyn Stream<Item = Result<serde_json::Value>> + Send + '_>> {
-        let stdout = Arc::clone(&self.stdout);
-        let max_buffer_size = self.max_buffer_size;
-
-        Box::pin(async_stream::stream! {
-            let mut stdout_guard = stdout.lock().await;
-            if let Some(ref mut reader) = *stdout_guard {
-                let mut line = String::new();
-                let mut buffer_size = 0;
-
-                loop {
-                    line.clear();
-                    match reader.read_line(&mut line).await {
-                        Ok(0) => {
-                            // EOF
-                            break;
-                        }
-                        Ok(n) => {
-                            buffer_size += n;
-                            if buffer_size > max_buffer_size {
-                                yield Err(ClaudeError::Transport(format!(
-                                    "Buffer size exceeded maximum of {} bytes",
-                                    max_buffer_size
-                                )));
-                                break;
-                            }
-
-                            let trimmed = line.trim();
-                            if trimmed.is_empty() {
-                                continue;
-                            }
-
-                            match serde_json::from_str::<serde_json::Value>(trimmed) {
-                                Ok(json) => {
-                                    yield Ok(json);
-                                }
-                                Err(e) => {
-                                    yield Err(ClaudeError::JsonDecode(JsonDecodeError::new(
-                                        format!("Failed to parse JSON: {}", e),
-                                        trimmed.to_string(),
-                                    )));
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            yield Err(ClaudeError::Transport(format!("Failed to read line: {}", e)));
-                            break;
-                        }
+        let output_seen = Arc::clone(&self.diagnostics.output_seen);
+        Box::pin(
+            read_json_lines(Arc::clone(&self.stdout), self.max_buffer_size).inspect(
+                move |item| {
+                    if matches!(item, Ok(_) | Err(ClaudeError::JsonDecode(_))) {
+                        output_seen.store(true, Ordering::SeqCst);
                     }
-                }
-            }
-        })
+                },
+            ),
+        )
     }
 
     async fn close(&mut self) -> Result<()> {
@@ -813,8 +1286,8 @@ impl Transport for SubprocessTransport {
             let _ = stdin.shutdown().await;
         }
 
-        // Wait for process to exit
-        if let Some(mut process) = self.process.take() {
+        // Wait for process to exit, keeping it if the wait is cancelled
+        if let Some(process) = self.process.as_mut() {
             let status = process.wait().await.map_err(|e| {
                 ClaudeError::Process(ProcessError::new(
                     format!("Failed to wait for process: {}", e),
@@ -822,13 +1295,13 @@ impl Transport for SubprocessTransport {
                     None,
                 ))
             })?;
+            self.process = None;
 
             if !status.success() {
-                return Err(ClaudeError::Process(ProcessError::new(
-                    "Claude CLI exited with non-zero status".to_string(),
-                    status.code(),
-                    None,
-                )));
+                let stderr = self.diagnostics.stderr.read().await;
+                return Err(ClaudeError::Process(
+                    self.diagnostics.exit_error(status.into(), stderr),
+                ));
             }
         }
 
@@ -849,12 +1322,152 @@ impl Transport for SubprocessTransport {
         }
         Ok(())
     }
+
+    fn writer(&self) -> TransportWriter {
+        Arc::clone(&self.stdin)
+    }
+
+    async fn abort(&mut self) {
+        self.kill().await;
+    }
+
+    async fn terminate(&mut self) -> Result<()> {
+        let Some(process) = self.process.as_mut() else {
+            return Ok(());
+        };
+        #[cfg(unix)]
+        signal_group(process, libc::SIGTERM);
+        // Windows has no gentler way to stop a process
+        #[cfg(not(unix))]
+        process
+            .start_kill()
+            .map_err(|e| ClaudeError::Transport(format!("Failed to stop the CLI: {}", e)))?;
+        Ok(())
+    }
+
+    async fn kill(&mut self) {
+        if let Some(mut process) = self.process.take() {
+            #[cfg(unix)]
+            signal_group(&process, libc::SIGKILL);
+            let _ = process.kill().await;
+        }
+        self.ready = false;
+    }
+
+    async fn exit_info(&mut self) -> Option<TransportExitInfo> {
+        let process = self.process.as_mut()?;
+        let status = tokio::time::timeout(EXIT_STATUS_WAIT, process.wait())
+            .await
+            .ok()?
+            .ok()?;
+        Some(status.into())
+    }
+
+    fn cli_version(&self) -> Option<semver::Version> {
+        self.cli_version.clone()
+    }
 }
 
 impl Drop for SubprocessTransport {
     fn drop(&mut self) {
         if let Some(mut process) = self.process.take() {
+            #[cfg(unix)]
+            signal_group(&process, libc::SIGKILL);
             let _ = process.start_kill();
         }
     }
 }
+
+/// Send `signal` to the CLI and everything in its process group
+///
+/// Does nothing once the CLI has been waited for, as its process group ID may
+/// have been reused.
+#[cfg(unix)]
+fn signal_group(process: &Child, signal: libc::c_int) {
+    if let Some(group) = process.id().and_then(|pid| libc::pid_t::try_from(pid).ok()) {
+        // SAFETY: killpg has no memory-safety preconditions; a group that is
+        // already gone yields ESRCH, which is ignored
+        unsafe {
+            libc::killpg(group, signal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transport(options: ClaudeAgentOptions, cli_version: Option<&str>) -> SubprocessTransport {
+        let options = ClaudeAgentOptions {
+            cli_path: Some(PathBuf::from("claude")),
+            ..options
+        };
+        let mut transport = SubprocessTransport::new(QueryPrompt::Streaming, options).unwrap();
+        transport.cli_version = cli_version.map(|version| semver::Version::parse(version).unwrap());
+        transport
+    }
+
+    fn options_with_optional_flags() -> ClaudeAgentOptions {
+        ClaudeAgentOptions::builder()
+            .max_budget_usd(2.5)
+            .include_partial_messages(true)
+            .fork_session(true)
+            .build()
+    }
+
+    #[test]
+    fn test_unsupported_flags_are_omitted() {
+        let args = transport(options_with_optional_flags(), Some("1.0.88")).build_command();
+        assert!(!args.contains(&"--max-budget-usd".to_string()));
+        assert!(!args.contains(&"2.5".to_string()));
+        assert!(!args.contains(&"--fork-session".to_string()));
+        assert!(args.contains(&"--include-partial-messages".to_string()));
+    }
+
+    #[test]
+    fn test_flags_are_passed_to_capable_or_unknown_clis() {
+        for version in [Some("2.0.29"), None] {
+            let args = transport(options_with_optional_flags(), version).build_command();
+            for flag in ["--max-budget-usd", "--include-partial-messages", "--fork-session"] {
+                assert!(args.contains(&flag.to_string()), "{flag} missing for {version:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_system_prompt_template_is_rendered() {
+        use crate::prompts::{PromptError, SystemPromptBuilder};
+
+        let template = SystemPromptBuilder::new()
+            .role("You review {{language}} code.")
+            .var("language", "Rust");
+        let options = ClaudeAgentOptions::builder()
+            .system_prompt_template(template.clone())
+            .build();
+        let args = transport(options, None).build_command();
+        let flag = args.iter().position(|arg| arg == "--system-prompt").unwrap();
+        assert_eq!(args[flag + 1], "You review Rust code.");
+
+        let options = ClaudeAgentOptions::builder()
+            .system_prompt_template(template.preset("claude_code"))
+            .build();
+        let args = transport(options, None).build_command();
+        let flag = args.iter().position(|arg| arg == "--append-system-prompt").unwrap();
+        assert_eq!(args[flag + 1], "You review Rust code.");
+
+        let options = ClaudeAgentOptions::builder()
+            .system_prompt("Plain")
+            .system_prompt_template(SystemPromptBuilder::new().role("Templated"))
+            .cli_path("claude")
+            .build();
+        let result = SubprocessTransport::new(QueryPrompt::Streaming, options);
+        assert!(matches!(result, Err(ClaudeError::InvalidConfig(_))));
+
+        let options = ClaudeAgentOptions::builder()
+            .system_prompt_template(SystemPromptBuilder::new().role("{{missing}}"))
+            .cli_path("claude")
+            .build();
+        let result = SubprocessTransport::new(QueryPrompt::Streaming, options);
+        assert!(matches!(result, Err(ClaudeError::Prompt(PromptError::MissingVariable(_)))));
+    }
+}
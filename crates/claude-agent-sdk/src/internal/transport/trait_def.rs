@@ -3,10 +3,24 @@
 use async_trait::async_trait;
 use futures::stream::Stream;
 use std::pin::Pin;
+use std::sync::Arc;
+use tokio::io::AsyncWrite;
+use tokio::sync::Mutex;
 
-use crate::errors::Result;
+use crate::errors::{Result, TransportExitInfo};
+
+/// Shared handle on the input side of a transport
+///
+/// `None` once input has ended.
+pub type TransportWriter = Arc<Mutex<Option<Box<dyn AsyncWrite + Send + Unpin>>>>;
 
 /// Transport trait for communicating with Claude Code CLI
+///
+/// Messages are newline-delimited JSON in both directions (the CLI's
+/// `stream-json` format). [`SubprocessTransport`](super::SubprocessTransport)
+/// spawns the CLI and [`SocketTransport`](super::SocketTransport) connects to
+/// one that is already running; other transports can be passed to
+/// [`ClaudeClient::with_transport`](crate::ClaudeClient::with_transport).
 #[async_trait]
 pub trait Transport: Send + Sync {
     /// Connect the transport
@@ -29,4 +43,47 @@ pub trait Transport: Send + Sync {
 
     /// End input stream (close stdin)
     async fn end_input(&mut self) -> Result<()>;
+
+    /// Handle for writing while [`read_messages`](Self::read_messages) is in use
+    ///
+    /// [`ClaudeClient`](crate::ClaudeClient) reads on a background task for as long
+    /// as it is connected, so it writes through this handle, one JSON message per
+    /// line, instead of through [`write`](Self::write). Valid once connected.
+    fn writer(&self) -> TransportWriter;
+
+    /// Stop at once, without waiting for the other side to finish
+    ///
+    /// Used after a timeout or a broken message stream. Defaults to
+    /// [`close`](Self::close), ignoring its error.
+    async fn abort(&mut self) {
+        let _ = self.close().await;
+    }
+
+    /// Ask the process behind the transport to stop, e.g. with `SIGTERM`
+    ///
+    /// Used by [`ClaudeClient::shutdown`](crate::ClaudeClient::shutdown) when the
+    /// CLI does not exit after its input ended; [`close`](Self::close) then waits
+    /// for it to exit. Defaults to doing nothing, for transports without a
+    /// process to signal.
+    async fn terminate(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Stop the process behind the transport at once, e.g. with `SIGKILL`
+    ///
+    /// The last resort of [`ClaudeClient::shutdown`](crate::ClaudeClient::shutdown).
+    /// Defaults to [`abort`](Self::abort).
+    async fn kill(&mut self) {
+        self.abort().await;
+    }
+
+    /// How the process behind the transport exited, once its output has ended
+    async fn exit_info(&mut self) -> Option<TransportExitInfo> {
+        None
+    }
+
+    /// Version of the CLI behind the transport, if known after `connect`
+    fn cli_version(&self) -> Option<semver::Version> {
+        None
+    }
 }
@@ -0,0 +1,340 @@
+//! Turn admission and completion tracking
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::errors::{ClaudeError, Result, TransportExitInfo};
+use crate::types::config::TurnPolicy;
+use crate::types::messages::Message;
+
+/// What starts a turn
+#[derive(Debug, PartialEq)]
+pub(crate) enum Turn {
+    /// Serialized messages for the CLI, which answers each of their `results`
+    /// user messages with a result
+    Prompt { line: String, results: usize },
+    /// Local command message, delivered on the receive stream in place of the CLI's answer
+    Local(serde_json::Value),
+}
+
+impl Turn {
+    /// Number of messages ending the turn
+    fn results(&self) -> usize {
+        match self {
+            Turn::Prompt { results, .. } => *results,
+            Turn::Local(_) => 1,
+        }
+    }
+}
+
+/// What a message ending a turn did to the running turn
+#[derive(Debug, PartialEq)]
+pub(crate) enum Finished {
+    /// The turn waits for more results
+    Continues,
+    /// The turn ended, and the queued turn, if any, starts now
+    Ended(Option<Turn>),
+}
+
+#[derive(Default)]
+struct GateState {
+    in_progress: bool,
+    queued: VecDeque<Turn>,
+    // Results due before the current turn ends, under `Reject` and `Queue`
+    awaiting: usize,
+    // Results due for the turns sent, under any policy
+    running: usize,
+}
+
+/// Decides whether a new user message may start a turn
+///
+/// A turn starts when a user message is sent and ends when its result message is
+/// received from the CLI. A turn of several user messages, such as a
+/// conversation history, ends with the result of the last one. A local command
+/// is a turn that ends when its message is received.
+///
+/// Under [`TurnPolicy::Interleave`] results can't be told apart by turn, so each
+/// result ends a turn.
+pub(crate) struct TurnGate {
+    policy: TurnPolicy,
+    capacity: usize,
+    state: Mutex<GateState>,
+}
+
+impl TurnGate {
+    pub(crate) fn new(policy: TurnPolicy, capacity: usize) -> Self {
+        Self {
+            policy,
+            capacity,
+            state: Mutex::new(GateState::default()),
+        }
+    }
+
+    /// Admit a turn
+    ///
+    /// Returns the turn if it should start now, or `None` if it was queued.
+    pub(crate) fn admit(&self, message: Turn) -> Result<Option<Turn>> {
+        let mut state = self.state.lock().unwrap();
+        match self.policy {
+            TurnPolicy::Interleave => {
+                state.running += message.results();
+                return Ok(Some(message));
+            },
+            TurnPolicy::Reject if state.in_progress => return Err(ClaudeError::TurnInProgress),
+            TurnPolicy::Queue if state.in_progress => {
+                if state.queued.len() >= self.capacity {
+                    return Err(ClaudeError::TurnQueueFull {
+                        capacity: self.capacity,
+                    });
+                }
+                state.queued.push_back(message);
+                return Ok(None);
+            },
+            TurnPolicy::Reject | TurnPolicy::Queue => {},
+        }
+
+        state.in_progress = true;
+        state.awaiting = message.results();
+        state.running += message.results();
+        Ok(Some(message))
+    }
+
+    /// Record that a result or local command message arrived
+    pub(crate) fn finish_turn(&self) -> Finished {
+        let mut state = self.state.lock().unwrap();
+        state.running = state.running.saturating_sub(1);
+        if self.policy == TurnPolicy::Interleave {
+            return Finished::Ended(None);
+        }
+
+        state.awaiting = state.awaiting.saturating_sub(1);
+        if state.awaiting > 0 {
+            return Finished::Continues;
+        }
+        let next = state.queued.pop_front();
+        state.in_progress = next.is_some();
+        if let Some(turn) = &next {
+            state.awaiting = turn.results();
+            state.running += turn.results();
+        }
+        Finished::Ended(next)
+    }
+
+    /// Whether a sent turn is still waiting for its result
+    pub(crate) fn is_running(&self) -> bool {
+        self.state.lock().unwrap().running > 0
+    }
+
+    /// Forget the current turn and anything queued, e.g. after a failed send
+    pub(crate) fn reset(&self) {
+        *self.state.lock().unwrap() = GateState::default();
+    }
+}
+
+/// Whether `message` is the last of its turn
+pub(crate) fn message_ends_turn(message: &Message) -> bool {
+    matches!(message, Message::Result(_) | Message::LocalCommand(_))
+}
+
+/// Messages received since the last result, to report a turn the CLI never finished
+#[derive(Default)]
+pub(crate) struct TurnProgress {
+    messages: usize,
+    last_type: Option<&'static str>,
+    seen_result: bool,
+}
+
+impl TurnProgress {
+    pub(crate) fn observe(&mut self, message: &Message) {
+        if message_ends_turn(message) {
+            *self = Self {
+                seen_result: true,
+                ..Self::default()
+            };
+        } else {
+            self.messages += 1;
+            self.last_type = Some(message.type_name());
+        }
+    }
+
+    /// Whether messages arrived after the last result
+    pub(crate) fn is_pending(&self) -> bool {
+        self.messages > 0
+    }
+
+    /// Whether any result arrived
+    pub(crate) fn seen_result(&self) -> bool {
+        self.seen_result
+    }
+
+    /// Error ending a turn whose output stopped before its result
+    pub(crate) fn incomplete(&self, process_exit: Option<TransportExitInfo>) -> ClaudeError {
+        ClaudeError::IncompleteTurn {
+            messages_received: self.messages,
+            last_message_type: self.last_type.map(String::from),
+            process_exit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(line: &str) -> Turn {
+        Turn::Prompt {
+            line: line.to_string(),
+            results: 1,
+        }
+    }
+
+    #[test]
+    fn test_reject_until_result() {
+        let gate = TurnGate::new(TurnPolicy::Reject, 4);
+        assert_eq!(gate.admit(prompt("a")).unwrap(), Some(prompt("a")));
+        assert!(matches!(
+            gate.admit(prompt("b")),
+            Err(ClaudeError::TurnInProgress)
+        ));
+
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+        assert_eq!(gate.admit(prompt("b")).unwrap(), Some(prompt("b")));
+    }
+
+    #[test]
+    fn test_queue_flushes_in_order() {
+        let gate = TurnGate::new(TurnPolicy::Queue, 2);
+        assert_eq!(gate.admit(prompt("a")).unwrap(), Some(prompt("a")));
+        assert_eq!(gate.admit(prompt("b")).unwrap(), None);
+        assert_eq!(gate.admit(prompt("c")).unwrap(), None);
+        assert!(matches!(
+            gate.admit(prompt("d")),
+            Err(ClaudeError::TurnQueueFull { capacity: 2 })
+        ));
+
+        assert_eq!(gate.finish_turn(), Finished::Ended(Some(prompt("b"))));
+        assert_eq!(gate.admit(prompt("d")).unwrap(), None);
+        assert_eq!(gate.finish_turn(), Finished::Ended(Some(prompt("c"))));
+        assert_eq!(gate.finish_turn(), Finished::Ended(Some(prompt("d"))));
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+
+        // Idle again, so the next message is sent right away
+        assert_eq!(gate.admit(prompt("e")).unwrap(), Some(prompt("e")));
+    }
+
+    #[test]
+    fn test_local_turns_queue_with_prompts() {
+        let gate = TurnGate::new(TurnPolicy::Queue, 2);
+        let local = Turn::Local(serde_json::json!({"type": "local_command"}));
+        assert_eq!(gate.admit(prompt("a")).unwrap(), Some(prompt("a")));
+        assert_eq!(gate.admit(local).unwrap(), None);
+        assert_eq!(gate.admit(prompt("b")).unwrap(), None);
+
+        assert!(matches!(
+            gate.finish_turn(),
+            Finished::Ended(Some(Turn::Local(_)))
+        ));
+        assert_eq!(gate.finish_turn(), Finished::Ended(Some(prompt("b"))));
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+        assert!(!gate.is_running());
+    }
+
+    #[test]
+    fn test_turn_waits_for_each_result() {
+        let gate = TurnGate::new(TurnPolicy::Reject, 4);
+        let history = Turn::Prompt {
+            line: "a\nb".to_string(),
+            results: 2,
+        };
+        assert!(gate.admit(history).unwrap().is_some());
+
+        assert_eq!(gate.finish_turn(), Finished::Continues);
+        assert!(matches!(
+            gate.admit(prompt("c")),
+            Err(ClaudeError::TurnInProgress)
+        ));
+        assert!(gate.is_running());
+
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+        assert!(!gate.is_running());
+        assert_eq!(gate.admit(prompt("c")).unwrap(), Some(prompt("c")));
+    }
+
+    #[test]
+    fn test_interleave_never_blocks() {
+        let gate = TurnGate::new(TurnPolicy::Interleave, 0);
+        assert!(gate.admit(prompt("a")).unwrap().is_some());
+        assert!(gate.admit(prompt("b")).unwrap().is_some());
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+        assert!(gate.is_running());
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+        assert!(!gate.is_running());
+    }
+
+    #[test]
+    fn test_running_counts_queued_turns_once_sent() {
+        let gate = TurnGate::new(TurnPolicy::Queue, 2);
+        assert!(!gate.is_running());
+        gate.admit(prompt("a")).unwrap();
+        gate.admit(prompt("b")).unwrap();
+        assert!(gate.is_running());
+
+        assert_eq!(gate.finish_turn(), Finished::Ended(Some(prompt("b"))));
+        assert!(gate.is_running());
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+        assert!(!gate.is_running());
+
+        gate.admit(prompt("c")).unwrap();
+        gate.reset();
+        assert!(!gate.is_running());
+    }
+
+    #[test]
+    fn test_progress_restarts_at_result() {
+        let assistant: Message = serde_json::from_value(serde_json::json!({
+            "type": "assistant",
+            "message": {"model": "mock", "content": []}
+        }))
+        .unwrap();
+        let result: Message = serde_json::from_value(serde_json::json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 1,
+            "duration_api_ms": 1,
+            "is_error": false,
+            "num_turns": 1,
+            "session_id": "mock"
+        }))
+        .unwrap();
+
+        let mut progress = TurnProgress::default();
+        progress.observe(&assistant);
+        assert!(progress.is_pending());
+        assert!(!progress.seen_result());
+
+        progress.observe(&result);
+        assert!(!progress.is_pending());
+        assert!(progress.seen_result());
+
+        progress.observe(&assistant);
+        progress.observe(&assistant);
+        assert!(matches!(
+            progress.incomplete(None),
+            ClaudeError::IncompleteTurn {
+                messages_received: 2,
+                last_message_type: Some(ref kind),
+                process_exit: None,
+            } if kind == "assistant"
+        ));
+    }
+
+    #[test]
+    fn test_reset_drops_queue() {
+        let gate = TurnGate::new(TurnPolicy::Queue, 4);
+        gate.admit(prompt("a")).unwrap();
+        gate.admit(prompt("b")).unwrap();
+        gate.reset();
+        assert_eq!(gate.admit(prompt("c")).unwrap(), Some(prompt("c")));
+        assert_eq!(gate.finish_turn(), Finished::Ended(None));
+    }
+}
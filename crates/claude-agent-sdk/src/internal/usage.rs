@@ -0,0 +1,300 @@
+//! Client-side accumulation of token usage and cost
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::types::messages::{AssistantMessage, Message, ResultMessage};
+use crate::types::usage::{UsageSnapshot, UsageTotals};
+
+/// Adds up the usage reported by assistant and result messages
+#[derive(Default)]
+pub(crate) struct UsageTracker {
+    /// Usage of finished turns
+    total: UsageTotals,
+    sessions: BTreeMap<String, UsageTotals>,
+    /// Latest `total_cost_usd` of each session, a running total for the session
+    reported_costs: BTreeMap<String, f64>,
+    /// Tokens of the running turn, from its assistant messages
+    turn: UsageTotals,
+    turn_session: Option<String>,
+    /// API message IDs already counted in `turn`
+    turn_messages: HashSet<String>,
+    /// Estimated cost of `turn`, until the result reports the actual cost
+    turn_cost_usd: f64,
+    /// Tokens of the latest main-conversation API response, prompt included
+    context_tokens: u64,
+}
+
+impl UsageTracker {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn observe(&mut self, message: &Message) {
+        match message {
+            Message::Assistant(assistant) => self.observe_assistant(assistant),
+            Message::Result(result) => self.finish_turn(result),
+            // The summary's size is known once the next response arrives
+            Message::System(system) if system.compact_boundary().is_some() => {
+                self.context_tokens = 0;
+            },
+            _ => {},
+        }
+    }
+
+    fn observe_assistant(&mut self, assistant: &AssistantMessage) {
+        let Some(usage) = &assistant.message.usage else {
+            return;
+        };
+        // The CLI repeats the usage of an API response on each of its content blocks
+        if let Some(id) = &assistant.message.id
+            && !self.turn_messages.insert(id.clone())
+        {
+            return;
+        }
+
+        let usage = UsageTotals::from_usage(usage);
+        // Subagents have contexts of their own
+        if assistant.parent_tool_use_id.is_none() {
+            self.context_tokens = usage.total_tokens();
+        }
+        self.turn_cost_usd += usage.estimated_cost_usd(assistant.message.model.as_deref());
+        self.turn += &usage;
+        if let Some(session_id) = &assistant.session_id {
+            self.turn_session = Some(session_id.clone());
+        }
+    }
+
+    fn finish_turn(&mut self, result: &ResultMessage) {
+        let turn = std::mem::take(&mut self.turn);
+        self.turn_session = None;
+        self.turn_messages.clear();
+        self.turn_cost_usd = 0.0;
+
+        // The result's usage covers the whole turn; without it, keep what was seen
+        let mut usage = result.usage.as_ref().map_or(turn, UsageTotals::from_usage);
+        usage.total_cost_usd = self.turn_cost(result);
+        usage.num_turns = result.num_turns;
+
+        self.total += &usage;
+        *self.sessions.entry(result.session_id.clone()).or_default() += &usage;
+    }
+
+    /// Cost of the turn `result` ends
+    ///
+    /// The CLI reports the cost of the whole session so far, so the turn costs
+    /// the increase since the session's previous result. A lower cost means
+    /// the CLI started counting again, e.g. after resuming the session in a
+    /// new process.
+    fn turn_cost(&mut self, result: &ResultMessage) -> f64 {
+        let Some(reported) = result.total_cost_usd else {
+            return 0.0;
+        };
+        match self.reported_costs.insert(result.session_id.clone(), reported) {
+            Some(previous) if previous <= reported => reported - previous,
+            _ => reported,
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> UsageSnapshot {
+        let mut snapshot = UsageSnapshot {
+            total: self.total.clone(),
+            sessions: self.sessions.clone(),
+            context_tokens: self.context_tokens,
+        };
+        snapshot.total += &self.turn;
+        if let Some(session_id) = &self.turn_session {
+            *snapshot.sessions.entry(session_id.clone()).or_default() += &self.turn;
+        }
+        snapshot
+    }
+
+    /// Cost of finished turns plus the estimated cost of the running turn
+    pub(crate) fn projected_cost_usd(&self) -> f64 {
+        self.total.total_cost_usd + self.turn_cost_usd
+    }
+
+    /// Size of the conversation's context, see [`UsageSnapshot::context_tokens`]
+    pub(crate) fn context_tokens(&self) -> u64 {
+        self.context_tokens
+    }
+
+    /// Forget all usage, counting costs from the sessions' current totals on
+    pub(crate) fn reset(&mut self) {
+        let reported_costs = std::mem::take(&mut self.reported_costs);
+        *self = Self {
+            reported_costs,
+            ..Self::default()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::message_parser::MessageParser;
+    use serde_json::json;
+
+    fn assistant(id: &str, session_id: &str, input: u64, output: u64) -> Message {
+        MessageParser::parse(json!({
+            "type": "assistant",
+            "session_id": session_id,
+            "message": {
+                "id": id,
+                "content": [{"type": "text", "text": "hi"}],
+                "usage": {"input_tokens": input, "output_tokens": output}
+            }
+        }))
+        .unwrap()
+    }
+
+    fn result(session_id: &str, cost: f64, usage: Option<serde_json::Value>) -> Message {
+        MessageParser::parse(json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 1,
+            "duration_api_ms": 1,
+            "is_error": false,
+            "num_turns": 2,
+            "session_id": session_id,
+            "total_cost_usd": cost,
+            "usage": usage
+        }))
+        .unwrap()
+    }
+
+    fn result_without_cost(session_id: &str) -> Message {
+        MessageParser::parse(json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 1,
+            "duration_api_ms": 1,
+            "is_error": false,
+            "num_turns": 1,
+            "session_id": session_id
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_running_turn_counts_each_api_message_once() {
+        let mut tracker = UsageTracker::new();
+        tracker.observe(&assistant("msg_1", "s1", 10, 5));
+        // Second content block of the same API response
+        tracker.observe(&assistant("msg_1", "s1", 10, 5));
+        tracker.observe(&assistant("msg_2", "s1", 20, 7));
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(snapshot.total.input_tokens, 30);
+        assert_eq!(snapshot.total.output_tokens, 12);
+        assert_eq!(snapshot.sessions["s1"].input_tokens, 30);
+        assert_eq!(snapshot.total.total_cost_usd, 0.0);
+    }
+
+    #[test]
+    fn test_result_replaces_running_turn() {
+        let mut tracker = UsageTracker::new();
+        tracker.observe(&assistant("msg_1", "s1", 10, 5));
+        tracker.observe(&result(
+            "s1",
+            0.25,
+            Some(json!({
+                "input_tokens": 12,
+                "output_tokens": 6,
+                "cache_read_input_tokens": 100,
+                "cache_creation_input_tokens": 50
+            })),
+        ));
+        tracker.observe(&assistant("msg_1", "s2", 1, 1));
+        tracker.observe(&result("s2", 0.5, None));
+
+        let snapshot = tracker.snapshot();
+        assert_eq!(
+            snapshot.sessions["s1"],
+            UsageTotals {
+                input_tokens: 12,
+                output_tokens: 6,
+                cache_read_input_tokens: 100,
+                cache_creation_input_tokens: 50,
+                total_cost_usd: 0.25,
+                num_turns: 2,
+            }
+        );
+        // Without usage on the result, the assistant messages' tokens are kept
+        assert_eq!(snapshot.sessions["s2"].input_tokens, 1);
+        assert_eq!(snapshot.total.input_tokens, 13);
+        assert_eq!(snapshot.total.total_cost_usd, 0.75);
+        assert_eq!(snapshot.total.num_turns, 4);
+
+        tracker.reset();
+        assert_eq!(tracker.snapshot(), UsageSnapshot::default());
+    }
+
+    #[test]
+    fn test_projected_cost_estimates_running_turn() {
+        let mut tracker = UsageTracker::new();
+        tracker.observe(&result("s1", 0.5, None));
+        // A million Sonnet output tokens
+        tracker.observe(&assistant("msg_1", "s1", 0, 1_000_000));
+        assert!((tracker.projected_cost_usd() - 15.5).abs() < 1e-9);
+
+        tracker.observe(&result("s1", 0.75, None));
+        assert!((tracker.projected_cost_usd() - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_result_cost_is_a_running_total_per_session() {
+        let mut tracker = UsageTracker::new();
+        tracker.observe(&result("s1", 0.1, None));
+        tracker.observe(&result("s1", 0.3, None));
+        tracker.observe(&result("s2", 0.2, None));
+        tracker.observe(&result("s1", 0.6, None));
+
+        let snapshot = tracker.snapshot();
+        assert!((snapshot.sessions["s1"].total_cost_usd - 0.6).abs() < 1e-9);
+        assert!((snapshot.sessions["s2"].total_cost_usd - 0.2).abs() < 1e-9);
+        assert!((snapshot.total.total_cost_usd - 0.8).abs() < 1e-9);
+        assert!((tracker.projected_cost_usd() - 0.8).abs() < 1e-9);
+
+        // A resumed session counts from zero in its new process
+        tracker.observe(&result("s2", 0.05, None));
+        assert!((tracker.snapshot().sessions["s2"].total_cost_usd - 0.25).abs() < 1e-9);
+
+        // Results without a cost leave the running total alone
+        tracker.observe(&result_without_cost("s1"));
+        tracker.observe(&result("s1", 0.7, None));
+        assert!((tracker.snapshot().sessions["s1"].total_cost_usd - 0.7).abs() < 1e-9);
+
+        // After a reset, only what the sessions spend from then on counts
+        tracker.reset();
+        tracker.observe(&result("s1", 0.9, None));
+        assert!((tracker.snapshot().total.total_cost_usd - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_context_tokens_follow_latest_response() {
+        let mut tracker = UsageTracker::new();
+        tracker.observe(&assistant("msg_1", "s1", 1000, 50));
+        tracker.observe(&assistant("msg_2", "s1", 1200, 80));
+        assert_eq!(tracker.context_tokens(), 1280);
+        assert_eq!(tracker.snapshot().context_tokens, 1280);
+
+        // A subagent's answer leaves the main context alone
+        let mut subagent = assistant("msg_3", "s1", 9000, 10);
+        if let Message::Assistant(assistant) = &mut subagent {
+            assistant.parent_tool_use_id = Some("tool_1".to_string());
+        }
+        tracker.observe(&subagent);
+        assert_eq!(tracker.context_tokens(), 1280);
+
+        tracker.observe(
+            &MessageParser::parse(json!({
+                "type": "system",
+                "subtype": "compact_boundary",
+                "session_id": "s1",
+                "compact_metadata": {"trigger": "auto", "pre_tokens": 1280}
+            }))
+            .unwrap(),
+        );
+        assert_eq!(tracker.context_tokens(), 0);
+    }
+}
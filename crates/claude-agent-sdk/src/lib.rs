@@ -17,6 +17,7 @@
 //! - **Extended Thinking**: Configure maximum thinking tokens for complex reasoning
 //! - **Session Management**: Resume, fork, and manage conversation sessions
 //! - **Multimodal Input**: Send images alongside text using base64 or URLs
+//! - **Blocking API**: Synchronous wrappers for scripts without async code (`blocking` feature)
 //!
 //! ## Quick Start
 //!
@@ -125,6 +126,13 @@
 //! - Maximum base64 data size: 15MB (results in ~20MB decoded)
 //! - Large images may timeout or fail - resize before encoding
 //!
+//! [`UserContentBlock::image_from_path`] reads an image file, detects its format and
+//! checks its size before encoding it. With the `image` feature,
+//! `UserContentBlock::image_from_path_resized` downscales large images first.
+//!
+//! PDFs are sent the same way with [`UserContentBlock::document_base64`] and
+//! [`UserContentBlock::document_url`].
+//!
 //! ### Example: Query with Image
 //!
 //! ```no_run
@@ -165,7 +173,7 @@
 //! async fn main() -> anyhow::Result<()> {
 //!     let messages = query_with_content(vec![
 //!         UserContentBlock::text("Describe this architecture diagram"),
-//!         UserContentBlock::image_url("https://example.com/diagram.png"),
+//!         UserContentBlock::image_url("https://example.com/diagram.png")?,
 //!     ], None).await?;
 //!
 //!     Ok(())
@@ -233,62 +241,111 @@
 //! - [Plugin Guide](https://github.com/yourusername/claude-agent-sdk-rs/blob/master/PLUGIN_GUIDE.md) - Plugin development
 //! - [Examples](https://github.com/yourusername/claude-agent-sdk-rs/tree/master/examples) - 22 working examples
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
+#[cfg(feature = "openai-compat")]
+pub mod compat;
 pub mod errors;
 mod internal;
 pub mod mcp;
 pub mod observability;
 pub mod orchestration;
+pub mod prompts;
 pub mod query;
+pub mod retry;
+pub mod secrets;
 pub mod skills;
 pub mod commands;
 pub mod subagents;
+pub mod testing;
 pub mod todos;
+pub mod transcript;
 pub mod types;
 pub mod version;
 pub mod v2;
 
 // Re-export commonly used types
-pub use errors::{ClaudeError, ImageValidationError, Result};
+pub use errors::{
+    ClaudeError, DocumentValidationError, ErrorCode, ExitStage, ImageValidationError, ProcessError,
+    Result, StructuredOutputError, TransportExitInfo,
+};
 pub use mcp::{
-    TaskHandle, TaskHint, TaskId, TaskManager, TaskPriority, TaskProgress, TaskRequest, TaskResult,
-    TaskState, TaskStatus, TaskUri,
+    FileTaskStore, McpHealthReport, McpHealthStatus, McpPrompt, McpResource, PromptArgument,
+    PromptMessage, PromptRole, RateLimitMiddleware, ResourceContents, RestorePolicy, TaskHandle, TaskHint, TaskId, TaskManager,
+    TaskPriority, TaskProgress, TaskRequest, TaskResult, TaskState, TaskStatus, TaskStore, TaskUri,
+    ToolMiddleware, TracingMiddleware,
 };
 pub use observability::{
     Histogram, HistogramBuckets, LogLevel, LogObserver, Logger, MetricsCollector,
 };
 pub use orchestration::{
-    Agent, AgentFilter, AgentInput, AgentMetadata, AgentOutput, AgentRegistry,
-    ExecutionConfig, ExecutionContext, ExecutionTrace, Orchestrator, OrchestratorInput,
-    OrchestratorOutput, ParallelOrchestrator, SequentialOrchestrator,
+    Agent, AgentFilter, AgentInput, AgentMetadata, AgentOutcome, AgentOutput, AgentRegistry,
+    AgentStatus, DebateOrchestrator, ExecutionConfig, ExecutionContext, ExecutionTrace,
+    Orchestrator, OrchestratorInput, OrchestratorOutput, ParallelOrchestrator,
+    RouterOrchestrator, SequentialOrchestrator,
+};
+pub use retry::RetryPolicy;
+pub use secrets::{
+    CommandSecretProvider, EnvSecretProvider, FileSecretProvider, SecretProvider, SecretString,
 };
 pub use skills::{
-    Skill, SkillError, SkillInput, SkillOutput, SkillPackage, SkillRegistry, SkillResources,
+    PackagedSkill, Skill, SkillError, SkillInput, SkillOutput, SkillPackage, SkillRegistry,
+    SkillResources,
 };
 pub use subagents::{
-    DelegationStrategy, Subagent, SubagentCall, SubagentConfig, SubagentError, SubagentExecutor,
-    SubagentOutput,
+    DelegationDecision, DelegationStrategy, KeywordScorer, Subagent, SubagentCall, SubagentConfig,
+    SubagentError, SubagentExecutor, SubagentMatch, SubagentOutput, SubagentScorer,
+};
+pub use transcript::{MarkdownOptions, Transcript};
+pub use todos::{TodoError, TodoItem, TodoList, TodoPriority, TodoStatus, TodoStore, TodoTracker};
+pub use commands::{
+    ArgValue, CommandArgs, CommandError, CommandHandler, CommandParam, CommandRegistry,
+    CommandSpec, ParamType, SlashCommand, TypedCommandHandler, parse_command_line,
 };
-pub use todos::{TodoError, TodoItem, TodoList, TodoStatus};
-pub use commands::{CommandError, CommandHandler, CommandRegistry, SlashCommand};
 pub use types::{
+    checkpoints::{CheckpointDiff, CheckpointInfo, FileChange},
     config::*,
+    hook_rules::{CompiledHookRules, HookRule, HookRules, RuleAction, RuleDecision, RulePattern},
     hooks::*,
     mcp::{
-        McpServerConfig, McpServers, SdkMcpServer, SdkMcpTool, ToolHandler, ToolResult,
-        ToolResultContent as McpToolResultContent, create_sdk_mcp_server,
+        McpError, McpServerConfig, McpServers, SdkMcpServer, SdkMcpTool, SessionStateStore,
+        ToolCallContext, ToolHandler, ToolInputError, ToolResult, ToolResultBuilder,
+        ToolResultContent as McpToolResultContent, create_sdk_mcp_server, parse_tool_input,
+        tool_input_schema,
     },
     messages::*,
     permissions::*,
     plugin::*,
+    redaction::{DEFAULT_REDACTION_PATTERNS, RedactionHook, Redacted},
+    usage::{BudgetWarning, ModelTokenUsage, ServerToolUse, Usage, UsageSnapshot, UsageTotals},
+    validation::{ConfigIssue, IssueSeverity},
 };
 
 // Re-export public API
-pub use client::ClaudeClient;
-pub use query::{query, query_stream, query_stream_with_content, query_with_content};
+pub use client::{
+    ClaudeClient, ClientGuard, DEFAULT_SHUTDOWN_TIMEOUT, SessionHandle, ShutdownLevel,
+};
+pub use version::CliCapabilities;
+pub use internal::cli_installer::{
+    CliInstaller, DEFAULT_RELEASES_URL, InstallError, InstallProgress, RELEASES_URL_ENV,
+};
+pub use internal::transport::{
+    FrameDirection, RecordedFrame, SessionRecorder, SocketAddress, SocketTransport,
+    SubprocessTransport, Transport, TransportConfig, TransportWriter,
+    socket::{DEFAULT_SOCKET_MAX_BUFFER_SIZE, DEFAULT_SOCKET_RETRY_DELAY},
+};
+pub use query::{
+    query, query_stream, query_stream_typed, query_stream_with_content, query_stream_with_history,
+    query_typed, query_with_content, query_with_history, query_with_retry, query_with_timeout,
+    query_with_transport,
+};
+
+// Re-exported for deriving `JsonSchema` on typed tool inputs
+pub use schemars;
 
 // Re-export V2 API
 pub use v2::{
-    create_session, prompt, resume_session, Message as V2Message, PermissionMode as V2PermissionMode,
-    PromptResult, Session, SessionOptions,
+    create_session, list_sessions, prompt, resume_session, Message as V2Message,
+    PermissionMode as V2PermissionMode, PromptResult, Session, SessionInfo, SessionOptions,
 };
@@ -0,0 +1,648 @@
+//! Checking that configured MCP servers start and speak MCP
+//!
+//! A server with a mistyped command or URL otherwise only shows up as tools
+//! missing from Claude's answers. [`McpServers::verify`] runs the MCP
+//! `initialize` handshake against every configured server and lists the tools
+//! each advertises:
+//!
+//! - stdio servers are spawned and talked to over stdin and stdout, then killed
+//! - HTTP servers get the handshake as POST requests (streamable HTTP)
+//! - SSE servers are reached through the endpoint their event stream announces
+//! - SDK servers run in-process, so they are healthy by definition
+//!
+//! Set [`ClaudeAgentOptions::verify_mcp_servers`](crate::ClaudeAgentOptions::verify_mcp_servers)
+//! to have [`ClaudeClient::connect`](crate::ClaudeClient::connect) do this and
+//! fail with [`ClaudeError::McpServersUnhealthy`](crate::ClaudeError::McpServersUnhealthy).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::McpServers;
+//! use claude_agent_sdk::mcp::McpHealthStatus;
+//!
+//! # async fn example(servers: McpServers) {
+//! for report in servers.verify().await {
+//!     match &report.status {
+//!         McpHealthStatus::Healthy => println!("{}: {:?}", report.name, report.tools_advertised),
+//!         problem => eprintln!("{}: {}", report.name, problem),
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+
+use crate::types::mcp::{
+    McpHttpServerConfig, McpSdkServerConfig, McpServerConfig, McpServers, McpSseServerConfig,
+    McpStdioServerConfig,
+};
+
+/// How long each server gets to answer the handshake
+pub const DEFAULT_MCP_VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Protocol version sent in the `initialize` request
+const LATEST_PROTOCOL_VERSION: &str = "2025-11-25";
+
+/// Protocol versions a server may answer with
+const SUPPORTED_PROTOCOL_VERSIONS: [&str; 4] = [
+    "2024-11-05",
+    "2025-03-26",
+    "2025-06-18",
+    LATEST_PROTOCOL_VERSION,
+];
+
+/// Most `tools/list` pages read from one server
+const MAX_TOOL_PAGES: usize = 32;
+
+/// Outcome of checking one MCP server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpHealthStatus {
+    /// The server answered the handshake
+    Healthy,
+    /// The server could not be started or reached, or did not answer in time
+    Unreachable(String),
+    /// The server answered with a protocol version the SDK does not know
+    ProtocolMismatch(String),
+}
+
+impl fmt::Display for McpHealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            McpHealthStatus::Healthy => write!(f, "healthy"),
+            McpHealthStatus::Unreachable(reason) => write!(f, "unreachable: {}", reason),
+            McpHealthStatus::ProtocolMismatch(version) => {
+                write!(f, "unsupported protocol version `{}`", version)
+            },
+        }
+    }
+}
+
+/// Health of one configured MCP server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpHealthReport {
+    /// Name the server is configured under
+    pub name: String,
+    /// Whether the server answered the handshake
+    pub status: McpHealthStatus,
+    /// Names of the tools the server lists, empty unless healthy
+    pub tools_advertised: Vec<String>,
+}
+
+impl McpHealthReport {
+    /// Whether the server answered the handshake
+    pub fn is_healthy(&self) -> bool {
+        self.status == McpHealthStatus::Healthy
+    }
+
+    fn failed(name: String, status: McpHealthStatus) -> Self {
+        Self {
+            name,
+            status,
+            tools_advertised: Vec::new(),
+        }
+    }
+}
+
+impl McpServers {
+    /// Run the MCP handshake against every server, giving each
+    /// [`DEFAULT_MCP_VERIFY_TIMEOUT`]
+    ///
+    /// Servers are checked concurrently; the reports are sorted by name.
+    /// For [`McpServers::Path`], the servers in the file's `mcpServers` object
+    /// are checked.
+    pub async fn verify(&self) -> Vec<McpHealthReport> {
+        self.verify_with_timeout(DEFAULT_MCP_VERIFY_TIMEOUT).await
+    }
+
+    /// Like [`verify`](Self::verify), giving each server `timeout`
+    pub async fn verify_with_timeout(&self, timeout: Duration) -> Vec<McpHealthReport> {
+        let servers = match self {
+            McpServers::Empty => return Vec::new(),
+            McpServers::Dict(servers) => servers.clone(),
+            McpServers::Path(path) => match load_config_file(path) {
+                Ok(servers) => servers,
+                Err(reason) => {
+                    let name = path.display().to_string();
+                    return vec![McpHealthReport::failed(
+                        name,
+                        McpHealthStatus::Unreachable(reason),
+                    )];
+                },
+            },
+        };
+
+        let checks = servers
+            .into_iter()
+            .map(|(name, config)| check_server(name, config, timeout));
+        let mut reports = futures::future::join_all(checks).await;
+        reports.sort_by(|a, b| a.name.cmp(&b.name));
+        reports
+    }
+}
+
+async fn check_server(name: String, config: McpServerConfig, timeout: Duration) -> McpHealthReport {
+    let handshake = async {
+        match &config {
+            McpServerConfig::Sdk(sdk) => Ok(sdk_tools(sdk).await),
+            McpServerConfig::Stdio(stdio) => {
+                let mut server = StdioServer::spawn(stdio)?;
+                let result = handshake(&mut server).await;
+                server.stop().await;
+                result
+            },
+            McpServerConfig::Http(http) => handshake(&mut HttpServer::new(http)?).await,
+            McpServerConfig::Sse(sse) => handshake(&mut SseServer::connect(sse).await?).await,
+        }
+    };
+
+    match tokio::time::timeout(timeout, handshake).await {
+        Ok(Ok(tools_advertised)) => McpHealthReport {
+            name,
+            status: McpHealthStatus::Healthy,
+            tools_advertised,
+        },
+        Ok(Err(status)) => McpHealthReport::failed(name, status),
+        Err(_) => McpHealthReport::failed(
+            name,
+            McpHealthStatus::Unreachable(format!(
+                "no answer to the handshake within {:?}",
+                timeout
+            )),
+        ),
+    }
+}
+
+/// Tools of an in-process server, which needs no handshake
+async fn sdk_tools(config: &McpSdkServerConfig) -> Vec<String> {
+    let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"});
+    match config.instance.handle_message(request).await {
+        // Servers answer with the bare result or a JSON-RPC response
+        Ok(response) => tool_names(response.get("result").unwrap_or(&response)),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Servers as written in an MCP config file
+fn load_config_file(path: &std::path::Path) -> Result<HashMap<String, McpServerConfig>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let config: Value = serde_json::from_str(&text)
+        .map_err(|e| format!("cannot parse {}: {}", path.display(), e))?;
+    let Some(servers) = config.get("mcpServers").and_then(Value::as_object) else {
+        return Err(format!("{} has no `mcpServers` object", path.display()));
+    };
+
+    servers
+        .iter()
+        .map(|(name, server)| {
+            McpServerConfig::from_value(server.clone())
+                .map(|config| (name.clone(), config))
+                .map_err(|e| format!("server `{}` in {}: {}", name, path.display(), e))
+        })
+        .collect()
+}
+
+/// One MCP connection, as far as the handshake needs it
+trait McpExchange {
+    /// Send request `id` and wait for its result
+    async fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String>;
+
+    /// Send a notification, which has no answer
+    async fn notify(&mut self, method: &str) -> Result<(), String>;
+}
+
+/// Initialize the connection and list the server's tools
+async fn handshake(server: &mut impl McpExchange) -> Result<Vec<String>, McpHealthStatus> {
+    let params = json!({
+        "protocolVersion": LATEST_PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": {"name": "claude-agent-sdk", "version": crate::version::SDK_VERSION},
+    });
+    let result = server
+        .request(1, "initialize", params)
+        .await
+        .map_err(McpHealthStatus::Unreachable)?;
+    let version = result["protocolVersion"].as_str().unwrap_or_default();
+    if !SUPPORTED_PROTOCOL_VERSIONS.contains(&version) {
+        return Err(McpHealthStatus::ProtocolMismatch(version.to_string()));
+    }
+    server
+        .notify("notifications/initialized")
+        .await
+        .map_err(McpHealthStatus::Unreachable)?;
+
+    // A server without tools may not implement tools/list at all
+    let mut tools = Vec::new();
+    let mut cursor = None;
+    for page in 0..MAX_TOOL_PAGES {
+        let params = match cursor.take() {
+            Some(cursor) => json!({"cursor": cursor}),
+            None => json!({}),
+        };
+        let Ok(result) = server.request(2 + page as u64, "tools/list", params).await else {
+            break;
+        };
+        tools.extend(tool_names(&result));
+        match result["nextCursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+    Ok(tools)
+}
+
+fn tool_names(result: &Value) -> Vec<String> {
+    result["tools"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| tool["name"].as_str().map(String::from))
+        .collect()
+}
+
+fn request_body(id: u64, method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+}
+
+/// The result of `message` if it answers request `id`
+///
+/// Returns `None` for other messages, such as notifications.
+fn response_to(id: u64, message: &Value) -> Option<Result<Value, String>> {
+    if message.get("id").and_then(Value::as_u64) != Some(id) || message.get("method").is_some() {
+        return None;
+    }
+    Some(match message.get("error") {
+        Some(error) => Err(format!(
+            "error {}: {}",
+            error["code"],
+            error["message"].as_str().unwrap_or_default()
+        )),
+        None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+    })
+}
+
+/// A spawned stdio server
+struct StdioServer {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl StdioServer {
+    fn spawn(config: &McpStdioServerConfig) -> Result<Self, McpHealthStatus> {
+        let mut command = tokio::process::Command::new(&config.command);
+        command
+            .args(config.args.iter().flatten())
+            .envs(config.env.iter().flatten())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        let mut child = command.spawn().map_err(|e| {
+            McpHealthStatus::Unreachable(format!("cannot start `{}`: {}", config.command, e))
+        })?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        })
+    }
+
+    async fn send(&mut self, message: Value) -> Result<(), String> {
+        let line = format!("{}\n", message);
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("cannot write to the server: {}", e))
+    }
+
+    async fn stop(mut self) {
+        let _ = self.child.kill().await;
+    }
+}
+
+impl McpExchange for StdioServer {
+    async fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String> {
+        self.send(request_body(id, method, params)).await?;
+        loop {
+            let line = self
+                .stdout
+                .next_line()
+                .await
+                .map_err(|e| format!("cannot read from the server: {}", e))?
+                .ok_or_else(|| format!("server exited before answering `{}`", method))?;
+            // Servers may log to stdout; only JSON-RPC messages count
+            if let Ok(message) = serde_json::from_str::<Value>(&line)
+                && let Some(result) = response_to(id, &message)
+            {
+                return result;
+            }
+        }
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        self.send(json!({"jsonrpc": "2.0", "method": method})).await
+    }
+}
+
+/// Headers for requests to an HTTP or SSE server
+fn header_map(
+    headers: Option<&HashMap<String, String>>,
+) -> Result<reqwest::header::HeaderMap, McpHealthStatus> {
+    let mut map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers.into_iter().flatten() {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes());
+        let value = reqwest::header::HeaderValue::from_str(value);
+        let (Ok(name), Ok(value)) = (name, value) else {
+            return Err(McpHealthStatus::Unreachable(
+                "invalid HTTP header in the server config".to_string(),
+            ));
+        };
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// Session header of the streamable HTTP transport
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// A server using the streamable HTTP transport
+struct HttpServer {
+    client: reqwest::Client,
+    url: String,
+    headers: reqwest::header::HeaderMap,
+}
+
+impl HttpServer {
+    fn new(config: &McpHttpServerConfig) -> Result<Self, McpHealthStatus> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url: config.url.clone(),
+            headers: header_map(config.headers.as_ref())?,
+        })
+    }
+
+    async fn post(&mut self, body: Value) -> Result<reqwest::Response, String> {
+        let response = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .header(
+                reqwest::header::ACCEPT,
+                "application/json, text/event-stream",
+            )
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("cannot reach {}: {}", self.url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("{} answered HTTP {}", self.url, response.status()));
+        }
+        if let Some(session) = response.headers().get(SESSION_HEADER) {
+            self.headers.insert(SESSION_HEADER, session.clone());
+        }
+        Ok(response)
+    }
+}
+
+impl McpExchange for HttpServer {
+    async fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String> {
+        let response = self.post(request_body(id, method, params)).await?;
+        let is_event_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+
+        if is_event_stream {
+            let mut events = EventStream::new(response);
+            while let Some(event) = events.next().await? {
+                if let Ok(message) = serde_json::from_str::<Value>(&event.data)
+                    && let Some(result) = response_to(id, &message)
+                {
+                    return result;
+                }
+            }
+            return Err(format!(
+                "{} closed the stream before answering `{}`",
+                self.url, method
+            ));
+        }
+
+        let message: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("invalid answer to `{}`: {}", method, e))?;
+        response_to(id, &message).unwrap_or_else(|| Err(format!("no answer to `{}`", method)))
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        self.post(json!({"jsonrpc": "2.0", "method": method}))
+            .await?;
+        Ok(())
+    }
+}
+
+/// A server using the SSE transport: requests are posted to the endpoint its
+/// event stream announces, and answered on the stream
+struct SseServer {
+    client: reqwest::Client,
+    endpoint: reqwest::Url,
+    headers: reqwest::header::HeaderMap,
+    events: EventStream,
+}
+
+impl SseServer {
+    async fn connect(config: &McpSseServerConfig) -> Result<Self, McpHealthStatus> {
+        let unreachable = |reason: String| McpHealthStatus::Unreachable(reason);
+        let url = reqwest::Url::parse(&config.url)
+            .map_err(|e| unreachable(format!("invalid URL {}: {}", config.url, e)))?;
+        let headers = header_map(config.headers.as_ref())?;
+        let client = reqwest::Client::new();
+        let response = client
+            .get(url.clone())
+            .headers(headers.clone())
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| unreachable(format!("cannot reach {}: {}", url, e)))?;
+        if !response.status().is_success() {
+            return Err(unreachable(format!(
+                "{} answered HTTP {}",
+                url,
+                response.status()
+            )));
+        }
+
+        let mut events = EventStream::new(response);
+        let endpoint = loop {
+            match events.next().await.map_err(unreachable)? {
+                Some(event) if event.name == "endpoint" => break event.data,
+                Some(_) => continue,
+                None => return Err(unreachable(format!("{} announced no endpoint", url))),
+            }
+        };
+        let endpoint = url
+            .join(endpoint.trim())
+            .map_err(|e| unreachable(format!("invalid endpoint {}: {}", endpoint, e)))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            headers,
+            events,
+        })
+    }
+
+    async fn post(&self, body: Value) -> Result<(), String> {
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .headers(self.headers.clone())
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("cannot reach {}: {}", self.endpoint, e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "{} answered HTTP {}",
+                self.endpoint,
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl McpExchange for SseServer {
+    async fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String> {
+        self.post(request_body(id, method, params)).await?;
+        while let Some(event) = self.events.next().await? {
+            if let Ok(message) = serde_json::from_str::<Value>(&event.data)
+                && let Some(result) = response_to(id, &message)
+            {
+                return result;
+            }
+        }
+        Err(format!("event stream ended before answering `{}`", method))
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        self.post(json!({"jsonrpc": "2.0", "method": method})).await
+    }
+}
+
+/// One server-sent event
+struct Event {
+    name: String,
+    data: String,
+}
+
+/// Server-sent events read from an HTTP response body
+struct EventStream {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl EventStream {
+    fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// The next event, or `None` once the body ends
+    async fn next(&mut self) -> Result<Option<Event>, String> {
+        loop {
+            if let Some(event) = self.take_event() {
+                return Ok(Some(event));
+            }
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .map_err(|e| format!("cannot read the event stream: {}", e))?;
+            match chunk {
+                Some(chunk) => self
+                    .buffer
+                    .push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n")),
+                // A last event may lack its blank line
+                None if self.buffer.trim().is_empty() => return Ok(None),
+                None => self.buffer.push_str("\n\n"),
+            }
+        }
+    }
+
+    fn take_event(&mut self) -> Option<Event> {
+        loop {
+            let end = self.buffer.find("\n\n")?;
+            let block: String = self.buffer.drain(..end + 2).collect();
+            let mut event = Event {
+                name: "message".to_string(),
+                data: String::new(),
+            };
+            for line in block.lines() {
+                if let Some(name) = line.strip_prefix("event:") {
+                    event.name = name.trim().to_string();
+                } else if let Some(data) = line.strip_prefix("data:") {
+                    if !event.data.is_empty() {
+                        event.data.push('\n');
+                    }
+                    event.data.push_str(data.strip_prefix(' ').unwrap_or(data));
+                }
+            }
+            // Blocks of only comments or IDs carry no event
+            if !event.data.is_empty() {
+                return Some(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_to_skips_other_messages() {
+        let notification = json!({"jsonrpc": "2.0", "method": "notifications/message"});
+        assert!(response_to(1, &notification).is_none());
+        assert!(response_to(1, &json!({"jsonrpc": "2.0", "id": 2, "result": {}})).is_none());
+
+        let error =
+            json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32601, "message": "nope"}});
+        assert_eq!(
+            response_to(1, &error),
+            Some(Err("error -32601: nope".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_command_is_unreachable() {
+        let servers = McpServers::Dict(HashMap::from([(
+            "typo".to_string(),
+            McpServerConfig::Stdio(McpStdioServerConfig {
+                command: "definitely-not-an-mcp-server-7f3a".to_string(),
+                args: None,
+                env: None,
+            }),
+        )]));
+
+        let reports = servers.verify().await;
+        assert_eq!(reports.len(), 1);
+        assert!(
+            matches!(&reports[0].status, McpHealthStatus::Unreachable(reason) if reason.contains("cannot start"))
+        );
+    }
+}
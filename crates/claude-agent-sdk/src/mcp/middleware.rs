@@ -0,0 +1,448 @@
+//! Middleware wrapped around the tool calls of SDK MCP servers
+//!
+//! Middleware is added with
+//! [`McpSdkServerConfig::with_middleware`](crate::types::mcp::McpSdkServerConfig::with_middleware)
+//! and runs in registration order: the `before` hooks in the order the
+//! middleware was added, then the tool, then the `after` hooks in reverse.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! use claude_agent_sdk::create_sdk_mcp_server;
+//! use claude_agent_sdk::mcp::{RateLimitMiddleware, ToolMiddleware, TracingMiddleware};
+//!
+//! let middleware: Vec<Arc<dyn ToolMiddleware>> = vec![
+//!     Arc::new(TracingMiddleware::new()),
+//!     Arc::new(RateLimitMiddleware::new(10, Duration::from_secs(60))),
+//! ];
+//! let server = create_sdk_mcp_server("tools", "1.0.0", vec![]).with_middleware(middleware);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use crate::errors::Result;
+use crate::observability::Logger;
+use crate::types::mcp::{SdkMcpServer, ToolCallContext, ToolResult, ToolResultContent};
+
+/// Hooks run around every tool call of an SDK MCP server
+#[async_trait]
+pub trait ToolMiddleware: Send + Sync {
+    /// Called before `tool` runs with `args`
+    ///
+    /// Returning `Err` skips the tool and the remaining middleware, and answers
+    /// the call with the returned result instead.
+    async fn before(&self, tool: &str, args: &Value) -> std::result::Result<(), ToolResult> {
+        let _ = (tool, args);
+        Ok(())
+    }
+
+    /// Called with the result of `tool`, which the middleware may change
+    ///
+    /// Only called if the `before` hook of this middleware let the call
+    /// through.
+    async fn after(&self, tool: &str, result: &mut ToolResult) {
+        let _ = (tool, result);
+    }
+}
+
+/// Server running `middleware` around the tool calls of `inner`
+pub(crate) struct MiddlewareServer {
+    pub(crate) inner: Arc<dyn SdkMcpServer>,
+    pub(crate) middleware: Vec<Arc<dyn ToolMiddleware>>,
+}
+
+#[async_trait]
+impl SdkMcpServer for MiddlewareServer {
+    async fn handle_message(&self, message: Value) -> Result<Value> {
+        self.handle_message_with_context(message, ToolCallContext::default())
+            .await
+    }
+
+    async fn handle_message_with_context(
+        &self,
+        message: Value,
+        ctx: ToolCallContext,
+    ) -> Result<Value> {
+        if message["method"] != "tools/call" {
+            return self.inner.handle_message_with_context(message, ctx).await;
+        }
+        let tool = message["params"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let args = message["params"]["arguments"].clone();
+
+        let mut entered = 0;
+        let mut rejection = None;
+        for middleware in &self.middleware {
+            if let Err(result) = middleware.before(&tool, &args).await {
+                rejection = Some(result);
+                break;
+            }
+            entered += 1;
+        }
+
+        let (mut response, mut result) = match rejection {
+            Some(result) => (serde_json::json!({}), result),
+            // Errors become error results, so that `after` hooks see every outcome
+            None => match self.inner.handle_message_with_context(message, ctx).await {
+                Ok(response) => {
+                    let result = ToolResult {
+                        content: serde_json::from_value(response["content"].clone())?,
+                        is_error: response["isError"].as_bool().unwrap_or(false),
+                    };
+                    (response, result)
+                },
+                Err(e) => (serde_json::json!({}), error_result(e.to_string())),
+            },
+        };
+
+        for middleware in self.middleware[..entered].iter().rev() {
+            middleware.after(&tool, &mut result).await;
+        }
+
+        response["content"] = serde_json::json!(result.content);
+        response["isError"] = serde_json::json!(result.is_error);
+        Ok(response)
+    }
+}
+
+fn error_result(text: String) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Text { text }],
+        is_error: true,
+    }
+}
+
+/// Limits how often each tool can be called, with a token bucket per tool
+///
+/// Every tool may be called `max_calls` times in a burst, after which calls
+/// are allowed again at a rate of `max_calls` per `period`. Calls over the
+/// limit are answered with an error asking Claude to wait.
+#[derive(Debug)]
+pub struct RateLimitMiddleware {
+    max_calls: u32,
+    period: Duration,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimitMiddleware {
+    /// Allow `max_calls` calls of each tool per `period`
+    pub fn new(max_calls: u32, period: Duration) -> Self {
+        Self {
+            max_calls,
+            period,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for `tool`, or return how long until one is available
+    fn acquire(&self, tool: &str) -> std::result::Result<(), Duration> {
+        let capacity = f64::from(self.max_calls);
+        let rate = capacity / self.period.as_secs_f64();
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(tool.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if rate > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        } else {
+            Err(self.period)
+        }
+    }
+}
+
+#[async_trait]
+impl ToolMiddleware for RateLimitMiddleware {
+    async fn before(&self, tool: &str, _args: &Value) -> std::result::Result<(), ToolResult> {
+        self.acquire(tool).map_err(|wait| {
+            error_result(format!(
+                "The {} tool has been called too often. Please wait {} seconds before \
+                 calling it again.",
+                tool,
+                wait.as_secs_f64().ceil().max(1.0)
+            ))
+        })
+    }
+}
+
+/// Logs the name, duration and outcome of every tool call
+///
+/// Successful calls are logged at info level and failed ones at warn level,
+/// with the fields `tool`, `duration_ms` and `success`. Concurrent calls of
+/// the same tool are timed first in, first out.
+#[derive(Debug)]
+pub struct TracingMiddleware {
+    logger: Logger,
+    started: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl TracingMiddleware {
+    /// Log to the global `mcp_tools` logger
+    pub fn new() -> Self {
+        Self::with_logger(crate::observability::logger::logger("mcp_tools"))
+    }
+
+    /// Log to `logger`
+    pub fn with_logger(logger: Logger) -> Self {
+        Self {
+            logger,
+            started: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for TracingMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolMiddleware for TracingMiddleware {
+    async fn before(&self, tool: &str, _args: &Value) -> std::result::Result<(), ToolResult> {
+        self.started
+            .lock()
+            .unwrap()
+            .entry(tool.to_string())
+            .or_default()
+            .push_back(Instant::now());
+        Ok(())
+    }
+
+    async fn after(&self, tool: &str, result: &mut ToolResult) {
+        let started = self
+            .started
+            .lock()
+            .unwrap()
+            .get_mut(tool)
+            .and_then(VecDeque::pop_front);
+        let duration_ms = started.map_or(0, |started| started.elapsed().as_millis());
+
+        let fields = [
+            ("tool", tool.to_string()),
+            ("duration_ms", duration_ms.to_string()),
+            ("success", (!result.is_error).to_string()),
+        ];
+        if result.is_error {
+            self.logger.warn("Tool call failed", &fields);
+        } else {
+            self.logger.info("Tool call succeeded", &fields);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::{LogEntry, LogObserver};
+    use crate::types::mcp::{McpSdkServerConfig, create_sdk_mcp_server};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn echo_server(calls: Arc<AtomicUsize>) -> McpSdkServerConfig {
+        let tool = crate::tool!(
+            "echo",
+            "Echo",
+            json!({"type": "object"}),
+            move |args: Value| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    Ok(ToolResult {
+                        content: vec![ToolResultContent::Text {
+                            text: args.to_string(),
+                        }],
+                        is_error: false,
+                    })
+                }
+            }
+        );
+        create_sdk_mcp_server("echo", "1.0.0", vec![tool])
+    }
+
+    async fn call(config: &McpSdkServerConfig, tool: &str) -> Value {
+        config
+            .handle_jsonrpc(
+                "s",
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": {"name": tool, "arguments": {"x": 1}}
+                }),
+            )
+            .await["result"]
+            .clone()
+    }
+
+    /// Records the order of its hooks and appends a marker to results
+    struct Recording {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+        reject: bool,
+    }
+
+    #[async_trait]
+    impl ToolMiddleware for Recording {
+        async fn before(&self, tool: &str, _args: &Value) -> std::result::Result<(), ToolResult> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("before {} {}", self.name, tool));
+            if self.reject {
+                return Err(error_result(format!("rejected by {}", self.name)));
+            }
+            Ok(())
+        }
+
+        async fn after(&self, _tool: &str, result: &mut ToolResult) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("after {}", self.name));
+            result.content.push(ToolResultContent::Text {
+                text: self.name.to_string(),
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_runs_in_registration_order() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let recording = |name, reject| Recording {
+            name,
+            log: Arc::clone(&log),
+            reject,
+        };
+        let config = echo_server(Arc::clone(&calls)).with_middleware(vec![
+            Arc::new(recording("outer", false)),
+            Arc::new(recording("inner", false)),
+        ]);
+
+        let result = call(&config, "echo").await;
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["content"][0]["text"], "{\"x\":1}");
+        assert_eq!(result["content"][1]["text"], "inner");
+        assert_eq!(result["content"][2]["text"], "outer");
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "before outer echo",
+                "before inner echo",
+                "after inner",
+                "after outer"
+            ]
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejection_skips_the_tool() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let config = echo_server(Arc::clone(&calls)).with_middleware(vec![
+            Arc::new(Recording {
+                name: "outer",
+                log: Arc::clone(&log),
+                reject: false,
+            }),
+            Arc::new(Recording {
+                name: "guard",
+                log: Arc::clone(&log),
+                reject: true,
+            }),
+        ]);
+
+        let result = call(&config, "echo").await;
+        assert_eq!(result["isError"], true);
+        assert_eq!(result["content"][0]["text"], "rejected by guard");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        // Only middleware that let the call through sees the result
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["before outer echo", "before guard echo", "after outer"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_per_tool() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = echo_server(Arc::clone(&calls)).with_middleware(vec![Arc::new(
+            RateLimitMiddleware::new(2, Duration::from_secs(3600)),
+        )]);
+
+        assert_eq!(call(&config, "echo").await["isError"], false);
+        assert_eq!(call(&config, "echo").await["isError"], false);
+        let limited = call(&config, "echo").await;
+        assert_eq!(limited["isError"], true);
+        let text = limited["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Please wait 1800 seconds"), "{}", text);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Other tools have their own bucket, and get past the limiter
+        let other = call(&config, "other").await;
+        assert!(
+            !other["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("Please wait")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tracing_logs_outcomes() {
+        struct Entries(Mutex<Vec<LogEntry>>);
+        impl LogObserver for Entries {
+            fn on_log(&self, entry: &LogEntry) {
+                self.0.lock().unwrap().push(entry.clone());
+            }
+        }
+
+        let entries = Arc::new(Entries(Mutex::new(Vec::new())));
+        let logger =
+            Logger::new("test").with_observer(Arc::clone(&entries) as Arc<dyn LogObserver>);
+        let config = echo_server(Arc::new(AtomicUsize::new(0)))
+            .with_middleware(vec![Arc::new(TracingMiddleware::with_logger(logger))]);
+
+        call(&config, "echo").await;
+        call(&config, "missing").await;
+
+        let entries = entries.0.lock().unwrap();
+        let fields: Vec<HashMap<_, _>> = entries
+            .iter()
+            .map(|entry| entry.metadata.iter().cloned().collect())
+            .collect();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0]["tool"], "echo");
+        assert_eq!(fields[0]["success"], "true");
+        assert!(fields[0].contains_key("duration_ms"));
+        assert_eq!(entries[1].level, crate::observability::LogLevel::Warn);
+        assert_eq!(fields[1]["tool"], "missing");
+        assert_eq!(fields[1]["success"], "false");
+    }
+}
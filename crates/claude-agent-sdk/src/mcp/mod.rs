@@ -6,6 +6,10 @@
 //! # Modules
 //!
 //! - [`tasks`] - Async Tasks primitive for "call-now, fetch-later" workflows
+//! - [`task_store`] - Persistent storage for tasks
+//! - [`middleware`] - Hooks around the tool calls of SDK MCP servers
+//! - [`health`] - Handshake checks of configured MCP servers
+//! - [`resources`] - Resources and prompts served by SDK MCP servers
 //!
 //! # Example
 //!
@@ -28,9 +32,21 @@
 //! # }
 //! ```
 
+pub mod health;
+pub mod middleware;
+pub mod resources;
+pub mod task_store;
 pub mod tasks;
+pub(crate) mod wire;
 
+pub use health::{DEFAULT_MCP_VERIFY_TIMEOUT, McpHealthReport, McpHealthStatus};
+pub use middleware::{RateLimitMiddleware, ToolMiddleware, TracingMiddleware};
+pub use resources::{
+    LIST_PAGE_SIZE, McpPrompt, McpResource, PromptArgument, PromptMessage, PromptRole,
+    ResourceContents,
+};
+pub use task_store::{FileTaskStore, TaskStore};
 pub use tasks::{
-    TaskHandle, TaskHint, TaskId, TaskManager, TaskPriority, TaskProgress, TaskRequest, TaskResult,
-    TaskState, TaskStatus, TaskUri,
+    CancellationToken, PROCESS_RESTARTED_ERROR, RestorePolicy, Task, TaskError, TaskHandle, TaskHint, TaskId,
+    TaskManager, TaskPriority, TaskProgress, TaskRequest, TaskResult, TaskState, TaskStatus, TaskUri,
 };
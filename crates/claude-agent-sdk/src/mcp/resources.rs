@@ -0,0 +1,484 @@
+//! Resources and prompts served by SDK MCP servers
+//!
+//! Add them with [`McpSdkServerConfig::add_resource`] and
+//! [`McpSdkServerConfig::add_prompt`]. The server then answers
+//! `resources/list`, `resources/read`, `prompts/list` and `prompts/get`, and
+//! advertises the `resources` and `prompts` capabilities on `initialize`.
+//!
+//! [`McpSdkServerConfig::add_resource`]: crate::types::mcp::McpSdkServerConfig::add_resource
+//! [`McpSdkServerConfig::add_prompt`]: crate::types::mcp::McpSdkServerConfig::add_prompt
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::errors::Result;
+use crate::types::mcp::McpError;
+
+/// Number of entries returned per page of `resources/list` and `prompts/list`
+pub const LIST_PAGE_SIZE: usize = 100;
+
+type ResourceProvider =
+    Arc<dyn Fn() -> BoxFuture<'static, anyhow::Result<ResourceContents>> + Send + Sync>;
+
+type PromptBuilder = Arc<
+    dyn Fn(HashMap<String, String>) -> BoxFuture<'static, anyhow::Result<Vec<PromptMessage>>>
+        + Send
+        + Sync,
+>;
+
+/// Content of a resource, as returned by its provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceContents {
+    /// Text content
+    Text(String),
+    /// Binary content, sent base64-encoded
+    Blob(Vec<u8>),
+}
+
+/// A resource of an SDK MCP server, read on demand
+#[derive(Clone)]
+pub struct McpResource {
+    /// URI the CLI reads the resource by
+    pub uri: String,
+    /// Human-readable name
+    pub name: String,
+    /// MIME type of the content
+    pub mime_type: String,
+    provider: ResourceProvider,
+}
+
+impl McpResource {
+    /// Create a resource whose content comes from `provider`
+    ///
+    /// The provider runs on every `resources/read`. Return an [`McpError`]
+    /// from it to choose the JSON-RPC error code the CLI receives.
+    pub fn new<F, Fut>(
+        uri: impl Into<String>,
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        provider: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<ResourceContents>> + Send + 'static,
+    {
+        Self {
+            uri: uri.into(),
+            name: name.into(),
+            mime_type: mime_type.into(),
+            provider: Arc::new(move || provider().boxed()),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({"uri": self.uri, "name": self.name, "mimeType": self.mime_type})
+    }
+
+    async fn read(&self) -> Result<Value> {
+        let contents = (self.provider)()
+            .await
+            .map_err(|e| provider_error(e, &format!("Failed to read {}", self.uri)))?;
+        let mut entry = json!({"uri": self.uri, "mimeType": self.mime_type});
+        match contents {
+            ResourceContents::Text(text) => entry["text"] = json!(text),
+            ResourceContents::Blob(bytes) => entry["blob"] = json!(BASE64.encode(bytes)),
+        }
+        Ok(json!({"contents": [entry]}))
+    }
+}
+
+/// An argument of a prompt template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PromptArgument {
+    /// Argument name
+    pub name: String,
+    /// What the argument is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether `prompts/get` fails without it
+    pub required: bool,
+}
+
+impl PromptArgument {
+    /// An argument that must be given
+    pub fn required(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: Some(description.into()),
+            required: true,
+        }
+    }
+
+    /// An argument that may be left out
+    pub fn optional(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            required: false,
+            ..Self::required(name, description)
+        }
+    }
+}
+
+/// Speaker of a prompt message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptRole {
+    /// The user
+    User,
+    /// Claude
+    Assistant,
+}
+
+/// A text message of a prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptMessage {
+    /// Speaker of the message
+    pub role: PromptRole,
+    /// Message text
+    pub text: String,
+}
+
+impl PromptMessage {
+    /// A message from the user
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: PromptRole::User,
+            text: text.into(),
+        }
+    }
+
+    /// A message from Claude
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self {
+            role: PromptRole::Assistant,
+            text: text.into(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({"role": self.role, "content": {"type": "text", "text": self.text}})
+    }
+}
+
+/// A prompt template of an SDK MCP server
+#[derive(Clone)]
+pub struct McpPrompt {
+    /// Prompt name
+    pub name: String,
+    /// What the prompt does
+    pub description: String,
+    /// Arguments the prompt takes
+    pub arguments: Vec<PromptArgument>,
+    builder: PromptBuilder,
+}
+
+impl McpPrompt {
+    /// Create a prompt whose messages come from `builder`
+    ///
+    /// The builder receives the arguments of `prompts/get` by name, after the
+    /// required ones have been checked. Return an [`McpError`] from it to
+    /// choose the JSON-RPC error code the CLI receives.
+    pub fn new<F, Fut>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        arguments: Vec<PromptArgument>,
+        builder: F,
+    ) -> Self
+    where
+        F: Fn(HashMap<String, String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<Vec<PromptMessage>>> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            arguments,
+            builder: Arc::new(move |args| builder(args).boxed()),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({"name": self.name, "description": self.description, "arguments": self.arguments})
+    }
+
+    async fn get(&self, params: &Value) -> Result<Value> {
+        let mut arguments = HashMap::new();
+        if let Some(given) = params["arguments"].as_object() {
+            for (name, value) in given {
+                let value = value.as_str().ok_or_else(|| {
+                    McpError::invalid_params(format!("Argument {} must be a string", name))
+                })?;
+                arguments.insert(name.clone(), value.to_string());
+            }
+        }
+        if let Some(missing) = self
+            .arguments
+            .iter()
+            .find(|argument| argument.required && !arguments.contains_key(&argument.name))
+        {
+            return Err(McpError::invalid_params(format!(
+                "Missing required argument {} of prompt {}",
+                missing.name, self.name
+            ))
+            .into());
+        }
+
+        let messages = (self.builder)(arguments)
+            .await
+            .map_err(|e| provider_error(e, &format!("Failed to build prompt {}", self.name)))?;
+        let messages: Vec<_> = messages.iter().map(PromptMessage::to_json).collect();
+        Ok(json!({"description": self.description, "messages": messages}))
+    }
+}
+
+/// Whether the request is served from `resources` and `prompts` rather than the server
+pub(crate) fn is_resource_request(
+    method: &str,
+    resources: &[McpResource],
+    prompts: &[McpPrompt],
+) -> bool {
+    (method.starts_with("resources/") && !resources.is_empty())
+        || (method.starts_with("prompts/") && !prompts.is_empty())
+}
+
+/// Handle a request for which [`is_resource_request`] holds
+pub(crate) async fn handle(
+    resources: &[McpResource],
+    prompts: &[McpPrompt],
+    method: &str,
+    params: &Value,
+) -> Result<Value> {
+    match method {
+        "resources/list" => {
+            let (page, next_cursor) = page(resources, params)?;
+            let resources: Vec<_> = page.iter().map(McpResource::to_json).collect();
+            Ok(with_cursor(json!({"resources": resources}), next_cursor))
+        },
+        "resources/templates/list" => Ok(json!({"resourceTemplates": []})),
+        "resources/read" => {
+            let uri = required_str(params, "uri")?;
+            let resource = resources
+                .iter()
+                .find(|resource| resource.uri == uri)
+                .ok_or_else(|| McpError::resource_not_found(uri))?;
+            resource.read().await
+        },
+        "prompts/list" => {
+            let (page, next_cursor) = page(prompts, params)?;
+            let prompts: Vec<_> = page.iter().map(McpPrompt::to_json).collect();
+            Ok(with_cursor(json!({"prompts": prompts}), next_cursor))
+        },
+        "prompts/get" => {
+            let name = required_str(params, "name")?;
+            let prompt = prompts
+                .iter()
+                .find(|prompt| prompt.name == name)
+                .ok_or_else(|| McpError::invalid_params(format!("Unknown prompt: {}", name)))?;
+            prompt.get(params).await
+        },
+        _ => Err(McpError::method_not_found(method).into()),
+    }
+}
+
+/// The page of `items` starting at the cursor in `params`, and the cursor of the next page
+///
+/// Cursors are offsets into `items`, which keep the order they were added in.
+fn page<'a, T>(items: &'a [T], params: &Value) -> Result<(&'a [T], Option<String>)> {
+    let start = match params["cursor"].as_str() {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .ok()
+            .filter(|start| *start <= items.len())
+            .ok_or_else(|| McpError::invalid_params(format!("Invalid cursor: {}", cursor)))?,
+        None => 0,
+    };
+    let end = (start + LIST_PAGE_SIZE).min(items.len());
+    let next_cursor = (end < items.len()).then(|| end.to_string());
+    Ok((&items[start..end], next_cursor))
+}
+
+fn with_cursor(mut result: Value, next_cursor: Option<String>) -> Value {
+    if let Some(cursor) = next_cursor {
+        result["nextCursor"] = json!(cursor);
+    }
+    result
+}
+
+fn required_str<'a>(params: &'a Value, field: &str) -> Result<&'a str> {
+    params[field]
+        .as_str()
+        .ok_or_else(|| McpError::invalid_params(format!("Missing {}", field)).into())
+}
+
+/// Keep an [`McpError`] of a provider, and report anything else as an internal error
+fn provider_error(error: anyhow::Error, context: &str) -> McpError {
+    error
+        .downcast_ref::<McpError>()
+        .cloned()
+        .unwrap_or_else(|| McpError::internal(format!("{}: {}", context, error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::mcp::{McpSdkServerConfig, create_sdk_mcp_server};
+
+    async fn request(config: &McpSdkServerConfig, method: &str, params: Value) -> Value {
+        let message = json!({"jsonrpc": "2.0", "id": 3, "method": method, "params": params});
+        config.handle_jsonrpc("s", message).await
+    }
+
+    fn log_server() -> McpSdkServerConfig {
+        create_sdk_mcp_server("logs", "1.0.0", vec![])
+            .add_resource("file:///app.log", "App log", "text/plain", || async {
+                Ok(ResourceContents::Text("boot ok\ndisk full".to_string()))
+            })
+            .add_resource(
+                "file:///core",
+                "Core dump",
+                "application/octet-stream",
+                || async { Ok(ResourceContents::Blob(vec![0, 1, 2])) },
+            )
+            .add_resource("file:///gone.log", "Rotated log", "text/plain", || async {
+                Err(McpError::resource_not_found("file:///gone.log").into())
+            })
+            .add_resource("file:///locked.log", "Locked log", "text/plain", || async {
+                anyhow::bail!("permission denied")
+            })
+            .add_prompt(
+                "summarize-logs",
+                "Summarize the log",
+                vec![
+                    PromptArgument::required("level", "Lowest level to report"),
+                    PromptArgument::optional("since", "Start time"),
+                ],
+                |args| async move {
+                    Ok(vec![PromptMessage::user(format!(
+                        "Summarize {} entries since {}",
+                        args["level"],
+                        args.get("since").map_or("boot", String::as_str)
+                    ))])
+                },
+            )
+    }
+
+    #[tokio::test]
+    async fn test_initialize_advertises_capabilities() {
+        let response = request(&log_server(), "initialize", json!({})).await;
+        let capabilities = &response["result"]["capabilities"];
+        assert_eq!(capabilities["resources"], json!({}));
+        assert_eq!(capabilities["prompts"], json!({}));
+        assert_eq!(capabilities["tools"], json!({}));
+
+        let plain = create_sdk_mcp_server("plain", "1.0.0", vec![]);
+        let response = request(&plain, "initialize", json!({})).await;
+        assert!(
+            response["result"]["capabilities"]
+                .get("resources")
+                .is_none()
+        );
+        let response = request(&plain, "resources/list", json!({})).await;
+        assert_eq!(response["error"]["code"], McpError::METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_read_resources() {
+        let config = log_server();
+        let listed = request(&config, "resources/list", json!({})).await;
+        assert_eq!(listed["result"]["resources"][0]["mimeType"], "text/plain");
+        assert_eq!(listed["result"]["resources"].as_array().unwrap().len(), 4);
+        assert!(listed["result"].get("nextCursor").is_none());
+
+        let text = request(&config, "resources/read", json!({"uri": "file:///app.log"})).await;
+        assert_eq!(text["result"]["contents"][0]["text"], "boot ok\ndisk full");
+        let blob = request(&config, "resources/read", json!({"uri": "file:///core"})).await;
+        assert_eq!(blob["result"]["contents"][0]["blob"], "AAEC");
+    }
+
+    #[tokio::test]
+    async fn test_provider_errors_become_jsonrpc_errors() {
+        let config = log_server();
+        let unknown = request(&config, "resources/read", json!({"uri": "file:///nope"})).await;
+        assert_eq!(unknown["error"]["code"], McpError::RESOURCE_NOT_FOUND);
+        assert_eq!(unknown["id"], 3);
+
+        let gone = request(
+            &config,
+            "resources/read",
+            json!({"uri": "file:///gone.log"}),
+        )
+        .await;
+        assert_eq!(gone["error"]["code"], McpError::RESOURCE_NOT_FOUND);
+
+        let locked = request(
+            &config,
+            "resources/read",
+            json!({"uri": "file:///locked.log"}),
+        )
+        .await;
+        assert_eq!(locked["error"]["code"], McpError::INTERNAL_ERROR);
+        assert_eq!(
+            locked["error"]["message"],
+            "Failed to read file:///locked.log: permission denied"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt() {
+        let config = log_server();
+        let listed = request(&config, "prompts/list", json!({})).await;
+        assert_eq!(
+            listed["result"]["prompts"][0]["arguments"][0],
+            json!({"name": "level", "description": "Lowest level to report", "required": true})
+        );
+
+        let params = json!({"name": "summarize-logs", "arguments": {"level": "warn"}});
+        let prompt = request(&config, "prompts/get", params).await;
+        assert_eq!(
+            prompt["result"]["messages"],
+            json!([{"role": "user", "content": {"type": "text", "text": "Summarize warn entries since boot"}}])
+        );
+
+        let params = json!({"name": "summarize-logs", "arguments": {}});
+        let missing = request(&config, "prompts/get", params).await;
+        assert_eq!(missing["error"]["code"], McpError::INVALID_PARAMS);
+        let unknown = request(&config, "prompts/get", json!({"name": "other"})).await;
+        assert_eq!(unknown["error"]["code"], McpError::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_list_pages_through_cursors() {
+        let mut config = create_sdk_mcp_server("many", "1.0.0", vec![]);
+        for i in 0..LIST_PAGE_SIZE + 5 {
+            config = config.add_resource(format!("mem://{}", i), "n", "text/plain", || async {
+                Ok(ResourceContents::Text(String::new()))
+            });
+        }
+        // Adding a URI again replaces it
+        config = config.add_resource("mem://0", "n", "text/plain", || async {
+            Ok(ResourceContents::Text(String::new()))
+        });
+
+        let first = request(&config, "resources/list", json!({})).await;
+        assert_eq!(
+            first["result"]["resources"].as_array().unwrap().len(),
+            LIST_PAGE_SIZE
+        );
+        let cursor = first["result"]["nextCursor"].clone();
+        assert!(cursor.is_string());
+
+        let second = request(&config, "resources/list", json!({"cursor": cursor})).await;
+        assert_eq!(second["result"]["resources"].as_array().unwrap().len(), 5);
+        assert_eq!(second["result"]["resources"][4]["uri"], "mem://0");
+        assert!(second["result"].get("nextCursor").is_none());
+
+        let invalid = request(&config, "resources/list", json!({"cursor": "x"})).await;
+        assert_eq!(invalid["error"]["code"], McpError::INVALID_PARAMS);
+    }
+}
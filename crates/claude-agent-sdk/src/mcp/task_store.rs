@@ -0,0 +1,185 @@
+//! Persistent storage for tasks, so they survive a restart of the process
+//!
+//! A [`TaskManager`](super::tasks::TaskManager) created with
+//! [`TaskManager::with_store`](super::tasks::TaskManager::with_store) writes every
+//! state transition through to its [`TaskStore`].
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use super::tasks::{Task, TaskId, TaskState};
+use crate::errors::{ClaudeError, Result};
+
+/// Storage backend for tasks
+#[async_trait]
+pub trait TaskStore: Send + Sync {
+    /// Save `task`, replacing any stored task with the same ID
+    async fn save(&self, task: &Task) -> Result<()>;
+
+    /// Load the task with ID `task_id`, if stored
+    async fn load(&self, task_id: &TaskId) -> Result<Option<Task>>;
+
+    /// Load the stored tasks in `state`, or all of them, oldest first
+    async fn list(&self, state: Option<&TaskState>) -> Result<Vec<Task>>;
+
+    /// Delete the task with ID `task_id`
+    ///
+    /// Returns whether the task was stored.
+    async fn delete(&self, task_id: &TaskId) -> Result<bool>;
+}
+
+/// Tasks kept as JSON files in one directory
+///
+/// Each task is stored in `<dir>/<task id>.json`. Saves are atomic, so a crash
+/// mid-save leaves the previous version of the task on disk.
+///
+/// # Example
+///
+/// ```no_run
+/// use claude_agent_sdk::mcp::{FileTaskStore, TaskManager};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let manager = TaskManager::with_store(FileTaskStore::open(".agent/tasks")?);
+/// for handle in manager.restore().await? {
+///     println!("Task {} needs to be run again", handle.id);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileTaskStore {
+    dir: PathBuf,
+}
+
+impl FileTaskStore {
+    /// Open the store in `dir`, creating the directory if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::Io` if the directory cannot be created
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Directory holding the tasks
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, task_id: &TaskId) -> Result<PathBuf> {
+        let valid = !task_id.is_empty()
+            && task_id
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+        if !valid {
+            return Err(ClaudeError::InvalidInput(format!(
+                "Invalid task ID: {:?}",
+                task_id
+            )));
+        }
+        Ok(self.dir.join(format!("{}.json", task_id)))
+    }
+}
+
+#[async_trait]
+impl TaskStore for FileTaskStore {
+    async fn save(&self, task: &Task) -> Result<()> {
+        let path = self.path_for(&task.id)?;
+        let contents = serde_json::to_vec_pretty(task)?;
+
+        // Unique per save, so concurrent saves never share a temporary file
+        let tmp = self
+            .dir
+            .join(format!(".{}.{}.tmp", task.id, uuid::Uuid::new_v4()));
+        let written = async {
+            tokio::fs::write(&tmp, &contents).await?;
+            tokio::fs::rename(&tmp, &path).await
+        }
+        .await;
+        if written.is_err() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+        }
+        Ok(written?)
+    }
+
+    async fn load(&self, task_id: &TaskId) -> Result<Option<Task>> {
+        match tokio::fs::read(self.path_for(task_id)?).await {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn list(&self, state: Option<&TaskState>) -> Result<Vec<Task>> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        let mut tasks = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            // Skips temporary files of saves in progress, which start with a dot
+            let is_task = path.extension().is_some_and(|ext| ext == "json")
+                && path
+                    .file_name()
+                    .is_some_and(|name| !name.to_string_lossy().starts_with('.'));
+            if !is_task {
+                continue;
+            }
+
+            let task: Task = serde_json::from_slice(&tokio::fs::read(&path).await?)?;
+            if state.is_none_or(|state| &task.state == state) {
+                tasks.push(task);
+            }
+        }
+
+        tasks.sort_by_key(|task| task.created_at);
+        Ok(tasks)
+    }
+
+    async fn delete(&self, task_id: &TaskId) -> Result<bool> {
+        match tokio::fs::remove_file(self.path_for(task_id)?).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::tasks::TaskRequest;
+
+    #[tokio::test]
+    async fn test_save_load_list_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileTaskStore::open(dir.path().join("tasks")).unwrap();
+
+        let queued = Task::new(TaskRequest::default());
+        let mut working = Task::new(TaskRequest::default());
+        working.state = TaskState::Working;
+        store.save(&queued).await.unwrap();
+        store.save(&working).await.unwrap();
+        std::fs::write(store.dir().join(".x.json.1234.tmp"), "{").unwrap();
+
+        let loaded = store.load(&working.id).await.unwrap().unwrap();
+        assert_eq!(loaded.state, TaskState::Working);
+        assert_eq!(store.list(None).await.unwrap().len(), 2);
+        let listed = store.list(Some(&TaskState::Queued)).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, queued.id);
+
+        assert!(store.delete(&queued.id).await.unwrap());
+        assert!(!store.delete(&queued.id).await.unwrap());
+        assert!(store.load(&queued.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_ids_outside_the_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileTaskStore::open(dir.path()).unwrap();
+        let result = store.load(&"../escape".to_string()).await;
+        assert!(matches!(result, Err(ClaudeError::InvalidInput(_))));
+    }
+}
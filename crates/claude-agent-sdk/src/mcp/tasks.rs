@@ -54,12 +54,19 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Persistence
+//!
+//! A manager created with [`TaskManager::with_store`] writes every transition
+//! through to a [`TaskStore`], and [`TaskManager::restore`] picks the tasks up
+//! again after a restart.
 
+use super::task_store::TaskStore;
 use crate::errors::{ClaudeError, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock, watch};
 use uuid::Uuid;
 
 /// Task ID
@@ -97,6 +104,13 @@ pub struct TaskHint {
     /// Whether the task can be cancelled
     #[serde(default)]
     pub cancellable: bool,
+    /// Seconds to keep the task once it finishes
+    ///
+    /// After that, its result is discarded and looking the task up fails with
+    /// [`TaskError::Expired`]. Without a TTL, finished tasks are kept until
+    /// [`TaskManager::cleanup_old_tasks`] removes them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
 }
 
 impl Default for TaskHint {
@@ -105,6 +119,7 @@ impl Default for TaskHint {
             estimated_duration_secs: None,
             supports_progress: false,
             cancellable: true,
+            ttl_secs: None,
         }
     }
 }
@@ -150,7 +165,7 @@ impl TaskState {
 }
 
 /// Task progress
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskProgress {
     /// Progress value between 0.0 and 1.0
     pub value: f64,
@@ -235,22 +250,48 @@ pub struct TaskHandle {
     pub status: TaskStatus,
 }
 
-/// Internal task storage
-#[derive(Debug, Clone)]
-struct Task {
-    id: TaskId,
-    request: TaskRequest,
-    state: TaskState,
-    progress: Option<TaskProgress>,
-    result: Option<serde_json::Value>,
-    error: Option<String>,
-    created_at: chrono::DateTime<chrono::Utc>,
-    updated_at: chrono::DateTime<chrono::Utc>,
-    completed_at: Option<chrono::DateTime<chrono::Utc>>,
+impl TaskHandle {
+    /// Wait until the task finishes and return its result
+    ///
+    /// Works for tasks restored by [`TaskManager::restore`] as well.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TaskManager::await_result`]
+    pub async fn await_result(&self, manager: &TaskManager) -> Result<TaskResult> {
+        manager.await_result(&self.id).await
+    }
+}
+
+/// A task as kept by the [`TaskManager`] and its [`TaskStore`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    /// Task ID
+    pub id: TaskId,
+    /// Request the task was created for
+    pub request: TaskRequest,
+    /// Task state
+    pub state: TaskState,
+    /// Current progress (if available)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<TaskProgress>,
+    /// Result data (if completed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// Error message (if failed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Timestamp when task was created
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Timestamp when task was last updated
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Timestamp when task completed (if terminal)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl Task {
-    fn new(request: TaskRequest) -> Self {
+    pub(crate) fn new(request: TaskRequest) -> Self {
         let now = chrono::Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
@@ -276,16 +317,184 @@ impl Task {
             completed_at: self.completed_at,
         }
     }
+
+    fn finish(&mut self, state: TaskState) {
+        let now = chrono::Utc::now();
+        self.state = state;
+        self.updated_at = now;
+        self.completed_at = Some(now);
+    }
+
+    /// Whether the task finished longer ago than its TTL
+    fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let ttl = self
+            .request
+            .task_hint
+            .as_ref()
+            .and_then(|hint| hint.ttl_secs)
+            .and_then(|secs| chrono::Duration::try_seconds(i64::try_from(secs).ok()?));
+        match (self.completed_at, ttl) {
+            (Some(completed_at), Some(ttl)) => now >= completed_at + ttl,
+            _ => false,
+        }
+    }
+}
+
+/// Task errors that callers may want to tell apart from a missing task
+///
+/// Returned inside [`ClaudeError::Other`]; use
+/// `error.downcast_ref::<TaskError>()` to check for them.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TaskError {
+    /// The task finished longer ago than its TTL, and was discarded
+    #[error("Task expired: {0}")]
+    Expired(TaskId),
+}
+
+/// Signals the code executing a task that the task was cancelled
+///
+/// Get one with [`TaskManager::cancellation_token`]. Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    cancelled: Arc<watch::Sender<bool>>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled yet
+    pub fn new() -> Self {
+        Self {
+            cancelled: Arc::new(watch::channel(false).0),
+        }
+    }
+
+    /// Cancel the token, waking everyone waiting in [`cancelled`](Self::cancelled)
+    pub fn cancel(&self) {
+        self.cancelled.send_replace(true);
+    }
+
+    /// Whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    /// Wait until the token is cancelled
+    pub async fn cancelled(&self) {
+        let mut cancelled = self.cancelled.subscribe();
+        // The sender lives as long as self, so this only returns once cancelled
+        let _ = cancelled.wait_for(|cancelled| *cancelled).await;
+    }
 }
 
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A task together with the state only kept while the process runs
+struct TaskEntry {
+    task: Task,
+    cancellation: CancellationToken,
+    /// Created by the first progress subscription, dropped when the task finishes
+    progress: Option<watch::Sender<TaskProgress>>,
+}
+
+/// Tasks of a [`TaskManager`]
+#[derive(Default)]
+struct TaskTable {
+    entries: HashMap<TaskId, TaskEntry>,
+    /// IDs of tasks discarded after their TTL
+    expired: HashSet<TaskId>,
+}
+
+impl TaskTable {
+    fn insert(&mut self, task: Task) {
+        let cancellation = CancellationToken::new();
+        if task.state == TaskState::Cancelled {
+            cancellation.cancel();
+        }
+        self.entries.insert(
+            task.id.clone(),
+            TaskEntry {
+                task,
+                cancellation,
+                progress: None,
+            },
+        );
+    }
+
+    fn get(&self, task_id: &TaskId) -> Result<&TaskEntry> {
+        match self.entries.get(task_id) {
+            Some(entry) if !entry.task.is_expired(chrono::Utc::now()) => Ok(entry),
+            _ => Err(self.missing(task_id)),
+        }
+    }
+
+    fn get_mut(&mut self, task_id: &TaskId) -> Result<&mut TaskEntry> {
+        let error = self.missing(task_id);
+        match self.entries.get_mut(task_id) {
+            Some(entry) if !entry.task.is_expired(chrono::Utc::now()) => Ok(entry),
+            _ => Err(error),
+        }
+    }
+
+    /// Error for a task that is not (or no longer) available
+    fn missing(&self, task_id: &TaskId) -> ClaudeError {
+        let expired = self.expired.contains(task_id)
+            || self
+                .entries
+                .get(task_id)
+                .is_some_and(|entry| entry.task.is_expired(chrono::Utc::now()));
+        if expired {
+            TaskError::Expired(task_id.clone()).into()
+        } else {
+            ClaudeError::NotFound(format!("Task not found: {}", task_id))
+        }
+    }
+
+    /// Discard the tasks past their TTL, returning their IDs
+    fn remove_expired(&mut self) -> Vec<TaskId> {
+        let now = chrono::Utc::now();
+        let expired: Vec<TaskId> = self
+            .entries
+            .values()
+            .filter(|entry| entry.task.is_expired(now))
+            .map(|entry| entry.task.id.clone())
+            .collect();
+        for task_id in &expired {
+            self.entries.remove(task_id);
+            self.expired.insert(task_id.clone());
+        }
+        expired
+    }
+}
+
+/// What [`TaskManager::restore`] does with tasks that were running when the
+/// process stopped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestorePolicy {
+    /// Mark them as failed, as their work was lost
+    #[default]
+    FailInterrupted,
+    /// Put them back in the queue to be run again
+    Requeue,
+}
+
+/// Error of tasks marked as failed by [`RestorePolicy::FailInterrupted`]
+pub const PROCESS_RESTARTED_ERROR: &str = "process restarted";
+
 /// Task manager
 ///
 /// Manages the lifecycle of async tasks, including creation,
 /// status polling, progress updates, and result retrieval.
 #[derive(Clone)]
 pub struct TaskManager {
-    tasks: Arc<RwLock<HashMap<TaskId, Task>>>,
+    tasks: Arc<RwLock<TaskTable>>,
     base_uri: String,
+    store: Option<Arc<dyn TaskStore>>,
+    restore_policy: RestorePolicy,
+    /// Notified whenever a task finishes
+    finished: Arc<Notify>,
 }
 
 impl TaskManager {
@@ -297,39 +506,151 @@ impl TaskManager {
     /// Create a new task manager with a custom base URI
     pub fn with_base_uri(base_uri: impl Into<String>) -> Self {
         Self {
-            tasks: Arc::new(RwLock::new(HashMap::new())),
+            tasks: Arc::new(RwLock::new(TaskTable::default())),
             base_uri: base_uri.into(),
+            store: None,
+            restore_policy: RestorePolicy::default(),
+            finished: Arc::new(Notify::new()),
         }
     }
 
+    /// Create a new task manager that persists tasks to `store`
+    ///
+    /// Every state transition is saved before the call making it returns.
+    /// Call [`restore`](Self::restore) to load the tasks saved by an earlier
+    /// process.
+    pub fn with_store(store: impl TaskStore + 'static) -> Self {
+        let mut manager = Self::new();
+        manager.store = Some(Arc::new(store));
+        manager
+    }
+
+    /// Set what [`restore`](Self::restore) does with interrupted tasks
+    pub fn with_restore_policy(mut self, policy: RestorePolicy) -> Self {
+        self.restore_policy = policy;
+        self
+    }
+
+    /// Load the tasks saved in the store
+    ///
+    /// Tasks that were working or waiting for input when the process stopped
+    /// are failed with [`PROCESS_RESTARTED_ERROR`] or requeued, depending on the
+    /// [`RestorePolicy`]. Finished tasks are loaded as they are, so their
+    /// results can still be fetched.
+    ///
+    /// Tasks past their TTL are deleted from the store.
+    ///
+    /// Returns handles for the queued tasks, which the caller should run again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::InvalidConfig` if the manager has no store, or any
+    /// error from the store.
+    pub async fn restore(&self) -> Result<Vec<TaskHandle>> {
+        let store = self.store.as_ref().ok_or_else(|| {
+            ClaudeError::InvalidConfig("Task manager has no store to restore from".to_string())
+        })?;
+
+        let mut queued = Vec::new();
+        let mut tasks = self.tasks.write().await;
+        for mut task in store.list(None).await? {
+            if task.is_expired(chrono::Utc::now()) {
+                store.delete(&task.id).await?;
+                tasks.expired.insert(task.id);
+                continue;
+            }
+            if matches!(task.state, TaskState::Working | TaskState::InputRequired) {
+                match self.restore_policy {
+                    RestorePolicy::FailInterrupted => {
+                        task.error = Some(PROCESS_RESTARTED_ERROR.to_string());
+                        task.finish(TaskState::Failed);
+                    },
+                    RestorePolicy::Requeue => {
+                        task.state = TaskState::Queued;
+                        task.progress = None;
+                        task.updated_at = chrono::Utc::now();
+                    },
+                }
+                store.save(&task).await?;
+            }
+
+            if task.state == TaskState::Queued {
+                queued.push(self.handle_for(&task));
+            }
+            tasks.insert(task);
+        }
+        self.finished.notify_waiters();
+
+        Ok(queued)
+    }
+
+    fn handle_for(&self, task: &Task) -> TaskHandle {
+        TaskHandle {
+            id: task.id.clone(),
+            uri: format!("{}/{}", self.base_uri, task.id),
+            status: task.to_status(),
+        }
+    }
+
+    /// Apply `change` to the task, save it to the store, if any, and notify
+    /// whoever waits on the task
+    async fn update(
+        &self,
+        task_id: &TaskId,
+        change: impl FnOnce(&mut Task) -> Result<()>,
+    ) -> Result<()> {
+        let mut tasks = self.tasks.write().await;
+        let entry = tasks.get_mut(task_id)?;
+
+        change(&mut entry.task)?;
+        if let Some(store) = &self.store {
+            store.save(&entry.task).await?;
+        }
+
+        if let (Some(sender), Some(progress)) = (&entry.progress, &entry.task.progress) {
+            sender.send_if_modified(|current| {
+                let modified = current != progress;
+                if modified {
+                    *current = progress.clone();
+                }
+                modified
+            });
+        }
+        if entry.task.state.is_terminal() {
+            if entry.task.state == TaskState::Cancelled {
+                entry.cancellation.cancel();
+            }
+            // Closes the subscribers' channels
+            entry.progress = None;
+            self.finished.notify_waiters();
+        }
+
+        Ok(())
+    }
+
     /// Create a new task
     ///
     /// Returns a task handle immediately with the task in Queued state.
     pub async fn create_task(&self, request: TaskRequest) -> Result<TaskHandle> {
         let task = Task::new(request);
-        let task_id = task.id.clone();
-        let uri = format!("{}/{}", self.base_uri, task_id);
-        let status = task.to_status();
+        let handle = self.handle_for(&task);
 
         // Store the task
         let mut tasks = self.tasks.write().await;
-        tasks.insert(task_id.clone(), task);
+        if let Some(store) = &self.store {
+            store.save(&task).await?;
+        }
+        tasks.insert(task);
 
-        Ok(TaskHandle {
-            id: task_id,
-            uri,
-            status,
-        })
+        Ok(handle)
     }
 
     /// Get task status
+    ///
+    /// Fails with [`TaskError::Expired`] once the task is past its TTL.
     pub async fn get_task_status(&self, task_id: &TaskId) -> Result<TaskStatus> {
         let tasks = self.tasks.read().await;
-        let task = tasks
-            .get(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
-
-        Ok(task.to_status())
+        Ok(tasks.get(task_id)?.task.to_status())
     }
 
     /// Get task result
@@ -337,9 +658,7 @@ impl TaskManager {
     /// Returns an error if the task hasn't completed yet.
     pub async fn get_task_result(&self, task_id: &TaskId) -> Result<TaskResult> {
         let tasks = self.tasks.read().await;
-        let task = tasks
-            .get(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
+        let task = &tasks.get(task_id)?.task;
 
         if task.state != TaskState::Completed {
             return Err(ClaudeError::InvalidInput(format!(
@@ -359,168 +678,207 @@ impl TaskManager {
         })
     }
 
-    /// Update task progress
+    /// Wait until the task finishes and return its result
     ///
-    /// This should be called by the worker executing the task.
-    pub async fn update_progress(&self, task_id: &TaskId, progress: TaskProgress) -> Result<()> {
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::NotFound` if the task does not exist,
+    /// [`TaskError::Expired`] if it is past its TTL, or
+    /// `ClaudeError::InvalidInput` if it failed or was cancelled.
+    pub async fn await_result(&self, task_id: &TaskId) -> Result<TaskResult> {
+        loop {
+            // Registered before checking, so a task finishing in between still wakes us
+            let finished = self.finished.notified();
+            tokio::pin!(finished);
+            finished.as_mut().enable();
+
+            let status = self.get_task_status(task_id).await?;
+            match status.state {
+                TaskState::Completed => return self.get_task_result(task_id).await,
+                TaskState::Failed => {
+                    return Err(ClaudeError::InvalidInput(format!(
+                        "Task failed: {}",
+                        status.error.unwrap_or_default()
+                    )));
+                },
+                TaskState::Cancelled => {
+                    return Err(ClaudeError::InvalidInput("Task was cancelled".to_string()));
+                },
+                _ => finished.await,
+            }
+        }
+    }
+
+    /// Subscribe to the progress of a task
+    ///
+    /// The receiver holds the latest progress, starting at 0 if none was reported
+    /// yet. Its channel closes when the task finishes; check the task's status
+    /// for the outcome.
+    pub async fn subscribe_progress(
+        &self,
+        task_id: &TaskId,
+    ) -> Result<watch::Receiver<TaskProgress>> {
         let mut tasks = self.tasks.write().await;
-        let task = tasks
-            .get_mut(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
-
-        if task.state.is_terminal() {
-            return Err(ClaudeError::InvalidInput(
-                "Cannot update progress for terminal task".to_string(),
-            ));
+        let entry = tasks.get_mut(task_id)?;
+        let progress = entry
+            .task
+            .progress
+            .clone()
+            .unwrap_or_else(|| TaskProgress::new(0.0));
+
+        if entry.task.state.is_terminal() {
+            // Dropping the sender leaves the receiver closed
+            return Ok(watch::channel(progress).1);
         }
+        Ok(entry
+            .progress
+            .get_or_insert_with(|| watch::channel(progress).0)
+            .subscribe())
+    }
 
-        task.progress = Some(progress);
-        task.updated_at = chrono::Utc::now();
+    /// Token the code executing the task should watch to stop once the task
+    /// is cancelled
+    pub async fn cancellation_token(&self, task_id: &TaskId) -> Result<CancellationToken> {
+        let tasks = self.tasks.read().await;
+        Ok(tasks.get(task_id)?.cancellation.clone())
+    }
 
-        Ok(())
+    /// Update task progress
+    ///
+    /// This should be called by the worker executing the task.
+    pub async fn update_progress(&self, task_id: &TaskId, progress: TaskProgress) -> Result<()> {
+        self.update(task_id, |task| {
+            if task.state.is_terminal() {
+                return Err(ClaudeError::InvalidInput(
+                    "Cannot update progress for terminal task".to_string(),
+                ));
+            }
+
+            task.progress = Some(progress);
+            task.updated_at = chrono::Utc::now();
+            Ok(())
+        })
+        .await
     }
 
     /// Mark task as working
     pub async fn mark_working(&self, task_id: &TaskId) -> Result<()> {
-        let mut tasks = self.tasks.write().await;
-        let task = tasks
-            .get_mut(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
-
-        if task.state.is_terminal() {
-            return Err(ClaudeError::InvalidInput(
-                "Cannot transition terminal task".to_string(),
-            ));
-        }
-
-        task.state = TaskState::Working;
-        task.updated_at = chrono::Utc::now();
-
-        Ok(())
+        self.update(task_id, |task| {
+            ensure_not_terminal(task)?;
+            task.state = TaskState::Working;
+            task.updated_at = chrono::Utc::now();
+            Ok(())
+        })
+        .await
     }
 
     /// Mark task as completed with result
     pub async fn mark_completed(&self, task_id: &TaskId, result: serde_json::Value) -> Result<()> {
-        let mut tasks = self.tasks.write().await;
-        let task = tasks
-            .get_mut(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
-
-        if task.state.is_terminal() {
-            return Err(ClaudeError::InvalidInput(
-                "Cannot transition terminal task".to_string(),
-            ));
-        }
-
-        let now = chrono::Utc::now();
-        task.state = TaskState::Completed;
-        task.result = Some(result);
-        task.updated_at = now;
-        task.completed_at = Some(now);
-
-        Ok(())
+        self.update(task_id, |task| {
+            ensure_not_terminal(task)?;
+            task.result = Some(result);
+            task.finish(TaskState::Completed);
+            Ok(())
+        })
+        .await
     }
 
     /// Mark task as failed
     pub async fn mark_failed(&self, task_id: &TaskId, error: impl Into<String>) -> Result<()> {
-        let mut tasks = self.tasks.write().await;
-        let task = tasks
-            .get_mut(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
-
-        if task.state.is_terminal() {
-            return Err(ClaudeError::InvalidInput(
-                "Cannot transition terminal task".to_string(),
-            ));
-        }
-
-        let now = chrono::Utc::now();
-        task.state = TaskState::Failed;
-        task.error = Some(error.into());
-        task.updated_at = now;
-        task.completed_at = Some(now);
-
-        Ok(())
+        self.update(task_id, |task| {
+            ensure_not_terminal(task)?;
+            task.error = Some(error.into());
+            task.finish(TaskState::Failed);
+            Ok(())
+        })
+        .await
     }
 
     /// Mark task as cancelled
     pub async fn mark_cancelled(&self, task_id: &TaskId) -> Result<()> {
-        let mut tasks = self.tasks.write().await;
-        let task = tasks
-            .get_mut(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
-
-        if task.state.is_terminal() {
-            return Err(ClaudeError::InvalidInput(
-                "Cannot transition terminal task".to_string(),
-            ));
-        }
-
-        let now = chrono::Utc::now();
-        task.state = TaskState::Cancelled;
-        task.updated_at = now;
-        task.completed_at = Some(now);
-
-        Ok(())
+        self.update(task_id, |task| {
+            ensure_not_terminal(task)?;
+            task.finish(TaskState::Cancelled);
+            Ok(())
+        })
+        .await
     }
 
     /// Mark task as requiring input
     pub async fn mark_input_required(&self, task_id: &TaskId) -> Result<()> {
-        let mut tasks = self.tasks.write().await;
-        let task = tasks
-            .get_mut(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
-
-        if task.state.is_terminal() {
-            return Err(ClaudeError::InvalidInput(
-                "Cannot transition terminal task".to_string(),
-            ));
-        }
-
-        task.state = TaskState::InputRequired;
-        task.updated_at = chrono::Utc::now();
-
-        Ok(())
+        self.update(task_id, |task| {
+            ensure_not_terminal(task)?;
+            task.state = TaskState::InputRequired;
+            task.updated_at = chrono::Utc::now();
+            Ok(())
+        })
+        .await
     }
 
     /// List all tasks
+    ///
+    /// Tasks past their TTL are discarded first.
     pub async fn list_tasks(&self) -> Result<Vec<TaskStatus>> {
+        self.purge_expired().await?;
         let tasks = self.tasks.read().await;
-        Ok(tasks.values().map(|t| t.to_status()).collect())
+        Ok(tasks
+            .entries
+            .values()
+            .map(|entry| entry.task.to_status())
+            .collect())
     }
 
-    /// Cancel a task
+    /// Cancel a task, signalling its [`CancellationToken`]
     ///
-    /// Returns an error if the task is already in a terminal state
-    /// or doesn't support cancellation.
-    pub async fn cancel_task(&self, task_id: &TaskId) -> Result<()> {
-        let mut tasks = self.tasks.write().await;
-        let task = tasks
-            .get_mut(task_id)
-            .ok_or_else(|| ClaudeError::NotFound(format!("Task not found: {}", task_id)))?;
+    /// Returns the task's state afterwards. Unlike [`cancel_task`](Self::cancel_task),
+    /// cancelling a finished task is not an error: it is left as it is and its
+    /// state returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::InvalidInput` if the task is not cancellable.
+    pub async fn cancel(&self, task_id: &TaskId) -> Result<TaskState> {
+        let state = self.get_task_status(task_id).await?.state;
+        if state.is_terminal() {
+            return Ok(state);
+        }
 
-        if task.state.is_terminal() {
-            return Err(ClaudeError::InvalidInput(format!(
-                "Cannot cancel task in state: {:?}",
-                task.state
-            )));
+        match self.cancel_task(task_id).await {
+            Ok(()) => Ok(TaskState::Cancelled),
+            Err(e) => match self.get_task_status(task_id).await?.state {
+                // Finished in the meantime
+                state if state.is_terminal() => Ok(state),
+                _ => Err(e),
+            },
         }
+    }
 
-        // Check if task is cancellable (based on request hint)
-        if let Some(hint) = &task.request.task_hint {
-            if !hint.cancellable {
-                return Err(ClaudeError::InvalidInput(
-                    "Task is not cancellable".to_string(),
-                ));
+    /// Cancel a task
+    ///
+    /// Returns an error if the task is already in a terminal state
+    /// or doesn't support cancellation. See also [`cancel`](Self::cancel).
+    pub async fn cancel_task(&self, task_id: &TaskId) -> Result<()> {
+        self.update(task_id, |task| {
+            if task.state.is_terminal() {
+                return Err(ClaudeError::InvalidInput(format!(
+                    "Cannot cancel task in state: {:?}",
+                    task.state
+                )));
             }
-        }
 
-        let now = chrono::Utc::now();
-        task.state = TaskState::Cancelled;
-        task.updated_at = now;
-        task.completed_at = Some(now);
+            // Check if task is cancellable (based on request hint)
+            if let Some(hint) = &task.request.task_hint {
+                if !hint.cancellable {
+                    return Err(ClaudeError::InvalidInput(
+                        "Task is not cancellable".to_string(),
+                    ));
+                }
+            }
 
-        Ok(())
+            task.finish(TaskState::Cancelled);
+            Ok(())
+        })
+        .await
     }
 
     /// Clean up old completed tasks
@@ -530,17 +888,52 @@ impl TaskManager {
         let mut tasks = self.tasks.write().await;
         let cutoff = chrono::Utc::now() - older_than;
 
-        let initial_count = tasks.len();
-        tasks.retain(|_, task| {
-            if let Some(completed_at) = task.completed_at {
-                completed_at > cutoff
-            } else {
-                true // Keep active tasks
+        let expired: Vec<TaskId> = tasks
+            .entries
+            .values()
+            // Active tasks have no completion time and are kept
+            .filter(|entry| {
+                entry
+                    .task
+                    .completed_at
+                    .is_some_and(|completed_at| completed_at <= cutoff)
+            })
+            .map(|entry| entry.task.id.clone())
+            .collect();
+
+        for task_id in &expired {
+            if let Some(store) = &self.store {
+                store.delete(task_id).await?;
             }
-        });
+            tasks.entries.remove(task_id);
+        }
+
+        Ok(expired.len())
+    }
+
+    /// Discard the tasks past their TTL
+    ///
+    /// Looking a discarded task up fails with [`TaskError::Expired`]. Returns the
+    /// number of tasks discarded.
+    pub async fn purge_expired(&self) -> Result<usize> {
+        let mut tasks = self.tasks.write().await;
+        let expired = tasks.remove_expired();
+        if let Some(store) = &self.store {
+            for task_id in &expired {
+                store.delete(task_id).await?;
+            }
+        }
+        Ok(expired.len())
+    }
+}
 
-        Ok(initial_count - tasks.len())
+fn ensure_not_terminal(task: &Task) -> Result<()> {
+    if task.state.is_terminal() {
+        return Err(ClaudeError::InvalidInput(
+            "Cannot transition terminal task".to_string(),
+        ));
     }
+    Ok(())
 }
 
 impl Default for TaskManager {
@@ -552,6 +945,7 @@ impl Default for TaskManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mcp::task_store::FileTaskStore;
     use serde_json::json;
 
     #[tokio::test]
@@ -779,4 +1173,207 @@ mod tests {
             .unwrap();
         assert_eq!(cleaned, 1);
     }
+
+    #[tokio::test]
+    async fn test_await_result_waits_for_completion() {
+        let manager = TaskManager::new();
+        let handle = manager.create_task(TaskRequest::default()).await.unwrap();
+
+        let worker = manager.clone();
+        let task_id = handle.id.clone();
+        tokio::spawn(async move {
+            worker.mark_working(&task_id).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            worker.mark_completed(&task_id, json!(42)).await.unwrap();
+        });
+
+        let result = handle.await_result(&manager).await.unwrap();
+        assert_eq!(result.data, json!(42));
+
+        let failed = manager.create_task(TaskRequest::default()).await.unwrap();
+        manager.mark_failed(&failed.id, "boom").await.unwrap();
+        assert!(failed.await_result(&manager).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restore_after_restart_fails_interrupted_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let (queued, working, completed) = {
+            let manager = TaskManager::with_store(FileTaskStore::open(dir.path()).unwrap());
+            let queued = manager.create_task(TaskRequest::default()).await.unwrap();
+            let working = manager.create_task(TaskRequest::default()).await.unwrap();
+            manager.mark_working(&working.id).await.unwrap();
+            let completed = manager.create_task(TaskRequest::default()).await.unwrap();
+            manager
+                .mark_completed(&completed.id, json!({"output": "done"}))
+                .await
+                .unwrap();
+            (queued, working, completed)
+        };
+
+        let manager = TaskManager::with_store(FileTaskStore::open(dir.path()).unwrap());
+        let to_run = manager.restore().await.unwrap();
+        assert_eq!(to_run.len(), 1);
+        assert_eq!(to_run[0].id, queued.id);
+
+        let status = manager.get_task_status(&working.id).await.unwrap();
+        assert_eq!(status.state, TaskState::Failed);
+        assert_eq!(status.error.as_deref(), Some(PROCESS_RESTARTED_ERROR));
+        assert!(working.await_result(&manager).await.is_err());
+
+        let result = completed.await_result(&manager).await.unwrap();
+        assert_eq!(result.data, json!({"output": "done"}));
+
+        // The failure was written back, so a later restart sees it too
+        let stored = FileTaskStore::open(dir.path())
+            .unwrap()
+            .load(&working.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.state, TaskState::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_restore_after_restart_requeues_interrupted_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let working = {
+            let manager = TaskManager::with_store(FileTaskStore::open(dir.path()).unwrap());
+            let working = manager.create_task(TaskRequest::default()).await.unwrap();
+            manager.mark_working(&working.id).await.unwrap();
+            manager
+                .update_progress(&working.id, TaskProgress::new(0.5))
+                .await
+                .unwrap();
+            working
+        };
+
+        let manager = TaskManager::with_store(FileTaskStore::open(dir.path()).unwrap())
+            .with_restore_policy(RestorePolicy::Requeue);
+        let to_run = manager.restore().await.unwrap();
+        assert_eq!(to_run.len(), 1);
+        assert_eq!(to_run[0].status.state, TaskState::Queued);
+        assert!(to_run[0].status.progress.is_none());
+
+        let worker = manager.clone();
+        let task_id = to_run[0].id.clone();
+        tokio::spawn(async move {
+            worker.mark_completed(&task_id, json!("rerun")).await.unwrap();
+        });
+        let result = working.await_result(&manager).await.unwrap();
+        assert_eq!(result.data, json!("rerun"));
+    }
+
+    #[tokio::test]
+    async fn test_restore_without_store_fails() {
+        let result = TaskManager::new().restore().await;
+        assert!(matches!(result, Err(ClaudeError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_deletes_from_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = TaskManager::with_store(FileTaskStore::open(dir.path()).unwrap());
+        let handle = manager.create_task(TaskRequest::default()).await.unwrap();
+        manager.mark_completed(&handle.id, json!({})).await.unwrap();
+
+        manager
+            .cleanup_old_tasks(chrono::Duration::seconds(0))
+            .await
+            .unwrap();
+
+        let store = FileTaskStore::open(dir.path()).unwrap();
+        assert!(store.list(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_signals_token_and_ignores_finished_tasks() {
+        let manager = TaskManager::new();
+        let handle = manager.create_task(TaskRequest::default()).await.unwrap();
+        let token = manager.cancellation_token(&handle.id).await.unwrap();
+
+        let worker = tokio::spawn(async move {
+            token.cancelled().await;
+            "stopped"
+        });
+        manager.mark_working(&handle.id).await.unwrap();
+        assert_eq!(
+            manager.cancel(&handle.id).await.unwrap(),
+            TaskState::Cancelled
+        );
+        assert_eq!(worker.await.unwrap(), "stopped");
+
+        let done = manager.create_task(TaskRequest::default()).await.unwrap();
+        manager.mark_completed(&done.id, json!(1)).await.unwrap();
+        assert_eq!(manager.cancel(&done.id).await.unwrap(), TaskState::Completed);
+        assert!(
+            !manager
+                .cancellation_token(&done.id)
+                .await
+                .unwrap()
+                .is_cancelled()
+        );
+        assert_eq!(
+            manager.cancel(&handle.id).await.unwrap(),
+            TaskState::Cancelled
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_task_is_reported_as_expired() {
+        let manager = TaskManager::new();
+        let request = TaskRequest {
+            task_hint: Some(TaskHint {
+                ttl_secs: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let handle = manager.create_task(request).await.unwrap();
+
+        // The TTL only starts once the task finishes
+        manager.mark_working(&handle.id).await.unwrap();
+        manager.mark_completed(&handle.id, json!(1)).await.unwrap();
+
+        let expired = |result: Result<TaskResult>| {
+            let error = result.unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<TaskError>(),
+                Some(&TaskError::Expired(handle.id.clone()))
+            );
+        };
+        expired(manager.get_task_result(&handle.id).await);
+        assert_eq!(manager.purge_expired().await.unwrap(), 1);
+        expired(manager.get_task_result(&handle.id).await);
+        expired(handle.await_result(&manager).await);
+        assert!(manager.list_tasks().await.unwrap().is_empty());
+
+        let unknown = manager.get_task_status(&"missing".to_string()).await;
+        assert!(matches!(unknown, Err(ClaudeError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_progress() {
+        let manager = TaskManager::new();
+        let handle = manager.create_task(TaskRequest::default()).await.unwrap();
+        let mut progress = manager.subscribe_progress(&handle.id).await.unwrap();
+        assert_eq!(progress.borrow().value, 0.0);
+
+        manager
+            .update_progress(&handle.id, TaskProgress::new(0.5).with_message("Half"))
+            .await
+            .unwrap();
+        progress.changed().await.unwrap();
+        assert_eq!(
+            *progress.borrow_and_update(),
+            TaskProgress::new(0.5).with_message("Half")
+        );
+
+        // The channel closes once the task finishes
+        manager.mark_completed(&handle.id, json!(1)).await.unwrap();
+        assert!(progress.changed().await.is_err());
+        let finished = manager.subscribe_progress(&handle.id).await.unwrap();
+        assert_eq!(finished.borrow().value, 0.5);
+        assert!(finished.has_changed().is_err());
+    }
 }
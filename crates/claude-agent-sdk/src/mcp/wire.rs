@@ -0,0 +1,192 @@
+//! MCP task methods as sent by the CLI to SDK MCP servers
+
+use std::sync::Arc;
+
+use serde_json::{Value, json};
+
+use super::tasks::{TaskError, TaskId, TaskManager, TaskRequest, TaskState, TaskStatus};
+use crate::errors::{ClaudeError, Result};
+use crate::types::mcp::{McpError, SdkMcpServer, ToolCallContext};
+
+/// Metadata key linking a result to the task that produced it
+const RELATED_TASK_META_KEY: &str = "io.modelcontextprotocol/related-task";
+
+/// The `tasks` capability advertised in the `initialize` result
+pub(crate) fn capabilities() -> Value {
+    json!({
+        "list": {},
+        "cancel": {},
+        "requests": {"tools": {"call": {}}}
+    })
+}
+
+/// Whether the request is served by the task manager rather than the server
+pub(crate) fn is_task_request(method: &str, params: &Value) -> bool {
+    method.starts_with("tasks/") || (method == "tools/call" && params.get("task").is_some())
+}
+
+/// Handle a request for which [`is_task_request`] holds
+pub(crate) async fn handle(
+    manager: &TaskManager,
+    server: &Arc<dyn SdkMcpServer>,
+    message: Value,
+    ctx: ToolCallContext,
+) -> Result<Value> {
+    let method = message["method"].as_str().unwrap_or_default();
+    let params = &message["params"];
+    match method {
+        "tools/call" => {
+            let mut params = params.clone();
+            let task = params
+                .as_object_mut()
+                .and_then(|params| params.remove("task"))
+                .unwrap_or_default();
+            let mut request = TaskRequest {
+                method: method.to_string(),
+                params,
+                ..Default::default()
+            };
+            // The protocol gives the TTL in milliseconds
+            if let Some(ttl_ms) = task["ttl"].as_u64() {
+                request
+                    .task_hint
+                    .get_or_insert_with(Default::default)
+                    .ttl_secs = Some(ttl_ms.div_ceil(1000));
+            }
+
+            let status = start(manager, server, request, ctx).await?;
+            Ok(json!({"task": task_json(&status)}))
+        },
+        "tasks/create" => {
+            let request: TaskRequest = serde_json::from_value(params.clone())
+                .map_err(|e| McpError::invalid_params(format!("Invalid task request: {}", e)))?;
+            if request.method != "tools/call" {
+                return Err(McpError::invalid_params(format!(
+                    "Cannot run {} as a task, only tools/call",
+                    request.method
+                ))
+                .into());
+            }
+
+            let status = start(manager, server, request, ctx).await?;
+            Ok(json!({"task": task_json(&status)}))
+        },
+        "tasks/get" => {
+            let status = manager
+                .get_task_status(&task_id(params)?)
+                .await
+                .map_err(unknown_task)?;
+            Ok(task_json(&status))
+        },
+        "tasks/result" => {
+            let task_id = task_id(params)?;
+            let result = manager.await_result(&task_id).await.map_err(unknown_task)?;
+
+            let mut data = result.data;
+            if let Some(object) = data.as_object_mut() {
+                object.insert(
+                    "_meta".to_string(),
+                    json!({RELATED_TASK_META_KEY: {"taskId": task_id}}),
+                );
+            }
+            Ok(data)
+        },
+        "tasks/cancel" => {
+            let task_id = task_id(params)?;
+            manager.cancel(&task_id).await.map_err(unknown_task)?;
+            let status = manager
+                .get_task_status(&task_id)
+                .await
+                .map_err(unknown_task)?;
+            Ok(task_json(&status))
+        },
+        "tasks/list" => {
+            let tasks: Vec<Value> = manager.list_tasks().await?.iter().map(task_json).collect();
+            Ok(json!({"tasks": tasks}))
+        },
+        _ => Err(McpError::method_not_found(method).into()),
+    }
+}
+
+/// Create a task for `request` and run it on `server` in the background
+async fn start(
+    manager: &TaskManager,
+    server: &Arc<dyn SdkMcpServer>,
+    request: TaskRequest,
+    ctx: ToolCallContext,
+) -> Result<TaskStatus> {
+    let message = json!({"method": request.method, "params": request.params});
+    let handle = manager.create_task(request).await?;
+    let cancellation = manager.cancellation_token(&handle.id).await?;
+
+    let manager = manager.clone();
+    let server = Arc::clone(server);
+    let task_id = handle.id.clone();
+    tokio::spawn(async move {
+        // Fails if the task was cancelled before it started
+        if manager.mark_working(&task_id).await.is_err() {
+            return;
+        }
+        tokio::select! {
+            _ = cancellation.cancelled() => {},
+            result = server.handle_message_with_context(message, ctx) => {
+                let _ = match result {
+                    Ok(result) => manager.mark_completed(&task_id, result).await,
+                    Err(e) => manager.mark_failed(&task_id, e.to_string()).await,
+                };
+            },
+        }
+    });
+
+    Ok(handle.status)
+}
+
+fn task_id(params: &Value) -> Result<TaskId> {
+    params["taskId"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| McpError::invalid_params("Missing taskId").into())
+}
+
+/// Report missing and expired tasks as invalid parameters
+fn unknown_task(error: ClaudeError) -> ClaudeError {
+    match &error {
+        ClaudeError::NotFound(message) => McpError::invalid_params(message.clone()).into(),
+        _ if error.downcast_ref::<TaskError>().is_some() => {
+            McpError::invalid_params(error.to_string()).into()
+        },
+        // Failed and cancelled tasks
+        ClaudeError::InvalidInput(message) => McpError::internal(message.clone()).into(),
+        _ => error,
+    }
+}
+
+/// A task in the protocol's format
+fn task_json(status: &TaskStatus) -> Value {
+    let wire_status = match status.state {
+        // The protocol has no separate state for tasks not started yet
+        TaskState::Queued | TaskState::Working => "working",
+        TaskState::InputRequired => "input_required",
+        TaskState::Completed => "completed",
+        TaskState::Failed => "failed",
+        TaskState::Cancelled => "cancelled",
+    };
+
+    let mut task = json!({
+        "taskId": status.id,
+        "status": wire_status,
+        "createdAt": status.created_at.to_rfc3339(),
+        "lastUpdatedAt": status.updated_at.to_rfc3339(),
+    });
+    let message = status
+        .error
+        .clone()
+        .or_else(|| status.progress.as_ref()?.message.clone());
+    if let Some(message) = message {
+        task["statusMessage"] = json!(message);
+    }
+    if let Some(progress) = &status.progress {
+        task["progress"] = json!(progress.value);
+    }
+    task
+}
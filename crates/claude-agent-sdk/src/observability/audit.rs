@@ -0,0 +1,486 @@
+//! # Tool Audit Log
+//!
+//! A record of every tool Claude ran, for compliance reviews. When
+//! [`ClaudeAgentOptions::audit_sink`](crate::ClaudeAgentOptions::audit_sink) is
+//! set, `query()`, `query_stream()` and [`ClaudeClient`](crate::ClaudeClient)
+//! record an [`AuditEvent`] for each tool use and tool result in the message
+//! stream, without any hooks.
+//!
+//! Events are numbered in the order they are recorded. With
+//! [`AuditLog::with_hash_chain`], each also carries the SHA-256 hash of its
+//! contents and of the event before it, so editing, removing or reordering
+//! events in a log file is detected by [`AuditLog::verify`].
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use claude_agent_sdk::ClaudeAgentOptions;
+//! use claude_agent_sdk::observability::{AuditLog, JsonlAuditSink};
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let log = AuditLog::new(JsonlAuditSink::open("tools.audit.jsonl")?).with_hash_chain();
+//! let options = ClaudeAgentOptions::builder()
+//!     .audit_sink(Arc::new(log))
+//!     .build();
+//! // ... run queries ...
+//!
+//! let events = AuditLog::verify("tools.audit.jsonl")?;
+//! println!("{events} audit events intact");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::types::config::PermissionMode;
+
+/// Default limit on the size of a recorded tool output
+pub const DEFAULT_AUDIT_OUTPUT_LIMIT: usize = 8 * 1024;
+
+/// What an [`AuditEvent`] records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    /// Claude asked to run a tool
+    ToolUse,
+    /// The tool's result was returned to Claude
+    ToolResult,
+}
+
+/// One entry of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// Position in the log, starting at 0; set by [`AuditLog::record`]
+    pub sequence: u64,
+    /// When the event was recorded
+    pub timestamp: DateTime<Utc>,
+    /// Tool use or tool result
+    pub kind: AuditEventKind,
+    /// Session the tool ran in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// ID linking a tool result to its tool use
+    pub tool_use_id: String,
+    /// Name of the tool, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// Input of a tool use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Value>,
+    /// Output of a tool result, possibly truncated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    /// Whether `output` was cut to the log's output limit
+    #[serde(default)]
+    pub output_truncated: bool,
+    /// Whether the tool result reported an error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+    /// Permission mode the CLI ran the tool under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_mode: Option<PermissionMode>,
+    /// Whether the tool was allowed up front by
+    /// [`ClaudeAgentOptions::allowed_tools`](crate::ClaudeAgentOptions::allowed_tools)
+    #[serde(default)]
+    pub pre_approved: bool,
+    /// Hash of the previous event, with a hash chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
+    /// Hash of this event, with a hash chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+impl AuditEvent {
+    /// Event of `kind` for `tool_use_id`, recorded now
+    pub fn new(kind: AuditEventKind, tool_use_id: impl Into<String>) -> Self {
+        Self {
+            sequence: 0,
+            timestamp: Utc::now(),
+            kind,
+            session_id: None,
+            tool_use_id: tool_use_id.into(),
+            tool_name: None,
+            input: None,
+            output: None,
+            output_truncated: false,
+            is_error: None,
+            permission_mode: None,
+            pre_approved: false,
+            prev_hash: None,
+            hash: None,
+        }
+    }
+
+    /// Hash of everything but the `hash` field, as `sha256:` and hex digits
+    fn compute_hash(&self) -> String {
+        let unhashed = Self {
+            hash: None,
+            ..self.clone()
+        };
+        let bytes = serde_json::to_vec(&unhashed).expect("audit events serialize to JSON");
+        let hex: String = Sha256::digest(&bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("sha256:{}", hex)
+    }
+}
+
+/// Storage for audit events
+pub trait AuditSink: Send + Sync {
+    /// Store `event`, which is final
+    fn record(&self, event: AuditEvent) -> std::io::Result<()>;
+
+    /// The last event stored before this sink was opened, to continue its log
+    fn last_event(&self) -> Option<AuditEvent> {
+        None
+    }
+}
+
+/// Appends audit events to a JSONL file, one per line
+pub struct JsonlAuditSink {
+    path: PathBuf,
+    file: Mutex<File>,
+    last_event: Option<AuditEvent>,
+}
+
+impl JsonlAuditSink {
+    /// Open `path` for appending, creating it if needed
+    ///
+    /// An existing log is continued: its numbering and hash chain carry on
+    /// from its last event.
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let last_event = match File::open(&path) {
+            Ok(file) => BufReader::new(file)
+                .lines()
+                .map_while(std::result::Result::ok)
+                .filter(|line| !line.trim().is_empty())
+                .last()
+                .map(|line| serde_json::from_str(&line))
+                .transpose()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            last_event,
+        })
+    }
+
+    /// Path of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AuditSink for JsonlAuditSink {
+    fn record(&self, event: AuditEvent) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(&event)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().unwrap();
+        file.write_all(&line)?;
+        file.flush()
+    }
+
+    fn last_event(&self) -> Option<AuditEvent> {
+        self.last_event.clone()
+    }
+}
+
+/// Keeps audit events in memory, e.g. for tests
+///
+/// Clones share the same events, so keep one to read what was recorded.
+#[derive(Clone, Default)]
+pub struct MemoryAuditSink {
+    events: Arc<Mutex<Vec<AuditEvent>>>,
+}
+
+impl MemoryAuditSink {
+    /// Empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events recorded so far
+    pub fn events(&self) -> Vec<AuditEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn record(&self, event: AuditEvent) -> std::io::Result<()> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn last_event(&self) -> Option<AuditEvent> {
+        self.events.lock().unwrap().last().cloned()
+    }
+}
+
+/// Why [`AuditLog::verify`] rejected a log
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    /// The log could not be read
+    #[error("Failed to read audit log: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A line is not an audit event
+    #[error("Line {line} is not an audit event: {source}")]
+    Parse {
+        /// Line number, starting at 1
+        line: usize,
+        /// The parse error
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Events are missing or out of order
+    #[error("Line {line} has sequence {found}, expected {expected}")]
+    Sequence {
+        /// Line number, starting at 1
+        line: usize,
+        /// Sequence following the previous event
+        expected: u64,
+        /// Sequence of the event on this line
+        found: u64,
+    },
+
+    /// An event does not match its hash, or its predecessor's
+    #[error("Hash chain broken at sequence {sequence} (line {line})")]
+    BrokenChain {
+        /// Line number, starting at 1
+        line: usize,
+        /// Sequence of the event on this line
+        sequence: u64,
+    },
+}
+
+#[derive(Default)]
+struct ChainState {
+    next_sequence: u64,
+    last_hash: Option<String>,
+}
+
+/// Numbers audit events, optionally hash-chains them, and stores them in a sink
+pub struct AuditLog {
+    sink: Box<dyn AuditSink>,
+    hash_chain: bool,
+    output_limit: usize,
+    state: Mutex<ChainState>,
+}
+
+impl AuditLog {
+    /// Log into `sink`, continuing after the last event it already holds
+    pub fn new(sink: impl AuditSink + 'static) -> Self {
+        let state = sink
+            .last_event()
+            .map(|last| ChainState {
+                next_sequence: last.sequence + 1,
+                last_hash: last.hash,
+            })
+            .unwrap_or_default();
+        Self {
+            sink: Box::new(sink),
+            hash_chain: false,
+            output_limit: DEFAULT_AUDIT_OUTPUT_LIMIT,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Chain the events together by their SHA-256 hashes
+    pub fn with_hash_chain(mut self) -> Self {
+        self.hash_chain = true;
+        self
+    }
+
+    /// Truncate tool outputs longer than `bytes`
+    ///
+    /// Default: [`DEFAULT_AUDIT_OUTPUT_LIMIT`]
+    pub fn with_output_limit(mut self, bytes: usize) -> Self {
+        self.output_limit = bytes;
+        self
+    }
+
+    /// Number `event`, truncate its output, chain it, and store it
+    ///
+    /// Failing to store it is logged rather than returned, so auditing never
+    /// interrupts a query.
+    pub fn record(&self, mut event: AuditEvent) {
+        if let Some(output) = event.output.take() {
+            let (output, truncated) = truncate_output(output, self.output_limit);
+            event.output = Some(output);
+            event.output_truncated |= truncated;
+        }
+
+        // Held while storing, so events are stored in sequence order
+        let mut state = self.state.lock().unwrap();
+        event.sequence = state.next_sequence;
+        event.prev_hash = None;
+        event.hash = None;
+        if self.hash_chain {
+            event.prev_hash = state.last_hash.clone();
+            event.hash = Some(event.compute_hash());
+        }
+
+        let hash = event.hash.clone();
+        match self.sink.record(event) {
+            Ok(()) => {
+                state.next_sequence += 1;
+                state.last_hash = hash;
+            },
+            Err(e) => warn!("Failed to record audit event: {}", e),
+        }
+    }
+
+    /// Check the JSONL log at `path` for gaps and tampering
+    ///
+    /// Sequences must increase by one from the first event. Events with a hash
+    /// must match it and name their predecessor's hash. Returns the number of
+    /// events checked.
+    pub fn verify(path: impl AsRef<Path>) -> std::result::Result<u64, AuditError> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut previous: Option<AuditEvent> = None;
+        let mut count = 0;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let number = index + 1;
+            let event: AuditEvent =
+                serde_json::from_str(&line).map_err(|source| AuditError::Parse {
+                    line: number,
+                    source,
+                })?;
+
+            if let Some(previous) = &previous
+                && event.sequence != previous.sequence + 1
+            {
+                return Err(AuditError::Sequence {
+                    line: number,
+                    expected: previous.sequence + 1,
+                    found: event.sequence,
+                });
+            }
+            if let Some(hash) = &event.hash {
+                let previous_hash = previous.as_ref().and_then(|p| p.hash.as_ref());
+                let linked = previous.is_none() || event.prev_hash.as_ref() == previous_hash;
+                if !linked || *hash != event.compute_hash() {
+                    return Err(AuditError::BrokenChain {
+                        line: number,
+                        sequence: event.sequence,
+                    });
+                }
+            }
+
+            previous = Some(event);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Cut `output` to about `limit` bytes of text or JSON
+fn truncate_output(output: Value, limit: usize) -> (Value, bool) {
+    let text = match output {
+        Value::String(text) => text,
+        other => {
+            let json = other.to_string();
+            if json.len() <= limit {
+                return (other, false);
+            }
+            json
+        },
+    };
+    if text.len() <= limit {
+        return (Value::String(text), false);
+    }
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (Value::String(text[..end].to_string()), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_use(id: &str) -> AuditEvent {
+        AuditEvent {
+            tool_name: Some("Bash".to_string()),
+            input: Some(json!({"command": "ls"})),
+            ..AuditEvent::new(AuditEventKind::ToolUse, id)
+        }
+    }
+
+    #[test]
+    fn test_numbers_and_truncates_events() {
+        let sink = MemoryAuditSink::new();
+        let log = AuditLog::new(sink.clone()).with_output_limit(3);
+
+        log.record(tool_use("t1"));
+        log.record(AuditEvent {
+            output: Some(json!("héllo world")),
+            ..AuditEvent::new(AuditEventKind::ToolResult, "t1")
+        });
+
+        let events = sink.events();
+        assert_eq!(events[0].sequence, 0);
+        assert_eq!(events[1].sequence, 1);
+        assert_eq!(events[1].output, Some(json!("hé")));
+        assert!(events[1].output_truncated);
+        assert!(events[0].hash.is_none());
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::new(JsonlAuditSink::open(&path).unwrap()).with_hash_chain();
+        for id in ["t1", "t2", "t3"] {
+            log.record(tool_use(id));
+        }
+        assert_eq!(AuditLog::verify(&path).unwrap(), 3);
+
+        // Reopening continues the chain
+        let log = AuditLog::new(JsonlAuditSink::open(&path).unwrap()).with_hash_chain();
+        log.record(tool_use("t4"));
+        assert_eq!(AuditLog::verify(&path).unwrap(), 4);
+
+        let original = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, original.replace("\"ls\"", "\"rm\"")).unwrap();
+        assert!(matches!(
+            AuditLog::verify(&path),
+            Err(AuditError::BrokenChain { line: 1, .. })
+        ));
+
+        let lines: Vec<&str> = original.lines().collect();
+        std::fs::write(&path, [lines[0], lines[2]].join("\n")).unwrap();
+        assert!(matches!(
+            AuditLog::verify(&path),
+            Err(AuditError::Sequence {
+                expected: 1,
+                found: 2,
+                ..
+            })
+        ));
+    }
+}
@@ -10,6 +10,7 @@
 //! - **Histogram Metrics**: Track distributions (latency, request sizes)
 //! - **Labeled Metrics**: Support for dimensional data
 //! - **Thread-Safe**: Safe for concurrent access from multiple threads
+//! - **Snapshots**: Immutable copies for test assertions and NDJSON export
 //!
 //! ## Example
 //!
@@ -25,11 +26,14 @@
 //! ```
 
 use std::collections::HashMap;
+use std::io::Write;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
+use super::snapshot::{MetricSeries, MetricsSnapshot};
+
 /// Kind of metric
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum MetricKind {
     /// Counter: cumulative value that only increases
     Counter,
@@ -71,10 +75,7 @@ impl LabeledMetric {
         value: f64,
         labels: Vec<(String, String)>,
     ) -> Self {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+        let timestamp = now_millis();
 
         Self {
             name: name.into(),
@@ -217,6 +218,31 @@ pub trait MetricStorage: Send + Sync {
     fn get_gauge(&self, name: &str, labels: &[(String, String)]) -> f64;
     fn get_histogram(&self, name: &str, labels: &[(String, String)]) -> Option<Histogram>;
     fn get_all_metrics(&self) -> Vec<LabeledMetric>;
+
+    /// Copy all series, including histograms where the storage keeps them
+    ///
+    /// The default implementation is built from [`get_all_metrics`](Self::get_all_metrics).
+    fn snapshot(&self) -> MetricsSnapshot {
+        let timestamp = now_millis();
+        let series = self
+            .get_all_metrics()
+            .into_iter()
+            .map(|m| MetricSeries::value(m.name, m.kind, &m.labels, m.value, timestamp))
+            .collect();
+        MetricsSnapshot::new(timestamp, series)
+    }
+
+    /// Write every labeled series as one JSON object per line
+    fn export_ndjson(&self, writer: &mut dyn Write) -> std::io::Result<()> {
+        self.snapshot().write_ndjson(writer)
+    }
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
 }
 
 /// In-memory metric storage
@@ -344,8 +370,71 @@ impl MetricStorage for MemoryMetricStorage {
 
         metrics
     }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        let timestamp = now_millis();
+
+        // Copy one map at a time so recording is only held up for a single clone
+        let counters = self.counters.read().unwrap().clone();
+        let gauges = self.gauges.read().unwrap().clone();
+        let histograms = self.histograms.read().unwrap().clone();
+
+        let mut series = Vec::new();
+        for (kind, values) in [(MetricKind::Counter, counters), (MetricKind::Gauge, gauges)] {
+            for (name, label_map) in values {
+                for (labels, value) in label_map {
+                    series.push(MetricSeries::value(&name, kind, &labels, value, timestamp));
+                }
+            }
+        }
+        for (name, label_map) in histograms {
+            for (labels, histogram) in label_map {
+                series.push(MetricSeries::histogram(&name, &labels, &histogram, timestamp));
+            }
+        }
+
+        MetricsSnapshot::new(timestamp, series)
+    }
 }
 
+/// Counter of turns answered by `fallback_model` instead of the requested model
+///
+/// Labelled with `requested_model` and `actual_model`.
+pub const FALLBACK_ACTIVATIONS_METRIC: &str = "sdk_fallback_activations_total";
+
+/// Counter of queries sent to the CLI
+pub const QUERIES_STARTED_METRIC: &str = "sdk_queries_started_total";
+
+/// Counter of queries that ended with a successful result
+pub const QUERIES_COMPLETED_METRIC: &str = "sdk_queries_completed_total";
+
+/// Counter of queries that ended with an error result or failed before one arrived
+pub const QUERIES_FAILED_METRIC: &str = "sdk_queries_failed_total";
+
+/// Histogram of the time from sending a query to its result, in milliseconds
+pub const QUERY_DURATION_METRIC: &str = "sdk_query_duration_ms";
+
+/// Histogram of the time from sending a query to its first assistant message, in milliseconds
+pub const TIME_TO_FIRST_MESSAGE_METRIC: &str = "sdk_query_time_to_first_message_ms";
+
+/// Counter of tool calls seen in assistant messages
+///
+/// Labelled with `tool`.
+pub const TOOL_INVOCATIONS_METRIC: &str = "sdk_tool_invocations_total";
+
+/// Gauge of the cost in USD reported by all results so far
+pub const COST_USD_METRIC: &str = "sdk_cost_usd_total";
+
+/// Histogram of how long hook callbacks ran, in milliseconds
+///
+/// Labelled with `event` and `outcome`, which is `completed` or `timed_out`.
+pub const HOOK_DURATION_METRIC: &str = "sdk_hook_duration_ms";
+
+/// Counter of secrets redacted from tool responses by a `RedactionHook`
+///
+/// Labelled with `tool` and `label`, the name of the matching pattern.
+pub const REDACTIONS_METRIC: &str = "sdk_redactions_total";
+
 /// Metrics collector
 pub struct MetricsCollector {
     storage: Arc<dyn MetricStorage>,
@@ -517,6 +606,18 @@ impl MetricsCollector {
         output
     }
 
+    /// Take an immutable copy of all metrics
+    ///
+    /// Series names include the collector prefix.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.storage.snapshot()
+    }
+
+    /// Export all metrics as NDJSON, one labeled series per line
+    pub fn export_ndjson(&self, mut writer: impl Write) -> std::io::Result<()> {
+        self.storage.export_ndjson(&mut writer)
+    }
+
     /// Export metrics as JSON
     pub fn export_json(&self) -> String {
         let metrics = self.get_all_metrics();
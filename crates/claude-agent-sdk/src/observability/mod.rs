@@ -5,13 +5,16 @@
 //! - **Structured Logging**: Context-aware logging with multiple output formats
 //! - **Metrics Collection**: Counters, gauges, histograms for performance monitoring
 //! - **Tracing Support**: Integration with the tracing ecosystem
+//! - **Audit Log**: Tamper-evident record of every tool invocation
 //!
 //! ## Features
 //!
 //! - Thread-safe metrics and logging
 //! - Prometheus-compatible metrics export
+//! - Metric snapshots with NDJSON export and diffing
 //! - JSON and text log formats
 //! - Timer utilities for measuring code execution time
+//! - OpenTelemetry spans for orchestration runs (`otel` feature)
 //!
 //! ## Example
 //!
@@ -28,14 +31,26 @@
 //! // Timer automatically recorded on drop
 //! ```
 
+pub mod audit;
 pub mod logger;
 pub mod metrics;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod snapshot;
 
 // Re-export commonly used types
+pub use audit::{
+    AuditError, AuditEvent, AuditEventKind, AuditLog, AuditSink, DEFAULT_AUDIT_OUTPUT_LIMIT, JsonlAuditSink,
+    MemoryAuditSink,
+};
 pub use logger::{
     ConsoleLogObserver, GlobalLogger, LogEntry, LogFormat, LogLevel, LogObserver, Logger,
 };
 pub use metrics::{
-    Histogram, HistogramBuckets, LabeledMetric, MetricKind, MetricStorage, MetricsCollector,
-    TimerGuard,
+    COST_USD_METRIC, FALLBACK_ACTIVATIONS_METRIC, HOOK_DURATION_METRIC, Histogram, HistogramBuckets, LabeledMetric, MetricKind, MetricStorage,
+    MetricsCollector, QUERIES_COMPLETED_METRIC, QUERIES_FAILED_METRIC, QUERIES_STARTED_METRIC, QUERY_DURATION_METRIC, REDACTIONS_METRIC,
+    TIME_TO_FIRST_MESSAGE_METRIC, TOOL_INVOCATIONS_METRIC, TimerGuard,
 };
+pub use snapshot::{BucketSnapshot, HistogramSnapshot, MetricSeries, MetricsSnapshot};
+#[cfg(feature = "otel")]
+pub use otel::{TraceSpan, TracedOrchestrator};
@@ -0,0 +1,775 @@
+//! # OpenTelemetry bridge for orchestration traces
+//!
+//! Available with the `otel` feature.
+//!
+//! - [`ExecutionTrace::to_spans`] turns a finished trace into a [`TraceSpan`]
+//!   tree: the orchestration run at the root, one child per agent execution
+//!   and one grandchild per retried attempt. [`TraceSpan::export`] replays the
+//!   tree through any [`Tracer`], so it reaches whatever exporter the tracer
+//!   provider is configured with.
+//! - [`TracedOrchestrator`] wraps an orchestrator and records the same spans
+//!   while the run is in progress.
+//!
+//! Token usage is read from an agent output's `data.usage.input_tokens` and
+//! `data.usage.output_tokens`, the shape of Claude's usage report.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::observability::otel::TracedOrchestrator;
+//! use claude_agent_sdk::orchestration::{Orchestrator, OrchestratorInput, SequentialOrchestrator};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! // Spans go to the globally registered tracer provider
+//! let orchestrator = TracedOrchestrator::new(SequentialOrchestrator::new());
+//! let output = orchestrator
+//!     .orchestrate(Vec::new(), OrchestratorInput::new("Research Rust"))
+//!     .await?;
+//!
+//! // Or export a finished trace after the fact
+//! let tracer = opentelemetry::global::tracer("my-app");
+//! output.execution_trace.to_spans().export(&tracer);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::orchestration::{
+    Agent, EventSink, ExecutionTrace, OrchestrationEvent, Orchestrator, OrchestratorInput,
+    OrchestratorOutput, Result, context::AgentExecution,
+};
+use futures::StreamExt;
+use opentelemetry::trace::{Span, SpanContext, Status, TraceContextExt, Tracer, noop::NoopTracer};
+use opentelemetry::{Context, KeyValue, global::BoxedTracer};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// Name of the tracer used by [`TracedOrchestrator::new`]
+pub const TRACER_NAME: &str = "claude-agent-sdk";
+
+/// Name of the root span of an orchestration run
+pub const RUN_SPAN_NAME: &str = "orchestrate";
+
+/// Name of the orchestrator
+pub const ORCHESTRATOR_NAME: &str = "orchestration.orchestrator";
+/// Number of agents taking part in the run
+pub const AGENT_COUNT: &str = "orchestration.agent_count";
+/// Agent chosen by the router pattern
+pub const ROUTED_AGENT: &str = "orchestration.routed_agent";
+/// Name of the agent
+pub const AGENT_NAME: &str = "orchestration.agent.name";
+/// Zero-based position of the agent in the run
+pub const AGENT_INDEX: &str = "orchestration.agent.index";
+/// Whether the run or agent succeeded
+pub const SUCCESS: &str = "orchestration.success";
+/// Wall-clock duration in milliseconds
+pub const DURATION_MS: &str = "orchestration.duration_ms";
+/// Confidence reported by the agent
+pub const CONFIDENCE: &str = "orchestration.agent.confidence";
+/// Whether the agent was cancelled for exceeding its timeout
+pub const TIMED_OUT: &str = "orchestration.agent.timed_out";
+/// Zero-based number of an attempt
+pub const ATTEMPT: &str = "orchestration.agent.attempt";
+/// Error message
+pub const ERROR_MESSAGE: &str = "error.message";
+/// Input tokens used
+pub const INPUT_TOKENS: &str = "gen_ai.usage.input_tokens";
+/// Output tokens used
+pub const OUTPUT_TOKENS: &str = "gen_ai.usage.output_tokens";
+
+/// Span of an orchestration trace, with its child spans
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceSpan {
+    /// Span name
+    pub name: String,
+
+    /// Start time
+    pub start_time: SystemTime,
+
+    /// End time
+    pub end_time: SystemTime,
+
+    /// Attributes
+    pub attributes: Vec<KeyValue>,
+
+    /// Status
+    pub status: Status,
+
+    /// Child spans, in start order
+    pub children: Vec<TraceSpan>,
+}
+
+impl TraceSpan {
+    /// Value of the attribute named `key`
+    pub fn attribute(&self, key: &str) -> Option<&opentelemetry::Value> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key.as_str() == key)
+            .map(|attribute| &attribute.value)
+    }
+
+    /// Record this span and its children with `tracer` as a new trace
+    ///
+    /// Returns the context of the recorded root span.
+    pub fn export<T: Tracer>(&self, tracer: &T) -> SpanContext {
+        self.export_with_parent(tracer, &Context::new())
+    }
+
+    /// Record this span and its children with `tracer` under `parent`
+    pub fn export_with_parent<T: Tracer>(&self, tracer: &T, parent: &Context) -> SpanContext {
+        let builder = tracer
+            .span_builder(self.name.clone())
+            .with_start_time(self.start_time)
+            .with_attributes(self.attributes.clone());
+        let mut span = tracer.build_with_context(builder, parent);
+
+        let context = parent.with_remote_span_context(span.span_context().clone());
+        for child in &self.children {
+            child.export_with_parent(tracer, &context);
+        }
+
+        span.set_status(self.status.clone());
+        span.end_with_timestamp(self.end_time);
+        span.span_context().clone()
+    }
+}
+
+impl ExecutionTrace {
+    /// Span tree of the run
+    ///
+    /// The root span covers the run, each agent execution becomes a child and
+    /// each retried attempt of that execution a grandchild. The root span's
+    /// status is left unset, since the trace does not record whether the run
+    /// as a whole succeeded.
+    pub fn to_spans(&self) -> TraceSpan {
+        let children: Vec<TraceSpan> = self
+            .agent_executions
+            .iter()
+            .enumerate()
+            .map(|(index, execution)| self.execution_span(index, execution))
+            .collect();
+
+        let start_time = SystemTime::from(self.start_time);
+        let end_time = match self.end_time {
+            Some(end_time) => SystemTime::from(end_time),
+            None => children
+                .iter()
+                .map(|child| child.end_time)
+                .max()
+                .unwrap_or(start_time),
+        };
+
+        let mut attributes = vec![KeyValue::new(AGENT_COUNT, children.len() as i64)];
+        if let Some(duration_ms) = self.duration_ms {
+            attributes.push(KeyValue::new(DURATION_MS, duration_ms as i64));
+        }
+        if let Some(routing) = &self.routing {
+            attributes.push(KeyValue::new(ROUTED_AGENT, routing.agent.clone()));
+        }
+        attributes.extend(total_usage(self.agent_executions.iter()));
+
+        TraceSpan {
+            name: RUN_SPAN_NAME.to_string(),
+            start_time,
+            end_time,
+            attributes,
+            status: Status::Unset,
+            children,
+        }
+    }
+
+    fn execution_span(&self, index: usize, execution: &AgentExecution) -> TraceSpan {
+        let start_time = SystemTime::from(execution.start_time);
+        let end_time = match (execution.end_time, execution.duration_ms) {
+            (Some(end_time), _) => SystemTime::from(end_time),
+            (None, Some(ms)) => start_time + std::time::Duration::from_millis(ms),
+            (None, None) => start_time,
+        };
+
+        let mut attributes = vec![
+            KeyValue::new(AGENT_NAME, execution.agent_name.clone()),
+            KeyValue::new(AGENT_INDEX, index as i64),
+            KeyValue::new(SUCCESS, execution.success),
+        ];
+        if let Some(duration_ms) = execution.duration_ms {
+            attributes.push(KeyValue::new(DURATION_MS, duration_ms as i64));
+        }
+        if let Some(output) = &execution.output {
+            attributes.push(KeyValue::new(CONFIDENCE, output.confidence));
+            attributes.extend(usage_attributes(usage_of(&output.data)));
+        }
+        if execution.timed_out {
+            attributes.push(KeyValue::new(TIMED_OUT, true));
+        }
+        if let Some(error) = &execution.error {
+            attributes.push(KeyValue::new(ERROR_MESSAGE, error.clone()));
+        }
+
+        let children = self
+            .retries
+            .iter()
+            .filter(|retry| {
+                retry.agent_name == execution.agent_name
+                    && SystemTime::from(retry.start_time) >= start_time
+                    && SystemTime::from(retry.end_time) <= end_time
+            })
+            .map(|retry| TraceSpan {
+                name: attempt_span_name(retry.attempt),
+                start_time: SystemTime::from(retry.start_time),
+                end_time: SystemTime::from(retry.end_time),
+                attributes: vec![
+                    KeyValue::new(AGENT_NAME, retry.agent_name.clone()),
+                    KeyValue::new(ATTEMPT, retry.attempt as i64),
+                    KeyValue::new(ERROR_MESSAGE, retry.error.clone()),
+                ],
+                status: Status::error(retry.error.clone()),
+                children: Vec::new(),
+            })
+            .collect();
+
+        TraceSpan {
+            name: agent_span_name(&execution.agent_name),
+            start_time,
+            end_time,
+            attributes,
+            status: match &execution.error {
+                None if execution.success => Status::Ok,
+                None => Status::Unset,
+                Some(error) => Status::error(error.clone()),
+            },
+            children,
+        }
+    }
+}
+
+fn agent_span_name(agent: &str) -> String {
+    format!("agent {}", agent)
+}
+
+fn attempt_span_name(attempt: usize) -> String {
+    format!("attempt {}", attempt)
+}
+
+/// Input and output tokens reported under `data.usage`
+fn usage_of(data: &serde_json::Value) -> Option<(u64, u64)> {
+    let usage = data.get("usage")?;
+    let input = usage.get("input_tokens").and_then(|v| v.as_u64());
+    let output = usage.get("output_tokens").and_then(|v| v.as_u64());
+    (input.is_some() || output.is_some()).then(|| (input.unwrap_or(0), output.unwrap_or(0)))
+}
+
+fn usage_attributes(usage: Option<(u64, u64)>) -> Vec<KeyValue> {
+    match usage {
+        Some((input, output)) => vec![
+            KeyValue::new(INPUT_TOKENS, input as i64),
+            KeyValue::new(OUTPUT_TOKENS, output as i64),
+        ],
+        None => Vec::new(),
+    }
+}
+
+fn total_usage<'a>(executions: impl Iterator<Item = &'a AgentExecution>) -> Vec<KeyValue> {
+    let total = executions
+        .filter_map(|execution| usage_of(&execution.output.as_ref()?.data))
+        .reduce(|(input, output), (more_input, more_output)| {
+            (input + more_input, output + more_output)
+        });
+    usage_attributes(total)
+}
+
+/// Orchestrator wrapper recording OpenTelemetry spans while the run progresses
+///
+/// Agent spans start and end as the wrapped orchestrator reports its steps,
+/// so a slow run shows up in the backend agent by agent. The span tree has
+/// the same shape as [`ExecutionTrace::to_spans`]; token usage is only known
+/// once the run finished and is recorded on the root span.
+///
+/// Patterns that only report run start and end through
+/// [`Orchestrator::orchestrate_with_sink`] yield a root span without children.
+pub struct TracedOrchestrator<O, T = BoxedTracer> {
+    inner: O,
+    tracer: T,
+}
+
+impl<O: Orchestrator> TracedOrchestrator<O> {
+    /// Wrap `inner`, recording spans with the global tracer provider
+    pub fn new(inner: O) -> Self {
+        Self::with_tracer(inner, opentelemetry::global::tracer(TRACER_NAME))
+    }
+}
+
+impl<O: Orchestrator> TracedOrchestrator<O, NoopTracer> {
+    /// Wrap `inner` without recording anything
+    pub fn disabled(inner: O) -> Self {
+        Self::with_tracer(inner, NoopTracer::new())
+    }
+}
+
+impl<O, T> TracedOrchestrator<O, T> {
+    /// Wrap `inner`, recording spans with `tracer`
+    pub fn with_tracer(inner: O, tracer: T) -> Self {
+        Self { inner, tracer }
+    }
+
+    /// The wrapped orchestrator
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    /// Unwrap the orchestrator
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+}
+
+#[async_trait::async_trait]
+impl<O, T> Orchestrator for TracedOrchestrator<O, T>
+where
+    O: Orchestrator,
+    T: Tracer + Send + Sync,
+    T::Span: Send + Sync,
+{
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    async fn orchestrate(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+    ) -> Result<OrchestratorOutput> {
+        self.orchestrate_with_sink(agents, input, EventSink::disabled())
+            .await
+    }
+
+    async fn orchestrate_with_sink(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+        events: EventSink,
+    ) -> Result<OrchestratorOutput> {
+        let builder = self.tracer.span_builder(RUN_SPAN_NAME).with_attributes([
+            KeyValue::new(ORCHESTRATOR_NAME, self.inner.name().to_string()),
+            KeyValue::new(AGENT_COUNT, agents.len() as i64),
+        ]);
+        let mut root = self.tracer.build_with_context(builder, &Context::new());
+        let root_context = Context::new().with_remote_span_context(root.span_context().clone());
+
+        let (sink, mut stream) = EventSink::channel();
+        let mut recorder = StepRecorder {
+            tracer: &self.tracer,
+            parent: root_context,
+            steps: HashMap::new(),
+            forward: events,
+        };
+        let run = self.inner.orchestrate_with_sink(agents, input, sink);
+        let record = async {
+            while let Some(event) = stream.next().await {
+                recorder.on_event(event);
+            }
+        };
+        let (result, ()) = tokio::join!(run, record);
+        recorder.abandon_open_steps();
+
+        match &result {
+            Ok(output) => {
+                root.set_attribute(KeyValue::new(SUCCESS, output.success));
+                if let Some(duration_ms) = output.execution_trace.duration_ms {
+                    root.set_attribute(KeyValue::new(DURATION_MS, duration_ms as i64));
+                }
+                if let Some(routing) = &output.execution_trace.routing {
+                    root.set_attribute(KeyValue::new(ROUTED_AGENT, routing.agent.clone()));
+                }
+                root.set_attributes(total_usage(output.execution_trace.agent_executions.iter()));
+                match &output.error {
+                    None => root.set_status(Status::Ok),
+                    Some(error) => {
+                        root.set_attribute(KeyValue::new(ERROR_MESSAGE, error.clone()));
+                        root.set_status(Status::error(error.clone()));
+                    },
+                }
+            },
+            Err(error) => {
+                root.set_attribute(KeyValue::new(SUCCESS, false));
+                root.set_attribute(KeyValue::new(ERROR_MESSAGE, error.to_string()));
+                root.set_status(Status::error(error.to_string()));
+            },
+        }
+        root.end();
+
+        result
+    }
+}
+
+/// Agent span that has not ended yet
+struct OpenStep<S> {
+    agent: String,
+    span: S,
+    context: Context,
+    attempt: usize,
+    attempt_started: SystemTime,
+}
+
+/// Turns step events into spans under the run's root span
+struct StepRecorder<'a, T: Tracer> {
+    tracer: &'a T,
+    parent: Context,
+    steps: HashMap<usize, OpenStep<T::Span>>,
+    forward: EventSink,
+}
+
+impl<T: Tracer> StepRecorder<'_, T> {
+    fn on_event(&mut self, event: OrchestrationEvent) {
+        self.forward.emit(event.clone());
+
+        match event {
+            OrchestrationEvent::StepStarted { agent, index } => {
+                let builder = self
+                    .tracer
+                    .span_builder(agent_span_name(&agent))
+                    .with_attributes([
+                        KeyValue::new(AGENT_NAME, agent.clone()),
+                        KeyValue::new(AGENT_INDEX, index as i64),
+                    ]);
+                let span = self.tracer.build_with_context(builder, &self.parent);
+                let context = self
+                    .parent
+                    .with_remote_span_context(span.span_context().clone());
+                self.steps.insert(
+                    index,
+                    OpenStep {
+                        agent,
+                        span,
+                        context,
+                        attempt: 0,
+                        attempt_started: SystemTime::now(),
+                    },
+                );
+            },
+            OrchestrationEvent::RetryScheduled {
+                agent,
+                delay,
+                error,
+                ..
+            } => {
+                let Some(step) = self.steps.values_mut().find(|step| step.agent == agent) else {
+                    return;
+                };
+                let builder = self
+                    .tracer
+                    .span_builder(attempt_span_name(step.attempt))
+                    .with_start_time(step.attempt_started)
+                    .with_attributes([
+                        KeyValue::new(AGENT_NAME, agent),
+                        KeyValue::new(ATTEMPT, step.attempt as i64),
+                        KeyValue::new(ERROR_MESSAGE, error.clone()),
+                    ]);
+                let mut span = self.tracer.build_with_context(builder, &step.context);
+                span.set_status(Status::error(error));
+                span.end();
+
+                step.attempt += 1;
+                step.attempt_started = SystemTime::now() + delay;
+            },
+            OrchestrationEvent::StepCompleted {
+                index,
+                duration,
+                confidence,
+                ..
+            } => {
+                if let Some(mut step) = self.steps.remove(&index) {
+                    step.span.set_attributes([
+                        KeyValue::new(SUCCESS, true),
+                        KeyValue::new(DURATION_MS, duration.as_millis() as i64),
+                        KeyValue::new(CONFIDENCE, confidence),
+                    ]);
+                    step.span.set_status(Status::Ok);
+                    step.span.end();
+                }
+            },
+            OrchestrationEvent::StepFailed { index, error, .. } => {
+                if let Some(mut step) = self.steps.remove(&index) {
+                    step.span.set_attributes([
+                        KeyValue::new(SUCCESS, false),
+                        KeyValue::new(ERROR_MESSAGE, error.clone()),
+                    ]);
+                    step.span.set_status(Status::error(error));
+                    step.span.end();
+                }
+            },
+            OrchestrationEvent::RunStarted { .. }
+            | OrchestrationEvent::StepProgress { .. }
+            | OrchestrationEvent::RunCompleted { .. } => {},
+        }
+    }
+
+    /// End the spans of steps the orchestrator never reported as finished
+    fn abandon_open_steps(&mut self) {
+        for (_, mut step) in self.steps.drain() {
+            step.span
+                .set_status(Status::error("Step did not report completion"));
+            step.span.end();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestration::agent::SimpleAgent;
+    use crate::orchestration::context::RetriedAttempt;
+    use crate::orchestration::{AgentInput, AgentOutput, SequentialOrchestrator};
+    use opentelemetry::trace::{SpanBuilder, SpanId, TraceFlags, TraceId, TraceState};
+    use std::borrow::Cow;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone)]
+    struct RecordedSpan {
+        name: String,
+        id: SpanId,
+        parent: Option<SpanId>,
+        attributes: Vec<KeyValue>,
+        status: Status,
+        start_time: SystemTime,
+        end_time: SystemTime,
+    }
+
+    impl RecordedSpan {
+        fn attribute(&self, key: &str) -> Option<&opentelemetry::Value> {
+            self.attributes
+                .iter()
+                .find(|attribute| attribute.key.as_str() == key)
+                .map(|attribute| &attribute.value)
+        }
+    }
+
+    /// Tracer keeping ended spans in memory; clones share the recording
+    #[derive(Clone, Default)]
+    struct RecordingTracer {
+        next_id: Arc<AtomicU64>,
+        ended: Arc<Mutex<Vec<RecordedSpan>>>,
+    }
+
+    impl RecordingTracer {
+        fn ended(&self) -> Vec<RecordedSpan> {
+            self.ended.lock().unwrap().clone()
+        }
+    }
+
+    struct RecordingSpan {
+        record: RecordedSpan,
+        context: SpanContext,
+        ended: Arc<Mutex<Vec<RecordedSpan>>>,
+    }
+
+    impl Tracer for RecordingTracer {
+        type Span = RecordingSpan;
+
+        fn build_with_context(&self, builder: SpanBuilder, parent_cx: &Context) -> RecordingSpan {
+            let id = SpanId::from(self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+            let parent = parent_cx
+                .has_active_span()
+                .then(|| parent_cx.span().span_context().span_id());
+            RecordingSpan {
+                record: RecordedSpan {
+                    name: builder.name.to_string(),
+                    id,
+                    parent,
+                    attributes: builder.attributes.unwrap_or_default(),
+                    status: Status::Unset,
+                    start_time: builder.start_time.unwrap_or_else(SystemTime::now),
+                    end_time: SystemTime::UNIX_EPOCH,
+                },
+                context: SpanContext::new(
+                    TraceId::from(1),
+                    id,
+                    TraceFlags::SAMPLED,
+                    false,
+                    TraceState::default(),
+                ),
+                ended: self.ended.clone(),
+            }
+        }
+    }
+
+    impl Span for RecordingSpan {
+        fn add_event_with_timestamp<N>(
+            &mut self,
+            _name: N,
+            _timestamp: SystemTime,
+            _attributes: Vec<KeyValue>,
+        ) where
+            N: Into<Cow<'static, str>>,
+        {
+        }
+
+        fn span_context(&self) -> &SpanContext {
+            &self.context
+        }
+
+        fn is_recording(&self) -> bool {
+            true
+        }
+
+        fn set_attribute(&mut self, attribute: KeyValue) {
+            self.record.attributes.push(attribute);
+        }
+
+        fn set_status(&mut self, status: Status) {
+            self.record.status = status;
+        }
+
+        fn update_name<N>(&mut self, new_name: N)
+        where
+            N: Into<Cow<'static, str>>,
+        {
+            self.record.name = new_name.into().to_string();
+        }
+
+        fn add_link(&mut self, _span_context: SpanContext, _attributes: Vec<KeyValue>) {}
+
+        fn end_with_timestamp(&mut self, timestamp: SystemTime) {
+            self.record.end_time = timestamp;
+            self.ended.lock().unwrap().push(self.record.clone());
+        }
+    }
+
+    fn sample_trace() -> ExecutionTrace {
+        let mut trace = ExecutionTrace::new();
+
+        let mut researcher = AgentExecution::new("Researcher", AgentInput::new("topic"));
+        trace.retries.push(RetriedAttempt {
+            agent_name: "Researcher".to_string(),
+            attempt: 0,
+            start_time: researcher.start_time,
+            end_time: researcher.start_time,
+            error: "rate limited".to_string(),
+            confidence: None,
+        });
+        let usage = serde_json::json!({"usage": {"input_tokens": 100, "output_tokens": 40}});
+        researcher.succeed(
+            AgentOutput::new("notes")
+                .with_confidence(0.9)
+                .with_data(usage),
+        );
+        trace.add_execution(researcher);
+
+        let mut writer = AgentExecution::new("Writer", AgentInput::new("notes"));
+        writer.fail("boom");
+        trace.add_execution(writer);
+
+        trace.complete();
+        trace
+    }
+
+    #[test]
+    fn test_to_spans_builds_tree() {
+        let root = sample_trace().to_spans();
+
+        assert_eq!(root.name, RUN_SPAN_NAME);
+        assert_eq!(root.attribute(AGENT_COUNT), Some(&2i64.into()));
+        assert_eq!(root.attribute(INPUT_TOKENS), Some(&100i64.into()));
+        assert_eq!(root.children.len(), 2);
+
+        let researcher = &root.children[0];
+        assert_eq!(researcher.name, "agent Researcher");
+        assert_eq!(researcher.status, Status::Ok);
+        assert_eq!(researcher.attribute(CONFIDENCE), Some(&0.9.into()));
+        assert_eq!(researcher.attribute(OUTPUT_TOKENS), Some(&40i64.into()));
+        assert_eq!(researcher.children.len(), 1);
+        assert_eq!(researcher.children[0].name, "attempt 0");
+        assert_eq!(researcher.children[0].status, Status::error("rate limited"));
+
+        let writer = &root.children[1];
+        assert_eq!(writer.status, Status::error("boom"));
+        assert_eq!(writer.attribute(ERROR_MESSAGE), Some(&"boom".into()));
+        assert_eq!(writer.attribute(INPUT_TOKENS), None);
+        assert!(writer.children.is_empty());
+        assert!(root.end_time >= writer.end_time);
+    }
+
+    #[test]
+    fn test_export_preserves_parents_and_times() {
+        let root = sample_trace().to_spans();
+        let tracer = RecordingTracer::default();
+
+        let root_context = root.export(&tracer);
+
+        // Spans end children first
+        let ended = tracer.ended();
+        let names: Vec<_> = ended.iter().map(|span| span.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["attempt 0", "agent Researcher", "agent Writer", RUN_SPAN_NAME]
+        );
+        let exported_root = &ended[3];
+        assert_eq!(exported_root.id, root_context.span_id());
+        assert_eq!(exported_root.parent, None);
+        assert_eq!(exported_root.start_time, root.start_time);
+        assert_eq!(ended[1].parent, Some(exported_root.id));
+        assert_eq!(ended[2].parent, Some(exported_root.id));
+        assert_eq!(ended[0].parent, Some(ended[1].id));
+        assert_eq!(ended[2].end_time, root.children[1].end_time);
+    }
+
+    #[tokio::test]
+    async fn test_traced_orchestrator_records_live_spans() {
+        let attempts = Arc::new(AtomicU64::new(0));
+        let counter = attempts.clone();
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(SimpleAgent::new("Flaky", "Fails once", move |input| {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err(anyhow::anyhow!("transient").into())
+                } else {
+                    Ok(AgentOutput::new(input.content)
+                        .with_data(serde_json::json!({"usage": {"input_tokens": 7}})))
+                }
+            })),
+            Box::new(SimpleAgent::new("Echo", "Echoes", |input| {
+                Ok(AgentOutput::new(input.content))
+            })),
+        ];
+
+        let tracer = RecordingTracer::default();
+        let orchestrator =
+            TracedOrchestrator::with_tracer(SequentialOrchestrator::new(), tracer.clone());
+        let (events, run) =
+            orchestrator.orchestrate_with_events(agents, OrchestratorInput::new("Test"));
+        let (events, output) = tokio::join!(events.collect::<Vec<_>>(), run);
+        let output = output.unwrap();
+
+        assert!(output.is_successful());
+        // Events still reach the caller
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, OrchestrationEvent::RetryScheduled { .. }))
+        );
+
+        let ended = tracer.ended();
+        let names: Vec<_> = ended.iter().map(|span| span.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["attempt 0", "agent Flaky", "agent Echo", RUN_SPAN_NAME]
+        );
+        let root = &ended[3];
+        assert_eq!(root.status, Status::Ok);
+        assert_eq!(
+            root.attribute(ORCHESTRATOR_NAME),
+            Some(&"SequentialOrchestrator".into())
+        );
+        assert_eq!(root.attribute(INPUT_TOKENS), Some(&7i64.into()));
+        assert_eq!(ended[1].parent, Some(root.id));
+        assert_eq!(ended[0].parent, Some(ended[1].id));
+        assert!(matches!(
+            &ended[0].status,
+            Status::Error { description } if description.contains("transient")
+        ));
+        assert_eq!(ended[1].status, Status::Ok);
+        assert_eq!(ended[2].attribute(AGENT_INDEX), Some(&1i64.into()));
+    }
+}
@@ -0,0 +1,418 @@
+//! # Metric Snapshots
+//!
+//! Immutable copies of collected metrics for offline analysis and test
+//! assertions. A [`MetricsSnapshot`] can be queried, diffed against an earlier
+//! snapshot, and written as NDJSON with one line per labeled series.
+//!
+//! ## Example
+//!
+//! ```
+//! use claude_agent_sdk::observability::MetricsCollector;
+//!
+//! let metrics = MetricsCollector::new();
+//! metrics.increment("requests", &[("route", "/search")]);
+//! let before = metrics.snapshot();
+//!
+//! metrics.increment("requests", &[("route", "/search")]);
+//! metrics.increment("requests", &[("route", "/search")]);
+//!
+//! let delta = metrics.snapshot().diff(&before);
+//! assert_eq!(delta.counter_value("requests", &[("route", "/search")]), 2.0);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use super::metrics::{Histogram, MetricKind};
+
+/// One histogram bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketSnapshot {
+    /// Upper bound of the bucket, `None` for the overflow bucket
+    pub le: Option<f64>,
+
+    /// Observations in this bucket (not cumulative)
+    pub count: u64,
+}
+
+/// Copy of a histogram's state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistogramSnapshot {
+    /// Total count of observations
+    pub count: u64,
+
+    /// Total sum of all values
+    pub sum: f64,
+
+    /// Per-bucket counts, ending with the overflow bucket
+    pub buckets: Vec<BucketSnapshot>,
+}
+
+impl From<&Histogram> for HistogramSnapshot {
+    fn from(histogram: &Histogram) -> Self {
+        let buckets = histogram
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| BucketSnapshot {
+                le: histogram.boundaries.get(i).copied(),
+                count,
+            })
+            .collect();
+
+        Self {
+            count: histogram.count,
+            sum: histogram.sum,
+            buckets,
+        }
+    }
+}
+
+impl HistogramSnapshot {
+    /// Get average value
+    pub fn avg(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+
+    fn diff(&self, earlier: &Self) -> Self {
+        let same_layout = self.buckets.len() == earlier.buckets.len()
+            && self
+                .buckets
+                .iter()
+                .zip(&earlier.buckets)
+                .all(|(a, b)| a.le == b.le);
+        if !same_layout {
+            return self.clone();
+        }
+
+        Self {
+            count: self.count.saturating_sub(earlier.count),
+            sum: self.sum - earlier.sum,
+            buckets: self
+                .buckets
+                .iter()
+                .zip(&earlier.buckets)
+                .map(|(now, then)| BucketSnapshot {
+                    le: now.le,
+                    count: now.count.saturating_sub(then.count),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// One labeled metric series
+///
+/// This is also the line format of [`MetricsSnapshot::write_ndjson`]: counters
+/// and gauges carry `value`, histograms carry `histogram`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSeries {
+    /// Metric name, including any collector prefix
+    pub name: String,
+
+    /// Metric kind
+    pub kind: MetricKind,
+
+    /// Label dimensions
+    pub labels: BTreeMap<String, String>,
+
+    /// Counter or gauge value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+
+    /// Histogram state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<HistogramSnapshot>,
+
+    /// Time of the snapshot (milliseconds since epoch)
+    pub timestamp: u64,
+}
+
+impl MetricSeries {
+    /// Create a counter or gauge series
+    pub fn value(
+        name: impl Into<String>,
+        kind: MetricKind,
+        labels: &[(String, String)],
+        value: f64,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            labels: labels.iter().cloned().collect(),
+            value: Some(value),
+            histogram: None,
+            timestamp,
+        }
+    }
+
+    /// Create a histogram series
+    pub fn histogram(
+        name: impl Into<String>,
+        labels: &[(String, String)],
+        histogram: &Histogram,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind: MetricKind::Histogram,
+            labels: labels.iter().cloned().collect(),
+            value: None,
+            histogram: Some(histogram.into()),
+            timestamp,
+        }
+    }
+
+    fn key(&self) -> (&str, u8, &BTreeMap<String, String>) {
+        (&self.name, kind_rank(self.kind), &self.labels)
+    }
+}
+
+/// Immutable copy of all metric series at one point in time
+///
+/// Series are sorted by name, then kind, then labels, so exports of the same
+/// data are byte-for-byte identical.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// Time the snapshot was taken (milliseconds since epoch)
+    pub timestamp: u64,
+
+    /// All series
+    pub series: Vec<MetricSeries>,
+}
+
+impl MetricsSnapshot {
+    /// Create a snapshot from series in any order
+    pub fn new(timestamp: u64, mut series: Vec<MetricSeries>) -> Self {
+        series.sort_by(|a, b| a.key().cmp(&b.key()));
+        Self { timestamp, series }
+    }
+
+    /// Find a series by name, kind and labels (label order does not matter)
+    pub fn find(
+        &self,
+        name: &str,
+        kind: MetricKind,
+        labels: &[(impl AsRef<str>, impl AsRef<str>)],
+    ) -> Option<&MetricSeries> {
+        let labels: BTreeMap<String, String> = labels
+            .iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+            .collect();
+        self.series
+            .iter()
+            .find(|series| series.key() == (name, kind_rank(kind), &labels))
+    }
+
+    /// Get a counter value, 0 if the series does not exist
+    pub fn counter_value(&self, name: &str, labels: &[(impl AsRef<str>, impl AsRef<str>)]) -> f64 {
+        self.find(name, MetricKind::Counter, labels)
+            .and_then(|series| series.value)
+            .unwrap_or(0.0)
+    }
+
+    /// Get a gauge value, 0 if the series does not exist
+    pub fn gauge_value(&self, name: &str, labels: &[(impl AsRef<str>, impl AsRef<str>)]) -> f64 {
+        self.find(name, MetricKind::Gauge, labels)
+            .and_then(|series| series.value)
+            .unwrap_or(0.0)
+    }
+
+    /// Get a histogram
+    pub fn histogram(
+        &self,
+        name: &str,
+        labels: &[(impl AsRef<str>, impl AsRef<str>)],
+    ) -> Option<&HistogramSnapshot> {
+        self.find(name, MetricKind::Histogram, labels)
+            .and_then(|series| series.histogram.as_ref())
+    }
+
+    /// Changes since `earlier`
+    ///
+    /// Every value is the difference to the matching series in `earlier`, or
+    /// the value itself for series that are new. Series that did not change are
+    /// left out. Histograms whose buckets changed layout are kept as they are.
+    pub fn diff(&self, earlier: &MetricsSnapshot) -> MetricsSnapshot {
+        let previous: BTreeMap<_, _> = earlier
+            .series
+            .iter()
+            .map(|series| (series.key(), series))
+            .collect();
+
+        let series = self
+            .series
+            .iter()
+            .filter_map(|series| {
+                let Some(before) = previous.get(&series.key()) else {
+                    return Some(series.clone());
+                };
+
+                let value = match (series.value, before.value) {
+                    (Some(now), Some(then)) => Some(now - then),
+                    (now, _) => now,
+                };
+                let histogram = match (&series.histogram, &before.histogram) {
+                    (Some(now), Some(then)) => Some(now.diff(then)),
+                    (now, _) => now.clone(),
+                };
+
+                let unchanged = value.is_none_or(|v| v == 0.0)
+                    && histogram.as_ref().is_none_or(|h| h.count == 0);
+                (!unchanged).then(|| MetricSeries {
+                    value,
+                    histogram,
+                    ..series.clone()
+                })
+            })
+            .collect();
+
+        MetricsSnapshot {
+            timestamp: self.timestamp,
+            series,
+        }
+    }
+
+    /// Write one JSON object per series, each on its own line
+    pub fn write_ndjson(&self, mut writer: impl Write) -> std::io::Result<()> {
+        for series in &self.series {
+            serde_json::to_writer(&mut writer, series)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()
+    }
+
+    /// Read series written by [`write_ndjson`](Self::write_ndjson)
+    ///
+    /// The snapshot timestamp is the latest series timestamp.
+    pub fn read_ndjson(reader: impl std::io::BufRead) -> std::io::Result<Self> {
+        let mut series = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            series.push(serde_json::from_str::<MetricSeries>(&line)?);
+        }
+
+        let timestamp = series.iter().map(|s| s.timestamp).max().unwrap_or(0);
+        Ok(Self::new(timestamp, series))
+    }
+}
+
+fn kind_rank(kind: MetricKind) -> u8 {
+    match kind {
+        MetricKind::Counter => 0,
+        MetricKind::Gauge => 1,
+        MetricKind::Histogram => 2,
+        MetricKind::Summary => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::metrics::{HistogramBuckets, MetricsCollector};
+
+    const EMPTY_LABELS: &[(&str, &str)] = &[];
+
+    fn snapshot_json(snapshot: &MetricsSnapshot) -> String {
+        let mut out = Vec::new();
+        snapshot.write_ndjson(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_ndjson_format_is_stable() {
+        let mut histogram = Histogram::new(HistogramBuckets::custom(vec![10.0, 100.0]));
+        histogram.observe(5.0);
+        histogram.observe(500.0);
+
+        let labels = vec![
+            ("route".to_string(), "/b".to_string()),
+            ("method".to_string(), "GET".to_string()),
+        ];
+        let snapshot = MetricsSnapshot::new(
+            7,
+            vec![
+                MetricSeries::histogram("latency_ms", &[], &histogram, 7),
+                MetricSeries::value("calls", MetricKind::Counter, &labels, 3.0, 7),
+            ],
+        );
+
+        assert_eq!(
+            snapshot_json(&snapshot),
+            concat!(
+                r#"{"name":"calls","kind":"Counter","labels":{"method":"GET","route":"/b"},"value":3.0,"timestamp":7}"#,
+                "\n",
+                r#"{"name":"latency_ms","kind":"Histogram","labels":{},"histogram":{"count":2,"sum":505.0,"buckets":[{"le":10.0,"count":1},{"le":100.0,"count":0},{"le":null,"count":1}]},"timestamp":7}"#,
+                "\n",
+            )
+        );
+
+        let read = MetricsSnapshot::read_ndjson(snapshot_json(&snapshot).as_bytes()).unwrap();
+        assert_eq!(read, snapshot);
+    }
+
+    #[test]
+    fn test_collector_snapshot_queries() {
+        let metrics = MetricsCollector::with_prefix("app");
+        metrics.increment("calls", &[("b", "2"), ("a", "1")]);
+        metrics.set_gauge("queue", 4.0, EMPTY_LABELS);
+        metrics.record("latency", MetricKind::Histogram, 30.0, EMPTY_LABELS);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            snapshot.counter_value("app_calls", &[("a", "1"), ("b", "2")]),
+            1.0
+        );
+        assert_eq!(snapshot.counter_value("app_calls", EMPTY_LABELS), 0.0);
+        assert_eq!(snapshot.gauge_value("app_queue", EMPTY_LABELS), 4.0);
+
+        let histogram = snapshot.histogram("app_latency", EMPTY_LABELS).unwrap();
+        assert_eq!(histogram.count, 1);
+        assert_eq!(histogram.sum, 30.0);
+
+        let mut out = Vec::new();
+        metrics.export_ndjson(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_diff_math() {
+        let metrics = MetricsCollector::new();
+        metrics.increment_by("calls", 2.0, EMPTY_LABELS);
+        metrics.increment("idle", EMPTY_LABELS);
+        metrics.set_gauge("queue", 10.0, EMPTY_LABELS);
+        metrics.record("latency", MetricKind::Histogram, 3.0, EMPTY_LABELS);
+        let before = metrics.snapshot();
+
+        metrics.increment_by("calls", 5.0, EMPTY_LABELS);
+        metrics.increment("errors", &[("kind", "timeout")]);
+        metrics.set_gauge("queue", 4.0, EMPTY_LABELS);
+        metrics.record("latency", MetricKind::Histogram, 3.0, EMPTY_LABELS);
+        metrics.record("latency", MetricKind::Histogram, 700.0, EMPTY_LABELS);
+        let delta = metrics.snapshot().diff(&before);
+
+        assert_eq!(delta.counter_value("calls", EMPTY_LABELS), 5.0);
+        assert_eq!(delta.counter_value("errors", &[("kind", "timeout")]), 1.0);
+        assert_eq!(delta.gauge_value("queue", EMPTY_LABELS), -6.0);
+        assert!(
+            delta
+                .find("idle", MetricKind::Counter, EMPTY_LABELS)
+                .is_none()
+        );
+
+        let latency = delta.histogram("latency", EMPTY_LABELS).unwrap();
+        assert_eq!(latency.count, 2);
+        assert_eq!(latency.sum, 703.0);
+        let counts: Vec<u64> = latency.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
+    }
+}
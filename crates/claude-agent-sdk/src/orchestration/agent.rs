@@ -3,9 +3,11 @@
 //! This module defines the core Agent trait and associated types for the
 //! multi-agent orchestration framework.
 
+use crate::orchestration::context::ExecutionContext;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Error type for agent operations
 #[derive(Debug, thiserror::Error)]
@@ -134,6 +136,42 @@ pub trait Agent: Send + Sync {
     /// Takes input and produces output asynchronously.
     /// Returns an error if execution fails.
     async fn execute(&self, input: AgentInput) -> Result<AgentOutput>;
+
+    /// Execute the agent's logic with access to the orchestration context
+    ///
+    /// Orchestrators call this method. Override it to report progress via
+    /// [`ExecutionContext::emit_progress`]; the default delegates to [`Agent::execute`].
+    async fn execute_with_context(
+        &self,
+        input: AgentInput,
+        _ctx: &ExecutionContext,
+    ) -> Result<AgentOutput> {
+        self.execute(input).await
+    }
+}
+
+/// Shared agents, e.g. those selected from an [`AgentRegistry`](crate::orchestration::AgentRegistry)
+#[async_trait]
+impl<A: Agent + ?Sized> Agent for Arc<A> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+
+    async fn execute(&self, input: AgentInput) -> Result<AgentOutput> {
+        (**self).execute(input).await
+    }
+
+    async fn execute_with_context(
+        &self,
+        input: AgentInput,
+        ctx: &ExecutionContext,
+    ) -> Result<AgentOutput> {
+        (**self).execute_with_context(input, ctx).await
+    }
 }
 
 /// Simple wrapper agent for easy creation
@@ -4,6 +4,8 @@
 //! including agent management, state tracking, and execution traces.
 
 use crate::orchestration::agent::AgentOutput;
+use crate::orchestration::events::{EventSink, OrchestrationEvent};
+use crate::orchestration::shared::{ContextChange, SharedContext};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -21,6 +23,33 @@ pub struct ExecutionConfig {
     /// Maximum parallel agent executions
     pub parallel_limit: usize,
 
+    /// Maximum time for a single agent, including its retries
+    ///
+    /// Used by the parallel pattern; a timed-out agent is cancelled and
+    /// recorded in the trace without failing the other agents.
+    #[serde(default)]
+    pub agent_timeout: Option<Duration>,
+
+    /// Maximum debate rounds, capping the rounds a debate asks for
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: usize,
+
+    /// Let the debate judge end a debate early by reporting consensus
+    #[serde(default)]
+    pub stop_on_consensus: bool,
+
+    /// Minimum confidence of an agent's output
+    ///
+    /// An output below it is retried, up to `max_retries` times, with feedback
+    /// appended to the input. If every attempt falls short, the most confident
+    /// output is used.
+    #[serde(default)]
+    pub min_confidence: Option<f64>,
+
+    /// How the parallel pattern assembles its result from the agents' outputs
+    #[serde(default)]
+    pub aggregation: AggregationStrategy,
+
     /// Enable detailed logging
     pub enable_logging: bool,
 
@@ -34,12 +63,21 @@ impl Default for ExecutionConfig {
             timeout: Duration::from_secs(300), // 5 minutes
             max_retries: 3,
             parallel_limit: 10,
+            agent_timeout: None,
+            max_rounds: default_max_rounds(),
+            stop_on_consensus: false,
+            min_confidence: None,
+            aggregation: AggregationStrategy::default(),
             enable_logging: true,
             enable_tracing: true,
         }
     }
 }
 
+fn default_max_rounds() -> usize {
+    10
+}
+
 impl ExecutionConfig {
     /// Create a new execution config with default values
     pub fn new() -> Self {
@@ -64,6 +102,36 @@ impl ExecutionConfig {
         self
     }
 
+    /// Set the per-agent timeout
+    pub fn with_agent_timeout(mut self, timeout: Duration) -> Self {
+        self.agent_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum debate rounds
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// End debates early once the judge reports consensus
+    pub fn with_stop_on_consensus(mut self, enable: bool) -> Self {
+        self.stop_on_consensus = enable;
+        self
+    }
+
+    /// Retry agent outputs with a confidence below `min_confidence`
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = Some(min_confidence);
+        self
+    }
+
+    /// Set how the parallel pattern aggregates agent outputs
+    pub fn with_aggregation(mut self, aggregation: AggregationStrategy) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
     /// Enable logging
     pub fn with_logging(mut self, enable: bool) -> Self {
         self.enable_logging = enable;
@@ -77,6 +145,43 @@ impl ExecutionConfig {
     }
 }
 
+/// How a [`ParallelOrchestrator`](crate::orchestration::ParallelOrchestrator)
+/// assembles its result from the outputs of the agents that completed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationStrategy {
+    /// The content of the most confident output, the first one on a tie
+    HighestConfidence,
+    /// Every output under a heading naming its agent, most confident first
+    WeightedMerge,
+    /// Every output as a numbered list, in agent order
+    #[default]
+    All,
+}
+
+/// Confidence of one agent's output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentConfidence {
+    /// Agent name
+    pub agent: String,
+
+    /// Confidence of the agent's output
+    pub confidence: f64,
+}
+
+/// How the outputs of a parallel run were aggregated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregationRecord {
+    /// Strategy used
+    pub strategy: AggregationStrategy,
+
+    /// Confidence of each completed agent, in agent order
+    pub confidences: Vec<AgentConfidence>,
+
+    /// Agents whose outputs are in the result, in the order they appear
+    pub selected: Vec<String>,
+}
+
 /// Execution trace for tracking orchestration runs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionTrace {
@@ -91,6 +196,76 @@ pub struct ExecutionTrace {
 
     /// Total execution duration in milliseconds
     pub duration_ms: Option<u64>,
+
+    /// Agent chosen by the router pattern
+    #[serde(default)]
+    pub routing: Option<RoutingDecision>,
+
+    /// Rounds of the debate pattern, in order
+    #[serde(default)]
+    pub debate_rounds: Vec<DebateRound>,
+
+    /// Writes to the shared context, in order
+    #[serde(default)]
+    pub context_changes: Vec<ContextChange>,
+
+    /// Agent attempts that failed and were retried, in order
+    #[serde(default)]
+    pub retries: Vec<RetriedAttempt>,
+
+    /// Aggregation of the agent outputs by the parallel pattern
+    #[serde(default)]
+    pub aggregation: Option<AggregationRecord>,
+}
+
+/// Agent attempt that failed and was retried
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetriedAttempt {
+    /// Agent name
+    pub agent_name: String,
+
+    /// Zero-based number of the failed attempt
+    pub attempt: usize,
+
+    /// Start time of the attempt
+    pub start_time: chrono::DateTime<chrono::Utc>,
+
+    /// Time the attempt failed
+    pub end_time: chrono::DateTime<chrono::Utc>,
+
+    /// Error of the attempt
+    pub error: String,
+
+    /// Confidence of the output, if the attempt was retried for falling
+    /// below the minimum confidence
+    #[serde(default)]
+    pub confidence: Option<f64>,
+}
+
+/// Agent chosen by a [`RouterOrchestrator`](crate::orchestration::RouterOrchestrator)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingDecision {
+    /// Name of the chosen agent
+    pub agent: String,
+
+    /// Zero-based position of the chosen agent in the run
+    pub index: usize,
+
+    /// Whether the fallback agent was used because the router found no match
+    pub fallback: bool,
+}
+
+/// One round of a [`DebateOrchestrator`](crate::orchestration::DebateOrchestrator) debate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebateRound {
+    /// One-based round number
+    pub round: usize,
+
+    /// Executions of the debaters taking part in the round
+    pub positions: Vec<AgentExecution>,
+
+    /// Consensus reported by the judge, if it evaluated this round
+    pub consensus: Option<bool>,
 }
 
 impl ExecutionTrace {
@@ -101,6 +276,11 @@ impl ExecutionTrace {
             end_time: None,
             agent_executions: Vec::new(),
             duration_ms: None,
+            routing: None,
+            debate_rounds: Vec::new(),
+            context_changes: Vec::new(),
+            retries: Vec::new(),
+            aggregation: None,
         }
     }
 
@@ -151,6 +331,10 @@ pub struct AgentExecution {
     /// Error message if failed
     pub error: Option<String>,
 
+    /// Whether the agent was cancelled for exceeding its timeout
+    #[serde(default)]
+    pub timed_out: bool,
+
     /// Execution duration in milliseconds
     pub duration_ms: Option<u64>,
 }
@@ -169,6 +353,7 @@ impl AgentExecution {
             output: None,
             success: false,
             error: None,
+            timed_out: false,
             duration_ms: None,
         }
     }
@@ -198,6 +383,12 @@ impl AgentExecution {
                 .num_milliseconds() as u64,
         );
     }
+
+    /// Mark execution as cancelled after exceeding `timeout`
+    pub fn time_out(&mut self, timeout: Duration) {
+        self.fail(format!("Timed out after {:?}", timeout));
+        self.timed_out = true;
+    }
 }
 
 /// Execution context for managing orchestration state
@@ -210,15 +401,24 @@ pub struct ExecutionContext {
 
     /// Execution trace
     trace: RwLock<ExecutionTrace>,
+
+    /// Progress event sink
+    events: EventSink,
+
+    /// Values shared between agents
+    shared: SharedContext,
 }
 
 impl Clone for ExecutionContext {
     fn clone(&self) -> Self {
-        // Create a new context with same config but empty state and trace
+        // Create a new context with same config, event sink and shared context
+        // but empty state and trace
         Self {
             config: self.config.clone(),
             state: RwLock::new(HashMap::new()),
             trace: RwLock::new(ExecutionTrace::new()),
+            events: self.events.clone(),
+            shared: self.shared.clone(),
         }
     }
 }
@@ -230,9 +430,81 @@ impl ExecutionContext {
             config,
             state: RwLock::new(HashMap::new()),
             trace: RwLock::new(ExecutionTrace::new()),
+            events: EventSink::disabled(),
+            shared: SharedContext::new(),
         }
     }
 
+    /// Use `shared` as the context shared between agents
+    ///
+    /// Lets callers seed values before a run and read them afterwards.
+    pub fn with_shared(mut self, shared: SharedContext) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    /// Context shared between the agents of the run
+    ///
+    /// Agents usually write through [`SharedContext::scope`] with their own name.
+    pub fn shared(&self) -> &SharedContext {
+        &self.shared
+    }
+
+    /// Run an agent with the shared context exposed to it
+    ///
+    /// A non-empty snapshot of the shared context is added to an object (or
+    /// null) `input.context` under `"shared_context"`, and the agent is marked
+    /// as running so overlapping writes can be detected.
+    pub(crate) async fn run_with_shared<F, T>(
+        &self,
+        agent: &str,
+        mut input: crate::orchestration::agent::AgentInput,
+        run: impl FnOnce(crate::orchestration::agent::AgentInput) -> F,
+    ) -> T
+    where
+        F: std::future::Future<Output = T>,
+    {
+        let snapshot = self.shared.snapshot().await;
+        if !snapshot.is_empty() {
+            if input.context.is_null() {
+                input.context = serde_json::json!({});
+            }
+            if let Some(context) = input.context.as_object_mut() {
+                context.insert(
+                    "shared_context".to_string(),
+                    serde_json::to_value(&snapshot).unwrap_or_default(),
+                );
+            }
+        }
+
+        self.shared.agent_started(agent).await;
+        let result = run(input).await;
+        self.shared.agent_finished(agent).await;
+        result
+    }
+
+    /// Attach an event sink receiving progress events
+    pub fn with_events(mut self, events: EventSink) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Get the progress event sink
+    pub fn events(&self) -> &EventSink {
+        &self.events
+    }
+
+    /// Report intermediate progress of an agent
+    ///
+    /// Agents call this from [`Agent::execute_with_context`](crate::orchestration::Agent::execute_with_context)
+    /// to surface `StepProgress` events.
+    pub fn emit_progress(&self, agent: impl Into<String>, message: impl Into<String>) {
+        self.events.emit(OrchestrationEvent::StepProgress {
+            agent: agent.into(),
+            message: message.into(),
+        });
+    }
+
     /// Get configuration
     pub fn config(&self) -> &ExecutionConfig {
         &self.config
@@ -262,9 +534,29 @@ impl ExecutionContext {
         state.clear();
     }
 
-    /// Get execution trace
+    /// Get execution trace, including the writes to the shared context
     pub async fn get_trace(&self) -> ExecutionTrace {
-        self.trace.read().await.clone()
+        let mut trace = self.trace.read().await.clone();
+        trace.context_changes = self.shared.changes().await;
+        trace
+    }
+
+    /// Add a debate round to trace
+    pub async fn add_debate_round(&self, round: DebateRound) {
+        let mut trace = self.trace.write().await;
+        trace.debate_rounds.push(round);
+    }
+
+    /// Add a retried attempt to trace
+    pub async fn add_retry(&self, retry: RetriedAttempt) {
+        let mut trace = self.trace.write().await;
+        trace.retries.push(retry);
+    }
+
+    /// Record how agent outputs were aggregated
+    pub async fn set_aggregation(&self, aggregation: AggregationRecord) {
+        let mut trace = self.trace.write().await;
+        trace.aggregation = Some(aggregation);
     }
 
     /// Add agent execution to trace
@@ -300,12 +592,18 @@ mod tests {
             .with_timeout(Duration::from_secs(60))
             .with_max_retries(5)
             .with_parallel_limit(20)
+            .with_agent_timeout(Duration::from_secs(10))
+            .with_min_confidence(0.7)
+            .with_aggregation(AggregationStrategy::HighestConfidence)
             .with_logging(false)
             .with_tracing(false);
 
         assert_eq!(config.timeout.as_secs(), 60);
         assert_eq!(config.max_retries, 5);
         assert_eq!(config.parallel_limit, 20);
+        assert_eq!(config.agent_timeout, Some(Duration::from_secs(10)));
+        assert_eq!(config.min_confidence, Some(0.7));
+        assert_eq!(config.aggregation, AggregationStrategy::HighestConfidence);
         assert!(!config.enable_logging);
         assert!(!config.enable_tracing);
     }
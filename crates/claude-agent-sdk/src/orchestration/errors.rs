@@ -22,6 +22,9 @@ pub enum OrchestrationError {
     #[error("Execution cancelled")]
     Cancelled,
 
+    #[error("No agent matched the input")]
+    NoAgentMatched,
+
     #[error("Partial success: {0} agents failed")]
     PartialSuccess(usize),
 
@@ -0,0 +1,178 @@
+//! # Orchestration progress events
+//!
+//! Orchestrators report their progress through an [`EventSink`] carried by the
+//! [`ExecutionContext`](crate::orchestration::ExecutionContext). Use
+//! [`Orchestrator::orchestrate_with_events`](crate::orchestration::Orchestrator::orchestrate_with_events)
+//! to receive the events as a stream, e.g. to drive a progress UI.
+//!
+//! ## Ordering
+//!
+//! - `RunStarted` is always the first event and `RunCompleted` always the last.
+//! - For a single agent, events are ordered: `StepStarted`, then any
+//!   `StepProgress` / `RetryScheduled`, then exactly one of `StepCompleted` or `StepFailed`.
+//! - The sequential pattern never interleaves steps: step `n + 1` starts only after
+//!   step `n` completed, and no further steps start after a `StepFailed`.
+//! - The parallel pattern interleaves events of different agents arbitrarily;
+//!   `StepStarted` is emitted once the agent acquired a concurrency slot.
+
+use futures::stream::Stream;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Progress event emitted during an orchestration run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OrchestrationEvent {
+    /// The orchestration run started
+    RunStarted {
+        /// Orchestrator name
+        orchestrator: String,
+        /// Number of agents taking part
+        agent_count: usize,
+    },
+    /// An agent started executing
+    StepStarted {
+        /// Agent name
+        agent: String,
+        /// Zero-based position of the agent in the run
+        index: usize,
+    },
+    /// An agent reported intermediate progress
+    StepProgress {
+        /// Agent name
+        agent: String,
+        /// Free-form progress message
+        message: String,
+    },
+    /// An agent finished successfully
+    StepCompleted {
+        /// Agent name
+        agent: String,
+        /// Zero-based position of the agent in the run
+        index: usize,
+        /// Wall-clock time spent in the step, including retries
+        duration: Duration,
+        /// Confidence reported by the agent
+        confidence: f64,
+    },
+    /// An agent failed after exhausting its retries
+    StepFailed {
+        /// Agent name
+        agent: String,
+        /// Zero-based position of the agent in the run
+        index: usize,
+        /// Failure description
+        error: String,
+    },
+    /// An agent attempt failed and will be retried
+    RetryScheduled {
+        /// Agent name
+        agent: String,
+        /// Number of the upcoming attempt (the first retry is attempt 1)
+        attempt: usize,
+        /// Delay before the retry
+        delay: Duration,
+        /// Error of the failed attempt
+        error: String,
+    },
+    /// The orchestration run finished
+    RunCompleted {
+        /// Whether the run succeeded
+        success: bool,
+        /// Total wall-clock duration of the run
+        duration: Duration,
+    },
+}
+
+/// Stream of orchestration events
+pub type OrchestrationEventStream = Pin<Box<dyn Stream<Item = OrchestrationEvent> + Send>>;
+
+/// Destination for orchestration events
+///
+/// Emitting never blocks; events are dropped silently when nobody listens.
+#[derive(Debug, Clone, Default)]
+pub struct EventSink {
+    tx: Option<mpsc::UnboundedSender<OrchestrationEvent>>,
+}
+
+impl EventSink {
+    /// Create a sink that discards all events
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Create a connected sink and the stream receiving its events
+    ///
+    /// The stream ends once every clone of the sink has been dropped.
+    pub fn channel() -> (Self, OrchestrationEventStream) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|event| (event, rx))
+        });
+        (Self { tx: Some(tx) }, Box::pin(stream))
+    }
+
+    /// Check whether events are delivered anywhere
+    pub fn is_enabled(&self) -> bool {
+        self.tx.as_ref().is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Emit an event
+    pub fn emit(&self, event: OrchestrationEvent) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_channel_delivers_events_in_order() {
+        let (sink, stream) = EventSink::channel();
+        assert!(sink.is_enabled());
+
+        sink.emit(OrchestrationEvent::StepProgress {
+            agent: "A".to_string(),
+            message: "one".to_string(),
+        });
+        sink.emit(OrchestrationEvent::StepProgress {
+            agent: "A".to_string(),
+            message: "two".to_string(),
+        });
+        drop(sink);
+
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[1],
+            OrchestrationEvent::StepProgress { message, .. } if message == "two"
+        ));
+    }
+
+    #[test]
+    fn test_disabled_sink_discards_events() {
+        let sink = EventSink::disabled();
+        assert!(!sink.is_enabled());
+        sink.emit(OrchestrationEvent::RunCompleted {
+            success: true,
+            duration: Duration::ZERO,
+        });
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = OrchestrationEvent::StepStarted {
+            agent: "Researcher".to_string(),
+            index: 0,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "step_started");
+        assert_eq!(json["agent"], "Researcher");
+    }
+}
@@ -10,6 +10,7 @@
 //! - **Type-Safe Agent Interface**: Strongly typed agent definitions with Rust's trait system
 //! - **Async-First Design**: Full async/await support with Tokio
 //! - **Execution Tracking**: Comprehensive execution traces for debugging and monitoring
+//! - **Progress Events**: Stream step-level events for UI integration
 //! - **Error Recovery**: Built-in retry logic and graceful degradation
 //! - **Extensible**: Easy to add custom agents and orchestrators
 //!
@@ -59,15 +60,31 @@
 pub mod agent;
 pub mod context;
 pub mod errors;
+pub mod events;
 pub mod orchestrator;
 pub mod patterns;
 pub mod registry;
+pub mod shared;
 
 // Re-export commonly used types
 pub use agent::{Agent, AgentInput, AgentOutput};
-pub use context::{ExecutionConfig, ExecutionContext, ExecutionTrace};
+pub use context::{
+    AgentConfidence, AggregationRecord, AggregationStrategy, DebateRound, ExecutionConfig,
+    ExecutionContext, ExecutionTrace, RetriedAttempt, RoutingDecision,
+};
 pub use errors::{OrchestrationError, Result};
-pub use orchestrator::{Orchestrator, OrchestratorInput, OrchestratorOutput};
+pub use events::{EventSink, OrchestrationEvent, OrchestrationEventStream};
+pub use orchestrator::{
+    AgentOutcome, AgentStatus, Orchestrator, OrchestratorInput, OrchestratorOutput,
+};
 pub use registry::{AgentFilter, AgentMetadata, AgentRegistry, AgentRegistryBuilder, RegistryError};
+pub use shared::{
+    ContextChange, ContextOperation, ScopedContext, SharedContext, SharedContextSnapshot,
+};
 
-pub use patterns::{parallel::ParallelOrchestrator, sequential::SequentialOrchestrator};
+pub use patterns::{
+    debate::DebateOrchestrator,
+    parallel::ParallelOrchestrator,
+    router::{KeywordRouter, Router, RouterOrchestrator},
+    sequential::SequentialOrchestrator,
+};
@@ -5,11 +5,17 @@
 
 use crate::orchestration::{
     agent::{Agent, AgentInput, AgentOutput},
-    context::ExecutionTrace,
-    errors::Result,
+    context::{ExecutionConfig, ExecutionContext, ExecutionTrace, RetriedAttempt},
+    errors::{OrchestrationError, Result},
+    events::{EventSink, OrchestrationEvent, OrchestrationEventStream},
+    registry::{AgentFilter, AgentRegistry},
+    shared::SharedContextSnapshot,
 };
+use futures::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Base delay in milliseconds for retry backoff
 const RETRY_BASE_DELAY_MS: u64 = 100;
@@ -72,6 +78,38 @@ pub struct OrchestratorOutput {
 
     /// Error message if failed
     pub error: Option<String>,
+
+    /// How each agent's execution ended, in agent order
+    ///
+    /// Filled in by patterns that let some agents fail or time out while
+    /// others complete, such as the parallel pattern.
+    #[serde(default)]
+    pub agent_outcomes: Vec<AgentOutcome>,
+
+    /// Final state of the context shared between agents
+    #[serde(default)]
+    pub shared_context: SharedContextSnapshot,
+}
+
+/// How an agent's execution ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentStatus {
+    /// The agent produced an output
+    Completed,
+    /// The agent was cancelled for exceeding its timeout
+    TimedOut,
+    /// The agent failed after exhausting its retries
+    Failed,
+}
+
+/// Final status of one agent in an orchestration run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentOutcome {
+    /// Agent name
+    pub agent: String,
+    /// How the agent's execution ended
+    pub status: AgentStatus,
 }
 
 impl OrchestratorOutput {
@@ -87,6 +125,8 @@ impl OrchestratorOutput {
             execution_trace,
             success: true,
             error: None,
+            agent_outcomes: Vec::new(),
+            shared_context: SharedContextSnapshot::default(),
         }
     }
 
@@ -98,13 +138,36 @@ impl OrchestratorOutput {
             execution_trace,
             success: false,
             error: Some(error.into()),
+            agent_outcomes: Vec::new(),
+            shared_context: SharedContextSnapshot::default(),
         }
     }
 
+    /// Set the per-agent outcomes
+    pub fn with_agent_outcomes(mut self, agent_outcomes: Vec<AgentOutcome>) -> Self {
+        self.agent_outcomes = agent_outcomes;
+        self
+    }
+
+    /// Set the final state of the shared context
+    pub fn with_shared_context(mut self, shared_context: SharedContextSnapshot) -> Self {
+        self.shared_context = shared_context;
+        self
+    }
+
     /// Check if orchestration succeeded
     pub fn is_successful(&self) -> bool {
         self.success
     }
+
+    /// Names of the agents whose execution ended with `status`, in agent order
+    pub fn agents_with_status(&self, status: AgentStatus) -> Vec<&str> {
+        self.agent_outcomes
+            .iter()
+            .filter(|outcome| outcome.status == status)
+            .map(|outcome| outcome.agent.as_str())
+            .collect()
+    }
 }
 
 /// Core Orchestrator trait
@@ -125,6 +188,52 @@ pub trait Orchestrator: Send + Sync {
         agents: Vec<Box<dyn Agent>>,
         input: OrchestratorInput,
     ) -> Result<OrchestratorOutput>;
+
+    /// Execute orchestration while reporting progress events to `events`
+    ///
+    /// The default implementation only emits `RunStarted` and `RunCompleted`
+    /// around [`Orchestrator::orchestrate`]. The built-in patterns override it
+    /// to report every step.
+    async fn orchestrate_with_sink(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+        events: EventSink,
+    ) -> Result<OrchestratorOutput> {
+        let started = Instant::now();
+        events.emit(OrchestrationEvent::RunStarted {
+            orchestrator: self.name().to_string(),
+            agent_count: agents.len(),
+        });
+
+        let result = self.orchestrate(agents, input).await;
+
+        events.emit(OrchestrationEvent::RunCompleted {
+            success: matches!(&result, Ok(output) if output.success),
+            duration: started.elapsed(),
+        });
+        result
+    }
+
+    /// Execute orchestration and stream its progress events
+    ///
+    /// Returns the event stream together with the orchestration future. Events
+    /// only flow while the future is being polled, so drive both concurrently
+    /// (e.g. with `tokio::join!` or by spawning the future). The stream ends
+    /// once the future has completed.
+    ///
+    /// See [`events`](crate::orchestration::events) for ordering guarantees.
+    fn orchestrate_with_events<'a>(
+        &'a self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+    ) -> (
+        OrchestrationEventStream,
+        BoxFuture<'a, Result<OrchestratorOutput>>,
+    ) {
+        let (sink, stream) = EventSink::channel();
+        (stream, self.orchestrate_with_sink(agents, input, sink))
+    }
 }
 
 /// Base orchestrator that provides common functionality
@@ -158,35 +267,82 @@ impl BaseOrchestrator {
         agent: &dyn Agent,
         input: AgentInput,
         max_retries: usize,
+    ) -> AgentOutput {
+        let ctx = ExecutionContext::new(ExecutionConfig::default());
+        self.execute_agent_in_context(agent, input, max_retries, &ctx)
+            .await
+    }
+
+    /// Execute an agent with retry logic, reporting retries to the context's event sink
+    ///
+    /// With [`ExecutionConfig::min_confidence`] set, an output below it is
+    /// retried too, without delay and with feedback appended to the input. If
+    /// no attempt reaches the minimum, the most confident output is returned.
+    pub async fn execute_agent_in_context(
+        &self,
+        agent: &dyn Agent,
+        input: AgentInput,
+        max_retries: usize,
+        ctx: &ExecutionContext,
     ) -> AgentOutput {
         let mut last_error = None;
+        let mut best: Option<AgentOutput> = None;
+        let mut attempt_input = input.clone();
 
         for attempt in 0..=max_retries {
-            match agent.execute(input.clone()).await {
-                Ok(output) => return output,
-                Err(e) => {
-                    last_error = Some(e.to_string());
-                    if attempt < max_retries {
-                        // Exponential backoff with jitter to prevent thundering herd
-                        let base_delay = RETRY_BASE_DELAY_MS * 2_u64.pow(attempt as u32);
-                        let jitter = {
-                            // Simple jitter using system time nanoseconds as entropy
-                            let nanos = std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .map(|d| d.subsec_nanos())
-                                .unwrap_or(0);
-                            let jitter_range = (base_delay as f64 * RETRY_JITTER_FACTOR) as u64;
-                            if jitter_range > 0 {
-                                (nanos as u64) % jitter_range
-                            } else {
-                                0
+            let started = chrono::Utc::now();
+            let (error, confidence, delay) =
+                match agent.execute_with_context(attempt_input.clone(), ctx).await {
+                    Ok(output) => match ctx.config().min_confidence {
+                        Some(min) if output.confidence < min => {
+                            let error = format!(
+                                "Confidence {:.2} is below the minimum of {:.2}",
+                                output.confidence, min
+                            );
+                            attempt_input.content = format!(
+                                "{}\n\nFeedback: your previous answer had a confidence of {:.2}, \
+                                 below the required {:.2}. Improve on it.\n\nPrevious answer:\n{}",
+                                input.content, output.confidence, min, output.content
+                            );
+                            let confidence = output.confidence;
+                            if best
+                                .as_ref()
+                                .is_none_or(|best| output.confidence > best.confidence)
+                            {
+                                best = Some(output);
                             }
-                        };
-                        tokio::time::sleep(std::time::Duration::from_millis(base_delay + jitter))
-                            .await;
-                    }
-                },
+                            (error, Some(confidence), Duration::ZERO)
+                        },
+                        _ => return output,
+                    },
+                    Err(e) => (e.to_string(), None, Self::retry_delay(attempt)),
+                };
+
+            if attempt < max_retries {
+                if ctx.is_tracing_enabled() {
+                    ctx.add_retry(RetriedAttempt {
+                        agent_name: agent.name().to_string(),
+                        attempt,
+                        start_time: started,
+                        end_time: chrono::Utc::now(),
+                        error: error.clone(),
+                        confidence,
+                    })
+                    .await;
+                }
+                ctx.events().emit(OrchestrationEvent::RetryScheduled {
+                    agent: agent.name().to_string(),
+                    attempt: attempt + 1,
+                    delay,
+                    error: error.clone(),
+                });
+                tokio::time::sleep(delay).await;
             }
+            last_error = Some(error);
+        }
+
+        if let Some(best) = best {
+            return best;
         }
 
         // All retries failed
@@ -199,6 +355,25 @@ impl BaseOrchestrator {
         .with_confidence(0.0)
     }
 
+    /// Exponential backoff with jitter to prevent thundering herd
+    fn retry_delay(attempt: usize) -> Duration {
+        let base_delay = RETRY_BASE_DELAY_MS * 2_u64.pow(attempt as u32);
+        let jitter = {
+            // Simple jitter using system time nanoseconds as entropy
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.subsec_nanos())
+                .unwrap_or(0);
+            let jitter_range = (base_delay as f64 * RETRY_JITTER_FACTOR) as u64;
+            if jitter_range > 0 {
+                (nanos as u64) % jitter_range
+            } else {
+                0
+            }
+        };
+        Duration::from_millis(base_delay + jitter)
+    }
+
     /// Convert orchestrator input to agent input
     pub fn input_to_agent_input(&self, input: &OrchestratorInput) -> AgentInput {
         AgentInput::new(&input.content)
@@ -207,6 +382,33 @@ impl BaseOrchestrator {
     }
 }
 
+/// Agents of `registry` matching `filter`, for building an orchestrator
+///
+/// Fails if no agent matches.
+pub(crate) async fn select_agents(
+    registry: &AgentRegistry,
+    filter: &AgentFilter,
+) -> Result<Vec<Arc<dyn Agent>>> {
+    let agents = registry.select(filter).await;
+    if agents.is_empty() {
+        return Err(OrchestrationError::invalid_config(
+            "No registered agent matches the filter",
+        ));
+    }
+    Ok(agents)
+}
+
+/// An orchestrator's own agents followed by the agents given to a run
+pub(crate) fn with_own_agents(
+    own: &[Arc<dyn Agent>],
+    agents: Vec<Box<dyn Agent>>,
+) -> Vec<Box<dyn Agent>> {
+    own.iter()
+        .map(|agent| Box::new(Arc::clone(agent)) as Box<dyn Agent>)
+        .chain(agents)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,4 +484,96 @@ mod tests {
         assert!(output.content.contains("failed after"));
         assert_eq!(output.confidence, 0.0);
     }
+
+    #[tokio::test]
+    async fn test_retried_attempts_recorded_in_trace() {
+        let orchestrator = BaseOrchestrator::new("Test", "Test");
+        let agent = SimpleAgent::new("FailingAgent", "Always fails", |_input| {
+            Err(anyhow::anyhow!("Always fails").into())
+        });
+        let ctx = ExecutionContext::new(ExecutionConfig::default());
+
+        orchestrator
+            .execute_agent_in_context(&agent, AgentInput::new("Hello"), 2, &ctx)
+            .await;
+
+        // The final attempt is not retried
+        let retries = ctx.get_trace().await.retries;
+        assert_eq!(retries.len(), 2);
+        assert_eq!(retries[0].agent_name, "FailingAgent");
+        assert_eq!(retries[0].attempt, 0);
+        assert_eq!(retries[1].attempt, 1);
+        assert!(retries[1].error.contains("Always fails"));
+        assert!(retries[0].end_time <= retries[1].start_time);
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_is_retried_with_feedback() {
+        let orchestrator = BaseOrchestrator::new("Test", "Test");
+        let inputs = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&inputs);
+        let agent = SimpleAgent::new("Writer", "Improves when told", move |input| {
+            seen.lock().unwrap().push(input.content.clone());
+            let confidence = if input.content.contains("Feedback") { 0.9 } else { 0.4 };
+            Ok(AgentOutput::new("draft").with_confidence(confidence))
+        });
+        let ctx = ExecutionContext::new(ExecutionConfig::default().with_min_confidence(0.8));
+
+        let output = orchestrator
+            .execute_agent_in_context(&agent, AgentInput::new("Write"), 3, &ctx)
+            .await;
+
+        assert_eq!(output.confidence, 0.9);
+        let inputs = inputs.lock().unwrap().clone();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0], "Write");
+        assert!(inputs[1].starts_with("Write\n\nFeedback:"));
+        assert!(inputs[1].contains("0.40") && inputs[1].contains("0.80"));
+        assert!(inputs[1].ends_with("Previous answer:\ndraft"));
+
+        let retries = ctx.get_trace().await.retries;
+        assert_eq!(retries.len(), 1);
+        assert_eq!(retries[0].confidence, Some(0.4));
+        assert!(retries[0].error.contains("below the minimum"));
+    }
+
+    #[tokio::test]
+    async fn test_most_confident_output_is_kept_when_gate_is_never_met() {
+        let orchestrator = BaseOrchestrator::new("Test", "Test");
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let agent = SimpleAgent::new("Unsure", "Never confident", move |_input| {
+            let call = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let confidence = [0.3, 0.6, 0.5][call];
+            Ok(AgentOutput::new(format!("attempt {}", call)).with_confidence(confidence))
+        });
+        let ctx = ExecutionContext::new(ExecutionConfig::default().with_min_confidence(0.8));
+
+        let output = orchestrator
+            .execute_agent_in_context(&agent, AgentInput::new("Decide"), 2, &ctx)
+            .await;
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(output.content, "attempt 1");
+        assert_eq!(output.confidence, 0.6);
+        let retries = ctx.get_trace().await.retries;
+        let confidences: Vec<_> = retries.iter().map(|retry| retry.confidence).collect();
+        assert_eq!(confidences, vec![Some(0.3), Some(0.6)]);
+    }
+
+    #[tokio::test]
+    async fn test_outputs_are_not_gated_without_min_confidence() {
+        let orchestrator = BaseOrchestrator::new("Test", "Test");
+        let agent = SimpleAgent::new("Unsure", "Low confidence", |_input| {
+            Ok(AgentOutput::new("maybe").with_confidence(0.1))
+        });
+        let ctx = ExecutionContext::new(ExecutionConfig::default());
+
+        let output = orchestrator
+            .execute_agent_in_context(&agent, AgentInput::new("Decide"), 2, &ctx)
+            .await;
+
+        assert_eq!(output.confidence, 0.1);
+        assert!(ctx.get_trace().await.retries.is_empty());
+    }
 }
@@ -0,0 +1,512 @@
+//! # Debate Orchestration Pattern
+//!
+//! Agents argue over several rounds, each seeing the others' previous positions,
+//! and a judge agent turns the transcript into the final answer.
+//!
+//! ```text
+//!          Round 1         Round 2
+//!        → Agent A ─┐    → Agent A ─┐
+//! Input ─┼→ Agent B ─┼───┼→ Agent B ─┼→ Judge → Output
+//!        → Agent C ─┘    → Agent C ─┘
+//! ```
+//!
+//! Use cases:
+//! - Weighing competing proposals
+//! - Stress-testing an answer against critics
+//! - Reducing single-agent bias
+//!
+//! A debater that fails is recorded in the round's trace and sits out the
+//! remaining rounds. With [`ExecutionConfig::stop_on_consensus`], the judge
+//! evaluates every round and can end the debate early by reporting
+//! `consensus: true`, either in its output's `data` or as a JSON object in its
+//! content.
+
+use crate::orchestration::{
+    Result,
+    agent::{Agent, AgentInput, AgentOutput},
+    context::{AgentExecution, DebateRound, ExecutionConfig, ExecutionContext},
+    errors::OrchestrationError,
+    events::{EventSink, OrchestrationEvent},
+    orchestrator::{BaseOrchestrator, Orchestrator, OrchestratorInput, OrchestratorOutput},
+};
+use futures::future::join_all;
+use std::time::Instant;
+use tracing::{debug, warn};
+
+/// Debate orchestrator that runs rounds of debaters followed by a judge
+pub struct DebateOrchestrator {
+    base: BaseOrchestrator,
+    debaters: Vec<Box<dyn Agent>>,
+    judge: Box<dyn Agent>,
+    rounds: usize,
+    config: ExecutionConfig,
+}
+
+/// What a debater said in a round
+struct Position {
+    agent: String,
+    content: String,
+}
+
+impl DebateOrchestrator {
+    /// Create a debate between `agents` over `rounds` rounds, decided by `judge`
+    ///
+    /// The number of rounds is capped by [`ExecutionConfig::max_rounds`].
+    pub fn new(agents: Vec<Box<dyn Agent>>, judge: Box<dyn Agent>, rounds: usize) -> Self {
+        Self {
+            base: BaseOrchestrator::new(
+                "DebateOrchestrator",
+                "Runs rounds of debate between agents and lets a judge decide",
+            ),
+            debaters: agents,
+            judge,
+            rounds,
+            config: ExecutionConfig::new(),
+        }
+    }
+
+    /// Set the execution config, e.g. the round cap and early termination
+    pub fn with_config(mut self, config: ExecutionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Run the debate between the agents given to [`new`](Self::new)
+    pub async fn debate(&self, input: OrchestratorInput) -> Result<OrchestratorOutput> {
+        self.orchestrate(Vec::new(), input).await
+    }
+
+    /// Input of a debater: the original input plus the other debaters'
+    /// positions from the previous round
+    fn debater_input(
+        base: &AgentInput,
+        round: usize,
+        agent: &str,
+        previous: &[Position],
+    ) -> AgentInput {
+        let others: Vec<String> = previous
+            .iter()
+            .filter(|position| position.agent != agent)
+            .map(|position| format!("### {}\n\n{}", position.agent, position.content))
+            .collect();
+
+        let mut input = base.clone();
+        if !others.is_empty() {
+            input.content = format!(
+                "{}\n\n## Other positions from round {}\n\n{}",
+                base.content,
+                round - 1,
+                others.join("\n\n")
+            );
+        }
+        input.with_metadata("debate_round", round.to_string())
+    }
+
+    /// Input of the judge: the original input plus the whole transcript
+    fn judge_input(base: &AgentInput, transcript: &[Vec<Position>]) -> AgentInput {
+        let rounds: Vec<String> = transcript
+            .iter()
+            .enumerate()
+            .map(|(index, positions)| {
+                let positions: Vec<String> = positions
+                    .iter()
+                    .map(|position| format!("#### {}\n\n{}", position.agent, position.content))
+                    .collect();
+                format!("### Round {}\n\n{}", index + 1, positions.join("\n\n"))
+            })
+            .collect();
+
+        let mut input = base.clone();
+        input.content = format!(
+            "{}\n\n## Debate transcript\n\n{}",
+            base.content,
+            rounds.join("\n\n")
+        );
+        input.with_metadata("debate_round", transcript.len().to_string())
+    }
+
+    /// Whether the judge reported consensus
+    fn consensus_of(output: &AgentOutput) -> bool {
+        if let Some(consensus) = output.data.get("consensus").and_then(|v| v.as_bool()) {
+            return consensus;
+        }
+        serde_json::from_str::<serde_json::Value>(output.content.trim())
+            .ok()
+            .and_then(|value| value.get("consensus")?.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Execute one agent, reporting events and recording the execution
+    async fn execute_step(
+        &self,
+        agent: &dyn Agent,
+        index: usize,
+        input: AgentInput,
+        ctx: &ExecutionContext,
+    ) -> (AgentOutput, AgentExecution) {
+        let mut exec_record = AgentExecution::new(agent.name(), input.clone());
+
+        ctx.events().emit(OrchestrationEvent::StepStarted {
+            agent: agent.name().to_string(),
+            index,
+        });
+        let step_started = Instant::now();
+
+        let output = self
+            .base
+            .execute_agent_in_context(agent, input, self.config.max_retries, ctx)
+            .await;
+
+        if output.is_successful() {
+            ctx.events().emit(OrchestrationEvent::StepCompleted {
+                agent: agent.name().to_string(),
+                index,
+                duration: step_started.elapsed(),
+                confidence: output.confidence,
+            });
+            exec_record.succeed(output.clone());
+        } else {
+            ctx.events().emit(OrchestrationEvent::StepFailed {
+                agent: agent.name().to_string(),
+                index,
+                error: output.content.clone(),
+            });
+            exec_record.fail(output.content.clone());
+        }
+
+        if ctx.is_tracing_enabled() {
+            ctx.add_execution(exec_record.clone()).await;
+        }
+        (output, exec_record)
+    }
+
+    /// Run the debate rounds and the judge
+    async fn execute_debate(
+        &self,
+        debaters: &[&dyn Agent],
+        input: AgentInput,
+        rounds: usize,
+        ctx: &ExecutionContext,
+    ) -> Result<Vec<AgentOutput>> {
+        let judge_index = debaters.len();
+        let mut active = vec![true; debaters.len()];
+        let mut transcript: Vec<Vec<Position>> = Vec::new();
+        let mut outputs = Vec::new();
+
+        for round in 1..=rounds {
+            if ctx.is_logging_enabled() {
+                debug!(
+                    orchestrator = %self.base.name(),
+                    round,
+                    total = rounds,
+                    "Starting debate round"
+                );
+            }
+
+            let previous = transcript.last().map(Vec::as_slice).unwrap_or_default();
+            let steps = debaters
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| active[*index])
+                .map(|(index, agent)| {
+                    let agent_input = Self::debater_input(&input, round, agent.name(), previous);
+                    async move {
+                        (
+                            index,
+                            self.execute_step(*agent, index, agent_input, ctx).await,
+                        )
+                    }
+                });
+            let results = join_all(steps).await;
+
+            let mut positions = Vec::new();
+            let mut executions = Vec::new();
+            for (index, (output, exec_record)) in results {
+                if exec_record.success {
+                    positions.push(Position {
+                        agent: exec_record.agent_name.clone(),
+                        content: output.content.clone(),
+                    });
+                    outputs.push(output);
+                } else {
+                    warn!(
+                        agent = %exec_record.agent_name,
+                        round,
+                        "Debater failed and leaves the debate"
+                    );
+                    active[index] = false;
+                }
+                executions.push(exec_record);
+            }
+            transcript.push(positions);
+
+            let mut debate_round = DebateRound {
+                round,
+                positions: executions,
+                consensus: None,
+            };
+            if !active.contains(&true) {
+                ctx.add_debate_round(debate_round).await;
+                return Err(OrchestrationError::agent_failure(
+                    "all debaters",
+                    format!("No debater left after round {}", round),
+                ));
+            }
+
+            let last_round = round == rounds;
+            if !last_round && !self.config.stop_on_consensus {
+                ctx.add_debate_round(debate_round).await;
+                continue;
+            }
+
+            let judge_input = Self::judge_input(&input, &transcript);
+            let (verdict, _) = self
+                .execute_step(self.judge.as_ref(), judge_index, judge_input, ctx)
+                .await;
+            if !verdict.is_successful() {
+                ctx.add_debate_round(debate_round).await;
+                return Err(OrchestrationError::agent_failure(
+                    self.judge.name(),
+                    verdict.content,
+                ));
+            }
+
+            let consensus = Self::consensus_of(&verdict);
+            debate_round.consensus = Some(consensus);
+            ctx.add_debate_round(debate_round).await;
+            if last_round || consensus {
+                outputs.push(verdict);
+                return Ok(outputs);
+            }
+        }
+
+        unreachable!("the last round always returns")
+    }
+}
+
+#[async_trait::async_trait]
+impl Orchestrator for DebateOrchestrator {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn description(&self) -> &str {
+        self.base.description()
+    }
+
+    /// Run the debate
+    ///
+    /// `agents` join the debaters given to [`DebateOrchestrator::new`].
+    async fn orchestrate(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+    ) -> Result<OrchestratorOutput> {
+        self.orchestrate_with_sink(agents, input, EventSink::disabled())
+            .await
+    }
+
+    async fn orchestrate_with_sink(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+        events: EventSink,
+    ) -> Result<OrchestratorOutput> {
+        let debaters: Vec<&dyn Agent> = self
+            .debaters
+            .iter()
+            .chain(&agents)
+            .map(|agent| agent.as_ref())
+            .collect();
+        if debaters.is_empty() {
+            return Err(OrchestrationError::invalid_config(
+                "At least one debater is required",
+            ));
+        }
+        let rounds = self.rounds.min(self.config.max_rounds);
+        if rounds == 0 {
+            return Err(OrchestrationError::invalid_config(
+                "At least one debate round is required",
+            ));
+        }
+
+        let run_started = Instant::now();
+        events.emit(OrchestrationEvent::RunStarted {
+            orchestrator: self.base.name().to_string(),
+            agent_count: debaters.len() + 1,
+        });
+
+        let ctx = ExecutionContext::new(self.config.clone()).with_events(events.clone());
+        let agent_input = self.base.input_to_agent_input(&input);
+
+        let result = self
+            .execute_debate(&debaters, agent_input, rounds, &ctx)
+            .await;
+
+        ctx.complete_trace().await;
+        let trace = ctx.get_trace().await;
+        events.emit(OrchestrationEvent::RunCompleted {
+            success: result.is_ok(),
+            duration: run_started.elapsed(),
+        });
+
+        match result {
+            Ok(outputs) => {
+                let verdict = outputs.last().map(|output| output.content.clone());
+                Ok(OrchestratorOutput::success(
+                    verdict.unwrap_or_default(),
+                    outputs,
+                    trace,
+                ))
+            },
+            Err(e) => Ok(OrchestratorOutput::failure(e.to_string(), trace)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestration::agent::SimpleAgent;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn debater(name: &'static str) -> Box<dyn Agent> {
+        Box::new(SimpleAgent::new(name, "Debater", move |input| {
+            Ok(AgentOutput::new(format!(
+                "{} says round {}",
+                name, input.metadata["debate_round"]
+            )))
+        }))
+    }
+
+    /// Judge counting its calls, reporting consensus from call `consensus_at` on
+    fn judge(calls: &Arc<AtomicUsize>, consensus_at: usize) -> Box<dyn Agent> {
+        let calls = Arc::clone(calls);
+        Box::new(SimpleAgent::new("Judge", "Decides", move |input| {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(AgentOutput::new(format!("Verdict on: {}", input.content))
+                .with_data(serde_json::json!({"consensus": call >= consensus_at})))
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_debaters_see_other_positions() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let orchestrator = DebateOrchestrator::new(
+            vec![debater("Pro"), debater("Con")],
+            judge(&calls, usize::MAX),
+            2,
+        );
+
+        let output = orchestrator
+            .debate(OrchestratorInput::new("Tabs or spaces?"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        // 2 debaters x 2 rounds, then the judge
+        assert_eq!(output.agent_outputs.len(), 5);
+
+        let rounds = &output.execution_trace.debate_rounds;
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(rounds[0].consensus, None);
+        assert_eq!(rounds[1].consensus, Some(false));
+
+        let pro_round_2 = &rounds[1].positions[0].input.content;
+        assert!(pro_round_2.contains("## Other positions from round 1"));
+        assert!(pro_round_2.contains("Con says round 1"));
+        assert!(!pro_round_2.contains("Pro says"));
+
+        assert!(output.result.contains("### Round 1"));
+        assert!(output.result.contains("Con says round 2"));
+    }
+
+    #[tokio::test]
+    async fn test_consensus_ends_debate_early() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let orchestrator =
+            DebateOrchestrator::new(vec![debater("Pro"), debater("Con")], judge(&calls, 2), 5)
+                .with_config(ExecutionConfig::new().with_stop_on_consensus(true));
+
+        let output = orchestrator
+            .debate(OrchestratorInput::new("Tabs or spaces?"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        let rounds = &output.execution_trace.debate_rounds;
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(rounds[0].consensus, Some(false));
+        assert_eq!(rounds[1].consensus, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_rounds_are_capped_by_config() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let orchestrator = DebateOrchestrator::new(vec![debater("Pro")], judge(&calls, 1), 50)
+            .with_config(ExecutionConfig::new().with_max_rounds(3));
+
+        let output = orchestrator
+            .debate(OrchestratorInput::new("Topic"))
+            .await
+            .unwrap();
+        assert_eq!(output.execution_trace.debate_rounds.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_debater_sits_out_remaining_rounds() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let failing: Box<dyn Agent> = Box::new(SimpleAgent::new("Flaky", "Fails", |_input| {
+            Err(anyhow::anyhow!("boom").into())
+        }));
+        let orchestrator =
+            DebateOrchestrator::new(vec![debater("Pro"), failing], judge(&calls, usize::MAX), 3)
+                .with_config(ExecutionConfig::new().with_max_retries(0));
+
+        let output = orchestrator
+            .debate(OrchestratorInput::new("Topic"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        let rounds = &output.execution_trace.debate_rounds;
+        assert_eq!(rounds[0].positions.len(), 2);
+        assert!(!rounds[0].positions[1].success);
+        assert_eq!(rounds[1].positions.len(), 1);
+        assert_eq!(rounds[2].positions[0].agent_name, "Pro");
+    }
+
+    #[tokio::test]
+    async fn test_debate_fails_without_debaters_left() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let failing: Box<dyn Agent> = Box::new(SimpleAgent::new("Flaky", "Fails", |_input| {
+            Err(anyhow::anyhow!("boom").into())
+        }));
+        let orchestrator = DebateOrchestrator::new(vec![failing], judge(&calls, 1), 2)
+            .with_config(ExecutionConfig::new().with_max_retries(0));
+
+        let output = orchestrator
+            .debate(OrchestratorInput::new("Topic"))
+            .await
+            .unwrap();
+        assert!(!output.is_successful());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(output.execution_trace.debate_rounds.len(), 1);
+
+        let empty = DebateOrchestrator::new(Vec::new(), judge(&calls, 1), 2)
+            .debate(OrchestratorInput::new("Topic"))
+            .await;
+        assert!(matches!(empty, Err(OrchestrationError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn test_consensus_from_json_content() {
+        let output = AgentOutput::new(r#"{"consensus": true, "winner": "Pro"}"#);
+        assert!(DebateOrchestrator::consensus_of(&output));
+        assert!(!DebateOrchestrator::consensus_of(&AgentOutput::new(
+            "Pro wins"
+        )));
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! This module contains various orchestration patterns for coordinating multiple agents.
 
+pub mod debate;
 pub mod parallel;
+pub mod router;
 pub mod sequential;
 
 // Re-export orchestrators
+pub use debate::DebateOrchestrator;
 pub use parallel::ParallelOrchestrator;
+pub use router::{KeywordRouter, Router, RouterOrchestrator};
 pub use sequential::SequentialOrchestrator;
@@ -12,15 +12,41 @@
 //! - Multi-angle analysis
 //! - Parallel task processing
 //! - Performance optimization
+//!
+//! At most [`with_concurrency`](ParallelOrchestrator::with_concurrency) agents
+//! run at a time. An agent exceeding
+//! [`with_agent_timeout`](ParallelOrchestrator::with_agent_timeout) is cancelled
+//! and reported in [`OrchestratorOutput::agent_outcomes`] without failing the
+//! run; a failing agent fails the run.
+//!
+//! The result is assembled from the outputs of the completed agents according
+//! to the [`AggregationStrategy`], which is recorded in the trace together with
+//! each agent's confidence. With
+//! [`with_min_confidence`](ParallelOrchestrator::with_min_confidence), agents
+//! are retried until their output is confident enough.
+//!
+//! Agents can exchange values through the run's [`SharedContext`]. When two
+//! running agents write the same key, the last write wins and the conflict is
+//! logged and flagged in the trace's `context_changes`.
 
 use crate::orchestration::{
     Result,
     agent::{Agent, AgentInput, AgentOutput},
-    context::{AgentExecution, ExecutionContext},
-    orchestrator::{BaseOrchestrator, Orchestrator, OrchestratorInput, OrchestratorOutput},
+    context::{
+        AgentConfidence, AgentExecution, AggregationRecord, AggregationStrategy, ExecutionContext,
+    },
+    events::{EventSink, OrchestrationEvent},
+    errors::OrchestrationError,
+    orchestrator::{
+        AgentOutcome, AgentStatus, BaseOrchestrator, Orchestrator, OrchestratorInput,
+        OrchestratorOutput, select_agents, with_own_agents,
+    },
+    registry::{AgentFilter, AgentRegistry},
+    shared::SharedContext,
 };
 use futures::future::join_all;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 use tracing::debug;
 
@@ -30,14 +56,23 @@ const DEFAULT_MAX_RETRIES: usize = 3;
 /// Default parallel execution limit
 const DEFAULT_PARALLEL_LIMIT: usize = 10;
 
-/// Base delay in milliseconds for retry backoff
-const RETRY_BASE_DELAY_MS: u64 = 100;
-
 /// Parallel orchestrator that executes agents concurrently
 pub struct ParallelOrchestrator {
     base: BaseOrchestrator,
     max_retries: usize,
     parallel_limit: usize,
+    agent_timeout: Option<Duration>,
+    min_confidence: Option<f64>,
+    aggregation: AggregationStrategy,
+    agents: Vec<Arc<dyn Agent>>,
+    shared: Option<SharedContext>,
+}
+
+/// Result of one agent's execution
+struct AgentRun {
+    agent: String,
+    status: AgentStatus,
+    output: AgentOutput,
 }
 
 impl ParallelOrchestrator {
@@ -50,18 +85,84 @@ impl ParallelOrchestrator {
             ),
             max_retries: DEFAULT_MAX_RETRIES,
             parallel_limit: DEFAULT_PARALLEL_LIMIT,
+            agent_timeout: None,
+            min_confidence: None,
+            aggregation: AggregationStrategy::default(),
+            agents: Vec::new(),
+            shared: None,
         }
     }
 
+    /// Create a parallel orchestrator over the agents of `registry` matching `filter`
+    ///
+    /// The agents keep their registration order in the output, before any
+    /// agents given to [`orchestrate`](Orchestrator::orchestrate).
+    ///
+    /// # Errors
+    ///
+    /// Returns `OrchestrationError::InvalidConfig` if no agent matches.
+    pub async fn from_registry(registry: &AgentRegistry, filter: &AgentFilter) -> Result<Self> {
+        let mut orchestrator = Self::new();
+        orchestrator.agents = select_agents(registry, filter).await?;
+        Ok(orchestrator)
+    }
+
+    /// Run the orchestrator's own agents, e.g. those selected from a registry
+    pub async fn run(&self, input: OrchestratorInput) -> Result<OrchestratorOutput> {
+        self.orchestrate(Vec::new(), input).await
+    }
+
     /// Set max retries per agent
     pub fn with_max_retries(mut self, max_retries: usize) -> Self {
         self.max_retries = max_retries;
         self
     }
 
+    /// Set the maximum number of agents executing at once
+    ///
+    /// A limit of 0 is treated as 1.
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.parallel_limit = limit.max(1);
+        self
+    }
+
     /// Set parallel execution limit
-    pub fn with_parallel_limit(mut self, limit: usize) -> Self {
-        self.parallel_limit = limit;
+    ///
+    /// Same as [`with_concurrency`](Self::with_concurrency).
+    pub fn with_parallel_limit(self, limit: usize) -> Self {
+        self.with_concurrency(limit)
+    }
+
+    /// Cancel agents still executing `timeout` after they started
+    ///
+    /// The timeout covers retries but not the wait for a concurrency slot.
+    pub fn with_agent_timeout(mut self, timeout: Duration) -> Self {
+        self.agent_timeout = Some(timeout);
+        self
+    }
+
+    /// Retry agents whose output has a confidence below `min_confidence`
+    ///
+    /// See
+    /// [`ExecutionConfig::min_confidence`](crate::orchestration::ExecutionConfig::min_confidence).
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = Some(min_confidence);
+        self
+    }
+
+    /// Set how the result is assembled from the agents' outputs
+    ///
+    /// Defaults to [`AggregationStrategy::All`].
+    pub fn with_aggregation(mut self, aggregation: AggregationStrategy) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
+    /// Share `shared` with the agents instead of a fresh context per run
+    ///
+    /// Lets callers seed values before a run and keep them across runs.
+    pub fn with_shared_context(mut self, shared: SharedContext) -> Self {
+        self.shared = Some(shared);
         self
     }
 
@@ -71,8 +172,9 @@ impl ParallelOrchestrator {
         agents: Vec<Box<dyn Agent>>,
         input: AgentInput,
         ctx: &ExecutionContext,
-    ) -> Result<Vec<AgentOutput>> {
-        let semaphore = Arc::new(Semaphore::new(self.parallel_limit));
+    ) -> Vec<AgentRun> {
+        let semaphore = Arc::new(Semaphore::new(ctx.config().parallel_limit));
+        let agent_timeout = ctx.config().agent_timeout;
         let agents_count = agents.len();
         let mut futures = Vec::new();
 
@@ -80,7 +182,7 @@ impl ParallelOrchestrator {
             let agent_ref = agent.as_ref();
             let input_clone = input.clone();
             let semaphore_clone = semaphore.clone();
-            let ctx_clone = ctx.clone();
+            let base = &self.base;
             let base_name = self.base.name().to_string();
 
             let future = async move {
@@ -90,7 +192,7 @@ impl ParallelOrchestrator {
                 // Create execution record
                 let mut exec_record = AgentExecution::new(agent_ref.name(), input_clone.clone());
 
-                if ctx_clone.is_logging_enabled() {
+                if ctx.is_logging_enabled() {
                     debug!(
                         orchestrator = %base_name,
                         agent = %agent_ref.name(),
@@ -100,89 +202,90 @@ impl ParallelOrchestrator {
                     );
                 }
 
-                // Execute agent with retry
-                let output =
-                    Self::execute_agent_with_retry_static(agent_ref, input_clone, self.max_retries)
-                        .await;
-
-                let success = output.is_successful();
+                ctx.events().emit(OrchestrationEvent::StepStarted {
+                    agent: agent_ref.name().to_string(),
+                    index,
+                });
+                let step_started = Instant::now();
+
+                // Execute agent with retry, cancelling it on timeout
+                let (status, output) = ctx
+                    .run_with_shared(agent_ref.name(), input_clone, |input| async move {
+                        let execution =
+                            base.execute_agent_in_context(agent_ref, input, self.max_retries, ctx);
+                        match agent_timeout {
+                            Some(timeout) => match tokio::time::timeout(timeout, execution).await {
+                                Ok(output) => (Self::status_of(&output), output),
+                                Err(_) => {
+                                    let error = format!(
+                                        "Agent {} timed out after {:?}",
+                                        agent_ref.name(),
+                                        timeout
+                                    );
+                                    (
+                                        AgentStatus::TimedOut,
+                                        AgentOutput::new(error).with_confidence(0.0),
+                                    )
+                                },
+                            },
+                            None => {
+                                let output = execution.await;
+                                (Self::status_of(&output), output)
+                            },
+                        }
+                    })
+                    .await;
+                if let (AgentStatus::TimedOut, Some(timeout)) = (status, agent_timeout) {
+                    exec_record.time_out(timeout);
+                }
 
-                if success {
-                    exec_record.succeed(output.clone());
-                } else {
-                    exec_record.fail(output.content.clone());
+                match status {
+                    AgentStatus::Completed => {
+                        ctx.events().emit(OrchestrationEvent::StepCompleted {
+                            agent: agent_ref.name().to_string(),
+                            index,
+                            duration: step_started.elapsed(),
+                            confidence: output.confidence,
+                        });
+                        exec_record.succeed(output.clone());
+                    },
+                    AgentStatus::TimedOut | AgentStatus::Failed => {
+                        ctx.events().emit(OrchestrationEvent::StepFailed {
+                            agent: agent_ref.name().to_string(),
+                            index,
+                            error: output.content.clone(),
+                        });
+                        if status == AgentStatus::Failed {
+                            exec_record.fail(output.content.clone());
+                        }
+                    },
                 }
 
                 // Add to trace if enabled
-                if ctx_clone.is_tracing_enabled() {
-                    ctx_clone.add_execution(exec_record).await;
+                if ctx.is_tracing_enabled() {
+                    ctx.add_execution(exec_record).await;
                 }
 
-                (agent_ref.name().to_string(), output, success)
+                AgentRun {
+                    agent: agent_ref.name().to_string(),
+                    status,
+                    output,
+                }
             };
 
             futures.push(future);
         }
 
-        // Wait for all agents to complete
-        let results = join_all(futures).await;
-
-        // Check for failures and collect outputs
-        let mut outputs = Vec::new();
-        let mut failed_agents = Vec::new();
-
-        for (agent_name, output, success) in results {
-            if success {
-                outputs.push(output);
-            } else {
-                failed_agents.push(agent_name);
-            }
-        }
-
-        // If any agents failed, return error
-        if !failed_agents.is_empty() {
-            return Err(
-                crate::orchestration::errors::OrchestrationError::agent_failure(
-                    failed_agents.join(", "),
-                    "Execution failed",
-                ),
-            );
-        }
-
-        Ok(outputs)
+        // Wait for all agents to complete; results keep the agents' order
+        join_all(futures).await
     }
 
-    // Static version for use in async block
-    async fn execute_agent_with_retry_static(
-        agent: &dyn Agent,
-        input: AgentInput,
-        max_retries: usize,
-    ) -> AgentOutput {
-        let mut last_error = None;
-
-        for attempt in 0..=max_retries {
-            match agent.execute(input.clone()).await {
-                Ok(output) => return output,
-                Err(e) => {
-                    last_error = Some(e.to_string());
-                    if attempt < max_retries {
-                        tokio::time::sleep(std::time::Duration::from_millis(
-                            RETRY_BASE_DELAY_MS * 2_u64.pow(attempt as u32),
-                        ))
-                        .await;
-                    }
-                },
-            }
+    fn status_of(output: &AgentOutput) -> AgentStatus {
+        if output.is_successful() {
+            AgentStatus::Completed
+        } else {
+            AgentStatus::Failed
         }
-
-        // All retries failed
-        AgentOutput::new(format!(
-            "Agent {} failed after {} retries: {}",
-            agent.name(),
-            max_retries,
-            last_error.unwrap_or_else(|| "Unknown error".to_string())
-        ))
-        .with_confidence(0.0)
     }
 }
 
@@ -207,6 +310,17 @@ impl Orchestrator for ParallelOrchestrator {
         agents: Vec<Box<dyn Agent>>,
         input: OrchestratorInput,
     ) -> Result<OrchestratorOutput> {
+        self.orchestrate_with_sink(agents, input, EventSink::disabled())
+            .await
+    }
+
+    async fn orchestrate_with_sink(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+        events: EventSink,
+    ) -> Result<OrchestratorOutput> {
+        let agents = with_own_agents(&self.agents, agents);
         if agents.is_empty() {
             return Err(
                 crate::orchestration::errors::OrchestrationError::invalid_config(
@@ -215,53 +329,139 @@ impl Orchestrator for ParallelOrchestrator {
             );
         }
 
+        let run_started = Instant::now();
+        events.emit(OrchestrationEvent::RunStarted {
+            orchestrator: self.base.name().to_string(),
+            agent_count: agents.len(),
+        });
+
         // Create execution context
         let mut config = crate::orchestration::context::ExecutionConfig::new();
         config.parallel_limit = self.parallel_limit;
-        let ctx = ExecutionContext::new(config);
+        config.agent_timeout = self.agent_timeout;
+        config.min_confidence = self.min_confidence;
+        config.aggregation = self.aggregation;
+        let ctx = ExecutionContext::new(config)
+            .with_events(events.clone())
+            .with_shared(self.shared.clone().unwrap_or_default());
 
         let agent_input = self.base.input_to_agent_input(&input);
 
         // Execute agents in parallel
-        let outputs = match self.execute_parallel(agents, agent_input, &ctx).await {
-            Ok(outputs) => outputs,
-            Err(e) => {
-                ctx.complete_trace().await;
-                let trace = ctx.get_trace().await;
-                return Ok(OrchestratorOutput::failure(e.to_string(), trace));
-            },
+        let runs = self.execute_parallel(agents, agent_input, &ctx).await;
+        let shared_context = ctx.shared().snapshot().await;
+
+        let outcomes: Vec<AgentOutcome> = runs
+            .iter()
+            .map(|run| AgentOutcome {
+                agent: run.agent.clone(),
+                status: run.status,
+            })
+            .collect();
+        let failed_agents: Vec<&str> = runs
+            .iter()
+            .filter(|run| run.status == AgentStatus::Failed)
+            .map(|run| run.agent.as_str())
+            .collect();
+
+        // Failed agents fail the run; timed-out agents only if none completed
+        let error = if !failed_agents.is_empty() {
+            Some(OrchestrationError::agent_failure(failed_agents.join(", "), "Execution failed"))
+        } else if runs.iter().all(|run| run.status == AgentStatus::TimedOut) {
+            Some(OrchestrationError::timeout("All agents timed out"))
+        } else {
+            None
         };
+        if let Some(e) = error {
+            ctx.complete_trace().await;
+            let trace = ctx.get_trace().await;
+            events.emit(OrchestrationEvent::RunCompleted {
+                success: false,
+                duration: run_started.elapsed(),
+            });
+            return Ok(OrchestratorOutput::failure(e.to_string(), trace)
+                .with_agent_outcomes(outcomes)
+                .with_shared_context(shared_context));
+        }
 
-        // Complete trace
+        let completed: Vec<AgentRun> = runs
+            .into_iter()
+            .filter(|run| run.status == AgentStatus::Completed)
+            .collect();
+
+        // Aggregate results
+        let (aggregated, record) = Self::aggregate_results(ctx.config().aggregation, &completed);
+        if ctx.is_tracing_enabled() {
+            ctx.set_aggregation(record).await;
+        }
         ctx.complete_trace().await;
         let trace = ctx.get_trace().await;
+        let outputs: Vec<AgentOutput> = completed.into_iter().map(|run| run.output).collect();
 
-        // Aggregate results
-        let aggregated = self.aggregate_results(&outputs);
+        events.emit(OrchestrationEvent::RunCompleted {
+            success: true,
+            duration: run_started.elapsed(),
+        });
 
-        Ok(OrchestratorOutput::success(aggregated, outputs, trace))
+        Ok(OrchestratorOutput::success(aggregated, outputs, trace)
+            .with_agent_outcomes(outcomes)
+            .with_shared_context(shared_context))
     }
 }
 
 impl ParallelOrchestrator {
-    /// Aggregate multiple agent outputs into a single result
-    fn aggregate_results(&self, outputs: &[AgentOutput]) -> String {
-        if outputs.is_empty() {
-            return String::new();
-        }
-
-        if outputs.len() == 1 {
-            return outputs[0].content.clone();
-        }
-
-        // Combine all outputs
-        let mut result = String::from("Parallel execution results:\n\n");
+    /// Aggregate the outputs of the completed agents into a single result
+    fn aggregate_results(
+        strategy: AggregationStrategy,
+        runs: &[AgentRun],
+    ) -> (String, AggregationRecord) {
+        let confidences = runs
+            .iter()
+            .map(|run| AgentConfidence {
+                agent: run.agent.clone(),
+                confidence: run.output.confidence,
+            })
+            .collect();
+
+        // Most confident first; the sort is stable, so ties keep agent order
+        let mut ranked: Vec<&AgentRun> = runs.iter().collect();
+        ranked.sort_by(|a, b| b.output.confidence.total_cmp(&a.output.confidence));
+
+        let selected: Vec<&AgentRun> = match strategy {
+            AggregationStrategy::HighestConfidence => ranked.into_iter().take(1).collect(),
+            AggregationStrategy::WeightedMerge => ranked,
+            AggregationStrategy::All => runs.iter().collect(),
+        };
 
-        for (index, output) in outputs.iter().enumerate() {
-            result.push_str(&format!("{}. {}\n", index + 1, output.content));
-        }
+        let result = match selected.as_slice() {
+            [] => String::new(),
+            [run] => run.output.content.clone(),
+            _ if strategy == AggregationStrategy::WeightedMerge => selected
+                .iter()
+                .map(|run| {
+                    format!(
+                        "## {} (confidence {:.2})\n\n{}",
+                        run.agent, run.output.confidence, run.output.content
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            _ => {
+                // Combine all outputs
+                let mut result = String::from("Parallel execution results:\n\n");
+                for (index, run) in selected.iter().enumerate() {
+                    result.push_str(&format!("{}. {}\n", index + 1, run.output.content));
+                }
+                result
+            },
+        };
 
-        result
+        let record = AggregationRecord {
+            strategy,
+            confidences,
+            selected: selected.iter().map(|run| run.agent.clone()).collect(),
+        };
+        (result, record)
     }
 }
 
@@ -271,6 +471,56 @@ mod tests {
     use crate::orchestration::agent::SimpleAgent;
     use std::sync::atomic::{AtomicUsize, Ordering};
 
+    /// Agent that sleeps before echoing its name, tracking concurrent runs
+    struct SleepyAgent {
+        name: String,
+        delay: Duration,
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+    }
+
+    impl SleepyAgent {
+        fn boxed(name: &str, delay_ms: u64) -> Box<dyn Agent> {
+            Self::tracked(name, delay_ms, &Arc::default(), &Arc::default())
+        }
+
+        fn tracked(
+            name: &str,
+            delay_ms: u64,
+            running: &Arc<AtomicUsize>,
+            max_running: &Arc<AtomicUsize>,
+        ) -> Box<dyn Agent> {
+            Box::new(Self {
+                name: name.to_string(),
+                delay: Duration::from_millis(delay_ms),
+                running: Arc::clone(running),
+                max_running: Arc::clone(max_running),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Agent for SleepyAgent {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn description(&self) -> &str {
+            "Sleeps, then answers"
+        }
+
+        async fn execute(
+            &self,
+            _input: AgentInput,
+        ) -> crate::orchestration::agent::Result<AgentOutput> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(AgentOutput::new(self.name.clone()))
+        }
+    }
+
     #[tokio::test]
     async fn test_parallel_orchestrator() {
         let orchestrator = ParallelOrchestrator::new();
@@ -453,4 +703,376 @@ mod tests {
         let max_val = max_concurrent.load(Ordering::SeqCst);
         assert!(max_val <= 2, "Expected max 2 concurrent, got {}", max_val);
     }
+
+    #[tokio::test]
+    async fn test_parallel_orchestrator_event_sequence() {
+        use futures::StreamExt;
+
+        let orchestrator = ParallelOrchestrator::new().with_max_retries(0);
+
+        let mut agents: Vec<Box<dyn Agent>> = Vec::new();
+        for i in 0..3 {
+            agents.push(Box::new(SimpleAgent::new(
+                format!("Agent{}", i),
+                format!("Agent {}", i),
+                |input| Ok(AgentOutput::new(input.content)),
+            )));
+        }
+        agents.push(Box::new(SimpleAgent::new("Failing", "Always fails", |_input| {
+            Err(anyhow::anyhow!("boom").into())
+        })));
+
+        let (stream, run) =
+            orchestrator.orchestrate_with_events(agents, OrchestratorInput::new("Test"));
+        let (events, output) = tokio::join!(stream.collect::<Vec<_>>(), run);
+        assert!(!output.unwrap().is_successful());
+
+        assert!(matches!(
+            events.first(),
+            Some(OrchestrationEvent::RunStarted { agent_count: 4, .. })
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(OrchestrationEvent::RunCompleted { success: false, .. })
+        ));
+
+        // Per agent: started strictly before its terminal event, exactly once each
+        for i in 0..4 {
+            let started = events
+                .iter()
+                .position(|e| matches!(e, OrchestrationEvent::StepStarted { index, .. } if *index == i))
+                .expect("missing StepStarted");
+            let finished: Vec<usize> = events
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| {
+                    matches!(e, OrchestrationEvent::StepCompleted { index, .. }
+                        | OrchestrationEvent::StepFailed { index, .. } if *index == i)
+                })
+                .map(|(pos, _)| pos)
+                .collect();
+            assert_eq!(finished.len(), 1);
+            assert!(started < finished[0]);
+        }
+
+        assert!(events.iter().any(|e| matches!(
+            e,
+            OrchestrationEvent::StepFailed { agent, .. } if agent == "Failing"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_with_async_agents() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let agents: Vec<Box<dyn Agent>> = (0..6)
+            .map(|i| SleepyAgent::tracked(&format!("Agent{}", i), 20, &running, &max_running))
+            .collect();
+
+        let orchestrator = ParallelOrchestrator::new().with_concurrency(2);
+        let output = orchestrator
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_results_keep_agent_order() {
+        // The first agent finishes last
+        let agents: Vec<Box<dyn Agent>> = vec![
+            SleepyAgent::boxed("Slow", 60),
+            SleepyAgent::boxed("Medium", 30),
+            SleepyAgent::boxed("Fast", 1),
+        ];
+
+        let output = ParallelOrchestrator::new()
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        let contents: Vec<&str> = output
+            .agent_outputs
+            .iter()
+            .map(|output| output.content.as_str())
+            .collect();
+        assert_eq!(contents, ["Slow", "Medium", "Fast"]);
+        assert_eq!(
+            output.agents_with_status(AgentStatus::Completed),
+            ["Slow", "Medium", "Fast"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_timeout_is_recorded_without_failing_run() {
+        let agents: Vec<Box<dyn Agent>> = vec![
+            SleepyAgent::boxed("First", 1),
+            SleepyAgent::boxed("Stuck", 10_000),
+            SleepyAgent::boxed("Last", 20),
+        ];
+
+        let orchestrator =
+            ParallelOrchestrator::new().with_agent_timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let output = orchestrator
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        assert!(output.is_successful());
+        assert_eq!(
+            output.agent_outcomes,
+            vec![
+                AgentOutcome {
+                    agent: "First".to_string(),
+                    status: AgentStatus::Completed,
+                },
+                AgentOutcome {
+                    agent: "Stuck".to_string(),
+                    status: AgentStatus::TimedOut,
+                },
+                AgentOutcome {
+                    agent: "Last".to_string(),
+                    status: AgentStatus::Completed,
+                },
+            ]
+        );
+        assert_eq!(output.agent_outputs.len(), 2);
+        assert!(!output.result.contains("Stuck"));
+
+        let stuck = output
+            .execution_trace
+            .agent_executions
+            .iter()
+            .find(|execution| execution.agent_name == "Stuck")
+            .unwrap();
+        assert!(stuck.timed_out);
+        assert!(!stuck.success);
+        assert!(stuck.error.as_deref().unwrap().contains("Timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_all_agents_timing_out_fails_run() {
+        let agents: Vec<Box<dyn Agent>> = vec![SleepyAgent::boxed("Stuck", 10_000)];
+
+        let output = ParallelOrchestrator::new()
+            .with_agent_timeout(Duration::from_millis(50))
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert!(!output.is_successful());
+        assert_eq!(output.agents_with_status(AgentStatus::TimedOut), ["Stuck"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_run_reports_outcomes() {
+        let agents: Vec<Box<dyn Agent>> = vec![
+            SleepyAgent::boxed("Fine", 1),
+            Box::new(SimpleAgent::new("Failing", "Always fails", |_input| {
+                Err(anyhow::anyhow!("boom").into())
+            })),
+        ];
+
+        let output = ParallelOrchestrator::new()
+            .with_max_retries(0)
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert!(!output.is_successful());
+        assert!(output.error.as_deref().unwrap().contains("Failing"));
+        assert_eq!(output.agents_with_status(AgentStatus::Completed), ["Fine"]);
+        assert_eq!(output.agents_with_status(AgentStatus::Failed), ["Failing"]);
+    }
+
+    /// Agent that writes one shared value and reports the shared values it was given
+    struct SharedWriter {
+        name: &'static str,
+        delay_ms: u64,
+        key: &'static str,
+        value: serde_json::Value,
+    }
+
+    #[async_trait::async_trait]
+    impl Agent for SharedWriter {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Writes to the shared context"
+        }
+
+        async fn execute(&self, input: AgentInput) -> crate::orchestration::agent::Result<AgentOutput> {
+            Ok(AgentOutput::new(input.content))
+        }
+
+        async fn execute_with_context(
+            &self,
+            input: AgentInput,
+            ctx: &ExecutionContext,
+        ) -> crate::orchestration::agent::Result<AgentOutput> {
+            tokio::time::sleep(std::time::Duration::from_millis(self.delay_ms)).await;
+            ctx.shared()
+                .set(self.name, self.key, self.value.clone())
+                .await;
+            let seen = input
+                .context
+                .get("shared_context")
+                .map(|shared| shared.to_string())
+                .unwrap_or_default();
+            Ok(AgentOutput::new(seen))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parallel_writes_last_writer_wins() {
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(SharedWriter {
+                name: "Fast",
+                delay_ms: 10,
+                key: "plan",
+                value: serde_json::json!("fast"),
+            }),
+            Box::new(SharedWriter {
+                name: "Slow",
+                delay_ms: 60,
+                key: "plan",
+                value: serde_json::json!("slow"),
+            }),
+        ];
+
+        let output = ParallelOrchestrator::new()
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        assert_eq!(
+            output.shared_context.get("plan"),
+            Some(&serde_json::json!("slow"))
+        );
+
+        let changes = &output.execution_trace.context_changes;
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].agent, "Fast");
+        assert_eq!(changes[0].conflict_with, None);
+        assert_eq!(changes[1].agent, "Slow");
+        assert_eq!(changes[1].conflict_with.as_deref(), Some("Fast"));
+    }
+
+    /// Agent answering with its name at a fixed confidence
+    fn confident(name: &'static str, confidence: f64) -> Box<dyn Agent> {
+        Box::new(SimpleAgent::new(name, "Fixed confidence", move |_input| {
+            Ok(AgentOutput::new(format!("{} says", name)).with_confidence(confidence))
+        }))
+    }
+
+    fn confident_agents() -> Vec<Box<dyn Agent>> {
+        vec![confident("Low", 0.6), confident("High", 0.9), confident("Mid", 0.75)]
+    }
+
+    #[tokio::test]
+    async fn test_highest_confidence_aggregation() {
+        let output = ParallelOrchestrator::new()
+            .with_aggregation(AggregationStrategy::HighestConfidence)
+            .orchestrate(confident_agents(), OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        assert_eq!(output.result, "High says");
+        assert_eq!(output.agent_outputs.len(), 3);
+
+        let record = output.execution_trace.aggregation.unwrap();
+        assert_eq!(record.strategy, AggregationStrategy::HighestConfidence);
+        assert_eq!(record.selected, ["High"]);
+        let confidences: Vec<_> = record
+            .confidences
+            .iter()
+            .map(|c| (c.agent.as_str(), c.confidence))
+            .collect();
+        assert_eq!(confidences, [("Low", 0.6), ("High", 0.9), ("Mid", 0.75)]);
+    }
+
+    #[tokio::test]
+    async fn test_highest_confidence_tie_keeps_agent_order() {
+        let agents = vec![confident("First", 0.8), confident("Second", 0.8)];
+        let output = ParallelOrchestrator::new()
+            .with_aggregation(AggregationStrategy::HighestConfidence)
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert_eq!(output.result, "First says");
+    }
+
+    #[tokio::test]
+    async fn test_weighted_merge_orders_by_confidence() {
+        let output = ParallelOrchestrator::new()
+            .with_aggregation(AggregationStrategy::WeightedMerge)
+            .orchestrate(confident_agents(), OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.result,
+            "## High (confidence 0.90)\n\nHigh says\n\n\
+             ## Mid (confidence 0.75)\n\nMid says\n\n\
+             ## Low (confidence 0.60)\n\nLow says"
+        );
+        let record = output.execution_trace.aggregation.unwrap();
+        assert_eq!(record.strategy, AggregationStrategy::WeightedMerge);
+        assert_eq!(record.selected, ["High", "Mid", "Low"]);
+    }
+
+    #[tokio::test]
+    async fn test_all_aggregation_is_the_default() {
+        let output = ParallelOrchestrator::new()
+            .orchestrate(confident_agents(), OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.result,
+            "Parallel execution results:\n\n1. Low says\n2. High says\n3. Mid says\n"
+        );
+        let record = output.execution_trace.aggregation.unwrap();
+        assert_eq!(record.strategy, AggregationStrategy::All);
+        assert_eq!(record.selected, ["Low", "High", "Mid"]);
+    }
+
+    #[tokio::test]
+    async fn test_min_confidence_retries_agents_below_threshold() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let improving: Box<dyn Agent> =
+            Box::new(SimpleAgent::new("Improving", "Better with feedback", move |input| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let confidence = if input.content.contains("Feedback") { 0.95 } else { 0.55 };
+                Ok(AgentOutput::new("answer").with_confidence(confidence))
+            }));
+        let agents = vec![improving, confident("Steady", 0.85)];
+
+        let output = ParallelOrchestrator::new()
+            .with_min_confidence(0.8)
+            .with_aggregation(AggregationStrategy::HighestConfidence)
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(output.result, "answer");
+        let trace = output.execution_trace;
+        assert_eq!(trace.retries.len(), 1);
+        assert_eq!(trace.retries[0].agent_name, "Improving");
+        assert_eq!(trace.retries[0].confidence, Some(0.55));
+        let record = trace.aggregation.unwrap();
+        assert_eq!(record.confidences[0].confidence, 0.95);
+        assert_eq!(record.selected, ["Improving"]);
+    }
 }
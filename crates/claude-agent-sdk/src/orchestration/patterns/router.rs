@@ -0,0 +1,450 @@
+//! # Router Orchestration Pattern
+//!
+//! A router picks the one agent best suited to the input, and only that agent
+//! executes.
+//!
+//! ```text
+//!                   ┌→ Agent A
+//! Input → Router ───┼→ Agent B → Output
+//!                   └→ Agent C
+//! ```
+//!
+//! Use cases:
+//! - Dispatching requests to specialists
+//! - Triage before expensive processing
+//! - Cost control by avoiding unnecessary agents
+//!
+//! The routing decision is recorded in [`ExecutionTrace::routing`]. When the
+//! router finds no match, the fallback agent runs if one is set; otherwise
+//! orchestration fails with [`OrchestrationError::NoAgentMatched`].
+
+use crate::orchestration::{
+    Result,
+    agent::{Agent, AgentInput},
+    context::{AgentExecution, ExecutionContext, ExecutionTrace, RoutingDecision},
+    errors::OrchestrationError,
+    events::{EventSink, OrchestrationEvent},
+    orchestrator::{BaseOrchestrator, Orchestrator, OrchestratorInput, OrchestratorOutput},
+    registry::AgentMetadata,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::debug;
+
+/// Default maximum retries for agent execution
+const DEFAULT_MAX_RETRIES: usize = 3;
+
+/// Chooses the agent handling an input
+pub trait Router: Send + Sync {
+    /// Index into `agents` of the agent that should handle `input`
+    ///
+    /// Return [`OrchestrationError::NoAgentMatched`] when no agent fits, to
+    /// let the orchestrator use its fallback agent.
+    fn route(&self, input: &OrchestratorInput, agents: &[AgentMetadata]) -> Result<usize>;
+}
+
+/// Router scoring agents by the keywords found in the input
+///
+/// An agent's keywords are the ones set with
+/// [`with_keywords`](Self::with_keywords), or else its name, category, tags
+/// and skills. Keywords match whole words of the input, ignoring case. The
+/// agent with the most matching keywords wins; ties go to the earlier agent.
+#[derive(Debug, Clone, Default)]
+pub struct KeywordRouter {
+    keywords: HashMap<String, Vec<String>>,
+}
+
+impl KeywordRouter {
+    /// Create a router using the agents' metadata as keywords
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route inputs containing any of `keywords` to the agent named `agent`
+    pub fn with_keywords<I, S>(mut self, agent: impl Into<String>, keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keywords.insert(
+            agent.into(),
+            keywords
+                .into_iter()
+                .map(|k| k.into().to_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    fn keywords_of(&self, agent: &AgentMetadata) -> Vec<String> {
+        if let Some(keywords) = self.keywords.get(&agent.name) {
+            return keywords.clone();
+        }
+        std::iter::once(&agent.name)
+            .chain(std::iter::once(&agent.category))
+            .chain(&agent.tags)
+            .chain(&agent.skills)
+            .filter(|keyword| !keyword.is_empty())
+            .map(|keyword| keyword.to_lowercase())
+            .collect()
+    }
+}
+
+impl Router for KeywordRouter {
+    fn route(&self, input: &OrchestratorInput, agents: &[AgentMetadata]) -> Result<usize> {
+        let content = input.content.to_lowercase();
+        let words: Vec<&str> = content
+            .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        let mut best: Option<(usize, usize)> = None;
+        for (index, agent) in agents.iter().enumerate() {
+            let score = self
+                .keywords_of(agent)
+                .iter()
+                .filter(|keyword| words.contains(&keyword.as_str()))
+                .count();
+            if score > 0 && best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((index, score));
+            }
+        }
+        best.map(|(index, _)| index)
+            .ok_or(OrchestrationError::NoAgentMatched)
+    }
+}
+
+/// Router orchestrator that executes the one agent chosen by a [`Router`]
+pub struct RouterOrchestrator {
+    base: BaseOrchestrator,
+    router: Box<dyn Router>,
+    fallback: Option<usize>,
+    metadata: HashMap<String, AgentMetadata>,
+    max_retries: usize,
+    min_confidence: Option<f64>,
+}
+
+impl RouterOrchestrator {
+    /// Create a router orchestrator using `router`
+    pub fn new(router: Box<dyn Router>) -> Self {
+        Self {
+            base: BaseOrchestrator::new(
+                "RouterOrchestrator",
+                "Routes the input to the single most suitable agent",
+            ),
+            router,
+            fallback: None,
+            metadata: HashMap::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            min_confidence: None,
+        }
+    }
+
+    /// Create a router orchestrator using a [`KeywordRouter`]
+    pub fn keyword() -> Self {
+        Self::new(Box::new(KeywordRouter::new()))
+    }
+
+    /// Run the agent at `index` when the router finds no match
+    pub fn with_fallback(mut self, index: usize) -> Self {
+        self.fallback = Some(index);
+        self
+    }
+
+    /// Describe an agent to the router
+    ///
+    /// Agents without metadata are described by their name and description.
+    /// Metadata is matched to agents by name.
+    pub fn with_agent_metadata(mut self, metadata: AgentMetadata) -> Self {
+        self.metadata.insert(metadata.name.clone(), metadata);
+        self
+    }
+
+    /// Set max retries for the chosen agent
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Retry the chosen agent while its output has a confidence below `min_confidence`
+    ///
+    /// See
+    /// [`ExecutionConfig::min_confidence`](crate::orchestration::ExecutionConfig::min_confidence).
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = Some(min_confidence);
+        self
+    }
+
+    /// Metadata handed to the router, in agent order
+    fn describe_agents(&self, agents: &[Box<dyn Agent>]) -> Vec<AgentMetadata> {
+        agents
+            .iter()
+            .map(|agent| {
+                self.metadata.get(agent.name()).cloned().unwrap_or_else(|| {
+                    AgentMetadata::new(agent.name(), agent.name(), agent.description(), "")
+                })
+            })
+            .collect()
+    }
+
+    /// Ask the router for an agent, falling back if it finds none
+    fn choose(
+        &self,
+        input: &OrchestratorInput,
+        agents: &[Box<dyn Agent>],
+    ) -> Result<RoutingDecision> {
+        let (index, fallback) = match self.router.route(input, &self.describe_agents(agents)) {
+            Ok(index) => (index, false),
+            Err(OrchestrationError::NoAgentMatched) => (
+                self.fallback.ok_or(OrchestrationError::NoAgentMatched)?,
+                true,
+            ),
+            Err(e) => return Err(e),
+        };
+
+        let agent = agents.get(index).ok_or_else(|| {
+            OrchestrationError::invalid_config(format!(
+                "Agent index {} is out of range for {} agents",
+                index,
+                agents.len()
+            ))
+        })?;
+        Ok(RoutingDecision {
+            agent: agent.name().to_string(),
+            index,
+            fallback,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Orchestrator for RouterOrchestrator {
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn description(&self) -> &str {
+        self.base.description()
+    }
+
+    async fn orchestrate(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+    ) -> Result<OrchestratorOutput> {
+        self.orchestrate_with_sink(agents, input, EventSink::disabled())
+            .await
+    }
+
+    async fn orchestrate_with_sink(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+        events: EventSink,
+    ) -> Result<OrchestratorOutput> {
+        if agents.is_empty() {
+            return Err(OrchestrationError::invalid_config(
+                "At least one agent is required",
+            ));
+        }
+
+        let decision = self.choose(&input, &agents)?;
+
+        let run_started = Instant::now();
+        events.emit(OrchestrationEvent::RunStarted {
+            orchestrator: self.base.name().to_string(),
+            agent_count: agents.len(),
+        });
+
+        let mut config = crate::orchestration::context::ExecutionConfig::new();
+        config.min_confidence = self.min_confidence;
+        let ctx = ExecutionContext::new(config).with_events(events.clone());
+
+        let agent = agents[decision.index].as_ref();
+        let agent_input: AgentInput = self.base.input_to_agent_input(&input);
+        let mut exec_record = AgentExecution::new(agent.name(), agent_input.clone());
+
+        if ctx.is_logging_enabled() {
+            debug!(
+                orchestrator = %self.base.name(),
+                agent = %agent.name(),
+                fallback = decision.fallback,
+                "Executing routed agent"
+            );
+        }
+
+        ctx.events().emit(OrchestrationEvent::StepStarted {
+            agent: agent.name().to_string(),
+            index: decision.index,
+        });
+        let step_started = Instant::now();
+
+        let output = self
+            .base
+            .execute_agent_in_context(agent, agent_input, self.max_retries, &ctx)
+            .await;
+        let success = output.is_successful();
+
+        if success {
+            ctx.events().emit(OrchestrationEvent::StepCompleted {
+                agent: agent.name().to_string(),
+                index: decision.index,
+                duration: step_started.elapsed(),
+                confidence: output.confidence,
+            });
+            exec_record.succeed(output.clone());
+        } else {
+            ctx.events().emit(OrchestrationEvent::StepFailed {
+                agent: agent.name().to_string(),
+                index: decision.index,
+                error: output.content.clone(),
+            });
+            exec_record.fail(output.content.clone());
+        }
+
+        if ctx.is_tracing_enabled() {
+            ctx.add_execution(exec_record).await;
+        }
+        ctx.complete_trace().await;
+        let trace = ExecutionTrace {
+            routing: Some(decision),
+            ..ctx.get_trace().await
+        };
+
+        events.emit(OrchestrationEvent::RunCompleted {
+            success,
+            duration: run_started.elapsed(),
+        });
+
+        if !success {
+            let error = OrchestrationError::agent_failure(agent.name(), output.content);
+            return Ok(OrchestratorOutput::failure(error.to_string(), trace));
+        }
+        Ok(OrchestratorOutput::success(
+            output.content.clone(),
+            vec![output],
+            trace,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestration::agent::{AgentOutput, SimpleAgent};
+
+    fn agents() -> Vec<Box<dyn Agent>> {
+        ["coder", "researcher"]
+            .into_iter()
+            .map(|name| -> Box<dyn Agent> {
+                Box::new(SimpleAgent::new(
+                    name,
+                    format!("The {}", name),
+                    move |input| Ok(AgentOutput::new(format!("{}: {}", name, input.content))),
+                ))
+            })
+            .collect()
+    }
+
+    fn metadata(name: &str, category: &str, tags: &[&str]) -> AgentMetadata {
+        let mut metadata = AgentMetadata::new(name, name, "", category);
+        metadata.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        metadata
+    }
+
+    #[test]
+    fn test_keyword_router_scores_metadata() {
+        let agents = vec![
+            metadata("coder", "development", &["rust", "bug", "refactor"]),
+            metadata("researcher", "research", &["papers", "survey"]),
+        ];
+        let router = KeywordRouter::new();
+
+        let route = |content: &str| router.route(&OrchestratorInput::new(content), &agents);
+        assert_eq!(route("Fix the Rust bug in main.rs").unwrap(), 0);
+        assert_eq!(route("Survey recent papers on RAG").unwrap(), 1);
+        // "research" is a keyword, "researching" is not
+        assert!(matches!(
+            route("Go researching"),
+            Err(OrchestrationError::NoAgentMatched)
+        ));
+    }
+
+    #[test]
+    fn test_keyword_router_explicit_keywords_replace_metadata() {
+        let agents = vec![
+            metadata("coder", "", &["rust"]),
+            metadata("researcher", "", &[]),
+        ];
+        let router = KeywordRouter::new().with_keywords("coder", ["code", "compile"]);
+
+        let route = |content: &str| router.route(&OrchestratorInput::new(content), &agents);
+        assert_eq!(route("compile this CODE").unwrap(), 0);
+        assert!(route("rust").is_err());
+        assert_eq!(route("ask the researcher").unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_router_runs_only_the_chosen_agent() {
+        let orchestrator = RouterOrchestrator::new(Box::new(
+            KeywordRouter::new().with_keywords("researcher", ["find", "sources"]),
+        ));
+
+        let output = orchestrator
+            .orchestrate(agents(), OrchestratorInput::new("Find sources on WASM"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        assert_eq!(output.result, "researcher: Find sources on WASM");
+        assert_eq!(output.agent_outputs.len(), 1);
+        assert_eq!(output.execution_trace.agent_executions.len(), 1);
+        assert_eq!(
+            output.execution_trace.routing,
+            Some(RoutingDecision {
+                agent: "researcher".to_string(),
+                index: 1,
+                fallback: false,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_router_fallback_and_no_match() {
+        let input = || OrchestratorInput::new("Something unrelated");
+
+        let output = RouterOrchestrator::keyword()
+            .with_fallback(0)
+            .orchestrate(agents(), input())
+            .await
+            .unwrap();
+        assert_eq!(output.result, "coder: Something unrelated");
+        assert!(output.execution_trace.routing.unwrap().fallback);
+
+        let result = RouterOrchestrator::keyword()
+            .orchestrate(agents(), input())
+            .await;
+        assert!(matches!(result, Err(OrchestrationError::NoAgentMatched)));
+
+        let result = RouterOrchestrator::keyword()
+            .with_fallback(5)
+            .orchestrate(agents(), input())
+            .await;
+        assert!(matches!(result, Err(OrchestrationError::InvalidConfig(_))));
+    }
+
+    #[tokio::test]
+    async fn test_router_uses_registered_metadata() {
+        let orchestrator = RouterOrchestrator::keyword().with_agent_metadata(metadata(
+            "coder",
+            "development",
+            &["typescript"],
+        ));
+
+        let output = orchestrator
+            .orchestrate(agents(), OrchestratorInput::new("Port this to TypeScript"))
+            .await
+            .unwrap();
+        assert_eq!(output.execution_trace.routing.unwrap().agent, "coder");
+    }
+}
@@ -11,13 +11,25 @@
 //! - Data processing pipelines
 //! - Multi-step reasoning
 //! - Content generation and refinement
+//!
+//! Besides the output chain, agents can exchange values through the run's
+//! [`SharedContext`]; its final state is returned in
+//! [`OrchestratorOutput::shared_context`].
 
 use crate::orchestration::{
     Result,
     agent::{Agent, AgentInput, AgentOutput},
     context::{AgentExecution, ExecutionContext},
-    orchestrator::{BaseOrchestrator, Orchestrator, OrchestratorInput, OrchestratorOutput},
+    events::{EventSink, OrchestrationEvent},
+    orchestrator::{
+        BaseOrchestrator, Orchestrator, OrchestratorInput, OrchestratorOutput, select_agents,
+        with_own_agents,
+    },
+    registry::{AgentFilter, AgentRegistry},
+    shared::SharedContext,
 };
+use std::sync::Arc;
+use std::time::Instant;
 use tracing::debug;
 
 /// Default maximum retries for agent execution
@@ -27,6 +39,9 @@ const DEFAULT_MAX_RETRIES: usize = 3;
 pub struct SequentialOrchestrator {
     base: BaseOrchestrator,
     max_retries: usize,
+    min_confidence: Option<f64>,
+    agents: Vec<Arc<dyn Agent>>,
+    shared: Option<SharedContext>,
 }
 
 impl SequentialOrchestrator {
@@ -38,15 +53,54 @@ impl SequentialOrchestrator {
                 "Executes agents sequentially, passing each output to the next input",
             ),
             max_retries: DEFAULT_MAX_RETRIES,
+            min_confidence: None,
+            agents: Vec::new(),
+            shared: None,
         }
     }
 
+    /// Create a sequential orchestrator over the agents of `registry` matching `filter`
+    ///
+    /// The agents run in registration order, before any agents given to
+    /// [`orchestrate`](Orchestrator::orchestrate).
+    ///
+    /// # Errors
+    ///
+    /// Returns `OrchestrationError::InvalidConfig` if no agent matches.
+    pub async fn from_registry(registry: &AgentRegistry, filter: &AgentFilter) -> Result<Self> {
+        let mut orchestrator = Self::new();
+        orchestrator.agents = select_agents(registry, filter).await?;
+        Ok(orchestrator)
+    }
+
+    /// Run the orchestrator's own agents, e.g. those selected from a registry
+    pub async fn run(&self, input: OrchestratorInput) -> Result<OrchestratorOutput> {
+        self.orchestrate(Vec::new(), input).await
+    }
+
     /// Set max retries per agent
     pub fn with_max_retries(mut self, max_retries: usize) -> Self {
         self.max_retries = max_retries;
         self
     }
 
+    /// Retry agents whose output has a confidence below `min_confidence`
+    ///
+    /// See
+    /// [`ExecutionConfig::min_confidence`](crate::orchestration::ExecutionConfig::min_confidence).
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.min_confidence = Some(min_confidence);
+        self
+    }
+
+    /// Share `shared` with the agents instead of a fresh context per run
+    ///
+    /// Lets callers seed values before a run and keep them across runs.
+    pub fn with_shared_context(mut self, shared: SharedContext) -> Self {
+        self.shared = Some(shared);
+        self
+    }
+
     /// Execute agents sequentially
     async fn execute_sequential(
         &self,
@@ -70,15 +124,29 @@ impl SequentialOrchestrator {
                 );
             }
 
+            ctx.events().emit(OrchestrationEvent::StepStarted {
+                agent: agent.name().to_string(),
+                index,
+            });
+            let step_started = Instant::now();
+
             // Execute agent with retry
-            let output = self
-                .base
-                .execute_agent_with_retry(agent.as_ref(), input.clone(), self.max_retries)
+            let output = ctx
+                .run_with_shared(agent.name(), input.clone(), |input| {
+                    self.base
+                        .execute_agent_in_context(agent.as_ref(), input, self.max_retries, ctx)
+                })
                 .await;
 
             let success = output.is_successful();
 
             if success {
+                ctx.events().emit(OrchestrationEvent::StepCompleted {
+                    agent: agent.name().to_string(),
+                    index,
+                    duration: step_started.elapsed(),
+                    confidence: output.confidence,
+                });
                 exec_record.succeed(output.clone());
                 outputs.push(output.clone());
 
@@ -87,6 +155,11 @@ impl SequentialOrchestrator {
                     .with_context(output.data.clone())
                     .with_metadata("previous_agent", agent.name());
             } else {
+                ctx.events().emit(OrchestrationEvent::StepFailed {
+                    agent: agent.name().to_string(),
+                    index,
+                    error: output.content.clone(),
+                });
                 exec_record.fail(output.content.clone());
                 return Err(
                     crate::orchestration::errors::OrchestrationError::agent_failure(
@@ -127,6 +200,17 @@ impl Orchestrator for SequentialOrchestrator {
         agents: Vec<Box<dyn Agent>>,
         input: OrchestratorInput,
     ) -> Result<OrchestratorOutput> {
+        self.orchestrate_with_sink(agents, input, EventSink::disabled())
+            .await
+    }
+
+    async fn orchestrate_with_sink(
+        &self,
+        agents: Vec<Box<dyn Agent>>,
+        input: OrchestratorInput,
+        events: EventSink,
+    ) -> Result<OrchestratorOutput> {
+        let agents = with_own_agents(&self.agents, agents);
         if agents.is_empty() {
             return Err(
                 crate::orchestration::errors::OrchestrationError::invalid_config(
@@ -135,9 +219,18 @@ impl Orchestrator for SequentialOrchestrator {
             );
         }
 
+        let run_started = Instant::now();
+        events.emit(OrchestrationEvent::RunStarted {
+            orchestrator: self.base.name().to_string(),
+            agent_count: agents.len(),
+        });
+
         // Create execution context
-        let config = crate::orchestration::context::ExecutionConfig::new();
-        let ctx = ExecutionContext::new(config);
+        let mut config = crate::orchestration::context::ExecutionConfig::new();
+        config.min_confidence = self.min_confidence;
+        let ctx = ExecutionContext::new(config)
+            .with_events(events.clone())
+            .with_shared(self.shared.clone().unwrap_or_default());
 
         let agent_input = self.base.input_to_agent_input(&input);
 
@@ -147,7 +240,12 @@ impl Orchestrator for SequentialOrchestrator {
             Err(e) => {
                 ctx.complete_trace().await;
                 let trace = ctx.get_trace().await;
-                return Ok(OrchestratorOutput::failure(e.to_string(), trace));
+                events.emit(OrchestrationEvent::RunCompleted {
+                    success: false,
+                    duration: run_started.elapsed(),
+                });
+                return Ok(OrchestratorOutput::failure(e.to_string(), trace)
+                    .with_shared_context(ctx.shared().snapshot().await));
             },
         };
 
@@ -159,7 +257,13 @@ impl Orchestrator for SequentialOrchestrator {
         let final_output = outputs.last().unwrap();
         let result = final_output.content.clone();
 
-        Ok(OrchestratorOutput::success(result, outputs, trace))
+        events.emit(OrchestrationEvent::RunCompleted {
+            success: true,
+            duration: run_started.elapsed(),
+        });
+
+        Ok(OrchestratorOutput::success(result, outputs, trace)
+            .with_shared_context(ctx.shared().snapshot().await))
     }
 }
 
@@ -245,4 +349,222 @@ mod tests {
         assert!(output.is_successful());
         assert_eq!(output.agent_outputs[0].content, "Success: Test");
     }
+
+    struct ProgressAgent;
+
+    #[async_trait::async_trait]
+    impl Agent for ProgressAgent {
+        fn name(&self) -> &str {
+            "Progress"
+        }
+
+        fn description(&self) -> &str {
+            "Reports progress"
+        }
+
+        async fn execute(&self, input: AgentInput) -> crate::orchestration::agent::Result<AgentOutput> {
+            Ok(AgentOutput::new(input.content).with_confidence(0.8))
+        }
+
+        async fn execute_with_context(
+            &self,
+            input: AgentInput,
+            ctx: &ExecutionContext,
+        ) -> crate::orchestration::agent::Result<AgentOutput> {
+            ctx.emit_progress(self.name(), "halfway");
+            self.execute(input).await
+        }
+    }
+
+    fn event_kind(event: &OrchestrationEvent) -> String {
+        match event {
+            OrchestrationEvent::RunStarted { .. } => "run_started".to_string(),
+            OrchestrationEvent::StepStarted { agent, .. } => format!("started:{}", agent),
+            OrchestrationEvent::StepProgress { agent, message } => {
+                format!("progress:{}:{}", agent, message)
+            },
+            OrchestrationEvent::StepCompleted { agent, .. } => format!("completed:{}", agent),
+            OrchestrationEvent::StepFailed { agent, .. } => format!("failed:{}", agent),
+            OrchestrationEvent::RetryScheduled { agent, attempt, .. } => {
+                format!("retry:{}:{}", agent, attempt)
+            },
+            OrchestrationEvent::RunCompleted { success, .. } => format!("run_completed:{}", success),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequential_orchestrator_event_sequence() {
+        use futures::StreamExt;
+
+        let orchestrator = SequentialOrchestrator::new().with_max_retries(1);
+
+        let call_count = std::sync::atomic::AtomicUsize::new(0);
+        let flaky = SimpleAgent::new("Flaky", "Fails once", move |input| {
+            if call_count.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                Err(anyhow::anyhow!("Temporary failure").into())
+            } else {
+                Ok(AgentOutput::new(input.content))
+            }
+        });
+
+        let agents: Vec<Box<dyn Agent>> = vec![Box::new(ProgressAgent), Box::new(flaky)];
+        let (stream, run) =
+            orchestrator.orchestrate_with_events(agents, OrchestratorInput::new("Test"));
+
+        let (events, output) = tokio::join!(stream.collect::<Vec<_>>(), run);
+        assert!(output.unwrap().is_successful());
+
+        let kinds: Vec<String> = events.iter().map(event_kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "run_started",
+                "started:Progress",
+                "progress:Progress:halfway",
+                "completed:Progress",
+                "started:Flaky",
+                "retry:Flaky:1",
+                "completed:Flaky",
+                "run_completed:true",
+            ]
+        );
+
+        match &events[3] {
+            OrchestrationEvent::StepCompleted { index, confidence, .. } => {
+                assert_eq!(*index, 0);
+                assert_eq!(*confidence, 0.8);
+            },
+            other => panic!("Expected StepCompleted, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequential_orchestrator_events_stop_after_failure() {
+        use futures::StreamExt;
+
+        let orchestrator = SequentialOrchestrator::new().with_max_retries(0);
+
+        let failing = SimpleAgent::new("Failing", "Always fails", |_input| {
+            Err(anyhow::anyhow!("boom").into())
+        });
+        let never = SimpleAgent::new("Never", "Never runs", |input| {
+            Ok(AgentOutput::new(input.content))
+        });
+
+        let agents: Vec<Box<dyn Agent>> = vec![Box::new(failing), Box::new(never)];
+        let (stream, run) =
+            orchestrator.orchestrate_with_events(agents, OrchestratorInput::new("Test"));
+
+        let (events, output) = tokio::join!(stream.collect::<Vec<_>>(), run);
+        assert!(!output.unwrap().is_successful());
+
+        let kinds: Vec<String> = events.iter().map(event_kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "run_started",
+                "started:Failing",
+                "failed:Failing",
+                "run_completed:false",
+            ]
+        );
+    }
+
+    /// Agent that writes one shared value and reports the shared values it was given
+    struct SharedWriter {
+        name: &'static str,
+        delay_ms: u64,
+        key: &'static str,
+        value: serde_json::Value,
+    }
+
+    #[async_trait::async_trait]
+    impl Agent for SharedWriter {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Writes to the shared context"
+        }
+
+        async fn execute(&self, input: AgentInput) -> crate::orchestration::agent::Result<AgentOutput> {
+            Ok(AgentOutput::new(input.content))
+        }
+
+        async fn execute_with_context(
+            &self,
+            input: AgentInput,
+            ctx: &ExecutionContext,
+        ) -> crate::orchestration::agent::Result<AgentOutput> {
+            tokio::time::sleep(std::time::Duration::from_millis(self.delay_ms)).await;
+            ctx.shared()
+                .set(self.name, self.key, self.value.clone())
+                .await;
+            let seen = input
+                .context
+                .get("shared_context")
+                .map(|shared| shared.to_string())
+                .unwrap_or_default();
+            Ok(AgentOutput::new(seen))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequential_agents_share_context() {
+        let shared = SharedContext::new();
+        shared
+            .set("caller", "topic", serde_json::json!("rust"))
+            .await;
+
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(SharedWriter {
+                name: "Writer",
+                delay_ms: 0,
+                key: "Writer/draft",
+                value: serde_json::json!("v1"),
+            }),
+            Box::new(SharedWriter {
+                name: "Reviewer",
+                delay_ms: 0,
+                key: "Reviewer/verdict",
+                value: serde_json::json!("ok"),
+            }),
+        ];
+
+        let output = SequentialOrchestrator::new()
+            .with_shared_context(shared.clone())
+            .orchestrate(agents, OrchestratorInput::new("Test"))
+            .await
+            .unwrap();
+
+        assert!(output.is_successful());
+        assert_eq!(output.agent_outputs[0].content, r#"{"topic":"rust"}"#);
+        assert_eq!(
+            output.agent_outputs[1].content,
+            r#"{"Writer/draft":"v1","topic":"rust"}"#
+        );
+        assert_eq!(
+            output.shared_context.get_scoped("Reviewer", "verdict"),
+            Some(&serde_json::json!("ok"))
+        );
+        assert_eq!(output.shared_context.values.len(), 3);
+
+        let writers: Vec<_> = output
+            .execution_trace
+            .context_changes
+            .iter()
+            .map(|change| (change.agent.as_str(), change.conflict_with.is_some()))
+            .collect();
+        assert_eq!(
+            writers,
+            [("caller", false), ("Writer", false), ("Reviewer", false)]
+        );
+
+        // The caller's handle sees the final state
+        assert_eq!(
+            shared.get("Writer/draft").await,
+            Some(serde_json::json!("v1"))
+        );
+    }
 }
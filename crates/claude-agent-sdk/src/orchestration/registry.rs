@@ -14,7 +14,8 @@
 //! ## Example
 //!
 //! ```no_run
-//! use claude_agent_sdk::orchestration::registry::{AgentRegistry, AgentMetadata};
+//! use claude_agent_sdk::orchestration::ParallelOrchestrator;
+//! use claude_agent_sdk::orchestration::registry::{AgentFilter, AgentMetadata, AgentRegistry};
 //! use claude_agent_sdk::orchestration::agent::{SimpleAgent, AgentOutput};
 //!
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
@@ -45,6 +46,10 @@
 //!
 //! // Retrieve and use the agent
 //! let agent = registry.get("researcher").await?;
+//!
+//! // Or build an orchestrator from every matching agent
+//! let filter = AgentFilter::new().with_any_tags(["academic", "news"]);
+//! let orchestrator = ParallelOrchestrator::from_registry(&registry, &filter).await?;
 //! # Ok(())
 //! # }
 //! ```
@@ -53,6 +58,7 @@ use crate::orchestration::agent::{Agent, AgentError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::RwLock;
 
 /// Error type for agent registry operations
@@ -208,9 +214,10 @@ impl AgentMetadata {
         self.skills.iter().any(|s| s == skill)
     }
 
-    /// Check if agent has a specific tag
+    /// Check if agent has a specific tag, ignoring case
     pub fn has_tag(&self, tag: &str) -> bool {
-        self.tags.iter().any(|t| t == tag)
+        let tag = tag.to_lowercase();
+        self.tags.iter().any(|t| t.to_lowercase() == tag)
     }
 }
 
@@ -223,6 +230,9 @@ pub struct AgentFilter {
     /// Filter by tags (AND logic - agent must have all tags)
     pub tags: Vec<String>,
 
+    /// Filter by tags (OR logic - agent must have at least one tag)
+    pub any_tags: Vec<String>,
+
     /// Filter by tools (AND logic - agent must have all tools)
     pub tools: Vec<String>,
 
@@ -251,6 +261,26 @@ impl AgentFilter {
         self
     }
 
+    /// Require all of `tags`
+    pub fn with_all_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Require at least one of `tags`
+    pub fn with_any_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.any_tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
     /// Add a tool requirement
     pub fn with_tool(mut self, tool: impl Into<String>) -> Self {
         self.tools.push(tool.into());
@@ -290,6 +320,11 @@ impl AgentFilter {
             }
         }
 
+        // Check any-tags (agent must have at least one of them)
+        if !self.any_tags.is_empty() && !self.any_tags.iter().any(|tag| metadata.has_tag(tag)) {
+            return false;
+        }
+
         // Check tools (agent must have all specified tools)
         for tool in &self.tools {
             if !metadata.has_tool(tool) {
@@ -308,10 +343,21 @@ impl AgentFilter {
     }
 }
 
+/// A registered agent
+struct RegisteredAgent {
+    agent: Arc<dyn Agent>,
+    metadata: AgentMetadata,
+    /// Registration order, kept when the agent is updated
+    order: u64,
+}
+
 /// Centralized registry for agent definitions
 pub struct AgentRegistry {
-    /// Map of agent ID to (agent, metadata) pairs
-    agents: Arc<RwLock<HashMap<String, (Box<dyn Agent>, AgentMetadata)>>>,
+    /// Map of agent ID to registered agent
+    agents: Arc<RwLock<HashMap<String, RegisteredAgent>>>,
+
+    /// Order given to the next registered agent
+    next_order: AtomicU64,
 
     /// Registry name for logging
     name: String,
@@ -322,6 +368,7 @@ impl AgentRegistry {
     pub fn new() -> Self {
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
+            next_order: AtomicU64::new(0),
             name: "AgentRegistry".to_string(),
         }
     }
@@ -330,6 +377,7 @@ impl AgentRegistry {
     pub fn with_name(name: impl Into<String>) -> Self {
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
+            next_order: AtomicU64::new(0),
             name: name.into(),
         }
     }
@@ -339,6 +387,15 @@ impl AgentRegistry {
         &self,
         agent: Box<dyn Agent>,
         metadata: AgentMetadata,
+    ) -> Result<()> {
+        self.register_shared(Arc::from(agent), metadata).await
+    }
+
+    /// Register an agent shared with other owners
+    pub async fn register_shared(
+        &self,
+        agent: Arc<dyn Agent>,
+        metadata: AgentMetadata,
     ) -> Result<()> {
         let id = metadata.id.clone();
 
@@ -363,7 +420,12 @@ impl AgentRegistry {
             )));
         }
 
-        agents.insert(id.clone(), (agent, metadata.clone()));
+        let entry = RegisteredAgent {
+            agent,
+            metadata: metadata.clone(),
+            order: self.next_order.fetch_add(1, Ordering::Relaxed),
+        };
+        agents.insert(id.clone(), entry);
 
         tracing::info!(
             registry = self.name,
@@ -387,8 +449,17 @@ impl AgentRegistry {
         let mut agents = self.agents.write().await;
 
         let is_update = agents.contains_key(&id);
-
-        agents.insert(id.clone(), (agent, metadata));
+        let order = match agents.get(&id) {
+            Some(existing) => existing.order,
+            None => self.next_order.fetch_add(1, Ordering::Relaxed),
+        };
+
+        let entry = RegisteredAgent {
+            agent: Arc::from(agent),
+            metadata,
+            order,
+        };
+        agents.insert(id.clone(), entry);
 
         if is_update {
             tracing::info!(
@@ -427,27 +498,24 @@ impl AgentRegistry {
     pub async fn get(&self, id: &str) -> Result<Arc<dyn Agent>> {
         let agents = self.agents.read().await;
 
-        let (_agent, metadata) = agents.get(id).ok_or_else(|| RegistryError::AgentNotFound(id.to_string()))?;
+        let entry = agents.get(id).ok_or_else(|| RegistryError::AgentNotFound(id.to_string()))?;
 
         // Check if agent is enabled
-        if !metadata.enabled {
+        if !entry.metadata.enabled {
             return Err(RegistryError::AgentNotFound(format!("{} (disabled)", id)));
         }
 
-        // We can't return the agent directly, so we need to clone it
-        // This is a limitation - in practice, you'd want a different approach
-        // For now, we'll return an error indicating this
-        Err(RegistryError::Other("Direct agent retrieval not supported - use get_metadata or list_agents".to_string()))
+        Ok(Arc::clone(&entry.agent))
     }
 
     /// Get agent metadata by ID
     pub async fn get_metadata(&self, id: &str) -> Result<AgentMetadata> {
         let agents = self.agents.read().await;
 
-        let (_agent, metadata) = agents.get(id)
+        let entry = agents.get(id)
             .ok_or_else(|| RegistryError::AgentNotFound(id.to_string()))?;
 
-        Ok(metadata.clone())
+        Ok(entry.metadata.clone())
     }
 
     /// Check if an agent exists
@@ -459,7 +527,7 @@ impl AgentRegistry {
     /// Check if an agent exists and is enabled
     pub async fn is_enabled(&self, id: &str) -> bool {
         let agents = self.agents.read().await;
-        agents.get(id).map(|(_, m)| m.enabled).unwrap_or(false)
+        agents.get(id).map(|entry| entry.metadata.enabled).unwrap_or(false)
     }
 
     /// List all agent IDs
@@ -471,7 +539,7 @@ impl AgentRegistry {
     /// List all agent metadata
     pub async fn list_metadata(&self) -> Vec<AgentMetadata> {
         let agents = self.agents.read().await;
-        agents.values().map(|(_, m)| m.clone()).collect()
+        agents.values().map(|entry| entry.metadata.clone()).collect()
     }
 
     /// Find agents matching a filter
@@ -479,8 +547,37 @@ impl AgentRegistry {
         let agents = self.agents.read().await;
         agents
             .values()
-            .filter(|(_, metadata)| filter.matches(metadata))
-            .map(|(_, metadata)| metadata.clone())
+            .filter(|entry| filter.matches(&entry.metadata))
+            .map(|entry| entry.metadata.clone())
+            .collect()
+    }
+
+    /// Agents matching a filter, in registration order
+    ///
+    /// The agents are shared with the registry, so they can be handed to
+    /// orchestrators without re-creating them.
+    pub async fn select(&self, filter: &AgentFilter) -> Vec<Arc<dyn Agent>> {
+        self.select_with_metadata(filter)
+            .await
+            .into_iter()
+            .map(|(agent, _)| agent)
+            .collect()
+    }
+
+    /// Agents matching a filter with their metadata, in registration order
+    pub async fn select_with_metadata(
+        &self,
+        filter: &AgentFilter,
+    ) -> Vec<(Arc<dyn Agent>, AgentMetadata)> {
+        let agents = self.agents.read().await;
+        let mut selected: Vec<&RegisteredAgent> = agents
+            .values()
+            .filter(|entry| filter.matches(&entry.metadata))
+            .collect();
+        selected.sort_by_key(|entry| entry.order);
+        selected
+            .into_iter()
+            .map(|entry| (Arc::clone(&entry.agent), entry.metadata.clone()))
             .collect()
     }
 
@@ -493,7 +590,7 @@ impl AgentRegistry {
     /// Count enabled agents
     pub async fn count_enabled(&self) -> usize {
         let agents = self.agents.read().await;
-        agents.values().filter(|(_, m)| m.enabled).count()
+        agents.values().filter(|entry| entry.metadata.enabled).count()
     }
 
     /// Clear all agents
@@ -517,14 +614,14 @@ impl AgentRegistry {
     ) -> std::result::Result<crate::orchestration::agent::AgentOutput, AgentError> {
         let agents = self.agents.read().await;
 
-        let (agent, metadata) = agents.get(id)
+        let entry = agents.get(id)
             .ok_or_else(|| AgentError::InvalidInput(format!("Agent not found: {}", id)))?;
 
-        if !metadata.enabled {
+        if !entry.metadata.enabled {
             return Err(AgentError::InvalidInput(format!("Agent is disabled: {}", id)));
         }
 
-        agent.execute(input).await
+        entry.agent.execute(input).await
     }
 }
 
@@ -752,4 +849,103 @@ mod tests {
 
         assert_eq!(registry.count().await, 2);
     }
+
+    async fn tagged_registry() -> AgentRegistry {
+        let registry = AgentRegistry::new();
+        let agents = [
+            ("writer", &["Content", "english"][..]),
+            ("reviewer", &["content", "QA"][..]),
+            ("translator", &["French"][..]),
+        ];
+        for (name, tags) in agents {
+            let agent = SimpleAgent::new(name, name, move |input| {
+                Ok(AgentOutput::new(format!("{}: {}", name, input.content)))
+            });
+            let metadata = tags.iter().fold(
+                AgentMetadata::new(name, name, format!("The {}", name), "content"),
+                |metadata, tag| metadata.with_tag(*tag),
+            );
+            registry.register(Box::new(agent), metadata).await.unwrap();
+        }
+        registry
+    }
+
+    fn names(agents: &[Arc<dyn Agent>]) -> Vec<&str> {
+        agents.iter().map(|agent| agent.name()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_select_tags_ignore_case() {
+        let registry = tagged_registry().await;
+
+        let filter = AgentFilter::new().with_tag("CONTENT");
+        assert_eq!(names(&registry.select(&filter).await), ["writer", "reviewer"]);
+
+        let filter = AgentFilter::new().with_all_tags(["content", "qa"]);
+        assert_eq!(names(&registry.select(&filter).await), ["reviewer"]);
+
+        let filter = AgentFilter::new().with_any_tags(["qa", "french"]);
+        assert_eq!(names(&registry.select(&filter).await), ["reviewer", "translator"]);
+
+        let filter = AgentFilter::new().with_any_tags(["german"]);
+        assert!(registry.select(&filter).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_select_preserves_metadata_and_agents() {
+        let registry = tagged_registry().await;
+
+        let selected = registry
+            .select_with_metadata(&AgentFilter::new().with_any_tags(["english"]))
+            .await;
+        assert_eq!(selected.len(), 1);
+        let (agent, metadata) = &selected[0];
+        assert_eq!(agent.name(), metadata.name);
+        assert_eq!(metadata.description, "The writer");
+        assert_eq!(metadata.tags, ["Content", "english"]);
+
+        let output = agent.execute(AgentInput::new("draft")).await.unwrap();
+        assert_eq!(output.content, "writer: draft");
+
+        // The registry hands out the same agent
+        let fetched = registry.get("writer").await.unwrap();
+        assert!(Arc::ptr_eq(agent, &fetched));
+    }
+
+    #[tokio::test]
+    async fn test_orchestrators_from_registry() {
+        use crate::orchestration::{
+            OrchestrationError, OrchestratorInput, ParallelOrchestrator, SequentialOrchestrator,
+        };
+
+        let registry = tagged_registry().await;
+        let filter = AgentFilter::new().with_tag("content");
+
+        let output = SequentialOrchestrator::from_registry(&registry, &filter)
+            .await
+            .unwrap()
+            .run(OrchestratorInput::new("topic"))
+            .await
+            .unwrap();
+        assert_eq!(output.result, "reviewer: writer: topic");
+
+        let output = ParallelOrchestrator::from_registry(&registry, &filter)
+            .await
+            .unwrap()
+            .run(OrchestratorInput::new("topic"))
+            .await
+            .unwrap();
+        assert_eq!(output.agent_outputs.len(), 2);
+        assert_eq!(output.agent_outputs[0].content, "writer: topic");
+
+        let empty = AgentFilter::new().with_tag("missing");
+        assert!(matches!(
+            SequentialOrchestrator::from_registry(&registry, &empty).await,
+            Err(OrchestrationError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            ParallelOrchestrator::from_registry(&registry, &empty).await,
+            Err(OrchestrationError::InvalidConfig(_))
+        ));
+    }
 }
@@ -0,0 +1,370 @@
+//! # Shared context between agents
+//!
+//! A [`SharedContext`] is a key-value blackboard that every agent of an
+//! orchestration run can read and write through
+//! [`ExecutionContext::shared`](crate::orchestration::ExecutionContext::shared).
+//! Agents normally write to their own namespace through a [`ScopedContext`],
+//! so keys look like `researcher/findings`.
+//!
+//! Every write is recorded as a [`ContextChange`] and ends up in the run's
+//! [`ExecutionTrace`](crate::orchestration::ExecutionTrace). When an agent
+//! overwrites a key that another agent wrote while the first one was running,
+//! as happens with parallel agents, the last write wins and the conflict is
+//! logged and flagged on the change.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Separator between an agent's namespace and the key
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+/// Kind of write made to a [`SharedContext`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextOperation {
+    /// The value was replaced
+    Set,
+    /// A value was appended to the array stored under the key
+    Append,
+}
+
+/// One write to a [`SharedContext`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextChange {
+    /// Full key, including the namespace
+    pub key: String,
+
+    /// Agent that made the write
+    pub agent: String,
+
+    /// Kind of write
+    pub operation: ContextOperation,
+
+    /// When the write happened
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+
+    /// Agent whose concurrent write to the same key was overwritten
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conflict_with: Option<String>,
+}
+
+/// Serializable copy of a [`SharedContext`]'s values, ordered by key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SharedContextSnapshot {
+    /// Values by full key
+    pub values: BTreeMap<String, serde_json::Value>,
+}
+
+impl SharedContextSnapshot {
+    /// Value stored under a full key
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.values.get(key)
+    }
+
+    /// Value stored under `key` in `agent`'s namespace
+    pub fn get_scoped(&self, agent: &str, key: &str) -> Option<&serde_json::Value> {
+        self.values.get(&SharedContext::scoped_key(agent, key))
+    }
+
+    /// Whether no value is stored
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct Tracking {
+    /// Incremented on every write and every agent start
+    sequence: u64,
+    /// Sequence number at which each running agent started
+    running: HashMap<String, u64>,
+    /// Sequence number and writer of the last write to each key
+    last_write: HashMap<String, (u64, String)>,
+    changes: Vec<ContextChange>,
+}
+
+/// Key-value store shared by the agents of an orchestration run
+///
+/// Cloning is cheap and yields a handle to the same store.
+///
+/// # Example
+///
+/// ```
+/// # use claude_agent_sdk::orchestration::SharedContext;
+/// # async fn example() {
+/// let shared = SharedContext::new();
+/// let researcher = shared.scope("researcher");
+/// researcher.set("topic", serde_json::json!("rust")).await;
+/// researcher.append("sources", serde_json::json!("docs.rs")).await;
+///
+/// assert_eq!(
+///     shared.get("researcher/topic").await,
+///     Some(serde_json::json!("rust"))
+/// );
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct SharedContext {
+    values: Arc<RwLock<HashMap<String, serde_json::Value>>>,
+    tracking: Arc<RwLock<Tracking>>,
+}
+
+impl SharedContext {
+    /// Create an empty shared context
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Full key for `key` in `agent`'s namespace
+    pub fn scoped_key(agent: &str, key: &str) -> String {
+        format!("{}{}{}", agent, NAMESPACE_SEPARATOR, key)
+    }
+
+    /// Handle writing to `agent`'s namespace
+    pub fn scope(&self, agent: impl Into<String>) -> ScopedContext {
+        ScopedContext {
+            shared: self.clone(),
+            agent: agent.into(),
+        }
+    }
+
+    /// Value stored under a full key
+    pub async fn get(&self, key: &str) -> Option<serde_json::Value> {
+        self.values.read().await.get(key).cloned()
+    }
+
+    /// Store `value` under a full key, recording `agent` as the writer
+    pub async fn set(&self, agent: &str, key: impl Into<String>, value: serde_json::Value) {
+        let key = key.into();
+        let mut values = self.values.write().await;
+        values.insert(key.clone(), value);
+        self.record(agent, key, ContextOperation::Set).await;
+    }
+
+    /// Append `value` to the array stored under a full key
+    ///
+    /// A missing key starts a new array; an existing non-array value becomes
+    /// the first element.
+    pub async fn append(&self, agent: &str, key: impl Into<String>, value: serde_json::Value) {
+        let key = key.into();
+        let mut values = self.values.write().await;
+        let entry = values
+            .entry(key.clone())
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        match entry {
+            serde_json::Value::Array(items) => items.push(value),
+            other => *other = serde_json::Value::Array(vec![other.take(), value]),
+        }
+        self.record(agent, key, ContextOperation::Append).await;
+    }
+
+    /// Copy of all values
+    pub async fn snapshot(&self) -> SharedContextSnapshot {
+        SharedContextSnapshot {
+            values: self
+                .values
+                .read()
+                .await
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
+    }
+
+    /// Writes made so far, in order
+    pub async fn changes(&self) -> Vec<ContextChange> {
+        self.tracking.read().await.changes.clone()
+    }
+
+    /// Mark `agent` as running, so overlapping writes can be detected
+    pub(crate) async fn agent_started(&self, agent: &str) {
+        let mut tracking = self.tracking.write().await;
+        tracking.sequence += 1;
+        let sequence = tracking.sequence;
+        tracking.running.insert(agent.to_string(), sequence);
+    }
+
+    /// Mark `agent` as no longer running
+    pub(crate) async fn agent_finished(&self, agent: &str) {
+        self.tracking.write().await.running.remove(agent);
+    }
+
+    /// Record a write; called while the values lock is held so the order of
+    /// changes matches the order of writes
+    async fn record(&self, agent: &str, key: String, operation: ContextOperation) {
+        let mut tracking = self.tracking.write().await;
+        tracking.sequence += 1;
+        let sequence = tracking.sequence;
+
+        let started = tracking.running.get(agent).copied();
+        let conflict_with = match (tracking.last_write.get(&key), started) {
+            (Some((written, writer)), Some(started)) if writer != agent && *written > started => {
+                Some(writer.clone())
+            }
+            _ => None,
+        };
+        if let Some(other) = &conflict_with {
+            warn!(
+                key = %key,
+                agent = %agent,
+                overwritten = %other,
+                "Concurrent writes to shared context key; last writer wins"
+            );
+        }
+
+        tracking
+            .last_write
+            .insert(key.clone(), (sequence, agent.to_string()));
+        tracking.changes.push(ContextChange {
+            key,
+            agent: agent.to_string(),
+            operation,
+            timestamp: chrono::Utc::now(),
+            conflict_with,
+        });
+    }
+}
+
+/// Handle to a [`SharedContext`] that reads and writes one agent's namespace
+#[derive(Clone)]
+pub struct ScopedContext {
+    shared: SharedContext,
+    agent: String,
+}
+
+impl ScopedContext {
+    /// Agent owning the namespace
+    pub fn agent(&self) -> &str {
+        &self.agent
+    }
+
+    /// The underlying shared context, for keys outside the namespace
+    pub fn shared(&self) -> &SharedContext {
+        &self.shared
+    }
+
+    /// Value stored under `key` in this agent's namespace
+    pub async fn get(&self, key: &str) -> Option<serde_json::Value> {
+        self.shared
+            .get(&SharedContext::scoped_key(&self.agent, key))
+            .await
+    }
+
+    /// Value stored under `key` in another agent's namespace
+    pub async fn get_from(&self, agent: &str, key: &str) -> Option<serde_json::Value> {
+        self.shared
+            .get(&SharedContext::scoped_key(agent, key))
+            .await
+    }
+
+    /// Store `value` under `key` in this agent's namespace
+    pub async fn set(&self, key: &str, value: serde_json::Value) {
+        self.shared
+            .set(
+                &self.agent,
+                SharedContext::scoped_key(&self.agent, key),
+                value,
+            )
+            .await
+    }
+
+    /// Append `value` to the array under `key` in this agent's namespace
+    pub async fn append(&self, key: &str, value: serde_json::Value) {
+        self.shared
+            .append(
+                &self.agent,
+                SharedContext::scoped_key(&self.agent, key),
+                value,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_scoped_get_set_append() {
+        let shared = SharedContext::new();
+        let writer = shared.scope("writer");
+        let reader = shared.scope("reader");
+
+        writer.set("draft", json!("v1")).await;
+        writer.append("notes", json!("a")).await;
+        writer.append("notes", json!("b")).await;
+
+        assert_eq!(writer.get("draft").await, Some(json!("v1")));
+        assert_eq!(reader.get("draft").await, None);
+        assert_eq!(
+            reader.get_from("writer", "notes").await,
+            Some(json!(["a", "b"]))
+        );
+
+        // Appending to a scalar keeps the scalar as the first element
+        shared.append("reader", "writer/draft", json!("v2")).await;
+        assert_eq!(writer.get("draft").await, Some(json!(["v1", "v2"])));
+
+        let changes = shared.changes().await;
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|change| (change.agent.as_str(), change.key.as_str(), change.operation))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("writer", "writer/draft", ContextOperation::Set),
+                ("writer", "writer/notes", ContextOperation::Append),
+                ("writer", "writer/notes", ContextOperation::Append),
+                ("reader", "writer/draft", ContextOperation::Append),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_ordered_and_serializable() {
+        let shared = SharedContext::new();
+        shared.set("b", "b/key", json!(2)).await;
+        shared.set("a", "a/key", json!(1)).await;
+
+        let snapshot = shared.snapshot().await;
+        assert_eq!(snapshot.get_scoped("a", "key"), Some(&json!(1)));
+        assert_eq!(
+            serde_json::to_string(&snapshot).unwrap(),
+            r#"{"a/key":1,"b/key":2}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_writes_flag_conflict() {
+        let shared = SharedContext::new();
+
+        // Sequential: the second agent starts after the first one wrote
+        shared.agent_started("first").await;
+        shared.set("first", "plan", json!("a")).await;
+        shared.agent_finished("first").await;
+        shared.agent_started("second").await;
+        shared.set("second", "plan", json!("b")).await;
+        shared.agent_finished("second").await;
+
+        // Parallel: both run while the other writes
+        shared.agent_started("left").await;
+        shared.agent_started("right").await;
+        shared.set("left", "plan", json!("left")).await;
+        shared.set("right", "plan", json!("right")).await;
+
+        assert_eq!(shared.get("plan").await, Some(json!("right")));
+        let conflicts: Vec<_> = shared
+            .changes()
+            .await
+            .into_iter()
+            .map(|change| change.conflict_with)
+            .collect();
+        assert_eq!(conflicts, [None, None, None, Some("left".to_string())]);
+    }
+}
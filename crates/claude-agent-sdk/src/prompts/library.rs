@@ -0,0 +1,510 @@
+//! Saved prompts and the prompt library
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::{PromptError, PromptTemplate};
+use crate::errors::YamlParseError;
+
+/// Variable declared in a prompt's frontmatter
+///
+/// In YAML, a variable is either just its name or a map with `name`,
+/// `description` and `default`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "RawVariable")]
+pub struct PromptVariable {
+    /// Variable name
+    pub name: String,
+    /// What the variable is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Value used when none is provided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawVariable {
+    Name(String),
+    Full {
+        name: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        default: Option<String>,
+    },
+}
+
+impl From<RawVariable> for PromptVariable {
+    fn from(raw: RawVariable) -> Self {
+        match raw {
+            RawVariable::Name(name) => Self {
+                name,
+                description: None,
+                default: None,
+            },
+            RawVariable::Full {
+                name,
+                description,
+                default,
+            } => Self {
+                name,
+                description,
+                default,
+            },
+        }
+    }
+}
+
+/// Frontmatter of a saved prompt
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptMetadata {
+    /// Prompt name, shared by all versions
+    pub name: String,
+    /// Prompt version
+    pub version: Version,
+    /// What the prompt does
+    pub description: Option<String>,
+    /// Declared variables
+    pub variables: Vec<PromptVariable>,
+    /// Model the prompt was written for
+    pub model: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawFrontmatter {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    variables: Vec<PromptVariable>,
+    #[serde(default)]
+    model: Option<String>,
+}
+
+/// Deserialize the YAML frontmatter of a prompt
+#[cfg(feature = "yaml")]
+fn parse_frontmatter(frontmatter: &str) -> Result<RawFrontmatter, PromptError> {
+    serde_yaml::from_str(frontmatter)
+        .map_err(|e| YamlParseError::from_serde(frontmatter, &e).into())
+}
+
+/// Deserialize the YAML frontmatter of a prompt, which needs the `yaml` feature
+#[cfg(not(feature = "yaml"))]
+fn parse_frontmatter(frontmatter: &str) -> Result<RawFrontmatter, PromptError> {
+    Err(YamlParseError::new("YAML support not enabled", frontmatter).into())
+}
+
+/// A named, versioned prompt
+#[derive(Debug, Clone)]
+pub struct SavedPrompt {
+    /// Frontmatter
+    pub metadata: PromptMetadata,
+    /// Prompt body
+    pub template: PromptTemplate,
+    /// File the prompt was loaded from
+    pub path: Option<PathBuf>,
+}
+
+impl SavedPrompt {
+    /// Parse a prompt from markdown with YAML frontmatter
+    pub fn parse(source: &str) -> Result<Self, PromptError> {
+        let (frontmatter, body) = split_frontmatter(source).ok_or_else(|| {
+            PromptError::InvalidFormat("expected YAML frontmatter between `---` lines".to_string())
+        })?;
+
+        let raw = parse_frontmatter(frontmatter)?;
+        if raw.name.trim().is_empty() {
+            return Err(PromptError::InvalidFormat(
+                "name must not be empty".to_string(),
+            ));
+        }
+        let version = Version::parse(&raw.version)
+            .map_err(|e| PromptError::InvalidVersion(format!("{}: {}", raw.version, e)))?;
+
+        Ok(Self {
+            metadata: PromptMetadata {
+                name: raw.name,
+                version,
+                description: raw.description,
+                variables: raw.variables,
+                model: raw.model,
+            },
+            template: PromptTemplate::new(body.trim()),
+            path: None,
+        })
+    }
+
+    /// Load a prompt from a markdown file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PromptError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| PromptError::Io(format!("Failed to read {:?}: {}", path, e)))?;
+
+        let mut prompt = Self::parse(&source).map_err(|e| PromptError::File {
+            path: path.to_path_buf(),
+            source: Box::new(e),
+        })?;
+        prompt.path = Some(path.to_path_buf());
+        Ok(prompt)
+    }
+
+    /// Prompt body before rendering
+    pub fn body(&self) -> &str {
+        self.template.source()
+    }
+
+    /// Render the prompt with `vars`
+    ///
+    /// Every key in `vars` must be a declared variable; declared variables
+    /// without a value fall back to their default.
+    pub fn render(&self, vars: &HashMap<String, String>) -> Result<String, PromptError> {
+        let declared = &self.metadata.variables;
+        if let Some(unknown) = vars
+            .keys()
+            .find(|key| !declared.iter().any(|v| &v.name == *key))
+        {
+            return Err(PromptError::UnknownVariable(unknown.clone()));
+        }
+
+        let mut values = vars.clone();
+        for variable in declared {
+            if let Some(default) = &variable.default {
+                values
+                    .entry(variable.name.clone())
+                    .or_insert_with(|| default.clone());
+            }
+        }
+
+        self.template.render(&values)
+    }
+}
+
+/// Split `source` into frontmatter and body
+fn split_frontmatter(source: &str) -> Option<(&str, &str)> {
+    let rest = source
+        .strip_prefix("---\r\n")
+        .or_else(|| source.strip_prefix("---\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Kind of problem reported by [`PromptLibrary::lint`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptLintKind {
+    /// The body uses a variable the frontmatter does not declare
+    UndeclaredVariable(String),
+    /// The frontmatter declares a variable the body never uses
+    UnusedVariable(String),
+}
+
+/// Problem found by [`PromptLibrary::lint`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptLint {
+    /// Prompt name
+    pub name: String,
+    /// Prompt version
+    pub version: Version,
+    /// What is wrong
+    pub kind: PromptLintKind,
+}
+
+/// Collection of saved prompts, keyed by name with any number of versions each
+#[derive(Debug, Clone, Default)]
+pub struct PromptLibrary {
+    // Versions are kept sorted in ascending order
+    prompts: BTreeMap<String, Vec<SavedPrompt>>,
+}
+
+impl PromptLibrary {
+    /// Create an empty library
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `.md` file below `dir`, including subdirectories
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, PromptError> {
+        let mut library = Self::new();
+        library.add_dir(dir.as_ref())?;
+        Ok(library)
+    }
+
+    fn add_dir(&mut self, dir: &Path) -> Result<(), PromptError> {
+        let mut entries = std::fs::read_dir(dir)
+            .map_err(|e| PromptError::Io(format!("Failed to read directory {:?}: {}", dir, e)))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<Vec<_>>>()
+            .map_err(|e| PromptError::Io(format!("Failed to read directory {:?}: {}", dir, e)))?;
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                self.add_dir(&path)?;
+            } else if path.extension().and_then(|e| e.to_str()) == Some("md") {
+                self.insert(SavedPrompt::from_file(&path)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a prompt
+    ///
+    /// Fails if the library already has a prompt with the same name and version.
+    pub fn insert(&mut self, prompt: SavedPrompt) -> Result<(), PromptError> {
+        let versions = self
+            .prompts
+            .entry(prompt.metadata.name.clone())
+            .or_default();
+        match versions.binary_search_by(|p| p.metadata.version.cmp(&prompt.metadata.version)) {
+            Ok(_) => Err(PromptError::Duplicate {
+                name: prompt.metadata.name,
+                version: prompt.metadata.version,
+            }),
+            Err(index) => {
+                versions.insert(index, prompt);
+                Ok(())
+            }
+        }
+    }
+
+    /// Latest version of the prompt `name`
+    pub fn get(&self, name: &str) -> Option<&SavedPrompt> {
+        self.latest(name)
+    }
+
+    /// Highest semver version of the prompt `name`
+    pub fn latest(&self, name: &str) -> Option<&SavedPrompt> {
+        self.prompts.get(name).and_then(|versions| versions.last())
+    }
+
+    /// Highest version of the prompt `name` matching the semver requirement `req`, e.g. `^3`
+    pub fn get_version(&self, name: &str, req: &str) -> Result<&SavedPrompt, PromptError> {
+        let req = VersionReq::parse(req)
+            .map_err(|e| PromptError::InvalidVersion(format!("{}: {}", req, e)))?;
+
+        self.versions(name)
+            .iter()
+            .rev()
+            .find(|p| req.matches(&p.metadata.version))
+            .ok_or_else(|| PromptError::NotFound(format!("{} {}", name, req)))
+    }
+
+    /// All versions of the prompt `name`, oldest first
+    pub fn versions(&self, name: &str) -> &[SavedPrompt] {
+        self.prompts.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Names of all prompts, sorted
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.prompts.keys().map(String::as_str)
+    }
+
+    /// Number of prompts, counting every version
+    pub fn len(&self) -> usize {
+        self.prompts.values().map(Vec::len).sum()
+    }
+
+    /// Check whether the library is empty
+    pub fn is_empty(&self) -> bool {
+        self.prompts.is_empty()
+    }
+
+    /// Report variables that are used but not declared, or declared but not used
+    pub fn lint(&self) -> Vec<PromptLint> {
+        let mut lints = Vec::new();
+
+        for prompt in self.prompts.values().flatten() {
+            let used: HashSet<&str> = prompt.template.variables().into_iter().collect();
+            let declared: HashSet<&str> = prompt
+                .metadata
+                .variables
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect();
+
+            let lint = |kind| PromptLint {
+                name: prompt.metadata.name.clone(),
+                version: prompt.metadata.version.clone(),
+                kind,
+            };
+            for name in prompt.template.variables() {
+                if !declared.contains(name) {
+                    lints.push(lint(PromptLintKind::UndeclaredVariable(name.to_string())));
+                }
+            }
+            for variable in &prompt.metadata.variables {
+                if !used.contains(variable.name.as_str()) {
+                    lints.push(lint(PromptLintKind::UnusedVariable(variable.name.clone())));
+                }
+            }
+        }
+
+        lints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str, version: &str, variables: &str, body: &str) -> String {
+        format!("---\nname: {name}\nversion: {version}\nvariables: {variables}\n---\n{body}\n")
+    }
+
+    fn fixture_library() -> (tempfile::TempDir, PromptLibrary) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("triage")).unwrap();
+        let files = [
+            (
+                "triage/v1.md",
+                fixture("bug-triage", "1.0.0", "[report]", "Triage: {{report}}"),
+            ),
+            (
+                "triage/v3.md",
+                fixture(
+                    "bug-triage",
+                    "3.1.0",
+                    "[report, {name: product, default: the SDK}]",
+                    "Triage this {{ product }} bug:\n\n{{report}}",
+                ),
+            ),
+            (
+                "triage/v2.md",
+                fixture("bug-triage", "2.4.0", "[report]", "Classify: {{report}}"),
+            ),
+            (
+                "summary.md",
+                fixture(
+                    "summary",
+                    "0.1.0",
+                    "[text, tone]",
+                    "Summarize {{text}} as {{audience}}",
+                ),
+            ),
+            ("notes.txt", "not a prompt".to_string()),
+        ];
+        for (path, contents) in files {
+            std::fs::write(dir.path().join(path), contents).unwrap();
+        }
+
+        let library = PromptLibrary::load_dir(dir.path()).unwrap();
+        (dir, library)
+    }
+
+    #[test]
+    fn test_versions_coexist_and_latest_is_by_semver() {
+        let (_dir, library) = fixture_library();
+
+        assert_eq!(library.len(), 4);
+        assert_eq!(
+            library.names().collect::<Vec<_>>(),
+            vec!["bug-triage", "summary"]
+        );
+
+        let versions: Vec<String> = library
+            .versions("bug-triage")
+            .iter()
+            .map(|p| p.metadata.version.to_string())
+            .collect();
+        assert_eq!(versions, vec!["1.0.0", "2.4.0", "3.1.0"]);
+        assert_eq!(
+            library.get("bug-triage").unwrap().metadata.version,
+            Version::new(3, 1, 0)
+        );
+
+        let v2 = library.get_version("bug-triage", "^2").unwrap();
+        assert_eq!(v2.metadata.version, Version::new(2, 4, 0));
+        assert!(v2.path.as_ref().unwrap().ends_with("triage/v2.md"));
+
+        assert!(matches!(
+            library.get_version("bug-triage", "^4"),
+            Err(PromptError::NotFound(_))
+        ));
+        assert!(matches!(
+            library.get_version("bug-triage", "not a version"),
+            Err(PromptError::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_version_is_rejected() {
+        let (_dir, mut library) = fixture_library();
+        let duplicate = SavedPrompt::parse(&fixture("bug-triage", "2.4.0", "[]", "again")).unwrap();
+
+        assert!(matches!(
+            library.insert(duplicate),
+            Err(PromptError::Duplicate { name, .. }) if name == "bug-triage"
+        ));
+    }
+
+    #[test]
+    fn test_render_validates_variables_and_applies_defaults() {
+        let (_dir, library) = fixture_library();
+        let prompt = library.get("bug-triage").unwrap();
+
+        let vars = HashMap::from([("report".to_string(), "Crash on start".to_string())]);
+        assert_eq!(
+            prompt.render(&vars).unwrap(),
+            "Triage this the SDK bug:\n\nCrash on start"
+        );
+
+        let mut unknown = vars.clone();
+        unknown.insert("severity".to_string(), "high".to_string());
+        assert!(matches!(
+            prompt.render(&unknown),
+            Err(PromptError::UnknownVariable(name)) if name == "severity"
+        ));
+
+        assert!(matches!(
+            prompt.render(&HashMap::new()),
+            Err(PromptError::MissingVariable(name)) if name == "report"
+        ));
+    }
+
+    #[test]
+    fn test_lint_reports_undeclared_and_unused_variables() {
+        let (_dir, library) = fixture_library();
+
+        let lints = library.lint();
+        assert_eq!(lints.len(), 2);
+        assert!(lints.iter().all(|l| l.name == "summary"));
+        assert!(lints.contains(&PromptLint {
+            name: "summary".to_string(),
+            version: Version::new(0, 1, 0),
+            kind: PromptLintKind::UndeclaredVariable("audience".to_string()),
+        }));
+        assert!(
+            lints
+                .iter()
+                .any(|l| l.kind == PromptLintKind::UnusedVariable("tone".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_files_name_the_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("bad.md"), fixture("x", "one", "[]", "body")).unwrap();
+
+        let err = PromptLibrary::load_dir(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("bad.md"));
+        assert!(
+            matches!(err, PromptError::File { source, .. } if matches!(*source, PromptError::InvalidVersion(_)))
+        );
+
+        assert!(matches!(
+            SavedPrompt::parse("no frontmatter"),
+            Err(PromptError::InvalidFormat(_))
+        ));
+    }
+}
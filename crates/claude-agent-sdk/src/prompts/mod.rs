@@ -0,0 +1,141 @@
+//! # Prompt library
+//!
+//! Named, versioned prompts stored as markdown files with YAML frontmatter:
+//!
+//! ```markdown
+//! ---
+//! name: bug-triage
+//! version: 3.1.0
+//! description: Classify a bug report
+//! model: claude-sonnet-4-20250514
+//! variables:
+//!   - report
+//!   - name: product
+//!     default: the SDK
+//! ---
+//! Triage the following bug report for {{product}}:
+//!
+//! {{report}}
+//! ```
+//!
+//! Load a directory with [`PromptLibrary::load_dir`], then send a prompt with
+//! [`query_prompt`] or [`Session::send_prompt`](crate::v2::Session::send_prompt).
+//! A prompt's `model` hint overrides the configured model for that turn only.
+//!
+//! System prompts are composed from templated sections with
+//! [`SystemPromptBuilder`], which is passed to
+//! [`ClaudeAgentOptions::builder().system_prompt_template()`](crate::ClaudeAgentOptions::builder).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::prompts::{PromptLibrary, query_prompt};
+//! use std::collections::HashMap;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let library = PromptLibrary::load_dir("prompts")?;
+//! let vars = HashMap::from([("report".to_string(), "Crash on startup".to_string())]);
+//! let messages = query_prompt(&library, "bug-triage", &vars, None).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod library;
+mod system;
+mod template;
+
+pub use library::{
+    PromptLibrary, PromptLint, PromptLintKind, PromptMetadata, PromptVariable, SavedPrompt,
+};
+pub use system::SystemPromptBuilder;
+pub use template::PromptTemplate;
+
+use std::collections::HashMap;
+use thiserror::Error;
+
+use crate::errors::{Result, YamlParseError};
+use crate::types::config::ClaudeAgentOptions;
+use crate::types::messages::Message;
+
+/// Errors that can occur in the prompt library
+#[derive(Debug, Error)]
+pub enum PromptError {
+    /// IO error
+    #[error("IO error: {0}")]
+    Io(String),
+
+    /// A prompt file could not be loaded
+    #[error("Error in {path:?}: {source}")]
+    File {
+        /// The prompt file
+        path: std::path::PathBuf,
+        /// What went wrong
+        source: Box<PromptError>,
+    },
+
+    /// The file is not markdown with YAML frontmatter
+    #[error("Invalid prompt file: {0}")]
+    InvalidFormat(String),
+
+    /// The frontmatter is not valid YAML
+    #[error("Invalid prompt frontmatter: {0}")]
+    Yaml(#[from] YamlParseError),
+
+    /// A version or version requirement is not valid semver
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+
+    /// A prompt with the same name and version is already in the library
+    #[error("Duplicate prompt: {name} {version}")]
+    Duplicate {
+        /// Prompt name
+        name: String,
+        /// Prompt version
+        version: semver::Version,
+    },
+
+    /// No prompt matches the requested name and version
+    #[error("Prompt not found: {0}")]
+    NotFound(String),
+
+    /// A variable used by the prompt has no value
+    #[error("Missing value for variable '{0}'")]
+    MissingVariable(String),
+
+    /// A value was provided for a variable the prompt does not declare
+    #[error("Unknown variable '{0}'")]
+    UnknownVariable(String),
+
+    /// A template is malformed
+    #[error("Invalid template at line {line}, column {column}: {message}")]
+    Template {
+        /// Line of the problem, starting at 1
+        line: usize,
+        /// Column of the problem in characters, starting at 1
+        column: usize,
+        /// What is wrong
+        message: String,
+    },
+}
+
+/// Render the latest version of the prompt `name` and send it as a one-shot query
+///
+/// The prompt's model hint, if any, replaces the model in `options`.
+pub async fn query_prompt(
+    library: &PromptLibrary,
+    name: &str,
+    vars: &HashMap<String, String>,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<Vec<Message>> {
+    let prompt = library
+        .get(name)
+        .ok_or_else(|| PromptError::NotFound(name.to_string()))?;
+    let text = prompt.render(vars)?;
+
+    let mut options = options.unwrap_or_default();
+    if let Some(model) = &prompt.metadata.model {
+        options.model = Some(model.clone());
+    }
+
+    crate::query::query(text, Some(options)).await
+}
@@ -0,0 +1,398 @@
+//! Composing system prompts from templated sections
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{PromptError, PromptTemplate};
+use crate::types::config::{SystemPrompt, SystemPromptPreset};
+
+/// Kind of a [`SystemPromptBuilder`] section, which decides its heading
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SectionKind {
+    Role,
+    Constraints,
+    Examples,
+    OutputFormat,
+    Custom(String),
+}
+
+impl SectionKind {
+    /// Kind named by a `[name]` header line in a template file
+    fn from_header(name: &str) -> Self {
+        match name {
+            "role" => Self::Role,
+            "constraints" => Self::Constraints,
+            "examples" => Self::Examples,
+            "output_format" => Self::OutputFormat,
+            heading => Self::Custom(heading.to_string()),
+        }
+    }
+
+    /// Heading written above the section, including its trailing newlines
+    fn heading(&self) -> Option<String> {
+        match self {
+            Self::Role => None,
+            Self::Constraints => Some("Constraints:\n".to_string()),
+            Self::Examples => Some("Examples:\n".to_string()),
+            Self::OutputFormat => Some("Output format:\n".to_string()),
+            Self::Custom(heading) if heading.starts_with('#') => Some(format!("{}\n\n", heading)),
+            Self::Custom(heading) => Some(format!("{}:\n", heading)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Section {
+    kind: SectionKind,
+    template: PromptTemplate,
+    /// Line of the template file the source starts on, for error positions
+    first_line: usize,
+}
+
+impl Section {
+    fn new(kind: SectionKind, source: String, first_line: usize) -> Self {
+        Self {
+            kind,
+            template: PromptTemplate::new(source),
+            first_line,
+        }
+    }
+}
+
+/// Builds a system prompt from sections with `{{variable}}` placeholders
+///
+/// A prompt is made of a role, followed by constraints, examples, an output
+/// format and custom sections, in the order they are set. The role has no
+/// heading; other sections are headed `Constraints:`, `Examples:`,
+/// `Output format:` or `<heading>:`, and a custom heading starting with `#` is
+/// used as a markdown heading. Sections are separated by a blank line, and
+/// sections that render to nothing are left out.
+///
+/// Section text is a [`PromptTemplate`], rendered with the builder's
+/// variables. Wrapping a whole section in `{{#if name}}…{{/if}}` makes it
+/// conditional.
+///
+/// With [`preset`](Self::preset), the rendered text is appended to a preset
+/// prompt instead of replacing it.
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::prompts::SystemPromptBuilder;
+///
+/// let prompt = SystemPromptBuilder::new()
+///     .role("You review {{language}} code.")
+///     .constraints("{{#if strict}}- Reject any use of unsafe.{{/if}}")
+///     .output_format("A list of findings.")
+///     .var("language", "Rust")
+///     .render()
+///     .unwrap();
+/// assert_eq!(prompt, "You review Rust code.\n\nOutput format:\nA list of findings.");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemPromptBuilder {
+    sections: Vec<Section>,
+    vars: HashMap<String, String>,
+    preset: Option<String>,
+}
+
+impl SystemPromptBuilder {
+    /// Create a builder without sections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load sections from a template file
+    ///
+    /// See [`from_template_str`](Self::from_template_str) for the format.
+    /// Errors name the file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PromptError> {
+        let path = path.as_ref();
+        std::fs::read_to_string(path)
+            .map_err(|e| PromptError::Io(e.to_string()))
+            .and_then(|source| Self::from_template_str(&source))
+            .map_err(|e| PromptError::File {
+                path: path.to_path_buf(),
+                source: Box::new(e),
+            })
+    }
+
+    /// Parse sections from template text
+    ///
+    /// A line holding only `[role]`, `[constraints]`, `[examples]`,
+    /// `[output_format]` or `[<heading>]` starts a section of that kind. Text
+    /// before the first such line is the role.
+    ///
+    /// ```text
+    /// You are a {{language}} reviewer.
+    ///
+    /// [constraints]
+    /// - Only comment on changed lines.
+    ///
+    /// [output_format]
+    /// One finding per line.
+    /// ```
+    ///
+    /// Every section is checked for template errors.
+    pub fn from_template_str(source: &str) -> Result<Self, PromptError> {
+        let mut builder = Self::new();
+        let mut kind = SectionKind::Role;
+        let mut text = String::new();
+        let mut first_line = 1;
+        for (index, line) in source.lines().enumerate() {
+            let header = line
+                .trim()
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                .map(str::trim)
+                .filter(|name| !name.is_empty());
+            match header {
+                Some(name) => {
+                    builder.push(Section::new(kind, std::mem::take(&mut text), first_line));
+                    kind = SectionKind::from_header(name);
+                    first_line = index + 2;
+                },
+                None => {
+                    text.push_str(line);
+                    text.push('\n');
+                },
+            }
+        }
+        builder.push(Section::new(kind, text, first_line));
+
+        for section in &builder.sections {
+            section
+                .template
+                .check()
+                .map_err(at_line(section.first_line))?;
+        }
+        Ok(builder)
+    }
+
+    /// Set the role, which opens the prompt without a heading
+    pub fn role(self, text: impl Into<String>) -> Self {
+        self.with_section(SectionKind::Role, text.into())
+    }
+
+    /// Set the constraints section
+    pub fn constraints(self, text: impl Into<String>) -> Self {
+        self.with_section(SectionKind::Constraints, text.into())
+    }
+
+    /// Set the examples section
+    pub fn examples(self, text: impl Into<String>) -> Self {
+        self.with_section(SectionKind::Examples, text.into())
+    }
+
+    /// Set the output format section
+    pub fn output_format(self, text: impl Into<String>) -> Self {
+        self.with_section(SectionKind::OutputFormat, text.into())
+    }
+
+    /// Set a section with a custom heading
+    pub fn section(self, heading: impl Into<String>, text: impl Into<String>) -> Self {
+        self.with_section(SectionKind::Custom(heading.into()), text.into())
+    }
+
+    /// Set the value of a variable
+    pub fn var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Set the values of several variables
+    pub fn vars(mut self, vars: HashMap<String, String>) -> Self {
+        self.vars.extend(vars);
+        self
+    }
+
+    /// Append the rendered text to the preset prompt `name`, such as `claude_code`
+    pub fn preset(mut self, name: impl Into<String>) -> Self {
+        self.preset = Some(name.into());
+        self
+    }
+
+    /// Render the sections to text
+    pub fn render(&self) -> Result<String, PromptError> {
+        let mut parts = Vec::new();
+        for section in &self.sections {
+            let body = section
+                .template
+                .render(&self.vars)
+                .map_err(at_line(section.first_line))?;
+            let body = body.trim();
+            if body.is_empty() {
+                continue;
+            }
+            parts.push(match section.kind.heading() {
+                Some(heading) => format!("{}{}", heading, body),
+                None => body.to_string(),
+            });
+        }
+        Ok(parts.join("\n\n"))
+    }
+
+    /// Render the prompt: text, or a preset with the text appended
+    pub fn build(&self) -> Result<SystemPrompt, PromptError> {
+        let text = self.render()?;
+        Ok(match &self.preset {
+            Some(preset) if text.is_empty() => {
+                SystemPrompt::Preset(SystemPromptPreset::new(preset))
+            },
+            Some(preset) => SystemPrompt::Preset(SystemPromptPreset::with_append(preset, text)),
+            None => SystemPrompt::Text(text),
+        })
+    }
+
+    fn with_section(mut self, kind: SectionKind, source: String) -> Self {
+        self.push(Section::new(kind, source, 1));
+        self
+    }
+
+    /// Add `section`, replacing an earlier one of the same kind
+    fn push(&mut self, section: Section) {
+        match self.sections.iter_mut().find(|s| s.kind == section.kind) {
+            Some(existing) => *existing = section,
+            None => self.sections.push(section),
+        }
+    }
+}
+
+/// Move the position of a template error from a section to its file
+fn at_line(first_line: usize) -> impl Fn(PromptError) -> PromptError {
+    move |e| match e {
+        PromptError::Template {
+            line,
+            column,
+            message,
+        } => PromptError::Template {
+            line: line + first_line - 1,
+            column,
+            message,
+        },
+        e => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_and_variables() {
+        let builder = SystemPromptBuilder::new()
+            .role("You are a {{ language }} reviewer.")
+            .constraints("- Be brief")
+            .examples("Input: x\nOutput: y")
+            .output_format("JSON")
+            .section("Tone", "Friendly")
+            .section("## Notes", "None")
+            .vars(HashMap::from([(
+                "language".to_string(),
+                "Rust".to_string(),
+            )]));
+
+        assert_eq!(
+            builder.render().unwrap(),
+            "You are a Rust reviewer.\n\nConstraints:\n- Be brief\n\nExamples:\nInput: x\n\
+             Output: y\n\nOutput format:\nJSON\n\nTone:\nFriendly\n\n## Notes\n\nNone"
+        );
+        assert!(
+            matches!(builder.build().unwrap(), SystemPrompt::Text(text) if text.starts_with("You"))
+        );
+    }
+
+    #[test]
+    fn test_setting_a_section_again_replaces_it() {
+        let builder = SystemPromptBuilder::new()
+            .role("first")
+            .constraints("c")
+            .role("second");
+        assert_eq!(builder.render().unwrap(), "second\n\nConstraints:\nc");
+    }
+
+    #[test]
+    fn test_missing_variable() {
+        let err = SystemPromptBuilder::new()
+            .role("Hello {{name}}")
+            .render()
+            .unwrap_err();
+        assert!(matches!(err, PromptError::MissingVariable(ref name) if name == "name"));
+    }
+
+    #[test]
+    fn test_values_are_not_expanded() {
+        let prompt = SystemPromptBuilder::new()
+            .role("{{text}}")
+            .var("text", "{{literal}} {{#if x}}")
+            .render()
+            .unwrap();
+        assert_eq!(prompt, "{{literal}} {{#if x}}");
+    }
+
+    #[test]
+    fn test_conditional_section_is_dropped() {
+        let prompt = SystemPromptBuilder::new()
+            .role("Role")
+            .examples("{{#if examples}}{{examples}}{{/if}}")
+            .output_format("Text")
+            .render()
+            .unwrap();
+        assert_eq!(prompt, "Role\n\nOutput format:\nText");
+    }
+
+    #[test]
+    fn test_preset() {
+        let prompt = SystemPromptBuilder::new()
+            .role("Extra rules")
+            .preset("claude_code")
+            .build()
+            .unwrap();
+        assert!(matches!(
+            prompt,
+            SystemPrompt::Preset(ref preset)
+                if preset.preset == "claude_code" && preset.append.as_deref() == Some("Extra rules")
+        ));
+
+        let prompt = SystemPromptBuilder::new()
+            .preset("claude_code")
+            .build()
+            .unwrap();
+        assert!(matches!(prompt, SystemPrompt::Preset(ref preset) if preset.append.is_none()));
+    }
+
+    #[test]
+    fn test_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reviewer.txt");
+        std::fs::write(
+            &path,
+            "You review {{language}} code.\n\n[constraints]\n- Be kind\n\n[Checklist]\n\
+             {{#if tests}}\n- Tests pass\n{{/if}}\n[output_format]\nMarkdown\n",
+        )
+        .unwrap();
+
+        let builder = SystemPromptBuilder::from_file(&path)
+            .unwrap()
+            .var("language", "Rust");
+        assert_eq!(
+            builder.render().unwrap(),
+            "You review Rust code.\n\nConstraints:\n- Be kind\n\nOutput format:\nMarkdown"
+        );
+        assert_eq!(
+            builder.var("tests", "true").render().unwrap(),
+            "You review Rust code.\n\nConstraints:\n- Be kind\n\nChecklist:\n- Tests pass\n\n\
+             Output format:\nMarkdown"
+        );
+
+        // Positions are within the file
+        std::fs::write(&path, "Role\n\n[examples]\nok\n{{#if}}\n").unwrap();
+        let err = SystemPromptBuilder::from_file(&path).unwrap_err();
+        assert!(matches!(
+            err,
+            PromptError::File { ref source, .. }
+                if matches!(**source, PromptError::Template { line: 5, column: 1, .. })
+        ));
+        assert!(err.to_string().contains("reviewer.txt"));
+    }
+}
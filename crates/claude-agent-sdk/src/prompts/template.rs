@@ -0,0 +1,338 @@
+//! Minimal `{{variable}}` template engine with `{{#if}}` blocks
+
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use super::PromptError;
+
+/// Matches `{{ name }}` and `{{#if name}}` tags
+fn placeholder_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\{\{\s*(#if\s+)?([A-Za-z_][A-Za-z0-9_-]*)\s*\}\}").unwrap())
+}
+
+/// Prompt text with `{{variable}}` placeholders
+///
+/// - `{{name}}` is replaced with the value of the variable `name`. Whitespace
+///   inside the braces is ignored, so `{{ topic }}` and `{{topic}}` refer to
+///   the same variable. Values are inserted as is, so they may contain braces.
+/// - `{{#if name}}…{{else}}…{{/if}}` keeps the first part if `name` has a value
+///   that is neither empty nor `false`, and the `{{else}}` part otherwise. A
+///   block tag alone on its line takes the line with it.
+///
+/// Malformed templates fail with [`PromptError::Template`], giving the line
+/// and column of the problem.
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::prompts::PromptTemplate;
+/// use std::collections::HashMap;
+///
+/// let template = PromptTemplate::new("Summarize {{ topic }} in {{words}} words.");
+/// assert_eq!(template.variables(), vec!["topic", "words"]);
+///
+/// let vars = HashMap::from([
+///     ("topic".to_string(), "Rust".to_string()),
+///     ("words".to_string(), "50".to_string()),
+/// ]);
+/// assert_eq!(template.render(&vars).unwrap(), "Summarize Rust in 50 words.");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    source: String,
+}
+
+impl PromptTemplate {
+    /// Create a template from its source text
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+
+    /// The template source
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Names of the referenced variables, including `{{#if}}` conditions, in
+    /// order of first use
+    pub fn variables(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        placeholder_pattern()
+            .captures_iter(&self.source)
+            .filter(|caps| caps.get(1).is_some() || &caps[2] != "else")
+            .filter_map(|caps| caps.get(2).map(|m| m.as_str()))
+            .filter(|name| seen.insert(*name))
+            .collect()
+    }
+
+    /// Check the template for syntax errors without rendering it
+    pub fn check(&self) -> Result<(), PromptError> {
+        parse(&self.source).map(|_| ())
+    }
+
+    /// Substitute every placeholder with its value from `vars`
+    ///
+    /// Fails with [`PromptError::MissingVariable`] naming the first placeholder
+    /// without a value. Placeholders in a block that is left out need no value.
+    pub fn render(&self, vars: &HashMap<String, String>) -> Result<String, PromptError> {
+        let nodes = parse(&self.source)?;
+        let mut out = String::new();
+        render_nodes(&nodes, vars, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// Parsed template
+#[derive(Debug)]
+enum Node<'a> {
+    Text(&'a str),
+    Var(&'a str),
+    If {
+        name: &'a str,
+        then: Vec<Node<'a>>,
+        otherwise: Vec<Node<'a>>,
+    },
+}
+
+/// Tag of a `{{…}}` placeholder
+enum Tag<'a> {
+    Var(&'a str),
+    If(&'a str),
+    Else,
+    EndIf,
+}
+
+/// Open `{{#if}}` block while parsing
+struct Block<'a> {
+    name: &'a str,
+    /// Where the `{{#if}}` is, for reporting it unclosed
+    offset: usize,
+    then: Vec<Node<'a>>,
+    /// Set once `{{else}}` is seen
+    otherwise: Option<Vec<Node<'a>>>,
+}
+
+impl<'a> Block<'a> {
+    fn nodes(&mut self) -> &mut Vec<Node<'a>> {
+        self.otherwise.as_mut().unwrap_or(&mut self.then)
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Template error at byte `offset` of `source`
+fn template_error(source: &str, offset: usize, message: String) -> PromptError {
+    let before = &source[..offset];
+    let line = 1 + before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    PromptError::Template {
+        line,
+        column: before[line_start..].chars().count() + 1,
+        message,
+    }
+}
+
+fn parse(source: &str) -> Result<Vec<Node<'_>>, PromptError> {
+    let error = |offset, message: String| template_error(source, offset, message);
+    let mut root = Vec::new();
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = source[pos..].find("{{") {
+        let start = pos + found;
+        let inner_start = start + 2;
+        let Some(len) = source[inner_start..].find("}}") else {
+            return Err(error(start, "unclosed '{{'".to_string()));
+        };
+        let end = inner_start + len + 2;
+        let inner = source[inner_start..end - 2].trim();
+        let tag = if let Some(name) = inner.strip_prefix("#if") {
+            let name = name.trim();
+            if !inner[3..].starts_with(char::is_whitespace) || !is_variable_name(name) {
+                return Err(error(
+                    start,
+                    format!("expected '{{{{#if <variable>}}}}', found '{}'", inner),
+                ));
+            }
+            Tag::If(name)
+        } else if inner == "else" {
+            Tag::Else
+        } else if inner == "/if" {
+            Tag::EndIf
+        } else if is_variable_name(inner) {
+            Tag::Var(inner)
+        } else {
+            return Err(error(
+                start,
+                format!("invalid placeholder '{{{{{}}}}}'", inner),
+            ));
+        };
+
+        // A block tag alone on its line takes the whole line
+        let (text_end, next) = match tag {
+            Tag::Var(_) => (start, end),
+            _ => {
+                let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+                let rest = &source[end..];
+                let line_end = rest.find('\n').map_or(source.len(), |i| end + i + 1);
+                if source[line_start..start].trim().is_empty()
+                    && source[end..line_end].trim().is_empty()
+                {
+                    (line_start.max(pos), line_end)
+                } else {
+                    (start, end)
+                }
+            },
+        };
+
+        let nodes = blocks.last_mut().map_or(&mut root, |block| block.nodes());
+        if text_end > pos {
+            nodes.push(Node::Text(&source[pos..text_end]));
+        }
+        match tag {
+            Tag::Var(name) => nodes.push(Node::Var(name)),
+            Tag::If(name) => blocks.push(Block {
+                name,
+                offset: start,
+                then: Vec::new(),
+                otherwise: None,
+            }),
+            Tag::Else => match blocks.last_mut() {
+                Some(block) if block.otherwise.is_none() => block.otherwise = Some(Vec::new()),
+                Some(_) => return Err(error(start, "duplicate '{{else}}'".to_string())),
+                None => return Err(error(start, "'{{else}}' outside '{{#if}}'".to_string())),
+            },
+            Tag::EndIf => {
+                let Some(block) = blocks.pop() else {
+                    return Err(error(start, "'{{/if}}' without '{{#if}}'".to_string()));
+                };
+                let node = Node::If {
+                    name: block.name,
+                    then: block.then,
+                    otherwise: block.otherwise.unwrap_or_default(),
+                };
+                blocks
+                    .last_mut()
+                    .map_or(&mut root, |block| block.nodes())
+                    .push(node);
+            },
+        }
+        pos = next;
+    }
+
+    if let Some(block) = blocks.last() {
+        return Err(error(
+            block.offset,
+            format!("'{{{{#if {}}}}}' is never closed", block.name),
+        ));
+    }
+    if pos < source.len() {
+        root.push(Node::Text(&source[pos..]));
+    }
+    Ok(root)
+}
+
+fn render_nodes(
+    nodes: &[Node<'_>],
+    vars: &HashMap<String, String>,
+    out: &mut String,
+) -> Result<(), PromptError> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(name) => match vars.get(*name) {
+                Some(value) => out.push_str(value),
+                None => return Err(PromptError::MissingVariable(name.to_string())),
+            },
+            Node::If {
+                name,
+                then,
+                otherwise,
+            } => {
+                let set = vars
+                    .get(*name)
+                    .is_some_and(|value| !value.is_empty() && value != "false");
+                render_nodes(if set { then } else { otherwise }, vars, out)?;
+            },
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variables_are_unique_and_ordered() {
+        let template = PromptTemplate::new("{{b}} {{ a }} {{b}} {not} {{ 1x }}");
+        assert_eq!(template.variables(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_render_reports_missing_variable() {
+        let template = PromptTemplate::new("Hello {{name}}");
+        let err = template.render(&HashMap::new()).unwrap_err();
+        assert!(matches!(err, PromptError::MissingVariable(name) if name == "name"));
+    }
+
+    #[test]
+    fn test_render_does_not_expand_values() {
+        let template = PromptTemplate::new("{{a}}");
+        let vars = HashMap::from([("a".to_string(), "{{b}}".to_string())]);
+        assert_eq!(template.render(&vars).unwrap(), "{{b}}");
+    }
+
+    #[test]
+    fn test_variables_include_conditions() {
+        let template = PromptTemplate::new("{{#if a}}{{b}}{{else}}{{ c }}{{/if}}{{a}}");
+        assert_eq!(template.variables(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_conditionals() {
+        let template = PromptTemplate::new(
+            "Always.\n{{#if strict}}\nStrict mode.\n{{else}}\nRelaxed mode.\n{{/if}}\nDone.",
+        );
+        let render = |value: Option<&str>| {
+            let vars = value
+                .map(|value| HashMap::from([("strict".to_string(), value.to_string())]))
+                .unwrap_or_default();
+            template.render(&vars).unwrap()
+        };
+
+        assert_eq!(render(Some("yes")), "Always.\nStrict mode.\nDone.");
+        assert_eq!(render(Some("false")), "Always.\nRelaxed mode.\nDone.");
+        assert_eq!(render(Some("")), "Always.\nRelaxed mode.\nDone.");
+        assert_eq!(render(None), "Always.\nRelaxed mode.\nDone.");
+
+        // An unset variable inside a skipped block is not an error
+        let template = PromptTemplate::new("a{{#if flag}} {{missing}}{{/if}}");
+        assert_eq!(template.render(&HashMap::new()).unwrap(), "a");
+    }
+
+    #[test]
+    fn test_parse_errors_have_positions() {
+        let error = |source: &str| match PromptTemplate::new(source).check() {
+            Err(PromptError::Template { line, column, .. }) => (line, column),
+            other => panic!("expected a template error, got {:?}", other),
+        };
+
+        assert_eq!(error("Hello {{name"), (1, 7));
+        assert_eq!(error("line\n  {{bad name}}"), (2, 3));
+        assert_eq!(error("a\nb\n{{#if x}}\nc"), (3, 1));
+        assert_eq!(error("{{/if}}"), (1, 1));
+        assert_eq!(error("{{#if x}}{{else}}{{else}}{{/if}}"), (1, 18));
+        assert_eq!(error("é {{#each x}}"), (1, 3));
+    }
+}
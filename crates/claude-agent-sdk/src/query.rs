@@ -1,20 +1,35 @@
 //! Simple query function for one-shot interactions
 
-use crate::errors::Result;
+use crate::errors::{ClaudeError, Result, StructuredOutputError};
+use crate::internal::audit::ToolAudit;
 use crate::internal::client::InternalClient;
+use crate::internal::fallback::FallbackDetector;
+use crate::internal::query_metrics::QueryMetrics;
 use crate::internal::message_parser::MessageParser;
-use crate::internal::transport::subprocess::QueryPrompt;
+use crate::internal::transport::recording::record_if_requested;
+use crate::internal::transport::subprocess::{QueryPrompt, within_message_timeout};
 use crate::internal::transport::{SubprocessTransport, Transport};
-use crate::types::config::ClaudeAgentOptions;
-use crate::types::messages::{Message, UserContentBlock};
+use crate::internal::turns::TurnProgress;
+use crate::retry::RetryPolicy;
+use crate::types::config::{ClaudeAgentOptions, json_schema_output_format};
+use crate::types::messages::{CollectedResponse, ConversationTurn, Message, UserContentBlock};
 use futures::stream::{Stream, StreamExt};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::oneshot;
 
 /// Query Claude Code for one-shot interactions.
 ///
 /// This function is ideal for simple, stateless queries where you don't need
 /// bidirectional communication or conversation management.
 ///
+/// If the CLI's output ends before a result message, for example because the
+/// process crashed, this fails with [`ClaudeError::IncompleteTurn`](crate::ClaudeError::IncompleteTurn)
+/// instead of returning the partial messages. The streaming variants yield that
+/// error as their last item.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -46,11 +61,61 @@ pub async fn query(
 ) -> Result<Vec<Message>> {
     let query_prompt = QueryPrompt::Text(prompt.into());
     let opts = options.unwrap_or_default();
+    opts.check()?;
 
     let client = InternalClient::new(query_prompt, opts)?;
     client.execute().await
 }
 
+/// Query Claude through `transport` instead of a spawned CLI.
+///
+/// Works like [`query`], but the prompt goes through `transport` as a
+/// stream-json user message followed by the end of input. Use it with a
+/// [`MockTransport`](crate::testing::MockTransport) to test code built on
+/// one-shot queries, or with a [`SocketTransport`](crate::SocketTransport).
+/// Options that become CLI flags have no effect, as the transport decides how
+/// the CLI runs.
+///
+/// # Examples
+///
+/// ```
+/// use claude_agent_sdk::query_with_transport;
+/// use claude_agent_sdk::testing::MockTransport;
+///
+/// # async fn example() -> claude_agent_sdk::Result<()> {
+/// let transport = MockTransport::builder()
+///     .on_user_message("capital of France")
+///     .respond_assistant_text("Paris")
+///     .respond_result("Paris")
+///     .build();
+///
+/// let messages = query_with_transport("What is the capital of France?", None, Box::new(transport))
+///     .await?;
+/// assert_eq!(messages.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn query_with_transport(
+    prompt: impl Into<String>,
+    options: Option<ClaudeAgentOptions>,
+    transport: Box<dyn Transport>,
+) -> Result<Vec<Message>> {
+    let opts = options.unwrap_or_default();
+    opts.check()?;
+
+    let user_message = serde_json::json!({
+        "type": "user",
+        "message": {
+            "role": "user",
+            "content": prompt.into()
+        },
+        "session_id": "default"
+    });
+    let transport = record_if_requested(transport, &opts)?;
+    let client = InternalClient::with_transport(transport, Some(user_message), &opts);
+    client.execute().await
+}
+
 /// Query Claude Code with streaming responses for memory-efficient processing.
 ///
 /// Unlike `query()` which collects all messages in memory before returning,
@@ -93,35 +158,81 @@ pub async fn query_stream(
     prompt: impl Into<String>,
     options: Option<ClaudeAgentOptions>,
 ) -> Result<Pin<Box<dyn Stream<Item = Result<Message>> + Send>>> {
-    let query_prompt = QueryPrompt::Text(prompt.into());
-    let opts = options.unwrap_or_default();
-
-    let mut transport = SubprocessTransport::new(query_prompt, opts)?;
-    transport.connect().await?;
+    stream_prompt(QueryPrompt::Text(prompt.into()), options).await
+}
 
-    // Move transport into the stream to extend its lifetime
-    let stream = async_stream::stream! {
-        let mut message_stream = transport.read_messages();
-        while let Some(json_result) = message_stream.next().await {
-            match json_result {
-                Ok(json) => {
-                    match MessageParser::parse(json) {
-                        Ok(message) => yield Ok(message),
-                        Err(e) => {
-                            yield Err(e);
-                            break;
-                        }
-                    }
-                }
-                Err(e) => {
-                    yield Err(e);
-                    break;
-                }
-            }
-        }
-    };
+/// Query Claude Code, giving up if the CLI goes quiet for longer than `timeout`.
+///
+/// The timeout bounds the gap between consecutive messages, not the whole run, so
+/// long multi-turn queries succeed as long as messages keep arriving. When it is
+/// exceeded the CLI process is killed and
+/// [`ClaudeError::MessageTimeout`](crate::ClaudeError::MessageTimeout) is returned.
+/// This is [`query`] with [`ClaudeAgentOptions::message_timeout`] set.
+///
+/// # Examples
+///
+/// ```no_run
+/// use claude_agent_sdk::{ClaudeError, query_with_timeout};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     match query_with_timeout("What is 2 + 2?", None, Duration::from_secs(60)).await {
+///         Ok(messages) => println!("Received {} messages", messages.len()),
+///         Err(ClaudeError::MessageTimeout { timeout }) => {
+///             println!("CLI stalled for {:?}", timeout)
+///         }
+///         Err(e) => return Err(e.into()),
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn query_with_timeout(
+    prompt: impl Into<String>,
+    options: Option<ClaudeAgentOptions>,
+    timeout: Duration,
+) -> Result<Vec<Message>> {
+    let mut opts = options.unwrap_or_default();
+    opts.message_timeout = Some(timeout);
+    query(prompt, Some(opts)).await
+}
 
-    Ok(Box::pin(stream))
+/// Query Claude Code, retrying transient failures according to `policy`.
+///
+/// Each attempt runs [`query`] from scratch with a copy of `options`, so a
+/// failed attempt's partial output is discarded. See [`RetryPolicy`] for which
+/// errors are retried and how attempts are spaced.
+///
+/// # Errors
+///
+/// Returns [`ClaudeError::RetriesExhausted`](crate::ClaudeError::RetriesExhausted),
+/// wrapping the last failure, if retries did not help, or the error of a
+/// failure the policy does not retry.
+///
+/// # Examples
+///
+/// ```no_run
+/// use claude_agent_sdk::{RetryPolicy, query_with_retry};
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let policy = RetryPolicy::new(3)
+///         .on_retry(|attempt, e| eprintln!("Attempt {} failed: {}", attempt, e));
+///     let messages = query_with_retry("What is 2 + 2?", None, &policy).await?;
+///     println!("Received {} messages", messages.len());
+///     Ok(())
+/// }
+/// ```
+pub async fn query_with_retry(
+    prompt: impl Into<String>,
+    options: Option<ClaudeAgentOptions>,
+    policy: &RetryPolicy,
+) -> Result<Vec<Message>> {
+    let prompt = prompt.into();
+    let options = options.unwrap_or_default();
+    policy
+        .retry(|| query(prompt.clone(), Some(options.clone())))
+        .await
 }
 
 /// Query Claude Code with structured content blocks (supports images).
@@ -132,7 +243,8 @@ pub async fn query_stream(
 /// # Errors
 ///
 /// Returns an error if:
-/// - The content vector is empty (must include at least one text or image block)
+/// - The content vector is empty (must include at least one text, image or document block)
+/// - [`ClaudeAgentOptions::validate`] reports an error
 /// - Claude CLI cannot be found or started
 /// - The query execution fails
 ///
@@ -146,7 +258,7 @@ pub async fn query_stream(
 ///     // Create content with text and image
 ///     let content = vec![
 ///         UserContentBlock::text("What's in this image?"),
-///         UserContentBlock::image_url("https://example.com/image.png"),
+///         UserContentBlock::image_url("https://example.com/image.png")?,
 ///     ];
 ///
 ///     let messages = query_with_content(content, None).await?;
@@ -173,6 +285,7 @@ pub async fn query_with_content(
 
     let query_prompt = QueryPrompt::Content(content_blocks);
     let opts = options.unwrap_or_default();
+    opts.check()?;
 
     let client = InternalClient::new(query_prompt, opts)?;
     client.execute().await
@@ -186,7 +299,8 @@ pub async fn query_with_content(
 /// # Errors
 ///
 /// Returns an error if:
-/// - The content vector is empty (must include at least one text or image block)
+/// - The content vector is empty (must include at least one text, image or document block)
+/// - [`ClaudeAgentOptions::validate`] reports an error
 /// - Claude CLI cannot be found or started
 /// - The streaming connection fails
 ///
@@ -229,32 +343,288 @@ pub async fn query_stream_with_content(
     let content_blocks = content.into();
     UserContentBlock::validate_content(&content_blocks)?;
 
-    let query_prompt = QueryPrompt::Content(content_blocks);
+    stream_prompt(QueryPrompt::Content(content_blocks), options).await
+}
+
+/// Query Claude Code with prior conversation turns as the prompt.
+///
+/// The turns are written to the CLI as one stream-json message each, in order,
+/// and Claude answers the last one, which must be a user turn. Use this to
+/// replay a transcript or to seed few-shot examples.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - [`ConversationTurn::validate_history`] rejects the turns, for example
+///   because the last turn is an assistant turn or an assistant turn holds an image
+/// - [`ClaudeAgentOptions::validate`] reports an error
+/// - Claude CLI cannot be found or started
+///
+/// # Examples
+///
+/// ```no_run
+/// use claude_agent_sdk::{ConversationTurn, query_with_history};
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let history = vec![
+///         ConversationTurn::user("Translate 'cat' to French"),
+///         ConversationTurn::assistant("chat"),
+///         ConversationTurn::user("Translate 'dog' to French"),
+///     ];
+///
+///     let messages = query_with_history(history, None).await?;
+///     println!("Received {} messages", messages.len());
+///     Ok(())
+/// }
+/// ```
+pub async fn query_with_history(
+    turns: Vec<ConversationTurn>,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<Vec<Message>> {
+    ConversationTurn::validate_history(&turns)?;
+
+    let query_prompt = QueryPrompt::History(turns);
     let opts = options.unwrap_or_default();
+    opts.check()?;
 
-    let mut transport = SubprocessTransport::new(query_prompt, opts)?;
-    transport.connect().await?;
+    let client = InternalClient::new(query_prompt, opts)?;
+    client.execute().await
+}
+
+/// Query Claude Code with prior conversation turns, streaming the answer.
+///
+/// The streaming variant of [`query_with_history`].
+///
+/// # Errors
+///
+/// Same as [`query_with_history`].
+pub async fn query_stream_with_history(
+    turns: Vec<ConversationTurn>,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<Pin<Box<dyn Stream<Item = Result<Message>> + Send>>> {
+    ConversationTurn::validate_history(&turns)?;
+    stream_prompt(QueryPrompt::History(turns), options).await
+}
+
+/// Start the CLI with `query_prompt` and stream its messages
+async fn stream_prompt(
+    query_prompt: QueryPrompt,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<Pin<Box<dyn Stream<Item = Result<Message>> + Send>>> {
+    let opts = options.unwrap_or_default();
+    opts.check()?;
+
+    let message_timeout = opts.message_timeout;
+    let strict_parsing = opts.strict_parsing;
+    let mut fallback = FallbackDetector::new(&opts);
+    let mut metrics = QueryMetrics::new(&opts);
+    let mut audit = ToolAudit::new(&opts);
+    let transport = SubprocessTransport::new(query_prompt, opts)?;
+    let diagnostics = transport.diagnostics();
+    let mut transport = transport.into_boxed()?;
+    metrics.start();
+    if let Err(e) = transport.connect().await {
+        metrics.fail();
+        return Err(diagnostics.explain(e, transport.exit_info().await).await);
+    }
 
     let stream = async_stream::stream! {
         let mut message_stream = transport.read_messages();
-        while let Some(json_result) = message_stream.next().await {
+        let mut progress = TurnProgress::default();
+        loop {
+            let next = within_message_timeout(message_timeout, message_stream.next()).await;
+            let json_result = match next {
+                Ok(Some(json_result)) => json_result,
+                Ok(None) => break,
+                Err(e) => {
+                    // The CLI stalled; don't leave it running
+                    drop(message_stream);
+                    transport.abort().await;
+                    metrics.fail();
+                    yield Err(e);
+                    return;
+                }
+            };
             match json_result {
                 Ok(json) => {
-                    match MessageParser::parse(json) {
-                        Ok(message) => yield Ok(message),
+                    match MessageParser::parse_with(json, strict_parsing) {
+                        Ok(mut message) => {
+                            fallback.observe(&mut message);
+                            metrics.observe(&message);
+                            audit.observe(&message);
+                            progress.observe(&message);
+                            yield Ok(message)
+                        },
                         Err(e) => {
+                            metrics.fail();
                             yield Err(e);
-                            break;
+                            return;
                         }
                     }
                 }
                 Err(e) => {
+                    metrics.fail();
                     yield Err(e);
-                    break;
+                    return;
                 }
             }
         }
+
+        // The output ended cleanly but the CLI never reported a result
+        drop(message_stream);
+        if !progress.seen_result() {
+            metrics.fail();
+            let incomplete = progress.incomplete(transport.exit_info().await);
+            yield Err(diagnostics.explain(incomplete, transport.exit_info().await).await);
+        }
     };
 
     Ok(Box::pin(stream))
 }
+
+/// Query Claude Code for output matching the type `T`.
+///
+/// The JSON schema of `T` is generated with `schemars` and passed to the CLI as
+/// [`ClaudeAgentOptions::output_format`], replacing any format set in `options`.
+/// The structured output of the result is deserialized into `T`; when the CLI
+/// reports none, the last fenced JSON block of Claude's answer is used instead.
+///
+/// # Errors
+///
+/// Besides the errors of [`query`], fails with
+/// [`ClaudeError::StructuredOutput`] if the answer holds no output matching
+/// `T`. Its `text` is the text Claude answered with, for debugging the prompt.
+///
+/// # Examples
+///
+/// ```no_run
+/// use claude_agent_sdk::query_typed;
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Capital {
+///     city: String,
+///     population: u64,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let capital: Capital = query_typed("What is the capital of France?", None).await?;
+///     println!("{} has {} inhabitants", capital.city, capital.population);
+///     Ok(())
+/// }
+/// ```
+pub async fn query_typed<T>(
+    prompt: impl Into<String>,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<T>
+where
+    T: DeserializeOwned + JsonSchema,
+{
+    let messages = query(prompt, Some(typed_options::<T>(options))).await?;
+    typed_result(&CollectedResponse { messages })
+}
+
+/// Query Claude Code for output matching the type `T`, streaming the messages.
+///
+/// Like [`query_typed`], but returns the messages as they arrive along with a
+/// receiver for the typed output. The receiver resolves once the stream has
+/// yielded the result message, so the stream must be polled to completion.
+/// If the stream fails first, the receiver gets a
+/// [`ClaudeError::StructuredOutput`] describing the failure.
+///
+/// # Examples
+///
+/// ```no_run
+/// use claude_agent_sdk::{Message, query_stream_typed};
+/// use futures::StreamExt;
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Summary {
+///     title: String,
+///     bullet_points: Vec<String>,
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> anyhow::Result<()> {
+///     let (mut stream, summary) =
+///         query_stream_typed::<Summary>("Summarize the README", None).await?;
+///     while let Some(message) = stream.next().await {
+///         if let Message::Assistant(_) = message? {
+///             println!("...");
+///         }
+///     }
+///     let summary = summary.await??;
+///     println!("{}: {:?}", summary.title, summary.bullet_points);
+///     Ok(())
+/// }
+/// ```
+pub async fn query_stream_typed<T>(
+    prompt: impl Into<String>,
+    options: Option<ClaudeAgentOptions>,
+) -> Result<(
+    Pin<Box<dyn Stream<Item = Result<Message>> + Send>>,
+    oneshot::Receiver<Result<T>>,
+)>
+where
+    T: DeserializeOwned + JsonSchema + Send + 'static,
+{
+    let mut messages = query_stream(prompt, Some(typed_options::<T>(options))).await?;
+    let (result_tx, result_rx) = oneshot::channel();
+
+    let stream = async_stream::stream! {
+        let mut collected = CollectedResponse::default();
+        let mut result_tx = Some(result_tx);
+        while let Some(item) = messages.next().await {
+            match &item {
+                Ok(message) => {
+                    collected.messages.push(message.clone());
+                    if let Message::Result(_) = message
+                        && let Some(tx) = result_tx.take()
+                    {
+                        let _ = tx.send(typed_result(&collected));
+                    }
+                },
+                Err(e) => {
+                    if let Some(tx) = result_tx.take() {
+                        let error = StructuredOutputError {
+                            message: format!("query failed before its result: {}", e),
+                            raw: None,
+                            text: Some(collected.text()),
+                        };
+                        let _ = tx.send(Err(error.into()));
+                    }
+                },
+            }
+            yield item;
+        }
+    };
+
+    Ok((Box::pin(stream), result_rx))
+}
+
+/// `options` asking for output matching `T`
+fn typed_options<T: JsonSchema>(options: Option<ClaudeAgentOptions>) -> ClaudeAgentOptions {
+    let mut options = options.unwrap_or_default();
+    options.output_format = Some(json_schema_output_format::<T>());
+    options
+}
+
+/// Deserialize the structured output of `response`, keeping Claude's text on failure
+fn typed_result<T: DeserializeOwned>(response: &CollectedResponse) -> Result<T> {
+    response.structured_as(true).map_err(|e| match e {
+        ClaudeError::StructuredOutput(error) => {
+            let text = response.text();
+            let text = match response.result().and_then(|r| r.result.clone()) {
+                Some(result) if text.is_empty() => result,
+                _ => text,
+            };
+            error.with_text(text).into()
+        },
+        other => other,
+    })
+}
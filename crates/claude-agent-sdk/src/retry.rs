@@ -0,0 +1,351 @@
+//! Retrying transient CLI failures with exponential backoff
+//!
+//! A [`RetryPolicy`] decides which errors are worth another attempt and how
+//! long to wait between attempts. It is honored by
+//! [`query_with_retry`](crate::query::query_with_retry) and
+//! [`SubagentExecutor`](crate::subagents::SubagentExecutor).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::{ClaudeError, RetryPolicy, query_with_retry};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> claude_agent_sdk::Result<()> {
+//! let policy = RetryPolicy::new(4)
+//!     .with_backoff(Duration::from_millis(500), Duration::from_secs(10))
+//!     .retry_on(|e| matches!(e, ClaudeError::Process(_) | ClaudeError::JsonDecode(_)))
+//!     .on_retry(|attempt, e| eprintln!("attempt {} failed: {}", attempt, e));
+//!
+//! let messages = query_with_retry("What is 2 + 2?", None, &policy).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::errors::{ClaudeError, Result};
+
+/// Predicate deciding whether an error is worth another attempt
+pub type RetryPredicate = Arc<dyn Fn(&ClaudeError) -> bool + Send + Sync>;
+
+/// Callback told about each failed attempt that will be retried
+///
+/// Receives the number of the attempt that failed, starting at 1, and its error.
+pub type RetryCallback = Arc<dyn Fn(u32, &ClaudeError) + Send + Sync>;
+
+/// When and how often to retry a failed operation
+///
+/// The delay before retry `n` is `initial_backoff * 2^(n - 1)`, plus up to
+/// `jitter` of that as random extra, capped at `max_backoff`.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first; `1` disables retries
+    pub max_attempts: u32,
+
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+
+    /// Upper bound on any delay
+    pub max_backoff: Duration,
+
+    /// Random extra delay, as a fraction of the delay (0.0 to 1.0)
+    pub jitter: f64,
+
+    /// Which errors are retried; by default [`RetryPolicy::is_transient`]
+    pub retry_on: RetryPredicate,
+
+    /// Called before each retry
+    pub on_retry: Option<RetryCallback>,
+}
+
+impl RetryPolicy {
+    /// Create a policy making at most `max_attempts` attempts, with default backoff
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Set the first and the largest delay between attempts
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Set the random extra delay, as a fraction of the delay
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Retry only errors for which `predicate` returns true
+    pub fn retry_on(
+        mut self,
+        predicate: impl Fn(&ClaudeError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_on = Arc::new(predicate);
+        self
+    }
+
+    /// Call `callback` with the attempt number and error before each retry
+    pub fn on_retry(
+        mut self,
+        callback: impl Fn(u32, &ClaudeError) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_retry = Some(Arc::new(callback));
+        self
+    }
+
+    /// Whether `error` is a failure that may succeed on another attempt
+    ///
+    /// The same as [`ClaudeError::is_retryable`]: true for process, connection
+    /// and JSON decode errors and timeouts, which happen when the CLI fails to
+    /// spawn, is updating itself or is slow.
+    pub fn is_transient(error: &ClaudeError) -> bool {
+        error.is_retryable()
+    }
+
+    /// Run `operation` until it succeeds, fails with an error that is not
+    /// retried, or runs out of attempts
+    ///
+    /// Each retry is logged as a tracing warning and reported to the
+    /// `on_retry` callback.
+    ///
+    /// # Errors
+    ///
+    /// If more than one attempt was made, returns
+    /// [`ClaudeError::RetriesExhausted`] wrapping the last error. An error from
+    /// a single attempt is returned as is.
+    pub async fn retry<T, F, Fut>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let error = match operation().await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            if attempt >= max_attempts || !(self.retry_on)(&error) {
+                return Err(if attempt == 1 {
+                    error
+                } else {
+                    ClaudeError::RetriesExhausted {
+                        attempts: attempt,
+                        last_error: Box::new(error),
+                    }
+                });
+            }
+
+            let delay = self.delay(attempt);
+            warn!(
+                attempt,
+                max_attempts,
+                delay_ms = delay.as_millis() as u64,
+                error = %error,
+                "Retrying after transient failure"
+            );
+            if let Some(on_retry) = &self.on_retry {
+                on_retry(attempt, &error);
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Delay after failed attempt `attempt` (starting at 1)
+    fn delay(&self, attempt: u32) -> Duration {
+        let base = self
+            .initial_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt - 1))
+            .min(self.max_backoff);
+
+        // Simple jitter using system time nanoseconds as entropy
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let fraction = f64::from(nanos % 1000) / 1000.0;
+        base.mul_f64(1.0 + self.jitter * fraction)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, 200ms to 5s apart with 20% jitter, retrying transient errors
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            jitter: 0.2,
+            retry_on: Arc::new(Self::is_transient),
+            on_retry: None,
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("jitter", &self.jitter)
+            .field("retry_on", &"<function>")
+            .field("on_retry", &self.on_retry.as_ref().map(|_| "<function>"))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ProcessError;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn process_error() -> ClaudeError {
+        ClaudeError::Process(ProcessError::new("spawn race", None, None))
+    }
+
+    fn fast(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy::new(max_attempts)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+            .with_jitter(0.0)
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let calls = AtomicU32::new(0);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_by_callback = seen.clone();
+        let policy = fast(3).on_retry(move |attempt, e| {
+            seen_by_callback
+                .lock()
+                .unwrap()
+                .push((attempt, e.to_string()))
+        });
+
+        let value = policy
+            .retry(|| async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(process_error())
+                } else {
+                    Ok("done")
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(value, "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].0, 1);
+        assert_eq!(seen[1].0, 2);
+        assert!(seen[0].1.contains("spawn race"));
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_error_wraps_last_failure() {
+        let calls = AtomicU32::new(0);
+
+        let err = fast(3)
+            .retry(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(process_error())
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        match &err {
+            ClaudeError::RetriesExhausted {
+                attempts,
+                last_error,
+            } => {
+                assert_eq!(*attempts, 3);
+                assert!(matches!(**last_error, ClaudeError::Process(_)));
+            },
+            other => panic!("expected RetriesExhausted, got {other:?}"),
+        }
+        assert!(err.to_string().contains("3 attempts"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_errors_not_retried_are_returned_as_is() {
+        let calls = AtomicU32::new(0);
+
+        let err = fast(5)
+            .retry(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(ClaudeError::InvalidConfig("permission denied".to_string()))
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(err, ClaudeError::InvalidConfig(_)));
+    }
+
+    #[tokio::test]
+    async fn test_custom_predicate() {
+        let calls = AtomicU32::new(0);
+        let policy = fast(4).retry_on(|e| matches!(e, ClaudeError::InvalidConfig(_)));
+
+        let err = policy
+            .retry(|| async {
+                if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                    Err::<(), _>(ClaudeError::InvalidConfig("flaky".to_string()))
+                } else {
+                    Err(process_error())
+                }
+            })
+            .await
+            .unwrap_err();
+
+        // The process error is not retried by this predicate
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(matches!(
+            err,
+            ClaudeError::RetriesExhausted { attempts: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_delay_backs_off_exponentially_up_to_max() {
+        let policy = RetryPolicy::new(10)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(1000))
+            .with_jitter(0.0);
+
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(4), Duration::from_millis(800));
+        assert_eq!(policy.delay(5), Duration::from_millis(1000));
+        assert_eq!(policy.delay(40), Duration::from_millis(1000));
+
+        let jittered = policy.with_jitter(0.5).delay(1);
+        assert!(jittered >= Duration::from_millis(100) && jittered <= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(RetryPolicy::is_transient(&process_error()));
+        assert!(!RetryPolicy::is_transient(&ClaudeError::InvalidConfig(
+            "x".to_string()
+        )));
+        assert!(!RetryPolicy::is_transient(&ClaudeError::TurnInProgress));
+    }
+}
@@ -0,0 +1,346 @@
+//! Pluggable secret resolution for API keys and tokens
+//!
+//! Sensitive values such as `ANTHROPIC_API_KEY` should not live in
+//! [`ClaudeAgentOptions::env`](crate::ClaudeAgentOptions::env) as plain strings.
+//! Instead, configure a [`SecretProvider`] via
+//! [`ClaudeAgentOptions::secret_provider`](crate::ClaudeAgentOptions::secret_provider)
+//! and the SDK will resolve the names listed in
+//! [`ClaudeAgentOptions::secret_env`](crate::ClaudeAgentOptions::secret_env)
+//! only when the CLI process is spawned.
+//!
+//! Resolved values are held in a [`SecretString`], which is zeroized when dropped,
+//! and are injected into the child environment without being copied into the
+//! options or the transport. See [`SecretProvider`] for the one copy made when
+//! spawning.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::ClaudeAgentOptions;
+//! use claude_agent_sdk::secrets::CommandSecretProvider;
+//! use std::sync::Arc;
+//!
+//! let provider = CommandSecretProvider::new("op", ["read", "op://Private/anthropic/{key}"]);
+//!
+//! let options = ClaudeAgentOptions::builder()
+//!     .secret_provider(Arc::new(provider))
+//!     .build();
+//! ```
+
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use crate::errors::{ClaudeError, Result};
+use crate::types::config::ClaudeAgentOptions;
+
+pub use secrecy::{ExposeSecret, SecretString};
+
+/// Environment variables resolved through the secret provider by default
+pub const DEFAULT_SECRET_ENV: &[&str] = &["ANTHROPIC_API_KEY"];
+
+/// Placeholder replaced with the requested key in [`CommandSecretProvider`] arguments
+const KEY_PLACEHOLDER: &str = "{key}";
+
+/// Source of sensitive configuration values
+///
+/// Implementations are called lazily, once per key, each time a CLI process is spawned.
+///
+/// The returned secret is zeroized once the process is spawned. Spawning also
+/// copies it into the environment of the process command as an ordinary string,
+/// which is freed but not overwritten when the command is dropped right after
+/// the spawn.
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// Resolve the secret stored under `key`
+    async fn get(&self, key: &str) -> Result<SecretString>;
+
+    /// Human-readable provider name used in error messages
+    fn name(&self) -> &str;
+}
+
+/// Reads secrets from environment variables of the current process
+///
+/// With a prefix, the key `ANTHROPIC_API_KEY` is looked up as
+/// `<prefix>ANTHROPIC_API_KEY`, which allows keeping the value under a name
+/// the child process does not inherit automatically.
+#[derive(Debug, Clone, Default)]
+pub struct EnvSecretProvider {
+    prefix: Option<String>,
+}
+
+impl EnvSecretProvider {
+    /// Create a provider that reads variables by their exact name
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a provider that prepends `prefix` to every key
+    pub fn with_prefix(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: Some(prefix.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for EnvSecretProvider {
+    async fn get(&self, key: &str) -> Result<SecretString> {
+        let var = match &self.prefix {
+            Some(prefix) => format!("{}{}", prefix, key),
+            None => key.to_string(),
+        };
+
+        std::env::var(&var)
+            .map(SecretString::from)
+            .map_err(|e| ClaudeError::NotFound(format!("environment variable {}: {}", var, e)))
+    }
+
+    fn name(&self) -> &str {
+        "env"
+    }
+}
+
+/// Reads secrets from files named after the key inside a directory
+///
+/// This matches the layout used by Docker and Kubernetes secret mounts,
+/// e.g. `/run/secrets/ANTHROPIC_API_KEY`. Trailing newlines are stripped.
+#[derive(Debug, Clone)]
+pub struct FileSecretProvider {
+    dir: PathBuf,
+}
+
+impl FileSecretProvider {
+    /// Create a provider reading from `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for FileSecretProvider {
+    async fn get(&self, key: &str) -> Result<SecretString> {
+        if key.contains(['/', '\\']) || key == ".." {
+            return Err(ClaudeError::InvalidInput(format!(
+                "secret key '{}' is not a valid file name",
+                key
+            )));
+        }
+
+        let path = self.dir.join(key);
+        let mut contents = tokio::fs::read_to_string(&path).await?;
+        let trimmed_len = contents.trim_end_matches(['\r', '\n']).len();
+        contents.truncate(trimmed_len);
+
+        Ok(SecretString::from(contents))
+    }
+
+    fn name(&self) -> &str {
+        "file"
+    }
+}
+
+/// Resolves secrets by running an external command and reading its stdout
+///
+/// Any `{key}` in the arguments is replaced with the requested key, so
+/// password-manager CLIs such as `op read op://vault/item/{key}` can be used directly.
+#[derive(Debug, Clone)]
+pub struct CommandSecretProvider {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandSecretProvider {
+    /// Create a provider that runs `program` with `args`
+    pub fn new<I, S>(program: impl Into<String>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for CommandSecretProvider {
+    async fn get(&self, key: &str) -> Result<SecretString> {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| arg.replace(KEY_PLACEHOLDER, key))
+            .collect();
+
+        let output = tokio::process::Command::new(&self.program)
+            .args(&args)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(ClaudeError::InternalError(format!(
+                "`{}` exited with status {}",
+                self.program, output.status
+            )));
+        }
+
+        let mut value = String::from_utf8(output.stdout).map_err(|_| {
+            ClaudeError::InternalError(format!("`{}` produced non-UTF-8 output", self.program))
+        })?;
+        let trimmed_len = value.trim_end_matches(['\r', '\n']).len();
+        value.truncate(trimmed_len);
+
+        Ok(SecretString::from(value))
+    }
+
+    fn name(&self) -> &str {
+        "command"
+    }
+}
+
+/// Resolve every name in `options.secret_env` through the configured provider
+///
+/// Returns an empty list when no provider is configured. Failures are reported
+/// as [`ClaudeError::InvalidConfig`] naming both the key and the provider.
+pub(crate) async fn resolve_secret_env(
+    options: &ClaudeAgentOptions,
+) -> Result<Vec<(String, SecretString)>> {
+    let Some(provider) = &options.secret_provider else {
+        return Ok(Vec::new());
+    };
+
+    let mut resolved = Vec::with_capacity(options.secret_env.len());
+    for key in &options.secret_env {
+        let secret = provider.get(key).await.map_err(|e| {
+            ClaudeError::InvalidConfig(format!(
+                "Failed to resolve secret '{}' from {} provider: {}",
+                key,
+                provider.name(),
+                e
+            ))
+        })?;
+        resolved.push((key.clone(), secret));
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FakeProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl SecretProvider for FakeProvider {
+        async fn get(&self, key: &str) -> Result<SecretString> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match key {
+                "ANTHROPIC_API_KEY" => Ok(SecretString::from("sk-test-123")),
+                _ => Err(ClaudeError::NotFound(key.to_string())),
+            }
+        }
+
+        fn name(&self) -> &str {
+            "fake"
+        }
+    }
+
+    fn options_with_fake(calls: &Arc<AtomicUsize>) -> ClaudeAgentOptions {
+        ClaudeAgentOptions::builder()
+            .secret_provider(Arc::new(FakeProvider {
+                calls: Arc::clone(calls),
+            }))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_resolution_is_deferred_until_requested() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let options = options_with_fake(&calls);
+        let _cloned = options.clone();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let resolved = resolve_secret_env(&options).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].0, "ANTHROPIC_API_KEY");
+        assert_eq!(resolved[0].1.expose_secret(), "sk-test-123");
+    }
+
+    #[tokio::test]
+    async fn test_no_provider_resolves_nothing() {
+        let options = ClaudeAgentOptions::default();
+        assert_eq!(options.secret_env, vec!["ANTHROPIC_API_KEY".to_string()]);
+        assert!(resolve_secret_env(&options).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failure_names_key_and_provider() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut options = options_with_fake(&calls);
+        options.secret_env = vec!["MISSING_TOKEN".to_string()];
+
+        let err = resolve_secret_env(&options).await.unwrap_err();
+        assert!(matches!(err, ClaudeError::InvalidConfig(_)));
+        let msg = err.to_string();
+        assert!(msg.contains("MISSING_TOKEN"));
+        assert!(msg.contains("fake provider"));
+    }
+
+    #[test]
+    fn test_secret_string_debug_is_redacted() {
+        let secret = SecretString::from("sk-very-secret");
+        let debug = format!("{:?}", secret);
+        assert!(!debug.contains("sk-very-secret"));
+        assert!(debug.contains("REDACTED"));
+    }
+
+    #[test]
+    fn test_secret_string_zeroizes_on_drop() {
+        // SecretString implements ZeroizeOnDrop, which guarantees the buffer is wiped
+        fn assert_zeroize_on_drop<T: secrecy::zeroize::ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<SecretString>();
+    }
+
+    #[tokio::test]
+    async fn test_env_provider_with_prefix() {
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("SDK_SECRET_TEST_ANTHROPIC_API_KEY", "from-env") };
+
+        let provider = EnvSecretProvider::with_prefix("SDK_SECRET_TEST_");
+        let secret = provider.get("ANTHROPIC_API_KEY").await.unwrap();
+        assert_eq!(secret.expose_secret(), "from-env");
+
+        assert!(provider.get("UNSET_VARIABLE").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_strips_trailing_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("ANTHROPIC_API_KEY"), "sk-file\n").unwrap();
+
+        let provider = FileSecretProvider::new(dir.path());
+        let secret = provider.get("ANTHROPIC_API_KEY").await.unwrap();
+        assert_eq!(secret.expose_secret(), "sk-file");
+
+        assert!(provider.get("../etc/passwd").await.is_err());
+        assert!(provider.get("MISSING").await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_provider_substitutes_key() {
+        let provider = CommandSecretProvider::new("echo", ["value-for-{key}"]);
+        let secret = provider.get("ANTHROPIC_API_KEY").await.unwrap();
+        assert_eq!(secret.expose_secret(), "value-for-ANTHROPIC_API_KEY");
+
+        let failing = CommandSecretProvider::new("false", Vec::<String>::new());
+        assert!(failing.get("ANTHROPIC_API_KEY").await.is_err());
+    }
+}
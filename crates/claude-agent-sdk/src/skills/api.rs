@@ -1,12 +1,18 @@
 //! Skills API Client
 //!
 //! HTTP client for interacting with the Anthropic Skills API.
-//! Supports uploading, listing, and deleting skills.
+//! Supports uploading, listing, downloading, installing, and deleting skills.
 
-use reqwest::Client;
-use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use sha2::{Digest, Sha256};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
 
+use super::auditor::{AuditError, SkillAuditReport, SkillAuditor};
+use super::skill_md::{SkillMdError, SkillMdFile};
+
 /// Errors that can occur when interacting with the Skills API
 #[derive(Debug, Error)]
 pub enum SkillsError {
@@ -29,6 +35,150 @@ pub enum SkillsError {
     /// Skill not found
     #[error("Skill not found: {0}")]
     SkillNotFound(String),
+
+    /// Downloaded archive does not match the checksum sent by the API
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// Checksum reported by the API
+        expected: String,
+        /// Checksum of the received files
+        actual: String,
+    },
+
+    /// Archive contents cannot be installed as a skill directory
+    #[error("Invalid skill archive: {0}")]
+    InvalidArchive(String),
+
+    /// A different version of the skill is already installed
+    #[error("Skill '{skill}' version {installed} is already installed (requested {requested}); use force to overwrite")]
+    VersionConflict {
+        /// Skill name
+        skill: String,
+        /// Version found on disk
+        installed: String,
+        /// Version being installed
+        requested: String,
+    },
+
+    /// Installed SKILL.md could not be parsed
+    #[error("Invalid SKILL.md: {0}")]
+    SkillMd(#[from] SkillMdError),
+
+    /// Auditing the installed skill failed
+    #[error("Audit failed: {0}")]
+    Audit(#[from] AuditError),
+}
+
+/// HTTP method of a [`TransportRequest`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    /// GET
+    Get,
+    /// POST
+    Post,
+    /// DELETE
+    Delete,
+}
+
+/// Request handed to a [`SkillsTransport`]
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    /// HTTP method
+    pub method: HttpMethod,
+    /// Full URL, including query parameters
+    pub url: String,
+    /// Request headers
+    pub headers: Vec<(String, String)>,
+    /// Request body
+    pub body: Option<Vec<u8>>,
+}
+
+impl TransportRequest {
+    /// Value of a header, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Response returned by a [`SkillsTransport`]
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+impl TransportResponse {
+    /// Create a response from a status code and body
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+
+    /// Whether the status is 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, SkillsError> {
+        serde_json::from_slice(&self.body).map_err(|e| SkillsError::InvalidResponse(e.to_string()))
+    }
+}
+
+/// HTTP layer used by [`SkillsApiClient`]
+///
+/// The default implementation is [`ReqwestTransport`]. Tests and callers with
+/// their own HTTP stack can supply another one through
+/// [`SkillsApiClient::with_transport`].
+#[async_trait]
+pub trait SkillsTransport: Send + Sync {
+    /// Send a request and return the response, whatever its status
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, SkillsError>;
+}
+
+/// [`SkillsTransport`] backed by `reqwest`
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Create a transport using the given `reqwest` client
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl SkillsTransport for ReqwestTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, SkillsError> {
+        let mut builder = match request.method {
+            HttpMethod::Get => self.client.get(&request.url),
+            HttpMethod::Post => self.client.post(&request.url),
+            HttpMethod::Delete => self.client.delete(&request.url),
+        };
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let body = response.bytes().await?.to_vec();
+        Ok(TransportResponse { status, body })
+    }
 }
 
 /// Information about a skill from the Skills API
@@ -79,6 +229,261 @@ pub struct UploadSkillResponse {
     pub status: String,
 }
 
+/// A file inside a [`SkillArchive`]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveFile {
+    /// Path relative to the skill directory, using `/` separators
+    pub path: String,
+
+    /// File contents, base64-encoded on the wire
+    #[serde(with = "base64_bytes")]
+    pub content: Vec<u8>,
+}
+
+/// A skill version downloaded from the Skills API
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SkillArchive {
+    /// Skill information
+    pub skill: SkillApiInfo,
+
+    /// Version contained in the archive
+    pub version: String,
+
+    /// SHA-256 of the files, as returned by the API (see [`SkillArchive::compute_checksum`])
+    pub checksum: String,
+
+    /// Files making up the skill directory
+    pub files: Vec<ArchiveFile>,
+}
+
+/// Result of [`SkillArchive::install_to`]
+#[derive(Debug, Clone)]
+pub struct InstalledSkill {
+    /// Directory the skill was written to
+    pub path: PathBuf,
+
+    /// Parsed SKILL.md of the installed skill
+    pub skill: SkillMdFile,
+
+    /// Audit report, so callers can gate on `risk_level` before enabling the skill
+    pub report: SkillAuditReport,
+}
+
+impl SkillArchive {
+    /// Compute the checksum of the archive's files
+    ///
+    /// Files are hashed in path order; each contributes its path, a NUL byte,
+    /// its length as a little-endian `u64`, and its contents. The result is
+    /// `sha256:` followed by the lowercase hex digest.
+    pub fn compute_checksum(&self) -> String {
+        let mut files: Vec<&ArchiveFile> = self.files.iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut hasher = Sha256::new();
+        for file in files {
+            hasher.update(file.path.as_bytes());
+            hasher.update([0u8]);
+            hasher.update((file.content.len() as u64).to_le_bytes());
+            hasher.update(&file.content);
+        }
+
+        let hex: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("sha256:{}", hex)
+    }
+
+    /// Check the files against the checksum sent by the API
+    ///
+    /// The expected value may omit the `sha256:` prefix and is compared
+    /// case-insensitively.
+    pub fn verify(&self) -> Result<(), SkillsError> {
+        let actual = self.compute_checksum();
+        let expected = self.checksum.trim();
+        let expected_hex = expected.strip_prefix("sha256:").unwrap_or(expected);
+
+        if expected_hex.eq_ignore_ascii_case(&actual["sha256:".len()..]) {
+            Ok(())
+        } else {
+            Err(SkillsError::ChecksumMismatch {
+                expected: expected.to_string(),
+                actual,
+            })
+        }
+    }
+
+    /// Install the skill under `skills_dir`
+    ///
+    /// The files are written to a staging directory inside `skills_dir` and
+    /// renamed to `skills_dir/<name>` once complete, `<name>` being the name
+    /// from the archive's SKILL.md. An installed skill with a different
+    /// version is only replaced when `force` is set. The installed skill is
+    /// then parsed and audited with the default [`SkillAuditor`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `SkillsError` if:
+    /// - The checksum does not match
+    /// - A file path is absolute, escapes the skill directory, or SKILL.md is missing
+    /// - A different version is installed and `force` is false
+    /// - Writing, parsing, or auditing fails
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use claude_agent_sdk::skills::api::SkillsApiClient;
+    /// # use claude_agent_sdk::skills::RiskLevel;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SkillsApiClient::new("sk-ant-...");
+    /// let archive = client.download("skill-id-123", Some("1.2.0")).await?;
+    /// let installed = archive.install_to(".claude/skills", false)?;
+    /// if installed.report.risk_level >= RiskLevel::High {
+    ///     std::fs::remove_dir_all(&installed.path)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn install_to(
+        &self,
+        skills_dir: impl AsRef<Path>,
+        force: bool,
+    ) -> Result<InstalledSkill, SkillsError> {
+        self.verify()?;
+
+        let mut files = Vec::with_capacity(self.files.len());
+        for file in &self.files {
+            files.push((archive_path(&file.path)?, &file.content));
+        }
+        if !files.iter().any(|(path, _)| path == Path::new("SKILL.md")) {
+            return Err(SkillsError::InvalidArchive(
+                "archive has no SKILL.md at its root".to_string(),
+            ));
+        }
+
+        let skills_dir = skills_dir.as_ref();
+        std::fs::create_dir_all(skills_dir)?;
+
+        let staging = skills_dir.join(format!(".install-{}", uuid::Uuid::new_v4()));
+        let staged = write_files(&staging, &files).and_then(|()| {
+            let skill = SkillMdFile::parse(staging.join("SKILL.md"))?;
+            skill.metadata.validate()?;
+            Ok(skill.metadata)
+        });
+        let metadata = match staged {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(e);
+            },
+        };
+
+        let target = skills_dir.join(&metadata.name);
+        let backup = if target.exists() {
+            let installed = SkillMdFile::parse(target.join("SKILL.md"))
+                .map(|skill| skill.metadata.version)
+                .ok();
+            if installed.as_deref() != Some(metadata.version.as_str()) && !force {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(SkillsError::VersionConflict {
+                    skill: metadata.name,
+                    installed: installed.unwrap_or_else(|| "unknown".to_string()),
+                    requested: metadata.version,
+                });
+            }
+
+            let backup = skills_dir.join(format!(".backup-{}", uuid::Uuid::new_v4()));
+            if let Err(e) = std::fs::rename(&target, &backup) {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(e.into());
+            }
+            Some(backup)
+        } else {
+            None
+        };
+
+        if let Err(e) = std::fs::rename(&staging, &target) {
+            if let Some(backup) = &backup {
+                let _ = std::fs::rename(backup, &target);
+            }
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e.into());
+        }
+        if let Some(backup) = backup {
+            if let Err(e) = std::fs::remove_dir_all(&backup) {
+                tracing::warn!("Failed to remove {}: {}", backup.display(), e);
+            }
+        }
+
+        let skill = SkillMdFile::parse(target.join("SKILL.md"))?;
+        let report = SkillAuditor::default_auditor().audit(&skill)?;
+
+        Ok(InstalledSkill {
+            path: target,
+            skill,
+            report,
+        })
+    }
+}
+
+/// Turn an archive path into a relative path that stays inside the skill directory
+fn archive_path(path: &str) -> Result<PathBuf, SkillsError> {
+    let mut relative = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {},
+            _ => {
+                return Err(SkillsError::InvalidArchive(format!(
+                    "path '{}' escapes the skill directory",
+                    path
+                )));
+            },
+        }
+    }
+
+    if relative.as_os_str().is_empty() {
+        return Err(SkillsError::InvalidArchive("empty file path".to_string()));
+    }
+    Ok(relative)
+}
+
+/// Write archive files below `root`, marking files in `scripts/` executable
+fn write_files(root: &Path, files: &[(PathBuf, &Vec<u8>)]) -> Result<(), SkillsError> {
+    std::fs::create_dir_all(root)?;
+    for (relative, content) in files {
+        let path = root.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)?;
+
+        #[cfg(unix)]
+        if relative.starts_with("scripts") {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    Ok(())
+}
+
+/// Serde helpers for base64-encoded byte fields
+mod base64_bytes {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
 /// HTTP client for the Anthropic Skills API
 pub struct SkillsApiClient {
     /// API key for authentication
@@ -87,8 +492,8 @@ pub struct SkillsApiClient {
     /// Base URL for the API
     base_url: String,
 
-    /// HTTP client
-    client: Client,
+    /// HTTP transport
+    transport: Arc<dyn SkillsTransport>,
 
     /// API version header
     api_version: String,
@@ -122,7 +527,7 @@ impl SkillsApiClient {
         Self {
             api_key: api_key.into(),
             base_url: base_url.to_string(),
-            client: Client::new(),
+            transport: Arc::new(ReqwestTransport::default()),
             api_version: "2023-06-01".to_string(),
         }
     }
@@ -137,11 +542,21 @@ impl SkillsApiClient {
         Self {
             api_key: api_key.into(),
             base_url: "https://api.anthropic.com/v1".to_string(),
-            client: Client::new(),
+            transport: Arc::new(ReqwestTransport::default()),
             api_version: api_version.to_string(),
         }
     }
 
+    /// Replace the HTTP transport
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - Transport used for every request
+    pub fn with_transport(mut self, transport: Arc<dyn SkillsTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
     /// Upload a skill directory to the Skills API
     ///
     /// # Arguments
@@ -181,27 +596,14 @@ impl SkillsApiClient {
         // 2. Upload to API
         let url = format!("{}/skills", self.base_url);
         let response = self
-            .client
-            .post(&url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", &self.api_version)
-            .header("Content-Type", "application/zip")
-            .body(zip_bytes)
-            .send()
+            .send(HttpMethod::Post, url, Some(("application/zip", zip_bytes)))
             .await?;
 
         // 3. Check status
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(SkillsError::ApiError(format!(
-                "Upload failed with status {}: {}",
-                status, error_text
-            )));
-        }
+        Self::check_status(&response, "Upload", None)?;
 
         // 4. Parse response
-        let upload_response: UploadSkillResponse = response.json().await?;
+        let upload_response: UploadSkillResponse = response.json()?;
         Ok(upload_response.skill)
     }
 
@@ -229,26 +631,123 @@ impl SkillsApiClient {
     /// # }
     /// ```
     pub async fn list_skills(&self) -> Result<Vec<SkillApiInfo>, SkillsError> {
-        let url = format!("{}/skills", self.base_url);
-        let response = self
-            .client
-            .get(&url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", &self.api_version)
-            .send()
-            .await?;
+        let mut skills = Vec::new();
+        let mut seen_tokens = std::collections::HashSet::new();
+        let mut page: Option<String> = None;
+
+        loop {
+            let response = self.list_skills_page(page.as_deref()).await?;
+            skills.extend(response.skills);
+
+            match response.next_token {
+                Some(token) if !token.is_empty() => {
+                    if !seen_tokens.insert(token.clone()) {
+                        return Err(SkillsError::InvalidResponse(format!(
+                            "list skills returned page token '{}' twice",
+                            token
+                        )));
+                    }
+                    page = Some(token);
+                },
+                _ => break,
+            }
+        }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(SkillsError::ApiError(format!(
-                "List skills failed with status {}: {}",
-                status, error_text
-            )));
+        Ok(skills)
+    }
+
+    /// Fetch a single page of skills
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - `next_token` from the previous page, or `None` for the first page
+    ///
+    /// # Errors
+    ///
+    /// Returns `SkillsError` if the request fails
+    pub async fn list_skills_page(
+        &self,
+        page: Option<&str>,
+    ) -> Result<ListSkillsResponse, SkillsError> {
+        let base = format!("{}/skills", self.base_url);
+        let url = match page {
+            Some(token) => Self::url_with_params(&base, &[("page", token)])?,
+            None => base,
+        };
+        let response = self.send(HttpMethod::Get, url, None).await?;
+        Self::check_status(&response, "List skills", None)?;
+        response.json()
+    }
+
+    /// Download a skill from the Skills API
+    ///
+    /// The archive's checksum is verified before it is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `skill_id` - The skill identifier
+    /// * `version` - Version to download, or `None` for the latest
+    ///
+    /// # Errors
+    ///
+    /// Returns `SkillsError` if the skill is not found, the request fails, the
+    /// response is malformed, or the checksum does not match
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use claude_agent_sdk::skills::api::SkillsApiClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SkillsApiClient::new("sk-ant-...");
+    /// let archive = client.download("skill-id-123", None).await?;
+    /// println!("Downloaded {} {}", archive.skill.name, archive.version);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download(
+        &self,
+        skill_id: &str,
+        version: Option<&str>,
+    ) -> Result<SkillArchive, SkillsError> {
+        let base = format!("{}/skills/{}/download", self.base_url, skill_id);
+        let url = match version {
+            Some(version) => Self::url_with_params(&base, &[("version", version)])?,
+            None => base,
+        };
+        let response = self.send(HttpMethod::Get, url, None).await?;
+        Self::check_status(&response, "Download", Some(skill_id))?;
+
+        let archive: SkillArchive = response.json()?;
+        if let Some(version) = version {
+            if archive.version != version {
+                return Err(SkillsError::InvalidResponse(format!(
+                    "requested version {} of '{}', got {}",
+                    version, skill_id, archive.version
+                )));
+            }
         }
+        archive.verify()?;
+        Ok(archive)
+    }
 
-        let list_response: ListSkillsResponse = response.json().await?;
-        Ok(list_response.skills)
+    /// Download a skill and install it under `skills_dir`
+    ///
+    /// Shorthand for [`download`](Self::download) followed by
+    /// [`SkillArchive::install_to`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `SkillsError` if downloading or installing fails
+    pub async fn install(
+        &self,
+        skill_id: &str,
+        version: Option<&str>,
+        skills_dir: impl AsRef<Path>,
+        force: bool,
+    ) -> Result<InstalledSkill, SkillsError> {
+        self.download(skill_id, version)
+            .await?
+            .install_to(skills_dir, force)
     }
 
     /// Get details of a specific skill
@@ -266,28 +765,10 @@ impl SkillsApiClient {
     /// Returns `SkillsError` if the skill is not found or the request fails
     pub async fn get_skill(&self, skill_id: &str) -> Result<SkillApiInfo, SkillsError> {
         let url = format!("{}/skills/{}", self.base_url, skill_id);
-        let response = self
-            .client
-            .get(&url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", &self.api_version)
-            .send()
-            .await?;
-
-        if response.status().as_u16() == 404 {
-            return Err(SkillsError::SkillNotFound(skill_id.to_string()));
-        }
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(SkillsError::ApiError(format!(
-                "Get skill failed with status {}: {}",
-                status, error_text
-            )));
-        }
+        let response = self.send(HttpMethod::Get, url, None).await?;
+        Self::check_status(&response, "Get skill", Some(skill_id))?;
 
-        let skill: SkillApiInfo = response.json().await?;
+        let skill: SkillApiInfo = response.json()?;
         Ok(skill)
     }
 
@@ -318,30 +799,67 @@ impl SkillsApiClient {
     /// ```
     pub async fn delete_skill(&self, skill_id: &str) -> Result<(), SkillsError> {
         let url = format!("{}/skills/{}", self.base_url, skill_id);
-        let response = self
-            .client
-            .delete(&url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", &self.api_version)
-            .send()
-            .await?;
+        let response = self.send(HttpMethod::Delete, url, None).await?;
+        Self::check_status(&response, "Delete", Some(skill_id))?;
+
+        Ok(())
+    }
+
+    /// Send a request with the authentication and version headers
+    async fn send(
+        &self,
+        method: HttpMethod,
+        url: String,
+        body: Option<(&str, Vec<u8>)>,
+    ) -> Result<TransportResponse, SkillsError> {
+        let mut headers = vec![
+            ("x-api-key".to_string(), self.api_key.clone()),
+            ("anthropic-version".to_string(), self.api_version.clone()),
+        ];
+        let body = body.map(|(content_type, bytes)| {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+            bytes
+        });
+
+        self.transport
+            .send(TransportRequest {
+                method,
+                url,
+                headers,
+                body,
+            })
+            .await
+    }
 
-        if response.status().as_u16() == 404 {
+    /// Map 404 to `SkillNotFound` (when a skill id is given) and other failures to `ApiError`
+    fn check_status(
+        response: &TransportResponse,
+        operation: &str,
+        skill_id: Option<&str>,
+    ) -> Result<(), SkillsError> {
+        if let (404, Some(skill_id)) = (response.status, skill_id) {
             return Err(SkillsError::SkillNotFound(skill_id.to_string()));
         }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        if !response.is_success() {
             return Err(SkillsError::ApiError(format!(
-                "Delete failed with status {}: {}",
-                status, error_text
+                "{} failed with status {}: {}",
+                operation,
+                response.status,
+                response.text()
             )));
         }
 
         Ok(())
     }
 
+    /// Append URL-encoded query parameters
+    fn url_with_params(base: &str, params: &[(&str, &str)]) -> Result<String, SkillsError> {
+        Url::parse_with_params(base, params)
+            .map(String::from)
+            .map_err(|e| SkillsError::ApiError(format!("Invalid URL '{}': {}", base, e)))
+    }
+
     /// Zip a skill directory into bytes
     ///
     /// # Arguments
@@ -514,4 +1032,286 @@ mod tests {
         assert_eq!(deserialized.skill.id, "skill-123");
         assert_eq!(deserialized.status, "success");
     }
+
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockTransport {
+        responses: Mutex<VecDeque<Result<TransportResponse, SkillsError>>>,
+        requests: Mutex<Vec<TransportRequest>>,
+    }
+
+    impl MockTransport {
+        fn with_responses(
+            responses: impl IntoIterator<Item = Result<TransportResponse, SkillsError>>,
+        ) -> Arc<Self> {
+            Arc::new(Self {
+                responses: Mutex::new(responses.into_iter().collect()),
+                requests: Mutex::default(),
+            })
+        }
+
+        fn urls(&self) -> Vec<String> {
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|request| request.url.clone())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl SkillsTransport for MockTransport {
+        async fn send(&self, request: TransportRequest) -> Result<TransportResponse, SkillsError> {
+            self.requests.lock().unwrap().push(request);
+            self.responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("unexpected request")
+        }
+    }
+
+    fn mock_client(transport: Arc<MockTransport>) -> SkillsApiClient {
+        SkillsApiClient::with_base_url("test-key", "https://skills.test/v1").with_transport(transport)
+    }
+
+    fn json_response(status: u16, value: serde_json::Value) -> Result<TransportResponse, SkillsError> {
+        Ok(TransportResponse::new(status, value.to_string()))
+    }
+
+    fn api_info(id: &str) -> SkillApiInfo {
+        SkillApiInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: "A test skill".to_string(),
+            created_at: "2026-01-13T00:00:00Z".to_string(),
+            version: None,
+            author: None,
+        }
+    }
+
+    fn archive(name: &str, version: &str, extra: &[(&str, &str)]) -> SkillArchive {
+        let skill_md = format!(
+            "---\nname: {}\ndescription: Test skill\nversion: {}\n---\n\n# {}\n",
+            name, version, name
+        );
+        let mut files = vec![ArchiveFile {
+            path: "SKILL.md".to_string(),
+            content: skill_md.into_bytes(),
+        }];
+        files.extend(extra.iter().map(|(path, content)| ArchiveFile {
+            path: path.to_string(),
+            content: content.as_bytes().to_vec(),
+        }));
+
+        let mut archive = SkillArchive {
+            skill: api_info(name),
+            version: version.to_string(),
+            checksum: String::new(),
+            files,
+        };
+        archive.checksum = archive.compute_checksum();
+        archive
+    }
+
+    #[tokio::test]
+    async fn test_list_skills_follows_pagination() {
+        let transport = MockTransport::with_responses([
+            json_response(
+                200,
+                serde_json::json!({
+                    "skills": [api_info("skill-1")],
+                    "total_count": 2,
+                    "next_token": "page 2"
+                }),
+            ),
+            json_response(
+                200,
+                serde_json::json!({ "skills": [api_info("skill-2")], "total_count": 2 }),
+            ),
+        ]);
+        let client = mock_client(transport.clone());
+
+        let skills = client.list_skills().await.unwrap();
+
+        let ids: Vec<_> = skills.iter().map(|skill| skill.id.as_str()).collect();
+        assert_eq!(ids, ["skill-1", "skill-2"]);
+        assert_eq!(
+            transport.urls(),
+            [
+                "https://skills.test/v1/skills",
+                "https://skills.test/v1/skills?page=page+2"
+            ]
+        );
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests[0].method, HttpMethod::Get);
+        assert_eq!(requests[0].header("X-Api-Key"), Some("test-key"));
+        assert_eq!(requests[0].header("anthropic-version"), Some("2023-06-01"));
+    }
+
+    #[tokio::test]
+    async fn test_list_skills_rejects_repeated_page_token() {
+        let page = serde_json::json!({ "skills": [], "total_count": 0, "next_token": "again" });
+        let transport =
+            MockTransport::with_responses([json_response(200, page.clone()), json_response(200, page)]);
+
+        let err = mock_client(transport).list_skills().await.unwrap_err();
+        assert!(matches!(err, SkillsError::InvalidResponse(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_transport_errors_are_returned() {
+        let transport = MockTransport::with_responses([Err(SkillsError::IoError(
+            std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused"),
+        ))]);
+
+        let err = mock_client(transport).list_skills().await.unwrap_err();
+        assert!(matches!(err, SkillsError::IoError(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_error_status_maps_to_api_error() {
+        let transport =
+            MockTransport::with_responses([Ok(TransportResponse::new(500, "overloaded"))]);
+
+        let err = mock_client(transport).get_skill("skill-1").await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "API error: Get skill failed with status 500: overloaded"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_verifies_checksum() {
+        let good = archive("pdf-tools", "1.2.0", &[]);
+        let mut tampered = good.clone();
+        tampered.files[0].content.push(b'!');
+
+        let transport = MockTransport::with_responses([
+            json_response(200, serde_json::to_value(&good).unwrap()),
+            json_response(200, serde_json::to_value(&tampered).unwrap()),
+        ]);
+        let client = mock_client(transport.clone());
+
+        let downloaded = client.download("skill-1", Some("1.2.0")).await.unwrap();
+        assert_eq!(downloaded.version, "1.2.0");
+        assert_eq!(downloaded.files[0].content, good.files[0].content);
+
+        let err = client.download("skill-1", None).await.unwrap_err();
+        assert!(matches!(err, SkillsError::ChecksumMismatch { .. }), "{err}");
+
+        assert_eq!(
+            transport.urls(),
+            [
+                "https://skills.test/v1/skills/skill-1/download?version=1.2.0",
+                "https://skills.test/v1/skills/skill-1/download"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_not_found() {
+        let transport = MockTransport::with_responses([Ok(TransportResponse::new(404, ""))]);
+
+        let err = mock_client(transport).download("missing", None).await.unwrap_err();
+        assert!(matches!(err, SkillsError::SkillNotFound(id) if id == "missing"));
+    }
+
+    #[test]
+    fn test_checksum_accepts_bare_hex() {
+        let mut archive = archive("pdf-tools", "1.0.0", &[]);
+        archive.checksum = archive.checksum["sha256:".len()..].to_uppercase();
+        assert!(archive.verify().is_ok());
+    }
+
+    #[test]
+    fn test_install_to_writes_skill_and_audits() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive(
+            "pdf-tools",
+            "1.0.0",
+            &[
+                ("scripts/extract.sh", "#!/bin/sh\necho extract\n"),
+                ("resources/guide.md", "# Guide\n"),
+            ],
+        );
+
+        let installed = archive.install_to(dir.path(), false).unwrap();
+
+        assert_eq!(installed.path, dir.path().join("pdf-tools"));
+        assert_eq!(installed.skill.metadata.version, "1.0.0");
+        assert!(installed.path.join("resources/guide.md").is_file());
+        assert!(installed.report.files_scanned > 0);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(installed.path.join("scripts/extract.sh"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        let leftovers: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, ["pdf-tools"]);
+    }
+
+    #[test]
+    fn test_install_to_refuses_other_version_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        archive("pdf-tools", "1.0.0", &[("old.md", "old")])
+            .install_to(dir.path(), false)
+            .unwrap();
+
+        let newer = archive("pdf-tools", "2.0.0", &[]);
+        let err = newer.install_to(dir.path(), false).unwrap_err();
+        assert!(
+            matches!(&err, SkillsError::VersionConflict { installed, requested, .. }
+                if installed == "1.0.0" && requested == "2.0.0"),
+            "{err}"
+        );
+        assert!(dir.path().join("pdf-tools/old.md").exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let installed = newer.install_to(dir.path(), true).unwrap();
+        assert_eq!(installed.skill.metadata.version, "2.0.0");
+        assert!(!installed.path.join("old.md").exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_install_to_reinstalls_same_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive("pdf-tools", "1.0.0", &[]);
+        archive.install_to(dir.path(), false).unwrap();
+        assert!(archive.install_to(dir.path(), false).is_ok());
+    }
+
+    #[test]
+    fn test_install_to_rejects_unsafe_archives() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let escaping = archive("pdf-tools", "1.0.0", &[("../evil.sh", "rm -rf /")]);
+        let err = escaping.install_to(dir.path(), false).unwrap_err();
+        assert!(matches!(err, SkillsError::InvalidArchive(_)), "{err}");
+
+        let mut no_skill_md = archive("pdf-tools", "1.0.0", &[("README.md", "hi")]);
+        no_skill_md.files.remove(0);
+        no_skill_md.checksum = no_skill_md.compute_checksum();
+        let err = no_skill_md.install_to(dir.path(), false).unwrap_err();
+        assert!(matches!(err, SkillsError::InvalidArchive(_)), "{err}");
+
+        let invalid_name = archive("Not A Name", "1.0.0", &[]);
+        let err = invalid_name.install_to(dir.path(), false).unwrap_err();
+        assert!(matches!(err, SkillsError::SkillMd(_)), "{err}");
+
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
 }
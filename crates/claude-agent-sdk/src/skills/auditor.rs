@@ -9,6 +9,7 @@
 //! - Dangerous command detection (eval, exec, system)
 //! - File access pattern analysis
 //! - Risk level assessment
+//! - Incremental audits against a saved [`AuditBaseline`]
 //!
 //! ## Example
 //!
@@ -21,6 +22,7 @@
 //!     allow_network: false,
 //!     check_scripts: true,
 //!     check_resources: true,
+//!     update_baseline: true,
 //! };
 //!
 //! let auditor = SkillAuditor::new(config);
@@ -35,11 +37,17 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
-use crate::skills::skill_md::SkillMdFile;
-use std::path::Path;
+use crate::skills::skill_md::{SkillMdError, SkillMdFile, SkillsDirScanner};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Version of the baseline file format written by [`AuditBaseline::save`]
+const BASELINE_VERSION: u32 = 1;
 
 /// Risk level for a skill
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub enum RiskLevel {
     /// Safe - only from trusted sources
     #[default]
@@ -84,7 +92,7 @@ pub struct SkillAuditIssue {
 }
 
 /// Type of security issue
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum IssueType {
     /// Network access detected
     NetworkAccess,
@@ -167,6 +175,8 @@ pub struct AuditConfig {
     pub check_scripts: bool,
     /// Check resource files
     pub check_resources: bool,
+    /// Return a refreshed baseline from [`SkillAuditor::audit_changed`]
+    pub update_baseline: bool,
 }
 
 impl Default for AuditConfig {
@@ -176,19 +186,178 @@ impl Default for AuditConfig {
             allow_network: false,
             check_scripts: true,
             check_resources: true,
+            update_baseline: true,
+        }
+    }
+}
+
+/// Verdict of a previous audit, kept in an [`AuditBaseline`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditSummary {
+    /// Overall risk level
+    pub risk_level: RiskLevel,
+    /// Overall safety assessment
+    pub safe: bool,
+    /// Number of issues found by each rule
+    pub issue_counts: BTreeMap<IssueType, usize>,
+}
+
+impl From<&SkillAuditReport> for AuditSummary {
+    fn from(report: &SkillAuditReport) -> Self {
+        let mut issue_counts = BTreeMap::new();
+        for issue in &report.issues {
+            *issue_counts.entry(issue.issue_type).or_insert(0) += 1;
+        }
+        Self {
+            risk_level: report.risk_level,
+            safe: report.safe,
+            issue_counts,
+        }
+    }
+}
+
+/// Baseline entry for one skill
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// Hash of the audited files and the audit configuration
+    pub content_hash: String,
+    /// Verdict for that content
+    pub summary: AuditSummary,
+}
+
+/// Audit verdicts from a previous run, keyed by skill directory
+///
+/// Paths are relative to the skills directory, so a baseline saved on one
+/// checkout can be reused on another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditBaseline {
+    version: u32,
+    skills: BTreeMap<PathBuf, BaselineEntry>,
+}
+
+impl Default for AuditBaseline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuditBaseline {
+    /// Create an empty baseline, under which every skill counts as new
+    pub fn new() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            skills: BTreeMap::new(),
+        }
+    }
+
+    /// Number of skills in the baseline
+    pub fn len(&self) -> usize {
+        self.skills.len()
+    }
+
+    /// Whether the baseline has no skills
+    pub fn is_empty(&self) -> bool {
+        self.skills.is_empty()
+    }
+
+    /// Entry for the skill directory `path`, relative to the skills directory
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<&BaselineEntry> {
+        self.skills.get(path.as_ref())
+    }
+
+    /// Write the baseline to `path` as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AuditError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| AuditError::Baseline(e.to_string()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Read a baseline written by [`AuditBaseline::save`]
+    ///
+    /// Fails with [`AuditError::Baseline`] if the file is malformed or was
+    /// written in a different format version.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let json = std::fs::read_to_string(path)?;
+        let baseline: Self =
+            serde_json::from_str(&json).map_err(|e| AuditError::Baseline(e.to_string()))?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(AuditError::Baseline(format!(
+                "unsupported baseline version {}",
+                baseline.version
+            )));
         }
+        Ok(baseline)
+    }
+}
+
+/// A skill whose risk level increased against the baseline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRegression {
+    /// Skill directory, relative to the skills directory
+    pub path: PathBuf,
+    /// Risk level in the baseline
+    pub previous: RiskLevel,
+    /// Risk level now
+    pub current: RiskLevel,
+}
+
+/// Change in the number of issues one rule found in a skill
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleDelta {
+    /// Rule that produced the issues
+    pub rule: IssueType,
+    /// Issues in the baseline
+    pub before: usize,
+    /// Issues now
+    pub after: usize,
+}
+
+/// Result of [`SkillAuditor::audit_changed`]
+///
+/// All paths are skill directories relative to the skills directory.
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalAuditResult {
+    /// Current verdict of every skill, audited or reused from the baseline
+    pub summaries: BTreeMap<PathBuf, AuditSummary>,
+    /// Full reports of the skills audited in this run
+    pub reports: BTreeMap<PathBuf, SkillAuditReport>,
+    /// Skills not in the baseline
+    pub added: Vec<PathBuf>,
+    /// Skills whose content changed since the baseline
+    pub changed: Vec<PathBuf>,
+    /// Skills whose baseline verdict was reused
+    pub unchanged: Vec<PathBuf>,
+    /// Baseline skills that no longer exist
+    pub removed: Vec<PathBuf>,
+    /// Changed skills whose risk level increased
+    pub regressions: Vec<AuditRegression>,
+    /// Rules whose issue count differs from the baseline, per audited skill
+    pub rule_deltas: BTreeMap<PathBuf, Vec<RuleDelta>>,
+    /// Baseline for the next run, if [`AuditConfig::update_baseline`] is set
+    pub baseline: Option<AuditBaseline>,
+}
+
+impl IncrementalAuditResult {
+    /// Check if any skill got riskier than its baseline
+    pub fn has_regressions(&self) -> bool {
+        !self.regressions.is_empty()
     }
 }
 
 /// Skills security auditor
 pub struct SkillAuditor {
     config: AuditConfig,
+    rules_run: AtomicUsize,
 }
 
 impl SkillAuditor {
     /// Create a new auditor with the given configuration
     pub fn new(config: AuditConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            rules_run: AtomicUsize::new(0),
+        }
     }
 
     /// Create an auditor with default configuration
@@ -238,6 +407,145 @@ impl SkillAuditor {
         Ok(report)
     }
 
+    /// Audit only the skills in `skills_dir` that changed since `baseline`
+    ///
+    /// Skills whose files hash the same as in the baseline keep their
+    /// baseline verdict without being audited again. New and changed skills
+    /// are audited, and skills missing from `skills_dir` are dropped. Changed
+    /// skills whose risk level went up are listed in
+    /// [`IncrementalAuditResult::regressions`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::skills::auditor::{AuditBaseline, SkillAuditor};
+    ///
+    /// let baseline = AuditBaseline::load("audit-baseline.json").unwrap_or_default();
+    /// let auditor = SkillAuditor::default_auditor();
+    /// let result = auditor.audit_changed(".claude/skills", &baseline)?;
+    /// if let Some(baseline) = &result.baseline {
+    ///     baseline.save("audit-baseline.json")?;
+    /// }
+    /// if result.has_regressions() {
+    ///     std::process::exit(1);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn audit_changed(
+        &self,
+        skills_dir: impl AsRef<Path>,
+        baseline: &AuditBaseline,
+    ) -> Result<IncrementalAuditResult, AuditError> {
+        let skills_dir = skills_dir.as_ref();
+        let mut result = IncrementalAuditResult::default();
+        let mut next = AuditBaseline::new();
+
+        for skill in SkillsDirScanner::new(skills_dir).scan()? {
+            let path = skill
+                .skill_dir
+                .strip_prefix(skills_dir)
+                .unwrap_or(&skill.skill_dir)
+                .to_path_buf();
+            let content_hash = self.content_hash(&skill);
+            let previous = baseline.skills.get(&path);
+
+            let summary = match previous {
+                Some(entry) if entry.content_hash == content_hash => {
+                    result.unchanged.push(path.clone());
+                    entry.summary.clone()
+                }
+                _ => {
+                    let report = self.audit(&skill)?;
+                    let summary = AuditSummary::from(&report);
+                    let before = previous.map(|entry| &entry.summary);
+                    let deltas = rule_deltas(before, &summary);
+                    if !deltas.is_empty() {
+                        result.rule_deltas.insert(path.clone(), deltas);
+                    }
+                    match before {
+                        Some(before) => {
+                            if summary.risk_level > before.risk_level {
+                                result.regressions.push(AuditRegression {
+                                    path: path.clone(),
+                                    previous: before.risk_level,
+                                    current: summary.risk_level,
+                                });
+                            }
+                            result.changed.push(path.clone());
+                        }
+                        None => result.added.push(path.clone()),
+                    }
+                    result.reports.insert(path.clone(), report);
+                    summary
+                }
+            };
+
+            next.skills.insert(
+                path.clone(),
+                BaselineEntry {
+                    content_hash,
+                    summary: summary.clone(),
+                },
+            );
+            result.summaries.insert(path, summary);
+        }
+
+        result.removed = baseline
+            .skills
+            .keys()
+            .filter(|path| !next.skills.contains_key(*path))
+            .cloned()
+            .collect();
+        result.added.sort();
+        result.changed.sort();
+        result.unchanged.sort();
+        result.regressions.sort_by(|a, b| a.path.cmp(&b.path));
+        if self.config.update_baseline {
+            result.baseline = Some(next);
+        }
+
+        Ok(result)
+    }
+
+    /// Number of rule checks run by this auditor so far
+    pub fn rules_run(&self) -> usize {
+        self.rules_run.load(Ordering::Relaxed)
+    }
+
+    /// FNV-1a over every file [`Self::audit`] reads and the config flags that
+    /// change its verdict
+    fn content_hash(&self, skill: &SkillMdFile) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |bytes: &[u8]| {
+            for byte in bytes.iter().copied().chain([0]) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        };
+
+        feed(&[
+            u8::from(self.config.strict_mode),
+            u8::from(self.config.allow_network),
+            u8::from(self.config.check_scripts),
+            u8::from(self.config.check_resources),
+        ]);
+        feed(skill.content.as_bytes());
+
+        let mut files = Vec::new();
+        if self.config.check_scripts {
+            files.extend(&skill.scripts);
+        }
+        if self.config.check_resources {
+            files.extend(&skill.resources);
+        }
+        for file in files {
+            feed(file.to_string_lossy().as_bytes());
+            feed(&std::fs::read(file).unwrap_or_default());
+        }
+
+        format!("{hash:016x}")
+    }
+
     /// Read file content safely
     fn read_file(&self, path: &Path) -> Option<String> {
         std::fs::read_to_string(path).ok()
@@ -281,6 +589,8 @@ impl SkillAuditor {
 
     /// Check for network access patterns
     fn check_network_access(&self, content: &str, file: &str, report: &mut SkillAuditReport) {
+        self.rules_run.fetch_add(1, Ordering::Relaxed);
+
         let patterns = [
             "http://",
             "https://",
@@ -325,6 +635,8 @@ impl SkillAuditor {
 
     /// Check for dangerous commands
     fn check_dangerous_commands(&self, content: &str, file: &str, report: &mut SkillAuditReport) {
+        self.rules_run.fetch_add(1, Ordering::Relaxed);
+
         let dangerous_patterns = [
             ("eval(", RiskLevel::High),
             ("exec(", RiskLevel::High),
@@ -356,6 +668,8 @@ impl SkillAuditor {
 
     /// Check for file access patterns
     fn check_file_access_patterns(&self, content: &str, file: &str, report: &mut SkillAuditReport) {
+        self.rules_run.fetch_add(1, Ordering::Relaxed);
+
         let file_patterns = [
             ("open(", RiskLevel::Low),
             ("File.open", RiskLevel::Low),
@@ -385,6 +699,8 @@ impl SkillAuditor {
 
     /// Check for code execution patterns
     fn check_code_execution(&self, content: &str, file: &str, report: &mut SkillAuditReport) {
+        self.rules_run.fetch_add(1, Ordering::Relaxed);
+
         let code_exec_patterns = [
             "compile(",
             "execfile(",
@@ -426,6 +742,38 @@ pub enum AuditError {
 
     #[error("Failed to read skill content: {0}")]
     ReadError(String),
+
+    #[error("Failed to scan skills: {0}")]
+    Scan(#[from] SkillMdError),
+
+    #[error("Invalid audit baseline: {0}")]
+    Baseline(String),
+}
+
+/// Per-rule issue counts that differ between `before` and `after`
+fn rule_deltas(before: Option<&AuditSummary>, after: &AuditSummary) -> Vec<RuleDelta> {
+    let count = |summary: Option<&AuditSummary>, rule| {
+        summary
+            .and_then(|summary| summary.issue_counts.get(rule).copied())
+            .unwrap_or(0)
+    };
+    let rules: BTreeSet<&IssueType> = before
+        .into_iter()
+        .flat_map(|summary| summary.issue_counts.keys())
+        .chain(after.issue_counts.keys())
+        .collect();
+
+    rules
+        .into_iter()
+        .filter_map(|rule| {
+            let delta = RuleDelta {
+                rule: *rule,
+                before: count(before, rule),
+                after: count(Some(after), rule),
+            };
+            (delta.before != delta.after).then_some(delta)
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -664,4 +1012,158 @@ eval(__import__('os').system('rm -rf /'))
         assert_eq!(RiskLevel::Safe.to_string(), "Safe");
         assert_eq!(RiskLevel::Critical.to_string(), "Critical");
     }
+    fn write_skill(skills_dir: &Path, name: &str, body: &str) {
+        let dir = skills_dir.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {name}\ndescription: Skill {name}\n---\n\n{body}\n"),
+        )
+        .unwrap();
+    }
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_audit_changed_only_audits_changed_skills() {
+        let temp = TempDir::new().unwrap();
+        let skills = temp.path();
+        write_skill(skills, "alpha", "Plain text.");
+        write_skill(skills, "beta", "Reads `open(\"data.txt\")`.");
+        write_skill(skills, "gamma", "Also plain.");
+
+        let auditor = SkillAuditor::default_auditor();
+        let first = auditor
+            .audit_changed(skills, &AuditBaseline::new())
+            .unwrap();
+        assert_eq!(first.added, paths(&["alpha", "beta", "gamma"]));
+        assert!(first.unchanged.is_empty());
+        let per_skill = auditor.rules_run() / 3;
+        assert!(per_skill > 0);
+
+        // Nothing changed: every verdict comes from the baseline
+        let baseline = first.baseline.unwrap();
+        let rules_before = auditor.rules_run();
+        let second = auditor.audit_changed(skills, &baseline).unwrap();
+        assert_eq!(auditor.rules_run(), rules_before);
+        assert_eq!(second.unchanged, paths(&["alpha", "beta", "gamma"]));
+        assert!(second.reports.is_empty());
+        assert_eq!(second.summaries[Path::new("beta")].risk_level, RiskLevel::Low);
+
+        // Edit one skill, add one and remove one
+        write_skill(skills, "alpha", "Still plain, but edited.");
+        write_skill(skills, "delta", "New skill.");
+        fs::remove_dir_all(skills.join("gamma")).unwrap();
+
+        let rules_before = auditor.rules_run();
+        let third = auditor.audit_changed(skills, &baseline).unwrap();
+        assert_eq!(auditor.rules_run() - rules_before, 2 * per_skill);
+        assert_eq!(third.changed, paths(&["alpha"]));
+        assert_eq!(third.added, paths(&["delta"]));
+        assert_eq!(third.unchanged, paths(&["beta"]));
+        assert_eq!(third.removed, paths(&["gamma"]));
+        assert_eq!(third.summaries.len(), 3);
+        assert!(!third.has_regressions());
+
+        let next = third.baseline.unwrap();
+        assert_eq!(next.len(), 3);
+        assert!(next.get("gamma").is_none());
+    }
+
+    #[test]
+    fn test_audit_changed_reports_regressions_and_rule_deltas() {
+        let temp = TempDir::new().unwrap();
+        let skills = temp.path();
+        write_skill(skills, "fetcher", "Reads `open(\"data.txt\")`.");
+        write_skill(skills, "cleaner", "Runs `os.system(\"ls\")`.");
+
+        let auditor = SkillAuditor::default_auditor();
+        let baseline = auditor
+            .audit_changed(skills, &AuditBaseline::new())
+            .unwrap()
+            .baseline
+            .unwrap();
+
+        write_skill(
+            skills,
+            "fetcher",
+            "Reads `open(\"data.txt\")` and calls `requests.get(url)`.",
+        );
+        write_skill(skills, "cleaner", "Lists files.");
+
+        let result = auditor.audit_changed(skills, &baseline).unwrap();
+        assert_eq!(
+            result.regressions,
+            vec![AuditRegression {
+                path: PathBuf::from("fetcher"),
+                previous: RiskLevel::Low,
+                current: RiskLevel::Medium,
+            }]
+        );
+        assert_eq!(
+            result.rule_deltas[Path::new("fetcher")],
+            vec![RuleDelta {
+                rule: IssueType::NetworkAccess,
+                before: 0,
+                after: 1,
+            }]
+        );
+        // Getting safer is a delta but not a regression
+        assert_eq!(
+            result.rule_deltas[Path::new("cleaner")],
+            vec![RuleDelta {
+                rule: IssueType::DangerousCommand,
+                // `os.system(` matches both `os.system` and `system(`
+                before: 2,
+                after: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_audit_changed_without_baseline_update() {
+        let temp = TempDir::new().unwrap();
+        write_skill(temp.path(), "alpha", "Plain text.");
+
+        let auditor = SkillAuditor::new(AuditConfig {
+            update_baseline: false,
+            ..Default::default()
+        });
+        let result = auditor
+            .audit_changed(temp.path(), &AuditBaseline::new())
+            .unwrap();
+        assert_eq!(result.added, paths(&["alpha"]));
+        assert!(result.baseline.is_none());
+    }
+
+    #[test]
+    fn test_audit_baseline_save_load() {
+        let temp = TempDir::new().unwrap();
+        let skills = temp.path().join("skills");
+        write_skill(&skills, "beta", "Reads `open(\"data.txt\")`.");
+
+        let auditor = SkillAuditor::default_auditor();
+        let baseline = auditor
+            .audit_changed(&skills, &AuditBaseline::new())
+            .unwrap()
+            .baseline
+            .unwrap();
+
+        let file = temp.path().join("baseline.json");
+        baseline.save(&file).unwrap();
+        let loaded = AuditBaseline::load(&file).unwrap();
+        assert_eq!(loaded, baseline);
+        assert_eq!(
+            loaded.get("beta").unwrap().summary.issue_counts[&IssueType::FileAccess],
+            1
+        );
+
+        fs::write(&file, r#"{"version":99,"skills":{}}"#).unwrap();
+        assert!(matches!(
+            AuditBaseline::load(&file),
+            Err(AuditError::Baseline(_))
+        ));
+    }
 }
@@ -2,7 +2,7 @@
 
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
 
 /// Dependency requirement for a skill
@@ -32,6 +32,20 @@ impl Dependency {
             version_requirement: Some(version.into()),
         }
     }
+
+    /// Parse a dependency as written in skill metadata
+    ///
+    /// `other-skill` depends on any version, `other-skill@^2` on versions
+    /// matching the semver requirement after the `@`.
+    pub fn parse(spec: &str) -> Self {
+        match spec.split_once('@') {
+            Some((skill_id, version)) if !version.trim().is_empty() => {
+                Self::with_version(skill_id.trim(), version.trim())
+            },
+            Some((skill_id, _)) => Self::new(skill_id.trim()),
+            None => Self::new(spec.trim()),
+        }
+    }
 }
 
 impl fmt::Display for Dependency {
@@ -44,26 +58,66 @@ impl fmt::Display for Dependency {
     }
 }
 
-/// Result of dependency resolution
+/// A dependency that no available skill satisfies
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ResolutionResult {
-    /// All dependencies resolved successfully
-    Resolved {
-        /// Load order (topological sort)
-        load_order: Vec<String>,
-    },
-
-    /// Circular dependency detected
-    CircularDependency {
-        /// Cycle of skill IDs
-        cycle: Vec<String>,
-    },
-
-    /// Missing dependencies
-    MissingDependencies {
-        /// Missing skill IDs
-        missing: Vec<String>,
-    },
+pub struct MissingDependency {
+    /// Skill that declares the dependency
+    pub skill_id: String,
+
+    /// The unsatisfied dependency
+    pub dependency: Dependency,
+
+    /// Version of the skill with that ID, when it exists but does not
+    /// satisfy the version requirement
+    pub available_version: Option<String>,
+}
+
+impl fmt::Display for MissingDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.available_version {
+            Some(ref version) => write!(
+                f,
+                "{} requires {} (found version {})",
+                self.skill_id, self.dependency, version
+            ),
+            None => write!(f, "{} requires {}", self.skill_id, self.dependency),
+        }
+    }
+}
+
+/// Result of dependency resolution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolutionResult {
+    /// Load order (topological sort), dependencies first
+    ///
+    /// Skills on a cycle, and skills depending on them, are left out.
+    pub load_order: Vec<String>,
+
+    /// Dependencies no available skill satisfies
+    pub missing: Vec<MissingDependency>,
+
+    /// Circular dependencies, each spelled out as a path that starts and
+    /// ends with the same skill ID (e.g. `a -> b -> a`)
+    pub cycles: Vec<Vec<String>>,
+
+    /// Problems that did not stop resolution, such as version requirements
+    /// that could not be parsed
+    pub warnings: Vec<String>,
+}
+
+impl ResolutionResult {
+    /// Whether every skill can be loaded with all of its dependencies
+    pub fn is_resolved(&self) -> bool {
+        self.missing.is_empty() && self.cycles.is_empty()
+    }
+
+    /// Missing dependencies declared by `skill_id`
+    pub fn missing_for<'a>(
+        &'a self,
+        skill_id: &'a str,
+    ) -> impl Iterator<Item = &'a MissingDependency> + 'a {
+        self.missing.iter().filter(move |m| m.skill_id == skill_id)
+    }
 }
 
 /// Dependency resolver for Agent Skills
@@ -100,137 +154,153 @@ impl DependencyResolver {
 
     /// Resolve dependencies for a set of skills
     ///
+    /// Resolution does not stop at the first problem: the result carries the
+    /// load order of every skill outside a cycle together with all missing
+    /// dependencies and cycles found.
+    ///
     /// # Arguments
     /// * `skills` - Map of skill_id to their dependencies
     ///
     /// # Returns
-    /// Resolution result with load order or error
+    /// Resolution result with load order, missing dependencies and cycles
     pub fn resolve(&self, skills: &HashMap<String, Vec<Dependency>>) -> ResolutionResult {
-        // Check for missing dependencies
-        let missing = self.find_missing_dependencies(skills);
-        if !missing.is_empty() {
-            return ResolutionResult::MissingDependencies { missing };
-        }
-
-        // Check for circular dependencies
-        if let Some(cycle) = self.detect_cycles(skills) {
-            return ResolutionResult::CircularDependency { cycle };
-        }
+        let mut result = ResolutionResult::default();
+        result.missing = self.find_missing_dependencies(skills, &mut result.warnings);
+        result.cycles = self.detect_cycles(skills);
+        result.load_order = self.topological_sort(skills);
+        result
+    }
 
-        // Topological sort for load order
-        let load_order = self.topological_sort(skills);
+    /// Find all missing dependencies, including ones whose version does not match
+    fn find_missing_dependencies(
+        &self,
+        skills: &HashMap<String, Vec<Dependency>>,
+        warnings: &mut Vec<String>,
+    ) -> Vec<MissingDependency> {
+        let mut missing = Vec::new();
 
-        ResolutionResult::Resolved { load_order }
-    }
+        for skill_id in sorted_keys(skills) {
+            for dep in &skills[skill_id] {
+                let Some(available_version) = self.available.get(&dep.skill_id) else {
+                    missing.push(MissingDependency {
+                        skill_id: skill_id.clone(),
+                        dependency: dep.clone(),
+                        available_version: None,
+                    });
+                    continue;
+                };
 
-    /// Find all missing dependencies
-    fn find_missing_dependencies(&self, skills: &HashMap<String, Vec<Dependency>>) -> Vec<String> {
-        let mut missing = HashSet::new();
+                let Some(ref version_req_str) = dep.version_requirement else {
+                    continue;
+                };
+                let version_req = match VersionReq::parse(version_req_str) {
+                    Ok(version_req) => version_req,
+                    Err(e) => {
+                        warnings.push(format!(
+                            "{}: invalid version requirement '{}' for dependency '{}': {}",
+                            skill_id, version_req_str, dep.skill_id, e
+                        ));
+                        continue;
+                    },
+                };
 
-        for deps in skills.values() {
-            for dep in deps {
-                if !self.available.contains_key(&dep.skill_id) {
-                    missing.insert(dep.skill_id.clone());
+                let satisfied = Version::parse(available_version)
+                    .is_ok_and(|version| version_req.matches(&version));
+                if !satisfied {
+                    missing.push(MissingDependency {
+                        skill_id: skill_id.clone(),
+                        dependency: dep.clone(),
+                        available_version: Some(available_version.clone()),
+                    });
                 }
             }
         }
 
-        missing.into_iter().collect()
+        missing
     }
 
     /// Detect circular dependencies using DFS
-    fn detect_cycles(&self, skills: &HashMap<String, Vec<Dependency>>) -> Option<Vec<String>> {
+    ///
+    /// Skills are searched in ID order and each cycle is reported once, from
+    /// the first of its skills the search reaches.
+    fn detect_cycles(&self, skills: &HashMap<String, Vec<Dependency>>) -> Vec<Vec<String>> {
         let mut visited = HashSet::new();
-        let mut rec_stack = HashSet::new();
         let mut path = Vec::new();
+        let mut cycles = Vec::new();
 
-        for skill_id in skills.keys() {
-            if self.dfs_cycle_detect(skill_id, skills, &mut visited, &mut rec_stack, &mut path) {
-                return Some(path);
+        for skill_id in sorted_keys(skills) {
+            if !visited.contains(skill_id.as_str()) {
+                Self::dfs_cycle_detect(skill_id, skills, &mut visited, &mut path, &mut cycles);
             }
         }
 
-        None
+        cycles
     }
 
     /// DFS helper for cycle detection
-    fn dfs_cycle_detect(
-        &self,
-        skill_id: &str,
-        skills: &HashMap<String, Vec<Dependency>>,
-        visited: &mut HashSet<String>,
-        rec_stack: &mut HashSet<String>,
-        path: &mut Vec<String>,
-    ) -> bool {
-        visited.insert(skill_id.to_string());
-        rec_stack.insert(skill_id.to_string());
-        path.push(skill_id.to_string());
+    fn dfs_cycle_detect<'a>(
+        skill_id: &'a str,
+        skills: &'a HashMap<String, Vec<Dependency>>,
+        visited: &mut HashSet<&'a str>,
+        path: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        visited.insert(skill_id);
+        path.push(skill_id);
 
         if let Some(deps) = skills.get(skill_id) {
             for dep in deps {
-                if !visited.contains(&dep.skill_id) {
-                    if self.dfs_cycle_detect(&dep.skill_id, skills, visited, rec_stack, path) {
-                        return true;
-                    }
-                } else if rec_stack.contains(&dep.skill_id) {
-                    path.push(dep.skill_id.clone());
-                    return true;
+                if let Some(start) = path.iter().position(|id| *id == dep.skill_id) {
+                    let mut cycle: Vec<String> =
+                        path[start..].iter().map(|id| id.to_string()).collect();
+                    cycle.push(dep.skill_id.clone());
+                    cycles.push(cycle);
+                } else if !visited.contains(dep.skill_id.as_str()) {
+                    Self::dfs_cycle_detect(&dep.skill_id, skills, visited, path, cycles);
                 }
             }
         }
 
-        rec_stack.remove(skill_id);
         path.pop();
-        false
     }
 
     /// Topological sort to determine load order
+    ///
+    /// Only skills in `skills` are ordered; ties are broken by skill ID so the
+    /// order is stable.
     fn topological_sort(&self, skills: &HashMap<String, Vec<Dependency>>) -> Vec<String> {
-        let mut in_degree: HashMap<String, usize> = HashMap::new();
-        let mut all_skills: HashSet<String> = HashSet::new();
+        let mut in_degree: HashMap<&str, usize> = HashMap::new();
+        let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
 
-        // Collect all skills and initialize in-degrees
         for (skill_id, deps) in skills {
-            all_skills.insert(skill_id.clone());
-            in_degree.insert(skill_id.clone(), deps.len());
-
-            for dep in deps {
-                all_skills.insert(dep.skill_id.clone());
-                if !in_degree.contains_key(&dep.skill_id) {
-                    in_degree.insert(dep.skill_id.clone(), 0);
-                }
-            }
-        }
-
-        // Build adjacency list
-        let mut adj: HashMap<String, Vec<String>> = HashMap::new();
-        for (skill_id, deps) in skills {
-            for dep in deps {
-                adj.entry(dep.skill_id.clone())
-                    .or_insert_with(Vec::new)
-                    .push(skill_id.clone());
+            let known: HashSet<&str> = deps
+                .iter()
+                .map(|dep| dep.skill_id.as_str())
+                .filter(|dep| skills.contains_key(*dep))
+                .collect();
+            in_degree.insert(skill_id.as_str(), known.len());
+            for dep in known {
+                dependents.entry(dep).or_default().push(skill_id.as_str());
             }
         }
 
         // Kahn's algorithm for topological sort
-        let mut queue: Vec<String> = all_skills
+        let mut ready: BTreeSet<&str> = in_degree
             .iter()
-            .filter(|id| *in_degree.get(*id).unwrap_or(&0) == 0)
-            .cloned()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| *id)
             .collect();
 
         let mut result = Vec::new();
 
-        while let Some(skill_id) = queue.pop() {
-            result.push(skill_id.clone());
+        while let Some(skill_id) = ready.pop_first() {
+            result.push(skill_id.to_string());
 
-            if let Some(neighbors) = adj.get(&skill_id) {
-                for neighbor in neighbors {
-                    if let Some(degree) = in_degree.get_mut(neighbor) {
-                        *degree -= 1;
-                        if *degree == 0 {
-                            queue.push(neighbor.clone());
-                        }
+            for dependent in dependents.get(skill_id).into_iter().flatten() {
+                if let Some(degree) = in_degree.get_mut(dependent) {
+                    *degree -= 1;
+                    if *degree == 0 {
+                        ready.insert(dependent);
                     }
                 }
             }
@@ -276,6 +346,13 @@ impl DependencyResolver {
     }
 }
 
+/// Keys of `skills` in ID order
+fn sorted_keys(skills: &HashMap<String, Vec<Dependency>>) -> Vec<&String> {
+    let mut keys: Vec<_> = skills.keys().collect();
+    keys.sort();
+    keys
+}
+
 impl Default for DependencyResolver {
     fn default() -> Self {
         Self::new()
@@ -320,15 +397,8 @@ mod tests {
         skills.insert("dep1".to_string(), vec![]);
 
         let result = resolver.resolve(&skills);
-        match result {
-            ResolutionResult::Resolved { load_order } => {
-                // dep1 should come before main
-                let dep1_idx = load_order.iter().position(|id| id == "dep1").unwrap();
-                let main_idx = load_order.iter().position(|id| id == "main").unwrap();
-                assert!(dep1_idx < main_idx);
-            },
-            _ => panic!("Expected resolved result"),
-        }
+        assert!(result.is_resolved());
+        assert_eq!(result.load_order, vec!["dep1", "main"]);
     }
 
     #[test]
@@ -342,13 +412,9 @@ mod tests {
         skills.insert("skill2".to_string(), vec![Dependency::new("skill1")]);
 
         let result = resolver.resolve(&skills);
-        match result {
-            ResolutionResult::CircularDependency { cycle } => {
-                assert!(cycle.contains(&"skill1".to_string()));
-                assert!(cycle.contains(&"skill2".to_string()));
-            },
-            _ => panic!("Expected circular dependency error"),
-        }
+        assert!(!result.is_resolved());
+        assert_eq!(result.cycles, vec![vec!["skill1", "skill2", "skill1"]]);
+        assert!(result.load_order.is_empty());
     }
 
     #[test]
@@ -361,12 +427,16 @@ mod tests {
         skills.insert("main".to_string(), vec![Dependency::new("missing-dep")]);
 
         let result = resolver.resolve(&skills);
-        match result {
-            ResolutionResult::MissingDependencies { missing } => {
-                assert_eq!(missing, vec!["missing-dep".to_string()]);
-            },
-            _ => panic!("Expected missing dependencies error"),
-        }
+        assert!(!result.is_resolved());
+        assert_eq!(
+            result.missing,
+            vec![MissingDependency {
+                skill_id: "main".to_string(),
+                dependency: Dependency::new("missing-dep"),
+                available_version: None,
+            }]
+        );
+        assert_eq!(result.load_order, vec!["main"]);
     }
 
     #[test]
@@ -387,19 +457,8 @@ mod tests {
         skills.insert("d".to_string(), vec![]);
 
         let result = resolver.resolve(&skills);
-        match result {
-            ResolutionResult::Resolved { load_order } => {
-                // d should be first (no dependencies)
-                assert_eq!(load_order[0], "d");
-                // b and c should come before a
-                let a_idx = load_order.iter().position(|id| id == "a").unwrap();
-                let b_idx = load_order.iter().position(|id| id == "b").unwrap();
-                let c_idx = load_order.iter().position(|id| id == "c").unwrap();
-                assert!(b_idx < a_idx);
-                assert!(c_idx < a_idx);
-            },
-            _ => panic!("Expected resolved result"),
-        }
+        assert!(result.is_resolved());
+        assert_eq!(result.load_order, vec!["d", "b", "c", "a"]);
     }
 
     #[test]
@@ -417,4 +476,64 @@ mod tests {
 
         assert!(resolver.validate_versions(&skills));
     }
+
+    #[test]
+    fn test_dependency_parse() {
+        assert_eq!(Dependency::parse("other-skill"), Dependency::new("other-skill"));
+        assert_eq!(
+            Dependency::parse("other-skill@^2"),
+            Dependency::with_version("other-skill", "^2")
+        );
+        assert_eq!(Dependency::parse("other-skill@"), Dependency::new("other-skill"));
+    }
+
+    #[test]
+    fn test_version_mismatch_is_missing() {
+        let mut resolver = DependencyResolver::new();
+        resolver.add_skill("dep1", "1.4.0");
+        resolver.add_skill("main", "1.0.0");
+
+        let mut skills = HashMap::new();
+        skills.insert("main".to_string(), vec![Dependency::parse("dep1@^2")]);
+        skills.insert("dep1".to_string(), vec![]);
+
+        let result = resolver.resolve(&skills);
+        assert_eq!(result.missing.len(), 1);
+        assert_eq!(result.missing[0].available_version.as_deref(), Some("1.4.0"));
+        assert_eq!(
+            result.missing[0].to_string(),
+            "main requires dep1@^2 (found version 1.4.0)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_version_requirement_is_warning() {
+        let mut resolver = DependencyResolver::new();
+        resolver.add_skill("dep1", "1.0.0");
+
+        let mut skills = HashMap::new();
+        skills.insert("main".to_string(), vec![Dependency::parse("dep1@not-a-version")]);
+        skills.insert("dep1".to_string(), vec![]);
+
+        let result = resolver.resolve(&skills);
+        assert!(result.is_resolved());
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("not-a-version"));
+        assert_eq!(result.load_order, vec!["dep1", "main"]);
+    }
+
+    #[test]
+    fn test_cycle_excludes_dependents_only() {
+        let resolver = DependencyResolver::new();
+
+        let mut skills = HashMap::new();
+        skills.insert("a".to_string(), vec![Dependency::new("b")]);
+        skills.insert("b".to_string(), vec![Dependency::new("c")]);
+        skills.insert("c".to_string(), vec![Dependency::new("b")]);
+        skills.insert("d".to_string(), vec![]);
+
+        let result = resolver.resolve(&skills);
+        assert_eq!(result.cycles, vec![vec!["b", "c", "b"]]);
+        assert_eq!(result.load_order, vec!["d"]);
+    }
 }
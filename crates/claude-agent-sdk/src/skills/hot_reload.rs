@@ -3,11 +3,15 @@
 //! This module provides file system monitoring capabilities to automatically
 //! reload skill configurations when they change on disk.
 
-use crate::skills::{SkillError, SkillPackage};
+use crate::skills::{SkillError, SkillPackage, SkillSearchIndex};
+#[cfg(feature = "hot-reload")]
+use crate::skills::SkillMdFile;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{info, warn};
+#[cfg(feature = "hot-reload")]
+use tracing::{debug, error};
 
 /// Configuration for hot reload behavior
 #[derive(Debug, Clone)]
@@ -67,7 +71,6 @@ impl HotReloadWatcher {
         config: HotReloadConfig,
         event_sender: mpsc::UnboundedSender<HotReloadEvent>,
     ) -> Result<Self, SkillError> {
-        use notify::EventKind;
         use notify::Watcher;
 
         let watch_path = watch_path.as_ref();
@@ -124,25 +127,39 @@ impl HotReloadWatcher {
             None => return,
         };
 
-        // Skip if not a file
-        if !path.is_file() {
+        // A removed path no longer exists, so it may have been a skill file or a
+        // whole skill directory; report it either way
+        if let EventKind::Remove(_) = event.kind {
+            debug!("Remove event: path={:?}", path);
+            let _ = sender.send(HotReloadEvent::SkillDeleted { path: path.clone() });
             return;
         }
 
-        // Check file pattern
-        let file_name = match path.file_name() {
-            Some(name) => name.to_string_lossy(),
-            None => return,
-        };
+        // Files written into a new directory before it is watched raise no
+        // events of their own, so pick them up from the directory
+        if let EventKind::Create(_) = event.kind
+            && path.is_dir()
+        {
+            let Ok(entries) = std::fs::read_dir(path) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let file = entry.path();
+                if file.is_file() && Self::matches_patterns(&file, patterns) {
+                    Self::load_and_send_event(&file, sender, |path, skill| {
+                        HotReloadEvent::SkillCreated { path, skill }
+                    });
+                }
+            }
+            return;
+        }
 
-        let matches_pattern = patterns
-            .iter()
-            .any(|pattern| match pattern.strip_prefix('*') {
-                Some(ext) => file_name.ends_with(ext),
-                None => file_name == *pattern,
-            });
+        // Skip if not a file
+        if !path.is_file() {
+            return;
+        }
 
-        if !matches_pattern {
+        if !Self::matches_patterns(path, patterns) {
             debug!("Skipping file (pattern mismatch): {:?}", path);
             return;
         }
@@ -161,13 +178,25 @@ impl HotReloadWatcher {
                     HotReloadEvent::SkillModified { path, skill }
                 });
             },
-            EventKind::Remove(_) => {
-                let _ = sender.send(HotReloadEvent::SkillDeleted { path: path.clone() });
-            },
             _ => {},
         }
     }
 
+    /// Whether the file name of `path` matches one of `patterns`
+    fn matches_patterns(path: &Path, patterns: &[String]) -> bool {
+        let Some(file_name) = path.file_name() else {
+            return false;
+        };
+        let file_name = file_name.to_string_lossy();
+
+        patterns
+            .iter()
+            .any(|pattern| match pattern.strip_prefix('*') {
+                Some(ext) => file_name.ends_with(ext),
+                None => file_name == *pattern,
+            })
+    }
+
     /// Load a skill file and send the appropriate event
     fn load_and_send_event(
         path: &Path,
@@ -188,8 +217,14 @@ impl HotReloadWatcher {
         let _ = sender.send(event_builder(path.to_path_buf(), skill));
     }
 
-    /// Load a skill from a file (supports both JSON and YAML)
+    /// Load a skill from a file (supports SKILL.md, JSON and YAML)
     fn load_skill(path: &Path) -> Result<SkillPackage, SkillError> {
+        if path.file_name().is_some_and(|name| name == "SKILL.md") {
+            return SkillMdFile::parse(path)
+                .map(|skill_md| skill_md.to_skill_package())
+                .map_err(|e| SkillError::Io(format!("Failed to load SKILL.md: {}", e)));
+        }
+
         let extension = path
             .extension()
             .and_then(|ext| ext.to_str())
@@ -243,6 +278,7 @@ impl HotReloadWatcher {
 pub struct HotReloadManager {
     event_receiver: mpsc::UnboundedReceiver<HotReloadEvent>,
     skills: std::collections::HashMap<PathBuf, SkillPackage>,
+    search_index: Option<SkillSearchIndex>,
 }
 
 impl HotReloadManager {
@@ -251,9 +287,21 @@ impl HotReloadManager {
         Self {
             event_receiver,
             skills: std::collections::HashMap::new(),
+            search_index: None,
         }
     }
 
+    /// Keep `index` up to date as skills are created, modified and deleted
+    pub fn with_search_index(mut self, index: SkillSearchIndex) -> Self {
+        self.search_index = Some(index);
+        self
+    }
+
+    /// The search index, if one was attached with [`with_search_index`](Self::with_search_index)
+    pub fn search_index(&self) -> Option<&SkillSearchIndex> {
+        self.search_index.as_ref()
+    }
+
     /// Get all currently loaded skills
     pub fn get_skills(&self) -> Vec<&SkillPackage> {
         self.skills.values().collect()
@@ -281,21 +329,38 @@ impl HotReloadManager {
         match event {
             HotReloadEvent::SkillCreated { path, skill } => {
                 info!("Skill created: {:?}", path);
-                self.skills.insert(path, skill);
+                self.insert_skill(path, skill);
             },
             HotReloadEvent::SkillModified { path, skill } => {
                 info!("Skill modified: {:?}", path);
-                self.skills.insert(path, skill);
+                self.insert_skill(path, skill);
             },
             HotReloadEvent::SkillDeleted { path } => {
                 info!("Skill deleted: {:?}", path);
-                self.skills.remove(&path);
+                if let Some(skill) = self.skills.remove(&path)
+                    && let Some(index) = &mut self.search_index
+                {
+                    index.remove(&skill.metadata.id);
+                }
             },
             HotReloadEvent::Error { path, error } => {
                 warn!("Skill error at {:?}: {}", path, error);
             },
         }
     }
+
+    fn insert_skill(&mut self, path: PathBuf, skill: SkillPackage) {
+        if let Some(index) = &mut self.search_index {
+            // The file may now hold a skill with a different ID
+            if let Some(previous) = self.skills.get(&path)
+                && previous.metadata.id != skill.metadata.id
+            {
+                index.remove(&previous.metadata.id);
+            }
+            index.upsert(&skill);
+        }
+        self.skills.insert(path, skill);
+    }
 }
 
 #[cfg(test)]
@@ -351,4 +416,44 @@ mod tests {
         let count = manager.process_events();
         assert_eq!(count, 1);
     }
+
+    #[test]
+    fn test_hot_reload_manager_updates_search_index() {
+        let skill = |id: &str, description: &str| -> SkillPackage {
+            serde_json::from_value(serde_json::json!({
+                "metadata": {"id": id, "name": id, "description": description, "version": "1.0.0"},
+                "instructions": ""
+            }))
+            .unwrap()
+        };
+        let path = PathBuf::from("/test/skill.json");
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut manager =
+            HotReloadManager::new(receiver).with_search_index(SkillSearchIndex::default());
+
+        sender
+            .send(HotReloadEvent::SkillCreated {
+                path: path.clone(),
+                skill: skill("converter", "Convert CSV to Parquet"),
+            })
+            .unwrap();
+        manager.process_events();
+        let index = manager.search_index().unwrap();
+        assert_eq!(index.search("parquet", 5)[0].id, "converter");
+
+        sender
+            .send(HotReloadEvent::SkillModified {
+                path: path.clone(),
+                skill: skill("converter", "Convert CSV to Avro"),
+            })
+            .unwrap();
+        manager.process_events();
+        let index = manager.search_index().unwrap();
+        assert!(index.search("parquet", 5).is_empty());
+        assert_eq!(index.search("avro", 5)[0].id, "converter");
+
+        sender.send(HotReloadEvent::SkillDeleted { path }).unwrap();
+        manager.process_events();
+        assert!(manager.search_index().unwrap().is_empty());
+    }
 }
@@ -16,9 +16,13 @@ pub mod auditor;
 pub mod dependency;
 pub mod error;
 pub mod hot_reload;
+pub mod packaged;
 pub mod performance;
 pub mod progressive_disclosure;
+pub mod reloadable;
 pub mod sandbox;
+pub mod search;
+pub mod selection;
 pub mod skill_md;
 pub mod tags;
 pub mod tool_restriction;
@@ -36,23 +40,46 @@ mod integration_tests;
 
 use async_trait::async_trait;
 use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
 
-pub use api::{ListSkillsResponse, SkillApiInfo, SkillsApiClient, SkillsError, UploadSkillResponse};
+pub use api::{
+    ArchiveFile, HttpMethod, InstalledSkill, ListSkillsResponse, ReqwestTransport, SkillApiInfo,
+    SkillArchive, SkillsApiClient, SkillsError, SkillsTransport, TransportRequest,
+    TransportResponse, UploadSkillResponse,
+};
 pub use auditor::{
-    AuditConfig, AuditError, IssueType, RiskLevel, SkillAuditor, SkillAuditIssue, SkillAuditReport,
+    AuditBaseline, AuditConfig, AuditError, AuditRegression, AuditSummary, BaselineEntry,
+    IncrementalAuditResult, IssueType, RiskLevel, RuleDelta, SkillAuditor, SkillAuditIssue,
+    SkillAuditReport,
 };
-pub use dependency::{Dependency, DependencyResolver, ResolutionResult};
+pub use dependency::{Dependency, DependencyResolver, MissingDependency, ResolutionResult};
 pub use error::{SkillError, SkillOutput, SkillResult};
 pub use hot_reload::{HotReloadConfig, HotReloadEvent, HotReloadManager, HotReloadWatcher};
-pub use performance::{BatchOperations, IndexedSkillCollection, LruCache, PerformanceStats};
+pub use packaged::PackagedSkill;
+pub use performance::{
+    BatchOperations, IndexedSkillCollection, LruCache, PerformanceStats, SkillCacheConfig,
+    SkillStats,
+};
 pub use progressive_disclosure::ProgressiveSkillLoader;
-pub use sandbox::{SandboxConfig, SandboxExecutor, SandboxResult, SandboxUtils};
+pub use reloadable::{ReloadableSkillRegistry, SkillRegistryEvent};
+pub use sandbox::{
+    IsolationLevel, SandboxConfig, SandboxExecution, SandboxExecutor, SandboxResult, SandboxUtils,
+};
+pub use search::{ScoredSkill, SkillSearchIndex};
+pub use selection::{GET_SKILL_INSTRUCTIONS_TOOL, RUN_SKILL_TOOL, SEARCH_SKILLS_TOOL};
 pub use skill_md::{HookConfig, HookType, SkillContext, SkillHooks, SkillMdError, SkillMdFile, SkillMdMetadata, SkillsDirScanner};
-pub use tags::{TagFilter, TagOperator, TagQueryBuilder, TagUtils};
+pub use tags::{
+    ClaudeTagging, InferConfig, KeywordCorpus, ScoredTag, TagBackfill, TagFilter, TagOperator,
+    TagQueryBuilder, TagSource, TagUtils, infer as infer_tags,
+};
 pub use tool_restriction::{ToolRestriction, ToolRestrictionError};
 pub use types::{SkillInput, SkillMetadata, SkillPackage, SkillResources, SkillStatus};
 pub use version::{CompatibilityResult, VersionManager};
-pub use vscode::{VsCodeExportConfig, VsCodeUtils, export_batch_to_vscode, export_to_vscode};
+pub use vscode::{
+    VsCodeExportConfig, VsCodeExportReport, VsCodeUtils, export_batch_to_vscode,
+    export_batch_to_vscode_with_report, export_to_vscode, import_from_vscode, render_vscode,
+};
 
 /// The core Skill trait
 #[async_trait]
@@ -61,11 +88,41 @@ pub trait Skill: Send + Sync {
     fn description(&self) -> String;
     async fn execute(&self, input: SkillInput) -> SkillResult;
     fn validate(&self) -> Result<(), SkillError>;
+
+    /// The package the skill was loaded from, if any
+    fn skill_package(&self) -> Option<&SkillPackage> {
+        None
+    }
+
+    /// Whether Claude may find and run the skill by itself
+    ///
+    /// `false` for SKILL.md skills with `disable_model_invocation: true`,
+    /// which [`SkillRegistry::as_mcp_server`] hides.
+    fn model_invocable(&self) -> bool {
+        true
+    }
+
+    /// Whether [`SkillRegistry::execute`] may reuse outputs for identical inputs
+    ///
+    /// Defaults to the `cacheable` flag of the skill's package.
+    fn cacheable(&self) -> bool {
+        self.skill_package()
+            .is_some_and(|package| package.metadata.cacheable)
+    }
 }
 
+/// Outputs of cacheable skills, keyed by skill name and JSON input
+type OutputCache = LruCache<(String, String), (Instant, SkillOutput)>;
+
 /// Simple skill registry
 pub struct SkillRegistry {
     skills: std::collections::HashMap<String, Box<dyn Skill>>,
+    cli_version: Option<String>,
+    compatibility: std::collections::HashMap<String, CompatibilityResult>,
+    stats: Arc<SkillStats>,
+    cache_config: SkillCacheConfig,
+    output_cache: Mutex<OutputCache>,
+    search_index: SkillSearchIndex,
 }
 
 impl Default for SkillRegistry {
@@ -78,16 +135,154 @@ impl SkillRegistry {
     pub fn new() -> Self {
         Self {
             skills: std::collections::HashMap::new(),
+            cli_version: None,
+            compatibility: std::collections::HashMap::new(),
+            stats: Arc::new(SkillStats::new()),
+            cache_config: SkillCacheConfig::default(),
+            output_cache: Mutex::new(LruCache::new(SkillCacheConfig::default().max_entries)),
+            search_index: SkillSearchIndex::default(),
         }
     }
 
+    /// Set the Claude Code CLI version skills' `min_cli_version` is checked against
+    ///
+    /// Takes the version `claude --version` reports, as passed to
+    /// [`check_version`](crate::version::check_version). Until it is set,
+    /// `min_cli_version` is not enforced.
+    pub fn set_cli_version(&mut self, version: impl Into<String>) {
+        self.cli_version = Some(version.into());
+    }
+
+    /// Bound the outputs kept for [`cacheable`](Skill::cacheable) skills
+    ///
+    /// Drops the outputs cached so far.
+    pub fn set_cache_config(&mut self, config: SkillCacheConfig) {
+        self.cache_config = config;
+        *self.lock_cache() = LruCache::new(config.max_entries);
+    }
+
     pub fn register(&mut self, skill: Box<dyn Skill>) -> Result<(), SkillError> {
         let name = skill.name();
         skill.validate()?;
+        self.forget_outputs(&name);
+        if let Some(package) = self.skills.get(&name).and_then(|s| s.skill_package()) {
+            self.search_index.remove(&package.metadata.id);
+        }
+        if let Some(package) = skill.skill_package() {
+            self.search_index.upsert(package);
+        }
         self.skills.insert(name, skill);
         Ok(())
     }
 
+    /// Run a registered skill, recording the execution in [`stats`](Self::stats)
+    ///
+    /// A [`SkillOutput`] that is not `success` counts as a failure, like an
+    /// error. For [`cacheable`](Skill::cacheable) skills, a successful output
+    /// is reused for the same input until the TTL of the
+    /// [`SkillCacheConfig`] passes.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::{SkillInput, SkillRegistry};
+    ///
+    /// # async fn example() -> Result<(), claude_agent_sdk::skills::SkillError> {
+    /// let mut registry = SkillRegistry::new();
+    /// registry.load_dir(".claude/skills")?;
+    /// let input = SkillInput { params: serde_json::json!({"file": "src/lib.rs"}) };
+    /// let output = registry.execute("code-reviewer", input).await?;
+    /// println!("{}", output);
+    ///
+    /// if let Some(stats) = registry.stats_for("code-reviewer") {
+    ///     println!("p95: {:?}, failures: {}", stats.p95_latency, stats.failures);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// [`SkillError::NotFound`] if no skill is registered as `name`, or the
+    /// error of the skill
+    pub async fn execute(&self, name: &str, input: SkillInput) -> SkillResult {
+        let skill = self
+            .skills
+            .get(name)
+            .ok_or_else(|| SkillError::NotFound(name.to_string()))?;
+        let started = Instant::now();
+
+        let cache_key = (skill.cacheable() && self.cache_config.max_entries > 0)
+            .then(|| (name.to_string(), input.params.to_string()));
+        if let Some(ref key) = cache_key {
+            let cached = self.cached_output(key);
+            self.stats.record_cache(name, cached.is_some());
+            if let Some(output) = cached {
+                self.stats.record(name, started.elapsed(), None);
+                return Ok(output);
+            }
+        }
+
+        let result = skill.execute(input).await;
+        let error = match &result {
+            Ok(output) if output.success => None,
+            Ok(output) => Some(output.error.as_deref().unwrap_or("Unknown error").to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+        self.stats.record(name, started.elapsed(), error.as_deref());
+        if let (Some(key), Ok(output), None) = (cache_key, &result, &error) {
+            self.lock_cache().put(key, (Instant::now(), output.clone()));
+        }
+        result
+    }
+
+    /// Execution statistics of all skills together
+    pub fn stats(&self) -> PerformanceStats {
+        self.stats.total()
+    }
+
+    /// Execution statistics of one skill, or `None` if it never ran
+    pub fn stats_for(&self, name: &str) -> Option<PerformanceStats> {
+        self.stats.get(name)
+    }
+
+    /// Forget the execution statistics of every skill
+    pub fn reset_stats(&self) {
+        self.stats.reset();
+    }
+
+    /// The statistics [`execute`](Self::execute) records into
+    ///
+    /// Hand it to [`SandboxExecutor::with_stats`] so that script runs are
+    /// counted with the skills.
+    pub fn shared_stats(&self) -> Arc<SkillStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// Drop every cached output
+    pub fn clear_cache(&self) {
+        self.lock_cache().clear();
+    }
+
+    /// A cached output for `key` that has not expired
+    fn cached_output(&self, key: &(String, String)) -> Option<SkillOutput> {
+        let mut cache = self.lock_cache();
+        let (stored, output) = cache.get(key)?.clone();
+        if stored.elapsed() < self.cache_config.ttl {
+            Some(output)
+        } else {
+            cache.remove(key);
+            None
+        }
+    }
+
+    /// Drop the cached outputs of `name`
+    fn forget_outputs(&self, name: &str) {
+        self.lock_cache().retain(|(skill, _)| skill != name);
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, OutputCache> {
+        self.output_cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     pub fn get(&self, name: &str) -> Option<&dyn Skill> {
         self.skills.get(name).map(|s| s.as_ref())
     }
@@ -96,6 +291,354 @@ impl SkillRegistry {
         self.skills.keys().cloned().collect()
     }
 
+    /// Metadata of the registered skills matching `filter`, sorted by name
+    ///
+    /// Only skills loaded from a [`SkillPackage`] have tags to match.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::{SkillRegistry, TagFilter};
+    ///
+    /// let mut registry = SkillRegistry::new();
+    /// registry.load_dir(".claude/skills")?;
+    /// for skill in registry.find(&TagFilter::new().has("devops-*")) {
+    ///     println!("{}: {}", skill.name, skill.description);
+    /// }
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    pub fn find(&self, filter: &TagFilter) -> Vec<&SkillMetadata> {
+        let mut found: Vec<&SkillMetadata> = self
+            .skills
+            .values()
+            .filter_map(|skill| skill.skill_package())
+            .map(|package| &package.metadata)
+            .filter(|metadata| filter.matches_tags(&metadata.tags))
+            .collect();
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+        found
+    }
+
+    /// [`find`](Self::find) with a filter built by `build`
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    ///
+    /// let mut registry = SkillRegistry::new();
+    /// registry.load_dir(".claude/skills")?;
+    /// let reviewers = registry.find_query(|q| {
+    ///     q.all_of(["git"]).any_of(["review", "lint"]).none_of(["deprecated"])
+    /// });
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    pub fn find_query(&self, build: impl FnOnce(TagFilter) -> TagFilter) -> Vec<&SkillMetadata> {
+        self.find(&build(TagFilter::new()))
+    }
+
+    /// Registered skills matching a free-text `query`, best first, with their score
+    ///
+    /// Ranked by [`SkillSearchIndex::search`] over the name, tags, description
+    /// and instructions of each skill loaded from a [`SkillPackage`]. The index
+    /// is updated as skills are registered and unregistered.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    ///
+    /// let mut registry = SkillRegistry::new();
+    /// registry.load_dir(".claude/skills")?;
+    /// for (score, skill) in registry.search("convert pdf to text").into_iter().take(3) {
+    ///     println!("{:.2} {}: {}", score, skill.name, skill.description);
+    /// }
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    pub fn search(&self, query: &str) -> Vec<(f64, &SkillMetadata)> {
+        self.search_index
+            .search(query, usize::MAX)
+            .into_iter()
+            .filter_map(|hit| {
+                let package = self.skills.get(&hit.name)?.skill_package()?;
+                (package.metadata.id == hit.id).then_some((hit.score, &package.metadata))
+            })
+            .collect()
+    }
+
+    /// Register a discovered skill package as an executable [`PackagedSkill`]
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    ///
+    /// let mut registry = SkillRegistry::new();
+    /// for package in SkillRegistry::discover_from_dir("/path/to/skills")? {
+    ///     registry.register_package(package)?;
+    /// }
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// [`SkillError::VersionConflict`] if the package requires a newer SDK or
+    /// CLI (see [`compatibility`](Self::compatibility))
+    pub fn register_package(&mut self, package: SkillPackage) -> Result<(), SkillError> {
+        let name = package.metadata.name.clone();
+        if let Some(reason) = self.check_compatibility(&package.metadata) {
+            return Err(SkillError::VersionConflict(format!("{}: {}", name, reason)));
+        }
+        self.register(Box::new(PackagedSkill::new(package)))
+    }
+
+    /// Result of checking a skill's `min_sdk_version` and `min_cli_version`
+    ///
+    /// Available for every skill registered from a package or directory that
+    /// declares either field, including skills left out as incompatible.
+    pub fn compatibility(&self, name: &str) -> Option<&CompatibilityResult> {
+        self.compatibility.get(name)
+    }
+
+    /// Status of a skill
+    ///
+    /// [`SkillStatus::Ready`] for registered skills, [`SkillStatus::Incompatible`]
+    /// for skills not registered because they require a newer SDK or CLI.
+    pub fn status(&self, name: &str) -> Option<SkillStatus> {
+        if self.skills.contains_key(name) {
+            Some(SkillStatus::Ready)
+        } else if let Some(CompatibilityResult::Incompatible { .. }) = self.compatibility.get(name) {
+            Some(SkillStatus::Incompatible)
+        } else {
+            None
+        }
+    }
+
+    /// Check a skill against [`SDK_VERSION`](crate::version::SDK_VERSION) and the CLI version
+    ///
+    /// Records the result for [`compatibility`](Self::compatibility) and
+    /// returns why the skill is incompatible, if it is.
+    pub(crate) fn check_compatibility(&mut self, metadata: &SkillMetadata) -> Option<String> {
+        let result = VersionManager::new().check_skill(
+            metadata,
+            crate::version::SDK_VERSION,
+            self.cli_version.as_deref(),
+        )?;
+        let reason = match result {
+            CompatibilityResult::Incompatible { ref reason, .. } => Some(reason.clone()),
+            _ => None,
+        };
+        self.compatibility.insert(metadata.name.clone(), result);
+        reason
+    }
+
+    /// Discover the skills in a directory and register them
+    ///
+    /// Registers SKILL.md skills in subdirectories of `dir`, keeping their
+    /// `allowed_tools` and `model`, then `.json` skill packages in `dir`
+    /// itself. A JSON package with the same ID as a SKILL.md skill is skipped.
+    /// Skills requiring a newer SDK or CLI are logged and marked
+    /// [`SkillStatus::Incompatible`] instead of being registered.
+    ///
+    /// # Returns
+    /// Names of the registered skills, sorted
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    ///
+    /// let mut registry = SkillRegistry::new();
+    /// let loaded = registry.load_dir(".claude/skills")?;
+    /// println!("Loaded skills: {:?}", loaded);
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<Vec<String>, SkillError> {
+        let mut names = Vec::new();
+        for (path, skill) in Self::discover_skills(dir.as_ref())? {
+            if let Some(reason) = self.check_compatibility(&skill.package().metadata) {
+                tracing::warn!("Not registering skill {} from {:?}: {}", skill.name(), path, reason);
+                continue;
+            }
+            names.push(skill.name());
+            self.register(Box::new(skill))?;
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Resolve the dependencies between the skills in a directory
+    ///
+    /// Discovers skills like [`load_dir`](Self::load_dir) and matches each
+    /// entry of their `dependencies` against the other skills by name (or
+    /// ID), honouring an optional semver requirement such as
+    /// `other-skill@^2`. Requirements that fail to parse are reported as
+    /// warnings naming the SKILL.md they came from, and match any version.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    ///
+    /// let plan = SkillRegistry::resolve_dependencies(".claude/skills")?;
+    /// for cycle in &plan.cycles {
+    ///     eprintln!("circular dependency: {}", cycle.join(" -> "));
+    /// }
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    pub fn resolve_dependencies<P: AsRef<Path>>(dir: P) -> Result<ResolutionResult, SkillError> {
+        let skills = Self::discover_skills(dir.as_ref())?;
+        Ok(Self::resolve_discovered(&skills).0)
+    }
+
+    /// Discover the skills in a directory and register them in dependency order
+    ///
+    /// Skills are registered following the load order of
+    /// [`resolve_dependencies`](Self::resolve_dependencies). Skills on a
+    /// dependency cycle are never registered. Unless `allow_missing` is set,
+    /// neither is a skill with a missing dependency, nor any skill depending
+    /// on one that was left out. Skills requiring a newer SDK or CLI are
+    /// left out as by [`load_dir`](Self::load_dir).
+    ///
+    /// # Returns
+    /// The resolution, with `load_order` limited to the skills registered
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    ///
+    /// let mut registry = SkillRegistry::new();
+    /// let plan = registry.load_dir_resolved(".claude/skills", false)?;
+    /// for missing in &plan.missing {
+    ///     eprintln!("not loaded: {}", missing);
+    /// }
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    pub fn load_dir_resolved<P: AsRef<Path>>(
+        &mut self,
+        dir: P,
+        allow_missing: bool,
+    ) -> Result<ResolutionResult, SkillError> {
+        let skills = Self::discover_skills(dir.as_ref())?;
+        let (mut result, graph) = Self::resolve_discovered(&skills);
+
+        let mut by_name: std::collections::HashMap<_, _> = skills
+            .into_iter()
+            .map(|(path, skill)| (skill.name(), (path, skill)))
+            .collect();
+        let mut skipped = std::collections::HashSet::new();
+        let mut registered = Vec::new();
+
+        for name in std::mem::take(&mut result.load_order) {
+            if !allow_missing {
+                let skipped_dep = graph[&name]
+                    .iter()
+                    .find(|dep| skipped.contains(&dep.skill_id))
+                    .map(|dep| dep.skill_id.clone());
+                let missing_dep = result.missing_for(&name).next().map(|m| m.dependency.to_string());
+                if let Some(dep) = missing_dep.or(skipped_dep) {
+                    tracing::warn!("Not registering skill {}: dependency {} is not available", name, dep);
+                    skipped.insert(name);
+                    continue;
+                }
+            }
+
+            let Some((path, skill)) = by_name.remove(&name) else {
+                continue;
+            };
+            if let Some(reason) = self.check_compatibility(&skill.package().metadata) {
+                tracing::warn!("Not registering skill {} from {:?}: {}", name, path, reason);
+                skipped.insert(name);
+                continue;
+            }
+            self.register(Box::new(skill))?;
+            registered.push(name);
+        }
+
+        for cycle in &result.cycles {
+            tracing::warn!("Not registering skills on circular dependency: {}", cycle.join(" -> "));
+        }
+
+        result.load_order = registered;
+        Ok(result)
+    }
+
+    /// Resolve the dependencies of discovered skills, keyed by skill name
+    fn resolve_discovered(
+        skills: &[(std::path::PathBuf, PackagedSkill)],
+    ) -> (ResolutionResult, std::collections::HashMap<String, Vec<Dependency>>) {
+        let ids: std::collections::HashMap<_, _> = skills
+            .iter()
+            .map(|(_, skill)| (skill.package().metadata.id.as_str(), skill.name()))
+            .collect();
+
+        let mut resolver = DependencyResolver::new();
+        let mut graph = std::collections::HashMap::new();
+        let mut warnings = Vec::new();
+
+        for (path, skill) in skills {
+            let metadata = &skill.package().metadata;
+            resolver.add_skill(metadata.name.clone(), metadata.version.clone());
+
+            let mut deps = Vec::new();
+            for spec in &metadata.dependencies {
+                let mut dep = Dependency::parse(spec);
+                if let Some(name) = ids.get(dep.skill_id.as_str()) {
+                    dep.skill_id = name.clone();
+                }
+                if let Some(ref requirement) = dep.version_requirement {
+                    if let Err(e) = semver::VersionReq::parse(requirement) {
+                        let warning = format!(
+                            "{}: invalid version requirement '{}' for dependency '{}': {}",
+                            path.display(),
+                            requirement,
+                            dep.skill_id,
+                            e
+                        );
+                        tracing::warn!("{}", warning);
+                        warnings.push(warning);
+                        dep.version_requirement = None;
+                    }
+                }
+                deps.push(dep);
+            }
+            graph.insert(metadata.name.clone(), deps);
+        }
+
+        let mut result = resolver.resolve(&graph);
+        warnings.append(&mut result.warnings);
+        result.warnings = warnings;
+        (result, graph)
+    }
+
+    /// Remove a registered skill, returning it if it was registered
+    pub fn unregister(&mut self, name: &str) -> Option<Box<dyn Skill>> {
+        self.forget_outputs(name);
+        let skill = self.skills.remove(name)?;
+        if let Some(package) = skill.skill_package() {
+            self.search_index.remove(&package.metadata.id);
+        }
+        Some(skill)
+    }
+
+    /// Skills [`load_dir`](Self::load_dir) registers, with the file each came from
+    pub(crate) fn discover_skills(
+        dir: &Path,
+    ) -> Result<Vec<(std::path::PathBuf, PackagedSkill)>, SkillError> {
+        let scanner = SkillsDirScanner::new(dir);
+        let skill_md_files = scanner
+            .scan()
+            .map_err(|e| SkillError::Io(format!("Failed to scan skills directory: {}", e)))?;
+        let mut skills: Vec<_> = skill_md_files
+            .iter()
+            .map(|file| (file.path().to_path_buf(), PackagedSkill::from_skill_md(file)))
+            .collect();
+
+        let mut seen_ids: std::collections::HashSet<_> = skills
+            .iter()
+            .map(|(_, skill)| skill.package().metadata.id.clone())
+            .collect();
+        for (path, package) in Self::discover_packages(dir)? {
+            if seen_ids.insert(package.metadata.id.clone()) {
+                skills.push((path, PackagedSkill::new(package)));
+            }
+        }
+        Ok(skills)
+    }
+
     /// Discover and load skill packages from a directory
     ///
     /// This method searches for `.json` files in the given directory,
@@ -118,7 +661,14 @@ impl SkillRegistry {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn discover_from_dir<P: AsRef<Path>>(dir: P) -> Result<Vec<SkillPackage>, SkillError> {
-        let dir = dir.as_ref();
+        let packages = Self::discover_packages(dir.as_ref())?;
+        Ok(packages.into_iter().map(|(_, package)| package).collect())
+    }
+
+    /// [`discover_from_dir`](Self::discover_from_dir), keeping the path of each package
+    fn discover_packages(
+        dir: &Path,
+    ) -> Result<Vec<(std::path::PathBuf, SkillPackage)>, SkillError> {
 
         if !dir.exists() {
             return Err(SkillError::Io(format!(
@@ -157,7 +707,7 @@ impl SkillRegistry {
                         package.metadata.name,
                         path
                     );
-                    packages.push(package);
+                    packages.push((path, package));
                 },
                 Err(e) => {
                     tracing::warn!("Failed to load skill package from {:?}: {}", path, e);
@@ -273,4 +823,65 @@ impl SkillRegistry {
 
         Ok(all_packages)
     }
+
+    /// Infer tags for the SKILL.md skills in `dir` and write them into their frontmatter
+    ///
+    /// Tags are inferred with [`tags::infer_with_claude`], which only contacts Claude
+    /// when [`InferConfig::claude`] is set. Existing tags are kept and only the `tags:`
+    /// entry of each frontmatter is rewritten. With `dry_run`, no file is modified.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::{InferConfig, SkillRegistry};
+    ///
+    /// # async fn example() -> Result<(), claude_agent_sdk::skills::SkillError> {
+    /// let report = SkillRegistry::backfill_tags(".claude/skills", &InferConfig::default(), true).await?;
+    /// for entry in report {
+    ///     println!("{}: {:?}", entry.skill, entry.added);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn backfill_tags<P: AsRef<Path>>(
+        dir: P,
+        config: &InferConfig,
+        dry_run: bool,
+    ) -> Result<Vec<TagBackfill>, SkillError> {
+        let scanner = crate::skills::SkillsDirScanner::new(dir.as_ref());
+        let skills: Vec<SkillMdFile> = scanner
+            .scan()
+            .map_err(|e| SkillError::Io(format!("Failed to scan skills directory: {}", e)))?
+            .into_iter()
+            .filter(|skill| !config.only_untagged || skill.metadata.tags.is_empty())
+            .collect();
+
+        let mut config = config.clone();
+        if config.corpus.is_none() {
+            config.corpus = Some(tags::KeywordCorpus::from_skills(&skills));
+        }
+        let inferred = tags::infer_with_claude(&skills, &config).await;
+
+        let mut report = Vec::new();
+        for (skill, added) in skills.into_iter().zip(inferred) {
+            if added.is_empty() {
+                continue;
+            }
+
+            let path = skill.skill_dir.join("SKILL.md");
+            if !dry_run {
+                let names: Vec<String> = added.iter().map(|t| t.tag.clone()).collect();
+                tags::write_tags(&path, &names)?;
+            }
+
+            report.push(TagBackfill {
+                path,
+                skill: skill.metadata.name,
+                existing: skill.metadata.tags,
+                added,
+                written: !dry_run,
+            });
+        }
+
+        Ok(report)
+    }
 }
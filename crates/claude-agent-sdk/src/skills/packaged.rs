@@ -0,0 +1,466 @@
+//! Running discovered skill packages as executable [`Skill`]s
+//!
+//! [`SkillRegistry::discover_from_dir`](super::SkillRegistry::discover_from_dir) and
+//! friends return [`SkillPackage`]s, which only describe a skill. [`PackagedSkill`]
+//! adapts a package to the [`Skill`] trait by sending its instructions, with
+//! the input, to Claude.
+
+use async_trait::async_trait;
+use std::fmt;
+
+use super::progressive_disclosure::{
+    SKILL_LIST_RESOURCES_TOOL, SKILL_RESOURCE_TOOL, SKILL_RESOURCES_SERVER, skill_resource_server,
+    skill_resource_tool_names,
+};
+use super::{Skill, SkillError, SkillInput, SkillMdFile, SkillOutput, SkillPackage, SkillResult};
+use crate::prompts::SystemPromptBuilder;
+use crate::types::config::{ClaudeAgentOptions, PermissionMode, Tools};
+use crate::types::mcp::{McpServerConfig, McpServers};
+use crate::types::messages::CollectedResponse;
+
+/// A [`SkillPackage`] executed through [`query`](crate::query::query)
+///
+/// The package's `resources.tools` restrict the tools Claude may use; when
+/// empty, tools are not restricted. A pattern such as `Bash(python:*)` makes
+/// the tool available but only approves the matching calls, so a restricted
+/// skill never runs with [`PermissionMode::BypassPermissions`]. A model,
+/// typically from the `model` field of a SKILL.md file, overrides the model of
+/// the base options.
+///
+/// When the package has resource folders, they are not put in the prompt:
+/// Claude reads them on demand through the tools of
+/// [`skill_resource_server`], which is added to the options as
+/// [`SKILL_RESOURCES_SERVER`].
+///
+/// # Example
+///
+/// ```no_run
+/// use claude_agent_sdk::skills::{PackagedSkill, Skill, SkillInput, SkillRegistry};
+///
+/// # async fn example() -> Result<(), claude_agent_sdk::skills::SkillError> {
+/// for package in SkillRegistry::discover_from_dir("skills")? {
+///     let skill = PackagedSkill::new(package).with_model("claude-sonnet-4-5");
+///     let output = skill
+///         .execute(SkillInput { params: serde_json::json!({"file": "src/lib.rs"}) })
+///         .await?;
+///     println!("{}", output);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct PackagedSkill {
+    package: SkillPackage,
+    model: Option<String>,
+    options: ClaudeAgentOptions,
+    prompt_template: Option<SystemPromptBuilder>,
+    model_invocable: bool,
+}
+
+impl PackagedSkill {
+    /// Wrap `package`, running it with default options
+    pub fn new(package: SkillPackage) -> Self {
+        Self {
+            package,
+            model: None,
+            options: ClaudeAgentOptions::default(),
+            prompt_template: None,
+            model_invocable: true,
+        }
+    }
+
+    /// Wrap a parsed SKILL.md file, keeping its `allowed_tools`, `model` and
+    /// `disable_model_invocation`
+    pub fn from_skill_md(skill_md: &SkillMdFile) -> Self {
+        let mut skill = Self::new(skill_md.to_skill_package());
+        skill.model = skill_md.metadata.model.clone();
+        skill.model_invocable = skill_md.metadata.disable_model_invocation != Some(true);
+        skill
+    }
+
+    /// Run the skill with `model`
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Base options for running the skill, such as its working directory
+    ///
+    /// The skill's tool restriction and model are applied on top.
+    pub fn with_options(mut self, options: ClaudeAgentOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Compose the prompt with `template` instead of the default layout
+    ///
+    /// The variables `name`, `instructions` and `input` are set by
+    /// [`render_prompt`](Self::render_prompt); `input` is empty when there is
+    /// none. The template's preset is ignored, since the result is the prompt
+    /// of the query rather than a system prompt.
+    pub fn with_prompt_template(mut self, template: SystemPromptBuilder) -> Self {
+        self.prompt_template = Some(template);
+        self
+    }
+
+    /// The wrapped package
+    pub fn package(&self) -> &SkillPackage {
+        &self.package
+    }
+
+    /// Model the skill runs with, if it sets one
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Tools the skill may use, or `None` if it is not restricted
+    pub fn allowed_tools(&self) -> Option<&[String]> {
+        let tools = &self.package.resources.tools;
+        (!tools.is_empty()).then_some(tools.as_slice())
+    }
+
+    /// Resource folders of the package that exist
+    fn resource_folders(&self) -> Vec<&std::path::PathBuf> {
+        self.package
+            .resources
+            .folders
+            .iter()
+            .filter(|folder| folder.is_dir())
+            .collect()
+    }
+
+    /// Prompt sent to Claude: the instructions followed by the input
+    ///
+    /// A string input is appended as is; other non-null input is appended as
+    /// pretty-printed JSON. For a skill with resources, a note on how to read
+    /// them follows the instructions. The layout can be changed with
+    /// [`with_prompt_template`](Self::with_prompt_template).
+    ///
+    /// # Errors
+    ///
+    /// Returns `SkillError::Validation` if the package has no instructions or
+    /// the prompt template fails to render
+    pub fn render_prompt(&self, input: &SkillInput) -> Result<String, SkillError> {
+        let mut instructions = self.package.instructions.trim().to_string();
+        if instructions.is_empty() {
+            return Err(SkillError::Validation(format!(
+                "Skill '{}' has no instructions",
+                self.package.metadata.name
+            )));
+        }
+        if !self.resource_folders().is_empty() {
+            instructions.push_str(&format!(
+                "\n\nThis skill has resource files. List them with the `{}` tool and read \
+                 them with the `{}` tool, passing skill \"{}\".",
+                SKILL_LIST_RESOURCES_TOOL, SKILL_RESOURCE_TOOL, self.package.metadata.name
+            ));
+        }
+
+        let input = match &input.params {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(text) => text.clone(),
+            params => {
+                let json = serde_json::to_string_pretty(params)
+                    .map_err(|e| SkillError::Serialization(e.to_string()))?;
+                format!("```json\n{}\n```", json)
+            },
+        };
+        self.prompt_template
+            .clone()
+            .unwrap_or_else(|| {
+                SystemPromptBuilder::new()
+                    .role("{{instructions}}")
+                    .section("## Input", "{{input}}")
+            })
+            .var("name", &self.package.metadata.name)
+            .var("instructions", instructions)
+            .var("input", input)
+            .render()
+            .map_err(|e| {
+                SkillError::Validation(format!(
+                    "Prompt template of skill '{}': {}",
+                    self.package.metadata.name, e
+                ))
+            })
+    }
+
+    /// Options for one run of the skill
+    fn run_options(&self) -> ClaudeAgentOptions {
+        let mut options = self.options.clone();
+        if let Some(tools) = self.allowed_tools() {
+            let mut names: Vec<String> = Vec::new();
+            for tool in tools {
+                let name = tool.split_once('(').map_or(tool.as_str(), |(name, _)| name);
+                if !names.iter().any(|n| n == name) {
+                    names.push(name.to_string());
+                }
+            }
+            options.tools = Some(Tools::List(names));
+            options.allowed_tools = tools.to_vec();
+            if options.permission_mode == Some(PermissionMode::BypassPermissions) {
+                tracing::warn!(
+                    "Skill '{}' restricts its tools; running it without bypassing permissions",
+                    self.package.metadata.name
+                );
+                options.permission_mode = Some(PermissionMode::Default);
+            }
+        }
+        if let Some(model) = &self.model {
+            options.model = Some(model.clone());
+        }
+
+        let folders = self.resource_folders();
+        if !folders.is_empty() {
+            let name = &self.package.metadata.name;
+            let server = skill_resource_server(folders.into_iter().map(|dir| (name.clone(), dir.clone())));
+            match &mut options.mcp_servers {
+                McpServers::Dict(servers) => {
+                    servers.insert(SKILL_RESOURCES_SERVER.to_string(), McpServerConfig::Sdk(server));
+                },
+                McpServers::Empty => {
+                    options.mcp_servers = McpServers::Dict(
+                        [(SKILL_RESOURCES_SERVER.to_string(), McpServerConfig::Sdk(server))].into(),
+                    );
+                },
+                McpServers::Path(path) => {
+                    tracing::warn!(
+                        "Skill '{}': MCP servers come from {:?}, its resources are not available",
+                        name,
+                        path
+                    );
+                    return options;
+                },
+            }
+
+            options.allowed_tools.extend(skill_resource_tool_names());
+        }
+        options
+    }
+}
+
+#[async_trait]
+impl Skill for PackagedSkill {
+    fn name(&self) -> String {
+        self.package.metadata.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.package.metadata.description.clone()
+    }
+
+    async fn execute(&self, input: SkillInput) -> SkillResult {
+        let prompt = self.render_prompt(&input)?;
+        let messages = crate::query::query(prompt, Some(self.run_options()))
+            .await
+            .map_err(|e| SkillError::Execution(e.to_string()))?;
+
+        let response = CollectedResponse::from(messages);
+        let result = response.result().ok_or_else(|| {
+            SkillError::Execution(format!(
+                "Skill '{}' finished without a result",
+                self.package.metadata.name
+            ))
+        })?;
+        if result.is_error {
+            return Err(SkillError::Execution(
+                result
+                    .result
+                    .clone()
+                    .unwrap_or_else(|| format!("Skill '{}' failed", self.package.metadata.name)),
+            ));
+        }
+
+        let text = result.result.clone().unwrap_or_else(|| response.text());
+        Ok(SkillOutput::ok(text).with_metadata(serde_json::json!({
+            "session_id": result.session_id,
+            "num_turns": result.num_turns,
+            "total_cost_usd": result.total_cost_usd,
+        })))
+    }
+
+    fn validate(&self) -> Result<(), SkillError> {
+        if self.package.metadata.name.trim().is_empty() {
+            return Err(SkillError::Validation(
+                "Skill name cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn skill_package(&self) -> Option<&SkillPackage> {
+        Some(&self.package)
+    }
+
+    fn model_invocable(&self) -> bool {
+        self.model_invocable
+    }
+}
+
+impl fmt::Debug for PackagedSkill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackagedSkill")
+            .field("name", &self.package.metadata.name)
+            .field("model", &self.model)
+            .field("allowed_tools", &self.allowed_tools())
+            .field("prompt_template", &self.prompt_template)
+            .field("model_invocable", &self.model_invocable)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::{SkillMetadata, SkillResources};
+
+    fn package(instructions: &str, tools: &[&str]) -> SkillPackage {
+        SkillPackage {
+            metadata: SkillMetadata {
+                id: "skill.reviewer".to_string(),
+                name: "reviewer".to_string(),
+                description: "Reviews code".to_string(),
+                version: "1.0.0".to_string(),
+                author: None,
+                dependencies: vec![],
+                tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
+            },
+            instructions: instructions.to_string(),
+            scripts: vec![],
+            resources: SkillResources {
+                tools: tools.iter().map(|tool| tool.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let skill = PackagedSkill::new(package("Review the file.\n", &[]));
+
+        let none = SkillInput::default();
+        assert_eq!(skill.render_prompt(&none).unwrap(), "Review the file.");
+
+        let text = SkillInput {
+            params: serde_json::json!("src/lib.rs"),
+        };
+        assert_eq!(
+            skill.render_prompt(&text).unwrap(),
+            "Review the file.\n\n## Input\n\nsrc/lib.rs"
+        );
+
+        let json = SkillInput {
+            params: serde_json::json!({"file": "a.rs"}),
+        };
+        assert_eq!(
+            skill.render_prompt(&json).unwrap(),
+            "Review the file.\n\n## Input\n\n```json\n{\n  \"file\": \"a.rs\"\n}\n```"
+        );
+    }
+
+    #[test]
+    fn test_render_prompt_with_template() {
+        let template = SystemPromptBuilder::new()
+            .role("Skill {{name}}:\n{{instructions}}")
+            .section("Task", "{{#if input}}Work on {{input}}.{{else}}Pick a file.{{/if}}");
+        let skill =
+            PackagedSkill::new(package("Review the file.", &[])).with_prompt_template(template);
+
+        let text = SkillInput {
+            params: serde_json::json!("src/lib.rs"),
+        };
+        assert_eq!(
+            skill.render_prompt(&text).unwrap(),
+            "Skill reviewer:\nReview the file.\n\nTask:\nWork on src/lib.rs."
+        );
+        assert_eq!(
+            skill.render_prompt(&SkillInput::default()).unwrap(),
+            "Skill reviewer:\nReview the file.\n\nTask:\nPick a file."
+        );
+
+        let skill = skill.with_prompt_template(SystemPromptBuilder::new().role("{{#if input}}"));
+        assert!(matches!(
+            skill.render_prompt(&SkillInput::default()),
+            Err(SkillError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_instructions_fails_validation() {
+        let skill = PackagedSkill::new(package("  \n", &[]));
+        assert!(skill.validate().is_ok());
+        assert!(matches!(
+            skill.execute(SkillInput::default()).await,
+            Err(SkillError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_options_apply_tools_and_model() {
+        let base = ClaudeAgentOptions::builder()
+            .model("base-model")
+            .max_turns(2)
+            .build();
+
+        let unrestricted = PackagedSkill::new(package("x", &[])).with_options(base.clone());
+        let options = unrestricted.run_options();
+        assert!(options.tools.is_none());
+        assert_eq!(options.model.as_deref(), Some("base-model"));
+        assert_eq!(options.max_turns, Some(2));
+
+        let tools = ["Read", "Grep", "Bash(git:*)", "Bash(cargo:*)"];
+        let restricted = PackagedSkill::new(package("x", &tools))
+            .with_options(base.clone())
+            .with_model("skill-model");
+        let options = restricted.run_options();
+        assert!(matches!(options.tools, Some(Tools::List(ref names)) if names == &["Read", "Grep", "Bash"]));
+        assert_eq!(options.allowed_tools, tools);
+        assert_eq!(options.model.as_deref(), Some("skill-model"));
+        assert_eq!(options.permission_mode, None);
+
+        // Bypassing permissions would approve any Bash command
+        let mut bypass = base;
+        bypass.permission_mode = Some(PermissionMode::BypassPermissions);
+        let options = PackagedSkill::new(package("x", &["Bash(git:*)"]))
+            .with_options(bypass.clone())
+            .run_options();
+        assert_eq!(options.permission_mode, Some(PermissionMode::Default));
+        let options = PackagedSkill::new(package("x", &[])).with_options(bypass).run_options();
+        assert_eq!(options.permission_mode, Some(PermissionMode::BypassPermissions));
+    }
+
+    #[test]
+    fn test_resources_are_served_not_inlined() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let resources = temp_dir.path().join("resources");
+        std::fs::create_dir(&resources).unwrap();
+        std::fs::write(resources.join("guide.md"), "A very long guide").unwrap();
+
+        let mut package = package("Review the file.", &["Read"]);
+        package.resources.folders.push(resources);
+        let skill = PackagedSkill::new(package);
+
+        let prompt = skill.render_prompt(&SkillInput::default()).unwrap();
+        assert!(!prompt.contains("A very long guide"));
+        assert!(prompt.contains(SKILL_RESOURCE_TOOL));
+
+        let options = skill.run_options();
+        let McpServers::Dict(servers) = &options.mcp_servers else {
+            panic!("Expected the resources server to be configured");
+        };
+        assert!(matches!(
+            servers.get(SKILL_RESOURCES_SERVER),
+            Some(McpServerConfig::Sdk(_))
+        ));
+        assert_eq!(
+            options.allowed_tools,
+            vec![
+                "Read",
+                "mcp__skill-resources__skill_resource",
+                "mcp__skill-resources__skill_list_resources"
+            ]
+        );
+    }
+}
@@ -1,12 +1,16 @@
 //! # Performance Optimization for Agent Skills
 //!
 //! This module provides performance optimizations for large-scale skill operations,
-//! including indexing, caching, and batch processing.
+//! including indexing, caching, and batch processing, and the execution
+//! statistics [`SkillRegistry`](super::SkillRegistry) keeps in [`SkillStats`].
 
+use crate::skills::search::SkillSearchIndex;
 use crate::skills::tags::TagFilter;
-use crate::skills::types::SkillPackage;
-use std::collections::{HashMap, HashSet};
+use crate::skills::types::{SkillMetadata, SkillPackage};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use std::time::{Duration, Instant};
 use tracing::debug;
 
@@ -27,6 +31,21 @@ pub struct PerformanceStats {
 
     /// Items processed
     pub items_processed: usize,
+
+    /// Operations that succeeded
+    pub successes: usize,
+
+    /// Operations that failed
+    pub failures: usize,
+
+    /// Median latency of the recent operations
+    pub p50_latency: Option<Duration>,
+
+    /// 95th percentile latency of the recent operations
+    pub p95_latency: Option<Duration>,
+
+    /// Message of the latest failure
+    pub last_error: Option<String>,
 }
 
 impl PerformanceStats {
@@ -132,6 +151,19 @@ where
         self.map.contains_key(key)
     }
 
+    /// Remove a value from the cache
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.map.remove(key)?;
+        self.access_order.retain(|k| k != key);
+        Some(value)
+    }
+
+    /// Remove the values whose key does not satisfy `keep`
+    pub fn retain(&mut self, mut keep: impl FnMut(&K) -> bool) {
+        self.map.retain(|k, _| keep(k));
+        self.access_order.retain(|k| keep(k));
+    }
+
     /// Clear the cache
     pub fn clear(&mut self) {
         self.map.clear();
@@ -149,6 +181,204 @@ where
     }
 }
 
+/// Number of latencies kept per skill for the p50 and p95 of [`SkillStats`]
+const LATENCY_WINDOW: usize = 256;
+
+/// Execution statistics of skills, per skill name
+///
+/// [`SkillRegistry`](super::SkillRegistry) records every
+/// [`execute`](super::SkillRegistry::execute) here, and
+/// [`SandboxExecutor::with_stats`](super::SandboxExecutor::with_stats) every
+/// script run. Counters are atomics; only the window of recent latencies and
+/// the last error are briefly locked.
+#[derive(Debug, Default)]
+pub struct SkillStats {
+    skills: RwLock<HashMap<String, Arc<SkillCounters>>>,
+}
+
+/// Counters of one skill
+#[derive(Debug, Default)]
+struct SkillCounters {
+    invocations: AtomicUsize,
+    successes: AtomicUsize,
+    failures: AtomicUsize,
+    cache_hits: AtomicUsize,
+    cache_misses: AtomicUsize,
+    total_nanos: AtomicU64,
+    latencies: Mutex<VecDeque<Duration>>,
+    last_error: Mutex<Option<(Instant, String)>>,
+}
+
+impl SkillStats {
+    /// Create empty statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one execution of `skill` taking `elapsed`, failed with `error` if set
+    pub fn record(&self, skill: &str, elapsed: Duration, error: Option<&str>) {
+        let counters = self.counters(skill);
+        counters.invocations.fetch_add(1, Ordering::Relaxed);
+        counters
+            .total_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        match error {
+            None => counters.successes.fetch_add(1, Ordering::Relaxed),
+            Some(error) => {
+                *lock(&counters.last_error) = Some((Instant::now(), error.to_string()));
+                counters.failures.fetch_add(1, Ordering::Relaxed)
+            },
+        };
+
+        let mut latencies = lock(&counters.latencies);
+        if latencies.len() == LATENCY_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(elapsed);
+    }
+
+    /// Record whether an execution of `skill` was answered from the output cache
+    pub fn record_cache(&self, skill: &str, hit: bool) {
+        let counters = self.counters(skill);
+        let counter = if hit {
+            &counters.cache_hits
+        } else {
+            &counters.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Statistics of one skill, or `None` if nothing was recorded for it
+    ///
+    /// `operations` and `items_processed` both count its executions.
+    pub fn get(&self, skill: &str) -> Option<PerformanceStats> {
+        let counters = self.read().get(skill).cloned()?;
+        let latencies: Vec<Duration> = lock(&counters.latencies).iter().copied().collect();
+        let mut stats = counters.totals();
+        stats.p50_latency = percentile(latencies.clone(), 50);
+        stats.p95_latency = percentile(latencies, 95);
+        stats.last_error = lock(&counters.last_error)
+            .as_ref()
+            .map(|(_, error)| error.clone());
+        Some(stats)
+    }
+
+    /// Statistics of all skills together
+    ///
+    /// The latencies are those of every skill, and `last_error` is the latest
+    /// failure of any, prefixed with the skill's name.
+    pub fn total(&self) -> PerformanceStats {
+        let mut stats = PerformanceStats::new();
+        let mut latencies = Vec::new();
+        let mut last_error: Option<(Instant, String)> = None;
+        for (skill, counters) in self.read().iter() {
+            let totals = counters.totals();
+            stats.operations += totals.operations;
+            stats.items_processed += totals.items_processed;
+            stats.successes += totals.successes;
+            stats.failures += totals.failures;
+            stats.cache_hits += totals.cache_hits;
+            stats.cache_misses += totals.cache_misses;
+            stats.total_duration += totals.total_duration;
+            latencies.extend(lock(&counters.latencies).iter().copied());
+            if let Some((at, error)) = lock(&counters.last_error).as_ref() {
+                if last_error.as_ref().is_none_or(|(latest, _)| at > latest) {
+                    last_error = Some((*at, format!("{}: {}", skill, error)));
+                }
+            }
+        }
+        stats.p50_latency = percentile(latencies.clone(), 50);
+        stats.p95_latency = percentile(latencies, 95);
+        stats.last_error = last_error.map(|(_, error)| error);
+        stats
+    }
+
+    /// Names of the skills with recorded statistics, sorted
+    pub fn skills(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.read().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Forget the statistics of every skill
+    pub fn reset(&self) {
+        self.skills
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    /// Forget the statistics of `skill`
+    pub fn reset_skill(&self, skill: &str) {
+        self.skills
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(skill);
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, Arc<SkillCounters>>> {
+        self.skills.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn counters(&self, skill: &str) -> Arc<SkillCounters> {
+        if let Some(counters) = self.read().get(skill) {
+            return Arc::clone(counters);
+        }
+        let mut skills = self.skills.write().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(skills.entry(skill.to_string()).or_default())
+    }
+}
+
+impl SkillCounters {
+    /// The counters as statistics, without latencies or last error
+    fn totals(&self) -> PerformanceStats {
+        let invocations = self.invocations.load(Ordering::Relaxed);
+        PerformanceStats {
+            operations: invocations,
+            total_duration: Duration::from_nanos(self.total_nanos.load(Ordering::Relaxed)),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            items_processed: invocations,
+            successes: self.successes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            ..PerformanceStats::default()
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Nearest-rank percentile of `samples`
+fn percentile(mut samples: Vec<Duration>, percent: usize) -> Option<Duration> {
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_unstable();
+    let rank = (samples.len() * percent).div_ceil(100).max(1);
+    Some(samples[rank - 1])
+}
+
+/// Bounds of the output cache of a [`SkillRegistry`](super::SkillRegistry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkillCacheConfig {
+    /// How long a cached output is reused
+    pub ttl: Duration,
+
+    /// Most outputs kept, across all skills; 0 disables caching
+    pub max_entries: usize,
+}
+
+impl Default for SkillCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(300),
+            max_entries: 256,
+        }
+    }
+}
+
 /// Multi-indexed skill collection for fast queries
 #[derive(Debug, Clone)]
 pub struct IndexedSkillCollection {
@@ -163,6 +393,9 @@ pub struct IndexedSkillCollection {
 
     /// Cache for query results
     query_cache: LruCache<String, Vec<usize>>,
+
+    /// Full-text index of names, descriptions, instructions and tags
+    search_index: SkillSearchIndex,
 }
 
 impl Default for IndexedSkillCollection {
@@ -179,6 +412,7 @@ impl IndexedSkillCollection {
             by_name: HashMap::new(),
             by_tag: HashMap::new(),
             query_cache: LruCache::new(100),
+            search_index: SkillSearchIndex::default(),
         }
     }
 
@@ -189,6 +423,7 @@ impl IndexedSkillCollection {
             by_name: HashMap::with_capacity(capacity),
             by_tag: HashMap::new(),
             query_cache: LruCache::new(100),
+            search_index: SkillSearchIndex::default(),
         }
     }
 
@@ -207,6 +442,7 @@ impl IndexedSkillCollection {
                 .push(index);
         }
 
+        self.search_index.upsert(&skill);
         self.skills.push(skill);
     }
 
@@ -265,6 +501,20 @@ impl IndexedSkillCollection {
         indices.iter().map(|&i| &self.skills[i]).collect()
     }
 
+    /// Skills matching a free-text `query`, best first, with their score
+    ///
+    /// See [`SkillSearchIndex::search`] for the ranking.
+    pub fn search(&self, query: &str) -> Vec<(f64, &SkillMetadata)> {
+        self.search_index
+            .search(query, usize::MAX)
+            .into_iter()
+            .filter_map(|hit| {
+                let skill = self.get_by_name(&hit.name)?;
+                (skill.metadata.id == hit.id).then_some((hit.score, &skill.metadata))
+            })
+            .collect()
+    }
+
     /// Get all skills
     pub fn all(&self) -> Vec<&SkillPackage> {
         self.skills.iter().collect()
@@ -286,6 +536,7 @@ impl IndexedSkillCollection {
         self.by_name.clear();
         self.by_tag.clear();
         self.query_cache.clear();
+        self.search_index = SkillSearchIndex::default();
     }
 
     /// Rebuild all indexes
@@ -353,6 +604,9 @@ mod tests {
                 author: None,
                 dependencies: Vec::new(),
                 tags: tags.into_iter().map(String::from).collect(),
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: String::new(),
             scripts: Vec::new(),
@@ -360,6 +614,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_indexed_collection_search() {
+        let mut collection = IndexedSkillCollection::new();
+        let mut extractor = create_test_skill("pdf-extractor", vec!["pdf"]);
+        extractor.metadata.description = "Convert PDF documents to plain text".to_string();
+        let mut merger = create_test_skill("pdf-merger", vec!["pdf"]);
+        merger.metadata.description = "Merge PDF files".to_string();
+        collection.add_batch(vec![
+            extractor,
+            merger,
+            create_test_skill("text-summarizer", vec!["text"]),
+        ]);
+
+        let hits: Vec<&str> = collection
+            .search("convert pdf to text")
+            .into_iter()
+            .map(|(_, skill)| skill.name.as_str())
+            .collect();
+        assert_eq!(hits, vec!["pdf-extractor", "pdf-merger", "text-summarizer"]);
+
+        collection.clear();
+        assert!(collection.search("pdf").is_empty());
+    }
+
     #[test]
     fn test_performance_stats_default() {
         let stats = PerformanceStats::new();
@@ -424,6 +702,82 @@ mod tests {
         assert_eq!(cache.len(), 2);
     }
 
+    #[test]
+    fn test_lru_cache_remove_and_retain() {
+        let mut cache = LruCache::new(3);
+        cache.put("key1", 1);
+        cache.put("key2", 2);
+        cache.put("key3", 3);
+
+        assert_eq!(cache.remove(&"key1"), Some(1));
+        assert_eq!(cache.remove(&"key1"), None);
+        cache.retain(|key| *key != "key2");
+        assert_eq!(cache.len(), 1);
+
+        // Removed keys no longer take a slot
+        cache.put("key4", 4);
+        cache.put("key5", 5);
+        assert_eq!(cache.get(&"key3"), Some(&3));
+    }
+
+    #[test]
+    fn test_skill_stats_record() {
+        let stats = SkillStats::new();
+        for ms in 1..=20 {
+            stats.record("lint", Duration::from_millis(ms), None);
+        }
+        stats.record("lint", Duration::from_millis(100), Some("timeout"));
+        stats.record("format", Duration::from_millis(5), Some("bad input"));
+        stats.record_cache("lint", false);
+        stats.record_cache("lint", true);
+
+        let lint = stats.get("lint").unwrap();
+        assert_eq!(lint.operations, 21);
+        assert_eq!((lint.successes, lint.failures), (20, 1));
+        assert_eq!((lint.cache_hits, lint.cache_misses), (1, 1));
+        assert_eq!(lint.p50_latency, Some(Duration::from_millis(11)));
+        assert_eq!(lint.p95_latency, Some(Duration::from_millis(20)));
+        assert_eq!(lint.last_error.as_deref(), Some("timeout"));
+        assert_eq!(lint.total_duration, Duration::from_millis(310));
+
+        let total = stats.total();
+        assert_eq!(total.operations, 22);
+        assert_eq!(total.failures, 2);
+        assert_eq!(total.last_error.as_deref(), Some("format: bad input"));
+        assert_eq!(stats.skills(), vec!["format", "lint"]);
+        assert!(stats.get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_skill_stats_keep_recent_latencies() {
+        let stats = SkillStats::new();
+        for _ in 0..LATENCY_WINDOW {
+            stats.record("slow", Duration::from_secs(1), None);
+        }
+        for _ in 0..LATENCY_WINDOW {
+            stats.record("slow", Duration::from_millis(1), None);
+        }
+
+        let slow = stats.get("slow").unwrap();
+        assert_eq!(slow.operations, 2 * LATENCY_WINDOW);
+        assert_eq!(slow.p95_latency, Some(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn test_skill_stats_reset() {
+        let stats = SkillStats::new();
+        stats.record("lint", Duration::from_millis(1), None);
+        stats.record("format", Duration::from_millis(1), None);
+
+        stats.reset_skill("lint");
+        assert!(stats.get("lint").is_none());
+        assert!(stats.get("format").is_some());
+
+        stats.reset();
+        assert_eq!(stats.total().operations, 0);
+        assert_eq!(stats.total().p50_latency, None);
+    }
+
     #[test]
     fn test_lru_cache_access_order() {
         let mut cache = LruCache::new(2);
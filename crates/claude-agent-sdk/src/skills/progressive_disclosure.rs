@@ -4,14 +4,41 @@
 //! where SKILL.md contains essential information and supporting files are loaded
 //! on-demand to save context window space.
 //!
+//! Files in a skill's `resources/` directory are served to Claude on demand
+//! by the `skill_resource` and `skill_list_resources` tools of
+//! [`skill_resource_server`].
+//!
 //! Based on: https://code.claude.com/docs/en/skills
 
 use crate::skills::skill_md::{SkillMdError, SkillMdFile};
+use crate::types::mcp::{
+    McpSdkServerConfig, SdkMcpTool, ToolResult, ToolResultContent, create_sdk_mcp_server,
+};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use regex::Regex;
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
 
+/// Name of the MCP server created by [`skill_resource_server`]
+pub const SKILL_RESOURCES_SERVER: &str = "skill-resources";
+
+/// Tool returning the contents of a skill resource
+pub const SKILL_RESOURCE_TOOL: &str = "skill_resource";
+
+/// Tool listing the resources of a skill
+pub const SKILL_LIST_RESOURCES_TOOL: &str = "skill_list_resources";
+
+/// Largest resource, in bytes, [`read_resource`] returns
+pub const MAX_RESOURCE_BYTES: u64 = 1024 * 1024;
+
+/// How deep [`list_resources`] descends into a resources directory
+const MAX_RESOURCE_DEPTH: usize = 8;
+
 /// Errors for progressive disclosure
 #[derive(Debug, Error)]
 pub enum ProgressiveError {
@@ -23,6 +50,25 @@ pub enum ProgressiveError {
 
     #[error("Invalid reference format: {0}")]
     InvalidReference(String),
+
+    #[error("Invalid resource path: {0}")]
+    InvalidResourcePath(String),
+
+    #[error("Resource {resource} is {size} bytes, over the {limit} byte limit")]
+    ResourceTooLarge {
+        resource: String,
+        size: u64,
+        limit: u64,
+    },
+}
+
+/// Contents of a skill resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceContent {
+    /// A UTF-8 text file
+    Text(String),
+    /// Any other file
+    Binary(Vec<u8>),
 }
 
 /// Progressive skill loader implementing lazy loading of supporting files
@@ -66,8 +112,9 @@ pub enum ProgressiveError {
 #[derive(Debug, Clone)]
 pub struct ProgressiveSkillLoader {
     /// Path to the skill directory
-    #[allow(dead_code)]
     skill_dir: PathBuf,
+    /// Name of the skill from SKILL.md
+    name: String,
     /// Content from SKILL.md (always loaded)
     main_content: String,
     /// Referenced supporting files (discovered but not loaded)
@@ -118,6 +165,7 @@ impl ProgressiveSkillLoader {
 
         Ok(Self {
             skill_dir: skill_dir.to_path_buf(),
+            name: skill_file.metadata.name,
             main_content: skill_file.content,
             referenced_files,
             available_scripts,
@@ -238,6 +286,57 @@ impl ProgressiveSkillLoader {
         self.referenced_files.contains_key(filename)
     }
 
+    /// Name of the skill
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The skill's `resources/` directory, which may not exist
+    pub fn resources_dir(&self) -> PathBuf {
+        self.skill_dir.join("resources")
+    }
+
+    /// Paths of the files in the skill's `resources/` directory
+    ///
+    /// See [`list_resources`].
+    pub fn list_resources(&self) -> Result<Vec<String>, ProgressiveError> {
+        list_resources(&self.resources_dir())
+    }
+
+    /// Read a file from the skill's `resources/` directory
+    ///
+    /// See [`read_resource`].
+    pub fn read_resource(&self, resource: &str) -> Result<ResourceContent, ProgressiveError> {
+        read_resource(&self.resources_dir(), resource)
+    }
+
+    /// In-process MCP server giving Claude access to the skill's resources
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::skills::progressive_disclosure::{
+    ///     ProgressiveSkillLoader, SKILL_RESOURCES_SERVER,
+    /// };
+    /// use claude_agent_sdk::{ClaudeAgentOptions, McpServerConfig, McpServers};
+    /// use std::collections::HashMap;
+    ///
+    /// let loader = ProgressiveSkillLoader::load(".claude/skills/pdf-processor")?;
+    /// let mut servers = HashMap::new();
+    /// servers.insert(
+    ///     SKILL_RESOURCES_SERVER.to_string(),
+    ///     McpServerConfig::Sdk(loader.resource_server()),
+    /// );
+    /// let options = ClaudeAgentOptions {
+    ///     mcp_servers: McpServers::Dict(servers),
+    ///     ..Default::default()
+    /// };
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn resource_server(&self) -> McpSdkServerConfig {
+        skill_resource_server([(self.name.clone(), self.resources_dir())])
+    }
+
     /// Scan markdown content for file references
     ///
     /// Detects patterns like:
@@ -307,6 +406,251 @@ impl ProgressiveSkillLoader {
     }
 }
 
+/// Paths of the files below `root`, relative to it and sorted
+///
+/// Paths use `/` as separator. Symlinks pointing outside `root` are left
+/// out, and a missing `root` has no resources.
+pub fn list_resources(root: &Path) -> Result<Vec<String>, ProgressiveError> {
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+    let canonical_root = root
+        .canonicalize()
+        .map_err(|e| ProgressiveError::FileNotFound(root.to_path_buf(), e))?;
+
+    let mut resources = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| ProgressiveError::FileNotFound(dir.clone(), e))?;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            let Ok(target) = path.canonicalize() else {
+                continue;
+            };
+            if !target.starts_with(&canonical_root) {
+                continue;
+            }
+            if target.is_dir() {
+                if depth + 1 < MAX_RESOURCE_DEPTH {
+                    pending.push((path, depth + 1));
+                }
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let parts: Vec<_> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect();
+                resources.push(parts.join("/"));
+            }
+        }
+    }
+
+    resources.sort();
+    Ok(resources)
+}
+
+/// Read the file `resource`, a path relative to `root`
+///
+/// Absolute paths and paths with `..` are rejected, and symlinks are
+/// resolved so that the file read is always inside `root`.
+///
+/// # Errors
+///
+/// - [`ProgressiveError::InvalidResourcePath`] if `resource` is not a file inside `root`
+/// - [`ProgressiveError::ResourceTooLarge`] if the file is over [`MAX_RESOURCE_BYTES`]
+/// - [`ProgressiveError::FileNotFound`] if the file cannot be read
+pub fn read_resource(root: &Path, resource: &str) -> Result<ResourceContent, ProgressiveError> {
+    let path = resolve_resource(root, resource)?;
+    let size = std::fs::metadata(&path)
+        .map_err(|e| ProgressiveError::FileNotFound(path.clone(), e))?
+        .len();
+    if size > MAX_RESOURCE_BYTES {
+        return Err(ProgressiveError::ResourceTooLarge {
+            resource: resource.to_string(),
+            size,
+            limit: MAX_RESOURCE_BYTES,
+        });
+    }
+
+    let bytes = std::fs::read(&path).map_err(|e| ProgressiveError::FileNotFound(path, e))?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => ResourceContent::Text(text),
+        Err(e) => ResourceContent::Binary(e.into_bytes()),
+    })
+}
+
+/// Canonical path of `resource` below `root`
+fn resolve_resource(root: &Path, resource: &str) -> Result<PathBuf, ProgressiveError> {
+    let relative = Path::new(resource);
+    let is_relative = relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if resource.trim().is_empty() || !is_relative {
+        return Err(ProgressiveError::InvalidResourcePath(format!(
+            "'{}' must be a relative path without '..'",
+            resource
+        )));
+    }
+
+    let not_found = || {
+        ProgressiveError::InvalidResourcePath(format!("'{}' is not a resource of this skill", resource))
+    };
+    let root = root.canonicalize().map_err(|_| not_found())?;
+    let path = root.join(relative).canonicalize().map_err(|_| not_found())?;
+    if !path.starts_with(&root) {
+        return Err(ProgressiveError::InvalidResourcePath(format!(
+            "'{}' resolves outside the skill's resources",
+            resource
+        )));
+    }
+    if !path.is_file() {
+        return Err(not_found());
+    }
+    Ok(path)
+}
+
+/// Arguments of the `skill_resource` tool
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ResourceRequest {
+    /// Name of the skill
+    skill: String,
+    /// Path of the resource, relative to the skill's resources directory
+    resource: String,
+}
+
+/// Arguments of the `skill_list_resources` tool
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ListResourcesRequest {
+    /// Name of the skill; all skills when omitted
+    #[serde(default)]
+    skill: Option<String>,
+}
+
+/// In-process MCP server serving the resources of `skills`
+///
+/// Each skill is given by its name and resources directory; a skill may be
+/// listed more than once to serve several directories. The server has two
+/// tools:
+///
+/// - `skill_resource` (`{skill, resource}`): the contents of one resource,
+///   as text, or as base64 in a JSON object for binary files
+/// - `skill_list_resources` (`{skill?}`): the resources of one or all skills
+///
+/// Access is limited as described in [`read_resource`].
+pub fn skill_resource_server<I, S, P>(skills: I) -> McpSdkServerConfig
+where
+    I: IntoIterator<Item = (S, P)>,
+    S: Into<String>,
+    P: Into<PathBuf>,
+{
+    let mut roots: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for (skill, dir) in skills {
+        roots.entry(skill.into()).or_default().push(dir.into());
+    }
+    let roots = Arc::new(roots);
+
+    let read_roots = roots.clone();
+    let read = SdkMcpTool::typed(
+        SKILL_RESOURCE_TOOL,
+        "Read a file from a skill's resources directory",
+        move |request: ResourceRequest| {
+            let roots = read_roots.clone();
+            async move { Ok(resource_result(&roots, &request)) }
+        },
+    );
+
+    let list = SdkMcpTool::typed(
+        SKILL_LIST_RESOURCES_TOOL,
+        "List the files in a skill's resources directory",
+        move |request: ListResourcesRequest| {
+            let roots = roots.clone();
+            async move { Ok(list_result(&roots, request.skill.as_deref())) }
+        },
+    );
+
+    create_sdk_mcp_server(SKILL_RESOURCES_SERVER, env!("CARGO_PKG_VERSION"), vec![read, list])
+}
+
+/// Names under which Claude sees the tools of [`skill_resource_server`]
+pub fn skill_resource_tool_names() -> [String; 2] {
+    [SKILL_RESOURCE_TOOL, SKILL_LIST_RESOURCES_TOOL]
+        .map(|tool| format!("mcp__{}__{}", SKILL_RESOURCES_SERVER, tool))
+}
+
+fn tool_result(text: String, is_error: bool) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Text { text }],
+        is_error,
+    }
+}
+
+fn unknown_skill(roots: &BTreeMap<String, Vec<PathBuf>>, skill: &str) -> ToolResult {
+    let known: Vec<_> = roots.keys().map(String::as_str).collect();
+    tool_result(
+        format!("Unknown skill '{}'; skills with resources: {}", skill, known.join(", ")),
+        true,
+    )
+}
+
+fn resource_result(roots: &BTreeMap<String, Vec<PathBuf>>, request: &ResourceRequest) -> ToolResult {
+    let Some(dirs) = roots.get(&request.skill) else {
+        return unknown_skill(roots, &request.skill);
+    };
+
+    let mut error = None;
+    for dir in dirs {
+        match read_resource(dir, &request.resource) {
+            Ok(ResourceContent::Text(text)) => return tool_result(text, false),
+            Ok(ResourceContent::Binary(bytes)) => {
+                let body = serde_json::json!({
+                    "resource": request.resource,
+                    "encoding": "base64",
+                    "size": bytes.len(),
+                    "data": BASE64.encode(&bytes),
+                });
+                return tool_result(body.to_string(), false);
+            },
+            // Keep looking in the skill's other directories
+            Err(e @ ProgressiveError::InvalidResourcePath(_)) if error.is_none() => {
+                error = Some(e)
+            },
+            Err(ProgressiveError::InvalidResourcePath(_)) => {},
+            Err(e) => return tool_result(e.to_string(), true),
+        }
+    }
+
+    let message = error.map_or_else(
+        || format!("Skill '{}' has no resources", request.skill),
+        |e| e.to_string(),
+    );
+    tool_result(message, true)
+}
+
+fn list_result(roots: &BTreeMap<String, Vec<PathBuf>>, skill: Option<&str>) -> ToolResult {
+    let skills: Vec<_> = match skill {
+        Some(skill) => match roots.get_key_value(skill) {
+            Some(entry) => vec![entry],
+            None => return unknown_skill(roots, skill),
+        },
+        None => roots.iter().collect(),
+    };
+
+    let mut listing = serde_json::Map::new();
+    for (name, dirs) in skills {
+        let mut resources = Vec::new();
+        for dir in dirs {
+            match list_resources(dir) {
+                Ok(found) => resources.extend(found),
+                Err(e) => return tool_result(e.to_string(), true),
+            }
+        }
+        resources.sort();
+        resources.dedup();
+        listing.insert(name.clone(), serde_json::json!(resources));
+    }
+    tool_result(serde_json::Value::Object(listing).to_string(), false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -439,4 +783,143 @@ See [examples.md](examples.md) for usage.
         assert!(summary.contains("References: 2 files"));
         assert!(summary.contains("Scripts: 1 files"));
     }
+
+    fn create_resources(temp_dir: &Path) -> PathBuf {
+        create_test_skill(temp_dir);
+        let resources = temp_dir.join("resources");
+        fs::create_dir_all(resources.join("templates")).unwrap();
+        fs::write(resources.join("guide.md"), "The guide").unwrap();
+        fs::write(resources.join("templates").join("form.txt"), "Name:").unwrap();
+        fs::write(resources.join("logo.png"), [0x89, b'P', b'N', b'G', 0xff, 0x00]).unwrap();
+        resources
+    }
+
+    #[test]
+    fn test_read_and_list_resources() {
+        let temp_dir = TempDir::new().unwrap();
+        create_resources(temp_dir.path());
+
+        let loader = ProgressiveSkillLoader::load(temp_dir.path()).unwrap();
+        assert_eq!(loader.name(), "test-skill");
+        assert_eq!(
+            loader.list_resources().unwrap(),
+            vec!["guide.md", "logo.png", "templates/form.txt"]
+        );
+        assert_eq!(
+            loader.read_resource("templates/form.txt").unwrap(),
+            ResourceContent::Text("Name:".to_string())
+        );
+        assert!(matches!(
+            loader.read_resource("logo.png").unwrap(),
+            ResourceContent::Binary(bytes) if bytes.len() == 6
+        ));
+    }
+
+    #[test]
+    fn test_read_resource_rejects_paths_outside_resources() {
+        let temp_dir = TempDir::new().unwrap();
+        let resources = create_resources(temp_dir.path());
+
+        for resource in ["../SKILL.md", "templates/../../SKILL.md", "/etc/passwd", "", "templates"] {
+            assert!(
+                matches!(
+                    read_resource(&resources, resource),
+                    Err(ProgressiveError::InvalidResourcePath(_))
+                ),
+                "{} was not rejected",
+                resource
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(temp_dir.path().join("SKILL.md"), resources.join("escape.md"))
+                .unwrap();
+            std::os::unix::fs::symlink(resources.join("guide.md"), resources.join("alias.md"))
+                .unwrap();
+            assert!(matches!(
+                read_resource(&resources, "escape.md"),
+                Err(ProgressiveError::InvalidResourcePath(_))
+            ));
+            assert_eq!(
+                read_resource(&resources, "alias.md").unwrap(),
+                ResourceContent::Text("The guide".to_string())
+            );
+            assert!(!list_resources(&resources).unwrap().contains(&"escape.md".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_read_resource_size_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        let resources = create_resources(temp_dir.path());
+        let file = File::create(resources.join("big.bin")).unwrap();
+        file.set_len(MAX_RESOURCE_BYTES + 1).unwrap();
+
+        assert!(matches!(
+            read_resource(&resources, "big.bin"),
+            Err(ProgressiveError::ResourceTooLarge { size, .. }) if size == MAX_RESOURCE_BYTES + 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resource_server_tools() {
+        let temp_dir = TempDir::new().unwrap();
+        create_resources(temp_dir.path());
+        let server = ProgressiveSkillLoader::load(temp_dir.path())
+            .unwrap()
+            .resource_server();
+        assert_eq!(server.name, SKILL_RESOURCES_SERVER);
+
+        let call = |name: &str, arguments: serde_json::Value| {
+            server.instance.handle_message(serde_json::json!({
+                "method": "tools/call",
+                "params": {"name": name, "arguments": arguments}
+            }))
+        };
+
+        let listing = call(SKILL_LIST_RESOURCES_TOOL, serde_json::json!({})).await.unwrap();
+        let text = listing["content"][0]["text"].as_str().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(text).unwrap(),
+            serde_json::json!({"test-skill": ["guide.md", "logo.png", "templates/form.txt"]})
+        );
+
+        let guide = call(
+            SKILL_RESOURCE_TOOL,
+            serde_json::json!({"skill": "test-skill", "resource": "guide.md"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(guide["content"][0]["text"], "The guide");
+        assert_eq!(guide["isError"], false);
+
+        let logo = call(
+            SKILL_RESOURCE_TOOL,
+            serde_json::json!({"skill": "test-skill", "resource": "logo.png"}),
+        )
+        .await
+        .unwrap();
+        let logo: serde_json::Value =
+            serde_json::from_str(logo["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(logo["encoding"], "base64");
+        assert_eq!(logo["data"], BASE64.encode([0x89, b'P', b'N', b'G', 0xff, 0x00]));
+
+        let escape = call(
+            SKILL_RESOURCE_TOOL,
+            serde_json::json!({"skill": "test-skill", "resource": "../SKILL.md"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(escape["isError"], true);
+
+        let unknown = call(
+            SKILL_RESOURCE_TOOL,
+            serde_json::json!({"skill": "other", "resource": "guide.md"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(unknown["isError"], true);
+        assert!(unknown["content"][0]["text"].as_str().unwrap().contains("test-skill"));
+    }
 }
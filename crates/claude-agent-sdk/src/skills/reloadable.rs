@@ -0,0 +1,486 @@
+//! Keeping a [`SkillRegistry`] in sync with the skill files on disk
+//!
+//! [`ReloadableSkillRegistry`] applies [`HotReloadEvent`]s to a shared
+//! registry: changed files are re-parsed and replace their skill, new files
+//! add skills, and deleted files or skill directories remove them. Each
+//! change is reported as a [`SkillRegistryEvent`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{RwLock, RwLockReadGuard, mpsc};
+use tracing::{info, warn};
+
+use super::{
+    HotReloadConfig, HotReloadEvent, HotReloadWatcher, PackagedSkill, Skill, SkillError,
+    SkillMdFile, SkillPackage, SkillRegistry,
+};
+
+/// A change applied to a [`ReloadableSkillRegistry`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkillRegistryEvent {
+    /// A skill was registered
+    Added { name: String },
+    /// A registered skill was replaced by a new version
+    Updated { name: String },
+    /// A skill was unregistered
+    Removed { name: String },
+}
+
+/// A [`SkillRegistry`] that follows changes to skill files
+///
+/// The registry stays readable while a change is applied: files are parsed
+/// before the write lock is taken. A file that no longer parses or validates
+/// leaves the previously registered version in place, and a warning is logged.
+///
+/// Clones share the same registry.
+///
+/// # Example
+///
+/// ```no_run
+/// use claude_agent_sdk::skills::{ReloadableSkillRegistry, SkillRegistryEvent};
+///
+/// # async fn example() -> Result<(), claude_agent_sdk::skills::SkillError> {
+/// let (registry, mut changes) = ReloadableSkillRegistry::watch(".claude/skills")?;
+/// println!("Loaded skills: {:?}", registry.list().await);
+///
+/// while let Some(change) = changes.recv().await {
+///     if let SkillRegistryEvent::Updated { name } = change {
+///         println!("Reloaded {}", name);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ReloadableSkillRegistry {
+    registry: Arc<RwLock<SkillRegistry>>,
+    /// Keeps the file watcher running; None when driven by other events
+    watcher: Option<Arc<HotReloadWatcher>>,
+}
+
+impl ReloadableSkillRegistry {
+    /// Load the skills in `dir` and keep them up to date
+    ///
+    /// Skills are loaded as by [`SkillRegistry::load_dir`]; afterwards SKILL.md
+    /// and `.json` files under `dir` are watched. Must be called from within a
+    /// Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be loaded or watched, including
+    /// when the `hot-reload` feature is not enabled.
+    pub fn watch<P: AsRef<Path>>(
+        dir: P,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<SkillRegistryEvent>), SkillError> {
+        let dir = dir.as_ref();
+
+        let mut registry = SkillRegistry::new();
+        let mut sources = HashMap::new();
+        for (path, skill) in SkillRegistry::discover_skills(dir)? {
+            if let Some(reason) = registry.check_compatibility(&skill.package().metadata) {
+                warn!("Not registering skill {} from {:?}: {}", skill.name(), path, reason);
+                continue;
+            }
+            sources.insert(path, skill.name());
+            registry.register(Box::new(skill))?;
+        }
+
+        let config = HotReloadConfig {
+            file_patterns: vec!["SKILL.md".to_string(), "*.json".to_string()],
+            ..HotReloadConfig::default()
+        };
+        let (event_sender, events) = mpsc::unbounded_channel();
+        let watcher = HotReloadWatcher::new(dir, config, event_sender)?;
+
+        Ok(Self::spawn(
+            registry,
+            sources,
+            events,
+            Some(Arc::new(watcher)),
+        ))
+    }
+
+    /// Apply `events` from any source to `registry`
+    ///
+    /// Only skills added through `events` can be updated or removed by later
+    /// events. Must be called from within a Tokio runtime.
+    pub fn from_events(
+        registry: SkillRegistry,
+        events: mpsc::UnboundedReceiver<HotReloadEvent>,
+    ) -> (Self, mpsc::UnboundedReceiver<SkillRegistryEvent>) {
+        Self::spawn(registry, HashMap::new(), events, None)
+    }
+
+    fn spawn(
+        registry: SkillRegistry,
+        sources: HashMap<PathBuf, String>,
+        mut events: mpsc::UnboundedReceiver<HotReloadEvent>,
+        watcher: Option<Arc<HotReloadWatcher>>,
+    ) -> (Self, mpsc::UnboundedReceiver<SkillRegistryEvent>) {
+        let registry = Arc::new(RwLock::new(registry));
+        let (change_sender, changes) = mpsc::unbounded_channel();
+
+        let mut reloader = Reloader {
+            registry: registry.clone(),
+            sources,
+        };
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                for change in reloader.apply(event).await {
+                    // Changes are still applied when nobody listens
+                    let _ = change_sender.send(change);
+                }
+            }
+        });
+
+        let registry = Self { registry, watcher };
+        (registry, changes)
+    }
+
+    /// Read access to the current skills
+    pub async fn read(&self) -> RwLockReadGuard<'_, SkillRegistry> {
+        self.registry.read().await
+    }
+
+    /// Names of the registered skills, sorted
+    pub async fn list(&self) -> Vec<String> {
+        let mut names = self.registry.read().await.list();
+        names.sort();
+        names
+    }
+}
+
+impl std::fmt::Debug for ReloadableSkillRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReloadableSkillRegistry")
+            .field("watching", &self.watcher.is_some())
+            .finish()
+    }
+}
+
+/// Applies hot reload events; owned by the task consuming them
+struct Reloader {
+    registry: Arc<RwLock<SkillRegistry>>,
+    /// Name of the skill loaded from each file
+    sources: HashMap<PathBuf, String>,
+}
+
+impl Reloader {
+    async fn apply(&mut self, event: HotReloadEvent) -> Vec<SkillRegistryEvent> {
+        match event {
+            HotReloadEvent::SkillCreated { path, skill }
+            | HotReloadEvent::SkillModified { path, skill } => match Self::load(&path, skill) {
+                Ok(skill) => self.replace(path, skill).await,
+                Err(e) => {
+                    warn!("Keeping previous version of skill at {:?}: {}", path, e);
+                    Vec::new()
+                },
+            },
+            HotReloadEvent::SkillDeleted { path } => self.remove(&path).await,
+            HotReloadEvent::Error { path, error } => {
+                warn!("Keeping previous version of skill at {:?}: {}", path, error);
+                Vec::new()
+            },
+        }
+    }
+
+    /// The skill in `path`, re-parsing SKILL.md files to keep their model
+    fn load(path: &Path, package: SkillPackage) -> Result<PackagedSkill, SkillError> {
+        let skill = if path.file_name().is_some_and(|name| name == "SKILL.md") {
+            let skill_md =
+                SkillMdFile::parse(path).map_err(|e| SkillError::Validation(e.to_string()))?;
+            PackagedSkill::from_skill_md(&skill_md)
+        } else {
+            PackagedSkill::new(package)
+        };
+        skill.validate()?;
+        Ok(skill)
+    }
+
+    async fn replace(&mut self, path: PathBuf, skill: PackagedSkill) -> Vec<SkillRegistryEvent> {
+        let name = skill.name();
+        let previous = self.sources.get(&path).cloned();
+
+        let mut registry = self.registry.write().await;
+        if let Some(reason) = registry.check_compatibility(&skill.package().metadata) {
+            warn!("Keeping previous version of skill at {:?}: {}", path, reason);
+            return Vec::new();
+        }
+        let mut changes = Vec::new();
+        if let Some(previous) = previous.as_ref().filter(|previous| **previous != name) {
+            // The file now defines a skill with another name
+            registry.unregister(previous);
+            changes.push(SkillRegistryEvent::Removed {
+                name: previous.clone(),
+            });
+        }
+        let existed = registry.get(&name).is_some();
+        registry
+            .register(Box::new(skill))
+            .expect("skill was validated");
+        drop(registry);
+
+        info!("Reloaded skill '{}' from {:?}", name, path);
+        self.sources.insert(path, name.clone());
+        changes.push(if existed {
+            SkillRegistryEvent::Updated { name }
+        } else {
+            SkillRegistryEvent::Added { name }
+        });
+        changes
+    }
+
+    /// Unregister the skills loaded from `path` or, for a directory, from below it
+    async fn remove(&mut self, path: &Path) -> Vec<SkillRegistryEvent> {
+        let removed: Vec<_> = self
+            .sources
+            .keys()
+            .filter(|source| source.starts_with(path))
+            .cloned()
+            .collect();
+        if removed.is_empty() {
+            return Vec::new();
+        }
+
+        let mut registry = self.registry.write().await;
+        let mut changes = Vec::new();
+        for source in removed {
+            if let Some(name) = self.sources.remove(&source)
+                && registry.unregister(&name).is_some()
+            {
+                info!("Removed skill '{}' ({:?} was deleted)", name, source);
+                changes.push(SkillRegistryEvent::Removed { name });
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::{SkillMetadata, SkillResources};
+    use std::time::Duration;
+
+    fn package(name: &str, description: &str) -> SkillPackage {
+        SkillPackage {
+            metadata: SkillMetadata {
+                id: name.to_string(),
+                name: name.to_string(),
+                description: description.to_string(),
+                version: "1.0.0".to_string(),
+                author: None,
+                dependencies: vec![],
+                tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
+            },
+            instructions: "Do it.".to_string(),
+            scripts: vec![],
+            resources: SkillResources::default(),
+        }
+    }
+
+    async fn next(changes: &mut mpsc::UnboundedReceiver<SkillRegistryEvent>) -> SkillRegistryEvent {
+        tokio::time::timeout(Duration::from_secs(5), changes.recv())
+            .await
+            .expect("timed out waiting for a registry change")
+            .expect("reloader stopped")
+    }
+
+    #[tokio::test]
+    async fn test_json_packages_are_added_updated_and_removed() {
+        let (events, receiver) = mpsc::unbounded_channel();
+        let (registry, mut changes) =
+            ReloadableSkillRegistry::from_events(SkillRegistry::new(), receiver);
+        let path = PathBuf::from("/skills/formatter.json");
+
+        events
+            .send(HotReloadEvent::SkillCreated {
+                path: path.clone(),
+                skill: package("formatter", "Formats code"),
+            })
+            .unwrap();
+        assert_eq!(
+            next(&mut changes).await,
+            SkillRegistryEvent::Added {
+                name: "formatter".to_string()
+            }
+        );
+
+        events
+            .send(HotReloadEvent::SkillModified {
+                path: path.clone(),
+                skill: package("formatter", "Formats Rust code"),
+            })
+            .unwrap();
+        assert_eq!(
+            next(&mut changes).await,
+            SkillRegistryEvent::Updated {
+                name: "formatter".to_string()
+            }
+        );
+        assert_eq!(
+            registry
+                .read()
+                .await
+                .get("formatter")
+                .unwrap()
+                .description(),
+            "Formats Rust code"
+        );
+
+        // Renaming the skill in place replaces the old name
+        events
+            .send(HotReloadEvent::SkillModified {
+                path: path.clone(),
+                skill: package("fmt", "Formats Rust code"),
+            })
+            .unwrap();
+        assert_eq!(
+            next(&mut changes).await,
+            SkillRegistryEvent::Removed {
+                name: "formatter".to_string()
+            }
+        );
+        assert_eq!(
+            next(&mut changes).await,
+            SkillRegistryEvent::Added {
+                name: "fmt".to_string()
+            }
+        );
+
+        events.send(HotReloadEvent::SkillDeleted { path }).unwrap();
+        assert_eq!(
+            next(&mut changes).await,
+            SkillRegistryEvent::Removed {
+                name: "fmt".to_string()
+            }
+        );
+        assert!(registry.list().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_edit_keeps_previous_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skill_dir = temp_dir.path().join("reviewer");
+        std::fs::create_dir(&skill_dir).unwrap();
+        let skill_md = skill_dir.join("SKILL.md");
+        std::fs::write(
+            &skill_md,
+            "---\nname: reviewer\ndescription: Reviews code\nmodel: claude-sonnet-4-5\n---\n\nReview.\n",
+        )
+        .unwrap();
+
+        let (events, receiver) = mpsc::unbounded_channel();
+        let (registry, mut changes) =
+            ReloadableSkillRegistry::from_events(SkillRegistry::new(), receiver);
+        let created = |path: &Path| HotReloadEvent::SkillCreated {
+            path: path.to_path_buf(),
+            // Ignored: SKILL.md files are re-parsed
+            skill: package("ignored", ""),
+        };
+
+        events.send(created(&skill_md)).unwrap();
+        assert_eq!(
+            next(&mut changes).await,
+            SkillRegistryEvent::Added {
+                name: "reviewer".to_string()
+            }
+        );
+
+        // Broken frontmatter, then a watcher error: both are ignored
+        std::fs::write(&skill_md, "---\nname: [unclosed\n---\n").unwrap();
+        events.send(created(&skill_md)).unwrap();
+        events
+            .send(HotReloadEvent::Error {
+                path: skill_md.clone(),
+                error: "unreadable".to_string(),
+            })
+            .unwrap();
+        // Deleting the directory removes the skill loaded from inside it
+        events
+            .send(HotReloadEvent::SkillDeleted { path: skill_dir })
+            .unwrap();
+
+        assert_eq!(
+            next(&mut changes).await,
+            SkillRegistryEvent::Removed {
+                name: "reviewer".to_string()
+            }
+        );
+        assert!(registry.read().await.get("reviewer").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_registry_stays_readable_between_changes() {
+        let mut initial = SkillRegistry::new();
+        initial
+            .register_package(package("existing", "Already here"))
+            .unwrap();
+        let (_events, receiver) = mpsc::unbounded_channel();
+        let (registry, _changes) = ReloadableSkillRegistry::from_events(initial, receiver);
+
+        let reader = registry.read().await;
+        let second = registry.clone();
+        // A second reader is not blocked by the first
+        assert_eq!(second.list().await, vec!["existing"]);
+        assert!(reader.get("existing").is_some());
+    }
+
+    #[cfg(feature = "hot-reload")]
+    #[tokio::test]
+    async fn test_watch_follows_skill_directories() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let write_skill = |name: &str, description: &str| {
+            let skill_dir = temp_dir.path().join(name);
+            std::fs::create_dir_all(&skill_dir).unwrap();
+            std::fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: {description}\n---\n\nDo it.\n"),
+            )
+            .unwrap();
+        };
+        write_skill("reviewer", "Reviews code");
+
+        let (registry, mut changes) = ReloadableSkillRegistry::watch(temp_dir.path()).unwrap();
+        assert_eq!(registry.list().await, vec!["reviewer"]);
+
+        // A file change may raise several events, so skip repeated updates
+        let mut next_change = async || loop {
+            match next(&mut changes).await {
+                SkillRegistryEvent::Updated { .. } => continue,
+                change => break change,
+            }
+        };
+
+        write_skill("formatter", "Formats code");
+        assert_eq!(
+            next_change().await,
+            SkillRegistryEvent::Added {
+                name: "formatter".to_string()
+            }
+        );
+
+        std::fs::remove_dir_all(temp_dir.path().join("reviewer")).unwrap();
+        assert_eq!(
+            next_change().await,
+            SkillRegistryEvent::Removed {
+                name: "reviewer".to_string()
+            }
+        );
+        assert_eq!(registry.list().await, vec!["formatter"]);
+    }
+
+    #[cfg(not(feature = "hot-reload"))]
+    #[tokio::test]
+    async fn test_watch_requires_hot_reload_feature() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            ReloadableSkillRegistry::watch(temp_dir.path()),
+            Err(SkillError::Configuration(_))
+        ));
+    }
+}
@@ -16,7 +16,6 @@
 //! use claude_agent_sdk::skills::sandbox::{SandboxConfig, SandboxExecutor};
 //! use std::time::Duration;
 //!
-//! #[cfg(feature = "sandbox")]
 //! async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //!     // Create a sandbox configuration
 //!     let config = SandboxConfig::new()
@@ -28,7 +27,7 @@
 //!
 //!     // Execute a script
 //!     let script = r#"
-//!         print("Hello from sandbox!")
+//!         echo "Hello from sandbox!"
 //!     "#;
 //!
 //!     let result = executor.execute(script, None).await?;
@@ -83,6 +82,7 @@
 //!     allow_network: false,
 //!     check_scripts: true,
 //!     check_resources: true,
+//!     update_baseline: true,
 //! };
 //! let auditor = SkillAuditor::new(audit_config);
 //!
@@ -105,7 +105,7 @@
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! let config = SandboxConfig::default();
 //! let executor = SandboxExecutor::new(config);
-//! let script = "print('hello')";
+//! let script = "echo hello";
 //! let result = executor.execute(script, None).await?;
 //!
 //! // Check resource consumption
@@ -128,7 +128,7 @@
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 //! let config = SandboxConfig::default();
 //! let executor = SandboxExecutor::new(config);
-//! let script = "print('hello')";
+//! let script = "echo hello";
 //! let result = executor.execute(script, None).await?;
 //!
 //! if result.timed_out {
@@ -227,23 +227,67 @@
 //! - Read-only operations on trusted data
 //! - Skills from verified, trusted sources with minimal resource usage
 //!
-//! ## Feature Availability
+//! ## Platform Support
 //!
-//! The sandbox functionality requires the `sandbox` feature to be enabled:
-//!
-//! ```toml
-//! [dependencies]
-//! claude-agent-sdk = { version = "0.1", features = ["sandbox"] }
-//! ```
-//!
-//! When the sandbox feature is disabled, sandbox operations will gracefully
-//! degrade to direct execution with appropriate warnings.
+//! Scripts run as child processes of [`SandboxConfig::interpreter`], in their
+//! working directory and with a sanitized environment. Denying them the rest of
+//! the filesystem needs Landlock (Linux 5.13 and later);
+//! [`SandboxResult::isolation`] records what was applied, and
+//! [`SandboxConfig::untrusted`] refuses to run scripts unconfined. Memory, fuel
+//! and network limits are not enforced for child processes.
 
 use crate::skills::error::SkillError;
+use crate::skills::performance::SkillStats;
+use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::warn;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tracing::{debug, info, warn};
+
+/// Environment variables passed through to scripts by default
+///
+/// Everything else is removed; `HOME` and `TMPDIR` point at the working directory.
+pub const DEFAULT_ALLOWED_ENV: &[&str] = &["PATH", "LANG", "LC_ALL", "LC_CTYPE", "TERM", "TZ"];
+
+/// System paths scripts may read and execute under filesystem isolation
+///
+/// Enough for an interpreter to load; paths missing on this system are skipped.
+pub const SYSTEM_READ_ONLY_PATHS: &[&str] = &[
+    "/bin",
+    "/sbin",
+    "/usr",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/etc/alternatives",
+    "/etc/group",
+    "/etc/ld.so.cache",
+    "/etc/ld.so.conf",
+    "/etc/ld.so.conf.d",
+    "/etc/localtime",
+    "/etc/nsswitch.conf",
+    "/etc/passwd",
+];
+
+/// Device files scripts may read and write under filesystem isolation
+#[cfg(target_os = "linux")]
+const DEVICE_PATHS: &[&str] = &["/dev/null", "/dev/zero", "/dev/random", "/dev/urandom"];
+
+fn default_interpreter() -> String {
+    "sh".to_string()
+}
+
+fn default_max_output_bytes() -> Option<usize> {
+    Some(10 * 1024 * 1024)
+}
+
+fn default_allowed_env() -> Vec<String> {
+    DEFAULT_ALLOWED_ENV.iter().map(|name| name.to_string()).collect()
+}
 
 /// Sandbox execution configuration
 ///
@@ -292,7 +336,11 @@ pub struct SandboxConfig {
     /// Only enable for trusted skills with legitimate network needs.
     pub allow_network: bool,
 
-    /// Whether to allow file system access
+    /// Whether to allow file system access outside the working directory
+    ///
+    /// When `false`, the script can only use its working directory, plus read-only
+    /// [`SYSTEM_READ_ONLY_PATHS`] and [`read_only_paths`](Self::read_only_paths),
+    /// where the platform can enforce it; see [`IsolationLevel`].
     ///
     /// **Security Warning**: Filesystem access can lead to:
     /// - Unauthorized data access
@@ -302,11 +350,46 @@ pub struct SandboxConfig {
     /// Always use `working_directory` to restrict access to a specific directory.
     pub allow_filesystem: bool,
 
-    /// Working directory for file system access (if enabled)
+    /// Working directory of the script
+    ///
+    /// A fresh, empty directory is used and removed afterwards when unset.
     ///
     /// **Security Best Practice**: Always specify a working directory to prevent
     /// access to sensitive system files. Use a temporary or project-specific directory.
     pub working_directory: Option<String>,
+
+    /// Refuse to run scripts when filesystem access is denied but the platform
+    /// cannot enforce it
+    ///
+    /// Without this, such scripts run in their working directory with a warning.
+    #[serde(default)]
+    pub untrusted: bool,
+
+    /// Program the script is passed to as its first argument
+    ///
+    /// Default: `"sh"`
+    #[serde(default = "default_interpreter")]
+    pub interpreter: String,
+
+    /// Environment variables passed through to the script
+    ///
+    /// Default: [`DEFAULT_ALLOWED_ENV`]
+    #[serde(default = "default_allowed_env")]
+    pub allowed_env: Vec<String>,
+
+    /// Further paths the script may read under filesystem isolation, such as
+    /// an interpreter installed outside the system directories
+    #[serde(default)]
+    pub read_only_paths: Vec<PathBuf>,
+
+    /// Most bytes kept of each of stdout and stderr (None = unlimited)
+    ///
+    /// Output past the limit is read and dropped, and the result is marked
+    /// [`truncated`](SandboxResult::truncated).
+    ///
+    /// Default: 10 MB
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: Option<usize>,
 }
 
 impl Default for SandboxConfig {
@@ -318,6 +401,11 @@ impl Default for SandboxConfig {
             allow_network: false,
             allow_filesystem: false,
             working_directory: None,
+            untrusted: false,
+            interpreter: default_interpreter(),
+            allowed_env: default_allowed_env(),
+            read_only_paths: Vec::new(),
+            max_output_bytes: default_max_output_bytes(),
         }
     }
 }
@@ -359,6 +447,40 @@ impl SandboxConfig {
         self
     }
 
+    /// Refuse to run without filesystem isolation when filesystem access is denied
+    pub fn with_untrusted(mut self, untrusted: bool) -> Self {
+        self.untrusted = untrusted;
+        self
+    }
+
+    /// Set the program scripts are run with
+    pub fn with_interpreter(mut self, interpreter: impl Into<String>) -> Self {
+        self.interpreter = interpreter.into();
+        self
+    }
+
+    /// Set the environment variables passed through to scripts
+    pub fn with_allowed_env<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_env = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Let scripts read `path` under filesystem isolation
+    pub fn with_read_only_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.read_only_paths.push(path.into());
+        self
+    }
+
+    /// Set the most bytes kept of each of stdout and stderr
+    pub fn with_max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = Some(max_output_bytes);
+        self
+    }
+
     /// Create a restrictive config for untrusted skills
     pub fn restrictive() -> Self {
         Self {
@@ -368,6 +490,8 @@ impl SandboxConfig {
             allow_network: false,
             allow_filesystem: false,
             working_directory: None,
+            untrusted: true,
+            ..Self::default()
         }
     }
 
@@ -380,10 +504,26 @@ impl SandboxConfig {
             allow_network: true,
             allow_filesystem: true,
             working_directory: Some("/tmp".to_string()),
+            ..Self::default()
         }
     }
 }
 
+/// How far a sandboxed script was kept from the rest of the filesystem
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IsolationLevel {
+    /// The script was not run as a process
+    #[default]
+    None,
+    /// Run in its working directory with a sanitized environment; it could still
+    /// reach any path its user can
+    WorkingDirectory,
+    /// The kernel (Landlock) denied access outside the working directory and the
+    /// read-only paths
+    Filesystem,
+}
+
 /// Result of a sandboxed execution
 ///
 /// Contains the output, exit status, and resource usage information from
@@ -429,6 +569,14 @@ pub struct SandboxResult {
 
     /// Fuel/instructions consumed during execution (if measured)
     pub fuel_consumed: Option<u64>,
+
+    /// Filesystem isolation actually applied to the script
+    #[serde(default)]
+    pub isolation: IsolationLevel,
+
+    /// Whether output past [`SandboxConfig::max_output_bytes`] was dropped
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 impl SandboxResult {
@@ -446,6 +594,8 @@ impl SandboxResult {
     /// #     timed_out: false,
     /// #     memory_used: None,
     /// #     fuel_consumed: None,
+    /// #     isolation: Default::default(),
+    /// #     truncated: false,
     /// # };
     /// if result.is_success() {
     ///     println!("Script completed successfully");
@@ -471,6 +621,8 @@ impl SandboxResult {
     /// #     timed_out: false,
     /// #     memory_used: None,
     /// #     fuel_consumed: None,
+    /// #     isolation: Default::default(),
+    /// #     truncated: false,
     /// # };
     /// if let Some(error) = result.error_message() {
     ///     eprintln!("Script failed: {}", error);
@@ -497,8 +649,13 @@ impl SandboxResult {
 
 /// Sandbox executor for skill scripts
 ///
-/// The `SandboxExecutor` provides a secure execution environment for skill scripts
-/// with configurable resource limits and security restrictions.
+/// Scripts run as child processes of [`SandboxConfig::interpreter`], in their
+/// working directory, with only [`SandboxConfig::allowed_env`] in their
+/// environment. When [`SandboxConfig::allow_filesystem`] is `false`, Linux
+/// kernels with Landlock (5.13 and later) deny them every other path; elsewhere
+/// they run unconfined with a warning, or not at all if
+/// [`SandboxConfig::untrusted`] is set. [`SandboxResult::isolation`] records
+/// which applied.
 ///
 /// # Example
 ///
@@ -506,13 +663,12 @@ impl SandboxResult {
 /// use claude_agent_sdk::skills::sandbox::{SandboxConfig, SandboxExecutor};
 /// use std::time::Duration;
 ///
-/// #[cfg(feature = "sandbox")]
 /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
 ///     let config = SandboxConfig::restrictive();
 ///     let executor = SandboxExecutor::new(config);
 ///
 ///     let result = executor.execute(
-///         "print('Hello, World!')",
+///         "echo 'Hello, World!'",
 ///         None
 ///     ).await?;
 ///
@@ -520,12 +676,11 @@ impl SandboxResult {
 ///     Ok(())
 /// }
 /// ```
-#[cfg(feature = "sandbox")]
 pub struct SandboxExecutor {
     config: SandboxConfig,
+    stats: Option<(Arc<SkillStats>, String)>,
 }
 
-#[cfg(feature = "sandbox")]
 impl SandboxExecutor {
     /// Create a new sandbox executor with the given configuration
     ///
@@ -541,13 +696,38 @@ impl SandboxExecutor {
     /// let executor = SandboxExecutor::new(SandboxConfig::default());
     /// ```
     pub fn new(config: SandboxConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            stats: None,
+        }
     }
 
-    /// Execute a script in the sandbox
+    /// Record every script run in `stats`, as an execution of `skill`
+    ///
+    /// A run fails when it cannot start, times out or exits with a non-zero
+    /// code.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    /// use claude_agent_sdk::skills::sandbox::{SandboxConfig, SandboxExecutor};
     ///
-    /// This method executes the given script code within a WebAssembly sandbox
-    /// with the configured resource limits.
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let registry = SkillRegistry::new();
+    /// let executor = SandboxExecutor::new(SandboxConfig::default())
+    ///     .with_stats(registry.shared_stats(), "report-generator");
+    /// executor.execute("echo done", None).await?;
+    /// println!("{:?}", registry.stats_for("report-generator"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_stats(mut self, stats: Arc<SkillStats>, skill: impl Into<String>) -> Self {
+        self.stats = Some((stats, skill.into()));
+        self
+    }
+
+    /// Execute a script in the sandbox
     ///
     /// # Arguments
     /// * `script` - The script code to execute
@@ -555,47 +735,113 @@ impl SandboxExecutor {
     ///
     /// # Returns
     /// A `SandboxResult` containing the execution output and metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SkillError::Execution`] if filesystem access is denied for an
+    /// untrusted script on a platform that cannot enforce it, and
+    /// [`SkillError::Configuration`] if the working directory is unusable.
     pub async fn execute(
         &self,
         script: &str,
         args: Option<Vec<String>>,
     ) -> Result<SandboxResult, SkillError> {
-        let start_time = std::time::Instant::now();
+        self.execute_streaming(script, args).await?.wait().await
+    }
 
+    /// Start a script in the sandbox and follow its output as it is printed
+    ///
+    /// The timeout runs from now, whether or not the output is read. Output past
+    /// [`SandboxConfig::max_output_bytes`] is dropped, for the line streams as
+    /// well as the final result.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::skills::sandbox::{SandboxConfig, SandboxExecutor};
+    /// use futures::StreamExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let executor = SandboxExecutor::new(SandboxConfig::default());
+    /// let execution = executor
+    ///     .execute_streaming("for i in 1 2 3; do echo step $i; sleep 1; done", None)
+    ///     .await?;
+    ///
+    /// let mut lines = execution.stdout_lines();
+    /// tokio::spawn(async move {
+    ///     while let Some(line) = lines.next().await {
+    ///         println!("progress: {}", line);
+    ///     }
+    /// });
+    /// let result = execution.wait().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SandboxExecutor::execute`], or [`SkillError::Execution`] if the
+    /// interpreter cannot be started.
+    pub async fn execute_streaming(
+        &self,
+        script: &str,
+        args: Option<Vec<String>>,
+    ) -> Result<SandboxExecution, SkillError> {
         info!(
-            "Executing script in sandbox with timeout={:?}, max_memory={:?}, max_fuel={:?}",
-            self.config.timeout, self.config.max_memory, self.config.max_fuel
+            "Executing script in sandbox with timeout={:?}, allow_filesystem={}",
+            self.config.timeout, self.config.allow_filesystem
         );
 
-        // For now, we'll implement a simple timeout-based execution
-        // In a full implementation, this would use wasm-sandbox crate
-        let result = tokio::time::timeout(self.config.timeout, async {
-            self.execute_script(script, args).await
-        })
-        .await;
+        let started = tokio::time::Instant::now();
+        let execution = self.start(script, args, started);
+        if let (Err(e), Some((stats, skill))) = (&execution, &self.stats) {
+            stats.record(skill, started.elapsed(), Some(&e.to_string()));
+        }
+        execution
+    }
 
-        let execution_time = start_time.elapsed();
+    /// Start the script of [`execute_streaming`](Self::execute_streaming)
+    fn start(
+        &self,
+        script: &str,
+        args: Option<Vec<String>>,
+        started: tokio::time::Instant,
+    ) -> Result<SandboxExecution, SkillError> {
+        let run = ScriptRun::prepare(&self.config, script)?;
+        debug!(
+            "Running {} with {:?} isolation in {}",
+            self.config.interpreter,
+            run.isolation,
+            run.work_dir.display()
+        );
 
-        match result {
-            Ok(Ok(mut result)) => {
-                result.execution_time_ms = execution_time.as_millis() as u64;
-                Ok(result)
-            },
-            Ok(Err(e)) => Err(e),
-            Err(_) => {
-                // Timeout
-                warn!("Script execution timed out after {:?}", self.config.timeout);
-                Ok(SandboxResult {
-                    stdout: String::new(),
-                    stderr: format!("Execution timed out after {:?}", self.config.timeout),
-                    exit_code: -1,
-                    execution_time_ms: execution_time.as_millis() as u64,
-                    timed_out: true,
-                    memory_used: None,
-                    fuel_consumed: None,
-                })
-            },
-        }
+        let mut command = run.command(&self.config, args.unwrap_or_default())?;
+        let mut child = command
+            .spawn()
+            .map_err(|e| SkillError::Execution(format!("Failed to start script: {}", e)))?;
+
+        let limit = self.config.max_output_bytes;
+        let stdout = OutputCapture::new();
+        let stderr = OutputCapture::new();
+        let readers = [
+            child.stdout.take().map(|pipe| stdout.spawn_reader(pipe, limit)),
+            child.stderr.take().map(|pipe| stderr.spawn_reader(pipe, limit)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Ok(SandboxExecution {
+            process_group: child.id(),
+            child,
+            readers,
+            stdout,
+            stderr,
+            started,
+            timeout: self.config.timeout,
+            run,
+            stats: self.stats.clone(),
+        })
     }
 
     /// Execute a script file in the sandbox
@@ -619,91 +865,439 @@ impl SandboxExecutor {
 
         self.execute(&script, args).await
     }
+}
 
-    /// Internal script execution implementation
-    async fn execute_script(
+impl Default for SandboxExecutor {
+    fn default() -> Self {
+        Self::new(SandboxConfig::default())
+    }
+}
+
+/// A script written out for one execution, with the directories it runs in
+///
+/// The script, and the working directory if none was configured, live in a
+/// private directory removed on drop.
+struct ScriptRun {
+    run_dir: PathBuf,
+    script_path: PathBuf,
+    work_dir: PathBuf,
+    isolation: IsolationLevel,
+}
+
+impl ScriptRun {
+    fn prepare(config: &SandboxConfig, script: &str) -> Result<Self, SkillError> {
+        let work_dir = match &config.working_directory {
+            Some(dir) => Some(validate_working_directory(Path::new(dir))?),
+            None => None,
+        };
+        let isolation = if config.allow_filesystem {
+            IsolationLevel::WorkingDirectory
+        } else if SandboxUtils::filesystem_isolation_available() {
+            IsolationLevel::Filesystem
+        } else if config.untrusted {
+            return Err(SkillError::Execution(
+                "filesystem isolation unavailable on this platform".to_string(),
+            ));
+        } else {
+            warn!(
+                "Filesystem isolation is unavailable on this platform; the script can reach files outside its working directory"
+            );
+            IsolationLevel::WorkingDirectory
+        };
+
+        let run_dir = std::env::temp_dir().join(format!("claude-sandbox-{}", uuid::Uuid::new_v4()));
+        let io_error = |e: std::io::Error| SkillError::Io(format!("Failed to prepare script: {}", e));
+        std::fs::create_dir(&run_dir).map_err(io_error)?;
+        let mut run = Self {
+            script_path: run_dir.join("script"),
+            work_dir: run_dir.join("work"),
+            run_dir,
+            isolation,
+        };
+        std::fs::write(&run.script_path, script).map_err(io_error)?;
+        match work_dir {
+            Some(dir) => run.work_dir = dir,
+            None => std::fs::create_dir(&run.work_dir).map_err(io_error)?,
+        }
+        Ok(run)
+    }
+
+    fn command(
         &self,
-        script: &str,
-        _args: Option<Vec<String>>,
-    ) -> Result<SandboxResult, SkillError> {
-        debug!("Executing script ({} bytes)", script.len());
-
-        // Note: This is a simplified implementation for demonstration
-        // In production, this would:
-        // 1. Compile the script to WebAssembly
-        // 2. Use wasm-sandbox crate for isolated execution
-        // 3. Enforce memory and fuel limits
-        // 4. Capture stdout/stderr properly
-        // 5. Return detailed resource usage
-
-        // For now, we'll provide a safe fallback that validates and parses
-        // but doesn't actually execute arbitrary code
-        warn!(
-            "Sandbox feature is enabled but using safe fallback (WASM compilation not yet implemented)"
-        );
+        config: &SandboxConfig,
+        args: Vec<String>,
+    ) -> Result<tokio::process::Command, SkillError> {
+        let mut command = tokio::process::Command::new(&config.interpreter);
+        command
+            .arg(&self.script_path)
+            .args(args)
+            .current_dir(&self.work_dir)
+            .env_clear()
+            .envs(
+                config
+                    .allowed_env
+                    .iter()
+                    .filter_map(|name| std::env::var_os(name).map(|value| (name, value))),
+            )
+            .env("HOME", &self.work_dir)
+            .env("TMPDIR", &self.work_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Its own process group, so that whatever it starts can be killed with it
+        #[cfg(unix)]
+        command.process_group(0);
+
+        #[cfg(target_os = "linux")]
+        if self.isolation == IsolationLevel::Filesystem {
+            landlock_rules::confine(&mut command, self, config)?;
+        }
+        Ok(command)
+    }
+}
+
+impl Drop for ScriptRun {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.run_dir) {
+            warn!(
+                "Failed to remove sandbox directory {}: {}",
+                self.run_dir.display(),
+                e
+            );
+        }
+    }
+}
+
+/// How long to wait for the output pipes to close once the script has exited
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A script running in the sandbox, from [`SandboxExecutor::execute_streaming`]
+///
+/// The script runs in its own process group, which is killed when it exits or
+/// times out, and when the handle is dropped, so that nothing it started outlives it.
+pub struct SandboxExecution {
+    child: tokio::process::Child,
+    process_group: Option<u32>,
+    readers: Vec<tokio::task::JoinHandle<()>>,
+    stdout: OutputCapture,
+    stderr: OutputCapture,
+    started: tokio::time::Instant,
+    timeout: Duration,
+    run: ScriptRun,
+    stats: Option<(Arc<SkillStats>, String)>,
+}
+
+impl SandboxExecution {
+    /// Lines the script prints to stdout, from the first, without their newlines
+    ///
+    /// Each call starts a new stream; streams end when the script's output does.
+    pub fn stdout_lines(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        self.stdout.lines()
+    }
+
+    /// Lines the script prints to stderr, from the first, without their newlines
+    pub fn stderr_lines(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        self.stderr.lines()
+    }
+
+    /// Wait for the script to finish or time out
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SkillError::Io`] if the script's status cannot be read.
+    pub async fn wait(mut self) -> Result<SandboxResult, SkillError> {
+        let result = self.wait_for_exit().await;
+        if let Some((stats, skill)) = self.stats.take() {
+            let error = match &result {
+                Ok(result) => result.error_message(),
+                Err(e) => Some(e.to_string()),
+            };
+            stats.record(&skill, self.started.elapsed(), error.as_deref());
+        }
+        result
+    }
+
+    /// [`wait`](Self::wait), without recording the run
+    async fn wait_for_exit(&mut self) -> Result<SandboxResult, SkillError> {
+        let deadline = self.started + self.timeout;
+        let status = tokio::time::timeout_at(deadline, self.child.wait()).await;
+        let timed_out = status.is_err();
+        // Whatever the script left running goes with it
+        self.kill().await;
+
+        let exit_code = match status {
+            Ok(Ok(status)) => status.code().unwrap_or(-1),
+            Ok(Err(e)) => {
+                return Err(SkillError::Io(format!("Failed to wait for script: {}", e)));
+            },
+            Err(_) => {
+                warn!("Script execution timed out after {:?}", self.timeout);
+                -1
+            },
+        };
+        for reader in std::mem::take(&mut self.readers) {
+            let abort = reader.abort_handle();
+            if tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, reader).await.is_err() {
+                abort.abort();
+            }
+        }
 
+        let (stdout, stdout_truncated) = self.stdout.finish();
+        let (mut stderr, stderr_truncated) = self.stderr.finish();
+        if timed_out {
+            if !stderr.is_empty() && !stderr.ends_with('\n') {
+                stderr.push('\n');
+            }
+            stderr.push_str(&format!("Execution timed out after {:?}", self.timeout));
+        }
         Ok(SandboxResult {
-            stdout: "Sandbox execution (safe fallback mode)".to_string(),
-            stderr: String::new(),
-            exit_code: 0,
-            execution_time_ms: 0,
-            timed_out: false,
-            memory_used: Some(0),
-            fuel_consumed: Some(0),
+            stdout,
+            stderr,
+            exit_code,
+            execution_time_ms: self.started.elapsed().as_millis() as u64,
+            timed_out,
+            memory_used: None,
+            fuel_consumed: None,
+            isolation: self.run.isolation,
+            truncated: stdout_truncated || stderr_truncated,
         })
     }
+
+    /// Kill the script and everything in its process group
+    async fn kill(&mut self) {
+        self.kill_group();
+        let _ = self.child.kill().await;
+    }
+
+    fn kill_group(&self) {
+        #[cfg(unix)]
+        if let Some(group) = self.process_group.and_then(|pid| libc::pid_t::try_from(pid).ok()) {
+            // SAFETY: killpg has no memory-safety preconditions; a group that is
+            // already gone yields ESRCH, which is ignored
+            unsafe {
+                libc::killpg(group, libc::SIGKILL);
+            }
+        }
+    }
 }
 
-#[cfg(feature = "sandbox")]
-impl Default for SandboxExecutor {
-    fn default() -> Self {
-        Self::new(SandboxConfig::default())
+impl Drop for SandboxExecution {
+    fn drop(&mut self) {
+        self.kill_group();
     }
 }
 
-/// Fallback implementation when sandbox feature is disabled
-#[cfg(not(feature = "sandbox"))]
-#[allow(dead_code)]
-pub struct SandboxExecutor {
-    config: SandboxConfig,
+/// Output of one stream of a script, kept up to the size limit
+#[derive(Default)]
+struct OutputBuffer {
+    bytes: Vec<u8>,
+    /// Offsets of the newlines in `bytes`
+    newlines: Vec<usize>,
+    truncated: bool,
+    closed: bool,
 }
 
-#[cfg(not(feature = "sandbox"))]
-impl SandboxExecutor {
-    /// Create a new sandbox executor (fallback mode)
-    pub fn new(config: SandboxConfig) -> Self {
-        warn!("Sandbox feature is disabled. Executor will run in fallback mode.");
-        Self { config }
+impl OutputBuffer {
+    /// Keep what fits of `data`; returns whether anything was kept
+    fn push(&mut self, data: &[u8], limit: Option<usize>) -> bool {
+        let room = limit.map_or(data.len(), |limit| limit.saturating_sub(self.bytes.len()));
+        if data.len() > room {
+            self.truncated = true;
+        }
+        let kept = &data[..data.len().min(room)];
+        let start = self.bytes.len();
+        self.newlines.extend(
+            kept.iter()
+                .enumerate()
+                .filter(|(_, byte)| **byte == b'\n')
+                .map(|(offset, _)| start + offset),
+        );
+        self.bytes.extend_from_slice(kept);
+        !kept.is_empty()
     }
 
-    /// Execute in fallback mode (safe but not isolated)
-    pub async fn execute(
-        &self,
-        _script: &str,
-        _args: Option<Vec<String>>,
-    ) -> Result<SandboxResult, SkillError> {
-        warn!("Attempting sandbox execution without 'sandbox' feature enabled");
-        Err(SkillError::Configuration(
-            "Sandbox feature is disabled. Enable with --features sandbox".to_string(),
-        ))
+    /// Number of lines so far; an unterminated last line counts once the output has ended
+    fn line_count(&self) -> usize {
+        let tail = self.newlines.last().map_or(0, |end| end + 1) < self.bytes.len();
+        self.newlines.len() + usize::from(self.closed && tail)
     }
 
-    /// Execute a file in fallback mode
-    pub async fn execute_file<P: AsRef<Path>>(
+    fn line(&self, index: usize) -> String {
+        let start = match index {
+            0 => 0,
+            _ => self.newlines[index - 1] + 1,
+        };
+        let end = self.newlines.get(index).copied().unwrap_or(self.bytes.len());
+        String::from_utf8_lossy(&self.bytes[start..end]).into_owned()
+    }
+}
+
+/// One output stream of a script, shared by its reader task and line streams
+#[derive(Clone)]
+struct OutputCapture {
+    buffer: Arc<tokio::sync::watch::Sender<OutputBuffer>>,
+}
+
+impl OutputCapture {
+    fn new() -> Self {
+        Self {
+            buffer: Arc::new(tokio::sync::watch::Sender::new(OutputBuffer::default())),
+        }
+    }
+
+    /// Read `pipe` to its end, keeping up to `limit` bytes
+    ///
+    /// Output past the limit is still read, so that the script is not blocked
+    /// on a full pipe.
+    fn spawn_reader(
         &self,
-        _path: P,
-        _args: Option<Vec<String>>,
-    ) -> Result<SandboxResult, SkillError> {
-        Err(SkillError::Configuration(
-            "Sandbox feature is disabled. Enable with --features sandbox".to_string(),
+        mut pipe: impl AsyncRead + Send + Unpin + 'static,
+        limit: Option<usize>,
+    ) -> tokio::task::JoinHandle<()> {
+        let buffer = Arc::clone(&self.buffer);
+        tokio::spawn(async move {
+            let mut chunk = vec![0u8; 8192];
+            loop {
+                match pipe.read(&mut chunk).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => {
+                        buffer.send_if_modified(|output| output.push(&chunk[..read], limit));
+                    },
+                }
+            }
+            buffer.send_modify(|output| output.closed = true);
+        })
+    }
+
+    fn lines(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        let mut updates = self.buffer.subscribe();
+        Box::pin(async_stream::stream! {
+            let mut next = 0;
+            loop {
+                let (lines, closed) = {
+                    let output = updates.borrow_and_update();
+                    let count = output.line_count();
+                    let lines: Vec<String> = (next..count).map(|index| output.line(index)).collect();
+                    next = count;
+                    (lines, output.closed)
+                };
+                for line in lines {
+                    yield line;
+                }
+                if closed || updates.changed().await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// The output kept, and whether any was dropped
+    fn finish(&self) -> (String, bool) {
+        self.buffer.send_modify(|output| output.closed = true);
+        let output = self.buffer.borrow();
+        (
+            String::from_utf8_lossy(&output.bytes).into_owned(),
+            output.truncated,
+        )
+    }
+}
+
+/// The canonical form of a configured working directory, if scripts may run in it
+fn validate_working_directory(dir: &Path) -> Result<PathBuf, SkillError> {
+    let canonical = dir.canonicalize().map_err(|e| {
+        SkillError::Configuration(format!(
+            "Invalid sandbox working directory {}: {}",
+            dir.display(),
+            e
         ))
+    })?;
+    if !canonical.is_dir() {
+        return Err(SkillError::Configuration(format!(
+            "Sandbox working directory {} is not a directory",
+            dir.display()
+        )));
+    }
+    if canonical.parent().is_none() {
+        return Err(SkillError::Configuration(
+            "Sandbox working directory cannot be the filesystem root".to_string(),
+        ));
     }
+    Ok(canonical)
 }
 
-#[cfg(not(feature = "sandbox"))]
-impl Default for SandboxExecutor {
-    fn default() -> Self {
-        Self::new(SandboxConfig::default())
+/// Filesystem isolation with Landlock
+#[cfg(target_os = "linux")]
+mod landlock_rules {
+    use super::{DEVICE_PATHS, SYSTEM_READ_ONLY_PATHS, SandboxConfig, ScriptRun, SkillError};
+    use landlock::{
+        ABI, Access, AccessFs, CompatLevel, Compatible, Ruleset, RulesetAttr, RulesetCreated,
+        RulesetCreatedAttr, RulesetError, RulesetStatus, path_beneath_rules,
+    };
+    use std::path::PathBuf;
+
+    /// Newest access rights handled; older kernels enforce what they support
+    const ABI_VERSION: ABI = ABI::V3;
+
+    /// A ruleset handling the basic filesystem rights, or an error if the
+    /// kernel cannot enforce them
+    fn base_ruleset() -> Result<RulesetCreated, RulesetError> {
+        Ruleset::default()
+            .set_compatibility(CompatLevel::HardRequirement)
+            .handle_access(AccessFs::from_all(ABI::V1))?
+            .set_compatibility(CompatLevel::BestEffort)
+            .handle_access(AccessFs::from_all(ABI_VERSION))?
+            .create()
+    }
+
+    pub(super) fn available() -> bool {
+        base_ruleset().is_ok()
+    }
+
+    fn ruleset(run: &ScriptRun, config: &SandboxConfig) -> Result<RulesetCreated, RulesetError> {
+        let read_only = SYSTEM_READ_ONLY_PATHS
+            .iter()
+            .map(PathBuf::from)
+            .chain(config.read_only_paths.iter().cloned())
+            .chain([run.script_path.clone()])
+            .filter(|path| path.exists());
+        let devices = DEVICE_PATHS
+            .iter()
+            .map(PathBuf::from)
+            .filter(|path| path.exists());
+        base_ruleset()?
+            .add_rules(path_beneath_rules(read_only, AccessFs::from_read(ABI_VERSION)))?
+            .add_rules(path_beneath_rules(devices, AccessFs::from_file(ABI_VERSION)))?
+            .add_rules(path_beneath_rules(
+                [&run.work_dir],
+                AccessFs::from_all(ABI_VERSION),
+            ))
+    }
+
+    /// Confine `command` to the run's working directory and the read-only paths
+    pub(super) fn confine(
+        command: &mut tokio::process::Command,
+        run: &ScriptRun,
+        config: &SandboxConfig,
+    ) -> Result<(), SkillError> {
+        let mut ruleset = Some(ruleset(run, config).map_err(|e| {
+            SkillError::Execution(format!("Failed to set up filesystem isolation: {}", e))
+        })?);
+        // SAFETY: the hook only makes the prctl and landlock_restrict_self system
+        // calls, which are safe between fork and exec
+        unsafe {
+            command.pre_exec(move || {
+                let Some(ruleset) = ruleset.take() else {
+                    return Ok(());
+                };
+                match ruleset.restrict_self() {
+                    Ok(status) if status.ruleset != RulesetStatus::NotEnforced => Ok(()),
+                    Ok(_) => Err(std::io::Error::from(std::io::ErrorKind::Unsupported)),
+                    Err(_) => Err(std::io::Error::last_os_error()),
+                }
+            });
+        }
+        Ok(())
     }
 }
 
@@ -736,6 +1330,20 @@ impl SandboxUtils {
         script.len() * 10
     }
 
+    /// Whether this platform can deny scripts access outside their working directory
+    ///
+    /// True on Linux kernels with Landlock enabled.
+    pub fn filesystem_isolation_available() -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            landlock_rules::available()
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+
     /// Check if a config is safe for untrusted code
     pub fn is_safe_config(config: &SandboxConfig) -> bool {
         !config.allow_network && !config.allow_filesystem && config.max_memory.is_some()
@@ -816,6 +1424,8 @@ mod tests {
             timed_out: false,
             memory_used: Some(1024),
             fuel_consumed: Some(1000),
+            isolation: IsolationLevel::Filesystem,
+            truncated: false,
         };
 
         assert!(result.is_success());
@@ -832,6 +1442,8 @@ mod tests {
             timed_out: false,
             memory_used: None,
             fuel_consumed: None,
+            isolation: IsolationLevel::Filesystem,
+            truncated: false,
         };
 
         assert!(!result.is_success());
@@ -851,6 +1463,8 @@ mod tests {
             timed_out: true,
             memory_used: None,
             fuel_consumed: None,
+            isolation: IsolationLevel::Filesystem,
+            truncated: false,
         };
 
         assert!(!result.is_success());
@@ -860,6 +1474,194 @@ mod tests {
         );
     }
 
+    fn config_in(dir: &Path) -> SandboxConfig {
+        SandboxConfig::new().with_filesystem_access(false, Some(dir.display().to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_execute_in_working_directory_with_sanitized_env() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("input.txt"), "from the working dir").unwrap();
+        let executor = SandboxExecutor::new(config_in(dir.path()));
+
+        let result = executor
+            .execute(
+                "cat input.txt; echo; echo \"$HOME|$1\"; env | cut -d= -f1 | sort | tr '\\n' ' '",
+                Some(vec!["arg".to_string()]),
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_success(), "{:?}", result);
+        let lines: Vec<&str> = result.stdout.lines().collect();
+        assert_eq!(lines[0], "from the working dir");
+        let work_dir = dir.path().canonicalize().unwrap();
+        assert_eq!(lines[1], format!("{}|arg", work_dir.display()));
+        for name in lines[2].split_whitespace() {
+            assert!(
+                DEFAULT_ALLOWED_ENV.contains(&name)
+                    || ["HOME", "TMPDIR", "PWD", "SHLVL", "OLDPWD", "_"].contains(&name),
+                "unexpected variable {}",
+                name
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_isolated_script_cannot_read_outside_working_directory() {
+        if !SandboxUtils::filesystem_isolation_available() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("id_rsa");
+        std::fs::write(&secret, "secret").unwrap();
+        let executor = SandboxExecutor::new(config_in(dir.path()).with_untrusted(true));
+
+        let script = format!("echo ok > made.txt && cat made.txt && cat {}", secret.display());
+        let result = executor.execute(&script, None).await.unwrap();
+        assert_eq!(result.isolation, IsolationLevel::Filesystem);
+        assert!(!result.is_success());
+        assert_eq!(result.stdout, "ok\n");
+        assert!(!result.stdout.contains("secret"));
+        assert!(result.stderr.contains("Permission denied"), "{}", result.stderr);
+
+        // The same script reaches the file when filesystem access is allowed
+        let executor = SandboxExecutor::new(
+            SandboxConfig::new().with_filesystem_access(true, Some(dir.path().display().to_string())),
+        );
+        let result = executor.execute(&script, None).await.unwrap();
+        assert_eq!(result.isolation, IsolationLevel::WorkingDirectory);
+        assert_eq!(result.stdout, "ok\nsecret");
+    }
+
+    #[tokio::test]
+    async fn test_scratch_working_directory_is_removed() {
+        let executor = SandboxExecutor::new(SandboxConfig::new());
+        let result = executor.execute("pwd", None).await.unwrap();
+        assert!(result.is_success(), "{:?}", result);
+        let work_dir = PathBuf::from(result.stdout.trim());
+        assert!(work_dir.ends_with("work"));
+        assert!(!work_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_working_directory_is_rejected() {
+        let executor = SandboxExecutor::new(
+            SandboxConfig::new().with_filesystem_access(true, Some("/".to_string())),
+        );
+        let err = executor.execute("pwd", None).await.unwrap_err();
+        assert!(matches!(err, SkillError::Configuration(_)), "{:?}", err);
+
+        let executor = SandboxExecutor::new(config_in(Path::new("/does/not/exist")));
+        let err = executor.execute("pwd", None).await.unwrap_err();
+        assert!(matches!(err, SkillError::Configuration(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_script_runs_are_recorded_in_stats() {
+        let stats = Arc::new(SkillStats::new());
+        let executor = SandboxExecutor::new(SandboxConfig::new())
+            .with_stats(Arc::clone(&stats), "report");
+
+        assert!(executor.execute("echo ok", None).await.unwrap().is_success());
+        let result = executor.execute("echo broken >&2; exit 3", None).await.unwrap();
+        assert!(!result.is_success());
+
+        let report = stats.get("report").unwrap();
+        assert_eq!(report.operations, 2);
+        assert_eq!((report.successes, report.failures), (1, 1));
+        assert_eq!(
+            report.last_error.as_deref(),
+            Some("Script exited with error code 3: broken\n")
+        );
+        assert!(report.p95_latency >= report.p50_latency);
+    }
+
+    #[tokio::test]
+    async fn test_execute_times_out() {
+        let executor =
+            SandboxExecutor::new(SandboxConfig::new().with_timeout(Duration::from_millis(200)));
+        let result = executor.execute("sleep 5", None).await.unwrap();
+        assert!(result.timed_out);
+        assert_eq!(result.exit_code, -1);
+        assert!(result.execution_time_ms < 5000);
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_yields_lines_as_printed() {
+        use futures::StreamExt;
+
+        let executor = SandboxExecutor::new(SandboxConfig::new());
+        let execution = executor
+            .execute_streaming(
+                "i=0; while [ $i -lt 5 ]; do echo line $i; echo err $i >&2; i=$((i+1)); sleep 0.05; done; printf tail",
+                None,
+            )
+            .await
+            .unwrap();
+
+        let mut stdout = execution.stdout_lines();
+        let first = tokio::time::timeout(Duration::from_millis(200), stdout.next())
+            .await
+            .expect("first line before the script ends");
+        assert_eq!(first.as_deref(), Some("line 0"));
+
+        let stderr = execution.stderr_lines();
+        let result = execution.wait().await.unwrap();
+        let rest: Vec<String> = stdout.collect().await;
+        assert_eq!(rest, vec!["line 1", "line 2", "line 3", "line 4", "tail"]);
+        assert_eq!(stderr.collect::<Vec<_>>().await.len(), 5);
+        assert!(result.is_success(), "{:?}", result);
+        assert!(!result.truncated);
+        assert!(result.stdout.starts_with("line 0\nline 1\n"));
+        assert!(result.stdout.ends_with("line 4\ntail"));
+    }
+
+    #[tokio::test]
+    async fn test_output_is_capped_while_timeout_still_fires() {
+        let executor = SandboxExecutor::new(
+            SandboxConfig::new()
+                .with_timeout(Duration::from_millis(500))
+                .with_max_output_bytes(1000),
+        );
+
+        let result = executor
+            .execute("while :; do echo 0123456789; done", None)
+            .await
+            .unwrap();
+        assert!(result.timed_out);
+        assert!(result.truncated);
+        assert_eq!(result.stdout.len(), 1000);
+        assert!(result.stderr.contains("timed out"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_forked_children() {
+        let executor =
+            SandboxExecutor::new(SandboxConfig::new().with_timeout(Duration::from_millis(300)));
+        let execution = executor
+            .execute_streaming("sleep 30 & echo $!; sleep 30", None)
+            .await
+            .unwrap();
+
+        let result = execution.wait().await.unwrap();
+        assert!(result.timed_out);
+        assert!(result.execution_time_ms < 5000);
+        let pid: libc::pid_t = result.stdout.trim().parse().unwrap();
+        // Gone, or a zombie waiting to be reaped by init
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+        let running = if std::path::Path::new("/proc").exists() {
+            !stat.is_empty() && !stat.contains(") Z")
+        } else {
+            // SAFETY: signal 0 only checks that the process exists
+            unsafe { libc::kill(pid, 0) == 0 }
+        };
+        assert!(!running, "child {} still running: {}", pid, stat);
+    }
+
     #[test]
     fn test_validate_script_empty() {
         let result = SandboxUtils::validate_script("");
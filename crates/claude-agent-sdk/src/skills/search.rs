@@ -0,0 +1,615 @@
+//! Free-text search over skills
+//!
+//! [`SkillSearchIndex`] ranks skills against a query such as
+//! "convert csv to parquet" with BM25F, a lexical ranking that weights matches in
+//! the name above the tags, the tags above the description and the description
+//! above the instructions. Identifiers are split into words, so `csvToParquet`
+//! and `csv_to_parquet` both match "csv parquet", and query words also match
+//! the longer words they start, at a discount, so "conv" finds "converter".
+//!
+//! The index can be kept current with [`upsert`](SkillSearchIndex::upsert) and
+//! [`remove`](SkillSearchIndex::remove) (see
+//! [`HotReloadManager::with_search_index`](super::HotReloadManager::with_search_index)),
+//! and saved to disk so startup only re-indexes skills that changed.
+//! [`SkillRegistry::search`](super::SkillRegistry::search) and
+//! [`IndexedSkillCollection::search`](super::IndexedSkillCollection::search)
+//! keep one for their skills.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use super::error::SkillError;
+use super::tags::STOPWORDS;
+use super::types::SkillPackage;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+
+/// BM25 length normalization
+const B: f64 = 0.75;
+
+/// Indexed fields: name, description, instructions and tags
+const FIELD_COUNT: usize = 4;
+
+/// Weight of a match in the name, description, instructions and tags
+const FIELD_WEIGHTS: [f64; FIELD_COUNT] = [3.0, 2.0, 1.0, 2.5];
+
+/// Weight of a word the query word is only a prefix of
+const PREFIX_WEIGHT: f64 = 0.5;
+
+/// Shortest query word also matched as a prefix
+const MIN_PREFIX_LEN: usize = 3;
+
+/// Most indexed words a query word is expanded to as a prefix
+const MAX_PREFIX_EXPANSIONS: usize = 32;
+
+/// Version of the saved index layout
+const FORMAT_VERSION: u32 = 2;
+
+/// A search hit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredSkill {
+    /// Skill ID
+    pub id: String,
+    /// Skill name
+    pub name: String,
+    /// Relevance, higher is better; only comparable within one search
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct FieldTerms {
+    length: usize,
+    terms: HashMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedSkill {
+    name: String,
+    /// Hash of the indexed text, to skip unchanged skills on [`SkillSearchIndex::sync`]
+    fingerprint: u64,
+    /// Name, description, instructions and tags
+    fields: [FieldTerms; FIELD_COUNT],
+}
+
+impl IndexedSkill {
+    fn new(skill: &SkillPackage) -> Self {
+        let texts = field_texts(skill);
+        let fields = texts.each_ref().map(|text| {
+            let mut field = FieldTerms::default();
+            for token in tokenize(text) {
+                field.length += 1;
+                *field.terms.entry(token).or_insert(0) += 1;
+            }
+            field
+        });
+
+        Self {
+            name: skill.metadata.name.clone(),
+            fingerprint: fingerprint(&texts),
+            fields,
+        }
+    }
+
+    fn distinct_terms(&self) -> HashSet<&String> {
+        self.fields.iter().flat_map(|f| f.terms.keys()).collect()
+    }
+}
+
+/// BM25F index over skill names, descriptions, instructions and tags
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillSearchIndex {
+    version: u32,
+    skills: BTreeMap<String, IndexedSkill>,
+    /// Number of skills containing each term in any field, sorted for prefix lookups
+    document_frequencies: BTreeMap<String, usize>,
+    /// Summed token counts of each field over all skills
+    total_lengths: [usize; FIELD_COUNT],
+}
+
+impl Default for SkillSearchIndex {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            skills: BTreeMap::new(),
+            document_frequencies: BTreeMap::new(),
+            total_lengths: [0; FIELD_COUNT],
+        }
+    }
+}
+
+impl SkillSearchIndex {
+    /// Index `skills`, keyed by their metadata ID
+    pub fn build(skills: &[SkillPackage]) -> Self {
+        let mut index = Self::default();
+        for skill in skills {
+            index.upsert(skill);
+        }
+        index
+    }
+
+    /// Number of indexed skills
+    pub fn len(&self) -> usize {
+        self.skills.len()
+    }
+
+    /// Whether no skills are indexed
+    pub fn is_empty(&self) -> bool {
+        self.skills.is_empty()
+    }
+
+    /// Whether the skill with `id` is indexed
+    pub fn contains(&self, id: &str) -> bool {
+        self.skills.contains_key(id)
+    }
+
+    /// Add a skill, or re-index it if its ID is already known
+    pub fn upsert(&mut self, skill: &SkillPackage) {
+        let id = &skill.metadata.id;
+        let indexed = IndexedSkill::new(skill);
+        if self.skills.get(id) == Some(&indexed) {
+            return;
+        }
+
+        self.remove(id);
+        for term in indexed.distinct_terms() {
+            *self.document_frequencies.entry(term.clone()).or_insert(0) += 1;
+        }
+        for (total, field) in self.total_lengths.iter_mut().zip(&indexed.fields) {
+            *total += field.length;
+        }
+        self.skills.insert(id.clone(), indexed);
+    }
+
+    /// Drop the skill with `id`; returns whether it was indexed
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(indexed) = self.skills.remove(id) else {
+            return false;
+        };
+
+        for term in indexed.distinct_terms() {
+            if let Some(count) = self.document_frequencies.get_mut(term) {
+                *count -= 1;
+                if *count == 0 {
+                    self.document_frequencies.remove(term);
+                }
+            }
+        }
+        for (total, field) in self.total_lengths.iter_mut().zip(&indexed.fields) {
+            *total -= field.length;
+        }
+        true
+    }
+
+    /// Make the index match `skills`, re-indexing only skills whose text changed
+    ///
+    /// Returns the number of skills added, updated or removed.
+    pub fn sync(&mut self, skills: &[SkillPackage]) -> usize {
+        let mut changed = 0;
+        let current: HashSet<&str> = skills.iter().map(|s| s.metadata.id.as_str()).collect();
+        let stale: Vec<String> = self
+            .skills
+            .keys()
+            .filter(|id| !current.contains(id.as_str()))
+            .cloned()
+            .collect();
+        for id in stale {
+            self.remove(&id);
+            changed += 1;
+        }
+
+        for skill in skills {
+            let unchanged = self
+                .skills
+                .get(&skill.metadata.id)
+                .is_some_and(|indexed| indexed.fingerprint == fingerprint(&field_texts(skill)));
+            if !unchanged {
+                self.upsert(skill);
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Up to `limit` skills matching `query`, best first
+    ///
+    /// Query words of three or more characters also match the indexed words
+    /// they are a prefix of, with half the weight. Skills matching no query
+    /// word are not returned, and skills scoring the same are sorted by name.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<ScoredSkill> {
+        let mut terms = tokenize(query);
+        let mut seen = HashSet::new();
+        terms.retain(|term| seen.insert(term.clone()));
+        if terms.is_empty() || self.skills.is_empty() {
+            return Vec::new();
+        }
+
+        let skill_count = self.skills.len() as f64;
+        let average_lengths = self
+            .total_lengths
+            .map(|total| (total as f64 / skill_count).max(1.0));
+        // Each query word with the indexed words it matches, weighted by their IDF
+        let expansions: Vec<Vec<(&str, f64)>> = terms
+            .iter()
+            .map(|term| {
+                self.expand(term)
+                    .map(|(word, df, weight)| {
+                        let df = df as f64;
+                        let idf = (1.0 + (skill_count - df + 0.5) / (df + 0.5)).ln();
+                        (word, weight * idf)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut hits: Vec<ScoredSkill> = self
+            .skills
+            .iter()
+            .filter_map(|(id, indexed)| {
+                let mut score = 0.0;
+                for words in &expansions {
+                    // A query word counts once, for the indexed word it matches best
+                    score += words
+                        .iter()
+                        .map(|(word, weighted_idf)| {
+                            let tf = weighted_frequency(indexed, word, &average_lengths);
+                            weighted_idf * tf * (K1 + 1.0) / (K1 + tf)
+                        })
+                        .fold(0.0, f64::max);
+                }
+                (score > 0.0).then(|| ScoredSkill {
+                    id: id.clone(),
+                    name: indexed.name.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Indexed words matching `term`, with their document frequency and weight
+    fn expand<'a>(&'a self, term: &'a str) -> impl Iterator<Item = (&'a str, usize, f64)> + 'a {
+        let exact = self
+            .document_frequencies
+            .get_key_value(term)
+            .map(|(word, &df)| (word.as_str(), df, 1.0));
+        let prefixed = (term.chars().count() >= MIN_PREFIX_LEN)
+            .then(|| {
+                self.document_frequencies
+                    .range::<str, _>((std::ops::Bound::Excluded(term), std::ops::Bound::Unbounded))
+                    .take_while(move |(word, _)| word.starts_with(term))
+                    .take(MAX_PREFIX_EXPANSIONS)
+                    .map(|(word, &df)| (word.as_str(), df, PREFIX_WEIGHT))
+            })
+            .into_iter()
+            .flatten();
+        exact.into_iter().chain(prefixed)
+    }
+
+    /// Save the index as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SkillError> {
+        let json =
+            serde_json::to_string(self).map_err(|e| SkillError::Serialization(e.to_string()))?;
+        std::fs::write(path, json).map_err(|e| SkillError::Io(e.to_string()))
+    }
+
+    /// Load an index written by [`save`](Self::save)
+    ///
+    /// Fails with [`SkillError::Serialization`] if the file was written by an
+    /// incompatible version of the SDK.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SkillError> {
+        let json = std::fs::read_to_string(path).map_err(|e| SkillError::Io(e.to_string()))?;
+        let index: Self =
+            serde_json::from_str(&json).map_err(|e| SkillError::Serialization(e.to_string()))?;
+        if index.version != FORMAT_VERSION {
+            return Err(SkillError::Serialization(format!(
+                "Unsupported search index version {}",
+                index.version
+            )));
+        }
+        Ok(index)
+    }
+
+    /// Load the index saved at `path` and bring it up to date with `skills`
+    ///
+    /// Falls back to building from scratch if the file is missing or unreadable.
+    /// The index is saved back when anything changed.
+    pub fn load_or_build(
+        path: impl AsRef<Path>,
+        skills: &[SkillPackage],
+    ) -> Result<Self, SkillError> {
+        let path = path.as_ref();
+        let (index, changed) = match Self::load(path) {
+            Ok(mut index) => {
+                let changed = index.sync(skills);
+                (index, changed > 0)
+            },
+            Err(_) => (Self::build(skills), true),
+        };
+        if changed {
+            index.save(path)?;
+        }
+        Ok(index)
+    }
+}
+
+/// BM25F pseudo term frequency: field frequencies, length-normalized and weighted
+fn weighted_frequency(
+    indexed: &IndexedSkill,
+    term: &str,
+    average_lengths: &[f64; FIELD_COUNT],
+) -> f64 {
+    indexed
+        .fields
+        .iter()
+        .zip(FIELD_WEIGHTS)
+        .zip(average_lengths)
+        .map(|((field, weight), average)| {
+            let tf = field.terms.get(term).copied().unwrap_or(0) as f64;
+            let norm = 1.0 - B + B * field.length as f64 / average;
+            weight * tf / norm
+        })
+        .sum()
+}
+
+fn field_texts(skill: &SkillPackage) -> [String; FIELD_COUNT] {
+    [
+        skill.metadata.name.clone(),
+        skill.metadata.description.clone(),
+        skill.instructions.clone(),
+        skill.metadata.tags.join("\n"),
+    ]
+}
+
+/// FNV-1a over the fields, stable across runs so saved indexes stay valid
+fn fingerprint(texts: &[String; FIELD_COUNT]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for text in texts {
+        for byte in text.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// Lowercase search terms of `text`
+///
+/// Splits on anything that is not a letter or digit, and splits identifiers at
+/// case changes (`parseJSONResponse` gives `parse`, `json`, `response`). Single characters
+/// and stopwords are dropped.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .flat_map(split_camel_case)
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() >= 2 && !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Split `word` before an uppercase letter that follows a lowercase letter or digit,
+/// or that starts a lowercase run after an acronym
+fn split_camel_case(word: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (at, current) = chars[i];
+        let previous = chars[i - 1].1;
+        let next_is_lower = chars.get(i + 1).is_some_and(|(_, c)| c.is_lowercase());
+        let boundary = current.is_uppercase()
+            && (previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower));
+        if boundary {
+            parts.push(&word[start..at]);
+            start = at;
+        }
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::types::SkillMetadata;
+
+    fn skill(id: &str, name: &str, description: &str, instructions: &str) -> SkillPackage {
+        SkillPackage {
+            metadata: SkillMetadata {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: description.to_string(),
+                version: "1.0.0".to_string(),
+                author: None,
+                dependencies: Vec::new(),
+                tags: Vec::new(),
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
+            },
+            instructions: instructions.to_string(),
+            scripts: Vec::new(),
+            resources: Default::default(),
+        }
+    }
+
+    fn corpus() -> Vec<SkillPackage> {
+        vec![
+            skill(
+                "parquet",
+                "csv-to-parquet",
+                "Convert CSV files into Parquet tables",
+                "Read the CSV with pandas and call `df.to_parquet(path)`.",
+            ),
+            skill(
+                "pipeline",
+                "data-pipeline",
+                "Build ETL pipelines that can load CSV or JSON and write Parquet",
+                "Use pyarrow for columnar output. Validate the schema first.",
+            ),
+            skill(
+                "pdf",
+                "pdf-tools",
+                "Extract text and tables from PDF documents",
+                "Use pdfplumber. Tables can be exported to CSV afterwards.",
+            ),
+            skill(
+                "review",
+                "code-review",
+                "Review pull requests for bugs and style",
+                "Check error handling, naming and tests. Run `cargo clippy`.",
+            ),
+            skill(
+                "http",
+                "httpClient",
+                "Call REST APIs",
+                "Send requests with parseJSONResponse and retry_on_timeout helpers.",
+            ),
+        ]
+    }
+
+    fn ids(hits: &[ScoredSkill]) -> Vec<&str> {
+        hits.iter().map(|h| h.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tokenize_splits_identifiers() {
+        assert_eq!(
+            tokenize("parseJSONResponse retry_on_timeout HTTPServer utf8Decode"),
+            vec![
+                "parse", "json", "response", "retry", "timeout", "http", "server", "utf8", "decode"
+            ]
+        );
+        assert_eq!(
+            tokenize("Convert a CSV to Parquet!"),
+            vec!["convert", "csv", "parquet"]
+        );
+        assert!(tokenize("  -- ").is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_name_over_description_over_instructions() {
+        let index = SkillSearchIndex::build(&corpus());
+
+        let hits = index.search("convert csv to parquet", 10);
+        assert_eq!(ids(&hits), vec!["parquet", "pipeline", "pdf"]);
+        assert!(hits.windows(2).all(|w| w[0].score > w[1].score));
+
+        // Named after the query beats mentioning it in the description
+        assert_eq!(
+            ids(&index.search("parquet", 10)),
+            vec!["parquet", "pipeline"]
+        );
+        assert_eq!(ids(&index.search("pdf", 10)), vec!["pdf"]);
+
+        // Identifiers in names and instructions match their parts
+        assert_eq!(ids(&index.search("http client", 10)), vec!["http"]);
+        assert_eq!(ids(&index.search("parse json", 10))[0], "http");
+
+        assert!(index.search("kubernetes", 10).is_empty());
+        assert!(index.search("the", 10).is_empty());
+        assert_eq!(index.search("csv", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_search_matches_prefixes_and_boosts_tags() {
+        let mut tagged = skill("ocr", "scan-reader", "Read scanned pages", "");
+        tagged.metadata.tags = vec!["pdf".to_string(), "ocr".to_string()];
+        let mut skills = corpus();
+        skills.push(tagged);
+        let index = SkillSearchIndex::build(&skills);
+
+        // "pdf" in the name beats it as a tag, which beats it in the instructions
+        assert_eq!(ids(&index.search("pdf", 10)), vec!["pdf", "ocr"]);
+        assert_eq!(ids(&index.search("ocr", 10)), vec!["ocr"]);
+
+        // Words match as prefixes, below whole words
+        assert_eq!(ids(&index.search("conv", 10)), vec!["parquet"]);
+        assert_eq!(ids(&index.search("pars", 10)), vec!["http"]);
+        let hits = index.search("pdfplum", 10);
+        assert_eq!(ids(&hits), vec!["pdf"]);
+        assert!(hits[0].score < index.search("pdfplumber", 10)[0].score);
+        // Too short to be a prefix
+        assert!(index.search("pd", 10).is_empty());
+    }
+
+    #[test]
+    fn test_equal_scores_are_sorted_by_name() {
+        let skills: Vec<SkillPackage> = ["zeta", "alpha", "mid"]
+            .into_iter()
+            .map(|name| skill(name, &format!("{}-lint", name), "Lint code", ""))
+            .collect();
+        let index = SkillSearchIndex::build(&skills);
+
+        let hits = index.search("lint", 10);
+        assert_eq!(ids(&hits), vec!["alpha", "mid", "zeta"]);
+        assert!(hits.windows(2).all(|w| w[0].score == w[1].score));
+    }
+
+    #[test]
+    fn test_incremental_updates_match_rebuild() {
+        let mut skills = corpus();
+        let mut index = SkillSearchIndex::build(&skills);
+
+        assert!(index.remove("pdf"));
+        assert!(!index.remove("pdf"));
+        skills.retain(|s| s.metadata.id != "pdf");
+
+        skills[1].metadata.description = "Stream Kafka topics into a warehouse".to_string();
+        index.upsert(&skills[1]);
+        skills[0].metadata.tags = vec!["columnar".to_string()];
+        index.upsert(&skills[0]);
+        let added = skill("k8s", "kubectl-helper", "Manage Kubernetes deployments", "");
+        index.upsert(&added);
+        skills.push(added);
+
+        assert_eq!(index, SkillSearchIndex::build(&skills));
+        assert_eq!(index.len(), 5);
+        assert_eq!(ids(&index.search("kafka", 10)), vec!["pipeline"]);
+        assert_eq!(ids(&index.search("parquet", 10)), vec!["parquet"]);
+        assert_eq!(ids(&index.search("kubernetes", 10)), vec!["k8s"]);
+        assert_eq!(ids(&index.search("columnar", 10)), vec!["parquet", "pipeline"]);
+        assert!(index.search("pdfplumber", 10).is_empty());
+    }
+
+    #[test]
+    fn test_save_load_and_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("search-index.json");
+        let mut skills = corpus();
+
+        let index = SkillSearchIndex::load_or_build(&path, &skills).unwrap();
+        assert_eq!(SkillSearchIndex::load(&path).unwrap(), index);
+
+        let mut loaded = SkillSearchIndex::load(&path).unwrap();
+        assert_eq!(loaded.sync(&skills), 0);
+
+        skills[0].instructions.push_str(" Partition by date.");
+        skills.pop();
+        assert_eq!(loaded.sync(&skills), 2);
+        assert_eq!(loaded, SkillSearchIndex::build(&skills));
+
+        let reloaded = SkillSearchIndex::load_or_build(&path, &skills).unwrap();
+        assert_eq!(reloaded, loaded);
+        assert_eq!(SkillSearchIndex::load(&path).unwrap(), loaded);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            SkillSearchIndex::load(&path),
+            Err(SkillError::Serialization(_))
+        ));
+        assert_eq!(
+            SkillSearchIndex::load_or_build(&path, &skills).unwrap(),
+            loaded
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! Letting Claude find and run registered skills
+//!
+//! [`SkillRegistry::as_mcp_server`] turns a registry into an in-process MCP
+//! server, so that Claude can search the skills mid-conversation, read the
+//! instructions of one, and run it. Skills with `disable_model_invocation: true`
+//! are hidden from all of its tools; skills with `user_invocable: false` are
+//! not, since that flag only concerns the `/` menu.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::{Skill, SkillInput, SkillRegistry};
+use crate::types::mcp::{
+    McpSdkServerConfig, SdkMcpTool, ToolResult, ToolResultContent, create_sdk_mcp_server,
+};
+
+/// Tool ranking the registered skills against a query
+pub const SEARCH_SKILLS_TOOL: &str = "search_skills";
+
+/// Tool returning the instructions of a skill
+pub const GET_SKILL_INSTRUCTIONS_TOOL: &str = "get_skill_instructions";
+
+/// Tool running a skill
+pub const RUN_SKILL_TOOL: &str = "run_skill";
+
+/// Skills `search_skills` returns when no limit is given
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+/// Arguments of the `search_skills` tool
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SearchRequest {
+    /// What the skill should do, e.g. "convert pdf to text"
+    query: String,
+    /// Most skills to return; 10 when omitted
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+/// Arguments of the `get_skill_instructions` tool
+#[derive(Debug, Deserialize, JsonSchema)]
+struct InstructionsRequest {
+    /// Name of the skill
+    name: String,
+}
+
+/// Arguments of the `run_skill` tool
+#[derive(Debug, Deserialize, JsonSchema)]
+struct RunRequest {
+    /// Name of the skill
+    name: String,
+    /// Input of the skill: text, or a JSON object of parameters
+    #[serde(default)]
+    input: serde_json::Value,
+}
+
+impl SkillRegistry {
+    /// In-process MCP server letting Claude discover and run the registered skills
+    ///
+    /// The server has three tools:
+    ///
+    /// - `search_skills` (`{query, limit?}`): the skills matching the query,
+    ///   best first, with their name, description, tags and score
+    /// - `get_skill_instructions` (`{name}`): the instructions of a skill
+    /// - `run_skill` (`{name, input?}`): the [`SkillOutput`](super::SkillOutput)
+    ///   of running a skill through [`execute`](Self::execute), as JSON
+    ///
+    /// Skills that are not [`model_invocable`](Skill::model_invocable) are
+    /// left out of all three. A [`PackagedSkill`](super::PackagedSkill) run by
+    /// `run_skill` is limited to its `allowed_tools`, as when run directly.
+    /// Claude sees the tools as `mcp__<name>__<tool>`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use claude_agent_sdk::skills::SkillRegistry;
+    /// use claude_agent_sdk::{ClaudeAgentOptions, McpServerConfig, McpServers};
+    /// use std::sync::Arc;
+    ///
+    /// let mut registry = SkillRegistry::new();
+    /// registry.load_dir(".claude/skills")?;
+    /// let server = Arc::new(registry).as_mcp_server("skills");
+    ///
+    /// let options = ClaudeAgentOptions::builder()
+    ///     .mcp_servers(McpServers::Dict(
+    ///         [("skills".to_string(), McpServerConfig::Sdk(server))].into(),
+    ///     ))
+    ///     .allowed_tools(vec!["mcp__skills__search_skills".to_string()])
+    ///     .build();
+    /// # Ok::<(), claude_agent_sdk::skills::SkillError>(())
+    /// ```
+    pub fn as_mcp_server(self: Arc<Self>, name: impl Into<String>) -> McpSdkServerConfig {
+        let registry = Arc::clone(&self);
+        let search = SdkMcpTool::typed(
+            SEARCH_SKILLS_TOOL,
+            "Find skills for a task. Returns the best matching skills with their \
+             name, description and tags.",
+            move |request: SearchRequest| {
+                let registry = Arc::clone(&registry);
+                async move { Ok(registry.search_result(&request)) }
+            },
+        );
+
+        let registry = Arc::clone(&self);
+        let instructions = SdkMcpTool::typed(
+            GET_SKILL_INSTRUCTIONS_TOOL,
+            "Get the instructions of a skill, to follow them yourself",
+            move |request: InstructionsRequest| {
+                let registry = Arc::clone(&registry);
+                async move { Ok(registry.instructions_result(&request.name)) }
+            },
+        );
+
+        let run = SdkMcpTool::typed(
+            RUN_SKILL_TOOL,
+            "Run a skill with the given input and return its output",
+            move |request: RunRequest| {
+                let registry = Arc::clone(&self);
+                async move { Ok(registry.run_result(request).await) }
+            },
+        );
+
+        create_sdk_mcp_server(
+            name,
+            env!("CARGO_PKG_VERSION"),
+            vec![search, instructions, run],
+        )
+    }
+
+    /// The skill named `name`, if Claude may see it
+    fn model_visible(&self, name: &str) -> Option<&dyn Skill> {
+        self.get(name).filter(|skill| skill.model_invocable())
+    }
+
+    fn search_result(&self, request: &SearchRequest) -> ToolResult {
+        let hits: Vec<serde_json::Value> = self
+            .search(&request.query)
+            .into_iter()
+            .filter(|(_, metadata)| self.model_visible(&metadata.name).is_some())
+            .take(request.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .map(|(score, metadata)| {
+                serde_json::json!({
+                    "name": metadata.name,
+                    "description": metadata.description,
+                    "tags": metadata.tags,
+                    "score": score,
+                })
+            })
+            .collect();
+        tool_result(serde_json::Value::Array(hits).to_string(), false)
+    }
+
+    fn instructions_result(&self, name: &str) -> ToolResult {
+        let Some(skill) = self.model_visible(name) else {
+            return unknown_skill(name);
+        };
+        match skill.skill_package() {
+            Some(package) if !package.instructions.trim().is_empty() => {
+                tool_result(package.instructions.clone(), false)
+            }
+            _ => tool_result(format!("Skill '{}' has no instructions", name), true),
+        }
+    }
+
+    async fn run_result(&self, request: RunRequest) -> ToolResult {
+        if self.model_visible(&request.name).is_none() {
+            return unknown_skill(&request.name);
+        }
+        let input = SkillInput {
+            params: request.input,
+        };
+        match self.execute(&request.name, input).await {
+            Ok(output) => match serde_json::to_string(&output) {
+                Ok(json) => tool_result(json, !output.success),
+                Err(e) => tool_result(e.to_string(), true),
+            },
+            Err(e) => tool_result(e.to_string(), true),
+        }
+    }
+}
+
+fn tool_result(text: String, is_error: bool) -> ToolResult {
+    ToolResult {
+        content: vec![ToolResultContent::Text { text }],
+        is_error,
+    }
+}
+
+fn unknown_skill(name: &str) -> ToolResult {
+    tool_result(
+        format!(
+            "Unknown skill '{}'; use {} to find skills",
+            name, SEARCH_SKILLS_TOOL
+        ),
+        true,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::{SkillError, SkillOutput, SkillResult};
+    use async_trait::async_trait;
+
+    /// Skill upper-casing its text input
+    struct UpperSkill;
+
+    #[async_trait]
+    impl Skill for UpperSkill {
+        fn name(&self) -> String {
+            "upper".to_string()
+        }
+
+        fn description(&self) -> String {
+            "Upper-case text".to_string()
+        }
+
+        async fn execute(&self, input: SkillInput) -> SkillResult {
+            match input.params.as_str() {
+                Some(text) => Ok(SkillOutput::ok(text.to_uppercase())),
+                None => Ok(SkillOutput::err("expected text")),
+            }
+        }
+
+        fn validate(&self) -> Result<(), SkillError> {
+            Ok(())
+        }
+    }
+
+    fn write_skill(dir: &std::path::Path, name: &str, frontmatter: &str, body: &str) {
+        let skill_dir = dir.join(name);
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            format!("---\nname: {}\n{}---\n\n{}\n", name, frontmatter, body),
+        )
+        .unwrap();
+    }
+
+    fn registry() -> (tempfile::TempDir, Arc<SkillRegistry>) {
+        let dir = tempfile::tempdir().unwrap();
+        write_skill(
+            dir.path(),
+            "pdf-to-text",
+            "description: Convert PDF documents to plain text\ntags: [pdf]\n",
+            "Run pdftotext on the file.",
+        );
+        write_skill(
+            dir.path(),
+            "pdf-merge",
+            "description: Merge PDF files\nuser_invocable: false\n",
+            "Use qpdf to merge.",
+        );
+        write_skill(
+            dir.path(),
+            "pdf-secrets",
+            "description: Decrypt PDF files\ndisable_model_invocation: true\n",
+            "Use the vault password.",
+        );
+
+        let mut registry = SkillRegistry::new();
+        registry.load_dir(dir.path()).unwrap();
+        registry.register(Box::new(UpperSkill)).unwrap();
+        (dir, Arc::new(registry))
+    }
+
+    async fn call(
+        server: &McpSdkServerConfig,
+        tool: &str,
+        arguments: serde_json::Value,
+    ) -> (String, bool) {
+        let response = server
+            .instance
+            .handle_message(serde_json::json!({
+                "method": "tools/call",
+                "params": {"name": tool, "arguments": arguments}
+            }))
+            .await
+            .unwrap();
+        let text = response["content"][0]["text"].as_str().unwrap().to_string();
+        (text, response["isError"] == true)
+    }
+
+    #[tokio::test]
+    async fn test_search_skills_hides_skills_claude_may_not_invoke() {
+        let (_dir, registry) = registry();
+        assert_eq!(registry.search("pdf").len(), 3);
+        let server = registry.as_mcp_server("skills");
+        assert_eq!(server.name, "skills");
+
+        let (text, is_error) = call(
+            &server,
+            SEARCH_SKILLS_TOOL,
+            serde_json::json!({"query": "pdf"}),
+        )
+        .await;
+        assert!(!is_error);
+        let hits: Vec<serde_json::Value> = serde_json::from_str(&text).unwrap();
+        let names: Vec<&str> = hits.iter().map(|h| h["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["pdf-to-text", "pdf-merge"]);
+        assert_eq!(
+            hits[0]["description"],
+            "Convert PDF documents to plain text"
+        );
+        assert_eq!(hits[0]["tags"], serde_json::json!(["pdf"]));
+        assert!(hits[0]["score"].as_f64().unwrap() > hits[1]["score"].as_f64().unwrap());
+
+        let (text, _) = call(
+            &server,
+            SEARCH_SKILLS_TOOL,
+            serde_json::json!({"query": "pdf", "limit": 1}),
+        )
+        .await;
+        assert_eq!(
+            serde_json::from_str::<Vec<serde_json::Value>>(&text)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_skill_instructions() {
+        let (_dir, registry) = registry();
+        let server = registry.as_mcp_server("skills");
+        let instructions = |name: &str| {
+            call(
+                &server,
+                GET_SKILL_INSTRUCTIONS_TOOL,
+                serde_json::json!({"name": name}),
+            )
+        };
+
+        let (text, is_error) = instructions("pdf-merge").await;
+        assert!(!is_error);
+        assert_eq!(text.trim(), "Use qpdf to merge.");
+
+        let (text, is_error) = instructions("pdf-secrets").await;
+        assert!(is_error);
+        assert!(text.contains("Unknown skill 'pdf-secrets'"));
+        assert!(!text.contains("vault"));
+
+        let (text, is_error) = instructions("upper").await;
+        assert!(is_error);
+        assert_eq!(text, "Skill 'upper' has no instructions");
+    }
+
+    #[tokio::test]
+    async fn test_run_skill() {
+        let (_dir, registry) = registry();
+        let server = Arc::clone(&registry).as_mcp_server("skills");
+
+        let (text, is_error) = call(
+            &server,
+            RUN_SKILL_TOOL,
+            serde_json::json!({"name": "upper", "input": "hello"}),
+        )
+        .await;
+        assert!(!is_error);
+        let output: SkillOutput = serde_json::from_str(&text).unwrap();
+        assert_eq!(output.data, "HELLO");
+
+        let (text, is_error) = call(
+            &server,
+            RUN_SKILL_TOOL,
+            serde_json::json!({"name": "upper"}),
+        )
+        .await;
+        assert!(is_error);
+        assert_eq!(
+            serde_json::from_str::<SkillOutput>(&text)
+                .unwrap()
+                .error
+                .as_deref(),
+            Some("expected text")
+        );
+
+        // Hidden skills are not run
+        let (text, is_error) = call(
+            &server,
+            RUN_SKILL_TOOL,
+            serde_json::json!({"name": "pdf-secrets"}),
+        )
+        .await;
+        assert!(is_error);
+        assert!(text.starts_with("Unknown skill"));
+
+        let stats = registry.stats_for("upper").unwrap();
+        assert_eq!((stats.successes, stats.failures), (1, 1));
+        assert!(registry.stats_for("pdf-secrets").is_none());
+    }
+}
@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+use crate::errors::YamlParseError;
+
 // Use types from the current module's types.rs
 use super::types::SkillPackage;
 
@@ -18,7 +20,7 @@ pub enum SkillMdError {
     IoError(#[from] std::io::Error),
 
     #[error("YAML parsing error: {0}")]
-    YamlError(String),
+    YamlError(#[from] YamlParseError),
 
     #[error("Missing required field: {0}")]
     MissingField(String),
@@ -29,6 +31,9 @@ pub enum SkillMdError {
     #[error("Failed to parse skill package: {0}")]
     PackageError(String),
 
+    #[error("Failed to serialize frontmatter: {0}")]
+    SerializeError(String),
+
     // === Validation Errors ===
 
     #[error("Name exceeds maximum length of 64 characters (got {0} characters)")]
@@ -73,6 +78,18 @@ pub struct SkillMdMetadata {
     #[serde(default)]
     pub dependencies: Vec<String>,
 
+    /// Oldest SDK version the skill works with (e.g. "0.3")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_sdk_version: Option<String>,
+
+    /// Oldest Claude Code CLI version the skill works with (e.g. "2.0")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_cli_version: Option<String>,
+
+    /// Whether outputs may be reused for identical inputs (default: false)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cacheable: bool,
+
     // === Advanced Fields (Claude Code Official) ===
 
     /// Tool restrictions - limits which tools the skill can use
@@ -107,6 +124,11 @@ pub struct SkillMdMetadata {
     /// Does not affect auto-discovery based on description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_model_invocation: Option<bool>,
+
+    /// Fields this SDK does not know, kept so rewriting SKILL.md does not drop them
+    #[cfg(feature = "yaml")]
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
 }
 
 impl SkillMdMetadata {
@@ -185,6 +207,30 @@ fn default_user_invocable() -> bool {
     true
 }
 
+/// Serialize metadata to a YAML mapping
+#[cfg(feature = "yaml")]
+fn to_mapping(metadata: &SkillMdMetadata) -> Result<serde_yaml::Mapping, SkillMdError> {
+    match serde_yaml::to_value(metadata) {
+        Ok(serde_yaml::Value::Mapping(mapping)) => Ok(mapping),
+        Ok(_) => Err(SkillMdError::SerializeError(
+            "metadata is not a mapping".to_string(),
+        )),
+        Err(e) => Err(SkillMdError::SerializeError(e.to_string())),
+    }
+}
+
+/// Whether a field only holds the value it defaults to when absent
+#[cfg(feature = "yaml")]
+fn is_default_field(key: &serde_yaml::Value, value: &serde_yaml::Value) -> bool {
+    match key.as_str() {
+        Some("version") => value.as_str() == Some(default_version().as_str()),
+        Some("tags" | "dependencies") => value.as_sequence().is_some_and(|s| s.is_empty()),
+        Some("user_invocable") => value.as_bool() == Some(default_user_invocable()),
+        Some("cacheable") => value.as_bool() == Some(false),
+        _ => false,
+    }
+}
+
 /// Context mode for skill execution
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -258,6 +304,21 @@ pub struct SkillMdFile {
     pub forms: Option<PathBuf>,
     /// Resource cache for progressive disclosure (maps name to path)
     _resource_cache: Option<std::collections::HashMap<String, PathBuf>>,
+    /// Path of the SKILL.md file
+    path: PathBuf,
+    /// Frontmatter as read, for rewriting it with minimal changes
+    #[cfg(feature = "yaml")]
+    original: OriginalFrontmatter,
+}
+
+/// Frontmatter of a SKILL.md file as it was read
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone)]
+struct OriginalFrontmatter {
+    /// The YAML mapping, in file order
+    raw: serde_yaml::Mapping,
+    /// The parsed metadata serialized again, to detect which fields changed
+    parsed: serde_yaml::Mapping,
 }
 
 impl SkillMdFile {
@@ -294,10 +355,30 @@ impl SkillMdFile {
             .ok_or_else(|| SkillMdError::InvalidFormat)?;
 
         // Read the file
-        let content = std::fs::read_to_string(path)?;
+        let text = std::fs::read_to_string(path)?;
 
         // Split frontmatter and content
-        let (metadata, content) = Self::parse_frontmatter(&content)?;
+        let (metadata, content) = Self::parse_frontmatter(&text)?;
+        #[cfg(feature = "yaml")]
+        let original = {
+            let (yaml_content, _) = Self::split_frontmatter(&text)?;
+            OriginalFrontmatter {
+                raw: serde_yaml::from_str(yaml_content)
+                    .map_err(|e| YamlParseError::from_serde(yaml_content, &e))?,
+                parsed: to_mapping(&metadata)?,
+            }
+        };
+
+        for (field, minimum) in [
+            ("min_sdk_version", &metadata.min_sdk_version),
+            ("min_cli_version", &metadata.min_cli_version),
+        ] {
+            if let Some(minimum) = minimum {
+                if let Err(e) = crate::skills::version::min_version_requirement(minimum) {
+                    tracing::warn!("{}: ignoring {} '{}': {}", path.display(), field, minimum, e);
+                }
+            }
+        }
 
         // Discover associated files
         let scripts = Self::discover_scripts(&skill_dir);
@@ -317,11 +398,101 @@ impl SkillMdFile {
             reference,
             forms,
             _resource_cache: Some(resource_cache),
+            path: path.to_path_buf(),
+            #[cfg(feature = "yaml")]
+            original,
         })
     }
 
-    /// Parse YAML frontmatter and markdown content
-    fn parse_frontmatter(content: &str) -> Result<(SkillMdMetadata, String), SkillMdError> {
+    /// Path of the SKILL.md file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Render the file with the current metadata
+    ///
+    /// The frontmatter keeps the original key order, including fields this SDK
+    /// does not know. Unchanged fields keep their original value, changed ones
+    /// are updated in place, and new fields are appended unless they hold a
+    /// default value. The markdown body is kept byte for byte.
+    #[cfg(feature = "yaml")]
+    pub fn to_markdown(&self) -> Result<String, SkillMdError> {
+        let current = to_mapping(&self.metadata)?;
+        let mut merged = serde_yaml::Mapping::new();
+
+        for (key, raw_value) in &self.original.raw {
+            match current.get(key) {
+                Some(value) if self.original.parsed.get(key) == Some(value) => {
+                    merged.insert(key.clone(), raw_value.clone());
+                },
+                Some(value) => {
+                    merged.insert(key.clone(), value.clone());
+                },
+                // Never captured by the metadata, e.g. `author: null`
+                None if !self.original.parsed.contains_key(key) => {
+                    merged.insert(key.clone(), raw_value.clone());
+                },
+                // Removed through the metadata
+                None => {},
+            }
+        }
+        for (key, value) in &current {
+            if !merged.contains_key(key)
+                && !self.original.raw.contains_key(key)
+                && !is_default_field(key, value)
+            {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+
+        let yaml = serde_yaml::to_string(&merged)
+            .map_err(|e| SkillMdError::SerializeError(e.to_string()))?;
+        Ok(format!("---\n{}---{}", yaml, self.content))
+    }
+
+    /// Write the current metadata and content back to the SKILL.md file
+    ///
+    /// See [`to_markdown`](Self::to_markdown) for how the frontmatter is kept
+    /// stable. Writing an unchanged file twice produces identical bytes.
+    #[cfg(feature = "yaml")]
+    pub fn write_back(&self) -> Result<(), SkillMdError> {
+        std::fs::write(&self.path, self.to_markdown()?)?;
+        Ok(())
+    }
+
+    /// Edit the metadata, validate it and write the file back
+    ///
+    /// Nothing is written, and the metadata is left unchanged, if validation
+    /// fails.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::skills::skill_md::SkillMdFile;
+    ///
+    /// let mut skill = SkillMdFile::parse(".claude/skills/my-skill/SKILL.md")?;
+    /// skill.update_frontmatter(|meta| meta.version = "1.1.0".to_string())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "yaml")]
+    pub fn update_frontmatter(
+        &mut self,
+        update: impl FnOnce(&mut SkillMdMetadata),
+    ) -> Result<(), SkillMdError> {
+        let mut metadata = self.metadata.clone();
+        update(&mut metadata);
+        metadata.validate()?;
+
+        let previous = std::mem::replace(&mut self.metadata, metadata);
+        if let Err(e) = self.write_back() {
+            self.metadata = previous;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Split a SKILL.md file into its YAML frontmatter and markdown content
+    fn split_frontmatter(content: &str) -> Result<(&str, String), SkillMdError> {
         if !content.starts_with("---") {
             return Err(SkillMdError::InvalidFormat);
         }
@@ -343,9 +514,28 @@ impl SkillMdFile {
             parts[2].to_string()
         };
 
+        Ok((yaml_content, markdown_content))
+    }
+
+    /// Deserialize the YAML frontmatter
+    #[cfg(feature = "yaml")]
+    fn parse_metadata(yaml_content: &str) -> Result<SkillMdMetadata, SkillMdError> {
+        serde_yaml::from_str(yaml_content)
+            .map_err(|e| YamlParseError::from_serde(yaml_content, &e).into())
+    }
+
+    /// Deserialize the YAML frontmatter, which needs the `yaml` feature
+    #[cfg(not(feature = "yaml"))]
+    fn parse_metadata(yaml_content: &str) -> Result<SkillMdMetadata, SkillMdError> {
+        Err(YamlParseError::new("YAML support not enabled", yaml_content).into())
+    }
+
+    /// Parse YAML frontmatter and markdown content
+    fn parse_frontmatter(content: &str) -> Result<(SkillMdMetadata, String), SkillMdError> {
+        let (yaml_content, markdown_content) = Self::split_frontmatter(content)?;
+
         // Parse YAML frontmatter
-        let metadata: SkillMdMetadata = serde_yaml::from_str(yaml_content)
-            .map_err(|e| SkillMdError::YamlError(e.to_string()))?;
+        let metadata = Self::parse_metadata(yaml_content)?;
 
         // Validate required fields
         if metadata.name.is_empty() {
@@ -519,6 +709,9 @@ impl SkillMdFile {
                 author: self.metadata.author.clone(),
                 dependencies: self.metadata.dependencies.clone(),
                 tags: self.metadata.tags.clone(),
+                min_sdk_version: self.metadata.min_sdk_version.clone(),
+                min_cli_version: self.metadata.min_cli_version.clone(),
+                cacheable: self.metadata.cacheable,
             },
             instructions: self.content.clone(),
             scripts: self.scripts.iter()
@@ -526,16 +719,24 @@ impl SkillMdFile {
                 .collect(),
             resources: SkillResources {
                 folders: resource_folders,
-                tools: vec![],
+                tools: self.metadata.allowed_tools.clone().unwrap_or_default(),
                 tests: vec![],
             },
         }
     }
 }
 
+/// Default number of directory levels [`SkillsDirScanner`] searches for skills
+pub const DEFAULT_SCAN_DEPTH: usize = 3;
+
 /// Scanner for discovering skills from .claude/skills/ directories
+///
+/// Skills may be grouped in category directories, such as
+/// `.claude/skills/devops/terraform-helper/SKILL.md`. Any directory containing
+/// a SKILL.md file is a skill; the scanner does not look for skills inside it.
 pub struct SkillsDirScanner {
     base_dir: PathBuf,
+    max_depth: usize,
 }
 
 impl SkillsDirScanner {
@@ -557,9 +758,30 @@ impl SkillsDirScanner {
     pub fn new<P: AsRef<Path>>(base_dir: P) -> Self {
         Self {
             base_dir: base_dir.as_ref().to_path_buf(),
+            max_depth: DEFAULT_SCAN_DEPTH,
         }
     }
 
+    /// Search for skills at most `max_depth` directory levels below the base
+    /// directory (default: [`DEFAULT_SCAN_DEPTH`])
+    ///
+    /// A depth of 1 only finds skills directly in the base directory.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::skills::skill_md::SkillsDirScanner;
+    ///
+    /// // Finds skills/devops/terraform-helper/SKILL.md but not deeper
+    /// let scanner = SkillsDirScanner::new("skills").with_max_depth(2);
+    /// let skills = scanner.scan()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
     /// Create a new scanner for project .claude/skills/ directory
     ///
     /// # Arguments
@@ -576,9 +798,7 @@ impl SkillsDirScanner {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn from_project_dir<P: AsRef<Path>>(project_dir: P) -> Self {
-        Self {
-            base_dir: project_dir.as_ref().join(".claude").join("skills"),
-        }
+        Self::new(project_dir.as_ref().join(".claude").join("skills"))
     }
 
     /// Create a new scanner for user ~/.config/claude/skills/ directory
@@ -606,16 +826,20 @@ impl SkillsDirScanner {
                 )
             ))?;
 
-        Ok(Self {
-            base_dir: PathBuf::from(home)
+        Ok(Self::new(
+            PathBuf::from(home)
                 .join(".config")
                 .join("claude")
                 .join("skills"),
-        })
+        ))
     }
 
     /// Scan the skills directory and load all SKILL.md files
     ///
+    /// Skills in nested category directories are found down to the scanner's
+    /// max depth. Skills are returned in path order; when two share a name,
+    /// both are returned and a warning is logged.
+    ///
     /// Returns an empty Vec if the directory doesn't exist (not an error)
     ///
     /// # Returns
@@ -637,58 +861,29 @@ impl SkillsDirScanner {
     /// # }
     /// ```
     pub fn scan(&self) -> Result<Vec<SkillMdFile>, SkillMdError> {
-        if !self.base_dir.exists() {
-            // Return empty if directory doesn't exist (not an error)
-            tracing::debug!(
-                "Skills directory does not exist: {:?}",
-                self.base_dir
-            );
-            return Ok(Vec::new());
-        }
-
         let mut skills = Vec::new();
 
-        // Read entries in skills directory
-        let entries = std::fs::read_dir(&self.base_dir)
-            .map_err(|e| SkillMdError::IoError(e))?;
-
-        for entry in entries {
-            let entry = entry.map_err(|e| SkillMdError::IoError(e))?;
-            let skill_dir = entry.path();
-
-            // Skip if not a directory
-            if !skill_dir.is_dir() {
-                continue;
-            }
-
-            // Look for SKILL.md file
-            let skill_md = skill_dir.join("SKILL.md");
-            if skill_md.exists() {
-                match SkillMdFile::parse(&skill_md) {
-                    Ok(skill) => {
-                        tracing::info!(
-                            "Loaded skill '{}' from {:?}",
-                            skill.metadata.name,
-                            skill_md
-                        );
-                        skills.push(skill);
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Failed to load skill from {:?}: {}",
-                            skill_md, e
-                        );
-                        // Continue loading other skills
-                    }
+        for skill_md in self.find_skill_files()? {
+            match SkillMdFile::parse(&skill_md) {
+                Ok(skill) => {
+                    tracing::info!(
+                        "Loaded skill '{}' from {:?}",
+                        skill.metadata.name,
+                        skill_md
+                    );
+                    skills.push(skill);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load skill from {:?}: {}",
+                        skill_md, e
+                    );
+                    // Continue loading other skills
                 }
-            } else {
-                tracing::debug!(
-                    "No SKILL.md found in {:?}",
-                    skill_dir
-                );
             }
         }
 
+        warn_duplicate_names(&skills);
         Ok(skills)
     }
 
@@ -725,52 +920,26 @@ impl SkillsDirScanner {
     /// # }
     /// ```
     pub async fn scan_parallel(&self) -> Result<Vec<SkillMdFile>, SkillMdError> {
-        if !self.base_dir.exists() {
-            // Return empty if directory doesn't exist (not an error)
-            tracing::debug!(
-                "Skills directory does not exist: {:?}",
-                self.base_dir
-            );
-            return Ok(Vec::new());
-        }
-
-        // Read entries in skills directory
-        let entries = std::fs::read_dir(&self.base_dir)
-            .map_err(|e| SkillMdError::IoError(e))?;
-
-        // Collect all skill directory paths
-        let skill_dirs: Vec<PathBuf> = entries
-            .filter_map(|entry| entry.ok())
-            .map(|entry| entry.path())
-            .filter(|path| path.is_dir())
-            .collect();
-
         // Create parsing futures for each skill directory
-        let parse_futures: Vec<_> = skill_dirs
+        let parse_futures: Vec<_> = self
+            .find_skill_files()?
             .into_iter()
-            .filter_map(|skill_dir| {
-                let skill_md = skill_dir.join("SKILL.md");
-                if skill_md.exists() {
-                    let skill_md_clone = skill_md.clone();
-                    Some(async move {
-                        tokio::task::spawn_blocking(move || {
-                            SkillMdFile::parse(&skill_md)
-                        })
-                        .await
-                        .unwrap_or_else(|e| {
-                            tracing::error!(
-                                "Task failed for {:?}: {}",
-                                skill_md_clone, e
-                            );
-                            Err(SkillMdError::IoError(std::io::Error::new(
-                                std::io::ErrorKind::Other,
-                                "Task execution failed"
-                            )))
-                        })
+            .map(|skill_md| {
+                let skill_md_clone = skill_md.clone();
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        SkillMdFile::parse(&skill_md)
+                    })
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::error!(
+                            "Task failed for {:?}: {}",
+                            skill_md_clone, e
+                        );
+                        Err(SkillMdError::IoError(std::io::Error::other(
+                            "Task execution failed",
+                        )))
                     })
-                } else {
-                    tracing::debug!("No SKILL.md found in {:?}", skill_dir);
-                    None
                 }
             })
             .collect();
@@ -801,8 +970,90 @@ impl SkillsDirScanner {
             skills.len()
         );
 
+        warn_duplicate_names(&skills);
         Ok(skills)
     }
+
+    /// Paths of the SKILL.md files below the base directory, sorted
+    ///
+    /// Returns an empty Vec if the base directory doesn't exist. Directories
+    /// that cannot be read, or that were already visited through a symlink,
+    /// are skipped.
+    fn find_skill_files(&self) -> Result<Vec<PathBuf>, SkillMdError> {
+        if !self.base_dir.exists() {
+            // Return empty if directory doesn't exist (not an error)
+            tracing::debug!(
+                "Skills directory does not exist: {:?}",
+                self.base_dir
+            );
+            return Ok(Vec::new());
+        }
+
+        let mut skill_files = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        visited.insert(self.base_dir.canonicalize()?);
+        // Directories to search, with their depth below the base directory
+        let mut pending = vec![(self.base_dir.clone(), 0)];
+
+        while let Some((dir, depth)) = pending.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                // The base directory must be readable; nested ones may not be
+                Err(e) if depth == 0 => return Err(SkillMdError::IoError(e)),
+                Err(e) => {
+                    tracing::warn!("Failed to read skills directory {:?}: {}", dir, e);
+                    continue;
+                }
+            };
+
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let path = entry.path();
+                if !path.is_dir() {
+                    continue;
+                }
+
+                // Guard against symlink cycles
+                let Ok(canonical) = path.canonicalize() else {
+                    continue;
+                };
+                if !visited.insert(canonical) {
+                    tracing::debug!("Skipping already visited directory {:?}", path);
+                    continue;
+                }
+
+                let skill_md = path.join("SKILL.md");
+                if skill_md.is_file() {
+                    // A skill's own subdirectories hold its resources, not skills
+                    skill_files.push(skill_md);
+                } else if depth + 1 < self.max_depth {
+                    pending.push((path, depth + 1));
+                } else {
+                    tracing::debug!("No SKILL.md found in {:?}", path);
+                }
+            }
+        }
+
+        skill_files.sort();
+        Ok(skill_files)
+    }
+}
+
+/// Log a warning for each skill whose name was already used by another skill
+fn warn_duplicate_names(skills: &[SkillMdFile]) {
+    let mut seen: std::collections::HashMap<&str, &Path> = std::collections::HashMap::new();
+    for skill in skills {
+        match seen.get(skill.metadata.name.as_str()) {
+            Some(first) => tracing::warn!(
+                "Skill name '{}' is used by both {:?} and {:?}",
+                skill.metadata.name,
+                first,
+                skill.skill_dir
+            ),
+            None => {
+                seen.insert(&skill.metadata.name, &skill.skill_dir);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1442,6 +1693,80 @@ version: 1.0.0
         assert_eq!(sync_names, parallel_names);
     }
 
+    fn write_named_skill(dir: &Path, name: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("SKILL.md"),
+            format!("---\nname: {}\ndescription: Skill {}\n---\n\n# {}\n", name, name, name),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scan_nested_skill_directories() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skills_dir = temp_dir.path();
+        write_named_skill(&skills_dir.join("top"), "top");
+        write_named_skill(&skills_dir.join("devops").join("terraform-helper"), "terraform-helper");
+        // Not a separate skill: it belongs to terraform-helper
+        write_named_skill(
+            &skills_dir.join("devops").join("terraform-helper").join("resources"),
+            "inner",
+        );
+        write_named_skill(&skills_dir.join("a").join("b").join("c").join("too-deep"), "too-deep");
+
+        let scanner = SkillsDirScanner::new(skills_dir);
+        for skills in [scanner.scan().unwrap(), scanner.scan_parallel().await.unwrap()] {
+            let found: Vec<_> = skills
+                .iter()
+                .map(|s| (s.metadata.name.as_str(), s.skill_dir.clone()))
+                .collect();
+            assert_eq!(
+                found,
+                vec![
+                    (
+                        "terraform-helper",
+                        skills_dir.join("devops").join("terraform-helper")
+                    ),
+                    ("top", skills_dir.join("top")),
+                ]
+            );
+        }
+
+        let deep = SkillsDirScanner::new(skills_dir).with_max_depth(4).scan().unwrap();
+        assert!(deep.iter().any(|s| s.metadata.name == "too-deep"));
+        let shallow = SkillsDirScanner::new(skills_dir).with_max_depth(1).scan().unwrap();
+        assert_eq!(shallow.len(), 1);
+        assert_eq!(shallow[0].metadata.name, "top");
+    }
+
+    #[test]
+    fn test_scan_returns_duplicate_names() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_named_skill(&temp_dir.path().join("dev").join("lint"), "lint");
+        write_named_skill(&temp_dir.path().join("ops").join("lint"), "lint");
+
+        let skills = SkillsDirScanner::new(temp_dir.path()).scan().unwrap();
+        assert_eq!(skills.len(), 2);
+        assert!(skills.iter().all(|s| s.metadata.name == "lint"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_survives_symlink_cycle() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let category = temp_dir.path().join("category");
+        write_named_skill(&category.join("skill"), "skill");
+        std::os::unix::fs::symlink(temp_dir.path(), category.join("loop")).unwrap();
+
+        let skills = SkillsDirScanner::new(temp_dir.path())
+            .with_max_depth(usize::MAX)
+            .scan()
+            .unwrap();
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].skill_dir, category.join("skill"));
+    }
+
     #[test]
     fn test_progressive_disclosure_resource_cache() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -1611,4 +1936,123 @@ version: 1.0.0
         // New API provides the same resources
         assert_eq!(skill.get_resource_names().len(), 2);
     }
+
+    #[cfg(feature = "yaml")]
+    const FIELD_RICH_SKILL_MD: &str = r#"---
+description: Review pull requests
+name: pr-review
+license: Apache-2.0
+allowed-tools: Read, Grep
+tags:
+  - review
+hooks:
+  pre_tool_use:
+    - matcher: Bash
+      command: ./check.sh
+      timeout_ms: 500
+metadata:
+  owner: platform-team
+  reviewed: 2025-01-01
+version: 2.1.0
+---
+
+# PR Review
+
+Keep the --- separators and trailing spaces   
+exactly as written.
+"#;
+
+    #[cfg(feature = "yaml")]
+    fn write_skill(dir: &Path, text: &str) -> SkillMdFile {
+        let path = dir.join("SKILL.md");
+        std::fs::write(&path, text).unwrap();
+        SkillMdFile::parse(path).unwrap()
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_unknown_frontmatter_fields_are_captured() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skill = write_skill(temp_dir.path(), FIELD_RICH_SKILL_MD);
+
+        let extra = &skill.metadata.extra;
+        assert_eq!(extra["license"].as_str(), Some("Apache-2.0"));
+        assert_eq!(extra["allowed-tools"].as_str(), Some("Read, Grep"));
+        assert_eq!(extra["metadata"]["owner"].as_str(), Some("platform-team"));
+        assert!(!extra.contains_key("name"));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_write_back_round_trip_is_idempotent() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skill = write_skill(temp_dir.path(), FIELD_RICH_SKILL_MD);
+
+        skill.write_back().unwrap();
+        let first = std::fs::read_to_string(skill.path()).unwrap();
+        let reparsed = SkillMdFile::parse(skill.path()).unwrap();
+        assert_eq!(
+            to_mapping(&reparsed.metadata).unwrap(),
+            to_mapping(&skill.metadata).unwrap()
+        );
+        assert_eq!(reparsed.content, skill.content);
+
+        reparsed.write_back().unwrap();
+        let second = std::fs::read_to_string(skill.path()).unwrap();
+        assert_eq!(first, second);
+
+        // Original key order, nested unknown fields and the body survive
+        let keys: Vec<&str> = first
+            .lines()
+            .skip(1)
+            .take_while(|line| *line != "---")
+            .filter(|line| !line.starts_with(' ') && !line.starts_with('-'))
+            .map(|line| line.split(':').next().unwrap())
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "description",
+                "name",
+                "license",
+                "allowed-tools",
+                "tags",
+                "hooks",
+                "metadata",
+                "version"
+            ]
+        );
+        assert!(first.contains("timeout_ms: 500"));
+        assert!(first.ends_with(&skill.content));
+        assert!(!first.contains("user_invocable"));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_update_frontmatter_changes_only_edited_fields() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut skill = write_skill(temp_dir.path(), FIELD_RICH_SKILL_MD);
+
+        skill
+            .update_frontmatter(|meta| {
+                meta.version = "2.2.0".to_string();
+                meta.tags.push("git".to_string());
+                meta.model = Some("claude-sonnet-4-20250514".to_string());
+            })
+            .unwrap();
+
+        let text = std::fs::read_to_string(skill.path()).unwrap();
+        let updated = SkillMdFile::parse(skill.path()).unwrap();
+        assert_eq!(updated.metadata.version, "2.2.0");
+        assert_eq!(updated.metadata.tags, vec!["review", "git"]);
+        assert_eq!(updated.metadata.extra, skill.metadata.extra);
+        assert!(text.contains("timeout_ms: 500"));
+        assert!(text.contains("version: 2.2.0\nmodel: claude-sonnet-4-20250514\n---"));
+
+        // Invalid edits are rejected without touching the file
+        let err = skill.update_frontmatter(|meta| meta.name = "Bad Name".to_string());
+        assert!(err.is_err());
+        assert_eq!(skill.metadata.name, "pr-review");
+        assert_eq!(std::fs::read_to_string(skill.path()).unwrap(), text);
+    }
 }
@@ -1,7 +1,14 @@
 //! Tag-based filtering and querying for Agent Skills
+//!
+//! Also provides tag inference for skills whose frontmatter has no `tags:`,
+//! see [`infer`] and [`SkillRegistry::backfill_tags`](super::SkillRegistry::backfill_tags).
 
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::Path;
+
+use super::skill_md::SkillMdFile;
+use crate::types::config::ClaudeAgentOptions;
 
 /// Tag query operator
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,9 +38,14 @@ impl fmt::Display for TagOperator {
 }
 
 /// Tag filter for querying skills
+///
+/// Tags match case-insensitively unless [`case_sensitive`](Self::case_sensitive)
+/// is set. A tag ending in `*` matches every tag starting with the text
+/// before it, so `devops-*` matches `devops-aws` and `devops-k8s`.
 #[derive(Debug, Clone)]
 pub struct TagFilter {
     operators: Vec<TagOperator>,
+    case_sensitive: bool,
 }
 
 impl TagFilter {
@@ -41,9 +53,16 @@ impl TagFilter {
     pub fn new() -> Self {
         Self {
             operators: Vec::new(),
+            case_sensitive: false,
         }
     }
 
+    /// Match tags with their exact case
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
     /// Add a "has tag" condition
     pub fn has(mut self, tag: impl Into<String>) -> Self {
         self.operators.push(TagOperator::Has(tag.into()));
@@ -57,40 +76,84 @@ impl TagFilter {
     }
 
     /// Add an "any of tags" condition
-    pub fn any_of(mut self, tags: Vec<String>) -> Self {
-        self.operators.push(TagOperator::AnyOf(tags));
+    pub fn any_of<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.operators
+            .push(TagOperator::AnyOf(tags.into_iter().map(Into::into).collect()));
         self
     }
 
     /// Add an "all of tags" condition
-    pub fn all_of(mut self, tags: Vec<String>) -> Self {
-        self.operators.push(TagOperator::AllOf(tags));
+    pub fn all_of<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.operators
+            .push(TagOperator::AllOf(tags.into_iter().map(Into::into).collect()));
         self
     }
 
     /// Add a "none of tags" condition
-    pub fn none_of(mut self, tags: Vec<String>) -> Self {
-        self.operators.push(TagOperator::NoneOf(tags));
+    pub fn none_of<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.operators
+            .push(TagOperator::NoneOf(tags.into_iter().map(Into::into).collect()));
         self
     }
 
     /// Check if a set of tags matches all filter conditions
     pub fn matches(&self, tags: &HashSet<String>) -> bool {
-        for op in &self.operators {
-            if !self.matches_operator(op, tags) {
-                return false;
-            }
-        }
-        true
+        self.matches_tags(tags)
+    }
+
+    /// Check if a list of tags matches all filter conditions
+    pub fn matches_tags<'a>(&self, tags: impl IntoIterator<Item = &'a String> + Clone) -> bool {
+        self.operators
+            .iter()
+            .all(|op| self.matches_operator(op, tags.clone()))
     }
 
-    fn matches_operator(&self, op: &TagOperator, item_tags: &HashSet<String>) -> bool {
+    fn matches_operator<'a>(
+        &self,
+        op: &TagOperator,
+        item_tags: impl IntoIterator<Item = &'a String> + Clone,
+    ) -> bool {
+        let has = |pattern: &str| {
+            item_tags
+                .clone()
+                .into_iter()
+                .any(|tag| self.tag_matches(pattern, tag))
+        };
         match op {
-            TagOperator::Has(tag) => item_tags.contains(tag),
-            TagOperator::NotHas(tag) => !item_tags.contains(tag),
-            TagOperator::AnyOf(tags) => tags.iter().any(|t| item_tags.contains(t)),
-            TagOperator::AllOf(tags) => tags.iter().all(|t| item_tags.contains(t)),
-            TagOperator::NoneOf(tags) => tags.iter().all(|t| !item_tags.contains(t)),
+            TagOperator::Has(tag) => has(tag),
+            TagOperator::NotHas(tag) => !has(tag),
+            TagOperator::AnyOf(tags) => tags.iter().any(|t| has(t)),
+            TagOperator::AllOf(tags) => tags.iter().all(|t| has(t)),
+            TagOperator::NoneOf(tags) => tags.iter().all(|t| !has(t)),
+        }
+    }
+
+    /// Whether `tag` matches `pattern`, which may end in a `*` wildcard
+    fn tag_matches(&self, pattern: &str, tag: &str) -> bool {
+        let eq = |a: &str, b: &str| {
+            if self.case_sensitive {
+                a == b
+            } else {
+                a.eq_ignore_ascii_case(b)
+            }
+        };
+        match pattern.strip_suffix('*') {
+            Some(prefix) => tag
+                .get(..prefix.len())
+                .is_some_and(|start| eq(start, prefix)),
+            None => eq(pattern, tag),
         }
     }
 }
@@ -344,6 +407,415 @@ impl TagUtils {
     }
 }
 
+/// Where an inferred tag came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagSource {
+    /// Keyword extracted from the description and instructions
+    Keyword,
+    /// Tool referenced by `allowed_tools` or the instructions
+    Tool,
+    /// File extension of a bundled script
+    FileExtension,
+    /// Suggested by Claude
+    Claude,
+}
+
+/// Inferred tag with a confidence score in `0.0..=1.0`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredTag {
+    /// Normalized tag name
+    pub tag: String,
+    /// Confidence score
+    pub score: f64,
+    /// Signal the tag was derived from
+    pub source: TagSource,
+}
+
+/// Opt-in configuration for Claude-assisted tag inference
+#[derive(Clone)]
+pub struct ClaudeTagging {
+    /// Options for the tagging queries; a small model keeps them cheap
+    pub options: ClaudeAgentOptions,
+    /// Number of skills tagged concurrently
+    pub batch_size: usize,
+}
+
+impl ClaudeTagging {
+    /// Create a configuration with the given query options and a batch size of 4
+    pub fn new(options: ClaudeAgentOptions) -> Self {
+        Self {
+            options,
+            batch_size: 4,
+        }
+    }
+}
+
+/// Configuration for tag inference
+#[derive(Clone)]
+pub struct InferConfig {
+    /// Maximum number of tags inferred per skill
+    pub max_tags: usize,
+    /// Minimum score for a tag to be kept
+    pub min_score: f64,
+    /// Only infer tags for skills without any tags
+    pub only_untagged: bool,
+    /// Document frequencies used to down-weight keywords common to all skills
+    pub corpus: Option<KeywordCorpus>,
+    /// Claude-assisted inference, disabled unless set explicitly
+    pub claude: Option<ClaudeTagging>,
+}
+
+impl Default for InferConfig {
+    fn default() -> Self {
+        Self {
+            max_tags: 5,
+            min_score: 0.3,
+            only_untagged: true,
+            corpus: None,
+            claude: None,
+        }
+    }
+}
+
+/// Keyword document frequencies over a set of skills
+#[derive(Debug, Clone, Default)]
+pub struct KeywordCorpus {
+    documents: usize,
+    frequencies: HashMap<String, usize>,
+}
+
+impl KeywordCorpus {
+    /// Count in how many of `skills` each keyword occurs
+    pub fn from_skills(skills: &[SkillMdFile]) -> Self {
+        let mut corpus = Self::default();
+        for skill in skills {
+            corpus.documents += 1;
+            let keywords: HashSet<String> = keywords(&skill_text(skill)).collect();
+            for keyword in keywords {
+                *corpus.frequencies.entry(keyword).or_insert(0) += 1;
+            }
+        }
+        corpus
+    }
+
+    /// Smoothed inverse document frequency, normalized to `0.0..=1.0`
+    fn idf(&self, keyword: &str) -> f64 {
+        if self.documents < 2 {
+            return 1.0;
+        }
+        let df = self.frequencies.get(keyword).copied().unwrap_or(0) as f64;
+        let n = self.documents as f64;
+        ((n + 1.0) / (df + 1.0)).ln() / (n + 1.0).ln()
+    }
+}
+
+/// Words never used as tags or search terms
+pub(super) const STOPWORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "also", "always", "an", "and",
+    "any", "are", "as", "at", "available", "be", "because", "been", "before", "being", "below",
+    "between", "both", "but", "by", "can", "cannot", "could", "did", "do", "does", "doing", "done",
+    "down", "during", "each", "e.g", "eg", "etc", "every", "example", "examples", "few", "file",
+    "files", "first", "following", "for", "from", "further", "get", "given", "had", "has", "have",
+    "having", "he", "help", "her", "here", "him", "his", "how", "however", "i", "ie", "if", "in",
+    "include", "including", "input", "instead", "into", "is", "it", "its", "itself", "just",
+    "like", "make", "may", "me", "might", "more", "most", "must", "my", "need", "needed", "needs",
+    "never", "new", "no", "nor", "not", "note", "now", "of", "off", "on", "once", "one", "only",
+    "or", "other", "our", "out", "output", "over", "own", "provide", "provided", "provides",
+    "run", "same", "see", "should", "skill", "skills", "so", "some", "step", "steps", "such",
+    "than", "that", "the", "their", "them", "then", "there", "these", "they", "this", "those",
+    "through", "to", "too", "two", "under", "until", "up", "use", "used", "user", "uses", "using",
+    "very", "via", "want", "was", "way", "we", "well", "were", "what", "when", "where", "which",
+    "while", "who", "why", "will", "with", "within", "without", "would", "you", "your",
+];
+
+/// Tool names and the canonical tag they imply
+const TOOL_TAGS: &[(&str, &str)] = &[
+    ("Bash", "shell"),
+    ("Read", "filesystem"),
+    ("Write", "filesystem"),
+    ("Edit", "filesystem"),
+    ("MultiEdit", "filesystem"),
+    ("Glob", "search"),
+    ("Grep", "search"),
+    ("WebFetch", "web"),
+    ("WebSearch", "web"),
+    ("NotebookEdit", "jupyter"),
+    ("git", "git"),
+    ("docker", "docker"),
+    ("kubectl", "kubernetes"),
+    ("npm", "javascript"),
+    ("cargo", "rust"),
+    ("pip", "python"),
+    ("python", "python"),
+    ("psql", "sql"),
+];
+
+/// Script file extensions and the canonical tag they imply
+const EXTENSION_TAGS: &[(&str, &str)] = &[
+    ("py", "python"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("ps1", "powershell"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("ts", "typescript"),
+    ("rs", "rust"),
+    ("go", "go"),
+    ("rb", "ruby"),
+    ("sql", "sql"),
+];
+
+/// Infer tags for `skill` from its content, tools and scripts
+///
+/// This is the heuristic mode: keywords are ranked by term frequency (weighted by
+/// [`InferConfig::corpus`] when set) and combined with tags implied by referenced
+/// tools and script file extensions. Tags the skill already has are never returned.
+/// Use [`infer_with_claude`] for Claude-assisted inference.
+pub fn infer(skill: &SkillMdFile, config: &InferConfig) -> Vec<ScoredTag> {
+    let mut scored: HashMap<String, ScoredTag> = HashMap::new();
+    let mut add = |tag: &str, score: f64, source: TagSource| {
+        let tag = TagUtils::normalize_tag(tag);
+        if !TagUtils::is_valid_tag(&tag) {
+            return;
+        }
+        let entry = scored.entry(tag.clone()).or_insert(ScoredTag {
+            tag,
+            score,
+            source,
+        });
+        if score > entry.score {
+            entry.score = score;
+            entry.source = source;
+        }
+    };
+
+    // Keywords, with the description counting double
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for keyword in keywords(&skill.metadata.description) {
+        *frequencies.entry(keyword).or_insert(0) += 2;
+    }
+    for keyword in keywords(&skill.content) {
+        *frequencies.entry(keyword).or_insert(0) += 1;
+    }
+    let max_frequency = frequencies.values().copied().max().unwrap_or(1) as f64;
+    for (keyword, frequency) in &frequencies {
+        let idf = config.corpus.as_ref().map_or(1.0, |corpus| corpus.idf(keyword));
+        add(keyword, *frequency as f64 / max_frequency * idf, TagSource::Keyword);
+    }
+
+    // Tools from allowed_tools and the instructions
+    let allowed_tools = skill.metadata.allowed_tools.as_deref().unwrap_or_default();
+    for (tool, tag) in TOOL_TAGS {
+        let allowed = allowed_tools
+            .iter()
+            .any(|allowed| allowed.split('(').next() == Some(*tool));
+        if allowed || mentions_word(&skill.content, tool) {
+            add(tag, 1.0, TagSource::Tool);
+        }
+    }
+
+    // Script file extensions
+    for script in &skill.scripts {
+        let extension = script.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        if let Some((_, tag)) = EXTENSION_TAGS.iter().find(|(ext, _)| *ext == extension) {
+            add(tag, 1.0, TagSource::FileExtension);
+        }
+    }
+
+    finalize(scored.into_values().collect(), skill, config)
+}
+
+/// Infer tags for several skills, asking Claude when [`InferConfig::claude`] is set
+///
+/// Claude-assisted mode sends one query per skill, [`ClaudeTagging::batch_size`] at a
+/// time, and merges the suggestions with the heuristic tags. A failed query falls back
+/// to the heuristic tags for that skill.
+pub async fn infer_with_claude(skills: &[SkillMdFile], config: &InferConfig) -> Vec<Vec<ScoredTag>> {
+    let Some(claude) = &config.claude else {
+        return skills.iter().map(|skill| infer(skill, config)).collect();
+    };
+
+    let mut results = Vec::with_capacity(skills.len());
+    for batch in skills.chunks(claude.batch_size.max(1)) {
+        let suggestions = futures::future::join_all(
+            batch.iter().map(|skill| suggest_tags(skill, &claude.options)),
+        )
+        .await;
+
+        for (skill, suggestion) in batch.iter().zip(suggestions) {
+            let mut tags = infer(skill, config);
+            match suggestion {
+                Ok(suggested) => {
+                    for tag in suggested {
+                        match tags.iter_mut().find(|t| t.tag == tag) {
+                            Some(existing) => existing.score = existing.score.max(0.9),
+                            None => tags.push(ScoredTag {
+                                tag,
+                                score: 0.9,
+                                source: TagSource::Claude,
+                            }),
+                        }
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!("Claude tag inference failed for {}: {}", skill.metadata.name, e);
+                },
+            }
+            results.push(finalize(tags, skill, config));
+        }
+    }
+
+    results
+}
+
+/// Ask Claude for tags describing `skill`
+async fn suggest_tags(
+    skill: &SkillMdFile,
+    options: &ClaudeAgentOptions,
+) -> crate::errors::Result<Vec<String>> {
+    let prompt = format!(
+        "Suggest up to 5 short lowercase tags for the following agent skill. \
+         Reply with the tags only, comma-separated.\n\nName: {}\nDescription: {}\n\n{}",
+        skill.metadata.name, skill.metadata.description, skill.content
+    );
+
+    let mut options = options.clone();
+    options.max_turns = Some(1);
+    let messages = crate::query::query(prompt, Some(options)).await?;
+    let reply = crate::types::messages::CollectedResponse::from(messages).text();
+
+    Ok(TagUtils::parse_tags(&reply))
+}
+
+/// Drop existing and weak tags, then keep the best `max_tags`
+fn finalize(mut tags: Vec<ScoredTag>, skill: &SkillMdFile, config: &InferConfig) -> Vec<ScoredTag> {
+    let existing: HashSet<String> = skill
+        .metadata
+        .tags
+        .iter()
+        .map(|tag| TagUtils::normalize_tag(tag))
+        .collect();
+
+    tags.retain(|t| t.score >= config.min_score && !existing.contains(&t.tag));
+    tags.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.tag.cmp(&b.tag)));
+    tags.truncate(config.max_tags);
+    tags
+}
+
+/// Description and instructions of a skill
+fn skill_text(skill: &SkillMdFile) -> String {
+    format!("{}\n{}", skill.metadata.description, skill.content)
+}
+
+/// Lowercase keyword candidates of `text`, without stopwords and short or numeric tokens
+fn keywords(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-')
+        .map(|word| word.trim_matches('-').to_lowercase())
+        .filter(|word| {
+            word.len() >= 3
+                && word.len() <= 30
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && !STOPWORDS.contains(&word.as_str())
+        })
+}
+
+/// Check whether `word` occurs in `text` as a whole word
+fn mentions_word(text: &str, word: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|token| token == word)
+}
+
+/// Add `tags` to the `tags:` entry of the SKILL.md frontmatter in `source`
+///
+/// Only the `tags:` entry is rewritten (or inserted before the closing `---`);
+/// every other line, including comments, is left untouched. Existing tags are kept.
+/// Returns `None` if `source` has no frontmatter.
+#[cfg(feature = "yaml")]
+pub(crate) fn add_tags_to_frontmatter(source: &str, tags: &[String]) -> Option<String> {
+    let newline = if source.contains("\r\n") { "\r\n" } else { "\n" };
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return None;
+    }
+    let end = 1 + lines[1..].iter().position(|l| l.trim_end() == "---")?;
+
+    let tags_start = (1..end).find(|&i| lines[i].starts_with("tags:"));
+    let (existing, replace_range, indent) = match tags_start {
+        Some(start) => {
+            let mut stop = start + 1;
+            while stop < end && (lines[stop].starts_with([' ', '\t']) || lines[stop].starts_with("- ")) {
+                stop += 1;
+            }
+            let block = lines[start..stop].concat();
+            let parsed: serde_yaml::Value = serde_yaml::from_str(&block).ok()?;
+            let existing: Vec<String> = parsed["tags"]
+                .as_sequence()
+                .map(|seq| seq.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            let indent = lines[start + 1..stop]
+                .iter()
+                .find_map(|l| l.find("- ").map(|i| l[..i].to_string()))
+                .unwrap_or_else(|| "  ".to_string());
+            (existing, start..stop, indent)
+        },
+        None => (Vec::new(), end..end, "  ".to_string()),
+    };
+
+    let mut merged = existing;
+    for tag in tags {
+        if !merged.contains(tag) {
+            merged.push(tag.clone());
+        }
+    }
+
+    let mut block = format!("tags:{}", newline);
+    for tag in &merged {
+        block.push_str(&format!("{}- {}{}", indent, tag, newline));
+    }
+
+    let mut output = lines[..replace_range.start].concat();
+    output.push_str(&block);
+    output.push_str(&lines[replace_range.end..].concat());
+    Some(output)
+}
+
+/// Outcome of backfilling tags for one skill
+#[derive(Debug, Clone)]
+pub struct TagBackfill {
+    /// Path of the SKILL.md file
+    pub path: std::path::PathBuf,
+    /// Skill name
+    pub skill: String,
+    /// Tags the skill had before
+    pub existing: Vec<String>,
+    /// Inferred tags added to the skill
+    pub added: Vec<ScoredTag>,
+    /// Whether the file was updated (`false` in dry-run mode)
+    pub written: bool,
+}
+
+/// Write inferred `tags` into the SKILL.md at `path`
+#[cfg(feature = "yaml")]
+pub(crate) fn write_tags(path: &Path, tags: &[String]) -> Result<(), super::SkillError> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| super::SkillError::Io(format!("Failed to read {:?}: {}", path, e)))?;
+    let updated = add_tags_to_frontmatter(&source, tags).ok_or_else(|| {
+        super::SkillError::InvalidMetadata(format!("No YAML frontmatter in {:?}", path))
+    })?;
+    std::fs::write(path, updated)
+        .map_err(|e| super::SkillError::Io(format!("Failed to write {:?}: {}", path, e)))
+}
+
+/// Write inferred `tags` into the SKILL.md at `path`, which needs the `yaml` feature
+#[cfg(not(feature = "yaml"))]
+pub(crate) fn write_tags(_path: &Path, _tags: &[String]) -> Result<(), super::SkillError> {
+    Err(super::SkillError::Configuration(
+        "YAML support not enabled".to_string(),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -551,4 +1023,175 @@ mod tests {
         assert_eq!(TagUtils::tag_similarity(&tags1, &tags4), 0.0);
         assert_eq!(TagUtils::tag_similarity(&tags4, &tags4), 1.0);
     }
+
+    fn write_skill(root: &Path, name: &str, skill_md: &str, scripts: &[&str]) -> SkillMdFile {
+        let dir = root.join(name);
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        for script in scripts {
+            std::fs::write(dir.join("scripts").join(script), "").unwrap();
+        }
+        std::fs::write(dir.join("SKILL.md"), skill_md).unwrap();
+        SkillMdFile::parse(dir.join("SKILL.md")).unwrap()
+    }
+
+    const PDF_SKILL: &str = "---
+name: pdf-extractor
+description: Extract text and tables from PDF documents
+allowed_tools:
+  - Bash(python:*)
+  - Read
+tags: []
+---
+
+# PDF extraction
+
+Extract text from a PDF with the bundled script. Tables in the PDF are
+converted to CSV. For scanned PDF pages, run OCR first.
+";
+
+    #[test]
+    fn test_infer_heuristic_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = write_skill(dir.path(), "pdf-extractor", PDF_SKILL, &["extract.py"]);
+
+        let tags = infer(&skill, &InferConfig::default());
+        let names: Vec<&str> = tags.iter().map(|t| t.tag.as_str()).collect();
+
+        assert!(names.contains(&"pdf"));
+        assert!(names.contains(&"python"));
+        assert!(names.contains(&"shell"));
+        assert!(!names.contains(&"the"));
+        assert!(tags.len() <= 5);
+
+        let python = tags.iter().find(|t| t.tag == "python").unwrap();
+        assert!(matches!(python.source, TagSource::Tool | TagSource::FileExtension));
+    }
+
+    #[test]
+    fn test_infer_skips_existing_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = PDF_SKILL.replace("tags: []", "tags: [pdf]");
+        let skill = write_skill(dir.path(), "pdf-extractor", &source, &[]);
+
+        let tags = infer(&skill, &InferConfig::default());
+        assert!(tags.iter().all(|t| t.tag != "pdf"));
+    }
+
+    #[test]
+    fn test_corpus_down_weights_shared_keywords() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = write_skill(dir.path(), "pdf-extractor", PDF_SKILL, &[]);
+        let csv = write_skill(
+            dir.path(),
+            "csv-cleaner",
+            "---\nname: csv-cleaner\ndescription: Clean CSV exports\n---\nDeduplicate CSV rows and extract headers.\n",
+            &[],
+        );
+
+        let config = InferConfig {
+            corpus: Some(KeywordCorpus::from_skills(&[pdf.clone(), csv])),
+            ..Default::default()
+        };
+        let tags = infer(&pdf, &config);
+        let score = |name: &str| tags.iter().find(|t| t.tag == name).map(|t| t.score);
+
+        assert!(score("pdf").unwrap() > score("extract").unwrap_or(0.0));
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_add_tags_preserves_rest_of_file() {
+        let source = "---
+name: pdf-extractor   # keep this comment
+description: >
+  Extract text
+tags:
+    - documents
+allowed_tools: [Read]
+---
+
+Body with --- inside
+";
+        let updated =
+            add_tags_to_frontmatter(source, &["pdf".to_string(), "documents".to_string()]).unwrap();
+
+        assert_eq!(
+            updated,
+            "---
+name: pdf-extractor   # keep this comment
+description: >
+  Extract text
+tags:
+    - documents
+    - pdf
+allowed_tools: [Read]
+---
+
+Body with --- inside
+"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_add_tags_inserts_missing_key_and_replaces_flow_list() {
+        let missing = "---\nname: a\ndescription: b\n---\nbody\n";
+        assert_eq!(
+            add_tags_to_frontmatter(missing, &["x".to_string()]).unwrap(),
+            "---\nname: a\ndescription: b\ntags:\n  - x\n---\nbody\n"
+        );
+
+        let flow = "---\r\nname: a\r\ntags: [old]\r\ndescription: b\r\n---\r\n";
+        assert_eq!(
+            add_tags_to_frontmatter(flow, &["new".to_string()]).unwrap(),
+            "---\r\nname: a\r\ntags:\r\n  - old\r\n  - new\r\ndescription: b\r\n---\r\n"
+        );
+
+        assert!(add_tags_to_frontmatter("no frontmatter", &[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_backfill_tags_dry_run_and_write() {
+        let dir = tempfile::tempdir().unwrap();
+        write_skill(dir.path(), "pdf-extractor", PDF_SKILL, &["extract.py"]);
+        let path = dir.path().join("pdf-extractor").join("SKILL.md");
+        let config = InferConfig::default();
+
+        let report = super::super::SkillRegistry::backfill_tags(dir.path(), &config, true)
+            .await
+            .unwrap();
+        assert_eq!(report.len(), 1);
+        assert!(!report[0].written);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), PDF_SKILL);
+
+        let report = super::super::SkillRegistry::backfill_tags(dir.path(), &config, false)
+            .await
+            .unwrap();
+        assert!(report[0].written);
+        let skill = SkillMdFile::parse(&path).unwrap();
+        let added: Vec<String> = report[0].added.iter().map(|t| t.tag.clone()).collect();
+        assert_eq!(skill.metadata.tags, added);
+        assert!(std::fs::read_to_string(&path).unwrap().ends_with("run OCR first.\n"));
+
+        // Tagged skills are left alone on the next run
+        let report = super::super::SkillRegistry::backfill_tags(dir.path(), &config, false)
+            .await
+            .unwrap();
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_tag_filter_case_and_glob() {
+        let tags = vec!["Git".to_string(), "devops-aws".to_string()];
+
+        assert!(TagFilter::new().has("git").matches_tags(&tags));
+        assert!(!TagFilter::new().has("git").case_sensitive(true).matches_tags(&tags));
+        assert!(TagFilter::new().has("Git").case_sensitive(true).matches_tags(&tags));
+
+        assert!(TagFilter::new().has("devops-*").matches_tags(&tags));
+        assert!(TagFilter::new().has("DEVOPS-*").matches_tags(&tags));
+        assert!(!TagFilter::new().has("devops-").matches_tags(&tags));
+        assert!(TagFilter::new().none_of(["ml-*"]).matches_tags(&tags));
+        assert!(!TagFilter::new().all_of(["git", "ops-*"]).matches_tags(&tags));
+    }
 }
@@ -33,9 +33,69 @@ impl Skill for TestSkill {
     }
 }
 
+/// Skill answering with its input, counting its runs, failing on `{"fail": true}`
+struct EchoSkill {
+    package: SkillPackage,
+    runs: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl EchoSkill {
+    fn new(name: &str, cacheable: bool) -> Self {
+        let package = SkillPackage {
+            metadata: SkillMetadata {
+                id: name.to_string(),
+                name: name.to_string(),
+                description: "Echoes its input".to_string(),
+                version: "1.0.0".to_string(),
+                author: None,
+                dependencies: vec![],
+                tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable,
+            },
+            instructions: String::new(),
+            scripts: vec![],
+            resources: SkillResources::default(),
+        };
+        Self {
+            package,
+            runs: Default::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Skill for EchoSkill {
+    fn name(&self) -> String {
+        self.package.metadata.name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.package.metadata.description.clone()
+    }
+
+    async fn execute(&self, input: SkillInput) -> SkillResult {
+        self.runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        if input.params["fail"] == true {
+            return Err(SkillError::Execution("asked to fail".into()));
+        }
+        Ok(SkillOutput::ok(input.params))
+    }
+
+    fn validate(&self) -> std::result::Result<(), SkillError> {
+        Ok(())
+    }
+
+    fn skill_package(&self) -> Option<&SkillPackage> {
+        Some(&self.package)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::Ordering;
 
     #[test]
     fn test_skill_registry_register() {
@@ -68,6 +128,204 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[tokio::test]
+    async fn test_skill_registry_execute_records_stats() {
+        let mut registry = SkillRegistry::new();
+        registry.register(Box::new(EchoSkill::new("echo", false))).unwrap();
+
+        let input = |params| SkillInput { params };
+        let output = registry
+            .execute("echo", input(serde_json::json!({"n": 1})))
+            .await
+            .unwrap();
+        assert_eq!(output.data, serde_json::json!({"n": 1}));
+        let err = registry
+            .execute("echo", input(serde_json::json!({"fail": true})))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SkillError::Execution(_)));
+        let err = registry
+            .execute("missing", SkillInput::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SkillError::NotFound(_)));
+
+        let stats = registry.stats_for("echo").unwrap();
+        assert_eq!(stats.operations, 2);
+        assert_eq!((stats.successes, stats.failures), (1, 1));
+        assert_eq!(
+            stats.last_error.as_deref(),
+            Some("Skill execution failed: asked to fail")
+        );
+        assert!(stats.p50_latency.is_some());
+        assert_eq!(stats.cache_hits + stats.cache_misses, 0);
+        assert!(registry.stats_for("missing").is_none());
+        assert_eq!(registry.stats().operations, 2);
+
+        registry.reset_stats();
+        assert!(registry.stats_for("echo").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_skill_registry_caches_cacheable_outputs() {
+        let mut registry = SkillRegistry::new();
+        let cached = EchoSkill::new("cached", true);
+        let cached_runs = std::sync::Arc::clone(&cached.runs);
+        let uncached = EchoSkill::new("uncached", false);
+        let uncached_runs = std::sync::Arc::clone(&uncached.runs);
+        registry.register(Box::new(cached)).unwrap();
+        registry.register(Box::new(uncached)).unwrap();
+
+        for name in ["cached", "uncached"] {
+            for n in [1, 1, 2, 1] {
+                let input = SkillInput {
+                    params: serde_json::json!({"n": n}),
+                };
+                registry.execute(name, input).await.unwrap();
+            }
+        }
+        assert_eq!(cached_runs.load(Ordering::SeqCst), 2);
+        assert_eq!(uncached_runs.load(Ordering::SeqCst), 4);
+
+        let stats = registry.stats_for("cached").unwrap();
+        assert_eq!(stats.operations, 4);
+        assert_eq!((stats.cache_hits, stats.cache_misses), (2, 2));
+
+        // Failures are not cached
+        let failing = SkillInput {
+            params: serde_json::json!({"fail": true}),
+        };
+        for _ in 0..2 {
+            registry.execute("cached", failing.clone()).await.unwrap_err();
+        }
+        assert_eq!(cached_runs.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_skill_registry_cache_expires_and_is_bounded() {
+        let mut registry = SkillRegistry::new();
+        let skill = EchoSkill::new("cached", true);
+        let runs = std::sync::Arc::clone(&skill.runs);
+        registry.register(Box::new(skill)).unwrap();
+        registry.set_cache_config(SkillCacheConfig {
+            ttl: std::time::Duration::from_millis(50),
+            max_entries: 1,
+        });
+
+        let input = |n| SkillInput {
+            params: serde_json::json!({"n": n}),
+        };
+        registry.execute("cached", input(1)).await.unwrap();
+        registry.execute("cached", input(1)).await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // Only one output fits
+        registry.execute("cached", input(2)).await.unwrap();
+        registry.execute("cached", input(1)).await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+        registry.execute("cached", input(1)).await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+
+        // Registering the skill again drops its outputs
+        let skill = EchoSkill::new("cached", true);
+        let runs = std::sync::Arc::clone(&skill.runs);
+        registry.register(Box::new(skill)).unwrap();
+        registry.execute("cached", input(1)).await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_skill_registry_search_follows_registrations() {
+        let mut registry = SkillRegistry::new();
+        let mut pdf = EchoSkill::new("pdf-to-text", false);
+        pdf.package.metadata.description = "Convert PDF documents to plain text".to_string();
+        let mut ocr = EchoSkill::new("ocr", false);
+        ocr.package.metadata.tags = vec!["pdf".to_string()];
+        registry.register(Box::new(pdf)).unwrap();
+        registry.register(Box::new(ocr)).unwrap();
+        // Skills without a package are not searched
+        registry
+            .register(Box::new(TestSkill {
+                name: "pdf".to_string(),
+                description: "PDF".to_string(),
+            }))
+            .unwrap();
+
+        let names = |registry: &SkillRegistry, query| -> Vec<String> {
+            registry
+                .search(query)
+                .into_iter()
+                .map(|(_, skill)| skill.name.clone())
+                .collect()
+        };
+        assert_eq!(names(&registry, "convert pdf to text"), vec!["pdf-to-text", "ocr"]);
+
+        // Replacing a skill re-indexes it
+        let mut ocr = EchoSkill::new("ocr", false);
+        ocr.package.metadata.description = "Recognize text in scans".to_string();
+        registry.register(Box::new(ocr)).unwrap();
+        assert_eq!(names(&registry, "pdf"), vec!["pdf-to-text"]);
+        assert_eq!(names(&registry, "scan"), vec!["ocr"]);
+
+        registry.unregister("pdf-to-text");
+        assert!(names(&registry, "pdf").is_empty());
+        assert_eq!(names(&registry, "text"), vec!["ocr"]);
+    }
+
+    /// Register `count` synthetic skills, search them and unregister half
+    fn search_workload(count: usize) -> std::time::Duration {
+        let started = std::time::Instant::now();
+        let mut registry = SkillRegistry::new();
+        for i in 0..count {
+            let mut skill = EchoSkill::new(&format!("skill-{}", i), false);
+            let metadata = &mut skill.package.metadata;
+            metadata.description = format!("Convert format{} files to format{}", i % 37, i % 11);
+            metadata.tags = vec![format!("group{}", i % 13)];
+            skill.package.instructions = format!("Step {} of the tool{} pipeline", i, i % 7);
+            registry.register(Box::new(skill)).unwrap();
+        }
+        for i in 0..50 {
+            let hits = registry.search(&format!("convert format{} group{} tool", i % 37, i % 13));
+            assert!(!hits.is_empty());
+        }
+        for i in (0..count).step_by(2) {
+            registry.unregister(&format!("skill-{}", i));
+        }
+        assert_eq!(registry.search("convert").len(), count / 2);
+        started.elapsed()
+    }
+
+    #[test]
+    fn test_skill_registry_search_scales_linearly() {
+        let small = search_workload(100);
+        let large = search_workload(400);
+
+        // Four times the skills; quadratic indexing or search would take 16 times as long
+        assert!(
+            large < small * 10 + std::time::Duration::from_millis(100),
+            "100 skills: {:?}, 400 skills: {:?}",
+            small,
+            large
+        );
+
+        // Ties are broken by name
+        let registry = {
+            let mut registry = SkillRegistry::new();
+            for name in ["b-linter", "c-linter", "a-linter"] {
+                registry.register(Box::new(EchoSkill::new(name, false))).unwrap();
+            }
+            registry
+        };
+        let names: Vec<&str> = registry
+            .search("linter")
+            .into_iter()
+            .map(|(_, skill)| skill.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a-linter", "b-linter", "c-linter"]);
+    }
+
     #[test]
     fn test_skill_output_ok() {
         let output = SkillOutput::ok("test data");
@@ -94,6 +352,9 @@ mod tests {
                 author: None,
                 dependencies: vec![],
                 tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "Test instructions".to_string(),
             scripts: vec![],
@@ -125,6 +386,9 @@ mod tests {
                 author: None,
                 dependencies: vec![],
                 tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "Test instructions 1".to_string(),
             scripts: vec![],
@@ -140,6 +404,9 @@ mod tests {
                 author: None,
                 dependencies: vec![],
                 tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "Test instructions 2".to_string(),
             scripts: vec![],
@@ -160,6 +427,268 @@ mod tests {
         fs::remove_dir(&temp_dir).unwrap();
     }
 
+    #[test]
+    fn test_load_dir_registers_skill_md_and_json_packages() {
+        use std::fs;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let skill_dir = temp_dir.path().join("reviewer");
+        fs::create_dir(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: reviewer\ndescription: Reviews code\nmodel: claude-sonnet-4-5\n\
+             allowed_tools:\n  - Read\n  - Grep\n---\n\nReview the file.\n",
+        )
+        .unwrap();
+
+        let package = SkillPackage {
+            metadata: SkillMetadata {
+                id: "formatter".to_string(),
+                name: "formatter".to_string(),
+                description: "Formats code".to_string(),
+                version: "1.0.0".to_string(),
+                author: None,
+                dependencies: vec![],
+                tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
+            },
+            instructions: "Format the file.".to_string(),
+            scripts: vec![],
+            resources: SkillResources::default(),
+        };
+        package
+            .save_to_file(temp_dir.path().join("formatter.json"))
+            .unwrap();
+
+        let mut registry = SkillRegistry::new();
+        let names = registry.load_dir(temp_dir.path()).unwrap();
+        assert_eq!(names, vec!["formatter", "reviewer"]);
+        assert!(registry.get("reviewer").is_some());
+        assert_eq!(
+            registry.get("formatter").unwrap().description(),
+            "Formats code"
+        );
+
+        let skill_md = SkillMdFile::parse(skill_dir.join("SKILL.md")).unwrap();
+        let reviewer = PackagedSkill::from_skill_md(&skill_md);
+        assert_eq!(reviewer.model(), Some("claude-sonnet-4-5"));
+        assert_eq!(
+            reviewer.allowed_tools(),
+            Some(&["Read".to_string(), "Grep".to_string()][..])
+        );
+    }
+
+    fn write_skill(dir: &std::path::Path, name: &str, version: &str, dependencies: &[&str]) {
+        let skill_dir = dir.join(name);
+        std::fs::create_dir(&skill_dir).unwrap();
+        let mut frontmatter = format!(
+            "---\nname: {}\ndescription: The {} skill\nversion: \"{}\"\n",
+            name, name, version
+        );
+        if !dependencies.is_empty() {
+            frontmatter.push_str("dependencies:\n");
+            for dep in dependencies {
+                frontmatter.push_str(&format!("  - \"{}\"\n", dep));
+            }
+        }
+        frontmatter.push_str("---\n\nDo the work.\n");
+        std::fs::write(skill_dir.join("SKILL.md"), frontmatter).unwrap();
+    }
+
+    #[test]
+    fn test_load_dir_resolved_registers_in_dependency_order() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_skill(temp_dir.path(), "app", "1.0.0", &["utils@^2", "logger"]);
+        write_skill(temp_dir.path(), "utils", "2.1.0", &["logger"]);
+        write_skill(temp_dir.path(), "logger", "1.0.0", &[]);
+
+        let mut registry = SkillRegistry::new();
+        let plan = registry.load_dir_resolved(temp_dir.path(), false).unwrap();
+        assert!(plan.is_resolved());
+        assert_eq!(plan.load_order, vec!["logger", "utils", "app"]);
+        assert!(registry.get("app").is_some());
+    }
+
+    #[test]
+    fn test_load_dir_resolved_skips_missing_dependencies() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_skill(temp_dir.path(), "app", "1.0.0", &["utils"]);
+        write_skill(temp_dir.path(), "utils", "1.0.0", &["logger@^2"]);
+        write_skill(temp_dir.path(), "logger", "1.5.0", &[]);
+        write_skill(temp_dir.path(), "other", "1.0.0", &[]);
+
+        let mut registry = SkillRegistry::new();
+        let plan = registry.load_dir_resolved(temp_dir.path(), false).unwrap();
+        assert_eq!(plan.load_order, vec!["logger", "other"]);
+        assert_eq!(plan.missing.len(), 1);
+        assert_eq!(plan.missing[0].skill_id, "utils");
+        assert_eq!(plan.missing[0].available_version.as_deref(), Some("1.5.0"));
+        assert!(registry.get("utils").is_none());
+        assert!(registry.get("app").is_none());
+
+        let mut registry = SkillRegistry::new();
+        let plan = registry.load_dir_resolved(temp_dir.path(), true).unwrap();
+        assert_eq!(plan.load_order, vec!["logger", "other", "utils", "app"]);
+        assert!(registry.get("app").is_some());
+    }
+
+    #[test]
+    fn test_resolve_dependencies_reports_cycles_and_bad_requirements() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_skill(temp_dir.path(), "a", "1.0.0", &["b"]);
+        write_skill(temp_dir.path(), "b", "1.0.0", &["a"]);
+        write_skill(temp_dir.path(), "c", "1.0.0", &["a@not-a-version"]);
+        write_skill(temp_dir.path(), "d", "1.0.0", &[]);
+
+        let plan = SkillRegistry::resolve_dependencies(temp_dir.path()).unwrap();
+        assert_eq!(plan.cycles, vec![vec!["a", "b", "a"]]);
+        assert_eq!(plan.load_order, vec!["d"]);
+        assert_eq!(plan.warnings.len(), 1);
+        assert!(plan.warnings[0].contains(&temp_dir.path().join("c").join("SKILL.md").display().to_string()));
+        assert!(plan.warnings[0].contains("not-a-version"));
+
+        let mut registry = SkillRegistry::new();
+        registry.load_dir_resolved(temp_dir.path(), true).unwrap();
+        assert_eq!(registry.list(), vec!["d"]);
+    }
+
+    #[test]
+    fn test_load_dir_skips_incompatible_skills() {
+        use std::fs;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        for (name, constraint) in [
+            ("future", "min_sdk_version: \"999.0\""),
+            ("current", "min_sdk_version: \"0.0.1\""),
+            ("new-cli", "min_cli_version: \"2.1\""),
+            ("malformed", "min_sdk_version: not-a-version"),
+        ] {
+            let skill_dir = temp_dir.path().join(name);
+            fs::create_dir(&skill_dir).unwrap();
+            fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("---\nname: {}\ndescription: Test\n{}\n---\n\nBody\n", name, constraint),
+            )
+            .unwrap();
+        }
+
+        let skill_md = SkillMdFile::parse(temp_dir.path().join("future").join("SKILL.md")).unwrap();
+        assert_eq!(
+            skill_md.to_skill_package().metadata.min_sdk_version.as_deref(),
+            Some("999.0")
+        );
+
+        let mut registry = SkillRegistry::new();
+        registry.set_cli_version("2.0.14");
+        let names = registry.load_dir(temp_dir.path()).unwrap();
+        assert_eq!(names, vec!["current", "malformed"]);
+
+        assert_eq!(registry.status("future"), Some(SkillStatus::Incompatible));
+        assert_eq!(registry.status("new-cli"), Some(SkillStatus::Incompatible));
+        assert_eq!(registry.status("current"), Some(SkillStatus::Ready));
+        let Some(CompatibilityResult::Incompatible { reason, .. }) = registry.compatibility("future")
+        else {
+            panic!("Expected incompatible result");
+        };
+        assert!(reason.contains("min_sdk_version"));
+        assert!(matches!(
+            registry.compatibility("current"),
+            Some(CompatibilityResult::Compatible { .. })
+        ));
+        assert_eq!(registry.compatibility("malformed"), None);
+
+        // Without a CLI version, min_cli_version is not enforced
+        let mut registry = SkillRegistry::new();
+        let names = registry.load_dir(temp_dir.path()).unwrap();
+        assert_eq!(names, vec!["current", "malformed", "new-cli"]);
+    }
+
+    #[test]
+    fn test_register_package_rejects_incompatible() {
+        let mut package = SkillPackage {
+            metadata: SkillMetadata {
+                id: "future".to_string(),
+                name: "future".to_string(),
+                description: "Needs a newer SDK".to_string(),
+                version: "1.0.0".to_string(),
+                author: None,
+                dependencies: vec![],
+                tags: vec![],
+                min_sdk_version: Some("999.0".to_string()),
+                min_cli_version: None,
+                cacheable: false,
+            },
+            instructions: "Do the work.".to_string(),
+            scripts: vec![],
+            resources: SkillResources::default(),
+        };
+
+        let mut registry = SkillRegistry::new();
+        let err = registry.register_package(package.clone()).unwrap_err();
+        assert!(matches!(err, SkillError::VersionConflict(_)));
+        assert!(registry.get("future").is_none());
+
+        package.metadata.min_sdk_version = None;
+        registry.register_package(package).unwrap();
+        assert_eq!(registry.status("future"), Some(SkillStatus::Ready));
+    }
+
+    #[test]
+    fn test_find_by_tags_sorted_by_name() {
+        let mut registry = SkillRegistry::new();
+        for (name, tags) in [
+            ("reviewer", vec!["Git", "review"]),
+            ("linter", vec!["git", "lint"]),
+            ("legacy", vec!["git", "lint", "deprecated"]),
+            ("deployer", vec!["devops-aws"]),
+        ] {
+            registry
+                .register_package(SkillPackage {
+                    metadata: SkillMetadata {
+                        id: name.to_string(),
+                        name: name.to_string(),
+                        description: format!("The {} skill", name),
+                        version: "1.0.0".to_string(),
+                        author: None,
+                        dependencies: vec![],
+                        tags: tags.into_iter().map(String::from).collect(),
+                        min_sdk_version: None,
+                        min_cli_version: None,
+                        cacheable: false,
+                    },
+                    instructions: "Do the work.".to_string(),
+                    scripts: vec![],
+                    resources: SkillResources::default(),
+                })
+                .unwrap();
+        }
+        registry
+            .register(Box::new(TestSkill {
+                name: "untagged".to_string(),
+                description: "No package".to_string(),
+            }))
+            .unwrap();
+
+        let names = |found: Vec<&SkillMetadata>| -> Vec<String> {
+            found.into_iter().map(|m| m.name.clone()).collect()
+        };
+
+        assert_eq!(
+            names(registry.find_query(|q| {
+                q.all_of(["git"]).any_of(["review", "lint"]).none_of(["deprecated"])
+            })),
+            vec!["linter", "reviewer"]
+        );
+        assert_eq!(
+            names(registry.find_query(|q| q.has("git").case_sensitive(true))),
+            vec!["legacy", "linter"]
+        );
+        assert_eq!(names(registry.find(&TagFilter::new().has("devops-*"))), vec!["deployer"]);
+        assert_eq!(registry.find(&TagFilter::new()).len(), 4);
+    }
+
     #[test]
     fn test_discover_from_nonexistent_dir() {
         let result = SkillRegistry::discover_from_dir("/nonexistent/path/that/does/not/exist");
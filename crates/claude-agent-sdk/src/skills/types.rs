@@ -18,6 +18,17 @@ pub struct SkillMetadata {
     pub dependencies: Vec<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Oldest SDK version the skill works with (e.g. `0.3`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_sdk_version: Option<String>,
+    /// Oldest Claude Code CLI version the skill works with (e.g. `2.0`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_cli_version: Option<String>,
+    /// Whether outputs may be reused for identical inputs
+    ///
+    /// See [`SkillRegistry::execute`](super::SkillRegistry::execute).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cacheable: bool,
 }
 
 /// Resources associated with a Skill
@@ -199,6 +210,8 @@ pub enum SkillStatus {
     Completed,
     Failed,
     Disabled,
+    /// Requires a newer SDK or CLI than the one running
+    Incompatible,
 }
 
 /// A complete Skill package
@@ -247,7 +260,12 @@ impl SkillPackage {
     pub fn load_from_yaml<P: AsRef<std::path::Path>>(path: P) -> io::Result<Self> {
         let content = fs::read_to_string(path)?;
         let package: SkillPackage = serde_yaml::from_str(&content)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            .map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    crate::errors::YamlParseError::from_serde(content.as_str(), &e),
+                )
+            })?;
         Ok(package)
     }
 }
@@ -266,6 +284,9 @@ mod tests {
             author: Some("Test Author".to_string()),
             dependencies: vec!["dep1".to_string(), "dep2".to_string()],
             tags: vec!["test".to_string(), "example".to_string()],
+            min_sdk_version: None,
+            min_cli_version: None,
+            cacheable: false,
         };
 
         assert_eq!(metadata.id, "test-skill");
@@ -328,6 +349,9 @@ mod tests {
                 author: Some("Test Author".to_string()),
                 dependencies: vec![],
                 tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "Test instructions".to_string(),
             scripts: vec![],
@@ -351,6 +375,9 @@ mod tests {
                 author: Some("Test Author".to_string()),
                 dependencies: vec!["dep1".to_string()],
                 tags: vec!["test".to_string(), "yaml".to_string()],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "Test instructions for YAML".to_string(),
             scripts: vec!["script1.sh".to_string()],
@@ -400,6 +427,9 @@ mod tests {
                 author: Some("YAML Test Author".to_string()),
                 dependencies: vec!["yaml-dep".to_string()],
                 tags: vec!["yaml-test".to_string()],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "YAML test instructions".to_string(),
             scripts: vec!["yaml_script.sh".to_string()],
@@ -456,6 +486,9 @@ mod tests {
                 author: None,
                 dependencies: vec![],
                 tags: vec![],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "Minimal instructions".to_string(),
             scripts: vec![],
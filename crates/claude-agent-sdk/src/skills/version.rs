@@ -1,5 +1,6 @@
 //! Semantic version management for Agent Skills
 
+use super::SkillMetadata;
 use semver::{Version, VersionReq};
 use std::collections::HashMap;
 use std::fmt;
@@ -200,12 +201,118 @@ impl VersionManager {
         Ok(())
     }
 
+    /// Check the SDK and CLI versions a skill requires
+    ///
+    /// Compares `min_sdk_version` with `sdk_version` and `min_cli_version`
+    /// with `cli_version`. A constraint is skipped when the skill does not
+    /// declare it, when `cli_version` is unknown, or when a version does not
+    /// parse (logged at debug level; SKILL.md parsing already warns about it).
+    ///
+    /// # Returns
+    /// The first failed constraint as [`CompatibilityResult::Incompatible`],
+    /// otherwise the last satisfied one, or `None` when nothing was checked
+    ///
+    /// # Examples
+    /// ```
+    /// use claude_agent_sdk::skills::{CompatibilityResult, SkillMetadata, VersionManager};
+    ///
+    /// let metadata = SkillMetadata {
+    ///     id: "skill.reviewer".to_string(),
+    ///     name: "reviewer".to_string(),
+    ///     description: "Reviews code".to_string(),
+    ///     version: "1.0.0".to_string(),
+    ///     author: None,
+    ///     dependencies: vec![],
+    ///     tags: vec![],
+    ///     min_sdk_version: Some("0.3".to_string()),
+    ///     min_cli_version: None,
+    ///     cacheable: false,
+    /// };
+    ///
+    /// let result = VersionManager::new().check_skill(&metadata, "0.2.5", None);
+    /// assert!(matches!(result, Some(CompatibilityResult::Incompatible { .. })));
+    /// ```
+    pub fn check_skill(
+        &self,
+        metadata: &SkillMetadata,
+        sdk_version: &str,
+        cli_version: Option<&str>,
+    ) -> Option<CompatibilityResult> {
+        let constraints = [
+            ("SDK", "min_sdk_version", &metadata.min_sdk_version, Some(sdk_version)),
+            ("CLI", "min_cli_version", &metadata.min_cli_version, cli_version),
+        ];
+
+        let mut checked = None;
+        for (component, field, minimum, running) in constraints {
+            let Some(minimum) = minimum else {
+                continue;
+            };
+            let Some(running) = running else {
+                tracing::debug!(
+                    "Skill {}: {} version unknown, not checking {}",
+                    metadata.name,
+                    component,
+                    field
+                );
+                continue;
+            };
+
+            let requirement = format!(">={}", normalize(minimum));
+            match self.check_requirement(normalize(running), &requirement) {
+                CompatibilityResult::Incompatible {
+                    version,
+                    requirement,
+                    ..
+                } => {
+                    let reason = format!(
+                        "requires {} >= {} ({}), running {}",
+                        component,
+                        normalize(minimum),
+                        field,
+                        version
+                    );
+                    return Some(CompatibilityResult::Incompatible {
+                        version,
+                        requirement,
+                        reason,
+                    });
+                },
+                CompatibilityResult::ParseError { input, error } => {
+                    tracing::debug!(
+                        "Skill {}: not checking {}, cannot parse '{}': {}",
+                        metadata.name,
+                        field,
+                        input,
+                        error
+                    );
+                },
+                compatible => checked = Some(compatible),
+            }
+        }
+
+        checked
+    }
+
     /// Get all available skill versions
     pub fn available_versions(&self) -> &HashMap<String, Version> {
         &self.available
     }
 }
 
+/// Requirement matching versions at or above a `min_*_version` field
+///
+/// Partial versions such as `0.3` and a leading `v` are accepted.
+pub(crate) fn min_version_requirement(minimum: &str) -> Result<VersionReq, semver::Error> {
+    VersionReq::parse(&format!(">={}", normalize(minimum)))
+}
+
+/// Trim whitespace and a leading `v` from a version string
+fn normalize(version: &str) -> &str {
+    let version = version.trim();
+    version.strip_prefix('v').unwrap_or(version)
+}
+
 impl Default for VersionManager {
     fn default() -> Self {
         Self::new()
@@ -447,4 +554,52 @@ mod tests {
             CompatibilityResult::Compatible { .. }
         ));
     }
+
+    fn metadata(min_sdk_version: Option<&str>, min_cli_version: Option<&str>) -> SkillMetadata {
+        SkillMetadata {
+            id: "skill.test".to_string(),
+            name: "test".to_string(),
+            description: "Test skill".to_string(),
+            version: "1.0.0".to_string(),
+            author: None,
+            dependencies: vec![],
+            tags: vec![],
+            min_sdk_version: min_sdk_version.map(String::from),
+            min_cli_version: min_cli_version.map(String::from),
+            cacheable: false,
+        }
+    }
+
+    #[test]
+    fn test_check_skill_constraints() {
+        let manager = VersionManager::new();
+
+        assert_eq!(manager.check_skill(&metadata(None, None), "0.3.0", Some("2.0.0")), None);
+        assert!(matches!(
+            manager.check_skill(&metadata(Some("0.3"), Some("v2.0")), "0.3.1", Some("2.0.14")),
+            Some(CompatibilityResult::Compatible { .. })
+        ));
+
+        let Some(CompatibilityResult::Incompatible { reason, .. }) =
+            manager.check_skill(&metadata(Some("0.3"), Some("2.1")), "0.3.1", Some("2.0.14"))
+        else {
+            panic!("Expected incompatible result");
+        };
+        assert_eq!(reason, "requires CLI >= 2.1 (min_cli_version), running 2.0.14");
+    }
+
+    #[test]
+    fn test_check_skill_skips_unknown_and_malformed() {
+        let manager = VersionManager::new();
+
+        // CLI version not detected
+        assert_eq!(manager.check_skill(&metadata(None, Some("2.0")), "0.3.0", None), None);
+        // Malformed minimum is not a failure
+        assert_eq!(
+            manager.check_skill(&metadata(Some("three"), None), "0.3.0", None),
+            None
+        );
+        assert!(min_version_requirement("three").is_err());
+        assert!(min_version_requirement("v0.3").is_ok());
+    }
 }
@@ -1,13 +1,15 @@
 //! # VS Code Skills Format Export
 //!
 //! This module provides functionality to export skills in VS Code's SKILL.md format,
-//! which includes YAML frontmatter with metadata and markdown content.
+//! which includes YAML frontmatter with metadata and markdown content, and to
+//! import that format back into [`SkillPackage`]s. Skill scripts can also be
+//! exported as VS Code tasks in `.vscode/tasks.json`.
 
 use crate::skills::error::SkillError;
-use crate::skills::types::SkillPackage;
+use crate::skills::types::{SkillMetadata, SkillPackage, SkillResources};
+use serde_json::Value;
 use std::fs;
-use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// VS Code Skill format configuration
 #[derive(Debug, Clone)]
@@ -23,6 +25,15 @@ pub struct VsCodeExportConfig {
 
     /// Custom footer to add to the markdown
     pub footer: Option<String>,
+
+    /// Also write `.vscode/tasks.json` with one task per skill script
+    pub emit_tasks: bool,
+
+    /// Shell used to run script tasks
+    pub task_shell: String,
+
+    /// Compute the changes without writing any files
+    pub dry_run: bool,
 }
 
 impl Default for VsCodeExportConfig {
@@ -32,6 +43,9 @@ impl Default for VsCodeExportConfig {
             include_resources: true,
             include_examples: true,
             footer: None,
+            emit_tasks: false,
+            task_shell: "bash".to_string(),
+            dry_run: false,
         }
     }
 }
@@ -65,6 +79,32 @@ impl VsCodeExportConfig {
         self.footer = Some(footer);
         self
     }
+
+    /// Emit `.vscode/tasks.json`, running scripts with the given shell
+    pub fn with_tasks(mut self, shell: impl Into<String>) -> Self {
+        self.emit_tasks = true;
+        self.task_shell = shell.into();
+        self
+    }
+
+    /// Set whether to only compute the changes
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+/// Outcome of an export
+#[derive(Debug, Clone, Default)]
+pub struct VsCodeExportReport {
+    /// Skill files written (or that would be written in a dry run)
+    pub skill_files: Vec<PathBuf>,
+
+    /// Path of `.vscode/tasks.json`, when tasks were emitted
+    pub tasks_file: Option<PathBuf>,
+
+    /// Line diff between the files on disk and the exported files
+    pub diff: String,
 }
 
 /// Utility functions for VS Code Skills format
@@ -163,14 +203,8 @@ impl VsCodeUtils {
     }
 }
 
-/// Export a skill package to VS Code SKILL.md format
-pub fn export_to_vscode<P: AsRef<Path>>(
-    skill: &SkillPackage,
-    output_path: P,
-    config: &VsCodeExportConfig,
-) -> Result<(), SkillError> {
-    let output_path = output_path.as_ref();
-
+/// Render a skill package in VS Code SKILL.md format
+pub fn render_vscode(skill: &SkillPackage, config: &VsCodeExportConfig) -> Result<String, SkillError> {
     // Normalize and validate name
     let normalized_name = VsCodeUtils::normalize_name(&skill.metadata.name);
     VsCodeUtils::validate_name(&normalized_name)?;
@@ -185,21 +219,31 @@ pub fn export_to_vscode<P: AsRef<Path>>(
     // YAML Frontmatter
     content.push_str("---\n");
     content.push_str(&format!("name: {}\n", normalized_name));
-    content.push_str(&format!("description: {}\n", description));
+    content.push_str(&format!("description: {}\n", yaml_scalar(&description)));
 
     // Add version if available
     if !skill.metadata.version.is_empty() {
-        content.push_str(&format!("version: {}\n", skill.metadata.version));
+        content.push_str(&format!("version: {}\n", yaml_scalar(&skill.metadata.version)));
     }
 
     // Add author if available
     if let Some(ref author) = skill.metadata.author {
-        content.push_str(&format!("author: {}\n", author));
+        content.push_str(&format!("author: {}\n", yaml_scalar(author)));
     }
 
     // Add tags if available
     if !skill.metadata.tags.is_empty() {
-        content.push_str(&format!("tags: [{}]\n", skill.metadata.tags.join(", ")));
+        let tags: Vec<String> = skill.metadata.tags.iter().map(|tag| yaml_scalar(tag)).collect();
+        content.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    }
+
+    for (field, value) in [
+        ("min_sdk_version", &skill.metadata.min_sdk_version),
+        ("min_cli_version", &skill.metadata.min_cli_version),
+    ] {
+        if let Some(value) = value {
+            content.push_str(&format!("{}: {}\n", field, yaml_scalar(value)));
+        }
     }
 
     content.push_str("---\n\n");
@@ -216,7 +260,9 @@ pub fn export_to_vscode<P: AsRef<Path>>(
         content.push_str("## Scripts\n\n");
         for (i, script) in skill.scripts.iter().enumerate() {
             content.push_str(&format!("### Script {}\n\n", i + 1));
-            content.push_str("```");
+            // The fence is longer than any backtick run in the script
+            let fence = "`".repeat(longest_backtick_run(script).max(2) + 1);
+            content.push_str(&fence);
             // Try to detect language from shebang or extension
             if script.contains("#!/bin/bash") || script.contains("#!/bin/sh") {
                 content.push_str("bash");
@@ -227,9 +273,11 @@ pub fn export_to_vscode<P: AsRef<Path>>(
             } else {
                 content.push_str("text");
             }
-            content.push_str("\n");
+            content.push('\n');
             content.push_str(script);
-            content.push_str("\n```\n\n");
+            content.push('\n');
+            content.push_str(&fence);
+            content.push_str("\n\n");
         }
     }
 
@@ -240,7 +288,7 @@ pub fn export_to_vscode<P: AsRef<Path>>(
         for dep in &skill.metadata.dependencies {
             content.push_str(&format!("- {}\n", dep));
         }
-        content.push_str("\n");
+        content.push('\n');
     }
 
     // Resources section
@@ -257,7 +305,7 @@ pub fn export_to_vscode<P: AsRef<Path>>(
                 for folder in &skill.resources.folders {
                     content.push_str(&format!("- `{}`\n", folder.display()));
                 }
-                content.push_str("\n");
+                content.push('\n');
             }
 
             if has_tools {
@@ -265,7 +313,7 @@ pub fn export_to_vscode<P: AsRef<Path>>(
                 for tool in &skill.resources.tools {
                     content.push_str(&format!("- {}\n", tool));
                 }
-                content.push_str("\n");
+                content.push('\n');
             }
 
             if has_tests {
@@ -273,7 +321,7 @@ pub fn export_to_vscode<P: AsRef<Path>>(
                 for test in &skill.resources.tests {
                     content.push_str(&format!("- {}\n", test));
                 }
-                content.push_str("\n");
+                content.push('\n');
             }
         }
     }
@@ -290,16 +338,28 @@ pub fn export_to_vscode<P: AsRef<Path>>(
     if let Some(ref footer) = config.footer {
         content.push_str("---\n\n");
         content.push_str(footer);
-        content.push_str("\n");
+        content.push('\n');
     }
 
-    // Write to file
-    let mut file = fs::File::create(output_path)
-        .map_err(|e| SkillError::Io(format!("Failed to create SKILL.md file: {}", e)))?;
+    Ok(content)
+}
 
-    file.write_all(content.as_bytes())
-        .map_err(|e| SkillError::Io(format!("Failed to write SKILL.md file: {}", e)))?;
+/// Export a skill package to VS Code SKILL.md format
+///
+/// With `emit_tasks`, the skill's scripts are merged into `.vscode/tasks.json`
+/// next to `output_path`. Nothing is written when `dry_run` is set.
+pub fn export_to_vscode<P: AsRef<Path>>(
+    skill: &SkillPackage,
+    output_path: P,
+    config: &VsCodeExportConfig,
+) -> Result<(), SkillError> {
+    let output_path = output_path.as_ref();
+    let workspace = output_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
 
+    export_files(&[(skill, output_path.to_path_buf())], workspace, config)?;
     Ok(())
 }
 
@@ -309,27 +369,459 @@ pub fn export_batch_to_vscode<P: AsRef<Path>>(
     output_dir: P,
     config: &VsCodeExportConfig,
 ) -> Result<Vec<String>, SkillError> {
+    let report = export_batch_to_vscode_with_report(skills, output_dir, config)?;
+
+    Ok(report
+        .skill_files
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Export multiple skills to a directory and report what changed
+///
+/// Tasks go to `output_dir/.vscode/tasks.json` when `emit_tasks` is set. Set
+/// `dry_run` to get the diff without touching the files.
+///
+/// # Example
+///
+/// ```no_run
+/// # use claude_agent_sdk::skills::vscode::{VsCodeExportConfig, export_batch_to_vscode_with_report};
+/// # fn example(skills: &[claude_agent_sdk::skills::SkillPackage]) -> Result<(), Box<dyn std::error::Error>> {
+/// let config = VsCodeExportConfig::new().with_tasks("bash").with_dry_run(true);
+/// let report = export_batch_to_vscode_with_report(skills, "skills", &config)?;
+/// print!("{}", report.diff);
+/// # Ok(())
+/// # }
+/// ```
+pub fn export_batch_to_vscode_with_report<P: AsRef<Path>>(
+    skills: &[SkillPackage],
+    output_dir: P,
+    config: &VsCodeExportConfig,
+) -> Result<VsCodeExportReport, SkillError> {
     let output_dir = output_dir.as_ref();
 
     // Create output directory if it doesn't exist
-    if !output_dir.exists() {
+    if !output_dir.exists() && !config.dry_run {
         fs::create_dir_all(output_dir)
             .map_err(|e| SkillError::Io(format!("Failed to create output directory: {}", e)))?;
     }
 
-    let mut exported = Vec::new();
+    let targets: Vec<(&SkillPackage, PathBuf)> = skills
+        .iter()
+        .map(|skill| {
+            let normalized_name = VsCodeUtils::normalize_name(&skill.metadata.name);
+            (skill, output_dir.join(format!("{}.md", normalized_name)))
+        })
+        .collect();
+
+    export_files(&targets, output_dir, config)
+}
+
+/// Render every target, merge tasks, and write the files that changed
+fn export_files(
+    targets: &[(&SkillPackage, PathBuf)],
+    workspace: &Path,
+    config: &VsCodeExportConfig,
+) -> Result<VsCodeExportReport, SkillError> {
+    let mut report = VsCodeExportReport::default();
+    let mut writes = Vec::new();
+
+    for (skill, path) in targets {
+        writes.push((path.clone(), render_vscode(skill, config)?));
+        report.skill_files.push(path.clone());
+    }
+
+    if config.emit_tasks {
+        let tasks_path = workspace.join(".vscode").join("tasks.json");
+        let existing = read_optional(&tasks_path)?;
+        let skills: Vec<&SkillPackage> = targets.iter().map(|(skill, _)| *skill).collect();
+        let tasks = merge_tasks(existing.as_deref(), &skills, &config.task_shell)?;
+        writes.push((tasks_path.clone(), tasks));
+        report.tasks_file = Some(tasks_path);
+    }
+
+    for (path, content) in &writes {
+        let existing = read_optional(path)?;
+        if existing.as_deref() == Some(content.as_str()) {
+            continue;
+        }
+        report
+            .diff
+            .push_str(&line_diff(path, existing.as_deref(), content));
+
+        if !config.dry_run {
+            if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).map_err(|e| {
+                    SkillError::Io(format!("Failed to create {}: {}", parent.display(), e))
+                })?;
+            }
+            fs::write(path, content)
+                .map_err(|e| SkillError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+        }
+    }
+
+    Ok(report)
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, SkillError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(SkillError::Io(format!(
+            "Failed to read {}: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+/// Build tasks.json content, keeping existing tasks whose labels are not ours
+fn merge_tasks(
+    existing: Option<&str>,
+    skills: &[&SkillPackage],
+    shell: &str,
+) -> Result<String, SkillError> {
+    let mut root = match existing {
+        Some(text) => {
+            // tasks.json is JSONC; whole-line comments are common in VS Code templates
+            let json: String = text
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("//"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            serde_json::from_str::<Value>(&json).map_err(|e| {
+                SkillError::Serialization(format!("Cannot merge existing tasks.json: {}", e))
+            })?
+        },
+        None => serde_json::json!({ "version": "2.0.0" }),
+    };
+    let object = root.as_object_mut().ok_or_else(|| {
+        SkillError::Serialization("Cannot merge existing tasks.json: not a JSON object".to_string())
+    })?;
+    object
+        .entry("version")
+        .or_insert_with(|| Value::from("2.0.0"));
+
+    let mut tasks = match object.remove("tasks") {
+        Some(Value::Array(tasks)) => tasks,
+        Some(_) => {
+            return Err(SkillError::Serialization(
+                "Cannot merge existing tasks.json: \"tasks\" is not an array".to_string(),
+            ));
+        },
+        None => Vec::new(),
+    };
 
     for skill in skills {
-        let normalized_name = VsCodeUtils::normalize_name(&skill.metadata.name);
-        let file_name = format!("{}.md", normalized_name);
-        let file_path = output_dir.join(&file_name);
+        for task in skill_tasks(skill, shell) {
+            let label = task.get("label").cloned();
+            match tasks.iter_mut().find(|existing| existing.get("label") == label.as_ref()) {
+                Some(existing) => *existing = task,
+                None => tasks.push(task),
+            }
+        }
+    }
+    object.insert("tasks".to_string(), Value::Array(tasks));
+
+    let mut content = serde_json::to_string_pretty(&root)
+        .map_err(|e| SkillError::Serialization(e.to_string()))?;
+    content.push('\n');
+    Ok(content)
+}
+
+/// One VS Code task per script; scripts that are files are passed to the shell by path
+fn skill_tasks(skill: &SkillPackage, shell: &str) -> Vec<Value> {
+    let name = VsCodeUtils::normalize_name(&skill.metadata.name);
+
+    skill
+        .scripts
+        .iter()
+        .enumerate()
+        .map(|(i, script)| {
+            let path = Path::new(script);
+            let (label, args) = if !script.contains('\n') && path.is_file() {
+                let stem = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_else(|| format!("script {}", i + 1));
+                (format!("{}: {}", name, stem), vec![script.clone()])
+            } else {
+                (
+                    format!("{}: script {}", name, i + 1),
+                    vec!["-c".to_string(), script.clone()],
+                )
+            };
+
+            serde_json::json!({
+                "label": label,
+                "type": "process",
+                "command": shell,
+                "args": args,
+                "detail": skill.metadata.description,
+                "problemMatcher": [],
+            })
+        })
+        .collect()
+}
+
+/// Line diff of a file, with `-`/`+` prefixes for removed and added lines
+fn line_diff(path: &Path, old: Option<&str>, new: &str) -> String {
+    let old_lines: Vec<&str> = old.map(|old| old.lines().collect()).unwrap_or_default();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; new_lines.len() + 1]; old_lines.len() + 1];
+    for i in (0..old_lines.len()).rev() {
+        for j in (0..new_lines.len()).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
 
-        export_to_vscode(skill, &file_path, config)?;
+    let mut diff = match old {
+        Some(_) => format!("--- {}\n+++ {}\n", path.display(), path.display()),
+        None => format!("--- /dev/null\n+++ {}\n", path.display()),
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < old_lines.len() || j < new_lines.len() {
+        if i < old_lines.len() && j < new_lines.len() && old_lines[i] == new_lines[j] {
+            diff.push_str(&format!(" {}\n", old_lines[i]));
+            i += 1;
+            j += 1;
+        } else if i < old_lines.len() && (j == new_lines.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("-{}\n", old_lines[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{}\n", new_lines[j]));
+            j += 1;
+        }
+    }
+    diff
+}
 
-        exported.push(file_path.to_string_lossy().to_string());
+/// Quote a YAML value unless it is safe as a plain scalar
+fn yaml_scalar(value: &str) -> String {
+    let plain = value.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && !value.ends_with(' ')
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '.' | '-' | '_' | '/'))
+        && !matches!(
+            value.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "null" | "y" | "n"
+        );
+
+    if plain {
+        value.to_string()
+    } else {
+        serde_json::to_string(value).unwrap_or_else(|_| format!("'{}'", value))
     }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Frontmatter written by [`render_vscode`]
+#[derive(serde::Deserialize)]
+struct ExportedFrontmatter {
+    name: String,
+    description: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    min_sdk_version: Option<String>,
+    #[serde(default)]
+    min_cli_version: Option<String>,
+}
+
+/// Import skills exported with [`export_to_vscode`] or [`export_batch_to_vscode`]
+///
+/// `path` is either a single exported file or a directory, in which case every
+/// `.md` file in it is imported in file name order. All fields written by the
+/// exporter are read back; the id is derived from the name as for SKILL.md
+/// skills, and the usage examples and footer are skipped.
+pub fn import_from_vscode<P: AsRef<Path>>(path: P) -> Result<Vec<SkillPackage>, SkillError> {
+    let path = path.as_ref();
+
+    let files = if path.is_dir() {
+        let mut files: Vec<PathBuf> = fs::read_dir(path)
+            .map_err(|e| SkillError::Io(format!("Failed to read {}: {}", path.display(), e)))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.is_file() && file.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    files
+        .iter()
+        .map(|file| {
+            let content = fs::read_to_string(file)
+                .map_err(|e| SkillError::Io(format!("Failed to read {}: {}", file.display(), e)))?;
+            parse_vscode(&content).map_err(|e| match e {
+                SkillError::InvalidMetadata(message) => {
+                    SkillError::InvalidMetadata(format!("{}: {}", file.display(), message))
+                },
+                other => other,
+            })
+        })
+        .collect()
+}
+
+/// Section headings written by [`render_vscode`] after the instructions
+const SECTION_HEADINGS: [&str; 4] = [
+    "## Scripts\n\n",
+    "## Dependencies\n\n",
+    "## Resources\n\n",
+    "## Usage Examples\n\n",
+];
+
+#[cfg(feature = "yaml")]
+fn parse_frontmatter(yaml: &str) -> Result<ExportedFrontmatter, SkillError> {
+    serde_yaml::from_str(yaml).map_err(|e| SkillError::InvalidMetadata(e.to_string()))
+}
 
-    Ok(exported)
+#[cfg(not(feature = "yaml"))]
+fn parse_frontmatter(_yaml: &str) -> Result<ExportedFrontmatter, SkillError> {
+    Err(SkillError::Configuration(
+        "YAML support not enabled".to_string(),
+    ))
+}
+
+fn parse_vscode(content: &str) -> Result<SkillPackage, SkillError> {
+    let invalid = |message: &str| SkillError::InvalidMetadata(message.to_string());
+
+    let rest = content
+        .strip_prefix("---\n")
+        .ok_or_else(|| invalid("missing frontmatter"))?;
+    let end = rest
+        .find("\n---\n")
+        .ok_or_else(|| invalid("unterminated frontmatter"))?;
+    let frontmatter = parse_frontmatter(&rest[..end])?;
+    let mut body = rest[end + "\n---\n".len()..]
+        .strip_prefix('\n')
+        .unwrap_or(&rest[end + "\n---\n".len()..]);
+
+    let mut package = SkillPackage {
+        metadata: SkillMetadata {
+            id: format!("skill.{}", frontmatter.name),
+            name: frontmatter.name,
+            description: frontmatter.description,
+            version: frontmatter.version,
+            author: frontmatter.author,
+            dependencies: Vec::new(),
+            tags: frontmatter.tags,
+            min_sdk_version: frontmatter.min_sdk_version,
+            min_cli_version: frontmatter.min_cli_version,
+            cacheable: false,
+        },
+        instructions: String::new(),
+        scripts: Vec::new(),
+        resources: SkillResources::default(),
+    };
+
+    if let Some(rest) = body.strip_prefix("# Instructions\n\n") {
+        // Instructions run until the first exporter heading that follows a
+        // blank line, or the footer rule when there are no other sections
+        let end = SECTION_HEADINGS
+            .iter()
+            .filter_map(|heading| rest.find(&format!("\n\n{}", heading)).map(|i| i + 2))
+            .min()
+            .or_else(|| rest.rfind("\n\n---\n\n").map(|i| i + 2))
+            .unwrap_or(rest.len());
+        let instructions = &rest[..end];
+        package.instructions = instructions
+            .strip_suffix("\n\n")
+            .unwrap_or(instructions)
+            .to_string();
+        body = &rest[end..];
+    }
+
+    if let Some(mut rest) = body.strip_prefix("## Scripts\n\n") {
+        while let Some(script) = rest.strip_prefix("### Script ") {
+            let (_, after_heading) = script
+                .split_once("\n\n")
+                .ok_or_else(|| invalid("malformed script heading"))?;
+            let fence_len = after_heading.chars().take_while(|c| *c == '`').count();
+            if fence_len < 3 {
+                return Err(invalid("script is not in a code block"));
+            }
+            let (_, code) = after_heading
+                .split_once('\n')
+                .ok_or_else(|| invalid("unterminated script code block"))?;
+            let closing = format!("\n{}\n", "`".repeat(fence_len));
+            let code_end = code
+                .find(&closing)
+                .ok_or_else(|| invalid("unterminated script code block"))?;
+            package.scripts.push(code[..code_end].to_string());
+            rest = &code[code_end + closing.len()..];
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+        }
+        body = rest;
+    }
+
+    if let Some(rest) = body.strip_prefix("## Dependencies\n\n") {
+        let rest = rest
+            .strip_prefix("This skill requires the following dependencies:\n\n")
+            .unwrap_or(rest);
+        let (items, rest) = take_list(rest);
+        package.metadata.dependencies = items;
+        body = rest;
+    }
+
+    if let Some(mut rest) = body.strip_prefix("## Resources\n\n") {
+        loop {
+            if let Some(list) = rest.strip_prefix("### Folders\n\n") {
+                let (items, next) = take_list(list);
+                package.resources.folders = items
+                    .iter()
+                    .map(|item| {
+                        PathBuf::from(
+                            item.strip_prefix('`')
+                                .and_then(|item| item.strip_suffix('`'))
+                                .unwrap_or(item),
+                        )
+                    })
+                    .collect();
+                rest = next;
+            } else if let Some(list) = rest.strip_prefix("### Tools\n\n") {
+                let (items, next) = take_list(list);
+                package.resources.tools = items;
+                rest = next;
+            } else if let Some(list) = rest.strip_prefix("### Tests\n\n") {
+                let (items, next) = take_list(list);
+                package.resources.tests = items;
+                rest = next;
+            } else {
+                break;
+            }
+        }
+    }
+
+    Ok(package)
+}
+
+/// Read `- item` lines up to the blank line that ends the list
+fn take_list(text: &str) -> (Vec<String>, &str) {
+    let mut items = Vec::new();
+    let mut rest = text;
+    while let Some(item) = rest.strip_prefix("- ") {
+        let (line, next) = item.split_once('\n').unwrap_or((item, ""));
+        items.push(line.to_string());
+        rest = next;
+    }
+    (items, rest.strip_prefix('\n').unwrap_or(rest))
 }
 
 #[cfg(test)]
@@ -348,6 +840,9 @@ mod tests {
                 author: Some("Test Author".to_string()),
                 dependencies: vec!["dep1".to_string(), "dep2".to_string()],
                 tags: vec!["rust".to_string(), "api".to_string()],
+                min_sdk_version: None,
+                min_cli_version: None,
+                cacheable: false,
             },
             instructions: "This is a test skill with instructions.".to_string(),
             scripts: vec!["#!/bin/bash\necho 'Hello'".to_string()],
@@ -440,6 +935,9 @@ mod tests {
         assert!(config.include_resources);
         assert!(config.include_examples);
         assert!(config.footer.is_none());
+        assert!(!config.emit_tasks);
+        assert_eq!(config.task_shell, "bash");
+        assert!(!config.dry_run);
     }
 
     #[test]
@@ -454,5 +952,186 @@ mod tests {
         assert!(!config.include_resources);
         assert!(!config.include_examples);
         assert_eq!(config.footer, Some("Custom footer".to_string()));
+
+        let config = VsCodeExportConfig::new().with_tasks("zsh").with_dry_run(true);
+        assert!(config.emit_tasks);
+        assert_eq!(config.task_shell, "zsh");
+        assert!(config.dry_run);
+    }
+
+    fn assert_round_trip(original: &SkillPackage, imported: &SkillPackage) {
+        assert_eq!(imported.metadata.id, format!("skill.{}", original.metadata.name));
+        assert_eq!(
+            SkillMetadata {
+                id: original.metadata.id.clone(),
+                ..imported.metadata.clone()
+            },
+            original.metadata
+        );
+        assert_eq!(imported.instructions, original.instructions);
+        assert_eq!(imported.scripts, original.scripts);
+        assert_eq!(imported.resources, original.resources);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = create_test_skill("test-skill", "A test skill");
+        let path = dir.path().join("test-skill.md");
+
+        export_to_vscode(&skill, &path, &VsCodeExportConfig::default()).unwrap();
+        let imported = import_from_vscode(&path).unwrap();
+
+        assert_eq!(imported.len(), 1);
+        assert_round_trip(&skill, &imported[0]);
+    }
+
+    #[test]
+    fn test_round_trip_preserves_awkward_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut skill = create_test_skill("report-writer", "Writes reports: fast, #1 choice");
+        skill.metadata.author = Some("O'Neil <o@example.com>".to_string());
+        skill.metadata.tags = vec!["c++".to_string(), "yes".to_string(), "a, b".to_string()];
+        skill.metadata.version = "1.0".to_string();
+        skill.metadata.min_sdk_version = Some("0.1".to_string());
+        skill.metadata.min_cli_version = Some("2.0".to_string());
+        skill.instructions =
+            "Intro\n\n## Scripts\n\nNot a real section\n\n---\n\nAfter a rule\n".to_string();
+        skill.scripts = vec![
+            "echo '```'\n```\ncat <<EOF\nEOF".to_string(),
+            "fn main() {}".to_string(),
+        ];
+        skill.resources.tests.push("smoke".to_string());
+
+        let config = VsCodeExportConfig::new().with_footer("Generated".to_string());
+        let report = export_batch_to_vscode_with_report(
+            std::slice::from_ref(&skill),
+            dir.path(),
+            &config,
+        )
+        .unwrap();
+        let imported = import_from_vscode(&report.skill_files[0]).unwrap();
+
+        // The "## Scripts" inside the instructions is followed by a real one, which wins
+        assert_ne!(imported[0].instructions, skill.instructions);
+
+        skill.instructions = "Intro\n\n---\n\nAfter a rule\n".to_string();
+        export_batch_to_vscode(std::slice::from_ref(&skill), dir.path(), &config).unwrap();
+        let imported = import_from_vscode(dir.path()).unwrap();
+        assert_round_trip(&skill, &imported[0]);
+    }
+
+    #[test]
+    fn test_import_directory_in_name_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let skills = vec![
+            create_test_skill("zeta", "Last"),
+            create_test_skill("alpha", "First"),
+        ];
+        export_batch_to_vscode(&skills, dir.path(), &VsCodeExportConfig::default()).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a skill").unwrap();
+
+        let imported = import_from_vscode(dir.path()).unwrap();
+        let names: Vec<_> = imported.iter().map(|skill| skill.metadata.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "zeta"]);
+        assert_round_trip(&skills[1], &imported[0]);
+    }
+
+    #[test]
+    fn test_import_rejects_missing_frontmatter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.md");
+        fs::write(&path, "# Just markdown\n").unwrap();
+
+        let err = import_from_vscode(&path).unwrap_err();
+        assert!(matches!(err, SkillError::InvalidMetadata(_)), "{err}");
+    }
+
+    #[test]
+    fn test_tasks_json_merges_with_user_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let tasks_path = dir.path().join(".vscode/tasks.json");
+        fs::create_dir_all(tasks_path.parent().unwrap()).unwrap();
+        fs::write(
+            &tasks_path,
+            r#"{
+    // See https://go.microsoft.com/fwlink/?LinkId=733558
+    "version": "2.0.0",
+    "tasks": [
+        { "label": "build", "type": "shell", "command": "cargo build" },
+        { "label": "test-skill: script 1", "type": "shell", "command": "stale" }
+    ]
+}"#,
+        )
+        .unwrap();
+
+        let skill = create_test_skill("test-skill", "A test skill");
+        let config = VsCodeExportConfig::new().with_tasks("zsh");
+        let report = export_batch_to_vscode_with_report(&[skill], dir.path(), &config).unwrap();
+        assert_eq!(report.tasks_file.as_deref(), Some(tasks_path.as_path()));
+
+        let tasks: Value = serde_json::from_str(&fs::read_to_string(&tasks_path).unwrap()).unwrap();
+        let tasks = tasks["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0]["command"], "cargo build");
+        assert_eq!(tasks[1]["label"], "test-skill: script 1");
+        assert_eq!(tasks[1]["command"], "zsh");
+        assert_eq!(tasks[1]["args"][0], "-c");
+        assert_eq!(tasks[1]["args"][1], "#!/bin/bash\necho 'Hello'");
+    }
+
+    #[test]
+    fn test_tasks_use_script_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("deploy.sh");
+        fs::write(&script, "echo deploy").unwrap();
+        let mut skill = create_test_skill("deployer", "Deploys things");
+        skill.scripts = vec![script.to_string_lossy().to_string()];
+
+        let tasks = skill_tasks(&skill, "bash");
+        assert_eq!(tasks[0]["label"], "deployer: deploy");
+        assert_eq!(tasks[0]["args"], serde_json::json!([script.to_string_lossy()]));
+    }
+
+    #[test]
+    fn test_invalid_tasks_json_is_not_clobbered() {
+        let dir = tempfile::tempdir().unwrap();
+        let tasks_path = dir.path().join(".vscode/tasks.json");
+        fs::create_dir_all(tasks_path.parent().unwrap()).unwrap();
+        fs::write(&tasks_path, "{ not json").unwrap();
+
+        let skill = create_test_skill("test-skill", "A test skill");
+        let config = VsCodeExportConfig::new().with_tasks("bash");
+        assert!(export_batch_to_vscode(&[skill], dir.path(), &config).is_err());
+        assert_eq!(fs::read_to_string(&tasks_path).unwrap(), "{ not json");
+    }
+
+    #[test]
+    fn test_dry_run_returns_diff_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = create_test_skill("test-skill", "A test skill");
+        let path = dir.path().join("test-skill.md");
+        export_to_vscode(&skill, &path, &VsCodeExportConfig::default()).unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        let mut changed = skill.clone();
+        changed.metadata.version = "2.0.0".to_string();
+        let config = VsCodeExportConfig::new().with_tasks("bash").with_dry_run(true);
+        let report = export_batch_to_vscode_with_report(&[changed], dir.path(), &config).unwrap();
+
+        assert!(report.diff.contains("-version: \"1.0.0\"\n+version: \"2.0.0\"\n"));
+        assert!(report.diff.contains(" name: test-skill\n"));
+        assert!(report.diff.contains("--- /dev/null\n"));
+        assert!(report.diff.contains("+      \"label\": \"test-skill: script 1\",\n"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+        assert!(!dir.path().join(".vscode").exists());
+
+        let unchanged = export_batch_to_vscode_with_report(
+            &[skill],
+            dir.path(),
+            &VsCodeExportConfig::new().with_dry_run(true),
+        )
+        .unwrap();
+        assert!(unchanged.diff.is_empty());
     }
 }
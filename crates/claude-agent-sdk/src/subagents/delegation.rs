@@ -0,0 +1,149 @@
+//! Choosing which subagent handles an input
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use super::types::{DelegationStrategy, Subagent};
+
+/// How well a subagent fits an input
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubagentMatch {
+    /// Fit of the subagent, higher is better; zero means no fit
+    pub score: f64,
+
+    /// Human-readable explanation of the score
+    pub reason: String,
+}
+
+/// Scores subagents against an input for [`DelegationStrategy::Auto`]
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::subagents::{Subagent, SubagentMatch, SubagentScorer};
+///
+/// /// Prefers subagents allowed to use more tools
+/// struct ToolCountScorer;
+///
+/// impl SubagentScorer for ToolCountScorer {
+///     fn score(&self, subagent: &Subagent, _input: &str) -> SubagentMatch {
+///         SubagentMatch {
+///             score: subagent.allowed_tools.len() as f64,
+///             reason: format!("{} tools", subagent.allowed_tools.len()),
+///         }
+///     }
+/// }
+/// ```
+pub trait SubagentScorer: Send + Sync {
+    /// Score `subagent` against `input`
+    fn score(&self, subagent: &Subagent, input: &str) -> SubagentMatch;
+}
+
+/// Default scorer: matches input keywords against a subagent's name and description
+///
+/// The score is the fraction of the input's keywords (words of three or more
+/// letters or digits, case-insensitive) that appear in the subagent's name
+/// or description.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeywordScorer;
+
+impl SubagentScorer for KeywordScorer {
+    fn score(&self, subagent: &Subagent, input: &str) -> SubagentMatch {
+        let keywords = keywords(input);
+        let known: BTreeSet<_> = keywords_of(&subagent.name)
+            .chain(keywords_of(&subagent.description))
+            .collect();
+        let matched: Vec<_> = keywords
+            .iter()
+            .filter(|word| known.contains(*word))
+            .map(String::as_str)
+            .collect();
+
+        if matched.is_empty() {
+            return SubagentMatch {
+                score: 0.0,
+                reason: "no matching keywords".to_string(),
+            };
+        }
+        SubagentMatch {
+            score: matched.len() as f64 / keywords.len() as f64,
+            reason: format!("matched keywords: {}", matched.join(", ")),
+        }
+    }
+}
+
+fn keywords_of(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+}
+
+fn keywords(text: &str) -> BTreeSet<String> {
+    keywords_of(text).collect()
+}
+
+/// Which subagent was chosen to handle an input, and why
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::subagents::{DelegationDecision, DelegationStrategy};
+///
+/// let decision = DelegationDecision {
+///     subagent_name: "reviewer".to_string(),
+///     strategy: DelegationStrategy::Auto,
+///     score: Some(0.5),
+///     reason: "matched keywords: review".to_string(),
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DelegationDecision {
+    /// Name of the chosen subagent
+    pub subagent_name: String,
+
+    /// Strategy that made the choice
+    pub strategy: DelegationStrategy,
+
+    /// Score of the chosen subagent (None when the strategy does not score)
+    pub score: Option<f64>,
+
+    /// Human-readable explanation of the choice
+    pub reason: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subagent(name: &str, description: &str) -> Subagent {
+        Subagent {
+            name: name.to_string(),
+            description: description.to_string(),
+            instructions: String::new(),
+            allowed_tools: vec![],
+            max_turns: None,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_keyword_scorer() {
+        let reviewer = subagent("code-reviewer", "Reviews code for bugs");
+
+        let found = KeywordScorer.score(&reviewer, "Find BUGS in this code!");
+        // "find", "bugs", "this", "code": two of four match
+        assert_eq!(found.score, 0.5);
+        assert_eq!(found.reason, "matched keywords: bugs, code");
+
+        let none = KeywordScorer.score(&reviewer, "write a poem");
+        assert_eq!(none.score, 0.0);
+        assert_eq!(none.reason, "no matching keywords");
+    }
+
+    #[test]
+    fn test_keyword_scorer_ignores_short_words() {
+        let agent = subagent("db", "Runs SQL on a DB");
+        assert_eq!(KeywordScorer.score(&agent, "db a on").score, 0.0);
+        assert_eq!(KeywordScorer.score(&agent, "sql").score, 1.0);
+    }
+}
@@ -3,8 +3,21 @@
 //! This module provides functionality for creating and managing subagents,
 //! which are specialized Claude instances with specific capabilities and instructions.
 
+mod delegation;
 mod types;
 
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use futures::{Stream, StreamExt};
+
+use crate::prompts::SystemPromptBuilder;
+use crate::retry::RetryPolicy;
+use crate::types::config::ClaudeAgentOptions;
+use crate::types::messages::Message;
+
+pub use delegation::{DelegationDecision, KeywordScorer, SubagentMatch, SubagentScorer};
 pub use types::{
     DelegationStrategy, Subagent, SubagentCall, SubagentConfig, SubagentError,
     SubagentOutput,
@@ -43,6 +56,14 @@ pub use types::{
 pub struct SubagentExecutor {
     subagents: std::collections::HashMap<String, Subagent>,
     strategy: DelegationStrategy,
+    output_format: Option<serde_json::Value>,
+    lenient_structured_output: bool,
+    scorer: Arc<dyn SubagentScorer>,
+    retry_policy: Option<RetryPolicy>,
+    base_options: Option<ClaudeAgentOptions>,
+    prompt_template: Option<SystemPromptBuilder>,
+    // Shared by concurrent `delegate` calls for round-robin rotation
+    next_round_robin: AtomicUsize,
 }
 
 impl SubagentExecutor {
@@ -62,9 +83,107 @@ impl SubagentExecutor {
         Self {
             subagents: std::collections::HashMap::new(),
             strategy,
+            output_format: None,
+            lenient_structured_output: false,
+            scorer: Arc::new(KeywordScorer),
+            retry_policy: None,
+            base_options: None,
+            prompt_template: None,
+            next_round_robin: AtomicUsize::new(0),
         }
     }
 
+    /// Use `scorer` to pick subagents under [`DelegationStrategy::Auto`]
+    ///
+    /// Defaults to [`KeywordScorer`].
+    pub fn with_scorer(mut self, scorer: impl SubagentScorer + 'static) -> Self {
+        self.scorer = Arc::new(scorer);
+        self
+    }
+
+    /// Request structured output from every subagent
+    ///
+    /// `output_format` uses the same shape as
+    /// [`ClaudeAgentOptions::output_format`](crate::types::config::ClaudeAgentOptions::output_format).
+    /// The result is available as [`SubagentOutput::structured`].
+    pub fn with_output_format(mut self, output_format: serde_json::Value) -> Self {
+        self.output_format = Some(output_format);
+        self
+    }
+
+    /// Fall back to the last fenced JSON block of a subagent's response when
+    /// its result carries no structured output
+    pub fn with_lenient_structured_output(mut self, lenient: bool) -> Self {
+        self.lenient_structured_output = lenient;
+        self
+    }
+
+    /// Retry failed subagent runs according to `policy`
+    ///
+    /// Applies to [`execute`](Self::execute) and [`delegate`](Self::delegate).
+    /// [`execute_stream`](Self::execute_stream) only retries starting the
+    /// subagent, since messages already streamed cannot be taken back.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Start every execution from a clone of `options`
+    ///
+    /// Hooks, permission mode, working directory, MCP servers and any other
+    /// settings are passed through to each subagent. The subagent's own
+    /// fields then take precedence:
+    ///
+    /// - `system_prompt` is always built from the subagent's description and instructions,
+    ///   see [`with_prompt_template`](Self::with_prompt_template)
+    /// - `allowed_tools` is replaced when the subagent lists any tools
+    /// - `model` and `max_turns` are replaced when the subagent sets them
+    /// - `output_format` is replaced when set with [`with_output_format`](Self::with_output_format)
+    ///
+    /// Overrides passed to [`execute_with`](Self::execute_with) are applied last.
+    /// Without base options, executions start from `ClaudeAgentOptions::default()`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::subagents::{SubagentExecutor, DelegationStrategy};
+    /// # use claude_agent_sdk::{ClaudeAgentOptions, PermissionMode};
+    /// let base = ClaudeAgentOptions::builder()
+    ///     .permission_mode(PermissionMode::AcceptEdits)
+    ///     .cwd("/path/to/project")
+    ///     .build();
+    /// let executor = SubagentExecutor::new(DelegationStrategy::Auto).with_base_options(base);
+    /// ```
+    pub fn with_base_options(mut self, options: ClaudeAgentOptions) -> Self {
+        self.base_options = Some(options);
+        self
+    }
+
+    /// Compose each subagent's system prompt with `template`
+    ///
+    /// The variables `name`, `description` and `instructions` are set from the
+    /// subagent. The default template is the description followed by an
+    /// `Instructions:` section. A template that fails to render fails the
+    /// execution with `SubagentError::InvalidInput`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::subagents::{SubagentExecutor, DelegationStrategy};
+    /// use claude_agent_sdk::prompts::SystemPromptBuilder;
+    ///
+    /// let template = SystemPromptBuilder::new()
+    ///     .role("You are {{name}}, {{description}}")
+    ///     .constraints("{{instructions}}")
+    ///     .preset("claude_code");
+    /// let executor =
+    ///     SubagentExecutor::new(DelegationStrategy::Auto).with_prompt_template(template);
+    /// ```
+    pub fn with_prompt_template(mut self, template: SystemPromptBuilder) -> Self {
+        self.prompt_template = Some(template);
+        self
+    }
+
     /// Register a subagent
     ///
     /// # Arguments
@@ -127,65 +246,66 @@ impl SubagentExecutor {
         &self,
         name: &str,
         input: &str,
+    ) -> Result<SubagentOutput, SubagentError> {
+        self.execute_with(name, input, |_| {}).await
+    }
+
+    /// Execute a subagent by name, adjusting its options for this call only
+    ///
+    /// `configure` runs after the base options and subagent fields have been
+    /// combined (see [`with_base_options`](Self::with_base_options)), so its
+    /// changes take precedence over both.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subagent is not found or execution fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::subagents::{SubagentExecutor, DelegationStrategy};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let executor = SubagentExecutor::new(DelegationStrategy::Auto);
+    /// # // ... register subagent ...
+    /// let output = executor
+    ///     .execute_with("my-agent", "Hello", |options| {
+    ///         options.max_turns = Some(1);
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_with(
+        &self,
+        name: &str,
+        input: &str,
+        configure: impl FnOnce(&mut ClaudeAgentOptions),
     ) -> Result<SubagentOutput, SubagentError> {
         let subagent = self
             .subagents
             .get(name)
             .ok_or_else(|| SubagentError::NotFound(name.to_string()))?;
 
-        // Build system prompt from description and instructions
-        let system_prompt = format!(
-            "{}\n\nInstructions:\n{}",
-            subagent.description, subagent.instructions
-        );
-
-        // Build ClaudeAgentOptions using match to handle conditional fields
-        let options = match (&subagent.model, subagent.max_turns) {
-            (Some(model), Some(max_turns)) => {
-                crate::types::config::ClaudeAgentOptions::builder()
-                    .system_prompt(crate::types::config::SystemPrompt::Text(
-                        system_prompt,
-                    ))
-                    .allowed_tools(subagent.allowed_tools.clone())
-                    .model(model.clone())
-                    .max_turns(max_turns)
-                    .build()
-            }
-            (Some(model), None) => {
-                crate::types::config::ClaudeAgentOptions::builder()
-                    .system_prompt(crate::types::config::SystemPrompt::Text(
-                        system_prompt,
-                    ))
-                    .allowed_tools(subagent.allowed_tools.clone())
-                    .model(model.clone())
-                    .build()
-            }
-            (None, Some(max_turns)) => {
-                crate::types::config::ClaudeAgentOptions::builder()
-                    .system_prompt(crate::types::config::SystemPrompt::Text(
-                        system_prompt,
-                    ))
-                    .allowed_tools(subagent.allowed_tools.clone())
-                    .max_turns(max_turns)
-                    .build()
-            }
-            (None, None) => {
-                crate::types::config::ClaudeAgentOptions::builder()
-                    .system_prompt(crate::types::config::SystemPrompt::Text(
-                        system_prompt,
-                    ))
-                    .allowed_tools(subagent.allowed_tools.clone())
-                    .build()
-            }
-        };
+        let mut options = self.build_options(subagent)?;
+        configure(&mut options);
 
         // Execute query
-        let messages = crate::query::query(input, Some(options))
-            .await
-            .map_err(|e| SubagentError::ExecutionFailed(format!("Query failed: {}", e)))?;
+        let messages = match &self.retry_policy {
+            Some(policy) => {
+                policy
+                    .retry(|| crate::query::query(input, Some(options.clone())))
+                    .await
+            },
+            None => crate::query::query(input, Some(options)).await,
+        }
+        .map_err(|e| SubagentError::ExecutionFailed(format!("Query failed: {}", e)))?;
+
+        let collected = crate::types::messages::CollectedResponse::from(messages);
+        let structured = collected.structured_output(self.lenient_structured_output);
 
         // Convert messages to JSON values for SubagentOutput
-        let json_messages = messages
+        let json_messages = collected
+            .messages
             .into_iter()
             .map(|msg| serde_json::to_value(msg).map_err(|e| {
                 SubagentError::ExecutionFailed(format!("Failed to serialize message: {}", e))
@@ -195,9 +315,206 @@ impl SubagentExecutor {
         Ok(SubagentOutput {
             subagent_name: name.to_string(),
             messages: json_messages,
+            structured,
         })
     }
 
+    /// Execute a subagent by name, streaming its messages as they arrive
+    ///
+    /// Unlike [`execute`](Self::execute), messages are not collected, so memory
+    /// use stays flat for long-running subagents. The stream ends after the
+    /// `Result` message.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the subagent to execute
+    /// * `input` - The input to provide to the subagent
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subagent is not found or cannot be started.
+    /// Errors while running are yielded by the stream as
+    /// `SubagentError::ExecutionFailed`, naming the subagent, and end it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::subagents::{SubagentExecutor, DelegationStrategy};
+    /// # use claude_agent_sdk::Message;
+    /// # use futures::StreamExt;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let executor = SubagentExecutor::new(DelegationStrategy::Auto);
+    /// # // ... register subagent ...
+    /// let mut stream = executor.execute_stream("my-agent", "Hello").await?;
+    /// while let Some(message) = stream.next().await {
+    ///     if let Message::Assistant(msg) = message? {
+    ///         println!("Progress: {:?}", msg.message.content);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_stream(
+        &self,
+        name: &str,
+        input: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Message, SubagentError>> + Send>>, SubagentError>
+    {
+        let subagent = self
+            .subagents
+            .get(name)
+            .ok_or_else(|| SubagentError::NotFound(name.to_string()))?;
+
+        let options = self.build_options(subagent)?;
+        let messages = match &self.retry_policy {
+            Some(policy) => {
+                policy
+                    .retry(|| crate::query::query_stream(input, Some(options.clone())))
+                    .await
+            },
+            None => crate::query::query_stream(input, Some(options)).await,
+        }
+        .map_err(|e| execution_failed(name, e))?;
+
+        Ok(subagent_stream(name.to_string(), messages))
+    }
+
+    /// Options for running `subagent`, shared by both execution paths
+    ///
+    /// Overlays the subagent's fields on the base options, following the
+    /// precedence documented on [`with_base_options`](Self::with_base_options).
+    fn build_options(&self, subagent: &Subagent) -> Result<ClaudeAgentOptions, SubagentError> {
+        // Build system prompt from description and instructions
+        let system_prompt = self
+            .prompt_template
+            .clone()
+            .unwrap_or_else(|| {
+                SystemPromptBuilder::new()
+                    .role("{{description}}")
+                    .section("Instructions", "{{instructions}}")
+            })
+            .var("name", &subagent.name)
+            .var("description", &subagent.description)
+            .var("instructions", &subagent.instructions)
+            .build()
+            .map_err(|e| {
+                SubagentError::InvalidInput(format!(
+                    "System prompt of subagent '{}': {}",
+                    subagent.name, e
+                ))
+            })?;
+
+        let mut options = self.base_options.clone().unwrap_or_default();
+        options.system_prompt = Some(system_prompt);
+        options.system_prompt_template = None;
+        if !subagent.allowed_tools.is_empty() {
+            options.allowed_tools = subagent.allowed_tools.clone();
+        }
+        if subagent.model.is_some() {
+            options.model = subagent.model.clone();
+        }
+        if subagent.max_turns.is_some() {
+            options.max_turns = subagent.max_turns;
+        }
+        if self.output_format.is_some() {
+            options.output_format = self.output_format.clone();
+        }
+        Ok(options)
+    }
+
+    /// Execute the subagent chosen for `input` by the delegation strategy
+    ///
+    /// See [`select`](Self::select) for how the subagent is chosen.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no subagent can be chosen or execution fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::subagents::{SubagentExecutor, DelegationStrategy};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let executor = SubagentExecutor::new(DelegationStrategy::Auto);
+    /// # // ... register subagents ...
+    /// let (decision, output) = executor.delegate("Review this code for bugs").await?;
+    /// println!("Delegated to {}: {}", decision.subagent_name, decision.reason);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delegate(
+        &self,
+        input: &str,
+    ) -> Result<(DelegationDecision, SubagentOutput), SubagentError> {
+        let decision = self.select(input)?;
+        let output = self.execute(&decision.subagent_name, input).await?;
+        Ok((decision, output))
+    }
+
+    /// Choose the subagent for `input` without executing it
+    ///
+    /// - `Auto` scores every subagent with the scorer and picks the highest,
+    ///   breaking ties by name.
+    /// - `RoundRobin` picks the next subagent in name order. The rotation is
+    ///   shared by all callers, including concurrent ones.
+    /// - `Manual` and `ToolCall` never choose; call [`execute`](Self::execute)
+    ///   with a subagent name instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SubagentError::InvalidInput` if the strategy does not choose,
+    /// no subagents are registered, or no subagent scores above zero
+    pub fn select(&self, input: &str) -> Result<DelegationDecision, SubagentError> {
+        if matches!(
+            self.strategy,
+            DelegationStrategy::Manual | DelegationStrategy::ToolCall
+        ) {
+            return Err(SubagentError::InvalidInput(format!(
+                "{:?} delegation does not choose subagents; call execute() with a subagent name",
+                self.strategy
+            )));
+        }
+
+        let mut subagents: Vec<_> = self.subagents.values().collect();
+        if subagents.is_empty() {
+            return Err(SubagentError::InvalidInput(
+                "No subagents registered".to_string(),
+            ));
+        }
+        subagents.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if self.strategy == DelegationStrategy::RoundRobin {
+            let turn = self.next_round_robin.fetch_add(1, Ordering::Relaxed);
+            return Ok(DelegationDecision {
+                subagent_name: subagents[turn % subagents.len()].name.clone(),
+                strategy: self.strategy,
+                score: None,
+                reason: format!("round robin turn {}", turn + 1),
+            });
+        }
+
+        // Strictly greater keeps the first in name order on ties
+        let mut best: Option<(&Subagent, SubagentMatch)> = None;
+        for subagent in subagents {
+            let found = self.scorer.score(subagent, input);
+            if best.as_ref().is_none_or(|(_, top)| found.score > top.score) {
+                best = Some((subagent, found));
+            }
+        }
+
+        match best {
+            Some((subagent, found)) if found.score > 0.0 => Ok(DelegationDecision {
+                subagent_name: subagent.name.clone(),
+                strategy: self.strategy,
+                score: Some(found.score),
+                reason: found.reason,
+            }),
+            _ => Err(SubagentError::InvalidInput(
+                "No subagent matches the input".to_string(),
+            )),
+        }
+    }
+
     /// Get all registered subagent names
     ///
     /// # Returns
@@ -249,6 +566,35 @@ impl SubagentExecutor {
     }
 }
 
+/// Error for a subagent whose query failed
+fn execution_failed(name: &str, error: crate::errors::ClaudeError) -> SubagentError {
+    SubagentError::ExecutionFailed(format!("Subagent '{}' failed: {}", name, error))
+}
+
+/// Adapt a query stream for a subagent: stop after the result or the first error
+fn subagent_stream(
+    name: String,
+    mut messages: impl Stream<Item = crate::errors::Result<Message>> + Send + Unpin + 'static,
+) -> Pin<Box<dyn Stream<Item = Result<Message, SubagentError>> + Send>> {
+    Box::pin(async_stream::stream! {
+        while let Some(message) = messages.next().await {
+            match message {
+                Ok(message) => {
+                    let is_result = matches!(message, Message::Result(_));
+                    yield Ok(message);
+                    if is_result {
+                        break;
+                    }
+                },
+                Err(e) => {
+                    yield Err(execution_failed(&name, e));
+                    break;
+                },
+            }
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -337,4 +683,325 @@ mod tests {
 
         assert!(matches!(result, Err(SubagentError::NotFound(_))));
     }
+
+    fn result_message() -> Message {
+        serde_json::from_value(serde_json::json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 1,
+            "duration_api_ms": 1,
+            "is_error": false,
+            "num_turns": 1,
+            "session_id": "s"
+        }))
+        .unwrap()
+    }
+
+    fn assistant_message() -> Message {
+        serde_json::from_value(serde_json::json!({
+            "type": "assistant",
+            "message": {"model": "m", "content": [{"type": "text", "text": "working"}]}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_options() {
+        let executor = SubagentExecutor::new(DelegationStrategy::Auto)
+            .with_output_format(serde_json::json!({"type": "json_schema"}));
+        let subagent = Subagent {
+            name: "reviewer".to_string(),
+            description: "Reviews code".to_string(),
+            instructions: "Look for bugs".to_string(),
+            allowed_tools: vec!["Read".to_string()],
+            max_turns: Some(3),
+            model: None,
+        };
+
+        let options = executor.build_options(&subagent).unwrap();
+        assert!(matches!(
+            options.system_prompt,
+            Some(crate::types::config::SystemPrompt::Text(ref prompt))
+                if prompt == "Reviews code\n\nInstructions:\nLook for bugs"
+        ));
+        assert_eq!(options.allowed_tools, vec!["Read".to_string()]);
+        assert_eq!(options.max_turns, Some(3));
+        assert_eq!(options.model, None);
+        assert!(options.output_format.is_some());
+    }
+
+    #[test]
+    fn test_build_options_overlays_base_options() {
+        use crate::types::config::PermissionMode;
+        use crate::types::mcp::{McpServerConfig, McpServers, McpStdioServerConfig};
+
+        let mut servers = std::collections::HashMap::new();
+        servers.insert(
+            "docs".to_string(),
+            McpServerConfig::Stdio(McpStdioServerConfig {
+                command: "docs-server".to_string(),
+                args: None,
+                env: None,
+            }),
+        );
+        let base = ClaudeAgentOptions::builder()
+            .permission_mode(PermissionMode::AcceptEdits)
+            .cwd("/work")
+            .mcp_servers(McpServers::Dict(servers))
+            .allowed_tools(vec!["Bash".to_string()])
+            .model("base-model")
+            .max_turns(10)
+            .output_format(serde_json::json!({"type": "base"}))
+            .system_prompt("base prompt")
+            .build();
+        let executor = SubagentExecutor::new(DelegationStrategy::Auto).with_base_options(base);
+
+        // Subagent fields win where set
+        let reviewer = Subagent {
+            name: "reviewer".to_string(),
+            description: "Reviews code".to_string(),
+            instructions: "Look for bugs".to_string(),
+            allowed_tools: vec!["Read".to_string()],
+            max_turns: Some(3),
+            model: Some("subagent-model".to_string()),
+        };
+        let options = executor.build_options(&reviewer).unwrap();
+        assert_eq!(options.permission_mode, Some(PermissionMode::AcceptEdits));
+        assert_eq!(options.cwd, Some(std::path::PathBuf::from("/work")));
+        assert!(matches!(&options.mcp_servers, McpServers::Dict(servers) if servers.contains_key("docs")));
+        assert!(matches!(
+            options.system_prompt,
+            Some(crate::types::config::SystemPrompt::Text(ref prompt))
+                if prompt == "Reviews code\n\nInstructions:\nLook for bugs"
+        ));
+        assert_eq!(options.allowed_tools, vec!["Read".to_string()]);
+        assert_eq!(options.model.as_deref(), Some("subagent-model"));
+        assert_eq!(options.max_turns, Some(3));
+        assert_eq!(options.output_format, Some(serde_json::json!({"type": "base"})));
+
+        // Unset subagent fields fall back to the base options
+        let options = executor
+            .build_options(&named_subagent("docs", "Writes documentation"))
+            .unwrap();
+        assert_eq!(options.allowed_tools, vec!["Bash".to_string()]);
+        assert_eq!(options.model.as_deref(), Some("base-model"));
+        assert_eq!(options.max_turns, Some(10));
+
+        // The executor's output format beats the base one
+        let executor = executor.with_output_format(serde_json::json!({"type": "executor"}));
+        let options = executor.build_options(&reviewer).unwrap();
+        assert_eq!(options.output_format, Some(serde_json::json!({"type": "executor"})));
+    }
+
+    #[test]
+    fn test_build_options_with_prompt_template() {
+        use crate::types::config::SystemPrompt;
+
+        let template = SystemPromptBuilder::new()
+            .role("You are {{name}}. {{description}}.")
+            .constraints("{{instructions}}")
+            .preset("claude_code");
+        let executor = SubagentExecutor::new(DelegationStrategy::Auto)
+            .with_base_options(ClaudeAgentOptions::builder().system_prompt("base prompt").build())
+            .with_prompt_template(template);
+        let options = executor
+            .build_options(&named_subagent("docs", "Writes documentation"))
+            .unwrap();
+        assert!(matches!(
+            options.system_prompt,
+            Some(SystemPrompt::Preset(ref preset))
+                if preset.append.as_deref() == Some("You are docs. Writes documentation.")
+        ));
+
+        let executor = SubagentExecutor::new(DelegationStrategy::Auto)
+            .with_prompt_template(SystemPromptBuilder::new().role("{{unknown}}"));
+        let result = executor.build_options(&named_subagent("docs", "Writes documentation"));
+        assert!(matches!(
+            result,
+            Err(SubagentError::InvalidInput(ref msg)) if msg.contains("unknown")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_not_found_skips_override() {
+        let executor = SubagentExecutor::new(DelegationStrategy::Auto);
+        let result = executor
+            .execute_with("nonexistent", "input", |_| panic!("override must not run"))
+            .await;
+        assert!(matches!(result, Err(SubagentError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_subagent_stream_ends_after_result() {
+        let messages = futures::stream::iter(vec![
+            Ok(assistant_message()),
+            Ok(result_message()),
+            Ok(assistant_message()),
+        ]);
+
+        let collected: Vec<_> = subagent_stream("reviewer".to_string(), messages)
+            .collect()
+            .await;
+        assert_eq!(collected.len(), 2);
+        assert!(matches!(collected[0], Ok(Message::Assistant(_))));
+        assert!(matches!(collected[1], Ok(Message::Result(_))));
+    }
+
+    #[tokio::test]
+    async fn test_subagent_stream_names_subagent_in_errors() {
+        let messages = futures::stream::iter(vec![
+            Ok(assistant_message()),
+            Err(crate::errors::ClaudeError::InvalidConfig("boom".to_string())),
+            Ok(result_message()),
+        ]);
+
+        let collected: Vec<_> = subagent_stream("reviewer".to_string(), messages)
+            .collect()
+            .await;
+        assert_eq!(collected.len(), 2);
+        match &collected[1] {
+            Err(SubagentError::ExecutionFailed(msg)) => {
+                assert!(msg.contains("reviewer"), "{msg}");
+                assert!(msg.contains("boom"), "{msg}");
+            },
+            other => panic!("expected ExecutionFailed, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_stream_not_found() {
+        let executor = SubagentExecutor::new(DelegationStrategy::Auto);
+        assert!(matches!(
+            executor.execute_stream("nonexistent", "input").await,
+            Err(SubagentError::NotFound(_))
+        ));
+    }
+
+    fn named_subagent(name: &str, description: &str) -> Subagent {
+        Subagent {
+            name: name.to_string(),
+            description: description.to_string(),
+            instructions: String::new(),
+            allowed_tools: vec![],
+            max_turns: None,
+            model: None,
+        }
+    }
+
+    fn executor_with_agents(strategy: DelegationStrategy) -> SubagentExecutor {
+        let mut executor = SubagentExecutor::new(strategy);
+        for (name, description) in [
+            ("tester", "Writes and runs tests"),
+            ("reviewer", "Reviews code for bugs"),
+            ("docs", "Writes documentation"),
+        ] {
+            executor.register(named_subagent(name, description)).unwrap();
+        }
+        executor
+    }
+
+    #[test]
+    fn test_auto_selects_best_match() {
+        let executor = executor_with_agents(DelegationStrategy::Auto);
+
+        let decision = executor.select("Please check this code for bugs").unwrap();
+        assert_eq!(decision.subagent_name, "reviewer");
+        assert_eq!(decision.strategy, DelegationStrategy::Auto);
+        assert!(decision.score.unwrap() > 0.0);
+        assert!(decision.reason.contains("bugs"), "{}", decision.reason);
+
+        // "writes" matches docs and tester equally; ties go to the first name
+        assert_eq!(executor.select("writes").unwrap().subagent_name, "docs");
+
+        assert!(matches!(
+            executor.select("bake a cake"),
+            Err(SubagentError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_auto_uses_custom_scorer() {
+        struct Fixed;
+        impl SubagentScorer for Fixed {
+            fn score(&self, subagent: &Subagent, _input: &str) -> SubagentMatch {
+                SubagentMatch {
+                    score: if subagent.name == "tester" { 0.9 } else { 0.1 },
+                    reason: "fixed".to_string(),
+                }
+            }
+        }
+
+        let executor = executor_with_agents(DelegationStrategy::Auto).with_scorer(Fixed);
+        let decision = executor.select("Reviews code for bugs").unwrap();
+        assert_eq!(decision.subagent_name, "tester");
+        assert_eq!(decision.score, Some(0.9));
+        assert_eq!(decision.reason, "fixed");
+    }
+
+    #[test]
+    fn test_round_robin_rotates_in_name_order() {
+        let executor = executor_with_agents(DelegationStrategy::RoundRobin);
+
+        let picks: Vec<_> = (0..4)
+            .map(|_| executor.select("anything").unwrap().subagent_name)
+            .collect();
+        assert_eq!(picks, vec!["docs", "reviewer", "tester", "docs"]);
+    }
+
+    #[test]
+    fn test_round_robin_is_shared_across_threads() {
+        let executor = executor_with_agents(DelegationStrategy::RoundRobin);
+
+        let picks: Vec<String> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        (0..30)
+                            .map(|_| executor.select("x").unwrap().subagent_name)
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        // Every turn is taken exactly once, so the load is split evenly
+        for name in ["docs", "reviewer", "tester"] {
+            assert_eq!(picks.iter().filter(|pick| *pick == name).count(), 40);
+        }
+    }
+
+    #[test]
+    fn test_manual_strategies_do_not_select() {
+        for strategy in [DelegationStrategy::Manual, DelegationStrategy::ToolCall] {
+            let executor = executor_with_agents(strategy);
+            match executor.select("Reviews code") {
+                Err(SubagentError::InvalidInput(msg)) => {
+                    assert!(msg.contains("execute()"), "{msg}")
+                },
+                other => panic!("expected InvalidInput, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_select_without_subagents() {
+        let executor = SubagentExecutor::new(DelegationStrategy::RoundRobin);
+        assert!(matches!(
+            executor.select("anything"),
+            Err(SubagentError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delegate_manual_fails_before_executing() {
+        let executor = executor_with_agents(DelegationStrategy::Manual);
+        assert!(matches!(
+            executor.delegate("Reviews code").await,
+            Err(SubagentError::InvalidInput(_))
+        ));
+    }
 }
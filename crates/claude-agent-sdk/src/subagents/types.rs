@@ -175,6 +175,7 @@ impl SubagentConfig {
 /// * `Auto` - Claude automatically decides when to delegate
 /// * `Manual` - Requires explicit SubagentTool calls
 /// * `ToolCall` - Delegate through tool calls
+/// * `RoundRobin` - Rotate through the registered subagents
 ///
 /// # Example
 ///
@@ -193,6 +194,9 @@ pub enum DelegationStrategy {
 
     /// Delegation happens through tool calls
     ToolCall,
+
+    /// Each delegation goes to the next subagent, in name order
+    RoundRobin,
 }
 
 /// Represents a single subagent execution call
@@ -270,6 +274,7 @@ impl SubagentCall {
 /// let output = SubagentOutput {
 ///     subagent_name: "reviewer".to_string(),
 ///     messages: vec![],
+///     structured: None,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -281,6 +286,17 @@ pub struct SubagentOutput {
     /// Note: This is a placeholder - in a full implementation,
     /// this would contain actual Message types from the SDK
     pub messages: Vec<serde_json::Value>,
+
+    /// Structured output, when the executor requested an output format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<serde_json::Value>,
+}
+
+impl SubagentOutput {
+    /// Deserialize the structured output into `T`
+    pub fn structured_as<T: serde::de::DeserializeOwned>(&self) -> crate::errors::Result<T> {
+        crate::types::messages::deserialize_structured(self.structured.clone())
+    }
 }
 
 /// Errors that can occur in subagent operations
@@ -453,6 +469,7 @@ mod tests {
         let output = SubagentOutput {
             subagent_name: "agent".to_string(),
             messages: vec![],
+            structured: None,
         };
 
         assert_eq!(output.subagent_name, "agent");
@@ -0,0 +1,732 @@
+//! Testing agent code without the Claude Code CLI
+//!
+//! [`MockTransport`] stands in for the CLI: it plays back a script of messages
+//! in answer to what the SDK writes, and records everything written so tests
+//! can assert on it. Pass it to
+//! [`ClaudeClient::with_transport`](crate::ClaudeClient::with_transport) or
+//! [`query_with_transport`](crate::query_with_transport).
+//! [`ReplayTransport`] plays back a session recorded with
+//! [`ClaudeAgentOptions::record_to`](crate::ClaudeAgentOptions::record_to) the
+//! same way.
+//!
+//! # Example
+//!
+//! ```
+//! use claude_agent_sdk::testing::MockTransport;
+//! use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient};
+//!
+//! # async fn example() -> claude_agent_sdk::Result<()> {
+//! let transport = MockTransport::builder()
+//!     .on_user_message("hello")
+//!     .respond_assistant_text("Hi there!")
+//!     .respond_result("Hi there!")
+//!     .build();
+//! let recorder = transport.recorder();
+//!
+//! let mut client = ClaudeClient::with_transport(Box::new(transport), ClaudeAgentOptions::default());
+//! client.connect().await?;
+//! let response = client.query_collect("hello").await?;
+//! assert_eq!(response.text(), "Hi there!");
+//! assert_eq!(recorder.user_messages(), vec!["hello"]);
+//! client.disconnect().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Scripts
+//!
+//! A script is a list of [`MockStep`]s. The steps before the first trigger
+//! ([`MockStep::OnUserMessage`] or [`MockStep::OnControlResponse`]) play when
+//! the transport connects; each trigger waits for a matching write before the
+//! steps after it play. Control requests from the SDK, such as `initialize`
+//! and `interrupt`, are answered with success without being scripted. Output
+//! ends at [`MockStep::Close`], or once input has ended and everything played
+//! has been read.
+//!
+//! Scripts load from JSON too, as the [`fixtures`] do:
+//!
+//! ```json
+//! [
+//!   {"on_user_message": "capital of France"},
+//!   {"respond": {"type": "assistant", "message": {"model": "mock", "content": []}}},
+//!   {"delay_ms": 100},
+//!   {"error": "connection reset"},
+//!   "close"
+//! ]
+//! ```
+
+use std::collections::VecDeque;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex, MutexGuard};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::{Mutex, Notify};
+
+use crate::errors::{ClaudeError, Result};
+use crate::internal::transport::{Transport, TransportWriter};
+
+mod replay;
+
+pub use replay::{ReplayReport, ReplaySpeed, ReplayTransport};
+
+/// Scripts for common exchanges, in the JSON form [`MockTransport::from_json`] reads
+///
+/// Each one answers the first user message, whatever it says.
+pub mod fixtures {
+    /// A plain text answer
+    pub const BASIC_TEXT: &str = include_str!("fixtures/basic_text.json");
+
+    /// A `Read` tool call, its result, and an answer based on it
+    pub const TOOL_USE: &str = include_str!("fixtures/tool_use.json");
+
+    /// A `Bash` tool call the CLI refused for lack of permission
+    pub const PERMISSION_DENIED: &str = include_str!("fixtures/permission_denied.json");
+}
+
+/// Session ID used by the messages the builder makes
+const MOCK_SESSION_ID: &str = "mock-session";
+
+/// Model name used by the messages the builder makes
+const MOCK_MODEL: &str = "mock";
+
+/// One step of a [`MockTransport`] script
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MockStep {
+    /// Wait for a user message containing this text; `""` matches any
+    ///
+    /// A user message that does not match fails the read side with a
+    /// [`ClaudeError::Transport`] error.
+    OnUserMessage(String),
+    /// Wait for a control response, i.e. the answer to a control request the
+    /// script sent
+    OnControlResponse,
+    /// Send a message, as the CLI would print it
+    Respond(Value),
+    /// Pause the output for this many milliseconds
+    DelayMs(u64),
+    /// Fail the read side with a [`ClaudeError::Transport`] error
+    Error(String),
+    /// End the output
+    Close,
+}
+
+impl MockStep {
+    fn is_trigger(&self) -> bool {
+        matches!(self, Self::OnUserMessage(_) | Self::OnControlResponse)
+    }
+}
+
+/// What the read side hands out next
+enum Output {
+    Message(Value),
+    Delay(Duration),
+    Error(String),
+    Close,
+}
+
+#[derive(Default)]
+struct State {
+    steps: VecDeque<MockStep>,
+    output: VecDeque<Output>,
+    written: Vec<Value>,
+    input_ended: bool,
+}
+
+impl State {
+    /// Queue the steps up to the next trigger
+    fn play(&mut self) {
+        while let Some(step) = self.steps.front() {
+            if step.is_trigger() {
+                break;
+            }
+            let output = match self.steps.pop_front() {
+                Some(MockStep::Respond(message)) => Output::Message(message),
+                Some(MockStep::DelayMs(ms)) => Output::Delay(Duration::from_millis(ms)),
+                Some(MockStep::Error(message)) => Output::Error(message),
+                _ => Output::Close,
+            };
+            self.output.push_back(output);
+        }
+    }
+
+    fn receive(&mut self, message: Value) {
+        self.written.push(message.clone());
+        match message.get("type").and_then(Value::as_str) {
+            Some("control_request") => self.output.push_back(Output::Message(json!({
+                "type": "control_response",
+                "response": {
+                    "subtype": "success",
+                    "request_id": message["request_id"],
+                    "response": {},
+                },
+            }))),
+            Some("user") => {
+                let text = user_text(&message);
+                match self.steps.front() {
+                    Some(MockStep::OnUserMessage(pattern)) if text.contains(pattern.as_str()) => {
+                        self.steps.pop_front();
+                        self.play();
+                    },
+                    _ => self.output.push_back(Output::Error(format!(
+                        "MockTransport: unexpected user message {:?}",
+                        text
+                    ))),
+                }
+            },
+            Some("control_response")
+                if self.steps.front() == Some(&MockStep::OnControlResponse) =>
+            {
+                self.steps.pop_front();
+                self.play();
+            },
+            _ => {},
+        }
+    }
+}
+
+/// The text of a stream-json user message, joining text blocks
+fn user_text(message: &Value) -> String {
+    match &message["message"]["content"] {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// State shared by a scripted transport, its writer and its handles
+#[derive(Default)]
+struct Shared<T = State> {
+    state: Arc<StdMutex<T>>,
+    changed: Arc<Notify>,
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            changed: Arc::clone(&self.changed),
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, T> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.lock());
+        self.changed.notify_one();
+    }
+}
+
+/// Where a [`ScriptWriter`] sends what is written to it
+trait LineSink: Send + Unpin {
+    /// Take one line, without its newline
+    fn receive_line(&self, line: &str);
+
+    /// Note that input has ended
+    fn end_input(&self);
+}
+
+impl LineSink for Shared {
+    fn receive_line(&self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        let message =
+            serde_json::from_str(line).unwrap_or_else(|_| Value::String(line.to_string()));
+        self.update(|state| state.receive(message));
+    }
+
+    fn end_input(&self) {
+        self.update(|state| state.input_ended = true);
+    }
+}
+
+/// The input side of a scripted transport, splitting what is written into lines
+struct ScriptWriter<S> {
+    sink: S,
+    line: Vec<u8>,
+}
+
+impl<S: LineSink> ScriptWriter<S> {
+    fn new(sink: S) -> Self {
+        Self {
+            sink,
+            line: Vec::new(),
+        }
+    }
+}
+
+impl<S: LineSink> AsyncWrite for ScriptWriter<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.line.extend_from_slice(buf);
+        while let Some(end) = self.line.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.line.drain(..=end).collect();
+            self.sink.receive_line(String::from_utf8_lossy(&line).trim_end());
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let rest = std::mem::take(&mut self.line);
+        if !rest.is_empty() {
+            self.sink.receive_line(String::from_utf8_lossy(&rest).trim_end());
+        }
+        self.sink.end_input();
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A [`Transport`] that plays back a script instead of running the CLI
+///
+/// Build one with [`builder`](Self::builder), or load a script with
+/// [`from_json`](Self::from_json) or [`from_fixture`](Self::from_fixture).
+/// See the [module docs](self) for how scripts play.
+pub struct MockTransport {
+    shared: Shared,
+    writer: TransportWriter,
+    ready: bool,
+}
+
+impl MockTransport {
+    /// Start building a script
+    pub fn builder() -> MockTransportBuilder {
+        MockTransportBuilder::default()
+    }
+
+    /// A transport playing `steps`
+    pub fn from_steps(steps: impl IntoIterator<Item = MockStep>) -> Self {
+        let shared = Shared::<State>::default();
+        shared.lock().steps = steps.into_iter().collect();
+        Self {
+            shared,
+            writer: Arc::new(Mutex::new(None)),
+            ready: false,
+        }
+    }
+
+    /// A transport playing a JSON script, such as one of the [`fixtures`]
+    pub fn from_json(script: &str) -> Result<Self> {
+        let steps: Vec<MockStep> = serde_json::from_str(script)
+            .map_err(|e| ClaudeError::InvalidConfig(format!("Invalid mock script: {}", e)))?;
+        Ok(Self::from_steps(steps))
+    }
+
+    /// A transport playing the JSON script in the file at `path`
+    pub fn from_fixture(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let script = std::fs::read_to_string(path)?;
+        let steps: Vec<MockStep> = serde_json::from_str(&script).map_err(|e| {
+            ClaudeError::InvalidConfig(format!("Invalid mock script {}: {}", path.display(), e))
+        })?;
+        Ok(Self::from_steps(steps))
+    }
+
+    /// A handle on what gets written to this transport
+    ///
+    /// Take it before handing the transport over.
+    pub fn recorder(&self) -> MockRecorder {
+        MockRecorder {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn connect(&mut self) -> Result<()> {
+        if self.ready {
+            return Ok(());
+        }
+        self.shared.update(State::play);
+        *self.writer.lock().await = Some(Box::new(ScriptWriter::new(self.shared.clone())));
+        self.ready = true;
+        Ok(())
+    }
+
+    async fn write(&mut self, data: &str) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        let writer = writer
+            .as_mut()
+            .ok_or_else(|| ClaudeError::Transport("MockTransport input has ended".to_string()))?;
+        writer.write_all(data.as_bytes()).await?;
+        if !data.ends_with('\n') {
+            writer.write_all(b"\n").await?;
+        }
+        Ok(())
+    }
+
+    fn read_messages(
+        &mut self,
+    ) -> Pin<Box<dyn Stream<Item = Result<serde_json::Value>> + Send + '_>> {
+        let shared = self.shared.clone();
+        Box::pin(async_stream::stream! {
+            loop {
+                let next = {
+                    let mut state = shared.lock();
+                    match state.output.pop_front() {
+                        Some(output) => Some(output),
+                        None if state.input_ended => Some(Output::Close),
+                        None => None,
+                    }
+                };
+                match next {
+                    Some(Output::Message(message)) => yield Ok(message),
+                    Some(Output::Delay(delay)) => tokio::time::sleep(delay).await,
+                    Some(Output::Error(message)) => yield Err(ClaudeError::Transport(message)),
+                    Some(Output::Close) => break,
+                    None => shared.changed.notified().await,
+                }
+            }
+        })
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.end_input().await?;
+        self.ready = false;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    async fn end_input(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.lock().await.take() {
+            writer.shutdown().await?;
+        }
+        Ok(())
+    }
+
+    fn writer(&self) -> TransportWriter {
+        Arc::clone(&self.writer)
+    }
+}
+
+/// Read access to what was written to a [`MockTransport`]
+#[derive(Clone)]
+pub struct MockRecorder {
+    shared: Shared,
+}
+
+impl MockRecorder {
+    /// Every message written, in order
+    ///
+    /// Lines that are not JSON are kept as strings.
+    pub fn written(&self) -> Vec<Value> {
+        self.shared.lock().written.clone()
+    }
+
+    /// The text of each user message written
+    pub fn user_messages(&self) -> Vec<String> {
+        self.written_of_type("user").iter().map(user_text).collect()
+    }
+
+    /// The `request` of each control request written, e.g. `initialize`
+    pub fn control_requests(&self) -> Vec<Value> {
+        self.written_of_type("control_request")
+            .into_iter()
+            .map(|mut message| message["request"].take())
+            .collect()
+    }
+
+    /// Whether every step of the script has played
+    pub fn is_finished(&self) -> bool {
+        self.shared.lock().steps.is_empty()
+    }
+
+    fn written_of_type(&self, kind: &str) -> Vec<Value> {
+        self.shared
+            .lock()
+            .written
+            .iter()
+            .filter(|message| message["type"] == kind)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Builds a [`MockTransport`] script step by step
+#[derive(Debug, Default)]
+pub struct MockTransportBuilder {
+    steps: Vec<MockStep>,
+}
+
+impl MockTransportBuilder {
+    /// Wait for a user message containing `pattern`; `""` matches any
+    pub fn on_user_message(mut self, pattern: impl Into<String>) -> Self {
+        self.steps.push(MockStep::OnUserMessage(pattern.into()));
+        self
+    }
+
+    /// Wait for the answer to a control request sent with [`respond`](Self::respond)
+    pub fn on_control_response(mut self) -> Self {
+        self.steps.push(MockStep::OnControlResponse);
+        self
+    }
+
+    /// Send `message` as is
+    pub fn respond(mut self, message: Value) -> Self {
+        self.steps.push(MockStep::Respond(message));
+        self
+    }
+
+    /// Send an assistant message with a single text block
+    pub fn respond_assistant_text(self, text: impl Into<String>) -> Self {
+        self.respond_assistant(json!([{"type": "text", "text": text.into()}]))
+    }
+
+    /// Send an assistant message calling tool `name`
+    pub fn respond_tool_use(
+        self,
+        id: impl Into<String>,
+        name: impl Into<String>,
+        input: Value,
+    ) -> Self {
+        self.respond_assistant(json!([{
+            "type": "tool_use",
+            "id": id.into(),
+            "name": name.into(),
+            "input": input,
+        }]))
+    }
+
+    /// Send the user message the CLI prints with a tool's result
+    pub fn respond_tool_result(
+        self,
+        tool_use_id: impl Into<String>,
+        content: impl Into<String>,
+        is_error: bool,
+    ) -> Self {
+        self.respond(json!({
+            "type": "user",
+            "session_id": MOCK_SESSION_ID,
+            "message": {
+                "role": "user",
+                "content": [{
+                    "type": "tool_result",
+                    "tool_use_id": tool_use_id.into(),
+                    "content": content.into(),
+                    "is_error": is_error,
+                }],
+            },
+        }))
+    }
+
+    /// Send a successful result message, ending the turn
+    pub fn respond_result(self, result: impl Into<String>) -> Self {
+        self.respond(json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 0,
+            "duration_api_ms": 0,
+            "is_error": false,
+            "num_turns": 1,
+            "session_id": MOCK_SESSION_ID,
+            "total_cost_usd": 0.0,
+            "result": result.into(),
+        }))
+    }
+
+    /// Pause the output
+    pub fn delay(mut self, delay: Duration) -> Self {
+        let ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
+        self.steps.push(MockStep::DelayMs(ms));
+        self
+    }
+
+    /// Fail the read side with a [`ClaudeError::Transport`] error
+    pub fn error(mut self, message: impl Into<String>) -> Self {
+        self.steps.push(MockStep::Error(message.into()));
+        self
+    }
+
+    /// End the output, as if the CLI had exited
+    pub fn close(mut self) -> Self {
+        self.steps.push(MockStep::Close);
+        self
+    }
+
+    /// The script so far
+    pub fn steps(&self) -> &[MockStep] {
+        &self.steps
+    }
+
+    /// Build the transport
+    pub fn build(self) -> MockTransport {
+        MockTransport::from_steps(self.steps)
+    }
+
+    fn respond_assistant(self, content: Value) -> Self {
+        self.respond(json!({
+            "type": "assistant",
+            "session_id": MOCK_SESSION_ID,
+            "message": {
+                "model": MOCK_MODEL,
+                "role": "assistant",
+                "content": content,
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn user_message(text: &str) -> String {
+        json!({"type": "user", "message": {"role": "user", "content": text}}).to_string()
+    }
+
+    #[test]
+    fn test_fixtures_parse() {
+        for fixture in [
+            fixtures::BASIC_TEXT,
+            fixtures::TOOL_USE,
+            fixtures::PERMISSION_DENIED,
+        ] {
+            let steps: Vec<MockStep> = serde_json::from_str(fixture).unwrap();
+            assert_eq!(steps[0], MockStep::OnUserMessage(String::new()));
+        }
+    }
+
+    #[test]
+    fn test_step_json_form() {
+        let steps: Vec<MockStep> = serde_json::from_str(
+            r#"[{"on_user_message": "hi"}, "on_control_response", {"delay_ms": 5},
+                {"error": "boom"}, "close"]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                MockStep::OnUserMessage("hi".to_string()),
+                MockStep::OnControlResponse,
+                MockStep::DelayMs(5),
+                MockStep::Error("boom".to_string()),
+                MockStep::Close,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plays_after_trigger_and_records() {
+        let mut transport = MockTransport::builder()
+            .respond_assistant_text("ready")
+            .on_user_message("hello")
+            .respond_result("done")
+            .build();
+        let recorder = transport.recorder();
+        transport.connect().await.unwrap();
+        transport.write(&user_message("hello there")).await.unwrap();
+        transport.end_input().await.unwrap();
+
+        let messages: Vec<Value> = transport
+            .read_messages()
+            .map(|message| message.unwrap())
+            .collect()
+            .await;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["type"], "assistant");
+        assert_eq!(messages[1]["result"], "done");
+        assert_eq!(recorder.user_messages(), vec!["hello there"]);
+        assert!(recorder.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_answers_control_requests() {
+        let mut transport = MockTransport::builder().build();
+        let recorder = transport.recorder();
+        transport.connect().await.unwrap();
+        let request = json!({
+            "type": "control_request",
+            "request_id": "req_1",
+            "request": {"subtype": "interrupt"},
+        });
+        transport.write(&request.to_string()).await.unwrap();
+        transport.end_input().await.unwrap();
+
+        let mut messages = transport.read_messages();
+        let response = messages.next().await.unwrap().unwrap();
+        assert_eq!(response["response"]["request_id"], "req_1");
+        assert_eq!(response["response"]["subtype"], "success");
+        assert!(messages.next().await.is_none());
+        drop(messages);
+        assert_eq!(
+            recorder.control_requests(),
+            vec![json!({"subtype": "interrupt"})]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_user_message_fails_read() {
+        let mut transport = MockTransport::builder()
+            .on_user_message("hello")
+            .respond_result("done")
+            .build();
+        transport.connect().await.unwrap();
+        transport.write(&user_message("goodbye")).await.unwrap();
+
+        let error = transport.read_messages().next().await.unwrap().unwrap_err();
+        assert!(
+            matches!(error, ClaudeError::Transport(ref message) if message.contains("goodbye")),
+            "{:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_delay_and_close() {
+        let mut transport = MockTransport::builder()
+            .delay(Duration::from_millis(20))
+            .error("connection reset")
+            .respond_result("after error")
+            .close()
+            .respond_result("never sent")
+            .build();
+        transport.connect().await.unwrap();
+
+        let started = std::time::Instant::now();
+        let items: Vec<Result<Value>> = transport.read_messages().collect().await;
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[0], Err(ClaudeError::Transport(ref m)) if m == "connection reset"));
+        assert_eq!(items[1].as_ref().unwrap()["result"], "after error");
+    }
+
+    #[test]
+    fn test_from_json_rejects_bad_script() {
+        let error = MockTransport::from_json(r#"[{"bogus": 1}]"#).err().unwrap();
+        assert!(
+            matches!(error, ClaudeError::InvalidConfig(_)),
+            "{:?}",
+            error
+        );
+    }
+}
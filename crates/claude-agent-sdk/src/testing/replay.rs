@@ -0,0 +1,438 @@
+//! Replaying a recorded session without the CLI
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::Stream;
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use super::{LineSink, ScriptWriter, Shared, user_text};
+use crate::errors::{ClaudeError, Result};
+use crate::internal::transport::recording::{FrameDirection, RecordedFrame};
+use crate::internal::transport::{Transport, TransportWriter};
+
+/// How fast a [`ReplayTransport`] plays back inbound frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplaySpeed {
+    /// As soon as the writes before them have been made
+    #[default]
+    Fast,
+    /// With the gaps between frames as they were recorded
+    Realtime,
+}
+
+#[derive(Default)]
+struct ReplayState {
+    frames: VecDeque<RecordedFrame>,
+    /// Recorded outbound frames not yet matched to a write
+    expected: VecDeque<Value>,
+    /// Writes made that the reader has not yet passed in the recording
+    pending_writes: usize,
+    writes: usize,
+    /// Request IDs of recorded control requests, mapped to the ones sent now
+    request_ids: HashMap<String, String>,
+    mismatches: Vec<String>,
+    input_ended: bool,
+}
+
+impl ReplayState {
+    fn mismatch(&mut self, message: String) {
+        warn!("Replay mismatch: {}", message);
+        self.mismatches.push(message);
+    }
+
+    fn receive(&mut self, written: Value) {
+        self.writes += 1;
+        self.pending_writes += 1;
+        let Some(expected) = self.expected.pop_front() else {
+            self.mismatch(format!(
+                "write {} is not in the recording: {}",
+                self.writes, written
+            ));
+            return;
+        };
+
+        if let (Some(recorded), Some(sent)) =
+            (control_request_id(&expected), control_request_id(&written))
+        {
+            self.request_ids
+                .insert(recorded.to_string(), sent.to_string());
+        }
+        if frame_key(&expected) != frame_key(&written) {
+            self.mismatch(format!(
+                "write {} differs from the recording: expected {}, got {}",
+                self.writes, expected, written
+            ));
+        }
+    }
+
+    /// Answers to control requests go to the request IDs sent now
+    fn rewrite(&self, mut frame: Value) -> Value {
+        if frame["type"] == "control_response"
+            && let Some(recorded) = frame["response"]["request_id"].as_str()
+            && let Some(sent) = self.request_ids.get(recorded)
+        {
+            frame["response"]["request_id"] = Value::String(sent.clone());
+        }
+        frame
+    }
+}
+
+impl LineSink for Shared<ReplayState> {
+    fn receive_line(&self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        let written =
+            serde_json::from_str(line).unwrap_or_else(|_| Value::String(line.to_string()));
+        self.update(|state| state.receive(written));
+    }
+
+    fn end_input(&self) {
+        self.update(|state| state.input_ended = true);
+    }
+}
+
+fn control_request_id(frame: &Value) -> Option<&str> {
+    if frame["type"] == "control_request" {
+        frame["request_id"].as_str()
+    } else {
+        None
+    }
+}
+
+/// What a write is compared by
+///
+/// Request IDs and session IDs differ between runs, and a one-shot prompt is
+/// sent as plain text by [`query`](crate::query) but as a user message by
+/// [`query_with_transport`](crate::query_with_transport).
+fn frame_key(frame: &Value) -> Value {
+    match frame {
+        Value::String(text) => json!({"user": text}),
+        _ => match frame["type"].as_str() {
+            Some("user") => json!({"user": user_text(frame)}),
+            Some("control_request") => json!({"control_request": frame["request"]["subtype"]}),
+            _ => frame.clone(),
+        },
+    }
+}
+
+/// What the reader does next
+enum Next {
+    Emit(Value, Duration),
+    Wait,
+    End,
+}
+
+/// A [`Transport`] that plays back a session recorded with
+/// [`ClaudeAgentOptions::record_to`](crate::ClaudeAgentOptions::record_to)
+///
+/// Inbound frames are played in order, each once the writes recorded before it
+/// have been made. Writes are compared with the recorded ones by content, so
+/// that request and session IDs may differ; a write that does not match is
+/// logged as a warning and listed in the [`ReplayReport`], and replay goes on.
+///
+/// ```no_run
+/// use claude_agent_sdk::query_with_transport;
+/// use claude_agent_sdk::testing::ReplayTransport;
+///
+/// # async fn example() -> claude_agent_sdk::Result<()> {
+/// let transport = ReplayTransport::open("session.jsonl")?;
+/// let report = transport.report();
+/// let messages = query_with_transport("What is 2 + 2?", None, Box::new(transport)).await?;
+/// assert!(report.mismatches().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplayTransport {
+    shared: Shared<ReplayState>,
+    writer: TransportWriter,
+    speed: ReplaySpeed,
+    ready: bool,
+}
+
+impl ReplayTransport {
+    /// Play back the recording at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::from_frames(RecordedFrame::read_all(path)?))
+    }
+
+    /// Play back `frames`
+    pub fn from_frames(frames: impl IntoIterator<Item = RecordedFrame>) -> Self {
+        let shared = Shared::<ReplayState>::default();
+        {
+            let mut state = shared.lock();
+            state.frames = frames.into_iter().collect();
+            state.expected = state
+                .frames
+                .iter()
+                .filter(|frame| frame.direction == FrameDirection::Outbound)
+                .map(|frame| frame.frame.clone())
+                .collect();
+        }
+        Self {
+            shared,
+            writer: Arc::new(Mutex::new(None)),
+            speed: ReplaySpeed::default(),
+            ready: false,
+        }
+    }
+
+    /// Play back at `speed` instead of [`ReplaySpeed::Fast`]
+    pub fn with_speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// A handle on how the replay went
+    ///
+    /// Take it before handing the transport over.
+    pub fn report(&self) -> ReplayReport {
+        ReplayReport {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn connect(&mut self) -> Result<()> {
+        if !self.ready {
+            *self.writer.lock().await = Some(Box::new(ScriptWriter::new(self.shared.clone())));
+            self.ready = true;
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, data: &str) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        let writer = writer
+            .as_mut()
+            .ok_or_else(|| ClaudeError::Transport("ReplayTransport input has ended".to_string()))?;
+        writer.write_all(data.as_bytes()).await?;
+        if !data.ends_with('\n') {
+            writer.write_all(b"\n").await?;
+        }
+        Ok(())
+    }
+
+    fn read_messages(
+        &mut self,
+    ) -> Pin<Box<dyn Stream<Item = Result<serde_json::Value>> + Send + '_>> {
+        let shared = self.shared.clone();
+        let realtime = self.speed == ReplaySpeed::Realtime;
+        Box::pin(async_stream::stream! {
+            let mut last_ts = None;
+            loop {
+                let next = {
+                    let mut state = shared.lock();
+                    loop {
+                        let Some(frame) = state.frames.front() else {
+                            break Next::End;
+                        };
+                        let ts_ms = frame.ts_ms;
+                        if frame.direction == FrameDirection::Inbound {
+                            let frame = state.frames.pop_front().map(|frame| frame.frame);
+                            let frame = state.rewrite(frame.unwrap_or_default());
+                            let gap = match last_ts.replace(ts_ms) {
+                                Some(last) if realtime => ts_ms.saturating_sub(last),
+                                _ => 0,
+                            };
+                            break Next::Emit(frame, Duration::from_millis(gap));
+                        }
+
+                        if state.pending_writes > 0 {
+                            state.pending_writes -= 1;
+                        } else if state.input_ended {
+                            let missing = state.expected.pop_front().unwrap_or_default();
+                            state.mismatch(format!("recorded write was not made: {}", missing));
+                        } else {
+                            break Next::Wait;
+                        }
+                        state.frames.pop_front();
+                        last_ts = Some(ts_ms);
+                    }
+                };
+                match next {
+                    Next::Emit(frame, gap) => {
+                        if !gap.is_zero() {
+                            tokio::time::sleep(gap).await;
+                        }
+                        yield Ok(frame);
+                    },
+                    Next::Wait => shared.changed.notified().await,
+                    Next::End => break,
+                }
+            }
+        })
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.end_input().await?;
+        self.ready = false;
+        Ok(())
+    }
+
+    fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    async fn end_input(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.lock().await.take() {
+            writer.shutdown().await?;
+        }
+        Ok(())
+    }
+
+    fn writer(&self) -> TransportWriter {
+        Arc::clone(&self.writer)
+    }
+}
+
+/// How a [`ReplayTransport`] replay went
+#[derive(Clone)]
+pub struct ReplayReport {
+    shared: Shared<ReplayState>,
+}
+
+impl ReplayReport {
+    /// Writes that did not match the recording, described for people
+    pub fn mismatches(&self) -> Vec<String> {
+        self.shared.lock().mismatches.clone()
+    }
+
+    /// Whether the whole recording has been played
+    pub fn is_finished(&self) -> bool {
+        self.shared.lock().frames.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn frame(ts_ms: u64, direction: FrameDirection, frame: Value) -> RecordedFrame {
+        RecordedFrame {
+            ts_ms,
+            direction,
+            frame,
+        }
+    }
+
+    fn recording() -> Vec<RecordedFrame> {
+        vec![
+            frame(
+                0,
+                FrameDirection::Outbound,
+                json!({"type": "control_request", "request_id": "req_1_old", "request": {"subtype": "initialize"}}),
+            ),
+            frame(
+                5,
+                FrameDirection::Inbound,
+                json!({"type": "control_response", "response": {"subtype": "success", "request_id": "req_1_old"}}),
+            ),
+            frame(
+                10,
+                FrameDirection::Outbound,
+                json!({"type": "user", "message": {"role": "user", "content": "hello"}}),
+            ),
+            frame(
+                60,
+                FrameDirection::Inbound,
+                json!({"type": "result", "subtype": "success", "result": "hi"}),
+            ),
+        ]
+    }
+
+    async fn write(transport: &mut ReplayTransport, message: Value) {
+        transport.write(&message.to_string()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replays_with_new_request_ids() {
+        let mut transport = ReplayTransport::from_frames(recording());
+        let report = transport.report();
+        transport.connect().await.unwrap();
+        write(
+            &mut transport,
+            json!({"type": "control_request", "request_id": "req_1_new", "request": {"subtype": "initialize"}}),
+        )
+        .await;
+        write(
+            &mut transport,
+            json!({"type": "user", "message": {"role": "user", "content": "hello"}, "session_id": "default"}),
+        )
+        .await;
+        transport.end_input().await.unwrap();
+
+        let frames: Vec<Value> = transport
+            .read_messages()
+            .map(|frame| frame.unwrap())
+            .collect()
+            .await;
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["response"]["request_id"], "req_1_new");
+        assert_eq!(frames[1]["result"], "hi");
+        assert!(report.mismatches().is_empty(), "{:?}", report.mismatches());
+        assert!(report.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_mismatches_are_reported_not_fatal() {
+        let mut transport = ReplayTransport::from_frames(recording());
+        let report = transport.report();
+        transport.connect().await.unwrap();
+        write(
+            &mut transport,
+            json!({"type": "control_request", "request_id": "req_1_new", "request": {"subtype": "initialize"}}),
+        )
+        .await;
+        transport.write("goodbye").await.unwrap();
+        transport.write("one more").await.unwrap();
+        transport.end_input().await.unwrap();
+
+        let frames: Vec<_> = transport.read_messages().collect().await;
+        assert!(frames.iter().all(|frame| frame.is_ok()));
+        assert_eq!(frames.len(), 2);
+
+        let mismatches = report.mismatches();
+        assert_eq!(mismatches.len(), 2, "{:?}", mismatches);
+        assert!(mismatches[0].contains("goodbye"));
+        assert!(mismatches[1].contains("not in the recording"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_write_is_skipped_once_input_ends() {
+        let mut transport = ReplayTransport::from_frames(recording());
+        let report = transport.report();
+        transport.connect().await.unwrap();
+        transport.end_input().await.unwrap();
+
+        let frames: Vec<_> = transport.read_messages().collect().await;
+        assert_eq!(frames.len(), 2);
+        assert_eq!(report.mismatches().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_realtime_keeps_recorded_gaps() {
+        let mut transport = ReplayTransport::from_frames(vec![
+            frame(100, FrameDirection::Inbound, json!({"type": "system"})),
+            frame(150, FrameDirection::Inbound, json!({"type": "result"})),
+        ])
+        .with_speed(ReplaySpeed::Realtime);
+        transport.connect().await.unwrap();
+
+        let started = std::time::Instant::now();
+        let frames: Vec<_> = transport.read_messages().collect().await;
+        assert_eq!(frames.len(), 2);
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
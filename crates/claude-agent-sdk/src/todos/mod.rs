@@ -2,9 +2,19 @@
 //!
 //! This module provides functionality for managing todo lists within the SDK,
 //! allowing agents and users to track tasks and their completion status.
+//! Lists can be saved to JSON files, and a [`TodoStore`] keeps named lists in
+//! a directory so they survive restarts. A [`TodoTracker`] follows the list
+//! Claude keeps with its `TodoWrite` tool during a run.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+
+mod store;
+mod tracker;
+
+pub use store::TodoStore;
+pub use tracker::TodoTracker;
 
 /// Todo status
 ///
@@ -73,9 +83,38 @@ impl TodoStatus {
     }
 }
 
+/// Todo priority
+///
+/// Ordered from `Low` to `Urgent`, so priorities compare as expected.
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::todos::TodoPriority;
+///
+/// assert!(TodoPriority::Urgent > TodoPriority::High);
+/// assert_eq!(TodoPriority::default(), TodoPriority::Medium);
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum TodoPriority {
+    /// Can wait
+    Low,
+    /// Normal priority
+    #[default]
+    Medium,
+    /// Should be done soon
+    High,
+    /// Needs attention now
+    Urgent,
+}
+
 /// A todo item in a todo list
 ///
 /// Represents a single task with content and status.
+/// Items saved before `priority` and `due_date` existed load with
+/// `Medium` priority and no due date.
 ///
 /// # Example
 ///
@@ -88,6 +127,9 @@ impl TodoStatus {
 ///     content: "Write documentation".to_string(),
 ///     status: claude_agent_sdk::todos::TodoStatus::Pending,
 ///     created_at: chrono::Utc::now(),
+///     priority: claude_agent_sdk::todos::TodoPriority::High,
+///     due_date: None,
+///     active_form: None,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +145,19 @@ pub struct TodoItem {
 
     /// Timestamp when the todo item was created
     pub created_at: chrono::DateTime<chrono::Utc>,
+
+    /// Priority of the todo item
+    #[serde(default)]
+    pub priority: TodoPriority,
+
+    /// When the todo item should be completed by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Present-tense description shown while the item is in progress,
+    /// such as "Running tests"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_form: Option<String>,
 }
 
 impl TodoItem {
@@ -130,9 +185,55 @@ impl TodoItem {
             content: content.into(),
             status: TodoStatus::Pending,
             created_at: chrono::Utc::now(),
+            priority: TodoPriority::default(),
+            due_date: None,
+            active_form: None,
         }
     }
 
+    /// Set the priority
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::todos::{TodoItem, TodoPriority};
+    /// let item = TodoItem::new("123", "Fix outage").with_priority(TodoPriority::Urgent);
+    /// assert_eq!(item.priority, TodoPriority::Urgent);
+    /// ```
+    pub fn with_priority(mut self, priority: TodoPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the due date
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::todos::TodoItem;
+    /// let due = chrono::Utc::now() + chrono::Duration::days(1);
+    /// let item = TodoItem::new("123", "Write docs").with_due_date(due);
+    /// assert_eq!(item.due_date, Some(due));
+    /// ```
+    pub fn with_due_date(mut self, due_date: chrono::DateTime<chrono::Utc>) -> Self {
+        self.due_date = Some(due_date);
+        self
+    }
+
+    /// Check if the item is past its due date and not completed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::todos::TodoItem;
+    /// let now = chrono::Utc::now();
+    /// let item = TodoItem::new("123", "Write docs").with_due_date(now - chrono::Duration::hours(1));
+    /// assert!(item.is_overdue(now));
+    /// ```
+    pub fn is_overdue(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        !self.status.is_completed() && self.due_date.is_some_and(|due| due < now)
+    }
+
     /// Mark the todo item as completed
     ///
     /// # Returns
@@ -263,6 +364,38 @@ impl TodoList {
         self.items.last().unwrap()
     }
 
+    /// Add a new todo item, configured by `build` before it is inserted
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - Content/description for the new todo item
+    /// * `build` - Sets further fields, typically with `with_priority` and `with_due_date`
+    ///
+    /// # Returns
+    ///
+    /// Reference to the newly added todo item
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::todos::{TodoList, TodoPriority};
+    /// # let mut list = TodoList::new("My Tasks");
+    /// let due = chrono::Utc::now() + chrono::Duration::days(2);
+    /// let item = list.add_with("Ship release", |item| {
+    ///     item.with_priority(TodoPriority::High).with_due_date(due)
+    /// });
+    /// assert_eq!(item.priority, TodoPriority::High);
+    /// ```
+    pub fn add_with(
+        &mut self,
+        content: impl Into<String>,
+        build: impl FnOnce(TodoItem) -> TodoItem,
+    ) -> &TodoItem {
+        let item = build(TodoItem::new(uuid::Uuid::new_v4().to_string(), content));
+        self.items.push(item);
+        self.items.last().unwrap()
+    }
+
     /// Complete a todo item by ID
     ///
     /// # Arguments
@@ -432,6 +565,69 @@ impl TodoList {
             .collect()
     }
 
+    /// Get all items, highest priority first
+    ///
+    /// Items with the same priority are ordered oldest first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::todos::{TodoList, TodoPriority};
+    /// # let mut list = TodoList::new("My Tasks");
+    /// list.add("Routine");
+    /// list.add_with("Outage", |item| item.with_priority(TodoPriority::Urgent));
+    /// assert_eq!(list.sorted_by_priority()[0].content, "Outage");
+    /// ```
+    pub fn sorted_by_priority(&self) -> Vec<&TodoItem> {
+        let mut items: Vec<_> = self.items.iter().collect();
+        items.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.created_at.cmp(&b.created_at))
+        });
+        items
+    }
+
+    /// Get the items that are past their due date and not completed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::todos::TodoList;
+    /// # let mut list = TodoList::new("My Tasks");
+    /// let yesterday = chrono::Utc::now() - chrono::Duration::days(1);
+    /// list.add_with("Late", |item| item.with_due_date(yesterday));
+    /// list.add("No due date");
+    /// assert_eq!(list.overdue().len(), 1);
+    /// ```
+    pub fn overdue(&self) -> Vec<&TodoItem> {
+        let now = chrono::Utc::now();
+        self.items
+            .iter()
+            .filter(|item| item.is_overdue(now))
+            .collect()
+    }
+
+    /// Get the highest-priority pending item
+    ///
+    /// Items with the same priority are ordered oldest first. In-progress items
+    /// are not returned, as they are already being worked on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use claude_agent_sdk::todos::{TodoList, TodoPriority};
+    /// # let mut list = TodoList::new("My Tasks");
+    /// list.add("Routine");
+    /// list.add_with("Important", |item| item.with_priority(TodoPriority::High));
+    /// assert_eq!(list.next_actionable().unwrap().content, "Important");
+    /// ```
+    pub fn next_actionable(&self) -> Option<&TodoItem> {
+        self.sorted_by_priority()
+            .into_iter()
+            .find(|item| item.status == TodoStatus::Pending)
+    }
+
     /// Get the count of items by status
     ///
     /// # Returns
@@ -535,6 +731,55 @@ impl TodoList {
         }
         (self.completed_count() as f64 / self.len() as f64) * 100.0
     }
+
+    /// Save the list to `path` as JSON
+    ///
+    /// The file is written next to `path` first and then renamed over it, so a
+    /// crash mid-save leaves either the old or the new contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::Io` if the file cannot be written
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::todos::TodoList;
+    /// let mut list = TodoList::new("My Tasks");
+    /// list.add("Write docs");
+    /// list.save_to_file("todos.json")?;
+    /// # Ok::<(), claude_agent_sdk::todos::TodoError>(())
+    /// ```
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), TodoError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| TodoError::InvalidInput(format!("Cannot serialize todo list: {}", e)))?;
+        store::write_atomic(path.as_ref(), json.as_bytes())
+    }
+
+    /// Load a list saved with [`TodoList::save_to_file`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::Io` if the file cannot be read, or
+    /// `TodoError::InvalidInput` naming the file and position if it is not a
+    /// valid todo list
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::todos::TodoList;
+    /// let list = TodoList::load_from_file("todos.json")?;
+    /// println!("{}: {} items", list.name, list.len());
+    /// # Ok::<(), claude_agent_sdk::todos::TodoError>(())
+    /// ```
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, TodoError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| TodoError::Io(format!("Cannot read {}: {}", path.display(), e)))?;
+        serde_json::from_str(&json).map_err(|e| {
+            TodoError::InvalidInput(format!("{} is not a valid todo list: {}", path.display(), e))
+        })
+    }
 }
 
 /// Errors that can occur in todo operations
@@ -543,6 +788,7 @@ impl TodoList {
 ///
 /// * `NotFound` - Todo item not found
 /// * `InvalidInput` - Invalid input provided
+/// * `Io` - Reading or writing a todo file failed
 ///
 /// # Example
 ///
@@ -559,6 +805,9 @@ pub enum TodoError {
 
     /// Invalid input provided
     InvalidInput(String),
+
+    /// Reading or writing a todo file failed
+    Io(String),
 }
 
 impl std::fmt::Display for TodoError {
@@ -566,6 +815,7 @@ impl std::fmt::Display for TodoError {
         match self {
             TodoError::NotFound(id) => write!(f, "Todo item not found: {}", id),
             TodoError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            TodoError::Io(msg) => write!(f, "I/O error: {}", msg),
         }
     }
 }
@@ -765,4 +1015,150 @@ mod tests {
         list.reset(&id).unwrap();
         assert_eq!(list.items[0].status, TodoStatus::Pending);
     }
+
+    #[test]
+    fn test_sorted_by_priority_ties_fall_back_to_created_at() {
+        let mut list = TodoList::new("My Tasks");
+        let start = chrono::Utc::now();
+        for (content, priority, minutes) in [
+            ("high newer", TodoPriority::High, 5),
+            ("low", TodoPriority::Low, 0),
+            ("high older", TodoPriority::High, 1),
+            ("medium", TodoPriority::Medium, 2),
+            ("urgent", TodoPriority::Urgent, 9),
+        ] {
+            list.add_with(content, |mut item| {
+                item.created_at = start + chrono::Duration::minutes(minutes);
+                item.with_priority(priority)
+            });
+        }
+
+        let order: Vec<_> = list
+            .sorted_by_priority()
+            .iter()
+            .map(|item| item.content.as_str())
+            .collect();
+        assert_eq!(
+            order,
+            vec!["urgent", "high older", "high newer", "medium", "low"]
+        );
+    }
+
+    #[test]
+    fn test_next_actionable_skips_started_and_completed() {
+        let mut list = TodoList::new("My Tasks");
+        assert!(list.next_actionable().is_none());
+
+        let urgent = list
+            .add_with("urgent", |item| item.with_priority(TodoPriority::Urgent))
+            .id
+            .clone();
+        let high = list
+            .add_with("high", |item| item.with_priority(TodoPriority::High))
+            .id
+            .clone();
+        list.add("medium");
+
+        list.start(&urgent).unwrap();
+        assert_eq!(list.next_actionable().unwrap().id, high);
+        list.complete(&high).unwrap();
+        assert_eq!(list.next_actionable().unwrap().content, "medium");
+    }
+
+    #[test]
+    fn test_overdue() {
+        let mut list = TodoList::new("My Tasks");
+        let now = chrono::Utc::now();
+        list.add_with("late", |item| {
+            item.with_due_date(now - chrono::Duration::hours(1))
+        });
+        let done = list
+            .add_with("late but done", |item| {
+                item.with_due_date(now - chrono::Duration::hours(1))
+            })
+            .id
+            .clone();
+        list.add_with("future", |item| {
+            item.with_due_date(now + chrono::Duration::hours(1))
+        });
+        list.add("no due date");
+        list.complete(&done).unwrap();
+
+        let overdue: Vec<_> = list
+            .overdue()
+            .iter()
+            .map(|item| item.content.as_str())
+            .collect();
+        assert_eq!(overdue, vec!["late"]);
+    }
+
+    #[test]
+    fn test_old_format_items_deserialize_with_defaults() {
+        let json = r#"{
+            "id": "list-1",
+            "name": "Old",
+            "items": [{
+                "id": "1",
+                "content": "Legacy task",
+                "status": "Pending",
+                "created_at": "2024-01-02T03:04:05.123456789Z"
+            }]
+        }"#;
+
+        let list: TodoList = serde_json::from_str(json).unwrap();
+        let item = &list.items[0];
+        assert_eq!(item.priority, TodoPriority::Medium);
+        assert_eq!(item.due_date, None);
+        assert_eq!(
+            item.created_at.to_rfc3339(),
+            "2024-01-02T03:04:05.123456789+00:00"
+        );
+
+        // No due date is not written back
+        let saved = serde_json::to_value(item).unwrap();
+        assert!(saved.get("due_date").is_none());
+        assert_eq!(saved["priority"], "Medium");
+    }
+
+    #[test]
+    fn test_todo_list_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+
+        let mut list = TodoList::new("My Tasks");
+        list.add("Task 1");
+        let id = list.add("Task 2").id.clone();
+        list.complete(&id).unwrap();
+        list.save_to_file(&path).unwrap();
+
+        let loaded = TodoList::load_from_file(&path).unwrap();
+        assert_eq!(loaded.id, list.id);
+        assert_eq!(loaded.name, list.name);
+        assert_eq!(loaded.len(), 2);
+        for (loaded, original) in loaded.items.iter().zip(&list.items) {
+            assert_eq!(loaded.id, original.id);
+            assert_eq!(loaded.status, original.status);
+            assert_eq!(loaded.created_at, original.created_at);
+        }
+    }
+
+    #[test]
+    fn test_todo_list_load_corrupted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        std::fs::write(&path, r#"{"id": "1", "name": "#).unwrap();
+
+        match TodoList::load_from_file(&path) {
+            Err(TodoError::InvalidInput(msg)) => {
+                assert!(msg.contains("tasks.json"), "{}", msg);
+                assert!(msg.contains("line 1"), "{}", msg);
+            },
+            other => panic!("expected InvalidInput, got {:?}", other),
+        }
+
+        assert!(matches!(
+            TodoList::load_from_file(dir.path().join("missing.json")),
+            Err(TodoError::Io(_))
+        ));
+    }
 }
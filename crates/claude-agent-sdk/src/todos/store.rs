@@ -0,0 +1,273 @@
+//! Directory-backed storage for named todo lists
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::{TodoError, TodoList};
+
+/// Named todo lists kept as JSON files in one directory
+///
+/// Each list is stored in `<dir>/<name>.json`. Characters other than ASCII
+/// letters, digits, `-` and `_` are percent-encoded in the file name, so every
+/// list name maps to its own file. Saves are atomic: concurrent saves of the
+/// same list leave one complete version on disk, never a mix.
+///
+/// # Example
+///
+/// ```no_run
+/// use claude_agent_sdk::todos::TodoStore;
+///
+/// let store = TodoStore::open(".agent/todos")?;
+/// let mut list = store.get_or_create("release")?;
+/// list.add("Tag the release");
+/// store.save(&list)?;
+///
+/// for list in store.list_all()? {
+///     println!("{}: {:.0}% done", list.name, list.completion_percentage());
+/// }
+/// # Ok::<(), claude_agent_sdk::todos::TodoError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TodoStore {
+    dir: PathBuf,
+}
+
+impl TodoStore {
+    /// Open the store in `dir`, creating the directory if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::Io` if the directory cannot be created
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, TodoError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| TodoError::Io(format!("Cannot create {}: {}", dir.display(), e)))?;
+        Ok(Self { dir })
+    }
+
+    /// Directory holding the lists
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load every list in the store, sorted by name
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::Io` if the directory cannot be read, or
+    /// `TodoError::InvalidInput` if any list file is corrupted
+    pub fn list_all(&self) -> Result<Vec<TodoList>, TodoError> {
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|e| TodoError::Io(format!("Cannot read {}: {}", self.dir.display(), e)))?;
+
+        let mut lists = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|e| TodoError::Io(format!("Cannot read {}: {}", self.dir.display(), e)))?
+                .path();
+            // Skips temporary files of saves in progress, which start with a dot
+            let is_list = path.extension().is_some_and(|ext| ext == "json")
+                && path
+                    .file_name()
+                    .is_some_and(|name| !name.to_string_lossy().starts_with('.'));
+            if is_list {
+                lists.push(TodoList::load_from_file(&path)?);
+            }
+        }
+
+        lists.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(lists)
+    }
+
+    /// Load the list called `name`, if it exists
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::InvalidInput` if the name is empty or the list file
+    /// is corrupted, or `TodoError::Io` if it cannot be read
+    pub fn get(&self, name: &str) -> Result<Option<TodoList>, TodoError> {
+        let path = self.path_for(name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        TodoList::load_from_file(&path).map(Some)
+    }
+
+    /// Load the list called `name`, creating and saving an empty one if needed
+    ///
+    /// # Errors
+    ///
+    /// Same as [`TodoStore::get`] and [`TodoStore::save`]
+    pub fn get_or_create(&self, name: &str) -> Result<TodoList, TodoError> {
+        if let Some(list) = self.get(name)? {
+            return Ok(list);
+        }
+        let list = TodoList::new(name);
+        self.save(&list)?;
+        Ok(list)
+    }
+
+    /// Save `list` under its name, replacing any list with the same name
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::InvalidInput` if the list name is empty, or
+    /// `TodoError::Io` if the file cannot be written
+    pub fn save(&self, list: &TodoList) -> Result<(), TodoError> {
+        list.save_to_file(self.path_for(&list.name)?)
+    }
+
+    /// Delete the list called `name`
+    ///
+    /// Returns whether the list existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::InvalidInput` if the name is empty, or
+    /// `TodoError::Io` if the file cannot be removed
+    pub fn remove(&self, name: &str) -> Result<bool, TodoError> {
+        let path = self.path_for(name)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(TodoError::Io(format!(
+                "Cannot remove {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    fn path_for(&self, name: &str) -> Result<PathBuf, TodoError> {
+        if name.is_empty() {
+            return Err(TodoError::InvalidInput(
+                "Todo list name cannot be empty".to_string(),
+            ));
+        }
+
+        let mut file_name = String::with_capacity(name.len() + 5);
+        for byte in name.bytes() {
+            if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+                file_name.push(byte as char);
+            } else {
+                file_name.push_str(&format!("%{:02X}", byte));
+            }
+        }
+        file_name.push_str(".json");
+        Ok(self.dir.join(file_name))
+    }
+}
+
+/// Write `contents` to a temporary file next to `path`, then rename it over `path`
+pub(super) fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), TodoError> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| TodoError::InvalidInput(format!("{} is not a file", path.display())))?;
+    // Unique per save, so concurrent saves never share a temporary file
+    let tmp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        uuid::Uuid::new_v4()
+    ));
+
+    let written = std::fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&tmp, path));
+
+    written.map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        TodoError::Io(format!("Cannot write {}: {}", path.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_or_create_persists_new_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TodoStore::open(dir.path().join("todos")).unwrap();
+        assert!(store.get("release").unwrap().is_none());
+
+        let created = store.get_or_create("release").unwrap();
+        let loaded = store.get_or_create("release").unwrap();
+        assert_eq!(loaded.id, created.id);
+
+        // A new store over the same directory sees it too
+        let reopened = TodoStore::open(store.dir()).unwrap();
+        assert_eq!(reopened.get("release").unwrap().unwrap().id, created.id);
+    }
+
+    #[test]
+    fn test_list_all_sorted_and_skips_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TodoStore::open(dir.path()).unwrap();
+
+        let mut list = store.get_or_create("weekly/chores").unwrap();
+        list.add("Water plants");
+        store.save(&list).unwrap();
+        store.get_or_create("a list").unwrap();
+        std::fs::write(dir.path().join(".x.json.1234.tmp"), "{").unwrap();
+
+        let names: Vec<_> = store
+            .list_all()
+            .unwrap()
+            .into_iter()
+            .map(|list| list.name)
+            .collect();
+        assert_eq!(names, vec!["a list", "weekly/chores"]);
+        assert!(dir.path().join("weekly%2Fchores.json").exists());
+
+        assert!(store.remove("a list").unwrap());
+        assert!(!store.remove("a list").unwrap());
+        assert_eq!(store.list_all().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_corrupted_list_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TodoStore::open(dir.path()).unwrap();
+        std::fs::write(dir.path().join("broken.json"), "not json").unwrap();
+
+        assert!(matches!(
+            store.get("broken"),
+            Err(TodoError::InvalidInput(msg)) if msg.contains("broken.json")
+        ));
+        assert!(matches!(store.list_all(), Err(TodoError::InvalidInput(_))));
+        assert!(matches!(
+            store.get_or_create(""),
+            Err(TodoError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_concurrent_saves_leave_a_complete_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TodoStore::open(dir.path()).unwrap();
+        let base = store.get_or_create("shared").unwrap();
+
+        std::thread::scope(|scope| {
+            for writer in 0..2 {
+                let store = &store;
+                let mut list = base.clone();
+                scope.spawn(move || {
+                    for i in 0..50 {
+                        list.add(format!("writer {} item {}", writer, i));
+                        store.save(&list).unwrap();
+                        // Readers never observe a partially written file
+                        store.get("shared").unwrap().unwrap();
+                    }
+                });
+            }
+        });
+
+        let list = store.get("shared").unwrap().unwrap();
+        assert_eq!(list.len(), 50);
+        let leftovers = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(leftovers, 1);
+    }
+}
@@ -0,0 +1,221 @@
+//! Following the todo list Claude keeps with its `TodoWrite` tool
+
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::watch;
+
+use super::{TodoError, TodoItem, TodoList, TodoPriority, TodoStatus};
+use crate::types::messages::{ContentBlock, Message};
+
+/// Name of the CLI tool that rewrites the todo list
+const TODO_WRITE_TOOL: &str = "TodoWrite";
+
+/// Todo item in the shape of the CLI's `TodoWrite` input
+///
+/// Fields the SDK does not know are ignored.
+#[derive(Deserialize)]
+struct CliTodo {
+    content: String,
+    status: CliStatus,
+    #[serde(default, rename = "activeForm")]
+    active_form: Option<String>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    priority: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CliStatus {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+impl From<CliStatus> for TodoStatus {
+    fn from(status: CliStatus) -> Self {
+        match status {
+            CliStatus::Pending => TodoStatus::Pending,
+            CliStatus::InProgress => TodoStatus::InProgress,
+            CliStatus::Completed => TodoStatus::Completed,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TodoWriteInput {
+    todos: Vec<CliTodo>,
+}
+
+/// The todo list Claude maintains during a run
+///
+/// Feed it messages with [`observe`](Self::observe), or let
+/// [`ClaudeClient`](crate::ClaudeClient) do so by setting
+/// [`ClaudeAgentOptions::track_todos`](crate::ClaudeAgentOptions::track_todos).
+/// Each `TodoWrite` call replaces the whole list; items whose content did not
+/// change keep their ID and `created_at`.
+///
+/// # Example
+///
+/// ```
+/// use claude_agent_sdk::todos::{TodoStatus, TodoTracker};
+/// use serde_json::json;
+///
+/// let tracker = TodoTracker::new();
+/// let mut updates = tracker.subscribe();
+///
+/// tracker.apply_todo_write(&json!({"todos": [
+///     {"content": "Run tests", "status": "in_progress", "activeForm": "Running tests"},
+///     {"content": "Fix failures", "status": "pending", "activeForm": "Fixing failures"},
+/// ]}))?;
+///
+/// assert!(updates.has_changed().unwrap());
+/// let list = updates.borrow_and_update();
+/// assert_eq!(list.items[0].status, TodoStatus::InProgress);
+/// assert_eq!(list.items[0].active_form.as_deref(), Some("Running tests"));
+/// # Ok::<(), claude_agent_sdk::todos::TodoError>(())
+/// ```
+pub struct TodoTracker {
+    list: watch::Sender<TodoList>,
+}
+
+impl TodoTracker {
+    /// Tracker with an empty list
+    pub fn new() -> Self {
+        Self {
+            list: watch::Sender::new(TodoList::new("todos")),
+        }
+    }
+
+    /// The list as of the last `TodoWrite`
+    pub fn current(&self) -> TodoList {
+        self.list.borrow().clone()
+    }
+
+    /// Receiver notified of each new version of the list
+    pub fn subscribe(&self) -> watch::Receiver<TodoList> {
+        self.list.subscribe()
+    }
+
+    /// Apply the `TodoWrite` calls in `message`
+    ///
+    /// Inputs that don't parse are skipped with a warning, as the CLI's tool
+    /// input is outside the SDK's control.
+    pub fn observe(&self, message: &Message) {
+        let Message::Assistant(assistant) = message else {
+            return;
+        };
+        for block in &assistant.message.content {
+            if let ContentBlock::ToolUse(tool_use) = block
+                && tool_use.name == TODO_WRITE_TOOL
+                && let Err(e) = self.apply_todo_write(&tool_use.input)
+            {
+                tracing::warn!("Ignoring TodoWrite input: {}", e);
+            }
+        }
+    }
+
+    /// Replace the list with the one in a `TodoWrite` tool input
+    ///
+    /// # Errors
+    ///
+    /// Returns `TodoError::InvalidInput` if `input` is not a `TodoWrite` input
+    pub fn apply_todo_write(&self, input: &Value) -> Result<(), TodoError> {
+        let input = TodoWriteInput::deserialize(input)
+            .map_err(|e| TodoError::InvalidInput(e.to_string()))?;
+
+        self.list.send_modify(|list| {
+            let mut previous = std::mem::take(&mut list.items);
+            list.items = input
+                .todos
+                .into_iter()
+                .map(|todo| {
+                    // Reuse the earlier item with the same content, once
+                    let mut item = match previous.iter().position(|p| p.content == todo.content) {
+                        Some(index) => previous.remove(index),
+                        None => TodoItem::new(
+                            todo.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                            todo.content,
+                        ),
+                    };
+                    item.status = todo.status.into();
+                    item.active_form = todo.active_form;
+                    if let Some(priority) = todo.priority.as_deref().and_then(parse_priority) {
+                        item.priority = priority;
+                    }
+                    item
+                })
+                .collect();
+        });
+        Ok(())
+    }
+}
+
+impl Default for TodoTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Priority as older CLI versions send it
+fn parse_priority(priority: &str) -> Option<TodoPriority> {
+    match priority {
+        "low" => Some(TodoPriority::Low),
+        "medium" => Some(TodoPriority::Medium),
+        "high" => Some(TodoPriority::High),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rewrites_keep_unchanged_items() {
+        let tracker = TodoTracker::new();
+        tracker
+            .apply_todo_write(&json!({"todos": [
+                {"content": "Read code", "status": "in_progress", "activeForm": "Reading code"},
+                {"content": "Write tests", "status": "pending", "activeForm": "Writing tests"},
+            ]}))
+            .unwrap();
+        let first = tracker.current();
+
+        tracker
+            .apply_todo_write(&json!({"todos": [
+                {"content": "Read code", "status": "completed", "activeForm": "Reading code"},
+                {"content": "Write more tests", "status": "in_progress", "activeForm": "Writing"},
+            ]}))
+            .unwrap();
+        let second = tracker.current();
+
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.items[0].id, first.items[0].id);
+        assert_eq!(second.items[0].created_at, first.items[0].created_at);
+        assert_eq!(second.items[0].status, TodoStatus::Completed);
+        assert_ne!(second.items[1].id, first.items[1].id);
+        assert_eq!(second.items[1].status, TodoStatus::InProgress);
+    }
+
+    #[test]
+    fn test_tolerates_extra_fields_and_rejects_bad_input() {
+        let tracker = TodoTracker::new();
+        tracker
+            .apply_todo_write(&json!({"todos": [
+                {"content": "Ship", "status": "pending", "id": "1", "priority": "high", "owner": "x"},
+            ], "extra": true}))
+            .unwrap();
+        let item = &tracker.current().items[0];
+        assert_eq!(item.id, "1");
+        assert_eq!(item.priority, TodoPriority::High);
+        assert!(item.active_form.is_none());
+
+        let err =
+            tracker.apply_todo_write(&json!({"todos": [{"content": "x", "status": "blocked"}]}));
+        assert!(matches!(err, Err(TodoError::InvalidInput(_))));
+        assert_eq!(tracker.current().items.len(), 1);
+    }
+}
@@ -0,0 +1,587 @@
+//! # Conversation transcripts
+//!
+//! A [`Transcript`] collects the messages of a conversation and exports them
+//! as readable markdown or as lossless JSON.
+//!
+//! Feed it messages with [`Transcript::push`], wrap a message stream with
+//! [`Transcript::record`], or let a client record on its own with
+//! [`ClaudeClient::enable_transcript`](crate::ClaudeClient::enable_transcript).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use claude_agent_sdk::{Transcript, query_stream};
+//! use futures::StreamExt;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let transcript = Transcript::new();
+//! let mut stream = transcript.record(query_stream("List the files in src/", None).await?);
+//! while let Some(message) = stream.next().await {
+//!     message?;
+//! }
+//! std::fs::write("transcript.md", transcript.to_markdown())?;
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::{Stream, StreamExt};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+use crate::errors::Result;
+use crate::internal::message_parser::MessageParser;
+use crate::types::messages::{
+    ContentBlock, ImageSource, Message, ResultMessage, ToolResultBlock, ToolResultContent,
+    UserMessage,
+};
+
+/// Default of [`MarkdownOptions::max_tool_output_chars`]
+pub const DEFAULT_MAX_TOOL_OUTPUT_CHARS: usize = 2000;
+
+/// Messages of a conversation, in the order they were sent and received
+///
+/// Clones share the recorded messages, so a transcript can be read while a
+/// stream wrapped by [`record`](Self::record) is still being consumed. Use
+/// [`snapshot`](Self::snapshot) for an independent copy.
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    messages: Arc<Mutex<Vec<Message>>>,
+}
+
+/// Item of a stream that [`Transcript::record`] can wrap
+pub trait TranscriptItem {
+    /// The message carried by the item, if any
+    fn message(&self) -> Option<&Message>;
+}
+
+impl TranscriptItem for Message {
+    fn message(&self) -> Option<&Message> {
+        Some(self)
+    }
+}
+
+impl<E> TranscriptItem for std::result::Result<Message, E> {
+    fn message(&self) -> Option<&Message> {
+        self.as_ref().ok()
+    }
+}
+
+/// How [`Transcript::to_markdown_with`] renders a transcript
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// Include Claude's thinking blocks as quotes
+    pub include_thinking: bool,
+    /// Characters of a tool result shown before it is cut off
+    pub max_tool_output_chars: usize,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            include_thinking: false,
+            max_tool_output_chars: DEFAULT_MAX_TOOL_OUTPUT_CHARS,
+        }
+    }
+}
+
+impl Transcript {
+    /// Create an empty transcript
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a transcript exported with [`to_json`](Self::to_json)
+    pub fn from_json(json: &str) -> Result<Self> {
+        let values: Vec<serde_json::Value> = serde_json::from_str(json)?;
+        let messages = values
+            .into_iter()
+            .map(MessageParser::parse)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            messages: Arc::new(Mutex::new(messages)),
+        })
+    }
+
+    /// Add a message
+    pub fn push(&self, message: Message) {
+        self.messages.lock().unwrap().push(message);
+    }
+
+    /// Pass the items of `stream` through unchanged, adding their messages
+    pub fn record<S>(&self, stream: S) -> impl Stream<Item = S::Item> + use<S>
+    where
+        S: Stream,
+        S::Item: TranscriptItem,
+    {
+        let transcript = self.clone();
+        stream.inspect(move |item| {
+            if let Some(message) = item.message() {
+                transcript.push(message.clone());
+            }
+        })
+    }
+
+    /// The recorded messages
+    pub fn messages(&self) -> Vec<Message> {
+        self.messages.lock().unwrap().clone()
+    }
+
+    /// Number of recorded messages
+    pub fn len(&self) -> usize {
+        self.messages.lock().unwrap().len()
+    }
+
+    /// Whether no message was recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every message
+    pub fn clear(&self) {
+        self.messages.lock().unwrap().clear();
+    }
+
+    /// Copy of the transcript that does not see later messages
+    pub fn snapshot(&self) -> Self {
+        Self {
+            messages: Arc::new(Mutex::new(self.messages())),
+        }
+    }
+
+    /// The messages as a JSON array, in the CLI's stream-json format
+    ///
+    /// Nothing is left out, so [`from_json`](Self::from_json) restores the
+    /// transcript.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(
+            &*self.messages.lock().unwrap(),
+        )?)
+    }
+
+    /// Render the conversation as markdown with default [`MarkdownOptions`]
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_with(&MarkdownOptions::default())
+    }
+
+    /// Render the conversation as markdown
+    ///
+    /// User and assistant turns are headed by their role. Tool calls are shown
+    /// as fenced JSON, followed by their results cut to
+    /// [`MarkdownOptions::max_tool_output_chars`]. Images are replaced by their
+    /// media type and size. Each result message adds a footer with the turn's
+    /// cost and token usage.
+    pub fn to_markdown_with(&self, options: &MarkdownOptions) -> String {
+        let mut renderer = Renderer {
+            options,
+            out: String::new(),
+            role: None,
+        };
+        for message in self.messages.lock().unwrap().iter() {
+            renderer.message(message);
+        }
+        renderer.out.trim_end().to_string() + "\n"
+    }
+}
+
+/// Turn author, to head a run of messages only once
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Speaker {
+    User,
+    Assistant,
+}
+
+struct Renderer<'a> {
+    options: &'a MarkdownOptions,
+    out: String,
+    role: Option<Speaker>,
+}
+
+impl Renderer<'_> {
+    fn message(&mut self, message: &Message) {
+        match message {
+            Message::User(user) => self.user(user),
+            Message::Assistant(assistant) => {
+                for block in &assistant.message.content {
+                    self.block(Speaker::Assistant, block);
+                }
+            },
+            Message::Result(result) => self.footer(result),
+            Message::LocalCommand(command) => {
+                self.speaker(Speaker::User);
+                self.paragraph(&format!("`{}`", command.command));
+                self.speaker(Speaker::Assistant);
+                self.fenced("", &command.output);
+            },
+            Message::System(_)
+            | Message::StreamEvent(_)
+            | Message::ControlCancelRequest(_)
+            | Message::Unknown(_) => {},
+        }
+    }
+
+    fn user(&mut self, user: &UserMessage) {
+        if let Some(text) = &user.text {
+            self.speaker(Speaker::User);
+            self.paragraph(text);
+        }
+        let blocks = match &user.content {
+            Some(blocks) => blocks.clone(),
+            None => match user.extra.get("message").and_then(|m| m.get("content")) {
+                Some(serde_json::Value::String(text)) => {
+                    vec![ContentBlock::Text(crate::types::messages::TextBlock {
+                        text: text.clone(),
+                    })]
+                },
+                Some(content) => serde_json::from_value(content.clone()).unwrap_or_default(),
+                None => Vec::new(),
+            },
+        };
+        for block in &blocks {
+            self.block(Speaker::User, block);
+        }
+    }
+
+    /// Render a block of a message by `author`
+    ///
+    /// Tool results come in user messages but continue the assistant's turn.
+    fn block(&mut self, author: Speaker, block: &ContentBlock) {
+        match block {
+            ContentBlock::Text(text) => {
+                self.speaker(author);
+                self.paragraph(&text.text);
+            },
+            ContentBlock::Thinking(thinking) if self.options.include_thinking => {
+                self.speaker(Speaker::Assistant);
+                let quoted: Vec<String> = thinking
+                    .thinking
+                    .lines()
+                    .map(|line| format!("> {}", line).trim_end().to_string())
+                    .collect();
+                self.paragraph(&format!("> **Thinking**\n>\n{}", quoted.join("\n")));
+            },
+            ContentBlock::Thinking(_) => {},
+            ContentBlock::ToolUse(tool_use) => {
+                self.speaker(Speaker::Assistant);
+                self.paragraph(&format!(
+                    "**Tool call:** `{}` (`{}`)",
+                    tool_use.name, tool_use.id
+                ));
+                let input = serde_json::to_string_pretty(&tool_use.input).unwrap_or_default();
+                self.fenced("json", &input);
+            },
+            ContentBlock::ToolResult(result) => self.tool_result(result),
+            ContentBlock::Image(image) => {
+                self.speaker(author);
+                self.paragraph(&image_placeholder(&image.source));
+            },
+            ContentBlock::Unknown(value) => {
+                let kind = value
+                    .get("type")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("unknown");
+                self.speaker(author);
+                self.paragraph(&format!("*[{} block]*", kind));
+            },
+        }
+    }
+
+    fn tool_result(&mut self, result: &ToolResultBlock) {
+        self.speaker(Speaker::Assistant);
+        let label = if result.is_error == Some(true) {
+            "Tool error"
+        } else {
+            "Tool result"
+        };
+        self.paragraph(&format!("**{}:** `{}`", label, result.tool_use_id));
+
+        let output = match &result.content {
+            None => String::new(),
+            Some(ToolResultContent::Text(text)) => text.clone(),
+            Some(ToolResultContent::Blocks(blocks)) => blocks
+                .iter()
+                .map(
+                    |block| match serde_json::from_value::<ContentBlock>(block.clone()) {
+                        Ok(ContentBlock::Text(text)) => text.text,
+                        Ok(ContentBlock::Image(image)) => image_placeholder(&image.source),
+                        _ => block.to_string(),
+                    },
+                )
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        self.fenced("", &truncate(&output, self.options.max_tool_output_chars));
+    }
+
+    fn footer(&mut self, result: &ResultMessage) {
+        let mut parts = vec![format!("{} turns", result.num_turns)];
+        parts.push(format!("{:.1} s", result.duration_ms as f64 / 1000.0));
+        if let Some(cost) = result.total_cost_usd {
+            parts.push(format!("${:.4}", cost));
+        }
+        if let Some(usage) = &result.token_usage {
+            parts.push(format!(
+                "{} input / {} output tokens",
+                usage.input_tokens, usage.output_tokens
+            ));
+        }
+        let status = if result.is_error {
+            format!("Error ({})", result.subtype)
+        } else {
+            "Done".to_string()
+        };
+        self.paragraph(&format!("---\n\n*{}: {}*", status, parts.join(" · ")));
+        self.role = None;
+    }
+
+    /// Head the following content with `speaker` unless it already is
+    fn speaker(&mut self, speaker: Speaker) {
+        if self.role != Some(speaker) {
+            self.role = Some(speaker);
+            let heading = match speaker {
+                Speaker::User => "## User",
+                Speaker::Assistant => "## Assistant",
+            };
+            self.paragraph(heading);
+        }
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        self.out.push_str(text.trim_end());
+        self.out.push_str("\n\n");
+    }
+
+    /// Fence `text` with more backticks than it contains in a row
+    fn fenced(&mut self, language: &str, text: &str) {
+        let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+        let _ = write!(
+            self.out,
+            "{}{}\n{}\n{}\n\n",
+            fence,
+            language,
+            text.trim_end(),
+            fence
+        );
+    }
+}
+
+/// `text` cut to `max` characters, noting how much was left out
+fn truncate(text: &str, max: usize) -> String {
+    let total = text.chars().count();
+    if total <= max {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max).collect();
+    format!("{}\n… ({} more characters)", kept, total - max)
+}
+
+/// Short description of an image in place of its data
+fn image_placeholder(source: &ImageSource) -> String {
+    match source {
+        ImageSource::Base64 { media_type, data } => {
+            let padding = data.bytes().rev().take_while(|b| *b == b'=').count();
+            let bytes = (data.len() / 4 * 3).saturating_sub(padding);
+            format!("*[image: {}, {}]*", media_type, format_size(bytes))
+        },
+        ImageSource::Url { url } => format!("*[image: {}]*", url),
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(value: serde_json::Value) -> Message {
+        MessageParser::parse(value).unwrap()
+    }
+
+    fn prompt(text: &str) -> Message {
+        message(json!({
+            "type": "user",
+            "message": {"role": "user", "content": text},
+            "session_id": "default"
+        }))
+    }
+
+    fn assistant(content: serde_json::Value) -> Message {
+        message(json!({
+            "type": "assistant",
+            "message": {"content": content, "model": "claude-sonnet-4-5"},
+            "session_id": "s1"
+        }))
+    }
+
+    fn tool_result(id: &str, content: serde_json::Value, is_error: bool) -> Message {
+        message(json!({
+            "type": "user",
+            "message": {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": id, "content": content, "is_error": is_error}
+            ]},
+            "parent_tool_use_id": null,
+            "session_id": "s1"
+        }))
+    }
+
+    fn result(cost: f64) -> Message {
+        message(json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 2500,
+            "duration_api_ms": 2000,
+            "is_error": false,
+            "num_turns": 2,
+            "session_id": "s1",
+            "total_cost_usd": cost,
+            "usage": {"input_tokens": 120, "output_tokens": 45}
+        }))
+    }
+
+    fn conversation() -> Transcript {
+        let transcript = Transcript::new();
+        for message in [
+            message(json!({"type": "system", "subtype": "init", "session_id": "s1"})),
+            prompt("List the files"),
+            assistant(json!([
+                {"type": "thinking", "thinking": "Use ls.\nThen answer.", "signature": "sig"},
+                {"type": "text", "text": "Let me look."},
+                {"type": "tool_use", "id": "tool_1", "name": "Bash", "input": {"command": "ls"}}
+            ])),
+            tool_result("tool_1", json!("Cargo.toml\nsrc"), false),
+            assistant(json!([{"type": "text", "text": "There are two entries."}])),
+            result(0.0123),
+            prompt("Thanks"),
+            assistant(json!([{"type": "text", "text": "You're welcome."}])),
+            result(0.002),
+        ] {
+            transcript.push(message);
+        }
+        transcript
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = conversation().to_markdown();
+        assert_eq!(
+            markdown,
+            "## User\n\nList the files\n\n\
+             ## Assistant\n\nLet me look.\n\n\
+             **Tool call:** `Bash` (`tool_1`)\n\n```json\n{\n  \"command\": \"ls\"\n}\n```\n\n\
+             **Tool result:** `tool_1`\n\n```\nCargo.toml\nsrc\n```\n\n\
+             There are two entries.\n\n\
+             ---\n\n*Done: 2 turns · 2.5 s · $0.0123 · 120 input / 45 output tokens*\n\n\
+             ## User\n\nThanks\n\n\
+             ## Assistant\n\nYou're welcome.\n\n\
+             ---\n\n*Done: 2 turns · 2.5 s · $0.0020 · 120 input / 45 output tokens*\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_with_thinking() {
+        let options = MarkdownOptions {
+            include_thinking: true,
+            ..MarkdownOptions::default()
+        };
+        let markdown = conversation().to_markdown_with(&options);
+        assert!(markdown.contains(
+            "## Assistant\n\n> **Thinking**\n>\n> Use ls.\n> Then answer.\n\nLet me look."
+        ));
+    }
+
+    #[test]
+    fn test_tool_output_is_truncated_and_fenced() {
+        let transcript = Transcript::new();
+        transcript.push(tool_result("tool_1", json!("x".repeat(30)), true));
+        transcript.push(tool_result(
+            "tool_2",
+            json!("```rust\nfn main() {}\n```"),
+            false,
+        ));
+
+        let options = MarkdownOptions {
+            max_tool_output_chars: 10,
+            ..MarkdownOptions::default()
+        };
+        let markdown = transcript.to_markdown_with(&options);
+        assert!(
+            markdown.contains(
+                "**Tool error:** `tool_1`\n\n```\nxxxxxxxxxx\n… (20 more characters)\n```"
+            )
+        );
+        assert!(markdown.contains("````\n```rust\nfn\n… (14 more characters)\n````"));
+    }
+
+    #[test]
+    fn test_images_become_placeholders() {
+        let data = "A".repeat(4096);
+        let transcript = Transcript::new();
+        transcript.push(message(json!({
+            "type": "user",
+            "message": {"role": "user", "content": [
+                {"type": "text", "text": "What is this?"},
+                {"type": "image", "source": {
+                    "type": "base64", "media_type": "image/png", "data": data
+                }}
+            ]}
+        })));
+        transcript.push(tool_result(
+            "tool_1",
+            json!([{
+                "type": "image",
+                "source": {"type": "url", "url": "https://example.com/a.png"}
+            }]),
+            false,
+        ));
+
+        let markdown = transcript.to_markdown();
+        assert!(markdown.contains("What is this?\n\n*[image: image/png, 3.0 KB]*"));
+        assert!(markdown.contains("*[image: https://example.com/a.png]*"));
+        assert!(!markdown.contains("AAAA"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let transcript = conversation();
+        let json = transcript.to_json().unwrap();
+        let restored = Transcript::from_json(&json).unwrap();
+
+        assert_eq!(restored.len(), transcript.len());
+        assert_eq!(restored.to_json().unwrap(), json);
+        assert_eq!(restored.to_markdown(), transcript.to_markdown());
+        let Message::Result(result) = &restored.messages()[5] else {
+            panic!("expected a result message");
+        };
+        assert_eq!(result.token_usage.as_ref().unwrap().input_tokens, 120);
+    }
+
+    #[tokio::test]
+    async fn test_record_passes_items_through() {
+        let transcript = Transcript::new();
+        let items: Vec<Result<Message>> = vec![
+            Ok(prompt("Hi")),
+            Err(crate::ClaudeError::Transport("dropped".to_string())),
+            Ok(result(0.001)),
+        ];
+
+        let recorded: Vec<_> = transcript
+            .record(futures::stream::iter(items))
+            .collect()
+            .await;
+        assert_eq!(recorded.len(), 3);
+        assert!(recorded[1].is_err());
+        assert_eq!(transcript.len(), 2);
+
+        let snapshot = transcript.snapshot();
+        transcript.push(prompt("More"));
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(transcript.len(), 3);
+    }
+}
@@ -0,0 +1,49 @@
+//! File checkpoint types
+//!
+//! With `enable_file_checkpointing` the CLI snapshots tracked files at every
+//! user prompt. [`ClaudeClient`](crate::ClaudeClient) records those prompts as
+//! [`CheckpointInfo`]s, together with the file edits Claude made after each one,
+//! so a rewind target can be picked without collecting message UUIDs by hand.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A user prompt that files can be rewound to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    /// UUID of the user message, as passed to
+    /// [`rewind_files`](crate::ClaudeClient::rewind_files)
+    pub user_message_id: String,
+    /// When the prompt was sent
+    pub created_at: DateTime<Utc>,
+    /// Start of the prompt text
+    pub prompt_preview: String,
+    /// Number of distinct files edited since this checkpoint
+    ///
+    /// `None` for checkpoints replayed from before the client connected, whose
+    /// later edits were not all observed.
+    pub files_changed_since: Option<usize>,
+}
+
+/// A file edited since a checkpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    /// Path as given to the editing tool
+    pub path: String,
+    /// Tools that edited the file, in order (e.g. `Write`, `Edit`)
+    pub tools: Vec<String>,
+}
+
+/// What rewinding to a checkpoint would change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointDiff {
+    /// The checkpoint
+    pub checkpoint: CheckpointInfo,
+    /// Files that would be restored, in order of first edit
+    pub files: Vec<FileChange>,
+    /// Whether every edit since the checkpoint was observed
+    ///
+    /// `false` for replayed checkpoints: files edited before the client
+    /// connected are restored too but are not listed.
+    pub complete: bool,
+}
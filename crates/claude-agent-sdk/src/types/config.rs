@@ -4,16 +4,160 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use typed_builder::TypedBuilder;
 
 use super::hooks::{HookEvent, HookMatcher};
 use super::mcp::McpServers;
 use super::permissions::CanUseToolCallback;
 use super::plugin::SdkPluginConfig;
+use crate::secrets::{DEFAULT_SECRET_ENV, SecretProvider, SecretString};
+
+/// Default time to wait for the result after interrupting a turn past its deadline
+pub const DEFAULT_DEADLINE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Default number of turns [`TurnPolicy::Queue`] holds back
+pub const DEFAULT_TURN_QUEUE_CAPACITY: usize = 16;
+
+/// Default fraction of the budget at which [`ClaudeAgentOptions::on_budget_warning`] is called
+pub const DEFAULT_BUDGET_SOFT_LIMIT_RATIO: f64 = 0.8;
+
+/// What [`ClaudeClient`](crate::ClaudeClient) does with a query sent while a turn is running
+///
+/// A turn runs from sending a prompt until its result message has been received
+/// through [`receive_response`](crate::ClaudeClient::receive_response) or
+/// [`receive_messages`](crate::ClaudeClient::receive_messages).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnPolicy {
+    /// Fail with [`ClaudeError::TurnInProgress`](crate::ClaudeError::TurnInProgress)
+    #[default]
+    Reject,
+    /// Hold the query back and send it when the running turn's result arrives
+    ///
+    /// At most [`ClaudeAgentOptions::turn_queue_capacity`] queries wait at a time.
+    Queue,
+    /// Send right away, interleaving the messages of both turns
+    Interleave,
+}
+
+/// Phase of establishing a connection to the CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectPhase {
+    /// Locating the CLI executable (or installing it)
+    Discovery,
+    /// Running `claude --version` to check compatibility
+    VersionCheck,
+    /// Resolving secrets and starting the CLI process
+    Spawn,
+    /// Initialize control request round-trip
+    Initialize,
+}
+
+impl std::fmt::Display for ConnectPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ConnectPhase::Discovery => "CLI discovery",
+            ConnectPhase::VersionCheck => "version check",
+            ConnectPhase::Spawn => "process spawn",
+            ConnectPhase::Initialize => "initialize handshake",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Connection status of a [`ClaudeClient`](crate::ClaudeClient)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// Not connected; `connect()` starts a new CLI process
+    #[default]
+    Disconnected,
+    /// A `connect()` call is in flight
+    Connecting,
+    /// Connected and ready for queries
+    Connected,
+    /// A `disconnect()` call is in flight
+    Disconnecting,
+}
+
+/// Progress of a connection attempt, reported through
+/// [`ClaudeAgentOptions::connect_progress`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectProgress {
+    /// A phase started
+    Started(ConnectPhase),
+    /// A phase finished successfully
+    Completed {
+        /// The finished phase
+        phase: ConnectPhase,
+        /// Time spent in the phase
+        elapsed: Duration,
+    },
+    /// A phase was skipped, e.g. because it is disabled or timed out non-fatally
+    Skipped {
+        /// The skipped phase
+        phase: ConnectPhase,
+        /// Why the phase was skipped
+        reason: String,
+    },
+}
+
+/// Callback receiving [`ConnectProgress`] events
+pub type ConnectProgressCallback = Arc<dyn Fn(ConnectProgress) + Send + Sync>;
+
+/// Callback receiving a [`BudgetWarning`](crate::BudgetWarning) when spending nears the budget
+pub type BudgetWarningCallback = Arc<dyn Fn(crate::types::usage::BudgetWarning) + Send + Sync>;
+
+/// Time limits for the phases of establishing a connection
+///
+/// A version check that exceeds its limit is skipped with a warning; every
+/// other phase fails with [`ClaudeError::ConnectTimeout`](crate::ClaudeError::ConnectTimeout).
+/// CLI auto-installation is not bounded by `discovery`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectTimeouts {
+    /// Locating the CLI executable (default: 10s)
+    #[serde(with = "super::config_file::duration")]
+    pub discovery: Duration,
+    /// Running `claude --version` (default: 5s)
+    #[serde(with = "super::config_file::duration")]
+    pub version_check: Duration,
+    /// Resolving secrets and starting the process (default: 30s)
+    #[serde(with = "super::config_file::duration")]
+    pub spawn: Duration,
+    /// Initialize control request (default: 60s)
+    #[serde(with = "super::config_file::duration")]
+    pub initialize: Duration,
+}
+
+impl Default for ConnectTimeouts {
+    fn default() -> Self {
+        Self {
+            discovery: Duration::from_secs(10),
+            version_check: Duration::from_secs(5),
+            spawn: Duration::from_secs(30),
+            initialize: Duration::from_secs(60),
+        }
+    }
+}
 
 /// Main configuration options for Claude Agent
-#[derive(Clone, TypedBuilder)]
+///
+/// # Configuration files
+///
+/// Options can be loaded from TOML or JSON files with
+/// [`from_toml_file`](Self::from_toml_file) and
+/// [`from_json_file`](Self::from_json_file), using the field names as keys.
+/// Durations are written as seconds or strings such as `"500ms"` and `"2m"`,
+/// and enums as the CLI writes them, e.g. `permission_mode = "acceptEdits"`.
+/// Callbacks, hooks, secret providers, collectors and in-process MCP servers
+/// are code only: they are skipped when serializing and ignored in files. Add
+/// them with [`merge`](Self::merge).
+#[derive(Clone, TypedBuilder, Serialize, Deserialize)]
 #[builder(doc)]
+#[serde(default)]
 pub struct ClaudeAgentOptions {
     /// Tools configuration (list of tool names or preset)
     #[builder(default, setter(strip_option))]
@@ -24,8 +168,20 @@ pub struct ClaudeAgentOptions {
     /// System prompt configuration
     #[builder(default, setter(into, strip_option))]
     pub system_prompt: Option<SystemPrompt>,
+    /// System prompt composed from templated sections, rendered on connect
+    ///
+    /// Rendering errors fail the connection with
+    /// [`ClaudeError::Prompt`](crate::ClaudeError::Prompt). Setting both this and `system_prompt` is a configuration error.
+    ///
+    /// Code only: not read from or written to configuration files.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip)]
+    pub system_prompt_template: Option<crate::prompts::SystemPromptBuilder>,
     /// MCP server configuration
+    ///
+    /// In-process SDK servers are code only.
     #[builder(default)]
+    #[serde(skip_serializing_if = "McpServers::is_empty")]
     pub mcp_servers: McpServers,
     /// Permission mode
     #[builder(default, setter(strip_option))]
@@ -55,6 +211,32 @@ pub struct ClaudeAgentOptions {
     /// Maximum budget in USD for the conversation
     #[builder(default, setter(strip_option))]
     pub max_budget_usd: Option<f64>,
+    /// Also enforce [`max_budget_usd`](Self::max_budget_usd) in
+    /// [`ClaudeClient`](crate::ClaudeClient), whatever the CLI version does with it
+    ///
+    /// The client adds up the cost reported by result messages, plus an estimate for
+    /// the running turn from the usage on its assistant messages. When that reaches the
+    /// budget, the turn is interrupted, its remaining messages are delivered, and the
+    /// receive stream ends with [`ClaudeError::BudgetExceeded`](crate::ClaudeError::BudgetExceeded).
+    /// Later queries fail with the same error until
+    /// [`reset_usage`](crate::ClaudeClient::reset_usage).
+    ///
+    /// Default: `false`
+    #[builder(default = false)]
+    pub enforce_budget_client_side: bool,
+    /// Fraction of [`max_budget_usd`](Self::max_budget_usd) at which
+    /// [`on_budget_warning`](Self::on_budget_warning) is called
+    ///
+    /// Default: [`DEFAULT_BUDGET_SOFT_LIMIT_RATIO`]
+    #[builder(default = DEFAULT_BUDGET_SOFT_LIMIT_RATIO)]
+    pub budget_soft_limit_ratio: f64,
+    /// Called once when spending crosses the soft limit, with
+    /// [`enforce_budget_client_side`](Self::enforce_budget_client_side) set
+    ///
+    /// Code only: not read from or written to configuration files.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip)]
+    pub on_budget_warning: Option<BudgetWarningCallback>,
     /// Maximum tokens for thinking blocks
     #[builder(default, setter(strip_option))]
     pub max_thinking_tokens: Option<u32>,
@@ -76,20 +258,126 @@ pub struct ClaudeAgentOptions {
     /// Environment variables
     #[builder(default)]
     pub env: HashMap<String, String>,
+    /// Provider used to resolve sensitive environment variables at spawn time
+    ///
+    /// When set, every name in `secret_env` is resolved through this provider
+    /// right before the CLI process starts and overrides any value in `env`.
+    ///
+    /// Code only: not read from or written to configuration files.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip)]
+    pub secret_provider: Option<Arc<dyn SecretProvider>>,
+    /// Environment variable names resolved through `secret_provider`
+    ///
+    /// Default: `["ANTHROPIC_API_KEY"]`
+    #[builder(default = DEFAULT_SECRET_ENV.iter().map(|s| s.to_string()).collect(), setter(into))]
+    pub secret_env: Vec<String>,
+    /// API key for this client's CLI process, passed as `ANTHROPIC_API_KEY`
+    ///
+    /// Set only in the environment of the spawned CLI, so clients in one process can use
+    /// different credentials. Takes precedence over `env` and `secret_provider`.
+    ///
+    /// Read from configuration files, but never written to them.
+    #[builder(default, setter(into, strip_option))]
+    #[serde(skip_serializing)]
+    pub api_key: Option<SecretString>,
+    /// OAuth token for this client's CLI process, passed as `ANTHROPIC_AUTH_TOKEN`
+    ///
+    /// Like `api_key`, set only for the spawned CLI, and never written to
+    /// configuration files.
+    #[builder(default, setter(into, strip_option))]
+    #[serde(skip_serializing)]
+    pub auth_token: Option<SecretString>,
+    /// Configuration directory of the CLI, passed as `CLAUDE_CONFIG_DIR`
+    ///
+    /// Holds the CLI's stored login, settings and sessions; defaults to `~/.claude`.
+    #[builder(default, setter(into, strip_option))]
+    pub config_dir: Option<PathBuf>,
     /// Extra CLI arguments
+    ///
+    /// In configuration files, a flag without a value is written as `true`.
     #[builder(default)]
+    #[serde(with = "super::config_file::extra_args")]
     pub extra_args: HashMap<String, Option<String>>,
     /// Maximum buffer size for subprocess output
     #[builder(default, setter(strip_option))]
     pub max_buffer_size: Option<usize>,
+    /// Time budget for a whole turn, from query submission until the result message
+    ///
+    /// The budget also covers spawning and connecting to the CLI. When it runs out,
+    /// the turn is interrupted and [`ClaudeError::DeadlineExceeded`](crate::ClaudeError::DeadlineExceeded)
+    /// is returned with the messages received so far. The deadline takes precedence over
+    /// every other timeout; interrupting or dropping the call earlier still cancels it.
+    ///
+    /// Applies to [`query`](crate::query), [`query_with_content`](crate::query_with_content)
+    /// and [`ClaudeClient::query_collect`](crate::ClaudeClient::query_collect). Streaming
+    /// APIs are not bounded by it.
+    #[builder(default, setter(strip_option))]
+    #[serde(with = "super::config_file::optional_duration")]
+    pub turn_deadline: Option<Duration>,
+    /// How long to wait for the result after interrupting a turn past its deadline
+    ///
+    /// If no result arrives in time, the connection is torn down.
+    ///
+    /// Default: [`DEFAULT_DEADLINE_GRACE_PERIOD`]
+    #[builder(default = DEFAULT_DEADLINE_GRACE_PERIOD)]
+    #[serde(with = "super::config_file::duration")]
+    pub deadline_grace_period: Duration,
+    /// Longest gap allowed between two messages from the CLI during a turn
+    ///
+    /// Unlike [`turn_deadline`](Self::turn_deadline) this bounds silence rather than total
+    /// duration, so long turns keep running as long as messages keep arriving. When the
+    /// gap is exceeded, [`ClaudeError::MessageTimeout`](crate::ClaudeError::MessageTimeout)
+    /// is returned. One-shot queries kill the CLI process at that point.
+    ///
+    /// Applies to [`query`](crate::query), [`query_stream`](crate::query_stream) and their
+    /// content variants, and to [`ClaudeClient::receive_response`](crate::ClaudeClient::receive_response).
+    /// [`ClaudeClient::receive_messages`](crate::ClaudeClient::receive_messages) waits
+    /// across turns and is not bounded by it.
+    #[builder(default, setter(strip_option))]
+    #[serde(with = "super::config_file::optional_duration")]
+    pub message_timeout: Option<Duration>,
+    /// What to do with a query sent before the previous turn's result was received
+    #[builder(default)]
+    pub turn_policy: TurnPolicy,
+    /// Maximum number of queries waiting under [`TurnPolicy::Queue`]
+    ///
+    /// Default: [`DEFAULT_TURN_QUEUE_CAPACITY`]
+    #[builder(default = DEFAULT_TURN_QUEUE_CAPACITY)]
+    pub turn_queue_capacity: usize,
+    /// Reconnect once when a [`ClaudeClient`](crate::ClaudeClient) query finds the
+    /// CLI process dead, instead of failing
+    ///
+    /// See [`ClaudeClient::reconnect`](crate::ClaudeClient::reconnect).
+    #[builder(default = false)]
+    pub auto_reconnect: bool,
+    /// Compact the conversation before a [`ClaudeClient`](crate::ClaudeClient)
+    /// query once its context holds this many tokens
+    ///
+    /// The context size is [`UsageSnapshot::context_tokens`](crate::UsageSnapshot::context_tokens).
+    /// When a query is sent while no turn is running and the size has reached
+    /// the threshold, [`ClaudeClient::compact`](crate::ClaudeClient::compact) runs
+    /// first. If a `PreCompact` hook blocks it, the query is sent anyway and the
+    /// next one tries again. The CLI's own automatic compaction is unaffected.
+    #[builder(default, setter(strip_option))]
+    pub auto_compact_threshold_tokens: Option<u64>,
     /// Callback for stderr output
+    ///
+    /// Code only: not read from or written to configuration files.
     #[builder(default, setter(strip_option))]
+    #[serde(skip)]
     pub stderr_callback: Option<Arc<dyn Fn(String) + Send + Sync>>,
     /// Callback for tool usage permission
+    ///
+    /// Code only: not read from or written to configuration files.
     #[builder(default, setter(strip_option))]
+    #[serde(skip)]
     pub can_use_tool: Option<CanUseToolCallback>,
     /// Hook callbacks
+    ///
+    /// Code only: not read from or written to configuration files.
     #[builder(default, setter(strip_option))]
+    #[serde(skip)]
     pub hooks: Option<HashMap<HookEvent, Vec<HookMatcher>>>,
     /// User identifier
     #[builder(default, setter(into, strip_option))]
@@ -119,8 +407,17 @@ pub struct ClaudeAgentOptions {
     /// Plugin configurations for custom plugins
     #[builder(default, setter(into))]
     pub plugins: Vec<SdkPluginConfig>,
+    /// Directory whose plugin subdirectories are loaded along with [`plugins`](Self::plugins)
+    ///
+    /// Plugins are found with [`SdkPluginConfig::discover`] when the CLI is
+    /// started; those failing [`SdkPluginConfig::validate`] are skipped with a
+    /// warning. A relative path is resolved against [`cwd`](Self::cwd).
+    #[builder(default, setter(into, strip_option))]
+    pub plugins_dir: Option<PathBuf>,
     /// Output format for structured outputs (matches Messages API structure)
     /// Example: `json!({"type": "json_schema", "schema": {"type": "object", "properties": {...}}})`
+    ///
+    /// [`json_schema_output_format`] builds it from a Rust type.
     #[builder(default, setter(strip_option))]
     pub output_format: Option<serde_json::Value>,
     /// Enable file checkpointing to track file changes during the session.
@@ -128,6 +425,12 @@ pub struct ClaudeAgentOptions {
     /// using `ClaudeClient.rewind_files()`.
     #[builder(default = false)]
     pub enable_file_checkpointing: bool,
+    /// Follow the todo list Claude keeps with its `TodoWrite` tool
+    ///
+    /// When enabled, [`ClaudeClient::todos`](crate::ClaudeClient::todos) is updated
+    /// from the messages it receives.
+    #[builder(default = false)]
+    pub track_todos: bool,
     /// Enable automatic discovery and loading of SKILL.md files
     ///
     /// When enabled, the SDK will automatically scan and load skills from
@@ -161,8 +464,84 @@ pub struct ClaudeAgentOptions {
     /// Callback for CLI installation progress
     ///
     /// Provides real-time updates during automatic CLI installation.
+    ///
+    /// Code only: not read from or written to configuration files.
     #[builder(default, setter(strip_option))]
+    #[serde(skip)]
     pub cli_install_callback: Option<Arc<dyn Fn(crate::internal::cli_installer::InstallProgress) + Send + Sync>>,
+    /// Exact CLI version for the auto-installer, such as `"2.0.14"`
+    ///
+    /// With auto-install enabled, the SDK runs this version from the CLI cache,
+    /// downloading and verifying it first if needed, instead of looking for an
+    /// installed CLI. Installation fails if the version cannot be downloaded or
+    /// is older than [`MIN_CLI_VERSION`](crate::version::MIN_CLI_VERSION).
+    #[builder(default, setter(into, strip_option))]
+    pub cli_version: Option<String>,
+    /// Directory where the auto-installer keeps downloaded CLI binaries
+    ///
+    /// Binaries in it are reused without going online, which lets machines
+    /// without network access share a populated cache. Defaults to
+    /// [`CliInstaller::default_cache_dir`](crate::CliInstaller::default_cache_dir).
+    #[builder(default, setter(into, strip_option))]
+    pub cli_cache_dir: Option<PathBuf>,
+    /// Time limits for each phase of connecting to the CLI
+    #[builder(default)]
+    pub connect_timeouts: ConnectTimeouts,
+    /// Check the MCP servers before starting the CLI
+    ///
+    /// [`ClaudeClient::connect`](crate::ClaudeClient::connect) runs
+    /// [`McpServers::verify`] and fails with
+    /// [`ClaudeError::McpServersUnhealthy`](crate::ClaudeError::McpServersUnhealthy)
+    /// naming every server that did not answer the handshake.
+    ///
+    /// Default: `false`
+    #[builder(default = false)]
+    pub verify_mcp_servers: bool,
+    /// Callback for connection progress, e.g. to show "starting Claude Code…"
+    ///
+    /// Code only: not read from or written to configuration files.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip)]
+    pub connect_progress: Option<ConnectProgressCallback>,
+    /// Collector for SDK metrics such as
+    /// [`FALLBACK_ACTIVATIONS_METRIC`](crate::observability::metrics::FALLBACK_ACTIVATIONS_METRIC)
+    ///
+    /// When set, `query()`, `query_stream()` and [`ClaudeClient`](crate::ClaudeClient) also
+    /// record query counts, latencies, tool calls and cost, and `ClaudeClient` records how
+    /// long hooks ran; see the `*_METRIC` constants in
+    /// [`observability::metrics`](crate::observability::metrics).
+    ///
+    /// Code only: not read from or written to configuration files.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip)]
+    pub metrics: Option<Arc<crate::observability::MetricsCollector>>,
+    /// Audit log recording every tool use and tool result
+    ///
+    /// `query()`, `query_stream()` and [`ClaudeClient`](crate::ClaudeClient) take the
+    /// events from the message stream, so no hooks are needed; see
+    /// [`observability::audit`](crate::observability::audit).
+    ///
+    /// Code only: not read from or written to configuration files.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip)]
+    pub audit_sink: Option<Arc<crate::observability::AuditLog>>,
+    /// Fail on messages and content blocks of unknown types
+    ///
+    /// By default they are passed on as [`Message::Unknown`](crate::Message::Unknown) and
+    /// [`ContentBlock::Unknown`](crate::ContentBlock::Unknown), so that newer CLI versions
+    /// don't break the message stream.
+    ///
+    /// Default: `false`
+    #[builder(default = false)]
+    pub strict_parsing: bool,
+    /// Record every frame sent to and received from the CLI to this file
+    ///
+    /// The file is JSONL of [`RecordedFrame`](crate::RecordedFrame)s, written by a
+    /// [`SessionRecorder`](crate::SessionRecorder) and replayed without the CLI by
+    /// [`ReplayTransport`](crate::testing::ReplayTransport). It is replaced on each
+    /// connection, and holds prompts and answers in full.
+    #[builder(default, setter(into, strip_option))]
+    pub record_to: Option<PathBuf>,
 }
 
 impl Default for ClaudeAgentOptions {
@@ -171,6 +550,103 @@ impl Default for ClaudeAgentOptions {
     }
 }
 
+impl std::fmt::Debug for ClaudeAgentOptions {
+    /// Credentials are redacted, and of `env` only the names are shown
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mcp_servers = match &self.mcp_servers {
+            McpServers::Empty => None,
+            McpServers::Dict(servers) => Some(format!("{:?}", servers.keys().collect::<Vec<_>>())),
+            McpServers::Path(path) => Some(path.display().to_string()),
+        };
+        let hooks = self.hooks.as_ref().map(|hooks| hooks.keys().collect::<Vec<_>>());
+        let env = self.env.keys().collect::<Vec<_>>();
+        let secret_provider = self.secret_provider.as_ref().map(|provider| provider.name());
+
+        f.debug_struct("ClaudeAgentOptions")
+            .field("tools", &self.tools)
+            .field("allowed_tools", &self.allowed_tools)
+            .field("system_prompt", &self.system_prompt)
+            .field("system_prompt_template", &self.system_prompt_template)
+            .field("mcp_servers", &mcp_servers)
+            .field("permission_mode", &self.permission_mode)
+            .field("continue_conversation", &self.continue_conversation)
+            .field("resume", &self.resume)
+            .field("max_turns", &self.max_turns)
+            .field("disallowed_tools", &self.disallowed_tools)
+            .field("model", &self.model)
+            .field("fallback_model", &self.fallback_model)
+            .field("betas", &self.betas)
+            .field("max_budget_usd", &self.max_budget_usd)
+            .field("enforce_budget_client_side", &self.enforce_budget_client_side)
+            .field("budget_soft_limit_ratio", &self.budget_soft_limit_ratio)
+            .field("on_budget_warning", &self.on_budget_warning.as_ref().map(|_| "<function>"))
+            .field("max_thinking_tokens", &self.max_thinking_tokens)
+            .field("permission_prompt_tool_name", &self.permission_prompt_tool_name)
+            .field("cwd", &self.cwd)
+            .field("cli_path", &self.cli_path)
+            .field("settings", &self.settings)
+            .field("add_dirs", &self.add_dirs)
+            .field("env", &env)
+            .field("secret_provider", &secret_provider)
+            .field("secret_env", &self.secret_env)
+            .field("api_key", &self.api_key)
+            .field("auth_token", &self.auth_token)
+            .field("config_dir", &self.config_dir)
+            .field("extra_args", &self.extra_args)
+            .field("max_buffer_size", &self.max_buffer_size)
+            .field("turn_deadline", &self.turn_deadline)
+            .field("deadline_grace_period", &self.deadline_grace_period)
+            .field("message_timeout", &self.message_timeout)
+            .field("turn_policy", &self.turn_policy)
+            .field("turn_queue_capacity", &self.turn_queue_capacity)
+            .field("auto_reconnect", &self.auto_reconnect)
+            .field("auto_compact_threshold_tokens", &self.auto_compact_threshold_tokens)
+            .field("stderr_callback", &self.stderr_callback.as_ref().map(|_| "<function>"))
+            .field("can_use_tool", &self.can_use_tool.as_ref().map(|_| "<function>"))
+            .field("hooks", &hooks)
+            .field("user", &self.user)
+            .field("include_partial_messages", &self.include_partial_messages)
+            .field("fork_session", &self.fork_session)
+            .field("agents", &self.agents)
+            .field("setting_sources", &self.setting_sources)
+            .field("sandbox", &self.sandbox)
+            .field("plugins", &self.plugins)
+            .field("plugins_dir", &self.plugins_dir)
+            .field("output_format", &self.output_format)
+            .field("enable_file_checkpointing", &self.enable_file_checkpointing)
+            .field("track_todos", &self.track_todos)
+            .field("auto_discover_skills", &self.auto_discover_skills)
+            .field("project_skills_dir", &self.project_skills_dir)
+            .field("user_skills_dir", &self.user_skills_dir)
+            .field("auto_install_cli", &self.auto_install_cli)
+            .field(
+                "cli_install_callback",
+                &self.cli_install_callback.as_ref().map(|_| "<function>"),
+            )
+            .field("cli_version", &self.cli_version)
+            .field("cli_cache_dir", &self.cli_cache_dir)
+            .field("connect_timeouts", &self.connect_timeouts)
+            .field("verify_mcp_servers", &self.verify_mcp_servers)
+            .field("connect_progress", &self.connect_progress.as_ref().map(|_| "<function>"))
+            .field("metrics", &self.metrics.as_ref().map(|_| "<collector>"))
+            .field("audit_sink", &self.audit_sink.as_ref().map(|_| "<audit log>"))
+            .field("strict_parsing", &self.strict_parsing)
+            .field("record_to", &self.record_to)
+            .finish()
+    }
+}
+
+/// [`ClaudeAgentOptions::output_format`] asking for output matching `T`
+///
+/// The JSON schema is generated from `T`, see
+/// [`tool_input_schema`](crate::types::mcp::tool_input_schema).
+pub fn json_schema_output_format<T: schemars::JsonSchema>() -> serde_json::Value {
+    serde_json::json!({
+        "type": "json_schema",
+        "schema": crate::types::mcp::tool_input_schema::<T>(),
+    })
+}
+
 /// System prompt configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -0,0 +1,841 @@
+//! Loading [`ClaudeAgentOptions`] from TOML and JSON files, and combining them
+//! with options set in code
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserializer;
+use serde::de::{DeserializeOwned, Visitor};
+use serde_json::Value;
+use tracing::warn;
+
+use super::config::{
+    ClaudeAgentOptions, DEFAULT_BUDGET_SOFT_LIMIT_RATIO, DEFAULT_DEADLINE_GRACE_PERIOD,
+    DEFAULT_TURN_QUEUE_CAPACITY,
+};
+use super::mcp::McpServers;
+use crate::errors::{ClaudeError, Result};
+use crate::secrets::DEFAULT_SECRET_ENV;
+
+impl ClaudeAgentOptions {
+    /// Load options from a JSON file
+    ///
+    /// See [Configuration files](Self#configuration-files) for the format.
+    /// `${VAR}` in path options is replaced by the environment variable `VAR`,
+    /// and keys that are not options are logged as a warning.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read, is not valid JSON, has an option of
+    /// the wrong type, or refers to an environment variable that is not set.
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        Self::from_json_str(&read(path)?).map_err(|e| in_file(path, e))
+    }
+
+    /// Parse options from JSON text, like [`from_json_file`](Self::from_json_file)
+    ///
+    /// # Errors
+    ///
+    /// See [`from_json_file`](Self::from_json_file).
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let value = serde_json::from_str(json)
+            .map_err(|e| ClaudeError::InvalidConfig(format!("invalid JSON: {}", e)))?;
+        from_value(value)
+    }
+
+    /// Load options from a TOML file
+    ///
+    /// See [Configuration files](Self#configuration-files) for the format.
+    /// `${VAR}` in path options is replaced by the environment variable `VAR`,
+    /// and keys that are not options are logged as a warning.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::ClaudeAgentOptions;
+    ///
+    /// # fn example() -> claude_agent_sdk::Result<()> {
+    /// // agent.toml:
+    /// //   model = "sonnet"
+    /// //   permission_mode = "acceptEdits"
+    /// //   turn_deadline = "5m"
+    /// //   cwd = "${HOME}/projects/app"
+    /// let options = ClaudeAgentOptions::from_toml_file("agent.toml")?.merge(
+    ///     ClaudeAgentOptions::builder()
+    ///         .stderr_callback(std::sync::Arc::new(|line| eprintln!("{}", line)))
+    ///         .build(),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read, is not valid TOML, has an option of
+    /// the wrong type, or refers to an environment variable that is not set.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        Self::from_toml_str(&read(path)?).map_err(|e| in_file(path, e))
+    }
+
+    /// Parse options from TOML text, like [`from_toml_file`](Self::from_toml_file)
+    ///
+    /// # Errors
+    ///
+    /// See [`from_toml_file`](Self::from_toml_file).
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        let value = toml::from_str(toml)
+            .map_err(|e| ClaudeError::InvalidConfig(format!("invalid TOML: {}", e)))?;
+        from_value(value)
+    }
+
+    /// Combine these options, typically loaded from a file, with `overrides`
+    /// set in code
+    ///
+    /// An option of `overrides` replaces the one here unless it has its default
+    /// value, so a file can choose the model while code adds hooks and
+    /// callbacks. The maps `env`, `extra_args`, `agents` and named MCP servers
+    /// are combined, with `overrides` winning for keys in both, and hook
+    /// matchers of `overrides` run after those here. Since `false` is the
+    /// default of every flag, an override cannot turn off a flag set here.
+    pub fn merge(self, overrides: ClaudeAgentOptions) -> Self {
+        // Destructured so that a new option cannot be forgotten here
+        let ClaudeAgentOptions {
+            tools,
+            allowed_tools,
+            system_prompt,
+            system_prompt_template,
+            mcp_servers,
+            permission_mode,
+            continue_conversation,
+            resume,
+            max_turns,
+            disallowed_tools,
+            model,
+            fallback_model,
+            betas,
+            max_budget_usd,
+            enforce_budget_client_side,
+            budget_soft_limit_ratio,
+            on_budget_warning,
+            max_thinking_tokens,
+            permission_prompt_tool_name,
+            cwd,
+            cli_path,
+            settings,
+            add_dirs,
+            env,
+            secret_provider,
+            secret_env,
+            api_key,
+            auth_token,
+            config_dir,
+            extra_args,
+            max_buffer_size,
+            turn_deadline,
+            deadline_grace_period,
+            message_timeout,
+            turn_policy,
+            turn_queue_capacity,
+            auto_reconnect,
+            auto_compact_threshold_tokens,
+            stderr_callback,
+            can_use_tool,
+            hooks,
+            user,
+            include_partial_messages,
+            fork_session,
+            agents,
+            setting_sources,
+            sandbox,
+            plugins,
+            plugins_dir,
+            output_format,
+            enable_file_checkpointing,
+            track_todos,
+            auto_discover_skills,
+            project_skills_dir,
+            user_skills_dir,
+            auto_install_cli,
+            cli_install_callback,
+            cli_version,
+            cli_cache_dir,
+            connect_timeouts,
+            verify_mcp_servers,
+            connect_progress,
+            metrics,
+            audit_sink,
+            strict_parsing,
+            record_to,
+        } = overrides;
+
+        let default_secret_env: Vec<String> = DEFAULT_SECRET_ENV
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        // A system prompt and a template conflict, so they are overridden together
+        let (system_prompt, system_prompt_template) =
+            if system_prompt.is_some() || system_prompt_template.is_some() {
+                (system_prompt, system_prompt_template)
+            } else {
+                (self.system_prompt, self.system_prompt_template)
+            };
+
+        Self {
+            tools: tools.or(self.tools),
+            allowed_tools: non_empty(self.allowed_tools, allowed_tools),
+            system_prompt,
+            system_prompt_template,
+            mcp_servers: merge_mcp_servers(self.mcp_servers, mcp_servers),
+            permission_mode: permission_mode.or(self.permission_mode),
+            continue_conversation: self.continue_conversation || continue_conversation,
+            resume: resume.or(self.resume),
+            max_turns: max_turns.or(self.max_turns),
+            disallowed_tools: non_empty(self.disallowed_tools, disallowed_tools),
+            model: model.or(self.model),
+            fallback_model: fallback_model.or(self.fallback_model),
+            betas: non_empty(self.betas, betas),
+            max_budget_usd: max_budget_usd.or(self.max_budget_usd),
+            enforce_budget_client_side: self.enforce_budget_client_side
+                || enforce_budget_client_side,
+            budget_soft_limit_ratio: changed(
+                self.budget_soft_limit_ratio,
+                budget_soft_limit_ratio,
+                DEFAULT_BUDGET_SOFT_LIMIT_RATIO,
+            ),
+            on_budget_warning: on_budget_warning.or(self.on_budget_warning),
+            max_thinking_tokens: max_thinking_tokens.or(self.max_thinking_tokens),
+            permission_prompt_tool_name: permission_prompt_tool_name
+                .or(self.permission_prompt_tool_name),
+            cwd: cwd.or(self.cwd),
+            cli_path: cli_path.or(self.cli_path),
+            settings: settings.or(self.settings),
+            add_dirs: non_empty(self.add_dirs, add_dirs),
+            env: merge_maps(self.env, env),
+            secret_provider: secret_provider.or(self.secret_provider),
+            secret_env: changed(self.secret_env, secret_env, default_secret_env),
+            api_key: api_key.or(self.api_key),
+            auth_token: auth_token.or(self.auth_token),
+            config_dir: config_dir.or(self.config_dir),
+            extra_args: merge_maps(self.extra_args, extra_args),
+            max_buffer_size: max_buffer_size.or(self.max_buffer_size),
+            turn_deadline: turn_deadline.or(self.turn_deadline),
+            deadline_grace_period: changed(
+                self.deadline_grace_period,
+                deadline_grace_period,
+                DEFAULT_DEADLINE_GRACE_PERIOD,
+            ),
+            message_timeout: message_timeout.or(self.message_timeout),
+            turn_policy: changed(self.turn_policy, turn_policy, Default::default()),
+            turn_queue_capacity: changed(
+                self.turn_queue_capacity,
+                turn_queue_capacity,
+                DEFAULT_TURN_QUEUE_CAPACITY,
+            ),
+            auto_reconnect: self.auto_reconnect || auto_reconnect,
+            auto_compact_threshold_tokens: auto_compact_threshold_tokens
+                .or(self.auto_compact_threshold_tokens),
+            stderr_callback: stderr_callback.or(self.stderr_callback),
+            can_use_tool: can_use_tool.or(self.can_use_tool),
+            hooks: match (self.hooks, hooks) {
+                (Some(mut base), Some(hooks)) => {
+                    for (event, matchers) in hooks {
+                        base.entry(event).or_default().extend(matchers);
+                    }
+                    Some(base)
+                },
+                (base, hooks) => hooks.or(base),
+            },
+            user: user.or(self.user),
+            include_partial_messages: self.include_partial_messages || include_partial_messages,
+            fork_session: self.fork_session || fork_session,
+            agents: match (self.agents, agents) {
+                (Some(base), Some(agents)) => Some(merge_maps(base, agents)),
+                (base, agents) => agents.or(base),
+            },
+            setting_sources: setting_sources.or(self.setting_sources),
+            sandbox: sandbox.or(self.sandbox),
+            plugins: non_empty(self.plugins, plugins),
+            plugins_dir: plugins_dir.or(self.plugins_dir),
+            output_format: output_format.or(self.output_format),
+            enable_file_checkpointing: self.enable_file_checkpointing || enable_file_checkpointing,
+            track_todos: self.track_todos || track_todos,
+            auto_discover_skills: self.auto_discover_skills || auto_discover_skills,
+            project_skills_dir: project_skills_dir.or(self.project_skills_dir),
+            user_skills_dir: user_skills_dir.or(self.user_skills_dir),
+            auto_install_cli: self.auto_install_cli || auto_install_cli,
+            cli_install_callback: cli_install_callback.or(self.cli_install_callback),
+            cli_version: cli_version.or(self.cli_version),
+            cli_cache_dir: cli_cache_dir.or(self.cli_cache_dir),
+            connect_timeouts: changed(self.connect_timeouts, connect_timeouts, Default::default()),
+            verify_mcp_servers: self.verify_mcp_servers || verify_mcp_servers,
+            connect_progress: connect_progress.or(self.connect_progress),
+            metrics: metrics.or(self.metrics),
+            audit_sink: audit_sink.or(self.audit_sink),
+            strict_parsing: self.strict_parsing || strict_parsing,
+            record_to: record_to.or(self.record_to),
+        }
+    }
+
+    /// Replace `${VAR}` in the path options by environment variables
+    fn expand_env_vars(&mut self, var: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+        let paths = [
+            &mut self.cwd,
+            &mut self.cli_path,
+            &mut self.config_dir,
+            &mut self.plugins_dir,
+            &mut self.project_skills_dir,
+            &mut self.user_skills_dir,
+            &mut self.cli_cache_dir,
+            &mut self.record_to,
+        ];
+        for path in paths.into_iter().flatten() {
+            expand_path(path, var)?;
+        }
+        for path in &mut self.add_dirs {
+            expand_path(path, var)?;
+        }
+        if let McpServers::Path(path) = &mut self.mcp_servers {
+            expand_path(path, var)?;
+        }
+        if let Some(settings) = &mut self.settings {
+            *settings = expand_env(settings, var)?;
+        }
+        Ok(())
+    }
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| {
+        std::io::Error::new(e.kind(), format!("cannot read {}: {}", path.display(), e)).into()
+    })
+}
+
+fn in_file(path: &Path, error: ClaudeError) -> ClaudeError {
+    match error {
+        ClaudeError::InvalidConfig(message) => {
+            ClaudeError::InvalidConfig(format!("{}: {}", path.display(), message))
+        },
+        error => error,
+    }
+}
+
+fn from_value(value: Value) -> Result<ClaudeAgentOptions> {
+    let (options, unknown) = parse(value, &|name| std::env::var(name).ok())?;
+    if !unknown.is_empty() {
+        warn!(
+            "Ignoring unknown configuration keys: {}",
+            unknown.join(", ")
+        );
+    }
+    Ok(options)
+}
+
+/// The options in `value` and the keys that are not options
+fn parse(
+    value: Value,
+    var: &dyn Fn(&str) -> Option<String>,
+) -> Result<(ClaudeAgentOptions, Vec<String>)> {
+    let fields = field_names::<ClaudeAgentOptions>();
+    let unknown = match &value {
+        Value::Object(keys) => keys
+            .keys()
+            .filter(|key| !fields.contains(&key.as_str()))
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut options: ClaudeAgentOptions = serde_path_to_error::deserialize(value).map_err(|e| {
+        let path = e.path().to_string();
+        ClaudeError::InvalidConfig(match path.as_str() {
+            "." => e.into_inner().to_string(),
+            _ => format!("{}: {}", path, e.into_inner()),
+        })
+    })?;
+    options.expand_env_vars(var)?;
+    Ok((options, unknown))
+}
+
+/// The keys a struct deriving `Deserialize` reads, skipped fields excluded
+fn field_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    /// Records the fields serde asks for, then gives up
+    struct Fields<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for Fields<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> std::result::Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("fields recorded"))
+        }
+
+        fn deserialize_any<V: Visitor<'de>>(
+            self,
+            _visitor: V,
+        ) -> std::result::Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("not a struct"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+            byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+            enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(Fields(&mut fields));
+    fields
+}
+
+fn expand_path(path: &mut PathBuf, var: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    if let Some(text) = path.to_str().filter(|text| text.contains("${")) {
+        *path = PathBuf::from(expand_env(text, var)?);
+    }
+    Ok(())
+}
+
+/// `text` with every `${VAR}` replaced by the value of `VAR`
+fn expand_env(text: &str, var: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find('}') else {
+            return Err(ClaudeError::InvalidConfig(format!(
+                "unclosed `${{` in `{}`",
+                text
+            )));
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        let value = var(name).ok_or_else(|| {
+            ClaudeError::InvalidConfig(format!(
+                "environment variable `{}` used in `{}` is not set",
+                name, text
+            ))
+        })?;
+        expanded.push_str(&value);
+        rest = &rest[start + 3 + len..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn non_empty<T>(base: Vec<T>, overrides: Vec<T>) -> Vec<T> {
+    if overrides.is_empty() {
+        base
+    } else {
+        overrides
+    }
+}
+
+fn changed<T: PartialEq>(base: T, overrides: T, default: T) -> T {
+    if overrides == default {
+        base
+    } else {
+        overrides
+    }
+}
+
+fn merge_maps<V>(
+    mut base: HashMap<String, V>,
+    overrides: HashMap<String, V>,
+) -> HashMap<String, V> {
+    base.extend(overrides);
+    base
+}
+
+fn merge_mcp_servers(base: McpServers, overrides: McpServers) -> McpServers {
+    match (base, overrides) {
+        (McpServers::Dict(base), McpServers::Dict(servers)) => {
+            McpServers::Dict(merge_maps(base, servers))
+        },
+        (base, overrides) if overrides.is_empty() => base,
+        (_, overrides) => overrides,
+    }
+}
+
+/// Serde for a `Duration` as seconds, or a string such as `"500ms"`, `"30s"`,
+/// `"1.5m"` or `"2h"`
+pub(super) mod duration {
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = if duration.subsec_nanos() == 0 {
+            format!("{}s", duration.as_secs())
+        } else if duration.subsec_nanos() % 1_000_000 == 0 {
+            format!("{}ms", duration.as_millis())
+        } else {
+            format!("{}s", duration.as_secs_f64())
+        };
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserializer.deserialize_any(DurationVisitor)
+    }
+
+    struct DurationVisitor;
+
+    impl Visitor<'_> for DurationVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("seconds, or a string such as \"500ms\", \"30s\", \"2m\" or \"1h\"")
+        }
+
+        fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Duration, E> {
+            Ok(Duration::from_secs(secs))
+        }
+
+        fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Duration, E> {
+            u64::try_from(secs)
+                .map(Duration::from_secs)
+                .map_err(|_| E::custom("duration cannot be negative"))
+        }
+
+        fn visit_f64<E: de::Error>(self, secs: f64) -> Result<Duration, E> {
+            Duration::try_from_secs_f64(secs).map_err(E::custom)
+        }
+
+        fn visit_str<E: de::Error>(self, text: &str) -> Result<Duration, E> {
+            let text = text.trim();
+            let split = text
+                .find(|c: char| c.is_ascii_alphabetic())
+                .unwrap_or(text.len());
+            let (number, unit) = text.split_at(split);
+            let number: f64 = number
+                .trim()
+                .parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(text), &self))?;
+            let secs = match unit {
+                "ms" => number / 1000.0,
+                "" | "s" => number,
+                "m" => number * 60.0,
+                "h" => number * 3600.0,
+                _ => return Err(E::invalid_value(de::Unexpected::Str(text), &self)),
+            };
+            Duration::try_from_secs_f64(secs).map_err(E::custom)
+        }
+    }
+}
+
+/// Serde for an `Option<Duration>`, see [`duration`]
+pub(super) mod optional_duration {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => super::duration::serialize(duration, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super::duration")] Duration);
+
+        Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(duration)| duration))
+    }
+}
+
+/// Serde for extra CLI arguments, writing a flag without a value as `true`
+pub(super) mod extra_args {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Arg<'a> {
+        Flag(bool),
+        Value(std::borrow::Cow<'a, str>),
+    }
+
+    pub fn serialize<S: Serializer>(
+        args: &HashMap<String, Option<String>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(args.iter().map(|(flag, value)| {
+            let arg = match value {
+                Some(value) => Arg::Value(value.into()),
+                None => Arg::Flag(true),
+            };
+            (flag, arg)
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<String, Option<String>>, D::Error> {
+        let args = HashMap::<String, Arg>::deserialize(deserializer)?;
+        Ok(args
+            .into_iter()
+            .filter_map(|(flag, arg)| match arg {
+                Arg::Flag(true) => Some((flag, None)),
+                Arg::Flag(false) => None,
+                Arg::Value(value) => Some((flag, Some(value.into_owned()))),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::config::{PermissionMode, SettingSource, SystemPrompt, TurnPolicy};
+    use crate::types::hooks::{HookEvent, HookMatcher};
+    use crate::types::mcp::{McpServerConfig, McpStdioServerConfig};
+    use std::time::Duration;
+
+    fn vars(name: &str) -> Option<String> {
+        (name == "HOME").then(|| "/home/ada".to_string())
+    }
+
+    fn parse_json(json: Value) -> (ClaudeAgentOptions, Vec<String>) {
+        parse(json, &vars).unwrap()
+    }
+
+    fn file_options() -> ClaudeAgentOptions {
+        ClaudeAgentOptions::builder()
+            .allowed_tools(vec!["Read".to_string(), "Grep".to_string()])
+            .system_prompt("Be brief")
+            .mcp_servers(McpServers::Dict(HashMap::from([(
+                "docs".to_string(),
+                McpServerConfig::Stdio(McpStdioServerConfig {
+                    command: "docs-server".to_string(),
+                    args: Some(vec!["--stdio".to_string()]),
+                    env: None,
+                }),
+            )])))
+            .permission_mode(PermissionMode::AcceptEdits)
+            .max_turns(8)
+            .model("sonnet")
+            .max_budget_usd(2.5)
+            .cwd("/srv/app")
+            .add_dirs(vec![PathBuf::from("/srv/shared")])
+            .env(HashMap::from([(
+                "RUST_LOG".to_string(),
+                "info".to_string(),
+            )]))
+            .extra_args(HashMap::from([
+                ("verbose".to_string(), None),
+                ("debug-to".to_string(), Some("/tmp/claude.log".to_string())),
+            ]))
+            .turn_deadline(Duration::from_secs(300))
+            .deadline_grace_period(Duration::from_millis(2500))
+            .message_timeout(Duration::from_secs(90))
+            .turn_policy(TurnPolicy::Queue)
+            .setting_sources(vec![SettingSource::Project])
+            .include_partial_messages(true)
+            .build()
+    }
+
+    fn assert_same(parsed: &ClaudeAgentOptions, original: &ClaudeAgentOptions) {
+        assert_eq!(
+            serde_json::to_value(parsed).unwrap(),
+            serde_json::to_value(original).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let original = file_options();
+        let json = serde_json::to_value(&original).unwrap();
+        assert_eq!(json["permission_mode"], "acceptEdits");
+        assert_eq!(json["turn_deadline"], "300s");
+        assert_eq!(json["deadline_grace_period"], "2500ms");
+        assert_eq!(json["extra_args"]["verbose"], true);
+        assert_eq!(json["mcp_servers"]["docs"]["type"], "stdio");
+
+        let (parsed, unknown) = parse_json(json);
+        assert!(unknown.is_empty(), "{:?}", unknown);
+        assert_same(&parsed, &original);
+        assert_eq!(parsed.extra_args.get("verbose"), Some(&None));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_round_trip() {
+        let original = file_options();
+        let text = toml::to_string(&original).unwrap();
+        let parsed = ClaudeAgentOptions::from_toml_str(&text).unwrap();
+        assert_same(&parsed, &original);
+
+        let defaults = toml::to_string(&ClaudeAgentOptions::default()).unwrap();
+        assert_same(
+            &ClaudeAgentOptions::from_toml_str(&defaults).unwrap(),
+            &ClaudeAgentOptions::default(),
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.toml");
+        std::fs::write(
+            &path,
+            r#"
+model = "opus"
+permission_mode = "acceptEdits"
+turn_deadline = 90
+message_timeout = "1.5m"
+api_key = "sk-from-file"
+
+[connect_timeouts]
+initialize = "2m"
+"#,
+        )
+        .unwrap();
+
+        let options = ClaudeAgentOptions::from_toml_file(&path).unwrap();
+        assert_eq!(options.model.as_deref(), Some("opus"));
+        assert_eq!(options.permission_mode, Some(PermissionMode::AcceptEdits));
+        assert_eq!(options.turn_deadline, Some(Duration::from_secs(90)));
+        assert_eq!(options.message_timeout, Some(Duration::from_secs(90)));
+        assert!(options.api_key.is_some());
+        assert_eq!(
+            options.connect_timeouts.initialize,
+            Duration::from_secs(120)
+        );
+        assert_eq!(options.connect_timeouts.spawn, Duration::from_secs(30));
+
+        // Secrets are read but never written
+        assert!(
+            serde_json::to_value(&options)
+                .unwrap()
+                .get("api_key")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys_are_reported() {
+        let (options, unknown) = parse_json(serde_json::json!({
+            "model": "haiku",
+            "max_turn": 3,
+            "hooks": {},
+        }));
+        assert_eq!(options.model.as_deref(), Some("haiku"));
+        assert_eq!(unknown, ["hooks", "max_turn"]);
+    }
+
+    #[test]
+    fn test_invalid_values_name_the_option() {
+        let error = parse(serde_json::json!({"max_turns": "many"}), &vars).unwrap_err();
+        assert!(matches!(&error, ClaudeError::InvalidConfig(_)));
+        assert!(error.to_string().contains("max_turns"), "{}", error);
+
+        let error = parse(serde_json::json!({"permission_mode": "yolo"}), &vars).unwrap_err();
+        assert!(error.to_string().contains("yolo"), "{}", error);
+
+        let error = parse(serde_json::json!({"turn_deadline": "soon"}), &vars).unwrap_err();
+        assert!(error.to_string().contains("turn_deadline"), "{}", error);
+    }
+
+    #[test]
+    fn test_env_vars_in_paths() {
+        let (options, _) = parse_json(serde_json::json!({
+            "cwd": "${HOME}/projects/app",
+            "add_dirs": ["${HOME}/shared", "/opt/data"],
+            "mcp_servers": "${HOME}/.mcp.json",
+            "model": "${HOME}",
+        }));
+        assert_eq!(options.cwd, Some(PathBuf::from("/home/ada/projects/app")));
+        assert_eq!(
+            options.add_dirs,
+            [
+                PathBuf::from("/home/ada/shared"),
+                PathBuf::from("/opt/data")
+            ]
+        );
+        let McpServers::Path(servers) = &options.mcp_servers else {
+            panic!("not a path");
+        };
+        assert_eq!(servers, Path::new("/home/ada/.mcp.json"));
+        // Only paths are interpolated
+        assert_eq!(options.model.as_deref(), Some("${HOME}"));
+
+        let error = parse(serde_json::json!({"cwd": "${NOPE}/x"}), &vars).unwrap_err();
+        assert!(error.to_string().contains("NOPE"));
+        let error = parse(serde_json::json!({"cwd": "${HOME"}), &vars).unwrap_err();
+        assert!(error.to_string().contains("unclosed"));
+    }
+
+    #[test]
+    fn test_merge_keeps_file_values_and_adds_code_only_options() {
+        let base = file_options();
+        let overrides = ClaudeAgentOptions::builder()
+            .model("opus")
+            .env(HashMap::from([("DEBUG".to_string(), "1".to_string())]))
+            .stderr_callback(std::sync::Arc::new(|_| {}))
+            .hooks(HashMap::from([(
+                HookEvent::PreToolUse,
+                vec![HookMatcher::builder().build()],
+            )]))
+            .build();
+
+        let merged = base.merge(overrides);
+        assert_eq!(merged.model.as_deref(), Some("opus"));
+        assert_eq!(merged.max_turns, Some(8));
+        assert_eq!(merged.allowed_tools, ["Read", "Grep"]);
+        assert_eq!(merged.permission_mode, Some(PermissionMode::AcceptEdits));
+        assert_eq!(merged.turn_policy, TurnPolicy::Queue);
+        assert_eq!(merged.deadline_grace_period, Duration::from_millis(2500));
+        assert!(merged.include_partial_messages);
+        assert_eq!(merged.env.len(), 2);
+        assert!(
+            matches!(&merged.system_prompt, Some(SystemPrompt::Text(text)) if text == "Be brief")
+        );
+        assert!(
+            matches!(&merged.mcp_servers, McpServers::Dict(servers) if servers.contains_key("docs"))
+        );
+        assert!(merged.stderr_callback.is_some());
+        assert_eq!(merged.hooks.unwrap()[&HookEvent::PreToolUse].len(), 1);
+    }
+
+    #[test]
+    fn test_merge_appends_hooks_and_combines_servers() {
+        let hooks =
+            |count| HashMap::from([(HookEvent::Stop, vec![HookMatcher::builder().build(); count])]);
+        let servers = |name: &str| {
+            McpServers::Dict(HashMap::from([(
+                name.to_string(),
+                McpServerConfig::Stdio(McpStdioServerConfig {
+                    command: name.to_string(),
+                    args: None,
+                    env: None,
+                }),
+            )]))
+        };
+        let base = ClaudeAgentOptions::builder()
+            .hooks(hooks(1))
+            .mcp_servers(servers("a"))
+            .build();
+        let overrides = ClaudeAgentOptions::builder()
+            .hooks(hooks(2))
+            .mcp_servers(servers("b"))
+            .build();
+
+        let merged = base.merge(overrides);
+        assert_eq!(merged.hooks.unwrap()[&HookEvent::Stop].len(), 3);
+        assert!(matches!(&merged.mcp_servers, McpServers::Dict(servers) if servers.len() == 2));
+    }
+}
@@ -0,0 +1,606 @@
+//! Declarative `PreToolUse` rules, compiled into a single hook
+//!
+//! Instead of writing a hook callback for every policy, describe the policy as
+//! [`HookRule`]s. The rules are evaluated in order and the first one matching a
+//! tool call decides whether the call is allowed, denied, or needs the user's
+//! approval.
+//!
+//! # Example
+//!
+//! ```
+//! use claude_agent_sdk::{HookRule, HookRules, Hooks, RuleAction};
+//!
+//! # fn example() -> claude_agent_sdk::Result<()> {
+//! let rules = HookRules::new()
+//!     .rule(
+//!         HookRule::deny("Bash")
+//!             .field("command")
+//!             .matches_regex(r"\brm\s+-rf\b")
+//!             .message("Recursive deletes are not allowed"),
+//!     )
+//!     .rule(HookRule::allow("Read").field("file_path").matches_glob("./src/**"))
+//!     .rule(HookRule::deny("Read").message("Only files under src/ can be read"));
+//!
+//! let mut hooks = Hooks::new();
+//! hooks.add_pre_tool_use_rules(rules)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use futures::FutureExt;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::hooks::{
+    HookCallback, HookInput, HookJsonOutput, HookMatcher, HookSpecificOutput,
+    PreToolUseHookSpecificOutput, SyncHookJsonOutput,
+};
+use crate::errors::{ClaudeError, Result};
+
+/// What to do with a tool call matched by a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    /// Run the tool without asking
+    Allow,
+    /// Refuse the tool call
+    Deny,
+    /// Ask the user for permission
+    Ask,
+}
+
+impl RuleAction {
+    /// The permission decision sent to the CLI
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleAction::Allow => "allow",
+            RuleAction::Deny => "deny",
+            RuleAction::Ask => "ask",
+        }
+    }
+}
+
+/// Pattern a selected value of the tool input must match
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RulePattern {
+    /// Regular expression, matching anywhere in the value unless anchored
+    Regex(String),
+    /// Glob matching the whole value
+    ///
+    /// `*` and `?` do not match `/`, while `**` does. Globs starting with `./`
+    /// match paths relative to the session's working directory. Paths are
+    /// normalized before matching, so `./src/../.env` does not match `./src/**`.
+    Glob(String),
+}
+
+/// A declarative rule for `PreToolUse` hooks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookRule {
+    /// Tool names the rule applies to, as a glob such as `Bash` or `mcp__github__*`
+    pub tool: String,
+    /// Value of the tool input to match, e.g. `command` or `edits[0].old_string`
+    ///
+    /// Dots separate keys and `[n]` indexes arrays; a selector starting with
+    /// `/` is a JSON pointer. Without a selector the pattern is matched against
+    /// the whole input as JSON. Non-string values are matched as JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Pattern the selected value must match, or `None` to match every call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<RulePattern>,
+    /// What to do with matching calls
+    pub action: RuleAction,
+    /// Reason given to Claude, or to the user when asking
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl HookRule {
+    /// Rule with `action` for calls of `tool`
+    pub fn new(tool: impl Into<String>, action: RuleAction) -> Self {
+        Self {
+            tool: tool.into(),
+            field: None,
+            pattern: None,
+            action,
+            message: None,
+        }
+    }
+
+    /// Rule allowing calls of `tool`
+    pub fn allow(tool: impl Into<String>) -> Self {
+        Self::new(tool, RuleAction::Allow)
+    }
+
+    /// Rule denying calls of `tool`
+    pub fn deny(tool: impl Into<String>) -> Self {
+        Self::new(tool, RuleAction::Deny)
+    }
+
+    /// Rule asking the user about calls of `tool`
+    pub fn ask(tool: impl Into<String>) -> Self {
+        Self::new(tool, RuleAction::Ask)
+    }
+
+    /// Match the value selected by `selector` rather than the whole input
+    pub fn field(mut self, selector: impl Into<String>) -> Self {
+        self.field = Some(selector.into());
+        self
+    }
+
+    /// Only match calls whose selected value matches the regular expression
+    pub fn matches_regex(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(RulePattern::Regex(pattern.into()));
+        self
+    }
+
+    /// Only match calls whose selected value matches the glob
+    pub fn matches_glob(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(RulePattern::Glob(pattern.into()));
+        self
+    }
+
+    /// Set the reason given for the decision
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// Ordered list of [`HookRule`]s where the first matching rule wins
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookRules {
+    /// Rules in the order they are evaluated
+    pub rules: Vec<HookRule>,
+    /// Action for calls no rule matches, or `None` to leave them to the CLI's
+    /// usual permission handling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_action: Option<RuleAction>,
+}
+
+impl HookRules {
+    /// Create an empty rule list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `rule` after the existing rules
+    pub fn rule(mut self, rule: HookRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set the action for calls no rule matches
+    pub fn default_action(mut self, action: RuleAction) -> Self {
+        self.default_action = Some(action);
+        self
+    }
+
+    /// Compile the patterns of all rules
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::InvalidConfig` naming the first rule with an
+    /// invalid pattern
+    pub fn compile(self) -> Result<CompiledHookRules> {
+        let rules = self
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| CompiledRule::new(rule, index))
+            .collect::<Result<_>>()?;
+        Ok(CompiledHookRules {
+            rules: Arc::new(rules),
+            default_action: self.default_action,
+        })
+    }
+
+    /// Compile the rules into a hook callback for `PreToolUse`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::InvalidConfig` if a pattern is invalid
+    pub fn into_callback(self) -> Result<HookCallback> {
+        Ok(self.compile()?.into_callback())
+    }
+
+    /// Compile the rules into a `PreToolUse` matcher for all tools
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::InvalidConfig` if a pattern is invalid
+    pub fn into_hook_matcher(self) -> Result<HookMatcher> {
+        Ok(HookMatcher {
+            matcher: None,
+            hooks: vec![self.into_callback()?],
+            timeout: None,
+        })
+    }
+}
+
+/// Decision of [`CompiledHookRules::evaluate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleDecision {
+    /// What to do with the tool call
+    pub action: RuleAction,
+    /// Reason for the decision
+    pub message: String,
+}
+
+/// [`HookRules`] with their patterns compiled
+#[derive(Debug, Clone)]
+pub struct CompiledHookRules {
+    rules: Arc<Vec<CompiledRule>>,
+    default_action: Option<RuleAction>,
+}
+
+impl CompiledHookRules {
+    /// Decide on a call of `tool_name` with `tool_input`, made in `cwd`
+    ///
+    /// Returns `None` if no rule matches and there is no default action.
+    pub fn evaluate(&self, tool_name: &str, tool_input: &Value, cwd: &str) -> Option<RuleDecision> {
+        let matched = self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(tool_name, tool_input, cwd));
+        match matched {
+            Some(rule) => Some(RuleDecision {
+                action: rule.rule.action,
+                message: rule.rule.message.clone().unwrap_or_else(|| {
+                    format!(
+                        "{} by hook rule {}",
+                        past_tense(rule.rule.action),
+                        rule.index + 1
+                    )
+                }),
+            }),
+            None => self.default_action.map(|action| RuleDecision {
+                action,
+                message: format!("{} by default, no hook rule matched", past_tense(action)),
+            }),
+        }
+    }
+
+    /// Hook callback applying the rules to `PreToolUse` inputs
+    ///
+    /// Other inputs are answered without a decision.
+    pub fn into_callback(self) -> HookCallback {
+        Arc::new(move |input, _tool_use_id, _context| {
+            let decision = match &input {
+                HookInput::PreToolUse(input) => {
+                    self.evaluate(&input.tool_name, &input.tool_input, &input.cwd)
+                },
+                _ => None,
+            };
+            let output = match decision {
+                Some(decision) => SyncHookJsonOutput {
+                    hook_specific_output: Some(HookSpecificOutput::PreToolUse(
+                        PreToolUseHookSpecificOutput {
+                            permission_decision: Some(decision.action.as_str().to_string()),
+                            permission_decision_reason: Some(decision.message),
+                            updated_input: None,
+                        },
+                    )),
+                    ..Default::default()
+                },
+                None => SyncHookJsonOutput::default(),
+            };
+            futures::future::ready(HookJsonOutput::Sync(output)).boxed()
+        })
+    }
+}
+
+fn past_tense(action: RuleAction) -> &'static str {
+    match action {
+        RuleAction::Allow => "Allowed",
+        RuleAction::Deny => "Denied",
+        RuleAction::Ask => "Confirmation required",
+    }
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    rule: HookRule,
+    index: usize,
+    tool: Regex,
+    pattern: Option<CompiledPattern>,
+}
+
+#[derive(Debug)]
+enum CompiledPattern {
+    Regex(Regex),
+    Glob { regex: Regex, relative: bool },
+}
+
+impl CompiledRule {
+    fn new(rule: HookRule, index: usize) -> Result<Self> {
+        let invalid = |e: regex::Error| {
+            ClaudeError::InvalidConfig(format!("Invalid pattern in hook rule {}: {}", index + 1, e))
+        };
+        let tool = glob_to_regex(&rule.tool, false).map_err(invalid)?;
+        let pattern = match &rule.pattern {
+            None => None,
+            Some(RulePattern::Regex(pattern)) => Some(CompiledPattern::Regex(
+                Regex::new(pattern).map_err(invalid)?,
+            )),
+            Some(RulePattern::Glob(pattern)) => Some(CompiledPattern::Glob {
+                regex: glob_to_regex(pattern, true).map_err(invalid)?,
+                relative: pattern.starts_with("./"),
+            }),
+        };
+        Ok(Self {
+            rule,
+            index,
+            tool,
+            pattern,
+        })
+    }
+
+    fn matches(&self, tool_name: &str, tool_input: &Value, cwd: &str) -> bool {
+        if !self.tool.is_match(tool_name) {
+            return false;
+        }
+        let Some(pattern) = &self.pattern else {
+            return true;
+        };
+        let selected = match &self.rule.field {
+            Some(selector) => match select(tool_input, selector) {
+                Some(value) => value,
+                None => return false,
+            },
+            None => tool_input,
+        };
+        let text = match selected {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+
+        match pattern {
+            CompiledPattern::Regex(regex) => regex.is_match(&text),
+            CompiledPattern::Glob { regex, relative } => {
+                regex.is_match(&normalize_path(&text, relative.then_some(cwd)))
+            },
+        }
+    }
+}
+
+/// Translate a glob into an anchored regex, escaping everything but wildcards
+///
+/// With `paths`, `*` and `?` stop at `/` and `**` crosses it; otherwise `*`
+/// matches anything.
+fn glob_to_regex(glob: &str, paths: bool) -> std::result::Result<Regex, regex::Error> {
+    let (any, one) = if paths {
+        ("[^/]*", "[^/]")
+    } else {
+        (".*", ".")
+    };
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `**/` also matches no directory at all
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            },
+            '*' => regex.push_str(any),
+            '?' => regex.push_str(one),
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex)
+}
+
+/// Value of `input` at `selector`, see [`HookRule::field`]
+fn select<'a>(input: &'a Value, selector: &str) -> Option<&'a Value> {
+    if selector.starts_with('/') {
+        return input.pointer(selector);
+    }
+
+    let mut value = input;
+    for part in selector.split('.') {
+        let (key, mut indexes) = match part.find('[') {
+            Some(start) => (&part[..start], &part[start..]),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            value = value.get(key)?;
+        }
+        while let Some(rest) = indexes.strip_prefix('[') {
+            let (index, after) = rest.split_once(']')?;
+            value = value.get(index.parse::<usize>().ok()?)?;
+            indexes = after;
+        }
+        if !indexes.is_empty() {
+            return None;
+        }
+    }
+    Some(value)
+}
+
+/// Resolve `.` and `..` in `path`, and make it relative to `cwd` if given
+fn normalize_path(path: &str, cwd: Option<&str>) -> String {
+    let mut path = path.to_string();
+    if let Some(cwd) = cwd {
+        let cwd = cwd.trim_end_matches('/');
+        if let Some(rest) = path
+            .strip_prefix(cwd)
+            .and_then(|rest| rest.strip_prefix('/'))
+        {
+            path = rest.to_string();
+        } else if path.starts_with('/') {
+            // Outside the working directory, so no relative glob matches it
+            return path;
+        }
+    }
+
+    let absolute = path.starts_with('/');
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {},
+            ".." => {
+                if parts.last().is_some_and(|last| *last != "..") {
+                    parts.pop();
+                } else if !absolute {
+                    parts.push("..");
+                }
+            },
+            part => parts.push(part),
+        }
+    }
+
+    let joined = parts.join("/");
+    match (absolute, cwd.is_some()) {
+        (true, _) => format!("/{}", joined),
+        (false, true) => format!("./{}", joined),
+        (false, false) => joined,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules() -> CompiledHookRules {
+        HookRules::new()
+            .rule(
+                HookRule::deny("Bash")
+                    .field("command")
+                    .matches_regex(r"\brm\s+-rf\b")
+                    .message("No recursive deletes"),
+            )
+            .rule(
+                HookRule::allow("Read")
+                    .field("file_path")
+                    .matches_glob("./src/**"),
+            )
+            .rule(HookRule::deny("Read"))
+            .rule(
+                HookRule::ask("Write")
+                    .field("file_path")
+                    .matches_glob("**/*.rs"),
+            )
+            .rule(
+                HookRule::deny("MultiEdit")
+                    .field("edits[1].new_string")
+                    .matches_regex("TODO"),
+            )
+            .rule(
+                HookRule::deny("mcp__*")
+                    .field("/options/force")
+                    .matches_regex("^true$"),
+            )
+            .compile()
+            .unwrap()
+    }
+
+    fn action(tool: &str, input: Value) -> Option<RuleAction> {
+        rules()
+            .evaluate(tool, &input, "/work/project")
+            .map(|decision| decision.action)
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let denied = rules()
+            .evaluate(
+                "Bash",
+                &json!({"command": "cd /tmp && rm -rf build"}),
+                "/work",
+            )
+            .unwrap();
+        assert_eq!(denied.action, RuleAction::Deny);
+        assert_eq!(denied.message, "No recursive deletes");
+        assert_eq!(action("Bash", json!({"command": "rm -rfx"})), None);
+        assert_eq!(action("Bash", json!({"command": "ls"})), None);
+
+        let read = |path: &str| action("Read", json!({"file_path": path}));
+        assert_eq!(read("/work/project/src/lib.rs"), Some(RuleAction::Allow));
+        assert_eq!(read("src/deep/mod.rs"), Some(RuleAction::Allow));
+        assert_eq!(read("/work/project/Cargo.toml"), Some(RuleAction::Deny));
+        assert_eq!(read("/work/project/src/../.env"), Some(RuleAction::Deny));
+        assert_eq!(read("/etc/passwd"), Some(RuleAction::Deny));
+    }
+
+    #[test]
+    fn test_glob_and_selector_syntax() {
+        let write = |path: &str| action("Write", json!({"file_path": path}));
+        assert_eq!(write("/a/b/main.rs"), Some(RuleAction::Ask));
+        assert_eq!(write("main.rs"), Some(RuleAction::Ask));
+        assert_eq!(write("/a/main.rs.bak"), None);
+        // Regex metacharacters in globs are literal
+        let literal = glob_to_regex("a+b(1).txt", true).unwrap();
+        assert!(literal.is_match("a+b(1).txt"));
+        assert!(!literal.is_match("aab1.txt"));
+
+        let edits = |second: &str| {
+            action(
+                "MultiEdit",
+                json!({"edits": [{"new_string": "TODO"}, {"new_string": second}]}),
+            )
+        };
+        assert_eq!(edits("// TODO later"), Some(RuleAction::Deny));
+        assert_eq!(edits("done"), None);
+        assert_eq!(action("MultiEdit", json!({"edits": []})), None);
+
+        let force = |force: Value| action("mcp__db__drop", json!({"options": {"force": force}}));
+        assert_eq!(force(json!(true)), Some(RuleAction::Deny));
+        assert_eq!(force(json!(false)), None);
+    }
+
+    #[test]
+    fn test_default_action_and_invalid_patterns() {
+        let rules = HookRules::new()
+            .default_action(RuleAction::Ask)
+            .compile()
+            .unwrap();
+        let decision = rules.evaluate("Bash", &json!({}), "/").unwrap();
+        assert_eq!(decision.action, RuleAction::Ask);
+
+        let invalid = HookRules::new()
+            .rule(HookRule::allow("Bash"))
+            .rule(HookRule::deny("Bash").matches_regex("("))
+            .compile();
+        match invalid {
+            Err(ClaudeError::InvalidConfig(message)) => assert!(message.contains("rule 2")),
+            other => panic!("expected an invalid config error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_callback_sets_permission_decision() {
+        let callback = rules().into_callback();
+        let input: HookInput = serde_json::from_value(json!({
+            "hook_event_name": "PreToolUse",
+            "session_id": "s",
+            "transcript_path": "/tmp/t",
+            "cwd": "/work",
+            "tool_name": "Bash",
+            "tool_input": {"command": "rm -rf /"}
+        }))
+        .unwrap();
+
+        let output = callback(input, None, Default::default()).await;
+        let output = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            output["hookSpecificOutput"],
+            json!({
+                "hookEventName": "PreToolUse",
+                "permissionDecision": "deny",
+                "permissionDecisionReason": "No recursive deletes"
+            })
+        );
+    }
+}
@@ -4,6 +4,7 @@ use futures::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use typed_builder::TypedBuilder;
 
 /// Hook events that can be intercepted
@@ -33,11 +34,17 @@ pub struct HookMatcher {
     /// Hook callbacks to invoke
     #[builder(default)]
     pub hooks: Vec<HookCallback>,
-    /// Timeout in seconds for all hooks in this matcher (default: 60)
+    /// Timeout in seconds for each hook in this matcher (default: 60)
+    ///
+    /// A hook that runs longer is abandoned and answered with a message
+    /// telling the CLI to continue.
     #[builder(default, setter(strip_option))]
     pub timeout: Option<f64>,
 }
 
+/// How long a hook may run when its [`HookMatcher`] sets no timeout
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Hook callback type
 pub type HookCallback = Arc<
     dyn Fn(HookInput, Option<String>, HookContext) -> BoxFuture<'static, HookJsonOutput>
@@ -66,6 +73,43 @@ pub enum HookInput {
     PreCompact(PreCompactHookInput),
 }
 
+impl HookInput {
+    /// ID of the tool use, for `PreToolUse` and `PostToolUse` inputs
+    pub fn tool_use_id(&self) -> Option<&str> {
+        match self {
+            HookInput::PreToolUse(input) => input.tool_use_id.as_deref(),
+            HookInput::PostToolUse(input) => input.tool_use_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The typed tool input, for `PreToolUse` and `PostToolUse` inputs
+    pub fn typed_tool_input(&self) -> Option<KnownToolInput> {
+        match self {
+            HookInput::PreToolUse(input) => Some(input.typed_tool_input()),
+            HookInput::PostToolUse(input) => Some(input.typed_tool_input()),
+            _ => None,
+        }
+    }
+
+    /// Reconcile the tool use ID of the input with the one sent next to it
+    ///
+    /// The CLI puts the ID in the input, next to it, or both; the input is
+    /// given `tool_use_id` when it has none, and the resulting ID is returned
+    /// for the callback's `tool_use_id` argument.
+    pub(crate) fn merge_tool_use_id(&mut self, tool_use_id: Option<String>) -> Option<String> {
+        let field = match self {
+            HookInput::PreToolUse(input) => &mut input.tool_use_id,
+            HookInput::PostToolUse(input) => &mut input.tool_use_id,
+            _ => return tool_use_id,
+        };
+        if field.is_none() {
+            *field = tool_use_id;
+        }
+        field.clone()
+    }
+}
+
 /// Pre-tool-use hook input
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreToolUseHookInput {
@@ -82,6 +126,16 @@ pub struct PreToolUseHookInput {
     pub tool_name: String,
     /// Tool input parameters
     pub tool_input: serde_json::Value,
+    /// ID of the tool use, shared with the matching `PostToolUse` input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+}
+
+impl PreToolUseHookInput {
+    /// The tool input, typed when the tool is a built-in one
+    pub fn typed_tool_input(&self) -> KnownToolInput {
+        KnownToolInput::parse(&self.tool_name, &self.tool_input)
+    }
 }
 
 /// Post-tool-use hook input
@@ -102,6 +156,16 @@ pub struct PostToolUseHookInput {
     pub tool_input: serde_json::Value,
     /// Tool response (output from the tool)
     pub tool_response: serde_json::Value,
+    /// ID of the tool use, shared with the matching `PreToolUse` input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+}
+
+impl PostToolUseHookInput {
+    /// The tool input, typed when the tool is a built-in one
+    pub fn typed_tool_input(&self) -> KnownToolInput {
+        KnownToolInput::parse(&self.tool_name, &self.tool_input)
+    }
 }
 
 /// User-prompt-submit hook input
@@ -152,6 +216,165 @@ pub struct SubagentStopHookInput {
     pub stop_hook_active: bool,
 }
 
+/// Input of a built-in tool, as seen by `PreToolUse` and `PostToolUse` hooks
+///
+/// Obtained with [`HookInput::typed_tool_input`]. Tools this SDK does not know,
+/// such as MCP tools, and inputs that do not have the expected shape are
+/// kept as [`KnownToolInput::Other`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum KnownToolInput {
+    /// Input of the `Bash` tool
+    Bash(BashToolInput),
+    /// Input of the `Read` tool
+    Read(ReadToolInput),
+    /// Input of the `Write` tool
+    Write(WriteToolInput),
+    /// Input of the `Edit` tool
+    Edit(EditToolInput),
+    /// Input of the `Glob` tool
+    Glob(GlobToolInput),
+    /// Input of the `Grep` tool
+    Grep(GrepToolInput),
+    /// Input of the `WebFetch` tool
+    WebFetch(WebFetchToolInput),
+    /// Input of any other tool, unparsed
+    Other(serde_json::Value),
+}
+
+impl KnownToolInput {
+    /// Type the `input` of the tool named `tool_name`
+    pub fn parse(tool_name: &str, input: &serde_json::Value) -> Self {
+        fn typed<T: serde::de::DeserializeOwned>(
+            input: &serde_json::Value,
+            variant: fn(T) -> KnownToolInput,
+        ) -> KnownToolInput {
+            T::deserialize(input)
+                .map(variant)
+                .unwrap_or_else(|_| KnownToolInput::Other(input.clone()))
+        }
+
+        match tool_name {
+            "Bash" => typed(input, KnownToolInput::Bash),
+            "Read" => typed(input, KnownToolInput::Read),
+            "Write" => typed(input, KnownToolInput::Write),
+            "Edit" => typed(input, KnownToolInput::Edit),
+            "Glob" => typed(input, KnownToolInput::Glob),
+            "Grep" => typed(input, KnownToolInput::Grep),
+            "WebFetch" => typed(input, KnownToolInput::WebFetch),
+            _ => KnownToolInput::Other(input.clone()),
+        }
+    }
+
+    /// File the tool reads or writes, for `Read`, `Write` and `Edit`
+    pub fn file_path(&self) -> Option<&str> {
+        match self {
+            KnownToolInput::Read(input) => Some(&input.file_path),
+            KnownToolInput::Write(input) => Some(&input.file_path),
+            KnownToolInput::Edit(input) => Some(&input.file_path),
+            _ => None,
+        }
+    }
+}
+
+/// Input of the `Bash` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BashToolInput {
+    /// Command to run
+    pub command: String,
+    /// Timeout in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// What the command does, in a few words
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the command runs in the background
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_in_background: Option<bool>,
+}
+
+/// Input of the `Read` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadToolInput {
+    /// Absolute path of the file
+    pub file_path: String,
+    /// Line to start reading from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Number of lines to read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+/// Input of the `Write` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteToolInput {
+    /// Absolute path of the file
+    pub file_path: String,
+    /// New content of the file
+    pub content: String,
+}
+
+/// Input of the `Edit` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EditToolInput {
+    /// Absolute path of the file
+    pub file_path: String,
+    /// Text to replace
+    pub old_string: String,
+    /// Replacement text
+    pub new_string: String,
+    /// Whether every occurrence is replaced, rather than a unique one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace_all: Option<bool>,
+}
+
+/// Input of the `Glob` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobToolInput {
+    /// Glob pattern, e.g. `src/**/*.rs`
+    pub pattern: String,
+    /// Directory to search in; the working directory when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// Input of the `Grep` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrepToolInput {
+    /// Regular expression to search for
+    pub pattern: String,
+    /// File or directory to search in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Glob filtering the searched files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glob: Option<String>,
+    /// File type filtering the searched files, e.g. `rust`
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub file_type: Option<String>,
+    /// `content`, `files_with_matches` or `count`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mode: Option<String>,
+    /// Whether the search ignores case
+    #[serde(default, rename = "-i", skip_serializing_if = "Option::is_none")]
+    pub case_insensitive: Option<bool>,
+    /// Whether patterns may span lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multiline: Option<bool>,
+    /// Most results to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_limit: Option<u64>,
+}
+
+/// Input of the `WebFetch` tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebFetchToolInput {
+    /// URL to fetch
+    pub url: String,
+    /// What to extract from the page
+    pub prompt: String,
+}
+
 /// Pre-compact hook input
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreCompactHookInput {
@@ -304,6 +527,12 @@ pub struct PostToolUseHookSpecificOutput {
     #[serde(skip_serializing_if = "Option::is_none", rename = "additionalContext")]
     #[builder(default, setter(into, strip_option))]
     pub additional_context: Option<String>,
+    /// Replacement for the output of an MCP tool
+    ///
+    /// The CLI ignores this for built-in tools.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "updatedMCPToolOutput")]
+    #[builder(default, setter(strip_option))]
+    pub updated_mcp_tool_output: Option<serde_json::Value>,
 }
 
 impl Default for PostToolUseHookSpecificOutput {
@@ -405,6 +634,235 @@ mod tests {
         }
     }
 
+    /// `PreToolUse` hook input as sent by the CLI for `tool_name` and `tool_input`
+    fn pre_tool_use(tool_name: &str, tool_input: serde_json::Value) -> HookInput {
+        serde_json::from_value(json!({
+            "session_id": "5f1c0c2e-8d0b-4d5e-9a51-1c7e4b0f2a9d",
+            "transcript_path": "/home/dev/.claude/projects/-home-dev-app/5f1c0c2e.jsonl",
+            "cwd": "/home/dev/app",
+            "permission_mode": "default",
+            "hook_event_name": "PreToolUse",
+            "tool_name": tool_name,
+            "tool_input": tool_input,
+            "tool_use_id": "toolu_01HGpLJ1NwS4yW7pV5rX3c8e"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tool_hook_inputs_carry_tool_use_id() {
+        let pre = pre_tool_use("Bash", json!({"command": "ls"}));
+        assert_eq!(pre.tool_use_id(), Some("toolu_01HGpLJ1NwS4yW7pV5rX3c8e"));
+
+        let post: HookInput = serde_json::from_value(json!({
+            "session_id": "s",
+            "transcript_path": "/tmp/t.jsonl",
+            "cwd": "/tmp",
+            "hook_event_name": "PostToolUse",
+            "tool_name": "Bash",
+            "tool_input": {"command": "ls"},
+            "tool_response": {"stdout": "Cargo.toml\n", "stderr": "", "interrupted": false, "isImage": false},
+            "tool_use_id": "toolu_01HGpLJ1NwS4yW7pV5rX3c8e"
+        }))
+        .unwrap();
+        assert_eq!(post.tool_use_id(), pre.tool_use_id());
+
+        // Round trip keeps the ID; older CLIs omit it
+        let json = serde_json::to_value(&pre).unwrap();
+        assert_eq!(json["tool_use_id"], "toolu_01HGpLJ1NwS4yW7pV5rX3c8e");
+        let old: HookInput = serde_json::from_value(json!({
+            "hook_event_name": "PreToolUse",
+            "session_id": "s",
+            "transcript_path": "/tmp/t.jsonl",
+            "cwd": "/tmp",
+            "tool_name": "Bash",
+            "tool_input": {}
+        }))
+        .unwrap();
+        assert_eq!(old.tool_use_id(), None);
+        assert!(serde_json::to_value(&old).unwrap().get("tool_use_id").is_none());
+    }
+
+    #[test]
+    fn test_merge_tool_use_id() {
+        // Only next to the input: copied into it
+        let mut input = pre_tool_use("Bash", json!({"command": "ls"}));
+        if let HookInput::PreToolUse(pre) = &mut input {
+            pre.tool_use_id = None;
+        }
+        assert_eq!(
+            input.merge_tool_use_id(Some("toolu_1".to_string())).as_deref(),
+            Some("toolu_1")
+        );
+        assert_eq!(input.tool_use_id(), Some("toolu_1"));
+
+        // Only in the input: given to the callback
+        let mut input = pre_tool_use("Bash", json!({"command": "ls"}));
+        assert_eq!(
+            input.merge_tool_use_id(None).as_deref(),
+            Some("toolu_01HGpLJ1NwS4yW7pV5rX3c8e")
+        );
+
+        // Events without tools keep the ID sent next to them
+        let mut input = HookInput::Stop(StopHookInput {
+            session_id: "s".to_string(),
+            transcript_path: "/tmp/t.jsonl".to_string(),
+            cwd: "/tmp".to_string(),
+            permission_mode: None,
+            stop_hook_active: false,
+        });
+        assert_eq!(input.merge_tool_use_id(Some("x".to_string())).as_deref(), Some("x"));
+        assert_eq!(input.tool_use_id(), None);
+        assert_eq!(input.typed_tool_input(), None);
+    }
+
+    #[test]
+    fn test_typed_tool_input_bash() {
+        let input = pre_tool_use(
+            "Bash",
+            json!({"command": "cargo test --workspace", "description": "Run the tests", "timeout": 600000}),
+        );
+        assert_eq!(
+            input.typed_tool_input(),
+            Some(KnownToolInput::Bash(BashToolInput {
+                command: "cargo test --workspace".to_string(),
+                timeout: Some(600000),
+                description: Some("Run the tests".to_string()),
+                run_in_background: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_typed_tool_input_read() {
+        let input = pre_tool_use(
+            "Read",
+            json!({"file_path": "/home/dev/app/src/main.rs", "offset": 120, "limit": 40}),
+        );
+        let typed = input.typed_tool_input().unwrap();
+        assert_eq!(
+            typed,
+            KnownToolInput::Read(ReadToolInput {
+                file_path: "/home/dev/app/src/main.rs".to_string(),
+                offset: Some(120),
+                limit: Some(40),
+            })
+        );
+        assert_eq!(typed.file_path(), Some("/home/dev/app/src/main.rs"));
+    }
+
+    #[test]
+    fn test_typed_tool_input_write() {
+        let input = pre_tool_use(
+            "Write",
+            json!({"file_path": "/home/dev/app/notes.md", "content": "# Notes\n"}),
+        );
+        assert_eq!(
+            input.typed_tool_input(),
+            Some(KnownToolInput::Write(WriteToolInput {
+                file_path: "/home/dev/app/notes.md".to_string(),
+                content: "# Notes\n".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_typed_tool_input_edit() {
+        let input = pre_tool_use(
+            "Edit",
+            json!({
+                "file_path": "/home/dev/app/src/lib.rs",
+                "old_string": "fn old()",
+                "new_string": "fn new()",
+                "replace_all": true
+            }),
+        );
+        let typed = input.typed_tool_input().unwrap();
+        assert_eq!(
+            typed,
+            KnownToolInput::Edit(EditToolInput {
+                file_path: "/home/dev/app/src/lib.rs".to_string(),
+                old_string: "fn old()".to_string(),
+                new_string: "fn new()".to_string(),
+                replace_all: Some(true),
+            })
+        );
+        assert_eq!(typed.file_path(), Some("/home/dev/app/src/lib.rs"));
+    }
+
+    #[test]
+    fn test_typed_tool_input_glob() {
+        let input = pre_tool_use("Glob", json!({"pattern": "**/*.rs", "path": "/home/dev/app"}));
+        assert_eq!(
+            input.typed_tool_input(),
+            Some(KnownToolInput::Glob(GlobToolInput {
+                pattern: "**/*.rs".to_string(),
+                path: Some("/home/dev/app".to_string()),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_typed_tool_input_grep() {
+        let input = pre_tool_use(
+            "Grep",
+            json!({
+                "pattern": "fn main",
+                "path": "src",
+                "type": "rust",
+                "output_mode": "content",
+                "-i": true,
+                "-n": true
+            }),
+        );
+        let Some(KnownToolInput::Grep(grep)) = input.typed_tool_input() else {
+            panic!("Expected Grep input");
+        };
+        assert_eq!(grep.pattern, "fn main");
+        assert_eq!(grep.path.as_deref(), Some("src"));
+        assert_eq!(grep.file_type.as_deref(), Some("rust"));
+        assert_eq!(grep.output_mode.as_deref(), Some("content"));
+        assert_eq!(grep.case_insensitive, Some(true));
+        assert_eq!(grep.glob, None);
+        assert_eq!(
+            serde_json::to_value(&grep).unwrap(),
+            json!({"pattern": "fn main", "path": "src", "type": "rust", "output_mode": "content", "-i": true})
+        );
+    }
+
+    #[test]
+    fn test_typed_tool_input_web_fetch() {
+        let input = pre_tool_use(
+            "WebFetch",
+            json!({"url": "https://docs.rs/serde", "prompt": "Summarize the derive docs"}),
+        );
+        assert_eq!(
+            input.typed_tool_input(),
+            Some(KnownToolInput::WebFetch(WebFetchToolInput {
+                url: "https://docs.rs/serde".to_string(),
+                prompt: "Summarize the derive docs".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_typed_tool_input_falls_back_to_other() {
+        // MCP and future tools
+        let mcp_input = json!({"query": "rust"});
+        let input = pre_tool_use("mcp__skills__search_skills", mcp_input.clone());
+        assert_eq!(input.typed_tool_input(), Some(KnownToolInput::Other(mcp_input)));
+        let todo_input = json!({"todos": [{"content": "Ship it", "status": "pending"}]});
+        let input = pre_tool_use("TodoWrite", todo_input.clone());
+        assert_eq!(input.typed_tool_input(), Some(KnownToolInput::Other(todo_input)));
+
+        // Built-in tool with an unexpected input
+        let odd = json!({"cmd": "ls"});
+        let input = pre_tool_use("Bash", odd.clone());
+        let typed = input.typed_tool_input().unwrap();
+        assert_eq!(typed, KnownToolInput::Other(odd));
+        assert_eq!(typed.file_path(), None);
+    }
+
     #[test]
     fn test_stop_hook_input_deserialization() {
         let json_str = r#"{
@@ -501,6 +959,7 @@ mod tests {
     fn test_hook_specific_output_posttooluse_serialization() {
         let output = HookSpecificOutput::PostToolUse(PostToolUseHookSpecificOutput {
             additional_context: Some("Error occurred".to_string()),
+            updated_mcp_tool_output: None,
         });
 
         let json = serde_json::to_value(&output).unwrap();
@@ -817,6 +1276,7 @@ mod tests {
             permission_mode: None,
             tool_name: "Bash".to_string(),
             tool_input: serde_json::json!({"command": "ls"}),
+            tool_use_id: None,
         });
 
         let result = hook_callback(input, None, HookContext::default()).await;
@@ -854,6 +1314,7 @@ mod tests {
             permission_mode: None,
             tool_name: "Bash".to_string(),
             tool_input: serde_json::json!({"command": "ls"}),
+            tool_use_id: None,
         });
 
         let result = hook_callback(input, None, HookContext::default()).await;
@@ -1020,6 +1481,28 @@ impl Hooks {
         });
     }
 
+    /// Add a PostToolUse hook redacting secrets from tool responses
+    pub fn add_redaction(&mut self, redaction: super::redaction::RedactionHook) {
+        self.hooks
+            .entry(HookEvent::PostToolUse)
+            .or_default()
+            .push(redaction.into_hook_matcher());
+    }
+
+    /// Add declarative rules deciding on tool calls as a PreToolUse hook
+    ///
+    /// # Errors
+    ///
+    /// Returns `ClaudeError::InvalidConfig` if a rule has an invalid pattern
+    pub fn add_pre_tool_use_rules(
+        &mut self,
+        rules: super::hook_rules::HookRules,
+    ) -> crate::errors::Result<()> {
+        let matcher = rules.into_hook_matcher()?;
+        self.hooks.entry(HookEvent::PreToolUse).or_default().push(matcher);
+        Ok(())
+    }
+
     // Generate all hook methods
     generate_hook_methods! {
         with_matcher: {
@@ -1,13 +1,28 @@
 //! MCP (Model Context Protocol) types for Claude Agent SDK
 
 use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use futures::future::BoxFuture;
+use schemars::JsonSchema;
+use schemars::generate::SchemaSettings;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::any::Any;
 use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 
 use crate::errors::Result;
+use crate::mcp::TaskManager;
+use crate::mcp::middleware::{MiddlewareServer, ToolMiddleware};
+use crate::mcp::resources::{
+    McpPrompt, McpResource, PromptArgument, PromptMessage, ResourceContents,
+};
 
 /// MCP servers configuration
 #[derive(Clone, Default)]
@@ -34,6 +49,103 @@ pub enum McpServerConfig {
     Sdk(McpSdkServerConfig),
 }
 
+impl McpServers {
+    /// Whether no servers are configured
+    pub fn is_empty(&self) -> bool {
+        match self {
+            McpServers::Empty => true,
+            McpServers::Dict(servers) => servers.is_empty(),
+            McpServers::Path(_) => false,
+        }
+    }
+}
+
+/// A path to a config file, or a map from names to external servers
+///
+/// Servers are written as in the `mcpServers` object of an MCP config file:
+/// with a `type` of `stdio` (the default), `sse` or `http`. In-process SDK
+/// servers have no file representation and are left out.
+impl Serialize for McpServers {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        match self {
+            McpServers::Empty => serializer.serialize_none(),
+            McpServers::Path(path) => path.serialize(serializer),
+            McpServers::Dict(servers) => {
+                let external: Vec<_> = servers
+                    .iter()
+                    .filter_map(|(name, server)| Some((name, server.to_value()?)))
+                    .collect();
+                let mut map = serializer.serialize_map(Some(external.len()))?;
+                for (name, server) in external {
+                    map.serialize_entry(name, &server)?;
+                }
+                map.end()
+            },
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for McpServers {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Path(PathBuf),
+            Dict(HashMap<String, serde_json::Value>),
+        }
+
+        Ok(match Option::<Repr>::deserialize(deserializer)? {
+            None => McpServers::Empty,
+            Some(Repr::Path(path)) => McpServers::Path(path),
+            Some(Repr::Dict(servers)) => McpServers::Dict(
+                servers
+                    .into_iter()
+                    .map(|(name, server)| {
+                        McpServerConfig::from_value(server)
+                            .map(|config| (name.clone(), config))
+                            .map_err(|e| serde::de::Error::custom(format!("server `{name}`: {e}")))
+                    })
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+        })
+    }
+}
+
+impl McpServerConfig {
+    /// Parse an external server as written in an MCP config file
+    ///
+    /// The `type` field selects the transport and defaults to `stdio`.
+    pub(crate) fn from_value(server: serde_json::Value) -> std::result::Result<Self, String> {
+        let parsed = match server.get("type").and_then(serde_json::Value::as_str) {
+            None | Some("stdio") => serde_json::from_value(server).map(McpServerConfig::Stdio),
+            Some("http") => serde_json::from_value(server).map(McpServerConfig::Http),
+            Some("sse") => serde_json::from_value(server).map(McpServerConfig::Sse),
+            Some(other) => return Err(format!("unknown type `{}`", other)),
+        };
+        parsed.map_err(|e| e.to_string())
+    }
+
+    /// The server as written in an MCP config file, or `None` for SDK servers
+    pub(crate) fn to_value(&self) -> Option<serde_json::Value> {
+        let (kind, value) = match self {
+            McpServerConfig::Stdio(config) => ("stdio", serde_json::to_value(config)),
+            McpServerConfig::Sse(config) => ("sse", serde_json::to_value(config)),
+            McpServerConfig::Http(config) => ("http", serde_json::to_value(config)),
+            McpServerConfig::Sdk(_) => return None,
+        };
+        let mut value = value.ok()?;
+        value["type"] = kind.into();
+        Some(value)
+    }
+}
+
 /// Stdio MCP server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpStdioServerConfig {
@@ -74,6 +186,406 @@ pub struct McpSdkServerConfig {
     pub name: String,
     /// Server instance
     pub instance: Arc<dyn SdkMcpServer>,
+    /// Per-session tool state, see [`McpSdkServerConfig::with_session_state`]
+    pub session_state: Option<SessionStateStore>,
+    /// Tasks served to the CLI, see [`McpSdkServerConfig::with_task_manager`]
+    pub task_manager: Option<TaskManager>,
+    /// Hooks around tool calls, see [`McpSdkServerConfig::with_middleware`]
+    pub middleware: Vec<Arc<dyn ToolMiddleware>>,
+    /// Resources served to the CLI, see [`McpSdkServerConfig::add_resource`]
+    pub resources: Vec<McpResource>,
+    /// Prompts served to the CLI, see [`McpSdkServerConfig::add_prompt`]
+    pub prompts: Vec<McpPrompt>,
+}
+
+impl McpSdkServerConfig {
+    /// Give tool handlers a separate `S` for every session
+    ///
+    /// Handlers access it through [`ToolCallContext::session_state`]. The state is
+    /// created on the first tool call of a session and dropped when the session
+    /// reports a result or has been idle for [`DEFAULT_SESSION_IDLE_TTL`].
+    pub fn with_session_state<S>(mut self) -> Self
+    where
+        S: Default + Send + Sync + 'static,
+    {
+        self.session_state = Some(SessionStateStore::new::<S>());
+        self
+    }
+
+    /// Set how long an unused session state is kept
+    ///
+    /// Has no effect unless [`McpSdkServerConfig::with_session_state`] was called first.
+    pub fn with_session_idle_ttl(mut self, ttl: Duration) -> Self {
+        if let Some(store) = self.session_state.take() {
+            self.session_state = Some(store.with_idle_ttl(ttl));
+        }
+        self
+    }
+
+    /// Serve the MCP task methods from `manager`
+    ///
+    /// The CLI can then call tools as tasks (a `tools/call` with a `task`
+    /// parameter, or `tasks/create`), which return a task reference right away
+    /// while the tool runs in the background, and follow them up with
+    /// `tasks/get`, `tasks/result`, `tasks/cancel` and `tasks/list`. Tool
+    /// handlers can also create tasks in `manager` themselves.
+    pub fn with_task_manager(mut self, manager: TaskManager) -> Self {
+        self.task_manager = Some(manager);
+        self
+    }
+
+    /// Run `middleware` around every tool call, after any added before
+    ///
+    /// The `before` hooks run in the order the middleware was added, and the
+    /// `after` hooks in reverse. Tool calls run as tasks go through the
+    /// middleware too. With middleware, errors of a tool are reported to Claude
+    /// as error results rather than JSON-RPC errors.
+    pub fn with_middleware(mut self, middleware: Vec<Arc<dyn ToolMiddleware>>) -> Self {
+        self.middleware.extend(middleware);
+        self
+    }
+
+    /// Serve a resource at `uri`, whose content `provider` produces on every read
+    ///
+    /// A resource added again with the same URI replaces the earlier one.
+    /// Errors of the provider reach the CLI as JSON-RPC errors, with the code
+    /// of an [`McpError`] if the provider returned one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::create_sdk_mcp_server;
+    /// use claude_agent_sdk::mcp::{PromptArgument, PromptMessage, ResourceContents};
+    ///
+    /// let server = create_sdk_mcp_server("logs", "1.0.0", vec![])
+    ///     .add_resource("file:///var/log/app.log", "Application log", "text/plain", || async {
+    ///         let log = tokio::fs::read_to_string("/var/log/app.log").await?;
+    ///         Ok(ResourceContents::Text(log))
+    ///     })
+    ///     .add_prompt(
+    ///         "summarize-logs",
+    ///         "Summarize the application log",
+    ///         vec![PromptArgument::optional("focus", "What to look for, e.g. errors")],
+    ///         |args| async move {
+    ///             let focus = args.get("focus").map_or("anything unusual", String::as_str);
+    ///             Ok(vec![PromptMessage::user(format!(
+    ///                 "Read file:///var/log/app.log and summarize it, focusing on {}.",
+    ///                 focus
+    ///             ))])
+    ///         },
+    ///     );
+    /// ```
+    pub fn add_resource<F, Fut>(
+        mut self,
+        uri: impl Into<String>,
+        name: impl Into<String>,
+        mime_type: impl Into<String>,
+        provider: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<ResourceContents>> + Send + 'static,
+    {
+        let resource = McpResource::new(uri, name, mime_type, provider);
+        self.resources.retain(|existing| existing.uri != resource.uri);
+        self.resources.push(resource);
+        self
+    }
+
+    /// Serve a prompt template, whose messages `builder` produces from the arguments
+    ///
+    /// A prompt added again with the same name replaces the earlier one. See
+    /// [`McpSdkServerConfig::add_resource`] for an example.
+    pub fn add_prompt<F, Fut>(
+        mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        arguments: Vec<PromptArgument>,
+        builder: F,
+    ) -> Self
+    where
+        F: Fn(HashMap<String, String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<Vec<PromptMessage>>> + Send + 'static,
+    {
+        let prompt = McpPrompt::new(name, description, arguments, builder);
+        self.prompts.retain(|existing| existing.name != prompt.name);
+        self.prompts.push(prompt);
+        self
+    }
+
+    /// Handle a JSON-RPC message from the CLI, received in `session_id`
+    ///
+    /// Returns the JSON-RPC response. Errors of the server are reported as
+    /// JSON-RPC errors, with the code of an [`McpError`] if the server returned
+    /// one and [`McpError::INTERNAL_ERROR`] otherwise.
+    pub async fn handle_jsonrpc(
+        &self,
+        session_id: &str,
+        message: serde_json::Value,
+    ) -> serde_json::Value {
+        let id = message.get("id").cloned().unwrap_or(serde_json::Value::Null);
+        match self.dispatch(session_id, message).await {
+            Ok(result) => serde_json::json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(e) => {
+                let error = e
+                    .downcast_ref::<McpError>()
+                    .cloned()
+                    .unwrap_or_else(|| McpError::internal(e.to_string()));
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": error.code, "message": error.message}
+                })
+            },
+        }
+    }
+
+    async fn dispatch(
+        &self,
+        session_id: &str,
+        message: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let method = message["method"].as_str().unwrap_or_default().to_string();
+        if crate::mcp::resources::is_resource_request(&method, &self.resources, &self.prompts) {
+            return crate::mcp::resources::handle(
+                &self.resources,
+                &self.prompts,
+                &method,
+                &message["params"],
+            )
+            .await;
+        }
+
+        let ctx = self.call_context(session_id, &message);
+        let server = self.server();
+        let mut result = match &self.task_manager {
+            Some(tasks) if crate::mcp::wire::is_task_request(&method, &message["params"]) => {
+                return crate::mcp::wire::handle(tasks, &server, message, ctx).await;
+            },
+            _ => server.handle_message_with_context(message, ctx).await?,
+        };
+
+        if method == "initialize"
+            && let Some(capabilities) = result
+                .get_mut("capabilities")
+                .and_then(serde_json::Value::as_object_mut)
+        {
+            if self.task_manager.is_some() {
+                capabilities.insert("tasks".to_string(), crate::mcp::wire::capabilities());
+            }
+            if !self.resources.is_empty() {
+                capabilities.insert("resources".to_string(), serde_json::json!({}));
+            }
+            if !self.prompts.is_empty() {
+                capabilities.insert("prompts".to_string(), serde_json::json!({}));
+            }
+        }
+        Ok(result)
+    }
+
+    /// The server instance, wrapped in the middleware if there is any
+    fn server(&self) -> Arc<dyn SdkMcpServer> {
+        if self.middleware.is_empty() {
+            return Arc::clone(&self.instance);
+        }
+        Arc::new(MiddlewareServer {
+            inner: Arc::clone(&self.instance),
+            middleware: self.middleware.clone(),
+        })
+    }
+
+    /// Build the context for an MCP message received in `session_id`
+    pub(crate) fn call_context(
+        &self,
+        session_id: &str,
+        message: &serde_json::Value,
+    ) -> ToolCallContext {
+        let tool_use_id = message["params"]["_meta"][TOOL_USE_ID_META_KEY]
+            .as_str()
+            .map(String::from);
+
+        ToolCallContext {
+            session_id: session_id.to_string(),
+            tool_use_id,
+            server_name: self.name.clone(),
+            state: self.session_state.as_ref().map(|store| store.get(session_id)),
+        }
+    }
+}
+
+/// Session id used before the CLI reported one
+pub const DEFAULT_SESSION_ID: &str = "default";
+
+/// How long session state survives without tool calls by default
+pub const DEFAULT_SESSION_IDLE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Key under which the CLI passes the tool use id in MCP request metadata
+const TOOL_USE_ID_META_KEY: &str = "claudecode/toolUseId";
+
+type SessionState = Arc<dyn Any + Send + Sync>;
+
+/// Lazily created tool state, one instance per session
+#[derive(Clone)]
+pub struct SessionStateStore {
+    factory: Arc<dyn Fn() -> SessionState + Send + Sync>,
+    idle_ttl: Duration,
+    sessions: Arc<std::sync::Mutex<HashMap<String, (SessionState, Instant)>>>,
+}
+
+impl SessionStateStore {
+    /// Create a store holding a default `S` per session
+    pub fn new<S>() -> Self
+    where
+        S: Default + Send + Sync + 'static,
+    {
+        Self {
+            factory: Arc::new(|| Arc::new(RwLock::new(S::default())) as SessionState),
+            idle_ttl: DEFAULT_SESSION_IDLE_TTL,
+            sessions: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Set how long an unused session state is kept
+    pub fn with_idle_ttl(mut self, ttl: Duration) -> Self {
+        self.idle_ttl = ttl;
+        self
+    }
+
+    /// Drop the state of `session_id`
+    pub fn end_session(&self, session_id: &str) {
+        self.lock().remove(session_id);
+    }
+
+    /// Number of sessions currently holding state
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check whether no session holds state
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Get the state of `session_id`, creating it if needed and evicting idle sessions
+    fn get(&self, session_id: &str) -> SessionState {
+        let now = Instant::now();
+        let mut sessions = self.lock();
+        sessions.retain(|_, (_, last_used)| now.duration_since(*last_used) < self.idle_ttl);
+
+        let entry = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| ((self.factory)(), now));
+        entry.1 = now;
+        Arc::clone(&entry.0)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (SessionState, Instant)>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Context of a single tool invocation
+#[derive(Clone)]
+pub struct ToolCallContext {
+    /// Session the call belongs to
+    pub session_id: String,
+    /// Id of the tool use block that triggered the call, if the CLI provided it
+    pub tool_use_id: Option<String>,
+    /// Name of the SDK MCP server handling the call
+    pub server_name: String,
+    state: Option<SessionState>,
+}
+
+impl ToolCallContext {
+    /// Create a context without session state
+    pub fn new(session_id: impl Into<String>, server_name: impl Into<String>) -> Self {
+        Self {
+            session_id: session_id.into(),
+            tool_use_id: None,
+            server_name: server_name.into(),
+            state: None,
+        }
+    }
+
+    /// State of the current session
+    ///
+    /// Returns `None` if the server was not configured with
+    /// [`McpSdkServerConfig::with_session_state`] for the type `S`.
+    pub fn session_state<S>(&self) -> Option<Arc<RwLock<S>>>
+    where
+        S: Send + Sync + 'static,
+    {
+        self.state.clone()?.downcast::<RwLock<S>>().ok()
+    }
+}
+
+impl Default for ToolCallContext {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_ID, "")
+    }
+}
+
+impl std::fmt::Debug for ToolCallContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolCallContext")
+            .field("session_id", &self.session_id)
+            .field("tool_use_id", &self.tool_use_id)
+            .field("server_name", &self.server_name)
+            .field("has_state", &self.state.is_some())
+            .finish()
+    }
+}
+
+/// JSON-RPC error of an MCP request
+///
+/// Return it from [`SdkMcpServer::handle_message`] (converted with `into()`) to
+/// choose the error code the CLI receives.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("MCP error {code}: {message}")]
+pub struct McpError {
+    /// JSON-RPC error code
+    pub code: i64,
+    /// Error message
+    pub message: String,
+}
+
+impl McpError {
+    /// The method does not exist
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    /// The parameters are invalid, e.g. an unknown task ID
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// The server failed to handle the request
+    pub const INTERNAL_ERROR: i64 = -32603;
+    /// The requested resource does not exist
+    pub const RESOURCE_NOT_FOUND: i64 = -32002;
+
+    /// Create an error with `code`
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Error for an unknown method
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(Self::METHOD_NOT_FOUND, format!("Unknown method: {}", method))
+    }
+
+    /// Error for invalid parameters
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    /// Error for an unknown resource URI
+    pub fn resource_not_found(uri: &str) -> Self {
+        Self::new(Self::RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri))
+    }
+
+    /// Error for a failure of the server
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(Self::INTERNAL_ERROR, message)
+    }
 }
 
 /// Trait for SDK MCP server implementations
@@ -81,12 +593,34 @@ pub struct McpSdkServerConfig {
 pub trait SdkMcpServer: Send + Sync {
     /// Handle an MCP message
     async fn handle_message(&self, message: serde_json::Value) -> Result<serde_json::Value>;
+
+    /// Handle an MCP message with the context of the calling session
+    ///
+    /// Defaults to [`SdkMcpServer::handle_message`], ignoring the context.
+    async fn handle_message_with_context(
+        &self,
+        message: serde_json::Value,
+        _ctx: ToolCallContext,
+    ) -> Result<serde_json::Value> {
+        self.handle_message(message).await
+    }
 }
 
 /// Tool handler trait
 pub trait ToolHandler: Send + Sync {
     /// Handle a tool invocation
     fn handle(&self, args: serde_json::Value) -> BoxFuture<'static, Result<ToolResult>>;
+
+    /// Handle a tool invocation with the context of the calling session
+    ///
+    /// Defaults to [`ToolHandler::handle`], ignoring the context.
+    fn handle_with_context(
+        &self,
+        args: serde_json::Value,
+        _ctx: ToolCallContext,
+    ) -> BoxFuture<'static, Result<ToolResult>> {
+        self.handle(args)
+    }
 }
 
 /// Tool result
@@ -113,10 +647,145 @@ pub enum ToolResultContent {
         /// Base64-encoded image data
         data: String,
         /// MIME type
+        #[serde(rename = "mimeType", alias = "mime_type")]
         mime_type: String,
     },
 }
 
+impl ToolResult {
+    /// A successful result with a single text block
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::builder().text(text).build()
+    }
+
+    /// A successful result with `value` as pretty-printed JSON text
+    ///
+    /// Use [`ToolResultBuilder::json_compact`] to keep large values on one line.
+    pub fn json(value: serde_json::Value) -> Self {
+        Self::builder().json(value).build()
+    }
+
+    /// A successful result with a PNG image, e.g. a rendered chart
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a PNG image or are over the size
+    /// limit of [`UserContentBlock::image_from_bytes`](crate::UserContentBlock::image_from_bytes).
+    pub fn image_png(bytes: &[u8]) -> Result<Self> {
+        Ok(Self::builder().image_png(bytes)?.build())
+    }
+
+    /// A successful result with a base64-encoded image
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same media types and sizes as
+    /// [`UserContentBlock::image_base64`](crate::UserContentBlock::image_base64).
+    pub fn image_base64(media_type: impl Into<String>, data: impl Into<String>) -> Result<Self> {
+        Ok(Self::builder().image_base64(media_type, data)?.build())
+    }
+
+    /// A failed result, telling Claude what went wrong
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::builder().text(message).error().build()
+    }
+
+    /// Start a result with several content blocks, kept in the order added
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use claude_agent_sdk::ToolResult;
+    /// use serde_json::json;
+    ///
+    /// let result = ToolResult::builder()
+    ///     .text("Revenue by quarter:")
+    ///     .json(json!({"q1": 120, "q2": 135}))
+    ///     .build();
+    /// assert_eq!(result.content.len(), 2);
+    /// ```
+    pub fn builder() -> ToolResultBuilder {
+        ToolResultBuilder::default()
+    }
+}
+
+/// Builds a [`ToolResult`] block by block, see [`ToolResult::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct ToolResultBuilder {
+    content: Vec<ToolResultContent>,
+    is_error: bool,
+}
+
+impl ToolResultBuilder {
+    /// Add a text block
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.content.push(ToolResultContent::Text { text: text.into() });
+        self
+    }
+
+    /// Add `value` as a block of pretty-printed JSON text
+    pub fn json(self, value: serde_json::Value) -> Self {
+        // Serializing a `Value` cannot fail
+        self.text(serde_json::to_string_pretty(&value).unwrap_or_default())
+    }
+
+    /// Add `value` as a block of JSON text on a single line
+    pub fn json_compact(self, value: serde_json::Value) -> Self {
+        self.text(value.to_string())
+    }
+
+    /// Add a PNG image block
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a PNG image or are over the size
+    /// limit.
+    pub fn image_png(self, bytes: &[u8]) -> Result<Self> {
+        crate::types::messages::check_image_size(bytes.len())?;
+        let media_type = crate::types::messages::sniff_image_media_type(bytes)?;
+        if media_type != "image/png" {
+            return Err(crate::errors::ImageValidationError::new(format!(
+                "Expected a PNG image, got {}",
+                media_type
+            ))
+            .into());
+        }
+        self.image_base64(media_type, BASE64.encode(bytes))
+    }
+
+    /// Add a base64-encoded image block
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the media type is not PNG, JPEG, GIF or WebP, or
+    /// the data is over the size limit.
+    pub fn image_base64(
+        mut self,
+        media_type: impl Into<String>,
+        data: impl Into<String>,
+    ) -> Result<Self> {
+        let mime_type = media_type.into();
+        let data = data.into();
+        crate::types::messages::check_image_base64(&mime_type, &data)?;
+        self.content.push(ToolResultContent::Image { data, mime_type });
+        Ok(self)
+    }
+
+    /// Mark the result as an error
+    pub fn error(mut self) -> Self {
+        self.is_error = true;
+        self
+    }
+
+    /// Finish the result
+    pub fn build(self) -> ToolResult {
+        ToolResult {
+            content: self.content,
+            is_error: self.is_error,
+        }
+    }
+}
+
 /// SDK MCP tool definition
 pub struct SdkMcpTool {
     /// Tool name
@@ -129,6 +798,147 @@ pub struct SdkMcpTool {
     pub handler: Arc<dyn ToolHandler>,
 }
 
+impl SdkMcpTool {
+    /// Create a tool whose input is deserialized into `T`
+    ///
+    /// The input schema is generated from `T`. Arguments that do not
+    /// deserialize are answered with an error result naming the offending
+    /// field, without calling `handler`. This is what `tool!` expands to for
+    /// `typed` handlers.
+    pub fn typed<T, F, Fut>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        handler: F,
+    ) -> Self
+    where
+        T: JsonSchema + DeserializeOwned + 'static,
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<ToolResult>> + Send + 'static,
+    {
+        Self::typed_with_context(name, description, move |args, _ctx| handler(args))
+    }
+
+    /// Like [`SdkMcpTool::typed`], passing the [`ToolCallContext`] to `handler`
+    pub fn typed_with_context<T, F, Fut>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        handler: F,
+    ) -> Self
+    where
+        T: JsonSchema + DeserializeOwned + 'static,
+        F: Fn(T, ToolCallContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<ToolResult>> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            input_schema: tool_input_schema::<T>(),
+            handler: Arc::new(TypedToolHandler {
+                handler,
+                _input: PhantomData,
+            }),
+        }
+    }
+}
+
+/// Handler of a tool created with [`SdkMcpTool::typed_with_context`]
+struct TypedToolHandler<T, F> {
+    handler: F,
+    _input: PhantomData<fn(T)>,
+}
+
+impl<T, F, Fut> ToolHandler for TypedToolHandler<T, F>
+where
+    T: DeserializeOwned + 'static,
+    F: Fn(T, ToolCallContext) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<ToolResult>> + Send + 'static,
+{
+    fn handle(&self, args: serde_json::Value) -> BoxFuture<'static, Result<ToolResult>> {
+        self.handle_with_context(args, Default::default())
+    }
+
+    fn handle_with_context(
+        &self,
+        args: serde_json::Value,
+        ctx: ToolCallContext,
+    ) -> BoxFuture<'static, Result<ToolResult>> {
+        match parse_tool_input::<T>(args) {
+            Ok(input) => {
+                let fut = (self.handler)(input, ctx);
+                Box::pin(async move { fut.await.map_err(|e| e.into()) })
+            },
+            Err(e) => Box::pin(std::future::ready(Ok(e.to_tool_result()))),
+        }
+    }
+}
+
+/// JSON schema of the input of a tool taking `T`
+///
+/// Nested types are inlined, so the schema has no `$ref`s unless `T` is
+/// recursive.
+pub fn tool_input_schema<T: JsonSchema>() -> serde_json::Value {
+    let generator = SchemaSettings::draft2020_12()
+        .with(|settings| settings.inline_subschemas = true)
+        .into_generator();
+    let mut schema = generator.into_root_schema_for::<T>().to_value();
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("$schema");
+    }
+    schema
+}
+
+/// Deserialize the arguments of a tool call into `T`
+///
+/// # Errors
+///
+/// Returns a [`ToolInputError`] naming the field that failed to deserialize
+pub fn parse_tool_input<T: DeserializeOwned>(
+    args: serde_json::Value,
+) -> std::result::Result<T, ToolInputError> {
+    serde_path_to_error::deserialize(args).map_err(|e| {
+        let message = e.inner().to_string();
+        let path = e.path().to_string();
+        // serde reports a missing field at its parent
+        let missing = message
+            .strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next());
+        let field = match (missing, path.as_str()) {
+            (Some(name), ".") => name.to_string(),
+            (Some(name), _) => format!("{}.{}", path, name),
+            (None, _) => path,
+        };
+        ToolInputError { field, message }
+    })
+}
+
+/// Arguments of a tool call that do not match the tool's input type
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid value for {field}: {message}")]
+pub struct ToolInputError {
+    /// Path of the offending field, e.g. `address.city` or `items[2]`, or `.`
+    /// for the arguments as a whole
+    pub field: String,
+    /// What is wrong with the field
+    pub message: String,
+}
+
+impl ToolInputError {
+    /// Error result telling the model which field to fix
+    pub fn to_tool_result(&self) -> ToolResult {
+        let error = serde_json::json!({
+            "error": "invalid_arguments",
+            "field": self.field,
+            "message": self.message,
+        });
+        ToolResult {
+            content: vec![ToolResultContent::Text {
+                text: error.to_string(),
+            }],
+            is_error: true,
+        }
+    }
+}
+
 /// Create an in-process MCP server
 pub fn create_sdk_mcp_server(
     name: impl Into<String>,
@@ -144,6 +954,11 @@ pub fn create_sdk_mcp_server(
     McpSdkServerConfig {
         name: server.name.clone(),
         instance: Arc::new(server),
+        session_state: None,
+        task_manager: None,
+        middleware: Vec::new(),
+        resources: Vec::new(),
+        prompts: Vec::new(),
     }
 }
 
@@ -157,6 +972,15 @@ struct DefaultSdkMcpServer {
 #[async_trait]
 impl SdkMcpServer for DefaultSdkMcpServer {
     async fn handle_message(&self, message: serde_json::Value) -> Result<serde_json::Value> {
+        let ctx = ToolCallContext::new(DEFAULT_SESSION_ID, self.name.clone());
+        self.handle_message_with_context(message, ctx).await
+    }
+
+    async fn handle_message_with_context(
+        &self,
+        message: serde_json::Value,
+        ctx: ToolCallContext,
+    ) -> Result<serde_json::Value> {
         // Parse the MCP message
         let method = message["method"]
             .as_str()
@@ -206,24 +1030,98 @@ impl SdkMcpServer for DefaultSdkMcpServer {
                     crate::errors::ClaudeError::Transport(format!("Tool not found: {}", tool_name))
                 })?;
 
-                let result = tool.handler.handle(arguments).await?;
+                let result = tool.handler.handle_with_context(arguments, ctx).await?;
 
                 Ok(serde_json::json!({
                     "content": result.content,
                     "isError": result.is_error
                 }))
             },
-            _ => Err(crate::errors::ClaudeError::Transport(format!(
-                "Unknown method: {}",
-                method
-            ))),
+            _ => Err(McpError::method_not_found(method).into()),
         }
     }
 }
 
 /// Macro to create a tool
+///
+/// Prefix the handler with `with_context` to receive the [`ToolCallContext`]
+/// as a second argument:
+///
+/// ```ignore
+/// tool!("count", "Count calls", json!({"type": "object"}), with_context |_args, ctx| async move {
+///     let counter = ctx.session_state::<u64>().unwrap();
+///     *counter.write().await += 1;
+///     Ok(ToolResult { content: vec![], is_error: false })
+/// })
+/// ```
+///
+/// Prefix the handler with `typed` instead of passing a schema to take the
+/// arguments as a type deriving `Deserialize` and `JsonSchema`, see
+/// [`SdkMcpTool::typed`]. `typed with_context` combines both:
+///
+/// ```ignore
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Greet {
+///     name: String,
+/// }
+///
+/// tool!("greet", "Greet someone", typed |args: Greet| async move {
+///     Ok(ToolResult {
+///         content: vec![ToolResultContent::Text { text: format!("Hello, {}!", args.name) }],
+///         is_error: false,
+///     })
+/// })
+/// ```
 #[macro_export]
 macro_rules! tool {
+    ($name:expr, $desc:expr, typed with_context $handler:expr) => {
+        $crate::types::mcp::SdkMcpTool::typed_with_context($name, $desc, $handler)
+    };
+    ($name:expr, $desc:expr, typed $handler:expr) => {
+        $crate::types::mcp::SdkMcpTool::typed($name, $desc, $handler)
+    };
+    ($name:expr, $desc:expr, $schema:expr, with_context $handler:expr) => {{
+        struct Handler<F>(F);
+
+        impl<F, Fut> $crate::types::mcp::ToolHandler for Handler<F>
+        where
+            F: Fn(serde_json::Value, $crate::types::mcp::ToolCallContext) -> Fut + Send + Sync,
+            Fut: std::future::Future<Output = anyhow::Result<$crate::types::mcp::ToolResult>>
+                + Send
+                + 'static,
+        {
+            fn handle(
+                &self,
+                args: serde_json::Value,
+            ) -> futures::future::BoxFuture<
+                'static,
+                $crate::errors::Result<$crate::types::mcp::ToolResult>,
+            > {
+                self.handle_with_context(args, Default::default())
+            }
+
+            fn handle_with_context(
+                &self,
+                args: serde_json::Value,
+                ctx: $crate::types::mcp::ToolCallContext,
+            ) -> futures::future::BoxFuture<
+                'static,
+                $crate::errors::Result<$crate::types::mcp::ToolResult>,
+            > {
+                use futures::FutureExt;
+                let f = &self.0;
+                let fut = f(args, ctx);
+                async move { fut.await.map_err(|e| e.into()) }.boxed()
+            }
+        }
+
+        $crate::types::mcp::SdkMcpTool {
+            name: $name.to_string(),
+            description: $desc.to_string(),
+            input_schema: $schema,
+            handler: std::sync::Arc::new(Handler($handler)),
+        }
+    }};
     ($name:expr, $desc:expr, $schema:expr, $handler:expr) => {{
         struct Handler<F>(F);
 
@@ -256,3 +1154,398 @@ macro_rules! tool {
         }
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct Counter(u64);
+
+    fn counter_server() -> McpSdkServerConfig {
+        let tool = crate::tool!(
+            "count",
+            "Increment a per-session counter",
+            json!({"type": "object"}),
+            with_context |_args, ctx: ToolCallContext| async move {
+                let counter = ctx.session_state::<Counter>().expect("session state");
+                let mut counter = counter.write().await;
+                counter.0 += 1;
+                Ok(ToolResult {
+                    content: vec![ToolResultContent::Text {
+                        text: format!("{}:{}", ctx.session_id, counter.0),
+                    }],
+                    is_error: false,
+                })
+            }
+        );
+
+        create_sdk_mcp_server("counter", "1.0.0", vec![tool]).with_session_state::<Counter>()
+    }
+
+    fn call_message(tool_use_id: &str) -> serde_json::Value {
+        json!({
+            "method": "tools/call",
+            "params": {
+                "name": "count",
+                "arguments": {},
+                "_meta": {"claudecode/toolUseId": tool_use_id}
+            }
+        })
+    }
+
+    async fn call(config: &McpSdkServerConfig, session_id: &str) -> String {
+        let message = call_message("toolu_1");
+        let ctx = config.call_context(session_id, &message);
+        let response = config
+            .instance
+            .handle_message_with_context(message, ctx)
+            .await
+            .unwrap();
+        response["content"][0]["text"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_interleaved_sessions_have_isolated_state() {
+        let config = counter_server();
+
+        assert_eq!(call(&config, "session-a").await, "session-a:1");
+        assert_eq!(call(&config, "session-b").await, "session-b:1");
+        assert_eq!(call(&config, "session-a").await, "session-a:2");
+        assert_eq!(call(&config, "session-a").await, "session-a:3");
+        assert_eq!(call(&config, "session-b").await, "session-b:2");
+
+        assert_eq!(config.session_state.as_ref().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sessions_do_not_interfere() {
+        let config = counter_server();
+
+        let calls = (0..20).map(|i| {
+            let config = config.clone();
+            async move {
+                let session = if i % 2 == 0 { "even" } else { "odd" };
+                call(&config, session).await
+            }
+        });
+        futures::future::join_all(calls).await;
+
+        assert_eq!(call(&config, "even").await, "even:11");
+        assert_eq!(call(&config, "odd").await, "odd:11");
+    }
+
+    #[tokio::test]
+    async fn test_end_session_resets_state() {
+        let config = counter_server();
+        call(&config, "session-a").await;
+        call(&config, "session-a").await;
+
+        config.session_state.as_ref().unwrap().end_session("session-a");
+        assert!(config.session_state.as_ref().unwrap().is_empty());
+        assert_eq!(call(&config, "session-a").await, "session-a:1");
+    }
+
+    #[tokio::test]
+    async fn test_idle_sessions_are_evicted() {
+        let config = counter_server().with_session_idle_ttl(Duration::from_millis(20));
+        call(&config, "stale").await;
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        call(&config, "fresh").await;
+
+        let store = config.session_state.as_ref().unwrap();
+        assert_eq!(store.len(), 1);
+        assert_eq!(call(&config, "stale").await, "stale:1");
+    }
+
+    #[test]
+    fn test_call_context_reads_tool_use_id() {
+        let config = counter_server();
+        let ctx = config.call_context("session-a", &call_message("toolu_42"));
+        assert_eq!(ctx.session_id, "session-a");
+        assert_eq!(ctx.tool_use_id.as_deref(), Some("toolu_42"));
+        assert_eq!(ctx.server_name, "counter");
+        assert!(ctx.session_state::<Counter>().is_some());
+        assert!(ctx.session_state::<String>().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_context_free_tools_still_work() {
+        let tool = crate::tool!("echo", "Echo", json!({"type": "object"}), |args: serde_json::Value| async move {
+            Ok(ToolResult {
+                content: vec![ToolResultContent::Text {
+                    text: args.to_string(),
+                }],
+                is_error: false,
+            })
+        });
+        let config = create_sdk_mcp_server("plain", "1.0.0", vec![tool]);
+        assert!(config.call_context("s", &json!({})).session_state::<Counter>().is_none());
+
+        let response = config
+            .instance
+            .handle_message(json!({
+                "method": "tools/call",
+                "params": {"name": "echo", "arguments": {"x": 1}}
+            }))
+            .await
+            .unwrap();
+        assert_eq!(response["content"][0]["text"], "{\"x\":1}");
+    }
+
+    fn slow_server(delay: Duration) -> McpSdkServerConfig {
+        let tool = crate::tool!(
+            "slow",
+            "Answer after a delay",
+            json!({"type": "object"}),
+            move |_args: serde_json::Value| async move {
+                tokio::time::sleep(delay).await;
+                Ok(ToolResult {
+                    content: vec![ToolResultContent::Text {
+                        text: "done".to_string(),
+                    }],
+                    is_error: false,
+                })
+            }
+        );
+        create_sdk_mcp_server("jobs", "1.0.0", vec![tool]).with_task_manager(TaskManager::new())
+    }
+
+    async fn request(
+        config: &McpSdkServerConfig,
+        method: &str,
+        params: serde_json::Value,
+    ) -> serde_json::Value {
+        let message = json!({"jsonrpc": "2.0", "id": 7, "method": method, "params": params});
+        config.handle_jsonrpc("s", message).await
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_as_task() {
+        let config = slow_server(Duration::from_millis(50));
+
+        let initialized = request(&config, "initialize", json!({})).await;
+        let capabilities = &initialized["result"]["capabilities"];
+        assert!(capabilities["tasks"]["requests"]["tools"]["call"].is_object());
+
+        let created = request(
+            &config,
+            "tools/call",
+            json!({"name": "slow", "arguments": {}, "task": {"ttl": 60000}}),
+        )
+        .await;
+        assert_eq!(created["id"], 7);
+        let task = &created["result"]["task"];
+        assert_eq!(task["status"], "working");
+        let task_id = task["taskId"].as_str().unwrap();
+
+        let result = request(&config, "tasks/result", json!({"taskId": task_id})).await;
+        assert_eq!(result["result"]["content"][0]["text"], "done");
+        assert_eq!(
+            result["result"]["_meta"]["io.modelcontextprotocol/related-task"]["taskId"],
+            task_id
+        );
+
+        let status = request(&config, "tasks/get", json!({"taskId": task_id})).await;
+        assert_eq!(status["result"]["status"], "completed");
+        let listed = request(&config, "tasks/list", json!({})).await;
+        assert_eq!(listed["result"]["tasks"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_task_create_and_cancel() {
+        let config = slow_server(Duration::from_secs(30));
+
+        let created = request(
+            &config,
+            "tasks/create",
+            json!({"method": "tools/call", "params": {"name": "slow", "arguments": {}}}),
+        )
+        .await;
+        let task_id = created["result"]["task"]["taskId"].as_str().unwrap().to_string();
+
+        let cancelled = request(&config, "tasks/cancel", json!({"taskId": task_id})).await;
+        assert_eq!(cancelled["result"]["status"], "cancelled");
+        let result = request(&config, "tasks/result", json!({"taskId": task_id})).await;
+        assert_eq!(result["error"]["code"], McpError::INTERNAL_ERROR);
+
+        let unsupported = request(&config, "tasks/create", json!({"method": "tools/list"})).await;
+        assert_eq!(unsupported["error"]["code"], McpError::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_task_errors_are_jsonrpc_errors() {
+        let config = slow_server(Duration::ZERO);
+
+        let unknown = request(&config, "tasks/get", json!({"taskId": "nope"})).await;
+        assert_eq!(unknown["id"], 7);
+        assert_eq!(unknown["error"]["code"], McpError::INVALID_PARAMS);
+        let missing = request(&config, "tasks/cancel", json!({})).await;
+        assert_eq!(missing["error"]["code"], McpError::INVALID_PARAMS);
+        let method = request(&config, "tasks/frobnicate", json!({})).await;
+        assert_eq!(method["error"]["code"], McpError::METHOD_NOT_FOUND);
+
+        // Without a task manager, task methods are unknown to the server
+        let plain = create_sdk_mcp_server("plain", "1.0.0", vec![]);
+        let response = request(&plain, "tasks/get", json!({"taskId": "nope"})).await;
+        assert_eq!(response["error"]["code"], McpError::METHOD_NOT_FOUND);
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Unit {
+        Celsius,
+        DegreesFahrenheit,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct Location {
+        city: String,
+        country: Option<String>,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct Forecast {
+        location: Location,
+        unit: Unit,
+        days: Option<u32>,
+    }
+
+    fn forecast_tool() -> SdkMcpTool {
+        crate::tool!("forecast", "Weather forecast", typed |args: Forecast| async move {
+            let unit = match args.unit {
+                Unit::Celsius => "C",
+                Unit::DegreesFahrenheit => "F",
+            };
+            let place = match args.location.country {
+                Some(country) => format!("{}, {}", args.location.city, country),
+                None => args.location.city,
+            };
+            Ok(ToolResult {
+                content: vec![ToolResultContent::Text {
+                    text: format!("{} {} {}", place, args.days.unwrap_or(1), unit),
+                }],
+                is_error: false,
+            })
+        })
+    }
+
+    #[test]
+    fn test_typed_tool_schema() {
+        let schema = forecast_tool().input_schema;
+        assert_eq!(schema["type"], "object");
+        assert!(schema.get("$schema").is_none());
+        assert_eq!(schema["required"], json!(["location", "unit"]));
+        assert_eq!(
+            schema["properties"]["unit"]["enum"],
+            json!(["celsius", "degrees_fahrenheit"])
+        );
+        // Nested structs are inlined rather than referenced
+        let location = &schema["properties"]["location"];
+        assert_eq!(location["type"], "object");
+        assert_eq!(location["required"], json!(["city"]));
+        assert!(location["properties"]["country"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_typed_tool_deserializes_arguments() {
+        let config = create_sdk_mcp_server("weather", "1.0.0", vec![forecast_tool()]);
+        let call = |arguments: serde_json::Value| {
+            config.instance.handle_message(json!({
+                "method": "tools/call",
+                "params": {"name": "forecast", "arguments": arguments}
+            }))
+        };
+
+        let response = call(json!({"location": {"city": "Oslo"}, "unit": "degrees_fahrenheit"}))
+            .await
+            .unwrap();
+        assert_eq!(response["isError"], false);
+        assert_eq!(response["content"][0]["text"], "Oslo 1 F");
+
+        let response = call(json!({"location": {"city": 7}, "unit": "celsius"}))
+            .await
+            .unwrap();
+        assert_eq!(response["isError"], true);
+        let error: serde_json::Value =
+            serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(error["error"], "invalid_arguments");
+        assert_eq!(error["field"], "location.city");
+    }
+
+    #[test]
+    fn test_parse_tool_input_names_the_field() {
+        let missing = parse_tool_input::<Forecast>(json!({"location": {}, "unit": "celsius"}));
+        assert_eq!(missing.err().unwrap().field, "location.city");
+
+        let missing = parse_tool_input::<Forecast>(json!({"location": {"city": "Oslo"}}));
+        assert_eq!(missing.err().unwrap().field, "unit");
+
+        let variant = parse_tool_input::<Forecast>(
+            json!({"location": {"city": "Oslo"}, "unit": "kelvin"}),
+        );
+        let error = variant.err().unwrap();
+        assert_eq!(error.field, "unit");
+        assert!(error.message.contains("kelvin"));
+    }
+
+    const PNG_HEADER: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0];
+
+    #[test]
+    fn test_tool_result_round_trips_mixed_content() {
+        let result = ToolResult::builder()
+            .text("Chart:")
+            .image_png(PNG_HEADER)
+            .unwrap()
+            .json_compact(json!({"points": 3}))
+            .build();
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            value["content"],
+            json!([
+                {"type": "text", "text": "Chart:"},
+                {"type": "image", "data": "iVBORw0KGgoAAA==", "mimeType": "image/png"},
+                {"type": "text", "text": "{\"points\":3}"}
+            ])
+        );
+
+        let parsed: ToolResult = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&result).unwrap()
+        );
+
+        // Content written before the rename still reads
+        let legacy = json!({"type": "image", "data": "AA==", "mime_type": "image/gif"});
+        let legacy: ToolResultContent = serde_json::from_value(legacy).unwrap();
+        assert!(matches!(
+            legacy,
+            ToolResultContent::Image { mime_type, .. } if mime_type == "image/gif"
+        ));
+    }
+
+    #[test]
+    fn test_tool_result_constructors() {
+        let ToolResultContent::Text { text } = &ToolResult::json(json!({"a": 1})).content[0] else {
+            panic!("expected text");
+        };
+        assert_eq!(text, "{\n  \"a\": 1\n}");
+
+        let error = ToolResult::error("no such file");
+        assert!(error.is_error);
+        assert!(!ToolResult::text("ok").is_error);
+
+        assert!(ToolResult::image_base64("image/webp", "UklGRg==").is_ok());
+        assert!(matches!(
+            ToolResult::image_base64("image/svg+xml", "PHN2Zz4="),
+            Err(crate::ClaudeError::ImageValidation(_))
+        ));
+        assert!(matches!(
+            ToolResult::image_png(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Err(crate::ClaudeError::ImageValidation(_))
+        ));
+    }
+}
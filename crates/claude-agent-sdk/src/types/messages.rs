@@ -1,6 +1,15 @@
 //! Message types for Claude Agent SDK
 
-use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::errors::{ImageValidationError, Result, StructuredOutputError};
+use crate::types::usage::{ModelTokenUsage, Usage};
 
 /// Supported image MIME types for Claude API
 const SUPPORTED_IMAGE_MIME_TYPES: &[&str] = &["image/jpeg", "image/png", "image/gif", "image/webp"];
@@ -8,12 +17,120 @@ const SUPPORTED_IMAGE_MIME_TYPES: &[&str] = &["image/jpeg", "image/png", "image/
 /// Maximum base64 data size (15MB results in ~20MB decoded, within Claude's limits)
 const MAX_BASE64_SIZE: usize = 15_728_640;
 
+/// Maximum image size in bytes, the most whose base64 fits in [`MAX_BASE64_SIZE`]
+const MAX_IMAGE_SIZE: usize = MAX_BASE64_SIZE / 4 * 3;
+
 /// Allowed URL schemes for image URLs (SSRF prevention)
 const ALLOWED_URL_SCHEMES: &[&str] = &["https", "http"];
 
+/// Supported document MIME types for Claude API
+const SUPPORTED_DOCUMENT_MIME_TYPES: &[&str] = &["application/pdf"];
+
+/// Maximum base64 document size (32MB, the API's request size limit)
+const MAX_DOCUMENT_BASE64_SIZE: usize = 33_554_432;
+
 /// Maximum URL length to prevent DoS
 const MAX_URL_LENGTH: usize = 8192;
 
+/// Check that an image of `size` bytes fits in [`MAX_IMAGE_SIZE`]
+pub(crate) fn check_image_size(size: usize) -> std::result::Result<(), ImageValidationError> {
+    if size > MAX_IMAGE_SIZE {
+        return Err(ImageValidationError::TooLarge {
+            actual: size,
+            limit: MAX_IMAGE_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// A file length as a `usize`, saturating on 32-bit targets
+fn file_size(len: u64) -> usize {
+    usize::try_from(len).unwrap_or(usize::MAX)
+}
+
+/// The media type of an image, detected from its magic bytes
+pub(crate) fn sniff_image_media_type(
+    bytes: &[u8],
+) -> std::result::Result<&'static str, ImageValidationError> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Ok("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Ok("image/jpeg"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Ok("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Ok("image/webp"),
+        _ => {
+            let detected = match bytes {
+                [b'B', b'M', ..] => "BMP",
+                [b'I', b'I', 0x2A, 0x00, ..] | [b'M', b'M', 0x00, 0x2A, ..] => "TIFF",
+                [0x00, 0x00, 0x01, 0x00, ..] => "ICO",
+                [_, _, _, _, b'f', b't', b'y', b'p', ..] => "HEIF/AVIF",
+                _ => "unknown",
+            };
+            Err(ImageValidationError::UnsupportedFormat {
+                detected: detected.to_string(),
+            })
+        },
+    }
+}
+
+/// Check that base64 image `data` of `media_type` may be sent to Claude
+pub(crate) fn check_image_base64(
+    media_type: &str,
+    data: &str,
+) -> std::result::Result<(), ImageValidationError> {
+    if !SUPPORTED_IMAGE_MIME_TYPES.contains(&media_type) {
+        return Err(ImageValidationError::new(format!(
+            "Unsupported media type '{}'. Supported types: {:?}",
+            media_type, SUPPORTED_IMAGE_MIME_TYPES
+        )));
+    }
+    if data.len() > MAX_BASE64_SIZE {
+        return Err(ImageValidationError::TooLarge {
+            actual: data.len(),
+            limit: MAX_BASE64_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// Check that `url` may be sent as the source of a `kind` block
+///
+/// Only `https://` and `http://` URLs with a host are allowed (SSRF prevention).
+fn check_url(url: &str, kind: &str) -> std::result::Result<(), String> {
+    // Validate URL is not empty
+    if url.is_empty() {
+        return Err(format!("{} URL cannot be empty", kind));
+    }
+
+    // Validate URL length
+    if url.len() > MAX_URL_LENGTH {
+        return Err(format!(
+            "URL exceeds maximum length of {} characters",
+            MAX_URL_LENGTH
+        ));
+    }
+
+    // Parse and validate URL scheme (SSRF prevention)
+    let scheme = url
+        .split("://")
+        .next()
+        .map(|s| s.to_lowercase())
+        .unwrap_or_default();
+
+    if !ALLOWED_URL_SCHEMES.contains(&scheme.as_str()) {
+        return Err(format!(
+            "Invalid URL scheme '{}'. Only {} are allowed",
+            scheme,
+            ALLOWED_URL_SCHEMES.join(", ")
+        ));
+    }
+
+    // Basic URL format validation (must have scheme and host)
+    if !url.contains("://") || url.split("://").nth(1).is_none_or(|h| h.is_empty()) {
+        return Err("Invalid URL format: must include scheme and host".to_string());
+    }
+    Ok(())
+}
+
 /// Error types for assistant messages
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -33,7 +150,9 @@ pub enum AssistantMessageError {
 }
 
 /// Main message enum containing all message types from CLI
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Messages of a type this SDK does not know yet parse into [`Message::Unknown`].
+#[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Message {
     /// Assistant message
@@ -54,6 +173,249 @@ pub enum Message {
     /// Control cancel request (ignore this - it's internal control protocol)
     #[serde(rename = "control_cancel_request")]
     ControlCancelRequest(serde_json::Value),
+    /// Answer to a slash command handled by the client's command registry
+    ///
+    /// Never sent by the CLI; see
+    /// [`ClaudeClient::with_command_registry`](crate::ClaudeClient::with_command_registry).
+    #[serde(rename = "local_command")]
+    LocalCommand(LocalCommandMessage),
+    /// Message of an unknown type, kept as received including its `type`
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        Ok(match type_tag(&value) {
+            Some("assistant") => {
+                let mut assistant: AssistantMessage = from_tagged(value)?;
+                assistant.token_usage =
+                    assistant.message.usage.as_ref().and_then(Usage::from_value);
+                Message::Assistant(assistant)
+            },
+            Some("system") => Message::System(from_tagged(value)?),
+            Some("result") => {
+                let mut result: ResultMessage = from_tagged(value)?;
+                result.token_usage = result.parse_token_usage();
+                Message::Result(result)
+            },
+            Some("stream_event") => Message::StreamEvent(from_tagged(value)?),
+            Some("user") => Message::User(from_tagged(value)?),
+            Some("local_command") => Message::LocalCommand(from_tagged(value)?),
+            Some("control_cancel_request") => {
+                if let Some(object) = value.as_object_mut() {
+                    object.remove("type");
+                }
+                Message::ControlCancelRequest(value)
+            },
+            _ => Message::Unknown(value),
+        })
+    }
+}
+
+/// The `type` tag of a message or content block
+fn type_tag(value: &serde_json::Value) -> Option<&str> {
+    value.get("type").and_then(serde_json::Value::as_str)
+}
+
+/// Deserialize the content of a tagged message or content block
+fn from_tagged<T: DeserializeOwned, E: serde::de::Error>(
+    value: serde_json::Value,
+) -> std::result::Result<T, E> {
+    T::deserialize(value).map_err(E::custom)
+}
+
+impl Message {
+    /// The message's `type` tag in the CLI's stream-json output
+    ///
+    /// Returns `"unknown"` for [`Message::Unknown`]; see [`Message::unknown_type`].
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Message::Assistant(_) => "assistant",
+            Message::System(_) => "system",
+            Message::Result(_) => "result",
+            Message::StreamEvent(_) => "stream_event",
+            Message::User(_) => "user",
+            Message::ControlCancelRequest(_) => "control_cancel_request",
+            Message::LocalCommand(_) => "local_command",
+            Message::Unknown(_) => "unknown",
+        }
+    }
+
+    /// The `type` of the first unknown message or content block in this message
+    ///
+    /// Returns `None` when every part of the message is of a known type.
+    pub fn unknown_type(&self) -> Option<&str> {
+        let blocks: &[ContentBlock] = match self {
+            Message::Unknown(value) => return Some(type_tag(value).unwrap_or("")),
+            Message::Assistant(assistant) => &assistant.message.content,
+            Message::User(user) => user.content.as_deref().unwrap_or_default(),
+            _ => &[],
+        };
+        blocks.iter().find_map(|block| match block {
+            ContentBlock::Unknown(value) => Some(type_tag(value).unwrap_or("")),
+            _ => None,
+        })
+    }
+}
+
+/// Messages of a single turn, collected in the order they were received
+#[derive(Debug, Clone, Default)]
+pub struct CollectedResponse {
+    /// Received messages
+    pub messages: Vec<Message>,
+}
+
+impl CollectedResponse {
+    /// Concatenated text blocks of all assistant messages
+    pub fn text(&self) -> String {
+        self.messages
+            .iter()
+            .filter_map(|message| match message {
+                Message::Assistant(assistant) => Some(&assistant.message.content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|block| match block {
+                ContentBlock::Text(text) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The result message, if the turn finished
+    pub fn result(&self) -> Option<&ResultMessage> {
+        self.messages.iter().rev().find_map(|message| match message {
+            Message::Result(result) => Some(result),
+            _ => None,
+        })
+    }
+
+    /// Check whether the turn finished with a result message
+    pub fn is_complete(&self) -> bool {
+        self.result().is_some()
+    }
+
+    /// Structured output of the turn
+    ///
+    /// Uses [`ResultMessage::structured_output`]. When it is absent and `lenient` is set,
+    /// falls back to the last fenced JSON block in the assistant text.
+    pub fn structured_output(&self, lenient: bool) -> Option<serde_json::Value> {
+        resolve_structured_output(self.result(), &self.text(), lenient)
+    }
+
+    /// Deserialize the structured output of the turn into `T`
+    pub fn structured_as<T: DeserializeOwned>(&self, lenient: bool) -> Result<T> {
+        deserialize_structured(self.structured_output(lenient))
+    }
+}
+
+/// How a turn ended after [`ClaudeClient::interrupt_and_wait`](crate::ClaudeClient::interrupt_and_wait)
+#[derive(Debug, Clone)]
+pub enum InterruptOutcome {
+    /// The running turn ended with this result
+    ///
+    /// The turn may have finished on its own before the interrupt reached the CLI.
+    Stopped(Box<ResultMessage>),
+    /// The CLI acknowledged the interrupt, but the running turn's result did not arrive in time
+    Acknowledged,
+    /// No turn was running; the CLI acknowledged the interrupt
+    Idle,
+    /// Neither an acknowledgement nor a result arrived in time
+    TimedOut,
+}
+
+/// How a turn ended after [`ClaudeClient::compact`](crate::ClaudeClient::compact)
+#[derive(Debug, Clone)]
+pub enum CompactionOutcome {
+    /// The conversation was compacted
+    Compacted(CompactBoundary),
+    /// The turn ended with this result without compacting, for example
+    /// because a `PreCompact` hook blocked it
+    Skipped(Box<ResultMessage>),
+}
+
+/// A compaction, as reported by a `compact_boundary` system message
+///
+/// See [`SystemMessage::compact_boundary`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompactBoundary {
+    /// What started the compaction: `"manual"` or `"auto"`
+    pub trigger: String,
+    /// Tokens in the context before it was compacted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_tokens: Option<u64>,
+}
+
+/// Structured output from `result`, or from the last fenced JSON block of `text` if `lenient`
+pub(crate) fn resolve_structured_output(
+    result: Option<&ResultMessage>,
+    text: &str,
+    lenient: bool,
+) -> Option<serde_json::Value> {
+    if let Some(value) = result.and_then(ResultMessage::structured_output) {
+        return Some(value.clone());
+    }
+    if !lenient {
+        return None;
+    }
+    extract_fenced_json(text).or_else(|| {
+        result
+            .and_then(|r| r.result.as_deref())
+            .and_then(extract_fenced_json)
+    })
+}
+
+/// Deserialize structured output into `T`, keeping the raw value on failure
+pub(crate) fn deserialize_structured<T: DeserializeOwned>(
+    value: Option<serde_json::Value>,
+) -> Result<T> {
+    let value = value.ok_or_else(StructuredOutputError::missing)?;
+    serde_json::from_value(value.clone())
+        .map_err(|e| StructuredOutputError::from_serde::<T>(value, &e).into())
+}
+
+/// Last fenced code block in `text` that is tagged `json` (or untagged) and parses as JSON
+fn extract_fenced_json(text: &str) -> Option<serde_json::Value> {
+    let mut blocks = Vec::new();
+    // (whether the open block may hold JSON, its body)
+    let mut open: Option<(bool, String)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        match open.as_mut() {
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```") {
+                    let info = info.trim();
+                    open = Some((info.is_empty() || info.eq_ignore_ascii_case("json"), String::new()));
+                }
+            },
+            Some((json, body)) => {
+                if trimmed.starts_with("```") {
+                    if *json {
+                        blocks.push(std::mem::take(body));
+                    }
+                    open = None;
+                } else {
+                    body.push_str(line);
+                    body.push('\n');
+                }
+            },
+        }
+    }
+
+    blocks
+        .iter()
+        .rev()
+        .find_map(|body| serde_json::from_str(body).ok())
+}
+
+impl From<Vec<Message>> for CollectedResponse {
+    fn from(messages: Vec<Message>) -> Self {
+        Self { messages }
+    }
 }
 
 /// User message
@@ -120,6 +482,10 @@ pub struct AssistantMessage {
     /// UUID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub uuid: Option<String>,
+    /// Typed [`usage`](AssistantMessageInner::usage), filled in when the
+    /// message is parsed
+    #[serde(skip)]
+    pub token_usage: Option<Usage>,
 }
 
 /// Inner assistant message content
@@ -145,6 +511,23 @@ pub struct AssistantMessageInner {
     pub error: Option<AssistantMessageError>,
 }
 
+/// Output of a slash command run by the client instead of the CLI
+///
+/// Delivered on the receive stream as a turn of its own: it ends a
+/// [`receive_response`](crate::ClaudeClient::receive_response) stream the way
+/// a result message does.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalCommandMessage {
+    /// The command line as sent, e.g. `/deploy staging`
+    pub command: String,
+    /// Output of the command, or its error message
+    pub output: String,
+    /// Whether the command failed
+    pub is_error: bool,
+    /// Session the command was sent to
+    pub session_id: String,
+}
+
 /// System message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMessage {
@@ -176,6 +559,24 @@ pub struct SystemMessage {
     pub data: serde_json::Value,
 }
 
+impl SystemMessage {
+    /// The compaction this message marks, if its subtype is `compact_boundary`
+    ///
+    /// The CLI sends it once the conversation before it was replaced by a
+    /// summary, whether compaction was requested or automatic.
+    pub fn compact_boundary(&self) -> Option<CompactBoundary> {
+        if self.subtype != "compact_boundary" {
+            return None;
+        }
+        let metadata = self.data.get("compact_metadata");
+        Some(
+            metadata
+                .and_then(|metadata| CompactBoundary::deserialize(metadata).ok())
+                .unwrap_or_default(),
+        )
+    }
+}
+
 /// Result message indicating query completion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResultMessage {
@@ -194,18 +595,116 @@ pub struct ResultMessage {
     /// Total cost in USD
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_cost_usd: Option<f64>,
-    /// Usage statistics
+    /// Usage statistics, as sent by the CLI; see [`token_usage`](Self::token_usage)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<serde_json::Value>,
+    /// Usage of each model, keyed by model ID, as sent by the CLI in `modelUsage`
+    ///
+    /// `None` for CLIs that do not report it, or report it in a shape this SDK
+    /// does not know.
+    #[serde(
+        rename = "modelUsage",
+        default,
+        deserialize_with = "lenient_model_usage",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub model_usage: Option<BTreeMap<String, ModelTokenUsage>>,
+    /// Typed `usage` and `modelUsage`, filled in when the message is parsed
+    #[serde(skip)]
+    pub token_usage: Option<Usage>,
+    /// Tool uses the CLI did not allow during the turn
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permission_denials: Vec<PermissionDenial>,
     /// Result text (if any)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<String>,
     /// Structured output (when output_format is specified)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub structured_output: Option<serde_json::Value>,
+    /// Requested and actual model of the turn, filled in by the SDK
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<ModelFallback>,
+}
+
+/// Deserialize `modelUsage`, ignoring it rather than failing the whole result
+/// when its shape is unexpected
+fn lenient_model_usage<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<BTreeMap<String, ModelTokenUsage>>, D::Error> {
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value.and_then(|value| BTreeMap::deserialize(value).ok()))
+}
+
+/// A tool use the CLI did not allow, listed by
+/// [`ResultMessage::permission_denials`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionDenial {
+    /// Name of the tool, such as `"Bash"`
+    pub tool_name: String,
+    /// ID of the `tool_use` block that was denied
+    pub tool_use_id: String,
+    /// Input Claude gave the tool
+    pub tool_input: serde_json::Value,
+}
+
+/// Model that served a turn, compared with the requested one
+///
+/// The CLI switches to `fallback_model` when the requested model is unavailable.
+/// Only the model reported on assistant messages reveals that, so the SDK compares
+/// it with the model in effect when the turn started, including changes made
+/// through [`ClaudeClient::set_model`](crate::ClaudeClient::set_model).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelFallback {
+    /// Model configured for the turn (`None` for the CLI default)
+    pub requested_model: Option<String>,
+    /// Model reported by the last assistant message of the turn
+    pub actual_model: Option<String>,
+    /// Whether the CLI answered with the fallback model
+    pub fallback_used: bool,
+}
+
+impl ResultMessage {
+    /// Tool uses that were blocked, by permission rules, the permission mode
+    /// or a `can_use_tool` callback
+    pub fn permission_denials(&self) -> &[PermissionDenial] {
+        &self.permission_denials
+    }
+
+    /// Parse `usage` and `modelUsage` into a [`Usage`]
+    fn parse_token_usage(&self) -> Option<Usage> {
+        let by_model = self.model_usage.clone().unwrap_or_default();
+        let usage = self.usage.as_ref().and_then(Usage::from_value);
+        if usage.is_none() && by_model.is_empty() {
+            return None;
+        }
+        Some(Usage {
+            by_model,
+            ..usage.unwrap_or_default()
+        })
+    }
+
+    /// Structured output produced for a `json_schema` output format
+    ///
+    /// The CLI reports it in the top-level `structured_output` field of the result.
+    pub fn structured_output(&self) -> Option<&serde_json::Value> {
+        self.structured_output.as_ref()
+    }
+
+    /// Deserialize the structured output into `T`
+    ///
+    /// Fails with [`ClaudeError::StructuredOutput`](crate::ClaudeError::StructuredOutput)
+    /// if there is no structured output or it does not match `T`.
+    pub fn structured_as<T: DeserializeOwned>(&self) -> Result<T> {
+        deserialize_structured(self.structured_output.clone())
+    }
 }
 
 /// Stream event message
+///
+/// Sent with [`ClaudeAgentOptions::include_partial_messages`](crate::ClaudeAgentOptions::include_partial_messages)
+/// while Claude is answering. `event` is the Messages API streaming event as
+/// received; [`data`](Self::data) gives a typed view of it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamEvent {
     /// Event UUID
@@ -219,9 +718,118 @@ pub struct StreamEvent {
     pub parent_tool_use_id: Option<String>,
 }
 
-/// Content block types
+impl StreamEvent {
+    /// The event as a typed [`StreamEventData`]
+    pub fn data(&self) -> StreamEventData {
+        serde_json::from_value(self.event.clone())
+            .unwrap_or_else(|_| StreamEventData::Unknown(self.event.clone()))
+    }
+
+    /// Text added by a `content_block_delta` event with a `text_delta`
+    ///
+    /// Reads the text without copying the event, for rendering text as it arrives.
+    pub fn text_delta(&self) -> Option<&str> {
+        if self.event.get("type")?.as_str()? != "content_block_delta" {
+            return None;
+        }
+        let delta = self.event.get("delta")?;
+        if delta.get("type")?.as_str()? != "text_delta" {
+            return None;
+        }
+        delta.get("text")?.as_str()
+    }
+}
+
+/// Messages API streaming event, as carried by a [`StreamEvent`]
+///
+/// Events of other types or shapes are kept as [`StreamEventData::Unknown`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEventData {
+    /// Start of a message, with its content still empty
+    MessageStart {
+        /// The message without content
+        message: AssistantMessageInner,
+    },
+    /// Start of a content block
+    ContentBlockStart {
+        /// Position of the block in the message
+        index: usize,
+        /// The block, with empty text or input
+        content_block: ContentBlock,
+    },
+    /// Incremental content of a block
+    ContentBlockDelta {
+        /// Position of the block in the message
+        index: usize,
+        /// Content added to the block
+        delta: ContentBlockDelta,
+    },
+    /// End of a content block
+    ContentBlockStop {
+        /// Position of the block in the message
+        index: usize,
+    },
+    /// Change of the message's top-level fields, such as its stop reason
+    MessageDelta {
+        /// Changed fields
+        delta: MessageDelta,
+        /// Cumulative token usage
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usage: Option<serde_json::Value>,
+    },
+    /// End of the message
+    MessageStop,
+    /// Event of an unknown type
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+/// Content added to a block by a `content_block_delta` event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlockDelta {
+    /// Text of a text block
+    TextDelta {
+        /// Added text
+        text: String,
+    },
+    /// Part of the JSON input of a tool use block
+    InputJsonDelta {
+        /// Added JSON text, only valid JSON once the block stops
+        partial_json: String,
+    },
+    /// Text of a thinking block
+    ThinkingDelta {
+        /// Added thinking
+        thinking: String,
+    },
+    /// Signature of a thinking block, sent right before it stops
+    SignatureDelta {
+        /// The signature
+        signature: String,
+    },
+    /// Delta of an unknown type
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+/// Top-level message fields changed by a `message_delta` event
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MessageDelta {
+    /// Why Claude stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    /// Stop sequence that was hit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_sequence: Option<String>,
+}
+
+/// Content block types
+///
+/// Blocks of a type this SDK does not know yet parse into [`ContentBlock::Unknown`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
     /// Text block
     Text(TextBlock),
@@ -233,6 +841,23 @@ pub enum ContentBlock {
     ToolResult(ToolResultBlock),
     /// Image block
     Image(ImageBlock),
+    /// Block of an unknown type, kept as received including its `type`
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+impl<'de> Deserialize<'de> for ContentBlock {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Ok(match type_tag(&value) {
+            Some("text") => ContentBlock::Text(from_tagged(value)?),
+            Some("thinking") => ContentBlock::Thinking(from_tagged(value)?),
+            Some("tool_use") => ContentBlock::ToolUse(from_tagged(value)?),
+            Some("tool_result") => ContentBlock::ToolResult(from_tagged(value)?),
+            Some("image") => ContentBlock::Image(from_tagged(value)?),
+            _ => ContentBlock::Unknown(value),
+        })
+    }
 }
 
 /// Text content block
@@ -247,7 +872,8 @@ pub struct TextBlock {
 pub struct ThinkingBlock {
     /// Thinking content
     pub thinking: String,
-    /// Signature
+    /// Signature (empty in the `content_block_start` of a streamed block)
+    #[serde(default)]
     pub signature: String,
 }
 
@@ -320,6 +946,26 @@ pub struct ImageBlock {
     pub source: ImageSource,
 }
 
+/// Document source for user prompts
+///
+/// Supported format: PDF (`application/pdf`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DocumentSource {
+    /// Base64-encoded document data
+    Base64 {
+        /// MIME type ("application/pdf")
+        media_type: String,
+        /// Base64-encoded document data (without data URI prefix)
+        data: String,
+    },
+    /// URL reference to a document
+    Url {
+        /// Publicly accessible document URL
+        url: String,
+    },
+}
+
 /// Content block for user prompts (input)
 ///
 /// Represents content that can be included in user messages.
@@ -336,6 +982,17 @@ pub enum UserContentBlock {
         /// Image source (base64 or URL)
         source: ImageSource,
     },
+    /// Document content, such as a PDF
+    Document {
+        /// Document source (base64 or URL)
+        source: DocumentSource,
+        /// Title of the document, given to Claude with it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Context about the document, such as where it comes from; not quoted in citations
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        context: Option<String>,
+    },
 }
 
 impl UserContentBlock {
@@ -370,25 +1027,7 @@ impl UserContentBlock {
     ) -> crate::errors::Result<Self> {
         let media_type_str = media_type.into();
         let data_str = data.into();
-
-        // Validate MIME type
-        if !SUPPORTED_IMAGE_MIME_TYPES.contains(&media_type_str.as_str()) {
-            return Err(crate::errors::ImageValidationError::new(format!(
-                "Unsupported media type '{}'. Supported types: {:?}",
-                media_type_str, SUPPORTED_IMAGE_MIME_TYPES
-            ))
-            .into());
-        }
-
-        // Validate base64 size
-        if data_str.len() > MAX_BASE64_SIZE {
-            return Err(crate::errors::ImageValidationError::new(format!(
-                "Base64 data exceeds maximum size of {} bytes (got {} bytes)",
-                MAX_BASE64_SIZE,
-                data_str.len()
-            ))
-            .into());
-        }
+        check_image_base64(&media_type_str, &data_str)?;
 
         Ok(UserContentBlock::Image {
             source: ImageSource::Base64 {
@@ -398,6 +1037,100 @@ impl UserContentBlock {
         })
     }
 
+    /// Create an image content block from the bytes of a PNG, JPEG, GIF or WebP image
+    ///
+    /// The media type is detected from the first bytes of the image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The image is larger than the base64 limit allows (about 11MB)
+    /// - The image is not PNG, JPEG, GIF or WebP
+    pub fn image_from_bytes(bytes: &[u8]) -> crate::errors::Result<Self> {
+        check_image_size(bytes.len())?;
+        let media_type = sniff_image_media_type(bytes)?;
+        Self::image_base64(media_type, BASE64.encode(bytes))
+    }
+
+    /// Create an image content block from an image file
+    ///
+    /// The file size is checked before it is read, and the media type is
+    /// detected from its content rather than its extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The file cannot be read
+    /// - The file is larger than the base64 limit allows (about 11MB)
+    /// - The image is not PNG, JPEG, GIF or WebP
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::UserContentBlock;
+    /// let block = UserContentBlock::image_from_path("diagram.png")?;
+    /// # Ok::<(), claude_agent_sdk::ClaudeError>(())
+    /// ```
+    pub fn image_from_path(path: impl AsRef<Path>) -> crate::errors::Result<Self> {
+        let path = path.as_ref();
+        check_image_size(file_size(std::fs::metadata(path)?.len()))?;
+        Self::image_from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Create an image content block from an image file, without blocking
+    ///
+    /// See [`UserContentBlock::image_from_path`].
+    pub async fn image_from_path_async(path: impl AsRef<Path>) -> crate::errors::Result<Self> {
+        let path = path.as_ref();
+        check_image_size(file_size(tokio::fs::metadata(path).await?.len()))?;
+        Self::image_from_bytes(&tokio::fs::read(path).await?)
+    }
+
+    /// Create an image content block from an image file, downscaled so that
+    /// neither side is longer than `max_dimension` pixels
+    ///
+    /// Images that already fit are sent as they are. Others keep their aspect
+    /// ratio and format; an animated GIF keeps only its first frame. The size
+    /// limit applies to the downscaled image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The file cannot be read
+    /// - The image is not PNG, JPEG, GIF or WebP, or cannot be decoded
+    /// - The downscaled image is still larger than the base64 limit allows
+    #[cfg(feature = "image")]
+    pub fn image_from_path_resized(
+        path: impl AsRef<Path>,
+        max_dimension: u32,
+    ) -> crate::errors::Result<Self> {
+        let bytes = std::fs::read(path.as_ref())?;
+        let format = match sniff_image_media_type(&bytes)? {
+            "image/png" => image::ImageFormat::Png,
+            "image/jpeg" => image::ImageFormat::Jpeg,
+            "image/gif" => image::ImageFormat::Gif,
+            _ => image::ImageFormat::WebP,
+        };
+        let resize_error = |e: image::ImageError| {
+            crate::errors::ImageValidationError::new(format!("Cannot resize image: {}", e))
+        };
+        let decoded = image::load_from_memory_with_format(&bytes, format).map_err(resize_error)?;
+        if decoded.width() <= max_dimension && decoded.height() <= max_dimension {
+            return Self::image_from_bytes(&bytes);
+        }
+
+        let resized = decoded.resize(
+            max_dimension,
+            max_dimension,
+            image::imageops::FilterType::Lanczos3,
+        );
+        let mut encoded = std::io::Cursor::new(Vec::new());
+        resized
+            .write_to(&mut encoded, format)
+            .map_err(resize_error)?;
+        Self::image_from_bytes(encoded.get_ref())
+    }
+
     /// Create an image content block from URL
     ///
     /// # Security
@@ -415,52 +1148,106 @@ impl UserContentBlock {
     pub fn image_url(url: impl Into<String>) -> crate::errors::Result<Self> {
         let url_str = url.into();
 
-        // Validate URL is not empty
-        if url_str.is_empty() {
-            return Err(crate::errors::ImageValidationError::new(
-                "Image URL cannot be empty".to_string(),
-            )
-            .into());
-        }
+        check_url(&url_str, "Image").map_err(crate::errors::ImageValidationError::new)?;
 
-        // Validate URL length
-        if url_str.len() > MAX_URL_LENGTH {
-            return Err(crate::errors::ImageValidationError::new(format!(
-                "URL exceeds maximum length of {} characters",
-                MAX_URL_LENGTH
-            ))
-            .into());
-        }
+        Ok(UserContentBlock::Image {
+            source: ImageSource::Url { url: url_str },
+        })
+    }
 
-        // Parse and validate URL scheme (SSRF prevention)
-        let scheme = url_str
-            .split("://")
-            .next()
-            .map(|s| s.to_lowercase())
-            .unwrap_or_default();
+    /// Create a document content block from base64 data
+    ///
+    /// # Arguments
+    ///
+    /// * `media_type` - MIME type of the document ("application/pdf")
+    /// * `data` - Base64-encoded document data (without data URI prefix)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The MIME type is not supported (valid types: application/pdf)
+    /// - The base64 data exceeds the maximum size limit (32MB)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::UserContentBlock;
+    /// let block = UserContentBlock::document_base64("application/pdf", "JVBERi0xLjQK")?
+    ///     .with_title("Quarterly report");
+    /// # Ok::<(), claude_agent_sdk::ClaudeError>(())
+    /// ```
+    pub fn document_base64(
+        media_type: impl Into<String>,
+        data: impl Into<String>,
+    ) -> crate::errors::Result<Self> {
+        let media_type_str = media_type.into();
+        let data_str = data.into();
 
-        if !ALLOWED_URL_SCHEMES.contains(&scheme.as_str()) {
-            return Err(crate::errors::ImageValidationError::new(format!(
-                "Invalid URL scheme '{}'. Only {} are allowed",
-                scheme,
-                ALLOWED_URL_SCHEMES.join(", ")
+        if !SUPPORTED_DOCUMENT_MIME_TYPES.contains(&media_type_str.as_str()) {
+            return Err(crate::errors::DocumentValidationError::new(format!(
+                "Unsupported media type '{}'. Supported types: {:?}",
+                media_type_str, SUPPORTED_DOCUMENT_MIME_TYPES
             ))
             .into());
         }
 
-        // Basic URL format validation (must have scheme and host)
-        if !url_str.contains("://") || url_str.split("://").nth(1).map_or(true, |h| h.is_empty()) {
-            return Err(crate::errors::ImageValidationError::new(
-                "Invalid URL format: must include scheme and host".to_string(),
-            )
+        if data_str.len() > MAX_DOCUMENT_BASE64_SIZE {
+            return Err(crate::errors::DocumentValidationError::new(format!(
+                "Base64 data exceeds maximum size of {} bytes (got {} bytes)",
+                MAX_DOCUMENT_BASE64_SIZE,
+                data_str.len()
+            ))
             .into());
         }
 
-        Ok(UserContentBlock::Image {
-            source: ImageSource::Url { url: url_str },
+        Ok(UserContentBlock::Document {
+            source: DocumentSource::Base64 {
+                media_type: media_type_str,
+                data: data_str,
+            },
+            title: None,
+            context: None,
+        })
+    }
+
+    /// Create a document content block from URL
+    ///
+    /// URLs are validated as for [`UserContentBlock::image_url`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The URL is empty
+    /// - The URL exceeds the maximum length
+    /// - The URL scheme is not `https` or `http`
+    /// - The URL format is invalid
+    pub fn document_url(url: impl Into<String>) -> crate::errors::Result<Self> {
+        let url_str = url.into();
+        check_url(&url_str, "Document").map_err(crate::errors::DocumentValidationError::new)?;
+
+        Ok(UserContentBlock::Document {
+            source: DocumentSource::Url { url: url_str },
+            title: None,
+            context: None,
         })
     }
 
+    /// Set the title of a document block; other blocks are returned unchanged
+    pub fn with_title(mut self, new_title: impl Into<String>) -> Self {
+        if let UserContentBlock::Document { title, .. } = &mut self {
+            *title = Some(new_title.into());
+        }
+        self
+    }
+
+    /// Set the context of a document block; other blocks are returned unchanged
+    pub fn with_context(mut self, new_context: impl Into<String>) -> Self {
+        if let UserContentBlock::Document { context, .. } = &mut self {
+            *context = Some(new_context.into());
+        }
+        self
+    }
+
     /// Validate a collection of content blocks
     ///
     /// Ensures the content is non-empty. This is used internally by query functions
@@ -472,7 +1259,7 @@ impl UserContentBlock {
     pub fn validate_content(blocks: &[UserContentBlock]) -> crate::Result<()> {
         if blocks.is_empty() {
             return Err(crate::errors::ClaudeError::InvalidConfig(
-                "Content must include at least one block (text or image)".to_string(),
+                "Content must include at least one block (text, image or document)".to_string(),
             ));
         }
         Ok(())
@@ -493,11 +1280,272 @@ impl From<&str> for UserContentBlock {
     }
 }
 
+/// Author of a [`ConversationTurn`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// A prompt from the user
+    User,
+    /// A reply from Claude
+    Assistant,
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::User => write!(f, "user"),
+            Role::Assistant => write!(f, "assistant"),
+        }
+    }
+}
+
+/// Content of a [`ConversationTurn`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TurnContent {
+    /// Plain text
+    Text(String),
+    /// Structured content blocks
+    Blocks(Vec<UserContentBlock>),
+}
+
+impl From<String> for TurnContent {
+    fn from(text: String) -> Self {
+        TurnContent::Text(text)
+    }
+}
+
+impl From<&str> for TurnContent {
+    fn from(text: &str) -> Self {
+        TurnContent::Text(text.to_string())
+    }
+}
+
+impl From<Vec<UserContentBlock>> for TurnContent {
+    fn from(blocks: Vec<UserContentBlock>) -> Self {
+        TurnContent::Blocks(blocks)
+    }
+}
+
+/// One prior turn of a conversation, used to seed a query with history
+///
+/// See [`query_with_history`](crate::query_with_history).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationTurn {
+    /// Author of the turn
+    pub role: Role,
+    /// Content of the turn
+    pub content: TurnContent,
+}
+
+impl ConversationTurn {
+    /// A user turn with text or content blocks
+    pub fn user(content: impl Into<TurnContent>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    /// An assistant turn with text or text blocks
+    pub fn assistant(content: impl Into<TurnContent>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: content.into(),
+        }
+    }
+
+    /// Check that `turns` can be replayed to the CLI
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClaudeError::InvalidConfig`](crate::errors::ClaudeError::InvalidConfig) if:
+    /// - `turns` is empty
+    /// - The last turn is not a user turn, so Claude has nothing to answer
+    /// - A turn has no content
+    /// - An assistant turn contains an image or document block, which the CLI
+    ///   only accepts from the user
+    pub fn validate_history(turns: &[ConversationTurn]) -> crate::Result<()> {
+        let invalid = |message: String| Err(crate::errors::ClaudeError::InvalidConfig(message));
+
+        let Some(last) = turns.last() else {
+            return invalid("Conversation history must include at least one turn".to_string());
+        };
+        if last.role != Role::User {
+            return invalid(format!(
+                "Conversation history must end with a user turn, not an {} turn",
+                last.role
+            ));
+        }
+
+        for (index, turn) in turns.iter().enumerate() {
+            let empty = match &turn.content {
+                TurnContent::Text(text) => text.is_empty(),
+                TurnContent::Blocks(blocks) => blocks.is_empty(),
+            };
+            if empty {
+                return invalid(format!(
+                    "Conversation turn {} ({}) has no content",
+                    index, turn.role
+                ));
+            }
+            if turn.role != Role::Assistant {
+                continue;
+            }
+            if let TurnContent::Blocks(blocks) = &turn.content {
+                let unsupported = blocks.iter().find_map(|block| match block {
+                    UserContentBlock::Text { .. } => None,
+                    UserContentBlock::Image { .. } => Some("an image"),
+                    UserContentBlock::Document { .. } => Some("a document"),
+                });
+                if let Some(kind) = unsupported {
+                    return invalid(format!(
+                        "Conversation turn {} (assistant) contains {} block; assistant turns can only contain text",
+                        index, kind
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The stream-json input message for this turn
+    ///
+    /// Assistant content is always sent as text blocks, the shape the CLI
+    /// emits for assistant messages.
+    pub(crate) fn to_stream_json(&self, session_id: Option<&str>) -> serde_json::Value {
+        let content = match (&self.content, self.role) {
+            (TurnContent::Text(text), Role::Assistant) => {
+                serde_json::json!([UserContentBlock::text(text.clone())])
+            },
+            (TurnContent::Text(text), Role::User) => serde_json::json!(text),
+            (TurnContent::Blocks(blocks), _) => serde_json::json!(blocks),
+        };
+        let mut message = serde_json::json!({
+            "type": self.role.to_string(),
+            "message": {
+                "role": self.role.to_string(),
+                "content": content
+            }
+        });
+        if let Some(session_id) = session_id {
+            message["session_id"] = serde_json::json!(session_id);
+        }
+        message
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_collected_response_text_and_result() {
+        let assistant = |text: &str| {
+            serde_json::from_value::<Message>(json!({
+                "type": "assistant",
+                "message": {"content": [{"type": "text", "text": text}]}
+            }))
+            .unwrap()
+        };
+
+        let mut collected = CollectedResponse::from(vec![assistant("Hello, "), assistant("world")]);
+        assert_eq!(collected.text(), "Hello, world");
+        assert!(!collected.is_complete());
+
+        collected.messages.push(
+            serde_json::from_value(json!({
+                "type": "result",
+                "subtype": "success",
+                "duration_ms": 10,
+                "duration_api_ms": 8,
+                "is_error": false,
+                "num_turns": 1,
+                "session_id": "s"
+            }))
+            .unwrap(),
+        );
+        assert!(collected.is_complete());
+        assert_eq!(collected.result().unwrap().subtype, "success");
+    }
+
+    fn structured_fixture(structured_output: Option<serde_json::Value>, text: &str) -> CollectedResponse {
+        let mut result = json!({
+            "type": "result",
+            "subtype": "success",
+            "duration_ms": 10,
+            "duration_api_ms": 8,
+            "is_error": false,
+            "num_turns": 1,
+            "session_id": "s",
+            "result": text
+        });
+        if let Some(value) = structured_output {
+            result["structured_output"] = value;
+        }
+
+        CollectedResponse::from(vec![
+            serde_json::from_value(json!({
+                "type": "assistant",
+                "message": {"content": [{"type": "text", "text": text}]}
+            }))
+            .unwrap(),
+            serde_json::from_value(result).unwrap(),
+        ])
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Answer {
+        answer: u32,
+    }
+
+    #[test]
+    fn test_structured_output_field_present() {
+        let collected = structured_fixture(Some(json!({"answer": 4})), "```json\n{\"answer\": 5}\n```");
+
+        let result = collected.result().unwrap();
+        assert_eq!(result.structured_output(), Some(&json!({"answer": 4})));
+        assert_eq!(result.structured_as::<Answer>().unwrap(), Answer { answer: 4 });
+        // The field wins over fenced blocks even in lenient mode
+        assert_eq!(collected.structured_output(true), Some(json!({"answer": 4})));
+    }
+
+    #[test]
+    fn test_structured_output_fenced_fallback() {
+        let text = "First try:\n```json\n{\"answer\": 1}\n```\nCorrected:\n```json\n{\"answer\": 2}\n```\n```text\n{\"answer\": 3}\n```";
+        let collected = structured_fixture(None, text);
+
+        assert_eq!(collected.structured_output(false), None);
+        assert_eq!(collected.structured_output(true), Some(json!({"answer": 2})));
+        assert_eq!(collected.structured_as::<Answer>(true).unwrap(), Answer { answer: 2 });
+    }
+
+    #[test]
+    fn test_structured_output_absent() {
+        let collected = structured_fixture(None, "No JSON here, ```json\nnot json\n```");
+
+        assert!(collected.result().unwrap().structured_output().is_none());
+        assert_eq!(collected.structured_output(true), None);
+
+        let err = collected.structured_as::<Answer>(true).unwrap_err();
+        assert!(matches!(err, crate::ClaudeError::StructuredOutput(ref e) if e.raw.is_none()));
+    }
+
+    #[test]
+    fn test_structured_as_mismatch_keeps_raw_value() {
+        let collected = structured_fixture(Some(json!({"answer": "four"})), "");
+
+        let err = collected.result().unwrap().structured_as::<Answer>().unwrap_err();
+        let crate::ClaudeError::StructuredOutput(inner) = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(inner.raw, Some(json!({"answer": "four"})));
+        let message = err.to_string();
+        assert!(message.contains("Answer"));
+        assert!(message.contains("\"four\""));
+    }
+
     #[test]
     fn test_content_block_text_serialization() {
         let block = ContentBlock::Text(TextBlock {
@@ -570,6 +1618,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_result_model_usage() {
+        let result = |model_usage: serde_json::Value| -> ResultMessage {
+            serde_json::from_value(serde_json::json!({
+                "subtype": "success",
+                "duration_ms": 1,
+                "duration_api_ms": 1,
+                "is_error": false,
+                "num_turns": 1,
+                "session_id": "s",
+                "modelUsage": model_usage
+            }))
+            .unwrap()
+        };
+
+        let typed = result(serde_json::json!({
+            "claude-sonnet-4-5-20250929": {"inputTokens": 3, "outputTokens": 214, "costUSD": 0.0088}
+        }));
+        let by_model = typed.model_usage.as_ref().unwrap();
+        assert_eq!(by_model["claude-sonnet-4-5-20250929"].output_tokens, 214);
+        let json = serde_json::to_value(&typed).unwrap();
+        assert_eq!(json["modelUsage"]["claude-sonnet-4-5-20250929"]["costUSD"], 0.0088);
+        assert!(json.get("model_usage").is_none());
+
+        // An unexpected shape does not fail the result
+        assert!(result(serde_json::json!("n/a")).model_usage.is_none());
+        assert!(result(serde_json::Value::Null).model_usage.is_none());
+    }
+
     #[test]
     fn test_message_system_deserialization() {
         let json_str = r#"{
@@ -591,6 +1668,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_system_compact_boundary() {
+        let msg: Message = serde_json::from_value(json!({
+            "type": "system",
+            "subtype": "compact_boundary",
+            "session_id": "test-session",
+            "compact_metadata": {"trigger": "manual", "pre_tokens": 48210}
+        }))
+        .unwrap();
+        let Message::System(system) = msg else {
+            panic!("Expected System variant");
+        };
+        assert_eq!(
+            system.compact_boundary(),
+            Some(CompactBoundary {
+                trigger: "manual".to_string(),
+                pre_tokens: Some(48210),
+            })
+        );
+
+        let init: SystemMessage =
+            serde_json::from_value(json!({"subtype": "init", "session_id": "s"})).unwrap();
+        assert_eq!(init.compact_boundary(), None);
+    }
+
     #[test]
     fn test_tool_result_content_text() {
         let content = ToolResultContent::Text("Command output".to_string());
@@ -686,7 +1788,7 @@ mod tests {
 
     #[test]
     fn test_user_content_block_image_url_serialization() {
-        let block = UserContentBlock::image_url("https://example.com/image.webp");
+        let block = UserContentBlock::image_url("https://example.com/image.webp").unwrap();
 
         let json = serde_json::to_value(&block).unwrap();
         assert_eq!(json["type"], "image");
@@ -832,4 +1934,320 @@ mod tests {
         let err = block.unwrap_err().to_string();
         assert!(err.contains("exceeds maximum size"));
     }
+
+    const PNG_HEADER: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0];
+
+    fn base64_source(block: &UserContentBlock) -> (&str, &str) {
+        match block {
+            UserContentBlock::Image {
+                source: ImageSource::Base64 { media_type, data },
+            } => (media_type.as_str(), data.as_str()),
+            other => panic!("Expected base64 image, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_image_from_bytes_detects_media_type() {
+        let cases: [(&[u8], &str); 4] = [
+            (PNG_HEADER, "image/png"),
+            (&[0xFF, 0xD8, 0xFF, 0xE0], "image/jpeg"),
+            (b"GIF89a..", "image/gif"),
+            (b"RIFF\0\0\0\0WEBPVP8 ", "image/webp"),
+        ];
+        for (bytes, expected) in cases {
+            let block = UserContentBlock::image_from_bytes(bytes).unwrap();
+            let (media_type, data) = base64_source(&block);
+            assert_eq!(media_type, expected);
+            assert_eq!(BASE64.decode(data).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_image_from_bytes_names_unsupported_format() {
+        for (bytes, expected) in [
+            (&b"BM\0\0\0\0"[..], "BMP"),
+            (&b"II*\0\0\0"[..], "TIFF"),
+            (&b"hello"[..], "unknown"),
+        ] {
+            match UserContentBlock::image_from_bytes(bytes) {
+                Err(crate::ClaudeError::ImageValidation(
+                    ImageValidationError::UnsupportedFormat { detected },
+                )) => assert_eq!(detected, expected),
+                other => panic!("Expected UnsupportedFormat, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_image_from_path_checks_size_before_reading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("huge.png");
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_len(MAX_IMAGE_SIZE as u64 + 1).unwrap();
+
+        match UserContentBlock::image_from_path(&path) {
+            Err(crate::ClaudeError::ImageValidation(ImageValidationError::TooLarge {
+                actual,
+                limit,
+            })) => {
+                assert_eq!(actual, MAX_IMAGE_SIZE + 1);
+                assert_eq!(limit, MAX_IMAGE_SIZE);
+            },
+            other => panic!("Expected TooLarge, got {:?}", other),
+        }
+        assert!(BASE64.encode(vec![0u8; MAX_IMAGE_SIZE]).len() <= MAX_BASE64_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_image_from_path_async() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("image.bin");
+        std::fs::write(&path, PNG_HEADER).unwrap();
+
+        let block = UserContentBlock::image_from_path_async(&path).await.unwrap();
+        assert_eq!(block, UserContentBlock::image_from_path(&path).unwrap());
+        assert_eq!(base64_source(&block).0, "image/png");
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_image_from_path_resized() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wide.png");
+        image::RgbImage::new(200, 100).save(&path).unwrap();
+
+        let dimensions = |block: &UserContentBlock| {
+            let bytes = BASE64.decode(base64_source(block).1).unwrap();
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            (decoded.width(), decoded.height())
+        };
+        let block = UserContentBlock::image_from_path_resized(&path, 50).unwrap();
+        assert_eq!(base64_source(&block).0, "image/png");
+        assert_eq!(dimensions(&block), (50, 25));
+
+        let block = UserContentBlock::image_from_path_resized(&path, 400).unwrap();
+        assert_eq!(block, UserContentBlock::image_from_path(&path).unwrap());
+    }
+
+    #[test]
+    fn test_document_block_wire_format_round_trip() {
+        // A user message with a PDF as the CLI passes it to the API
+        let wire = json!([
+            {
+                "type": "document",
+                "source": {"type": "base64", "media_type": "application/pdf", "data": "JVBERi0xLjQK"},
+                "title": "Quarterly report",
+                "context": "Uploaded by finance"
+            },
+            {
+                "type": "document",
+                "source": {"type": "url", "url": "https://example.com/spec.pdf"}
+            },
+            {"type": "text", "text": "Summarize both documents"}
+        ]);
+
+        let blocks: Vec<UserContentBlock> = serde_json::from_value(wire.clone()).unwrap();
+        assert_eq!(
+            blocks,
+            vec![
+                UserContentBlock::document_base64("application/pdf", "JVBERi0xLjQK")
+                    .unwrap()
+                    .with_title("Quarterly report")
+                    .with_context("Uploaded by finance"),
+                UserContentBlock::document_url("https://example.com/spec.pdf").unwrap(),
+                UserContentBlock::text("Summarize both documents"),
+            ]
+        );
+        assert_eq!(serde_json::to_value(&blocks).unwrap(), wire);
+    }
+
+    #[test]
+    fn test_document_base64_validation() {
+        let err = UserContentBlock::document_base64("text/html", "PGh0bWw+")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unsupported media type 'text/html'"), "{}", err);
+
+        let large_data = "a".repeat(MAX_DOCUMENT_BASE64_SIZE + 1);
+        let err = UserContentBlock::document_base64("application/pdf", large_data)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("exceeds maximum size"), "{}", err);
+
+        let err = UserContentBlock::document_url("file:///etc/passwd")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid URL scheme"), "{}", err);
+    }
+
+    #[test]
+    fn test_with_title_ignores_other_blocks() {
+        let block = UserContentBlock::text("hello").with_title("ignored");
+        assert_eq!(block, UserContentBlock::text("hello"));
+    }
+
+    #[test]
+    fn test_unknown_message_and_content_block_round_trip() {
+        let message = json!({"type": "telemetry", "payload": {"spans": [1, 2]}});
+        let parsed: Message = serde_json::from_value(message.clone()).unwrap();
+        assert!(matches!(parsed, Message::Unknown(_)));
+        assert_eq!(parsed.unknown_type(), Some("telemetry"));
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), message);
+
+        let assistant = json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "text", "text": "Hi"},
+                {"type": "hologram", "frames": 3}
+            ]}
+        });
+        let parsed: Message = serde_json::from_value(assistant.clone()).unwrap();
+        let Message::Assistant(inner) = &parsed else {
+            panic!("expected an assistant message");
+        };
+        assert!(matches!(inner.message.content[0], ContentBlock::Text(_)));
+        assert!(matches!(inner.message.content[1], ContentBlock::Unknown(_)));
+        assert_eq!(parsed.unknown_type(), Some("hologram"));
+        let round_trip = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(round_trip["message"]["content"], assistant["message"]["content"]);
+    }
+
+    #[test]
+    fn test_known_message_with_invalid_content_still_fails() {
+        let result = serde_json::from_value::<Message>(json!({"type": "result", "subtype": 1}));
+        assert!(result.is_err());
+    }
+
+    fn stream_event(event: serde_json::Value) -> StreamEvent {
+        StreamEvent {
+            uuid: "e1".to_string(),
+            session_id: "sess-1".to_string(),
+            event,
+            parent_tool_use_id: None,
+        }
+    }
+
+    #[test]
+    fn test_stream_event_text_delta() {
+        let event = stream_event(json!({
+            "type": "content_block_delta",
+            "index": 0,
+            "delta": {"type": "text_delta", "text": "Hel"}
+        }));
+        assert_eq!(event.text_delta(), Some("Hel"));
+
+        let event = stream_event(json!({
+            "type": "content_block_delta",
+            "index": 1,
+            "delta": {"type": "input_json_delta", "partial_json": "{\"path\""}
+        }));
+        assert_eq!(event.text_delta(), None);
+        match event.data() {
+            StreamEventData::ContentBlockDelta {
+                index: 1,
+                delta: ContentBlockDelta::InputJsonDelta { partial_json },
+            } => assert_eq!(partial_json, "{\"path\""),
+            other => panic!("expected an input JSON delta, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stream_event_data() {
+        let start = stream_event(json!({
+            "type": "message_start",
+            "message": {"id": "msg_1", "type": "message", "role": "assistant", "content": [],
+                        "model": "claude-sonnet-4-5", "usage": {"input_tokens": 10}}
+        }));
+        let StreamEventData::MessageStart { message } = start.data() else {
+            panic!("expected message_start");
+        };
+        assert_eq!(message.id.as_deref(), Some("msg_1"));
+
+        let block_start = stream_event(json!({
+            "type": "content_block_start",
+            "index": 0,
+            "content_block": {"type": "thinking", "thinking": ""}
+        }));
+        assert!(matches!(
+            block_start.data(),
+            StreamEventData::ContentBlockStart { index: 0, content_block: ContentBlock::Thinking(_) }
+        ));
+
+        let thinking = stream_event(json!({
+            "type": "content_block_delta",
+            "index": 0,
+            "delta": {"type": "thinking_delta", "thinking": "Let me see"}
+        }));
+        assert!(matches!(
+            thinking.data(),
+            StreamEventData::ContentBlockDelta { delta: ContentBlockDelta::ThinkingDelta { .. }, .. }
+        ));
+
+        let delta = stream_event(json!({
+            "type": "message_delta",
+            "delta": {"stop_reason": "end_turn", "stop_sequence": null},
+            "usage": {"output_tokens": 15}
+        }));
+        let StreamEventData::MessageDelta { delta, usage } = delta.data() else {
+            panic!("expected message_delta");
+        };
+        assert_eq!(delta.stop_reason.as_deref(), Some("end_turn"));
+        assert_eq!(usage.unwrap()["output_tokens"], 15);
+
+        let stop = stream_event(json!({"type": "content_block_stop", "index": 0}));
+        assert!(matches!(stop.data(), StreamEventData::ContentBlockStop { index: 0 }));
+        let stop = stream_event(json!({"type": "message_stop"}));
+        assert!(matches!(stop.data(), StreamEventData::MessageStop));
+
+        let ping = stream_event(json!({"type": "ping"}));
+        assert!(matches!(ping.data(), StreamEventData::Unknown(_)));
+    }
+
+    #[test]
+    fn test_conversation_history_validation() {
+        let history = vec![
+            ConversationTurn::user("What is 2 + 2?"),
+            ConversationTurn::assistant("4"),
+            ConversationTurn::user(vec![UserContentBlock::text("And times 3?")]),
+        ];
+        assert!(ConversationTurn::validate_history(&history).is_ok());
+
+        let err = ConversationTurn::validate_history(&[]).unwrap_err();
+        assert!(err.to_string().contains("at least one turn"));
+
+        let err = ConversationTurn::validate_history(&history[..2]).unwrap_err();
+        assert!(err.to_string().contains("must end with a user turn"));
+
+        let image = UserContentBlock::image_url("https://example.com/chart.png").unwrap();
+        let with_image = vec![
+            ConversationTurn::user("Draw a chart"),
+            ConversationTurn::assistant(vec![UserContentBlock::text("Here:"), image]),
+            ConversationTurn::user("Thanks"),
+        ];
+        let err = ConversationTurn::validate_history(&with_image).unwrap_err();
+        assert!(err.to_string().contains("turn 1 (assistant) contains an image block"));
+
+        let empty = vec![ConversationTurn::user("")];
+        let err = ConversationTurn::validate_history(&empty).unwrap_err();
+        assert!(err.to_string().contains("turn 0 (user) has no content"));
+    }
+
+    #[test]
+    fn test_conversation_turn_stream_json() {
+        assert_eq!(
+            ConversationTurn::user("Hi").to_stream_json(None),
+            json!({"type": "user", "message": {"role": "user", "content": "Hi"}})
+        );
+        assert_eq!(
+            ConversationTurn::assistant("Hello!").to_stream_json(Some("default")),
+            json!({
+                "type": "assistant",
+                "message": {
+                    "role": "assistant",
+                    "content": [{"type": "text", "text": "Hello!"}]
+                },
+                "session_id": "default"
+            })
+        );
+    }
 }
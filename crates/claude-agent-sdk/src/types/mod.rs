@@ -1,8 +1,14 @@
 //! Type definitions for the Claude Agent SDK
 
+pub mod checkpoints;
 pub mod config;
+mod config_file;
+pub mod hook_rules;
 pub mod hooks;
 pub mod mcp;
 pub mod messages;
 pub mod permissions;
 pub mod plugin;
+pub mod redaction;
+pub mod usage;
+pub mod validation;
@@ -2,10 +2,66 @@
 //!
 //! Plugins allow you to extend Claude Code functionality with custom features,
 //! tools, and integrations. This module provides types for configuring and
-//! loading plugins from local paths.
+//! loading plugins from local paths, discovering them in a directory, and
+//! checking their manifests before the CLI loads them.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Location of a plugin's manifest, relative to the plugin directory
+pub const PLUGIN_MANIFEST: &str = ".claude-plugin/plugin.json";
+
+/// Manifest fields that name files or directories inside the plugin
+const ENTRY_FIELDS: [&str; 4] = ["commands", "agents", "hooks", "mcpServers"];
+
+/// Why a plugin directory would not load
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PluginValidationError {
+    /// The plugin has no manifest at [`PLUGIN_MANIFEST`]
+    #[error("{} has no plugin manifest", .0.display())]
+    MissingManifest(PathBuf),
+    /// The manifest is not a JSON object
+    #[error("invalid plugin manifest {}: {message}", .path.display())]
+    InvalidManifest {
+        /// Path of the manifest
+        path: PathBuf,
+        /// What failed to parse
+        message: String,
+    },
+    /// A required manifest field is absent or not a non-empty string
+    #[error("plugin manifest {} is missing `{field}`", .path.display())]
+    MissingField {
+        /// Path of the manifest
+        path: PathBuf,
+        /// The missing field
+        field: &'static str,
+    },
+    /// The plugin's name is not kebab-case
+    #[error("plugin name `{0}` must be kebab-case, e.g. `my-plugin`")]
+    InvalidName(String),
+    /// A file or directory the manifest refers to does not exist
+    #[error("`{field}` of plugin `{plugin}` refers to {}, which does not exist", .path.display())]
+    MissingEntry {
+        /// Name of the plugin
+        plugin: String,
+        /// Manifest field with the reference
+        field: &'static str,
+        /// The missing path, resolved against the plugin directory
+        path: PathBuf,
+    },
+}
+
+/// The fields of a plugin manifest the SDK checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginManifest {
+    /// Unique name of the plugin
+    pub name: String,
+    /// Version of the plugin, if given
+    pub version: Option<String>,
+    /// What the plugin does, if given
+    pub description: Option<String>,
+}
 
 /// Plugin configuration for extending Claude Code functionality
 ///
@@ -79,6 +135,137 @@ impl SdkPluginConfig {
             SdkPluginConfig::Local { path } => Some(path),
         }
     }
+
+    /// Local plugins in the subdirectories of `dir`, sorted by path
+    ///
+    /// A subdirectory is a plugin if it has a manifest at [`PLUGIN_MANIFEST`];
+    /// others are ignored. The plugins are not validated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dir` cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use claude_agent_sdk::SdkPluginConfig;
+    ///
+    /// for plugin in SdkPluginConfig::discover("./plugins")? {
+    ///     if let Err(e) = plugin.validate() {
+    ///         eprintln!("skipping plugin: {e}");
+    ///     }
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn discover(dir: impl AsRef<Path>) -> std::io::Result<Vec<Self>> {
+        let mut plugins = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() && path.join(PLUGIN_MANIFEST).is_file() {
+                plugins.push(Self::local(path));
+            }
+        }
+        plugins.sort_by(|a, b| a.path().cmp(&b.path()));
+        Ok(plugins)
+    }
+
+    /// Check that the plugin would load, returning its manifest
+    ///
+    /// The manifest must parse and name the plugin in kebab-case, and the
+    /// paths given in its `commands`, `agents`, `hooks` and `mcpServers` fields
+    /// must exist. Relative plugin paths are resolved against the current
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found as a [`PluginValidationError`].
+    pub fn validate(&self) -> Result<PluginManifest, PluginValidationError> {
+        match self {
+            SdkPluginConfig::Local { path } => validate_local(path),
+        }
+    }
+}
+
+fn validate_local(dir: &Path) -> Result<PluginManifest, PluginValidationError> {
+    let manifest_path = dir.join(PLUGIN_MANIFEST);
+    let text = std::fs::read_to_string(&manifest_path)
+        .map_err(|_| PluginValidationError::MissingManifest(dir.to_path_buf()))?;
+    let invalid = |message: String| PluginValidationError::InvalidManifest {
+        path: manifest_path.clone(),
+        message,
+    };
+    let manifest: Value = serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+    if !manifest.is_object() {
+        return Err(invalid("expected a JSON object".to_string()));
+    }
+
+    let name = manifest
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| PluginValidationError::MissingField {
+            path: manifest_path.clone(),
+            field: "name",
+        })?;
+    if !is_kebab_case(name) {
+        return Err(PluginValidationError::InvalidName(name.to_string()));
+    }
+
+    for field in ENTRY_FIELDS {
+        // Inline hook and MCP server configs are objects, which name no files
+        let entries = match manifest.get(field) {
+            Some(Value::String(entry)) => vec![entry.as_str()],
+            Some(Value::Array(entries)) => entries.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        for entry in entries {
+            let path = dir.join(entry);
+            if !path.exists() {
+                return Err(PluginValidationError::MissingEntry {
+                    plugin: name.to_string(),
+                    field,
+                    path,
+                });
+            }
+        }
+    }
+
+    let text_field = |field: &str| manifest.get(field).and_then(Value::as_str).map(String::from);
+    Ok(PluginManifest {
+        name: name.to_string(),
+        version: text_field("version"),
+        description: text_field("description"),
+    })
+}
+
+fn is_kebab_case(name: &str) -> bool {
+    !name.starts_with('-')
+        && !name.ends_with('-')
+        && !name.contains("--")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// The valid plugins in `dir`, logging a warning for each invalid one
+pub(crate) fn discover_valid(dir: &Path) -> Vec<SdkPluginConfig> {
+    let plugins = match SdkPluginConfig::discover(dir) {
+        Ok(plugins) => plugins,
+        Err(e) => {
+            tracing::warn!("Cannot discover plugins in {}: {}", dir.display(), e);
+            return Vec::new();
+        },
+    };
+    plugins
+        .into_iter()
+        .filter(|plugin| match plugin.validate() {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!("Skipping plugin: {}", e);
+                false
+            },
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -141,6 +328,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_kebab_case_names() {
+        assert!(is_kebab_case("my-plugin2"));
+        assert!(!is_kebab_case("My Plugin"));
+        assert!(!is_kebab_case("-plugin"));
+        assert!(!is_kebab_case("my--plugin"));
+    }
+
     #[test]
     fn test_plugin_with_home_directory() {
         let plugin = SdkPluginConfig::local("~/my-plugin");
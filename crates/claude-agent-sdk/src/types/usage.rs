@@ -0,0 +1,225 @@
+//! Token usage and cost types
+//!
+//! [`ClaudeClient`](crate::ClaudeClient) adds up the usage reported by the
+//! messages it receives, so the cost of many queries on one client can be
+//! read with [`usage`](crate::ClaudeClient::usage) instead of being summed by
+//! hand.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::AddAssign;
+
+/// Token usage of an assistant or result message, as reported by the CLI
+///
+/// Parsed from the message's `usage` object, and for result messages its
+/// `modelUsage` object, when the message is parsed; the raw JSON stays
+/// available in the messages' `usage` fields. Missing counts are zero and
+/// fields this SDK does not know are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Usage {
+    /// Input tokens not read from or written to the prompt cache
+    pub input_tokens: u64,
+    /// Generated tokens
+    pub output_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_input_tokens: u64,
+    /// Input tokens read from the prompt cache
+    pub cache_read_input_tokens: u64,
+    /// Requests made by server-side tools such as web search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_tool_use: Option<ServerToolUse>,
+    /// Usage of each model, by model ID
+    ///
+    /// Only result messages report it. Besides the model that answered, it
+    /// includes models the CLI used on its own, such as a small model for
+    /// housekeeping tasks.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_model: BTreeMap<String, ModelTokenUsage>,
+}
+
+impl Usage {
+    /// Parse a `usage` object, or `None` if it is not one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use claude_agent_sdk::Usage;
+    ///
+    /// let usage = serde_json::json!({"input_tokens": 12, "output_tokens": 40});
+    /// let usage = Usage::from_value(&usage).unwrap();
+    /// assert_eq!(usage.output_tokens, 40);
+    /// assert_eq!(usage.total_tokens(), 52);
+    /// ```
+    pub fn from_value(usage: &serde_json::Value) -> Option<Self> {
+        usage.is_object().then(|| Self::deserialize(usage).ok()).flatten()
+    }
+
+    /// All input tokens, cached or not, plus output tokens
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_read_input_tokens
+            + self.cache_creation_input_tokens
+    }
+}
+
+impl From<&Usage> for UsageTotals {
+    fn from(usage: &Usage) -> Self {
+        Self {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_read_input_tokens: usage.cache_read_input_tokens,
+            cache_creation_input_tokens: usage.cache_creation_input_tokens,
+            ..Self::default()
+        }
+    }
+}
+
+/// Requests made by server-side tools in a turn
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerToolUse {
+    /// Web searches
+    pub web_search_requests: u64,
+    /// Web fetches
+    pub web_fetch_requests: u64,
+}
+
+/// Usage of one model in a turn, an entry of [`Usage::by_model`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModelTokenUsage {
+    /// Input tokens not read from or written to the prompt cache
+    pub input_tokens: u64,
+    /// Generated tokens
+    pub output_tokens: u64,
+    /// Input tokens read from the prompt cache
+    pub cache_read_input_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_input_tokens: u64,
+    /// Web searches
+    pub web_search_requests: u64,
+    /// Cost in USD
+    #[serde(rename = "costUSD")]
+    pub cost_usd: f64,
+    /// Size of the model's context window in tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+}
+
+/// Token counts, cost and turns added up over one or more turns
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    /// Input tokens not read from or written to the prompt cache
+    pub input_tokens: u64,
+    /// Generated tokens
+    pub output_tokens: u64,
+    /// Input tokens read from the prompt cache
+    pub cache_read_input_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_input_tokens: u64,
+    /// Cost in USD, as reported by result messages
+    pub total_cost_usd: f64,
+    /// Agentic turns, as reported by result messages
+    pub num_turns: u32,
+}
+
+impl UsageTotals {
+    /// Token counts of a `usage` object from an assistant or result message
+    ///
+    /// Missing counts are zero; cost and turns are not part of `usage`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use claude_agent_sdk::UsageTotals;
+    ///
+    /// let usage = serde_json::json!({"input_tokens": 12, "output_tokens": 40});
+    /// let totals = UsageTotals::from_usage(&usage);
+    /// assert_eq!(totals.input_tokens, 12);
+    /// assert_eq!(totals.cache_read_input_tokens, 0);
+    /// ```
+    pub fn from_usage(usage: &serde_json::Value) -> Self {
+        let tokens = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+        Self {
+            input_tokens: tokens("input_tokens"),
+            output_tokens: tokens("output_tokens"),
+            cache_read_input_tokens: tokens("cache_read_input_tokens"),
+            cache_creation_input_tokens: tokens("cache_creation_input_tokens"),
+            ..Self::default()
+        }
+    }
+
+    /// All input tokens, cached or not, plus output tokens
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_read_input_tokens
+            + self.cache_creation_input_tokens
+    }
+
+    /// Approximate cost in USD of these tokens when answered by `model`
+    ///
+    /// A rough estimate from public per-token pricing, Sonnet's when the model is
+    /// unknown; cache reads cost a tenth and cache writes 1.25 times the input
+    /// price. Unlike [`total_cost_usd`](Self::total_cost_usd), it is available
+    /// before a turn's result arrives.
+    pub fn estimated_cost_usd(&self, model: Option<&str>) -> f64 {
+        // Rough pricing per million tokens (subject to change), Sonnet when unknown
+        let model = model.unwrap_or_default();
+        let (input_price, output_price) = if model.contains("opus") {
+            (15.0, 75.0)
+        } else if model.contains("haiku") {
+            (0.8, 4.0)
+        } else {
+            (3.0, 15.0)
+        };
+
+        let per_million = |tokens: u64, price: f64| tokens as f64 / 1_000_000.0 * price;
+        per_million(self.input_tokens, input_price)
+            + per_million(self.cache_read_input_tokens, input_price * 0.1)
+            + per_million(self.cache_creation_input_tokens, input_price * 1.25)
+            + per_million(self.output_tokens, output_price)
+    }
+}
+
+impl AddAssign<&UsageTotals> for UsageTotals {
+    fn add_assign(&mut self, other: &UsageTotals) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.total_cost_usd += other.total_cost_usd;
+        self.num_turns += other.num_turns;
+    }
+}
+
+/// Usage of a [`ClaudeClient`](crate::ClaudeClient) since it was created or
+/// since [`reset_usage`](crate::ClaudeClient::reset_usage)
+///
+/// Tokens of a turn still in progress are included as its assistant messages
+/// arrive; when the turn's result arrives, the result's usage replaces them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageSnapshot {
+    /// Usage of all sessions together
+    pub total: UsageTotals,
+    /// Usage of each session, by session ID
+    pub sessions: BTreeMap<String, UsageTotals>,
+    /// Tokens in the conversation's context, prompt and answer, as of the
+    /// latest assistant message
+    ///
+    /// Subagents' messages are left out. It drops to zero when the
+    /// conversation is compacted, until the next assistant message.
+    #[serde(default)]
+    pub context_tokens: u64,
+}
+
+/// Spending reported to [`ClaudeAgentOptions::on_budget_warning`](crate::ClaudeAgentOptions::on_budget_warning)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BudgetWarning {
+    /// Cost so far in USD, including an estimate for the running turn
+    pub spent_usd: f64,
+    /// [`ClaudeAgentOptions::max_budget_usd`](crate::ClaudeAgentOptions::max_budget_usd)
+    pub budget_usd: f64,
+}
@@ -0,0 +1,452 @@
+//! Early checks for contradictory [`ClaudeAgentOptions`]
+//!
+//! Options the CLI would reject, or silently misread, are caught before a
+//! process is started. [`ClaudeClient::try_new`](crate::ClaudeClient::try_new),
+//! [`query`](crate::query) and [`query_stream`](crate::query_stream) run
+//! [`ClaudeAgentOptions::validate`] themselves: errors fail the call and
+//! warnings are logged.
+//!
+//! # Example
+//!
+//! ```
+//! use claude_agent_sdk::{ClaudeAgentOptions, IssueSeverity};
+//!
+//! let options = ClaudeAgentOptions::builder().max_turns(0).build();
+//! let issues = options.validate().unwrap_err();
+//! assert_eq!(issues[0].severity, IssueSeverity::Error);
+//! assert_eq!(issues[0].field, "max_turns");
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+
+use super::config::ClaudeAgentOptions;
+use crate::errors::{ClaudeError, Result};
+
+/// The most thinking tokens any current model accepts
+const MAX_THINKING_TOKENS: u32 = 64_000;
+
+/// The most thinking tokens Opus 4 and Opus 4.1 accept
+const MAX_THINKING_TOKENS_OPUS_4: u32 = 32_000;
+
+/// How serious a [`ConfigIssue`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSeverity {
+    /// The CLI would reject the options or misbehave
+    Error,
+    /// The options work, but probably not as intended
+    Warning,
+}
+
+/// A problem [`ClaudeAgentOptions::validate`] found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// How serious the issue is
+    pub severity: IssueSeverity,
+    /// The option the issue is about, e.g. `"max_turns"`
+    pub field: &'static str,
+    /// What is wrong and which builder method to change
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            field,
+            message: message.into(),
+        }
+    }
+
+    fn warning(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl ClaudeAgentOptions {
+    /// Check the options for contradictions the CLI would reject
+    ///
+    /// Returns every issue found, errors and warnings alike, or `Ok(())` when
+    /// there are none. Each message names the builder method to change.
+    pub fn validate(&self) -> std::result::Result<(), Vec<ConfigIssue>> {
+        let mut issues = Vec::new();
+
+        if self.max_turns == Some(0) {
+            issues.push(ConfigIssue::error(
+                "max_turns",
+                "`.max_turns(0)` allows no turns at all; pass at least 1 or leave it unset",
+            ));
+        }
+
+        if self.resume.is_some() && self.continue_conversation {
+            issues.push(ConfigIssue::error(
+                "continue_conversation",
+                "`.resume(..)` and `.continue_conversation(true)` both pick the session to \
+                 continue; keep only one of them",
+            ));
+        }
+
+        if self.fork_session && self.resume.is_none() && !self.continue_conversation {
+            issues.push(ConfigIssue::warning(
+                "fork_session",
+                "`.fork_session(true)` has no effect without `.resume(..)` or \
+                 `.continue_conversation(true)`",
+            ));
+        }
+
+        if let (Some(model), Some(fallback)) = (&self.model, &self.fallback_model)
+            && model == fallback
+        {
+            issues.push(ConfigIssue::error(
+                "fallback_model",
+                format!(
+                    "`.fallback_model(\"{}\")` is the same as `.model(..)`; pick a different \
+                     fallback model",
+                    fallback
+                ),
+            ));
+        }
+
+        if !(self.budget_soft_limit_ratio > 0.0 && self.budget_soft_limit_ratio <= 1.0) {
+            issues.push(ConfigIssue::error(
+                "budget_soft_limit_ratio",
+                format!(
+                    "`.budget_soft_limit_ratio({})` must be above 0 and at most 1",
+                    self.budget_soft_limit_ratio
+                ),
+            ));
+        }
+
+        if self.enforce_budget_client_side && self.max_budget_usd.is_none() {
+            issues.push(ConfigIssue::warning(
+                "enforce_budget_client_side",
+                "`.enforce_budget_client_side(true)` has no effect without `.max_budget_usd(..)`",
+            ));
+        }
+
+        if self.auto_compact_threshold_tokens == Some(0) {
+            issues.push(ConfigIssue::error(
+                "auto_compact_threshold_tokens",
+                "`.auto_compact_threshold_tokens(0)` would compact before every query; pass the \
+                 context size to compact at or leave it unset",
+            ));
+        }
+
+        if let Some(tokens) = self.max_thinking_tokens {
+            let limit = thinking_token_limit(self.model.as_deref());
+            if tokens > limit {
+                issues.push(ConfigIssue::error(
+                    "max_thinking_tokens",
+                    format!(
+                        "`.max_thinking_tokens({})` is above the limit of {} for {}",
+                        tokens,
+                        limit,
+                        self.model.as_deref().unwrap_or("the default model")
+                    ),
+                ));
+            }
+        }
+
+        for path in self.plugins.iter().filter_map(|plugin| plugin.path()) {
+            let resolved = self.resolve_in_cwd(path);
+            if !resolved.exists() {
+                issues.push(ConfigIssue::error(
+                    "plugins",
+                    format!(
+                        "`.plugins(..)` loads a plugin from {}, which does not exist",
+                        resolved.display()
+                    ),
+                ));
+            }
+        }
+
+        if let Some(dir) = &self.plugins_dir {
+            let resolved = self.resolve_in_cwd(dir);
+            if !resolved.is_dir() {
+                issues.push(ConfigIssue::error(
+                    "plugins_dir",
+                    format!(
+                        "`.plugins_dir(..)` loads plugins from {}, which is not a directory",
+                        resolved.display()
+                    ),
+                ));
+            }
+        }
+
+        if let Some(format) = &self.output_format {
+            issues.extend(output_format_issue(format));
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Run [`validate`](Self::validate), logging warnings and failing on errors
+    pub(crate) fn check(&self) -> Result<()> {
+        let Err(issues) = self.validate() else {
+            return Ok(());
+        };
+
+        let mut errors = Vec::new();
+        for issue in issues {
+            match issue.severity {
+                IssueSeverity::Warning => warn!("Claude options: {}", issue),
+                IssueSeverity::Error => errors.push(issue.to_string()),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ClaudeError::InvalidConfig(errors.join("; ")))
+        }
+    }
+
+    /// `path` as the CLI sees it, relative paths being relative to [`cwd`](Self::cwd)
+    pub(crate) fn resolve_in_cwd(&self, path: &Path) -> PathBuf {
+        match &self.cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+}
+
+/// The thinking token limit of `model`, if it is known to be lower than the
+/// overall maximum
+fn thinking_token_limit(model: Option<&str>) -> u32 {
+    match model {
+        Some(model) if is_opus_4_or_4_1(model) => MAX_THINKING_TOKENS_OPUS_4,
+        _ => MAX_THINKING_TOKENS,
+    }
+}
+
+fn is_opus_4_or_4_1(model: &str) -> bool {
+    model.starts_with("claude-opus-4-1")
+        || model.starts_with("claude-opus-4-0")
+        || model.starts_with("claude-opus-4-2025")
+        || model == "claude-opus-4"
+}
+
+fn output_format_issue(format: &serde_json::Value) -> Option<ConfigIssue> {
+    let message = match format.get("type").and_then(|kind| kind.as_str()) {
+        Some("json_schema") if format.get("schema").is_none() => {
+            "`.output_format(..)` has no \"schema\" key; build it with \
+             `json_schema_output_format::<T>()`"
+        },
+        Some(_) => return None,
+        None => {
+            "`.output_format(..)` needs a \"type\" key, e.g. \
+             `{\"type\": \"json_schema\", \"schema\": ..}`"
+        },
+    };
+    Some(ConfigIssue::error("output_format", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::plugin::SdkPluginConfig;
+    use serde_json::json;
+
+    fn only_issue(options: ClaudeAgentOptions) -> ConfigIssue {
+        let mut issues = options.validate().unwrap_err();
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        issues.remove(0)
+    }
+
+    #[test]
+    fn test_default_options_are_valid() {
+        assert!(ClaudeAgentOptions::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_zero_max_turns() {
+        let issue = only_issue(ClaudeAgentOptions::builder().max_turns(0).build());
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.field, "max_turns");
+        assert!(issue.message.contains(".max_turns(0)"));
+
+        assert!(
+            ClaudeAgentOptions::builder()
+                .max_turns(1)
+                .build()
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_resume_with_continue_conversation() {
+        let options = ClaudeAgentOptions::builder()
+            .resume("session-1".to_string())
+            .continue_conversation(true)
+            .build();
+        let issue = only_issue(options);
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.field, "continue_conversation");
+    }
+
+    #[test]
+    fn test_fork_session_without_session_to_fork() {
+        let issue = only_issue(ClaudeAgentOptions::builder().fork_session(true).build());
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.field, "fork_session");
+
+        let forked = ClaudeAgentOptions::builder()
+            .fork_session(true)
+            .resume("session-1".to_string())
+            .build();
+        assert!(forked.validate().is_ok());
+    }
+
+    #[test]
+    fn test_budget_soft_limit_ratio_out_of_range() {
+        let options = ClaudeAgentOptions::builder()
+            .budget_soft_limit_ratio(1.5)
+            .build();
+        let issue = only_issue(options);
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.field, "budget_soft_limit_ratio");
+    }
+
+    #[test]
+    fn test_budget_enforcement_without_budget() {
+        let options = ClaudeAgentOptions::builder()
+            .enforce_budget_client_side(true)
+            .build();
+        let issue = only_issue(options);
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.field, "enforce_budget_client_side");
+    }
+
+    #[test]
+    fn test_zero_auto_compact_threshold() {
+        let options = ClaudeAgentOptions::builder()
+            .auto_compact_threshold_tokens(0)
+            .build();
+        let issue = only_issue(options);
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.field, "auto_compact_threshold_tokens");
+    }
+
+    #[test]
+    fn test_fallback_model_same_as_model() {
+        let options = ClaudeAgentOptions::builder()
+            .model("claude-sonnet-4-5".to_string())
+            .fallback_model("claude-sonnet-4-5".to_string())
+            .build();
+        let issue = only_issue(options);
+        assert_eq!(issue.field, "fallback_model");
+        assert!(
+            issue
+                .message
+                .contains(".fallback_model(\"claude-sonnet-4-5\")")
+        );
+
+        let options = ClaudeAgentOptions::builder()
+            .model("claude-sonnet-4-5".to_string())
+            .fallback_model("claude-haiku-4-5".to_string())
+            .build();
+        assert!(options.validate().is_ok());
+    }
+
+    #[test]
+    fn test_max_thinking_tokens_above_limit() {
+        let issue = only_issue(
+            ClaudeAgentOptions::builder()
+                .max_thinking_tokens(100_000)
+                .build(),
+        );
+        assert_eq!(issue.field, "max_thinking_tokens");
+        assert!(issue.message.contains("64000"));
+
+        let opus = ClaudeAgentOptions::builder()
+            .model("claude-opus-4-1-20250805".to_string())
+            .max_thinking_tokens(48_000)
+            .build();
+        assert!(only_issue(opus).message.contains("32000"));
+
+        let sonnet = ClaudeAgentOptions::builder()
+            .model("claude-sonnet-4-5".to_string())
+            .max_thinking_tokens(48_000)
+            .build();
+        assert!(sonnet.validate().is_ok());
+    }
+
+    #[test]
+    fn test_missing_plugin_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = ClaudeAgentOptions::builder()
+            .plugins(vec![
+                SdkPluginConfig::local(dir.path()),
+                SdkPluginConfig::local(dir.path().join("missing")),
+            ])
+            .build();
+        let issue = only_issue(options);
+        assert_eq!(issue.field, "plugins");
+        assert!(issue.message.contains("missing"));
+
+        let relative = ClaudeAgentOptions::builder()
+            .cwd(dir.path().parent().unwrap())
+            .plugins(vec![SdkPluginConfig::local(
+                dir.path().file_name().unwrap(),
+            )])
+            .build();
+        assert!(relative.validate().is_ok());
+    }
+
+    #[test]
+    fn test_output_format_without_schema() {
+        let options = ClaudeAgentOptions::builder()
+            .output_format(json!({"type": "json_schema"}))
+            .build();
+        let issue = only_issue(options);
+        assert_eq!(issue.field, "output_format");
+        assert!(issue.message.contains("json_schema_output_format"));
+
+        let untyped = ClaudeAgentOptions::builder()
+            .output_format(json!({"schema": {}}))
+            .build();
+        assert_eq!(only_issue(untyped).field, "output_format");
+
+        let complete = ClaudeAgentOptions::builder()
+            .output_format(json!({"type": "json_schema", "schema": {"type": "object"}}))
+            .build();
+        assert!(complete.validate().is_ok());
+    }
+
+    #[test]
+    fn test_check_fails_on_errors_only() {
+        assert!(
+            ClaudeAgentOptions::builder()
+                .fork_session(true)
+                .build()
+                .check()
+                .is_ok()
+        );
+
+        let err = ClaudeAgentOptions::builder()
+            .max_turns(0)
+            .build()
+            .check()
+            .unwrap_err();
+        assert!(
+            matches!(err, ClaudeError::InvalidConfig(ref message) if message.contains("max_turns"))
+        );
+    }
+}
@@ -120,8 +120,10 @@
 mod session;
 mod types;
 
-pub use session::{create_session, resume_session, Session};
-pub use types::{Message, PermissionMode, PromptResult, SessionOptions};
+pub use session::{create_session, list_sessions, resume_session, Session};
+pub use types::{
+    Message, OptionsExtension, PermissionMode, PromptResult, SessionInfo, SessionOptions,
+};
 
 use crate::errors::Result;
 use crate::types::config::ClaudeAgentOptions;
@@ -162,11 +164,13 @@ use crate::client::ClaudeClient;
 /// - Network connection fails
 /// - API returns an error
 /// - Response parsing fails
+/// - The CLI stops before reporting a result ([`ClaudeError::IncompleteTurn`](crate::ClaudeError::IncompleteTurn))
 pub async fn prompt(
     prompt: impl Into<String>,
     options: SessionOptions,
 ) -> Result<PromptResult> {
     let prompt_text = prompt.into();
+    let lenient = options.lenient_structured_output;
     let opts: ClaudeAgentOptions = options.into();
 
     // Create client and send query
@@ -180,6 +184,7 @@ pub async fn prompt(
     let mut input_tokens = 0;
     let mut output_tokens = 0;
     let mut model: Option<String> = None;
+    let mut result_message = None;
     let mut stream = client.receive_response();
 
     use futures::StreamExt;
@@ -201,18 +206,19 @@ pub async fn prompt(
                 }
 
                 // Track token usage if available
-                if let Some(usage) = &assist_msg.message.usage {
-                    // Usage is a JSON value, parse it to extract token counts
-                    if let Some(input) = usage.get("input_tokens").and_then(|v| v.as_u64()) {
-                        input_tokens = input;
-                    }
-                    if let Some(output) = usage.get("output_tokens").and_then(|v| v.as_u64()) {
-                        output_tokens = output;
-                    }
+                if let Some(usage) = &assist_msg.token_usage {
+                    input_tokens = usage.input_tokens;
+                    output_tokens = usage.output_tokens;
                 }
             }
-            crate::types::messages::Message::Result(_) => {
+            crate::types::messages::Message::Result(result) => {
+                // The result's usage covers the whole turn
+                if let Some(usage) = &result.token_usage {
+                    input_tokens = usage.input_tokens;
+                    output_tokens = usage.output_tokens;
+                }
                 // End of conversation
+                result_message = Some(result);
                 break;
             }
             _ => {
@@ -221,11 +227,20 @@ pub async fn prompt(
         }
     }
 
+    let structured = crate::types::messages::resolve_structured_output(
+        result_message.as_ref(),
+        &content,
+        lenient,
+    );
+    let fallback = result_message.and_then(|result| result.fallback);
+
     Ok(PromptResult {
         content,
         input_tokens,
         output_tokens,
         model,
+        structured,
+        fallback,
     })
 }
 
@@ -243,6 +258,8 @@ mod tests {
             input_tokens: 10,
             output_tokens: 20,
             model: Some("claude-sonnet-4-20250514".to_string()),
+            structured: None,
+            fallback: None,
         };
 
         assert_eq!(result.content, "Test response");
@@ -5,12 +5,17 @@
 use crate::client::ClaudeClient;
 use crate::errors::{ClaudeError, Result};
 use crate::types::config::ClaudeAgentOptions;
-use crate::types::messages::Message;
-use futures::StreamExt;
+use crate::types::messages::{Message, ResultMessage};
+use crate::prompts::{PromptError, PromptLibrary};
+use futures::stream::{Stream, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use super::types::SessionOptions;
+use super::types::{Message as V2Message, SessionInfo, SessionOptions};
 
 /// A conversation session with Claude
 ///
@@ -50,6 +55,12 @@ pub struct Session {
     client: Arc<Mutex<ClaudeClient>>,
     /// Connection state (set to false when closed)
     connected: std::sync::atomic::AtomicBool,
+    /// Model to switch back to once the current turn ends
+    restore_model: std::sync::Mutex<Option<Option<String>>>,
+    /// Session ID reported by the CLI
+    cli_session_id: std::sync::Mutex<Option<String>>,
+    /// Cost of the finished turns in USD
+    cost_usd: std::sync::Mutex<f64>,
 }
 
 impl Session {
@@ -62,6 +73,9 @@ impl Session {
             options,
             client: Arc::new(Mutex::new(client)),
             connected: std::sync::atomic::AtomicBool::new(true),
+            restore_model: std::sync::Mutex::new(None),
+            cli_session_id: std::sync::Mutex::new(None),
+            cost_usd: std::sync::Mutex::new(0.0),
         }
     }
 
@@ -105,10 +119,50 @@ impl Session {
         Ok(())
     }
 
+    /// Render the latest version of a saved prompt and send it
+    ///
+    /// If the prompt has a model hint, the session switches to that model for
+    /// this turn and back to its own model once `receive()` reaches the end of the turn.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::v2::Session;
+    /// # use claude_agent_sdk::prompts::PromptLibrary;
+    /// # use std::collections::HashMap;
+    /// # async fn example(session: &mut Session, library: &PromptLibrary) -> Result<(), Box<dyn std::error::Error>> {
+    /// let vars = HashMap::from([("report".to_string(), "Crash on startup".to_string())]);
+    /// session.send_prompt(library, "bug-triage", &vars).await?;
+    /// let messages = session.receive().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_prompt(
+        &mut self,
+        library: &PromptLibrary,
+        name: &str,
+        vars: &HashMap<String, String>,
+    ) -> Result<()> {
+        let prompt = library
+            .get(name)
+            .ok_or_else(|| PromptError::NotFound(name.to_string()))?;
+        let text = prompt.render(vars)?;
+
+        if let Some(model) = &prompt.metadata.model
+            && self.options.model.as_ref() != Some(model)
+        {
+            self.client.lock().await.set_model(Some(model)).await?;
+            *self.restore_model.lock().unwrap() = Some(self.options.model.clone());
+        }
+
+        self.send(text).await
+    }
+
     /// Receive messages from Claude
     ///
     /// This method returns all pending messages from Claude since the last `send()` call.
     /// Messages are returned until a `Result` message is encountered (end of turn).
+    /// Use [`receive_stream()`](Self::receive_stream) to handle them as they arrive.
     ///
     /// # Returns
     ///
@@ -129,42 +183,78 @@ impl Session {
     /// # }
     /// ```
     pub async fn receive(&self) -> Result<Vec<V2Message>> {
-        let client = self.client.lock().await;
-        let mut stream = client.receive_response();
-        let mut messages = Vec::new();
-
-        while let Some(result) = stream.next().await {
-            let msg = result?;
-
-            match msg {
-                Message::Assistant(assist_msg) => {
-                    // Extract text content
-                    let content = assist_msg
-                        .message
-                        .content
-                        .iter()
-                        .filter_map(|block| match block {
-                            crate::types::messages::ContentBlock::Text(text) => {
-                                Some(text.text.clone())
-                            }
-                            _ => None,
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n");
-
-                    messages.push(V2Message::Assistant { content });
-                }
-                Message::Result(_) => {
-                    // End of turn
-                    break;
-                }
-                _ => {
-                    // Ignore other message types
+        self.receive_stream().try_collect().await
+    }
+
+    /// Receive messages from Claude as they arrive
+    ///
+    /// Yields Claude's assistant messages since the last `send()` call and ends
+    /// at the end of the turn, or after the first error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use claude_agent_sdk::v2::Session;
+    /// # use futures::StreamExt;
+    /// # async fn example(session: &mut Session) -> Result<(), Box<dyn std::error::Error>> {
+    /// session.send("Fix the failing test").await?;
+    /// let mut messages = session.receive_stream();
+    /// while let Some(msg) = messages.next().await {
+    ///     let msg = msg?;
+    ///     println!("Claude: {}", msg.text());
+    ///     for tool_use in msg.tool_uses() {
+    ///         println!("  using {}", tool_use.name);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn receive_stream(&self) -> Pin<Box<dyn Stream<Item = Result<V2Message>> + Send + '_>> {
+        Box::pin(async_stream::try_stream! {
+            let client = self.client.lock().await;
+            let mut stream = client.receive_response();
+
+            while let Some(result) = stream.next().await {
+                match result? {
+                    msg @ Message::Assistant(_) => yield V2Message::from(msg),
+                    Message::Result(result) => {
+                        self.finish_turn(&result);
+                        // Undo a per-turn model override from send_prompt()
+                        let restore = self.restore_model.lock().unwrap().take();
+                        if let Some(model) = restore {
+                            client.set_model(model.as_deref()).await?;
+                        }
+                        break;
+                    },
+                    _ => {
+                        // Ignore other message types
+                    },
                 }
             }
+        })
+    }
+
+    /// Record the session ID and cost reported at the end of a turn
+    ///
+    /// The reported cost is already a running total for the session.
+    fn finish_turn(&self, result: &ResultMessage) {
+        *self.cli_session_id.lock().unwrap() = Some(result.session_id.clone());
+        if let Some(cost) = result.total_cost_usd {
+            *self.cost_usd.lock().unwrap() = cost;
         }
+    }
 
-        Ok(messages)
+    /// The session ID reported by the CLI
+    ///
+    /// Unlike [`id`](Self::id), this is the ID of the CLI's conversation, for
+    /// resuming it later. `None` until the first turn has ended.
+    pub fn session_id(&self) -> Option<String> {
+        self.cli_session_id.lock().unwrap().clone()
+    }
+
+    /// Cost of the turns finished so far in USD
+    pub fn cost_so_far(&self) -> f64 {
+        *self.cost_usd.lock().unwrap()
     }
 
     /// Get the model being used for this session
@@ -205,32 +295,6 @@ impl Session {
     }
 }
 
-/// Simplified message type for V2 API sessions
-///
-/// This is a streamlined version of the full Message type,
-/// focused on the most common use cases.
-///
-/// # Variants
-///
-/// * `Assistant` - Response from Claude with text content
-#[derive(Debug, Clone)]
-pub enum V2Message {
-    /// Response from Claude
-    Assistant {
-        /// The text content of the response
-        content: String,
-    },
-}
-
-impl V2Message {
-    /// Get the text content (if available)
-    pub fn as_text(&self) -> Option<&str> {
-        match self {
-            V2Message::Assistant { content } => Some(content),
-        }
-    }
-}
-
 /// Create a new session with Claude
 ///
 /// This function creates a new session and connects to Claude.
@@ -258,7 +322,7 @@ impl V2Message {
 /// ```
 pub async fn create_session(options: SessionOptions) -> Result<Session> {
     let opts: ClaudeAgentOptions = options.clone().into();
-    let mut client = ClaudeClient::new(opts);
+    let client = ClaudeClient::new(opts);
 
     client.connect().await?;
 
@@ -270,21 +334,22 @@ pub async fn create_session(options: SessionOptions) -> Result<Session> {
 
 /// Resume an existing session
 ///
-/// **Important**: True session resumption with conversation history persistence
-/// is not yet implemented. This function creates a new session with the provided ID,
-/// which is useful for session ID continuity but does NOT restore previous
-/// conversation context.
-///
-/// For actual conversation continuity, store messages externally and replay them.
+/// Connects to Claude with the conversation of session `session_id` restored,
+/// so the next message continues where it left off. [`list_sessions()`] lists
+/// the sessions that can be resumed.
 ///
 /// # Arguments
 ///
-/// * `session_id` - The ID to use for the session (does not load previous state)
+/// * `session_id` - The CLI session ID, as returned by [`Session::session_id()`]
 /// * `options` - Session options
 ///
 /// # Returns
 ///
-/// A new `Session` object with the provided ID
+/// A `Session` with the given ID
+///
+/// # Errors
+///
+/// Returns [`ClaudeError::SessionNotFound`] if the CLI has no session with this ID.
 ///
 /// # Example
 ///
@@ -293,25 +358,19 @@ pub async fn create_session(options: SessionOptions) -> Result<Session> {
 ///
 /// #[tokio::main]
 /// async fn example() -> Result<(), Box<dyn std::error::Error>> {
-///     // Note: This creates a fresh session with the given ID
-///     // Previous conversation history is NOT restored
-///     let session = resume_session("existing-session-id", SessionOptions::default()).await?;
-///     println!("Session created with ID: {}", session.id);
+///     let mut session = resume_session("existing-session-id", SessionOptions::default()).await?;
+///     session.send("Where were we?").await?;
+///     println!("Session resumed with ID: {}", session.id);
 ///     Ok(())
 /// }
 /// ```
-#[deprecated(
-    since = "0.2.0",
-    note = "Does not restore conversation history. Use create_session() and store messages externally for true persistence."
-)]
 pub async fn resume_session(
     session_id: &str,
     options: SessionOptions,
 ) -> Result<Session> {
-    // Note: This creates a new session with the provided ID.
-    // True session resumption requires persistent storage integration.
-    let opts: ClaudeAgentOptions = options.clone().into();
-    let mut client = ClaudeClient::new(opts);
+    let mut opts: ClaudeAgentOptions = options.clone().into();
+    opts.resume = Some(session_id.to_string());
+    let client = ClaudeClient::new(opts);
 
     client.connect().await?;
 
@@ -322,16 +381,198 @@ pub async fn resume_session(
     ))
 }
 
+/// List the sessions that can be resumed with [`resume_session()`]
+///
+/// Reads the CLI's session store, `projects/` in `$CLAUDE_CONFIG_DIR` or
+/// `~/.claude`, and returns the sessions of all projects, newest first.
+///
+/// # Example
+///
+/// ```no_run
+/// use claude_agent_sdk::v2::list_sessions;
+///
+/// #[tokio::main]
+/// async fn example() -> Result<(), Box<dyn std::error::Error>> {
+///     for session in list_sessions().await? {
+///         println!("{} {:?}: {}", session.id, session.cwd, session.summary.unwrap_or_default());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn list_sessions() -> Result<Vec<SessionInfo>> {
+    let config_dir = match std::env::var_os("CLAUDE_CONFIG_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let home = std::env::var_os("HOME").ok_or_else(|| {
+                ClaudeError::InvalidConfig("HOME is not set; cannot find sessions".to_string())
+            })?;
+            PathBuf::from(home).join(".claude")
+        },
+    };
+    let projects = config_dir.join("projects");
+    tokio::task::spawn_blocking(move || read_session_store(&projects))
+        .await
+        .map_err(|e| ClaudeError::InternalError(format!("Listing sessions failed: {}", e)))?
+}
+
+/// The sessions in `projects`, one directory per project with a transcript per session
+fn read_session_store(projects: &Path) -> Result<Vec<SessionInfo>> {
+    let mut sessions = Vec::new();
+    let project_dirs = match std::fs::read_dir(projects) {
+        Ok(dirs) => dirs,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(sessions),
+        Err(e) => return Err(e.into()),
+    };
+    for project in project_dirs {
+        let project = project?.path();
+        if !project.is_dir() {
+            continue;
+        }
+        for transcript in std::fs::read_dir(&project)? {
+            let transcript = transcript?.path();
+            if transcript.extension().is_some_and(|ext| ext == "jsonl")
+                && let Some(info) = read_session_info(&transcript)?
+            {
+                sessions.push(info);
+            }
+        }
+    }
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.created_at));
+    Ok(sessions)
+}
+
+/// Summary of a session transcript, read up to its first prompt
+fn read_session_info(transcript: &Path) -> Result<Option<SessionInfo>> {
+    let Some(id) = transcript.file_stem().and_then(|stem| stem.to_str()) else {
+        return Ok(None);
+    };
+    let mut info = SessionInfo {
+        id: id.to_string(),
+        created_at: None,
+        cwd: None,
+        summary: None,
+    };
+    let mut first_prompt = None;
+
+    let reader = BufReader::new(std::fs::File::open(transcript)?);
+    for line in reader.lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line?) else {
+            continue;
+        };
+        if info.created_at.is_none() {
+            info.created_at = entry
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(|t| t.parse().ok());
+        }
+        if info.cwd.is_none() {
+            info.cwd = entry.get("cwd").and_then(|cwd| cwd.as_str()).map(PathBuf::from);
+        }
+        match entry.get("type").and_then(|t| t.as_str()) {
+            Some("summary") if info.summary.is_none() => {
+                info.summary = entry.get("summary").and_then(|s| s.as_str()).map(String::from);
+            },
+            Some("user") if first_prompt.is_none() => {
+                first_prompt = prompt_text(&entry["message"]["content"]);
+            },
+            _ => {},
+        }
+        // Summaries come first, so the rest of the transcript adds nothing
+        if first_prompt.is_some() && info.created_at.is_some() && info.cwd.is_some() {
+            break;
+        }
+    }
+
+    info.summary = info.summary.or(first_prompt);
+    Ok(Some(info))
+}
+
+/// Text of a user message's content, either a string or text blocks
+fn prompt_text(content: &serde_json::Value) -> Option<String> {
+    let text = match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => return None,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_v2_message_as_text() {
-        let msg = V2Message::Assistant {
-            content: "Hello!".to_string(),
-        };
+    fn test_finish_turn_tracks_session_id_and_cost() {
+        let client = ClaudeClient::new(ClaudeAgentOptions::default());
+        let session = Session::new("local".to_string(), SessionOptions::default(), client);
+        assert_eq!(session.session_id(), None);
+        assert_eq!(session.cost_so_far(), 0.0);
+
+        // Results report the running cost of the session
+        for cost in [Some(0.25), None, Some(0.75)] {
+            let result: ResultMessage = serde_json::from_value(serde_json::json!({
+                "subtype": "success",
+                "duration_ms": 1,
+                "duration_api_ms": 1,
+                "is_error": false,
+                "num_turns": 1,
+                "session_id": "cli-session",
+                "total_cost_usd": cost,
+            }))
+            .unwrap();
+            session.finish_turn(&result);
+        }
+
+        assert_eq!(session.session_id().as_deref(), Some("cli-session"));
+        assert_eq!(session.cost_so_far(), 0.75);
+    }
+
+    #[test]
+    fn test_read_session_store() {
+        let projects = tempfile::tempdir().unwrap();
+        let project = projects.path().join("-home-me-app");
+        std::fs::create_dir(&project).unwrap();
+        std::fs::write(
+            project.join("older.jsonl"),
+            [
+                r#"{"type":"summary","summary":"Fix the login bug","leafUuid":"u2"}"#,
+                r#"{"type":"user","sessionId":"older","cwd":"/home/me/app","timestamp":"2025-01-01T10:00:00Z","message":{"role":"user","content":"the login fails"}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            project.join("newer.jsonl"),
+            [
+                "not json",
+                r#"{"type":"user","sessionId":"newer","cwd":"/home/me/app","timestamp":"2025-02-01T10:00:00Z","message":{"role":"user","content":[{"type":"text","text":" Add a logout button "}]}}"#,
+                r#"{"type":"assistant","sessionId":"newer","cwd":"/home/me/app","timestamp":"2025-02-01T10:00:05Z","message":{"content":[]}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        std::fs::write(project.join("notes.txt"), "ignored").unwrap();
+
+        let sessions = read_session_store(projects.path()).unwrap();
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, "newer");
+        assert_eq!(sessions[0].summary.as_deref(), Some("Add a logout button"));
+        assert_eq!(sessions[1].id, "older");
+        assert_eq!(sessions[1].summary.as_deref(), Some("Fix the login bug"));
+        assert_eq!(sessions[1].cwd, Some(PathBuf::from("/home/me/app")));
+        assert_eq!(
+            sessions[1].created_at.unwrap().to_rfc3339(),
+            "2025-01-01T10:00:00+00:00"
+        );
 
-        assert_eq!(msg.as_text(), Some("Hello!"));
+        let missing = projects.path().join("missing");
+        assert!(read_session_store(&missing).unwrap().is_empty());
     }
 }
@@ -3,8 +3,16 @@
 //! This module contains the simplified types used by the V2 API.
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
 use typed_builder::TypedBuilder;
 
+use crate::types::config::ClaudeAgentOptions;
+
+/// Change to the [`ClaudeAgentOptions`] a [`SessionOptions`] converts to
+pub type OptionsExtension = Arc<dyn Fn(&mut ClaudeAgentOptions) + Send + Sync>;
+
 /// Simplified session options for V2 API
 ///
 /// `SessionOptions` contains only the most commonly used configuration parameters,
@@ -27,8 +35,16 @@ use typed_builder::TypedBuilder;
 ///     .max_turns(10)
 ///     .permission_mode(PermissionMode::BypassPermissions)
 ///     .build();
+///
+/// // Restricted tools, plus a V1 option without a V2 field
+/// let options = SessionOptions::builder()
+///     .system_prompt("You review Rust code".to_string())
+///     .allowed_tools(vec!["Read".to_string(), "Grep".to_string()])
+///     .cwd("/path/to/repo")
+///     .extend(|options| options.fork_session = true)
+///     .build();
 /// ```
-#[derive(Debug, Clone, TypedBuilder, Serialize, Deserialize)]
+#[derive(Clone, TypedBuilder, Serialize, Deserialize)]
 pub struct SessionOptions {
     /// Model to use (None = system default)
     #[builder(default, setter(strip_option))]
@@ -57,6 +73,40 @@ pub struct SessionOptions {
     /// Whether to include partial messages in stream
     #[builder(default = false)]
     pub include_partial_messages: bool,
+
+    /// Output format for structured outputs, e.g. `{"type": "json_schema", "schema": {...}}`
+    #[builder(default, setter(strip_option))]
+    #[serde(default)]
+    pub output_format: Option<serde_json::Value>,
+
+    /// Fall back to the last fenced JSON block of the response when the
+    /// result carries no structured output
+    #[builder(default = false)]
+    #[serde(default)]
+    pub lenient_structured_output: bool,
+
+    /// Tools Claude may use without asking (empty = no restriction)
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+
+    /// Tools Claude may not use
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub disallowed_tools: Vec<String>,
+
+    /// Working directory (None = current directory)
+    #[builder(default, setter(into, strip_option))]
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+
+    /// Applied last to the converted [`ClaudeAgentOptions`], for settings
+    /// without a `SessionOptions` field
+    #[builder(default, setter(transform = |extend: impl Fn(&mut ClaudeAgentOptions) + Send + Sync + 'static| {
+        Some(Arc::new(extend) as OptionsExtension)
+    }))]
+    #[serde(skip)]
+    pub extend: Option<OptionsExtension>,
 }
 
 impl Default for SessionOptions {
@@ -69,10 +119,36 @@ impl Default for SessionOptions {
             max_thinking_tokens: None,
             system_prompt: None,
             include_partial_messages: false,
+            output_format: None,
+            lenient_structured_output: false,
+            allowed_tools: Vec::new(),
+            disallowed_tools: Vec::new(),
+            cwd: None,
+            extend: None,
         }
     }
 }
 
+impl fmt::Debug for SessionOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionOptions")
+            .field("model", &self.model)
+            .field("permission_mode", &self.permission_mode)
+            .field("max_budget_usd", &self.max_budget_usd)
+            .field("max_turns", &self.max_turns)
+            .field("max_thinking_tokens", &self.max_thinking_tokens)
+            .field("system_prompt", &self.system_prompt)
+            .field("include_partial_messages", &self.include_partial_messages)
+            .field("output_format", &self.output_format)
+            .field("lenient_structured_output", &self.lenient_structured_output)
+            .field("allowed_tools", &self.allowed_tools)
+            .field("disallowed_tools", &self.disallowed_tools)
+            .field("cwd", &self.cwd)
+            .field("extend", &self.extend.as_ref().map(|_| "<function>"))
+            .finish()
+    }
+}
+
 impl From<SessionOptions> for crate::types::config::ClaudeAgentOptions {
     fn from(options: SessionOptions) -> Self {
         // Convert permission_mode if present
@@ -86,7 +162,7 @@ impl From<SessionOptions> for crate::types::config::ClaudeAgentOptions {
         // Build ClaudeAgentOptions using builder with conditional field setting
         // Since we can't use if-else with builder reassignment due to TypedBuilder's type system,
         // we use a match to handle the different cases
-        match (options.model, permission_mode, options.max_budget_usd) {
+        let mut converted = match (options.model, permission_mode, options.max_budget_usd) {
             (Some(model), Some(pm), Some(max_budget)) => {
                 crate::types::config::ClaudeAgentOptions::builder()
                     .model(model)
@@ -179,7 +255,16 @@ impl From<SessionOptions> for crate::types::config::ClaudeAgentOptions {
                     .include_partial_messages(options.include_partial_messages)
                     .build()
             }
+        };
+
+        converted.output_format = options.output_format;
+        converted.allowed_tools = options.allowed_tools;
+        converted.disallowed_tools = options.disallowed_tools;
+        converted.cwd = options.cwd;
+        if let Some(extend) = options.extend {
+            extend(&mut converted);
         }
+        converted
     }
 }
 
@@ -232,6 +317,8 @@ impl From<PermissionMode> for crate::types::config::PermissionMode {
 ///     input_tokens: 15,
 ///     output_tokens: 5,
 ///     model: Some("claude-sonnet-4-20250514".to_string()),
+///     structured: None,
+///     fallback: None,
 /// };
 ///
 /// println!("Response: {}", result.content);
@@ -248,29 +335,64 @@ pub struct PromptResult {
     /// Number of tokens in the output
     pub output_tokens: u64,
 
-    /// Model used for generation (if known)
+    /// Model that actually generated the response (if known)
     pub model: Option<String>,
+
+    /// Structured output, when [`SessionOptions::output_format`] requested one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured: Option<serde_json::Value>,
+
+    /// Requested vs. actual model, showing whether the fallback model answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<crate::types::messages::ModelFallback>,
 }
 
 impl PromptResult {
+    /// Deserialize the structured output into `T`
+    pub fn structured_as<T: serde::de::DeserializeOwned>(&self) -> crate::errors::Result<T> {
+        crate::types::messages::deserialize_structured(self.structured.clone())
+    }
+
     /// Get the total token usage (input + output)
     pub fn total_tokens(&self) -> u64 {
         self.input_tokens + self.output_tokens
     }
 
+    /// Whether the turn was answered by the fallback model
+    pub fn fallback_used(&self) -> bool {
+        self.fallback
+            .as_ref()
+            .is_some_and(|fallback| fallback.fallback_used)
+    }
+
     /// Get the cost in USD (approximate)
     ///
-    /// This is a rough estimate based on public pricing.
+    /// This is a rough estimate based on public pricing for the model that
+    /// actually answered, so a fallback turn is priced at the fallback model.
     /// Actual costs may vary.
     pub fn estimated_cost_usd(&self) -> f64 {
-        // Rough pricing (subject to change)
-        // Input: $3/M tokens, Output: $15/M tokens
-        let input_cost = (self.input_tokens as f64) / 1_000_000.0 * 3.0;
-        let output_cost = (self.output_tokens as f64) / 1_000_000.0 * 15.0;
-        input_cost + output_cost
+        let usage = crate::types::usage::UsageTotals {
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            ..Default::default()
+        };
+        usage.estimated_cost_usd(self.model.as_deref())
     }
 }
 
+/// A session that can be resumed, as listed by [`list_sessions()`](super::list_sessions)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// Session ID, for [`resume_session()`](super::resume_session)
+    pub id: String,
+    /// When the session's first message was written
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Working directory of the session
+    pub cwd: Option<std::path::PathBuf>,
+    /// The session's summary, or else its first prompt
+    pub summary: Option<String>,
+}
+
 /// Simplified message type for V2 API
 ///
 /// This is a simplified version of the full `Message` type,
@@ -293,6 +415,9 @@ pub enum Message {
     Assistant {
         /// The text content
         content: String,
+        /// Tools Claude called in this message
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tool_uses: Vec<crate::types::messages::ToolUseBlock>,
     },
 
     /// Result from a tool execution
@@ -309,11 +434,24 @@ impl Message {
     pub fn as_text(&self) -> Option<&str> {
         match self {
             Message::User { content } => Some(content),
-            Message::Assistant { content } => Some(content),
+            Message::Assistant { content, .. } => Some(content),
             Message::ToolResult { .. } => None,
         }
     }
 
+    /// The text of a user or assistant message, empty for tool results
+    pub fn text(&self) -> &str {
+        self.as_text().unwrap_or_default()
+    }
+
+    /// Tools called by an assistant message, empty for other messages
+    pub fn tool_uses(&self) -> &[crate::types::messages::ToolUseBlock] {
+        match self {
+            Message::Assistant { tool_uses, .. } => tool_uses,
+            _ => &[],
+        }
+    }
+
     /// Check if this is a user message
     pub fn is_user(&self) -> bool {
         matches!(self, Message::User { .. })
@@ -365,11 +503,21 @@ impl From<crate::types::messages::Message> for Message {
                     })
                     .collect::<Vec<_>>()
                     .join("\n");
+                let tool_uses = assist_msg
+                    .message
+                    .content
+                    .into_iter()
+                    .filter_map(|block| match block {
+                        crate::types::messages::ContentBlock::ToolUse(tool_use) => Some(tool_use),
+                        _ => None,
+                    })
+                    .collect();
 
-                Message::Assistant { content }
+                Message::Assistant { content, tool_uses }
             }
             _ => Message::Assistant {
                 content: String::new(),
+                tool_uses: Vec::new(),
             },
         }
     }
@@ -400,6 +548,95 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_session_options_output_format_conversion() {
+        let schema = serde_json::json!({"type": "json_schema", "schema": {"type": "object"}});
+        let options = SessionOptions::builder()
+            .output_format(schema.clone())
+            .lenient_structured_output(true)
+            .build();
+
+        let converted: crate::types::config::ClaudeAgentOptions = options.into();
+        assert_eq!(converted.output_format, Some(schema));
+    }
+
+    #[test]
+    fn test_session_options_tools_and_cwd_conversion() {
+        let options = SessionOptions::builder()
+            .system_prompt("Be brief".to_string())
+            .permission_mode(PermissionMode::AcceptEdits)
+            .allowed_tools(vec!["Read".to_string()])
+            .disallowed_tools(vec!["Bash".to_string()])
+            .cwd("/tmp/project")
+            .build();
+
+        let converted: ClaudeAgentOptions = options.into();
+        assert_eq!(converted.allowed_tools, ["Read"]);
+        assert_eq!(converted.disallowed_tools, ["Bash"]);
+        assert_eq!(converted.cwd, Some(PathBuf::from("/tmp/project")));
+        assert!(matches!(
+            converted.system_prompt,
+            Some(crate::types::config::SystemPrompt::Text(ref text)) if text == "Be brief"
+        ));
+        assert!(matches!(
+            converted.permission_mode,
+            Some(crate::types::config::PermissionMode::AcceptEdits)
+        ));
+    }
+
+    #[test]
+    fn test_session_options_extend_runs_last() {
+        let options = SessionOptions::builder()
+            .allowed_tools(vec!["Read".to_string()])
+            .extend(|options| {
+                options.allowed_tools.push("Write".to_string());
+                options.fork_session = true;
+            })
+            .build();
+        assert!(format!("{:?}", options).contains("extend: Some(\"<function>\")"));
+
+        let converted: ClaudeAgentOptions = options.clone().into();
+        assert_eq!(converted.allowed_tools, ["Read", "Write"]);
+        assert!(converted.fork_session);
+
+        // A clone shares the extension
+        let converted: ClaudeAgentOptions = options.into();
+        assert!(converted.fork_session);
+    }
+
+    #[test]
+    fn test_session_options_default_conversion_unchanged() {
+        let converted: ClaudeAgentOptions = SessionOptions::default().into();
+        assert!(converted.allowed_tools.is_empty());
+        assert!(converted.disallowed_tools.is_empty());
+        assert!(converted.cwd.is_none());
+        assert!(converted.model.is_none());
+        assert!(converted.permission_mode.is_none());
+        assert_eq!(converted.max_turns, Some(0));
+        assert!(!converted.fork_session);
+
+        let json = serde_json::to_value(SessionOptions::default()).unwrap();
+        assert!(json.get("extend").is_none());
+        let parsed: SessionOptions = serde_json::from_value(json).unwrap();
+        assert!(parsed.extend.is_none());
+    }
+
+    #[test]
+    fn test_prompt_result_structured_as() {
+        let result = PromptResult {
+            content: String::new(),
+            input_tokens: 0,
+            output_tokens: 0,
+            model: None,
+            structured: Some(serde_json::json!({"answer": 4})),
+            fallback: None,
+        };
+
+        let value: std::collections::HashMap<String, u32> = result.structured_as().unwrap();
+        assert_eq!(value["answer"], 4);
+        assert!(result.structured_as::<Vec<String>>().is_err());
+    }
+
     #[test]
     fn test_prompt_result_total_tokens() {
         let result = PromptResult {
@@ -407,6 +644,8 @@ mod tests {
             input_tokens: 100,
             output_tokens: 50,
             model: None,
+            structured: None,
+            fallback: None,
         };
 
         assert_eq!(result.total_tokens(), 150);
@@ -427,6 +666,7 @@ mod tests {
     fn test_message_is_assistant() {
         let msg = Message::Assistant {
             content: "Hi there!".to_string(),
+            tool_uses: Vec::new(),
         };
 
         assert!(!msg.is_user());
@@ -434,6 +674,25 @@ mod tests {
         assert_eq!(msg.as_text(), Some("Hi there!"));
     }
 
+    #[test]
+    fn test_message_from_assistant_message() {
+        let message: crate::types::messages::Message = serde_json::from_value(serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [
+                {"type": "text", "text": "Let me look."},
+                {"type": "tool_use", "id": "t1", "name": "Read", "input": {"file_path": "a.rs"}},
+                {"type": "text", "text": "Reading a.rs"}
+            ]}
+        }))
+        .unwrap();
+
+        let msg = Message::from(message);
+        assert_eq!(msg.text(), "Let me look.\nReading a.rs");
+        assert_eq!(msg.tool_uses().len(), 1);
+        assert_eq!(msg.tool_uses()[0].name, "Read");
+        assert_eq!(msg.tool_uses()[0].input["file_path"], "a.rs");
+    }
+
     #[test]
     fn test_message_is_tool_result() {
         let msg = Message::ToolResult {
@@ -445,6 +704,8 @@ mod tests {
         assert!(!msg.is_user());
         assert!(!msg.is_assistant());
         assert_eq!(msg.as_text(), None);
+        assert_eq!(msg.text(), "");
+        assert!(msg.tool_uses().is_empty());
     }
 
     #[test]
@@ -454,6 +715,8 @@ mod tests {
             input_tokens: 1_000_000, // 1M input tokens
             output_tokens: 1_000_000, // 1M output tokens
             model: None,
+            structured: None,
+            fallback: None,
         };
 
         // 1M input * $3/M = $3
@@ -462,4 +725,23 @@ mod tests {
         let cost = result.estimated_cost_usd();
         assert!((cost - 18.0).abs() < 0.01); // Allow small floating point error
     }
+
+    #[test]
+    fn test_prompt_result_cost_uses_actual_model() {
+        let result = PromptResult {
+            content: "Test".to_string(),
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            model: Some("claude-3-5-haiku-20241022".to_string()),
+            structured: None,
+            fallback: Some(crate::types::messages::ModelFallback {
+                requested_model: Some("opus".to_string()),
+                actual_model: Some("claude-3-5-haiku-20241022".to_string()),
+                fallback_used: true,
+            }),
+        };
+
+        assert!(result.fallback_used());
+        assert!((result.estimated_cost_usd() - 4.8).abs() < 0.01);
+    }
 }
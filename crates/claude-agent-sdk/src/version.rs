@@ -12,6 +12,70 @@ pub const SKIP_VERSION_CHECK_ENV: &str = "CLAUDE_AGENT_SDK_SKIP_VERSION_CHECK";
 /// Entrypoint identifier for subprocess
 pub const ENTRYPOINT: &str = "sdk-rs";
 
+/// First CLI version accepting `--max-budget-usd`
+pub const BUDGET_MIN_VERSION: &str = "2.0.29";
+
+/// First CLI version accepting `--include-partial-messages`
+pub const PARTIAL_MESSAGES_MIN_VERSION: &str = "1.0.86";
+
+/// First CLI version accepting `--fork-session`
+pub const FORK_SESSION_MIN_VERSION: &str = "1.0.90";
+
+/// Optional CLI features, as derived from the CLI version
+///
+/// The SDK leaves out the flags of unsupported features when starting the
+/// CLI. See [`ClaudeClient::cli_capabilities`](crate::ClaudeClient::cli_capabilities).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CliCapabilities {
+    /// `--max-budget-usd`, see [`BUDGET_MIN_VERSION`]
+    pub supports_budget: bool,
+    /// `--include-partial-messages`, see [`PARTIAL_MESSAGES_MIN_VERSION`]
+    pub supports_partial_messages: bool,
+    /// `--fork-session`, see [`FORK_SESSION_MIN_VERSION`]
+    pub supports_fork_session: bool,
+}
+
+impl CliCapabilities {
+    /// Every feature, assumed when the CLI version is unknown
+    pub const ALL: Self = Self {
+        supports_budget: true,
+        supports_partial_messages: true,
+        supports_fork_session: true,
+    };
+
+    /// The features of CLI `version`
+    ///
+    /// Pre-release versions count as their release.
+    pub fn for_version(version: &semver::Version) -> Self {
+        let release = semver::Version::new(version.major, version.minor, version.patch);
+        let at_least = |minimum: &str| {
+            semver::Version::parse(minimum).is_ok_and(|minimum| release >= minimum)
+        };
+        Self {
+            supports_budget: at_least(BUDGET_MIN_VERSION),
+            supports_partial_messages: at_least(PARTIAL_MESSAGES_MIN_VERSION),
+            supports_fork_session: at_least(FORK_SESSION_MIN_VERSION),
+        }
+    }
+
+    /// The features of CLI `version`, or [`ALL`](Self::ALL) if it is unknown
+    pub fn for_optional_version(version: Option<&semver::Version>) -> Self {
+        version.map_or(Self::ALL, Self::for_version)
+    }
+}
+
+impl Default for CliCapabilities {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Parse the output of `claude --version`, such as `2.0.14 (Claude Code)`
+pub fn parse_cli_version(output: &str) -> Option<semver::Version> {
+    let version = output.lines().next()?.split_whitespace().next()?;
+    semver::Version::parse(version.trim_start_matches('v')).ok()
+}
+
 /// Parse a semantic version string into (major, minor, patch)
 pub fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
     let parts: Vec<&str> = version.trim_start_matches('v').split('.').collect();
@@ -77,4 +141,45 @@ mod tests {
         assert!(!check_version("1.9.9"));
         assert!(!check_version("1.99.99"));
     }
+
+    #[test]
+    fn test_parse_cli_version() {
+        let version = parse_cli_version("2.0.14 (Claude Code)\n").unwrap();
+        assert_eq!(version, semver::Version::new(2, 0, 14));
+        assert_eq!(
+            parse_cli_version("v2.1.0-beta.1").unwrap().to_string(),
+            "2.1.0-beta.1"
+        );
+        assert_eq!(parse_cli_version("Claude Code"), None);
+        assert_eq!(parse_cli_version(""), None);
+    }
+
+    #[test]
+    fn test_capabilities_by_version() {
+        let capabilities = |version: &str| {
+            CliCapabilities::for_version(&semver::Version::parse(version).unwrap())
+        };
+        let table = [
+            ("1.0.85", false, false, false),
+            ("1.0.86", false, true, false),
+            ("1.0.90", false, true, true),
+            ("2.0.0", false, true, true),
+            ("2.0.28", false, true, true),
+            ("2.0.29-beta.2", true, true, true),
+            ("2.0.29", true, true, true),
+            ("3.0.0", true, true, true),
+        ];
+        for (version, budget, partial_messages, fork_session) in table {
+            assert_eq!(
+                capabilities(version),
+                CliCapabilities {
+                    supports_budget: budget,
+                    supports_partial_messages: partial_messages,
+                    supports_fork_session: fork_session,
+                },
+                "CLI {version}"
+            );
+        }
+        assert_eq!(CliCapabilities::for_optional_version(None), CliCapabilities::ALL);
+    }
 }
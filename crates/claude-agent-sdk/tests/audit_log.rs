@@ -0,0 +1,82 @@
+//! Tool audit log recorded by `ClaudeClient` against a mock CLI
+//!
+//! The mock answers each user message with a tool call, its result, a text
+//! reply and a result.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::observability::{AuditEventKind, AuditLog, JsonlAuditSink, MemoryAuditSink};
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, PermissionMode};
+use serde_json::json;
+use std::sync::Arc;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"a.txt"}}]}}'
+            echo '{"type":"user","session_id":"sess-1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"file contents"}]}}'
+            echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"text","text":"done"}]}}'
+            echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"sess-1"}'
+            ;;
+    esac
+}
+
+serve
+"#;
+
+#[tokio::test]
+async fn test_client_records_tool_invocations() {
+    let mock = MockCli::new(MOCK_CLI);
+    let sink = MemoryAuditSink::new();
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .permission_mode(PermissionMode::AcceptEdits)
+        .allowed_tools(vec!["Read".to_string()])
+        .audit_sink(Arc::new(AuditLog::new(sink.clone())))
+        .build();
+    let mut client = ClaudeClient::new(options);
+
+    client.query_collect("read it").await.unwrap();
+    client.disconnect().await.unwrap();
+
+    let events = sink.events();
+    assert_eq!(events.len(), 2);
+    let (tool_use, tool_result) = (&events[0], &events[1]);
+
+    assert_eq!(tool_use.sequence, 0);
+    assert_eq!(tool_use.kind, AuditEventKind::ToolUse);
+    assert_eq!(tool_use.session_id.as_deref(), Some("sess-1"));
+    assert_eq!(tool_use.tool_name.as_deref(), Some("Read"));
+    assert_eq!(tool_use.input, Some(json!({"file_path": "a.txt"})));
+    assert_eq!(tool_use.permission_mode, Some(PermissionMode::AcceptEdits));
+    assert!(tool_use.pre_approved);
+
+    assert_eq!(tool_result.sequence, 1);
+    assert_eq!(tool_result.kind, AuditEventKind::ToolResult);
+    assert_eq!(tool_result.tool_use_id, "t1");
+    assert_eq!(tool_result.tool_name.as_deref(), Some("Read"));
+    assert_eq!(tool_result.output, Some(json!("file contents")));
+}
+
+#[tokio::test]
+async fn test_jsonl_log_verifies_across_clients() {
+    let mock = MockCli::new(MOCK_CLI);
+    let path = mock.dir().join("audit.jsonl");
+    let log = Arc::new(AuditLog::new(JsonlAuditSink::open(&path).unwrap()).with_hash_chain());
+
+    for prompt in ["first", "second"] {
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(mock.path())
+            .audit_sink(Arc::clone(&log))
+            .build();
+        let mut client = ClaudeClient::new(options);
+        client.query_collect(prompt).await.unwrap();
+        client.disconnect().await.unwrap();
+    }
+
+    assert_eq!(AuditLog::verify(&path).unwrap(), 4);
+}
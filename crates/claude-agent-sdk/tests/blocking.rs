@@ -0,0 +1,73 @@
+//! Blocking API against a mock CLI
+//!
+//! The mock answers every user message with its text as both the assistant
+//! message and the result.
+
+#![cfg(all(unix, feature = "blocking"))]
+
+use claude_agent_sdk::blocking;
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeError, Message};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            text=$(printf '%s' "$line" | sed -n 's/.*"content":"\([^"]*\)".*/\1/p')
+            echo "{\"type\":\"assistant\",\"session_id\":\"mock\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"$text\"}]}}"
+            echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"mock\",\"result\":\"$text\"}"
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    fn options(&self) -> ClaudeAgentOptions {
+        ClaudeAgentOptions::builder().cli_path(self.path()).build()
+    }
+}
+
+#[test]
+fn test_client_iterates_responses() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = blocking::Client::new(mock.options()).unwrap();
+    client.connect().unwrap();
+
+    for prompt in ["one", "two"] {
+        client.query(prompt).unwrap();
+        let messages: Vec<Message> = client.receive_response().collect::<Result<_, _>>().unwrap();
+        let [Message::Assistant(_), Message::Result(result)] = &messages[..] else {
+            panic!("unexpected messages {messages:?}");
+        };
+        assert_eq!(result.result.as_deref(), Some(prompt));
+    }
+
+    let response = client.query_collect("three").unwrap();
+    assert_eq!(response.text(), "three");
+    client.disconnect().unwrap();
+}
+
+#[test]
+fn test_dropping_a_connected_client_disconnects() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = blocking::Client::new(mock.options()).unwrap();
+    client.connect().unwrap();
+    client.query("hello").unwrap();
+    drop(client);
+}
+
+#[test]
+fn test_refuses_to_block_inside_a_runtime() {
+    let mock = MockCli::new(MOCK_CLI);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+
+    let err = blocking::query("hello", Some(mock.options())).unwrap_err();
+    assert!(matches!(err, ClaudeError::InsideAsyncRuntime));
+    let err = blocking::Client::new(mock.options()).err().unwrap();
+    assert!(matches!(err, ClaudeError::InsideAsyncRuntime));
+}
@@ -0,0 +1,199 @@
+//! Client-side budget enforcement against a mock CLI
+//!
+//! The mock answers the prompt "cheap" with a turn costing $0.15, and "nickel"
+//! with one costing $0.05. For "pricey" it sends an assistant message whose
+//! usage is worth $0.30 and waits to be interrupted before ending the turn.
+//! Like the real CLI, results report the running cost of the session.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{BudgetWarning, ClaudeAgentOptions, ClaudeClient, ClaudeError, Message};
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+cents=0
+result() {
+    cents=$((cents + $2))
+    cost=$(printf '%d.%02d' $((cents / 100)) $((cents % 100)))
+    echo "{\"type\":\"result\",\"subtype\":\"$1\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"mock\",\"total_cost_usd\":$cost}"
+}
+
+pending=""
+handle() {
+    case "$line" in
+        *'"subtype":"interrupt"'*)
+            ack
+            if [ -n "$pending" ]; then
+                result error_during_execution 30
+                pending=""
+            fi
+            ;;
+        *'"type":"user"'*)
+            text=$(printf '%s' "$line" | sed -n 's/.*"content":"\([^"]*\)".*/\1/p')
+            case "$text" in
+                cheap)
+                    result success 15
+                    ;;
+                nickel)
+                    result success 5
+                    ;;
+                pricey)
+                    usage='{"input_tokens":100000,"output_tokens":0}'
+                    echo "{\"type\":\"assistant\",\"session_id\":\"mock\",\"message\":{\"id\":\"msg_1\",\"model\":\"claude-sonnet-4-5\",\"content\":[{\"type\":\"text\",\"text\":\"partial\"}],\"usage\":$usage}}"
+                    pending=pricey
+                    ;;
+            esac
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    /// Client enforcing a $0.25 budget, recording its warnings
+    async fn client(
+        &self,
+        soft_limit_ratio: f64,
+    ) -> (ClaudeClient, Arc<Mutex<Vec<BudgetWarning>>>) {
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&warnings);
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .max_budget_usd(0.25)
+            .enforce_budget_client_side(true)
+            .budget_soft_limit_ratio(soft_limit_ratio)
+            .on_budget_warning(Arc::new(move |warning| {
+                recorded.lock().unwrap().push(warning);
+            }))
+            .deadline_grace_period(Duration::from_secs(5))
+            .build();
+
+        let client = ClaudeClient::new(options);
+        client.connect().await.unwrap();
+        (client, warnings)
+    }
+}
+
+/// Messages of the next turn, up to and including the error ending it
+async fn next_turn(client: &ClaudeClient) -> (Vec<Message>, Option<ClaudeError>) {
+    let mut messages = Vec::new();
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        match message {
+            Ok(message) => messages.push(message),
+            Err(e) => return (messages, Some(e)),
+        }
+    }
+    (messages, None)
+}
+
+#[tokio::test]
+async fn test_interrupts_a_turn_over_budget() {
+    let mock = MockCli::new(MOCK_CLI);
+    let (mut client, warnings) = mock.client(0.8).await;
+
+    client.query("pricey").await.unwrap();
+    let (messages, error) = next_turn(&client).await;
+
+    // The partial turn reaches the caller before the error
+    assert!(matches!(messages[0], Message::Assistant(_)));
+    let Message::Result(result) = &messages[1] else {
+        panic!("expected the interrupted turn's result");
+    };
+    assert_eq!(result.subtype, "error_during_execution");
+    let Some(ClaudeError::BudgetExceeded { spent, budget }) = error else {
+        panic!("expected BudgetExceeded, got {error:?}");
+    };
+    assert!((spent - 0.3).abs() < 1e-9);
+    assert_eq!(budget, 0.25);
+    assert_eq!(warnings.lock().unwrap().len(), 1);
+
+    // No more turns until the usage is reset
+    let err = client.query("cheap").await.unwrap_err();
+    assert!(matches!(err, ClaudeError::BudgetExceeded { .. }));
+
+    client.reset_usage();
+    client.query("cheap").await.unwrap();
+    let (messages, error) = next_turn(&client).await;
+    assert!(matches!(messages[..], [Message::Result(_)]));
+    assert!(error.is_none());
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_warns_before_the_result_reaching_the_budget() {
+    let mock = MockCli::new(MOCK_CLI);
+    let (mut client, warnings) = mock.client(0.5).await;
+
+    client.query("cheap").await.unwrap();
+    let (_, error) = next_turn(&client).await;
+    assert!(error.is_none());
+    let warning = warnings.lock().unwrap()[0];
+    assert_eq!(warning.spent_usd, 0.15);
+    assert_eq!(warning.budget_usd, 0.25);
+
+    client.query("cheap").await.unwrap();
+    let (messages, error) = next_turn(&client).await;
+    assert!(matches!(messages[..], [Message::Result(_)]));
+    assert!(matches!(error, Some(ClaudeError::BudgetExceeded { .. })));
+    assert_eq!(warnings.lock().unwrap().len(), 1);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_running_session_cost_is_counted_once() {
+    let mock = MockCli::new(MOCK_CLI);
+    let (mut client, warnings) = mock.client(0.9).await;
+
+    // $0.05 per turn: the session reports $0.05, $0.10, $0.15 and $0.20
+    for turn in 1..=4 {
+        client.query("nickel").await.unwrap();
+        let (messages, error) = next_turn(&client).await;
+        assert!(matches!(messages[..], [Message::Result(_)]));
+        assert!(error.is_none(), "turn {turn} failed: {error:?}");
+        let spent = client.usage().total.total_cost_usd;
+        assert!((spent - 0.05 * turn as f64).abs() < 1e-9);
+    }
+    assert!(warnings.lock().unwrap().is_empty());
+
+    // $0.15 more reaches the budget
+    client.query("cheap").await.unwrap();
+    let (_, error) = next_turn(&client).await;
+    let Some(ClaudeError::BudgetExceeded { spent, .. }) = error else {
+        panic!("expected BudgetExceeded, got {error:?}");
+    };
+    assert!((spent - 0.35).abs() < 1e-9);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_budget_exceeded_counts_the_running_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let (mut client, _) = mock.client(0.8).await;
+    assert!(!client.budget_exceeded());
+
+    client.query("pricey").await.unwrap();
+    let mut stream = client.receive_response();
+    let mut checked = false;
+    while let Some(message) = stream.next().await {
+        if let Ok(Message::Assistant(_)) = message {
+            // The running turn's estimated cost already uses up the budget
+            assert!(client.budget_exceeded());
+            checked = true;
+        }
+    }
+    drop(stream);
+    assert!(checked);
+    assert!(client.budget_exceeded());
+
+    client.disconnect().await.unwrap();
+}
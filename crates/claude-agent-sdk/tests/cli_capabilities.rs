@@ -0,0 +1,62 @@
+//! Detected CLI version and the flags left out for older CLIs
+//!
+//! The mock reports the version it is written with to `--version`, records its arguments in
+//! `args` next to itself and answers `initialize`.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{CliCapabilities, ClaudeAgentOptions, ClaudeClient};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+printf '%s\n' "$@" > "$(dirname "$0")/args"
+serve
+"#;
+
+/// Connect to a mock CLI of `version` and return the client and its arguments
+async fn connect(version: &str) -> (ClaudeClient, Vec<String>) {
+    let mock = MockCli::with_version(version, MOCK_CLI);
+
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .max_budget_usd(1.0)
+        .include_partial_messages(true)
+        .fork_session(true)
+        .build();
+    let client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+
+    let args = std::fs::read_to_string(mock.dir().join("args")).unwrap();
+    (client, args.lines().map(String::from).collect())
+}
+
+#[tokio::test]
+async fn test_old_cli_gets_only_supported_flags() {
+    let (client, args) = connect("1.0.88").await;
+    assert_eq!(client.cli_version(), Some(semver::Version::new(1, 0, 88)));
+    assert_eq!(
+        client.cli_capabilities(),
+        CliCapabilities {
+            supports_budget: false,
+            supports_partial_messages: true,
+            supports_fork_session: false,
+        }
+    );
+    assert!(args.contains(&"--include-partial-messages".to_string()));
+    assert!(!args.contains(&"--max-budget-usd".to_string()));
+    assert!(!args.contains(&"--fork-session".to_string()));
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_current_cli_gets_every_flag() {
+    let (client, args) = connect("2.1.0").await;
+    assert_eq!(client.cli_version(), Some(semver::Version::new(2, 1, 0)));
+    assert_eq!(client.cli_capabilities(), CliCapabilities::ALL);
+    for flag in ["--max-budget-usd", "--include-partial-messages", "--fork-session"] {
+        assert!(args.contains(&flag.to_string()), "{flag} missing");
+    }
+    client.disconnect().await.unwrap();
+}
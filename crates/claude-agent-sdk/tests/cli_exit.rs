@@ -0,0 +1,118 @@
+//! Errors for a CLI that exits with a failure
+//!
+//! With `MOCK_MODE=crash` the mock complains on stderr and exits with status 2
+//! before writing any output, like a CLI given a bad flag. With `MOCK_MODE=late`
+//! it answers the prompt first and fails afterwards.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, ConnectPhase, ExitStage, ProcessError, query,
+};
+use std::collections::HashMap;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+if [ "$MOCK_MODE" = "crash" ]; then
+    echo "error: unknown option '--bogus'" >&2
+    exit 2
+fi
+
+cat > /dev/null
+echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"s1"}'
+echo "error: failed to save session" >&2
+exit 2
+"#;
+
+fn options(mock: &MockCli, mode: &str) -> ClaudeAgentOptions {
+    ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .env(HashMap::from([("MOCK_MODE".to_string(), mode.to_string())]))
+        .extra_args(HashMap::from([(
+            "api-token".to_string(),
+            Some("secret-token-42".to_string()),
+        )]))
+        .build()
+}
+
+fn assert_startup_crash(error: &ProcessError) {
+    assert_eq!(error.exit_code, Some(2));
+    assert_eq!(error.stage, Some(ExitStage::BeforeHandshake));
+    assert!(error.exited_before_handshake());
+    assert_eq!(
+        error.stderr.as_deref(),
+        Some("error: unknown option '--bogus'")
+    );
+    assert!(
+        error.message.contains("unknown option '--bogus'"),
+        "{}",
+        error.message
+    );
+
+    let command = error.command.as_deref().unwrap();
+    assert!(
+        command.contains("--output-format stream-json"),
+        "{}",
+        command
+    );
+    assert!(command.contains("--api-token <elided>"), "{}", command);
+    assert!(
+        !error.message.contains("secret-token-42"),
+        "{}",
+        error.message
+    );
+}
+
+#[tokio::test]
+async fn test_client_connect_reports_startup_crash() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = ClaudeClient::new(options(&mock, "crash"));
+
+    match client.connect().await.unwrap_err() {
+        ClaudeError::ConnectFailed {
+            phase: ConnectPhase::Initialize,
+            source,
+        } => match *source {
+            ClaudeError::Process(error) => assert_startup_crash(&error),
+            other => panic!("expected a process error, got {other:?}"),
+        },
+        other => panic!("expected ConnectFailed, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_query_reports_startup_crash() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    match query("hello", Some(options(&mock, "crash")))
+        .await
+        .unwrap_err()
+    {
+        ClaudeError::Process(error) => assert_startup_crash(&error),
+        other => panic!("expected a process error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_query_reports_exit_after_conversation() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    match query("hello", Some(options(&mock, "late")))
+        .await
+        .unwrap_err()
+    {
+        ClaudeError::Process(error) => {
+            assert_eq!(error.exit_code, Some(2));
+            assert_eq!(error.stage, Some(ExitStage::MidConversation));
+            assert!(!error.exited_before_handshake());
+            assert!(
+                error.message.contains("failed to save session"),
+                "{}",
+                error.message
+            );
+        },
+        other => panic!("expected a process error, got {other:?}"),
+    }
+}
@@ -0,0 +1,100 @@
+//! Mock Claude CLI shared by the integration tests
+//!
+//! A mock is a `/bin/sh` script installed as `claude` in a temporary directory,
+//! to be passed to [`ClaudeAgentOptions::cli_path`]. It answers `--version`
+//! and then runs the test's script body, which can use these functions:
+//!
+//! - `serve` reads stdin line by line until it ends. It answers the
+//!   initialize request by calling `on_initialize`, which calls `ack` unless
+//!   the body redefines it, and sets `$initialized`. Every other line is
+//!   passed to `handle`, which does nothing unless the body defines it.
+//! - `ack` answers the control request being handled with success.
+//!
+//! While a line is handled, it is in `$line` and its request ID, if any, in
+//! `$id`.
+//!
+//! [`ClaudeAgentOptions::cli_path`]: claude_agent_sdk::ClaudeAgentOptions
+
+#![allow(dead_code)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Start of every mock, with `VERSION_CHECK` run for `--version`
+const PRELUDE: &str = r#"#!/bin/sh
+if [ "$1" = "--version" ]; then
+    VERSION_CHECK
+fi
+
+ack() {
+    echo "{\"type\":\"control_response\",\"response\":{\"subtype\":\"success\",\"request_id\":\"$id\",\"response\":{}}}"
+}
+
+on_initialize() {
+    ack
+}
+
+handle() {
+    :
+}
+
+serve() {
+    while IFS= read -r line || [ -n "$line" ]; do
+        id=$(printf '%s' "$line" | sed -n 's/.*"request_id":"\([^"]*\)".*/\1/p')
+        case "$line" in
+            *'"subtype":"initialize"'*)
+                initialized=1
+                on_initialize
+                ;;
+            *)
+                handle
+                ;;
+        esac
+    done
+}
+
+"#;
+
+/// Mock CLI script in a temporary directory, removed on drop
+pub struct MockCli {
+    dir: tempfile::TempDir,
+    script: PathBuf,
+}
+
+impl MockCli {
+    /// Mock of CLI version 2.0.0 running `body`
+    pub fn new(body: &str) -> Self {
+        Self::with_version("2.0.0", body)
+    }
+
+    /// Mock of CLI version `version` running `body`
+    pub fn with_version(version: &str, body: &str) -> Self {
+        let version_check = format!("echo \"{version} (Claude Code)\"\n    exit 0");
+        Self::with_version_check(&version_check, body)
+    }
+
+    /// Mock running `version_check` for `--version` and `body` otherwise
+    pub fn with_version_check(version_check: &str, body: &str) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("claude");
+        let prelude = PRELUDE.replace("VERSION_CHECK", version_check);
+        write_script(&script, &format!("{prelude}{body}"));
+        Self { dir, script }
+    }
+
+    /// Path of the script
+    pub fn path(&self) -> PathBuf {
+        self.script.clone()
+    }
+
+    /// Directory holding the script, for files shared with the test
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Write `contents` to `path` as an executable file
+pub fn write_script(path: &Path, contents: &str) {
+    std::fs::write(path, contents).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
@@ -0,0 +1,180 @@
+//! Conversation compaction against a mock CLI
+//!
+//! The mock answers each prompt with a context 1000 tokens larger than the
+//! last. For `/compact` it first asks for the `PreCompact` hook; unless the
+//! hook answers `"continue":false`, it reports a compaction boundary and starts
+//! the context over. The compaction's messages are in the session of the
+//! `/compact` message.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, CompactionOutcome, HookEvent, HookInput, HookJsonOutput,
+    HookMatcher, SyncHookJsonOutput,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+result() {
+    echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"${2:-mock}\",\"result\":\"$1\"}"
+}
+
+context=0
+handle() {
+    case "$line" in
+        *'"content":"/compact'*)
+            instructions=$(printf '%s' "$line" | sed -n 's/.*"content":"\/compact *\([^"]*\)".*/\1/p')
+            session=$(printf '%s' "$line" | sed -n 's/.*"session_id":"\([^"]*\)".*/\1/p')
+            echo "{\"type\":\"control_request\",\"request_id\":\"cli-1\",\"request\":{\"subtype\":\"hook_callback\",\"callback_id\":\"hook_0\",\"input\":{\"hook_event_name\":\"PreCompact\",\"session_id\":\"mock\",\"transcript_path\":\"/tmp/t\",\"cwd\":\"/tmp\",\"trigger\":\"manual\",\"custom_instructions\":\"$instructions\"}}}"
+            ;;
+        *'"continue":false'*)
+            result blocked "$session"
+            ;;
+        *'"type":"control_response"'*)
+            echo "{\"type\":\"system\",\"subtype\":\"compact_boundary\",\"session_id\":\"$session\",\"compact_metadata\":{\"trigger\":\"manual\",\"pre_tokens\":$context}}"
+            context=0
+            result compacted "$session"
+            ;;
+        *'"type":"user"'*)
+            context=$((context + 1000))
+            echo "{\"type\":\"assistant\",\"session_id\":\"mock\",\"message\":{\"id\":\"msg_$context\",\"content\":[{\"type\":\"text\",\"text\":\"ok\"}],\"usage\":{\"input_tokens\":$context,\"output_tokens\":0}}}"
+            result ok
+            ;;
+    esac
+}
+
+serve
+"#;
+
+/// Trigger and custom instructions of each `PreCompact` hook call
+type SeenCompactions = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+/// `PreCompact` hook recording its inputs, blocking compaction if `block`
+fn pre_compact_hook(block: bool, seen: SeenCompactions) -> HashMap<HookEvent, Vec<HookMatcher>> {
+    let hook = HookMatcher::builder()
+        .hooks(vec![Arc::new(move |input, _tool_use_id, _context| {
+            if let HookInput::PreCompact(input) = input {
+                seen.lock()
+                    .unwrap()
+                    .push((input.trigger, input.custom_instructions));
+            }
+            Box::pin(async move {
+                let output = if block {
+                    SyncHookJsonOutput::builder().continue_(false).build()
+                } else {
+                    SyncHookJsonOutput::default()
+                };
+                HookJsonOutput::Sync(output)
+            }) as _
+        })])
+        .build();
+    HashMap::from([(HookEvent::PreCompact, vec![hook])])
+}
+
+#[tokio::test]
+async fn test_compact_runs_pre_compact_hook() {
+    let mock = MockCli::new(MOCK_CLI);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .hooks(pre_compact_hook(false, Arc::clone(&seen)))
+        .build();
+    let mut client = ClaudeClient::new(options);
+
+    client.query_collect("first").await.unwrap();
+    client.query_collect("second").await.unwrap();
+    assert_eq!(client.usage().context_tokens, 2000);
+
+    let outcome = client
+        .compact(Some("Keep the file list".to_string()))
+        .await
+        .unwrap();
+    let CompactionOutcome::Compacted(boundary) = outcome else {
+        panic!("expected a compaction, got {outcome:?}");
+    };
+    assert_eq!(boundary.trigger, "manual");
+    assert_eq!(boundary.pre_tokens, Some(2000));
+    assert_eq!(client.usage().context_tokens, 0);
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![("manual".to_string(), Some("Keep the file list".to_string()))]
+    );
+
+    // The conversation goes on from the summary
+    client.query_collect("third").await.unwrap();
+    assert_eq!(client.usage().context_tokens, 1000);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pre_compact_hook_can_block_compaction() {
+    let mock = MockCli::new(MOCK_CLI);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .hooks(pre_compact_hook(true, Arc::clone(&seen)))
+        .build();
+    let mut client = ClaudeClient::new(options);
+
+    client.query_collect("first").await.unwrap();
+    let outcome = client.compact(None).await.unwrap();
+    let CompactionOutcome::Skipped(result) = outcome else {
+        panic!("expected compaction to be blocked, got {outcome:?}");
+    };
+    assert_eq!(result.result.as_deref(), Some("blocked"));
+    assert_eq!(client.usage().context_tokens, 1000);
+    assert_eq!(seen.lock().unwrap().len(), 1);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_auto_compaction_keeps_context_bounded() {
+    let mock = MockCli::new(MOCK_CLI);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .hooks(pre_compact_hook(false, Arc::clone(&seen)))
+        .auto_compact_threshold_tokens(2500)
+        .build();
+    let mut client = ClaudeClient::new(options);
+
+    for i in 0..9 {
+        let response = client.query_collect(format!("step {i}")).await.unwrap();
+        assert_eq!(response.result().unwrap().result.as_deref(), Some("ok"));
+        assert!(client.usage().context_tokens <= 3000);
+    }
+
+    // Compacted after every third answer
+    assert_eq!(seen.lock().unwrap().len(), 2);
+    assert_eq!(client.usage().context_tokens, 3000);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_compact_with_a_default_session_handle() {
+    let mock = MockCli::new(MOCK_CLI);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .hooks(pre_compact_hook(false, Arc::clone(&seen)))
+        .build();
+    let client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+
+    // The compaction's messages are routed to the handle's session
+    let session = client.session("default");
+    let outcome = tokio::time::timeout(Duration::from_secs(5), client.compact(None))
+        .await
+        .expect("compact never read its result")
+        .unwrap();
+    assert!(matches!(outcome, CompactionOutcome::Compacted(_)));
+    assert_eq!(seen.lock().unwrap().len(), 1);
+
+    drop(session);
+    client.disconnect().await.unwrap();
+}
@@ -0,0 +1,149 @@
+//! Concurrent connect() and disconnect() against a slow mock CLI
+//!
+//! Every process the mock starts appends a line to `$MOCK_SPAWNS`. It waits
+//! before answering the initialize request, so racing callers overlap. While
+//! `$MOCK_FAIL` exists, the process deletes it and exits without answering.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, ConnectTimeouts, ConnectionState};
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+echo spawned >> "$MOCK_SPAWNS"
+if [ -e "$MOCK_FAIL" ]; then
+    rm "$MOCK_FAIL"
+    exit 1
+fi
+
+on_initialize() {
+    sleep 0.3
+    ack
+}
+
+serve
+"#;
+
+const CALLERS: usize = 8;
+
+impl MockCli {
+    fn client(&self) -> Arc<ClaudeClient> {
+        let path = |name: &str| self.dir().join(name).display().to_string();
+        let env = std::collections::HashMap::from([
+            ("MOCK_SPAWNS".to_string(), path("spawns")),
+            ("MOCK_FAIL".to_string(), path("fail")),
+        ]);
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(env)
+            .connect_timeouts(ConnectTimeouts {
+                initialize: Duration::from_secs(1),
+                ..Default::default()
+            })
+            .build();
+        Arc::new(ClaudeClient::new(options))
+    }
+
+    fn fail_next_spawn(&self) {
+        std::fs::write(self.dir().join("fail"), "").unwrap();
+    }
+
+    fn spawns(&self) -> usize {
+        std::fs::read_to_string(self.dir().join("spawns"))
+            .unwrap_or_default()
+            .lines()
+            .count()
+    }
+}
+
+async fn race<F, Fut>(client: &Arc<ClaudeClient>, call: F) -> Vec<claude_agent_sdk::Result<()>>
+where
+    F: Fn(Arc<ClaudeClient>) -> Fut,
+    Fut: std::future::Future<Output = claude_agent_sdk::Result<()>> + Send + 'static,
+{
+    let tasks: Vec<_> = (0..CALLERS)
+        .map(|_| tokio::spawn(call(Arc::clone(client))))
+        .collect();
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await.unwrap());
+    }
+    results
+}
+
+#[tokio::test]
+async fn test_racing_connects_spawn_one_process() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = mock.client();
+    assert_eq!(client.state(), ConnectionState::Disconnected);
+
+    let results = race(&client, |c| async move { c.connect().await }).await;
+    assert!(results.iter().all(Result::is_ok), "{results:?}");
+    assert_eq!(mock.spawns(), 1);
+    assert!(client.is_connected());
+
+    let results = race(&client, |c| async move { c.disconnect().await }).await;
+    assert!(results.iter().all(Result::is_ok), "{results:?}");
+    assert_eq!(client.state(), ConnectionState::Disconnected);
+}
+
+#[tokio::test]
+async fn test_state_reports_in_flight_connect() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = mock.client();
+
+    let connecting = tokio::spawn({
+        let client = Arc::clone(&client);
+        async move { client.connect().await }
+    });
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(client.state(), ConnectionState::Connecting);
+    assert!(!client.is_connected());
+
+    connecting.await.unwrap().unwrap();
+    assert_eq!(client.state(), ConnectionState::Connected);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_connect_retries_after_failed_attempt() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = mock.client();
+
+    mock.fail_next_spawn();
+    let results = race(&client, |c| async move { c.connect().await }).await;
+    // One attempt is made; callers queued behind it share its failure
+    assert!(results.iter().all(Result::is_err), "{results:?}");
+    assert_eq!(mock.spawns(), 1);
+    assert_eq!(client.state(), ConnectionState::Disconnected);
+
+    client.connect().await.unwrap();
+    assert_eq!(mock.spawns(), 2);
+    assert!(client.is_connected());
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_connect_during_disconnect_reconnects() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = mock.client();
+    client.connect().await.unwrap();
+
+    let disconnecting = tokio::spawn({
+        let client = Arc::clone(&client);
+        async move { client.disconnect().await }
+    });
+    while client.state() != ConnectionState::Disconnecting {
+        tokio::task::yield_now().await;
+    }
+
+    client.connect().await.unwrap();
+    disconnecting.await.unwrap().unwrap();
+    assert!(client.is_connected());
+    assert_eq!(mock.spawns(), 2);
+    client.disconnect().await.unwrap();
+}
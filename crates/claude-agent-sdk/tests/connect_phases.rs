@@ -0,0 +1,150 @@
+//! Connect phase timeout and progress tests against a mock CLI
+//!
+//! The mock hangs on `--version` and, unless `MOCK_SILENT` is set, answers the
+//! initialize request like the real CLI.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, ConnectPhase, ConnectProgress, ConnectTimeouts,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+on_initialize() {
+    if [ -z "$MOCK_SILENT" ]; then
+        ack
+    fi
+}
+
+serve
+"#;
+
+impl MockCli {
+    /// Mock hanging on `--version`
+    fn hanging() -> Self {
+        Self::with_version_check("exec sleep 30", MOCK_CLI)
+    }
+
+    fn options(
+        &self,
+        silent: bool,
+        timeouts: ConnectTimeouts,
+    ) -> (ClaudeAgentOptions, Arc<Mutex<Vec<ConnectProgress>>>) {
+        let mut env = std::collections::HashMap::new();
+        if silent {
+            env.insert("MOCK_SILENT".to_string(), "1".to_string());
+        }
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(env)
+            .connect_timeouts(timeouts)
+            .connect_progress(Arc::new(move |event| sink.lock().unwrap().push(event)))
+            .build();
+
+        (options, events)
+    }
+}
+
+fn short_timeouts() -> ConnectTimeouts {
+    ConnectTimeouts {
+        version_check: Duration::from_millis(200),
+        initialize: Duration::from_secs(5),
+        ..ConnectTimeouts::default()
+    }
+}
+
+#[tokio::test]
+async fn test_hanging_version_check_is_skipped() {
+    let mock = MockCli::hanging();
+    let (options, events) = mock.options(false, short_timeouts());
+
+    let client = ClaudeClient::new(options);
+    let start = Instant::now();
+    client.connect().await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    let events = events.lock().unwrap().clone();
+    assert!(matches!(
+        &events[0],
+        ConnectProgress::Skipped {
+            phase: ConnectPhase::Discovery,
+            ..
+        }
+    ));
+    assert!(matches!(
+        events[1],
+        ConnectProgress::Started(ConnectPhase::VersionCheck)
+    ));
+    assert!(matches!(
+        &events[2],
+        ConnectProgress::Skipped { phase: ConnectPhase::VersionCheck, reason } if reason.contains("timed out")
+    ));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        ConnectProgress::Completed {
+            phase: ConnectPhase::Spawn,
+            ..
+        }
+    )));
+    assert!(matches!(
+        events.last(),
+        Some(ConnectProgress::Completed {
+            phase: ConnectPhase::Initialize,
+            ..
+        })
+    ));
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_initialize_timeout_names_phase() {
+    let mock = MockCli::hanging();
+    let timeouts = ConnectTimeouts {
+        initialize: Duration::from_millis(300),
+        ..short_timeouts()
+    };
+    let (options, events) = mock.options(true, timeouts);
+
+    let client = ClaudeClient::new(options);
+    let err = client.connect().await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        ClaudeError::ConnectTimeout { phase: ConnectPhase::Initialize, timeout } if timeout == Duration::from_millis(300)
+    ));
+    assert!(err.to_string().contains("initialize handshake"));
+    assert!(matches!(
+        events.lock().unwrap().last(),
+        Some(ConnectProgress::Started(ConnectPhase::Initialize))
+    ));
+}
+
+#[tokio::test]
+async fn test_version_check_failure_names_phase() {
+    let dir = tempfile::tempdir().unwrap();
+    let script = dir.path().join("claude");
+    // Not executable, so running `--version` fails outright
+    std::fs::write(&script, MOCK_CLI).unwrap();
+
+    let options = ClaudeAgentOptions::builder().cli_path(script).build();
+    let client = ClaudeClient::new(options);
+    let err = client.connect().await.unwrap_err();
+
+    assert!(matches!(
+        err,
+        ClaudeError::ConnectFailed {
+            phase: ConnectPhase::VersionCheck,
+            ..
+        }
+    ));
+    assert!(err.to_string().contains("version check"));
+}
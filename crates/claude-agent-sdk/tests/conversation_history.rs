@@ -0,0 +1,277 @@
+//! Conversation history replayed to a mock CLI
+//!
+//! The mock appends every message it reads, except the initialize request, to
+//! `$MOCK_LOG`. It answers once its input ends. With `$MOCK_ANSWER_ALL` set, it
+//! answers every user message instead, like the real CLI, with the number of
+//! user messages read so far and the message's session ID.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, ConversationTurn, Message, UserContentBlock,
+    query_stream_with_history, query_with_history,
+};
+use futures::StreamExt;
+use serde_json::{Value, json};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+answer() {
+    text=${1:-woof}
+    session=${2:-mock}
+    echo "{\"type\":\"assistant\",\"session_id\":\"$session\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"$text\"}]}}"
+    echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"$session\",\"result\":\"$text\"}"
+}
+
+users=0
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            printf '%s\n' "$line" >> "$MOCK_LOG"
+            users=$((users + 1))
+            session=$(printf '%s' "$line" | sed -n 's/.*"session_id":"\([^"]*\)".*/\1/p')
+            if [ -n "$MOCK_ANSWER_ALL" ]; then
+                answer "answer $users" "$session"
+            fi
+            ;;
+        *)
+            printf '%s\n' "$line" >> "$MOCK_LOG"
+            ;;
+    esac
+}
+
+serve
+answer
+"#;
+
+impl MockCli {
+    fn options(&self) -> ClaudeAgentOptions {
+        self.options_with_env(&[])
+    }
+
+    /// Options for a mock answering every user message
+    fn answering_options(&self) -> ClaudeAgentOptions {
+        self.options_with_env(&[("MOCK_ANSWER_ALL", "1")])
+    }
+
+    fn options_with_env(&self, extra: &[(&str, &str)]) -> ClaudeAgentOptions {
+        let log = self.dir().join("log");
+        let mut env =
+            std::collections::HashMap::from([("MOCK_LOG".to_string(), log.display().to_string())]);
+        env.extend(extra.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+        ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(env)
+            .build()
+    }
+
+    /// Messages the mock has read so far
+    fn received(&self) -> Vec<Value> {
+        std::fs::read_to_string(self.dir().join("log"))
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
+
+fn history() -> Vec<ConversationTurn> {
+    vec![
+        ConversationTurn::user("What does a cat say?"),
+        ConversationTurn::assistant("meow"),
+        ConversationTurn::user("What does a dog say?"),
+    ]
+}
+
+/// Role and content of each received message
+fn turns(received: &[Value]) -> Vec<(String, Value)> {
+    received
+        .iter()
+        .map(|message| {
+            (
+                message["type"].as_str().unwrap().to_string(),
+                message["message"]["content"].clone(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_query_with_history_writes_each_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let messages = query_with_history(history(), Some(mock.options()))
+        .await
+        .unwrap();
+
+    let Some(Message::Result(result)) = messages.last() else {
+        panic!("expected a result message last, got {:?}", messages.last());
+    };
+    assert_eq!(result.result.as_deref(), Some("woof"));
+    assert_eq!(
+        turns(&mock.received()),
+        vec![
+            ("user".to_string(), json!("What does a cat say?")),
+            (
+                "assistant".to_string(),
+                json!([{"type": "text", "text": "meow"}])
+            ),
+            ("user".to_string(), json!("What does a dog say?")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_query_stream_with_history_yields_answer() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let mut stream = query_stream_with_history(history(), Some(mock.options()))
+        .await
+        .unwrap();
+    let mut results = Vec::new();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            results.push(result.result.unwrap_or_default());
+        }
+    }
+
+    assert_eq!(results, vec!["woof"]);
+    assert_eq!(mock.received().len(), 3);
+}
+
+#[tokio::test]
+async fn test_invalid_history_is_rejected_before_spawning() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let image = UserContentBlock::image_url("https://example.com/cat.png").unwrap();
+    let history = vec![
+        ConversationTurn::user("Show me a cat"),
+        ConversationTurn::assistant(vec![image]),
+        ConversationTurn::user("Another one"),
+    ];
+    let err = query_with_history(history, Some(mock.options()))
+        .await
+        .unwrap_err();
+
+    match err {
+        ClaudeError::InvalidConfig(message) => {
+            assert!(message.contains("assistant turns can only contain text"))
+        },
+        other => panic!("expected InvalidConfig, got {other:?}"),
+    }
+    assert!(mock.received().is_empty());
+}
+
+/// Text of the results of the next response
+async fn response_results(client: &ClaudeClient) -> Vec<String> {
+    let mut results = Vec::new();
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            results.push(result.result.unwrap_or_default());
+        }
+    }
+    results
+}
+
+fn banana_history() -> Vec<ConversationTurn> {
+    vec![
+        ConversationTurn::user("Remember the word 'banana'"),
+        ConversationTurn::assistant("Got it"),
+        ConversationTurn::user("What was the word?"),
+    ]
+}
+
+#[tokio::test]
+async fn test_client_send_history() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.answering_options());
+    client.connect().await.unwrap();
+
+    client.send_history(banana_history()).await.unwrap();
+
+    // One response holds the answers to both user turns
+    assert_eq!(
+        response_results(&client).await,
+        vec!["answer 1", "answer 2"]
+    );
+
+    let received = mock.received();
+    let roles: Vec<_> = turns(&received).into_iter().map(|(role, _)| role).collect();
+    assert_eq!(roles, ["user", "assistant", "user"]);
+    assert!(
+        received
+            .iter()
+            .all(|message| message["session_id"] == "default")
+    );
+
+    // The client is ready for the next turn
+    client.query("And now?").await.unwrap();
+    assert_eq!(response_results(&client).await, vec!["answer 3"]);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_session_send_history() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = ClaudeClient::new(mock.answering_options());
+    client.connect().await.unwrap();
+
+    let session = client.session("replay");
+    session.send_history(banana_history()).await.unwrap();
+
+    let mut results = Vec::new();
+    let mut stream = session.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            results.push(result.result.unwrap_or_default());
+        }
+    }
+    drop(stream);
+    assert_eq!(results, vec!["answer 1", "answer 2"]);
+    assert!(
+        mock.received()
+            .iter()
+            .all(|message| message["session_id"] == "replay")
+    );
+
+    drop(session);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_send_history_turn_runs_until_the_last_answer() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.answering_options());
+    client.connect().await.unwrap();
+
+    client
+        .send_history_with_session(banana_history(), "replay")
+        .await
+        .unwrap();
+
+    // Stop reading after the answer to the first user turn
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            assert_eq!(result.result.as_deref(), Some("answer 1"));
+            break;
+        }
+    }
+    drop(stream);
+
+    // The turn still runs until the answer to the last one
+    assert!(matches!(
+        client.query("And now?").await,
+        Err(ClaudeError::TurnInProgress)
+    ));
+    assert_eq!(response_results(&client).await, vec!["answer 2"]);
+
+    client.query("And now?").await.unwrap();
+    assert_eq!(response_results(&client).await, vec!["answer 3"]);
+
+    client.disconnect().await.unwrap();
+}
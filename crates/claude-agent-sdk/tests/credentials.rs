@@ -0,0 +1,42 @@
+//! Explicit credentials reach the spawned CLI only
+//!
+//! The mock CLI writes the credential variables it was started with into its
+//! config directory, then answers the one-shot query.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, query};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+printf '%s\n%s\n' "$ANTHROPIC_API_KEY" "$ANTHROPIC_AUTH_TOKEN" > "$CLAUDE_CONFIG_DIR/credentials"
+cat > /dev/null
+echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"s1"}'
+"#;
+
+#[tokio::test]
+async fn test_credentials_are_passed_to_child_only() {
+    let mock = MockCli::new(MOCK_CLI);
+    let config_dir = tempfile::tempdir().unwrap();
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .api_key("sk-ant-test-key-1234")
+        .auth_token("oauth-test-token-5678")
+        .config_dir(config_dir.path())
+        .build();
+
+    let debug = format!("{:?}", options);
+    assert!(!debug.contains("sk-ant-test-key-1234"), "{}", debug);
+    assert!(!debug.contains("oauth-test-token-5678"), "{}", debug);
+
+    query("hello", Some(options)).await.unwrap();
+
+    let credentials = std::fs::read_to_string(config_dir.path().join("credentials")).unwrap();
+    assert_eq!(credentials, "sk-ant-test-key-1234\noauth-test-token-5678\n");
+    assert_ne!(
+        std::env::var("ANTHROPIC_API_KEY").ok().as_deref(),
+        Some("sk-ant-test-key-1234")
+    );
+}
@@ -0,0 +1,109 @@
+//! Fallback model reporting against a mock CLI
+//!
+//! The mock answers every user message with an assistant message from the model
+//! in `MOCK_MODEL`, as the real CLI does after switching to `--fallback-model`.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::observability::FALLBACK_ACTIVATIONS_METRIC;
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, Message, MetricsCollector};
+use futures::StreamExt;
+use std::sync::Arc;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            echo "{\"type\":\"assistant\",\"message\":{\"model\":\"$MOCK_MODEL\",\"content\":[{\"type\":\"text\",\"text\":\"4\"}],\"usage\":{\"input_tokens\":1000000,\"output_tokens\":1000000}}}"
+            echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"mock\"}"
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    fn env(model: &str) -> std::collections::HashMap<String, String> {
+        std::collections::HashMap::from([("MOCK_MODEL".to_string(), model.to_string())])
+    }
+}
+
+#[tokio::test]
+async fn test_client_reports_fallback_on_result() {
+    let mock = MockCli::new(MOCK_CLI);
+    let metrics = Arc::new(MetricsCollector::new());
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .env(MockCli::env("claude-sonnet-4-20250514"))
+        .model("claude-opus-4-20250514".to_string())
+        .fallback_model("claude-sonnet-4-20250514".to_string())
+        .metrics(Arc::clone(&metrics))
+        .build();
+
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+    client.query("What is 2 + 2?").await.unwrap();
+
+    let mut fallback = None;
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            fallback = result.fallback;
+        }
+    }
+    drop(stream);
+
+    let fallback = fallback.expect("result message carries the fallback report");
+    assert!(fallback.fallback_used);
+    assert_eq!(
+        fallback.requested_model.as_deref(),
+        Some("claude-opus-4-20250514")
+    );
+    assert_eq!(
+        fallback.actual_model.as_deref(),
+        Some("claude-sonnet-4-20250514")
+    );
+    assert_eq!(
+        metrics.get_counter(
+            FALLBACK_ACTIVATIONS_METRIC,
+            &[
+                ("requested_model", "claude-opus-4-20250514"),
+                ("actual_model", "claude-sonnet-4-20250514"),
+            ]
+        ),
+        1.0
+    );
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_matching_model_is_not_a_fallback() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .env(MockCli::env("claude-opus-4-20250514"))
+        .model("opus".to_string())
+        .fallback_model("sonnet".to_string())
+        .build();
+
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+    client.query("What is 2 + 2?").await.unwrap();
+
+    let mut fallback = None;
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            fallback = result.fallback;
+        }
+    }
+    drop(stream);
+
+    assert!(!fallback.unwrap().fallback_used);
+    client.disconnect().await.unwrap();
+}
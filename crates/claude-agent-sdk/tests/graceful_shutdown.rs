@@ -0,0 +1,138 @@
+//! ClaudeClient::shutdown and ClientGuard against mock CLIs that are
+//! increasingly hard to stop
+//!
+//! Each mock answers `initialize`, starts a `sleep` in the background as a
+//! stand-in for a tool process, and records both process IDs next to itself.
+//! What it does once its input ends depends on `$MODE`: exit, hang until
+//! signalled, or hang ignoring `SIGTERM`.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, ShutdownLevel};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+dir="$(dirname "$0")"
+if [ "$MODE" = "stubborn" ]; then
+    trap '' TERM
+fi
+sleep 30 &
+echo "$$ $!" > "$dir/pids"
+
+serve
+
+if [ "$MODE" = "polite" ]; then
+    kill $!
+    exit 0
+fi
+wait
+"#;
+
+impl MockCli {
+    async fn client(&self, mode: &str) -> ClaudeClient {
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(HashMap::from([("MODE".to_string(), mode.to_string())]))
+            .build();
+        let client = ClaudeClient::new(options);
+        client.connect().await.unwrap();
+        client
+    }
+
+    /// IDs of the CLI and of the `sleep` it started
+    fn pids(&self) -> Vec<String> {
+        let pids = std::fs::read_to_string(self.dir().join("pids")).unwrap();
+        pids.split_whitespace().map(String::from).collect()
+    }
+}
+
+/// Whether `pid` is a live process; zombies waiting to be reaped are dead
+fn is_running(pid: &str) -> bool {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "stat=", "-p", pid])
+        .output()
+        .unwrap();
+    let stat = String::from_utf8_lossy(&output.stdout);
+    !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+}
+
+async fn wait_until_stopped(pids: &[String]) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while pids.iter().any(|pid| is_running(pid)) {
+        assert!(Instant::now() < deadline, "still running: {pids:?}");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+async fn shutdown_level(mode: &str) -> ShutdownLevel {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = mock.client(mode).await;
+    let pids = mock.pids();
+
+    let timeout = Duration::from_secs(2);
+    let started = Instant::now();
+    let level = client.shutdown(timeout).await.unwrap();
+    assert!(started.elapsed() < timeout + Duration::from_millis(500));
+    assert!(!client.is_connected());
+
+    wait_until_stopped(&pids).await;
+    level
+}
+
+#[tokio::test]
+async fn test_cli_that_exits_shuts_down_gracefully() {
+    assert_eq!(shutdown_level("polite").await, ShutdownLevel::Graceful);
+}
+
+#[tokio::test]
+async fn test_hanging_cli_is_terminated() {
+    assert_eq!(shutdown_level("hang").await, ShutdownLevel::Terminated);
+}
+
+#[tokio::test]
+async fn test_cli_ignoring_sigterm_is_killed() {
+    assert_eq!(shutdown_level("stubborn").await, ShutdownLevel::Killed);
+}
+
+#[tokio::test]
+async fn test_shutdown_of_disconnected_client() {
+    let client = ClaudeClient::new(ClaudeAgentOptions::default());
+    let level = client.shutdown(Duration::from_millis(100)).await.unwrap();
+    assert_eq!(level, ShutdownLevel::Graceful);
+}
+
+#[tokio::test]
+async fn test_dropped_guard_stops_the_cli() {
+    let mock = MockCli::new(MOCK_CLI);
+    let guard = mock
+        .client("hang")
+        .await
+        .into_guard(tokio::runtime::Handle::current())
+        .with_timeout(Duration::from_millis(400));
+    assert!(guard.is_connected());
+    let pids = mock.pids();
+
+    drop(guard);
+    wait_until_stopped(&pids).await;
+}
+
+#[test]
+fn test_guard_stops_the_cli_on_panic() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mock = MockCli::new(MOCK_CLI);
+    let client = runtime.block_on(mock.client("hang"));
+    let pids = mock.pids();
+
+    let handle = runtime.handle().clone();
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+        let _guard = client.into_guard(handle);
+        panic!("tool crashed");
+    }));
+    assert!(panicked.is_err());
+
+    runtime.block_on(wait_until_stopped(&pids));
+}
@@ -0,0 +1,74 @@
+//! Hook timeouts enforced by `ClaudeClient` against a mock CLI
+//!
+//! The mock asks for a `PreToolUse` hook before answering a user message, and
+//! only finishes the turn once the hook was answered with `"continue":true`.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::observability::{HOOK_DURATION_METRIC, MetricsCollector};
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, HookEvent, HookJsonOutput, HookMatcher, SyncHookJsonOutput,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            echo '{"type":"control_request","request_id":"cli-1","request":{"subtype":"hook_callback","callback_id":"hook_0","tool_use_id":"t1","input":{"hook_event_name":"PreToolUse","session_id":"sess-1","transcript_path":"/tmp/t","cwd":"/tmp","tool_name":"Bash","tool_input":{}}}}'
+            ;;
+        *'"continue":true'*)
+            echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"sess-1"}'
+            ;;
+        *'"type":"control_response"'*)
+            echo '{"type":"result","subtype":"error_during_execution","duration_ms":1,"duration_api_ms":1,"is_error":true,"num_turns":1,"session_id":"sess-1"}'
+            ;;
+    esac
+}
+
+serve
+"#;
+
+#[tokio::test]
+async fn test_hook_exceeding_timeout_lets_the_query_continue() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let slow_hook = HookMatcher::builder()
+        .matcher("Bash")
+        .timeout(0.1)
+        .hooks(vec![Arc::new(|_input, _tool_use_id, _context| {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                HookJsonOutput::Sync(SyncHookJsonOutput::default())
+            }) as _
+        })])
+        .build();
+    let metrics = Arc::new(MetricsCollector::new());
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .hooks(HashMap::from([(HookEvent::PreToolUse, vec![slow_hook])]))
+        .metrics(Arc::clone(&metrics))
+        .build();
+    let mut client = ClaudeClient::new(options);
+
+    let started = Instant::now();
+    let response = client.query_collect("run it").await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(4));
+    client.disconnect().await.unwrap();
+
+    let result = response.result().expect("the turn should finish");
+    assert!(!result.is_error);
+
+    let durations = metrics
+        .get_histogram(
+            HOOK_DURATION_METRIC,
+            &[("event", "PreToolUse"), ("outcome", "timed_out")],
+        )
+        .unwrap();
+    assert_eq!(durations.count, 1);
+}
@@ -0,0 +1,131 @@
+//! Turns cut short by a CLI that exits before its result message
+//!
+//! The mock answers the initialize request, then replies to the first prompt
+//! with one assistant message and exits with status 3.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, Message, TransportExitInfo, query, query_stream,
+};
+use futures::StreamExt;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+handle() {
+    echo "{\"type\":\"assistant\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"partial\"}]}}"
+    exit 3
+}
+
+serve
+"#;
+
+const EXIT: TransportExitInfo = TransportExitInfo {
+    code: Some(3),
+    signal: None,
+};
+
+impl MockCli {
+    fn options(&self) -> ClaudeAgentOptions {
+        ClaudeAgentOptions::builder().cli_path(self.path()).build()
+    }
+}
+
+fn assert_incomplete(result: Option<claude_agent_sdk::Result<Message>>) {
+    match result {
+        Some(Err(ClaudeError::IncompleteTurn {
+            messages_received,
+            last_message_type,
+            process_exit,
+        })) => {
+            assert_eq!(messages_received, 1);
+            assert_eq!(last_message_type.as_deref(), Some("assistant"));
+            assert_eq!(process_exit, Some(EXIT));
+        },
+        other => panic!("expected IncompleteTurn, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_receive_response_ends_with_incomplete_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options());
+    client.connect().await.unwrap();
+    client.query("hello").await.unwrap();
+
+    let mut stream = client.receive_response();
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(Message::Assistant(_)))
+    ));
+    assert_incomplete(stream.next().await);
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_receive_messages_ends_with_incomplete_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options());
+    client.connect().await.unwrap();
+    client.query("hello").await.unwrap();
+
+    let mut stream = client.receive_messages();
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(Message::Assistant(_)))
+    ));
+    assert_incomplete(stream.next().await);
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_query_collect_fails_with_incomplete_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options());
+
+    let err = client.query_collect("hello").await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ClaudeError::IncompleteTurn {
+                messages_received: 1,
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+    assert!(err.to_string().contains("exited with code 3"), "{err}");
+}
+
+#[tokio::test]
+async fn test_one_shot_query_fails_with_incomplete_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let err = query("hello", Some(mock.options())).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ClaudeError::IncompleteTurn {
+                messages_received: 1,
+                process_exit: Some(EXIT),
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_query_stream_ends_with_incomplete_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let mut stream = query_stream("hello", Some(mock.options())).await.unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(Message::Assistant(_)))
+    ));
+    assert_incomplete(stream.next().await);
+    assert!(stream.next().await.is_none());
+}
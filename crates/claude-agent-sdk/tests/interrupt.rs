@@ -0,0 +1,223 @@
+//! Interrupts against a mock CLI
+//!
+//! The mock answers the prompt "quick" right away. For "silent" it sends
+//! nothing, and for "streaming" one assistant message, until interrupted; then
+//! it acknowledges the interrupt and ends the turn with an error result. For
+//! "stubborn" it acknowledges interrupts without ever ending the turn.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::mcp::CancellationToken;
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, ClaudeError, InterruptOutcome, Message};
+use futures::StreamExt;
+use std::time::Duration;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+assistant() {
+    echo "{\"type\":\"assistant\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"$1\"}]}}"
+}
+
+result() {
+    echo "{\"type\":\"result\",\"subtype\":\"$1\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":$2,\"num_turns\":1,\"session_id\":\"mock\",\"result\":\"$3\"}"
+}
+
+pending=""
+handle() {
+    case "$line" in
+        *'"subtype":"interrupt"'*)
+            ack
+            if [ "$pending" = "stoppable" ]; then
+                result error_during_execution true interrupted
+                pending=""
+            fi
+            ;;
+        *'"type":"user"'*)
+            text=$(printf '%s' "$line" | sed -n 's/.*"content":"\([^"]*\)".*/\1/p')
+            case "$text" in
+                quick)
+                    assistant quick
+                    result success false quick
+                    ;;
+                silent)
+                    pending=stoppable
+                    ;;
+                streaming)
+                    assistant partial
+                    pending=stoppable
+                    ;;
+                stubborn)
+                    assistant partial
+                    pending=stubborn
+                    ;;
+            esac
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    async fn client(&self) -> ClaudeClient {
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .deadline_grace_period(Duration::from_millis(500))
+            .build();
+
+        let client = ClaudeClient::new(options);
+        client.connect().await.unwrap();
+        client
+    }
+}
+
+const WAIT: Duration = Duration::from_secs(5);
+
+/// Text of the next turn's result
+async fn next_result(client: &ClaudeClient) -> String {
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            return result.result.unwrap_or_default();
+        }
+    }
+    panic!("stream ended without a result");
+}
+
+#[tokio::test]
+async fn test_interrupt_before_first_message() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    client.query("silent").await.unwrap();
+    let outcome = client.interrupt_and_wait(WAIT).await.unwrap();
+
+    let InterruptOutcome::Stopped(result) = outcome else {
+        panic!("expected the turn to stop, got {outcome:?}");
+    };
+    assert_eq!(result.subtype, "error_during_execution");
+
+    // The interrupted turn is over, so the next query is accepted
+    client.query("quick").await.unwrap();
+    assert_eq!(next_result(&client).await, "quick");
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_interrupt_mid_stream() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    client.query("streaming").await.unwrap();
+    {
+        let mut stream = client.receive_response();
+        let Some(Ok(Message::Assistant(_))) = stream.next().await else {
+            panic!("expected the assistant message first");
+        };
+    }
+    let outcome = client.interrupt_and_wait(WAIT).await.unwrap();
+    assert!(matches!(outcome, InterruptOutcome::Stopped(_)));
+
+    client.query("quick").await.unwrap();
+    assert_eq!(next_result(&client).await, "quick");
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_interrupt_after_result() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    client.query("quick").await.unwrap();
+    assert_eq!(next_result(&client).await, "quick");
+
+    let outcome = client.interrupt_and_wait(WAIT).await.unwrap();
+    assert!(matches!(outcome, InterruptOutcome::Idle));
+
+    client.query("quick").await.unwrap();
+    assert_eq!(next_result(&client).await, "quick");
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_acknowledged_without_result_forgets_the_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    client.query("stubborn").await.unwrap();
+    let outcome = client
+        .interrupt_and_wait(Duration::from_millis(300))
+        .await
+        .unwrap();
+    assert!(matches!(outcome, InterruptOutcome::Acknowledged));
+
+    // Not rejected as overlapping the stubborn turn
+    client.query("quick").await.unwrap();
+    assert_eq!(next_result(&client).await, "quick");
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_query_cancellable_interrupts_on_cancel() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        canceller.cancel();
+    });
+
+    let err = client
+        .query_cancellable("streaming", &token)
+        .await
+        .unwrap_err();
+    let ClaudeError::Cancelled { partial } = err else {
+        panic!("expected Cancelled, got {err:?}");
+    };
+    assert_eq!(partial.text(), "partial");
+    assert!(partial.is_complete());
+    assert!(client.is_connected());
+
+    let response = client
+        .query_cancellable("quick", &CancellationToken::new())
+        .await
+        .unwrap();
+    assert_eq!(response.text(), "quick");
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_query_cancellable_disconnects_if_the_turn_does_not_stop() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        canceller.cancel();
+    });
+
+    let err = client
+        .query_cancellable("stubborn", &token)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClaudeError::Cancelled { .. }));
+    assert!(!client.is_connected());
+
+    // Reconnects for the next query
+    let response = client
+        .query_cancellable("quick", &CancellationToken::new())
+        .await
+        .unwrap();
+    assert_eq!(response.text(), "quick");
+}
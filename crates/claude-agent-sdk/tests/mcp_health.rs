@@ -0,0 +1,259 @@
+//! MCP server health checks against mock servers
+//!
+//! The stdio mock logs a line to stdout, then answers `initialize` with the
+//! protocol version in `$VERSION` and lists two tools. The HTTP mock answers
+//! `initialize` as a server-sent event and everything else as plain JSON.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::mcp::{McpHealthReport, McpHealthStatus};
+use claude_agent_sdk::types::mcp::{McpHttpServerConfig, McpStdioServerConfig};
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, McpServerConfig, McpServers,
+    McpToolResultContent, ToolResult, create_sdk_mcp_server, tool,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+mod common;
+
+const MOCK_SERVER: &str = r#"#!/bin/sh
+echo "mock server starting"
+while IFS= read -r line; do
+    id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+    case "$line" in
+        *'"method":"initialize"'*)
+            echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"protocolVersion\":\"$VERSION\",\"capabilities\":{\"tools\":{}},\"serverInfo\":{\"name\":\"mock\",\"version\":\"1\"}}}"
+            ;;
+        *'"method":"tools/list"'*)
+            echo "{\"jsonrpc\":\"2.0\",\"id\":$id,\"result\":{\"tools\":[{\"name\":\"search\",\"inputSchema\":{}},{\"name\":\"fetch\",\"inputSchema\":{}}]}}"
+            ;;
+    esac
+done
+"#;
+
+fn write_script(dir: &Path, body: &str) -> String {
+    let script = dir.join("server");
+    common::write_script(&script, body);
+    script.display().to_string()
+}
+
+fn stdio(command: &str, version: &str) -> McpServerConfig {
+    McpServerConfig::Stdio(McpStdioServerConfig {
+        command: command.to_string(),
+        args: None,
+        env: Some(HashMap::from([(
+            "VERSION".to_string(),
+            version.to_string(),
+        )])),
+    })
+}
+
+fn status_of<'a>(reports: &'a [McpHealthReport], name: &str) -> &'a McpHealthReport {
+    reports.iter().find(|report| report.name == name).unwrap()
+}
+
+#[tokio::test]
+async fn test_verify_reports_each_server() {
+    let dir = tempfile::tempdir().unwrap();
+    let command = write_script(dir.path(), MOCK_SERVER);
+    let sdk = create_sdk_mcp_server(
+        "local",
+        "1.0.0",
+        vec![tool!(
+            "echo",
+            "Echo",
+            json!({"type": "object"}),
+            |args: Value| async move {
+                Ok(ToolResult {
+                    content: vec![McpToolResultContent::Text {
+                        text: args.to_string(),
+                    }],
+                    is_error: false,
+                })
+            }
+        )],
+    );
+
+    let servers = McpServers::Dict(HashMap::from([
+        ("good".to_string(), stdio(&command, "2025-06-18")),
+        ("future".to_string(), stdio(&command, "2099-01-01")),
+        (
+            "typo".to_string(),
+            stdio("/no/such/mcp-server", "2025-06-18"),
+        ),
+        ("local".to_string(), McpServerConfig::Sdk(sdk)),
+    ]));
+    let reports = servers.verify().await;
+
+    let names: Vec<_> = reports.iter().map(|report| report.name.as_str()).collect();
+    assert_eq!(names, ["future", "good", "local", "typo"]);
+
+    let good = status_of(&reports, "good");
+    assert!(good.is_healthy());
+    assert_eq!(good.tools_advertised, ["search", "fetch"]);
+
+    assert_eq!(
+        status_of(&reports, "future").status,
+        McpHealthStatus::ProtocolMismatch("2099-01-01".to_string())
+    );
+    assert!(matches!(
+        status_of(&reports, "typo").status,
+        McpHealthStatus::Unreachable(_)
+    ));
+
+    let local = status_of(&reports, "local");
+    assert!(local.is_healthy());
+    assert_eq!(local.tools_advertised, ["echo"]);
+}
+
+#[tokio::test]
+async fn test_silent_server_times_out() {
+    let dir = tempfile::tempdir().unwrap();
+    let command = write_script(dir.path(), "#!/bin/sh\ncat > /dev/null\n");
+    let servers = McpServers::Dict(HashMap::from([(
+        "silent".to_string(),
+        stdio(&command, "2025-06-18"),
+    )]));
+
+    let reports = servers
+        .verify_with_timeout(Duration::from_millis(300))
+        .await;
+    let McpHealthStatus::Unreachable(reason) = &reports[0].status else {
+        panic!("expected a timeout, got {:?}", reports[0]);
+    };
+    assert!(reason.contains("within"), "{reason}");
+}
+
+/// Serve streamable HTTP MCP on a local port until the test ends
+async fn serve_http() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let mut stream = BufReader::new(stream);
+                loop {
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                            return;
+                        }
+                        if line == "\r\n" {
+                            break;
+                        }
+                        let lower = line.to_ascii_lowercase();
+                        if let Some(length) = lower.strip_prefix("content-length:") {
+                            content_length = length.trim().parse().unwrap();
+                        }
+                    }
+                    let mut body = vec![0; content_length];
+                    stream.read_exact(&mut body).await.unwrap();
+                    let request: Value = serde_json::from_slice(&body).unwrap();
+
+                    let (content_type, body) = match request["method"].as_str() {
+                        Some("initialize") => {
+                            let response = json!({
+                                "jsonrpc": "2.0",
+                                "id": request["id"],
+                                "result": {"protocolVersion": "2025-03-26", "capabilities": {}},
+                            });
+                            (
+                                "text/event-stream",
+                                format!("event: message\ndata: {}\n\n", response),
+                            )
+                        },
+                        Some("tools/list") => {
+                            let response = json!({
+                                "jsonrpc": "2.0",
+                                "id": request["id"],
+                                "result": {"tools": [{"name": "remote", "inputSchema": {}}]},
+                            });
+                            ("application/json", response.to_string())
+                        },
+                        _ => ("application/json", String::new()),
+                    };
+                    let status = if body.is_empty() {
+                        "202 Accepted"
+                    } else {
+                        "200 OK"
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\ncontent-type: {}\r\nmcp-session-id: s1\r\ncontent-length: {}\r\n\r\n{}",
+                        status,
+                        content_type,
+                        body.len(),
+                        body
+                    );
+                    stream
+                        .get_mut()
+                        .write_all(response.as_bytes())
+                        .await
+                        .unwrap();
+                }
+            });
+        }
+    });
+    format!("http://{}/mcp", address)
+}
+
+#[tokio::test]
+async fn test_http_server_handshake() {
+    let url = serve_http().await;
+    let servers = McpServers::Dict(HashMap::from([
+        (
+            "remote".to_string(),
+            McpServerConfig::Http(McpHttpServerConfig { url, headers: None }),
+        ),
+        (
+            "down".to_string(),
+            // Nothing listens on the discard port
+            McpServerConfig::Http(McpHttpServerConfig {
+                url: "http://127.0.0.1:9/mcp".to_string(),
+                headers: None,
+            }),
+        ),
+    ]));
+
+    let reports = servers.verify().await;
+    let remote = status_of(&reports, "remote");
+    assert!(remote.is_healthy(), "{remote:?}");
+    assert_eq!(remote.tools_advertised, ["remote"]);
+    assert!(matches!(
+        status_of(&reports, "down").status,
+        McpHealthStatus::Unreachable(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_connect_fails_fast_on_broken_servers() {
+    let dir = tempfile::tempdir().unwrap();
+    let command = write_script(dir.path(), MOCK_SERVER);
+    let servers = HashMap::from([
+        ("good".to_string(), stdio(&command, "2025-06-18")),
+        (
+            "typo".to_string(),
+            stdio("/no/such/mcp-server", "2025-06-18"),
+        ),
+    ]);
+    let options = ClaudeAgentOptions::builder()
+        // Never started, as verification fails first
+        .cli_path(dir.path().join("missing-cli"))
+        .mcp_servers(McpServers::Dict(servers))
+        .verify_mcp_servers(true)
+        .build();
+
+    let err = ClaudeClient::new(options).connect().await.unwrap_err();
+    let ClaudeError::McpServersUnhealthy(reports) = &err else {
+        panic!("expected unhealthy servers, got {err:?}");
+    };
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].name, "typo");
+    assert!(err.to_string().contains("typo (unreachable"));
+}
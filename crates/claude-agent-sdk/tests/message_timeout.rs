@@ -0,0 +1,182 @@
+//! Message timeouts against a mock CLI
+//!
+//! The mock writes its pid to `$MOCK_PID`. With `MOCK_MODE=stall` it answers a
+//! prompt with one assistant message and then goes silent; otherwise it sends
+//! four assistant messages 200ms apart followed by the result.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, Message, query_stream, query_with_timeout,
+};
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+echo $$ > "$MOCK_PID"
+assistant() {
+    echo "{\"type\":\"assistant\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"$1\"}]}}"
+}
+
+handle() {
+    if [ "$MOCK_MODE" = "stall" ]; then
+        assistant "thinking"
+        exec sleep 2
+    fi
+    for i in 1 2 3 4; do
+        sleep 0.2
+        assistant "$i"
+    done
+    echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"mock\"}"
+}
+
+serve
+"#;
+
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+impl MockCli {
+    fn options(&self, stall: bool) -> ClaudeAgentOptions {
+        let env = std::collections::HashMap::from([
+            (
+                "MOCK_PID".to_string(),
+                self.dir().join("pid").display().to_string(),
+            ),
+            (
+                "MOCK_MODE".to_string(),
+                if stall { "stall" } else { "trickle" }.to_string(),
+            ),
+        ]);
+        ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(env)
+            .build()
+    }
+
+    /// Whether the mock process is still running
+    fn is_running(&self) -> bool {
+        let pid = std::fs::read_to_string(self.dir().join("pid")).unwrap();
+        std::process::Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .unwrap()
+            .success()
+    }
+}
+
+#[tokio::test]
+async fn test_query_with_timeout_kills_stalled_cli() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let started = Instant::now();
+    let err = query_with_timeout("hello", Some(mock.options(true)), TIMEOUT)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, ClaudeError::MessageTimeout { timeout } if timeout == TIMEOUT),
+        "{err:?}"
+    );
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert!(!mock.is_running());
+}
+
+#[tokio::test]
+async fn test_query_with_timeout_allows_long_runs_that_keep_talking() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let started = Instant::now();
+    let messages = query_with_timeout("hello", Some(mock.options(false)), TIMEOUT)
+        .await
+        .unwrap();
+    // The whole run outlasts the timeout, but no single gap does
+    assert!(started.elapsed() > TIMEOUT);
+    assert_eq!(messages.len(), 5);
+    assert!(matches!(messages.last(), Some(Message::Result(_))));
+}
+
+#[tokio::test]
+async fn test_query_stream_honours_message_timeout_option() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut options = mock.options(true);
+    options.message_timeout = Some(TIMEOUT);
+
+    let mut stream = query_stream("hello", Some(options)).await.unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Ok(Message::Assistant(_)))
+    ));
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(ClaudeError::MessageTimeout { .. }))
+    ));
+    assert!(stream.next().await.is_none());
+    assert!(!mock.is_running());
+}
+
+#[tokio::test]
+async fn test_client_receive_response_honours_message_timeout_option() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut options = mock.options(true);
+    options.message_timeout = Some(TIMEOUT);
+
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+    client.query("hello").await.unwrap();
+    {
+        let mut stream = client.receive_response();
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(Message::Assistant(_)))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(ClaudeError::MessageTimeout { .. }))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_receive_response_with_timeout_overrides_option() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(true));
+    client.connect().await.unwrap();
+    client.query("hello").await.unwrap();
+    {
+        let started = Instant::now();
+        let mut stream = client.receive_response_with_timeout(TIMEOUT);
+        assert!(matches!(
+            stream.next().await,
+            Some(Ok(Message::Assistant(_)))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(ClaudeError::MessageTimeout { timeout })) if timeout == TIMEOUT
+        ));
+        assert!(stream.next().await.is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_receive_response_with_timeout_allows_trickling_output() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(false));
+    client.connect().await.unwrap();
+    client.query("hello").await.unwrap();
+    {
+        let messages: Vec<_> = client
+            .receive_response_with_timeout(TIMEOUT)
+            .collect()
+            .await;
+        assert_eq!(messages.len(), 5);
+        assert!(matches!(messages.last(), Some(Ok(Message::Result(_)))));
+    }
+    client.disconnect().await.unwrap();
+}
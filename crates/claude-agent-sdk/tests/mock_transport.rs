@@ -0,0 +1,184 @@
+//! Agent code against testing::MockTransport instead of the CLI
+
+use claude_agent_sdk::testing::{MockTransport, fixtures};
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, ContentBlock, Message, query_with_transport,
+};
+use serde_json::json;
+use std::time::Duration;
+
+fn assistant_blocks(messages: &[Message]) -> Vec<&ContentBlock> {
+    messages
+        .iter()
+        .filter_map(|message| match message {
+            Message::Assistant(assistant) => Some(&assistant.message.content),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+fn result_text(messages: &[Message]) -> Option<&str> {
+    messages.iter().find_map(|message| match message {
+        Message::Result(result) => result.result.as_deref(),
+        _ => None,
+    })
+}
+
+#[tokio::test]
+async fn test_query_with_basic_text_fixture() {
+    let transport = MockTransport::from_json(fixtures::BASIC_TEXT).unwrap();
+    let recorder = transport.recorder();
+
+    let messages = query_with_transport("Hi!", None, Box::new(transport))
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        result_text(&messages),
+        Some("Hello! How can I help you today?")
+    );
+    assert_eq!(recorder.user_messages(), vec!["Hi!"]);
+    assert!(recorder.is_finished());
+}
+
+#[tokio::test]
+async fn test_query_with_tool_use_fixture() {
+    let transport = MockTransport::from_json(fixtures::TOOL_USE).unwrap();
+
+    let messages = query_with_transport("Summarize the README", None, Box::new(transport))
+        .await
+        .unwrap();
+    let tool_uses: Vec<_> = assistant_blocks(&messages)
+        .into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse(tool_use) => Some(tool_use),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(tool_uses.len(), 1);
+    assert_eq!(tool_uses[0].name, "Read");
+    assert_eq!(tool_uses[0].input["file_path"], "README.md");
+    assert!(
+        messages
+            .iter()
+            .any(|message| matches!(message, Message::User(_)))
+    );
+    assert_eq!(
+        result_text(&messages),
+        Some("The README describes an example project.")
+    );
+}
+
+#[tokio::test]
+async fn test_query_with_permission_denied_fixture() {
+    let transport = MockTransport::from_json(fixtures::PERMISSION_DENIED).unwrap();
+
+    let messages = query_with_transport("Clean the build", None, Box::new(transport))
+        .await
+        .unwrap();
+    let text: String = assistant_blocks(&messages)
+        .into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text(text) => Some(text.text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(text.contains("permission"), "{}", text);
+}
+
+#[tokio::test]
+async fn test_query_loads_fixture_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("script.json");
+    std::fs::write(&path, fixtures::BASIC_TEXT).unwrap();
+
+    let transport = MockTransport::from_fixture(&path).unwrap();
+    let messages = query_with_transport("Hi!", None, Box::new(transport))
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 2);
+
+    assert!(matches!(
+        MockTransport::from_fixture(dir.path().join("missing.json")),
+        Err(ClaudeError::Io(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_client_conversation_is_recorded() {
+    let transport = MockTransport::builder()
+        .on_user_message("weather")
+        .respond_tool_use("toolu_1", "WebSearch", json!({"query": "weather Paris"}))
+        .respond_tool_result("toolu_1", "Sunny, 21°C", false)
+        .respond_assistant_text("It is sunny.")
+        .respond_result("It is sunny.")
+        .on_user_message("tomorrow")
+        .delay(Duration::from_millis(10))
+        .respond_assistant_text("Rain.")
+        .respond_result("Rain.")
+        .build();
+    let recorder = transport.recorder();
+
+    let mut client =
+        ClaudeClient::with_transport(Box::new(transport), ClaudeAgentOptions::default());
+    client.connect().await.unwrap();
+
+    let response = client.query_collect("What's the weather?").await.unwrap();
+    assert_eq!(response.text(), "It is sunny.");
+    let response = client.query_collect("And tomorrow?").await.unwrap();
+    assert_eq!(response.text(), "Rain.");
+    client.disconnect().await.unwrap();
+
+    assert_eq!(
+        recorder.user_messages(),
+        vec!["What's the weather?", "And tomorrow?"]
+    );
+    assert_eq!(recorder.control_requests()[0]["subtype"], "initialize");
+    assert!(recorder.is_finished());
+}
+
+#[tokio::test]
+async fn test_injected_error_fails_query() {
+    let transport = MockTransport::builder()
+        .on_user_message("")
+        .respond_assistant_text("Working on it")
+        .error("connection reset by peer")
+        .build();
+
+    let error = query_with_transport("hello", None, Box::new(transport))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, ClaudeError::Transport(ref message) if message.contains("connection reset")),
+        "{:?}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_client_turn_cut_short_by_close() {
+    let transport = MockTransport::builder()
+        .on_user_message("")
+        .respond_assistant_text("Working on it")
+        .close()
+        .build();
+
+    let mut client =
+        ClaudeClient::with_transport(Box::new(transport), ClaudeAgentOptions::default());
+    client.connect().await.unwrap();
+
+    let error = client.query_collect("hello").await.unwrap_err();
+    assert!(
+        matches!(
+            error,
+            ClaudeError::IncompleteTurn {
+                messages_received: 1,
+                ..
+            }
+        ),
+        "{:?}",
+        error
+    );
+    client.disconnect().await.unwrap();
+}
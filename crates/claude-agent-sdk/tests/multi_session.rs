@@ -0,0 +1,170 @@
+//! Session handles against a mock CLI
+//!
+//! The mock answers every user message with its text as both the assistant
+//! message and the result, tagged with the message's session ID. It waits a
+//! second before answering "slow".
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, Message, SessionHandle, TurnPolicy,
+};
+use futures::StreamExt;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            text=$(printf '%s' "$line" | sed -n 's/.*"content":"\([^"]*\)".*/\1/p')
+            session=$(printf '%s' "$line" | sed -n 's/.*"session_id":"\([^"]*\)".*/\1/p')
+            if [ "$text" = "slow" ]; then
+                sleep 1
+            fi
+            echo "{\"type\":\"assistant\",\"session_id\":\"$session\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"$text\"}]}}"
+            echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"$session\",\"result\":\"$text\"}"
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    async fn client(&self) -> ClaudeClient {
+        self.client_with_policy(TurnPolicy::Interleave).await
+    }
+
+    async fn client_with_policy(&self, policy: TurnPolicy) -> ClaudeClient {
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .turn_policy(policy)
+            .build();
+
+        let client = ClaudeClient::new(options);
+        client.connect().await.unwrap();
+        client
+    }
+}
+
+/// Result of the next turn of `session`
+async fn session_result(session: &SessionHandle<'_>) -> (String, String) {
+    let mut stream = session.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            return (result.session_id, result.result.unwrap_or_default());
+        }
+    }
+    panic!("session {} ended without a result", session.session_id());
+}
+
+/// Result of the next turn received by the client itself
+async fn client_result(client: &ClaudeClient) -> (String, String) {
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::Result(result) = message.unwrap() {
+            return (result.session_id, result.result.unwrap_or_default());
+        }
+    }
+    panic!("client stream ended without a result");
+}
+
+fn result(session_id: &str, text: &str) -> (String, String) {
+    (session_id.to_string(), text.to_string())
+}
+
+#[tokio::test]
+async fn test_sessions_receive_only_their_messages() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = mock.client().await;
+
+    let first = client.session("first");
+    let second = client.session("second");
+    first.send("one").await.unwrap();
+    second.send("two").await.unwrap();
+
+    // Received in the other order, each handle sees only its own answer
+    assert_eq!(session_result(&second).await, result("second", "two"));
+    assert_eq!(session_result(&first).await, result("first", "one"));
+
+    let (a, b) = tokio::join!(
+        async {
+            first.send("three").await.unwrap();
+            session_result(&first).await
+        },
+        async {
+            second.send("four").await.unwrap();
+            session_result(&second).await
+        },
+    );
+    assert_eq!(a, result("first", "three"));
+    assert_eq!(b, result("second", "four"));
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unclaimed_sessions_reach_the_client_stream() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    client
+        .query_with_session("hello", "unclaimed")
+        .await
+        .unwrap();
+    let claimed = client.session("claimed");
+    assert_eq!(client.active_sessions(), vec!["claimed"]);
+
+    assert_eq!(client_result(&client).await, result("unclaimed", "hello"));
+    drop(claimed);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dropping_a_handle_keeps_other_sessions() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client().await;
+
+    let kept = client.session("kept");
+    let dropped = client.session("dropped");
+    kept.send("still here").await.unwrap();
+    dropped.send("gone").await.unwrap();
+    // The mock answers in order, so the kept session's answer is buffered too
+    assert_eq!(session_result(&dropped).await, result("dropped", "gone"));
+    drop(dropped);
+
+    assert_eq!(client.active_sessions(), vec!["kept"]);
+    assert_eq!(session_result(&kept).await, result("kept", "still here"));
+    drop(kept);
+
+    // Later messages of a dropped session go to the client's stream
+    client.query_with_session("late", "dropped").await.unwrap();
+    assert_eq!(client_result(&client).await, result("dropped", "late"));
+    assert!(client.active_sessions().is_empty());
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_turn_policy_applies_across_sessions() {
+    let mock = MockCli::new(MOCK_CLI);
+    let client = mock.client_with_policy(TurnPolicy::Reject).await;
+
+    let first = client.session("first");
+    let second = client.session("second");
+    first.send("slow").await.unwrap();
+    assert!(matches!(
+        second.send("two").await,
+        Err(ClaudeError::TurnInProgress)
+    ));
+
+    // Once the first session's turn is over, the second one may start
+    assert_eq!(session_result(&first).await, result("first", "slow"));
+    second.send("two").await.unwrap();
+    assert_eq!(session_result(&second).await, result("second", "two"));
+
+    client.disconnect().await.unwrap();
+}
@@ -0,0 +1,139 @@
+//! OpenAI-compatible chat client against a mock CLI
+//!
+//! The mock records its arguments, stdin and `CLAUDE_CODE_MAX_OUTPUT_TOKENS`
+//! in `$MOCK_CAPTURE`, then answers like the CLI. With
+//! `--include-partial-messages` it also streams the text as two deltas.
+
+#![cfg(all(unix, feature = "openai-compat"))]
+
+use claude_agent_sdk::ClaudeAgentOptions;
+use claude_agent_sdk::compat::openai::{ChatClient, ChatMessage, ChatRequest, ChatRole};
+use futures::StreamExt;
+use std::path::PathBuf;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+printf '%s\n' "$@" > "$MOCK_CAPTURE.args"
+printf '%s' "$CLAUDE_CODE_MAX_OUTPUT_TOKENS" > "$MOCK_CAPTURE.env"
+cat > "$MOCK_CAPTURE.stdin"
+
+case " $* " in
+    *" --include-partial-messages "*)
+        echo '{"type":"stream_event","uuid":"e1","session_id":"mock","event":{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Par"}}}'
+        echo '{"type":"stream_event","uuid":"e2","session_id":"mock","event":{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"is"}}}'
+        ;;
+esac
+echo '{"type":"assistant","message":{"model":"claude-3-5-haiku-20241022","content":[{"type":"text","text":"Paris"}]}}'
+echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"mock","result":"Paris","usage":{"input_tokens":20,"cache_read_input_tokens":5,"output_tokens":2}}'
+"#;
+
+impl MockCli {
+    fn client(&self) -> ChatClient {
+        let capture = self.dir().join("capture");
+        let env = std::collections::HashMap::from([(
+            "MOCK_CAPTURE".to_string(),
+            capture.display().to_string(),
+        )]);
+        ChatClient::new(
+            ClaudeAgentOptions::builder()
+                .cli_path(self.path())
+                .env(env)
+                .build(),
+        )
+    }
+
+    fn captured(&self, suffix: &str) -> String {
+        std::fs::read_to_string(self.dir().join(format!("capture.{suffix}"))).unwrap()
+    }
+}
+
+fn request() -> ChatRequest {
+    let mut request = ChatRequest::new(
+        "haiku",
+        vec![
+            ChatMessage::system("Answer in one word."),
+            ChatMessage::user("What is the capital of Italy?"),
+            ChatMessage::assistant("Rome"),
+            ChatMessage::user("And of France?"),
+        ],
+    );
+    request.max_tokens = Some(16);
+    request.temperature = Some(0.0);
+    request
+}
+
+#[tokio::test]
+async fn test_create_translates_request_and_response() {
+    let mock = MockCli::new(MOCK_CLI);
+    let response = mock.client().create(request()).await.unwrap();
+
+    let args: Vec<String> = mock.captured("args").lines().map(String::from).collect();
+    let arg = |flag: &str| {
+        let i = args.iter().position(|a| a == flag).unwrap();
+        args[i + 1].clone()
+    };
+    assert_eq!(arg("--model"), "haiku");
+    assert_eq!(arg("--system-prompt"), "Answer in one word.");
+    assert_eq!(mock.captured("env"), "16");
+
+    let stdin = mock.captured("stdin");
+    assert!(stdin.contains("User: What is the capital of Italy?\n\nAssistant: Rome"));
+    assert!(stdin.trim_end().ends_with("And of France?"));
+
+    assert!(response.id.starts_with("chatcmpl-"));
+    assert_eq!(response.object, "chat.completion");
+    assert_eq!(response.model, "claude-3-5-haiku-20241022");
+    assert_eq!(response.choices.len(), 1);
+    assert_eq!(response.choices[0].message, ChatMessage::assistant("Paris"));
+    assert_eq!(response.choices[0].finish_reason.as_deref(), Some("stop"));
+    assert_eq!(response.usage.prompt_tokens, 25);
+    assert_eq!(response.usage.completion_tokens, 2);
+    assert_eq!(response.usage.total_tokens, 27);
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["choices"][0]["message"]["role"], "assistant");
+    assert_eq!(json["usage"]["total_tokens"], 27);
+}
+
+#[tokio::test]
+async fn test_create_stream_yields_openai_deltas() {
+    let mock = MockCli::new(MOCK_CLI);
+    let chunks: Vec<_> = mock
+        .client()
+        .create_stream(request())
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+    assert!(
+        mock.captured("args")
+            .lines()
+            .any(|a| a == "--include-partial-messages")
+    );
+    assert_eq!(chunks.len(), 4);
+    assert!(chunks.iter().all(|c| c.id == chunks[0].id));
+    assert!(chunks.iter().all(|c| c.object == "chat.completion.chunk"));
+
+    assert_eq!(chunks[0].choices[0].delta.role, Some(ChatRole::Assistant));
+    let text: String = chunks
+        .iter()
+        .filter_map(|c| c.choices[0].delta.content.clone())
+        .collect();
+    assert_eq!(text, "Paris");
+
+    let last = chunks.last().unwrap();
+    assert_eq!(last.choices[0].finish_reason.as_deref(), Some("stop"));
+    assert_eq!(last.usage.unwrap().total_tokens, 27);
+    assert_eq!(last.model, "claude-3-5-haiku-20241022");
+
+    let json = serde_json::to_value(&chunks[1]).unwrap();
+    assert_eq!(
+        json["choices"][0],
+        serde_json::json!({"index": 0, "delta": {"content": "Par"}, "finish_reason": null})
+    );
+    assert!(json.get("usage").is_none());
+}
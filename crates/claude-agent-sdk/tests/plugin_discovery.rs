@@ -0,0 +1,129 @@
+//! Plugin discovery and manifest validation against the fixtures in
+//! `fixtures/plugins`
+//!
+//! `valid-plugin` is the only plugin there that loads; `not-a-plugin` has no
+//! manifest and is not a plugin at all.
+
+use claude_agent_sdk::{ClaudeAgentOptions, PluginValidationError, SdkPluginConfig, query};
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+mod common;
+#[cfg(unix)]
+use common::MockCli;
+
+fn plugins_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures/plugins")
+}
+
+fn plugin(name: &str) -> SdkPluginConfig {
+    SdkPluginConfig::local(plugins_dir().join(name))
+}
+
+#[test]
+fn test_discover_finds_plugins_with_a_manifest() {
+    let names: Vec<_> = SdkPluginConfig::discover(plugins_dir())
+        .unwrap()
+        .iter()
+        .map(|plugin| plugin.path().unwrap().file_name().unwrap().to_owned())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "bad-json",
+            "bad-name",
+            "missing-entry",
+            "missing-name",
+            "valid-plugin"
+        ]
+    );
+
+    assert!(SdkPluginConfig::discover(plugins_dir().join("missing")).is_err());
+}
+
+#[test]
+fn test_validate_accepts_a_well_formed_plugin() {
+    let manifest = plugin("valid-plugin").validate().unwrap();
+    assert_eq!(manifest.name, "valid-plugin");
+    assert_eq!(manifest.version.as_deref(), Some("0.2.0"));
+    assert!(manifest.description.is_some());
+}
+
+#[test]
+fn test_validate_names_the_problem() {
+    assert!(matches!(
+        plugin("not-a-plugin").validate(),
+        Err(PluginValidationError::MissingManifest(_))
+    ));
+    assert!(matches!(
+        plugin("bad-json").validate(),
+        Err(PluginValidationError::InvalidManifest { .. })
+    ));
+    assert!(matches!(
+        plugin("missing-name").validate(),
+        Err(PluginValidationError::MissingField { field: "name", .. })
+    ));
+    assert_eq!(
+        plugin("bad-name").validate(),
+        Err(PluginValidationError::InvalidName("Bad Name".to_string()))
+    );
+
+    let Err(PluginValidationError::MissingEntry {
+        plugin: name,
+        field,
+        path,
+    }) = plugin("missing-entry").validate()
+    else {
+        panic!("expected a missing entry");
+    };
+    assert_eq!((name.as_str(), field), ("missing-entry", "commands"));
+    assert!(path.ends_with("commands/absent.md"));
+}
+
+#[test]
+fn test_validate_options_rejects_a_missing_plugins_dir() {
+    let options = ClaudeAgentOptions::builder()
+        .plugins_dir(plugins_dir().join("missing"))
+        .build();
+    let issues = options.validate().unwrap_err();
+    assert_eq!(issues[0].field, "plugins_dir");
+
+    let options = ClaudeAgentOptions::builder()
+        .plugins_dir(plugins_dir())
+        .build();
+    assert!(options.validate().is_ok());
+}
+
+/// The mock CLI records its arguments, then ends the turn
+#[cfg(unix)]
+const MOCK_CLI: &str = r#"
+printf '%s\n' "$@" > "$(dirname "$0")/args.log"
+cat > /dev/null
+echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"mock"}'
+"#;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_plugins_dir_loads_only_valid_plugins() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let explicit = mock.dir().join("explicit");
+    std::fs::create_dir(&explicit).unwrap();
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .plugins(vec![SdkPluginConfig::local(&explicit)])
+        .plugins_dir(plugins_dir())
+        .build();
+    query("hello", Some(options)).await.unwrap();
+
+    let args = std::fs::read_to_string(mock.dir().join("args.log")).unwrap();
+    let args: Vec<_> = args.lines().collect();
+    let plugin_dirs: Vec<_> = args
+        .windows(2)
+        .filter(|pair| pair[0] == "--plugin-dir")
+        .map(|pair| PathBuf::from(pair[1]))
+        .collect();
+    assert_eq!(plugin_dirs.len(), 2, "unexpected plugins in {args:?}");
+    assert_eq!(plugin_dirs[0], explicit);
+    assert!(plugin_dirs[1].ends_with("valid-plugin"));
+}
@@ -0,0 +1,73 @@
+//! Query metrics recorded by `ClaudeClient` against a mock CLI
+//!
+//! The mock answers each user message with a tool call, a text reply and a
+//! result. Each turn costs $0.10, and like the real CLI the result reports the
+//! running cost of the session.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::observability::{
+    COST_USD_METRIC, MetricsCollector, QUERIES_COMPLETED_METRIC, QUERIES_FAILED_METRIC,
+    QUERIES_STARTED_METRIC, QUERY_DURATION_METRIC, TIME_TO_FIRST_MESSAGE_METRIC,
+    TOOL_INVOCATIONS_METRIC,
+};
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient};
+use std::sync::Arc;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+turns=0
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            turns=$((turns + 1))
+            echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"tool_use","id":"t1","name":"Read","input":{}}]}}'
+            echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"text","text":"done"}]}}'
+            echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"sess-1\",\"total_cost_usd\":0.$turns}"
+            ;;
+    esac
+}
+
+serve
+"#;
+
+const NO_LABELS: &[(&str, &str)] = &[];
+
+#[tokio::test]
+async fn test_client_records_query_metrics() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let metrics = Arc::new(MetricsCollector::new());
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .metrics(Arc::clone(&metrics))
+        .build();
+    let mut client = ClaudeClient::new(options);
+
+    client.query_collect("first").await.unwrap();
+    client.query_collect("second").await.unwrap();
+    client.disconnect().await.unwrap();
+
+    assert_eq!(metrics.get_counter(QUERIES_STARTED_METRIC, NO_LABELS), 2.0);
+    assert_eq!(
+        metrics.get_counter(QUERIES_COMPLETED_METRIC, NO_LABELS),
+        2.0
+    );
+    assert_eq!(metrics.get_counter(QUERIES_FAILED_METRIC, NO_LABELS), 0.0);
+    assert_eq!(
+        metrics.get_counter(TOOL_INVOCATIONS_METRIC, &[("tool", "Read")]),
+        2.0
+    );
+    assert!((metrics.get_gauge(COST_USD_METRIC, NO_LABELS) - 0.2).abs() < 1e-9);
+
+    let durations = metrics
+        .get_histogram(QUERY_DURATION_METRIC, NO_LABELS)
+        .unwrap();
+    assert_eq!(durations.count, 2);
+    let first_message = metrics
+        .get_histogram(TIME_TO_FIRST_MESSAGE_METRIC, NO_LABELS)
+        .unwrap();
+    assert_eq!(first_message.count, 2);
+}
@@ -0,0 +1,88 @@
+//! Typed queries against a mock CLI
+//!
+//! The mock answers with structured output only when it was given a JSON
+//! schema describing a `city`, and with plain text otherwise.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeError, query_stream_typed, query_typed};
+use futures::StreamExt;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+cat > /dev/null
+
+case "$*" in
+    *--json-schema*'"city"'*) structured=',"structured_output":{"city":"Paris","population":2102650}' ;;
+    *) structured='' ;;
+esac
+
+echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"text","text":"It is Paris."}]}}'
+echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"sess-1\"$structured}"
+"#;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Capital {
+    city: String,
+    population: u64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct Country {
+    #[allow(dead_code)]
+    name: String,
+}
+
+fn mock_options(mock: &MockCli) -> ClaudeAgentOptions {
+    ClaudeAgentOptions::builder().cli_path(mock.path()).build()
+}
+
+#[tokio::test]
+async fn test_query_typed_deserializes_structured_output() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let capital: Capital = query_typed("capital of France?", Some(mock_options(&mock)))
+        .await
+        .unwrap();
+
+    assert_eq!(capital.city, "Paris");
+    assert_eq!(capital.population, 2_102_650);
+}
+
+#[tokio::test]
+async fn test_query_typed_error_carries_claude_text() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let error = query_typed::<Country>("which country?", Some(mock_options(&mock)))
+        .await
+        .unwrap_err();
+
+    match error {
+        ClaudeError::StructuredOutput(error) => {
+            assert_eq!(error.text.as_deref(), Some("It is Paris."));
+        },
+        other => panic!("expected a structured output error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_query_stream_typed_resolves_after_the_stream() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let (mut stream, capital) =
+        query_stream_typed::<Capital>("capital of France?", Some(mock_options(&mock)))
+            .await
+            .unwrap();
+    let mut count = 0;
+    while let Some(message) = stream.next().await {
+        message.unwrap();
+        count += 1;
+    }
+
+    assert_eq!(count, 2);
+    assert_eq!(capital.await.unwrap().unwrap().city, "Paris");
+}
@@ -0,0 +1,132 @@
+//! Health check and reconnect against a mock CLI
+//!
+//! The mock logs its arguments to `$MOCK_LOG`, answers one user message with
+//! an assistant message and a result for session `sess-1`, then exits as if
+//! it had crashed.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, Message};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::time::Duration;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+echo "$*" >> "$MOCK_LOG"
+
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"text","text":"hello"}]}}'
+            echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"sess-1"}'
+            exit 0
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    fn options(&self, auto_reconnect: bool) -> ClaudeAgentOptions {
+        let log = self.dir().join("args.log");
+        ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(HashMap::from([(
+                "MOCK_LOG".to_string(),
+                log.to_string_lossy().into_owned(),
+            )]))
+            .auto_reconnect(auto_reconnect)
+            .build()
+    }
+
+    /// Arguments of each CLI process started so far
+    fn spawns(&self) -> Vec<String> {
+        std::fs::read_to_string(self.dir().join("args.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+async fn wait_until_dead(client: &ClaudeClient) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while client.is_alive() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("mock CLI should exit after its first result");
+}
+
+async fn receive_turn(client: &ClaudeClient) -> Vec<Message> {
+    let messages: Vec<_> = client.receive_response().collect().await;
+    messages.into_iter().map(Result::unwrap).collect()
+}
+
+#[tokio::test]
+async fn test_reconnect_resumes_last_session() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(false));
+    assert!(!client.is_alive());
+    client.connect().await.unwrap();
+    assert!(client.is_alive());
+
+    client.query("first").await.unwrap();
+    wait_until_dead(&client).await;
+    assert!(client.is_connected());
+    assert!(!client.is_alive());
+
+    client.reconnect().await.unwrap();
+    assert!(client.is_alive());
+
+    // The first turn's messages survive the reconnect
+    let messages = receive_turn(&client).await;
+    assert!(matches!(messages.last(), Some(Message::Result(_))));
+
+    let spawns = mock.spawns();
+    assert_eq!(spawns.len(), 2);
+    assert!(!spawns[0].contains("--resume"));
+    assert!(spawns[1].contains("--resume sess-1"));
+
+    client.query("second").await.unwrap();
+    let messages = receive_turn(&client).await;
+    assert!(matches!(messages.last(), Some(Message::Result(_))));
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_auto_reconnect_on_dead_process() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(true));
+    client.connect().await.unwrap();
+
+    let response = client.query_collect("first").await.unwrap();
+    assert!(response.result().is_some());
+    wait_until_dead(&client).await;
+
+    let response = client.query_collect("second").await.unwrap();
+    assert!(response.result().is_some());
+
+    let spawns = mock.spawns();
+    assert_eq!(spawns.len(), 2);
+    assert!(spawns[1].contains("--resume sess-1"));
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dead_process_without_auto_reconnect_fails() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(false));
+    client.connect().await.unwrap();
+
+    client.query_collect("first").await.unwrap();
+    wait_until_dead(&client).await;
+
+    assert!(client.query("second").await.is_err());
+    assert_eq!(mock.spawns().len(), 1);
+}
@@ -0,0 +1,143 @@
+//! Recording sessions with a mock CLI and replaying them without it
+//!
+//! The mock answers the initialize request and each user message with "4".
+//! A one-shot prompt arrives as plain text, so it is answered once stdin ends.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::testing::ReplayTransport;
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ContentBlock, FrameDirection, Message, RecordedFrame, query,
+    query_with_transport,
+};
+use serde_json::json;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+answer() {
+    echo '{"type":"assistant","session_id":"sess-1","message":{"model":"mock","content":[{"type":"text","text":"4"}]}}'
+    echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"sess-1","result":"4"}'
+}
+
+streaming=no
+
+on_initialize() {
+    streaming=yes
+    ack
+}
+
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            streaming=yes
+            answer
+            ;;
+    esac
+}
+
+serve
+if [ "$streaming" = no ]; then
+    answer
+fi
+"#;
+
+/// The assertions run against both the live and the replayed session
+fn assert_answered(messages: &[Message]) {
+    let text: String = messages
+        .iter()
+        .filter_map(|message| match message {
+            Message::Assistant(assistant) => Some(&assistant.message.content),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|block| match block {
+            ContentBlock::Text(text) => Some(text.text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(text, "4");
+    assert!(matches!(messages.last(), Some(Message::Result(_))));
+}
+
+#[tokio::test]
+async fn test_query_recording_replays_offline() {
+    let mock = MockCli::new(MOCK_CLI);
+    let recording = mock.dir().join("session.jsonl");
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .record_to(&recording)
+        .build();
+
+    let messages = query("What is 2 + 2?", Some(options)).await.unwrap();
+    assert_answered(&messages);
+
+    let frames = RecordedFrame::read_all(&recording).unwrap();
+    assert_eq!(frames[0].direction, FrameDirection::Outbound);
+    assert_eq!(frames[0].frame, json!("What is 2 + 2?"));
+    assert_eq!(frames.len(), 3);
+
+    let transport = ReplayTransport::open(&recording).unwrap();
+    let report = transport.report();
+    let messages = query_with_transport("What is 2 + 2?", None, Box::new(transport))
+        .await
+        .unwrap();
+    assert_answered(&messages);
+    assert!(report.mismatches().is_empty(), "{:?}", report.mismatches());
+    assert!(report.is_finished());
+}
+
+#[tokio::test]
+async fn test_divergent_replay_warns_and_continues() {
+    let mock = MockCli::new(MOCK_CLI);
+    let recording = mock.dir().join("session.jsonl");
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .record_to(&recording)
+        .build();
+    query("What is 2 + 2?", Some(options)).await.unwrap();
+
+    let transport = ReplayTransport::open(&recording).unwrap();
+    let report = transport.report();
+    let messages = query_with_transport("What is two plus two?", None, Box::new(transport))
+        .await
+        .unwrap();
+    assert_answered(&messages);
+
+    let mismatches = report.mismatches();
+    assert_eq!(mismatches.len(), 1, "{:?}", mismatches);
+    assert!(mismatches[0].contains("two plus two"), "{}", mismatches[0]);
+}
+
+#[tokio::test]
+async fn test_client_recording_replays_offline() {
+    let mock = MockCli::new(MOCK_CLI);
+    let recording = mock.dir().join("session.jsonl");
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .record_to(&recording)
+        .build();
+
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+    for prompt in ["What is 2 + 2?", "And 3 + 1?"] {
+        let response = client.query_collect(prompt).await.unwrap();
+        assert_answered(&response.messages);
+    }
+    client.disconnect().await.unwrap();
+
+    let transport = ReplayTransport::open(&recording).unwrap();
+    let report = transport.report();
+    let mut client =
+        ClaudeClient::with_transport(Box::new(transport), ClaudeAgentOptions::default());
+    client.connect().await.unwrap();
+    for prompt in ["What is 2 + 2?", "And 3 + 1?"] {
+        let response = client.query_collect(prompt).await.unwrap();
+        assert_answered(&response.messages);
+    }
+    client.disconnect().await.unwrap();
+
+    assert!(report.mismatches().is_empty(), "{:?}", report.mismatches());
+    assert!(report.is_finished());
+}
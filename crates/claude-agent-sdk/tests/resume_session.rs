@@ -0,0 +1,80 @@
+//! Resuming sessions against a mock CLI
+//!
+//! The mock only knows session `known-session`. Asked to resume any other, it
+//! fails the way the CLI does: a message on stderr and exit status 1.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, ClaudeError, query};
+use std::time::{Duration, Instant};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+resume=""
+while [ $# -gt 0 ]; do
+    if [ "$1" = "--resume" ]; then
+        resume="$2"
+    fi
+    shift
+done
+if [ -n "$resume" ] && [ "$resume" != "known-session" ]; then
+    echo "No conversation found with session ID: $resume" >&2
+    exit 1
+fi
+
+serve
+echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"known-session"}'
+"#;
+
+#[tokio::test]
+async fn test_client_connect_reports_unknown_session() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .resume("bogus-session".to_string())
+        .build();
+    let client = ClaudeClient::new(options);
+
+    let started = Instant::now();
+    let error = client.connect().await.unwrap_err();
+
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(
+        matches!(&error, ClaudeError::SessionNotFound(id) if id == "bogus-session"),
+        "{:?}",
+        error
+    );
+    assert!(!client.is_connected());
+}
+
+#[tokio::test]
+async fn test_client_connect_resumes_known_session() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .resume("known-session".to_string())
+        .build();
+    let client = ClaudeClient::new(options);
+
+    client.connect().await.unwrap();
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_query_reports_unknown_session() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .resume("bogus-session".to_string())
+        .build();
+
+    let error = query("hello", Some(options)).await.unwrap_err();
+
+    assert!(
+        matches!(&error, ClaudeError::SessionNotFound(id) if id == "bogus-session"),
+        "{:?}",
+        error
+    );
+}
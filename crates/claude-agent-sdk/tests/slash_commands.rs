@@ -0,0 +1,203 @@
+//! Slash commands answered by a client's command registry, against a mock CLI
+//!
+//! The mock answers every user message with its text as both the assistant
+//! message and the result, and logs the text next to itself. For "slow" it
+//! waits a moment before answering.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, CommandError, CommandRegistry, LocalCommandMessage, Message,
+    SlashCommand, TurnPolicy,
+};
+use futures::StreamExt;
+use std::sync::Arc;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+log="$(dirname "$0")/prompts.log"
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            text=$(printf '%s' "$line" | sed -n 's/.*"content":"\([^"]*\)".*/\1/p')
+            echo "$text" >> "$log"
+            if [ "$text" = "slow" ]; then
+                sleep 0.3
+            fi
+            echo "{\"type\":\"assistant\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"$text\"}]}}"
+            echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"default\",\"result\":\"$text\"}"
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    async fn client(&self, turn_policy: TurnPolicy) -> ClaudeClient {
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .turn_policy(turn_policy)
+            .build();
+
+        let client = ClaudeClient::new(options).with_command_registry(registry());
+        client.connect().await.unwrap();
+        client
+    }
+
+    /// Prompts the CLI received, in order
+    fn prompts(&self) -> Vec<String> {
+        std::fs::read_to_string(self.dir().join("prompts.log"))
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+fn registry() -> CommandRegistry {
+    let mut registry = CommandRegistry::new();
+    registry
+        .register(SlashCommand::new(
+            "ping",
+            "Answer locally",
+            Arc::new(|_, _| Box::pin(async { Ok("pong".to_string()) })),
+        ))
+        .unwrap();
+    registry
+        .register(SlashCommand::new(
+            "ask",
+            "Rewrite into a prompt",
+            Arc::new(|_, args| {
+                Box::pin(async move { Err(CommandError::ForwardToClaude(args.join(" "))) })
+            }),
+        ))
+        .unwrap();
+    registry
+        .register(SlashCommand::new(
+            "fail",
+            "Always fails",
+            Arc::new(|_, _| {
+                Box::pin(async { Err(CommandError::ExecutionFailed("broken".to_string())) })
+            }),
+        ))
+        .unwrap();
+    registry
+}
+
+/// Messages of the next turn
+async fn next_turn(client: &ClaudeClient) -> Vec<Message> {
+    let mut stream = client.receive_response();
+    let mut messages = Vec::new();
+    while let Some(message) = stream.next().await {
+        messages.push(message.unwrap());
+    }
+    messages
+}
+
+fn local(messages: &[Message]) -> &LocalCommandMessage {
+    match messages {
+        [Message::LocalCommand(local)] => local,
+        _ => panic!("expected a single local command message, got {messages:?}"),
+    }
+}
+
+fn result_text(messages: &[Message]) -> String {
+    match messages.last() {
+        Some(Message::Result(result)) => result.result.clone().unwrap_or_default(),
+        _ => panic!("expected the turn to end with a result, got {messages:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_registered_command_is_answered_locally() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client(TurnPolicy::Reject).await;
+
+    client.query("/ping").await.unwrap();
+    let turn = next_turn(&client).await;
+    let ping = local(&turn);
+    assert_eq!(ping.command, "/ping");
+    assert_eq!(ping.output, "pong");
+    assert!(!ping.is_error);
+
+    // The local turn is over, so the next query is accepted
+    client.query("/fail").await.unwrap();
+    let turn = next_turn(&client).await;
+    let fail = local(&turn);
+    assert!(fail.is_error);
+    assert!(fail.output.contains("broken"));
+
+    client.query("hello").await.unwrap();
+    assert_eq!(result_text(&next_turn(&client).await), "hello");
+    assert_eq!(mock.prompts(), vec!["hello"]);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unknown_and_forwarded_commands_reach_the_cli() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client(TurnPolicy::Reject).await;
+
+    client.query("/compact").await.unwrap();
+    assert_eq!(result_text(&next_turn(&client).await), "/compact");
+
+    client.query("/ask summarize the diff").await.unwrap();
+    assert_eq!(result_text(&next_turn(&client).await), "summarize the diff");
+
+    assert_eq!(mock.prompts(), vec!["/compact", "summarize the diff"]);
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_local_command_waits_for_the_running_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client(TurnPolicy::Queue).await;
+
+    client.query("slow").await.unwrap();
+    client.query("/ping").await.unwrap();
+    client.query("after").await.unwrap();
+
+    let types: Vec<_> = client
+        .receive_messages()
+        .take(5)
+        .map(|message| {
+            let message = message.unwrap();
+            match &message {
+                Message::Result(result) => format!("result {}", result.result.as_deref().unwrap()),
+                Message::LocalCommand(local) => format!("local {}", local.output),
+                other => other.type_name().to_string(),
+            }
+        })
+        .collect()
+        .await;
+    assert_eq!(
+        types,
+        vec![
+            "assistant",
+            "result slow",
+            "local pong",
+            "assistant",
+            "result after"
+        ]
+    );
+    assert_eq!(mock.prompts(), vec!["slow", "after"]);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_local_command_is_rejected_during_a_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client(TurnPolicy::Reject).await;
+
+    client.query("slow").await.unwrap();
+    let err = client.query("/ping").await.unwrap_err();
+    assert!(matches!(err, claude_agent_sdk::ClaudeError::TurnInProgress));
+    assert_eq!(result_text(&next_turn(&client).await), "slow");
+
+    client.disconnect().await.unwrap();
+}
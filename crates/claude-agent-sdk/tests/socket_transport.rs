@@ -0,0 +1,124 @@
+//! ClaudeClient over a socket instead of a spawned CLI
+//!
+//! The mock CLI server answers the initialize request and replies to every
+//! user message with its text, numbered by connection.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, SocketTransport, TransportConfig,
+};
+use serde_json::{Value, json};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+async fn serve(stream: UnixStream, connection: usize) {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let message: Value = serde_json::from_str(&line).unwrap();
+        let replies = match message["type"].as_str() {
+            Some("control_request") => vec![json!({
+                "type": "control_response",
+                "response": {
+                    "subtype": "success",
+                    "request_id": message["request_id"],
+                    "response": {},
+                },
+            })],
+            Some("user") => {
+                let text = format!(
+                    "{} (connection {})",
+                    message["message"]["content"], connection
+                );
+                vec![
+                    json!({
+                        "type": "assistant",
+                        "message": {"model": "mock", "content": [{"type": "text", "text": text}]},
+                    }),
+                    json!({
+                        "type": "result", "subtype": "success", "duration_ms": 1,
+                        "duration_api_ms": 1, "is_error": false, "num_turns": 1,
+                        "session_id": "socket",
+                    }),
+                ]
+            },
+            _ => Vec::new(),
+        };
+        for reply in replies {
+            write
+                .write_all(format!("{}\n", reply).as_bytes())
+                .await
+                .unwrap();
+        }
+    }
+}
+
+/// Serve the mock CLI on a Unix socket at `path`, counting connections
+fn mock_server(path: &Path) -> Arc<AtomicUsize> {
+    let listener = UnixListener::bind(path).unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&connections);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let connection = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::spawn(serve(stream, connection));
+        }
+    });
+    connections
+}
+
+#[tokio::test]
+async fn test_client_over_socket_reconnects() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("claude.sock");
+    let connections = mock_server(&socket);
+
+    let config = TransportConfig::unix(&socket);
+    let mut client = ClaudeClient::with_socket(config, ClaudeAgentOptions::default());
+    client.connect().await.unwrap();
+    let response = client.query_collect("hello").await.unwrap();
+    assert_eq!(response.text(), "\"hello\" (connection 1)");
+
+    client.reconnect().await.unwrap();
+    let response = client.query_collect("again").await.unwrap();
+    assert_eq!(response.text(), "\"again\" (connection 2)");
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_with_transport_connects_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("claude.sock");
+    mock_server(&socket);
+
+    let transport = SocketTransport::new(TransportConfig::unix(&socket));
+    let mut client =
+        ClaudeClient::with_transport(Box::new(transport), ClaudeAgentOptions::default());
+    client.connect().await.unwrap();
+    let response = client.query_collect("hello").await.unwrap();
+    assert_eq!(response.text(), "\"hello\" (connection 1)");
+    client.disconnect().await.unwrap();
+
+    let error = client.connect().await.unwrap_err();
+    assert!(
+        matches!(error, ClaudeError::InvalidConfig(_)),
+        "{:?}",
+        error
+    );
+}
+
+#[tokio::test]
+async fn test_client_reports_missing_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = TransportConfig::unix(dir.path().join("missing.sock"));
+    let client = ClaudeClient::with_socket(config, ClaudeAgentOptions::default());
+
+    let error = client.connect().await.unwrap_err();
+    assert!(matches!(error, ClaudeError::Connection(_)), "{:?}", error);
+}
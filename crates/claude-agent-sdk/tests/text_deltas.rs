@@ -0,0 +1,99 @@
+//! Text deltas of a response from a mock CLI
+//!
+//! With `--include-partial-messages`, the mock streams a thinking block, a tool
+//! call and two text deltas before the assistant message and the result;
+//! without it, only the assistant message and the result.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, Message};
+use futures::StreamExt;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+args=" $* "
+
+event() {
+    echo "{\"type\":\"stream_event\",\"uuid\":\"e$1\",\"session_id\":\"sess-1\",\"event\":$2}"
+}
+
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            case "$args" in
+                *" --include-partial-messages "*)
+                    event 1 '{"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","content":[],"model":"claude-sonnet-4-5"}}'
+                    event 2 '{"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}'
+                    event 3 '{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Hmm"}}'
+                    event 4 '{"type":"content_block_stop","index":0}'
+                    event 5 '{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"t1","name":"Read","input":{}}}'
+                    event 6 '{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{}"}}'
+                    event 7 '{"type":"content_block_stop","index":1}'
+                    event 8 '{"type":"content_block_start","index":2,"content_block":{"type":"text","text":""}}'
+                    event 9 '{"type":"content_block_delta","index":2,"delta":{"type":"text_delta","text":"Par"}}'
+                    event 10 '{"type":"content_block_delta","index":2,"delta":{"type":"text_delta","text":"is"}}'
+                    event 11 '{"type":"content_block_stop","index":2}'
+                    event 12 '{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":3}}'
+                    event 13 '{"type":"message_stop"}'
+                    ;;
+            esac
+            echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"text","text":"Paris"}]}}'
+            echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"sess-1"}'
+            ;;
+    esac
+}
+
+serve
+"#;
+
+async fn text_deltas(client: &ClaudeClient) -> Vec<String> {
+    let mut deltas = Vec::new();
+    let mut stream = client.receive_text_deltas();
+    while let Some(delta) = stream.next().await {
+        deltas.push(delta.unwrap());
+    }
+    deltas
+}
+
+#[tokio::test]
+async fn test_receive_text_deltas_streams_text_only() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .include_partial_messages(true)
+        .build();
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+
+    client.query("capital of France?").await.unwrap();
+    assert_eq!(text_deltas(&client).await, ["Par", "is"]);
+
+    // The response ended at its result, so the next one starts cleanly
+    client.query("and again?").await.unwrap();
+    let mut events = 0;
+    let mut stream = client.receive_response();
+    while let Some(message) = stream.next().await {
+        if let Message::StreamEvent(_) = message.unwrap() {
+            events += 1;
+        }
+    }
+    drop(stream);
+    assert_eq!(events, 13);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_receive_text_deltas_without_partial_messages() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder().cli_path(mock.path()).build();
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+
+    client.query("capital of France?").await.unwrap();
+    assert_eq!(text_deltas(&client).await, ["Paris"]);
+
+    client.disconnect().await.unwrap();
+}
@@ -0,0 +1,53 @@
+//! Todo tracking by `ClaudeClient` against a mock CLI
+//!
+//! The mock answers the first user message by writing a two-item todo list,
+//! and later ones by marking the first item completed.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::todos::TodoStatus;
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+status=in_progress
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            echo "{\"type\":\"assistant\",\"session_id\":\"s\",\"message\":{\"content\":[{\"type\":\"tool_use\",\"id\":\"t\",\"name\":\"TodoWrite\",\"input\":{\"todos\":[{\"content\":\"Read code\",\"status\":\"$status\",\"activeForm\":\"Reading code\"},{\"content\":\"Write tests\",\"status\":\"pending\",\"activeForm\":\"Writing tests\"}]}}]}}"
+            echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"s"}'
+            status=completed
+            ;;
+    esac
+}
+
+serve
+"#;
+
+#[tokio::test]
+async fn test_client_tracks_todo_writes() {
+    let mock = MockCli::new(MOCK_CLI);
+
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .track_todos(true)
+        .build();
+    let mut client = ClaudeClient::new(options);
+    let mut updates = client.todos().subscribe();
+
+    client.query_collect("plan").await.unwrap();
+    assert!(updates.has_changed().unwrap());
+    let first = updates.borrow_and_update().clone();
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.items[0].status, TodoStatus::InProgress);
+    assert_eq!(first.items[0].active_form.as_deref(), Some("Reading code"));
+
+    client.query_collect("continue").await.unwrap();
+    let second = client.todos().current();
+    assert_eq!(second.items[0].status, TodoStatus::Completed);
+    assert_eq!(second.items[0].created_at, first.items[0].created_at);
+
+    client.disconnect().await.unwrap();
+}
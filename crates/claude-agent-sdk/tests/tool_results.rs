@@ -0,0 +1,109 @@
+//! Tool results of an SDK MCP server as the CLI receives them
+//!
+//! The mock transport plays the CLI: on the user message it calls a tool of
+//! the `charts` server through an `mcp_message` control request, then ends the
+//! turn once the SDK has answered.
+
+use claude_agent_sdk::testing::{MockRecorder, MockTransport};
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, McpServerConfig, McpServers, ToolResult,
+    create_sdk_mcp_server, tool,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0];
+
+fn charts_server() -> McpServerConfig {
+    let render = tool!(
+        "render",
+        "Render a chart",
+        json!({"type": "object"}),
+        |args: Value| async move {
+            if args["series"].is_null() {
+                return Ok(ToolResult::error("series is required"));
+            }
+            Ok(ToolResult::builder()
+                .text("Chart of the series:")
+                .image_png(PNG)?
+                .json(json!({"points": args["series"]}))
+                .build())
+        }
+    );
+    McpServerConfig::Sdk(create_sdk_mcp_server("charts", "1.0.0", vec![render]))
+}
+
+/// Call `render` with `arguments` and return the MCP response the SDK wrote
+async fn call_render(arguments: Value) -> Value {
+    let transport = MockTransport::builder()
+        .on_user_message("chart")
+        .respond(json!({
+            "type": "control_request",
+            "request_id": "req_mcp",
+            "request": {
+                "subtype": "mcp_message",
+                "server_name": "charts",
+                "message": {
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": {"name": "render", "arguments": arguments}
+                }
+            }
+        }))
+        .on_control_response()
+        .respond_result("done")
+        .build();
+    let recorder = transport.recorder();
+
+    let options = ClaudeAgentOptions::builder()
+        .mcp_servers(McpServers::Dict(HashMap::from([(
+            "charts".to_string(),
+            charts_server(),
+        )])))
+        .build();
+    let mut client = ClaudeClient::with_transport(Box::new(transport), options);
+    client.connect().await.unwrap();
+    client.query_collect("chart").await.unwrap();
+    client.disconnect().await.unwrap();
+
+    mcp_response(&recorder)
+}
+
+fn mcp_response(recorder: &MockRecorder) -> Value {
+    recorder
+        .written()
+        .into_iter()
+        .find(|message| message["response"]["request_id"] == "req_mcp")
+        .map(|mut message| message["response"]["response"]["mcp_response"].take())
+        .expect("no answer to the mcp_message request")
+}
+
+#[tokio::test]
+async fn test_mixed_content_reaches_the_cli() {
+    let response = call_render(json!({"series": [1, 2]})).await;
+    assert_eq!(response["id"], 1);
+    assert_eq!(
+        response["result"],
+        json!({
+            "content": [
+                {"type": "text", "text": "Chart of the series:"},
+                {"type": "image", "data": "iVBORw0KGgoAAA==", "mimeType": "image/png"},
+                {"type": "text", "text": "{\n  \"points\": [\n    1,\n    2\n  ]\n}"}
+            ],
+            "isError": false
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_error_result_is_flagged() {
+    let response = call_render(json!({})).await;
+    assert_eq!(
+        response["result"],
+        json!({
+            "content": [{"type": "text", "text": "series is required"}],
+            "isError": true
+        })
+    );
+}
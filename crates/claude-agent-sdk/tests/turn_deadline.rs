@@ -0,0 +1,150 @@
+//! Turn deadline tests against a mock CLI
+//!
+//! The mock is a shell script speaking just enough of the stream-json protocol:
+//! it answers the initialize request, streams a partial answer for every prompt
+//! and then stalls until it receives an interrupt.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, ClaudeError};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+# One-shot text prompts come without the stream-json input protocol: stream and stall
+case " $* " in
+    *" --input-format "*) ;;
+    *)
+        echo '{"type":"assistant","message":{"content":[{"type":"text","text":"partial answer"}]},"session_id":"mock"}'
+        exec sleep 30
+        ;;
+esac
+
+handle() {
+    echo "$line" >> "$MOCK_LOG"
+    case "$line" in
+        *'"subtype":"interrupt"'*)
+            ack
+            if [ -z "$MOCK_IGNORE_INTERRUPT" ]; then
+                echo '{"type":"result","subtype":"error_during_execution","duration_ms":1,"duration_api_ms":1,"is_error":true,"num_turns":1,"session_id":"mock"}'
+            fi
+            ;;
+        *'"type":"user"'*)
+            echo '{"type":"assistant","message":{"content":[{"type":"text","text":"partial answer"}]},"session_id":"mock"}'
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    fn options(&self, ignore_interrupt: bool) -> ClaudeAgentOptions {
+        let mut env = std::collections::HashMap::new();
+        env.insert("MOCK_LOG".to_string(), self.log().display().to_string());
+        if ignore_interrupt {
+            env.insert("MOCK_IGNORE_INTERRUPT".to_string(), "1".to_string());
+        }
+
+        ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(env)
+            .deadline_grace_period(Duration::from_millis(500))
+            .build()
+    }
+
+    fn log(&self) -> PathBuf {
+        self.dir().join("stdin.log")
+    }
+
+    fn received(&self) -> String {
+        std::fs::read_to_string(self.log()).unwrap_or_default()
+    }
+}
+
+#[tokio::test]
+async fn test_deadline_interrupts_turn_and_returns_partial() {
+    let mock = MockCli::with_version("99.0.0", MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(false));
+
+    let deadline = Instant::now() + Duration::from_millis(1500);
+    let err = client
+        .query_with_deadline("Write a long essay", deadline)
+        .await
+        .unwrap_err();
+
+    let ClaudeError::DeadlineExceeded { elapsed, partial } = err else {
+        panic!("expected DeadlineExceeded, got {err:?}");
+    };
+    assert!(elapsed >= Duration::from_millis(1500));
+    assert!(elapsed < Duration::from_secs(5));
+    assert_eq!(partial.text(), "partial answer");
+    assert!(
+        partial.is_complete(),
+        "result after interrupt should be collected"
+    );
+
+    assert!(mock.received().contains(r#""subtype":"interrupt""#));
+
+    client.disconnect().await.ok();
+}
+
+#[tokio::test]
+async fn test_deadline_tears_down_when_interrupt_is_ignored() {
+    let mock = MockCli::with_version("99.0.0", MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(true));
+
+    let deadline = Instant::now() + Duration::from_millis(1500);
+    let err = client
+        .query_with_deadline("Write a long essay", deadline)
+        .await
+        .unwrap_err();
+
+    let ClaudeError::DeadlineExceeded { elapsed, partial } = err else {
+        panic!("expected DeadlineExceeded, got {err:?}");
+    };
+    assert!(elapsed < Duration::from_secs(5));
+    assert_eq!(partial.text(), "partial answer");
+    assert!(!partial.is_complete());
+    assert!(mock.received().contains(r#""subtype":"interrupt""#));
+
+    // The client was torn down and has to reconnect
+    assert!(client.interrupt().await.is_err());
+}
+
+#[tokio::test]
+async fn test_turn_deadline_option_bounds_one_shot_query() {
+    let mock = MockCli::with_version("99.0.0", MOCK_CLI);
+    let mut options = mock.options(false);
+    options.turn_deadline = Some(Duration::from_millis(1500));
+
+    let started = Instant::now();
+    let err = claude_agent_sdk::query("Write a long essay", Some(options))
+        .await
+        .unwrap_err();
+
+    let ClaudeError::DeadlineExceeded { partial, .. } = err else {
+        panic!("expected DeadlineExceeded, got {err:?}");
+    };
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(partial.text(), "partial answer");
+}
+
+#[tokio::test]
+async fn test_deadline_bounds_connect() {
+    let mock = MockCli::with_version("99.0.0", MOCK_CLI);
+    let mut client = ClaudeClient::new(mock.options(false));
+
+    let err = client
+        .query_with_deadline("Hello", Instant::now())
+        .await
+        .unwrap_err();
+
+    let ClaudeError::DeadlineExceeded { partial, .. } = err else {
+        panic!("expected DeadlineExceeded, got {err:?}");
+    };
+    assert!(partial.messages.is_empty());
+}
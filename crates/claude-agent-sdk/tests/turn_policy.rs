@@ -0,0 +1,125 @@
+//! Turn policies against a mock CLI
+//!
+//! The mock appends the text of every user message it reads to `$MOCK_LOG`
+//! and answers with that text as both the assistant message and the result.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, ClaudeError, Message, TurnPolicy};
+use futures::StreamExt;
+use std::time::Duration;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            text=$(printf '%s' "$line" | sed -n 's/.*"content":"\([^"]*\)".*/\1/p')
+            echo "$text" >> "$MOCK_LOG"
+            echo "{\"type\":\"assistant\",\"message\":{\"model\":\"mock\",\"content\":[{\"type\":\"text\",\"text\":\"$text\"}]}}"
+            echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"mock\",\"result\":\"$text\"}"
+            ;;
+    esac
+}
+
+serve
+"#;
+
+impl MockCli {
+    async fn client(&self, policy: TurnPolicy, capacity: usize) -> ClaudeClient {
+        let log = self.dir().join("log");
+        let env =
+            std::collections::HashMap::from([("MOCK_LOG".to_string(), log.display().to_string())]);
+        let options = ClaudeAgentOptions::builder()
+            .cli_path(self.path())
+            .env(env)
+            .turn_policy(policy)
+            .turn_queue_capacity(capacity)
+            .build();
+
+        let client = ClaudeClient::new(options);
+        client.connect().await.unwrap();
+        client
+    }
+
+    /// User messages the mock has read so far
+    fn received(&self) -> Vec<String> {
+        std::fs::read_to_string(self.dir().join("log"))
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// Results of the next `turns` turns
+async fn results(client: &ClaudeClient, turns: usize) -> Vec<String> {
+    let mut results = Vec::new();
+    for _ in 0..turns {
+        let mut stream = client.receive_response();
+        while let Some(message) = stream.next().await {
+            if let Message::Result(result) = message.unwrap() {
+                results.push(result.result.unwrap_or_default());
+            }
+        }
+    }
+    results
+}
+
+#[tokio::test]
+async fn test_reject_policy_refuses_overlapping_turn() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client(TurnPolicy::Reject, 4).await;
+
+    client.query("first").await.unwrap();
+    assert!(matches!(
+        client.query("second").await,
+        Err(ClaudeError::TurnInProgress)
+    ));
+
+    assert_eq!(results(&client, 1).await, vec!["first"]);
+    client.query("second").await.unwrap();
+    assert_eq!(results(&client, 1).await, vec!["second"]);
+    assert_eq!(mock.received(), vec!["first", "second"]);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_queue_policy_sends_queries_in_order() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client(TurnPolicy::Queue, 2).await;
+
+    client.query("alpha").await.unwrap();
+    client.query("beta").await.unwrap();
+    client.query("gamma").await.unwrap();
+    assert!(matches!(
+        client.query("delta").await,
+        Err(ClaudeError::TurnQueueFull { capacity: 2 })
+    ));
+
+    // Nothing queued is sent before the running turn's result is received
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(mock.received(), vec!["alpha"]);
+
+    assert_eq!(results(&client, 3).await, vec!["alpha", "beta", "gamma"]);
+    assert_eq!(mock.received(), vec!["alpha", "beta", "gamma"]);
+
+    client.disconnect().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_interleave_policy_sends_immediately() {
+    let mock = MockCli::new(MOCK_CLI);
+    let mut client = mock.client(TurnPolicy::Interleave, 0).await;
+
+    client.query("one").await.unwrap();
+    client.query("two").await.unwrap();
+
+    assert_eq!(results(&client, 2).await, vec!["one", "two"]);
+    assert_eq!(mock.received(), vec!["one", "two"]);
+
+    client.disconnect().await.unwrap();
+}
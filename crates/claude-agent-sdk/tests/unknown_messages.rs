@@ -0,0 +1,93 @@
+//! Messages and content blocks of unknown types from a mock CLI
+//!
+//! The mock answers each user message with a message of a made-up type, an
+//! assistant message holding a made-up content block, a text reply and a result.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{
+    ClaudeAgentOptions, ClaudeClient, ClaudeError, ContentBlock, Message, query,
+};
+use futures::StreamExt;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+answer() {
+    echo '{"type":"rate_limit_forecast","session_id":"sess-1","remaining":42}'
+    echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"hologram","frames":3},{"type":"text","text":"half"}]}}'
+    echo '{"type":"assistant","session_id":"sess-1","message":{"content":[{"type":"text","text":"done"}]}}'
+    echo '{"type":"result","subtype":"success","duration_ms":1,"duration_api_ms":1,"is_error":false,"num_turns":1,"session_id":"sess-1"}'
+}
+
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            answer
+            ;;
+    esac
+}
+
+serve
+
+# A one-shot query sends its prompt as plain text without initializing
+[ -n "$initialized" ] || answer
+"#;
+
+#[tokio::test]
+async fn test_client_stream_continues_past_unknown_types() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder().cli_path(mock.path()).build();
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+    client.query("hello").await.unwrap();
+
+    let mut messages = Vec::new();
+    {
+        let mut stream = client.receive_response();
+        while let Some(message) = stream.next().await {
+            messages.push(message.unwrap());
+        }
+    }
+    client.disconnect().await.unwrap();
+
+    assert_eq!(messages.len(), 4);
+    let Message::Unknown(unknown) = &messages[0] else {
+        panic!("expected an unknown message, got {:?}", messages[0]);
+    };
+    assert_eq!(unknown["remaining"], 42);
+    let Message::Assistant(assistant) = &messages[1] else {
+        panic!("expected an assistant message, got {:?}", messages[1]);
+    };
+    assert!(
+        matches!(&assistant.message.content[0], ContentBlock::Unknown(block) if block["frames"] == 3)
+    );
+    assert!(
+        matches!(&assistant.message.content[1], ContentBlock::Text(text) if text.text == "half")
+    );
+    assert!(matches!(&messages[2], Message::Assistant(_)));
+    assert!(matches!(&messages[3], Message::Result(_)));
+}
+
+#[tokio::test]
+async fn test_strict_parsing_rejects_unknown_types() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .strict_parsing(true)
+        .build();
+
+    let error = query("hello", Some(options)).await.unwrap_err();
+
+    match error {
+        ClaudeError::MessageParse(error) => {
+            assert!(
+                error.message.contains("rate_limit_forecast"),
+                "{}",
+                error.message
+            );
+        },
+        other => panic!("expected a message parse error, got {:?}", other),
+    }
+}
@@ -0,0 +1,140 @@
+//! Typed usage and permission denials parsed from result and assistant
+//! messages in `fixtures/raw_messages`
+//!
+//! The numbered fixtures were captured from CLI 2.0.20; the others follow the
+//! result layout of a later CLI and of a 1.0 CLI, see `fixtures/README.md`.
+
+use claude_agent_sdk::{Message, ModelTokenUsage, ResultMessage, ServerToolUse, Usage};
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures/raw_messages")
+}
+
+fn load(name: &str) -> Message {
+    let json = std::fs::read_to_string(fixtures_dir().join(name)).unwrap();
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("{name}: {e}"))
+}
+
+fn load_result(name: &str) -> ResultMessage {
+    match load(name) {
+        Message::Result(result) => result,
+        other => panic!("{name} is a {} message", other.type_name()),
+    }
+}
+
+/// Names of the fixtures starting with `prefix`
+fn fixtures(prefix: &str) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    names.sort();
+    names
+}
+
+/// The token counts of `usage` match the raw JSON they were parsed from
+fn assert_matches_raw(name: &str, usage: &Usage, raw: &serde_json::Value) {
+    let count = |key: &str| raw[key].as_u64().unwrap_or(0);
+    assert_eq!(usage.input_tokens, count("input_tokens"), "{name}");
+    assert_eq!(usage.output_tokens, count("output_tokens"), "{name}");
+    assert_eq!(usage.cache_read_input_tokens, count("cache_read_input_tokens"), "{name}");
+    assert_eq!(
+        usage.cache_creation_input_tokens,
+        count("cache_creation_input_tokens"),
+        "{name}"
+    );
+}
+
+#[test]
+fn test_every_message_with_usage_gets_typed_usage() {
+    for name in fixtures("assistant_") {
+        let Message::Assistant(assistant) = load(&name) else {
+            panic!("{name} is not an assistant message");
+        };
+        let raw = assistant.message.usage.as_ref().expect("raw usage is kept");
+        let usage = assistant.token_usage.as_ref().expect("typed usage");
+        assert_matches_raw(&name, usage, raw);
+        assert!(usage.by_model.is_empty(), "{name}");
+    }
+
+    for name in fixtures("result_") {
+        let result = load_result(&name);
+        let raw = result.usage.as_ref().expect("raw usage is kept");
+        assert_matches_raw(&name, result.token_usage.as_ref().unwrap(), raw);
+    }
+}
+
+#[test]
+fn test_result_usage_per_model() {
+    let result = load_result("result_003.json");
+    let usage = result.token_usage.clone().unwrap();
+    assert_eq!(usage.output_tokens, 214);
+    assert_eq!(
+        usage.server_tool_use,
+        Some(ServerToolUse {
+            web_search_requests: 0,
+            web_fetch_requests: 0,
+        })
+    );
+
+    assert_eq!(result.model_usage.as_ref(), Some(&usage.by_model));
+    let models: Vec<&str> = usage.by_model.keys().map(String::as_str).collect();
+    assert_eq!(models, ["claude-haiku-4-5-20251001", "claude-sonnet-4-5-20250929"]);
+    assert_eq!(
+        usage.by_model["claude-sonnet-4-5-20250929"],
+        ModelTokenUsage {
+            input_tokens: 3,
+            output_tokens: 214,
+            cache_read_input_tokens: 14422,
+            cache_creation_input_tokens: 337,
+            web_search_requests: 0,
+            cost_usd: 0.00880935,
+            context_window: Some(200000),
+        }
+    );
+    let cost: f64 = usage.by_model.values().map(|model| model.cost_usd).sum();
+    assert!((cost - result.total_cost_usd.unwrap()).abs() < 1e-9);
+    assert!(result.permission_denials().is_empty());
+}
+
+#[test]
+fn test_permission_denials() {
+    let result = load_result("result_permission_denied.json");
+    let denials = result.permission_denials();
+    assert_eq!(denials.len(), 1);
+    assert_eq!(denials[0].tool_name, "Bash");
+    assert_eq!(denials[0].tool_use_id, "toolu_01QkVwF3x8mZr2bN7YtJd4Lc");
+    assert_eq!(denials[0].tool_input["command"], "rm -rf build");
+
+    let usage = result.token_usage.unwrap();
+    assert_eq!(usage.server_tool_use.unwrap().web_fetch_requests, 1);
+}
+
+#[test]
+fn test_result_of_a_1_0_cli() {
+    let result = load_result("result_cli_1_0.json");
+    assert!(result.model_usage.is_none());
+    assert!(result.permission_denials().is_empty());
+
+    let usage = result.token_usage.unwrap();
+    assert_eq!(usage.cache_read_input_tokens, 13950);
+    assert!(usage.by_model.is_empty());
+}
+
+#[test]
+fn test_serialized_result_parses_to_the_same_usage() {
+    let Message::Result(result) = load("result_permission_denied.json") else {
+        unreachable!()
+    };
+    let json = serde_json::to_value(Message::Result(result.clone())).unwrap();
+    assert!(json.get("modelUsage").is_some());
+    assert!(json.get("token_usage").is_none());
+
+    let Message::Result(reparsed) = serde_json::from_value(json).unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(reparsed.token_usage, result.token_usage);
+    assert_eq!(reparsed.permission_denials, result.permission_denials);
+}
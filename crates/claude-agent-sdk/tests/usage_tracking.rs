@@ -0,0 +1,76 @@
+//! Client usage accumulation against a mock CLI
+//!
+//! The mock answers every user message with one API response split over two
+//! assistant messages, as the real CLI does for text followed by a tool use,
+//! then a result. Each turn costs $0.01, and like the real CLI the result
+//! reports the running cost of the session.
+
+#![cfg(unix)]
+
+use claude_agent_sdk::{ClaudeAgentOptions, ClaudeClient, Message};
+use futures::StreamExt;
+
+mod common;
+use common::MockCli;
+
+const MOCK_CLI: &str = r#"
+turns=0
+handle() {
+    case "$line" in
+        *'"type":"user"'*)
+            turns=$((turns + 1))
+            usage='{"input_tokens":10,"output_tokens":4,"cache_read_input_tokens":100}'
+            echo "{\"type\":\"assistant\",\"session_id\":\"mock\",\"message\":{\"id\":\"msg_1\",\"content\":[{\"type\":\"text\",\"text\":\"Reading\"}],\"usage\":$usage}}"
+            echo "{\"type\":\"assistant\",\"session_id\":\"mock\",\"message\":{\"id\":\"msg_1\",\"content\":[{\"type\":\"text\",\"text\":\"done\"}],\"usage\":$usage}}"
+            echo "{\"type\":\"result\",\"subtype\":\"success\",\"duration_ms\":1,\"duration_api_ms\":1,\"is_error\":false,\"num_turns\":1,\"session_id\":\"mock\",\"total_cost_usd\":0.0$turns,\"usage\":$usage}"
+            ;;
+    esac
+}
+
+serve
+"#;
+
+#[tokio::test]
+async fn test_usage_accumulates_across_queries() {
+    let mock = MockCli::new(MOCK_CLI);
+    let options = ClaudeAgentOptions::builder()
+        .cli_path(mock.path())
+        .max_budget_usd(0.015)
+        .build();
+
+    let mut client = ClaudeClient::new(options);
+    client.connect().await.unwrap();
+
+    client.query("first").await.unwrap();
+    let mut stream = client.receive_response();
+    // Usage is visible while the stream is still borrowed
+    while let Some(message) = stream.next().await {
+        if let Message::Assistant(_) = message.unwrap() {
+            let usage = client.usage();
+            assert_eq!(usage.total.input_tokens, 10);
+            assert_eq!(usage.total.total_cost_usd, 0.0);
+        }
+    }
+    drop(stream);
+
+    let usage = client.usage();
+    assert_eq!(usage.total.input_tokens, 10);
+    assert_eq!(usage.total.output_tokens, 4);
+    assert_eq!(usage.total.cache_read_input_tokens, 100);
+    assert_eq!(usage.total.total_cost_usd, 0.01);
+    assert!(!client.budget_exceeded());
+
+    client.query_collect("second").await.unwrap();
+    let usage = client.usage();
+    assert_eq!(usage.total.input_tokens, 20);
+    assert_eq!(usage.total.num_turns, 2);
+    assert_eq!(usage.total.total_cost_usd, 0.02);
+    assert_eq!(usage.sessions["mock"], usage.total);
+    assert!(client.budget_exceeded());
+
+    client.reset_usage();
+    assert_eq!(client.usage().total.total_tokens(), 0);
+    assert!(!client.budget_exceeded());
+
+    client.disconnect().await.unwrap();
+}